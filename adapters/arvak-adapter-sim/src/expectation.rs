@@ -0,0 +1,461 @@
+//! Expectation values of Pauli observables.
+//!
+//! An [`Observable`] is a weighted sum of Pauli strings. For evaluation its
+//! terms are grouped into qubit-wise commuting sets: each group needs one
+//! basis rotation of the final state, after which every term in the group is
+//! a parity over the rotated basis probabilities. Batched evaluation groups
+//! the terms once and reuses the groups for every parameter set.
+//!
+//! Terms in a group are estimated from the same shots, so their errors are
+//! correlated. An [`ExpectationEstimate`] therefore keeps the single-shot
+//! variance of every group's summed value, which is what determines the
+//! standard error of the total, next to the per-term variances.
+
+use arvak_hal::{Counts, HalError, HalResult};
+use arvak_ir::{Circuit, Instruction, IrResult, PauliAxis, QubitId, StandardGate};
+use serde::{Deserialize, Serialize};
+
+use crate::statevector::Statevector;
+
+/// One weighted Pauli string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObservableTerm {
+    /// Coefficient of the term.
+    pub coefficient: f64,
+
+    /// Non-identity Paulis by qubit; empty for the identity.
+    pub paulis: Vec<(u32, PauliAxis)>,
+}
+
+impl ObservableTerm {
+    /// Create a term.
+    pub fn new(coefficient: f64, paulis: impl IntoIterator<Item = (u32, PauliAxis)>) -> Self {
+        Self {
+            coefficient,
+            paulis: paulis.into_iter().collect(),
+        }
+    }
+
+    /// Get the Pauli acting on a qubit, `None` for the identity.
+    pub fn pauli_on(&self, qubit: u32) -> Option<PauliAxis> {
+        self.paulis
+            .iter()
+            .find(|(q, _)| *q == qubit)
+            .map(|(_, p)| *p)
+    }
+
+    /// Check whether two terms act with the same Pauli wherever both act.
+    pub fn commutes_qubitwise(&self, other: &ObservableTerm) -> bool {
+        self.paulis
+            .iter()
+            .all(|&(q, p)| other.pauli_on(q).is_none_or(|o| o == p))
+    }
+}
+
+/// A weighted sum of Pauli strings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Observable {
+    /// Terms of the observable.
+    pub terms: Vec<ObservableTerm>,
+}
+
+impl Observable {
+    /// Create an observable with no terms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a term.
+    pub fn with_term(
+        mut self,
+        coefficient: f64,
+        paulis: impl IntoIterator<Item = (u32, PauliAxis)>,
+    ) -> Self {
+        self.terms.push(ObservableTerm::new(coefficient, paulis));
+        self
+    }
+
+    /// Highest qubit a term acts on.
+    pub fn max_qubit(&self) -> Option<u32> {
+        self.terms
+            .iter()
+            .flat_map(|t| t.paulis.iter().map(|(q, _)| *q))
+            .max()
+    }
+
+    /// Partition the terms into qubit-wise commuting groups.
+    pub fn groups(&self) -> Vec<MeasurementGroup> {
+        let mut groups: Vec<MeasurementGroup> = Vec::new();
+        for (index, term) in self.terms.iter().enumerate() {
+            if term.paulis.is_empty() {
+                continue;
+            }
+            let fits = |g: &MeasurementGroup| {
+                term.paulis
+                    .iter()
+                    .all(|(q, p)| g.basis.iter().all(|(bq, bp)| bq != q || bp == p))
+            };
+            match groups.iter_mut().find(|g| fits(g)) {
+                Some(group) => {
+                    for &(q, p) in &term.paulis {
+                        if !group.basis.iter().any(|(bq, _)| *bq == q) {
+                            group.basis.push((q, p));
+                        }
+                    }
+                    group.terms.push(index);
+                }
+                None => groups.push(MeasurementGroup {
+                    basis: term.paulis.clone(),
+                    terms: vec![index],
+                }),
+            }
+        }
+        groups
+    }
+
+    /// Estimate the expectation value from measured counts, one [`Counts`]
+    /// per group in the order of `groups`.
+    ///
+    /// Each counts must come from the group's
+    /// [`measurement_circuit`](MeasurementGroup::measurement_circuit) run on
+    /// this simulator, so bitstrings are read with qubit 0 leftmost.
+    /// Variances are unbiased sample variances of the shots.
+    pub fn estimate_from_counts(
+        &self,
+        groups: &[MeasurementGroup],
+        counts: &[Counts],
+    ) -> HalResult<ExpectationEstimate> {
+        if counts.len() != groups.len() {
+            return Err(HalError::InvalidCircuit(format!(
+                "Expected counts for {} measurement groups, got {}",
+                groups.len(),
+                counts.len()
+            )));
+        }
+
+        let mut estimate = self.identity_estimate(groups.len());
+        for (g, (group, counts)) in groups.iter().zip(counts).enumerate() {
+            let shots = counts.total_shots();
+            if shots == 0 {
+                return Err(HalError::InvalidCircuit(format!(
+                    "No shots recorded for measurement group {}",
+                    g
+                )));
+            }
+            let mut outcomes = Vec::with_capacity(counts.len());
+            for (bitstring, &count) in counts.iter() {
+                let outcome = parse_outcome(bitstring)?;
+                outcomes.push((outcome, count as f64 / shots as f64));
+            }
+
+            // Bessel's correction turns the plug-in variances into unbiased ones.
+            let correction = if shots > 1 {
+                shots as f64 / (shots - 1) as f64
+            } else {
+                0.0
+            };
+            self.accumulate_group(&mut estimate, g, group, &outcomes, correction)?;
+        }
+        Ok(estimate)
+    }
+
+    /// Exact expectation value and single-shot variances in the state
+    /// prepared by `circuit`, using precomputed `groups` of this observable.
+    pub(crate) fn estimate(
+        &self,
+        circuit: &Circuit,
+        groups: &[MeasurementGroup],
+    ) -> HalResult<ExpectationEstimate> {
+        let mut sv = Statevector::new(circuit.num_qubits());
+        for (_, inst) in circuit.dag().topological_ops() {
+            sv.apply(inst);
+        }
+
+        let mut estimate = self.identity_estimate(groups.len());
+        for (g, group) in groups.iter().enumerate() {
+            let outcomes: Vec<(usize, f64)> = group
+                .rotated_probabilities(&sv)
+                .into_iter()
+                .enumerate()
+                .filter(|(_, p)| *p > 0.0)
+                .collect();
+            self.accumulate_group(&mut estimate, g, group, &outcomes, 1.0)?;
+        }
+        Ok(estimate)
+    }
+
+    /// Expectation value in the state prepared by `circuit`, using
+    /// precomputed `groups` of this observable.
+    pub(crate) fn evaluate(
+        &self,
+        circuit: &Circuit,
+        groups: &[MeasurementGroup],
+    ) -> HalResult<f64> {
+        Ok(self.estimate(circuit, groups)?.value)
+    }
+
+    /// Estimate holding only the identity terms, which have no variance.
+    fn identity_estimate(&self, num_groups: usize) -> ExpectationEstimate {
+        ExpectationEstimate {
+            value: self
+                .terms
+                .iter()
+                .filter(|t| t.paulis.is_empty())
+                .map(|t| t.coefficient)
+                .sum(),
+            term_variances: vec![0.0; self.terms.len()],
+            group_variances: vec![0.0; num_groups],
+        }
+    }
+
+    /// Add a group's contribution from `(outcome, probability)` pairs in
+    /// the group's rotated basis, scaling variances by `correction`.
+    fn accumulate_group(
+        &self,
+        estimate: &mut ExpectationEstimate,
+        g: usize,
+        group: &MeasurementGroup,
+        outcomes: &[(usize, f64)],
+        correction: f64,
+    ) -> HalResult<()> {
+        let masks = group
+            .terms
+            .iter()
+            .map(|&i| parity_mask(&self.terms[i]))
+            .collect::<HalResult<Vec<usize>>>()?;
+
+        let mut parities = vec![0.0; group.terms.len()];
+        let mut mean = 0.0;
+        let mut mean_square = 0.0;
+        for &(outcome, p) in outcomes {
+            let mut value = 0.0;
+            for (k, (&i, &mask)) in group.terms.iter().zip(&masks).enumerate() {
+                let sign = if (outcome & mask).count_ones() % 2 == 0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                parities[k] += p * sign;
+                value += self.terms[i].coefficient * sign;
+            }
+            mean += p * value;
+            mean_square += p * value * value;
+        }
+
+        for (&i, parity) in group.terms.iter().zip(parities) {
+            let c = self.terms[i].coefficient;
+            estimate.value += c * parity;
+            estimate.term_variances[i] = correction * c * c * (1.0 - parity * parity).max(0.0);
+        }
+        estimate.group_variances[g] = correction * (mean_square - mean * mean).max(0.0);
+        Ok(())
+    }
+}
+
+/// Expectation value of an observable with the spread of its estimate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpectationEstimate {
+    /// Expectation value.
+    pub value: f64,
+
+    /// Single-shot variance `c² (1 - ⟨P⟩²)` of each term, in term order.
+    pub term_variances: Vec<f64>,
+
+    /// Single-shot variance of each measurement group's summed terms.
+    pub group_variances: Vec<f64>,
+}
+
+impl ExpectationEstimate {
+    /// Variance of the estimate when every group is measured `shots` times.
+    pub fn variance(&self, shots: u32) -> f64 {
+        self.group_variances.iter().sum::<f64>() / f64::from(shots.max(1))
+    }
+
+    /// Standard error of the estimate when every group is measured
+    /// `shots` times.
+    pub fn std_error(&self, shots: u32) -> f64 {
+        self.variance(shots).sqrt()
+    }
+
+    /// Standard error of each term's contribution from `shots` shots.
+    pub fn term_std_errors(&self, shots: u32) -> Vec<f64> {
+        let shots = f64::from(shots.max(1));
+        self.term_variances
+            .iter()
+            .map(|v| (v / shots).sqrt())
+            .collect()
+    }
+}
+
+/// Terms measured together in one product basis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementGroup {
+    /// Pauli measured on each qubit the group acts on.
+    pub basis: Vec<(u32, PauliAxis)>,
+
+    /// Indices of the observable terms in the group.
+    pub terms: Vec<usize>,
+}
+
+impl MeasurementGroup {
+    /// Append this group's basis rotations and a full measurement to a
+    /// copy of `circuit`.
+    pub fn measurement_circuit(&self, circuit: &Circuit) -> IrResult<Circuit> {
+        let mut measured = circuit.clone();
+        for &(q, axis) in &self.basis {
+            let qubit = QubitId(q);
+            match axis {
+                PauliAxis::X => {
+                    measured.h(qubit)?;
+                }
+                PauliAxis::Y => {
+                    measured.sdg(qubit)?.h(qubit)?;
+                }
+                PauliAxis::Z => {}
+            }
+        }
+        measured.measure_all()?;
+        Ok(measured)
+    }
+
+    /// Probabilities of the state rotated into this group's basis.
+    fn rotated_probabilities(&self, state: &Statevector) -> Vec<f64> {
+        let mut rotated = state.clone();
+        for &(q, axis) in &self.basis {
+            let qubit = QubitId(q);
+            match axis {
+                PauliAxis::X => {
+                    rotated.apply(&Instruction::single_qubit_gate(StandardGate::H, qubit));
+                }
+                PauliAxis::Y => {
+                    rotated.apply(&Instruction::single_qubit_gate(StandardGate::Sdg, qubit));
+                    rotated.apply(&Instruction::single_qubit_gate(StandardGate::H, qubit));
+                }
+                PauliAxis::Z => {}
+            }
+        }
+        rotated.amplitudes().iter().map(|a| a.norm_sqr()).collect()
+    }
+}
+
+/// Bit mask of the qubits a term acts on.
+fn parity_mask(term: &ObservableTerm) -> HalResult<usize> {
+    term.paulis.iter().try_fold(0usize, |mask, &(q, _)| {
+        if q >= usize::BITS {
+            return Err(HalError::InvalidCircuit(format!(
+                "Observable acts on qubit {}, beyond the {} qubits an outcome can hold",
+                q,
+                usize::BITS
+            )));
+        }
+        Ok(mask | 1 << q)
+    })
+}
+
+/// Outcome index of a bitstring with qubit 0 leftmost, as produced by
+/// [`Statevector::outcome_to_bitstring`].
+fn parse_outcome(bitstring: &str) -> HalResult<usize> {
+    let invalid = || HalError::InvalidCircuit(format!("Invalid bitstring '{}'", bitstring));
+    if bitstring.len() > usize::BITS as usize {
+        return Err(invalid());
+    }
+    bitstring
+        .bytes()
+        .enumerate()
+        .try_fold(0usize, |outcome, (q, bit)| match bit {
+            b'0' => Ok(outcome),
+            b'1' => Ok(outcome | 1 << q),
+            _ => Err(invalid()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups() {
+        let observable = Observable::new()
+            .with_term(-1.0, [])
+            .with_term(0.5, [(0, PauliAxis::Z)])
+            .with_term(0.3, [(0, PauliAxis::Z), (1, PauliAxis::Z)])
+            .with_term(0.2, [(0, PauliAxis::X), (1, PauliAxis::X)])
+            .with_term(0.1, [(1, PauliAxis::X)]);
+
+        let groups = observable.groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].terms, vec![1, 2]);
+        assert_eq!(groups[1].terms, vec![3, 4]);
+        assert!(observable.terms[3].commutes_qubitwise(&observable.terms[4]));
+        assert!(!observable.terms[2].commutes_qubitwise(&observable.terms[3]));
+    }
+
+    #[test]
+    fn test_bell_expectations() {
+        let circuit = Circuit::bell().unwrap();
+        let observable = Observable::new()
+            .with_term(1.0, [(0, PauliAxis::Z), (1, PauliAxis::Z)])
+            .with_term(1.0, [(0, PauliAxis::X), (1, PauliAxis::X)])
+            .with_term(1.0, [(0, PauliAxis::Y), (1, PauliAxis::Y)])
+            .with_term(1.0, [(0, PauliAxis::Z)])
+            .with_term(0.5, []);
+
+        // ⟨ZZ⟩ = ⟨XX⟩ = 1, ⟨YY⟩ = -1 and ⟨Z⟩ = 0 for (|00⟩ + |11⟩)/√2.
+        let value = observable.evaluate(&circuit, &observable.groups()).unwrap();
+        assert!((value - 1.5).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_estimate_variances() {
+        let circuit = Circuit::bell().unwrap();
+        let observable = Observable::new()
+            .with_term(2.0, [(0, PauliAxis::Z)])
+            .with_term(1.0, [(1, PauliAxis::Z)])
+            .with_term(0.5, [(0, PauliAxis::X), (1, PauliAxis::X)]);
+        let groups = observable.groups();
+
+        let estimate = observable.estimate(&circuit, &groups).unwrap();
+        assert!((estimate.value - 0.5).abs() < 1e-10);
+        // Z0 and Z1 are each ±1 uniformly, XX is always +1.
+        assert!((estimate.term_variances[0] - 4.0).abs() < 1e-10);
+        assert!((estimate.term_variances[1] - 1.0).abs() < 1e-10);
+        assert!(estimate.term_variances[2].abs() < 1e-10);
+        // Z0 and Z1 are perfectly correlated: 2 Z0 + Z1 is ±3.
+        assert!((estimate.group_variances[0] - 9.0).abs() < 1e-10);
+        assert!((estimate.std_error(100) - 0.3).abs() < 1e-10);
+
+        // Qubit 0 is the leftmost bit.
+        let counts = [
+            Counts::from_pairs([("10", 3), ("01", 1)]),
+            Counts::from_pairs([("00", 2), ("11", 2)]),
+        ];
+        let sampled = observable.estimate_from_counts(&groups, &counts).unwrap();
+        // 2 Z0 + Z1 takes -1 three times and +1 once: mean -0.5, sample
+        // variance (3 * 0.5² + 1.5²) / 3 = 1.
+        assert!((sampled.value - 0.0).abs() < 1e-10);
+        assert!((sampled.group_variances[0] - 1.0).abs() < 1e-10);
+        assert!(sampled.group_variances[1].abs() < 1e-10);
+
+        let z0 = Observable::new().with_term(1.0, [(0, PauliAxis::Z)]);
+        let ones = [Counts::from_pairs([("10", 5)])];
+        let sampled = z0.estimate_from_counts(&z0.groups(), &ones).unwrap();
+        assert!((sampled.value + 1.0).abs() < 1e-10);
+        assert!(
+            z0.estimate_from_counts(&z0.groups(), &[Counts::from_pairs([("1x", 1)])])
+                .is_err()
+        );
+        assert!(
+            observable
+                .estimate_from_counts(&groups, &counts[..1])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parity_mask() {
+        let repeated = ObservableTerm::new(1.0, [(1, PauliAxis::Z), (1, PauliAxis::Z)]);
+        assert_eq!(parity_mask(&repeated).unwrap(), 0b10);
+        let wide = ObservableTerm::new(1.0, [(usize::BITS, PauliAxis::Z)]);
+        assert!(parity_mask(&wide).is_err());
+    }
+}
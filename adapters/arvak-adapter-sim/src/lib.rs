@@ -2,14 +2,33 @@
 //!
 //! This crate provides a high-performance local quantum simulator for testing,
 //! development, and small-scale experiments. It uses statevector simulation,
-//! which provides exact results but is limited to ~20-25 qubits.
+//! which provides exact results but is limited to ~20-25 qubits. Clifford
+//! circuits are detected and run on a stabilizer tableau instead, which
+//! scales to thousands of qubits, and wider circuits with bounded
+//! entanglement run on a matrix product state.
 //!
 //! # Features
 //!
 //! - **Exact Simulation**: Full statevector representation (no sampling noise)
 //! - **All Standard Gates**: Supports all gates from `arvak-ir`
+//! - **Stabilizer Engine**: Clifford-only circuits such as randomized
+//!   benchmarking and error-correction experiments are simulated on a
+//!   tableau, selected automatically, up to thousands of qubits
+//! - **MPS Engine**: Matrix-product-state simulation for low-entanglement
+//!   circuits such as QAOA on sparse graphs at 40+ qubits, with a
+//!   configurable bond dimension and the truncation error reported in the
+//!   result metadata; selected with [`SimulationMethod`] or automatically
+//!   when the circuit's bond-dimension bound fits
 //! - **Measurement Sampling**: Probabilistic measurement with configurable shots
+//! - **Noisy Simulation**: Gate errors, readout confusion and idle
+//!   decoherence from an [`arvak_hal::NoiseModel`], e.g. one built from a
+//!   device calibration, sampled as one trajectory per shot
 //! - **No External Dependencies**: Pure Rust implementation
+//! - **Batched Expectation Values**: Pauli observables evaluated over many
+//!   parameter sets in parallel, with variances and standard errors of
+//!   exact or shot-based estimates, plus parameter-shift gradients
+//! - **Execution Traces**: Optional per-gate norms, amplitudes and snapshots
+//!   at marked barriers, written as JSON for inspection
 //!
 //! # Performance
 //!
@@ -50,7 +69,18 @@
 //! }
 //! ```
 
+mod expectation;
+mod mps;
+mod noise;
 mod simulator;
+mod stabilizer;
 mod statevector;
+mod trace;
 
-pub use simulator::SimulatorBackend;
+pub use expectation::{ExpectationEstimate, MeasurementGroup, Observable, ObservableTerm};
+pub use mps::{DEFAULT_MAX_BOND_DIMENSION, DEFAULT_TRUNCATION_THRESHOLD};
+pub use simulator::{
+    DEFAULT_MAX_MPS_QUBITS, DEFAULT_MAX_STABILIZER_QUBITS, SimulationMethod, SimulatorBackend,
+};
+pub use stabilizer::is_clifford;
+pub use trace::{ExecutionTrace, StateSnapshot, TraceMode, TraceStep};
@@ -0,0 +1,725 @@
+//! Matrix-product-state (MPS) simulation engine.
+//!
+//! The state of n qubits is a chain of tensors `A[l][p][r]`, one per qubit
+//! in index order, whose bond indices `l` and `r` link neighbours. Memory
+//! grows with the bond dimension, which is bounded by the entanglement
+//! across each cut rather than by 2^n, so shallow circuits on sparse
+//! graphs (QAOA, Trotterized chains) of many dozens of qubits fit easily.
+//!
+//! The chain is kept in mixed canonical form around one site, so the
+//! singular values of a split are the Schmidt coefficients across that cut.
+//! Gates are applied by merging the sites they act on, applying the
+//! unitary and splitting the result again by SVD, keeping at most the
+//! maximum bond dimension and dropping singular values whose weight falls
+//! under the truncation threshold. Gates on distant qubits are applied
+//! after swapping the qubits next to each other, and the swaps are undone
+//! afterwards. The discarded weight is accumulated into a truncation error,
+//! one minus the fidelity bound of the final state.
+
+use num_complex::Complex64;
+use rand::Rng;
+
+use arvak_ir::{GateKind, Instruction, InstructionKind};
+
+/// Default maximum bond dimension of the MPS engine.
+pub const DEFAULT_MAX_BOND_DIMENSION: usize = 64;
+
+/// Default largest weight of discarded singular values per split.
+pub const DEFAULT_TRUNCATION_THRESHOLD: f64 = 1e-12;
+
+/// Relative weight under which singular values count as zero.
+const ZERO_WEIGHT: f64 = 1e-28;
+
+const ZERO: Complex64 = Complex64::new(0.0, 0.0);
+const ONE: Complex64 = Complex64::new(1.0, 0.0);
+
+/// The tensor of one qubit, indexed `[(l * 2 + p) * right + r]`.
+#[derive(Clone)]
+struct Site {
+    left: usize,
+    right: usize,
+    data: Vec<Complex64>,
+}
+
+/// A state as a matrix product of one tensor per qubit.
+#[derive(Clone)]
+pub struct MpsState {
+    sites: Vec<Site>,
+    /// Site every other site is orthogonalized towards.
+    center: usize,
+    max_bond_dimension: usize,
+    truncation_threshold: f64,
+    /// Product of the weights kept by every truncation.
+    fidelity: f64,
+    /// Largest bond dimension the state has had.
+    peak_bond_dimension: usize,
+    /// Time each qubit has spent idling in delays, in nanoseconds.
+    clock: Vec<f64>,
+    /// Backend sample period in nanoseconds, for delays given in `dt`.
+    dt_ns: f64,
+}
+
+impl MpsState {
+    /// Create a new state initialized to |0...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        let site = Site {
+            left: 1,
+            right: 1,
+            data: vec![ONE, ZERO],
+        };
+        Self {
+            sites: vec![site; num_qubits],
+            center: 0,
+            max_bond_dimension: DEFAULT_MAX_BOND_DIMENSION,
+            truncation_threshold: DEFAULT_TRUNCATION_THRESHOLD,
+            fidelity: 1.0,
+            peak_bond_dimension: 1,
+            clock: vec![0.0; num_qubits],
+            dt_ns: 1.0,
+        }
+    }
+
+    /// Set the maximum bond dimension and the largest weight of singular
+    /// values dropped at each split.
+    pub fn with_truncation(mut self, max_bond_dimension: usize, threshold: f64) -> Self {
+        self.max_bond_dimension = max_bond_dimension.max(1);
+        self.truncation_threshold = threshold;
+        self
+    }
+
+    /// Set the sample period used to convert delays in `dt` (default 1 ns).
+    pub fn with_dt(mut self, dt_ns: f64) -> Self {
+        self.dt_ns = dt_ns;
+        self
+    }
+
+    /// Elapsed circuit time in nanoseconds, counted as by the statevector
+    /// engine.
+    pub fn elapsed_ns(&self) -> f64 {
+        self.clock.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Accumulated truncation error: one minus the product of the weights
+    /// kept at every split. Zero when the simulation is exact.
+    pub fn truncation_error(&self) -> f64 {
+        (1.0 - self.fidelity).max(0.0)
+    }
+
+    /// Largest bond dimension reached during the simulation.
+    pub fn peak_bond_dimension(&self) -> usize {
+        self.peak_bond_dimension
+    }
+
+    /// Apply an instruction; resets draw their outcome from `rng`.
+    ///
+    /// Gates without a unitary (unbound parameters, opaque custom gates)
+    /// are skipped.
+    pub fn apply(&mut self, instruction: &Instruction, rng: &mut impl Rng) {
+        let qubits: Vec<_> = instruction.qubits.iter().map(|q| q.0 as usize).collect();
+        match &instruction.kind {
+            InstructionKind::Gate(gate) => {
+                let matrix = match &gate.kind {
+                    GateKind::Standard(gate) => gate.matrix(),
+                    GateKind::Custom(custom) => custom.matrix.clone(),
+                };
+                if let Some(matrix) = matrix {
+                    self.apply_matrix(&matrix, &qubits);
+                }
+            }
+            InstructionKind::Reset => {
+                if self.measure(qubits[0], rng) {
+                    self.apply_matrix(&[ZERO, ONE, ONE, ZERO], &qubits);
+                }
+            }
+            InstructionKind::Delay { .. } => {
+                let ns = instruction.delay_ns(Some(self.dt_ns)).unwrap_or(0.0);
+                for q in qubits {
+                    self.clock[q] += ns;
+                }
+            }
+            InstructionKind::Barrier => {
+                let now = qubits.iter().map(|&q| self.clock[q]).fold(0.0, f64::max);
+                for q in qubits {
+                    self.clock[q] = now;
+                }
+            }
+            InstructionKind::Measure | InstructionKind::Shuttle { .. } => {
+                // Like the statevector engine, measure all qubits at the end
+            }
+        }
+    }
+
+    /// Apply a row-major `2^k × 2^k` unitary to `qubits`, the first qubit
+    /// being the most significant bit of the matrix index.
+    fn apply_matrix(&mut self, matrix: &[Complex64], qubits: &[usize]) {
+        if let [q] = qubits {
+            let site = &mut self.sites[*q];
+            let right = site.right;
+            for l in 0..site.left {
+                let i0 = (l * 2) * right;
+                let i1 = (l * 2 + 1) * right;
+                for r in 0..right {
+                    let (a0, a1) = (site.data[i0 + r], site.data[i1 + r]);
+                    site.data[i0 + r] = matrix[0] * a0 + matrix[1] * a1;
+                    site.data[i1 + r] = matrix[2] * a0 + matrix[3] * a1;
+                }
+            }
+            return;
+        }
+
+        // Swap the qubits into a block starting at the leftmost one
+        let mut sorted = qubits.to_vec();
+        sorted.sort_unstable();
+        let start = sorted[0];
+        let mut swaps = Vec::new();
+        for (offset, &q) in sorted.iter().enumerate().skip(1) {
+            for site in (start + offset..q).rev() {
+                self.apply_swap(site);
+                swaps.push(site);
+            }
+        }
+
+        // Reorder the matrix so the block's first site is its top bit
+        let k = qubits.len();
+        let dim = 1 << k;
+        let position: Vec<usize> = qubits
+            .iter()
+            .map(|q| sorted.binary_search(q).unwrap_or(0))
+            .collect();
+        let to_gate_index = |block: usize| {
+            position.iter().enumerate().fold(0, |index, (j, &pos)| {
+                let bit = (block >> (k - 1 - pos)) & 1;
+                index | (bit << (k - 1 - j))
+            })
+        };
+        let mut block_matrix = vec![ZERO; dim * dim];
+        for row in 0..dim {
+            for col in 0..dim {
+                block_matrix[row * dim + col] =
+                    matrix[to_gate_index(row) * dim + to_gate_index(col)];
+            }
+        }
+        self.apply_block(start, k, &block_matrix);
+
+        for site in swaps.into_iter().rev() {
+            self.apply_swap(site);
+        }
+    }
+
+    /// Swap the qubits on sites `site` and `site + 1`.
+    fn apply_swap(&mut self, site: usize) {
+        let mut swap = vec![ZERO; 16];
+        for (row, col) in [(0, 0), (1, 2), (2, 1), (3, 3)] {
+            swap[row * 4 + col] = ONE;
+        }
+        self.apply_block(site, 2, &swap);
+    }
+
+    /// Apply a `2^k × 2^k` unitary to the `k` adjacent sites from `start`.
+    fn apply_block(&mut self, start: usize, k: usize, matrix: &[Complex64]) {
+        self.move_center(start);
+
+        // Merge the block into one tensor indexed [l][p][r]
+        let left = self.sites[start].left;
+        let mut phys = 2;
+        let mut theta = self.sites[start].data.clone();
+        for site in &self.sites[start + 1..start + k] {
+            let mut merged = vec![ZERO; left * phys * 2 * site.right];
+            for lp in 0..left * phys {
+                for m in 0..site.left {
+                    let a = theta[lp * site.left + m];
+                    if a == ZERO {
+                        continue;
+                    }
+                    let b = &site.data[m * 2 * site.right..(m + 1) * 2 * site.right];
+                    let out = &mut merged[lp * 2 * site.right..(lp + 1) * 2 * site.right];
+                    for (o, b) in out.iter_mut().zip(b) {
+                        *o += a * b;
+                    }
+                }
+            }
+            theta = merged;
+            phys *= 2;
+        }
+        let right = self.sites[start + k - 1].right;
+
+        // Apply the gate to the physical index
+        let mut column = vec![ZERO; phys];
+        for l in 0..left {
+            for r in 0..right {
+                let index = |p: usize| (l * phys + p) * right + r;
+                for (p, c) in column.iter_mut().enumerate() {
+                    *c = theta[index(p)];
+                }
+                for p in 0..phys {
+                    theta[index(p)] = (0..phys).map(|q| matrix[p * phys + q] * column[q]).sum();
+                }
+            }
+        }
+
+        // Split it again, left to right, leaving the centre on the last site
+        let mut bond = left;
+        for offset in 0..k - 1 {
+            phys /= 2;
+            let (u, rest, kept) = self.split(&theta, bond * 2, phys * right, true);
+            self.sites[start + offset] = Site {
+                left: bond,
+                right: kept,
+                data: u,
+            };
+            theta = rest;
+            bond = kept;
+        }
+        self.sites[start + k - 1] = Site {
+            left: bond,
+            right,
+            data: theta,
+        };
+        self.center = start + k - 1;
+    }
+
+    /// Move the orthogonality centre to `to`, splitting each site it
+    /// passes without truncation.
+    fn move_center(&mut self, to: usize) {
+        while self.center < to {
+            let c = self.center;
+            let Site { left, right, data } = self.sites[c].clone();
+            let (u, rest, kept) = self.split(&data, left * 2, right, false);
+            self.sites[c] = Site {
+                left,
+                right: kept,
+                data: u,
+            };
+            let next = &self.sites[c + 1];
+            let data = matmul(&rest, &next.data, kept, right, 2 * next.right);
+            self.sites[c + 1] = Site {
+                left: kept,
+                right: next.right,
+                data,
+            };
+            self.center += 1;
+        }
+        while self.center > to {
+            let c = self.center;
+            let Site { left, right, data } = self.sites[c].clone();
+            let (u, s, vh) = svd(&data, left, 2 * right);
+            let kept = s
+                .iter()
+                .filter(|&&v| v * v > ZERO_WEIGHT * s[0] * s[0])
+                .count()
+                .max(1);
+            let mut us = vec![ZERO; left * kept];
+            for i in 0..left {
+                for j in 0..kept {
+                    us[i * kept + j] = u[i * s.len() + j] * s[j];
+                }
+            }
+            self.sites[c] = Site {
+                left: kept,
+                right,
+                data: vh[..kept * 2 * right].to_vec(),
+            };
+            let prev = &self.sites[c - 1];
+            let data = matmul(&prev.data, &us, prev.left * 2, left, kept);
+            self.sites[c - 1] = Site {
+                left: prev.left,
+                right: kept,
+                data,
+            };
+            self.center -= 1;
+        }
+    }
+
+    /// Split a `rows × cols` matrix into `U` and `S·V†`. With `truncate`,
+    /// at most the maximum bond dimension of singular values is kept, and
+    /// the smallest ones are dropped while their weight stays under the
+    /// truncation threshold.
+    ///
+    /// Returns `U` (`rows × kept`), `S·V†` (`kept × cols`) and `kept`; the
+    /// kept values are rescaled so the state stays normalized.
+    fn split(
+        &mut self,
+        a: &[Complex64],
+        rows: usize,
+        cols: usize,
+        truncate: bool,
+    ) -> (Vec<Complex64>, Vec<Complex64>, usize) {
+        let (u, s, vh) = svd(a, rows, cols);
+        let weights: Vec<f64> = s.iter().map(|s| s * s).collect();
+        let total: f64 = weights.iter().sum();
+
+        let mut kept = weights
+            .iter()
+            .filter(|&&w| w > ZERO_WEIGHT * weights[0])
+            .count()
+            .max(1);
+        if truncate {
+            kept = kept.min(self.max_bond_dimension);
+            let mut dropped: f64 = weights[kept..].iter().sum();
+            while kept > 1 && dropped + weights[kept - 1] <= self.truncation_threshold * total {
+                kept -= 1;
+                dropped += weights[kept];
+            }
+        }
+
+        let kept_weight: f64 = weights[..kept].iter().sum();
+        let scale = if kept_weight > 0.0 {
+            self.fidelity *= kept_weight / total;
+            (total / kept_weight).sqrt()
+        } else {
+            1.0
+        };
+        self.peak_bond_dimension = self.peak_bond_dimension.max(kept);
+
+        let k = s.len();
+        let mut left = vec![ZERO; rows * kept];
+        for i in 0..rows {
+            left[i * kept..(i + 1) * kept].copy_from_slice(&u[i * k..i * k + kept]);
+        }
+        let mut rest = vh[..kept * cols].to_vec();
+        for (j, row) in rest.chunks_mut(cols).enumerate() {
+            let s = s[j] * scale;
+            row.iter_mut().for_each(|v| *v *= s);
+        }
+        (left, rest, kept)
+    }
+
+    /// Measure one qubit, collapsing the state, and return the outcome.
+    fn measure(&mut self, qubit: usize, rng: &mut impl Rng) -> bool {
+        self.move_center(qubit);
+        let site = &mut self.sites[qubit];
+        let right = site.right;
+        let weight = |data: &[Complex64], p: usize| -> f64 {
+            (0..site.left)
+                .flat_map(|l| &data[(l * 2 + p) * right..(l * 2 + p + 1) * right])
+                .map(|a| a.norm_sqr())
+                .sum()
+        };
+        let p0 = weight(&site.data, 0);
+        let p1 = weight(&site.data, 1);
+        let outcome = rng.r#gen::<f64>() * (p0 + p1) >= p0;
+        let norm = if outcome { p1 } else { p0 }.sqrt();
+        for l in 0..site.left {
+            for p in 0..2 {
+                for a in &mut site.data[(l * 2 + p) * right..(l * 2 + p + 1) * right] {
+                    *a = if (p == 1) == outcome { *a / norm } else { ZERO };
+                }
+            }
+        }
+        outcome
+    }
+
+    /// Prepare to draw many final measurements of the state.
+    pub fn sampler(&self) -> MpsSampler {
+        let mut state = self.clone();
+        state.move_center(0);
+        MpsSampler { sites: state.sites }
+    }
+}
+
+/// Draws final measurement outcomes of an MPS.
+///
+/// Every site but the first is right-orthogonal, so the marginal of each
+/// qubit given the earlier outcomes only needs the sites up to it.
+pub struct MpsSampler {
+    sites: Vec<Site>,
+}
+
+impl MpsSampler {
+    /// Draw one outcome, with qubit 0 first.
+    pub fn sample(&self, rng: &mut impl Rng) -> String {
+        let mut env = vec![ONE];
+        let mut bits = String::with_capacity(self.sites.len());
+        for site in &self.sites {
+            let branch = |p: usize| -> Vec<Complex64> {
+                let mut w = vec![ZERO; site.right];
+                for (l, e) in env.iter().enumerate() {
+                    let row = &site.data[(l * 2 + p) * site.right..(l * 2 + p + 1) * site.right];
+                    for (w, a) in w.iter_mut().zip(row) {
+                        *w += e * a;
+                    }
+                }
+                w
+            };
+            let (w0, w1) = (branch(0), branch(1));
+            let p0: f64 = w0.iter().map(|a| a.norm_sqr()).sum();
+            let p1: f64 = w1.iter().map(|a| a.norm_sqr()).sum();
+            let outcome = rng.r#gen::<f64>() * (p0 + p1) >= p0;
+            let (w, p) = if outcome { (w1, p1) } else { (w0, p0) };
+            let norm = p.sqrt();
+            env = w.into_iter().map(|a| a / norm).collect();
+            bits.push(if outcome { '1' } else { '0' });
+        }
+        bits
+    }
+}
+
+/// Row-major product of an `n × m` and an `m × p` matrix.
+fn matmul(a: &[Complex64], b: &[Complex64], n: usize, m: usize, p: usize) -> Vec<Complex64> {
+    let mut out = vec![ZERO; n * p];
+    for i in 0..n {
+        for k in 0..m {
+            let a = a[i * m + k];
+            if a == ZERO {
+                continue;
+            }
+            for (o, b) in out[i * p..(i + 1) * p]
+                .iter_mut()
+                .zip(&b[k * p..(k + 1) * p])
+            {
+                *o += a * b;
+            }
+        }
+    }
+    out
+}
+
+/// Thin singular value decomposition of a row-major `rows × cols` matrix.
+///
+/// Returns `U` (`rows × k`), the singular values in decreasing order and
+/// `V†` (`k × cols`), with `k = min(rows, cols)`. Uses one-sided Jacobi
+/// rotations, which are accurate for the small matrices of MPS splits.
+fn svd(a: &[Complex64], rows: usize, cols: usize) -> (Vec<Complex64>, Vec<f64>, Vec<Complex64>) {
+    if rows < cols {
+        // A = (A†)† = (U S V†)† = V S U†
+        let mut adj = vec![ZERO; rows * cols];
+        for i in 0..rows {
+            for j in 0..cols {
+                adj[j * rows + i] = a[i * cols + j].conj();
+            }
+        }
+        let (u, s, vh) = svd(&adj, cols, rows);
+        let k = s.len();
+        let mut new_u = vec![ZERO; rows * k];
+        for i in 0..k {
+            for j in 0..rows {
+                new_u[j * k + i] = vh[i * rows + j].conj();
+            }
+        }
+        let mut new_vh = vec![ZERO; k * cols];
+        for i in 0..cols {
+            for j in 0..k {
+                new_vh[j * cols + i] = u[i * k + j].conj();
+            }
+        }
+        return (new_u, s, new_vh);
+    }
+
+    // Orthogonalize the columns of A in place, accumulating the rotations
+    let mut columns: Vec<Vec<Complex64>> = (0..cols)
+        .map(|j| (0..rows).map(|i| a[i * cols + j]).collect())
+        .collect();
+    let mut v: Vec<Vec<Complex64>> = (0..cols)
+        .map(|j| (0..cols).map(|i| if i == j { ONE } else { ZERO }).collect())
+        .collect();
+    for _sweep in 0..64 {
+        let mut rotated = false;
+        for p in 0..cols {
+            for q in p + 1..cols {
+                let alpha: f64 = columns[p].iter().map(|x| x.norm_sqr()).sum();
+                let beta: f64 = columns[q].iter().map(|x| x.norm_sqr()).sum();
+                let gamma: Complex64 = columns[p]
+                    .iter()
+                    .zip(&columns[q])
+                    .map(|(x, y)| x.conj() * y)
+                    .sum();
+                let g = gamma.norm();
+                if g <= 1e-15 * (alpha * beta).sqrt() || g == 0.0 {
+                    continue;
+                }
+                rotated = true;
+                let zeta = (beta - alpha) / (2.0 * g);
+                let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                let c = 1.0 / (1.0 + t * t).sqrt();
+                let s = c * t;
+                let phase = (gamma / g).conj();
+                for m in [&mut columns, &mut v] {
+                    let (head, tail) = m.split_at_mut(q);
+                    for (x, y) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+                        let y_rot = *y * phase;
+                        let x_old = *x;
+                        *x = x_old * c - y_rot * s;
+                        *y = x_old * s + y_rot * c;
+                    }
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    let norms: Vec<f64> = columns
+        .iter()
+        .map(|col| col.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt())
+        .collect();
+    let mut order: Vec<usize> = (0..cols).collect();
+    order.sort_by(|&i, &j| norms[j].total_cmp(&norms[i]));
+
+    let k = cols;
+    let mut u = vec![ZERO; rows * k];
+    let mut vh = vec![ZERO; k * cols];
+    let mut s = Vec::with_capacity(k);
+    for (rank, &j) in order.iter().enumerate() {
+        let sigma = norms[j];
+        s.push(sigma);
+        if sigma > 0.0 {
+            for i in 0..rows {
+                u[i * k + rank] = columns[j][i] / sigma;
+            }
+        }
+        for i in 0..cols {
+            vh[rank * cols + i] = v[j][i].conj();
+        }
+    }
+    (u, s, vh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statevector::Statevector;
+    use arvak_ir::{Circuit, QubitId};
+
+    /// Contract the chain into amplitudes indexed like the statevector's,
+    /// with qubit `k` as bit `k`.
+    fn amplitudes(state: &MpsState) -> Vec<Complex64> {
+        let n = state.sites.len();
+        let mut amps = vec![ZERO; 1 << n];
+        for (index, amp) in amps.iter_mut().enumerate() {
+            let mut env = vec![ONE];
+            for (q, site) in state.sites.iter().enumerate() {
+                let p = (index >> q) & 1;
+                let mut next = vec![ZERO; site.right];
+                for (l, e) in env.iter().enumerate() {
+                    for (r, n) in next.iter_mut().enumerate() {
+                        *n += e * site.data[(l * 2 + p) * site.right + r];
+                    }
+                }
+                env = next;
+            }
+            *amp = env[0];
+        }
+        amps
+    }
+
+    fn circuit() -> Circuit {
+        let mut circuit = Circuit::with_size("mixed", 5, 0);
+        circuit
+            .h(QubitId(0))
+            .unwrap()
+            .ry(0.7, QubitId(3))
+            .unwrap()
+            .cx(QubitId(0), QubitId(3))
+            .unwrap()
+            .rzz(1.1, QubitId(4), QubitId(1))
+            .unwrap()
+            .t(QubitId(2))
+            .unwrap()
+            .ccx(QubitId(3), QubitId(0), QubitId(2))
+            .unwrap()
+            .rx(0.4, QubitId(1))
+            .unwrap()
+            .cswap(QubitId(4), QubitId(2), QubitId(0))
+            .unwrap()
+            .cp(0.9, QubitId(2), QubitId(1))
+            .unwrap()
+            .iswap(QubitId(1), QubitId(4))
+            .unwrap()
+            .ecr(QubitId(3), QubitId(2))
+            .unwrap();
+        circuit
+    }
+
+    #[test]
+    fn test_svd_reconstructs() {
+        let (rows, cols) = (3, 5);
+        let a: Vec<Complex64> = (0..rows * cols)
+            .map(|i| Complex64::new((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+            .collect();
+        for (a, rows, cols) in [(&a, rows, cols), (&a, cols, rows)] {
+            let (u, s, vh) = svd(a, rows, cols);
+            let k = s.len();
+            assert!(s.windows(2).all(|w| w[0] >= w[1]));
+            for i in 0..rows {
+                for j in 0..cols {
+                    let value: Complex64 =
+                        (0..k).map(|m| u[i * k + m] * s[m] * vh[m * cols + j]).sum();
+                    assert!((value - a[i * cols + j]).norm() < 1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gates_match_statevector() {
+        let circuit = circuit();
+        let mut rng = rand::thread_rng();
+        let mut mps = MpsState::new(5);
+        let mut sv = Statevector::new(5);
+        for (_, inst) in circuit.dag().topological_ops() {
+            mps.apply(inst, &mut rng);
+            sv.apply(inst);
+        }
+
+        for (a, b) in amplitudes(&mps).iter().zip(sv.amplitudes()) {
+            assert!((a - b).norm() < 1e-10, "{} != {}", a, b);
+        }
+        assert!(mps.truncation_error() < 1e-12);
+        assert!(mps.peak_bond_dimension() <= 4);
+    }
+
+    #[test]
+    fn test_truncation_is_reported() {
+        // Four Bell pairs spanning the middle cut need bond dimension 16
+        let mut circuit = Circuit::with_size("pairs", 8, 0);
+        for i in 0..4 {
+            circuit.ry(0.3 + 0.4 * f64::from(i), QubitId(i)).unwrap();
+            circuit.cx(QubitId(i), QubitId(7 - i)).unwrap();
+        }
+        let mut rng = rand::thread_rng();
+
+        let mut exact = MpsState::new(8);
+        let mut truncated = MpsState::new(8).with_truncation(4, 0.0);
+        for (_, inst) in circuit.dag().topological_ops() {
+            exact.apply(inst, &mut rng);
+            truncated.apply(inst, &mut rng);
+        }
+        assert_eq!(exact.peak_bond_dimension(), 16);
+        assert!(exact.truncation_error() < 1e-12);
+        assert!(truncated.peak_bond_dimension() <= 4);
+        assert!(truncated.truncation_error() > 0.01);
+
+        // The truncation error bounds the infidelity with the exact state
+        let overlap: Complex64 = amplitudes(&exact)
+            .iter()
+            .zip(amplitudes(&truncated))
+            .map(|(a, b)| a.conj() * b)
+            .sum();
+        let norm: f64 = amplitudes(&truncated).iter().map(|a| a.norm_sqr()).sum();
+        assert!((norm - 1.0).abs() < 1e-10);
+        assert!(1.0 - overlap.norm_sqr() <= truncated.truncation_error() + 1e-10);
+    }
+
+    #[test]
+    fn test_sampling_and_reset() {
+        let mut rng = rand::thread_rng();
+        let mut state = MpsState::new(3);
+        for (_, inst) in Circuit::ghz(3).unwrap().dag().topological_ops() {
+            state.apply(inst, &mut rng);
+        }
+        let sampler = state.sampler();
+        let shots: Vec<String> = (0..200).map(|_| sampler.sample(&mut rng)).collect();
+        assert!(shots.iter().all(|s| s == "000" || s == "111"));
+        assert!(shots.iter().any(|s| s == "000") && shots.iter().any(|s| s == "111"));
+
+        // Reset returns a flipped qubit to |0⟩
+        let mut circuit = Circuit::with_size("reset", 3, 0);
+        circuit.x(QubitId(1)).unwrap().reset(QubitId(1)).unwrap();
+        let mut state = MpsState::new(3);
+        for (_, inst) in circuit.dag().topological_ops() {
+            state.apply(inst, &mut rng);
+        }
+        assert_eq!(state.sampler().sample(&mut rng), "000");
+    }
+}
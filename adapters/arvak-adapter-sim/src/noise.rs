@@ -0,0 +1,158 @@
+//! Noisy trajectories on the statevector engine.
+//!
+//! Each shot samples one trajectory of the [`NoiseModel`]: after every gate
+//! a Pauli error is drawn from the gate's channel, qubits idling in delays
+//! relax and dephase, and readout flips each measured bit with the qubit's
+//! confusion probabilities. Thermal relaxation is simulated exactly, as
+//! amplitude damping followed by pure dephasing, rather than twirled.
+
+use arvak_hal::{NoiseChannel, NoiseModel};
+use arvak_ir::{Instruction, InstructionKind};
+use rand::Rng;
+
+use crate::statevector::Statevector;
+
+/// Apply the noise the model assigns to an instruction that just ran.
+pub(crate) fn apply_noise(
+    sv: &mut Statevector,
+    instruction: &Instruction,
+    model: &NoiseModel,
+    dt_ns: f64,
+    rng: &mut impl Rng,
+) {
+    let qubits: Vec<u32> = instruction.qubits.iter().map(|q| q.0).collect();
+    match &instruction.kind {
+        InstructionKind::Gate(_) => {
+            if let Some(channel) = model.gate_channel(instruction.name(), &qubits) {
+                apply_channel(sv, channel, &qubits, rng);
+            }
+        }
+        InstructionKind::Delay { .. } => {
+            let duration_ns = instruction.delay_ns(Some(dt_ns)).unwrap_or(0.0);
+            for &qubit in &qubits {
+                if let Some(idle) = model.idle_noise(qubit) {
+                    apply_channel(sv, &idle.channel(duration_ns), &[qubit], rng);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Flip the bits of a measured outcome with each qubit's readout
+/// confusion.
+pub(crate) fn read_out(
+    outcome: usize,
+    model: &NoiseModel,
+    num_qubits: usize,
+    rng: &mut impl Rng,
+) -> usize {
+    (0..num_qubits).fold(outcome, |outcome, qubit| {
+        let Some(readout) = model.readout_error(qubit as u32) else {
+            return outcome;
+        };
+        let mask = 1 << qubit;
+        let flip = if outcome & mask == 0 {
+            readout.flip_zero
+        } else {
+            readout.flip_one
+        };
+        if rng.r#gen::<f64>() < flip {
+            outcome ^ mask
+        } else {
+            outcome
+        }
+    })
+}
+
+fn apply_channel(sv: &mut Statevector, channel: &NoiseChannel, qubits: &[u32], rng: &mut impl Rng) {
+    if let NoiseChannel::ThermalRelaxation {
+        t1_us,
+        t2_us,
+        duration_ns,
+    } = channel
+    {
+        let t_us = duration_ns / 1000.0;
+        let gamma = 1.0 - (-t_us / t1_us).exp();
+        // Dephasing beyond what relaxation already causes
+        let dephasing_rate = (1.0 / t2_us - 0.5 / t1_us).max(0.0);
+        let phase_flip = (1.0 - (-t_us * dephasing_rate).exp()) / 2.0;
+        for &qubit in qubits {
+            sv.amplitude_damp(qubit as usize, gamma, rng.r#gen());
+            if rng.r#gen::<f64>() < phase_flip {
+                sv.apply_pauli(qubit as usize, 3);
+            }
+        }
+        return;
+    }
+
+    // Channels were validated when the model was set
+    let Ok(probabilities) = channel.pauli_probabilities(qubits.len()) else {
+        return;
+    };
+    let r: f64 = rng.r#gen();
+    let mut cumulative = 0.0;
+    let pauli = probabilities
+        .iter()
+        .position(|p| {
+            cumulative += p;
+            r < cumulative
+        })
+        .unwrap_or(0);
+    for (i, &qubit) in qubits.iter().enumerate() {
+        let digit = (pauli >> (2 * (qubits.len() - 1 - i))) & 3;
+        sv.apply_pauli(qubit as usize, digit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_ir::{Circuit, QubitId};
+
+    fn run(circuit: &Circuit, model: &NoiseModel, shots: usize) -> Vec<usize> {
+        let mut rng = rand::thread_rng();
+        (0..shots)
+            .map(|_| {
+                let mut sv = Statevector::new(circuit.num_qubits());
+                for (_, inst) in circuit.dag().topological_ops() {
+                    sv.apply(inst);
+                    apply_noise(&mut sv, inst, model, 1.0, &mut rng);
+                }
+                read_out(sv.sample(), model, circuit.num_qubits(), &mut rng)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bit_flip_channel() {
+        let mut circuit = Circuit::with_size("x", 2, 0);
+        circuit.x(QubitId(0)).unwrap();
+        let model = NoiseModel::new().with_gate_noise(
+            "x",
+            &[],
+            NoiseChannel::Pauli {
+                probabilities: [("X".to_string(), 1.0)].into(),
+            },
+        );
+        // The error undoes the gate
+        assert!(run(&circuit, &model, 20).iter().all(|&o| o == 0));
+    }
+
+    #[test]
+    fn test_relaxation_during_delay() {
+        let mut circuit = Circuit::with_size("t1", 1, 0);
+        circuit.x(QubitId(0)).unwrap();
+        circuit.delay(QubitId(0), 1_000_000).unwrap();
+        // A millisecond is many T1 times
+        let model = NoiseModel::new().with_idle_noise(0, 10.0, 10.0);
+        assert!(run(&circuit, &model, 20).iter().all(|&o| o == 0));
+    }
+
+    #[test]
+    fn test_readout_confusion() {
+        let circuit = Circuit::with_size("idle", 2, 0);
+        let model = NoiseModel::new().with_readout_error(1, 1.0, 0.0);
+        assert!(run(&circuit, &model, 20).iter().all(|&o| o == 0b10));
+    }
+}
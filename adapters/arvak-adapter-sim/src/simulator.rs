@@ -1,19 +1,27 @@
 //! Simulator backend implementation.
 
 use async_trait::async_trait;
+use rayon::prelude::*;
 use rustc_hash::FxHashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 use uuid::Uuid;
 
 use arvak_hal::{
     Backend, BackendConfig, BackendFactory, Capabilities, Counts, ExecutionResult, HalError,
-    HalResult, Job, JobId, JobStatus,
+    HalResult, Job, JobId, JobStatus, NOISE_MODEL_KEY, NoiseModel,
 };
 use arvak_ir::Circuit;
 
+use crate::expectation::{ExpectationEstimate, Observable};
+use crate::mps::{DEFAULT_MAX_BOND_DIMENSION, DEFAULT_TRUNCATION_THRESHOLD, MpsState};
+use crate::noise;
+use crate::stabilizer::{StabilizerState, has_resets, is_clifford};
 use crate::statevector::Statevector;
+use crate::trace::{ExecutionTrace, TraceMode};
 
 /// Job data for the simulator.
 struct SimJob {
@@ -21,12 +29,58 @@ struct SimJob {
     #[allow(dead_code)]
     circuit: Circuit,
     result: Option<ExecutionResult>,
+    trace: Option<ExecutionTrace>,
+}
+
+/// Default maximum width of circuits run by the stabilizer engine.
+pub const DEFAULT_MAX_STABILIZER_QUBITS: u32 = 5_000;
+
+/// Default maximum width of circuits run by the MPS engine.
+pub const DEFAULT_MAX_MPS_QUBITS: u32 = 128;
+
+/// Simulation engine used for a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimulationMethod {
+    /// Stabilizer for Clifford circuits, statevector for circuits up to the
+    /// qubit limit, and MPS for wider circuits whose entanglement bound
+    /// fits the maximum bond dimension, so results stay exact.
+    #[default]
+    Auto,
+
+    /// Full statevector.
+    Statevector,
+
+    /// Stabilizer tableau; only Clifford circuits are accepted.
+    Stabilizer,
+
+    /// Matrix product state, truncated to the maximum bond dimension.
+    Mps,
+}
+
+impl FromStr for SimulationMethod {
+    type Err = String;
+
+    /// Parse `auto`, `statevector`, `stabilizer` or `mps`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(SimulationMethod::Auto),
+            "statevector" => Ok(SimulationMethod::Statevector),
+            "stabilizer" => Ok(SimulationMethod::Stabilizer),
+            "mps" => Ok(SimulationMethod::Mps),
+            other => Err(format!(
+                "Unknown simulation method '{}', expected auto, statevector, stabilizer or mps",
+                other
+            )),
+        }
+    }
 }
 
 /// Local simulator backend.
 ///
 /// This backend simulates quantum circuits using a statevector simulation.
-/// It supports circuits up to ~20 qubits (limited by memory).
+/// It supports circuits up to ~20 qubits (limited by memory). Clifford
+/// circuits run on a stabilizer tableau instead, up to thousands of qubits,
+/// and wider circuits with little entanglement on a matrix product state.
 pub struct SimulatorBackend {
     /// Backend configuration.
     config: BackendConfig,
@@ -34,6 +88,22 @@ pub struct SimulatorBackend {
     jobs: Arc<Mutex<FxHashMap<String, SimJob>>>,
     /// Maximum number of qubits supported.
     max_qubits: u32,
+    /// Maximum number of qubits of Clifford circuits.
+    max_stabilizer_qubits: u32,
+    /// Simulation engine selection.
+    method: SimulationMethod,
+    /// Maximum number of qubits of circuits run on the MPS engine.
+    max_mps_qubits: u32,
+    /// Maximum bond dimension of the MPS engine.
+    max_bond_dimension: usize,
+    /// Largest weight of singular values the MPS engine drops per split.
+    truncation_threshold: f64,
+    /// Sample period in nanoseconds for delays given in `dt`.
+    dt_ns: f64,
+    /// Execution trace mode and the file traces are written to.
+    trace: Option<(TraceMode, PathBuf)>,
+    /// Noise applied to sampled jobs.
+    noise_model: Option<NoiseModel>,
 }
 
 impl SimulatorBackend {
@@ -43,6 +113,14 @@ impl SimulatorBackend {
             config: BackendConfig::new("simulator"),
             jobs: Arc::new(Mutex::new(FxHashMap::default())),
             max_qubits: 20,
+            max_stabilizer_qubits: DEFAULT_MAX_STABILIZER_QUBITS,
+            method: SimulationMethod::Auto,
+            max_mps_qubits: DEFAULT_MAX_MPS_QUBITS,
+            max_bond_dimension: DEFAULT_MAX_BOND_DIMENSION,
+            truncation_threshold: DEFAULT_TRUNCATION_THRESHOLD,
+            dt_ns: 1.0,
+            trace: None,
+            noise_model: None,
         }
     }
 
@@ -52,12 +130,364 @@ impl SimulatorBackend {
             config: BackendConfig::new("simulator"),
             jobs: Arc::new(Mutex::new(FxHashMap::default())),
             max_qubits,
+            max_stabilizer_qubits: DEFAULT_MAX_STABILIZER_QUBITS.max(max_qubits),
+            method: SimulationMethod::Auto,
+            max_mps_qubits: DEFAULT_MAX_MPS_QUBITS.max(max_qubits),
+            max_bond_dimension: DEFAULT_MAX_BOND_DIMENSION,
+            truncation_threshold: DEFAULT_TRUNCATION_THRESHOLD,
+            dt_ns: 1.0,
+            trace: None,
+            noise_model: None,
+        }
+    }
+
+    /// Set the maximum number of qubits of Clifford circuits, which run on
+    /// the stabilizer engine.
+    pub fn with_max_stabilizer_qubits(mut self, max_qubits: u32) -> Self {
+        self.max_stabilizer_qubits = max_qubits;
+        self
+    }
+
+    /// Select the simulation engine instead of choosing one per circuit.
+    pub fn with_method(mut self, method: SimulationMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Set the maximum number of qubits of circuits run on the MPS engine.
+    pub fn with_max_mps_qubits(mut self, max_qubits: u32) -> Self {
+        self.max_mps_qubits = max_qubits;
+        self
+    }
+
+    /// Set the maximum bond dimension of the MPS engine and the largest
+    /// weight of singular values it drops at each split.
+    ///
+    /// The discarded weight is reported as `truncation_error` in the result
+    /// metadata.
+    pub fn with_bond_dimension(
+        mut self,
+        max_bond_dimension: usize,
+        truncation_threshold: f64,
+    ) -> Self {
+        self.max_bond_dimension = max_bond_dimension;
+        self.truncation_threshold = truncation_threshold;
+        self
+    }
+
+    /// Set the sample period in nanoseconds used for delays given in `dt`.
+    ///
+    /// Delays only advance the simulated clock; the total is reported as
+    /// `duration_ns` in the result metadata.
+    pub fn with_dt(mut self, dt_ns: f64) -> Self {
+        self.dt_ns = dt_ns;
+        self
+    }
+
+    /// Record a gate-level trace of every job and write it to `path`.
+    ///
+    /// Traces need amplitudes, so traced jobs always run on the statevector
+    /// engine.
+    ///
+    /// The trace covers the first shot; each job overwrites the file, and
+    /// its path is reported as `trace_path` in the result metadata. Use
+    /// [`trace`](Self::trace) to get the trace of an earlier job.
+    pub fn with_trace(mut self, mode: TraceMode, path: impl Into<PathBuf>) -> Self {
+        self.trace = Some((mode, path.into()));
+        self
+    }
+
+    /// Run jobs under a noise model, e.g. one built from a backend's
+    /// calibration. Fails if a channel of the model is unphysical.
+    ///
+    /// Noisy jobs run on the statevector engine, one noise trajectory per
+    /// shot, and name the model as `noise_model` in the result metadata.
+    /// Expectation values are still computed without noise.
+    pub fn with_noise_model(mut self, model: NoiseModel) -> HalResult<Self> {
+        model.validate()?;
+        self.noise_model = Some(model);
+        Ok(self)
+    }
+
+    /// Get the noise model jobs run under, if any.
+    pub fn noise_model(&self) -> Option<&NoiseModel> {
+        self.noise_model.as_ref()
+    }
+
+    /// Get the execution trace recorded for a job.
+    pub fn trace(&self, job_id: &JobId) -> Option<ExecutionTrace> {
+        let jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
+        jobs.get(&job_id.0).and_then(|j| j.trace.clone())
+    }
+
+    /// Expectation value of `observable` in the state prepared by `circuit`.
+    ///
+    /// Measurements in the circuit are ignored.
+    pub fn expectation(&self, circuit: &Circuit, observable: &Observable) -> HalResult<f64> {
+        self.check_expectation_inputs(circuit, observable)?;
+        observable.evaluate(circuit, &observable.groups())
+    }
+
+    /// Exact expectation value of `observable` with the single-shot
+    /// variances of its terms and measurement groups, for reporting the
+    /// standard error a shot-based estimate would have.
+    pub fn expectation_estimate(
+        &self,
+        circuit: &Circuit,
+        observable: &Observable,
+    ) -> HalResult<ExpectationEstimate> {
+        self.check_expectation_inputs(circuit, observable)?;
+        observable.estimate(circuit, &observable.groups())
+    }
+
+    /// Expectation values of `observable` for each row of `params` bound to
+    /// the symbolic parameters of `ansatz`.
+    ///
+    /// Rows bind parameters in the order of [`Circuit::parameter_names`].
+    /// Parameter sets are evaluated in parallel, and the observable's
+    /// commuting groups are computed once for the whole batch.
+    pub fn batch_expectation(
+        &self,
+        ansatz: &Circuit,
+        params: &[Vec<f64>],
+        observable: &Observable,
+    ) -> HalResult<Vec<f64>> {
+        self.check_expectation_inputs(ansatz, observable)?;
+        let groups = observable.groups();
+        debug!(
+            "Batched expectation: {} parameter sets, {} terms in {} groups",
+            params.len(),
+            observable.terms.len(),
+            groups.len()
+        );
+
+        params
+            .par_iter()
+            .map(|row| {
+                let circuit = ansatz
+                    .bind_parameters(row)
+                    .map_err(|e| HalError::InvalidCircuit(e.to_string()))?;
+                observable.evaluate(&circuit, &groups)
+            })
+            .collect()
+    }
+
+    /// Gradient of the expectation value by the parameter-shift rule.
+    ///
+    /// Valid when every parameter enters a single Pauli rotation (`rx`,
+    /// `ry`, `rz`, `rxx`, ...) unscaled. The 2n shifted evaluations run as
+    /// one batch.
+    pub fn parameter_shift_gradient(
+        &self,
+        ansatz: &Circuit,
+        params: &[f64],
+        observable: &Observable,
+    ) -> HalResult<Vec<f64>> {
+        let shift = std::f64::consts::FRAC_PI_2;
+        let shifted: Vec<Vec<f64>> = (0..params.len())
+            .flat_map(|i| {
+                [shift, -shift].map(|s| {
+                    let mut row = params.to_vec();
+                    row[i] += s;
+                    row
+                })
+            })
+            .collect();
+
+        let values = self.batch_expectation(ansatz, &shifted, observable)?;
+        Ok(values
+            .chunks(2)
+            .map(|pair| (pair[0] - pair[1]) / 2.0)
+            .collect())
+    }
+
+    fn check_expectation_inputs(
+        &self,
+        circuit: &Circuit,
+        observable: &Observable,
+    ) -> HalResult<()> {
+        if circuit.num_qubits() > self.max_qubits as usize {
+            return Err(HalError::CircuitTooLarge(format!(
+                "Circuit has {} qubits but simulator only supports {}",
+                circuit.num_qubits(),
+                self.max_qubits
+            )));
+        }
+        if let Some(q) = observable.max_qubit() {
+            if q as usize >= circuit.num_qubits() {
+                return Err(HalError::InvalidCircuit(format!(
+                    "Observable acts on qubit {} but the circuit has {} qubits",
+                    q,
+                    circuit.num_qubits()
+                )));
+            }
         }
+        Ok(())
+    }
+
+    /// Choose the engine for a circuit. Traced and noisy circuits always
+    /// run on the statevector.
+    fn method_for(&self, circuit: &Circuit) -> SimulationMethod {
+        if self.trace.is_some() || self.noise_model.is_some() {
+            return SimulationMethod::Statevector;
+        }
+        match self.method {
+            SimulationMethod::Auto if is_clifford(circuit) => SimulationMethod::Stabilizer,
+            SimulationMethod::Auto
+                if circuit.num_qubits() > self.max_qubits as usize
+                    && circuit.bond_dimension_bound() <= self.max_bond_dimension as u64 =>
+            {
+                SimulationMethod::Mps
+            }
+            SimulationMethod::Auto => SimulationMethod::Statevector,
+            method => method,
+        }
+    }
+
+    /// Run a Clifford circuit on the stabilizer engine.
+    fn run_stabilizer(&self, circuit: &Circuit, shots: u32) -> ExecutionResult {
+        let start = Instant::now();
+        let num_qubits = circuit.num_qubits();
+        debug!(
+            "Starting stabilizer simulation: {} qubits, {} shots",
+            num_qubits, shots
+        );
+
+        let instructions: Vec<_> = circuit
+            .dag()
+            .topological_ops()
+            .map(|(_, inst)| inst.clone())
+            .collect();
+        let mut rng = rand::thread_rng();
+        let mut counts = Counts::new();
+        let prepare = |rng: &mut rand::rngs::ThreadRng| {
+            let mut state = StabilizerState::new(num_qubits).with_dt(self.dt_ns);
+            for inst in &instructions {
+                state.apply(inst, rng);
+            }
+            state
+        };
+
+        let mut duration_ns = 0.0;
+        if has_resets(circuit) {
+            // Resets collapse the state differently in every shot
+            for _ in 0..shots {
+                let mut state = prepare(&mut rng);
+                counts.insert(state.measure_all(&mut rng), 1);
+                duration_ns = state.elapsed_ns();
+            }
+        } else if shots > 0 {
+            let state = prepare(&mut rng);
+            let sampler = state.sampler();
+            for _ in 0..shots {
+                counts.insert(sampler.sample(&mut rng), 1);
+            }
+            duration_ns = state.elapsed_ns();
+        }
+
+        let elapsed = start.elapsed();
+        debug!("Stabilizer simulation completed in {:?}", elapsed);
+
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("method".to_string(), "stabilizer".into());
+        if duration_ns > 0.0 {
+            metadata.insert("duration_ns".to_string(), duration_ns.into());
+        }
+        ExecutionResult::new(counts, shots)
+            .with_execution_time(elapsed.as_millis() as u64)
+            .with_metadata(serde_json::Value::Object(metadata))
+    }
+
+    /// Run a circuit on the MPS engine.
+    fn run_mps(&self, circuit: &Circuit, shots: u32) -> ExecutionResult {
+        let start = Instant::now();
+        let num_qubits = circuit.num_qubits();
+        debug!(
+            "Starting MPS simulation: {} qubits, {} shots, bond dimension {}",
+            num_qubits, shots, self.max_bond_dimension
+        );
+
+        let instructions: Vec<_> = circuit
+            .dag()
+            .topological_ops()
+            .map(|(_, inst)| inst.clone())
+            .collect();
+        let mut rng = rand::thread_rng();
+        let mut counts = Counts::new();
+        let prepare = |rng: &mut rand::rngs::ThreadRng| {
+            let mut state = MpsState::new(num_qubits)
+                .with_truncation(self.max_bond_dimension, self.truncation_threshold)
+                .with_dt(self.dt_ns);
+            for inst in &instructions {
+                state.apply(inst, rng);
+            }
+            state
+        };
+
+        let mut last = None;
+        if has_resets(circuit) {
+            // Resets collapse the state differently in every shot
+            for _ in 0..shots {
+                let state = prepare(&mut rng);
+                counts.insert(state.sampler().sample(&mut rng), 1);
+                last = Some(state);
+            }
+        } else if shots > 0 {
+            let state = prepare(&mut rng);
+            let sampler = state.sampler();
+            for _ in 0..shots {
+                counts.insert(sampler.sample(&mut rng), 1);
+            }
+            last = Some(state);
+        }
+
+        let elapsed = start.elapsed();
+        debug!("MPS simulation completed in {:?}", elapsed);
+
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("method".to_string(), "mps".into());
+        metadata.insert(
+            "max_bond_dimension".to_string(),
+            self.max_bond_dimension.into(),
+        );
+        if let Some(state) = last {
+            if state.truncation_error() > 0.0 {
+                warn!(
+                    "MPS simulation truncated: error {:.3e} at bond dimension {}",
+                    state.truncation_error(),
+                    self.max_bond_dimension
+                );
+            }
+            metadata.insert(
+                "truncation_error".to_string(),
+                state.truncation_error().into(),
+            );
+            metadata.insert(
+                "bond_dimension".to_string(),
+                state.peak_bond_dimension().into(),
+            );
+            if state.elapsed_ns() > 0.0 {
+                metadata.insert("duration_ns".to_string(), state.elapsed_ns().into());
+            }
+        }
+        ExecutionResult::new(counts, shots)
+            .with_execution_time(elapsed.as_millis() as u64)
+            .with_metadata(serde_json::Value::Object(metadata))
     }
 
     /// Run simulation synchronously.
     #[instrument(skip(self, circuit))]
-    fn run_simulation(&self, circuit: &Circuit, shots: u32) -> ExecutionResult {
+    fn run_simulation(
+        &self,
+        circuit: &Circuit,
+        shots: u32,
+    ) -> (ExecutionResult, Option<ExecutionTrace>) {
+        match self.method_for(circuit) {
+            SimulationMethod::Stabilizer => return (self.run_stabilizer(circuit, shots), None),
+            SimulationMethod::Mps => return (self.run_mps(circuit, shots), None),
+            _ => {}
+        }
+
         let start = Instant::now();
 
         let num_qubits = circuit.num_qubits();
@@ -67,6 +497,7 @@ impl SimulatorBackend {
         );
 
         let mut counts = Counts::new();
+        let mut duration_ns = 0.0;
 
         // Collect instructions
         let instructions: Vec<_> = circuit
@@ -77,20 +508,38 @@ impl SimulatorBackend {
 
         debug!("Circuit has {} instructions", instructions.len());
 
+        let mut trace = self
+            .trace
+            .as_ref()
+            .map(|(mode, _)| ExecutionTrace::new(circuit.name(), num_qubits, mode.clone()));
+        let mut rng = rand::thread_rng();
+
         // Run shots
         for shot in 0..shots {
             // Initialize statevector
-            let mut sv = Statevector::new(num_qubits);
+            let mut sv = Statevector::new(num_qubits).with_dt(self.dt_ns);
 
-            // Apply all gates
+            // Apply all gates, with one noise trajectory per shot, tracing
+            // the first shot
+            let mut shot_trace = trace.as_mut().filter(|_| shot == 0);
             for inst in &instructions {
                 sv.apply(inst);
+                if let Some(model) = &self.noise_model {
+                    noise::apply_noise(&mut sv, inst, model, self.dt_ns, &mut rng);
+                }
+                if let Some(trace) = shot_trace.as_mut() {
+                    trace.record(inst, &sv);
+                }
             }
 
             // Sample and record result
-            let outcome = sv.sample();
+            let mut outcome = sv.sample();
+            if let Some(model) = &self.noise_model {
+                outcome = noise::read_out(outcome, model, num_qubits, &mut rng);
+            }
             let bitstring = sv.outcome_to_bitstring(outcome);
             counts.insert(bitstring, 1);
+            duration_ns = sv.elapsed_ns();
 
             if shot > 0 && shot % 1000 == 0 {
                 debug!("Completed {} shots", shot);
@@ -100,7 +549,30 @@ impl SimulatorBackend {
         let elapsed = start.elapsed();
         debug!("Simulation completed in {:?}", elapsed);
 
-        ExecutionResult::new(counts, shots).with_execution_time(elapsed.as_millis() as u64)
+        let mut metadata = serde_json::Map::new();
+        if duration_ns > 0.0 {
+            metadata.insert("duration_ns".to_string(), duration_ns.into());
+        }
+        if let Some(model) = &self.noise_model {
+            metadata.insert(NOISE_MODEL_KEY.to_string(), model.label().into());
+        }
+        if let (Some(trace), Some((_, path))) = (&trace, &self.trace) {
+            match trace.save(path) {
+                Ok(()) => {
+                    metadata.insert("trace_path".to_string(), path.display().to_string().into());
+                }
+                Err(e) => warn!("Failed to write trace to {}: {}", path.display(), e),
+            }
+        }
+
+        let result =
+            ExecutionResult::new(counts, shots).with_execution_time(elapsed.as_millis() as u64);
+        let result = if metadata.is_empty() {
+            result
+        } else {
+            result.with_metadata(serde_json::Value::Object(metadata))
+        };
+        (result, trace)
     }
 }
 
@@ -117,7 +589,10 @@ impl Backend for SimulatorBackend {
     }
 
     async fn capabilities(&self) -> HalResult<Capabilities> {
-        Ok(Capabilities::simulator(self.max_qubits))
+        let mut caps = Capabilities::simulator(self.max_qubits);
+        caps.features.push("stabilizer".into());
+        caps.features.push("mps".into());
+        Ok(caps)
     }
 
     async fn is_available(&self) -> HalResult<bool> {
@@ -126,12 +601,22 @@ impl Backend for SimulatorBackend {
 
     #[instrument(skip(self, circuit))]
     async fn submit(&self, circuit: &Circuit, shots: u32) -> HalResult<JobId> {
-        // Validate circuit size
-        if circuit.num_qubits() > self.max_qubits as usize {
+        // Validate circuit size against the limit of its engine
+        let max_qubits = match self.method_for(circuit) {
+            SimulationMethod::Stabilizer if !is_clifford(circuit) => {
+                return Err(HalError::InvalidCircuit(
+                    "Stabilizer simulation requires a Clifford circuit".into(),
+                ));
+            }
+            SimulationMethod::Stabilizer => self.max_stabilizer_qubits,
+            SimulationMethod::Mps => self.max_mps_qubits,
+            _ => self.max_qubits,
+        };
+        if circuit.num_qubits() > max_qubits as usize {
             return Err(HalError::CircuitTooLarge(format!(
                 "Circuit has {} qubits but simulator only supports {}",
                 circuit.num_qubits(),
-                self.max_qubits
+                max_qubits
             )));
         }
 
@@ -145,6 +630,7 @@ impl Backend for SimulatorBackend {
             job,
             circuit: circuit.clone(),
             result: None,
+            trace: None,
         };
 
         // Store job
@@ -156,13 +642,14 @@ impl Backend for SimulatorBackend {
         debug!("Submitted job: {}", job_id);
 
         // Run simulation immediately (in a real implementation, this would be async)
-        let result = self.run_simulation(circuit, shots);
+        let (result, trace) = self.run_simulation(circuit, shots);
 
         // Update job with result
         {
             let mut jobs = self.jobs.lock().unwrap_or_else(|e| e.into_inner());
             if let Some(sim_job) = jobs.get_mut(&job_id.0) {
                 sim_job.result = Some(result);
+                sim_job.trace = trace;
                 sim_job.job = sim_job.job.clone().with_status(JobStatus::Completed);
             }
         }
@@ -203,11 +690,74 @@ impl BackendFactory for SimulatorBackend {
             .and_then(|v| v.as_u64())
             .map(|v| v as u32)
             .unwrap_or(20);
+        let max_stabilizer_qubits = config
+            .extra
+            .get("max_stabilizer_qubits")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or(DEFAULT_MAX_STABILIZER_QUBITS);
+        let method = config
+            .extra
+            .get("method")
+            .and_then(|v| v.as_str())
+            .map(str::parse)
+            .transpose()
+            .map_err(HalError::Configuration)?
+            .unwrap_or_default();
+        let max_mps_qubits = config
+            .extra
+            .get("max_mps_qubits")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or(DEFAULT_MAX_MPS_QUBITS);
+        let max_bond_dimension = config
+            .extra
+            .get("max_bond_dimension")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(DEFAULT_MAX_BOND_DIMENSION);
+        let truncation_threshold = config
+            .extra
+            .get("truncation_threshold")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(DEFAULT_TRUNCATION_THRESHOLD);
+        let dt_ns = config
+            .extra
+            .get("dt_ns")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0);
+        let trace = match (
+            config.extra.get("trace_mode").and_then(|v| v.as_str()),
+            config.extra.get("trace_path").and_then(|v| v.as_str()),
+        ) {
+            (Some(mode), Some(path)) => Some((
+                mode.parse().map_err(HalError::Configuration)?,
+                PathBuf::from(path),
+            )),
+            _ => None,
+        };
+        let noise_model: Option<NoiseModel> = config
+            .extra
+            .get("noise_model")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .map_err(|e| HalError::Configuration(format!("noise_model: {}", e)))?;
+        if let Some(model) = &noise_model {
+            model.validate()?;
+        }
 
         Ok(Self {
             config,
             jobs: Arc::new(Mutex::new(FxHashMap::default())),
             max_qubits,
+            max_stabilizer_qubits,
+            method,
+            max_mps_qubits,
+            max_bond_dimension,
+            truncation_threshold,
+            dt_ns,
+            trace,
+            noise_model,
         })
     }
 }
@@ -244,6 +794,60 @@ mod tests {
         assert!(counts.get("01") + counts.get("10") == 0);
     }
 
+    #[tokio::test]
+    async fn test_simulator_noise_model() {
+        let config = BackendConfig::new("noisy").with_extra(
+            "noise_model",
+            serde_json::json!({
+                "source": "test",
+                "gates": [{
+                    "gate": "cx",
+                    "qubits": [0, 1],
+                    "channel": {"type": "pauli", "probabilities": {"XI": 1.0}}
+                }]
+            }),
+        );
+        let backend = SimulatorBackend::from_config(config).unwrap();
+        assert_eq!(backend.noise_model().unwrap().label(), "test");
+
+        // Every shot flips qubit 0 after the CX
+        let circuit = Circuit::bell().unwrap();
+        let job_id = backend.submit(&circuit, 200).await.unwrap();
+        let result = backend.result(&job_id).await.unwrap();
+        assert_eq!(result.counts.get("01") + result.counts.get("10"), 200);
+        assert_eq!(result.metadata[NOISE_MODEL_KEY], "test");
+
+        let unphysical = NoiseModel::new().with_readout_error(0, 2.0, 0.0);
+        assert!(
+            SimulatorBackend::new()
+                .with_noise_model(unphysical)
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simulator_run_chunked() {
+        let backend = SimulatorBackend::new();
+        let circuit = Circuit::bell().unwrap();
+
+        let mut snapshots = Vec::new();
+        let result = backend
+            .run_chunked(&circuit, 1000, 300, &mut |partial| snapshots.push(partial))
+            .await
+            .unwrap();
+
+        let completed: Vec<u32> = snapshots.iter().map(|p| p.shots_completed).collect();
+        assert_eq!(completed, vec![300, 600, 900]);
+        assert!(snapshots.iter().all(|p| !p.is_final));
+        assert!(
+            snapshots
+                .iter()
+                .all(|p| p.counts.total_shots() == u64::from(p.shots_completed))
+        );
+        assert_eq!(result.shots, 1000);
+        assert_eq!(result.counts.get("00") + result.counts.get("11"), 1000);
+    }
+
     #[tokio::test]
     async fn test_simulator_ghz_state() {
         let backend = SimulatorBackend::new();
@@ -258,13 +862,241 @@ mod tests {
         assert!(counts.get("000") + counts.get("111") == 1000);
     }
 
+    #[tokio::test]
+    async fn test_simulator_delay_duration() {
+        let backend = SimulatorBackend::new().with_dt(0.5);
+
+        let mut circuit = Circuit::bell().unwrap();
+        circuit.delay(arvak_ir::QubitId(0), 400).unwrap();
+        let job_id = backend.submit(&circuit, 100).await.unwrap();
+
+        let result = backend.result(&job_id).await.unwrap();
+        assert_eq!(result.counts.get("00") + result.counts.get("11"), 100);
+        assert_eq!(result.metadata["duration_ns"], 200.0);
+    }
+
+    #[tokio::test]
+    async fn test_simulator_trace() {
+        let dir = std::env::temp_dir().join(format!("arvak-sim-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trace.json");
+        let backend = SimulatorBackend::new().with_trace(TraceMode::Snapshots, &path);
+
+        let mut circuit = Circuit::with_size("bell", 2, 0);
+        circuit.h(arvak_ir::QubitId(0)).unwrap();
+        circuit.snapshot("superposed").unwrap();
+        circuit
+            .cx(arvak_ir::QubitId(0), arvak_ir::QubitId(1))
+            .unwrap();
+        let job_id = backend.submit(&circuit, 10).await.unwrap();
+
+        let trace = backend.trace(&job_id).unwrap();
+        let names: Vec<_> = trace.steps.iter().map(|s| s.instruction.as_str()).collect();
+        assert_eq!(names, vec!["h", "barrier", "cx"]);
+        let state = trace.snapshot("superposed").unwrap();
+        assert!((state[1].norm_sqr() - 0.5).abs() < 1e-10);
+        assert!(state[3].norm_sqr() < 1e-10);
+
+        let result = backend.result(&job_id).await.unwrap();
+        assert_eq!(result.metadata["trace_path"], path.display().to_string());
+        let loaded = ExecutionTrace::load(&path).unwrap();
+        assert_eq!(loaded.steps.len(), 3);
+        assert_eq!(loaded.snapshots[0].label, "superposed");
+        std::fs::remove_dir_all(dir).unwrap();
+
+        // Tracing is off by default.
+        let plain = SimulatorBackend::new();
+        let job_id = plain.submit(&circuit, 10).await.unwrap();
+        assert!(plain.trace(&job_id).is_none());
+    }
+
     #[tokio::test]
     async fn test_simulator_too_many_qubits() {
-        let backend = SimulatorBackend::with_max_qubits(5);
+        let backend = SimulatorBackend::with_max_qubits(5)
+            .with_max_stabilizer_qubits(8)
+            .with_max_mps_qubits(8);
 
-        let circuit = Circuit::with_size("test", 10, 0);
+        let mut circuit = Circuit::with_size("test", 10, 0);
+        circuit.t(arvak_ir::QubitId(0)).unwrap();
         let result = backend.submit(&circuit, 100).await;
 
         assert!(matches!(result, Err(HalError::CircuitTooLarge(_))));
+
+        // Clifford circuits get the stabilizer engine's limit
+        let result = backend.submit(&Circuit::ghz(10).unwrap(), 100).await;
+        assert!(matches!(result, Err(HalError::CircuitTooLarge(_))));
+        assert!(backend.submit(&Circuit::ghz(8).unwrap(), 100).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_simulator_wide_clifford_circuit() {
+        use arvak_ir::QubitId;
+
+        let backend = SimulatorBackend::new();
+        let n = 1_000;
+        let circuit = Circuit::ghz(n).unwrap();
+        let job_id = backend.submit(&circuit, 200).await.unwrap();
+        let result = backend.result(&job_id).await.unwrap();
+        assert_eq!(result.metadata["method"], "stabilizer");
+        let zeros = "0".repeat(n as usize);
+        let ones = "1".repeat(n as usize);
+        assert_eq!(result.counts.get(&zeros) + result.counts.get(&ones), 200);
+        assert!(result.counts.get(&zeros) > 0 && result.counts.get(&ones) > 0);
+
+        // Resetting half of a Bell pair leaves the other half random
+        let mut circuit = Circuit::bell().unwrap();
+        circuit.reset(QubitId(0)).unwrap();
+        let job_id = backend.submit(&circuit, 400).await.unwrap();
+        let counts = backend.result(&job_id).await.unwrap().counts;
+        assert_eq!(counts.get("00") + counts.get("01"), 400);
+        assert!(counts.get("00") > 100 && counts.get("01") > 100);
+    }
+
+    #[tokio::test]
+    async fn test_simulator_mps() {
+        use arvak_ir::QubitId;
+
+        // One QAOA layer on a 40-qubit ring: too wide for the statevector,
+        // but every cut is crossed by only two ZZ rotations
+        let n = 40;
+        let mut circuit = Circuit::with_size("qaoa", n, 0);
+        for i in 0..n {
+            circuit.h(QubitId(i)).unwrap();
+        }
+        for i in 0..n {
+            circuit.rzz(0.8, QubitId(i), QubitId((i + 1) % n)).unwrap();
+        }
+        for i in 0..n {
+            circuit.rx(0.6, QubitId(i)).unwrap();
+        }
+        assert_eq!(circuit.bond_dimension_bound(), 4);
+
+        let backend = SimulatorBackend::new();
+        let job_id = backend.submit(&circuit, 100).await.unwrap();
+        let result = backend.result(&job_id).await.unwrap();
+        assert_eq!(result.metadata["method"], "mps");
+        assert!(result.metadata["truncation_error"].as_f64().unwrap() < 1e-9);
+        assert!(result.metadata["bond_dimension"].as_u64().unwrap() <= 4);
+        assert_eq!(result.counts.total_shots(), 100);
+        assert!(
+            result
+                .counts
+                .iter()
+                .all(|(bits, _)| bits.len() == n as usize)
+        );
+
+        // Forcing a small bond dimension truncates, and says so
+        let config = BackendConfig::new("simulator")
+            .with_extra("method", "mps".into())
+            .with_extra("max_bond_dimension", 2.into());
+        let truncated = SimulatorBackend::from_config(config).unwrap();
+        let job_id = truncated.submit(&circuit, 10).await.unwrap();
+        let result = truncated.result(&job_id).await.unwrap();
+        assert_eq!(result.metadata["max_bond_dimension"], 2);
+        assert!(result.metadata["truncation_error"].as_f64().unwrap() > 0.0);
+
+        // MPS also runs narrow circuits when selected
+        let backend = SimulatorBackend::new().with_method(SimulationMethod::Mps);
+        let job_id = backend
+            .submit(&Circuit::bell().unwrap(), 200)
+            .await
+            .unwrap();
+        let result = backend.result(&job_id).await.unwrap();
+        assert_eq!(result.metadata["method"], "mps");
+        assert_eq!(result.counts.get("00") + result.counts.get("11"), 200);
+
+        // The stabilizer engine only takes Clifford circuits
+        let stabilizer = SimulatorBackend::new().with_method(SimulationMethod::Stabilizer);
+        assert!(matches!(
+            stabilizer.submit(&circuit, 10).await,
+            Err(HalError::InvalidCircuit(_))
+        ));
+        let config = BackendConfig::new("simulator").with_extra("method", "tensor".into());
+        assert!(SimulatorBackend::from_config(config).is_err());
+    }
+
+    fn ry_ansatz() -> Circuit {
+        use arvak_ir::{ParameterExpression, QubitId};
+
+        let mut circuit = Circuit::with_size("ansatz", 2, 0);
+        circuit
+            .ry(ParameterExpression::symbol("a"), QubitId(0))
+            .unwrap()
+            .ry(ParameterExpression::symbol("b"), QubitId(1))
+            .unwrap()
+            .cx(QubitId(0), QubitId(1))
+            .unwrap();
+        circuit
+    }
+
+    #[test]
+    fn test_batch_expectation() {
+        use arvak_ir::PauliAxis;
+
+        let backend = SimulatorBackend::new();
+        let ansatz = ry_ansatz();
+        let observable = Observable::new()
+            .with_term(1.0, [(0, PauliAxis::Z)])
+            .with_term(0.5, [(0, PauliAxis::X), (1, PauliAxis::X)]);
+
+        let params: Vec<Vec<f64>> = (0..8).map(|i| vec![i as f64 * 0.4, 0.3]).collect();
+        let batch = backend
+            .batch_expectation(&ansatz, &params, &observable)
+            .unwrap();
+
+        assert_eq!(batch.len(), params.len());
+        for (row, value) in params.iter().zip(&batch) {
+            let single = backend
+                .expectation(&ansatz.bind_parameters(row).unwrap(), &observable)
+                .unwrap();
+            assert!((single - value).abs() < 1e-12);
+            // CX maps Z0 to Z0 and X0X1 to X0, so the value is cos(a) + sin(a) / 2.
+            assert!((value - row[0].cos() - 0.5 * row[0].sin()).abs() < 1e-10);
+        }
+
+        let estimate = backend
+            .expectation_estimate(&ansatz.bind_parameters(&params[1]).unwrap(), &observable)
+            .unwrap();
+        assert!((estimate.value - batch[1]).abs() < 1e-12);
+        assert_eq!(estimate.term_variances.len(), 2);
+        assert!(estimate.std_error(1000) > 0.0);
+
+        let unbound = backend.batch_expectation(&ansatz, &[vec![0.1]], &observable);
+        assert!(matches!(unbound, Err(HalError::InvalidCircuit(_))));
+        let too_wide = Observable::new().with_term(1.0, [(2, PauliAxis::Z)]);
+        assert!(
+            backend
+                .expectation(&Circuit::bell().unwrap(), &too_wide)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parameter_shift_gradient() {
+        use arvak_ir::PauliAxis;
+
+        let backend = SimulatorBackend::new();
+        let ansatz = ry_ansatz();
+        let observable = Observable::new()
+            .with_term(1.0, [(0, PauliAxis::Z), (1, PauliAxis::Z)])
+            .with_term(0.7, [(1, PauliAxis::X)]);
+        let params = [0.4, -1.1];
+
+        let gradient = backend
+            .parameter_shift_gradient(&ansatz, &params, &observable)
+            .unwrap();
+
+        let h = 1e-6;
+        for i in 0..params.len() {
+            let mut plus = params.to_vec();
+            let mut minus = params.to_vec();
+            plus[i] += h;
+            minus[i] -= h;
+            let values = backend
+                .batch_expectation(&ansatz, &[plus, minus], &observable)
+                .unwrap();
+            let finite_difference = (values[0] - values[1]) / (2.0 * h);
+            assert!((gradient[i] - finite_difference).abs() < 1e-6);
+        }
     }
 }
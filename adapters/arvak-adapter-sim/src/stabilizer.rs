@@ -0,0 +1,617 @@
+//! Stabilizer (Clifford) simulation engine.
+//!
+//! Clifford circuits keep their state as a tableau of 2n Pauli generators
+//! (Aaronson & Gottesman, "Improved simulation of stabilizer circuits",
+//! 2004) instead of 2^n amplitudes, so randomized benchmarking and error
+//! correction circuits of thousands of qubits fit in memory.
+//!
+//! The computational-basis outcomes of a stabilizer state are uniform over
+//! an affine space: one reference outcome plus any combination of the X
+//! parts of the stabilizers. The tableau is built once per circuit and
+//! shots are drawn from that space, unless the circuit resets qubits and
+//! so depends on earlier random outcomes; then every shot is simulated.
+
+use std::f64::consts::FRAC_PI_2;
+
+use rand::Rng;
+
+use arvak_ir::{
+    Circuit, GateKind, Instruction, InstructionKind, ParameterExpression, StandardGate,
+};
+
+/// Primitive tableau update, on a gate's qubits by position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prim {
+    H(usize),
+    S(usize),
+    X(usize),
+    Y(usize),
+    Z(usize),
+    CX(usize, usize),
+}
+
+/// Number of quarter turns an angle makes, if it is a bound multiple of
+/// π/2.
+fn quarter_turns(angle: &ParameterExpression) -> Option<u8> {
+    let turns = angle.as_f64()? / FRAC_PI_2;
+    let rounded = turns.round();
+    ((turns - rounded).abs() < 1e-9).then(|| rounded.rem_euclid(4.0) as u8)
+}
+
+/// Append `Rz` by `turns` quarter turns, up to global phase.
+fn push_rz(ops: &mut Vec<Prim>, turns: u8, q: usize) {
+    match turns {
+        1 => ops.push(Prim::S(q)),
+        2 => ops.push(Prim::Z(q)),
+        3 => ops.extend([Prim::Z(q), Prim::S(q)]),
+        _ => {}
+    }
+}
+
+fn push_rx(ops: &mut Vec<Prim>, turns: u8, q: usize) {
+    ops.push(Prim::H(q));
+    push_rz(ops, turns, q);
+    ops.push(Prim::H(q));
+}
+
+fn push_ry(ops: &mut Vec<Prim>, turns: u8, q: usize) {
+    // Ry = S · Rx · S†
+    push_rz(ops, 3, q);
+    push_rx(ops, turns, q);
+    ops.push(Prim::S(q));
+}
+
+fn push_rzz(ops: &mut Vec<Prim>, turns: u8) {
+    ops.push(Prim::CX(0, 1));
+    push_rz(ops, turns, 1);
+    ops.push(Prim::CX(0, 1));
+}
+
+/// Decompose a Clifford gate into primitive updates in circuit order, up to
+/// global phase; `None` for gates that are not Clifford.
+fn decompose(gate: &StandardGate) -> Option<Vec<Prim>> {
+    use Prim::*;
+
+    let mut ops = Vec::new();
+    match gate {
+        StandardGate::I => {}
+        StandardGate::X => ops.push(X(0)),
+        StandardGate::Y => ops.push(Y(0)),
+        StandardGate::Z => ops.push(Z(0)),
+        StandardGate::H => ops.push(H(0)),
+        StandardGate::S => ops.push(S(0)),
+        StandardGate::Sdg => push_rz(&mut ops, 3, 0),
+        StandardGate::SX => ops.extend([H(0), S(0), H(0)]),
+        StandardGate::SXdg => push_rx(&mut ops, 3, 0),
+        StandardGate::Rz(theta) | StandardGate::P(theta) => {
+            push_rz(&mut ops, quarter_turns(theta)?, 0);
+        }
+        StandardGate::Rx(theta) => push_rx(&mut ops, quarter_turns(theta)?, 0),
+        StandardGate::Ry(theta) => push_ry(&mut ops, quarter_turns(theta)?, 0),
+        StandardGate::U(theta, phi, lambda) => {
+            // U = Rz(φ) · Ry(θ) · Rz(λ)
+            let (theta, phi, lambda) = (
+                quarter_turns(theta)?,
+                quarter_turns(phi)?,
+                quarter_turns(lambda)?,
+            );
+            push_rz(&mut ops, lambda, 0);
+            push_ry(&mut ops, theta, 0);
+            push_rz(&mut ops, phi, 0);
+        }
+        StandardGate::PRX(theta, phi) => {
+            // PRX = Rz(φ) · Rx(θ) · Rz(-φ)
+            let (theta, phi) = (quarter_turns(theta)?, quarter_turns(phi)?);
+            push_rz(&mut ops, (4 - phi) % 4, 0);
+            push_rx(&mut ops, theta, 0);
+            push_rz(&mut ops, phi, 0);
+        }
+        StandardGate::CX => ops.push(CX(0, 1)),
+        StandardGate::CY => {
+            push_rz(&mut ops, 3, 1);
+            ops.extend([CX(0, 1), S(1)]);
+        }
+        StandardGate::CZ => ops.extend([H(1), CX(0, 1), H(1)]),
+        StandardGate::CP(theta) => match quarter_turns(theta)? {
+            0 => {}
+            2 => ops.extend([H(1), CX(0, 1), H(1)]),
+            _ => return None,
+        },
+        StandardGate::Swap => ops.extend([CX(0, 1), CX(1, 0), CX(0, 1)]),
+        // iSWAP = SWAP · CZ · (S ⊗ S)
+        StandardGate::ISwap => ops.extend([
+            S(0),
+            S(1),
+            H(1),
+            CX(0, 1),
+            H(1),
+            CX(0, 1),
+            CX(1, 0),
+            CX(0, 1),
+        ]),
+        StandardGate::RZZ(theta) => push_rzz(&mut ops, quarter_turns(theta)?),
+        StandardGate::RXX(theta) => {
+            ops.extend([H(0), H(1)]);
+            push_rzz(&mut ops, quarter_turns(theta)?);
+            ops.extend([H(0), H(1)]);
+        }
+        StandardGate::RYY(theta) => {
+            let turns = quarter_turns(theta)?;
+            push_rz(&mut ops, 3, 0);
+            push_rz(&mut ops, 3, 1);
+            ops.extend([H(0), H(1)]);
+            push_rzz(&mut ops, turns);
+            ops.extend([H(0), H(1), S(0), S(1)]);
+        }
+        StandardGate::RZX(theta) => {
+            ops.push(H(1));
+            push_rzz(&mut ops, quarter_turns(theta)?);
+            ops.push(H(1));
+        }
+        // ECR = RZX(-π/4) · X · RZX(π/4) = RZX(-π/2) · X
+        StandardGate::ECR => {
+            ops.extend([X(0), H(1)]);
+            push_rzz(&mut ops, 3);
+            ops.push(H(1));
+        }
+        _ => return None,
+    }
+    Some(ops)
+}
+
+/// Check whether every gate of a circuit is a Clifford gate, so the
+/// stabilizer engine can run it.
+///
+/// Rotations count when their angles are bound multiples of π/2. Custom
+/// gates never do.
+pub fn is_clifford(circuit: &Circuit) -> bool {
+    circuit
+        .dag()
+        .topological_ops()
+        .all(|(_, inst)| match &inst.kind {
+            InstructionKind::Gate(gate) => match &gate.kind {
+                GateKind::Standard(gate) => decompose(gate).is_some(),
+                GateKind::Custom(_) => false,
+            },
+            _ => true,
+        })
+}
+
+/// Check whether a circuit resets qubits, making its state depend on
+/// random outcomes before the final measurement.
+pub(crate) fn has_resets(circuit: &Circuit) -> bool {
+    circuit
+        .dag()
+        .topological_ops()
+        .any(|(_, inst)| matches!(inst.kind, InstructionKind::Reset))
+}
+
+/// A stabilizer state as a bit-packed tableau.
+///
+/// Rows `0..n` are the destabilizers, rows `n..2n` the stabilizers and row
+/// `2n` is scratch space for deterministic measurements. Each row holds the
+/// X and Z bits of a Pauli string, one bit per qubit, and a sign.
+#[derive(Clone)]
+pub struct StabilizerState {
+    num_qubits: usize,
+    /// Words per row.
+    words: usize,
+    x: Vec<u64>,
+    z: Vec<u64>,
+    r: Vec<bool>,
+    /// Time each qubit has spent idling in delays, in nanoseconds.
+    clock: Vec<f64>,
+    /// Backend sample period in nanoseconds, for delays given in `dt`.
+    dt_ns: f64,
+}
+
+impl StabilizerState {
+    /// Create a new state initialized to |0...0⟩.
+    pub fn new(num_qubits: usize) -> Self {
+        let words = num_qubits.div_ceil(64).max(1);
+        let rows = 2 * num_qubits + 1;
+        let mut state = Self {
+            num_qubits,
+            words,
+            x: vec![0; rows * words],
+            z: vec![0; rows * words],
+            r: vec![false; rows],
+            clock: vec![0.0; num_qubits],
+            dt_ns: 1.0,
+        };
+        for q in 0..num_qubits {
+            state.x[q * words + q / 64] |= 1 << (q % 64);
+            state.z[(num_qubits + q) * words + q / 64] |= 1 << (q % 64);
+        }
+        state
+    }
+
+    /// Set the sample period used to convert delays in `dt` (default 1 ns).
+    pub fn with_dt(mut self, dt_ns: f64) -> Self {
+        self.dt_ns = dt_ns;
+        self
+    }
+
+    /// Elapsed circuit time in nanoseconds, counted as by the statevector
+    /// engine.
+    pub fn elapsed_ns(&self) -> f64 {
+        self.clock.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Apply an instruction; resets draw their outcome from `rng`.
+    ///
+    /// Gates that are not Clifford are skipped; check circuits with
+    /// [`is_clifford`] first.
+    pub fn apply(&mut self, instruction: &Instruction, rng: &mut impl Rng) {
+        match &instruction.kind {
+            InstructionKind::Gate(gate) => {
+                let GateKind::Standard(gate) = &gate.kind else {
+                    return;
+                };
+                let qubits: Vec<_> = instruction.qubits.iter().map(|q| q.0 as usize).collect();
+                for op in decompose(gate).unwrap_or_default() {
+                    match op {
+                        Prim::H(a) => self.h(qubits[a]),
+                        Prim::S(a) => self.s(qubits[a]),
+                        Prim::X(a) => self.pauli(qubits[a], false, true),
+                        Prim::Y(a) => self.pauli(qubits[a], true, true),
+                        Prim::Z(a) => self.pauli(qubits[a], true, false),
+                        Prim::CX(a, b) => self.cx(qubits[a], qubits[b]),
+                    }
+                }
+            }
+            InstructionKind::Reset => {
+                let qubit = instruction.qubits[0].0 as usize;
+                if self.measure(qubit, || rng.r#gen()) {
+                    self.pauli(qubit, false, true);
+                }
+            }
+            InstructionKind::Delay { .. } => {
+                let ns = instruction.delay_ns(Some(self.dt_ns)).unwrap_or(0.0);
+                for q in &instruction.qubits {
+                    self.clock[q.0 as usize] += ns;
+                }
+            }
+            InstructionKind::Barrier => {
+                let now = instruction
+                    .qubits
+                    .iter()
+                    .map(|q| self.clock[q.0 as usize])
+                    .fold(0.0, f64::max);
+                for q in &instruction.qubits {
+                    self.clock[q.0 as usize] = now;
+                }
+            }
+            InstructionKind::Measure | InstructionKind::Shuttle { .. } => {
+                // Like the statevector engine, measure all qubits at the end
+            }
+        }
+    }
+
+    fn bit(bits: &[u64], words: usize, row: usize, q: usize) -> bool {
+        bits[row * words + q / 64] >> (q % 64) & 1 == 1
+    }
+
+    /// Apply a Pauli given by whether it anticommutes with X and with Z.
+    fn pauli(&mut self, q: usize, flips_x: bool, flips_z: bool) {
+        for row in 0..2 * self.num_qubits {
+            let x = Self::bit(&self.x, self.words, row, q);
+            let z = Self::bit(&self.z, self.words, row, q);
+            self.r[row] ^= (flips_x && x) ^ (flips_z && z);
+        }
+    }
+
+    fn h(&mut self, q: usize) {
+        let (word, mask) = (q / 64, 1 << (q % 64));
+        for row in 0..2 * self.num_qubits {
+            let i = row * self.words + word;
+            let (x, z) = (self.x[i] & mask, self.z[i] & mask);
+            self.r[row] ^= x != 0 && z != 0;
+            self.x[i] ^= x ^ z;
+            self.z[i] ^= x ^ z;
+        }
+    }
+
+    fn s(&mut self, q: usize) {
+        let (word, mask) = (q / 64, 1 << (q % 64));
+        for row in 0..2 * self.num_qubits {
+            let i = row * self.words + word;
+            let (x, z) = (self.x[i] & mask, self.z[i] & mask);
+            self.r[row] ^= x != 0 && z != 0;
+            self.z[i] ^= x;
+        }
+    }
+
+    fn cx(&mut self, control: usize, target: usize) {
+        let w = self.words;
+        for row in 0..2 * self.num_qubits {
+            let xa = Self::bit(&self.x, w, row, control);
+            let za = Self::bit(&self.z, w, row, control);
+            let xb = Self::bit(&self.x, w, row, target);
+            let zb = Self::bit(&self.z, w, row, target);
+            self.r[row] ^= xa && zb && (xb == za);
+            if xa {
+                self.x[row * w + target / 64] ^= 1 << (target % 64);
+            }
+            if zb {
+                self.z[row * w + control / 64] ^= 1 << (control % 64);
+            }
+        }
+    }
+
+    /// Multiply row `h` by row `i`, keeping track of the sign.
+    fn rowsum(&mut self, h: usize, i: usize) {
+        let w = self.words;
+        // Sum of the i-exponents the single-qubit products contribute
+        let mut phase = 2 * i64::from(self.r[h]) + 2 * i64::from(self.r[i]);
+        for k in 0..w {
+            let (x1, z1) = (self.x[i * w + k], self.z[i * w + k]);
+            let (x2, z2) = (self.x[h * w + k], self.z[h * w + k]);
+            let y1 = x1 & z1;
+            let only_x1 = x1 & !z1;
+            let only_z1 = z1 & !x1;
+            let plus = (y1 & z2 & !x2) | (only_x1 & z2 & x2) | (only_z1 & x2 & !z2);
+            let minus = (y1 & x2 & !z2) | (only_x1 & z2 & !x2) | (only_z1 & x2 & z2);
+            phase += i64::from(plus.count_ones()) - i64::from(minus.count_ones());
+            self.x[h * w + k] = x1 ^ x2;
+            self.z[h * w + k] = z1 ^ z2;
+        }
+        self.r[h] = phase.rem_euclid(4) == 2;
+    }
+
+    fn copy_row(&mut self, dst: usize, src: usize) {
+        let w = self.words;
+        self.x.copy_within(src * w..(src + 1) * w, dst * w);
+        self.z.copy_within(src * w..(src + 1) * w, dst * w);
+        self.r[dst] = self.r[src];
+    }
+
+    fn clear_row(&mut self, row: usize) {
+        let w = self.words;
+        self.x[row * w..(row + 1) * w].fill(0);
+        self.z[row * w..(row + 1) * w].fill(0);
+        self.r[row] = false;
+    }
+
+    /// Measure a qubit in the computational basis, collapsing the state.
+    ///
+    /// `choose` picks the outcome when it is random.
+    pub fn measure(&mut self, q: usize, choose: impl FnOnce() -> bool) -> bool {
+        let n = self.num_qubits;
+        let w = self.words;
+        let anticommuting = (n..2 * n).find(|&row| Self::bit(&self.x, w, row, q));
+
+        if let Some(p) = anticommuting {
+            for row in 0..2 * n {
+                if row != p && Self::bit(&self.x, w, row, q) {
+                    self.rowsum(row, p);
+                }
+            }
+            self.copy_row(p - n, p);
+            self.clear_row(p);
+            self.z[p * w + q / 64] |= 1 << (q % 64);
+            let outcome = choose();
+            self.r[p] = outcome;
+            outcome
+        } else {
+            let scratch = 2 * n;
+            self.clear_row(scratch);
+            for row in 0..n {
+                if Self::bit(&self.x, w, row, q) {
+                    self.rowsum(scratch, row + n);
+                }
+            }
+            self.r[scratch]
+        }
+    }
+
+    /// Measure every qubit, returning the outcome with qubit 0 first.
+    pub fn measure_all(&mut self, rng: &mut impl Rng) -> String {
+        (0..self.num_qubits)
+            .map(|q| {
+                if self.measure(q, || rng.r#gen()) {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+
+    /// Prepare to draw many final measurements of the state.
+    pub fn sampler(&self) -> StabilizerSampler {
+        let n = self.num_qubits;
+        let w = self.words;
+
+        // Reduce the X parts of the stabilizers to a basis with distinct
+        // leading bits
+        let mut basis: Vec<(usize, Vec<u64>)> = Vec::new();
+        for row in n..2 * n {
+            let mut v = self.x[row * w..(row + 1) * w].to_vec();
+            for (pivot, b) in &basis {
+                if Self::bit(&v, w, 0, *pivot) {
+                    v.iter_mut().zip(b).for_each(|(a, b)| *a ^= b);
+                }
+            }
+            if let Some(pivot) = (0..n).find(|&q| Self::bit(&v, w, 0, q)) {
+                basis.push((pivot, v));
+            }
+        }
+
+        // Any outcome will do as the reference; take the one choosing 0
+        // wherever the outcome is random
+        let mut collapsed = self.clone();
+        let mut reference = vec![0u64; w];
+        for q in 0..n {
+            if collapsed.measure(q, || false) {
+                reference[q / 64] |= 1 << (q % 64);
+            }
+        }
+
+        StabilizerSampler {
+            num_qubits: n,
+            reference,
+            basis: basis.into_iter().map(|(_, v)| v).collect(),
+        }
+    }
+}
+
+/// Draws final measurement outcomes of a stabilizer state.
+pub struct StabilizerSampler {
+    num_qubits: usize,
+    /// One possible outcome, as bits by qubit.
+    reference: Vec<u64>,
+    /// Independent flips that turn one outcome into another.
+    basis: Vec<Vec<u64>>,
+}
+
+impl StabilizerSampler {
+    /// Draw one outcome, with qubit 0 first.
+    pub fn sample(&self, rng: &mut impl Rng) -> String {
+        let mut bits = self.reference.clone();
+        for v in &self.basis {
+            if rng.r#gen() {
+                bits.iter_mut().zip(v).for_each(|(a, b)| *a ^= b);
+            }
+        }
+        (0..self.num_qubits)
+            .map(|q| {
+                if bits[q / 64] >> (q % 64) & 1 == 1 {
+                    '1'
+                } else {
+                    '0'
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::statevector::Statevector;
+    use arvak_ir::QubitId;
+    use std::f64::consts::PI;
+
+    /// Probabilities of the outcomes of a stabilizer state, from its
+    /// affine space.
+    fn distribution(state: &StabilizerState) -> Vec<f64> {
+        let sampler = state.sampler();
+        let n = state.num_qubits;
+        let mut probs = vec![0.0; 1 << n];
+        let weight = 1.0 / f64::from(1u32 << sampler.basis.len());
+        for flips in 0..1usize << sampler.basis.len() {
+            let mut bits = sampler.reference[0];
+            for (j, v) in sampler.basis.iter().enumerate() {
+                if flips >> j & 1 == 1 {
+                    bits ^= v[0];
+                }
+            }
+            probs[bits as usize] += weight;
+        }
+        probs
+    }
+
+    #[test]
+    fn test_clifford_gates_match_statevector() {
+        let gates = [
+            StandardGate::H,
+            StandardGate::S,
+            StandardGate::Sdg,
+            StandardGate::X,
+            StandardGate::Y,
+            StandardGate::SX,
+            StandardGate::SXdg,
+            StandardGate::Rx((PI / 2.0).into()),
+            StandardGate::Ry((-PI / 2.0).into()),
+            StandardGate::Rz((3.0 * PI / 2.0).into()),
+            StandardGate::U((PI / 2.0).into(), PI.into(), (PI / 2.0).into()),
+            StandardGate::PRX((PI / 2.0).into(), (PI / 2.0).into()),
+            StandardGate::CX,
+            StandardGate::CY,
+            StandardGate::CZ,
+            StandardGate::CP(PI.into()),
+            StandardGate::Swap,
+            StandardGate::ISwap,
+            StandardGate::ECR,
+            StandardGate::RXX((PI / 2.0).into()),
+            StandardGate::RYY((PI / 2.0).into()),
+            StandardGate::RZZ((PI / 2.0).into()),
+            StandardGate::RZX((-PI / 2.0).into()),
+        ];
+        let mut rng = rand::thread_rng();
+
+        // Random layers of the gates, compared after every gate
+        for seed in 0..40usize {
+            let mut sv = Statevector::new(3);
+            let mut stab = StabilizerState::new(3);
+            for step in 0..12 {
+                let gate = &gates[(seed * 7 + step * 13 + step * step) % gates.len()];
+                let a = (seed + step) % 3;
+                let b = (a + 1 + (seed + step * 5) % 2) % 3;
+                let inst = match gate.num_qubits() {
+                    1 => Instruction::single_qubit_gate(gate.clone(), QubitId(a as u32)),
+                    _ => Instruction::two_qubit_gate(
+                        gate.clone(),
+                        QubitId(a as u32),
+                        QubitId(b as u32),
+                    ),
+                };
+                sv.apply(&inst);
+                stab.apply(&inst, &mut rng);
+
+                // Outcomes in every product of X, Y and Z bases fix the state
+                for basis in 0..27usize {
+                    let (mut sv, mut stab) = (sv.clone(), stab.clone());
+                    for q in 0..3u32 {
+                        let change: &[StandardGate] = match basis / 3usize.pow(q) % 3 {
+                            0 => &[],
+                            1 => &[StandardGate::H],
+                            _ => &[StandardGate::Sdg, StandardGate::H],
+                        };
+                        for gate in change {
+                            let inst = Instruction::single_qubit_gate(gate.clone(), QubitId(q));
+                            sv.apply(&inst);
+                            stab.apply(&inst, &mut rng);
+                        }
+                    }
+                    let expected = sv.amplitudes().iter().map(|a| a.norm_sqr());
+                    for (e, a) in expected.zip(distribution(&stab)) {
+                        assert!((e - a).abs() < 1e-9, "{} after {:?}", seed, gate);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_measurement_collapses() {
+        let mut rng = rand::thread_rng();
+        let mut state = StabilizerState::new(2);
+        state.h(0);
+        state.cx(0, 1);
+        let first = state.measure(0, || true);
+        assert!(first);
+        // The partner is now determined
+        assert!(state.measure(1, || false));
+        assert_eq!(state.sampler().basis.len(), 0);
+        assert_eq!(state.measure_all(&mut rng), "11");
+    }
+
+    #[test]
+    fn test_is_clifford() {
+        let mut circuit = Circuit::ghz(4).unwrap();
+        assert!(is_clifford(&circuit));
+        circuit.rz(PI / 2.0, QubitId(1)).unwrap();
+        circuit.reset(QubitId(2)).unwrap();
+        assert!(is_clifford(&circuit));
+        assert!(has_resets(&circuit));
+
+        circuit.t(QubitId(0)).unwrap();
+        assert!(!is_clifford(&circuit));
+
+        let mut rotated = Circuit::with_size("rz", 1, 0);
+        rotated.rz(0.3, QubitId(0)).unwrap();
+        assert!(!is_clifford(&rotated));
+    }
+}
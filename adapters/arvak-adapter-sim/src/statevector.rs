@@ -6,11 +6,16 @@ use std::f64::consts::PI;
 use arvak_ir::{GateKind, Instruction, InstructionKind, StandardGate};
 
 /// A statevector representing a quantum state.
+#[derive(Clone)]
 pub struct Statevector {
     /// The state amplitudes (2^n complex numbers).
     amplitudes: Vec<Complex64>,
     /// Number of qubits.
     num_qubits: usize,
+    /// Time each qubit has spent idling in delays, in nanoseconds.
+    clock: Vec<f64>,
+    /// Backend sample period in nanoseconds, for delays given in `dt`.
+    dt_ns: f64,
 }
 
 impl Statevector {
@@ -22,9 +27,39 @@ impl Statevector {
         Self {
             amplitudes,
             num_qubits,
+            clock: vec![0.0; num_qubits],
+            dt_ns: 1.0,
         }
     }
 
+    /// Set the sample period used to convert delays in `dt` (default 1 ns).
+    pub fn with_dt(mut self, dt_ns: f64) -> Self {
+        self.dt_ns = dt_ns;
+        self
+    }
+
+    /// Elapsed circuit time in nanoseconds.
+    ///
+    /// Gates are instantaneous in this simulator, so only delays advance the
+    /// clock; a barrier synchronizes the clocks of its qubits.
+    pub fn elapsed_ns(&self) -> f64 {
+        self.clock.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Get the state amplitudes, indexed by basis state.
+    pub fn amplitudes(&self) -> &[Complex64] {
+        &self.amplitudes
+    }
+
+    /// Norm of the state; 1 up to rounding for a valid state.
+    pub fn norm(&self) -> f64 {
+        self.amplitudes
+            .iter()
+            .map(|a| a.norm_sqr())
+            .sum::<f64>()
+            .sqrt()
+    }
+
     /// Get the number of qubits.
     #[allow(dead_code)]
     pub fn num_qubits(&self) -> usize {
@@ -43,10 +78,24 @@ impl Statevector {
                 let qubit = instruction.qubits[0].0 as usize;
                 self.reset(qubit);
             }
-            InstructionKind::Measure
-            | InstructionKind::Barrier
-            | InstructionKind::Delay { .. }
-            | InstructionKind::Shuttle { .. } => {
+            InstructionKind::Delay { .. } => {
+                // Delays leave the state unchanged but advance the clock
+                let ns = instruction.delay_ns(Some(self.dt_ns)).unwrap_or(0.0);
+                for q in &instruction.qubits {
+                    self.clock[q.0 as usize] += ns;
+                }
+            }
+            InstructionKind::Barrier => {
+                let now = instruction
+                    .qubits
+                    .iter()
+                    .map(|q| self.clock[q.0 as usize])
+                    .fold(0.0, f64::max);
+                for q in &instruction.qubits {
+                    self.clock[q.0 as usize] = now;
+                }
+            }
+            InstructionKind::Measure | InstructionKind::Shuttle { .. } => {
                 // These don't modify the statevector in simulation
             }
         }
@@ -58,9 +107,11 @@ impl Statevector {
             GateKind::Standard(std_gate) => {
                 self.apply_standard_gate(std_gate, qubits);
             }
-            GateKind::Custom(_) => {
-                // Custom gates would need matrix multiplication
-                // For now, skip
+            GateKind::Custom(custom) => {
+                // Custom gates without a matrix are opaque and skipped.
+                if let Some(matrix) = &custom.matrix {
+                    self.apply_matrix(matrix, qubits);
+                }
             }
         }
     }
@@ -134,8 +185,39 @@ impl Statevector {
             StandardGate::CCX => self.apply_ccx(qubits[0], qubits[1], qubits[2]),
             StandardGate::CSwap => self.apply_cswap(qubits[0], qubits[1], qubits[2]),
 
-            _ => {
-                // Other gates not yet implemented
+            // Everything else goes through its unitary; gates with unbound
+            // parameters have none and are skipped.
+            other => {
+                if let Some(matrix) = other.matrix() {
+                    self.apply_matrix(&matrix, qubits);
+                }
+            }
+        }
+    }
+
+    /// Apply a row-major `2^k × 2^k` unitary to `qubits`.
+    ///
+    /// The first qubit is the most significant bit of the matrix index.
+    fn apply_matrix(&mut self, matrix: &[Complex64], qubits: &[usize]) {
+        let k = qubits.len();
+        let dim = 1 << k;
+        let bits: Vec<usize> = qubits.iter().rev().map(|q| 1 << q).collect();
+        let mask: usize = bits.iter().sum();
+        let index = |base: usize, local: usize| {
+            base + (0..k)
+                .filter(|j| local & (1 << j) != 0)
+                .map(|j| bits[j])
+                .sum::<usize>()
+        };
+
+        let mut old = vec![Complex64::new(0.0, 0.0); dim];
+        for base in (0..self.amplitudes.len()).filter(|i| i & mask == 0) {
+            for (local, amp) in old.iter_mut().enumerate() {
+                *amp = self.amplitudes[index(base, local)];
+            }
+            for row in 0..dim {
+                self.amplitudes[index(base, row)] =
+                    (0..dim).map(|col| matrix[row * dim + col] * old[col]).sum();
             }
         }
     }
@@ -443,6 +525,54 @@ impl Statevector {
         }
     }
 
+    /// Apply the Pauli with index `pauli` (1 = X, 2 = Y, 3 = Z) to a
+    /// qubit; 0 leaves it unchanged.
+    pub(crate) fn apply_pauli(&mut self, qubit: usize, pauli: usize) {
+        match pauli {
+            1 => self.apply_x(qubit),
+            2 => self.apply_y(qubit),
+            3 => self.apply_z(qubit),
+            _ => {}
+        }
+    }
+
+    /// Run one trajectory of amplitude damping with decay probability
+    /// `gamma` on a qubit, using `r` uniform in `[0, 1)` to pick the jump.
+    pub(crate) fn amplitude_damp(&mut self, qubit: usize, gamma: f64, r: f64) {
+        let mask = 1 << qubit;
+        let excited: f64 = self
+            .amplitudes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| i & mask != 0)
+            .map(|(_, a)| a.norm_sqr())
+            .sum();
+        let jump = gamma * excited;
+        if r < jump {
+            // Decay: |1⟩ → |0⟩
+            for i in (0..self.amplitudes.len()).filter(|i| i & mask != 0) {
+                self.amplitudes[i & !mask] = self.amplitudes[i];
+                self.amplitudes[i] = Complex64::new(0.0, 0.0);
+            }
+            let norm = excited.sqrt();
+            for amp in &mut self.amplitudes {
+                *amp /= norm;
+            }
+        } else {
+            // No decay: |1⟩ is damped by sqrt(1 - gamma)
+            let damping = (1.0 - gamma).sqrt();
+            for i in (0..self.amplitudes.len()).filter(|i| i & mask != 0) {
+                self.amplitudes[i] *= damping;
+            }
+            let norm = (1.0 - jump).sqrt();
+            if norm > 0.0 {
+                for amp in &mut self.amplitudes {
+                    *amp /= norm;
+                }
+            }
+        }
+    }
+
     /// Sample a measurement outcome.
     pub fn sample(&self) -> usize {
         use rand::Rng;
@@ -487,6 +617,32 @@ mod tests {
         assert!(approx_eq(sv.amplitudes[3], Complex64::new(0.0, 0.0)));
     }
 
+    #[test]
+    fn test_delay_advances_clock() {
+        use arvak_ir::{QubitId, TimeUnit};
+
+        let mut sv = Statevector::new(2).with_dt(0.5);
+        sv.apply(&Instruction::single_qubit_gate(StandardGate::H, QubitId(0)));
+        let before = sv.amplitudes.clone();
+
+        sv.apply(&Instruction::delay(QubitId(0), 100));
+        sv.apply(&Instruction::delay_with_unit(
+            [QubitId(1)],
+            20,
+            TimeUnit::Ns,
+        ));
+        assert_eq!(sv.amplitudes, before);
+        assert_eq!(sv.clock, vec![50.0, 20.0]);
+
+        sv.apply(&Instruction::barrier([QubitId(0), QubitId(1)]));
+        sv.apply(&Instruction::delay_with_unit(
+            [QubitId(1)],
+            30,
+            TimeUnit::Ns,
+        ));
+        assert_eq!(sv.elapsed_ns(), 80.0);
+    }
+
     #[test]
     fn test_hadamard() {
         let mut sv = Statevector::new(1);
@@ -510,6 +666,48 @@ mod tests {
         assert!(approx_eq(sv.amplitudes[3], Complex64::new(sqrt2_inv, 0.0)));
     }
 
+    #[test]
+    fn test_matrix_gates_match_direct_implementations() {
+        // CX through its matrix, with the control on the higher qubit.
+        let mut direct = Statevector::new(2);
+        direct.apply_h(1);
+        direct.apply_cx(1, 0);
+        let mut generic = Statevector::new(2);
+        generic.apply_h(1);
+        generic.apply_matrix(&StandardGate::CX.matrix().unwrap(), &[1, 0]);
+        for (a, b) in direct.amplitudes.iter().zip(&generic.amplitudes) {
+            assert!(approx_eq(*a, *b));
+        }
+
+        // RZZ(π) on |11⟩ only contributes a global phase of -i.
+        let mut sv = Statevector::new(2);
+        sv.apply_x(0);
+        sv.apply_x(1);
+        sv.apply_standard_gate(&StandardGate::RZZ(PI.into()), &[0, 1]);
+        assert!(approx_eq(sv.amplitudes[3], Complex64::new(0.0, -1.0)));
+
+        // ECR maps |00⟩ to (|10⟩ - i|11⟩)/√2, flipping its first operand.
+        let mut sv = Statevector::new(2);
+        sv.apply_standard_gate(&StandardGate::ECR, &[0, 1]);
+        let sqrt2_inv = 1.0 / 2.0_f64.sqrt();
+        assert!(approx_eq(
+            sv.amplitudes[0b01],
+            Complex64::new(sqrt2_inv, 0.0)
+        ));
+        assert!(approx_eq(
+            sv.amplitudes[0b11],
+            Complex64::new(0.0, -sqrt2_inv)
+        ));
+
+        // CCZ flips the sign of |111⟩.
+        let mut sv = Statevector::new(3);
+        for q in 0..3 {
+            sv.apply_x(q);
+        }
+        sv.apply_standard_gate(&StandardGate::CCZ, &[0, 1, 2]);
+        assert!(approx_eq(sv.amplitudes[0b111], Complex64::new(-1.0, 0.0)));
+    }
+
     #[test]
     fn test_x_gate() {
         let mut sv = Statevector::new(1);
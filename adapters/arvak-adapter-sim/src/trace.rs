@@ -0,0 +1,263 @@
+//! Gate-level execution traces.
+//!
+//! With tracing enabled the simulator records the state after every
+//! instruction of the first shot. Depending on the [`TraceMode`], each step
+//! holds the state norm and optionally selected amplitudes, and barriers
+//! marked with a [`SNAPSHOT`](arvak_ir::Annotations::SNAPSHOT) label capture
+//! the full statevector. Traces are written as JSON:
+//!
+//! ```json
+//! {
+//!   "circuit": "bell",
+//!   "num_qubits": 2,
+//!   "mode": { "amplitudes": [0, 3] },
+//!   "steps": [
+//!     { "index": 0, "instruction": "h", "qubits": [0], "norm": 1.0,
+//!       "amplitudes": [[0, 0.7071, 0.0], [3, 0.0, 0.0]] }
+//!   ],
+//!   "snapshots": []
+//! }
+//! ```
+//!
+//! Amplitudes are `[re, im]` pairs, prefixed by their basis index where
+//! only some are recorded. Basis index bit `k` is the state of qubit `k`.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+
+use arvak_ir::Instruction;
+
+use crate::statevector::Statevector;
+
+/// What a trace records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceMode {
+    /// The state norm after every instruction.
+    Norms,
+
+    /// The norm and the given basis amplitudes after every instruction.
+    Amplitudes(Vec<usize>),
+
+    /// The norm after every instruction and the full state at each marked
+    /// barrier.
+    Snapshots,
+}
+
+impl FromStr for TraceMode {
+    type Err = String;
+
+    /// Parse `norms`, `snapshots` or `amplitudes:<i>,<j>,...`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "norms" => Ok(TraceMode::Norms),
+            "snapshots" => Ok(TraceMode::Snapshots),
+            other => {
+                let indices = other.strip_prefix("amplitudes:").ok_or_else(|| {
+                    format!(
+                        "Unknown trace mode '{}', expected norms, snapshots or amplitudes:<i>,...",
+                        other
+                    )
+                })?;
+                indices
+                    .split(',')
+                    .map(|i| {
+                        i.trim()
+                            .parse()
+                            .map_err(|_| format!("Invalid basis index '{}'", i.trim()))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(TraceMode::Amplitudes)
+            }
+        }
+    }
+}
+
+/// The state after one instruction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    /// Position of the instruction in execution order.
+    pub index: usize,
+
+    /// Instruction name.
+    pub instruction: String,
+
+    /// Qubits the instruction acts on.
+    pub qubits: Vec<u32>,
+
+    /// Norm of the state.
+    pub norm: f64,
+
+    /// Selected amplitudes as `(basis index, re, im)`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amplitudes: Vec<(usize, f64, f64)>,
+}
+
+/// The full state at a marked barrier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    /// Label of the barrier.
+    pub label: String,
+
+    /// Index of the barrier's step.
+    pub index: usize,
+
+    /// All amplitudes as `(re, im)`, in basis order.
+    pub amplitudes: Vec<(f64, f64)>,
+}
+
+/// A recorded gate-level trace of one shot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// Circuit name.
+    pub circuit: String,
+
+    /// Number of qubits.
+    pub num_qubits: usize,
+
+    /// What was recorded.
+    pub mode: TraceMode,
+
+    /// One step per instruction.
+    pub steps: Vec<TraceStep>,
+
+    /// Snapshots at marked barriers, in execution order.
+    #[serde(default)]
+    pub snapshots: Vec<StateSnapshot>,
+}
+
+impl ExecutionTrace {
+    /// Start an empty trace.
+    pub fn new(circuit: impl Into<String>, num_qubits: usize, mode: TraceMode) -> Self {
+        Self {
+            circuit: circuit.into(),
+            num_qubits,
+            mode,
+            steps: Vec::new(),
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Record the state after `instruction` has been applied.
+    pub(crate) fn record(&mut self, instruction: &Instruction, sv: &Statevector) {
+        let index = self.steps.len();
+        let amplitudes = match &self.mode {
+            TraceMode::Amplitudes(indices) => indices
+                .iter()
+                .filter_map(|&i| sv.amplitudes().get(i).map(|a| (i, a.re, a.im)))
+                .collect(),
+            _ => Vec::new(),
+        };
+        self.steps.push(TraceStep {
+            index,
+            instruction: instruction.name().to_string(),
+            qubits: instruction.qubits.iter().map(|q| q.0).collect(),
+            norm: sv.norm(),
+            amplitudes,
+        });
+
+        if self.mode == TraceMode::Snapshots && instruction.is_barrier() {
+            if let Some(label) = instruction.annotations.snapshot() {
+                self.snapshots.push(StateSnapshot {
+                    label: label.to_string(),
+                    index,
+                    amplitudes: sv.amplitudes().iter().map(|a| (a.re, a.im)).collect(),
+                });
+            }
+        }
+    }
+
+    /// Get the snapshot with the given label.
+    pub fn snapshot(&self, label: &str) -> Option<Vec<Complex64>> {
+        self.snapshots.iter().find(|s| s.label == label).map(|s| {
+            s.amplitudes
+                .iter()
+                .map(|&(re, im)| Complex64::new(re, im))
+                .collect()
+        })
+    }
+
+    /// Write the trace to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Read a trace from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(std::io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_ir::{Annotations, QubitId, StandardGate};
+
+    #[test]
+    fn test_parse_trace_mode() {
+        assert_eq!("norms".parse::<TraceMode>(), Ok(TraceMode::Norms));
+        assert_eq!("snapshots".parse::<TraceMode>(), Ok(TraceMode::Snapshots));
+        assert_eq!(
+            "amplitudes:0, 3".parse::<TraceMode>(),
+            Ok(TraceMode::Amplitudes(vec![0, 3]))
+        );
+        assert!("amplitudes:x".parse::<TraceMode>().is_err());
+        assert!("full".parse::<TraceMode>().is_err());
+    }
+
+    #[test]
+    fn test_record_and_roundtrip() {
+        let h = Instruction::single_qubit_gate(StandardGate::H, QubitId(0));
+        let marked = Instruction::barrier([QubitId(0), QubitId(1)])
+            .with_annotation(Annotations::SNAPSHOT, "superposed");
+        let plain = Instruction::barrier([QubitId(0), QubitId(1)]);
+
+        let mut sv = Statevector::new(2);
+        let mut trace = ExecutionTrace::new("test", 2, TraceMode::Snapshots);
+        for inst in [&h, &plain, &marked] {
+            sv.apply(inst);
+            trace.record(inst, &sv);
+        }
+
+        assert_eq!(trace.steps.len(), 3);
+        assert_eq!(trace.steps[0].instruction, "h");
+        assert!(trace.steps.iter().all(|s| (s.norm - 1.0).abs() < 1e-10));
+        assert!(trace.steps.iter().all(|s| s.amplitudes.is_empty()));
+        assert_eq!(trace.snapshots.len(), 1);
+        assert_eq!(trace.snapshots[0].index, 2);
+        let state = trace.snapshot("superposed").unwrap();
+        assert!((state[1].re - 1.0 / 2.0_f64.sqrt()).abs() < 1e-10);
+        assert!(trace.snapshot("missing").is_none());
+
+        let dir = std::env::temp_dir().join(format!("arvak-trace-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("trace.json");
+        trace.save(&path).unwrap();
+        let loaded = ExecutionTrace::load(&path).unwrap();
+        assert_eq!(loaded.mode, trace.mode);
+        assert_eq!(loaded.steps.len(), 3);
+        assert_eq!(loaded.snapshots[0].label, "superposed");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_record_amplitudes() {
+        let x = Instruction::single_qubit_gate(StandardGate::X, QubitId(1));
+        let mut sv = Statevector::new(2);
+        let mut trace = ExecutionTrace::new("test", 2, TraceMode::Amplitudes(vec![0, 2, 9]));
+        sv.apply(&x);
+        trace.record(&x, &sv);
+
+        // Out-of-range indices are skipped.
+        assert_eq!(
+            trace.steps[0].amplitudes,
+            vec![(0, 0.0, 0.0), (2, 1.0, 0.0)]
+        );
+        assert!(trace.snapshots.is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use arvak_ir::qubit::QubitId;
 use rustc_hash::FxHashSet;
 
 use crate::error::{UncomputeError, UncomputeResult};
-use crate::inverse::inverse_instruction;
+use crate::inverse::inverse_instructions;
 
 /// Scope of uncomputation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -145,11 +145,12 @@ impl UncomputeContext {
                 continue;
             }
 
-            let inverse = inverse_instruction(&inst)?;
-            circuit
-                .dag_mut()
-                .apply(inverse)
-                .map_err(|e| UncomputeError::CircuitError(e.to_string()))?;
+            for inverse in inverse_instructions(&inst)? {
+                circuit
+                    .dag_mut()
+                    .apply(inverse)
+                    .map_err(|e| UncomputeError::CircuitError(e.to_string()))?;
+            }
         }
 
         Ok(())
@@ -34,6 +34,11 @@ pub enum UncomputeError {
     #[error("Gate {0} cannot be inverted")]
     NonInvertibleGate(String),
 
+    /// The gate's inverse is not a single standard gate; it can be emitted
+    /// as a sequence by [`crate::inverse_instructions`].
+    #[error("Inverse of gate {0} is not a single gate")]
+    MultiGateInverse(String),
+
     /// Dependency cycle detected.
     #[error("Dependency cycle detected involving qubit {0}")]
     DependencyCycle(u32),
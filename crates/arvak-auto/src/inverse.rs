@@ -43,6 +43,8 @@ pub fn inverse_gate(gate: &StandardGate) -> UncomputeResult<StandardGate> {
         StandardGate::Swap => Ok(StandardGate::Swap),
         StandardGate::CCX => Ok(StandardGate::CCX),
         StandardGate::CSwap => Ok(StandardGate::CSwap),
+        StandardGate::CCZ => Ok(StandardGate::CCZ),
+        StandardGate::ECR => Ok(StandardGate::ECR),
 
         // S and T gates
         StandardGate::S => Ok(StandardGate::Sdg),
@@ -77,6 +79,7 @@ pub fn inverse_gate(gate: &StandardGate) -> UncomputeResult<StandardGate> {
         StandardGate::RXX(theta) => Ok(StandardGate::RXX(negate_param(theta))),
         StandardGate::RYY(theta) => Ok(StandardGate::RYY(negate_param(theta))),
         StandardGate::RZZ(theta) => Ok(StandardGate::RZZ(negate_param(theta))),
+        StandardGate::RZX(theta) => Ok(StandardGate::RZX(negate_param(theta))),
 
         // iSWAP is not self-inverse: iSWAP† ≠ iSWAP
         // iSWAP† = iSWAP^(-1) which would need decomposition
@@ -86,6 +89,10 @@ pub fn inverse_gate(gate: &StandardGate) -> UncomputeResult<StandardGate> {
             Err(UncomputeError::NonInvertibleGate("iswap".into()))
         }
 
+        // CSX† is controlled-SXdg, which has no standard gate; see
+        // inverse_instructions
+        StandardGate::CSX => Err(UncomputeError::MultiGateInverse("csx".into())),
+
         // CH (controlled-Hadamard) is self-inverse
         StandardGate::CH => Ok(StandardGate::CH),
 
@@ -122,6 +129,7 @@ pub fn inverse_instruction(instruction: &Instruction) -> UncomputeResult<Instruc
                 }),
                 qubits: instruction.qubits.clone(),
                 clbits: instruction.clbits.clone(),
+                annotations: instruction.annotations.clone(),
             })
         }
 
@@ -148,11 +156,25 @@ pub fn inverse_instruction(instruction: &Instruction) -> UncomputeResult<Instruc
                 },
                 qubits: instruction.qubits.clone(),
                 clbits: instruction.clbits.clone(),
+                annotations: instruction.annotations.clone(),
             })
         }
     }
 }
 
+/// Compute the inverse of an instruction as a sequence of instructions.
+///
+/// Like [`inverse_instruction`], but also inverts gates whose inverse is no
+/// single standard gate: CSX† is emitted as CSX·CSX·CSX, since SX⁴ = I.
+pub fn inverse_instructions(instruction: &Instruction) -> UncomputeResult<Vec<Instruction>> {
+    if let InstructionKind::Gate(gate) = &instruction.kind {
+        if gate.kind == GateKind::Standard(StandardGate::CSX) {
+            return Ok(vec![instruction.clone(); 3]);
+        }
+    }
+    inverse_instruction(instruction).map(|inverse| vec![inverse])
+}
+
 /// Check if a gate is self-inverse (Hermitian).
 pub fn is_self_inverse(gate: &StandardGate) -> bool {
     matches!(
@@ -168,6 +190,8 @@ pub fn is_self_inverse(gate: &StandardGate) -> bool {
             | StandardGate::Swap
             | StandardGate::CCX
             | StandardGate::CSwap
+            | StandardGate::CCZ
+            | StandardGate::ECR
             | StandardGate::CH
     )
 }
@@ -241,6 +265,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_inverse_csx() {
+        assert!(matches!(
+            inverse_gate(&StandardGate::CSX),
+            Err(UncomputeError::MultiGateInverse(_))
+        ));
+
+        let inst = Instruction::two_qubit_gate(
+            StandardGate::CSX,
+            arvak_ir::qubit::QubitId(0),
+            arvak_ir::qubit::QubitId(1),
+        );
+        let inverse = inverse_instructions(&inst).unwrap();
+        assert_eq!(inverse.len(), 3);
+        assert!(inverse.iter().all(|i| i.qubits == inst.qubits));
+
+        let inst = Instruction::single_qubit_gate(StandardGate::T, arvak_ir::qubit::QubitId(0));
+        let inverse = inverse_instructions(&inst).unwrap();
+        assert_eq!(inverse.len(), 1);
+    }
+
     #[test]
     fn test_measure_not_invertible() {
         let inst = Instruction::measure(arvak_ir::qubit::QubitId(0), arvak_ir::qubit::ClbitId(0));
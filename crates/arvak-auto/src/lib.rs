@@ -45,4 +45,6 @@ pub use analysis::{
 };
 pub use context::{UncomputeContext, UncomputeScope, uncompute};
 pub use error::{UncomputeError, UncomputeResult};
-pub use inverse::{InverseStrategy, inverse_gate, inverse_instruction, is_self_inverse};
+pub use inverse::{
+    InverseStrategy, inverse_gate, inverse_instruction, inverse_instructions, is_self_inverse,
+};
@@ -77,6 +77,7 @@ fn rebuild_props(props: &PropertySet) -> PropertySet {
     if let Some(ref bg) = props.basis_gates {
         new.basis_gates = Some(bg.clone());
     }
+    new.seed = props.seed;
     // Layout is intentionally NOT copied — each compilation starts fresh
     new
 }
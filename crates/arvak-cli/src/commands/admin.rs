@@ -0,0 +1,114 @@
+//! Admin command implementations.
+//!
+//! Surgical interventions on the local scheduler state: requeue a stuck job,
+//! fail a job with a reason, purge an owner's queued jobs and rebuild the
+//! store's indices. Every intervention is appended to `~/.arvak/audit.jsonl`.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use console::style;
+
+use arvak_sched::{HpcScheduler, JsonlAuditLog, ScheduledJobId};
+
+use super::common::{create_scheduler, default_state_dir};
+
+/// Principal recorded for interventions: the invoking OS user.
+///
+/// Anyone able to run this command can write the local state directory
+/// directly, so the local user is treated as an admin.
+fn operator() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// Create the local scheduler with the operator as admin and a file audit log.
+fn admin_scheduler() -> Result<(HpcScheduler, String)> {
+    let path = default_state_dir()?.join("audit.jsonl");
+    let audit = JsonlAuditLog::open(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to open audit log at {}: {}", path.display(), e))?;
+    let scheduler = create_scheduler()?.with_audit_log(Arc::new(audit));
+    let principal = operator();
+    scheduler.access_policy().add_admin(principal.clone());
+    Ok((scheduler, principal))
+}
+
+fn parse_job_id(job_id: &str) -> Result<ScheduledJobId> {
+    ScheduledJobId::parse(job_id).map_err(|e| anyhow::anyhow!("Invalid job ID '{}': {}", job_id, e))
+}
+
+/// Execute the admin requeue command.
+pub async fn execute_requeue(job_id: &str, reason: &str) -> Result<()> {
+    let (scheduler, principal) = admin_scheduler()?;
+    let parsed_id = parse_job_id(job_id)?;
+
+    scheduler
+        .force_requeue(&principal, &parsed_id, reason)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to requeue job: {}", e))?;
+
+    println!(
+        "{} Job {} requeued",
+        style("✓").green().bold(),
+        style(job_id).dim()
+    );
+    Ok(())
+}
+
+/// Execute the admin fail command.
+pub async fn execute_fail(job_id: &str, reason: &str) -> Result<()> {
+    let (scheduler, principal) = admin_scheduler()?;
+    let parsed_id = parse_job_id(job_id)?;
+
+    scheduler
+        .force_fail(&principal, &parsed_id, reason)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to fail job: {}", e))?;
+
+    println!(
+        "{} Job {} marked failed: {}",
+        style("✓").green().bold(),
+        style(job_id).dim(),
+        reason
+    );
+    Ok(())
+}
+
+/// Execute the admin purge command.
+pub async fn execute_purge(owner: &str) -> Result<()> {
+    let (scheduler, principal) = admin_scheduler()?;
+
+    let purged = scheduler
+        .purge_owner(&principal, owner)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to purge queue: {}", e))?;
+
+    println!(
+        "{} Cancelled {} queued job(s) of {}",
+        style("✓").green().bold(),
+        purged.len(),
+        style(owner).cyan()
+    );
+    for job_id in &purged {
+        println!("  {}", style(job_id).dim());
+    }
+    Ok(())
+}
+
+/// Execute the admin reindex command.
+pub async fn execute_reindex() -> Result<()> {
+    let (scheduler, principal) = admin_scheduler()?;
+
+    let jobs = scheduler
+        .rebuild_indices(&principal)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to rebuild indices: {}", e))?;
+
+    println!(
+        "{} Rebuilt indices for {} job(s)",
+        style("✓").green().bold(),
+        jobs
+    );
+    Ok(())
+}
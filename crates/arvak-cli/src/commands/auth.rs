@@ -7,6 +7,9 @@ use console::style;
 
 use arvak_hal::auth::{OidcAuth, OidcConfig};
 
+/// Known providers as (display name, config constructor) pairs.
+type ProviderList = Vec<(&'static str, fn(&str) -> OidcConfig)>;
+
 /// Execute the auth login subcommand.
 pub async fn execute_login(provider: &str, project: Option<&str>) -> Result<()> {
     let project_id = project.ok_or_else(|| {
@@ -65,7 +68,7 @@ pub async fn execute_login(provider: &str, project: Option<&str>) -> Result<()>
 /// Execute the auth status subcommand.
 pub async fn execute_status(provider: Option<&str>) -> Result<()> {
     // Check each known provider for cached tokens
-    let providers: Vec<(&str, fn(&str) -> OidcConfig)> = vec![
+    let providers: ProviderList = vec![
         ("CSC / LUMI", OidcConfig::lumi as fn(&str) -> OidcConfig),
         ("LRZ", OidcConfig::lrz as fn(&str) -> OidcConfig),
     ];
@@ -113,7 +116,7 @@ pub async fn execute_status(provider: Option<&str>) -> Result<()> {
 
 /// Execute the auth logout subcommand.
 pub async fn execute_logout(provider: Option<&str>) -> Result<()> {
-    let providers: Vec<(&str, fn(&str) -> OidcConfig)> = vec![
+    let providers: ProviderList = vec![
         ("CSC / LUMI", OidcConfig::lumi as fn(&str) -> OidcConfig),
         ("LRZ", OidcConfig::lrz as fn(&str) -> OidcConfig),
     ];
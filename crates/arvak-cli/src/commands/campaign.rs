@@ -0,0 +1,292 @@
+//! Campaign command implementation.
+//!
+//! Expands an experiment campaign into a workflow, previews the plan and
+//! runs it through the local workflow engine. Campaigns cache their nodes,
+//! so `--resume` continues an interrupted campaign by running it again:
+//! nodes that completed before are taken from the cache.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use console::style;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+
+use arvak_sched::{
+    Campaign, HpcScheduler, Scheduler, SqliteStore, Workflow, WorkflowId, WorkflowStatus,
+};
+
+use super::common::{batch_config, create_backend, default_state_dir, open_event_log};
+
+/// A campaign run, kept in `~/.arvak/campaigns/` to detect interrupted runs.
+#[derive(Debug, Serialize, Deserialize)]
+struct CampaignRecord {
+    name: String,
+    file: PathBuf,
+    workflow_id: String,
+    started_at: chrono::DateTime<chrono::Utc>,
+    finished: bool,
+}
+
+impl CampaignRecord {
+    fn path(name: &str) -> Result<PathBuf> {
+        let file_name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let dir = default_state_dir()?.join("campaigns");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(dir.join(format!("{}.json", file_name)))
+    }
+
+    fn load(name: &str) -> Result<Option<Self>> {
+        let path = Self::path(name)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let data = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let record = serde_json::from_str(&data)
+            .with_context(|| format!("Corrupt campaign record {}", path.display()))?;
+        Ok(Some(record))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path(&self.name)?;
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Print the expanded campaign.
+fn print_plan(campaign: &Campaign, workflow: &Workflow) {
+    let total_shots: u64 = workflow
+        .all_jobs()
+        .iter()
+        .filter(|job| job.task.is_none())
+        .map(|job| u64::from(job.shots))
+        .sum();
+    println!(
+        "{} Campaign {}: {} node(s), {} matrix point(s), {} shots",
+        style("→").cyan().bold(),
+        style(campaign.name()).cyan(),
+        workflow.len(),
+        campaign.points(),
+        total_shots
+    );
+    for (param, values) in &campaign.matrix {
+        println!("  {} = {}", style(param).bold(), values.join(", "));
+    }
+
+    let jobs: BTreeMap<&str, _> = workflow
+        .all_jobs()
+        .into_iter()
+        .map(|job| (job.name.as_str(), job))
+        .collect();
+    println!(
+        "\n  {:<28}  {:<32}  {:<8}  {}",
+        style("NODE").bold(),
+        style("RUNS").bold(),
+        style("SHOTS").bold(),
+        style("AFTER").bold()
+    );
+    println!("  {}", "-".repeat(80));
+    for node in &campaign.definition.nodes {
+        let Some(job) = jobs.get(node.name.as_str()) else {
+            continue;
+        };
+        let runs = match (&node.circuit, &node.task) {
+            (Some(circuit), _) => circuit.display().to_string(),
+            (None, Some(task)) => format!("task {}", task),
+            (None, None) => String::new(),
+        };
+        let shots = if job.task.is_some() {
+            "-".to_string()
+        } else {
+            job.shots.to_string()
+        };
+        let after = match node.depends_on.len() {
+            0 => String::new(),
+            1..=3 => node.depends_on.join(", "),
+            n => format!("{} nodes", n),
+        };
+        println!(
+            "  {:<28}  {:<32}  {:<8}  {}",
+            style(&job.name).cyan(),
+            runs,
+            shots,
+            after
+        );
+    }
+    println!();
+}
+
+/// Execute the campaign run command.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_run(
+    file: &str,
+    backend: &str,
+    scheduler: &str,
+    partition: Option<&str>,
+    account: Option<&str>,
+    time: Option<&str>,
+    resume: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let path = Path::new(file);
+    let campaign = Campaign::load(path).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let workflow = campaign
+        .to_workflow(path.parent().unwrap_or(Path::new(".")))
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    print_plan(&campaign, &workflow);
+    if dry_run {
+        return Ok(());
+    }
+
+    if let Some(node) = campaign.definition.nodes.iter().find(|n| n.task.is_some()) {
+        anyhow::bail!(
+            "Node '{}' runs a classical task closure, which the CLI cannot provide",
+            node.name
+        );
+    }
+
+    let previous = CampaignRecord::load(campaign.name())?;
+    match (&previous, resume) {
+        (Some(record), false) if !record.finished => anyhow::bail!(
+            "Campaign '{}' did not finish (workflow {}). Continue it with --resume",
+            record.name,
+            record.workflow_id
+        ),
+        (None, true) => anyhow::bail!("No earlier run of campaign '{}' to resume", campaign.name()),
+        (Some(record), true) => println!(
+            "{} Resuming campaign {} (previous workflow {})",
+            style("→").cyan().bold(),
+            style(campaign.name()).cyan(),
+            style(&record.workflow_id).dim()
+        ),
+        _ => {}
+    }
+
+    // Build the scheduler
+    let state_dir = default_state_dir()?;
+    let store = SqliteStore::new(state_dir.join("jobs.db"))
+        .map_err(|e| anyhow::anyhow!("Failed to open job store: {}", e))?;
+    let hpc = HpcScheduler::new(
+        batch_config(scheduler, partition, account, time)?,
+        vec![create_backend(backend)?],
+        Arc::new(store),
+    )
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?
+    .with_event_log(open_event_log()?);
+    let hpc = Arc::new(hpc);
+    let processor = hpc.clone().start_background_processor();
+
+    let total = workflow.len();
+    let workflow_id = hpc
+        .submit_workflow(workflow)
+        .await
+        .map_err(|e| anyhow::anyhow!("Submit failed: {}", e))?;
+    let mut record = CampaignRecord {
+        name: campaign.name().to_string(),
+        file: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+        workflow_id: workflow_id.to_string(),
+        started_at: chrono::Utc::now(),
+        finished: false,
+    };
+    record.save()?;
+
+    let status = track(&hpc, &workflow_id, total).await;
+    processor.abort();
+    let status = status?;
+    let progress = hpc
+        .workflow_progress(&workflow_id)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to get progress: {}", e))?;
+
+    match status {
+        WorkflowStatus::Completed => {
+            record.finished = true;
+            record.save()?;
+            println!(
+                "{} Campaign {} completed: {} node(s), {} from cache",
+                style("✓").green().bold(),
+                style(campaign.name()).cyan(),
+                progress.completed,
+                progress.cached
+            );
+            println!("  Results are in workflow {}", style(&workflow_id).cyan());
+            Ok(())
+        }
+        other => {
+            println!(
+                "{} Campaign {} stopped: {} completed ({} from cache), {} failed, {} skipped",
+                style("✗").red().bold(),
+                style(campaign.name()).cyan(),
+                progress.completed,
+                progress.cached,
+                progress.failed,
+                progress.skipped
+            );
+            println!(
+                "  Continue with: {} {} --resume",
+                style("arvak campaign run").dim(),
+                style(file).dim()
+            );
+            match other {
+                WorkflowStatus::Failed { reason } => anyhow::bail!("Campaign failed: {}", reason),
+                _ => anyhow::bail!("Campaign was cancelled"),
+            }
+        }
+    }
+}
+
+/// Show a progress bar until the workflow finishes, returning its final
+/// status.
+async fn track(
+    hpc: &HpcScheduler,
+    workflow_id: &WorkflowId,
+    total: usize,
+) -> Result<WorkflowStatus> {
+    let bar = ProgressBar::new(total as u64);
+    bar.set_style(
+        ProgressStyle::default_bar()
+            .template("{spinner:.cyan} [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar.enable_steady_tick(Duration::from_millis(100));
+
+    loop {
+        let progress = hpc
+            .workflow_progress(workflow_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get progress: {}", e))?;
+        bar.set_position((progress.completed + progress.failed + progress.skipped) as u64);
+        bar.set_message(format!(
+            "{} running, {} cached, {} failed",
+            progress.running, progress.cached, progress.failed
+        ));
+
+        let status = hpc
+            .workflow_status(workflow_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to get status: {}", e))?;
+        if status.is_terminal() {
+            bar.finish_and_clear();
+            return Ok(status);
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
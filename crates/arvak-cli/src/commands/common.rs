@@ -6,10 +6,14 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 
+use arvak_adapter_sim::SimulatorBackend;
 use arvak_compile::{BasisGates, CouplingMap};
+use arvak_hal::Backend;
 use arvak_ir::Circuit;
 use arvak_qasm3::parse;
-use arvak_sched::{HpcScheduler, SchedulerConfig, SqliteStore};
+use arvak_sched::{
+    HpcScheduler, JsonlEventLog, PbsConfig, Priority, SchedulerConfig, SlurmConfig, SqliteStore,
+};
 
 /// Load a circuit from a QASM3 or JSON file.
 pub fn load_circuit(path: &str) -> Result<Circuit> {
@@ -50,6 +54,113 @@ pub fn get_target_properties(target: &str) -> Result<(CouplingMap, BasisGates)>
     }
 }
 
+/// Parse a priority name (low, default, high, critical, urgent).
+pub fn parse_priority(priority: Option<&str>) -> Priority {
+    match priority.map(|p| p.to_lowercase()).as_deref() {
+        Some("low") => Priority::low(),
+        Some("high") => Priority::high(),
+        Some("critical") => Priority::critical(),
+        Some("urgent") => Priority::urgent(),
+        _ => Priority::default(),
+    }
+}
+
+/// Build the configuration of a batch scheduler (slurm, pbs).
+///
+/// `time` is a wall time limit in HH:MM:SS.
+pub fn batch_config(
+    scheduler: &str,
+    partition: Option<&str>,
+    account: Option<&str>,
+    time: Option<&str>,
+) -> Result<SchedulerConfig> {
+    let config = match scheduler.to_lowercase().as_str() {
+        "slurm" => {
+            let mut slurm = SlurmConfig::default();
+            if let Some(p) = partition {
+                slurm.partition = p.to_string();
+            }
+            if let Some(a) = account {
+                slurm.account = Some(a.to_string());
+            }
+            if let Some(t) = time {
+                // Parse HH:MM:SS to minutes
+                let parts: Vec<&str> = t.split(':').collect();
+                let minutes = match parts.len() {
+                    3 => {
+                        let h: u32 = parts[0].parse().unwrap_or(0);
+                        let m: u32 = parts[1].parse().unwrap_or(0);
+                        h * 60 + m
+                    }
+                    2 => {
+                        let h: u32 = parts[0].parse().unwrap_or(0);
+                        let m: u32 = parts[1].parse().unwrap_or(0);
+                        h * 60 + m
+                    }
+                    1 => parts[0].parse().unwrap_or(30),
+                    _ => 30,
+                };
+                slurm.time_limit = minutes;
+            }
+            SchedulerConfig::with_slurm(slurm)
+        }
+        "pbs" => {
+            let mut pbs = PbsConfig::default();
+            if let Some(p) = partition {
+                pbs.queue = p.to_string();
+            }
+            if let Some(a) = account {
+                pbs.account = Some(a.to_string());
+            }
+            if let Some(t) = time {
+                pbs.walltime = t.to_string();
+            }
+            SchedulerConfig::with_pbs(pbs)
+        }
+        other => {
+            anyhow::bail!("Unknown scheduler: '{}'. Available: slurm, pbs", other);
+        }
+    };
+    Ok(config)
+}
+
+/// Create a backend by name (simulator, iqm, ibm).
+pub fn create_backend(name: &str) -> Result<Arc<dyn Backend>> {
+    let backend: Arc<dyn Backend> =
+        match name.to_lowercase().as_str() {
+            "simulator" | "sim" => Arc::new(SimulatorBackend::new()),
+            #[cfg(feature = "iqm")]
+            "iqm" | "garnet" => {
+                use arvak_adapter_iqm::IqmBackend;
+                Arc::new(IqmBackend::new().map_err(|e| {
+                    anyhow::anyhow!("Failed to connect to IQM: {}. Set IQM_TOKEN.", e)
+                })?)
+            }
+            #[cfg(not(feature = "iqm"))]
+            "iqm" | "garnet" => {
+                anyhow::bail!("IQM backend not available. Rebuild with --features iqm");
+            }
+            #[cfg(feature = "ibm")]
+            "ibm" | "ibmq" => {
+                use arvak_adapter_ibm::IbmBackend;
+                Arc::new(IbmBackend::new().map_err(|e| {
+                    anyhow::anyhow!("Failed to connect to IBM: {}. Set IBM_QUANTUM_TOKEN.", e)
+                })?)
+            }
+            #[cfg(not(feature = "ibm"))]
+            "ibm" | "ibmq" => {
+                anyhow::bail!("IBM backend not available. Rebuild with --features ibm");
+            }
+            other => {
+                anyhow::bail!(
+                    "Unknown backend: '{}'. Available: simulator, iqm, ibm",
+                    other
+                );
+            }
+        };
+    Ok(backend)
+}
+
 /// Return the default Arvak state directory (~/.arvak/).
 pub fn default_state_dir() -> Result<PathBuf> {
     let home =
@@ -63,6 +174,19 @@ pub fn default_state_dir() -> Result<PathBuf> {
     Ok(state_dir)
 }
 
+/// Return the path of the scheduler event log (~/.arvak/events.jsonl).
+pub fn default_event_log_path() -> Result<PathBuf> {
+    Ok(default_state_dir()?.join("events.jsonl"))
+}
+
+/// Open the scheduler event log for appending.
+pub fn open_event_log() -> Result<Arc<JsonlEventLog>> {
+    let path = default_event_log_path()?;
+    let log = JsonlEventLog::open(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to open event log at {}: {}", path.display(), e))?;
+    Ok(Arc::new(log))
+}
+
 /// Create an HpcScheduler with mock SLURM adapter backed by local SQLite store.
 ///
 /// Used by `status`, `result`, and `wait` commands to query local job state
@@ -73,11 +197,10 @@ pub fn create_scheduler() -> Result<HpcScheduler> {
     let store = SqliteStore::new(&db_path)
         .map_err(|e| anyhow::anyhow!("Failed to open job store at {}: {}", db_path.display(), e))?;
     let config = SchedulerConfig::default();
-    Ok(HpcScheduler::with_mock_slurm(
-        config,
-        vec![],
-        Arc::new(store),
-    ))
+    Ok(
+        HpcScheduler::with_mock_slurm(config, vec![], Arc::new(store))
+            .with_event_log(open_event_log()?),
+    )
 }
 
 /// Print execution results in a table format (shared by run, result, wait).
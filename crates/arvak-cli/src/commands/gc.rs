@@ -0,0 +1,74 @@
+//! Garbage collection command implementation.
+//!
+//! Remove generated batch scripts, circuit files, results and logs for jobs
+//! past their retention window, plus files no longer tied to any job.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use console::style;
+
+use arvak_sched::{JobArtifacts, PbsConfig, RetentionPolicy, SlurmConfig, SqliteStore};
+
+use super::common::default_state_dir;
+
+/// Execute the gc command.
+pub async fn execute(
+    scheduler: &str,
+    work_dir: Option<&str>,
+    keep_days: u32,
+    keep_failed_days: u32,
+    dry_run: bool,
+) -> Result<()> {
+    let artifacts: Box<dyn JobArtifacts> = match scheduler.to_lowercase().as_str() {
+        "slurm" => {
+            let mut slurm = SlurmConfig::default();
+            if let Some(dir) = work_dir {
+                slurm.work_dir = PathBuf::from(dir);
+            }
+            Box::new(slurm)
+        }
+        "pbs" => {
+            let mut pbs = PbsConfig::default();
+            if let Some(dir) = work_dir {
+                pbs.work_dir = PathBuf::from(dir);
+            }
+            Box::new(pbs)
+        }
+        other => {
+            anyhow::bail!("Unknown scheduler: '{}'. Available: slurm, pbs", other);
+        }
+    };
+
+    let db_path = default_state_dir()?.join("jobs.db");
+    let store = SqliteStore::new(&db_path)
+        .map_err(|e| anyhow::anyhow!("Failed to open job store at {}: {}", db_path.display(), e))?;
+
+    let policy = RetentionPolicy::days(keep_days).with_failed_days(keep_failed_days);
+
+    println!(
+        "{} Collecting artifacts in {} (keep {}d, failed {}d){}",
+        style("→").cyan().bold(),
+        style(artifacts.work_dir().display()).dim(),
+        keep_days,
+        keep_failed_days,
+        if dry_run { " [dry run]" } else { "" }
+    );
+
+    let report = arvak_sched::collect_garbage(&store, artifacts.as_ref(), &policy, dry_run)
+        .await
+        .map_err(|e| anyhow::anyhow!("Garbage collection failed: {}", e))?;
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!(
+        "{} {} {} file(s), {:.1} MiB ({} expired job(s), {} orphan(s))",
+        style("✓").green().bold(),
+        verb,
+        report.files_removed,
+        report.bytes_freed as f64 / (1024.0 * 1024.0),
+        report.jobs_cleaned,
+        report.orphans_removed
+    );
+
+    Ok(())
+}
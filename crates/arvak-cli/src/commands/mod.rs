@@ -1,13 +1,19 @@
 //! CLI command implementations.
 
+pub mod admin;
 pub mod auth;
 pub mod backends;
+pub mod campaign;
 pub mod common;
 pub mod compile;
 pub mod eval;
+pub mod gc;
+pub mod recurring;
+pub mod replay;
 pub mod result;
 pub mod run;
 pub mod status;
 pub mod submit;
+pub mod template;
 pub mod version;
 pub mod wait;
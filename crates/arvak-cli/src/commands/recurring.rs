@@ -0,0 +1,205 @@
+//! Recurring job command implementations.
+//!
+//! Manage the cron-scheduled jobs kept in the local scheduler state store.
+//! Runs are submitted by the scheduler serving that store while its
+//! background processor runs.
+
+use anyhow::Result;
+use console::style;
+
+use arvak_sched::{CircuitSpec, CronSchedule, RecurringJob, ScheduledJob, Scheduler};
+
+use super::common::{create_scheduler, load_circuit, parse_priority};
+
+/// Execute the recurring add command.
+pub async fn execute_add(
+    name: &str,
+    cron: &str,
+    input: &str,
+    shots: u32,
+    priority: Option<&str>,
+    max_history: Option<usize>,
+) -> Result<()> {
+    let schedule = CronSchedule::parse(cron).map_err(|e| anyhow::anyhow!("{}", e))?;
+    let circuit = load_circuit(input)?;
+    let circuit_spec = CircuitSpec::from_circuit(&circuit)
+        .map_err(|e| anyhow::anyhow!("Failed to create circuit spec: {}", e))?;
+    let job = ScheduledJob::new(name, circuit_spec)
+        .with_shots(shots)
+        .with_priority(parse_priority(priority));
+
+    let mut recurring = RecurringJob::new(name, schedule, job);
+    if let Some(max_history) = max_history {
+        recurring = recurring.with_max_history(max_history);
+    }
+
+    let scheduler = create_scheduler()?;
+    scheduler
+        .save_recurring(recurring)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to save recurring job: {}", e))?;
+    let recurring = scheduler
+        .recurring_job(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load recurring job: {}", e))?;
+
+    println!(
+        "{} Recurring job {} saved ({})",
+        style("✓").green().bold(),
+        style(name).cyan(),
+        recurring.schedule
+    );
+    match recurring.next_run {
+        Some(next) => println!("  Next run: {}", next.format("%Y-%m-%d %H:%M UTC")),
+        None => println!("  The schedule never fires"),
+    }
+    Ok(())
+}
+
+/// Execute the recurring list command.
+pub async fn execute_list() -> Result<()> {
+    let recurring = create_scheduler()?
+        .recurring_jobs()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list recurring jobs: {}", e))?;
+
+    if recurring.is_empty() {
+        println!("No recurring jobs found.");
+        return Ok(());
+    }
+
+    println!(
+        "{} {} recurring job(s):\n",
+        style("→").cyan().bold(),
+        recurring.len()
+    );
+    println!(
+        "  {:<24}  {:<20}  {:<8}  {:<20}  {}",
+        style("NAME").bold(),
+        style("SCHEDULE").bold(),
+        style("STATE").bold(),
+        style("NEXT RUN").bold(),
+        style("LAST RUN").bold()
+    );
+    println!("  {}", "-".repeat(96));
+    for job in &recurring {
+        let state = if job.paused { "paused" } else { "active" };
+        let next = job
+            .next_run
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let last = match job.history.last() {
+            Some(run) if run.error.is_some() => {
+                format!("{} (failed)", run.scheduled_for.format("%Y-%m-%d %H:%M"))
+            }
+            Some(run) => run.scheduled_for.format("%Y-%m-%d %H:%M").to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "  {:<24}  {:<20}  {:<8}  {:<20}  {}",
+            style(&job.name).cyan(),
+            job.schedule,
+            state,
+            next,
+            last
+        );
+    }
+    Ok(())
+}
+
+/// Execute the recurring history command.
+pub async fn execute_history(name: &str) -> Result<()> {
+    let scheduler = create_scheduler()?;
+    let recurring = scheduler
+        .recurring_job(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load recurring job: {}", e))?;
+
+    if recurring.history.is_empty() {
+        println!("Recurring job {} has not run yet.", style(name).cyan());
+        return Ok(());
+    }
+
+    println!(
+        "{} Last {} run(s) of {}:\n",
+        style("→").cyan().bold(),
+        recurring.history.len(),
+        style(name).cyan()
+    );
+    println!(
+        "  {:<18}  {:<38}  {}",
+        style("SCHEDULED").bold(),
+        style("JOB").bold(),
+        style("STATUS").bold()
+    );
+    println!("  {}", "-".repeat(80));
+    for run in recurring.history.iter().rev() {
+        let status = match &run.error {
+            Some(error) => format!("not submitted: {}", error),
+            None => match scheduler.status(&run.job_id).await {
+                Ok(status) => status.name().to_string(),
+                Err(_) => "removed".to_string(),
+            },
+        };
+        println!(
+            "  {:<18}  {:<38}  {}",
+            run.scheduled_for.format("%Y-%m-%d %H:%M"),
+            style(&run.job_id).cyan(),
+            status
+        );
+    }
+    Ok(())
+}
+
+/// Execute the recurring pause command.
+pub async fn execute_pause(name: &str) -> Result<()> {
+    create_scheduler()?
+        .pause_recurring(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to pause recurring job: {}", e))?;
+
+    println!(
+        "{} Recurring job {} paused",
+        style("✓").green().bold(),
+        style(name).cyan()
+    );
+    Ok(())
+}
+
+/// Execute the recurring resume command.
+pub async fn execute_resume(name: &str) -> Result<()> {
+    let scheduler = create_scheduler()?;
+    scheduler
+        .resume_recurring(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to resume recurring job: {}", e))?;
+    let recurring = scheduler
+        .recurring_job(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load recurring job: {}", e))?;
+
+    println!(
+        "{} Recurring job {} resumed",
+        style("✓").green().bold(),
+        style(name).cyan()
+    );
+    if let Some(next) = recurring.next_run {
+        println!("  Next run: {}", next.format("%Y-%m-%d %H:%M UTC"));
+    }
+    Ok(())
+}
+
+/// Execute the recurring delete command.
+pub async fn execute_delete(name: &str) -> Result<()> {
+    create_scheduler()?
+        .delete_recurring(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to delete recurring job: {}", e))?;
+
+    println!(
+        "{} Recurring job {} deleted",
+        style("✓").green().bold(),
+        style(name).cyan()
+    );
+    Ok(())
+}
@@ -0,0 +1,91 @@
+//! Replay command implementation.
+//!
+//! Reconstruct scheduler decisions from the event log for postmortems.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use console::style;
+
+use arvak_sched::{JsonlEventLog, ReplayFilter, ScheduledJobId, replay};
+
+use super::common::default_event_log_path;
+
+/// Execute the replay command.
+pub async fn execute(
+    log: Option<&str>,
+    job_id: Option<&str>,
+    since: Option<&str>,
+    until: Option<&str>,
+    format: &str,
+    output: Option<&str>,
+) -> Result<()> {
+    let path = match log {
+        Some(path) => PathBuf::from(path),
+        None => default_event_log_path()?,
+    };
+
+    let mut filter = ReplayFilter::all();
+    if let Some(id) = job_id {
+        filter.job_id = Some(
+            ScheduledJobId::parse(id)
+                .map_err(|e| anyhow::anyhow!("Invalid job ID '{}': {}", id, e))?,
+        );
+    }
+    if let Some(since) = since {
+        filter = filter.with_since(parse_time(since)?);
+    }
+    if let Some(until) = until {
+        filter = filter.with_until(parse_time(until)?);
+    }
+
+    let events = JsonlEventLog::read(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read event log {}: {}", path.display(), e))?;
+    let trace = replay(events, &filter);
+
+    let rendered = match format {
+        "json" => serde_json::to_string_pretty(&trace)
+            .map_err(|e| anyhow::anyhow!("JSON serialization failed: {}", e))?,
+        "text" => trace.to_string(),
+        other => anyhow::bail!("Unknown format: '{}'. Available: text, json", other),
+    };
+
+    match output {
+        Some(out) => {
+            std::fs::write(out, &rendered)?;
+            println!(
+                "{} Wrote {} decision(s) for {} job(s) to {}",
+                style("✓").green().bold(),
+                trace.steps.len(),
+                trace.jobs.len(),
+                out
+            );
+        }
+        None if trace.steps.is_empty() => {
+            println!(
+                "{} No decisions in {} match the filter",
+                style("!").yellow().bold(),
+                path.display()
+            );
+        }
+        None => print!("{}", rendered),
+    }
+
+    Ok(())
+}
+
+/// Parse an RFC 3339 timestamp or a `YYYY-MM-DD` date (midnight UTC).
+fn parse_time(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(|d| d.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid time '{}': expected RFC 3339 (2026-01-31T12:00:00Z) or YYYY-MM-DD",
+                s
+            )
+        })
+}
@@ -1,13 +1,16 @@
 //! Run command implementation.
 
+use std::path::PathBuf;
+
 use anyhow::Result;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 
-use arvak_adapter_sim::SimulatorBackend;
+use arvak_adapter_sim::{SimulatorBackend, TraceMode};
 use arvak_compile::PassManagerBuilder;
-use arvak_hal::Backend;
+use arvak_hal::{Backend, PartialResult};
 use arvak_ir::Circuit;
+use arvak_sched::partial::write_snapshot;
 
 #[cfg(feature = "iqm")]
 use arvak_adapter_iqm::IqmBackend;
@@ -18,12 +21,19 @@ use arvak_adapter_ibm::IbmBackend;
 use super::common::{get_target_properties, load_circuit, print_results};
 
 /// Execute the run command.
+///
+/// With `partial` set to a snapshot file and a shot interval, the shots are
+/// run in chunks and the counts so far are written to the file after each.
+/// With `trace` set, the simulator writes a gate-level trace of the first
+/// shot to the given file.
 pub async fn execute(
     input: &str,
     shots: u32,
     backend: &str,
     do_compile: bool,
     target: Option<&str>,
+    partial: Option<(PathBuf, u32)>,
+    trace: Option<(TraceMode, PathBuf)>,
 ) -> Result<()> {
     println!(
         "{} Running {} on {} ({} shots)",
@@ -64,8 +74,16 @@ pub async fn execute(
     }
 
     // Create backend
+    let is_simulator = matches!(backend.to_lowercase().as_str(), "simulator" | "sim");
+    if trace.is_some() && !is_simulator {
+        anyhow::bail!("Execution traces are only available on the simulator");
+    }
+    let trace_path = trace.as_ref().map(|(_, path)| path.clone());
     let backend_impl: Box<dyn Backend> = match backend.to_lowercase().as_str() {
-        "simulator" | "sim" => Box::new(SimulatorBackend::new()),
+        "simulator" | "sim" => match trace {
+            Some((mode, path)) => Box::new(SimulatorBackend::new().with_trace(mode, path)),
+            None => Box::new(SimulatorBackend::new()),
+        },
         #[cfg(feature = "iqm")]
         "iqm" | "garnet" => {
             println!("  Connecting to IQM Resonance...");
@@ -123,15 +141,41 @@ pub async fn execute(
     spinner.set_message("Submitting job...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let job_id = backend_impl.submit(&circuit, shots).await?;
-    spinner.set_message(format!("Running job {}...", job_id));
-
-    // Wait for result
-    let result = backend_impl.wait(&job_id).await?;
+    let result = if let Some((path, chunk_shots)) = partial {
+        spinner.set_message(format!("Running in chunks of {} shots...", chunk_shots));
+        let mut on_partial = |partial: PartialResult| {
+            spinner.set_message(format!(
+                "Completed {} of {} shots...",
+                partial.shots_completed, partial.shots_total
+            ));
+            if let Err(e) = write_snapshot(&path, &partial) {
+                spinner.println(format!(
+                    "  {} Failed to write partial results: {}",
+                    style("!").yellow(),
+                    e
+                ));
+            }
+        };
+        backend_impl
+            .run_chunked(&circuit, shots, chunk_shots, &mut on_partial)
+            .await?
+    } else {
+        let job_id = backend_impl.submit(&circuit, shots).await?;
+        spinner.set_message(format!("Running job {}...", job_id));
+
+        // Wait for result
+        backend_impl.wait(&job_id).await?
+    };
     spinner.finish_and_clear();
 
     // Print results
     print_results(&result);
+    if let Some(path) = trace_path {
+        println!(
+            "  Execution trace written to {}",
+            style(path.display()).green()
+        );
+    }
 
     Ok(())
 }
@@ -5,7 +5,7 @@
 use anyhow::Result;
 use console::style;
 
-use arvak_sched::{JobFilter, ScheduledJobId, Scheduler};
+use arvak_sched::{JobFilter, Scheduler};
 
 use super::common::create_scheduler;
 
@@ -41,8 +41,8 @@ pub async fn execute(job_id: Option<&str>, all: bool) -> Result<()> {
         for job in &jobs {
             let status_name = job.status.name();
             let status_styled = match status_name {
-                "Completed" => style(status_name).green(),
-                "Failed" | "Cancelled" => style(status_name).red(),
+                "Completed" | "CachedComplete" => style(status_name).green(),
+                "Failed" | "Cancelled" | "DeadlineExceeded" => style(status_name).red(),
                 "Pending" | "WaitingOnDependencies" => style(status_name).yellow(),
                 _ => style(status_name).cyan(),
             };
@@ -64,18 +64,16 @@ pub async fn execute(job_id: Option<&str>, all: bool) -> Result<()> {
     let job_id_str = job_id
         .ok_or_else(|| anyhow::anyhow!("Please provide a job ID or use --all to list all jobs"))?;
 
-    let parsed_id = ScheduledJobId::parse(job_id_str)
-        .map_err(|e| anyhow::anyhow!("Invalid job ID '{}': {}", job_id_str, e))?;
-
-    let status = scheduler
-        .status(&parsed_id)
+    // Batch job and request IDs resolve to the job they refer to
+    let (resolved_id, status) = scheduler
+        .status_by_any_id(job_id_str)
         .await
         .map_err(|e| anyhow::anyhow!("Failed to get status: {}", e))?;
 
     let status_name = status.name();
     let status_styled = match status_name {
-        "Completed" => style(status_name).green().bold(),
-        "Failed" | "Cancelled" => style(status_name).red().bold(),
+        "Completed" | "CachedComplete" => style(status_name).green().bold(),
+        "Failed" | "Cancelled" | "DeadlineExceeded" => style(status_name).red().bold(),
         "Pending" | "WaitingOnDependencies" => style(status_name).yellow().bold(),
         _ => style(status_name).cyan().bold(),
     };
@@ -83,7 +81,7 @@ pub async fn execute(job_id: Option<&str>, all: bool) -> Result<()> {
     println!(
         "{} Job {} status: {}",
         style("→").cyan().bold(),
-        style(job_id_str).dim(),
+        style(resolved_id.to_string()).dim(),
         status_styled
     );
 
@@ -7,16 +7,15 @@ use std::sync::Arc;
 use anyhow::Result;
 use console::style;
 
-use arvak_adapter_sim::SimulatorBackend;
-use arvak_hal::Backend;
-use arvak_sched::{
-    CircuitSpec, HpcScheduler, PbsConfig, Priority, ScheduledJob, Scheduler, SchedulerConfig,
-    SlurmConfig, SqliteStore,
-};
+use arvak_sched::{CircuitSpec, HpcScheduler, ScheduledJob, Scheduler, SqliteStore};
 
-use super::common::{default_state_dir, load_circuit, print_results};
+use super::common::{
+    batch_config, create_backend, default_state_dir, load_circuit, open_event_log, parse_priority,
+    print_results,
+};
 
 /// Execute the submit command.
+#[allow(clippy::too_many_arguments)]
 pub async fn execute(
     input: &str,
     backend: &str,
@@ -53,100 +52,19 @@ pub async fn execute(
     let store = SqliteStore::new(&db_path)
         .map_err(|e| anyhow::anyhow!("Failed to open job store: {}", e))?;
 
-    let sched_config = match scheduler.to_lowercase().as_str() {
-        "slurm" => {
-            let mut slurm = SlurmConfig::default();
-            if let Some(p) = partition {
-                slurm.partition = p.to_string();
-            }
-            if let Some(a) = account {
-                slurm.account = Some(a.to_string());
-            }
-            if let Some(t) = time {
-                // Parse HH:MM:SS to minutes
-                let parts: Vec<&str> = t.split(':').collect();
-                let minutes = match parts.len() {
-                    3 => {
-                        let h: u32 = parts[0].parse().unwrap_or(0);
-                        let m: u32 = parts[1].parse().unwrap_or(0);
-                        h * 60 + m
-                    }
-                    2 => {
-                        let h: u32 = parts[0].parse().unwrap_or(0);
-                        let m: u32 = parts[1].parse().unwrap_or(0);
-                        h * 60 + m
-                    }
-                    1 => parts[0].parse().unwrap_or(30),
-                    _ => 30,
-                };
-                slurm.time_limit = minutes;
-            }
-            SchedulerConfig::with_slurm(slurm)
-        }
-        "pbs" => {
-            let mut pbs = PbsConfig::default();
-            if let Some(p) = partition {
-                pbs.queue = p.to_string();
-            }
-            if let Some(a) = account {
-                pbs.account = Some(a.to_string());
-            }
-            if let Some(t) = time {
-                pbs.walltime = t.to_string();
-            }
-            SchedulerConfig::with_pbs(pbs)
-        }
-        other => {
-            anyhow::bail!("Unknown scheduler: '{}'. Available: slurm, pbs", other);
-        }
-    };
+    let sched_config = batch_config(scheduler, partition, account, time)?;
 
     // Create backend
-    let backend_impl: Arc<dyn Backend> =
-        match backend.to_lowercase().as_str() {
-            "simulator" | "sim" => Arc::new(SimulatorBackend::new()),
-            #[cfg(feature = "iqm")]
-            "iqm" | "garnet" => {
-                use arvak_adapter_iqm::IqmBackend;
-                Arc::new(IqmBackend::new().map_err(|e| {
-                    anyhow::anyhow!("Failed to connect to IQM: {}. Set IQM_TOKEN.", e)
-                })?)
-            }
-            #[cfg(not(feature = "iqm"))]
-            "iqm" | "garnet" => {
-                anyhow::bail!("IQM backend not available. Rebuild with --features iqm");
-            }
-            #[cfg(feature = "ibm")]
-            "ibm" | "ibmq" => {
-                use arvak_adapter_ibm::IbmBackend;
-                Arc::new(IbmBackend::new().map_err(|e| {
-                    anyhow::anyhow!("Failed to connect to IBM: {}. Set IBM_QUANTUM_TOKEN.", e)
-                })?)
-            }
-            #[cfg(not(feature = "ibm"))]
-            "ibm" | "ibmq" => {
-                anyhow::bail!("IBM backend not available. Rebuild with --features ibm");
-            }
-            other => {
-                anyhow::bail!(
-                    "Unknown backend: '{}'. Available: simulator, iqm, ibm",
-                    other
-                );
-            }
-        };
+    let backend_impl = create_backend(backend)?;
 
     // Create HPC scheduler
     let hpc = HpcScheduler::new(sched_config, vec![backend_impl], Arc::new(store))
         .await
-        .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?;
+        .map_err(|e| anyhow::anyhow!("Failed to create scheduler: {}", e))?
+        .with_event_log(open_event_log()?);
 
     // Build job
-    let job_priority = match priority.map(|p| p.to_lowercase()).as_deref() {
-        Some("low") => Priority::low(),
-        Some("high") => Priority::high(),
-        Some("critical") => Priority::critical(),
-        _ => Priority::default(),
-    };
+    let job_priority = parse_priority(priority);
 
     let name = std::path::Path::new(input)
         .file_stem()
@@ -0,0 +1,169 @@
+//! Template command implementations.
+//!
+//! Manage the job templates kept in the local scheduler state store and
+//! queue jobs created from them.
+
+use anyhow::Result;
+use console::style;
+
+use arvak_sched::{CircuitSpec, JobTemplate, TemplateOverrides};
+
+use super::common::{create_scheduler, load_circuit, parse_priority};
+
+/// Parse `key=value` labels.
+fn parse_labels(labels: &[String]) -> Result<Vec<(String, String)>> {
+    labels
+        .iter()
+        .map(|label| {
+            label
+                .split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| anyhow::anyhow!("Invalid label '{}': expected KEY=VALUE", label))
+        })
+        .collect()
+}
+
+/// Execute the template save command.
+pub async fn execute_save(
+    name: &str,
+    input: &str,
+    shots: u32,
+    priority: Option<&str>,
+    labels: &[String],
+    description: Option<&str>,
+) -> Result<()> {
+    let circuit = load_circuit(input)?;
+    let circuit_spec = CircuitSpec::from_circuit(&circuit)
+        .map_err(|e| anyhow::anyhow!("Failed to create circuit spec: {}", e))?;
+
+    let mut template = JobTemplate::new(name, circuit_spec)
+        .with_shots(shots)
+        .with_priority(parse_priority(priority));
+    for (key, value) in parse_labels(labels)? {
+        template = template.with_label(key, value);
+    }
+    if let Some(description) = description {
+        template = template.with_description(description);
+    }
+
+    create_scheduler()?
+        .save_template(template)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to save template: {}", e))?;
+
+    println!(
+        "{} Template {} saved",
+        style("✓").green().bold(),
+        style(name).cyan()
+    );
+    Ok(())
+}
+
+/// Execute the template list command.
+pub async fn execute_list() -> Result<()> {
+    let templates = create_scheduler()?
+        .templates()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to list templates: {}", e))?;
+
+    if templates.is_empty() {
+        println!("No templates found.");
+        return Ok(());
+    }
+
+    println!(
+        "{} {} template(s):\n",
+        style("→").cyan().bold(),
+        templates.len()
+    );
+    println!(
+        "  {:<24}  {:<8}  {:<8}  {:<16}  {}",
+        style("NAME").bold(),
+        style("SHOTS").bold(),
+        style("PRIORITY").bold(),
+        style("UPDATED").bold(),
+        style("DESCRIPTION").bold()
+    );
+    println!("  {}", "-".repeat(80));
+    for template in &templates {
+        println!(
+            "  {:<24}  {:<8}  {:<8}  {:<16}  {}",
+            style(&template.name).cyan(),
+            template.shots,
+            template.priority.value(),
+            template.updated_at.format("%Y-%m-%d %H:%M"),
+            template.description.as_deref().unwrap_or("")
+        );
+    }
+    Ok(())
+}
+
+/// Execute the template show command.
+pub async fn execute_show(name: &str) -> Result<()> {
+    let template = create_scheduler()?
+        .template(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load template: {}", e))?;
+
+    println!("{}", serde_json::to_string_pretty(&template)?);
+    Ok(())
+}
+
+/// Execute the template delete command.
+pub async fn execute_delete(name: &str) -> Result<()> {
+    create_scheduler()?
+        .delete_template(name)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to delete template: {}", e))?;
+
+    println!(
+        "{} Template {} deleted",
+        style("✓").green().bold(),
+        style(name).cyan()
+    );
+    Ok(())
+}
+
+/// Execute the template submit command.
+///
+/// The job is queued in the local state store, where the scheduler serving
+/// that store picks it up.
+pub async fn execute_submit(
+    name: &str,
+    job_name: Option<&str>,
+    shots: Option<u32>,
+    priority: Option<&str>,
+    labels: &[String],
+) -> Result<()> {
+    let mut overrides = TemplateOverrides::new();
+    if let Some(job_name) = job_name {
+        overrides = overrides.with_name(job_name);
+    }
+    if let Some(shots) = shots {
+        overrides = overrides.with_shots(shots);
+    }
+    if priority.is_some() {
+        overrides = overrides.with_priority(parse_priority(priority));
+    }
+    for (key, value) in parse_labels(labels)? {
+        overrides = overrides.with_label(key, value);
+    }
+
+    let job_id = create_scheduler()?
+        .submit_template(name, overrides)
+        .await
+        .map_err(|e| anyhow::anyhow!("Submit failed: {}", e))?;
+
+    println!(
+        "{} Job submitted from template {}: {}",
+        style("✓").green().bold(),
+        style(name).cyan(),
+        style(&job_id).cyan()
+    );
+    println!(
+        "  Track with: {} {}",
+        style("arvak status").dim(),
+        style(&job_id).dim()
+    );
+    Ok(())
+}
@@ -51,7 +51,10 @@ use tracing_subscriber::EnvFilter;
 
 mod commands;
 
-use commands::{auth, backends, compile, eval, result, run, status, submit, version, wait};
+use commands::{
+    admin, auth, backends, campaign, compile, eval, gc, recurring, replay, result, run, status,
+    submit, template, version, wait,
+};
 
 /// Arvak - Rust-native quantum compilation and orchestration for HPC
 #[derive(Parser)]
@@ -108,6 +111,22 @@ enum Commands {
         /// Target for compilation
         #[arg(long)]
         target: Option<String>,
+
+        /// Write partial-results snapshots to this file while running
+        #[arg(long, env = arvak_sched::partial::PARTIAL_OUTPUT_ENV)]
+        partial_output: Option<std::path::PathBuf>,
+
+        /// Shots between partial-results snapshots
+        #[arg(long, env = arvak_sched::partial::PARTIAL_SHOTS_ENV, default_value = "1000")]
+        partial_shots: u32,
+
+        /// Write a gate-level execution trace to this file (simulator only)
+        #[arg(long)]
+        trace: Option<std::path::PathBuf>,
+
+        /// What the trace records: norms, snapshots or amplitudes:<i>,<j>,...
+        #[arg(long, default_value = "norms")]
+        trace_mode: String,
     },
 
     /// Submit a circuit to an HPC batch scheduler
@@ -140,7 +159,7 @@ enum Commands {
         #[arg(long)]
         time: Option<String>,
 
-        /// Job priority (low, default, high, critical)
+        /// Job priority (low, default, high, critical, urgent)
         #[arg(long)]
         priority: Option<String>,
 
@@ -185,6 +204,79 @@ enum Commands {
         timeout: u64,
     },
 
+    /// Operator interventions on local scheduler state (audited)
+    Admin {
+        #[command(subcommand)]
+        action: AdminAction,
+    },
+
+    /// Manage job templates and submit jobs from them
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+
+    /// Manage jobs resubmitted on a cron schedule
+    Recurring {
+        #[command(subcommand)]
+        action: RecurringAction,
+    },
+
+    /// Run experiment campaigns
+    Campaign {
+        #[command(subcommand)]
+        action: CampaignAction,
+    },
+
+    /// Remove stale batch scripts, circuit files and logs
+    Gc {
+        /// Batch scheduler (slurm, pbs)
+        #[arg(long, default_value = "slurm")]
+        scheduler: String,
+
+        /// Scheduler work directory (defaults to the adapter default)
+        #[arg(long)]
+        work_dir: Option<String>,
+
+        /// Keep artifacts of completed jobs for this many days
+        #[arg(long, default_value = "7")]
+        keep_days: u32,
+
+        /// Keep artifacts of failed or cancelled jobs for this many days
+        #[arg(long, default_value = "30")]
+        keep_failed_days: u32,
+
+        /// Report what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Replay scheduler decisions from the event log
+    Replay {
+        /// Event log (defaults to ~/.arvak/events.jsonl)
+        log: Option<String>,
+
+        /// Only trace this job ID (UUID)
+        #[arg(short, long)]
+        job: Option<String>,
+
+        /// Start of the time window (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// End of the time window (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Write the trace to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
     /// Evaluate a circuit: compilation observability, QDMI contract check, metrics
     Eval {
         /// Input file (QASM3)
@@ -267,6 +359,197 @@ enum AuthAction {
     },
 }
 
+#[derive(Subcommand)]
+enum AdminAction {
+    /// Put a stuck job back into the queue
+    Requeue {
+        /// Job ID (UUID)
+        job_id: String,
+
+        /// Reason recorded in the audit log
+        #[arg(short, long, default_value = "requeued by operator")]
+        reason: String,
+    },
+
+    /// Mark a job failed
+    Fail {
+        /// Job ID (UUID)
+        job_id: String,
+
+        /// Failure reason
+        #[arg(short, long)]
+        reason: String,
+    },
+
+    /// Cancel all jobs of an owner that have not started running
+    Purge {
+        /// Owner whose queue to purge
+        owner: String,
+    },
+
+    /// Rebuild the job store's indices from the stored jobs
+    Reindex,
+}
+
+#[derive(Subcommand)]
+enum TemplateAction {
+    /// Save a template, replacing any template of the same name
+    Save {
+        /// Template name
+        name: String,
+
+        /// Input file (QASM3)
+        #[arg(short, long)]
+        input: String,
+
+        /// Number of shots
+        #[arg(short, long, default_value = "1024")]
+        shots: u32,
+
+        /// Job priority (low, default, high, critical, urgent)
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Label copied into each job's metadata (KEY=VALUE, repeatable)
+        #[arg(short, long)]
+        label: Vec<String>,
+
+        /// What the template is for
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// List templates
+    List,
+
+    /// Show a template as JSON
+    Show {
+        /// Template name
+        name: String,
+    },
+
+    /// Delete a template
+    Delete {
+        /// Template name
+        name: String,
+    },
+
+    /// Queue a job created from a template
+    Submit {
+        /// Template name
+        name: String,
+
+        /// Job name (defaults to the template name)
+        #[arg(long)]
+        job_name: Option<String>,
+
+        /// Number of shots
+        #[arg(short, long)]
+        shots: Option<u32>,
+
+        /// Job priority (low, default, high, critical, urgent)
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Label added to the job's metadata (KEY=VALUE, repeatable)
+        #[arg(short, long)]
+        label: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RecurringAction {
+    /// Save a recurring job, replacing any recurring job of the same name
+    Add {
+        /// Recurring job name
+        name: String,
+
+        /// Cron expression in UTC, e.g. "0 6 * * *" or "@daily"
+        #[arg(long)]
+        cron: String,
+
+        /// Input file (QASM3)
+        #[arg(short, long)]
+        input: String,
+
+        /// Number of shots
+        #[arg(short, long, default_value = "1024")]
+        shots: u32,
+
+        /// Job priority (low, default, high, critical, urgent)
+        #[arg(long)]
+        priority: Option<String>,
+
+        /// Runs to keep in the history
+        #[arg(long)]
+        max_history: Option<usize>,
+    },
+
+    /// List recurring jobs
+    List,
+
+    /// Show the past runs of a recurring job
+    History {
+        /// Recurring job name
+        name: String,
+    },
+
+    /// Stop submitting runs until resumed
+    Pause {
+        /// Recurring job name
+        name: String,
+    },
+
+    /// Resume a paused recurring job, skipping missed runs
+    Resume {
+        /// Recurring job name
+        name: String,
+    },
+
+    /// Delete a recurring job
+    Delete {
+        /// Recurring job name
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CampaignAction {
+    /// Expand a campaign's experiment matrix and run it as a workflow
+    Run {
+        /// Campaign file (YAML)
+        file: String,
+
+        /// Backend to use (simulator, iqm, ibm)
+        #[arg(short, long, default_value = "simulator")]
+        backend: String,
+
+        /// Batch scheduler (slurm, pbs)
+        #[arg(long, default_value = "slurm")]
+        scheduler: String,
+
+        /// Scheduler partition/queue name
+        #[arg(long)]
+        partition: Option<String>,
+
+        /// Scheduler account/project
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Wall time limit per job (HH:MM:SS)
+        #[arg(long)]
+        time: Option<String>,
+
+        /// Continue an interrupted run, reusing the nodes that completed
+        #[arg(long)]
+        resume: bool,
+
+        /// Show the plan without running it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -299,7 +582,31 @@ async fn main() -> anyhow::Result<()> {
             backend,
             compile: do_compile,
             target,
-        } => run::execute(&input, shots, &backend, do_compile, target.as_deref()).await,
+            partial_output,
+            partial_shots,
+            trace,
+            trace_mode,
+        } => {
+            let partial = partial_output.map(|path| (path, partial_shots));
+            let trace = trace
+                .map(|path| {
+                    trace_mode
+                        .parse::<arvak_adapter_sim::TraceMode>()
+                        .map(|mode| (mode, path))
+                        .map_err(anyhow::Error::msg)
+                })
+                .transpose()?;
+            run::execute(
+                &input,
+                shots,
+                &backend,
+                do_compile,
+                target.as_deref(),
+                partial,
+                trace,
+            )
+            .await
+        }
 
         Commands::Submit {
             input,
@@ -340,6 +647,142 @@ async fn main() -> anyhow::Result<()> {
 
         Commands::Wait { job_id, timeout } => wait::execute(&job_id, timeout).await,
 
+        Commands::Admin { action } => match action {
+            AdminAction::Requeue { job_id, reason } => {
+                admin::execute_requeue(&job_id, &reason).await
+            }
+            AdminAction::Fail { job_id, reason } => admin::execute_fail(&job_id, &reason).await,
+            AdminAction::Purge { owner } => admin::execute_purge(&owner).await,
+            AdminAction::Reindex => admin::execute_reindex().await,
+        },
+
+        Commands::Template { action } => match action {
+            TemplateAction::Save {
+                name,
+                input,
+                shots,
+                priority,
+                label,
+                description,
+            } => {
+                template::execute_save(
+                    &name,
+                    &input,
+                    shots,
+                    priority.as_deref(),
+                    &label,
+                    description.as_deref(),
+                )
+                .await
+            }
+            TemplateAction::List => template::execute_list().await,
+            TemplateAction::Show { name } => template::execute_show(&name).await,
+            TemplateAction::Delete { name } => template::execute_delete(&name).await,
+            TemplateAction::Submit {
+                name,
+                job_name,
+                shots,
+                priority,
+                label,
+            } => {
+                template::execute_submit(
+                    &name,
+                    job_name.as_deref(),
+                    shots,
+                    priority.as_deref(),
+                    &label,
+                )
+                .await
+            }
+        },
+
+        Commands::Recurring { action } => match action {
+            RecurringAction::Add {
+                name,
+                cron,
+                input,
+                shots,
+                priority,
+                max_history,
+            } => {
+                recurring::execute_add(
+                    &name,
+                    &cron,
+                    &input,
+                    shots,
+                    priority.as_deref(),
+                    max_history,
+                )
+                .await
+            }
+            RecurringAction::List => recurring::execute_list().await,
+            RecurringAction::History { name } => recurring::execute_history(&name).await,
+            RecurringAction::Pause { name } => recurring::execute_pause(&name).await,
+            RecurringAction::Resume { name } => recurring::execute_resume(&name).await,
+            RecurringAction::Delete { name } => recurring::execute_delete(&name).await,
+        },
+
+        Commands::Campaign { action } => match action {
+            CampaignAction::Run {
+                file,
+                backend,
+                scheduler,
+                partition,
+                account,
+                time,
+                resume,
+                dry_run,
+            } => {
+                campaign::execute_run(
+                    &file,
+                    &backend,
+                    &scheduler,
+                    partition.as_deref(),
+                    account.as_deref(),
+                    time.as_deref(),
+                    resume,
+                    dry_run,
+                )
+                .await
+            }
+        },
+
+        Commands::Gc {
+            scheduler,
+            work_dir,
+            keep_days,
+            keep_failed_days,
+            dry_run,
+        } => {
+            gc::execute(
+                &scheduler,
+                work_dir.as_deref(),
+                keep_days,
+                keep_failed_days,
+                dry_run,
+            )
+            .await
+        }
+
+        Commands::Replay {
+            log,
+            job,
+            since,
+            until,
+            format,
+            output,
+        } => {
+            replay::execute(
+                log.as_deref(),
+                job.as_deref(),
+                since.as_deref(),
+                until.as_deref(),
+                &format,
+                output.as_deref(),
+            )
+            .await
+        }
+
         Commands::Eval {
             input,
             profile,
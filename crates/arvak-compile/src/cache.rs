@@ -0,0 +1,443 @@
+//! Cache of compiled circuits.
+//!
+//! Compiling the same circuit for the same target with the same pipeline
+//! always gives the same output (see [`PassManager`]), so the result can be
+//! reused. [`CompileCache`] keeps recently compiled circuits in memory and can
+//! also persist them to a directory shared between processes.
+
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use arvak_ir::hash::fnv1a;
+use arvak_ir::{CircuitDag, CircuitLevel, ClbitId, Instruction, QubitId};
+
+use crate::error::CompileResult;
+use crate::manager::PassManager;
+use crate::property::PropertySet;
+
+/// Identifies one compilation: what was compiled, how, and for which target.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CacheKey {
+    /// Canonical hash of the input circuit.
+    pub circuit: u64,
+    /// Fingerprint of the pipeline configuration, see
+    /// [`PassManagerBuilder::fingerprint`](crate::PassManagerBuilder::fingerprint).
+    pub pipeline: u64,
+    /// Target calibration version; a new calibration invalidates entries.
+    pub target_version: String,
+}
+
+impl CacheKey {
+    /// Create a cache key.
+    pub fn new(circuit: u64, pipeline: u64, target_version: impl Into<String>) -> Self {
+        Self {
+            circuit,
+            pipeline,
+            target_version: target_version.into(),
+        }
+    }
+
+    /// File name of the entry in an on-disk store.
+    fn file_name(&self) -> String {
+        format!(
+            "{:016x}-{:016x}-{:016x}.json",
+            self.circuit,
+            self.pipeline,
+            fnv1a(self.target_version.as_bytes())
+        )
+    }
+}
+
+/// Hit and miss counters of a [`CompileCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// Lookups answered from memory.
+    pub memory_hits: u64,
+    /// Lookups answered from the on-disk store.
+    pub disk_hits: u64,
+    /// Lookups that found nothing.
+    pub misses: u64,
+    /// Entries dropped from memory to stay within capacity.
+    pub evictions: u64,
+}
+
+/// A compiled circuit as stored on disk.
+#[derive(Serialize, Deserialize)]
+struct StoredCircuit {
+    key: CacheKey,
+    qubits: Vec<QubitId>,
+    clbits: Vec<ClbitId>,
+    global_phase: f64,
+    level: CircuitLevel,
+    ops: Vec<Instruction>,
+}
+
+impl StoredCircuit {
+    fn capture(key: &CacheKey, dag: &CircuitDag) -> Self {
+        let mut qubits: Vec<_> = dag.qubits().collect();
+        qubits.sort_by_key(|q| q.0);
+        let mut clbits: Vec<_> = dag.clbits().collect();
+        clbits.sort_by_key(|c| c.0);
+        Self {
+            key: key.clone(),
+            qubits,
+            clbits,
+            global_phase: dag.global_phase(),
+            level: dag.level(),
+            ops: dag.topological_ops().map(|(_, i)| i.clone()).collect(),
+        }
+    }
+
+    fn into_dag(self) -> CompileResult<CircuitDag> {
+        let mut dag = CircuitDag::new();
+        for q in self.qubits {
+            dag.add_qubit(q);
+        }
+        for c in self.clbits {
+            dag.add_clbit(c);
+        }
+        dag.set_global_phase(self.global_phase);
+        dag.set_level(self.level);
+        for inst in self.ops {
+            dag.apply(inst)?;
+        }
+        Ok(dag)
+    }
+}
+
+struct Entry {
+    dag: CircuitDag,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: FxHashMap<CacheKey, Entry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// Cache of compiled circuits with least-recently-used eviction.
+///
+/// Entries are kept in memory up to the configured capacity. With
+/// [`with_disk`](Self::with_disk), every entry is also written to a directory
+/// and memory misses fall back to it, so the cache survives restarts and can
+/// be shared by processes on the same file system.
+///
+/// The cache never fails a compilation: unreadable or corrupt disk entries
+/// are logged and treated as misses. Only the compiled circuit is cached;
+/// analysis results stored in the [`PropertySet`] by passes are not restored
+/// on a hit.
+///
+/// # Example
+///
+/// ```
+/// use arvak_compile::{BasisGates, CacheKey, CompileCache, CouplingMap, PassManagerBuilder};
+/// use arvak_ir::{Circuit, QubitId};
+/// use std::num::NonZeroUsize;
+///
+/// let builder = PassManagerBuilder::new()
+///     .with_target(CouplingMap::linear(3), BasisGates::iqm());
+/// let key = CacheKey::new(0x1234, builder.fingerprint(), "calibration-2026-10-15");
+/// let (pm, mut props) = builder.build();
+///
+/// let cache = CompileCache::new(NonZeroUsize::new(128).unwrap());
+/// let mut circuit = Circuit::with_size("bell", 2, 0);
+/// circuit.h(QubitId(0)).unwrap();
+/// circuit.cx(QubitId(0), QubitId(1)).unwrap();
+///
+/// let first = cache.get_or_compile(&key, &pm, &mut props, circuit.dag().clone()).unwrap();
+/// let again = cache.get_or_compile(&key, &pm, &mut props, circuit.into_dag()).unwrap();
+/// assert_eq!(first.num_ops(), again.num_ops());
+/// assert_eq!(cache.stats().memory_hits, 1);
+/// ```
+pub struct CompileCache {
+    capacity: NonZeroUsize,
+    dir: Option<PathBuf>,
+    lru: Mutex<Lru>,
+}
+
+impl CompileCache {
+    /// Create an in-memory cache holding at most `capacity` circuits.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            capacity,
+            dir: None,
+            lru: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Also store entries in `dir`, which is created if missing.
+    #[must_use]
+    pub fn with_disk(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    /// Get the on-disk store directory, if any.
+    pub fn disk_dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    /// Look up a compiled circuit.
+    pub fn get(&self, key: &CacheKey) -> Option<CircuitDag> {
+        let mut lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        lru.clock += 1;
+        let now = lru.clock;
+        if let Some(entry) = lru.entries.get_mut(key) {
+            entry.last_used = now;
+            let dag = entry.dag.clone();
+            lru.stats.memory_hits += 1;
+            return Some(dag);
+        }
+
+        match self.load(key) {
+            Some(dag) => {
+                lru.stats.disk_hits += 1;
+                self.remember(&mut lru, key.clone(), dag.clone());
+                Some(dag)
+            }
+            None => {
+                lru.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store a compiled circuit.
+    pub fn insert(&self, key: CacheKey, dag: &CircuitDag) {
+        self.store(&key, dag);
+        let mut lru = self.lru.lock().unwrap_or_else(|e| e.into_inner());
+        lru.clock += 1;
+        self.remember(&mut lru, key, dag.clone());
+    }
+
+    /// Return the cached compilation of `dag`, compiling and storing it on a miss.
+    pub fn get_or_compile(
+        &self,
+        key: &CacheKey,
+        pm: &PassManager,
+        properties: &mut PropertySet,
+        mut dag: CircuitDag,
+    ) -> CompileResult<CircuitDag> {
+        if let Some(cached) = self.get(key) {
+            debug!("Compile cache hit for circuit {:016x}", key.circuit);
+            return Ok(cached);
+        }
+        pm.run(&mut dag, properties)?;
+        self.insert(key.clone(), &dag);
+        Ok(dag)
+    }
+
+    /// Get the hit and miss counters.
+    pub fn stats(&self) -> CacheStats {
+        self.lru.lock().unwrap_or_else(|e| e.into_inner()).stats
+    }
+
+    /// Get the number of circuits held in memory.
+    pub fn len(&self) -> usize {
+        self.lru
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entries
+            .len()
+    }
+
+    /// Check if no circuits are held in memory.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn remember(&self, lru: &mut Lru, key: CacheKey, dag: CircuitDag) {
+        let last_used = lru.clock;
+        lru.entries.insert(key, Entry { dag, last_used });
+        while lru.entries.len() > self.capacity.get() {
+            let oldest = lru
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+                .expect("cache is over capacity");
+            lru.entries.remove(&oldest);
+            lru.stats.evictions += 1;
+        }
+    }
+
+    fn load(&self, key: &CacheKey) -> Option<CircuitDag> {
+        let path = self.dir.as_ref()?.join(key.file_name());
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!(
+                    "Failed to read compile cache entry {}: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+        let stored: StoredCircuit = match serde_json::from_slice(&bytes) {
+            Ok(stored) => stored,
+            Err(e) => {
+                warn!(
+                    "Ignoring corrupt compile cache entry {}: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+        // The file name only hashes the target version, so check the full key.
+        if &stored.key != key {
+            return None;
+        }
+        match stored.into_dag() {
+            Ok(dag) => Some(dag),
+            Err(e) => {
+                warn!(
+                    "Ignoring invalid compile cache entry {}: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    fn store(&self, key: &CacheKey, dag: &CircuitDag) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let path = dir.join(key.file_name());
+        let write = || -> std::io::Result<()> {
+            std::fs::create_dir_all(dir)?;
+            let bytes = serde_json::to_vec(&StoredCircuit::capture(key, dag))?;
+            // Write then rename so readers never see a partial entry.
+            let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+            std::fs::write(&tmp, bytes)?;
+            std::fs::rename(&tmp, &path)
+        };
+        if let Err(e) = write() {
+            warn!(
+                "Failed to write compile cache entry {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+impl std::fmt::Debug for CompileCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompileCache")
+            .field("capacity", &self.capacity)
+            .field("dir", &self.dir)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PassManagerBuilder;
+    use crate::property::{BasisGates, CouplingMap};
+    use arvak_ir::Circuit;
+
+    fn bell() -> CircuitDag {
+        let mut circuit = Circuit::with_size("bell", 3, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(2)).unwrap();
+        circuit.into_dag()
+    }
+
+    /// Operations on each qubit in order; independent of how a topological
+    /// sort interleaves operations on different qubits.
+    fn wire_ops(dag: &CircuitDag) -> Vec<(u32, Vec<Instruction>)> {
+        let mut wires: Vec<(u32, Vec<Instruction>)> =
+            dag.qubits().map(|q| (q.0, Vec::new())).collect();
+        wires.sort_by_key(|(q, _)| *q);
+        for (_, inst) in dag.topological_ops() {
+            for (q, ops) in &mut wires {
+                if inst.qubits.contains(&QubitId(*q)) {
+                    ops.push(inst.clone());
+                }
+            }
+        }
+        wires
+    }
+
+    fn builder() -> PassManagerBuilder {
+        PassManagerBuilder::new()
+            .with_optimization_level(0)
+            .with_target(CouplingMap::linear(3), BasisGates::iqm())
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = CompileCache::new(NonZeroUsize::new(2).unwrap());
+        let key = |n| CacheKey::new(n, 0, "v1");
+
+        cache.insert(key(1), &bell());
+        cache.insert(key(2), &bell());
+        assert!(cache.get(&key(1)).is_some());
+        // Key 2 is now the least recently used.
+        cache.insert(key(3), &bell());
+
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                memory_hits: 3,
+                disk_hits: 0,
+                misses: 1,
+                evictions: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_disk_store_shared() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = CacheKey::new(7, builder().fingerprint(), "cal-1");
+        let (pm, mut props) = builder().build();
+
+        let first = CompileCache::new(NonZeroUsize::new(4).unwrap()).with_disk(dir.path());
+        let compiled = first.get_or_compile(&key, &pm, &mut props, bell()).unwrap();
+        assert_eq!(first.stats().misses, 1);
+
+        // A second cache on the same directory, e.g. after a restart.
+        let second = CompileCache::new(NonZeroUsize::new(4).unwrap()).with_disk(dir.path());
+        let loaded = second.get(&key).unwrap();
+        assert_eq!(wire_ops(&loaded), wire_ops(&compiled));
+        assert_eq!(loaded.num_qubits(), compiled.num_qubits());
+        assert_eq!(loaded.global_phase(), compiled.global_phase());
+        assert_eq!(second.stats().disk_hits, 1);
+
+        // A new calibration misses.
+        assert!(
+            second
+                .get(&CacheKey::new(7, key.pipeline, "cal-2"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_corrupt_disk_entry_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = CacheKey::new(1, 2, "v");
+        std::fs::write(dir.path().join(key.file_name()), b"not json").unwrap();
+
+        let cache = CompileCache::new(NonZeroUsize::new(1).unwrap()).with_disk(dir.path());
+        assert!(cache.get(&key).is_none());
+        assert_eq!(cache.stats().misses, 1);
+    }
+}
@@ -49,6 +49,22 @@ pub enum CompileError {
         qubit: u32,
         detail: String,
     },
+
+    /// A mid-circuit measurement could not be moved to the end of the circuit.
+    #[error("Cannot defer measurement of qubit {qubit} into clbit {clbit}: {reason}")]
+    MeasurementDeferral {
+        qubit: u32,
+        clbit: u32,
+        reason: String,
+    },
+
+    /// A measurement could not be moved into a separate execution.
+    #[error("Cannot split measurement of qubit {qubit} into clbit {clbit}: {reason}")]
+    MeasurementSplit {
+        qubit: u32,
+        clbit: u32,
+        reason: String,
+    },
 }
 
 /// Result type for compilation operations.
@@ -61,9 +61,30 @@
 //! |-------|-----------------|
 //! | 0 | Layout + Routing only |
 //! | 1 | + Basis translation |
-//! | 2 | + CX cancellation, 1q optimization |
+//! | 2 | + SWAP absorption, CX cancellation, 1q optimization |
 //! | 3 | + Commutative cancellation, aggressive optimization |
 //!
+//! # Pipeline Presets
+//!
+//! [`PipelinePreset`]s name tuned pipelines per backend family and are
+//! selected by string:
+//!
+//! | Preset | Level | Commutation routing |
+//! |--------|-------|---------------------|
+//! | `superconducting-heavyhex` | 2 | yes |
+//! | `iontrap-alltoall` | 3 | no |
+//! | `simulator-fast` | 0 | no |
+//!
+//! ```rust
+//! use arvak_compile::{BasisGates, CouplingMap, PipelinePreset};
+//!
+//! let preset: PipelinePreset = "superconducting-heavyhex".parse().unwrap();
+//! let (pm, props) = preset
+//!     .builder()
+//!     .with_target(CouplingMap::linear(5), BasisGates::ibm())
+//!     .build();
+//! ```
+//!
 //! # Built-in Passes
 //!
 //! ## Layout Passes
@@ -71,7 +92,20 @@
 //! - [`passes::DenseLayout`]: Pack qubits into well-connected region
 //!
 //! ## Routing Passes
-//! - [`passes::BasicRouting`]: Greedy SWAP insertion for connectivity
+//! - [`passes::BasicRouting`]: Greedy SWAP insertion for connectivity, running
+//!   commuting gates ahead of blocked ones; with [`passes::RoutingCost::GateError`]
+//!   it takes the path of lowest two-qubit gate error instead of the shortest
+//! - [`passes::SwapAbsorption`]: Merge routing SWAPs into adjacent CX gates
+//!
+//! ## Synthesis Passes
+//! - [`passes::HighLevelSynthesis`]: Lower Toffoli, Fredkin and multi-controlled
+//!   gates (V-chain with free ancillas, recursive otherwise)
+//!
+//! ## Measurement Passes
+//! - [`passes::MeasurementDeferral`]: Move measurements to the end for targets
+//!   without mid-circuit measurement
+//! - [`passes::MeasurementSplit`]: Plan spreading measurements over several
+//!   executions for targets with limited classical memory
 //!
 //! ## Translation Passes
 //! - [`passes::BasisTranslation`]: Convert to target gate set (IQM: PRX+CZ, IBM: SX+RZ+CX)
@@ -102,16 +136,23 @@
 //! }
 //! ```
 
+pub mod cache;
 pub mod error;
 pub mod manager;
 pub mod pass;
+pub mod preset;
 pub mod property;
 pub mod unitary;
 
+#[cfg(test)]
+mod testing;
+
 // Built-in passes
 pub mod passes;
 
+pub use cache::{CacheKey, CacheStats, CompileCache};
 pub use error::{CompileError, CompileResult};
 pub use manager::{PassManager, PassManagerBuilder};
 pub use pass::{AnalysisPass, Pass, PassKind, TransformationPass};
-pub use property::{BasisGates, CouplingMap, Layout, PropertySet};
+pub use preset::PipelinePreset;
+pub use property::{BasisGates, CouplingMap, DEFAULT_SEED, ErrorRates, Layout, PropertySet};
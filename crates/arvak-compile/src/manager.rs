@@ -3,24 +3,47 @@
 use tracing::{debug, info, instrument};
 
 use arvak_ir::CircuitDag;
+use arvak_ir::hash::fnv1a;
 
 use crate::error::CompileResult;
 use crate::pass::Pass;
 use crate::passes::{
-    BasicRouting, BasisTranslation, MeasurementBarrierVerification, Optimize1qGates, TrivialLayout,
+    BasicRouting, BasisTranslation, HighLevelSynthesis, MeasurementBarrierVerification,
+    MeasurementDeferral, MeasurementSplit, Optimize1qGates, RoutingCost, SwapAbsorption,
+    TrivialLayout,
 };
-use crate::property::{BasisGates, CouplingMap, PropertySet};
+use crate::preset::PipelinePreset;
+use crate::property::{BasisGates, CouplingMap, DEFAULT_SEED, ErrorRates, Layout, PropertySet};
 
 /// Manages and executes a sequence of compilation passes.
+///
+/// Compilation is deterministic: stochastic passes seed their randomness from
+/// [`PropertySet::pass_seed`], so the same circuit, passes and seed always
+/// produce bit-identical output.
 pub struct PassManager {
     /// The passes to execute, in order.
     passes: Vec<Box<dyn Pass>>,
+    /// Seed for stochastic passes, if set explicitly.
+    seed: Option<u64>,
 }
 
 impl PassManager {
     /// Create a new empty pass manager.
     pub fn new() -> Self {
-        Self { passes: vec![] }
+        Self {
+            passes: vec![],
+            seed: None,
+        }
+    }
+
+    /// Set the seed for stochastic passes.
+    ///
+    /// Without an explicit seed, a seed already present in the property set
+    /// is kept, and [`DEFAULT_SEED`] is used otherwise.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
     }
 
     /// Add a pass to the manager.
@@ -37,6 +60,12 @@ impl PassManager {
             dag.num_qubits()
         );
 
+        if let Some(seed) = self.seed {
+            properties.seed = Some(seed);
+        }
+        let seed = *properties.seed.get_or_insert(DEFAULT_SEED);
+        debug!("Compilation seed: {}", seed);
+
         for pass in &self.passes {
             if pass.should_run(dag, properties) {
                 debug!("Running pass: {}", pass.name());
@@ -83,6 +112,12 @@ pub struct PassManagerBuilder {
     optimization_level: u8,
     /// Target properties.
     properties: PropertySet,
+    /// Let routing reorder commuting gates.
+    commutation_routing: bool,
+    /// What routing paths minimize.
+    routing_cost: RoutingCost,
+    /// Seed for stochastic passes.
+    seed: Option<u64>,
 }
 
 impl PassManagerBuilder {
@@ -91,6 +126,9 @@ impl PassManagerBuilder {
         Self {
             optimization_level: 1,
             properties: PropertySet::new(),
+            commutation_routing: true,
+            routing_cost: RoutingCost::SwapCount,
+            seed: None,
         }
     }
 
@@ -106,6 +144,14 @@ impl PassManagerBuilder {
         self
     }
 
+    /// Use the optimization level and routing options of a preset.
+    #[must_use]
+    pub fn with_preset(mut self, preset: PipelinePreset) -> Self {
+        self.optimization_level = preset.optimization_level();
+        self.commutation_routing = preset.commutation_routing();
+        self
+    }
+
     /// Set the target properties.
     #[must_use]
     pub fn with_properties(mut self, properties: PropertySet) -> Self {
@@ -121,9 +167,110 @@ impl PassManagerBuilder {
         self
     }
 
+    /// Start from the given layout instead of the trivial one.
+    #[must_use]
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.properties.layout = Some(layout);
+        self
+    }
+
+    /// Enable or disable commutation-aware reordering during routing.
+    ///
+    /// Enabled by default; see [`BasicRouting::with_commutation`].
+    #[must_use]
+    pub fn with_commutation_routing(mut self, enabled: bool) -> Self {
+        self.commutation_routing = enabled;
+        self
+    }
+
+    /// Choose what routing minimizes; see [`RoutingCost`].
+    #[must_use]
+    pub fn with_routing_cost(mut self, cost: RoutingCost) -> Self {
+        self.routing_cost = cost;
+        self
+    }
+
+    /// Set the target's error rates, used by [`RoutingCost::GateError`].
+    #[must_use]
+    pub fn with_error_rates(mut self, error_rates: ErrorRates) -> Self {
+        self.properties.error_rates = Some(error_rates);
+        self
+    }
+
+    /// Limit the number of clbits measured in one execution; see
+    /// [`MeasurementSplit`].
+    #[must_use]
+    pub fn with_max_clbits(mut self, max_clbits: usize) -> Self {
+        self.properties.max_clbits = Some(max_clbits);
+        self
+    }
+
+    /// Set the seed for stochastic passes; see [`PassManager::with_seed`].
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Fingerprint of the pipeline this builder produces.
+    ///
+    /// Covers the compiler version, optimization level, routing options,
+    /// seed and target properties, so two builders with the same fingerprint
+    /// compile any circuit identically. Used in [`CacheKey`](crate::CacheKey).
+    pub fn fingerprint(&self) -> u64 {
+        let props = &self.properties;
+        let mut config = format!(
+            "{};{};{};{:?};{}",
+            env!("CARGO_PKG_VERSION"),
+            self.optimization_level,
+            self.commutation_routing,
+            self.seed,
+            props.final_measurements_only
+        );
+        if let Some(max_clbits) = props.max_clbits {
+            config.push_str(&format!(";clbits:{}", max_clbits));
+        }
+        if self.routing_cost != RoutingCost::SwapCount {
+            config.push_str(&format!(";cost:{:?}", self.routing_cost));
+            if let Some(error_rates) = &props.error_rates {
+                config.push_str(&format!(";errors:{}", error_rates.fingerprint()));
+            }
+        }
+        if let Some(layout) = &props.layout {
+            let mut mapping: Vec<_> = layout.iter().map(|(q, p)| (q.0, p)).collect();
+            mapping.sort_unstable();
+            config.push_str(&format!(";layout:{:?}", mapping));
+        }
+        if let Some(cm) = &props.coupling_map {
+            let mut edges: Vec<_> = cm
+                .edges()
+                .iter()
+                .map(|&(a, b)| (a.min(b), a.max(b)))
+                .collect();
+            edges.sort_unstable();
+            edges.dedup();
+            config.push_str(&format!(";{}:{:?}", cm.num_qubits(), edges));
+        }
+        if let Some(basis) = &props.basis_gates {
+            let mut gates = basis.gates().to_vec();
+            gates.sort();
+            config.push_str(&format!(";{:?}", gates));
+        }
+        fnv1a(config.as_bytes())
+    }
+
     /// Build the pass manager and return it with the properties.
     pub fn build(self) -> (PassManager, PropertySet) {
         let mut pm = PassManager::new();
+        pm.seed = self.seed;
+
+        // Lower multi-controlled gates before anything needs 1q/2q gates
+        pm.add_pass(HighLevelSynthesis::new());
+
+        // Defer measurements first so ancillas are laid out and routed
+        if self.properties.final_measurements_only {
+            pm.add_pass(MeasurementDeferral::new());
+        }
 
         // Always add layout pass if we have a coupling map
         if self.properties.coupling_map.is_some() {
@@ -132,7 +279,16 @@ impl PassManagerBuilder {
 
         // Add routing if we have a coupling map
         if self.properties.coupling_map.is_some() {
-            pm.add_pass(BasicRouting);
+            pm.add_pass(
+                BasicRouting::new()
+                    .with_commutation(self.commutation_routing)
+                    .with_cost(self.routing_cost),
+            );
+        }
+
+        // Merge routing SWAPs into neighbouring CX before translation
+        if self.properties.coupling_map.is_some() && self.optimization_level >= 2 {
+            pm.add_pass(SwapAbsorption);
         }
 
         // Add basis translation if we have basis gates
@@ -143,6 +299,11 @@ impl PassManagerBuilder {
         // Add optimization passes based on level
         if self.optimization_level >= 1 {
             pm.add_pass(Optimize1qGates::new());
+
+            // Merged runs come out as RZ·RY·RZ; lower them to the basis again
+            if self.properties.basis_gates.is_some() {
+                pm.add_pass(BasisTranslation);
+            }
         }
 
         // Always add measurement barrier verification as the final pass
@@ -151,6 +312,11 @@ impl PassManagerBuilder {
             pm.add_pass(MeasurementBarrierVerification);
         }
 
+        // Plan splitting of oversized measurement sets on the final circuit
+        if self.properties.max_clbits.is_some() {
+            pm.add_pass(MeasurementSplit);
+        }
+
         (pm, self.properties)
     }
 }
@@ -164,7 +330,7 @@ impl Default for PassManagerBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arvak_ir::{Circuit, QubitId};
+    use arvak_ir::{Circuit, QubitId, StandardGate};
 
     #[test]
     fn test_empty_pass_manager() {
@@ -199,4 +365,113 @@ mod tests {
         assert!(props.coupling_map.is_some());
         assert!(props.basis_gates.is_some());
     }
+
+    #[test]
+    fn test_seeded_compilation_is_reproducible() {
+        let mut circuit = Circuit::with_size("test", 4, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(3)).unwrap();
+        circuit.cx(QubitId(2), QubitId(3)).unwrap();
+        circuit.ccx(QubitId(0), QubitId(1), QubitId(2)).unwrap();
+
+        let compile = || {
+            let (pm, mut props) = PassManagerBuilder::new()
+                .with_optimization_level(0)
+                .with_target(CouplingMap::linear(5), BasisGates::iqm())
+                .with_seed(42)
+                .build();
+            let mut dag = circuit.clone().into_dag();
+            pm.run(&mut dag, &mut props).unwrap();
+            let ops: Vec<_> = dag.topological_ops().map(|(_, i)| i.clone()).collect();
+            (ops, dag.global_phase().to_bits(), props.seed)
+        };
+
+        let first = compile();
+        assert_eq!(first.2, Some(42));
+        assert_eq!(first, compile());
+    }
+
+    #[test]
+    fn test_default_seed_recorded() {
+        let mut props = PropertySet::new();
+        let mut dag = Circuit::with_size("test", 1, 0).into_dag();
+        PassManager::new().run(&mut dag, &mut props).unwrap();
+        assert_eq!(props.seed, Some(DEFAULT_SEED));
+
+        // An explicit seed overrides the one in the property set.
+        PassManager::new()
+            .with_seed(5)
+            .run(&mut dag, &mut props)
+            .unwrap();
+        assert_eq!(props.seed, Some(5));
+    }
+
+    #[test]
+    fn test_builder_fingerprint() {
+        let base =
+            || PassManagerBuilder::new().with_target(CouplingMap::linear(5), BasisGates::iqm());
+        assert_eq!(base().fingerprint(), base().fingerprint());
+        assert_ne!(
+            base().fingerprint(),
+            base().with_optimization_level(3).fingerprint()
+        );
+        assert_ne!(base().fingerprint(), base().with_seed(1).fingerprint());
+        assert_ne!(
+            base().fingerprint(),
+            PassManagerBuilder::new()
+                .with_target(CouplingMap::star(5), BasisGates::iqm())
+                .fingerprint()
+        );
+
+        // Error rates only matter to routing that weighs them
+        let errors = || ErrorRates::new().with_two_qubit_error(0, 1, 0.02);
+        assert_eq!(
+            base().fingerprint(),
+            base().with_error_rates(errors()).fingerprint()
+        );
+        let fidelity = || base().with_routing_cost(RoutingCost::GateError);
+        assert_ne!(base().fingerprint(), fidelity().fingerprint());
+        assert_ne!(
+            fidelity().fingerprint(),
+            fidelity().with_error_rates(errors()).fingerprint()
+        );
+    }
+
+    /// Gates BasisTranslation handles for both the IQM and IBM targets.
+    /// Every gate basis translation handles directly.
+    fn fuzz_gates() -> Vec<StandardGate> {
+        arvak_ir::random::standard_gates()
+            .into_iter()
+            .filter(|g| g.num_qubits() <= 2)
+            .collect()
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn fuzz_compilation_preserves_semantics(
+            circuit in arvak_ir::strategy::circuit_with_gates(fuzz_gates(), 4, 6),
+            level in 0u8..=3,
+            iqm in proptest::bool::ANY,
+        ) {
+            let (coupling_map, basis) = if iqm {
+                (CouplingMap::star(5), BasisGates::iqm())
+            } else {
+                (CouplingMap::linear(5), BasisGates::ibm())
+            };
+            let (pm, mut props) = PassManagerBuilder::new()
+                .with_optimization_level(level)
+                .with_target(coupling_map, basis)
+                .build();
+
+            let mut dag = circuit.clone().into_dag();
+            pm.run(&mut dag, &mut props).unwrap();
+            crate::testing::assert_equivalent(&circuit, &dag, props.layout.as_ref().unwrap());
+            let basis = props.basis_gates.as_ref().unwrap();
+            for (_, inst) in dag.topological_ops() {
+                proptest::prop_assert!(basis.contains(inst.name()), "{}", inst.name());
+            }
+        }
+    }
 }
@@ -5,7 +5,9 @@
 //! to run on any circuit regardless of the target hardware.
 
 pub mod optimization;
+pub mod synthesis;
 pub mod verification;
 
 pub use optimization::{CancelCX, CommutativeCancellation, OneQubitBasis, Optimize1qGates};
+pub use synthesis::{HighLevelSynthesis, SynthesisResult};
 pub use verification::{MeasurementBarrierVerification, VerificationResult};
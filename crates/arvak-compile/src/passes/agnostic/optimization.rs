@@ -60,7 +60,7 @@ impl Optimize1qGates {
     }
 
     /// Get the unitary matrix for a single-qubit gate.
-    fn gate_to_unitary(gate: &StandardGate) -> Option<Unitary2x2> {
+    pub(crate) fn gate_to_unitary(gate: &StandardGate) -> Option<Unitary2x2> {
         match gate {
             StandardGate::I => Some(Unitary2x2::identity()),
             StandardGate::X => Some(Unitary2x2::x()),
@@ -267,7 +267,8 @@ impl Pass for Optimize1qGates {
                     if let InstructionKind::Gate(gate) = &inst.kind {
                         if let GateKind::Standard(std_gate) = &gate.kind {
                             if let Some(u) = Self::gate_to_unitary(std_gate) {
-                                combined = combined * u;
+                                // Later gates multiply from the left.
+                                combined = u * combined;
                             }
                         }
                     }
@@ -302,17 +303,9 @@ impl Pass for Optimize1qGates {
                 for &node_idx in remove.iter() {
                     let _ = dag.remove_op(node_idx);
                 }
-            } else {
-                // Need more nodes than we have - just update what we can
-                // This shouldn't happen with ZYZ decomposition (max 3 gates)
-                for (i, &node_idx) in nodes.iter().enumerate() {
-                    if i < num_new {
-                        if let Some(inst) = dag.get_instruction_mut(node_idx) {
-                            *inst = Instruction::single_qubit_gate(new_gates[i].clone(), qubit);
-                        }
-                    }
-                }
             }
+            // Otherwise the decomposition is longer than the run (e.g. two
+            // gates needing three rotations); leave the run as it is.
         }
 
         Ok(())
@@ -711,6 +704,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_optimize_1q_keeps_delays() {
+        // A dynamical decoupling pair must not be merged across the delay.
+        let mut circuit = Circuit::with_size("test", 1, 0);
+        circuit.x(QubitId(0)).unwrap();
+        circuit.delay(QubitId(0), 160).unwrap();
+        circuit.x(QubitId(0)).unwrap();
+        let mut dag = circuit.into_dag();
+
+        let mut props = PropertySet::new();
+        Optimize1qGates::new().run(&mut dag, &mut props).unwrap();
+        CommutativeCancellation::new()
+            .run(&mut dag, &mut props)
+            .unwrap();
+
+        let names: Vec<_> = dag.topological_ops().map(|(_, i)| i.name()).collect();
+        assert_eq!(names, vec!["x", "delay", "x"]);
+    }
+
     #[test]
     fn test_optimize_1q_reduces_count() {
         let mut circuit = Circuit::with_size("test", 1, 0);
@@ -0,0 +1,495 @@
+//! High-level synthesis of multi-controlled gates.
+//!
+//! Basis translation only handles one- and two-qubit gates. This pass lowers
+//! Toffoli, CCZ, Fredkin and multi-controlled gates (`mcx`, `mcz`, `mcp`) to H, RZ
+//! and CX, which every supported basis can translate.
+
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use arvak_ir::{CircuitDag, GateKind, Instruction, InstructionKind, QubitId, StandardGate};
+
+use crate::error::{CompileError, CompileResult};
+use crate::pass::{Pass, PassKind};
+use crate::passes::rebuild_dag;
+use crate::property::PropertySet;
+
+/// Summary of a high-level synthesis run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SynthesisResult {
+    /// High-level gates lowered.
+    pub gates_synthesized: usize,
+    /// Multi-controlled gates lowered with a V-chain of ancillas.
+    pub v_chain: usize,
+    /// Multi-controlled gates lowered recursively without ancillas.
+    pub recursive: usize,
+    /// Ancilla qubits added to the circuit.
+    pub ancillas_added: Vec<QubitId>,
+}
+
+/// Lowers multi-controlled and other high-level gates.
+///
+/// A multi-controlled X with `k > 2` controls uses a V-chain of `2(k-2)+1`
+/// Toffolis when `k-2` clean ancillas are available, and the recursive
+/// controlled-phase construction (no ancillas, exponentially more gates)
+/// otherwise. Ancillas are qubits the target has beyond those the circuit
+/// uses, further limited by [`with_max_ancillas`]; without a coupling map no
+/// ancillas are assumed. Ancillas are returned to |0⟩ and shared between
+/// gates.
+///
+/// Single-qubit phase gates are emitted as RZ; the difference is a global
+/// phase, which is added to the circuit's.
+///
+/// [`with_max_ancillas`]: HighLevelSynthesis::with_max_ancillas
+#[derive(Debug, Clone, Default)]
+pub struct HighLevelSynthesis {
+    max_ancillas: Option<usize>,
+}
+
+impl HighLevelSynthesis {
+    /// Create the pass with no ancilla limit beyond the target size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of ancilla qubits the pass may add.
+    #[must_use]
+    pub fn with_max_ancillas(mut self, max_ancillas: usize) -> Self {
+        self.max_ancillas = Some(max_ancillas);
+        self
+    }
+
+    fn ancilla_budget(&self, num_qubits: usize, properties: &PropertySet) -> usize {
+        let target_free = properties.coupling_map.as_ref().map_or(0, |cm| {
+            (cm.num_qubits() as usize).saturating_sub(num_qubits)
+        });
+        self.max_ancillas
+            .map_or(target_free, |m| m.min(target_free))
+    }
+}
+
+/// A gate the pass lowers.
+enum HighLevel {
+    /// Multi-controlled X.
+    Mcx(Vec<QubitId>, QubitId),
+    /// Multi-controlled Z.
+    Mcz(Vec<QubitId>, QubitId),
+    /// Multi-controlled phase.
+    Mcp(f64, Vec<QubitId>, QubitId),
+    /// Controlled SWAP.
+    CSwap(QubitId, QubitId, QubitId),
+}
+
+impl HighLevel {
+    fn of(inst: &Instruction) -> CompileResult<Option<Self>> {
+        let InstructionKind::Gate(gate) = &inst.kind else {
+            return Ok(None);
+        };
+        let q = &inst.qubits;
+        let split = || {
+            let (target, controls) = q.split_last().expect("gate has qubits");
+            (controls.to_vec(), *target)
+        };
+
+        Ok(match &gate.kind {
+            GateKind::Standard(StandardGate::CCX) => Some(HighLevel::Mcx(vec![q[0], q[1]], q[2])),
+            GateKind::Standard(StandardGate::CCZ) => Some(HighLevel::Mcz(vec![q[0], q[1]], q[2])),
+            GateKind::Standard(StandardGate::CSwap) => Some(HighLevel::CSwap(q[0], q[1], q[2])),
+            GateKind::Custom(custom) if !q.is_empty() => match custom.name.as_str() {
+                "mcx" => {
+                    let (controls, target) = split();
+                    Some(HighLevel::Mcx(controls, target))
+                }
+                "mcz" => {
+                    let (controls, target) = split();
+                    Some(HighLevel::Mcz(controls, target))
+                }
+                "mcp" => {
+                    let lambda =
+                        custom
+                            .params
+                            .first()
+                            .and_then(|p| p.as_f64())
+                            .ok_or_else(|| CompileError::PassFailed {
+                                name: "HighLevelSynthesis".into(),
+                                reason: "mcp requires a bound angle parameter".into(),
+                            })?;
+                    let (controls, target) = split();
+                    Some(HighLevel::Mcp(lambda, controls, target))
+                }
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    fn num_controls(&self) -> usize {
+        match self {
+            HighLevel::Mcx(c, _) | HighLevel::Mcz(c, _) | HighLevel::Mcp(_, c, _) => c.len(),
+            HighLevel::CSwap(..) => 1,
+        }
+    }
+}
+
+/// Emits the H/RZ/CX sequence for high-level gates.
+#[derive(Default)]
+struct Synthesizer {
+    ops: Vec<Instruction>,
+    phase: f64,
+}
+
+impl Synthesizer {
+    fn h(&mut self, q: QubitId) {
+        self.ops
+            .push(Instruction::single_qubit_gate(StandardGate::H, q));
+    }
+
+    fn cx(&mut self, c: QubitId, t: QubitId) {
+        self.ops
+            .push(Instruction::two_qubit_gate(StandardGate::CX, c, t));
+    }
+
+    /// P(λ) = e^{iλ/2} RZ(λ).
+    fn p(&mut self, lambda: f64, q: QubitId) {
+        self.ops.push(Instruction::single_qubit_gate(
+            StandardGate::Rz(lambda.into()),
+            q,
+        ));
+        self.phase += lambda / 2.0;
+    }
+
+    fn cp(&mut self, lambda: f64, c: QubitId, t: QubitId) {
+        self.p(lambda / 2.0, c);
+        self.cx(c, t);
+        self.p(-lambda / 2.0, t);
+        self.cx(c, t);
+        self.p(lambda / 2.0, t);
+    }
+
+    /// Toffoli with six CX.
+    fn ccx(&mut self, a: QubitId, b: QubitId, t: QubitId) {
+        let quarter = PI / 4.0;
+        self.h(t);
+        self.cx(b, t);
+        self.p(-quarter, t);
+        self.cx(a, t);
+        self.p(quarter, t);
+        self.cx(b, t);
+        self.p(-quarter, t);
+        self.cx(a, t);
+        self.p(quarter, b);
+        self.p(quarter, t);
+        self.h(t);
+        self.cx(a, b);
+        self.p(quarter, a);
+        self.p(-quarter, b);
+        self.cx(a, b);
+    }
+
+    fn mcx(&mut self, controls: &[QubitId], target: QubitId, ancillas: &[QubitId]) {
+        match controls {
+            [] => {
+                self.h(target);
+                self.p(PI, target);
+                self.h(target);
+            }
+            [c] => self.cx(*c, target),
+            [a, b] => self.ccx(*a, *b, target),
+            _ if ancillas.len() >= controls.len() - 2 => self.v_chain(controls, target, ancillas),
+            _ => {
+                self.h(target);
+                self.mcp(PI, controls, target, ancillas);
+                self.h(target);
+            }
+        }
+    }
+
+    /// Compute the AND of the controls into ancillas, flip the target, and
+    /// uncompute.
+    fn v_chain(&mut self, controls: &[QubitId], target: QubitId, ancillas: &[QubitId]) {
+        let k = controls.len();
+        let mut compute = vec![(controls[0], controls[1], ancillas[0])];
+        for i in 2..k - 1 {
+            compute.push((controls[i], ancillas[i - 2], ancillas[i - 1]));
+        }
+
+        for &(a, b, t) in &compute {
+            self.ccx(a, b, t);
+        }
+        self.ccx(controls[k - 1], ancillas[k - 3], target);
+        for &(a, b, t) in compute.iter().rev() {
+            self.ccx(a, b, t);
+        }
+    }
+
+    /// C^k P(λ) = CP(λ/2)[c_k,t] · C^{k-1}X[c_k] · CP(-λ/2)[c_k,t] ·
+    /// C^{k-1}X[c_k] · C^{k-1}P(λ/2)[t]
+    fn mcp(&mut self, lambda: f64, controls: &[QubitId], target: QubitId, ancillas: &[QubitId]) {
+        match controls {
+            [] => self.p(lambda, target),
+            [c] => self.cp(lambda, *c, target),
+            _ => {
+                let (last, rest) = controls.split_last().expect("at least two controls");
+                self.cp(lambda / 2.0, *last, target);
+                self.mcx(rest, *last, ancillas);
+                self.cp(-lambda / 2.0, *last, target);
+                self.mcx(rest, *last, ancillas);
+                self.mcp(lambda / 2.0, rest, target, ancillas);
+            }
+        }
+    }
+
+    fn synthesize(&mut self, gate: &HighLevel, ancillas: &[QubitId]) {
+        match gate {
+            HighLevel::Mcx(controls, target) => self.mcx(controls, *target, ancillas),
+            HighLevel::Mcz(controls, target) => {
+                if controls.len() > 2 && ancillas.len() >= controls.len() - 2 {
+                    self.h(*target);
+                    self.mcx(controls, *target, ancillas);
+                    self.h(*target);
+                } else {
+                    self.mcp(PI, controls, *target, ancillas);
+                }
+            }
+            HighLevel::Mcp(lambda, controls, target) => {
+                self.mcp(*lambda, controls, *target, ancillas)
+            }
+            HighLevel::CSwap(c, a, b) => {
+                self.cx(*b, *a);
+                self.ccx(*c, *a, *b);
+                self.cx(*b, *a);
+            }
+        }
+    }
+}
+
+impl Pass for HighLevelSynthesis {
+    fn name(&self) -> &str {
+        "HighLevelSynthesis"
+    }
+
+    fn kind(&self) -> PassKind {
+        PassKind::Transformation
+    }
+
+    fn run(&self, dag: &mut CircuitDag, properties: &mut PropertySet) -> CompileResult<()> {
+        let ops: Vec<Instruction> = dag.topological_ops().map(|(_, i)| i.clone()).collect();
+        let qubits: Vec<QubitId> = dag.qubits().collect();
+        let budget = self.ancilla_budget(qubits.len(), properties);
+
+        let mut lowered = Vec::with_capacity(ops.len());
+        for inst in ops {
+            let high_level = HighLevel::of(&inst)?;
+            lowered.push((inst, high_level));
+        }
+
+        let mut result = SynthesisResult::default();
+        let needed = lowered
+            .iter()
+            .filter_map(|(_, h)| h.as_ref())
+            .map(|h| h.num_controls().saturating_sub(2))
+            .max();
+        let Some(needed) = needed else {
+            properties.insert(result);
+            return Ok(());
+        };
+
+        // Ancillas are only worth adding if every V-chain fits.
+        let next_id = qubits.iter().map(|q| q.0 + 1).max().unwrap_or(0);
+        let ancillas: Vec<QubitId> = if needed > 0 && needed <= budget {
+            (next_id..next_id + needed as u32).map(QubitId).collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut synth = Synthesizer::default();
+        for (inst, high_level) in lowered {
+            let Some(gate) = high_level else {
+                synth.ops.push(inst);
+                continue;
+            };
+            synth.synthesize(&gate, &ancillas);
+            result.gates_synthesized += 1;
+            if gate.num_controls() > 2 {
+                if ancillas.is_empty() {
+                    result.recursive += 1;
+                } else {
+                    result.v_chain += 1;
+                }
+            }
+        }
+
+        let phase = dag.global_phase() + synth.phase;
+        let mut new_dag = rebuild_dag(dag, qubits.into_iter().chain(ancillas.clone()), synth.ops)?;
+        new_dag.set_global_phase(phase);
+        *dag = new_dag;
+        result.ancillas_added = ancillas;
+
+        debug!(
+            "High-level synthesis: {} gates lowered ({} V-chain, {} recursive), {} ancillas",
+            result.gates_synthesized,
+            result.v_chain,
+            result.recursive,
+            result.ancillas_added.len()
+        );
+        properties.insert(result);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{BasisGates, CouplingMap};
+    use arvak_ir::Circuit;
+    use num_complex::Complex64;
+
+    /// Apply the H/RZ/CX output of the pass to a basis state.
+    fn simulate(dag: &CircuitDag, input: usize) -> Vec<Complex64> {
+        let n = dag.num_qubits();
+        let mut state = vec![Complex64::new(0.0, 0.0); 1 << n];
+        state[input] = Complex64::new(1.0, 0.0);
+
+        for (_, inst) in dag.topological_ops() {
+            let bit = |q: QubitId| 1usize << q.0;
+            match inst.as_gate().map(|g| &g.kind) {
+                Some(GateKind::Standard(StandardGate::H)) => {
+                    let m = bit(inst.qubits[0]);
+                    let s = std::f64::consts::FRAC_1_SQRT_2;
+                    for i in (0..state.len()).filter(|i| i & m == 0) {
+                        let (a, b) = (state[i], state[i | m]);
+                        state[i] = (a + b) * s;
+                        state[i | m] = (a - b) * s;
+                    }
+                }
+                Some(GateKind::Standard(StandardGate::Rz(theta))) => {
+                    let theta = theta.as_f64().unwrap();
+                    let m = bit(inst.qubits[0]);
+                    for (i, amp) in state.iter_mut().enumerate() {
+                        let sign = if i & m == 0 { -1.0 } else { 1.0 };
+                        *amp *= Complex64::from_polar(1.0, sign * theta / 2.0);
+                    }
+                }
+                Some(GateKind::Standard(StandardGate::CX)) => {
+                    let (c, t) = (bit(inst.qubits[0]), bit(inst.qubits[1]));
+                    for i in (0..state.len()).filter(|i| i & c != 0 && i & t == 0) {
+                        state.swap(i, i | t);
+                    }
+                }
+                other => panic!("unexpected gate {:?}", other),
+            }
+        }
+
+        let phase = Complex64::from_polar(1.0, dag.global_phase());
+        state.into_iter().map(|a| a * phase).collect()
+    }
+
+    /// Check the DAG maps |x⟩ to `expected(x)` for all inputs on the
+    /// original qubits, with ancillas starting and ending in |0⟩.
+    fn assert_permutation(
+        dag: &CircuitDag,
+        num_qubits: usize,
+        expected: impl Fn(usize) -> (usize, f64),
+    ) {
+        for x in 0..1 << num_qubits {
+            let out = simulate(dag, x);
+            let (y, angle) = expected(x);
+            let want = Complex64::from_polar(1.0, angle);
+            assert!(
+                (out[y] - want).norm() < 1e-9,
+                "input {:b}: amplitude {} at {:b}, expected {}",
+                x,
+                out[y],
+                y,
+                want
+            );
+        }
+    }
+
+    fn mcx_expected(controls: usize, target: usize) -> impl Fn(usize) -> (usize, f64) {
+        let mask = (1 << controls) - 1;
+        move |x| {
+            if x & mask == mask {
+                (x ^ (1 << target), 0.0)
+            } else {
+                (x, 0.0)
+            }
+        }
+    }
+
+    fn synthesize(circuit: Circuit, props: &mut PropertySet) -> (CircuitDag, SynthesisResult) {
+        let mut dag = circuit.into_dag();
+        HighLevelSynthesis::new().run(&mut dag, props).unwrap();
+        let result = props.remove::<SynthesisResult>().unwrap();
+        (dag, result)
+    }
+
+    fn controls(k: u32) -> Vec<QubitId> {
+        (0..k).map(QubitId).collect()
+    }
+
+    #[test]
+    fn test_toffoli_and_fredkin() {
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.ccx(QubitId(0), QubitId(1), QubitId(2)).unwrap();
+        let (dag, result) = synthesize(circuit, &mut PropertySet::new());
+        assert_eq!(result.gates_synthesized, 1);
+        assert_permutation(&dag, 3, mcx_expected(2, 2));
+
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.cswap(QubitId(0), QubitId(1), QubitId(2)).unwrap();
+        let (dag, _) = synthesize(circuit, &mut PropertySet::new());
+        assert_permutation(&dag, 3, |x| {
+            let swapped = (x & 1) | ((x >> 1) & 1) << 2 | ((x >> 2) & 1) << 1;
+            (if x & 1 == 1 { swapped } else { x }, 0.0)
+        });
+    }
+
+    #[test]
+    fn test_mcx_recursive_without_ancillas() {
+        let mut circuit = Circuit::with_size("test", 5, 0);
+        circuit.mcx(&controls(4), QubitId(4)).unwrap();
+
+        let (dag, result) = synthesize(circuit, &mut PropertySet::new());
+        assert_eq!(result.recursive, 1);
+        assert!(result.ancillas_added.is_empty());
+        assert_eq!(dag.num_qubits(), 5);
+        assert_permutation(&dag, 5, mcx_expected(4, 4));
+    }
+
+    #[test]
+    fn test_mcx_v_chain_with_target_ancillas() {
+        let mut circuit = Circuit::with_size("test", 5, 0);
+        circuit.mcx(&controls(4), QubitId(4)).unwrap();
+
+        // A 7-qubit target leaves the two ancillas the V-chain needs.
+        let mut props =
+            PropertySet::new().with_target(CouplingMap::full(7), BasisGates::universal());
+        let (dag, result) = synthesize(circuit, &mut props);
+        assert_eq!(result.v_chain, 1);
+        assert_eq!(result.ancillas_added, vec![QubitId(5), QubitId(6)]);
+        assert_permutation(&dag, 5, mcx_expected(4, 4));
+    }
+
+    #[test]
+    fn test_mcz_and_mcp_phases() {
+        let mut circuit = Circuit::with_size("test", 4, 0);
+        circuit.mcz(&controls(3), QubitId(3)).unwrap();
+        let (dag, _) = synthesize(circuit, &mut PropertySet::new());
+        assert_permutation(&dag, 4, |x| (x, if x == 0b1111 { PI } else { 0.0 }));
+
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.ccz(QubitId(0), QubitId(1), QubitId(2)).unwrap();
+        let (dag, result) = synthesize(circuit, &mut PropertySet::new());
+        assert_eq!(result.gates_synthesized, 1);
+        assert_permutation(&dag, 3, |x| (x, if x == 0b111 { PI } else { 0.0 }));
+
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.mcp(0.7, &controls(2), QubitId(2)).unwrap();
+        let (dag, _) = synthesize(circuit, &mut PropertySet::new());
+        assert_permutation(&dag, 3, |x| (x, if x == 0b111 { 0.7 } else { 0.0 }));
+    }
+}
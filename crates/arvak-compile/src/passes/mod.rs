@@ -7,11 +7,39 @@
 pub mod agnostic;
 pub mod target;
 
+use arvak_ir::{CircuitDag, Instruction, QubitId};
+
+use crate::error::CompileResult;
+
 // Re-exports for backward compatibility
 pub use agnostic::{
-    CancelCX, CommutativeCancellation, MeasurementBarrierVerification, OneQubitBasis,
-    Optimize1qGates, VerificationResult,
+    CancelCX, CommutativeCancellation, HighLevelSynthesis, MeasurementBarrierVerification,
+    OneQubitBasis, Optimize1qGates, SynthesisResult, VerificationResult,
 };
 pub use target::{
-    BasicRouting, BasisTranslation, NeutralAtomRouting, TrivialLayout, ZoneAssignment,
+    BasicRouting, BasisTranslation, MeasurementDeferral, MeasurementDeferralResult,
+    MeasurementSplit, MeasurementSplitPlan, NeutralAtomRouting, RoutingCost, RoutingResult,
+    SwapAbsorption, SwapAbsorptionResult, TrivialLayout, ZoneAssignment,
 };
+
+/// Build a DAG with the same classical bits, phase and level as `template`
+/// from the given qubits and instruction sequence.
+pub(crate) fn rebuild_dag(
+    template: &CircuitDag,
+    qubits: impl IntoIterator<Item = QubitId>,
+    ops: impl IntoIterator<Item = Instruction>,
+) -> CompileResult<CircuitDag> {
+    let mut dag = CircuitDag::new();
+    for q in qubits {
+        dag.add_qubit(q);
+    }
+    for c in template.clbits() {
+        dag.add_clbit(c);
+    }
+    dag.set_global_phase(template.global_phase());
+    dag.set_level(template.level());
+    for inst in ops {
+        dag.apply(inst)?;
+    }
+    Ok(dag)
+}
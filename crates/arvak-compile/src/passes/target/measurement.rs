@@ -0,0 +1,300 @@
+//! Measurement deferral for targets without mid-circuit measurement.
+
+use rustc_hash::FxHashMap;
+use tracing::debug;
+
+use arvak_ir::{CircuitDag, Instruction, InstructionKind, QubitId, StandardGate};
+
+use crate::error::{CompileError, CompileResult};
+use crate::pass::{Pass, PassKind};
+use crate::passes::rebuild_dag;
+use crate::property::PropertySet;
+
+/// Summary of a measurement deferral run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MeasurementDeferralResult {
+    /// Measurements moved to the end of the circuit unchanged.
+    pub moved: usize,
+    /// Mid-circuit measurements replaced by a copy onto an ancilla.
+    pub deferred: usize,
+    /// Ancilla qubits added to the circuit.
+    pub ancillas_added: Vec<QubitId>,
+}
+
+/// Moves all measurements to the end of the circuit.
+///
+/// Measurements with no later operation on their qubit are moved to a final
+/// measurement block as-is, separated from the rest of the circuit by a
+/// barrier across all qubits. A mid-circuit measurement is replaced by the
+/// deferred-measurement principle: a CX copies the qubit onto a fresh ancilla
+/// at the point of measurement, and the ancilla is measured into the original
+/// classical bit at the end. Measurement outcome statistics are unchanged
+/// because the IR has no classically-controlled operations.
+///
+/// Final measurements keep their original relative order, so a classical bit
+/// written more than once still holds the last outcome.
+///
+/// The pass runs only when the target sets
+/// [`PropertySet::final_measurements_only`]. Ancillas are limited by the
+/// coupling map size, if one is set, and by [`with_max_ancillas`]; if a
+/// measurement cannot be deferred within that budget the pass fails with
+/// [`CompileError::MeasurementDeferral`] naming the measurement and the
+/// operation that follows it.
+///
+/// [`with_max_ancillas`]: MeasurementDeferral::with_max_ancillas
+#[derive(Debug, Clone, Default)]
+pub struct MeasurementDeferral {
+    max_ancillas: Option<usize>,
+}
+
+impl MeasurementDeferral {
+    /// Create the pass with no ancilla limit beyond the target size.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of ancilla qubits the pass may add.
+    #[must_use]
+    pub fn with_max_ancillas(mut self, max_ancillas: usize) -> Self {
+        self.max_ancillas = Some(max_ancillas);
+        self
+    }
+}
+
+impl Pass for MeasurementDeferral {
+    fn name(&self) -> &str {
+        "MeasurementDeferral"
+    }
+
+    fn kind(&self) -> PassKind {
+        PassKind::Transformation
+    }
+
+    fn run(&self, dag: &mut CircuitDag, properties: &mut PropertySet) -> CompileResult<()> {
+        let ops: Vec<Instruction> = dag.topological_ops().map(|(_, i)| i.clone()).collect();
+
+        // Index of the last operation on each qubit that acts on its state;
+        // barriers and delays do not.
+        let mut last_use = FxHashMap::default();
+        for (pos, inst) in ops.iter().enumerate() {
+            if is_timing_only(inst) {
+                continue;
+            }
+            for q in &inst.qubits {
+                last_use.insert(*q, pos);
+            }
+        }
+
+        let qubits: Vec<QubitId> = dag.qubits().collect();
+        let budget = self.ancilla_budget(qubits.len(), properties);
+        let mut next_ancilla = qubits.iter().map(|q| q.0 + 1).max().unwrap_or(0);
+
+        let mut body = Vec::with_capacity(ops.len());
+        let mut finals = Vec::new();
+        let mut result = MeasurementDeferralResult::default();
+
+        for (pos, inst) in ops.iter().enumerate() {
+            if !matches!(inst.kind, InstructionKind::Measure) {
+                body.push(inst.clone());
+                continue;
+            }
+
+            for (&qubit, &clbit) in inst.qubits.iter().zip(&inst.clbits) {
+                if last_use[&qubit] == pos {
+                    finals.push(Instruction::measure(qubit, clbit));
+                    result.moved += 1;
+                    continue;
+                }
+
+                if result.ancillas_added.len() >= budget {
+                    let next = ops[pos + 1..]
+                        .iter()
+                        .find(|i| !is_timing_only(i) && i.qubits.contains(&qubit))
+                        .map_or("an operation", |i| i.name());
+                    return Err(CompileError::MeasurementDeferral {
+                        qubit: qubit.0,
+                        clbit: clbit.0,
+                        reason: format!(
+                            "qubit is reused by '{}' afterwards and deferring needs an ancilla, \
+                             but the ancilla budget of {} is exhausted",
+                            next, budget
+                        ),
+                    });
+                }
+
+                let ancilla = QubitId(next_ancilla);
+                next_ancilla += 1;
+                body.push(Instruction::two_qubit_gate(
+                    StandardGate::CX,
+                    qubit,
+                    ancilla,
+                ));
+                finals.push(Instruction::measure(ancilla, clbit));
+                result.ancillas_added.push(ancilla);
+                result.deferred += 1;
+            }
+        }
+
+        if result.moved + result.deferred > 0 {
+            let all_qubits: Vec<_> = qubits
+                .iter()
+                .chain(&result.ancillas_added)
+                .copied()
+                .collect();
+            let barrier = Instruction::barrier(all_qubits.clone());
+            let ops = body.into_iter().chain([barrier]).chain(finals);
+            *dag = rebuild_dag(dag, all_qubits, ops)?;
+        }
+
+        debug!(
+            "Measurement deferral: {} moved, {} deferred onto {} ancillas",
+            result.moved,
+            result.deferred,
+            result.ancillas_added.len()
+        );
+        properties.insert(result);
+
+        Ok(())
+    }
+
+    fn should_run(&self, _dag: &CircuitDag, properties: &PropertySet) -> bool {
+        properties.final_measurements_only
+    }
+}
+
+impl MeasurementDeferral {
+    fn ancilla_budget(&self, num_qubits: usize, properties: &PropertySet) -> usize {
+        let target_free = properties
+            .coupling_map
+            .as_ref()
+            .map(|cm| (cm.num_qubits() as usize).saturating_sub(num_qubits));
+        match (self.max_ancillas, target_free) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(a), None) | (None, Some(a)) => a,
+            (None, None) => usize::MAX,
+        }
+    }
+}
+
+/// Barriers and delays only constrain timing, so they do not reuse a qubit.
+fn is_timing_only(inst: &Instruction) -> bool {
+    matches!(
+        inst.kind,
+        InstructionKind::Barrier | InstructionKind::Delay { .. }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{BasisGates, CouplingMap};
+    use arvak_ir::{Circuit, ClbitId};
+
+    fn final_only() -> PropertySet {
+        PropertySet::new().with_final_measurements_only(true)
+    }
+
+    fn measures_are_final(dag: &CircuitDag) -> bool {
+        let ops: Vec<_> = dag.topological_ops().map(|(_, i)| i.is_measure()).collect();
+        let first = ops.iter().position(|m| *m).unwrap_or(ops.len());
+        ops[first..].iter().all(|m| *m)
+    }
+
+    #[test]
+    fn test_consolidates_final_measurements() {
+        let mut circuit = Circuit::with_size("test", 2, 2);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.measure(QubitId(0), ClbitId(0)).unwrap();
+        circuit.x(QubitId(1)).unwrap();
+        circuit.measure(QubitId(1), ClbitId(1)).unwrap();
+
+        let mut dag = circuit.into_dag();
+        let mut props = final_only();
+        MeasurementDeferral::new()
+            .run(&mut dag, &mut props)
+            .unwrap();
+
+        let result = props.get::<MeasurementDeferralResult>().unwrap();
+        assert_eq!(result.moved, 2);
+        assert_eq!(result.deferred, 0);
+        assert_eq!(dag.num_qubits(), 2);
+        assert!(measures_are_final(&dag));
+    }
+
+    #[test]
+    fn test_delay_after_measurement_is_not_reuse() {
+        let mut circuit = Circuit::with_size("test", 1, 1);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.measure(QubitId(0), ClbitId(0)).unwrap();
+        circuit.delay(QubitId(0), 160).unwrap();
+
+        let mut dag = circuit.into_dag();
+        let mut props = final_only();
+        MeasurementDeferral::new()
+            .with_max_ancillas(0)
+            .run(&mut dag, &mut props)
+            .unwrap();
+
+        let result = props.get::<MeasurementDeferralResult>().unwrap();
+        assert_eq!(result.moved, 1);
+        assert_eq!(result.deferred, 0);
+        assert!(measures_are_final(&dag));
+    }
+
+    #[test]
+    fn test_defers_mid_circuit_measurement() {
+        let mut circuit = Circuit::with_size("test", 1, 2);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.measure(QubitId(0), ClbitId(0)).unwrap();
+        circuit.h(QubitId(0)).unwrap();
+        circuit.measure(QubitId(0), ClbitId(1)).unwrap();
+
+        let mut dag = circuit.into_dag();
+        let mut props = final_only();
+        MeasurementDeferral::new()
+            .run(&mut dag, &mut props)
+            .unwrap();
+
+        let result = props.get::<MeasurementDeferralResult>().unwrap();
+        assert_eq!(result.deferred, 1);
+        assert_eq!(result.ancillas_added, vec![QubitId(1)]);
+        assert_eq!(dag.num_qubits(), 2);
+        assert!(measures_are_final(&dag));
+
+        let names: Vec<_> = dag.topological_ops().map(|(_, i)| i.name()).collect();
+        assert_eq!(names, vec!["h", "cx", "h", "barrier", "measure", "measure"]);
+    }
+
+    #[test]
+    fn test_reports_exhausted_budget() {
+        let mut circuit = Circuit::with_size("test", 2, 1);
+        circuit.measure(QubitId(0), ClbitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+
+        // A 2-qubit target leaves no room for an ancilla.
+        let mut dag = circuit.into_dag();
+        let mut props = final_only().with_target(CouplingMap::linear(2), BasisGates::universal());
+        let err = MeasurementDeferral::new()
+            .run(&mut dag, &mut props)
+            .unwrap_err();
+
+        match err {
+            CompileError::MeasurementDeferral {
+                qubit,
+                clbit,
+                reason,
+            } => {
+                assert_eq!((qubit, clbit), (0, 0));
+                assert!(reason.contains("'cx'"));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_skipped_without_flag() {
+        let circuit = Circuit::with_size("test", 1, 1);
+        let dag = circuit.into_dag();
+        assert!(!MeasurementDeferral::new().should_run(&dag, &PropertySet::new()));
+    }
+}
@@ -0,0 +1,276 @@
+//! Measurement splitting for targets with limited classical memory.
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use arvak_ir::{CircuitDag, ClbitId, Instruction, InstructionKind};
+
+use crate::error::{CompileError, CompileResult};
+use crate::pass::{Pass, PassKind};
+use crate::property::PropertySet;
+
+/// How the measurements of a circuit are spread over several executions.
+///
+/// Each fragment is the full circuit measuring only its own clbits, which
+/// are renumbered from 0 in the order listed. Outcome bitstrings put clbit 0
+/// rightmost, both for fragments and for the original circuit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeasurementSplitPlan {
+    /// Number of clbits of the original circuit.
+    pub num_clbits: usize,
+    /// Original clbits measured by each fragment.
+    pub fragments: Vec<Vec<u32>>,
+}
+
+impl MeasurementSplitPlan {
+    /// Number of executions the circuit is split into.
+    pub fn len(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Check if the plan has no fragments.
+    pub fn is_empty(&self) -> bool {
+        self.fragments.is_empty()
+    }
+
+    /// Build the fragment circuits of `dag`, in plan order.
+    pub fn fragment_dags(&self, dag: &CircuitDag) -> CompileResult<Vec<CircuitDag>> {
+        self.fragments
+            .iter()
+            .map(|clbits| {
+                let renumber: FxHashMap<ClbitId, ClbitId> = clbits
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &c)| (ClbitId(c), ClbitId(i as u32)))
+                    .collect();
+
+                let mut fragment = CircuitDag::new();
+                for q in dag.qubits() {
+                    fragment.add_qubit(q);
+                }
+                for i in 0..clbits.len() {
+                    fragment.add_clbit(ClbitId(i as u32));
+                }
+                fragment.set_global_phase(dag.global_phase());
+                fragment.set_level(dag.level());
+
+                for (_, inst) in dag.topological_ops() {
+                    if !matches!(inst.kind, InstructionKind::Measure) {
+                        fragment.apply(inst.clone())?;
+                        continue;
+                    }
+                    for (&qubit, clbit) in inst.qubits.iter().zip(&inst.clbits) {
+                        if let Some(&renumbered) = renumber.get(clbit) {
+                            fragment.apply(Instruction::measure(qubit, renumbered))?;
+                        }
+                    }
+                }
+                Ok(fragment)
+            })
+            .collect()
+    }
+
+    /// Assemble an outcome of the original circuit from one outcome of each
+    /// fragment, in plan order. Clbits no fragment measures read as 0.
+    pub fn merge_outcomes(&self, outcomes: &[&str]) -> String {
+        let mut bits = vec![b'0'; self.num_clbits];
+        for (clbits, outcome) in self.fragments.iter().zip(outcomes) {
+            let outcome = outcome.as_bytes();
+            for (i, &clbit) in clbits.iter().enumerate() {
+                let (Some(pos), Some(bit_pos)) = (
+                    outcome.len().checked_sub(i + 1),
+                    self.num_clbits.checked_sub(clbit as usize + 1),
+                ) else {
+                    continue;
+                };
+                bits[bit_pos] = outcome[pos];
+            }
+        }
+        String::from_utf8(bits).expect("outcome bits are ASCII")
+    }
+}
+
+/// Plans splitting measurements over several executions when a circuit uses
+/// more clbits than the target can hold.
+///
+/// The measured clbits are divided in index order into fragments of at most
+/// [`PropertySet::max_clbits`] each, and the resulting
+/// [`MeasurementSplitPlan`] is stored in the property set. The circuit
+/// itself is left unchanged; build the executions with
+/// [`MeasurementSplitPlan::fragment_dags`] and combine their outcomes with
+/// [`MeasurementSplitPlan::merge_outcomes`]. Correlations between clbits of
+/// different fragments are not observed, as they are measured in separate
+/// executions.
+///
+/// Dropping a measurement from a fragment must not change the state seen by
+/// later operations, so every measurement has to be final; run
+/// [`MeasurementDeferral`](super::MeasurementDeferral) first otherwise. A
+/// measured qubit that is used again fails the pass with
+/// [`CompileError::MeasurementSplit`].
+#[derive(Debug, Clone, Default)]
+pub struct MeasurementSplit;
+
+impl MeasurementSplit {
+    /// Plan the split of `dag` for at most `max_clbits` clbits per
+    /// execution, or `None` if the circuit fits as it is.
+    pub fn plan(
+        &self,
+        dag: &CircuitDag,
+        max_clbits: usize,
+    ) -> CompileResult<Option<MeasurementSplitPlan>> {
+        if dag.num_clbits() <= max_clbits {
+            return Ok(None);
+        }
+        if max_clbits == 0 {
+            return Err(CompileError::InvalidConfiguration(
+                "max_clbits must be greater than 0".into(),
+            ));
+        }
+
+        let ops: Vec<&Instruction> = dag.topological_ops().map(|(_, i)| i).collect();
+        let mut measured = Vec::new();
+        for (pos, inst) in ops.iter().enumerate() {
+            if !matches!(inst.kind, InstructionKind::Measure) {
+                continue;
+            }
+            for (&qubit, &clbit) in inst.qubits.iter().zip(&inst.clbits) {
+                let reuse = ops[pos + 1..].iter().find(|i| {
+                    i.qubits.contains(&qubit)
+                        && !matches!(
+                            i.kind,
+                            InstructionKind::Barrier
+                                | InstructionKind::Delay { .. }
+                                | InstructionKind::Measure
+                        )
+                });
+                if let Some(next) = reuse {
+                    return Err(CompileError::MeasurementSplit {
+                        qubit: qubit.0,
+                        clbit: clbit.0,
+                        reason: format!(
+                            "qubit is reused by '{}' afterwards; defer mid-circuit \
+                             measurements first",
+                            next.name()
+                        ),
+                    });
+                }
+                measured.push(clbit.0);
+            }
+        }
+        measured.sort_unstable();
+        measured.dedup();
+
+        let mut fragments: Vec<Vec<u32>> =
+            measured.chunks(max_clbits).map(<[u32]>::to_vec).collect();
+        if fragments.is_empty() {
+            fragments.push(Vec::new());
+        }
+        Ok(Some(MeasurementSplitPlan {
+            num_clbits: dag.num_clbits(),
+            fragments,
+        }))
+    }
+}
+
+impl Pass for MeasurementSplit {
+    fn name(&self) -> &str {
+        "MeasurementSplit"
+    }
+
+    fn kind(&self) -> PassKind {
+        PassKind::Analysis
+    }
+
+    fn run(&self, dag: &mut CircuitDag, properties: &mut PropertySet) -> CompileResult<()> {
+        let Some(max_clbits) = properties.max_clbits else {
+            return Ok(());
+        };
+        properties.remove::<MeasurementSplitPlan>();
+        if let Some(plan) = self.plan(dag, max_clbits)? {
+            debug!(
+                "Measurement split: {} clbits into {} executions of at most {}",
+                plan.num_clbits,
+                plan.len(),
+                max_clbits
+            );
+            properties.insert(plan);
+        }
+        Ok(())
+    }
+
+    fn should_run(&self, _dag: &CircuitDag, properties: &PropertySet) -> bool {
+        properties.max_clbits.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_ir::{Circuit, QubitId};
+
+    fn ghz(n: u32) -> Circuit {
+        let mut circuit = Circuit::with_size("ghz", n, n);
+        circuit.h(QubitId(0)).unwrap();
+        for q in 1..n {
+            circuit.cx(QubitId(q - 1), QubitId(q)).unwrap();
+        }
+        circuit.measure_all().unwrap();
+        circuit
+    }
+
+    #[test]
+    fn test_splits_into_fragments() {
+        let mut dag = ghz(5).into_dag();
+        let mut props = PropertySet::new().with_max_clbits(2);
+        MeasurementSplit.run(&mut dag, &mut props).unwrap();
+
+        let plan = props.get::<MeasurementSplitPlan>().unwrap();
+        assert_eq!(plan.fragments, vec![vec![0, 1], vec![2, 3], vec![4]]);
+
+        let fragments = plan.fragment_dags(&dag).unwrap();
+        assert_eq!(fragments.len(), 3);
+        for (fragment, clbits) in fragments.iter().zip(&plan.fragments) {
+            assert_eq!(fragment.num_clbits(), clbits.len());
+            assert_eq!(fragment.num_qubits(), 5);
+            let measures = fragment
+                .topological_ops()
+                .filter(|(_, i)| i.is_measure())
+                .count();
+            assert_eq!(measures, clbits.len());
+        }
+
+        // Clbit 0 is rightmost in every bitstring.
+        assert_eq!(plan.merge_outcomes(&["01", "10", "1"]), "11001");
+    }
+
+    #[test]
+    fn test_fits_without_split() {
+        let mut dag = ghz(3).into_dag();
+        let mut props = PropertySet::new().with_max_clbits(3);
+        MeasurementSplit.run(&mut dag, &mut props).unwrap();
+        assert!(props.get::<MeasurementSplitPlan>().is_none());
+        assert!(!MeasurementSplit.should_run(&dag, &PropertySet::new()));
+    }
+
+    #[test]
+    fn test_rejects_mid_circuit_measurement() {
+        let mut circuit = Circuit::with_size("test", 1, 2);
+        circuit.measure(QubitId(0), ClbitId(0)).unwrap();
+        circuit.h(QubitId(0)).unwrap();
+        circuit.measure(QubitId(0), ClbitId(1)).unwrap();
+
+        let err = MeasurementSplit.plan(&circuit.into_dag(), 1).unwrap_err();
+        match err {
+            CompileError::MeasurementSplit {
+                qubit,
+                clbit,
+                reason,
+            } => {
+                assert_eq!((qubit, clbit), (0, 0));
+                assert!(reason.contains("'h'"));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+}
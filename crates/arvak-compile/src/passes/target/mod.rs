@@ -0,0 +1,21 @@
+//! Target-specific compilation passes.
+//!
+//! These passes require target hardware properties from the PropertySet
+//! (coupling map, basis gates, layout) and produce hardware-compatible
+//! circuits for specific quantum devices.
+
+pub mod layout;
+pub mod measurement;
+pub mod measurement_split;
+pub mod neutral_atom_routing;
+pub mod routing;
+pub mod swap_absorption;
+pub mod translation;
+
+pub use layout::TrivialLayout;
+pub use measurement::{MeasurementDeferral, MeasurementDeferralResult};
+pub use measurement_split::{MeasurementSplit, MeasurementSplitPlan};
+pub use neutral_atom_routing::{NeutralAtomRouting, ZoneAssignment};
+pub use routing::{BasicRouting, RoutingCost, RoutingResult};
+pub use swap_absorption::{SwapAbsorption, SwapAbsorptionResult};
+pub use translation::BasisTranslation;
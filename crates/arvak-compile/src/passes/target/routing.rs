@@ -0,0 +1,504 @@
+//! Routing passes for inserting SWAP gates.
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use arvak_ir::{CircuitDag, Instruction, QubitId, StandardGate};
+
+use crate::error::{CompileError, CompileResult};
+use crate::pass::{Pass, PassKind};
+use crate::passes::rebuild_dag;
+use crate::property::{CouplingMap, ErrorRates, Layout, PropertySet};
+
+/// Operations ahead of the first unrouted one considered for reordering.
+const LOOKAHEAD: usize = 32;
+
+/// What the router minimizes when it brings the operands of a blocked
+/// two-qubit gate together.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingCost {
+    /// Fewest SWAPs: a shortest path in the coupling map.
+    #[default]
+    SwapCount,
+
+    /// Lowest two-qubit gate error along the path, from the target's
+    /// [`ErrorRates`]: each SWAP counts as three two-qubit gates on its
+    /// coupler, and the gate itself as one on the coupler it runs on, so a
+    /// slightly longer path over better couplers wins. Couplers without a
+    /// calibrated error count with the mean error. Falls back to
+    /// [`RoutingCost::SwapCount`] when the target has no two-qubit errors.
+    GateError,
+}
+
+/// Summary of a routing run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutingResult {
+    /// SWAP gates inserted.
+    pub swaps_inserted: usize,
+    /// Operations moved ahead of a blocked gate they commute with.
+    pub gates_reordered: usize,
+}
+
+/// Basic routing pass.
+///
+/// Inserts SWAP gates to satisfy connectivity constraints.
+/// This is a simple greedy algorithm that may not produce
+/// optimal results but is fast and correct.
+///
+/// The routed circuit acts on physical qubits: operand `QubitId(p)` is
+/// physical qubit `p`, and the layout in the property set is updated to the
+/// final placement of each logical qubit. When a two-qubit gate is not
+/// executable, SWAPs are inserted along a shortest path to bring its
+/// operands together.
+///
+/// By default the router looks ahead for operations that commute with every
+/// pending operation before them (see [`Instruction::commutes_with`]) and
+/// are executable on the current layout, and runs those first, so they are
+/// not pulled apart by SWAPs inserted for the blocked gate. Disable this with
+/// [`with_commutation`] to route strictly in DAG order.
+///
+/// Paths are chosen by [`RoutingCost`], fewest SWAPs by default; see
+/// [`with_cost`].
+///
+/// [`with_commutation`]: BasicRouting::with_commutation
+/// [`with_cost`]: BasicRouting::with_cost
+#[derive(Debug, Clone)]
+pub struct BasicRouting {
+    commutation: bool,
+    cost: RoutingCost,
+}
+
+impl BasicRouting {
+    /// Create a routing pass with commutation-aware reordering enabled.
+    pub fn new() -> Self {
+        Self {
+            commutation: true,
+            cost: RoutingCost::SwapCount,
+        }
+    }
+
+    /// Choose what routing paths minimize.
+    #[must_use]
+    pub fn with_cost(mut self, cost: RoutingCost) -> Self {
+        self.cost = cost;
+        self
+    }
+
+    /// Enable or disable reordering of commuting operations.
+    #[must_use]
+    pub fn with_commutation(mut self, enabled: bool) -> Self {
+        self.commutation = enabled;
+        self
+    }
+
+    /// Check whether `inst` may run before all of the pending `earlier` ops.
+    fn can_run_before(&self, earlier: &[Option<Instruction>], inst: &Instruction) -> bool {
+        let mut earlier = earlier.iter().flatten();
+        if self.commutation {
+            earlier.all(|op| op.commutes_with(inst))
+        } else {
+            earlier.next().is_none()
+        }
+    }
+}
+
+impl Default for BasicRouting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for BasicRouting {
+    fn name(&self) -> &str {
+        "BasicRouting"
+    }
+
+    fn kind(&self) -> PassKind {
+        PassKind::Transformation
+    }
+
+    fn run(&self, dag: &mut CircuitDag, properties: &mut PropertySet) -> CompileResult<()> {
+        let coupling_map = properties
+            .coupling_map
+            .as_ref()
+            .ok_or(CompileError::MissingCouplingMap)?;
+
+        let layout = properties
+            .layout
+            .as_mut()
+            .ok_or(CompileError::MissingLayout)?;
+        let error_rates = match self.cost {
+            RoutingCost::SwapCount => None,
+            RoutingCost::GateError => properties.error_rates.as_ref(),
+        };
+
+        let mut wires: Vec<u32> = layout.iter().map(|(_, p)| p).collect();
+        let mut ops: Vec<Option<Instruction>> = dag
+            .topological_ops()
+            .map(|(_, inst)| Some(inst.clone()))
+            .collect();
+        let mut routed = Vec::with_capacity(ops.len());
+        let mut result = RoutingResult::default();
+        let mut head = 0;
+
+        loop {
+            while ops.get(head).is_some_and(Option::is_none) {
+                head += 1;
+            }
+            if head == ops.len() {
+                break;
+            }
+
+            // Run everything that is both allowed to go next and executable
+            // on the current layout.
+            let mut progressed = false;
+            for i in head..ops.len().min(head + LOOKAHEAD) {
+                let Some(inst) = &ops[i] else {
+                    continue;
+                };
+                if !self.can_run_before(&ops[head..i], inst)
+                    || !is_executable(inst, layout, coupling_map)?
+                {
+                    continue;
+                }
+                routed.push(to_physical(inst, layout)?);
+                if ops[head..i].iter().any(Option::is_some) {
+                    result.gates_reordered += 1;
+                }
+                ops[i] = None;
+                progressed = true;
+            }
+            if progressed {
+                continue;
+            }
+
+            // The first pending operation is a blocked two-qubit gate: move
+            // its first operand along a shortest path to the second.
+            let inst = ops[head].as_ref().expect("head is pending");
+            let p0 = physical(layout, inst.qubits[0])?;
+            let p1 = physical(layout, inst.qubits[1])?;
+            let path = match error_rates {
+                Some(error_rates) => lowest_error_path(coupling_map, error_rates, p0, p1),
+                None => coupling_map.shortest_path(p0, p1),
+            }
+            .ok_or(CompileError::RoutingFailed {
+                qubit1: p0,
+                qubit2: p1,
+            })?;
+            for edge in path[..path.len() - 1].windows(2) {
+                routed.push(Instruction::two_qubit_gate(
+                    StandardGate::Swap,
+                    QubitId(edge[0]),
+                    QubitId(edge[1]),
+                ));
+                layout.swap(edge[0], edge[1]);
+                wires.extend(edge);
+                result.swaps_inserted += 1;
+            }
+        }
+
+        wires.sort_unstable();
+        wires.dedup();
+        *dag = rebuild_dag(dag, wires.into_iter().map(QubitId), routed)?;
+
+        debug!(
+            "Routing: {} SWAPs inserted, {} gates reordered",
+            result.swaps_inserted, result.gates_reordered
+        );
+        properties.insert(result);
+
+        Ok(())
+    }
+
+    fn should_run(&self, _dag: &CircuitDag, properties: &PropertySet) -> bool {
+        properties.coupling_map.is_some() && properties.layout.is_some()
+    }
+}
+
+/// Path from `from` to `to` minimizing the infidelity of the SWAPs that
+/// move `from` next to `to` plus the gate on the last coupler, with each
+/// coupler weighted by `-ln(1 - error)`.
+///
+/// Falls back to a shortest path when no two-qubit errors are known.
+fn lowest_error_path(
+    coupling_map: &CouplingMap,
+    error_rates: &ErrorRates,
+    from: u32,
+    to: u32,
+) -> Option<Vec<u32>> {
+    let Some(mean) = error_rates.mean_two_qubit_error() else {
+        return coupling_map.shortest_path(from, to);
+    };
+    let weight = |a: u32, b: u32| {
+        let error = error_rates.two_qubit_error(a, b).unwrap_or(mean);
+        -(1.0 - error.clamp(0.0, 1.0 - 1e-12)).ln()
+    };
+
+    // Dijkstra over SWAP costs; `to` is only entered through the final gate.
+    let n = coupling_map.num_qubits() as usize;
+    let mut cost = vec![f64::INFINITY; n];
+    let mut previous = vec![None; n];
+    let mut done = vec![false; n];
+    cost[from as usize] = 0.0;
+    while let Some(current) = (0..n)
+        .filter(|&q| !done[q] && cost[q].is_finite() && q != to as usize)
+        .min_by(|&a, &b| cost[a].total_cmp(&cost[b]))
+    {
+        done[current] = true;
+        for next in coupling_map.neighbors(current as u32) {
+            let next_cost = cost[current]
+                + if next == to {
+                    weight(current as u32, next)
+                } else {
+                    3.0 * weight(current as u32, next)
+                };
+            if next_cost < cost[next as usize] {
+                cost[next as usize] = next_cost;
+                previous[next as usize] = Some(current as u32);
+            }
+        }
+    }
+
+    let mut path = vec![to];
+    let mut current = to;
+    while current != from {
+        current = previous[current as usize]?;
+        path.push(current);
+    }
+    path.reverse();
+    Some(path)
+}
+
+fn physical(layout: &Layout, qubit: QubitId) -> CompileResult<u32> {
+    layout
+        .get_physical(qubit)
+        .ok_or(CompileError::MissingLayout)
+}
+
+/// Only two-qubit gates are constrained by connectivity.
+fn is_executable(
+    inst: &Instruction,
+    layout: &Layout,
+    coupling_map: &CouplingMap,
+) -> CompileResult<bool> {
+    if !inst.is_gate() || inst.qubits.len() != 2 {
+        return Ok(true);
+    }
+    let p0 = physical(layout, inst.qubits[0])?;
+    let p1 = physical(layout, inst.qubits[1])?;
+    Ok(coupling_map.is_connected(p0, p1))
+}
+
+fn to_physical(inst: &Instruction, layout: &Layout) -> CompileResult<Instruction> {
+    let mut inst = inst.clone();
+    for q in &mut inst.qubits {
+        *q = QubitId(physical(layout, *q)?);
+    }
+    Ok(inst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::passes::TrivialLayout;
+    use crate::property::BasisGates;
+    use crate::testing::assert_equivalent;
+    use arvak_ir::{Circuit, ParameterExpression};
+
+    fn route(circuit: &Circuit, router: BasicRouting) -> (CircuitDag, PropertySet) {
+        let mut dag = circuit.clone().into_dag();
+        let mut props = PropertySet::new().with_target(CouplingMap::linear(5), BasisGates::iqm());
+        TrivialLayout.run(&mut dag, &mut props).unwrap();
+        router.run(&mut dag, &mut props).unwrap();
+        (dag, props)
+    }
+
+    fn assert_connected(dag: &CircuitDag) {
+        let coupling_map = CouplingMap::linear(5);
+        for (_, inst) in dag.topological_ops() {
+            if inst.qubits.len() == 2 {
+                let (a, b) = (inst.qubits[0].0, inst.qubits[1].0);
+                assert!(
+                    coupling_map.is_connected(a, b),
+                    "{} on {}, {}",
+                    inst.name(),
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_basic_routing_connected() {
+        // Create a circuit with a CX on adjacent qubits
+        let mut circuit = Circuit::with_size("test", 2, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+
+        let (dag, props) = route(&circuit, BasicRouting::new());
+
+        // No SWAPs needed, ops count should be the same
+        assert_eq!(dag.num_ops(), 2);
+        assert_eq!(props.get::<RoutingResult>().unwrap().swaps_inserted, 0);
+    }
+
+    #[test]
+    fn test_basic_routing_needs_swap() {
+        // Create a circuit with a CX on non-adjacent qubits
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.cx(QubitId(0), QubitId(2)).unwrap(); // Not adjacent in linear
+        circuit.h(QubitId(0)).unwrap();
+
+        let (dag, props) = route(&circuit, BasicRouting::new());
+
+        let names: Vec<_> = dag.topological_ops().map(|(_, i)| i.name()).collect();
+        assert_eq!(names, vec!["swap", "cx", "h"]);
+        assert_connected(&dag);
+
+        // Logical qubit 0 now sits on physical qubit 1.
+        let layout = props.layout.as_ref().unwrap();
+        assert_eq!(layout.get_physical(QubitId(0)), Some(1));
+        assert_equivalent(&circuit, &dag, layout);
+    }
+
+    #[test]
+    fn test_commutation_avoids_swap() {
+        // Both CX share a target, so the adjacent one can run first, before
+        // routing CX(0, 3) moves qubit 2 away from qubit 3.
+        let mut circuit = Circuit::with_size("test", 4, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.h(QubitId(2)).unwrap();
+        circuit.cx(QubitId(0), QubitId(3)).unwrap();
+        circuit.cx(QubitId(2), QubitId(3)).unwrap();
+
+        let (reordered, props) = route(&circuit, BasicRouting::new());
+        let result = props.get::<RoutingResult>().unwrap();
+        assert_eq!(result.swaps_inserted, 2);
+        assert_eq!(result.gates_reordered, 1);
+        assert_connected(&reordered);
+        assert_equivalent(&circuit, &reordered, props.layout.as_ref().unwrap());
+
+        let (fixed, props) = route(&circuit, BasicRouting::new().with_commutation(false));
+        let result = props.get::<RoutingResult>().unwrap();
+        assert_eq!(result.swaps_inserted, 3);
+        assert_eq!(result.gates_reordered, 0);
+        assert_connected(&fixed);
+        assert_equivalent(&circuit, &fixed, props.layout.as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_non_commuting_order_kept() {
+        // Qubit 3 is the target of the first CX and the control of the
+        // second, so they must stay in order.
+        let mut circuit = Circuit::with_size("test", 4, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.h(QubitId(3)).unwrap();
+        circuit.cx(QubitId(0), QubitId(3)).unwrap();
+        circuit.cx(QubitId(3), QubitId(2)).unwrap();
+
+        let (dag, props) = route(&circuit, BasicRouting::new());
+        assert_eq!(props.get::<RoutingResult>().unwrap().gates_reordered, 0);
+        assert_equivalent(&circuit, &dag, props.layout.as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_routing_equivalence() {
+        let q = QubitId;
+        let mut circuit = Circuit::with_size("test", 5, 0);
+        for i in 0..5 {
+            circuit.h(q(i)).unwrap();
+        }
+        for (step, (a, b)) in [(0, 4), (1, 3), (4, 2), (0, 2), (3, 0), (1, 4), (2, 1)]
+            .into_iter()
+            .enumerate()
+        {
+            circuit.cx(q(a), q(b)).unwrap();
+            circuit
+                .rz(ParameterExpression::constant(0.3 * step as f64 + 0.1), q(a))
+                .unwrap();
+            circuit.h(q(b)).unwrap();
+        }
+
+        for commutation in [true, false] {
+            let (dag, props) = route(&circuit, BasicRouting::new().with_commutation(commutation));
+            assert_connected(&dag);
+            assert_equivalent(&circuit, &dag, props.layout.as_ref().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_gate_error_routing() {
+        // On a ring of five, CX(0, 2) is one SWAP away over the coupler
+        // (0, 1), which is poorly calibrated; going the other way round
+        // takes two SWAPs over good couplers.
+        let ring = CouplingMap::from_edges(5, [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+        let errors = ErrorRates::new()
+            .with_two_qubit_error(0, 1, 0.2)
+            .with_two_qubit_error(1, 2, 0.01)
+            .with_two_qubit_error(2, 3, 0.01)
+            .with_two_qubit_error(3, 4, 0.01)
+            .with_two_qubit_error(4, 0, 0.01);
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(2)).unwrap();
+
+        let route = |cost| {
+            let mut dag = circuit.clone().into_dag();
+            let mut props = PropertySet::new()
+                .with_target(ring.clone(), BasisGates::iqm())
+                .with_error_rates(errors.clone());
+            TrivialLayout.run(&mut dag, &mut props).unwrap();
+            BasicRouting::new()
+                .with_cost(cost)
+                .run(&mut dag, &mut props)
+                .unwrap();
+            (dag, props)
+        };
+        // Success probability of the two-qubit gates, a SWAP being three.
+        let fidelity = |dag: &CircuitDag| {
+            dag.topological_ops()
+                .filter(|(_, inst)| inst.qubits.len() == 2)
+                .map(|(_, inst)| {
+                    let gates = if inst.name() == "swap" { 3 } else { 1 };
+                    let error = errors
+                        .two_qubit_error(inst.qubits[0].0, inst.qubits[1].0)
+                        .unwrap();
+                    (1.0 - error).powi(gates)
+                })
+                .product::<f64>()
+        };
+
+        let (shortest, props) = route(RoutingCost::SwapCount);
+        assert_eq!(props.get::<RoutingResult>().unwrap().swaps_inserted, 1);
+        assert_equivalent(&circuit, &shortest, props.layout.as_ref().unwrap());
+
+        let (reliable, props) = route(RoutingCost::GateError);
+        assert_eq!(props.get::<RoutingResult>().unwrap().swaps_inserted, 2);
+        assert_equivalent(&circuit, &reliable, props.layout.as_ref().unwrap());
+        for (_, inst) in reliable.topological_ops() {
+            if inst.qubits.len() == 2 {
+                let (a, b) = (inst.qubits[0].0, inst.qubits[1].0);
+                assert!(ring.is_connected(a, b));
+                assert_ne!(
+                    (a.min(b), a.max(b)),
+                    (0, 1),
+                    "{} uses the bad coupler",
+                    inst.name()
+                );
+            }
+        }
+        assert!(fidelity(&reliable) > fidelity(&shortest));
+
+        // Without error rates the cost falls back to SWAP count.
+        let mut dag = circuit.clone().into_dag();
+        let mut props = PropertySet::new().with_target(ring.clone(), BasisGates::iqm());
+        TrivialLayout.run(&mut dag, &mut props).unwrap();
+        BasicRouting::new()
+            .with_cost(RoutingCost::GateError)
+            .run(&mut dag, &mut props)
+            .unwrap();
+        assert_eq!(props.get::<RoutingResult>().unwrap().swaps_inserted, 1);
+    }
+}
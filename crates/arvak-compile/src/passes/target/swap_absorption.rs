@@ -0,0 +1,238 @@
+//! Post-routing absorption of SWAP gates into neighbouring CX gates.
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use arvak_ir::{CircuitDag, GateKind, Instruction, InstructionKind, QubitId, StandardGate};
+
+use crate::error::CompileResult;
+use crate::pass::{Pass, PassKind};
+use crate::passes::rebuild_dag;
+use crate::property::PropertySet;
+
+/// CX gates in the decomposition of a SWAP.
+const SWAP_CX_COST: usize = 3;
+
+/// Summary of a SWAP absorption run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapAbsorptionResult {
+    /// SWAP + CX pairs replaced by a mirrored CX pair.
+    pub swaps_absorbed: usize,
+    /// Adjacent SWAP pairs on the same qubits removed.
+    pub swaps_cancelled: usize,
+    /// CX gates saved compared to decomposing every SWAP into three CX.
+    pub cx_saved: usize,
+}
+
+/// Absorbs routing SWAPs into adjacent CX gates.
+///
+/// A SWAP directly followed or preceded by a CX on the same pair of qubits
+/// costs four CX after translation, but the combination equals a mirrored
+/// CX pair:
+///
+/// ```text
+/// SWAP(a,b) · CX(c,t)  =  CX(c,t) · CX(t,c)
+/// CX(c,t) · SWAP(a,b)  =  CX(t,c) · CX(c,t)
+/// ```
+///
+/// Adjacent SWAPs on the same qubits cancel. Each rewrite saves two CX
+/// (six for a cancelled SWAP pair); the totals are stored in the property
+/// set as a [`SwapAbsorptionResult`].
+///
+/// Run after routing and before basis translation, while SWAPs are still
+/// explicit gates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwapAbsorption;
+
+impl SwapAbsorption {
+    /// Create a new SWAP absorption pass.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TwoQubit {
+    Swap(QubitId, QubitId),
+    Cx(QubitId, QubitId),
+}
+
+impl TwoQubit {
+    fn of(inst: &Instruction) -> Option<Self> {
+        let InstructionKind::Gate(gate) = &inst.kind else {
+            return None;
+        };
+        match (&gate.kind, inst.qubits.as_slice()) {
+            (GateKind::Standard(StandardGate::Swap), [a, b]) => Some(TwoQubit::Swap(*a, *b)),
+            (GateKind::Standard(StandardGate::CX), [c, t]) => Some(TwoQubit::Cx(*c, *t)),
+            _ => None,
+        }
+    }
+
+    fn pair(self) -> (QubitId, QubitId) {
+        let (a, b) = match self {
+            TwoQubit::Swap(a, b) | TwoQubit::Cx(a, b) => (a, b),
+        };
+        if a.0 <= b.0 { (a, b) } else { (b, a) }
+    }
+}
+
+fn cx(control: QubitId, target: QubitId) -> Instruction {
+    Instruction::two_qubit_gate(StandardGate::CX, control, target)
+}
+
+impl Pass for SwapAbsorption {
+    fn name(&self) -> &str {
+        "SwapAbsorption"
+    }
+
+    fn kind(&self) -> PassKind {
+        PassKind::Transformation
+    }
+
+    fn run(&self, dag: &mut CircuitDag, properties: &mut PropertySet) -> CompileResult<()> {
+        let mut ops: Vec<Option<Instruction>> = dag
+            .topological_ops()
+            .map(|(_, i)| Some(i.clone()))
+            .collect();
+        let mut replacements: Vec<Vec<Instruction>> = vec![Vec::new(); ops.len()];
+        let mut result = SwapAbsorptionResult::default();
+
+        for i in 0..ops.len() {
+            let Some(first) = ops[i].as_ref().and_then(TwoQubit::of) else {
+                continue;
+            };
+            let (a, b) = first.pair();
+
+            // The next operation touching either qubit must act on exactly
+            // this pair, otherwise the gates are not adjacent.
+            let Some(j) = (i + 1..ops.len()).find(|&j| {
+                ops[j]
+                    .as_ref()
+                    .is_some_and(|op| op.qubits.contains(&a) || op.qubits.contains(&b))
+            }) else {
+                continue;
+            };
+            let Some(second) = ops[j].as_ref().and_then(TwoQubit::of) else {
+                continue;
+            };
+            if second.pair() != (a, b) {
+                continue;
+            }
+
+            let rewrite = match (first, second) {
+                (TwoQubit::Swap(..), TwoQubit::Cx(c, t)) => {
+                    result.swaps_absorbed += 1;
+                    result.cx_saved += SWAP_CX_COST + 1 - 2;
+                    vec![cx(c, t), cx(t, c)]
+                }
+                (TwoQubit::Cx(c, t), TwoQubit::Swap(..)) => {
+                    result.swaps_absorbed += 1;
+                    result.cx_saved += SWAP_CX_COST + 1 - 2;
+                    vec![cx(t, c), cx(c, t)]
+                }
+                (TwoQubit::Swap(..), TwoQubit::Swap(..)) => {
+                    result.swaps_cancelled += 1;
+                    result.cx_saved += 2 * SWAP_CX_COST;
+                    vec![]
+                }
+                (TwoQubit::Cx(..), TwoQubit::Cx(..)) => continue,
+            };
+
+            // Nothing between i and j touches the pair, so the rewrite can
+            // take the place of the first gate.
+            ops[i] = None;
+            ops[j] = None;
+            replacements[i] = rewrite;
+        }
+
+        if result.swaps_absorbed + result.swaps_cancelled > 0 {
+            let qubits: Vec<_> = dag.qubits().collect();
+            let new_ops = ops
+                .into_iter()
+                .zip(replacements)
+                .flat_map(|(op, rewrite)| op.into_iter().chain(rewrite));
+            *dag = rebuild_dag(dag, qubits, new_ops)?;
+        }
+
+        debug!(
+            "SWAP absorption: {} absorbed, {} cancelled, {} CX saved",
+            result.swaps_absorbed, result.swaps_cancelled, result.cx_saved
+        );
+        properties.insert(result);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_ir::Circuit;
+
+    fn run(circuit: Circuit) -> (Vec<(String, Vec<u32>)>, SwapAbsorptionResult) {
+        let mut dag = circuit.into_dag();
+        let mut props = PropertySet::new();
+        SwapAbsorption.run(&mut dag, &mut props).unwrap();
+        let ops = dag
+            .topological_ops()
+            .map(|(_, i)| (i.name().to_string(), i.qubits.iter().map(|q| q.0).collect()))
+            .collect();
+        (ops, props.remove::<SwapAbsorptionResult>().unwrap())
+    }
+
+    #[test]
+    fn test_swap_then_cx() {
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.swap(QubitId(0), QubitId(1)).unwrap();
+        circuit.h(QubitId(2)).unwrap();
+        circuit.cx(QubitId(1), QubitId(0)).unwrap();
+
+        let (ops, result) = run(circuit);
+        assert_eq!(result.swaps_absorbed, 1);
+        assert_eq!(result.cx_saved, 2);
+
+        let on_pair: Vec<_> = ops.iter().filter(|(_, q)| q.len() == 2).collect();
+        assert_eq!(
+            on_pair,
+            vec![
+                &("cx".to_string(), vec![1, 0]),
+                &("cx".to_string(), vec![0, 1])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cx_then_swap_and_swap_pair() {
+        let mut circuit = Circuit::with_size("test", 4, 0);
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+        circuit.swap(QubitId(1), QubitId(0)).unwrap();
+        circuit.swap(QubitId(2), QubitId(3)).unwrap();
+        circuit.swap(QubitId(3), QubitId(2)).unwrap();
+
+        let (ops, result) = run(circuit);
+        assert_eq!(result.swaps_absorbed, 1);
+        assert_eq!(result.swaps_cancelled, 1);
+        assert_eq!(result.cx_saved, 8);
+        assert_eq!(
+            ops,
+            vec![
+                ("cx".to_string(), vec![1, 0]),
+                ("cx".to_string(), vec![0, 1])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interleaved_gate_blocks_absorption() {
+        let mut circuit = Circuit::with_size("test", 2, 0);
+        circuit.swap(QubitId(0), QubitId(1)).unwrap();
+        circuit.h(QubitId(1)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+
+        let (ops, result) = run(circuit);
+        assert_eq!(result, SwapAbsorptionResult::default());
+        assert_eq!(ops[0].0, "swap");
+    }
+}
@@ -0,0 +1,528 @@
+//! Basis translation passes.
+
+use std::f64::consts::PI;
+
+use arvak_ir::{
+    CircuitDag, Gate, GateKind, Instruction, InstructionKind, ParameterExpression, QubitId,
+    StandardGate,
+};
+
+use crate::error::{CompileError, CompileResult};
+use crate::pass::{Pass, PassKind};
+use crate::property::PropertySet;
+
+/// Basis translation pass.
+///
+/// Translates gates to the target basis gate set.
+/// Currently supports translation to:
+/// - IQM basis: PRX + CZ
+/// - IBM basis: RZ + SX + X + CX
+///
+/// Every one- and two-qubit standard gate is translated, up to a global
+/// phase: gates without a direct rule are first decomposed into simpler
+/// gates and CX, which are then translated. Three-qubit gates are lowered
+/// earlier by [`HighLevelSynthesis`](crate::passes::HighLevelSynthesis).
+pub struct BasisTranslation;
+
+impl Pass for BasisTranslation {
+    fn name(&self) -> &str {
+        "BasisTranslation"
+    }
+
+    fn kind(&self) -> PassKind {
+        PassKind::Transformation
+    }
+
+    fn run(&self, dag: &mut CircuitDag, properties: &mut PropertySet) -> CompileResult<()> {
+        let basis_gates = properties
+            .basis_gates
+            .as_ref()
+            .ok_or(CompileError::MissingBasisGates)?;
+
+        // Collect nodes that need translation
+        let nodes_to_translate: Vec<_> = dag
+            .topological_ops()
+            .filter_map(|(idx, inst)| {
+                if let Some(gate) = inst.as_gate() {
+                    if !is_in_basis(gate, basis_gates) {
+                        return Some((idx, inst.clone()));
+                    }
+                }
+                None
+            })
+            .collect();
+
+        // Translate each gate
+        for (node_idx, instruction) in nodes_to_translate {
+            let replacement = translate_gate(&instruction, basis_gates)?;
+            if replacement.is_empty() {
+                dag.remove_op(node_idx)?;
+            } else {
+                dag.substitute_node(node_idx, replacement)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn should_run(&self, _dag: &CircuitDag, properties: &PropertySet) -> bool {
+        properties.basis_gates.is_some()
+    }
+}
+
+/// Check if a gate is in the target basis.
+fn is_in_basis(gate: &Gate, basis: &crate::property::BasisGates) -> bool {
+    basis.contains(gate.name())
+}
+
+/// Translate a gate instruction to the target basis.
+fn translate_gate(
+    instruction: &Instruction,
+    basis: &crate::property::BasisGates,
+) -> CompileResult<Vec<Instruction>> {
+    let gate = match &instruction.kind {
+        InstructionKind::Gate(g) => g,
+        _ => return Ok(vec![instruction.clone()]),
+    };
+
+    let _qubit = instruction.qubits[0];
+
+    // Check if it's IQM basis (PRX + CZ)
+    let is_iqm = basis.contains("prx") && basis.contains("cz");
+    // Check if it's IBM basis (RZ + SX + X + CX)
+    let is_ibm = basis.contains("rz") && basis.contains("sx") && basis.contains("cx");
+
+    match &gate.kind {
+        GateKind::Standard(std_gate) => {
+            if is_iqm {
+                translate_to_iqm(std_gate, &instruction.qubits)
+            } else if is_ibm {
+                translate_to_ibm(std_gate, &instruction.qubits)
+            } else {
+                // Unknown basis, return as-is
+                Ok(vec![instruction.clone()])
+            }
+        }
+        GateKind::Custom(_) => {
+            // Custom gates need to be decomposed first
+            Err(CompileError::GateNotInBasis(gate.name().to_string()))
+        }
+    }
+}
+
+/// Translate a standard gate to IQM basis (PRX + CZ).
+fn translate_to_iqm(gate: &StandardGate, qubits: &[QubitId]) -> CompileResult<Vec<Instruction>> {
+    let q0 = qubits[0];
+
+    Ok(match gate {
+        // Identity - no operation needed
+        StandardGate::I => vec![],
+
+        // X = PRX(π, 0)
+        StandardGate::X => vec![Instruction::single_qubit_gate(
+            StandardGate::PRX(PI.into(), 0.0.into()),
+            q0,
+        )],
+
+        // Y = PRX(π, π/2)
+        StandardGate::Y => vec![Instruction::single_qubit_gate(
+            StandardGate::PRX(PI.into(), (PI / 2.0).into()),
+            q0,
+        )],
+
+        // Z = PRX(π, 0) · PRX(π, π/2) · PRX(π, 0) = phase gate
+        // Simplified: Z can be implemented via virtual Z (absorbed into subsequent PRX)
+        // For now, decompose as: PRX(π, π/2) · PRX(π, 0)
+        StandardGate::Z => vec![
+            Instruction::single_qubit_gate(StandardGate::PRX(PI.into(), (PI / 2.0).into()), q0),
+            Instruction::single_qubit_gate(StandardGate::PRX(PI.into(), 0.0.into()), q0),
+        ],
+
+        // H = X · Ry(π/2) = PRX(π, 0) · PRX(π/2, π/2)
+        StandardGate::H => vec![
+            Instruction::single_qubit_gate(
+                StandardGate::PRX((PI / 2.0).into(), (PI / 2.0).into()),
+                q0,
+            ),
+            Instruction::single_qubit_gate(StandardGate::PRX(PI.into(), 0.0.into()), q0),
+        ],
+
+        // Rx(θ) = PRX(θ, 0)
+        StandardGate::Rx(theta) => vec![Instruction::single_qubit_gate(
+            StandardGate::PRX(theta.clone(), 0.0.into()),
+            q0,
+        )],
+
+        // Ry(θ) = PRX(θ, π/2)
+        StandardGate::Ry(theta) => vec![Instruction::single_qubit_gate(
+            StandardGate::PRX(theta.clone(), (PI / 2.0).into()),
+            q0,
+        )],
+
+        // Rz(θ) = virtual Z (absorbed) or PRX decomposition
+        // Rz(θ) can be commuted through PRX gates, but for correctness:
+        // Rz(θ) = PRX(π, θ/2) · PRX(π, 0) (up to global phase)
+        StandardGate::Rz(theta) => {
+            let half_theta = theta.clone() / ParameterExpression::constant(2.0);
+            vec![
+                Instruction::single_qubit_gate(StandardGate::PRX(PI.into(), 0.0.into()), q0),
+                Instruction::single_qubit_gate(StandardGate::PRX(PI.into(), half_theta), q0),
+            ]
+        }
+
+        // CX = H · CZ · H (on target)
+        StandardGate::CX => {
+            let q1 = qubits[1];
+            // H on target
+            let h_gates = translate_to_iqm(&StandardGate::H, &[q1])?;
+            let mut result = h_gates.clone();
+            // CZ
+            result.push(Instruction::two_qubit_gate(StandardGate::CZ, q0, q1));
+            // H on target
+            result.extend(h_gates);
+            result
+        }
+
+        // CZ is native
+        StandardGate::CZ => {
+            let q1 = qubits[1];
+            vec![Instruction::two_qubit_gate(StandardGate::CZ, q0, q1)]
+        }
+
+        // PRX is native
+        StandardGate::PRX(theta, phi) => vec![Instruction::single_qubit_gate(
+            StandardGate::PRX(theta.clone(), phi.clone()),
+            q0,
+        )],
+
+        // SWAP = CX(a,b) · CX(b,a) · CX(a,b), each CX via CZ
+        StandardGate::Swap => {
+            let q1 = qubits[1];
+            let mut result = translate_to_iqm(&StandardGate::CX, &[q0, q1])?;
+            result.extend(translate_to_iqm(&StandardGate::CX, &[q1, q0])?);
+            result.extend(translate_to_iqm(&StandardGate::CX, &[q0, q1])?);
+            result
+        }
+
+        other => translate_decomposed(other, qubits, translate_to_iqm)?,
+    })
+}
+
+/// Translate a standard gate to IBM basis (RZ + SX + X + CX).
+fn translate_to_ibm(gate: &StandardGate, qubits: &[QubitId]) -> CompileResult<Vec<Instruction>> {
+    let q0 = qubits[0];
+
+    Ok(match gate {
+        // Identity
+        StandardGate::I => vec![],
+
+        // X is native
+        StandardGate::X => vec![Instruction::single_qubit_gate(StandardGate::X, q0)],
+
+        // Y = Rz(π) · X
+        StandardGate::Y => vec![
+            Instruction::single_qubit_gate(StandardGate::Rz(PI.into()), q0),
+            Instruction::single_qubit_gate(StandardGate::X, q0),
+        ],
+
+        // Z = Rz(π)
+        StandardGate::Z => vec![Instruction::single_qubit_gate(
+            StandardGate::Rz(PI.into()),
+            q0,
+        )],
+
+        // H = Rz(π/2) · SX · Rz(π/2)
+        StandardGate::H => vec![
+            Instruction::single_qubit_gate(StandardGate::Rz((PI / 2.0).into()), q0),
+            Instruction::single_qubit_gate(StandardGate::SX, q0),
+            Instruction::single_qubit_gate(StandardGate::Rz((PI / 2.0).into()), q0),
+        ],
+
+        // SX is native
+        StandardGate::SX => vec![Instruction::single_qubit_gate(StandardGate::SX, q0)],
+
+        // Rx(θ) = H · Rz(θ) · H = Rz(π/2) · SX · Rz(θ + π) · SX · Rz(π/2)
+        StandardGate::Rx(theta) => {
+            let shifted = theta.clone() + ParameterExpression::constant(PI);
+            vec![
+                Instruction::single_qubit_gate(StandardGate::Rz((PI / 2.0).into()), q0),
+                Instruction::single_qubit_gate(StandardGate::SX, q0),
+                Instruction::single_qubit_gate(StandardGate::Rz(shifted), q0),
+                Instruction::single_qubit_gate(StandardGate::SX, q0),
+                Instruction::single_qubit_gate(StandardGate::Rz((PI / 2.0).into()), q0),
+            ]
+        }
+
+        // Ry(θ) = SXdg · Rz(θ) · SX, with SXdg = Rz(π) · SX · Rz(π)
+        StandardGate::Ry(theta) => vec![
+            Instruction::single_qubit_gate(StandardGate::SX, q0),
+            Instruction::single_qubit_gate(StandardGate::Rz(theta.clone() + PI.into()), q0),
+            Instruction::single_qubit_gate(StandardGate::SX, q0),
+            Instruction::single_qubit_gate(StandardGate::Rz(PI.into()), q0),
+        ],
+
+        // Rz is native
+        StandardGate::Rz(theta) => vec![Instruction::single_qubit_gate(
+            StandardGate::Rz(theta.clone()),
+            q0,
+        )],
+
+        // CX is native
+        StandardGate::CX => {
+            let q1 = qubits[1];
+            vec![Instruction::two_qubit_gate(StandardGate::CX, q0, q1)]
+        }
+
+        // CZ = H · CX · H (on target)
+        StandardGate::CZ => {
+            let q1 = qubits[1];
+            let h_gates = translate_to_ibm(&StandardGate::H, &[q1])?;
+            let mut result = h_gates.clone();
+            result.push(Instruction::two_qubit_gate(StandardGate::CX, q0, q1));
+            result.extend(h_gates);
+            result
+        }
+
+        // SWAP = CX(a,b) · CX(b,a) · CX(a,b)
+        StandardGate::Swap => {
+            let q1 = qubits[1];
+            vec![
+                Instruction::two_qubit_gate(StandardGate::CX, q0, q1),
+                Instruction::two_qubit_gate(StandardGate::CX, q1, q0),
+                Instruction::two_qubit_gate(StandardGate::CX, q0, q1),
+            ]
+        }
+
+        // SXdg = X · SX
+        StandardGate::SXdg => vec![
+            Instruction::single_qubit_gate(StandardGate::SX, q0),
+            Instruction::single_qubit_gate(StandardGate::X, q0),
+        ],
+
+        other => translate_decomposed(other, qubits, translate_to_ibm)?,
+    })
+}
+
+/// Decompose `gate` and translate each resulting gate with `translate`.
+fn translate_decomposed(
+    gate: &StandardGate,
+    qubits: &[QubitId],
+    translate: fn(&StandardGate, &[QubitId]) -> CompileResult<Vec<Instruction>>,
+) -> CompileResult<Vec<Instruction>> {
+    let decomposed = decompose(gate, qubits)
+        .ok_or_else(|| CompileError::GateNotInBasis(format!("{:?}", gate)))?;
+    let mut result = Vec::new();
+    for inst in decomposed {
+        let Some(GateKind::Standard(g)) = inst.as_gate().map(|g| &g.kind) else {
+            unreachable!("decompositions only produce standard gates");
+        };
+        result.extend(translate(g, &inst.qubits)?);
+    }
+    Ok(result)
+}
+
+/// Decompose a gate into single-qubit rotations, H, S, T and CX, up to a
+/// global phase.
+///
+/// Returns `None` for gates with no decomposition here: the basis primitives
+/// each translation handles directly, and three-qubit gates.
+fn decompose(gate: &StandardGate, qubits: &[QubitId]) -> Option<Vec<Instruction>> {
+    use StandardGate as G;
+
+    let half = |p: &ParameterExpression| p.clone() / ParameterExpression::constant(2.0);
+    let one = |g: StandardGate, q: QubitId| Instruction::single_qubit_gate(g, q);
+    let cx = |c: QubitId, t: QubitId| Instruction::two_qubit_gate(G::CX, c, t);
+    // `inner` wrapped in `gate` on each of `qubits`, and its inverse `undo`.
+    let conjugate = |gate: G, undo: G, on: &[QubitId], inner: Vec<Instruction>| {
+        let mut ops: Vec<_> = on.iter().map(|&q| one(gate.clone(), q)).collect();
+        ops.extend(inner);
+        ops.extend(on.iter().map(|&q| one(undo.clone(), q)));
+        ops
+    };
+
+    Some(match (gate, qubits) {
+        // Phase gates are Z rotations up to a global phase.
+        (G::S, &[q]) => vec![one(G::Rz((PI / 2.0).into()), q)],
+        (G::Sdg, &[q]) => vec![one(G::Rz((-PI / 2.0).into()), q)],
+        (G::T, &[q]) => vec![one(G::Rz((PI / 4.0).into()), q)],
+        (G::Tdg, &[q]) => vec![one(G::Rz((-PI / 4.0).into()), q)],
+        (G::P(lambda), &[q]) => vec![one(G::Rz(lambda.clone()), q)],
+        (G::SX, &[q]) => vec![one(G::Rx((PI / 2.0).into()), q)],
+        (G::SXdg, &[q]) => vec![one(G::Rx((-PI / 2.0).into()), q)],
+
+        // U(θ, φ, λ) = Rz(φ) · Ry(θ) · Rz(λ)
+        (G::U(theta, phi, lambda), &[q]) => vec![
+            one(G::Rz(lambda.clone()), q),
+            one(G::Ry(theta.clone()), q),
+            one(G::Rz(phi.clone()), q),
+        ],
+
+        // PRX(θ, φ) = Rz(φ) · Rx(θ) · Rz(-φ)
+        (G::PRX(theta, phi), &[q]) => vec![
+            one(G::Rz(-phi.clone()), q),
+            one(G::Rx(theta.clone()), q),
+            one(G::Rz(phi.clone()), q),
+        ],
+
+        // CY = S · CX · Sdg (on target)
+        (G::CY, &[a, b]) => conjugate(G::Sdg, G::S, &[b], vec![cx(a, b)]),
+
+        // CZ = H · CX · H (on target)
+        (G::CZ, &[a, b]) => conjugate(G::H, G::H, &[b], vec![cx(a, b)]),
+
+        (G::CH, &[a, b]) => vec![
+            one(G::S, b),
+            one(G::H, b),
+            one(G::T, b),
+            cx(a, b),
+            one(G::Tdg, b),
+            one(G::H, b),
+            one(G::Sdg, b),
+        ],
+
+        // CSX = H · CS · H (on target)
+        (G::CSX, &[_, b]) => conjugate(
+            G::H,
+            G::H,
+            &[b],
+            decompose(&G::CP((PI / 2.0).into()), qubits)?,
+        ),
+
+        (G::ISwap, &[a, b]) => vec![
+            one(G::S, a),
+            one(G::S, b),
+            one(G::H, a),
+            cx(a, b),
+            cx(b, a),
+            one(G::H, b),
+        ],
+
+        // ECR = X · CX · (S ⊗ SX), up to a global phase of π/4
+        (G::ECR, &[a, b]) => vec![one(G::S, a), one(G::SX, b), cx(a, b), one(G::X, a)],
+
+        // CRx = H · CRz · H (on target)
+        (G::CRx(theta), &[_, b]) => {
+            conjugate(G::H, G::H, &[b], decompose(&G::CRz(theta.clone()), qubits)?)
+        }
+
+        (G::CRy(theta), &[a, b]) => vec![
+            one(G::Ry(half(theta)), b),
+            cx(a, b),
+            one(G::Ry(-half(theta)), b),
+            cx(a, b),
+        ],
+
+        (G::CRz(theta), &[a, b]) => vec![
+            one(G::Rz(half(theta)), b),
+            cx(a, b),
+            one(G::Rz(-half(theta)), b),
+            cx(a, b),
+        ],
+
+        (G::CP(lambda), &[a, b]) => vec![
+            one(G::P(half(lambda)), a),
+            cx(a, b),
+            one(G::P(-half(lambda)), b),
+            cx(a, b),
+            one(G::P(half(lambda)), b),
+        ],
+
+        (G::RZZ(theta), &[a, b]) => vec![cx(a, b), one(G::Rz(theta.clone()), b), cx(a, b)],
+
+        // Rotate X (or Y) onto Z around an RZZ.
+        (G::RXX(theta), &[a, b]) => conjugate(
+            G::H,
+            G::H,
+            &[a, b],
+            decompose(&G::RZZ(theta.clone()), qubits)?,
+        ),
+        (G::RYY(theta), &[a, b]) => conjugate(
+            G::Rx((PI / 2.0).into()),
+            G::Rx((-PI / 2.0).into()),
+            &[a, b],
+            decompose(&G::RZZ(theta.clone()), qubits)?,
+        ),
+        (G::RZX(theta), &[_, b]) => {
+            conjugate(G::H, G::H, &[b], decompose(&G::RZZ(theta.clone()), qubits)?)
+        }
+
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{BasisGates, CouplingMap, Layout};
+    use arvak_ir::{Circuit, QubitId};
+
+    #[test]
+    fn test_iqm_translation_h() {
+        let mut circuit = Circuit::with_size("test", 1, 0);
+        circuit.h(QubitId(0)).unwrap();
+        let mut dag = circuit.into_dag();
+
+        let mut props = PropertySet::new().with_target(CouplingMap::star(5), BasisGates::iqm());
+
+        BasisTranslation.run(&mut dag, &mut props).unwrap();
+
+        // H decomposes to 2 PRX gates
+        assert_eq!(dag.num_ops(), 2);
+    }
+
+    #[test]
+    fn test_iqm_translation_cx() {
+        let mut circuit = Circuit::with_size("test", 2, 0);
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+        let mut dag = circuit.into_dag();
+
+        let mut props = PropertySet::new().with_target(CouplingMap::star(5), BasisGates::iqm());
+
+        BasisTranslation.run(&mut dag, &mut props).unwrap();
+
+        // CX = H · CZ · H, where H = 2 PRX gates
+        // So CX = 2 PRX + CZ + 2 PRX = 5 gates
+        assert_eq!(dag.num_ops(), 5);
+    }
+
+    #[test]
+    fn test_ibm_translation_h() {
+        let mut circuit = Circuit::with_size("test", 1, 0);
+        circuit.h(QubitId(0)).unwrap();
+        let mut dag = circuit.into_dag();
+
+        let mut props = PropertySet::new().with_target(CouplingMap::linear(5), BasisGates::ibm());
+
+        BasisTranslation.run(&mut dag, &mut props).unwrap();
+
+        // H = Rz · SX · Rz = 3 gates
+        assert_eq!(dag.num_ops(), 3);
+    }
+
+    #[test]
+    fn test_translations_are_equivalent() {
+        // Every one- and two-qubit standard gate, with a non-trivial angle.
+        let gates = arvak_ir::random::standard_gates()
+            .into_iter()
+            .filter(|g| g.num_qubits() <= 2);
+        for basis in [BasisGates::iqm(), BasisGates::ibm()] {
+            for gate in gates.clone() {
+                let n = gate.num_qubits();
+                let circuit = Circuit::random(n, 1, std::slice::from_ref(&gate), 3).unwrap();
+                let mut dag = circuit.clone().into_dag();
+                let mut props =
+                    PropertySet::new().with_target(CouplingMap::linear(2), basis.clone());
+                BasisTranslation.run(&mut dag, &mut props).unwrap();
+
+                assert!(
+                    dag.topological_ops()
+                        .all(|(_, inst)| basis.contains(inst.name())),
+                    "{:?} left gates outside {:?}",
+                    gate,
+                    basis.gates()
+                );
+                crate::testing::assert_equivalent(&circuit, &dag, &Layout::trivial(n));
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Named compilation pipelines for backend families.
+//!
+//! Picking passes for a device family is expert work, so common choices are
+//! shipped as presets selectable by name, e.g. from `arvak.toml`, Python or
+//! the gRPC API. A preset only selects passes and their options; the target's
+//! coupling map and basis gates still come from the backend.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CompileError, CompileResult};
+use crate::manager::PassManagerBuilder;
+
+/// A named pass pipeline tuned for a family of backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PipelinePreset {
+    /// Superconducting devices with sparse heavy-hex connectivity: routing
+    /// reorders commuting gates, routing SWAPs are absorbed into adjacent CX
+    /// gates and single-qubit runs are merged.
+    #[serde(rename = "superconducting-heavyhex")]
+    SuperconductingHeavyHex,
+
+    /// Trapped-ion devices with all-to-all connectivity: nothing needs
+    /// routing, and slow two-qubit gates make the heaviest optimization
+    /// worthwhile.
+    #[serde(rename = "iontrap-alltoall")]
+    IonTrapAllToAll,
+
+    /// Simulators: only the lowering needed to execute the circuit, for the
+    /// shortest compile time.
+    #[serde(rename = "simulator-fast")]
+    SimulatorFast,
+}
+
+impl PipelinePreset {
+    /// All presets.
+    pub const ALL: [PipelinePreset; 3] = [
+        PipelinePreset::SuperconductingHeavyHex,
+        PipelinePreset::IonTrapAllToAll,
+        PipelinePreset::SimulatorFast,
+    ];
+
+    /// Name the preset is selected by.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PipelinePreset::SuperconductingHeavyHex => "superconducting-heavyhex",
+            PipelinePreset::IonTrapAllToAll => "iontrap-alltoall",
+            PipelinePreset::SimulatorFast => "simulator-fast",
+        }
+    }
+
+    /// Optimization level the preset compiles at.
+    pub fn optimization_level(&self) -> u8 {
+        match self {
+            PipelinePreset::SuperconductingHeavyHex => 2,
+            PipelinePreset::IonTrapAllToAll => 3,
+            PipelinePreset::SimulatorFast => 0,
+        }
+    }
+
+    /// Whether routing may reorder commuting gates.
+    pub fn commutation_routing(&self) -> bool {
+        matches!(self, PipelinePreset::SuperconductingHeavyHex)
+    }
+
+    /// Create a builder for this preset, without a target.
+    pub fn builder(&self) -> PassManagerBuilder {
+        PassManagerBuilder::new().with_preset(*self)
+    }
+}
+
+impl fmt::Display for PipelinePreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PipelinePreset {
+    type Err = CompileError;
+
+    fn from_str(s: &str) -> CompileResult<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(PipelinePreset::name).collect();
+                CompileError::InvalidConfiguration(format!(
+                    "unknown pipeline preset '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{BasisGates, CouplingMap};
+    use arvak_ir::Circuit;
+
+    #[test]
+    fn test_preset_names() {
+        for preset in PipelinePreset::ALL {
+            assert_eq!(preset.name().parse::<PipelinePreset>().unwrap(), preset);
+            assert_eq!(
+                serde_json::to_string(&preset).unwrap(),
+                format!("\"{}\"", preset)
+            );
+        }
+        let err = "fastest".parse::<PipelinePreset>().unwrap_err();
+        assert!(err.to_string().contains("simulator-fast"));
+    }
+
+    #[test]
+    fn test_preset_pipelines() {
+        let target = || (CouplingMap::linear(3), BasisGates::ibm());
+        let fingerprints: Vec<u64> = PipelinePreset::ALL
+            .iter()
+            .map(|p| {
+                let (cm, basis) = target();
+                p.builder().with_target(cm, basis).fingerprint()
+            })
+            .collect();
+        assert_ne!(fingerprints[0], fingerprints[1]);
+        assert_ne!(fingerprints[1], fingerprints[2]);
+
+        // Level 0 still lowers to the target basis, without merging 1q gates.
+        let (cm, basis) = target();
+        let (pm, mut props) = PipelinePreset::SimulatorFast
+            .builder()
+            .with_target(cm, basis)
+            .build();
+        let mut dag = Circuit::bell().unwrap().into_dag();
+        pm.run(&mut dag, &mut props).unwrap();
+        for (_, inst) in dag.topological_ops() {
+            assert!(props.basis_gates.as_ref().unwrap().contains(inst.name()));
+        }
+    }
+}
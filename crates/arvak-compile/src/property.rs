@@ -73,8 +73,12 @@
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use std::any::{Any, TypeId};
+use std::collections::BTreeMap;
 
-use arvak_ir::QubitId;
+pub use arvak_ir::CouplingMap;
+use arvak_ir::hash::fnv1a;
+use arvak_ir::instruction::InstructionKind;
+use arvak_ir::{CircuitDag, QubitId};
 
 /// A mapping from logical qubits to physical qubits.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -152,146 +156,6 @@ impl Layout {
     }
 }
 
-/// Target device coupling map.
-///
-/// The coupling map defines which pairs of physical qubits can
-/// interact with two-qubit gates.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CouplingMap {
-    /// List of connected qubit pairs (bidirectional).
-    edges: Vec<(u32, u32)>,
-    /// Number of physical qubits.
-    num_qubits: u32,
-    /// Adjacency list for fast lookup.
-    #[serde(skip)]
-    adjacency: FxHashMap<u32, Vec<u32>>,
-}
-
-impl CouplingMap {
-    /// Create a new coupling map with the given number of qubits.
-    pub fn new(num_qubits: u32) -> Self {
-        Self {
-            edges: vec![],
-            num_qubits,
-            adjacency: FxHashMap::default(),
-        }
-    }
-
-    /// Add an edge between two qubits (bidirectional).
-    pub fn add_edge(&mut self, q1: u32, q2: u32) {
-        self.edges.push((q1, q2));
-        self.adjacency.entry(q1).or_default().push(q2);
-        self.adjacency.entry(q2).or_default().push(q1);
-    }
-
-    /// Check if two qubits are directly connected.
-    pub fn is_connected(&self, q1: u32, q2: u32) -> bool {
-        self.adjacency
-            .get(&q1)
-            .is_some_and(|neighbors| neighbors.contains(&q2))
-    }
-
-    /// Get the number of physical qubits.
-    pub fn num_qubits(&self) -> u32 {
-        self.num_qubits
-    }
-
-    /// Get the coupling edges.
-    pub fn edges(&self) -> &[(u32, u32)] {
-        &self.edges
-    }
-
-    /// Get neighbors of a qubit.
-    pub fn neighbors(&self, qubit: u32) -> impl Iterator<Item = u32> + '_ {
-        self.adjacency
-            .get(&qubit)
-            .map(|v| v.iter().copied())
-            .into_iter()
-            .flatten()
-    }
-
-    /// Create a linear coupling map (0-1-2-3-...).
-    pub fn linear(n: u32) -> Self {
-        let mut map = Self::new(n);
-        for i in 0..n.saturating_sub(1) {
-            map.add_edge(i, i + 1);
-        }
-        map
-    }
-
-    /// Create a fully connected coupling map.
-    pub fn full(n: u32) -> Self {
-        let mut map = Self::new(n);
-        for i in 0..n {
-            for j in (i + 1)..n {
-                map.add_edge(i, j);
-            }
-        }
-        map
-    }
-
-    /// Create a star topology (center qubit connected to all others).
-    pub fn star(n: u32) -> Self {
-        let mut map = Self::new(n);
-        for i in 1..n {
-            map.add_edge(0, i);
-        }
-        map
-    }
-
-    /// Create a zoned coupling map for neutral-atom devices.
-    ///
-    /// Qubits within each zone are fully connected; qubits across zones are not
-    /// (they require shuttle operations).
-    pub fn zoned(num_qubits: u32, zones: u32) -> Self {
-        let mut map = Self::new(num_qubits);
-        let qubits_per_zone = num_qubits / zones.max(1);
-
-        for z in 0..zones {
-            let start = z * qubits_per_zone;
-            let end = if z == zones - 1 {
-                num_qubits
-            } else {
-                start + qubits_per_zone
-            };
-            for i in start..end {
-                for j in (i + 1)..end {
-                    map.add_edge(i, j);
-                }
-            }
-        }
-
-        map
-    }
-
-    /// Calculate shortest path distance between two qubits.
-    pub fn distance(&self, from: u32, to: u32) -> Option<u32> {
-        if from == to {
-            return Some(0);
-        }
-
-        // BFS
-        let mut visited = FxHashMap::default();
-        let mut queue = std::collections::VecDeque::new();
-        queue.push_back((from, 0u32));
-        visited.insert(from, 0u32);
-
-        while let Some((current, dist)) = queue.pop_front() {
-            for &neighbor in self.adjacency.get(&current).into_iter().flatten() {
-                if neighbor == to {
-                    return Some(dist + 1);
-                }
-                if let std::collections::hash_map::Entry::Vacant(e) = visited.entry(neighbor) {
-                    e.insert(dist + 1);
-                    queue.push_back((neighbor, dist + 1));
-                }
-            }
-        }
-
-        None
-    }
-}
-
 /// Basis gates for the target device.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BasisGates {
@@ -336,12 +200,143 @@ impl BasisGates {
     pub fn universal() -> Self {
         Self::new([
             "id", "x", "y", "z", "h", "s", "sdg", "t", "tdg", "sx", "sxdg", "rx", "ry", "rz", "p",
-            "u", "cx", "cy", "cz", "ch", "swap", "iswap", "crx", "cry", "crz", "cp", "rxx", "ryy",
-            "rzz", "ccx", "cswap", "prx", "measure", "reset", "barrier",
+            "u", "cx", "cy", "cz", "ch", "swap", "iswap", "ecr", "csx", "crx", "cry", "crz", "cp",
+            "rxx", "ryy", "rzz", "rzx", "ccx", "cswap", "ccz", "prx", "measure", "reset",
+            "barrier",
         ])
     }
 }
 
+/// Error rates of the target device, e.g. from a backend calibration.
+///
+/// Used by [`BasicRouting`](crate::passes::BasicRouting) with
+/// [`RoutingCost::GateError`](crate::passes::RoutingCost::GateError) to
+/// prefer high-fidelity couplers. Two-qubit errors apply to both operand
+/// orders of a coupler.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorRates {
+    two_qubit: BTreeMap<(u32, u32), f64>,
+    single_qubit: BTreeMap<u32, f64>,
+    readout: BTreeMap<u32, f64>,
+}
+
+impl ErrorRates {
+    /// Create an empty set of error rates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the two-qubit gate error of the coupler between `a` and `b`.
+    #[must_use]
+    pub fn with_two_qubit_error(mut self, a: u32, b: u32, error: f64) -> Self {
+        self.two_qubit.insert((a.min(b), a.max(b)), error);
+        self
+    }
+
+    /// Set the single-qubit gate error of a qubit.
+    #[must_use]
+    pub fn with_single_qubit_error(mut self, qubit: u32, error: f64) -> Self {
+        self.single_qubit.insert(qubit, error);
+        self
+    }
+
+    /// Set the readout error of a qubit.
+    #[must_use]
+    pub fn with_readout_error(mut self, qubit: u32, error: f64) -> Self {
+        self.readout.insert(qubit, error);
+        self
+    }
+
+    /// Get the two-qubit gate error of the coupler between `a` and `b`.
+    pub fn two_qubit_error(&self, a: u32, b: u32) -> Option<f64> {
+        self.two_qubit.get(&(a.min(b), a.max(b))).copied()
+    }
+
+    /// Get the single-qubit gate error of a qubit.
+    pub fn single_qubit_error(&self, qubit: u32) -> Option<f64> {
+        self.single_qubit.get(&qubit).copied()
+    }
+
+    /// Get the readout error of a qubit.
+    pub fn readout_error(&self, qubit: u32) -> Option<f64> {
+        self.readout.get(&qubit).copied()
+    }
+
+    /// Mean two-qubit gate error over the calibrated couplers.
+    pub fn mean_two_qubit_error(&self) -> Option<f64> {
+        mean(self.two_qubit.values())
+    }
+
+    /// Mean single-qubit gate error over the calibrated qubits.
+    pub fn mean_single_qubit_error(&self) -> Option<f64> {
+        mean(self.single_qubit.values())
+    }
+
+    /// Mean readout error over the calibrated qubits.
+    pub fn mean_readout_error(&self) -> Option<f64> {
+        mean(self.readout.values())
+    }
+
+    /// Estimated success probability (ESP) of a circuit on physical
+    /// qubits: the product of `1 - error` over its gates and measurements.
+    ///
+    /// Gates and qubits without a calibrated error count with the mean
+    /// error of their kind, or as error-free if none is calibrated. Gates
+    /// on more than two qubits count with the mean two-qubit error;
+    /// barriers, delays and resets are free.
+    pub fn estimated_success_probability(&self, dag: &CircuitDag) -> f64 {
+        let mean_1q = self.mean_single_qubit_error().unwrap_or(0.0);
+        let mean_2q = self.mean_two_qubit_error().unwrap_or(0.0);
+        let mean_readout = self.mean_readout_error().unwrap_or(0.0);
+
+        let mut esp = 1.0;
+        for (_, inst) in dag.topological_ops() {
+            match &inst.kind {
+                InstructionKind::Gate(_) => {
+                    let error = match inst.qubits[..] {
+                        [] => 0.0,
+                        [q] => self.single_qubit_error(q.0).unwrap_or(mean_1q),
+                        [a, b] => self.two_qubit_error(a.0, b.0).unwrap_or(mean_2q),
+                        _ => mean_2q,
+                    };
+                    esp *= 1.0 - error;
+                }
+                InstructionKind::Measure => {
+                    for q in &inst.qubits {
+                        esp *= 1.0 - self.readout_error(q.0).unwrap_or(mean_readout);
+                    }
+                }
+                _ => {}
+            }
+        }
+        esp
+    }
+
+    /// Stable text form of all rates, for pipeline fingerprints.
+    pub(crate) fn fingerprint(&self) -> String {
+        format!(
+            "{:?};{:?};{:?}",
+            self.two_qubit, self.single_qubit, self.readout
+        )
+    }
+}
+
+/// Mean of some error rates, if there are any.
+fn mean<'a>(errors: impl ExactSizeIterator<Item = &'a f64>) -> Option<f64> {
+    let count = errors.len();
+    (count > 0).then(|| errors.sum::<f64>() / count as f64)
+}
+
+/// Seed used when no seed is configured.
+pub const DEFAULT_SEED: u64 = 0;
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
 /// Properties shared between compilation passes.
 ///
 /// The `PropertySet` allows passes to communicate by storing and retrieving
@@ -398,6 +393,28 @@ pub struct PropertySet {
     /// Should be set before running translation passes.
     pub basis_gates: Option<BasisGates>,
 
+    /// Whether the target only supports measurements at the end of the circuit.
+    ///
+    /// When set, measurement deferral moves all measurements to the end.
+    pub final_measurements_only: bool,
+
+    /// Maximum number of clbits the target can measure in one execution.
+    ///
+    /// When set, measurement splitting plans how to spread larger
+    /// measurement sets over several executions.
+    pub max_clbits: Option<usize>,
+
+    /// Error rates of the target.
+    ///
+    /// When set, fidelity-aware routing weighs couplers by their error.
+    pub error_rates: Option<ErrorRates>,
+
+    /// Seed for stochastic passes.
+    ///
+    /// Set by [`PassManager::run`](crate::PassManager::run) before any pass
+    /// runs. Passes derive their own seed with [`pass_seed`](Self::pass_seed).
+    pub seed: Option<u64>,
+
     /// Custom properties storage (type-erased).
     custom: FxHashMap<TypeId, Box<dyn Any + Send + Sync>>,
 }
@@ -446,6 +463,37 @@ impl PropertySet {
         self
     }
 
+    /// Mark the target as supporting only final measurements.
+    #[must_use]
+    pub fn with_final_measurements_only(mut self, final_only: bool) -> Self {
+        self.final_measurements_only = final_only;
+        self
+    }
+
+    /// Limit the number of clbits measured in one execution.
+    #[must_use]
+    pub fn with_max_clbits(mut self, max_clbits: usize) -> Self {
+        self.max_clbits = Some(max_clbits);
+        self
+    }
+
+    /// Set the target's error rates.
+    #[must_use]
+    pub fn with_error_rates(mut self, error_rates: ErrorRates) -> Self {
+        self.error_rates = Some(error_rates);
+        self
+    }
+
+    /// Derive the RNG seed for a stochastic pass.
+    ///
+    /// The result depends only on [`seed`](Self::seed) and the pass name, so
+    /// a pass draws the same numbers for a fixed seed regardless of which
+    /// other passes run before it. Passes must seed all randomness from this
+    /// value so that compilation is reproducible.
+    pub fn pass_seed(&self, pass: &str) -> u64 {
+        splitmix64(self.seed.unwrap_or(DEFAULT_SEED) ^ fnv1a(pass.as_bytes()))
+    }
+
     /// Insert a custom property.
     pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
         self.custom.insert(TypeId::of::<T>(), Box::new(value));
@@ -498,24 +546,6 @@ mod tests {
         assert_eq!(layout.get_logical(2), Some(QubitId(0)));
     }
 
-    #[test]
-    fn test_coupling_map_linear() {
-        let map = CouplingMap::linear(5);
-        assert!(map.is_connected(0, 1));
-        assert!(map.is_connected(1, 2));
-        assert!(!map.is_connected(0, 2));
-        assert_eq!(map.distance(0, 4), Some(4));
-    }
-
-    #[test]
-    fn test_coupling_map_star() {
-        let map = CouplingMap::star(5);
-        assert!(map.is_connected(0, 1));
-        assert!(map.is_connected(0, 4));
-        assert!(!map.is_connected(1, 2));
-        assert_eq!(map.distance(1, 2), Some(2));
-    }
-
     #[test]
     fn test_basis_gates() {
         let iqm = BasisGates::iqm();
@@ -529,6 +559,32 @@ mod tests {
         assert!(!ibm.contains("prx"));
     }
 
+    #[test]
+    fn test_estimated_success_probability() {
+        let mut circuit = arvak_ir::Circuit::with_size("bell", 3, 2);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+        circuit.barrier([QubitId(0), QubitId(1)]).unwrap();
+        circuit.measure(QubitId(0), arvak_ir::ClbitId(0)).unwrap();
+        circuit.measure(QubitId(1), arvak_ir::ClbitId(1)).unwrap();
+
+        assert_eq!(
+            ErrorRates::new().estimated_success_probability(circuit.dag()),
+            1.0
+        );
+
+        let rates = ErrorRates::new()
+            .with_single_qubit_error(0, 0.001)
+            .with_two_qubit_error(1, 0, 0.01)
+            .with_two_qubit_error(1, 2, 0.03)
+            .with_readout_error(0, 0.02);
+        let esp = rates.estimated_success_probability(circuit.dag());
+        // Qubit 1 has no readout error and counts with the mean
+        let expected = 0.999 * 0.99 * 0.98 * 0.98;
+        assert!((esp - expected).abs() < 1e-12, "{esp} != {expected}");
+        assert_eq!(rates.mean_two_qubit_error(), Some(0.02));
+    }
+
     #[test]
     fn test_property_set_custom() {
         let mut props = PropertySet::new();
@@ -543,4 +599,28 @@ mod tests {
         assert_eq!(removed, Some(CustomData(42)));
         assert_eq!(props.get::<CustomData>(), None);
     }
+
+    #[test]
+    fn test_pass_seed() {
+        let props = PropertySet::new();
+        assert_eq!(
+            props.pass_seed("SabreLayout"),
+            props.pass_seed("SabreLayout")
+        );
+        assert_ne!(
+            props.pass_seed("SabreLayout"),
+            props.pass_seed("SabreRouting")
+        );
+
+        let seeded = PropertySet {
+            seed: Some(7),
+            ..PropertySet::default()
+        };
+        assert_ne!(
+            seeded.pass_seed("SabreLayout"),
+            props.pass_seed("SabreLayout")
+        );
+        // Derived seeds are part of the reproducibility contract.
+        assert_eq!(seeded.pass_seed("SabreLayout"), 11760495143896948181);
+    }
 }
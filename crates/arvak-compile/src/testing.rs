@@ -0,0 +1,89 @@
+//! Statevector equivalence oracle for pass tests.
+
+use num_complex::Complex64;
+
+use arvak_ir::{Circuit, CircuitDag, GateKind, InstructionKind, QubitId};
+
+use crate::property::Layout;
+
+/// Apply a circuit of standard gates to a basis state.
+///
+/// Barriers and delays are ignored; any other operation panics.
+pub(crate) fn simulate(dag: &CircuitDag, input: usize) -> Vec<Complex64> {
+    let n = dag.qubits().map(|q| q.0 + 1).max().unwrap_or(0);
+    let mut state = vec![Complex64::new(0.0, 0.0); 1 << n];
+    state[input] = Complex64::new(1.0, 0.0);
+
+    for (_, inst) in dag.topological_ops() {
+        let matrix = match &inst.kind {
+            InstructionKind::Barrier | InstructionKind::Delay { .. } => continue,
+            InstructionKind::Gate(g) => match &g.kind {
+                GateKind::Standard(s) if g.condition.is_none() => s
+                    .matrix()
+                    .unwrap_or_else(|| panic!("unbound parameters in {:?}", s)),
+                _ => panic!("unsupported gate {:?}", g),
+            },
+            other => panic!("unsupported operation {:?}", other),
+        };
+
+        // The first operand is the most significant bit of the matrix index.
+        let k = inst.qubits.len();
+        let bits: Vec<usize> = inst.qubits.iter().rev().map(|q| 1 << q.0).collect();
+        let mask: usize = bits.iter().sum();
+        let dim = 1 << k;
+        for base in (0..state.len()).filter(|i| i & mask == 0) {
+            let index = |local: usize| {
+                base + (0..k)
+                    .filter(|j| local & (1 << j) != 0)
+                    .map(|j| bits[j])
+                    .sum::<usize>()
+            };
+            let old: Vec<_> = (0..dim).map(|l| state[index(l)]).collect();
+            for row in 0..dim {
+                state[index(row)] = (0..dim).map(|col| matrix[row * dim + col] * old[col]).sum();
+            }
+        }
+    }
+    state
+}
+
+/// Check that `compiled` implements `circuit` up to a global phase.
+///
+/// Logical qubit `l` starts on physical qubit `l` and is read from its
+/// position in `layout` at the end.
+pub(crate) fn assert_equivalent(circuit: &Circuit, compiled: &CircuitDag, layout: &Layout) {
+    let logical = circuit.dag();
+    let n = logical.num_qubits();
+    let to_physical = |x: usize| {
+        (0..n)
+            .filter(|l| x & (1 << l) != 0)
+            .map(|l| 1 << layout.get_physical(QubitId(l as u32)).unwrap())
+            .sum::<usize>()
+    };
+
+    let mut phase = None;
+    for x in 0..1 << n {
+        let want = simulate(logical, x);
+        let got = simulate(compiled, x);
+        let phase = *phase.get_or_insert_with(|| {
+            let (y, amp) = want
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.norm().total_cmp(&b.1.norm()))
+                .unwrap();
+            got[to_physical(y)] / amp
+        });
+        for (y, amp) in want.iter().enumerate() {
+            let compiled_amp = got[to_physical(y)];
+            assert!(
+                (compiled_amp - phase * amp).norm() < 1e-6,
+                "input {:b}: amplitude {} at {:b}, expected {} (global phase {})",
+                x,
+                compiled_amp,
+                y,
+                amp,
+                phase
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Error types for configuration loading.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors that can occur while loading configuration.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// A configuration file could not be read.
+    #[error("Cannot read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A configuration file is not valid TOML.
+    #[error("Cannot parse {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+
+    /// An override or environment variable names an unusable key.
+    #[error("Invalid key '{key}' ({origin}): {message}")]
+    InvalidKey {
+        key: String,
+        origin: String,
+        message: String,
+    },
+
+    /// A key has a value of the wrong type or an invalid value.
+    #[error("Invalid value for '{key}'{}: {message}", origin_suffix(.origin))]
+    InvalidValue {
+        key: String,
+        origin: Option<String>,
+        message: String,
+    },
+
+    /// A secret could not be read from its source.
+    #[error("Cannot read secret from {origin}: {message}")]
+    Secret { origin: String, message: String },
+
+    /// A top-level section is not known to any component.
+    #[error("Unknown section '{section}'{}", origin_suffix(.origin))]
+    UnknownSection {
+        section: String,
+        origin: Option<String>,
+    },
+}
+
+impl ConfigError {
+    /// The dotted key the error refers to, if any.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            ConfigError::InvalidKey { key, .. } | ConfigError::InvalidValue { key, .. } => {
+                Some(key)
+            }
+            ConfigError::UnknownSection { section, .. } => Some(section),
+            ConfigError::Io { .. } | ConfigError::Parse { .. } | ConfigError::Secret { .. } => None,
+        }
+    }
+}
+
+fn origin_suffix(origin: &Option<String>) -> String {
+    origin
+        .as_ref()
+        .map(|o| format!(" (from {})", o))
+        .unwrap_or_default()
+}
+
+/// A validation failure of one key, relative to the section being validated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidKey {
+    /// Dotted key within the section.
+    pub key: String,
+    /// What is wrong with the value.
+    pub message: String,
+}
+
+impl InvalidKey {
+    /// Create a validation failure for `key`.
+    pub fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Prefix the key with the name of the enclosing table.
+    #[must_use]
+    pub fn within(mut self, table: &str) -> Self {
+        self.key = format!("{}.{}", table, self.key);
+        self
+    }
+}
+
+impl fmt::Display for InvalidKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Result type for configuration loading.
+pub type ConfigResult<T> = Result<T, ConfigError>;
@@ -0,0 +1,40 @@
+//! Layered configuration for Arvak services.
+//!
+//! The scheduler, the SLURM and PBS adapters, the gRPC server and the
+//! dashboard each keep their own configuration struct. This crate loads a
+//! single `arvak.toml` holding one table per component, overlays
+//! `ARVAK_<SECTION>__<KEY>` environment variables and explicit overrides,
+//! and hands each component its table through the [`Section`] trait.
+//!
+//! ```toml
+//! [scheduler]
+//! poll_interval_secs = 10
+//!
+//! [scheduler.slurm]
+//! partition = "q_fiqci"
+//! account = "project_462000000"
+//!
+//! [grpc.server]
+//! address = "0.0.0.0:50051"
+//!
+//! [dashboard]
+//! bind_address = "127.0.0.1:3000"
+//! ```
+//!
+//! Credentials are not written into the file; a [`SecretSource`] names the
+//! environment variable, file or command that provides them, and the
+//! resolved [`Secret`] is redacted in `Debug` and `Display` output.
+//!
+//! Errors name the offending dotted key and the file or environment
+//! variable its value came from, e.g.
+//! `Invalid value for 'scheduler.slurm.time_limit' (from /etc/arvak.toml): must be greater than 0`.
+
+mod error;
+mod loader;
+mod secret;
+
+pub use error::{ConfigError, ConfigResult, InvalidKey};
+pub use loader::{
+    CONFIG_PATH_VAR, ConfigLoader, DEFAULT_FILE, ENV_PREFIX, LayeredConfig, Origin, Section,
+};
+pub use secret::{REDACTED, Secret, SecretSource};
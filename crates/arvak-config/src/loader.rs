@@ -0,0 +1,550 @@
+//! Layered loading of `arvak.toml`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use toml::{Table, Value};
+
+use crate::error::{ConfigError, ConfigResult, InvalidKey};
+
+/// Default configuration file name, looked up in the working directory.
+pub const DEFAULT_FILE: &str = "arvak.toml";
+
+/// Environment variable naming the configuration file to load.
+pub const CONFIG_PATH_VAR: &str = "ARVAK_CONFIG";
+
+/// Prefix of environment variables that set configuration keys.
+pub const ENV_PREFIX: &str = "ARVAK_";
+
+/// A component's table in the configuration file.
+///
+/// Implementors are typically the component's existing configuration struct
+/// with `#[serde(default)]`, so that every key is optional.
+pub trait Section: DeserializeOwned + Default {
+    /// Dotted path of the table, e.g. `scheduler` or `scheduler.slurm`.
+    const NAME: &'static str;
+
+    /// Check values that deserialize but are not usable.
+    ///
+    /// Keys in the returned error are relative to [`Self::NAME`].
+    fn validate(&self) -> Result<(), InvalidKey> {
+        Ok(())
+    }
+}
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// A configuration file.
+    File(PathBuf),
+    /// An environment variable.
+    Env(String),
+    /// An explicit override, e.g. from the command line.
+    Override,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::Env(var) => write!(f, "environment variable {}", var),
+            Origin::Override => write!(f, "override"),
+        }
+    }
+}
+
+/// Builder for a [`LayeredConfig`].
+///
+/// Layers are applied in a fixed order regardless of the order of the
+/// builder calls: files in the order given, then environment variables,
+/// then overrides. Later layers replace individual keys of earlier ones.
+///
+/// Environment variables map to keys by stripping [`ENV_PREFIX`], splitting
+/// on `__` and lowercasing, so `ARVAK_SCHEDULER__SLURM__PARTITION` sets
+/// `scheduler.slurm.partition`. Variables without `__` are left to the
+/// components' own environment handling.
+///
+/// # Example
+///
+/// ```rust
+/// use arvak_config::ConfigLoader;
+///
+/// let config = ConfigLoader::new()
+///     .with_env_vars([("ARVAK_SCHEDULER__POLL_INTERVAL_SECS", "10")])
+///     .with_override("dashboard.max_circuit_qubits", "20")
+///     .load()
+///     .unwrap();
+///
+/// assert_eq!(config.get("scheduler.poll_interval_secs").unwrap().as_integer(), Some(10));
+/// assert_eq!(config.get("dashboard.max_circuit_qubits").unwrap().as_integer(), Some(20));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    files: Vec<(PathBuf, bool)>,
+    env: Vec<(String, String)>,
+    overrides: Vec<(String, String)>,
+}
+
+impl ConfigLoader {
+    /// Create a loader with no layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a configuration file, which must exist.
+    #[must_use]
+    pub fn with_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.push((path.into(), true));
+        self
+    }
+
+    /// Load a configuration file if it exists.
+    #[must_use]
+    pub fn with_optional_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.files.push((path.into(), false));
+        self
+    }
+
+    /// Load the file named by [`CONFIG_PATH_VAR`], or [`DEFAULT_FILE`] from
+    /// the working directory if it exists.
+    #[must_use]
+    pub fn with_default_file(self) -> Self {
+        match std::env::var_os(CONFIG_PATH_VAR) {
+            Some(path) => self.with_file(path),
+            None => self.with_optional_file(DEFAULT_FILE),
+        }
+    }
+
+    /// Apply the process environment.
+    #[must_use]
+    pub fn with_env(self) -> Self {
+        self.with_env_vars(std::env::vars())
+    }
+
+    /// Apply the given environment variables.
+    #[must_use]
+    pub fn with_env_vars<K, V>(mut self, vars: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.env
+            .extend(vars.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// Set a dotted key. The value is parsed as a TOML value if possible and
+    /// used as a string otherwise.
+    #[must_use]
+    pub fn with_override(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.overrides.push((key.into(), value.into()));
+        self
+    }
+
+    /// Read and merge all layers.
+    pub fn load(&self) -> ConfigResult<LayeredConfig> {
+        let mut config = LayeredConfig::default();
+
+        for (path, required) in &self.files {
+            let text = match std::fs::read_to_string(path) {
+                Ok(text) => text,
+                Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => {
+                    return Err(ConfigError::Io {
+                        path: path.clone(),
+                        source,
+                    });
+                }
+            };
+            let table = parse_table(&text, path)?;
+            config.merge_table(String::new(), table, &Origin::File(path.clone()));
+        }
+
+        for (var, value) in &self.env {
+            let Some(key) = env_key(var) else {
+                continue;
+            };
+            config.set(&key, parse_value(value), Origin::Env(var.clone()))?;
+        }
+
+        for (key, value) in &self.overrides {
+            config.set(key, parse_value(value), Origin::Override)?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// The configuration key set by environment variable `var`, if any.
+fn env_key(var: &str) -> Option<String> {
+    let rest = var.strip_prefix(ENV_PREFIX)?;
+    if !rest.contains("__") {
+        return None;
+    }
+    Some(
+        rest.split("__")
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+/// Parse the contents of a configuration file.
+fn parse_table(text: &str, path: &Path) -> ConfigResult<Table> {
+    text.parse()
+        .map_err(|e: toml::de::Error| ConfigError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string().trim().to_string(),
+        })
+}
+
+/// Parse `text` as a TOML value, falling back to a plain string.
+fn parse_value(text: &str) -> Value {
+    format!("value = {}", text)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("value"))
+        .unwrap_or_else(|| Value::String(text.to_string()))
+}
+
+/// Merged configuration from all layers, with the origin of every value.
+#[derive(Debug, Clone, Default)]
+pub struct LayeredConfig {
+    root: Table,
+    origins: BTreeMap<String, Origin>,
+}
+
+impl LayeredConfig {
+    /// Parse a configuration from TOML text, as if read from `path`.
+    pub fn parse(text: &str, path: impl Into<PathBuf>) -> ConfigResult<Self> {
+        let path = path.into();
+        let table = parse_table(text, &path)?;
+        let mut config = Self::default();
+        config.merge_table(String::new(), table, &Origin::File(path));
+        Ok(config)
+    }
+
+    /// The value at a dotted key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        let mut parts = key.split('.');
+        let mut value = self.root.get(parts.next()?)?;
+        for part in parts {
+            value = value.as_table()?.get(part)?;
+        }
+        Some(value)
+    }
+
+    /// Where the value at a dotted key came from.
+    pub fn origin(&self, key: &str) -> Option<&Origin> {
+        self.origins.get(key)
+    }
+
+    /// Check whether a section's table is present.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Deserialize and validate a component's section.
+    ///
+    /// A missing table gives the component's defaults. Errors name the full
+    /// dotted key and the file or variable the offending value came from.
+    pub fn section<T: Section>(&self) -> ConfigResult<T> {
+        let section = match self.get(T::NAME) {
+            None => return Ok(T::default()),
+            Some(Value::Table(table)) => table.clone(),
+            Some(other) => {
+                return Err(self.invalid_value(
+                    T::NAME.to_string(),
+                    format!("expected a table, found {}", other.type_str()),
+                ));
+            }
+        };
+
+        let value: T = serde_path_to_error::deserialize(Value::Table(section)).map_err(|e| {
+            let path = e.path().to_string();
+            let key = if path == "." {
+                T::NAME.to_string()
+            } else {
+                format!("{}.{}", T::NAME, path)
+            };
+            self.invalid_value(key, e.into_inner().message().to_string())
+        })?;
+
+        value
+            .validate()
+            .map_err(|e| self.invalid_value(format!("{}.{}", T::NAME, e.key), e.message))?;
+        Ok(value)
+    }
+
+    /// Reject top-level tables other than `known`, to catch misspelled
+    /// section names.
+    pub fn deny_unknown_sections(&self, known: &[&str]) -> ConfigResult<()> {
+        match self.root.keys().find(|k| !known.contains(&k.as_str())) {
+            Some(section) => Err(ConfigError::UnknownSection {
+                section: section.clone(),
+                origin: self.origin_near(section).map(|o| o.to_string()),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn invalid_value(&self, key: String, message: String) -> ConfigError {
+        ConfigError::InvalidValue {
+            origin: self.origin_near(&key).map(|o| o.to_string()),
+            key,
+            message,
+        }
+    }
+
+    /// Origin of `key`, of a value below it, or of its closest parent.
+    fn origin_near(&self, key: &str) -> Option<&Origin> {
+        if let Some(origin) = self.origins.get(key) {
+            return Some(origin);
+        }
+        let below = format!("{}.", key);
+        if let Some((_, origin)) = self
+            .origins
+            .range(below.clone()..)
+            .next()
+            .filter(|(k, _)| k.starts_with(&below))
+        {
+            return Some(origin);
+        }
+        let (parent, _) = key.rsplit_once('.')?;
+        self.origin_near(parent)
+    }
+
+    fn merge_table(&mut self, prefix: String, table: Table, origin: &Origin) {
+        for (name, value) in table {
+            let key = if prefix.is_empty() {
+                name
+            } else {
+                format!("{}.{}", prefix, name)
+            };
+            match value {
+                Value::Table(inner) if self.get(&key).is_none_or(Value::is_table) => {
+                    self.merge_table(key, inner, origin);
+                }
+                value => {
+                    // Replacing a value cannot fail: the key came from a
+                    // parsed table, so every parent is a table.
+                    let _ = self.set(&key, value, origin.clone());
+                }
+            }
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value, origin: Origin) -> ConfigResult<()> {
+        let invalid = |message: String| ConfigError::InvalidKey {
+            key: key.to_string(),
+            origin: origin.to_string(),
+            message,
+        };
+        let parts: Vec<&str> = key.split('.').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return Err(invalid("empty key segment".into()));
+        }
+
+        let (last, parents) = parts.split_last().expect("split yields at least one part");
+        let mut table = &mut self.root;
+        for (i, part) in parents.iter().enumerate() {
+            let entry = table
+                .entry(part.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            table = entry
+                .as_table_mut()
+                .ok_or_else(|| invalid(format!("'{}' is not a table", parts[..=i].join("."))))?;
+        }
+
+        // Drop origins of values the new one replaces.
+        let below = format!("{}.", key);
+        self.origins.retain(|k, _| !k.starts_with(&below));
+        if let Value::Table(inner) = &value {
+            let inner = inner.clone();
+            table.insert(last.to_string(), Value::Table(Table::new()));
+            self.merge_table(key.to_string(), inner, &origin);
+        } else {
+            table.insert(last.to_string(), value);
+            self.origins.insert(key.to_string(), origin);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Worker {
+        threads: u32,
+        name: String,
+        queue: Queue,
+    }
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Queue {
+        capacity: u32,
+    }
+
+    impl Section for Worker {
+        const NAME: &'static str = "worker";
+
+        fn validate(&self) -> Result<(), InvalidKey> {
+            if self.queue.capacity == 1 {
+                return Err(InvalidKey::new("capacity", "must not be 1").within("queue"));
+            }
+            Ok(())
+        }
+    }
+
+    fn write(dir: &tempfile::TempDir, name: &str, text: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_layers_and_origins() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = write(
+            &dir,
+            "base.toml",
+            "[worker]\nthreads = 2\nname = \"base\"\n[worker.queue]\ncapacity = 8\n",
+        );
+        let site = write(&dir, "site.toml", "[worker]\nname = \"site\"\n");
+
+        let config = ConfigLoader::new()
+            .with_override("worker.threads", "16")
+            .with_file(&base)
+            .with_optional_file(dir.path().join("missing.toml"))
+            .with_file(&site)
+            .with_env_vars([
+                ("ARVAK_WORKER__QUEUE__CAPACITY", "32"),
+                ("ARVAK_LOG_LEVEL", "debug"),
+                ("HOME", "/root"),
+            ])
+            .load()
+            .unwrap();
+
+        let worker: Worker = config.section().unwrap();
+        assert_eq!(
+            worker,
+            Worker {
+                threads: 16,
+                name: "site".into(),
+                queue: Queue { capacity: 32 },
+            }
+        );
+        assert_eq!(config.origin("worker.name"), Some(&Origin::File(site)));
+        assert_eq!(config.origin("worker.threads"), Some(&Origin::Override));
+        assert_eq!(
+            config.origin("worker.queue.capacity"),
+            Some(&Origin::Env("ARVAK_WORKER__QUEUE__CAPACITY".into()))
+        );
+        // Variables without a section separator are not configuration keys.
+        assert!(!config.contains("log_level"));
+    }
+
+    #[test]
+    fn test_missing_section_uses_defaults() {
+        let config = LayeredConfig::parse("", "empty.toml").unwrap();
+        assert_eq!(config.section::<Worker>().unwrap(), Worker::default());
+    }
+
+    #[test]
+    fn test_errors_point_at_keys() {
+        let err = LayeredConfig::parse("[worker.queue]\ncapacity = \"many\"\n", "a.toml")
+            .unwrap()
+            .section::<Worker>()
+            .unwrap_err();
+        assert_eq!(err.key(), Some("worker.queue.capacity"));
+        assert!(err.to_string().contains("(from a.toml)"), "{}", err);
+
+        let err = LayeredConfig::parse("[worker]\nthread = 4\n", "a.toml")
+            .unwrap()
+            .section::<Worker>()
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `thread`"),
+            "{}",
+            err
+        );
+
+        let err = ConfigLoader::new()
+            .with_env_vars([("ARVAK_WORKER__QUEUE__CAPACITY", "1")])
+            .load()
+            .unwrap()
+            .section::<Worker>()
+            .unwrap_err();
+        assert_eq!(err.key(), Some("worker.queue.capacity"));
+        assert!(
+            err.to_string()
+                .contains("environment variable ARVAK_WORKER__QUEUE__CAPACITY"),
+            "{}",
+            err
+        );
+
+        let err = LayeredConfig::parse("worker = 3\n", "a.toml")
+            .unwrap()
+            .section::<Worker>()
+            .unwrap_err();
+        assert_eq!(err.key(), Some("worker"));
+    }
+
+    #[test]
+    fn test_unknown_sections_and_bad_keys() {
+        let config = LayeredConfig::parse("[wroker]\nthreads = 1\n", "a.toml").unwrap();
+        let err = config.deny_unknown_sections(&["worker"]).unwrap_err();
+        assert_eq!(err.key(), Some("wroker"));
+        assert!(err.to_string().contains("a.toml"));
+
+        let err = ConfigLoader::new()
+            .with_override("worker.threads", "4")
+            .with_override("worker.threads.max", "8")
+            .load()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::InvalidKey { .. }), "{}", err);
+        assert!(
+            ConfigLoader::new()
+                .with_override("worker..threads", "1")
+                .load()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_file_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = ConfigLoader::new()
+            .with_file(dir.path().join("missing.toml"))
+            .load()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::Io { .. }));
+
+        let bad = write(&dir, "bad.toml", "[worker\n");
+        let err = ConfigLoader::new().with_file(&bad).load().unwrap_err();
+        assert!(matches!(err, ConfigError::Parse { .. }));
+    }
+
+    #[test]
+    fn test_value_parsing() {
+        assert_eq!(parse_value("10"), Value::Integer(10));
+        assert_eq!(parse_value("true"), Value::Boolean(true));
+        assert_eq!(parse_value("\"x\""), Value::String("x".into()));
+        assert_eq!(
+            parse_value("0.0.0.0:50051"),
+            Value::String("0.0.0.0:50051".into())
+        );
+        assert_eq!(
+            env_key("ARVAK_GRPC__SERVER__ADDRESS").as_deref(),
+            Some("grpc.server.address")
+        );
+        assert_eq!(env_key("ARVAK_BIND"), None);
+    }
+}
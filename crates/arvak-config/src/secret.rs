@@ -0,0 +1,196 @@
+//! Credentials referenced from configuration.
+//!
+//! Configuration files name where a credential lives rather than holding it:
+//!
+//! ```toml
+//! [scheduler.slurm]
+//! jwt = { env = "SLURM_JWT" }
+//!
+//! [grpc.auth]
+//! api_keys = [
+//!     { file = "/run/secrets/arvak-api-key" },
+//!     { command = ["pass", "show", "arvak/api-key"] },
+//! ]
+//! ```
+//!
+//! A [`SecretSource`] is resolved into a [`Secret`] when the component
+//! starts. `Secret` never prints its value through `Debug` or `Display`, so
+//! configuration structs and errors holding one can be logged safely.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ConfigError, ConfigResult};
+
+/// Text shown in place of a secret value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// A credential whose value is hidden from `Debug` and `Display`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Wrap a credential value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The credential value. Callers must not log the result.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Replace every occurrence of the value in `text`, e.g. in command
+    /// output that may echo a token back.
+    pub fn redact(&self, text: &str) -> String {
+        if self.0.is_empty() {
+            return text.to_string();
+        }
+        text.replace(&self.0, REDACTED)
+    }
+
+    /// Compare with `candidate` in time independent of where they differ.
+    pub fn matches(&self, candidate: &str) -> bool {
+        let (a, b) = (self.0.as_bytes(), candidate.as_bytes());
+        a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// Where a secret is read from.
+///
+/// In TOML this is a single-key table: `{ env = "VAR" }`,
+/// `{ file = "/path" }` or `{ command = ["program", "arg", ...] }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+pub enum SecretSource {
+    /// An environment variable.
+    Env(String),
+    /// A file, with trailing newlines removed.
+    File(PathBuf),
+    /// The standard output of a command, with trailing newlines removed.
+    Command(Vec<String>),
+}
+
+impl SecretSource {
+    /// Read the secret.
+    ///
+    /// Fails if the source is missing, the command exits unsuccessfully or
+    /// the value is empty. Errors describe the source but never the value.
+    pub fn resolve(&self) -> ConfigResult<Secret> {
+        let value = match self {
+            SecretSource::Env(var) => std::env::var(var).map_err(|e| self.error(e.to_string()))?,
+            SecretSource::File(path) => {
+                std::fs::read_to_string(path).map_err(|e| self.error(e.to_string()))?
+            }
+            SecretSource::Command(argv) => {
+                let (program, args) = argv
+                    .split_first()
+                    .ok_or_else(|| self.error("command is empty"))?;
+                let output = Command::new(program)
+                    .args(args)
+                    .output()
+                    .map_err(|e| self.error(e.to_string()))?;
+                if !output.status.success() {
+                    return Err(self.error(format!("exited with {}", output.status)));
+                }
+                String::from_utf8(output.stdout)
+                    .map_err(|_| self.error("output is not valid UTF-8"))?
+            }
+        };
+
+        let value = value.trim_end_matches(['\n', '\r']);
+        if value.is_empty() {
+            return Err(self.error("value is empty"));
+        }
+        Ok(Secret::new(value))
+    }
+
+    fn error(&self, message: impl Into<String>) -> ConfigError {
+        ConfigError::Secret {
+            origin: self.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretSource::Env(var) => write!(f, "environment variable {}", var),
+            SecretSource::File(path) => write!(f, "file {}", path.display()),
+            SecretSource::Command(argv) => {
+                write!(f, "command `{}`", argv.first().map_or("", String::as_str))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret = Secret::new("hunter2");
+        assert_eq!(format!("{}", secret), REDACTED);
+        assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+        assert_eq!(secret.expose(), "hunter2");
+        assert_eq!(
+            secret.redact("auth failed for token hunter2"),
+            "auth failed for token [REDACTED]"
+        );
+        assert!(secret.matches("hunter2"));
+        assert!(!secret.matches("hunter3"));
+        assert!(!secret.matches("hunter"));
+    }
+
+    #[test]
+    fn test_sources() {
+        #[derive(Deserialize)]
+        struct Table {
+            sources: Vec<SecretSource>,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "from-file\n").unwrap();
+
+        let table: Table = toml::from_str(&format!(
+            "sources = [{{ file = '{}' }}, {{ command = ['echo', 'from-command'] }}, {{ env = 'ARVAK_TEST_UNSET_SECRET' }}]",
+            path.display()
+        ))
+        .unwrap();
+
+        assert_eq!(table.sources[0].resolve().unwrap().expose(), "from-file");
+        #[cfg(unix)]
+        assert_eq!(table.sources[1].resolve().unwrap().expose(), "from-command");
+
+        let err = table.sources[2].resolve().unwrap_err();
+        assert!(matches!(err, ConfigError::Secret { .. }));
+        assert!(
+            err.to_string()
+                .contains("environment variable ARVAK_TEST_UNSET_SECRET"),
+            "{}",
+            err
+        );
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(table.sources[0].resolve().is_err());
+        assert!(toml::from_str::<Table>("sources = [{ vault = 'x' }]").is_err());
+    }
+}
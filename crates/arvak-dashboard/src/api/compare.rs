@@ -0,0 +1,288 @@
+//! Side-by-side job comparison endpoint.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use arvak_hal::Counts;
+use arvak_ir::Circuit;
+use arvak_sched::verify::{hellinger_distance, total_variation_distance, z_expectation};
+use arvak_sched::{ScheduledJob, ScheduledJobId, StateStore};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+
+use crate::api::jobs::job_to_summary;
+use crate::dto::{
+    CircuitStatsView, CompareParams, CompareResponse, ComparedExpectation, ComparedJob,
+    ComparedOutcome, DistributionDistance, ProvenanceDiff,
+};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Most bits single-bit expectation values are reported for.
+const MAX_EXPECTATION_BITS: usize = 32;
+
+/// GET /api/compare?job_a=..&job_b=.. - Compare two jobs side by side.
+pub async fn compare_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<CompareParams>,
+) -> Result<Json<CompareResponse>, ApiError> {
+    let store = state
+        .store
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
+
+    let (job_a, counts_a) = load(store.as_ref(), &params.job_a).await?;
+    let (job_b, counts_b) = load(store.as_ref(), &params.job_b).await?;
+
+    let distance = match (&counts_a, &counts_b) {
+        (Some(a), Some(b)) => Some(DistributionDistance {
+            total_variation: total_variation_distance(a, b),
+            hellinger: hellinger_distance(a, b),
+        }),
+        _ => None,
+    };
+
+    Ok(Json(CompareResponse {
+        outcomes: align_outcomes(counts_a.as_ref(), counts_b.as_ref()),
+        expectations: compare_expectations(counts_a.as_ref(), counts_b.as_ref()),
+        provenance: provenance_diff(&job_a, &job_b),
+        distance,
+        a: compared_job(job_a, counts_a.as_ref()),
+        b: compared_job(job_b, counts_b.as_ref()),
+    }))
+}
+
+/// Load a job and the counts of its result, if it has one.
+async fn load(
+    store: &dyn StateStore,
+    id: &str,
+) -> Result<(ScheduledJob, Option<Counts>), ApiError> {
+    let job_id = ScheduledJobId::parse(id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid job ID: {}", id)))?;
+
+    let job = store
+        .load_job(&job_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Job not found: {}", id)))?;
+
+    let result = store
+        .load_result(&job_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok((job, result.map(|r| r.counts)))
+}
+
+fn compared_job(job: ScheduledJob, counts: Option<&Counts>) -> ComparedJob {
+    let circuit = job
+        .circuits
+        .first()
+        .and_then(|spec| spec.resolve().ok())
+        .map(|c| circuit_stats(&c));
+
+    ComparedJob {
+        job: job_to_summary(job),
+        total_shots: counts.map(Counts::total_shots),
+        circuit,
+    }
+}
+
+fn circuit_stats(circuit: &Circuit) -> CircuitStatsView {
+    let dag = circuit.dag();
+    let mut gate_counts = std::collections::BTreeMap::new();
+    let mut multi_qubit_gates = 0;
+    for (_, inst) in dag.topological_ops() {
+        *gate_counts.entry(inst.name().to_string()).or_insert(0) += 1;
+        if inst.is_gate() && inst.qubits.len() > 1 {
+            multi_qubit_gates += 1;
+        }
+    }
+
+    CircuitStatsView {
+        num_qubits: circuit.num_qubits(),
+        depth: circuit.depth(),
+        num_ops: dag.num_ops(),
+        multi_qubit_gates,
+        gate_counts,
+    }
+}
+
+/// Align the outcomes of both jobs; a job without a result counts zero.
+fn align_outcomes(a: Option<&Counts>, b: Option<&Counts>) -> Vec<ComparedOutcome> {
+    let bitstrings: BTreeSet<&String> = a
+        .into_iter()
+        .chain(b)
+        .flat_map(|counts| counts.iter().map(|(bits, _)| bits))
+        .collect();
+
+    let count = |counts: Option<&Counts>, bits: &str| counts.map_or(0, |c| c.get(bits));
+    let probability = |counts: Option<&Counts>, bits: &str| {
+        counts
+            .filter(|c| c.total_shots() > 0)
+            .map_or(0.0, |c| c.get(bits) as f64 / c.total_shots() as f64)
+    };
+
+    let mut outcomes: Vec<ComparedOutcome> = bitstrings
+        .into_iter()
+        .map(|bits| ComparedOutcome {
+            bitstring: bits.clone(),
+            count_a: count(a, bits),
+            count_b: count(b, bits),
+            probability_a: probability(a, bits),
+            probability_b: probability(b, bits),
+        })
+        .collect();
+
+    outcomes.sort_by(|x, y| {
+        let (px, py) = (
+            x.probability_a.max(x.probability_b),
+            y.probability_a.max(y.probability_b),
+        );
+        py.total_cmp(&px)
+    });
+    outcomes
+}
+
+/// Z expectation values of each measured bit and, for several bits, their
+/// joint parity.
+fn compare_expectations(a: Option<&Counts>, b: Option<&Counts>) -> Vec<ComparedExpectation> {
+    let num_bits = a
+        .into_iter()
+        .chain(b)
+        .flat_map(|counts| counts.iter().map(|(bits, _)| bits.replace(' ', "").len()))
+        .max()
+        .unwrap_or(0);
+
+    let mut observables: Vec<Vec<usize>> = (0..num_bits.min(MAX_EXPECTATION_BITS))
+        .map(|q| vec![q])
+        .collect();
+    if num_bits > 1 {
+        observables.push((0..num_bits).collect());
+    }
+
+    observables
+        .into_iter()
+        .map(|qubits| {
+            let value_a = a.map(|c| z_expectation(c, &qubits));
+            let value_b = b.map(|c| z_expectation(c, &qubits));
+            ComparedExpectation {
+                observable: qubits.iter().map(|q| format!("Z{}", q)).collect(),
+                qubits,
+                value_a,
+                value_b,
+                delta: value_a.zip(value_b).map(|(a, b)| b - a),
+            }
+        })
+        .collect()
+}
+
+/// Provenance fields of both jobs, including every metadata entry.
+fn provenance_diff(a: &ScheduledJob, b: &ScheduledJob) -> Vec<ProvenanceDiff> {
+    fn fields(job: &ScheduledJob) -> Vec<(String, Option<String>)> {
+        let mut fields = vec![
+            ("backend".to_string(), job.matched_backend.clone()),
+            ("shots".to_string(), Some(job.shots.to_string())),
+            (
+                "num_circuits".to_string(),
+                Some(job.circuits.len().to_string()),
+            ),
+            (
+                "circuit_hash".to_string(),
+                job.circuits
+                    .first()
+                    .and_then(|spec| spec.canonical_hash().ok())
+                    .map(|hash| format!("{:016x}", hash)),
+            ),
+            (
+                "parent".to_string(),
+                job.parent.as_ref().map(ToString::to_string),
+            ),
+            ("derivation".to_string(), job.derivation.clone()),
+            ("owner".to_string(), job.owner.clone()),
+        ];
+        let mut metadata: Vec<_> = job
+            .metadata
+            .iter()
+            .map(|(k, v)| (format!("metadata.{}", k), Some(v.clone())))
+            .collect();
+        metadata.sort();
+        fields.extend(metadata);
+        fields
+    }
+
+    let (fields_a, fields_b) = (fields(a), fields(b));
+    let mut names: Vec<&String> = fields_a.iter().map(|(name, _)| name).collect();
+    for (name, _) in &fields_b {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let lookup = |fields: &[(String, Option<String>)], name: &str| {
+        fields
+            .iter()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.clone())
+    };
+    names
+        .into_iter()
+        .map(|name| {
+            let (a, b) = (lookup(&fields_a, name), lookup(&fields_b, name));
+            ProvenanceDiff {
+                field: name.clone(),
+                differs: a != b,
+                a,
+                b,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_sched::CircuitSpec;
+
+    #[test]
+    fn test_compare_helpers() {
+        let before = Counts::from_pairs([("00", 50u64), ("11", 30), ("01", 20)]);
+        let after = Counts::from_pairs([("00", 50u64), ("11", 50)]);
+
+        let outcomes = align_outcomes(Some(&before), Some(&after));
+        let bits: Vec<_> = outcomes.iter().map(|o| o.bitstring.as_str()).collect();
+        assert_eq!(bits, ["00", "11", "01"]);
+        assert_eq!((outcomes[2].count_a, outcomes[2].count_b), (20, 0));
+        assert_eq!(outcomes[1].probability_b, 0.5);
+
+        let expectations = compare_expectations(Some(&before), None);
+        let names: Vec<_> = expectations.iter().map(|e| e.observable.as_str()).collect();
+        assert_eq!(names, ["Z0", "Z1", "Z0Z1"]);
+        // Z0 is -1 on "11" and "01".
+        assert!((expectations[0].value_a.unwrap() - 0.0).abs() < 1e-12);
+        assert!((expectations[2].value_a.unwrap() - 0.6).abs() < 1e-12);
+        assert_eq!(
+            (expectations[2].value_b, expectations[2].delta),
+            (None, None)
+        );
+
+        let qasm = "OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];";
+        let a = ScheduledJob::new("a", CircuitSpec::from_qasm(qasm)).with_shots(100);
+        let b = ScheduledJob::new("b", CircuitSpec::from_qasm(qasm))
+            .with_shots(100)
+            .with_metadata("mitigation", "zne");
+        let diff = provenance_diff(&a, &b);
+        let differing: Vec<_> = diff
+            .iter()
+            .filter(|d| d.differs)
+            .map(|d| d.field.as_str())
+            .collect();
+        assert_eq!(differing, ["metadata.mitigation"]);
+
+        let stats = compared_job(a, None).circuit.unwrap();
+        assert_eq!((stats.num_qubits, stats.multi_qubit_gates), (2, 1));
+        assert_eq!(stats.gate_counts["cx"], 1);
+    }
+}
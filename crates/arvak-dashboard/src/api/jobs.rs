@@ -3,7 +3,8 @@
 use std::sync::Arc;
 
 use arvak_sched::{
-    CircuitSpec, JobFilter, Priority, ScheduledJob, ScheduledJobId, ScheduledJobStatus,
+    AheadReason, CircuitSpec, JobFilter, JobLineage, Priority, QueueExplanation, SchedError,
+    ScheduledJob, ScheduledJobId, ScheduledJobStatus,
 };
 use axum::{
     Json,
@@ -11,8 +12,8 @@ use axum::{
 };
 
 use crate::dto::{
-    CreateJobRequest, HistogramBar, JobDetails, JobListParams, JobSummary, ResultHistogram,
-    ResultStatistics,
+    CreateJobRequest, HistogramBar, JobAheadInfo, JobDetails, JobExplanationResponse,
+    JobLineageInfo, JobListParams, JobSummary, ResultHistogram, ResultStatistics,
 };
 use crate::error::ApiError;
 use crate::state::AppState;
@@ -54,6 +55,8 @@ pub async fn list_jobs(
 }
 
 /// GET /api/jobs/:id - Get job details.
+///
+/// `id` may also be a batch job or request ID the job is known by.
 pub async fn get_job(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
@@ -63,8 +66,12 @@ pub async fn get_job(
         .as_ref()
         .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
 
-    let job_id = ScheduledJobId::parse(&id)
-        .map_err(|_| ApiError::BadRequest(format!("Invalid job ID: {}", id)))?;
+    // Batch job and request IDs resolve to the job they refer to
+    let job_id = store
+        .resolve_job_id(&id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Job not found: {}", id)))?;
 
     let job = store
         .load_job(&job_id)
@@ -72,7 +79,76 @@ pub async fn get_job(
         .map_err(|e| ApiError::Internal(e.to_string()))?
         .ok_or_else(|| ApiError::NotFound(format!("Job not found: {}", id)))?;
 
-    Ok(Json(job_to_details(&job)))
+    let lineage = JobLineage::load(store.as_ref(), &job_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(job_to_details(&job, &lineage)))
+}
+
+/// GET /api/jobs/:id/children - List jobs derived from a job.
+pub async fn list_children(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<JobSummary>>, ApiError> {
+    let store = state
+        .store
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
+
+    let job_id = ScheduledJobId::parse(&id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid job ID: {}", id)))?;
+
+    let children = store
+        .list_children(&job_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    Ok(Json(children.into_iter().map(job_to_summary).collect()))
+}
+
+/// GET /api/jobs/:id/explain - Explain why a job has not started yet.
+pub async fn explain_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<JobExplanationResponse>, ApiError> {
+    let store = state
+        .store
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
+
+    let job_id = ScheduledJobId::parse(&id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid job ID: {}", id)))?;
+
+    let explanation = QueueExplanation::load(store.as_ref(), &job_id)
+        .await
+        .map_err(|e| match e {
+            SchedError::JobNotFound(_) => ApiError::NotFound(format!("Job not found: {}", id)),
+            e => ApiError::Internal(e.to_string()),
+        })?;
+
+    Ok(Json(JobExplanationResponse {
+        job_id: explanation.job_id.to_string(),
+        status: explanation.status.name().to_string(),
+        position: explanation.position,
+        queue_length: explanation.queue_length,
+        ahead: explanation
+            .ahead
+            .into_iter()
+            .map(|a| JobAheadInfo {
+                id: a.job_id.to_string(),
+                name: a.name,
+                owner: a.owner,
+                priority: a.priority.0,
+                reason: match a.reason {
+                    AheadReason::HigherPriority => "higher_priority",
+                    AheadReason::QueuedEarlier => "queued_earlier",
+                }
+                .to_string(),
+            })
+            .collect(),
+        holds: explanation.holds.iter().map(ToString::to_string).collect(),
+    }))
 }
 
 /// POST /api/jobs - Create a new job.
@@ -101,6 +177,13 @@ pub async fn create_job(
         job.matched_backend = Some(backend);
     }
 
+    if let Some(ref parent) = req.parent_id {
+        let parent = ScheduledJobId::parse(parent)
+            .map_err(|_| ApiError::BadRequest(format!("Invalid parent job ID: {}", parent)))?;
+        job.parent = Some(parent);
+        job.derivation = req.derivation;
+    }
+
     // Save the job
     store
         .save_job(&job)
@@ -193,7 +276,7 @@ pub async fn get_job_result(
 // Conversion helpers
 // ============================================================================
 
-fn job_to_summary(job: ScheduledJob) -> JobSummary {
+pub(crate) fn job_to_summary(job: ScheduledJob) -> JobSummary {
     let status_details = match &job.status {
         ScheduledJobStatus::SlurmQueued { slurm_job_id }
         | ScheduledJobStatus::SlurmRunning { slurm_job_id } => {
@@ -219,10 +302,11 @@ fn job_to_summary(job: ScheduledJob) -> JobSummary {
         created_at: job.created_at.to_rfc3339(),
         submitted_at: job.submitted_at.map(|t| t.to_rfc3339()),
         completed_at: job.completed_at.map(|t| t.to_rfc3339()),
+        parent_id: job.parent.map(|p| p.to_string()),
     }
 }
 
-fn job_to_details(job: &ScheduledJob) -> JobDetails {
+fn job_to_details(job: &ScheduledJob, lineage: &JobLineage) -> JobDetails {
     let status_details = match &job.status {
         ScheduledJobStatus::SlurmQueued { slurm_job_id }
         | ScheduledJobStatus::SlurmRunning { slurm_job_id } => {
@@ -237,9 +321,9 @@ fn job_to_details(job: &ScheduledJob) -> JobDetails {
     };
 
     // Get QASM from first circuit
-    let qasm = job.circuits.first().and_then(|c| match c {
+    let qasm = job.circuits.first().and_then(|c| match c.source() {
         CircuitSpec::Qasm3(qasm) => Some(qasm.clone()),
-        CircuitSpec::QasmFile(_) => None,
+        _ => None,
     });
 
     JobDetails {
@@ -260,6 +344,14 @@ fn job_to_details(job: &ScheduledJob) -> JobDetails {
             .iter()
             .map(|(k, v)| (k.clone(), v.clone()))
             .collect(),
+        workflow_id: job.workflow_id.as_ref().map(|id| id.to_string()),
+        batch_job_id: job.batch_job_id.clone(),
+        request_id: job.request_id.clone(),
+        lineage: JobLineageInfo {
+            derivation: lineage.derivation.clone(),
+            ancestors: lineage.ancestors.iter().map(|id| id.to_string()).collect(),
+            children: lineage.children.iter().map(|id| id.to_string()).collect(),
+        },
     }
 }
 
@@ -278,7 +370,7 @@ fn result_to_histogram(job_id: &str, result: &arvak_hal::ExecutionResult) -> Res
         .collect();
 
     // Sort by count descending
-    bars.sort_by(|a, b| b.count.cmp(&a.count));
+    bars.sort_by_key(|b| std::cmp::Reverse(b.count));
 
     let total_shots: u64 = bars.iter().map(|b| b.count).sum();
     let unique_outcomes = bars.len();
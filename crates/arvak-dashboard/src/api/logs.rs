@@ -0,0 +1,198 @@
+//! Live job output endpoint.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arvak_sched::{
+    LogLine, LogTail, SchedError, ScheduledJobId, ScheduledJobStatus, Scheduler, SchedulerHandle,
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::{SinkExt, Stream, StreamExt, channel::mpsc};
+
+use crate::dto::JobLogParams;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// How often a followed job's log files are read.
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// GET /api/jobs/:id/logs - Stream a job's stdout and stderr as server-sent
+/// events.
+///
+/// Each line is sent as a `stdout` or `stderr` event, with ANSI escape
+/// sequences stripped and lines longer than the configured limit truncated.
+/// Only the configured backlog of existing output is sent. With
+/// `follow=true` the stream stays open until the job finishes. The stream
+/// ends with an `end` event carrying the job's status.
+pub async fn stream_job_logs(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<JobLogParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let scheduler = state
+        .scheduler
+        .clone()
+        .ok_or_else(|| ApiError::Internal("No scheduler configured".to_string()))?;
+
+    let job_id = ScheduledJobId::parse(&id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid job ID: {}", id)))?;
+
+    let backlog = state.config.log_backlog_bytes;
+    let tail = scheduler
+        .job_logs(&job_id)
+        .await
+        .map_err(|e| match e {
+            SchedError::JobNotFound(_) => ApiError::NotFound(format!("Job not found: {}", id)),
+            e => ApiError::Internal(e.to_string()),
+        })?
+        .with_backlog(backlog);
+
+    let (tx, rx) = mpsc::channel(64);
+    let follower = LogFollower {
+        scheduler,
+        job_id,
+        follow: params.follow,
+        backlog,
+        max_line_chars: state.config.log_max_line_chars,
+    };
+    tokio::spawn(follower.run(tail, tx));
+
+    Ok(Sse::new(rx.map(Ok)).keep_alive(KeepAlive::default()))
+}
+
+/// Sends one job's log lines to a client until it finishes or the client
+/// goes away.
+struct LogFollower {
+    scheduler: SchedulerHandle,
+    job_id: ScheduledJobId,
+    follow: bool,
+    backlog: u64,
+    max_line_chars: usize,
+}
+
+impl LogFollower {
+    async fn run(self, mut tail: LogTail, mut tx: mpsc::Sender<Event>) {
+        loop {
+            // Check the status first, so output written before the job
+            // finished is read below.
+            let status = match self.scheduler.status(&self.job_id).await {
+                Ok(status) => status,
+                Err(e) => {
+                    tracing::warn!("Failed to get status of job {}: {}", self.job_id, e);
+                    return;
+                }
+            };
+            let done = !self.follow || status.is_terminal();
+
+            // Jobs name their log files once dispatched.
+            if tail.is_empty() {
+                match self.scheduler.job_logs(&self.job_id).await {
+                    Ok(logs) => tail = logs.with_backlog(self.backlog),
+                    Err(e) => tracing::warn!("Failed to find logs of job {}: {}", self.job_id, e),
+                }
+            }
+
+            let mut lines = match tail.read().await {
+                Ok(lines) => lines,
+                Err(e) => {
+                    tracing::warn!("Failed to read logs of job {}: {}", self.job_id, e);
+                    Vec::new()
+                }
+            };
+            if done {
+                lines.extend(tail.flush());
+            }
+            for line in &lines {
+                if tx.send(self.line_event(line)).await.is_err() {
+                    return;
+                }
+            }
+
+            if done {
+                let _ = tx.send(end_event(&status)).await;
+                return;
+            }
+            tokio::time::sleep(LOG_POLL_INTERVAL).await;
+        }
+    }
+
+    fn line_event(&self, line: &LogLine) -> Event {
+        Event::default()
+            .event(line.stream.to_string())
+            .data(truncate(&strip_ansi(&line.text), self.max_line_chars))
+    }
+}
+
+fn end_event(status: &ScheduledJobStatus) -> Event {
+    Event::default().event("end").data(status.name())
+}
+
+/// Remove ANSI escape sequences and other control characters except tabs.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => match chars.next() {
+                // CSI: parameters up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\u{7}' {
+                            break;
+                        }
+                        if c == '\u{1b}' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Cut a line to `max_chars` characters, marking the cut.
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}… [truncated]", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\u{1b}[1;31mError\u{1b}[0m: bad"), "Error: bad");
+        assert_eq!(
+            strip_ansi("\u{1b}]0;title\u{7}done\u{1b}]8;;url\u{1b}\\"),
+            "done"
+        );
+        assert_eq!(strip_ansi("50%\r\u{8}\tok"), "50%\tok");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("ääääää", 3), "äää… [truncated]");
+    }
+}
@@ -2,7 +2,12 @@
 
 pub mod backends;
 pub mod circuits;
+pub mod compare;
 pub mod eval;
 pub mod health;
 pub mod jobs;
+pub mod logs;
+pub mod runs;
+pub mod stats;
 pub mod vqe;
+pub mod workflows;
@@ -0,0 +1,51 @@
+//! Variational run endpoints.
+
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+
+use crate::dto::{IterationInfo, RunIterationsResponse};
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// GET /api/runs/:id/iterations - Get the recorded iterations of a VQE or
+/// QAOA run.
+pub async fn get_run_iterations(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<RunIterationsResponse>, ApiError> {
+    let store = state
+        .store
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
+
+    let records = store
+        .load_iterations(&id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    if records.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "No iterations recorded for run: {}",
+            id
+        )));
+    }
+
+    Ok(Json(RunIterationsResponse {
+        run_id: id,
+        iterations: records
+            .into_iter()
+            .map(|r| IterationInfo {
+                iteration: r.iteration,
+                params: r.params,
+                value: r.value,
+                std_error: r.variance.map(f64::sqrt),
+                job_ids: r.job_ids.iter().map(ToString::to_string).collect(),
+                recorded_at: r.recorded_at.to_rfc3339(),
+            })
+            .collect(),
+    }))
+}
@@ -0,0 +1,57 @@
+//! Queue statistics endpoints.
+
+use std::sync::Arc;
+
+use arvak_sched::JobFilter;
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use chrono::{Duration, Utc};
+
+use crate::dto::{HeatmapParams, QueueHeatmap};
+use crate::error::ApiError;
+use crate::state::AppState;
+use crate::stats::{DEFAULT_WINDOW_HOURS, MAX_WINDOW_HOURS, queue_heatmap};
+
+/// GET /api/stats/heatmap - Submissions and wait times by hour of day and
+/// backend or partition.
+pub async fn queue_heatmap_stats(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HeatmapParams>,
+) -> Result<Json<QueueHeatmap>, ApiError> {
+    let store = state
+        .store
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
+
+    let hours = params.hours.unwrap_or(DEFAULT_WINDOW_HOURS);
+    if hours == 0 || hours > MAX_WINDOW_HOURS {
+        return Err(ApiError::BadRequest(format!(
+            "hours must be between 1 and {}",
+            MAX_WINDOW_HOURS
+        )));
+    }
+
+    if let Some(heatmap) = state.stats.heatmap(hours, params.until, params.group_by) {
+        return Ok(Json(heatmap));
+    }
+
+    let end = params.until.unwrap_or_else(Utc::now);
+    let start = end - Duration::hours(i64::from(hours));
+    let filter = JobFilter {
+        created_after: Some(start),
+        created_before: Some(end),
+        ..Default::default()
+    };
+    let jobs = store
+        .list_jobs(&filter)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+
+    let heatmap = queue_heatmap(&jobs, start, end, params.group_by);
+    state
+        .stats
+        .insert_heatmap(hours, params.until, params.group_by, heatmap.clone());
+    Ok(Json(heatmap))
+}
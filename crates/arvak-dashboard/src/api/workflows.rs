@@ -0,0 +1,83 @@
+//! Workflow endpoints.
+
+use std::sync::Arc;
+
+use arvak_sched::{Workflow, WorkflowId, WorkflowStatus};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+};
+
+use crate::dto::WorkflowProgressResponse;
+use crate::error::ApiError;
+use crate::state::AppState;
+
+/// Load a workflow with its jobs' current states.
+async fn load_workflow(state: &AppState, id: &str) -> Result<Workflow, ApiError> {
+    let store = state
+        .store
+        .as_ref()
+        .ok_or_else(|| ApiError::Internal("No job store configured".to_string()))?;
+
+    let workflow_id = WorkflowId::parse(id)
+        .map_err(|_| ApiError::BadRequest(format!("Invalid workflow ID: {}", id)))?;
+
+    let mut workflow = store
+        .load_workflow(&workflow_id)
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?
+        .ok_or_else(|| ApiError::NotFound(format!("Workflow not found: {}", id)))?;
+
+    workflow
+        .refresh_from(store.as_ref())
+        .await
+        .map_err(|e| ApiError::Internal(e.to_string()))?;
+    Ok(workflow)
+}
+
+/// GET /api/workflows/:id/progress - Get workflow progress and ETA.
+pub async fn get_workflow_progress(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<WorkflowProgressResponse>, ApiError> {
+    let workflow = load_workflow(&state, &id).await?;
+    let progress = workflow.progress();
+    let status = match &workflow.status {
+        WorkflowStatus::Pending => "Pending",
+        WorkflowStatus::Running => "Running",
+        WorkflowStatus::Completed => "Completed",
+        WorkflowStatus::Failed { .. } => "Failed",
+        WorkflowStatus::Cancelled => "Cancelled",
+    };
+
+    Ok(Json(WorkflowProgressResponse {
+        id: workflow.id.to_string(),
+        name: workflow.name,
+        status: status.to_string(),
+        total: progress.total,
+        completed: progress.completed,
+        cached: progress.cached,
+        running: progress.running,
+        pending: progress.pending,
+        failed: progress.failed,
+        skipped: progress.skipped,
+        percent_complete: progress.percent_complete,
+        remaining_secs: progress.remaining_secs,
+        eta: progress.eta.map(|t| t.to_rfc3339()),
+    }))
+}
+
+/// GET /api/workflows/:id/dot - Render the workflow DAG with live job
+/// states in Graphviz DOT.
+pub async fn get_workflow_dot(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, ApiError> {
+    let workflow = load_workflow(&state, &id).await?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/vnd.graphviz")],
+        workflow.to_dot(),
+    ))
+}
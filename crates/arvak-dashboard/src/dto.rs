@@ -180,6 +180,10 @@ pub struct CreateJobRequest {
     /// Job priority (default 100).
     #[serde(default = "default_priority")]
     pub priority: u32,
+    /// Job this job is derived from (optional).
+    pub parent_id: Option<String>,
+    /// How the job is derived from its parent (e.g. "zne:scale=3").
+    pub derivation: Option<String>,
 }
 
 fn default_shots() -> u32 {
@@ -215,6 +219,8 @@ pub struct JobSummary {
     pub submitted_at: Option<String>,
     /// Completion timestamp (ISO 8601).
     pub completed_at: Option<String>,
+    /// Parent job ID, if derived.
+    pub parent_id: Option<String>,
 }
 
 /// Detailed job information.
@@ -246,6 +252,57 @@ pub struct JobDetails {
     pub completed_at: Option<String>,
     /// Job metadata.
     pub metadata: std::collections::HashMap<String, String>,
+    /// Workflow the job belongs to.
+    pub workflow_id: Option<String>,
+    /// Batch job ID of the latest submission.
+    pub batch_job_id: Option<String>,
+    /// ID of the request that submitted the job.
+    pub request_id: Option<String>,
+    /// Experiment lineage.
+    pub lineage: JobLineageInfo,
+}
+
+/// Parent/child links of a job.
+#[derive(Debug, Serialize)]
+pub struct JobLineageInfo {
+    /// How the job was derived from its parent.
+    pub derivation: Option<String>,
+    /// Ancestor job IDs, nearest first.
+    pub ancestors: Vec<String>,
+    /// Directly derived job IDs.
+    pub children: Vec<String>,
+}
+
+/// Why a job has not started yet.
+#[derive(Debug, Serialize)]
+pub struct JobExplanationResponse {
+    /// Job ID.
+    pub job_id: String,
+    /// Current status.
+    pub status: String,
+    /// 1-based queue position, if queued.
+    pub position: Option<usize>,
+    /// Number of queued jobs.
+    pub queue_length: usize,
+    /// Jobs dispatched before this one.
+    pub ahead: Vec<JobAheadInfo>,
+    /// Human-readable holds.
+    pub holds: Vec<String>,
+}
+
+/// A job queued ahead of another.
+#[derive(Debug, Serialize)]
+pub struct JobAheadInfo {
+    /// Job ID.
+    pub id: String,
+    /// Job name.
+    pub name: String,
+    /// Submitting principal.
+    pub owner: Option<String>,
+    /// Job priority.
+    pub priority: u32,
+    /// Why it is ahead ("higher_priority" or "queued_earlier").
+    pub reason: String,
 }
 
 /// Query parameters for listing jobs.
@@ -263,6 +320,14 @@ pub struct JobListParams {
     pub running: bool,
 }
 
+/// Query parameters for streaming job logs.
+#[derive(Debug, Deserialize, Default)]
+pub struct JobLogParams {
+    /// Keep the stream open until the job finishes.
+    #[serde(default)]
+    pub follow: bool,
+}
+
 /// Result histogram data.
 #[derive(Debug, Serialize)]
 pub struct ResultHistogram {
@@ -302,6 +367,246 @@ pub struct ResultStatistics {
     pub most_frequent_count: u64,
 }
 
+// ============================================================================
+// Workflow DTOs
+// ============================================================================
+
+/// Workflow progress summary.
+#[derive(Debug, Serialize)]
+pub struct WorkflowProgressResponse {
+    /// Workflow ID.
+    pub id: String,
+    /// Workflow name.
+    pub name: String,
+    /// Current status.
+    pub status: String,
+    /// Number of jobs.
+    pub total: usize,
+    /// Completed jobs.
+    pub completed: usize,
+    /// Completed jobs whose result came from the node cache.
+    pub cached: usize,
+    /// Dispatched, unfinished jobs.
+    pub running: usize,
+    /// Jobs not yet dispatched.
+    pub pending: usize,
+    /// Failed or cancelled jobs.
+    pub failed: usize,
+    /// Skipped jobs.
+    pub skipped: usize,
+    /// Percentage of finished jobs.
+    pub percent_complete: f64,
+    /// Estimated seconds left along the critical path.
+    pub remaining_secs: f64,
+    /// Estimated completion time (ISO 8601).
+    pub eta: Option<String>,
+}
+
+// ============================================================================
+// Variational Run DTOs
+// ============================================================================
+
+/// Recorded iterations of a VQE or QAOA run.
+#[derive(Debug, Serialize)]
+pub struct RunIterationsResponse {
+    /// Workflow or campaign ID the run is recorded under.
+    pub run_id: String,
+    /// Iterations, ordered by iteration number.
+    pub iterations: Vec<IterationInfo>,
+}
+
+/// One recorded iteration.
+#[derive(Debug, Serialize)]
+pub struct IterationInfo {
+    /// Iteration number.
+    pub iteration: usize,
+    /// Parameters evaluated.
+    pub params: Vec<f64>,
+    /// Objective value.
+    pub value: f64,
+    /// Standard error of the value, if known.
+    pub std_error: Option<f64>,
+    /// Jobs that evaluated the iteration.
+    pub job_ids: Vec<String>,
+    /// When the iteration was recorded (ISO 8601).
+    pub recorded_at: String,
+}
+
+// ============================================================================
+// Statistics DTOs
+// ============================================================================
+
+/// What the rows of a queue heatmap are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapGrouping {
+    /// One row per matched backend.
+    #[default]
+    Backend,
+    /// One row per batch partition, from the `partition` job metadata.
+    Partition,
+}
+
+/// Query parameters for the queue heatmap.
+#[derive(Debug, Deserialize, Default)]
+pub struct HeatmapParams {
+    /// Window length in hours (default: one week).
+    pub hours: Option<u32>,
+    /// End of the window (ISO 8601, default: now).
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Row grouping.
+    #[serde(default)]
+    pub group_by: HeatmapGrouping,
+}
+
+/// Submissions and wait times by hour of day and backend or partition.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueHeatmap {
+    /// Start of the window (ISO 8601).
+    pub window_start: String,
+    /// End of the window (ISO 8601).
+    pub window_end: String,
+    /// Row grouping.
+    pub group_by: HeatmapGrouping,
+    /// Rows, ordered by key.
+    pub rows: Vec<HeatmapRow>,
+    /// When the heatmap was computed (ISO 8601).
+    pub computed_at: String,
+}
+
+/// One backend or partition of a heatmap.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapRow {
+    /// Backend or partition name.
+    pub key: String,
+    /// One cell per hour of day (UTC), from 0 to 23.
+    pub cells: Vec<HeatmapCell>,
+}
+
+/// Jobs created in one hour of day.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapCell {
+    /// Hour of day (UTC).
+    pub hour: u32,
+    /// Jobs submitted.
+    pub submissions: u64,
+    /// Jobs dispatched to the batch system.
+    pub dispatched: u64,
+    /// Mean wait before dispatch, in seconds.
+    pub mean_wait_secs: Option<f64>,
+    /// Longest wait before dispatch, in seconds.
+    pub max_wait_secs: Option<f64>,
+}
+
+// ============================================================================
+// Comparison DTOs
+// ============================================================================
+
+/// Query parameters for comparing two jobs.
+#[derive(Debug, Deserialize)]
+pub struct CompareParams {
+    /// Job shown as "before".
+    pub job_a: String,
+    /// Job shown as "after".
+    pub job_b: String,
+}
+
+/// Two jobs side by side.
+#[derive(Debug, Serialize)]
+pub struct CompareResponse {
+    /// The "before" job.
+    pub a: ComparedJob,
+    /// The "after" job.
+    pub b: ComparedJob,
+    /// Outcomes of either job, aligned by bitstring and ordered by the
+    /// larger of their probabilities.
+    pub outcomes: Vec<ComparedOutcome>,
+    /// Distance between the distributions, if both jobs have results.
+    pub distance: Option<DistributionDistance>,
+    /// Z expectation values of each measured bit and their joint parity.
+    pub expectations: Vec<ComparedExpectation>,
+    /// Provenance fields of both jobs.
+    pub provenance: Vec<ProvenanceDiff>,
+}
+
+/// One side of a comparison.
+#[derive(Debug, Serialize)]
+pub struct ComparedJob {
+    /// Job summary.
+    pub job: JobSummary,
+    /// Shots in the result, if the job has one.
+    pub total_shots: Option<u64>,
+    /// Statistics of the job's first circuit, as submitted.
+    pub circuit: Option<CircuitStatsView>,
+}
+
+/// Size statistics of a circuit.
+#[derive(Debug, Serialize)]
+pub struct CircuitStatsView {
+    /// Number of qubits.
+    pub num_qubits: usize,
+    /// Circuit depth.
+    pub depth: usize,
+    /// Total number of operations.
+    pub num_ops: usize,
+    /// Number of gates acting on two or more qubits.
+    pub multi_qubit_gates: usize,
+    /// Operation counts by name.
+    pub gate_counts: std::collections::BTreeMap<String, usize>,
+}
+
+/// One outcome in both jobs.
+#[derive(Debug, Serialize)]
+pub struct ComparedOutcome {
+    /// Bitstring result.
+    pub bitstring: String,
+    /// Count in job A.
+    pub count_a: u64,
+    /// Count in job B.
+    pub count_b: u64,
+    /// Probability in job A.
+    pub probability_a: f64,
+    /// Probability in job B.
+    pub probability_b: f64,
+}
+
+/// Distances between two count distributions.
+#[derive(Debug, Serialize)]
+pub struct DistributionDistance {
+    /// Total variation distance.
+    pub total_variation: f64,
+    /// Hellinger distance.
+    pub hellinger: f64,
+}
+
+/// One expectation value in both jobs.
+#[derive(Debug, Serialize)]
+pub struct ComparedExpectation {
+    /// Observable, e.g. "Z0" or "Z0Z1".
+    pub observable: String,
+    /// Bits the Z-parity is taken over.
+    pub qubits: Vec<usize>,
+    /// Value in job A, if it has a result.
+    pub value_a: Option<f64>,
+    /// Value in job B, if it has a result.
+    pub value_b: Option<f64>,
+    /// Value in job B minus value in job A.
+    pub delta: Option<f64>,
+}
+
+/// One provenance field of both jobs.
+#[derive(Debug, Serialize)]
+pub struct ProvenanceDiff {
+    /// Field name; metadata entries are prefixed with "metadata.".
+    pub field: String,
+    /// Value for job A.
+    pub a: Option<String>,
+    /// Value for job B.
+    pub b: Option<String>,
+    /// Whether the values differ.
+    pub differs: bool,
+}
+
 // ============================================================================
 // Conversion implementations
 // ============================================================================
@@ -375,7 +680,9 @@ fn instruction_to_view(instruction: &Instruction) -> OperationView {
         InstructionKind::Measure => ("measure".to_string(), "M".to_string()),
         InstructionKind::Reset => ("reset".to_string(), "|0⟩".to_string()),
         InstructionKind::Barrier => ("barrier".to_string(), "║".to_string()),
-        InstructionKind::Delay { duration } => ("delay".to_string(), format!("D({})", duration)),
+        InstructionKind::Delay { duration, unit } => {
+            ("delay".to_string(), format!("D({}{})", duration, unit))
+        }
         InstructionKind::Shuttle { from_zone, to_zone } => (
             "shuttle".to_string(),
             format!("S({}-{})", from_zone, to_zone),
@@ -426,8 +733,11 @@ fn format_standard_gate_label(gate: &arvak_ir::StandardGate) -> String {
         CH => "CH".to_string(),
         Swap => "SWAP".to_string(),
         ISwap => "iSWAP".to_string(),
+        ECR => "ECR".to_string(),
+        CSX => "C√X".to_string(),
         CCX => "CCX".to_string(),
         CSwap => "CSWAP".to_string(),
+        CCZ => "CCZ".to_string(),
 
         // Parameterized gates
         Rx(p) => format!("RX({})", format_param(p)),
@@ -447,6 +757,7 @@ fn format_standard_gate_label(gate: &arvak_ir::StandardGate) -> String {
         RXX(p) => format!("RXX({})", format_param(p)),
         RYY(p) => format!("RYY({})", format_param(p)),
         RZZ(p) => format!("RZZ({})", format_param(p)),
+        RZX(p) => format!("RZX({})", format_param(p)),
         PRX(t, p) => format!("PRX({},{})", format_param(t), format_param(p)),
     }
 }
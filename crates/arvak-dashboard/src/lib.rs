@@ -31,6 +31,7 @@ pub mod error;
 pub mod processor;
 pub mod server;
 pub mod state;
+pub mod stats;
 pub mod ws;
 
 pub use dto::{
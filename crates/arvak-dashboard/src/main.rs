@@ -2,6 +2,7 @@
 
 use std::sync::Arc;
 
+use arvak_config::ConfigLoader;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use arvak_dashboard::{AppState, DashboardConfig, create_router};
@@ -17,8 +18,12 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    // Create configuration
-    let mut config = DashboardConfig::default();
+    // Load configuration from arvak.toml and ARVAK_DASHBOARD__* variables
+    let mut config: DashboardConfig = ConfigLoader::new()
+        .with_default_file()
+        .with_env()
+        .load()?
+        .section()?;
     if let Ok(bind) = std::env::var("ARVAK_BIND") {
         config.bind_address = bind.parse().expect("Invalid ARVAK_BIND address");
     }
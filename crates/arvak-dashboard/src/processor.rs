@@ -3,7 +3,7 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use arvak_sched::{JobFilter, ScheduledJobStatus};
+use arvak_sched::{FailureKind, JobFilter, ScheduledJobStatus};
 use tokio::time;
 use tracing::{error, info, warn};
 
@@ -63,6 +63,7 @@ pub async fn run_job_processor(state: Arc<AppState>) {
                                 &job_id,
                                 ScheduledJobStatus::Failed {
                                     reason: format!("Circuit resolve error: {}", e),
+                                    kind: FailureKind::Application,
                                     slurm_job_id: None,
                                     quantum_job_id: None,
                                 },
@@ -78,6 +79,7 @@ pub async fn run_job_processor(state: Arc<AppState>) {
                             &job_id,
                             ScheduledJobStatus::Failed {
                                 reason: "Job has no circuits".to_string(),
+                                kind: FailureKind::Application,
                                 slurm_job_id: None,
                                 quantum_job_id: None,
                             },
@@ -97,6 +99,7 @@ pub async fn run_job_processor(state: Arc<AppState>) {
                             &job_id,
                             ScheduledJobStatus::Failed {
                                 reason: format!("Backend submit error: {}", e),
+                                kind: FailureKind::Application,
                                 slurm_job_id: None,
                                 quantum_job_id: None,
                             },
@@ -144,6 +147,7 @@ pub async fn run_job_processor(state: Arc<AppState>) {
                             &job_id,
                             ScheduledJobStatus::Failed {
                                 reason: format!("Backend result error: {}", e),
+                                kind: FailureKind::Application,
                                 slurm_job_id: Some(slurm_job_id),
                                 quantum_job_id: Some(quantum_job_id),
                             },
@@ -41,6 +41,17 @@ pub fn create_router(state: Arc<AppState>) -> Router {
             get(api::jobs::get_job).delete(api::jobs::delete_job),
         )
         .route("/jobs/{id}/result", get(api::jobs::get_job_result))
+        .route("/jobs/{id}/children", get(api::jobs::list_children))
+        .route("/jobs/{id}/explain", get(api::jobs::explain_job))
+        .route("/jobs/{id}/logs", get(api::logs::stream_job_logs))
+        .route("/compare", get(api::compare::compare_jobs))
+        .route(
+            "/workflows/{id}/progress",
+            get(api::workflows::get_workflow_progress),
+        )
+        .route("/workflows/{id}/dot", get(api::workflows::get_workflow_dot))
+        .route("/runs/{id}/iterations", get(api::runs::get_run_iterations))
+        .route("/stats/heatmap", get(api::stats::queue_heatmap_stats))
         .route("/vqe/demo", get(api::vqe::vqe_demo))
         // Evaluator route
         .route("/eval", post(api::eval::evaluate));
@@ -2,14 +2,20 @@
 
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
+use arvak_config::{InvalidKey, Section};
 use arvak_hal::Backend;
-use arvak_sched::StateStore;
+use arvak_sched::{SchedulerHandle, StateStore};
 use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
-/// Dashboard configuration.
-#[derive(Debug, Clone)]
+use crate::stats::StatsCache;
+
+/// Dashboard configuration, read from the `[dashboard]` table of `arvak.toml`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct DashboardConfig {
     /// Address to bind the server to.
     pub bind_address: SocketAddr,
@@ -17,6 +23,12 @@ pub struct DashboardConfig {
     pub default_backend: Option<String>,
     /// Maximum qubits for circuit visualization (performance limit).
     pub max_circuit_qubits: usize,
+    /// Seconds computed statistics are cached for (0 disables caching).
+    pub stats_cache_secs: u64,
+    /// Most bytes of existing job output sent when a log stream starts.
+    pub log_backlog_bytes: u64,
+    /// Longest log line sent, in characters; longer lines are truncated.
+    pub log_max_line_chars: usize,
 }
 
 impl Default for DashboardConfig {
@@ -25,7 +37,30 @@ impl Default for DashboardConfig {
             bind_address: ([127, 0, 0, 1], 3000).into(),
             default_backend: None,
             max_circuit_qubits: 50,
+            stats_cache_secs: 60,
+            log_backlog_bytes: 64 * 1024,
+            log_max_line_chars: 4096,
+        }
+    }
+}
+
+impl Section for DashboardConfig {
+    const NAME: &'static str = "dashboard";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        if self.max_circuit_qubits == 0 {
+            return Err(InvalidKey::new(
+                "max_circuit_qubits",
+                "must be greater than 0",
+            ));
+        }
+        if self.log_max_line_chars == 0 {
+            return Err(InvalidKey::new(
+                "log_max_line_chars",
+                "must be greater than 0",
+            ));
         }
+        Ok(())
     }
 }
 
@@ -37,24 +72,27 @@ pub struct AppState {
     pub config: DashboardConfig,
     /// Job store for persistence (optional).
     pub store: Option<Arc<dyn StateStore>>,
+    /// Handle to the scheduler running the stored jobs, for live job
+    /// output (optional).
+    pub scheduler: Option<SchedulerHandle>,
+    /// Cache of computed statistics.
+    pub stats: StatsCache,
 }
 
 impl AppState {
     /// Create a new application state with default configuration.
     pub fn new() -> Self {
-        Self {
-            backends: Arc::new(RwLock::new(FxHashMap::default())),
-            config: DashboardConfig::default(),
-            store: None,
-        }
+        Self::with_config(DashboardConfig::default())
     }
 
     /// Create application state with custom configuration.
     pub fn with_config(config: DashboardConfig) -> Self {
         Self {
             backends: Arc::new(RwLock::new(FxHashMap::default())),
+            stats: StatsCache::new(Duration::from_secs(config.stats_cache_secs)),
             config,
             store: None,
+            scheduler: None,
         }
     }
 
@@ -64,6 +102,12 @@ impl AppState {
         self
     }
 
+    /// Set the handle to the scheduler running the stored jobs.
+    pub fn with_scheduler(mut self, scheduler: SchedulerHandle) -> Self {
+        self.scheduler = Some(scheduler);
+        self
+    }
+
     /// Register a backend.
     pub async fn register_backend(&self, backend: Arc<dyn Backend>) {
         let name = backend.name().to_string();
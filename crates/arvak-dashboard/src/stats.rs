@@ -0,0 +1,212 @@
+//! Queue statistics computed from the job store.
+//!
+//! The queue heatmap counts submissions and their wait before dispatch by
+//! hour of day (UTC) and backend or partition, so operators can see when
+//! the queue is congested. Heatmaps are cached for a configurable time so
+//! dashboards polling them do not rescan the store on every request.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use arvak_sched::ScheduledJob;
+use chrono::{DateTime, Timelike, Utc};
+use rustc_hash::FxHashMap;
+
+use crate::dto::{HeatmapCell, HeatmapGrouping, HeatmapRow, QueueHeatmap};
+
+/// Default heatmap window: one week.
+pub const DEFAULT_WINDOW_HOURS: u32 = 24 * 7;
+
+/// Longest heatmap window: 90 days.
+pub const MAX_WINDOW_HOURS: u32 = 24 * 90;
+
+/// Job metadata key holding the batch partition a job ran in.
+pub const PARTITION_METADATA_KEY: &str = "partition";
+
+/// Row of jobs without a matched backend or partition.
+const UNASSIGNED: &str = "unassigned";
+
+/// Aggregate jobs created in `[start, end)` into a heatmap.
+///
+/// Wait is the time from creation to dispatch to the batch system; jobs
+/// not dispatched yet count as submissions only.
+pub fn queue_heatmap(
+    jobs: &[ScheduledJob],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    group_by: HeatmapGrouping,
+) -> QueueHeatmap {
+    // Per row and hour: submissions, dispatched, total and max wait.
+    let mut rows: FxHashMap<String, [(u64, u64, f64, f64); 24]> = FxHashMap::default();
+
+    for job in jobs {
+        if job.created_at < start || job.created_at >= end {
+            continue;
+        }
+        let key = match group_by {
+            HeatmapGrouping::Backend => job.matched_backend.clone(),
+            HeatmapGrouping::Partition => job.metadata.get(PARTITION_METADATA_KEY).cloned(),
+        }
+        .unwrap_or_else(|| UNASSIGNED.to_string());
+
+        let cell =
+            &mut rows.entry(key).or_insert([(0, 0, 0.0, 0.0); 24])[job.created_at.hour() as usize];
+        cell.0 += 1;
+        if let Some(submitted_at) = job.submitted_at {
+            let wait = (submitted_at - job.created_at).num_milliseconds().max(0) as f64 / 1000.0;
+            cell.1 += 1;
+            cell.2 += wait;
+            cell.3 = cell.3.max(wait);
+        }
+    }
+
+    let mut rows: Vec<HeatmapRow> = rows
+        .into_iter()
+        .map(|(key, hours)| HeatmapRow {
+            key,
+            cells: hours
+                .iter()
+                .enumerate()
+                .map(
+                    |(hour, &(submissions, dispatched, total_wait, max_wait))| HeatmapCell {
+                        hour: hour as u32,
+                        submissions,
+                        dispatched,
+                        mean_wait_secs: (dispatched > 0).then(|| total_wait / dispatched as f64),
+                        max_wait_secs: (dispatched > 0).then_some(max_wait),
+                    },
+                )
+                .collect(),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.key.cmp(&b.key));
+
+    QueueHeatmap {
+        window_start: start.to_rfc3339(),
+        window_end: end.to_rfc3339(),
+        group_by,
+        rows,
+        computed_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// Cache key of a heatmap: the requested window and grouping.
+type HeatmapKey = (u32, Option<DateTime<Utc>>, HeatmapGrouping);
+
+/// Cache of computed statistics, keyed by the request that produced them.
+pub struct StatsCache {
+    ttl: Duration,
+    heatmaps: Mutex<FxHashMap<HeatmapKey, (Instant, QueueHeatmap)>>,
+}
+
+impl StatsCache {
+    /// Create a cache keeping entries for `ttl`; zero disables caching.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            heatmaps: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Get a cached heatmap for the window of `hours` ending at `until`
+    /// (or now).
+    pub fn heatmap(
+        &self,
+        hours: u32,
+        until: Option<DateTime<Utc>>,
+        group_by: HeatmapGrouping,
+    ) -> Option<QueueHeatmap> {
+        let heatmaps = self.heatmaps.lock().unwrap();
+        heatmaps
+            .get(&(hours, until, group_by))
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, heatmap)| heatmap.clone())
+    }
+
+    /// Cache a heatmap, dropping expired entries.
+    pub fn insert_heatmap(
+        &self,
+        hours: u32,
+        until: Option<DateTime<Utc>>,
+        group_by: HeatmapGrouping,
+        heatmap: QueueHeatmap,
+    ) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut heatmaps = self.heatmaps.lock().unwrap();
+        heatmaps.retain(|_, (at, _)| at.elapsed() < self.ttl);
+        heatmaps.insert((hours, until, group_by), (Instant::now(), heatmap));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_sched::CircuitSpec;
+    use chrono::TimeZone;
+
+    fn job(
+        backend: Option<&str>,
+        created_at: DateTime<Utc>,
+        wait_secs: Option<i64>,
+    ) -> ScheduledJob {
+        let mut job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        job.matched_backend = backend.map(str::to_string);
+        job.created_at = created_at;
+        job.submitted_at = wait_secs.map(|s| created_at + chrono::Duration::seconds(s));
+        job
+    }
+
+    #[test]
+    fn test_queue_heatmap() {
+        let at = |h, m| Utc.with_ymd_and_hms(2026, 3, 2, h, m, 0).unwrap();
+        let jobs = vec![
+            job(Some("iqm"), at(9, 0), Some(30)),
+            job(Some("iqm"), at(9, 40), Some(90)),
+            job(Some("iqm"), at(14, 0), None),
+            job(None, at(9, 5), Some(10)),
+            // Outside the window.
+            job(Some("iqm"), at(23, 0), Some(10)),
+        ];
+
+        let heatmap = queue_heatmap(&jobs, at(0, 0), at(20, 0), HeatmapGrouping::Backend);
+        let keys: Vec<_> = heatmap.rows.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, ["iqm", UNASSIGNED]);
+
+        let iqm = &heatmap.rows[0].cells;
+        assert_eq!(iqm.len(), 24);
+        assert_eq!((iqm[9].submissions, iqm[9].dispatched), (2, 2));
+        assert_eq!(iqm[9].mean_wait_secs, Some(60.0));
+        assert_eq!(iqm[9].max_wait_secs, Some(90.0));
+        assert_eq!((iqm[14].submissions, iqm[14].mean_wait_secs), (1, None));
+        assert_eq!(iqm[23].submissions, 0);
+
+        let by_partition = queue_heatmap(&jobs, at(0, 0), at(20, 0), HeatmapGrouping::Partition);
+        assert_eq!(by_partition.rows.len(), 1);
+        assert_eq!(by_partition.rows[0].cells[9].submissions, 3);
+    }
+
+    #[test]
+    fn test_stats_cache() {
+        let heatmap = queue_heatmap(&[], Utc::now(), Utc::now(), HeatmapGrouping::Backend);
+
+        let cache = StatsCache::new(Duration::from_secs(60));
+        cache.insert_heatmap(24, None, HeatmapGrouping::Backend, heatmap.clone());
+        assert!(cache.heatmap(24, None, HeatmapGrouping::Backend).is_some());
+        assert!(cache.heatmap(48, None, HeatmapGrouping::Backend).is_none());
+        assert!(
+            cache
+                .heatmap(24, None, HeatmapGrouping::Partition)
+                .is_none()
+        );
+
+        let disabled = StatsCache::new(Duration::ZERO);
+        disabled.insert_heatmap(24, None, HeatmapGrouping::Backend, heatmap);
+        assert!(
+            disabled
+                .heatmap(24, None, HeatmapGrouping::Backend)
+                .is_none()
+        );
+    }
+}
@@ -10,8 +10,12 @@ const state = {
     compileResult: null,
     backends: [],
     jobs: [],
+    runTimer: null,
 };
 
+// Poll interval for live run charts
+const RUN_POLL_MS = 3000;
+
 // ============================================================================
 // API Client
 // ============================================================================
@@ -112,6 +116,15 @@ const api = {
         return res.json();
     },
 
+    async getJobExplanation(id) {
+        const res = await fetch(`/api/jobs/${encodeURIComponent(id)}/explain`);
+        if (!res.ok) {
+            const error = await res.json();
+            throw new Error(error.message || 'Failed to explain job');
+        }
+        return res.json();
+    },
+
     async getJobResult(id) {
         const res = await fetch(`/api/jobs/${encodeURIComponent(id)}/result`);
         if (!res.ok) {
@@ -134,6 +147,15 @@ const api = {
         return res.json();
     },
 
+    async getRunIterations(id) {
+        const res = await fetch(`/api/runs/${encodeURIComponent(id)}/iterations`);
+        if (!res.ok) {
+            const error = await res.json().catch(() => ({}));
+            throw new Error(error.message || 'Failed to load run iterations');
+        }
+        return res.json();
+    },
+
     async getVqeDemo() {
         const res = await fetch('/api/vqe/demo');
         if (!res.ok) {
@@ -380,6 +402,7 @@ const circuitRenderer = {
 
 function showView(viewName) {
     state.currentView = viewName;
+    stopFollowingRun();
 
     // Update hash without triggering hashchange
     history.replaceState(null, '', '#' + viewName);
@@ -691,6 +714,10 @@ async function viewJobDetails(jobId) {
                     <textarea readonly rows="10">${escapeHtml(job.qasm)}</textarea>
                 </div>` : ''}
 
+                <div id="job-explain-container"></div>
+
+                ${renderJobLineage(job.lineage)}
+
                 ${Object.keys(job.metadata || {}).length > 0 ? `
                 <div class="job-metadata">
                     <h4>Metadata</h4>
@@ -708,6 +735,11 @@ async function viewJobDetails(jobId) {
 
         container.innerHTML = detailsHtml;
 
+        // If job is still waiting, explain what holds it back
+        if (isJobWaiting(job.status)) {
+            viewJobExplanation(job.id);
+        }
+
         // If job is complete, automatically load results
         if (isJobComplete(job.status)) {
             viewJobResult(job.id);
@@ -717,6 +749,54 @@ async function viewJobDetails(jobId) {
     }
 }
 
+function renderJobLineage(lineage) {
+    if (!lineage || (lineage.ancestors.length === 0 && lineage.children.length === 0)) {
+        return '';
+    }
+    const jobLink = (id) => `<a href="#" onclick="viewJobDetails('${id}'); return false;">${id}</a>`;
+    return `
+                <div class="job-lineage">
+                    <h4>Lineage</h4>
+                    ${lineage.derivation ? `<p>Derived by <code>${escapeHtml(lineage.derivation)}</code></p>` : ''}
+                    ${lineage.ancestors.length > 0 ? `<p>Parent: ${jobLink(lineage.ancestors[0])}${lineage.ancestors.length > 1 ? ` (root: ${jobLink(lineage.ancestors[lineage.ancestors.length - 1])})` : ''}</p>` : ''}
+                    ${lineage.children.length > 0 ? `<p>Children: ${lineage.children.map(jobLink).join(', ')}</p>` : ''}
+                </div>`;
+}
+
+function isJobWaiting(status) {
+    return ['pending', 'waitingondependencies', 'slurmqueued'].includes(status.toLowerCase());
+}
+
+async function viewJobExplanation(jobId) {
+    const container = document.getElementById('job-explain-container');
+    if (!container) return;
+
+    try {
+        const explanation = await api.getJobExplanation(jobId);
+        const reasons = { higher_priority: 'higher priority', queued_earlier: 'queued earlier' };
+        const jobLink = (id, name) => `<a href="#" onclick="viewJobDetails('${id}'); return false;">${escapeHtml(name)}</a>`;
+
+        container.innerHTML = `
+                <div class="job-explain">
+                    <h4>Why Queued?</h4>
+                    ${explanation.position ? `<p>Position ${explanation.position} of ${explanation.queue_length} in the queue.</p>` : ''}
+                    ${explanation.ahead.length > 0 ? `
+                    <p>Ahead:</p>
+                    <ul>
+                        ${explanation.ahead.map(a => `<li>${jobLink(a.id, a.name)} (priority ${a.priority}${a.owner ? `, ${escapeHtml(a.owner)}` : ''}): ${reasons[a.reason] || a.reason}</li>`).join('')}
+                    </ul>` : ''}
+                    ${explanation.holds.length > 0 ? `
+                    <p>Holds:</p>
+                    <ul>
+                        ${explanation.holds.map(h => `<li>${escapeHtml(h)}</li>`).join('')}
+                    </ul>` : ''}
+                    ${!explanation.position && explanation.holds.length === 0 ? '<p>Nothing is holding this job back.</p>' : ''}
+                </div>`;
+    } catch (error) {
+        showError(container, error.message);
+    }
+}
+
 function isJobComplete(status) {
     return ['completed', 'succeeded'].includes(status.toLowerCase());
 }
@@ -920,6 +1000,90 @@ async function loadVqe() {
     }
 }
 
+// ============================================================================
+// Live Run Controller
+// ============================================================================
+
+function stopFollowingRun() {
+    if (state.runTimer) {
+        clearInterval(state.runTimer);
+        state.runTimer = null;
+    }
+}
+
+function followRun() {
+    const runId = document.getElementById('vqe-run-id').value.trim();
+    stopFollowingRun();
+    if (!runId) {
+        loadVqe();
+        return;
+    }
+    loadRun(runId);
+    state.runTimer = setInterval(() => loadRun(runId), RUN_POLL_MS);
+}
+
+function showVqeDemo() {
+    stopFollowingRun();
+    document.getElementById('vqe-run-id').value = '';
+    loadVqe();
+}
+
+async function loadRun(runId) {
+    const container = document.getElementById('vqe-chart-container');
+    const legend = document.getElementById('vqe-legend');
+    const info = document.getElementById('vqe-info');
+
+    try {
+        const data = await api.getRunIterations(runId);
+        const iterations = data.iterations;
+        const latest = iterations[iterations.length - 1];
+        const values = iterations.map(d => d.value);
+
+        renderVQEChart(container, {
+            iterations: iterations.map(d => ({ iteration: d.iteration, energy: d.value })),
+            exact_energy: null,
+            value_label: 'Objective',
+        });
+
+        legend.innerHTML = `
+            <span class="vqe-legend-item">
+                <span class="vqe-legend-swatch" style="background: var(--accent);"></span>
+                ${escapeHtml(data.run_id)} (updates every ${RUN_POLL_MS / 1000}s)
+            </span>
+        `;
+
+        info.innerHTML = `
+            <div class="detail-item">
+                <span class="label">Iterations</span>
+                <span class="value">${iterations.length}</span>
+            </div>
+            <div class="detail-item">
+                <span class="label">Latest Value</span>
+                <span class="value">${latest.value.toFixed(6)}${latest.std_error != null ? ` &plusmn; ${latest.std_error.toExponential(2)}` : ''}</span>
+            </div>
+            <div class="detail-item">
+                <span class="label">Lowest Value</span>
+                <span class="value">${Math.min(...values).toFixed(6)}</span>
+            </div>
+            <div class="detail-item">
+                <span class="label">Highest Value</span>
+                <span class="value">${Math.max(...values).toFixed(6)}</span>
+            </div>
+            <div class="detail-item">
+                <span class="label">Latest Jobs</span>
+                <span class="value">${latest.job_ids.map(escapeHtml).join(', ') || '-'}</span>
+            </div>
+            <div class="detail-item">
+                <span class="label">Updated</span>
+                <span class="value">${new Date(latest.recorded_at).toLocaleTimeString()}</span>
+            </div>
+        `;
+    } catch (error) {
+        stopFollowingRun();
+        showError(container, error.message);
+    }
+}
+
 function renderVQEChart(container, data) {
     if (!container || !data.iterations || data.iterations.length === 0) return;
 
@@ -946,19 +1110,22 @@ function renderVQEChart(container, data) {
 
     // Y scale — energy
     const energies = iterations.map(d => d.energy);
-    const yMin = Math.min(d3.min(energies), exactEnergy) - 0.02;
+    const hasExact = exactEnergy != null;
+    const yMin = (hasExact ? Math.min(d3.min(energies), exactEnergy) : d3.min(energies)) - 0.02;
     const yMax = d3.max(energies) + 0.02;
     const y = d3.scaleLinear()
         .domain([yMin, yMax])
         .range([height, 0]);
 
     // Exact energy reference line (drawn first so it's behind)
-    svg.append('line')
-        .attr('class', 'vqe-exact')
-        .attr('x1', 0)
-        .attr('y1', y(exactEnergy))
-        .attr('x2', width)
-        .attr('y2', y(exactEnergy));
+    if (hasExact) {
+        svg.append('line')
+            .attr('class', 'vqe-exact')
+            .attr('x1', 0)
+            .attr('y1', y(exactEnergy))
+            .attr('x2', width)
+            .attr('y2', y(exactEnergy));
+    }
 
     // Line generator
     const line = d3.line()
@@ -1008,7 +1175,7 @@ function renderVQEChart(container, data) {
         .attr('y', -margin.left + 15)
         .attr('x', -(height / 2))
         .style('text-anchor', 'middle')
-        .text('Energy (Ha)');
+        .text(data.value_label || 'Energy (Ha)');
 }
 
 async function cancelJob(jobId) {
@@ -1425,6 +1592,8 @@ document.addEventListener('DOMContentLoaded', async () => {
     document.getElementById('refresh-jobs-btn').addEventListener('click', loadJobs);
     document.getElementById('eval-run-btn').addEventListener('click', runEvaluation);
     document.getElementById('eval-export-btn').addEventListener('click', exportEvalJson);
+    document.getElementById('vqe-follow-btn').addEventListener('click', followRun);
+    document.getElementById('vqe-demo-btn').addEventListener('click', showVqeDemo);
 
     // Allow Ctrl+Enter to visualize
     document.getElementById('qasm-input').addEventListener('keydown', e => {
@@ -118,7 +118,7 @@ impl ContractChecker {
                     (
                         "shuttle".into(),
                         tag,
-                        format!("Shuttle requires shuttling capability"),
+                        "Shuttle requires shuttling capability".to_string(),
                     )
                 }
             };
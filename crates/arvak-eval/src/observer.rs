@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use tracing::debug;
 
+use arvak_compile::passes::SwapAbsorptionResult;
 use arvak_compile::{PassManager, PropertySet};
 use arvak_ir::CircuitDag;
 use arvak_ir::instruction::InstructionKind;
@@ -116,6 +117,13 @@ pub struct CompilationObserver {
     pub final_metrics: CircuitSnapshot,
     /// The compiled DAG (for downstream contract checking).
     pub final_dag: CircuitDag,
+    /// CX savings from SWAP absorption, if the pass ran.
+    pub swap_absorption: Option<SwapAbsorptionResult>,
+    /// Seed used by stochastic passes.
+    pub seed: Option<u64>,
+    /// Estimated success probability of the compiled circuit, if the
+    /// target carried error rates.
+    pub esp: Option<f64>,
 }
 
 impl CompilationObserver {
@@ -164,6 +172,12 @@ impl CompilationObserver {
             initial_metrics,
             final_metrics: after_all,
             final_dag: dag.clone(),
+            swap_absorption: props.get::<SwapAbsorptionResult>().cloned(),
+            seed: props.seed,
+            esp: props
+                .error_rates
+                .as_ref()
+                .map(|rates| rates.estimated_success_probability(dag)),
         })
     }
 
@@ -175,6 +189,9 @@ impl CompilationObserver {
             final_snapshot: self.final_metrics,
             overall_delta,
             passes: self.pass_records,
+            swap_absorption: self.swap_absorption,
+            seed: self.seed,
+            esp: self.esp,
         }
     }
 }
@@ -190,12 +207,24 @@ pub struct CompilationReport {
     pub overall_delta: SnapshotDelta,
     /// Per-pass records.
     pub passes: Vec<PassRecord>,
+    /// CX savings from SWAP absorption, if the pass ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swap_absorption: Option<SwapAbsorptionResult>,
+    /// Seed used by stochastic passes; recompiling with it reproduces the
+    /// output exactly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Estimated success probability: the product of `1 - error` over every
+    /// gate and measurement of the compiled circuit, from the target's
+    /// error rates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub esp: Option<f64>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arvak_compile::PassManagerBuilder;
+    use arvak_compile::{BasisGates, CouplingMap, ErrorRates, PassManagerBuilder};
     use arvak_ir::{Circuit, QubitId};
 
     #[test]
@@ -248,5 +277,57 @@ mod tests {
 
         assert!(!observer.pass_records.is_empty());
         assert_eq!(observer.initial_metrics.depth, observer.final_metrics.depth);
+        assert!(observer.swap_absorption.is_none());
+        assert!(observer.esp.is_none());
+    }
+
+    #[test]
+    fn test_esp_reported() {
+        let mut circuit = Circuit::with_size("test", 2, 2);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+        circuit.measure_all().unwrap();
+
+        let rates = ErrorRates::new()
+            .with_two_qubit_error(0, 1, 0.05)
+            .with_readout_error(0, 0.1)
+            .with_readout_error(1, 0.1);
+        let (pm, mut props) = PassManagerBuilder::new()
+            .with_optimization_level(1)
+            .with_target(CouplingMap::linear(2), BasisGates::ibm())
+            .with_error_rates(rates)
+            .build();
+
+        let mut dag = circuit.into_dag();
+        let report = CompilationObserver::observe(&pm, &mut dag, &mut props)
+            .unwrap()
+            .into_report();
+
+        // One CX and two readouts; single-qubit gates carry no error here
+        let esp = report.esp.unwrap();
+        assert!((esp - 0.95 * 0.9 * 0.9).abs() < 1e-9, "esp = {}", esp);
+    }
+
+    #[test]
+    fn test_swap_absorption_reported() {
+        let mut circuit = Circuit::with_size("test", 3, 0);
+        circuit.swap(QubitId(0), QubitId(1)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+
+        let (pm, mut props) = PassManagerBuilder::new()
+            .with_optimization_level(2)
+            .with_target(CouplingMap::linear(3), BasisGates::ibm())
+            .with_seed(11)
+            .build();
+
+        let mut dag = circuit.into_dag();
+        let report = CompilationObserver::observe(&pm, &mut dag, &mut props)
+            .unwrap()
+            .into_report();
+
+        let absorbed = report.swap_absorption.unwrap();
+        assert_eq!(absorbed.swaps_absorbed, 1);
+        assert_eq!(absorbed.cx_saved, 2);
+        assert_eq!(report.seed, Some(11));
     }
 }
@@ -253,7 +253,7 @@ impl OrchestrationAnalyzer {
                     };
 
                     // Edge: quantum -> classical (measurement results)
-                    if nodes.len() >= 1 {
+                    if !nodes.is_empty() {
                         edges.push(HybridEdge {
                             from: phase_index - 1,
                             to: phase_index,
@@ -424,8 +424,7 @@ impl OrchestrationAnalyzer {
             // Find the latest group of any predecessor
             let max_pred_group = dependent_on
                 .get(&qn)
-                .map(|preds| preds.iter().filter_map(|p| assigned.get(p)).max().copied())
-                .flatten();
+                .and_then(|preds| preds.iter().filter_map(|p| assigned.get(p)).max().copied());
 
             let group = match max_pred_group {
                 Some(g) => g + 1,
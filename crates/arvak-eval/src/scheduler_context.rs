@@ -148,7 +148,7 @@ impl SchedulerContext {
     }
 
     /// Estimate walltime for a circuit.
-    fn estimate_walltime(
+    pub fn estimate_walltime(
         circuit_depth: usize,
         _total_ops: usize,
         constraints: &SchedulerConstraints,
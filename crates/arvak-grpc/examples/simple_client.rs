@@ -44,6 +44,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }),
         backend_id: "simulator".to_string(),
         shots: 1000,
+        deadline: 0,
     });
 
     let submit_response = client.submit_job(submit_request).await?;
@@ -87,6 +88,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nRetrieving results...");
     let result_request = Request::new(GetJobResultRequest {
         job_id: job_id.clone(),
+        format: PayloadFormat::Json as i32,
     });
 
     let result_response = client.get_job_result(result_request).await?;
@@ -36,6 +36,7 @@ cx q[0], q[1];
         }),
         backend_id: "simulator".to_string(),
         shots: 1000,
+        deadline: 0,
     };
 
     let response = client.submit_job(submit_req).await?;
@@ -115,6 +116,7 @@ h q[0];
                 backend_id: "simulator".to_string(),
                 shots: 100,
                 client_request_id: format!("batch-job-{}", i),
+                result_format: PayloadFormat::Json as i32,
             };
 
             // Small delay between submissions
@@ -6,12 +6,18 @@
 //! # Configuration
 //!
 //! The server can be configured via:
-//! 1. Configuration file: `--config path/to/config.yaml`
+//! 1. Configuration file: `--config path/to/config.yaml`, or the `[grpc]`
+//!    table of `--config arvak.toml` (an `arvak.toml` in the working
+//!    directory is picked up automatically)
 //! 2. Environment variables: `ARVAK_*`
 //! 3. .env file in working directory
 //!
 //! Environment variables override configuration file settings.
 //!
+//! API keys listed under `[grpc.auth]` in `arvak.toml` are read from their
+//! secret sources at startup; clients must then send one as
+//! `authorization: Bearer <key>`.
+//!
 //! # Usage
 //!
 //! ```bash
@@ -33,13 +39,15 @@
 //! - Shuts down gRPC and HTTP servers cleanly
 
 use arvak_grpc::proto::arvak_service_server::ArvakServiceServer;
-use arvak_grpc::server::{RequestIdInterceptor, TimingLayer};
+use arvak_grpc::server::{AuditInterceptor, AuthInterceptor, RequestIdInterceptor, TimingLayer};
 use arvak_grpc::{
     ArvakServiceImpl, Config, HealthState, Metrics, TracingConfig, TracingFormat, init_tracing,
     start_health_server,
 };
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Notify;
+use tonic::service::interceptor::InterceptedService;
 use tonic::transport::Server;
 use tower::ServiceBuilder;
 use tracing::{error, info, warn};
@@ -78,11 +86,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Create service with resource limits
     use arvak_grpc::server::{JobStore, backend_registry::create_default_registry};
-    let service = ArvakServiceImpl::with_limits(
-        JobStore::new(),
+    let mut service = ArvakServiceImpl::with_limits(
+        JobStore::new()
+            .with_idempotency_ttl(Duration::from_secs(config.storage.idempotency_ttl_seconds)),
         create_default_registry(),
         config.limits.clone(),
     );
+    if let Some(shots) = config.streaming.partial_shots {
+        service = service.with_partial_results(shots);
+    }
+    if let Some(path) = &config.storage.scheduler_db {
+        let store = arvak_sched::SqliteStore::new(path)?;
+        info!("Workflow progress served from {}", path);
+        service = service.with_workflow_store(Arc::new(store));
+    }
     let backend_registry = service.backends();
 
     // Set up graceful shutdown
@@ -141,9 +158,39 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     );
     info!("Graceful shutdown timeout: {}s", shutdown_timeout);
 
-    // Build gRPC server with middleware and interceptors
-    let service_with_interceptor =
-        ArvakServiceServer::with_interceptor(service, RequestIdInterceptor::new());
+    // Resolve API keys before accepting connections
+    let auth = AuthInterceptor::new()
+        .with_api_keys(config.auth.resolve_api_keys()?)
+        .with_admin_keys(config.auth.resolve_admin_api_keys()?);
+    if auth.is_enabled() {
+        info!("API key authentication enabled");
+    }
+
+    let audit = if config.audit.enabled {
+        let log: Arc<dyn arvak_sched::AuditLog> = match &config.audit.path {
+            Some(path) => {
+                info!("Audit records appended to {}", path);
+                Arc::new(arvak_sched::JsonlAuditLog::open(path)?)
+            }
+            None => Arc::new(arvak_sched::TracingAuditLog),
+        };
+        let mut audit = AuditInterceptor::new(log)
+            .with_sample_rate(config.audit.sample_rate)
+            .with_max_payload_bytes(config.audit.max_payload_bytes);
+        for (method, rate) in &config.audit.method_sample_rates {
+            audit = audit.with_method_sample_rate(method, *rate);
+        }
+        audit
+    } else {
+        AuditInterceptor::disabled()
+    };
+
+    // Build gRPC server with middleware and interceptors; request IDs are
+    // assigned before authentication so rejected requests can be traced
+    let service_with_interceptor = InterceptedService::new(
+        ArvakServiceServer::with_interceptor(service, auth),
+        RequestIdInterceptor::new(),
+    );
 
     // Enable gRPC reflection for tools like grpcurl
     let reflection_service = tonic_reflection::server::Builder::configure()
@@ -157,7 +204,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .tcp_keepalive(Some(std::time::Duration::from_secs(
             config.server.keepalive_seconds,
         )))
-        .layer(ServiceBuilder::new().layer(TimingLayer::new()).into_inner())
+        .layer(
+            ServiceBuilder::new()
+                .layer(TimingLayer::new())
+                .layer(audit)
+                .into_inner(),
+        )
         .add_service(reflection_service)
         .add_service(service_with_interceptor)
         .serve_with_shutdown(grpc_addr, async move {
@@ -0,0 +1,48 @@
+//! Error types for the gRPC client.
+
+use thiserror::Error;
+
+/// Result type for client operations.
+pub type ClientResult<T> = std::result::Result<T, ClientError>;
+
+/// Errors returned by [`ArvakClient`](super::ArvakClient).
+#[derive(Debug, Error)]
+pub enum ClientError {
+    /// Connecting to the server failed.
+    #[error("Transport error: {0}")]
+    Transport(#[from] tonic::transport::Error),
+
+    /// The server rejected or failed the call.
+    #[error("RPC failed: {0}")]
+    Status(Box<tonic::Status>),
+
+    /// The job failed on the server.
+    #[error("Job {job_id} failed: {message}")]
+    JobFailed { job_id: String, message: String },
+
+    /// The job was canceled.
+    #[error("Job {0} was canceled")]
+    JobCanceled(String),
+
+    /// The job did not finish in time.
+    #[error("Timed out waiting for job {0}")]
+    Timeout(String),
+
+    /// A circuit could not be converted to or from OpenQASM 3.
+    #[error("Circuit error: {0}")]
+    Circuit(#[from] arvak_qasm3::ParseError),
+
+    /// The server sent a response the client cannot interpret.
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The client configuration is invalid.
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+}
+
+impl From<tonic::Status> for ClientError {
+    fn from(status: tonic::Status) -> Self {
+        ClientError::Status(Box::new(status))
+    }
+}
@@ -0,0 +1,554 @@
+//! Typed client for the Arvak gRPC service.
+//!
+//! [`ArvakClient`] wraps the generated tonic stub: circuits go in as
+//! [`Circuit`]s, results come back as [`ExecutionResult`]s, and every call
+//! carries the configured deadline and API key. Idempotent calls (status,
+//! results, backend queries, compilation) are retried on transient failures
+//! according to the [`RetryPolicy`]. Submissions are retried too, under an
+//! idempotency key the server deduplicates them by; cancellations are sent
+//! once. Calls are spread round-robin over a pool of connections.
+//!
+//! ```rust,no_run
+//! use arvak_grpc::client::{ArvakClient, ClientConfig};
+//! use arvak_ir::Circuit;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> arvak_grpc::client::ClientResult<()> {
+//! let client = ArvakClient::connect(ClientConfig::new("http://localhost:50051")).await?;
+//! let job_id = client.submit(&Circuit::bell().unwrap(), "simulator", 1000).await?;
+//! let result = client.wait(&job_id, Duration::from_secs(60)).await?;
+//! println!("{:?}", result.counts.most_frequent());
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod error;
+pub mod retry;
+
+pub use error::{ClientError, ClientResult};
+pub use retry::RetryPolicy;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use arvak_compile::PipelinePreset;
+use arvak_hal::{Counts, ExecutionResult};
+use arvak_ir::Circuit;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Response, Status};
+
+use crate::proto::arvak_service_client::ArvakServiceClient;
+use crate::proto::{
+    BackendInfo, CancelJobRequest, CircuitPayload, CompileRequest, GetBackendInfoRequest,
+    GetJobResultRequest, GetJobStatusRequest, Job, JobResult, JobState, JobStatusUpdate,
+    ListBackendsRequest, SubmitJobRequest, WatchJobRequest, circuit_payload,
+};
+use crate::server::interceptors::{API_KEY_HEADER, IDEMPOTENCY_KEY_HEADER};
+
+/// Connection and call settings of an [`ArvakClient`].
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Server URL, e.g. `http://localhost:50051`.
+    pub endpoint: String,
+
+    /// Number of connections calls are spread over.
+    pub pool_size: usize,
+
+    /// Time allowed to establish a connection.
+    pub connect_timeout: Duration,
+
+    /// Deadline of each call, sent to the server as `grpc-timeout`.
+    pub timeout: Option<Duration>,
+
+    /// Interval between status polls in [`ArvakClient::wait`].
+    pub poll_interval: Duration,
+
+    /// Retry policy for idempotent calls.
+    pub retry: RetryPolicy,
+
+    /// API key sent with every call.
+    pub api_key: Option<String>,
+}
+
+impl ClientConfig {
+    /// Create a configuration for the server at `endpoint`.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            pool_size: 1,
+            connect_timeout: Duration::from_secs(10),
+            timeout: Some(Duration::from_secs(30)),
+            poll_interval: Duration::from_millis(500),
+            retry: RetryPolicy::default(),
+            api_key: None,
+        }
+    }
+
+    /// Set the number of pooled connections.
+    pub fn with_pool_size(mut self, pool_size: usize) -> Self {
+        self.pool_size = pool_size;
+        self
+    }
+
+    /// Set the connection timeout.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set the deadline of each call, or `None` for no deadline.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the status poll interval of [`ArvakClient::wait`].
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the retry policy for idempotent calls.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Authenticate with an API key.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+/// Options of [`ArvakClient::compile`].
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions {
+    /// Compile for this backend's topology and native gates.
+    pub backend_id: Option<String>,
+
+    /// Named pipeline preset; overrides the optimization level.
+    pub preset: Option<PipelinePreset>,
+
+    /// Optimization level 0-3, used without a preset.
+    pub optimization_level: u32,
+
+    /// Seed for stochastic passes.
+    pub seed: Option<u64>,
+}
+
+impl CompileOptions {
+    /// Compile with a pipeline preset.
+    pub fn preset(preset: PipelinePreset) -> Self {
+        Self {
+            preset: Some(preset),
+            ..Self::default()
+        }
+    }
+
+    /// Compile for a backend's topology and native gates.
+    pub fn with_backend(mut self, backend_id: impl Into<String>) -> Self {
+        self.backend_id = Some(backend_id.into());
+        self
+    }
+
+    /// Set the optimization level.
+    pub fn with_optimization_level(mut self, level: u32) -> Self {
+        self.optimization_level = level;
+        self
+    }
+
+    /// Set the seed for stochastic passes.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+/// A circuit compiled by the server.
+#[derive(Debug, Clone)]
+pub struct CompiledCircuit {
+    /// The compiled circuit.
+    pub circuit: Circuit,
+
+    /// The compiled circuit as OpenQASM 3.
+    pub qasm: String,
+
+    /// Circuit depth.
+    pub depth: u32,
+
+    /// Number of operations.
+    pub num_ops: u32,
+}
+
+/// Typed, retrying client for the Arvak gRPC service.
+///
+/// Cloning is cheap and clones share the connection pool.
+#[derive(Clone)]
+pub struct ArvakClient {
+    channels: Arc<[Channel]>,
+    next: Arc<AtomicUsize>,
+    api_key: Option<MetadataValue<tonic::metadata::Ascii>>,
+    timeout: Option<Duration>,
+    poll_interval: Duration,
+    retry: RetryPolicy,
+}
+
+impl ArvakClient {
+    /// Connect to the server, opening `pool_size` connections.
+    pub async fn connect(config: ClientConfig) -> ClientResult<Self> {
+        if config.pool_size == 0 {
+            return Err(ClientError::InvalidConfig(
+                "pool_size must be greater than 0".into(),
+            ));
+        }
+        let api_key = config
+            .api_key
+            .as_deref()
+            .map(|key| {
+                key.parse()
+                    .map_err(|_| ClientError::InvalidConfig("API key is not valid ASCII".into()))
+            })
+            .transpose()?;
+
+        let endpoint =
+            Endpoint::from_shared(config.endpoint.clone())?.connect_timeout(config.connect_timeout);
+        let mut channels = Vec::with_capacity(config.pool_size);
+        for _ in 0..config.pool_size {
+            channels.push(endpoint.connect().await?);
+        }
+
+        Ok(Self {
+            channels: channels.into(),
+            next: Arc::new(AtomicUsize::new(0)),
+            api_key,
+            timeout: config.timeout,
+            poll_interval: config.poll_interval,
+            retry: config.retry,
+        })
+    }
+
+    /// A client sharing this one's connections with a different per-call
+    /// deadline.
+    pub fn with_timeout(&self, timeout: Option<Duration>) -> Self {
+        Self {
+            timeout,
+            ..self.clone()
+        }
+    }
+
+    /// The deadline of each call.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Number of pooled connections.
+    pub fn pool_size(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Submit a circuit; returns the job ID.
+    pub async fn submit(
+        &self,
+        circuit: &Circuit,
+        backend_id: &str,
+        shots: u32,
+    ) -> ClientResult<String> {
+        self.submit_qasm(&arvak_qasm3::emit(circuit)?, backend_id, shots)
+            .await
+    }
+
+    /// Submit an OpenQASM 3 circuit; returns the job ID.
+    ///
+    /// Every attempt carries the same fresh idempotency key, so a retry of a
+    /// submission the server already accepted returns the original job
+    /// instead of running the circuit twice.
+    pub async fn submit_qasm(
+        &self,
+        qasm: &str,
+        backend_id: &str,
+        shots: u32,
+    ) -> ClientResult<String> {
+        self.submit_message(SubmitJobRequest {
+            circuit: Some(qasm_payload(qasm)),
+            backend_id: backend_id.to_string(),
+            shots,
+            deadline: 0,
+        })
+        .await
+    }
+
+    /// Submit a circuit that must finish by `deadline`; returns the job ID.
+    ///
+    /// The server rejects deadlines that have already passed, and fails the
+    /// job if it is still running at its deadline.
+    pub async fn submit_by(
+        &self,
+        circuit: &Circuit,
+        backend_id: &str,
+        shots: u32,
+        deadline: DateTime<Utc>,
+    ) -> ClientResult<String> {
+        self.submit_message(SubmitJobRequest {
+            circuit: Some(qasm_payload(&arvak_qasm3::emit(circuit)?)),
+            backend_id: backend_id.to_string(),
+            shots,
+            deadline: deadline.timestamp(),
+        })
+        .await
+    }
+
+    /// Submit a job under a fresh idempotency key.
+    async fn submit_message(&self, message: SubmitJobRequest) -> ClientResult<String> {
+        let key: MetadataValue<Ascii> = uuid::Uuid::new_v4()
+            .to_string()
+            .parse()
+            .expect("UUIDs are valid metadata");
+        let response = self
+            .retry
+            .run(|_| {
+                let mut request = self.request(message.clone());
+                request
+                    .metadata_mut()
+                    .insert(IDEMPOTENCY_KEY_HEADER, key.clone());
+                let mut stub = self.stub();
+                async move { stub.submit_job(request).await }
+            })
+            .await?;
+        Ok(response.into_inner().job_id)
+    }
+
+    /// Get the status of a job.
+    pub async fn status(&self, job_id: &str) -> ClientResult<Job> {
+        let message = GetJobStatusRequest {
+            job_id: job_id.to_string(),
+        };
+        let response = self
+            .idempotent(
+                |mut stub, request| async move { stub.get_job_status(request).await },
+                message,
+            )
+            .await?;
+        response
+            .job
+            .ok_or_else(|| ClientError::InvalidResponse("status response without job".into()))
+    }
+
+    /// Get the result of a completed job.
+    pub async fn result(&self, job_id: &str) -> ClientResult<ExecutionResult> {
+        let message = GetJobResultRequest {
+            job_id: job_id.to_string(),
+            format: 0,
+        };
+        let response = self
+            .idempotent(
+                |mut stub, request| async move { stub.get_job_result(request).await },
+                message,
+            )
+            .await?;
+        let result = response
+            .result
+            .ok_or_else(|| ClientError::InvalidResponse("result response without result".into()))?;
+        execution_result(result)
+    }
+
+    /// Cancel a job; returns whether it was canceled.
+    pub async fn cancel(&self, job_id: &str) -> ClientResult<bool> {
+        let message = CancelJobRequest {
+            job_id: job_id.to_string(),
+        };
+        let response = self.stub().cancel_job(self.request(message)).await?;
+        Ok(response.into_inner().success)
+    }
+
+    /// Poll a job until it finishes and return its result.
+    ///
+    /// Fails with [`ClientError::JobFailed`] or [`ClientError::JobCanceled`]
+    /// if the job does not complete, and with [`ClientError::Timeout`] if it
+    /// is still running after `max_wait`.
+    pub async fn wait(&self, job_id: &str, max_wait: Duration) -> ClientResult<ExecutionResult> {
+        let deadline = Instant::now() + max_wait;
+        loop {
+            let job = self.status(job_id).await?;
+            match JobState::try_from(job.state).unwrap_or(JobState::Unspecified) {
+                JobState::Completed => return self.result(job_id).await,
+                JobState::Failed => {
+                    return Err(ClientError::JobFailed {
+                        job_id: job_id.to_string(),
+                        message: job.error_message,
+                    });
+                }
+                JobState::Canceled => return Err(ClientError::JobCanceled(job_id.to_string())),
+                _ => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(ClientError::Timeout(job_id.to_string()));
+            }
+            tokio::time::sleep(self.poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// Stream status updates of a job until it finishes.
+    ///
+    /// Opening the stream is retried; the stream itself carries no deadline.
+    pub async fn watch(
+        &self,
+        job_id: &str,
+    ) -> ClientResult<impl Stream<Item = ClientResult<JobStatusUpdate>> + use<>> {
+        let message = WatchJobRequest {
+            job_id: job_id.to_string(),
+        };
+        let stream = self
+            .retry
+            .run(|_| {
+                let mut stub = self.stub();
+                // A deadline would end the stream while the job runs.
+                let request = self.request_with_timeout(message.clone(), None);
+                async move { stub.watch_job(request).await }
+            })
+            .await?
+            .into_inner();
+        Ok(stream.map(|update| update.map_err(ClientError::from)))
+    }
+
+    /// List the server's backends.
+    pub async fn list_backends(&self) -> ClientResult<Vec<BackendInfo>> {
+        let response = self
+            .idempotent(
+                |mut stub, request| async move { stub.list_backends(request).await },
+                ListBackendsRequest {},
+            )
+            .await?;
+        Ok(response.backends)
+    }
+
+    /// Get information about one backend.
+    pub async fn backend(&self, backend_id: &str) -> ClientResult<BackendInfo> {
+        let message = GetBackendInfoRequest {
+            backend_id: backend_id.to_string(),
+        };
+        let response = self
+            .idempotent(
+                |mut stub, request| async move { stub.get_backend_info(request).await },
+                message,
+            )
+            .await?;
+        response
+            .backend
+            .ok_or_else(|| ClientError::InvalidResponse("backend response without backend".into()))
+    }
+
+    /// Compile a circuit on the server.
+    pub async fn compile(
+        &self,
+        circuit: &Circuit,
+        options: &CompileOptions,
+    ) -> ClientResult<CompiledCircuit> {
+        let message = CompileRequest {
+            circuit: Some(qasm_payload(&arvak_qasm3::emit(circuit)?)),
+            backend_id: options.backend_id.clone().unwrap_or_default(),
+            preset: options
+                .preset
+                .map(|preset| preset.name().to_string())
+                .unwrap_or_default(),
+            optimization_level: options.optimization_level,
+            seed: options.seed,
+        };
+        let response = self
+            .idempotent(
+                |mut stub, request| async move { stub.compile(request).await },
+                message,
+            )
+            .await?;
+
+        let qasm = match response.circuit.and_then(|c| c.format) {
+            Some(circuit_payload::Format::Qasm3(qasm)) => qasm,
+            _ => {
+                return Err(ClientError::InvalidResponse(
+                    "compile response without OpenQASM 3 circuit".into(),
+                ));
+            }
+        };
+        Ok(CompiledCircuit {
+            circuit: arvak_qasm3::parse(&qasm)?,
+            qasm,
+            depth: response.depth,
+            num_ops: response.num_ops,
+        })
+    }
+
+    /// The stub for the next connection of the pool.
+    fn stub(&self) -> ArvakServiceClient<Channel> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.channels.len();
+        ArvakServiceClient::new(self.channels[i].clone())
+    }
+
+    /// Wrap a message with the deadline and API key.
+    fn request<T>(&self, message: T) -> Request<T> {
+        self.request_with_timeout(message, self.timeout)
+    }
+
+    fn request_with_timeout<T>(&self, message: T, timeout: Option<Duration>) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(timeout) = timeout {
+            request.set_timeout(timeout);
+        }
+        if let Some(key) = &self.api_key {
+            request.metadata_mut().insert(API_KEY_HEADER, key.clone());
+        }
+        request
+    }
+
+    /// Send an idempotent call, retrying transient failures on the next
+    /// connection of the pool.
+    async fn idempotent<M, T, F, Fut>(&self, call: F, message: M) -> ClientResult<T>
+    where
+        M: Clone,
+        F: Fn(ArvakServiceClient<Channel>, Request<M>) -> Fut,
+        Fut: Future<Output = Result<Response<T>, Status>>,
+    {
+        let response = self
+            .retry
+            .run(|_| call(self.stub(), self.request(message.clone())))
+            .await?;
+        Ok(response.into_inner())
+    }
+}
+
+impl std::fmt::Debug for ArvakClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArvakClient")
+            .field("pool_size", &self.channels.len())
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .finish_non_exhaustive()
+    }
+}
+
+fn qasm_payload(qasm: &str) -> CircuitPayload {
+    CircuitPayload {
+        format: Some(circuit_payload::Format::Qasm3(qasm.to_string())),
+    }
+}
+
+/// Convert a result message to an [`ExecutionResult`].
+fn execution_result(result: JobResult) -> ClientResult<ExecutionResult> {
+    let counts = Counts::from_pairs(result.counts);
+    let mut execution = ExecutionResult::new(counts, result.shots);
+    if result.execution_time_ms > 0 {
+        execution = execution.with_execution_time(result.execution_time_ms);
+    }
+    if !result.metadata_json.is_empty() {
+        let metadata = serde_json::from_str(&result.metadata_json).map_err(|e| {
+            ClientError::InvalidResponse(format!("result metadata is not JSON: {}", e))
+        })?;
+        execution = execution.with_metadata(metadata);
+    }
+    Ok(execution)
+}
@@ -0,0 +1,184 @@
+//! Retries with exponential backoff and jitter.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tonic::{Code, Status};
+use tracing::debug;
+
+/// Distinguishes the jitter draws of concurrent retries.
+static JITTER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// When and how often the client retries idempotent calls.
+///
+/// The delay before retry `n` (starting at 0) is drawn uniformly from zero
+/// to `initial_backoff * multiplier^n`, capped at `max_backoff` ("full
+/// jitter"), so clients that failed together do not retry together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts per call, including the first; 1 disables retries.
+    pub max_attempts: u32,
+
+    /// Upper bound of the first backoff.
+    pub initial_backoff: Duration,
+
+    /// Upper bound of any backoff.
+    pub max_backoff: Duration,
+
+    /// Growth of the backoff bound per attempt.
+    pub multiplier: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Set the total number of attempts per call.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the first and the largest backoff bound.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Whether a failed call may succeed when sent again.
+    ///
+    /// Only transient conditions are retried: the server being unreachable
+    /// or overloaded, or the call being aborted by a conflict.
+    pub fn is_retryable(status: &Status) -> bool {
+        matches!(
+            status.code(),
+            Code::Unavailable | Code::ResourceExhausted | Code::Aborted
+        )
+    }
+
+    /// Upper bound of the backoff before retry `retry` (starting at 0).
+    pub fn backoff_bound(&self, retry: u32) -> Duration {
+        let bound = self.initial_backoff.as_secs_f64() * self.multiplier.powi(retry as i32);
+        Duration::from_secs_f64(bound.min(self.max_backoff.as_secs_f64()))
+    }
+
+    /// Draw the backoff before retry `retry`.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.backoff_bound(retry).mul_f64(unit_random())
+    }
+
+    /// Run `call` until it succeeds, fails with a non-retryable status, or
+    /// the attempts are used up. `call` receives the attempt number,
+    /// starting at 0.
+    pub async fn run<T, F, Fut>(&self, mut call: F) -> Result<T, Status>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let mut attempt = 0;
+        loop {
+            match call(attempt).await {
+                Err(status) if attempt + 1 < self.max_attempts && Self::is_retryable(&status) => {
+                    let backoff = self.backoff(attempt);
+                    debug!(
+                        "Attempt {} failed with {:?}, retrying in {:?}",
+                        attempt + 1,
+                        status.code(),
+                        backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// A random number in `[0, 1)`, from the standard library's randomly keyed
+/// hasher.
+pub(crate) fn unit_random() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(JITTER_COUNTER.fetch_add(1, Ordering::Relaxed));
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    fn fast() -> RetryPolicy {
+        RetryPolicy::default().with_backoff(Duration::from_millis(1), Duration::from_millis(2))
+    }
+
+    #[test]
+    fn test_backoff_bounds() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff_bound(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff_bound(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff_bound(10), Duration::from_secs(5));
+        for retry in 0..8 {
+            assert!(policy.backoff(retry) <= policy.backoff_bound(retry));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() {
+        let calls = AtomicU32::new(0);
+        let result = fast()
+            .run(|attempt| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                async move {
+                    if attempt < 2 {
+                        Err(Status::unavailable("restarting"))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        // Attempts are bounded.
+        let result: Result<(), _> = fast()
+            .with_max_attempts(2)
+            .run(|_| async { Err(Status::unavailable("down")) })
+            .await;
+        assert_eq!(result.unwrap_err().code(), Code::Unavailable);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_permanent_failures() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = fast()
+            .run(|_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                async { Err(Status::not_found("no such job")) }
+            })
+            .await;
+        assert_eq!(result.unwrap_err().code(), Code::NotFound);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -1,7 +1,7 @@
 //! Configuration management for Arvak gRPC server.
 //!
 //! Supports loading configuration from:
-//! 1. Configuration files (YAML)
+//! 1. Configuration files (YAML, or the `[grpc]` table of an `arvak.toml`)
 //! 2. Environment variables (with ARVAK_ prefix)
 //! 3. .env files
 //!
@@ -10,12 +10,14 @@
 //! 2. Configuration file
 //! 3. Default values
 
+use arvak_config::{ConfigLoader, InvalidKey, Secret, SecretSource, Section};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
 use std::path::Path;
 
 /// Complete server configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     /// gRPC server configuration
     pub server: ServerConfig,
@@ -33,6 +35,18 @@ pub struct Config {
     /// Resource limits and quotas
     #[serde(default)]
     pub limits: ResourceLimits,
+
+    /// Client authentication
+    #[serde(default)]
+    pub auth: AuthConfig,
+
+    /// Audit records of RPCs
+    #[serde(default)]
+    pub audit: AuditConfig,
+
+    /// Streaming of partial results
+    #[serde(default)]
+    pub streaming: StreamingConfig,
 }
 
 /// gRPC server settings.
@@ -72,10 +86,21 @@ pub struct StorageConfig {
     /// Maximum number of database connections
     #[serde(default = "default_db_pool_size")]
     pub pool_size: u32,
+
+    /// HPC scheduler state database (e.g. `~/.arvak/jobs.db`) to report
+    /// workflow progress and job templates from; unset disables
+    /// `GetWorkflowProgress` and the template RPCs
+    #[serde(default)]
+    pub scheduler_db: Option<String>,
+
+    /// How long a submission's idempotency key is remembered, in seconds
+    #[serde(default = "default_idempotency_ttl")]
+    pub idempotency_ttl_seconds: u64,
 }
 
 /// Observability configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ObservabilityConfig {
     /// Metrics and health server configuration
     pub http_server: HttpServerConfig,
@@ -130,6 +155,81 @@ pub struct TracingConfig {
     pub service_name: String,
 }
 
+/// Client authentication settings.
+///
+/// With no API keys configured, requests are not authenticated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Sources of accepted API keys
+    #[serde(default)]
+    pub api_keys: Vec<SecretSource>,
+
+    /// Sources of API keys that may also use the admin RPCs
+    #[serde(default)]
+    pub admin_api_keys: Vec<SecretSource>,
+}
+
+impl AuthConfig {
+    /// Read the configured API keys.
+    pub fn resolve_api_keys(&self) -> Result<Vec<Secret>, ConfigError> {
+        Self::resolve(&self.api_keys)
+    }
+
+    /// Read the configured admin API keys.
+    pub fn resolve_admin_api_keys(&self) -> Result<Vec<Secret>, ConfigError> {
+        Self::resolve(&self.admin_api_keys)
+    }
+
+    fn resolve(sources: &[SecretSource]) -> Result<Vec<Secret>, ConfigError> {
+        sources
+            .iter()
+            .map(|source| {
+                source
+                    .resolve()
+                    .map_err(|e| ConfigError::SecretError(e.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Audit logging of RPCs.
+///
+/// Records go to the `arvak::audit` tracing target, and to `path` if set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditConfig {
+    /// Record RPCs
+    pub enabled: bool,
+
+    /// File to append records to as JSON lines
+    pub path: Option<String>,
+
+    /// Fraction of successful calls recorded; failed calls are always
+    /// recorded
+    pub sample_rate: f64,
+
+    /// Sample rates of individual RPCs by name (e.g. `GetJobStatus`),
+    /// overriding `sample_rate`
+    pub method_sample_rates: std::collections::HashMap<String, f64>,
+
+    /// Largest request payload recorded verbatim; larger payloads, such as
+    /// circuit blobs, are redacted to their size and digest, and 0 records
+    /// no payloads
+    pub max_payload_bytes: usize,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            sample_rate: 1.0,
+            method_sample_rates: std::collections::HashMap::new(),
+            max_payload_bytes: 0,
+        }
+    }
+}
+
 /// Backend configurations.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct BackendConfigs {
@@ -153,6 +253,15 @@ pub struct BackendConfig {
     pub settings: serde_json::Value,
 }
 
+/// Partial-results streaming.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StreamingConfig {
+    /// Run jobs in chunks of this many shots and report the counts so far
+    /// to `WatchJob` streams; unset runs every job in one go
+    #[serde(default)]
+    pub partial_shots: Option<u32>,
+}
+
 /// Resource limits and quotas.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceLimits {
@@ -206,6 +315,10 @@ fn default_db_pool_size() -> u32 {
     10
 }
 
+fn default_idempotency_ttl() -> u64 {
+    24 * 60 * 60 // 24 hours
+}
+
 fn default_log_level() -> String {
     "info".to_string()
 }
@@ -260,25 +373,36 @@ impl Default for Config {
                 backend: default_storage_type(),
                 connection_string: None,
                 pool_size: default_db_pool_size(),
+                scheduler_db: None,
+                idempotency_ttl_seconds: default_idempotency_ttl(),
             },
-            observability: ObservabilityConfig {
-                http_server: HttpServerConfig {
-                    address: default_http_address(),
-                    metrics_enabled: true,
-                    health_enabled: true,
-                },
-                logging: LoggingConfig {
-                    level: default_log_level(),
-                    format: default_log_format(),
-                },
-                tracing: TracingConfig {
-                    enabled: false,
-                    otlp_endpoint: None,
-                    service_name: default_service_name(),
-                },
-            },
+            observability: ObservabilityConfig::default(),
             backends: BackendConfigs::default(),
             limits: ResourceLimits::default(),
+            auth: AuthConfig::default(),
+            audit: AuditConfig::default(),
+            streaming: StreamingConfig::default(),
+        }
+    }
+}
+
+impl Default for ObservabilityConfig {
+    fn default() -> Self {
+        ObservabilityConfig {
+            http_server: HttpServerConfig {
+                address: default_http_address(),
+                metrics_enabled: true,
+                health_enabled: true,
+            },
+            logging: LoggingConfig {
+                level: default_log_level(),
+                format: default_log_format(),
+            },
+            tracing: TracingConfig {
+                enabled: false,
+                otlp_endpoint: None,
+                service_name: default_service_name(),
+            },
         }
     }
 }
@@ -308,6 +432,27 @@ impl Config {
         Ok(config)
     }
 
+    /// Load configuration from the `[grpc]` table of a TOML file such as
+    /// `arvak.toml`, with `ARVAK_GRPC__*` environment variables applied.
+    ///
+    /// Without a path, the file named by `ARVAK_CONFIG` or `arvak.toml` in
+    /// the working directory is used if present.
+    pub fn from_toml<P: AsRef<Path>>(path: Option<P>) -> Result<Self, ConfigError> {
+        let loader = match path {
+            Some(path) => ConfigLoader::new().with_file(path.as_ref()),
+            None => ConfigLoader::new().with_default_file(),
+        };
+        loader
+            .with_env()
+            .load()
+            .and_then(|layers| layers.section())
+            .map_err(|e| match e {
+                arvak_config::ConfigError::Io { .. } => ConfigError::IoError(e.to_string()),
+                arvak_config::ConfigError::Parse { .. } => ConfigError::ParseError(e.to_string()),
+                _ => ConfigError::ValidationError(e.to_string()),
+            })
+    }
+
     /// Load configuration from environment variables.
     ///
     /// Environment variables override configuration file values.
@@ -336,6 +481,9 @@ impl Config {
         if let Ok(conn) = std::env::var("ARVAK_STORAGE_CONNECTION") {
             config.storage.connection_string = Some(conn);
         }
+        if let Ok(path) = std::env::var("ARVAK_SCHEDULER_DB") {
+            config.storage.scheduler_db = Some(path);
+        }
 
         // HTTP server configuration
         if let Ok(addr) = std::env::var("ARVAK_HTTP_ADDRESS") {
@@ -377,7 +525,9 @@ impl Config {
     }
 
     /// Load configuration with the following precedence:
-    /// 1. Load from file if provided
+    /// 1. Load from file if provided; `.toml` files are read with
+    ///    [`Config::from_toml`], and without a file an `arvak.toml` is used
+    ///    if present
     /// 2. Apply environment variable overrides
     /// 3. Load .env file if it exists
     pub fn load(config_file: Option<&str>) -> Result<Self, ConfigError> {
@@ -385,10 +535,10 @@ impl Config {
         dotenvy::dotenv().ok();
 
         // Start with file or default
-        let mut config = if let Some(path) = config_file {
-            Self::from_file(path)?
-        } else {
-            Config::default()
+        let mut config = match config_file {
+            Some(path) if path.ends_with(".toml") => Self::from_toml(Some(path))?,
+            Some(path) => Self::from_file(path)?,
+            None => Self::from_toml(None::<&str>)?,
         };
 
         // Apply environment overrides
@@ -409,6 +559,9 @@ impl Config {
         if env_config.storage.backend != default_storage_type() {
             self.storage.backend = env_config.storage.backend;
         }
+        if env_config.storage.scheduler_db.is_some() {
+            self.storage.scheduler_db = env_config.storage.scheduler_db;
+        }
         if env_config.observability.logging.level != default_log_level() {
             self.observability.logging.level = env_config.observability.logging.level;
         }
@@ -419,81 +572,112 @@ impl Config {
 
     /// Validate configuration values.
     pub fn validate(&self) -> Result<(), ConfigError> {
-        // Validate server address
-        self.server.address.parse::<SocketAddr>().map_err(|_| {
+        Section::validate(self).map_err(|e| ConfigError::ValidationError(e.to_string()))
+    }
+
+    /// Get the parsed gRPC server address.
+    pub fn grpc_address(&self) -> Result<SocketAddr, ConfigError> {
+        self.server.address.parse().map_err(|_| {
             ConfigError::ValidationError(format!("Invalid server address: {}", self.server.address))
-        })?;
+        })
+    }
 
-        // Validate HTTP address
-        self.observability
-            .http_server
-            .address
-            .parse::<SocketAddr>()
-            .map_err(|_| {
-                ConfigError::ValidationError(format!(
-                    "Invalid HTTP address: {}",
-                    self.observability.http_server.address
-                ))
-            })?;
+    /// Get the parsed HTTP server address.
+    pub fn http_address(&self) -> Result<SocketAddr, ConfigError> {
+        self.observability.http_server.address.parse().map_err(|_| {
+            ConfigError::ValidationError(format!(
+                "Invalid HTTP address: {}",
+                self.observability.http_server.address
+            ))
+        })
+    }
+}
+
+impl Section for Config {
+    const NAME: &'static str = "grpc";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        // Validate server and HTTP addresses
+        self.server.address.parse::<SocketAddr>().map_err(|_| {
+            InvalidKey::new(
+                "server.address",
+                format!("invalid socket address '{}'", self.server.address),
+            )
+        })?;
+        let http = &self.observability.http_server.address;
+        http.parse::<SocketAddr>().map_err(|_| {
+            InvalidKey::new(
+                "observability.http_server.address",
+                format!("invalid socket address '{}'", http),
+            )
+        })?;
 
         // Validate storage backend
         match self.storage.backend.as_str() {
             "memory" | "sqlite" | "postgres" => {}
             other => {
-                return Err(ConfigError::ValidationError(format!(
-                    "Unknown storage backend: {}",
-                    other
-                )));
+                return Err(InvalidKey::new(
+                    "storage.backend",
+                    format!("unknown storage backend '{}'", other),
+                ));
             }
         }
 
-        // Validate log level
+        // Validate log level and format
         match self.observability.logging.level.as_str() {
             "trace" | "debug" | "info" | "warn" | "error" => {}
             other => {
-                return Err(ConfigError::ValidationError(format!(
-                    "Invalid log level: {}",
-                    other
-                )));
+                return Err(InvalidKey::new(
+                    "observability.logging.level",
+                    format!("invalid log level '{}'", other),
+                ));
             }
         }
-
-        // Validate log format
         match self.observability.logging.format.as_str() {
             "console" | "json" => {}
             other => {
-                return Err(ConfigError::ValidationError(format!(
-                    "Invalid log format: {}",
-                    other
-                )));
+                return Err(InvalidKey::new(
+                    "observability.logging.format",
+                    format!("invalid log format '{}'", other),
+                ));
             }
         }
 
         // Validate resource limits
         if self.limits.max_concurrent_jobs == 0 {
-            return Err(ConfigError::ValidationError(
-                "max_concurrent_jobs must be greater than 0".to_string(),
+            return Err(InvalidKey::new(
+                "limits.max_concurrent_jobs",
+                "must be greater than 0",
+            ));
+        }
+        if self.streaming.partial_shots == Some(0) {
+            return Err(InvalidKey::new(
+                "streaming.partial_shots",
+                "must be greater than 0",
             ));
         }
 
-        Ok(())
-    }
-
-    /// Get the parsed gRPC server address.
-    pub fn grpc_address(&self) -> Result<SocketAddr, ConfigError> {
-        self.server.address.parse().map_err(|_| {
-            ConfigError::ValidationError(format!("Invalid server address: {}", self.server.address))
-        })
-    }
+        // Validate audit sampling
+        if !(0.0..=1.0).contains(&self.audit.sample_rate) {
+            return Err(InvalidKey::new(
+                "audit.sample_rate",
+                "must be between 0 and 1",
+            ));
+        }
+        if let Some(method) = self
+            .audit
+            .method_sample_rates
+            .iter()
+            .find(|(_, rate)| !(0.0..=1.0).contains(*rate))
+            .map(|(method, _)| method)
+        {
+            return Err(InvalidKey::new(
+                format!("audit.method_sample_rates.{}", method),
+                "must be between 0 and 1",
+            ));
+        }
 
-    /// Get the parsed HTTP server address.
-    pub fn http_address(&self) -> Result<SocketAddr, ConfigError> {
-        self.observability.http_server.address.parse().map_err(|_| {
-            ConfigError::ValidationError(format!(
-                "Invalid HTTP address: {}",
-                self.observability.http_server.address
-            ))
-        })
+        Ok(())
     }
 }
 
@@ -508,6 +692,9 @@ pub enum ConfigError {
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    #[error("Secret error: {0}")]
+    SecretError(String),
 }
 
 #[cfg(test)]
@@ -542,6 +729,57 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_toml_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("arvak.toml");
+        std::fs::write(
+            &path,
+            "[grpc.server]\naddress = \"127.0.0.1:9090\"\n\n[grpc.observability.logging]\nformat = \"json\"\n",
+        )
+        .unwrap();
+
+        let config = Config::from_toml(Some(&path)).unwrap();
+        assert_eq!(config.server.address, "127.0.0.1:9090");
+        assert_eq!(config.server.timeout_seconds, 60);
+        assert_eq!(config.observability.logging.format, "json");
+        assert_eq!(config.observability.logging.level, "info");
+
+        std::fs::write(&path, "[grpc.storage]\nbackend = \"redis\"\n").unwrap();
+        let err = Config::from_toml(Some(&path)).unwrap_err().to_string();
+        assert!(err.contains("'grpc.storage.backend'"), "{}", err);
+    }
+
+    #[test]
+    fn test_audit_section() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("arvak.toml");
+        std::fs::write(
+            &path,
+            "[grpc.audit]\nenabled = true\nsample_rate = 0.1\n\n\
+             [grpc.audit.method_sample_rates]\nSubmitJob = 1.0\n",
+        )
+        .unwrap();
+
+        let config = Config::from_toml(Some(&path)).unwrap();
+        assert!(config.audit.enabled);
+        assert_eq!(config.audit.sample_rate, 0.1);
+        assert_eq!(config.audit.method_sample_rates["SubmitJob"], 1.0);
+        assert_eq!(config.audit.max_payload_bytes, 0);
+
+        let mut config = Config::default();
+        config
+            .audit
+            .method_sample_rates
+            .insert("WatchJob".into(), 2.0);
+        let err = config.validate().unwrap_err().to_string();
+        assert!(
+            err.contains("audit.method_sample_rates.WatchJob"),
+            "{}",
+            err
+        );
+    }
+
     #[test]
     fn test_grpc_address_parsing() {
         let config = Config::default();
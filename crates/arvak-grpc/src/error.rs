@@ -45,6 +45,42 @@ pub enum Error {
     #[error("Storage error: {0}")]
     StorageError(String),
 
+    /// Workflow not found.
+    #[error("Workflow not found: {0}")]
+    WorkflowNotFound(String),
+
+    /// Job template not found.
+    #[error("Template not found: {0}")]
+    TemplateNotFound(String),
+
+    /// The server is not configured for the operation.
+    #[error("Not configured: {0}")]
+    NotConfigured(String),
+
+    /// Caller may not perform the operation.
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    /// An idempotency key is malformed.
+    #[error("Invalid idempotency key: {0}")]
+    InvalidIdempotencyKey(String),
+
+    /// An idempotency key was reused for a different request.
+    #[error("Idempotency key reused for a different request: {0}")]
+    IdempotencyConflict(String),
+
+    /// The first request with an idempotency key is still being handled.
+    #[error("Request with idempotency key still in progress: {0}")]
+    IdempotencyInProgress(String),
+
+    /// A job deadline is malformed or has already passed.
+    #[error("Invalid deadline: {0}")]
+    InvalidDeadline(String),
+
+    /// Compilation failed.
+    #[error("Compilation error: {0}")]
+    Compile(#[from] arvak_compile::CompileError),
+
     /// Internal error.
     #[error("Internal error: {0}")]
     Internal(String),
@@ -62,6 +98,25 @@ impl From<Error> for Status {
             Error::QasmParse(msg) => Status::invalid_argument(format!("QASM parse error: {}", msg)),
             Error::JsonParse(e) => Status::invalid_argument(format!("JSON parse error: {}", e)),
             Error::StorageError(msg) => Status::internal(format!("Storage error: {}", msg)),
+            Error::WorkflowNotFound(msg) => Status::not_found(msg),
+            Error::TemplateNotFound(msg) => Status::not_found(msg),
+            Error::NotConfigured(msg) => Status::unimplemented(msg),
+            Error::PermissionDenied(msg) => Status::permission_denied(msg),
+            Error::InvalidIdempotencyKey(msg) => {
+                Status::invalid_argument(format!("Invalid idempotency key: {}", msg))
+            }
+            Error::IdempotencyConflict(key) => Status::invalid_argument(format!(
+                "Idempotency key reused for a different request: {}",
+                key
+            )),
+            Error::IdempotencyInProgress(key) => Status::aborted(format!(
+                "Request with idempotency key still in progress: {}",
+                key
+            )),
+            Error::InvalidDeadline(msg) => {
+                Status::invalid_argument(format!("Invalid deadline: {}", msg))
+            }
+            Error::Compile(e) => Status::invalid_argument(format!("Compilation error: {}", e)),
             Error::Internal(msg) => Status::internal(msg),
         }
     }
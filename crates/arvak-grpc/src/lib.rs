@@ -43,6 +43,7 @@
 //! }
 //! ```
 
+pub mod client;
 pub mod config;
 pub mod error;
 pub mod health;
@@ -54,7 +55,8 @@ pub mod storage;
 pub mod tracing_config;
 
 // Re-export commonly used types
-pub use config::{Config, ConfigError, ResourceLimits};
+pub use client::{ArvakClient, ClientConfig, ClientError, ClientResult, RetryPolicy};
+pub use config::{AuditConfig, AuthConfig, Config, ConfigError, ResourceLimits, StreamingConfig};
 pub use error::{Error, Result};
 pub use health::{HealthState, start_health_server};
 pub use metrics::Metrics;
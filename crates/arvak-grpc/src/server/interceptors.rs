@@ -3,12 +3,29 @@
 //! This module provides interceptors for:
 //! - Request ID generation and propagation
 //! - Request/response logging
-//! - Authentication (future)
+//! - API key authentication and admin access
+//! - Audit records of every RPC
 
-use tonic::{Request, Status};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use arvak_config::Secret;
+use arvak_ir::hash::{Fnv1a, fnv1a};
+use arvak_sched::{AuditEvent, AuditLog};
+use hyper::body::{Body, Bytes, Frame, SizeHint};
+use hyper::header::{HeaderMap, HeaderValue};
+use tonic::body::BoxBody;
+use tonic::metadata::MetadataMap;
+use tonic::{Code, Request, Status};
+use tower::{Layer, Service};
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::client::retry::unit_random;
+
 /// Request ID metadata key.
 pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
@@ -126,33 +143,446 @@ impl RateLimiter {
     }
 }
 
-/// Authentication interceptor (placeholder for future implementation).
+/// API key metadata key, an alternative to `authorization: Bearer <key>`.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Metadata key of a client-chosen key that makes job submissions
+/// idempotent: a submission repeated with the same key returns the jobs of
+/// the first instead of creating new ones.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Authentication interceptor checking API keys.
 ///
-/// This interceptor can be used to validate API keys, JWT tokens, or other
-/// authentication mechanisms.
-#[derive(Clone)]
+/// Clients send a key as `authorization: Bearer <key>` or `x-api-key: <key>`.
+/// Without configured keys every request is accepted. Keys are held as
+/// [`Secret`]s and never appear in logs.
+#[derive(Clone, Default)]
 pub struct AuthInterceptor {
-    // Future: add authentication state
-}
-
-impl Default for AuthInterceptor {
-    fn default() -> Self {
-        Self::new()
-    }
+    api_keys: Arc<Vec<Secret>>,
+    admin_keys: Arc<Vec<Secret>>,
 }
 
 impl AuthInterceptor {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Accept requests carrying one of `api_keys`.
+    pub fn with_api_keys(mut self, api_keys: Vec<Secret>) -> Self {
+        self.api_keys = Arc::new(api_keys);
+        self
+    }
+
+    /// Accept requests carrying one of `admin_keys` and allow them the
+    /// admin RPCs.
+    pub fn with_admin_keys(mut self, admin_keys: Vec<Secret>) -> Self {
+        self.admin_keys = Arc::new(admin_keys);
+        self
+    }
+
+    /// Check whether authentication is required.
+    pub fn is_enabled(&self) -> bool {
+        !self.api_keys.is_empty() || !self.admin_keys.is_empty()
+    }
+
+    /// The API key a request carries, if any.
+    pub(crate) fn presented_key(metadata: &MetadataMap) -> Option<&str> {
+        Self::key_from(|name| metadata.get(name).and_then(|v| v.to_str().ok()))
+    }
+
+    /// The API key found by looking up request headers with `get`.
+    fn key_from<'a>(get: impl Fn(&str) -> Option<&'a str>) -> Option<&'a str> {
+        if let Some(auth) = get("authorization") {
+            return auth.strip_prefix("Bearer ");
+        }
+        get(API_KEY_HEADER)
     }
 }
 
 impl tonic::service::Interceptor for AuthInterceptor {
     fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
-        // Future: implement authentication logic
-        // For now, just pass through
-        Ok(request)
+        let mut request = request;
+        if !self.is_enabled() {
+            request.extensions_mut().insert(AdminAccess);
+            return Ok(request);
+        }
+
+        match Self::presented_key(request.metadata()) {
+            Some(key) if self.admin_keys.iter().any(|k| k.matches(key)) => {
+                request.extensions_mut().insert(AdminAccess);
+                Ok(request)
+            }
+            Some(key) if self.api_keys.iter().any(|k| k.matches(key)) => Ok(request),
+            Some(_) => Err(Status::unauthenticated("Invalid API key")),
+            None => Err(Status::unauthenticated("Missing API key")),
+        }
+    }
+}
+
+/// Marker stored in request extensions when the caller may use admin RPCs.
+///
+/// Set for admin API keys, and for every request when authentication is
+/// disabled.
+#[derive(Clone, Copy, Debug)]
+pub struct AdminAccess;
+
+/// Audit interceptor recording RPCs to an [`AuditLog`].
+///
+/// Unlike the interceptors above this is a tower [`Layer`], as a record is
+/// only complete once the response has been sent: each record holds the
+/// caller, the RPC, a digest of the request, the latency and the final
+/// status. The caller is identified by a fingerprint of its API key, never
+/// the key itself. Requests without an `x-request-id` get one here, so the
+/// record and the service logs share it.
+///
+/// Failed calls are always recorded; successful ones are sampled at the
+/// configured rate, optionally per RPC. Requests up to
+/// [`with_max_payload_bytes`](Self::with_max_payload_bytes) are recorded
+/// verbatim (non-ASCII bytes escaped); larger ones, such as circuit blobs,
+/// are redacted to their size and digest.
+#[derive(Clone)]
+pub struct AuditInterceptor {
+    settings: Option<Arc<AuditSettings>>,
+}
+
+struct AuditSettings {
+    log: Arc<dyn AuditLog>,
+    sample_rate: f64,
+    method_sample_rates: HashMap<String, f64>,
+    max_payload_bytes: usize,
+}
+
+impl AuditInterceptor {
+    /// Record every RPC to `log`, without payloads.
+    pub fn new(log: Arc<dyn AuditLog>) -> Self {
+        Self {
+            settings: Some(Arc::new(AuditSettings {
+                log,
+                sample_rate: 1.0,
+                method_sample_rates: HashMap::new(),
+                max_payload_bytes: 0,
+            })),
+        }
+    }
+
+    /// Record nothing.
+    pub fn disabled() -> Self {
+        Self { settings: None }
+    }
+
+    /// Check whether RPCs are recorded.
+    pub fn is_enabled(&self) -> bool {
+        self.settings.is_some()
+    }
+
+    /// Record this fraction of successful calls.
+    pub fn with_sample_rate(mut self, rate: f64) -> Self {
+        self.update(|s| s.sample_rate = rate);
+        self
+    }
+
+    /// Record this fraction of successful calls of the RPC named `method`
+    /// (e.g. `GetJobStatus`), overriding the general sample rate.
+    pub fn with_method_sample_rate(mut self, method: impl Into<String>, rate: f64) -> Self {
+        let method = method.into();
+        self.update(|s| {
+            s.method_sample_rates.insert(method, rate);
+        });
+        self
     }
+
+    /// Record request payloads of up to `bytes` bytes; 0 records none.
+    pub fn with_max_payload_bytes(mut self, bytes: usize) -> Self {
+        self.update(|s| s.max_payload_bytes = bytes);
+        self
+    }
+
+    fn update(&mut self, f: impl FnOnce(&mut AuditSettings)) {
+        if let Some(settings) = self.settings.as_mut().and_then(Arc::get_mut) {
+            f(settings);
+        }
+    }
+}
+
+impl AuditSettings {
+    fn sample_rate(&self, method: &str) -> f64 {
+        self.method_sample_rates
+            .get(method)
+            .copied()
+            .unwrap_or(self.sample_rate)
+    }
+}
+
+impl<S> Layer<S> for AuditInterceptor {
+    type Service = AuditService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        AuditService {
+            inner: service,
+            settings: self.settings.clone(),
+        }
+    }
+}
+
+/// Service recording RPCs, created by [`AuditInterceptor`].
+#[derive(Clone)]
+pub struct AuditService<S> {
+    inner: S,
+    settings: Option<Arc<AuditSettings>>,
+}
+
+impl<S, B> Service<hyper::Request<BoxBody>> for AuditService<S>
+where
+    S: Service<hyper::Request<BoxBody>, Response = hyper::Response<B>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Body + Unpin,
+{
+    type Response = hyper::Response<AuditBody<B>>;
+    type Error = S::Error;
+    type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: hyper::Request<BoxBody>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let Some(settings) = self.settings.clone() else {
+            return Box::pin(async move {
+                let response = inner.call(req).await?;
+                Ok(response.map(|inner| AuditBody { inner, call: None }))
+            });
+        };
+
+        let request_id = match req.headers().get(REQUEST_ID_HEADER) {
+            Some(id) => id.to_str().unwrap_or_default().to_string(),
+            None => {
+                let id = Uuid::new_v4().to_string();
+                if let Ok(value) = HeaderValue::from_str(&id) {
+                    req.headers_mut().insert(REQUEST_ID_HEADER, value);
+                }
+                id
+            }
+        };
+        let principal =
+            AuthInterceptor::key_from(|name| req.headers().get(name).and_then(|v| v.to_str().ok()))
+                .map_or_else(
+                    || "anonymous".to_string(),
+                    |key| format!("key:{:016x}", fnv1a(key.as_bytes())),
+                );
+        let capture = Arc::new(Mutex::new(RequestCapture::new(settings.max_payload_bytes)));
+        let mut call = AuditCall {
+            settings,
+            path: req.uri().path().to_string(),
+            principal,
+            request_id,
+            start: Instant::now(),
+            capture: capture.clone(),
+            code: None,
+        };
+        let req = req.map(|inner| tonic::body::boxed(AuditRequestBody { inner, capture }));
+
+        Box::pin(async move {
+            match inner.call(req).await {
+                Ok(response) => {
+                    call.code = grpc_code(response.headers());
+                    Ok(response.map(|inner| AuditBody {
+                        inner,
+                        call: Some(call),
+                    }))
+                }
+                Err(e) => {
+                    call.code = Some(Code::Unknown);
+                    call.record();
+                    Err(e)
+                }
+            }
+        })
+    }
+}
+
+/// Response body that records its RPC once the final status is sent.
+pub struct AuditBody<B> {
+    inner: B,
+    call: Option<AuditCall>,
+}
+
+impl<B: Body + Unpin> Body for AuditBody<B> {
+    type Data = B::Data;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = &mut *self;
+        let frame = Pin::new(&mut this.inner).poll_frame(cx);
+        match &frame {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(trailers) = frame.trailers_ref() {
+                    if let Some(call) = this.call.as_mut() {
+                        call.code = grpc_code(trailers).or(call.code);
+                    }
+                }
+            }
+            Poll::Ready(Some(Err(_))) => {
+                if let Some(call) = this.call.as_mut() {
+                    call.code = Some(Code::Unknown);
+                }
+                this.finish();
+            }
+            Poll::Ready(None) => this.finish(),
+            Poll::Pending => {}
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> AuditBody<B> {
+    fn finish(&mut self) {
+        if let Some(call) = self.call.take() {
+            call.record();
+        }
+    }
+}
+
+impl<B> Drop for AuditBody<B> {
+    fn drop(&mut self) {
+        // A body dropped before its status was sent was cut short.
+        if let Some(call) = self.call.as_mut() {
+            call.code.get_or_insert(Code::Cancelled);
+        }
+        self.finish();
+    }
+}
+
+/// An RPC awaiting its audit record.
+struct AuditCall {
+    settings: Arc<AuditSettings>,
+    path: String,
+    principal: String,
+    request_id: String,
+    start: Instant,
+    capture: Arc<Mutex<RequestCapture>>,
+    code: Option<Code>,
+}
+
+impl AuditCall {
+    fn record(self) {
+        let code = self.code.unwrap_or(Code::Unknown);
+        let method = self.path.rsplit('/').next().unwrap_or_default();
+        if code == Code::Ok && unit_random() >= self.settings.sample_rate(method) {
+            return;
+        }
+
+        let allowed = !matches!(code, Code::Unauthenticated | Code::PermissionDenied);
+        let capture = self.capture.lock().unwrap_or_else(|e| e.into_inner());
+        let mut event = AuditEvent::new(self.principal, method, allowed)
+            .with_attribute("method", self.path.as_str())
+            .with_attribute("request_id", self.request_id)
+            .with_attribute("latency_ms", self.start.elapsed().as_millis().to_string())
+            .with_attribute("status", format!("{:?}", code))
+            .with_attribute(
+                "request_digest",
+                format!("{:016x}", capture.digest.finish()),
+            )
+            .with_attribute("request_bytes", capture.bytes.to_string());
+        if let Some(payload) = capture.payload() {
+            event = event.with_attribute("payload", payload);
+        }
+        drop(capture);
+        self.settings.log.record(event);
+    }
+}
+
+/// What an audited request has sent so far.
+struct RequestCapture {
+    digest: Fnv1a,
+    bytes: usize,
+    /// Start of the payload, up to one byte beyond the recorded maximum.
+    head: Vec<u8>,
+    max_payload_bytes: usize,
+}
+
+impl RequestCapture {
+    fn new(max_payload_bytes: usize) -> Self {
+        Self {
+            digest: Fnv1a::new(),
+            bytes: 0,
+            head: Vec::new(),
+            max_payload_bytes,
+        }
+    }
+
+    fn push(&mut self, data: &[u8]) {
+        self.digest.write(data);
+        self.bytes += data.len();
+        if self.max_payload_bytes > 0 {
+            let room = (self.max_payload_bytes + 1).saturating_sub(self.head.len());
+            self.head.extend_from_slice(&data[..data.len().min(room)]);
+        }
+    }
+
+    /// The payload to record, redacted if it is too large.
+    fn payload(&self) -> Option<String> {
+        if self.max_payload_bytes == 0 {
+            None
+        } else if self.bytes > self.max_payload_bytes {
+            Some(format!("[redacted: {} bytes]", self.bytes))
+        } else {
+            Some(self.head.escape_ascii().to_string())
+        }
+    }
+}
+
+/// Request body feeding a [`RequestCapture`].
+struct AuditRequestBody {
+    inner: BoxBody,
+    capture: Arc<Mutex<RequestCapture>>,
+}
+
+impl Body for AuditRequestBody {
+    type Data = Bytes;
+    type Error = Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Status>>> {
+        let frame = Pin::new(&mut self.inner).poll_frame(cx);
+        if let Poll::Ready(Some(Ok(frame))) = &frame {
+            if let Some(data) = frame.data_ref() {
+                self.capture
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(data);
+            }
+        }
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+/// The gRPC status code in response headers or trailers.
+fn grpc_code(headers: &HeaderMap) -> Option<Code> {
+    headers
+        .get("grpc-status")
+        .map(|code| Code::from_bytes(code.as_bytes()))
 }
 
 /// Error interceptor for handling and logging errors.
@@ -206,9 +636,81 @@ mod tests {
         assert!(request_id.is_some());
     }
 
+    #[test]
+    fn test_auth_interceptor() {
+        let mut open = AuthInterceptor::new();
+        assert!(open.call(Request::new(())).is_ok());
+
+        let mut auth = AuthInterceptor::new().with_api_keys(vec![Secret::new("k1")]);
+        let status = auth.call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer k1".parse().unwrap());
+        assert!(auth.call(request).is_ok());
+
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert(API_KEY_HEADER, "k2".parse().unwrap());
+        let status = auth.call(request).unwrap_err();
+        assert_eq!(status.message(), "Invalid API key");
+    }
+
+    #[test]
+    fn test_auth_interceptor_admin_keys() {
+        let mut open = AuthInterceptor::new();
+        let request = open.call(Request::new(())).unwrap();
+        assert!(request.extensions().get::<AdminAccess>().is_some());
+
+        let mut auth = AuthInterceptor::new()
+            .with_api_keys(vec![Secret::new("user")])
+            .with_admin_keys(vec![Secret::new("ops")]);
+
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert(API_KEY_HEADER, "user".parse().unwrap());
+        let request = auth.call(request).unwrap();
+        assert!(request.extensions().get::<AdminAccess>().is_none());
+
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer ops".parse().unwrap());
+        let request = auth.call(request).unwrap();
+        assert!(request.extensions().get::<AdminAccess>().is_some());
+    }
+
     #[test]
     fn test_rate_limiter() {
         let limiter = RateLimiter::new();
         assert!(limiter.allow("127.0.0.1"));
     }
+
+    #[test]
+    fn test_audit_payload_redaction() {
+        let mut capture = RequestCapture::new(8);
+        capture.push(b"\x00abc");
+        assert_eq!(capture.payload().unwrap(), "\\x00abc");
+        capture.push(b"defghij");
+        assert_eq!(capture.bytes, 11);
+        assert_eq!(capture.payload().unwrap(), "[redacted: 11 bytes]");
+        assert_eq!(capture.digest.finish(), fnv1a(b"\x00abcdefghij"));
+
+        assert!(RequestCapture::new(0).payload().is_none());
+    }
+
+    #[test]
+    fn test_audit_sample_rates() {
+        let audit = AuditInterceptor::new(Arc::new(arvak_sched::InMemoryAuditLog::new()))
+            .with_sample_rate(0.5)
+            .with_method_sample_rate("GetJobStatus", 0.0);
+        let settings = audit.settings.unwrap();
+        assert_eq!(settings.sample_rate("SubmitJob"), 0.5);
+        assert_eq!(settings.sample_rate("GetJobStatus"), 0.0);
+        assert!(!AuditInterceptor::disabled().is_enabled());
+    }
 }
@@ -4,31 +4,155 @@
 //! The actual storage implementation can be in-memory, SQLite, PostgreSQL, etc.
 
 use arvak_hal::job::{JobId, JobStatus};
-use arvak_hal::result::ExecutionResult;
+use arvak_hal::result::{ExecutionResult, PartialResult};
 use arvak_ir::circuit::Circuit;
-use chrono::Utc;
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::warn;
 
-use crate::error::Result;
-use crate::storage::{JobStorage, MemoryStorage, StoredJob};
+use crate::error::{Error, Result};
+use crate::storage::{IdempotencyRecord, JobFilter, JobStorage, MemoryStorage, StoredJob};
 
 /// Thread-safe job store using pluggable storage backend.
 #[derive(Clone)]
 pub struct JobStore {
     storage: Arc<dyn JobStorage>,
+    /// Latest partial result of running jobs; not persisted.
+    partials: Arc<RwLock<HashMap<JobId, PartialResult>>>,
+    /// How long idempotency keys are remembered after their request.
+    idempotency_ttl: Duration,
+}
+
+/// How long idempotency keys are remembered by default.
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a claimed idempotency key stays claimed without its request
+/// completing, e.g. because the server handling it crashed.
+const IDEMPOTENCY_CLAIM_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Outcome of [`JobStore::claim_idempotency_key`].
+#[derive(Debug)]
+pub enum Idempotency {
+    /// The request was already handled and created these jobs.
+    Replay(Vec<JobId>),
+    /// The request is new; create its jobs and complete the claim.
+    Claimed(IdempotencyClaim),
+}
+
+/// A claimed idempotency key whose request is being handled.
+///
+/// Dropping the claim without [`complete`](Self::complete), e.g. because the
+/// request failed or the client went away, releases the key so the request
+/// can be retried.
+pub struct IdempotencyClaim {
+    storage: Arc<dyn JobStorage>,
+    key: String,
+    ttl: Duration,
+    completed: bool,
+}
+
+impl std::fmt::Debug for IdempotencyClaim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdempotencyClaim")
+            .field("key", &self.key)
+            .field("ttl", &self.ttl)
+            .field("completed", &self.completed)
+            .finish()
+    }
+}
+
+impl IdempotencyClaim {
+    /// Record the jobs the request created, to be returned on replay.
+    pub async fn complete(mut self, job_ids: &[JobId]) -> Result<()> {
+        self.storage
+            .complete_idempotency_key(&self.key, job_ids, expiry(self.ttl))
+            .await?;
+        self.completed = true;
+        Ok(())
+    }
+}
+
+impl Drop for IdempotencyClaim {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        // Without a runtime the key is released when the claim times out
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let storage = self.storage.clone();
+        let key = std::mem::take(&mut self.key);
+        runtime.spawn(async move {
+            if let Err(e) = storage.release_idempotency_key(&key).await {
+                warn!(error = %e, "Failed to release idempotency key");
+            }
+        });
+    }
+}
+
+/// When a key remembered for `ttl` from now expires.
+fn expiry(ttl: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(ttl)
+        .ok()
+        .and_then(|ttl| Utc::now().checked_add_signed(ttl))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
 }
 
 impl JobStore {
     /// Create a new job store with in-memory storage.
     pub fn new() -> Self {
-        Self {
-            storage: Arc::new(MemoryStorage::new()),
-        }
+        Self::with_storage(Arc::new(MemoryStorage::new()))
     }
 
     /// Create a job store with a custom storage backend.
     pub fn with_storage(storage: Arc<dyn JobStorage>) -> Self {
-        Self { storage }
+        Self {
+            storage,
+            partials: Arc::default(),
+            idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
+        }
+    }
+
+    /// Set how long idempotency keys are remembered after their request.
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+
+    /// Claim an idempotency key for a request with the given fingerprint,
+    /// or get the jobs an earlier request with the key created.
+    ///
+    /// Keys are kept in the storage backend, so servers sharing a database
+    /// also share keys. Fails with [`Error::IdempotencyConflict`] if the key
+    /// was used for a different request, and with
+    /// [`Error::IdempotencyInProgress`] while the first request with the key
+    /// is still being handled.
+    pub async fn claim_idempotency_key(&self, key: &str, fingerprint: u64) -> Result<Idempotency> {
+        let claim = IdempotencyRecord {
+            fingerprint,
+            job_ids: None,
+            expires_at: expiry(self.idempotency_ttl.min(IDEMPOTENCY_CLAIM_TIMEOUT)),
+        };
+
+        if let Some(record) = self.storage.claim_idempotency_key(key, &claim).await? {
+            if record.fingerprint != fingerprint {
+                return Err(Error::IdempotencyConflict(key.to_string()));
+            }
+            return match record.job_ids {
+                Some(job_ids) => Ok(Idempotency::Replay(job_ids)),
+                None => Err(Error::IdempotencyInProgress(key.to_string())),
+            };
+        }
+
+        Ok(Idempotency::Claimed(IdempotencyClaim {
+            storage: self.storage.clone(),
+            key: key.to_string(),
+            ttl: self.idempotency_ttl,
+            completed: false,
+        }))
     }
 
     /// Create a new job and return its ID.
@@ -59,14 +183,42 @@ impl JobStore {
 
     /// Update job status.
     pub async fn update_status(&self, job_id: &JobId, status: JobStatus) -> Result<()> {
+        if status.is_terminal() {
+            self.clear_partial(job_id);
+        }
         self.storage.update_status(job_id, status).await
     }
 
     /// Store job result.
     pub async fn store_result(&self, job_id: &JobId, result: ExecutionResult) -> Result<()> {
+        self.clear_partial(job_id);
         self.storage.store_result(job_id, result).await
     }
 
+    /// Record the counts a running job has accumulated so far.
+    pub fn record_partial(&self, job_id: &JobId, partial: PartialResult) {
+        self.partials
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(job_id.clone(), partial);
+    }
+
+    /// Get the latest partial result of a running job.
+    pub fn partial(&self, job_id: &JobId) -> Option<PartialResult> {
+        self.partials
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(job_id)
+            .cloned()
+    }
+
+    fn clear_partial(&self, job_id: &JobId) {
+        self.partials
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(job_id);
+    }
+
     /// Get job by ID.
     pub async fn get_job(&self, job_id: &JobId) -> Result<StoredJob> {
         self.storage
@@ -75,6 +227,16 @@ impl JobStore {
             .ok_or_else(|| crate::error::Error::JobNotFound(job_id.0.clone()))
     }
 
+    /// List jobs matching a filter.
+    pub async fn list_jobs(&self, filter: JobFilter) -> Result<Vec<StoredJob>> {
+        self.storage.list_jobs(filter).await
+    }
+
+    /// Rebuild the storage backend's indices, returning the number of jobs.
+    pub async fn rebuild_indices(&self) -> Result<usize> {
+        self.storage.rebuild_indices().await
+    }
+
     /// Get job result by ID.
     pub async fn get_result(&self, job_id: &JobId) -> Result<ExecutionResult> {
         self.storage.get_result(job_id).await
@@ -108,6 +270,27 @@ mod tests {
         assert!(job.completed_at.is_none());
     }
 
+    #[tokio::test]
+    async fn test_partial_results() {
+        let store = JobStore::new();
+        let circuit = Circuit::with_size("test", 2, 0);
+        let job_id = store
+            .create_job(circuit, "simulator".to_string(), 1000)
+            .await
+            .unwrap();
+        assert!(store.partial(&job_id).is_none());
+
+        let counts = arvak_hal::Counts::from_pairs([("00", 120), ("11", 130)]);
+        store.record_partial(&job_id, PartialResult::new(counts, 250, 1000));
+        assert_eq!(store.partial(&job_id).unwrap().shots_completed, 250);
+
+        store
+            .store_result(&job_id, ExecutionResult::default())
+            .await
+            .unwrap();
+        assert!(store.partial(&job_id).is_none());
+    }
+
     #[tokio::test]
     async fn test_status_update() {
         let store = JobStore::new();
@@ -134,6 +317,57 @@ mod tests {
         assert!(job.completed_at.is_some());
     }
 
+    #[tokio::test]
+    async fn test_idempotency_keys() {
+        let storage = Arc::new(MemoryStorage::new());
+        let store = JobStore::with_storage(storage.clone());
+        let job_id = JobId::new("job-1".to_string());
+
+        let Idempotency::Claimed(claim) = store.claim_idempotency_key("k", 1).await.unwrap() else {
+            panic!("new key replayed");
+        };
+        assert!(matches!(
+            store.claim_idempotency_key("k", 1).await,
+            Err(Error::IdempotencyInProgress(_))
+        ));
+        claim.complete(std::slice::from_ref(&job_id)).await.unwrap();
+
+        match store.claim_idempotency_key("k", 1).await.unwrap() {
+            Idempotency::Replay(job_ids) => assert_eq!(job_ids, vec![job_id.clone()]),
+            Idempotency::Claimed(_) => panic!("key not remembered"),
+        }
+        assert!(matches!(
+            store.claim_idempotency_key("k", 2).await,
+            Err(Error::IdempotencyConflict(_))
+        ));
+
+        // Keys live in the storage backend, shared by stores using it.
+        let other = JobStore::with_storage(storage);
+        assert!(matches!(
+            other.claim_idempotency_key("k", 1).await,
+            Ok(Idempotency::Replay(_))
+        ));
+
+        // An abandoned claim releases the key.
+        drop(store.claim_idempotency_key("other", 1).await.unwrap());
+        tokio::task::yield_now().await;
+        assert!(matches!(
+            store.claim_idempotency_key("other", 2).await,
+            Ok(Idempotency::Claimed(_))
+        ));
+
+        // Expired keys are forgotten.
+        let store = JobStore::new().with_idempotency_ttl(Duration::ZERO);
+        let Ok(Idempotency::Claimed(claim)) = store.claim_idempotency_key("k", 1).await else {
+            panic!("new key replayed");
+        };
+        claim.complete(&[]).await.unwrap();
+        assert!(matches!(
+            store.claim_idempotency_key("k", 2).await,
+            Ok(Idempotency::Claimed(_))
+        ));
+    }
+
     #[tokio::test]
     async fn test_job_not_found() {
         let store = JobStore::new();
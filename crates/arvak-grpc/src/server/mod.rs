@@ -7,7 +7,9 @@ pub mod middleware;
 pub mod service;
 
 pub use backend_registry::BackendRegistry;
-pub use interceptors::{LoggingInterceptor, RequestIdInterceptor};
+pub use interceptors::{
+    AdminAccess, AuditInterceptor, AuthInterceptor, LoggingInterceptor, RequestIdInterceptor,
+};
 pub use job_store::JobStore;
 pub use middleware::{ConnectionInfoLayer, TimingLayer};
 pub use service::ArvakServiceImpl;
@@ -1,9 +1,14 @@
 //! gRPC service implementation.
 
 use arvak_hal::backend::Backend;
+use arvak_hal::error::HalError;
 use arvak_hal::job::{JobId, JobStatus};
+use arvak_hal::result::{ExecutionResult, PartialResult};
 use arvak_ir::circuit::Circuit;
+use arvak_ir::hash::{Fnv1a, fnv1a};
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
+use tonic::metadata::MetadataMap;
 use tonic::{Request, Response, Status};
 use tracing::{error, info, instrument, warn};
 
@@ -12,7 +17,13 @@ use crate::error::{Error, Result};
 use crate::metrics::Metrics;
 use crate::proto::*;
 use crate::resource_manager::ResourceManager;
-use crate::server::{BackendRegistry, JobStore};
+use crate::server::interceptors::{IDEMPOTENCY_KEY_HEADER, RequestId};
+use crate::server::job_store::Idempotency;
+use crate::server::{AdminAccess, AuthInterceptor, BackendRegistry, JobStore};
+use crate::storage::JobFilter;
+
+/// Longest accepted idempotency key.
+const MAX_IDEMPOTENCY_KEY_LEN: usize = 255;
 
 /// Arvak gRPC service implementation.
 pub struct ArvakServiceImpl {
@@ -20,6 +31,8 @@ pub struct ArvakServiceImpl {
     backends: Arc<BackendRegistry>,
     metrics: Metrics,
     resources: Option<ResourceManager>,
+    partial_shots: Option<u32>,
+    workflow_store: Option<Arc<dyn arvak_sched::StateStore>>,
 }
 
 impl ArvakServiceImpl {
@@ -37,6 +50,8 @@ impl ArvakServiceImpl {
             backends: Arc::new(backends),
             metrics,
             resources: None,
+            partial_shots: None,
+            workflow_store: None,
         }
     }
 
@@ -51,6 +66,19 @@ impl ArvakServiceImpl {
         service
     }
 
+    /// Run jobs in chunks of `shots` shots, so that watchers see the counts
+    /// accumulated so far while a job is running.
+    pub fn with_partial_results(mut self, shots: u32) -> Self {
+        self.partial_shots = Some(shots);
+        self
+    }
+
+    /// Report workflow progress from an HPC scheduler state store.
+    pub fn with_workflow_store(mut self, store: Arc<dyn arvak_sched::StateStore>) -> Self {
+        self.workflow_store = Some(store);
+        self
+    }
+
     /// Create a new service with default components.
     pub fn new() -> Self {
         use crate::server::backend_registry::create_default_registry;
@@ -81,11 +109,67 @@ impl ArvakServiceImpl {
         }
     }
 
+    /// Claim the idempotency key of a submission, if it carries one.
+    ///
+    /// Keys are scoped to the caller's API key, and remember a hash of the
+    /// RPC and its request so a key reused for another request is rejected.
+    async fn claim_submission(
+        &self,
+        metadata: &MetadataMap,
+        rpc: &str,
+        request: &impl prost::Message,
+    ) -> Result<Option<Idempotency>> {
+        let Some(key) = metadata.get(IDEMPOTENCY_KEY_HEADER) else {
+            return Ok(None);
+        };
+        let key = key
+            .to_str()
+            .ok()
+            .filter(|k| !k.is_empty() && k.len() <= MAX_IDEMPOTENCY_KEY_LEN)
+            .ok_or_else(|| {
+                Error::InvalidIdempotencyKey(format!(
+                    "must be 1 to {} ASCII characters",
+                    MAX_IDEMPOTENCY_KEY_LEN
+                ))
+            })?;
+
+        // Hashes are stored with the keys, so they must be stable across
+        // processes and releases
+        let api_key = AuthInterceptor::presented_key(metadata).unwrap_or_default();
+        let scoped_key = format!("{:016x}:{}", fnv1a(api_key.as_bytes()), key);
+
+        let mut fingerprint = Fnv1a::new();
+        fingerprint.write_str(rpc);
+        fingerprint.write(&request.encode_to_vec());
+
+        self.job_store
+            .claim_idempotency_key(&scoped_key, fingerprint.finish())
+            .await
+            .map(Some)
+    }
+
     /// Parse circuit from protobuf payload.
     fn parse_circuit(&self, payload: Option<CircuitPayload>) -> Result<Circuit> {
         Self::parse_circuit_static(payload)
     }
 
+    /// Parse the deadline of a submission, a Unix timestamp in seconds or 0
+    /// for none; deadlines that have already passed are rejected.
+    fn parse_deadline(secs: i64) -> Result<Option<DateTime<Utc>>> {
+        if secs == 0 {
+            return Ok(None);
+        }
+        let deadline = DateTime::from_timestamp(secs, 0)
+            .ok_or_else(|| Error::InvalidDeadline(format!("{} is out of range", secs)))?;
+        if deadline <= Utc::now() {
+            return Err(Error::InvalidDeadline(format!(
+                "{} has already passed",
+                deadline.to_rfc3339()
+            )));
+        }
+        Ok(Some(deadline))
+    }
+
     /// Execute a job synchronously (wait for completion).
     async fn execute_job_sync(
         job_store: Arc<JobStore>,
@@ -179,6 +263,92 @@ impl ArvakServiceImpl {
         }
     }
 
+    /// Convert a HAL partial result to protobuf partial counts.
+    fn to_proto_partial(partial: PartialResult) -> PartialCounts {
+        PartialCounts {
+            counts: partial
+                .counts
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+            shots_completed: partial.shots_completed,
+            shots_total: partial.shots_total,
+            is_final: partial.is_final,
+        }
+    }
+
+    /// Convert a HAL result to a protobuf result, encoding metadata in the
+    /// requested format.
+    fn to_proto_result(
+        job_id: &JobId,
+        result: &ExecutionResult,
+        format: PayloadFormat,
+    ) -> JobResult {
+        let counts = result
+            .counts
+            .iter()
+            .map(|(bitstring, count)| (bitstring.clone(), *count))
+            .collect();
+
+        let (metadata_json, metadata_cbor) = match format {
+            PayloadFormat::Json => (
+                serde_json::to_string(&result.metadata).unwrap_or_else(|_| "{}".to_string()),
+                Vec::new(),
+            ),
+            PayloadFormat::Cbor => {
+                let mut buf = Vec::new();
+                if let Err(e) = ciborium::into_writer(&result.metadata, &mut buf) {
+                    warn!("Failed to encode result metadata as CBOR: {}", e);
+                    buf.clear();
+                }
+                (String::new(), buf)
+            }
+        };
+
+        JobResult {
+            job_id: job_id.0.clone(),
+            counts,
+            shots: result.shots,
+            execution_time_ms: result.execution_time_ms.unwrap_or(0),
+            metadata_json,
+            metadata_cbor,
+        }
+    }
+
+    /// Check that the caller may use admin RPCs, recording the decision in
+    /// the `arvak::audit` tracing target.
+    fn authorize_admin<T>(
+        request: &Request<T>,
+        action: &str,
+        job_id: &str,
+        detail: &str,
+    ) -> Result<()> {
+        let allowed = request.extensions().get::<AdminAccess>().is_some();
+        let request_id = request
+            .extensions()
+            .get::<RequestId>()
+            .map(|id| id.0.as_str())
+            .unwrap_or_default();
+        info!(
+            target: "arvak::audit",
+            principal = "api-key",
+            action,
+            job_id,
+            allowed,
+            detail,
+            request_id,
+            "audit"
+        );
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::PermissionDenied(format!(
+                "{} requires an admin API key",
+                action
+            )))
+        }
+    }
+
     /// Spawn async task to execute a job.
     #[instrument(skip(job_store, backend, metrics, resources), fields(job_id = %job_id.0))]
     fn spawn_job_execution(
@@ -187,6 +357,8 @@ impl ArvakServiceImpl {
         job_id: JobId,
         metrics: Metrics,
         resources: Option<ResourceManager>,
+        partial_shots: Option<u32>,
+        deadline: Option<DateTime<Utc>>,
     ) {
         tokio::spawn(async move {
             // Get job details to access backend_id and submission time
@@ -223,64 +395,252 @@ impl ArvakServiceImpl {
                 .num_milliseconds() as u64;
             metrics.record_queue_time(&backend_id, queue_time);
 
-            // Execute on backend
+            // Execute on backend, in chunks when partial results are enabled
             let execution_start = chrono::Utc::now();
-            match backend.submit(&job.circuit, job.shots).await {
-                Ok(backend_job_id) => {
-                    // Wait for backend to complete
-                    match backend.wait(&backend_job_id).await {
-                        Ok(result) => {
-                            let duration = chrono::Utc::now()
-                                .signed_duration_since(execution_start)
-                                .num_milliseconds()
-                                as u64;
-
-                            if let Err(e) = job_store.store_result(&job_id, result).await {
-                                error!("Failed to store job result: {}", e);
-                                metrics.record_job_failed(&backend_id, "storage_error");
-                            } else {
-                                info!(duration_ms = duration, "Job completed successfully");
-                                metrics.record_job_completed(&backend_id, duration);
-                            }
-                            if let Some(ref resources) = resources {
-                                resources.job_completed().await;
-                            }
-                        }
-                        Err(e) => {
-                            let error_msg = format!("Backend wait failed: {}", e);
-                            warn!(error = %e, "Backend wait failed");
-                            metrics.record_job_failed(&backend_id, "backend_wait_error");
-                            if let Err(e) = job_store
-                                .update_status(&job_id, JobStatus::Failed(error_msg))
-                                .await
-                            {
-                                error!("Failed to update job status to failed: {}", e);
-                            }
-                            if let Some(ref resources) = resources {
-                                resources.job_completed().await;
-                            }
-                        }
+            let execution = async {
+                match partial_shots {
+                    Some(chunk_shots) => {
+                        let partials = job_store.clone();
+                        let partial_job_id = job_id.clone();
+                        let mut on_partial =
+                            move |partial| partials.record_partial(&partial_job_id, partial);
+                        backend
+                            .run_chunked(&job.circuit, job.shots, chunk_shots, &mut on_partial)
+                            .await
+                            .map_err(|e| ("Backend execution failed", "backend_execution_error", e))
                     }
+                    None => match backend.submit(&job.circuit, job.shots).await {
+                        // Wait for backend to complete
+                        Ok(backend_job_id) => backend
+                            .wait(&backend_job_id)
+                            .await
+                            .map_err(|e| ("Backend wait failed", "backend_wait_error", e)),
+                        Err(e) => Err(("Backend submit failed", "backend_submit_error", e)),
+                    },
                 }
-                Err(e) => {
-                    let error_msg = format!("Backend submit failed: {}", e);
-                    warn!(error = %e, "Backend submit failed");
-                    metrics.record_job_failed(&backend_id, "backend_submit_error");
+            };
+            // A result arriving after the deadline is of no use, so jobs
+            // still running then fail.
+            let outcome = match deadline {
+                Some(deadline) => {
+                    let left = (deadline - chrono::Utc::now()).to_std().unwrap_or_default();
+                    match tokio::time::timeout(left, execution).await {
+                        Ok(outcome) => outcome,
+                        Err(_) => Err((
+                            "Deadline exceeded",
+                            "deadline_exceeded",
+                            HalError::Timeout(job_id.0.clone()),
+                        )),
+                    }
+                }
+                None => execution.await,
+            };
+
+            match outcome {
+                Ok(result) => {
+                    let duration = chrono::Utc::now()
+                        .signed_duration_since(execution_start)
+                        .num_milliseconds() as u64;
+
+                    if let Err(e) = job_store.store_result(&job_id, result).await {
+                        error!("Failed to store job result: {}", e);
+                        metrics.record_job_failed(&backend_id, "storage_error");
+                    } else {
+                        info!(duration_ms = duration, "Job completed successfully");
+                        metrics.record_job_completed(&backend_id, duration);
+                    }
+                }
+                Err((what, reason, e)) => {
+                    let error_msg = format!("{}: {}", what, e);
+                    warn!(error = %e, "{}", what);
+                    metrics.record_job_failed(&backend_id, reason);
                     if let Err(e) = job_store
                         .update_status(&job_id, JobStatus::Failed(error_msg))
                         .await
                     {
                         error!("Failed to update job status to failed: {}", e);
                     }
-                    if let Some(ref resources) = resources {
-                        resources.job_completed().await;
-                    }
                 }
             }
+            if let Some(ref resources) = resources {
+                resources.job_completed().await;
+            }
         });
     }
 }
 
+impl ArvakServiceImpl {
+    /// Load a workflow with fresh job states and summarize its progress.
+    async fn workflow_progress(&self, workflow_id: &str) -> Result<GetWorkflowProgressResponse> {
+        use arvak_sched::{WorkflowId, WorkflowStatus};
+
+        let store = self.workflow_store.as_ref().ok_or_else(|| {
+            Error::NotConfigured("no scheduler state store for workflows".to_string())
+        })?;
+        let id = WorkflowId::parse(workflow_id)
+            .map_err(|_| Error::WorkflowNotFound(workflow_id.to_string()))?;
+        let mut workflow = store
+            .load_workflow(&id)
+            .await
+            .map_err(|e| Error::StorageError(e.to_string()))?
+            .ok_or_else(|| Error::WorkflowNotFound(workflow_id.to_string()))?;
+        workflow
+            .refresh_from(store.as_ref())
+            .await
+            .map_err(|e| Error::StorageError(e.to_string()))?;
+        let progress = workflow.progress();
+
+        let status = match workflow.status {
+            WorkflowStatus::Pending => "Pending",
+            WorkflowStatus::Running => "Running",
+            WorkflowStatus::Completed => "Completed",
+            WorkflowStatus::Failed { .. } => "Failed",
+            WorkflowStatus::Cancelled => "Cancelled",
+        };
+
+        Ok(GetWorkflowProgressResponse {
+            workflow_id: workflow.id.to_string(),
+            name: workflow.name,
+            status: status.to_string(),
+            total: progress.total as u32,
+            completed: progress.completed as u32,
+            running: progress.running as u32,
+            pending: progress.pending as u32,
+            failed: progress.failed as u32,
+            skipped: progress.skipped as u32,
+            percent_complete: progress.percent_complete,
+            remaining_seconds: progress.remaining_secs,
+            eta: progress.eta.map(|t| t.timestamp()).unwrap_or(0),
+        })
+    }
+
+    /// The scheduler state store holding job templates.
+    fn template_store(&self) -> Result<&Arc<dyn arvak_sched::StateStore>> {
+        self.workflow_store.as_ref().ok_or_else(|| {
+            Error::NotConfigured("no scheduler state store for templates".to_string())
+        })
+    }
+
+    /// Load a job template by name.
+    async fn load_template(&self, name: &str) -> Result<arvak_sched::JobTemplate> {
+        self.template_store()?
+            .load_template(name)
+            .await
+            .map_err(|e| Error::StorageError(e.to_string()))?
+            .ok_or_else(|| Error::TemplateNotFound(name.to_string()))
+    }
+
+    /// Save a job template from its protobuf form.
+    async fn save_template(&self, info: TemplateInfo) -> Result<()> {
+        use arvak_sched::{CircuitSpec, JobTemplate, Priority};
+
+        let circuit = self.parse_circuit(info.circuit)?;
+        let qasm = arvak_qasm3::emit(&circuit)?;
+        let mut template =
+            JobTemplate::new(info.name, CircuitSpec::from_qasm(qasm)).with_shots(info.shots.max(1));
+        if info.priority > 0 {
+            template = template.with_priority(Priority::new(info.priority));
+        }
+        if !info.description.is_empty() {
+            template = template.with_description(info.description);
+        }
+        template.labels = info.labels.into_iter().collect();
+        template
+            .validate()
+            .map_err(|e| Error::InvalidCircuit(e.to_string()))?;
+
+        let store = self.template_store()?;
+        if let Some(existing) = store
+            .load_template(&template.name)
+            .await
+            .map_err(|e| Error::StorageError(e.to_string()))?
+        {
+            template.created_at = existing.created_at;
+        }
+        template.updated_at = chrono::Utc::now();
+        store
+            .save_template(&template)
+            .await
+            .map_err(|e| Error::StorageError(e.to_string()))
+    }
+
+    /// Compile a circuit for the requested backend and pipeline.
+    async fn compile_circuit(&self, req: CompileRequest) -> Result<CompileResponse> {
+        use arvak_compile::{PassManagerBuilder, PipelinePreset};
+
+        let circuit = self.parse_circuit(req.circuit)?;
+
+        let mut builder = if req.preset.is_empty() {
+            PassManagerBuilder::new().with_optimization_level(req.optimization_level.min(3) as u8)
+        } else {
+            req.preset.parse::<PipelinePreset>()?.builder()
+        };
+        if !req.backend_id.is_empty() {
+            let backend = self.backends.get(&req.backend_id)?;
+            let caps = backend.capabilities().await?;
+            if let Some((coupling_map, basis_gates)) = compile_target(&caps) {
+                builder = builder.with_target(coupling_map, basis_gates);
+            }
+        }
+        if let Some(seed) = req.seed {
+            builder = builder.with_seed(seed);
+        }
+
+        let (pm, mut props) = builder.build();
+        let mut dag = circuit.into_dag();
+        pm.run(&mut dag, &mut props)?;
+        let compiled = Circuit::from_dag(dag);
+
+        Ok(CompileResponse {
+            circuit: Some(CircuitPayload {
+                format: Some(circuit_payload::Format::Qasm3(arvak_qasm3::emit(
+                    &compiled,
+                )?)),
+            }),
+            depth: compiled.depth() as u32,
+            num_ops: compiled.dag().num_ops() as u32,
+        })
+    }
+}
+
+/// Protobuf form of a job template.
+fn template_info(template: arvak_sched::JobTemplate) -> Result<TemplateInfo> {
+    let arvak_sched::CircuitSpec::Qasm3(qasm) = template.circuit else {
+        return Err(Error::InvalidCircuit(format!(
+            "template {} does not hold OpenQASM 3 source",
+            template.name
+        )));
+    };
+    Ok(TemplateInfo {
+        name: template.name,
+        description: template.description.unwrap_or_default(),
+        circuit: Some(CircuitPayload {
+            format: Some(circuit_payload::Format::Qasm3(qasm)),
+        }),
+        shots: template.shots,
+        priority: template.priority.value(),
+        labels: template.labels.into_iter().collect(),
+        updated_at: template.updated_at.timestamp(),
+    })
+}
+
+/// Coupling map and basis gates to compile for, `None` for simulators,
+/// which run any circuit as is.
+fn compile_target(
+    caps: &arvak_hal::Capabilities,
+) -> Option<(arvak_compile::CouplingMap, arvak_compile::BasisGates)> {
+    if caps.is_simulator {
+        return None;
+    }
+    let gates = caps
+        .gate_set
+        .native
+        .iter()
+        .map(String::as_str)
+        .chain(["measure", "barrier"]);
+    Some((caps.coupling_map(), arvak_compile::BasisGates::new(gates)))
+}
+
 impl Default for ArvakServiceImpl {
     fn default() -> Self {
         Self::new()
@@ -311,10 +671,29 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
         // Extract client IP from request metadata (if available)
         let client_ip = request.remote_addr().map(|addr| addr.ip().to_string());
 
-        let req = request.into_inner();
+        let (metadata, _, req) = request.into_parts();
 
         tracing::Span::current().record("backend_id", req.backend_id.as_str());
 
+        let claim = match self
+            .claim_submission(&metadata, "SubmitJob", &req)
+            .await
+            .map_err(Status::from)?
+        {
+            Some(Idempotency::Replay(job_ids)) => {
+                let job_id = job_ids
+                    .into_iter()
+                    .next()
+                    .map(|id| id.0)
+                    .unwrap_or_default();
+                tracing::Span::current().record("job_id", job_id.as_str());
+                info!("Job submission replayed");
+                return Ok(Response::new(SubmitJobResponse { job_id }));
+            }
+            Some(Idempotency::Claimed(claim)) => Some(claim),
+            None => None,
+        };
+
         // Check resource limits if manager is configured
         if let Some(ref resources) = self.resources {
             resources
@@ -325,6 +704,7 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
 
         // Parse circuit
         let circuit = self.parse_circuit(req.circuit).map_err(Status::from)?;
+        let deadline = Self::parse_deadline(req.deadline).map_err(Status::from)?;
 
         // Validate backend exists
         let backend = self.backends.get(&req.backend_id).map_err(Status::from)?;
@@ -336,6 +716,12 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
             .await
             .map_err(Status::from)?;
 
+        if let Some(claim) = claim {
+            if let Err(e) = claim.complete(std::slice::from_ref(&job_id)).await {
+                warn!(error = %e, "Failed to record idempotency key");
+            }
+        }
+
         tracing::Span::current().record("job_id", job_id.0.as_str());
         info!(shots = req.shots, "Job submitted");
 
@@ -354,6 +740,8 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
             job_id.clone(),
             self.metrics.clone(),
             self.resources.clone(),
+            self.partial_shots,
+            deadline,
         );
 
         // Record RPC duration
@@ -369,7 +757,22 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
         request: Request<SubmitBatchRequest>,
     ) -> std::result::Result<Response<SubmitBatchResponse>, Status> {
         let start = std::time::Instant::now();
-        let req = request.into_inner();
+        let (metadata, _, req) = request.into_parts();
+
+        let claim = match self
+            .claim_submission(&metadata, "SubmitBatch", &req)
+            .await
+            .map_err(Status::from)?
+        {
+            Some(Idempotency::Replay(job_ids)) => {
+                info!(jobs = job_ids.len(), "Batch submission replayed");
+                return Ok(Response::new(SubmitBatchResponse {
+                    job_ids: job_ids.into_iter().map(|id| id.0).collect(),
+                }));
+            }
+            Some(Idempotency::Claimed(claim)) => Some(claim),
+            None => None,
+        };
 
         // Validate backend exists
         let backend = self.backends.get(&req.backend_id).map_err(Status::from)?;
@@ -397,16 +800,26 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
                 job_id.clone(),
                 self.metrics.clone(),
                 self.resources.clone(),
+                self.partial_shots,
+                None,
             );
 
-            job_ids.push(job_id.0);
+            job_ids.push(job_id);
+        }
+
+        if let Some(claim) = claim {
+            if let Err(e) = claim.complete(&job_ids).await {
+                warn!(error = %e, "Failed to record idempotency key");
+            }
         }
 
         // Record RPC duration
         let duration = start.elapsed().as_millis() as u64;
         self.metrics.record_rpc_duration("SubmitBatch", duration);
 
-        Ok(Response::new(SubmitBatchResponse { job_ids }))
+        Ok(Response::new(SubmitBatchResponse {
+            job_ids: job_ids.into_iter().map(|id| id.0).collect(),
+        }))
     }
 
     #[instrument(skip(self, request), fields(job_id))]
@@ -475,11 +888,23 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
                             _ => String::new(),
                         };
 
+                        // Counts so far while running, the full counts once done
+                        let partial = match &job.status {
+                            JobStatus::Running => job_store.partial(&job.id),
+                            JobStatus::Completed => job_store
+                                .get_result(&job.id)
+                                .await
+                                .ok()
+                                .map(|result| PartialResult::from_result(&result)),
+                            _ => None,
+                        };
+
                         let update = JobStatusUpdate {
                             job_id: job.id.0.clone(),
                             state: Self::to_proto_state(&job.status) as i32,
                             timestamp: chrono::Utc::now().timestamp(),
                             error_message,
+                            partial: partial.map(Self::to_proto_partial),
                         };
 
                         // Send update
@@ -594,6 +1019,7 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
                 match result {
                     Ok(submission) => {
                         let client_request_id = submission.client_request_id.clone();
+                        let result_format = submission.result_format();
 
                         // Parse circuit
                         let circuit = match Self::parse_circuit_static(submission.circuit) {
@@ -670,28 +1096,15 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
 
                                     // Send completion notification
                                     if let Ok(result) = job_store_clone.get_result(&job_id).await {
-                                        let mut counts = std::collections::HashMap::new();
-                                        for (k, v) in result.counts.iter() {
-                                            counts.insert(k.clone(), *v);
-                                        }
-
-                                        let metadata_json = serde_json::to_string(&result.metadata)
-                                            .unwrap_or_else(|_| "{}".to_string());
+                                        let proto_result =
+                                            Self::to_proto_result(&job_id, &result, result_format);
 
                                         let _ = tx_clone
                                             .send(Ok(BatchJobResult {
                                                 job_id: job_id.0.clone(),
                                                 client_request_id,
                                                 result: Some(batch_job_result::Result::Completed(
-                                                    JobResult {
-                                                        job_id: job_id.0,
-                                                        counts,
-                                                        shots: result.shots,
-                                                        execution_time_ms: result
-                                                            .execution_time_ms
-                                                            .unwrap_or(0),
-                                                        metadata_json,
-                                                    },
+                                                    proto_result,
                                                 )),
                                             }))
                                             .await;
@@ -741,22 +1154,7 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
             .await
             .map_err(Status::from)?;
 
-        // Convert counts to protobuf map
-        let mut counts = std::collections::HashMap::new();
-        for (bitstring, count) in result.counts.iter() {
-            counts.insert(bitstring.clone(), *count);
-        }
-
-        let metadata_json =
-            serde_json::to_string(&result.metadata).unwrap_or_else(|_| "{}".to_string());
-
-        let proto_result = JobResult {
-            job_id: req.job_id,
-            counts,
-            shots: result.shots,
-            execution_time_ms: result.execution_time_ms.unwrap_or(0),
-            metadata_json,
-        };
+        let proto_result = Self::to_proto_result(&job_id, &result, req.format());
 
         Ok(Response::new(GetJobResultResponse {
             result: Some(proto_result),
@@ -796,6 +1194,244 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
         }))
     }
 
+    async fn requeue_job(
+        &self,
+        request: Request<RequeueJobRequest>,
+    ) -> std::result::Result<Response<AdminJobResponse>, Status> {
+        Self::authorize_admin(
+            &request,
+            "requeue",
+            &request.get_ref().job_id,
+            &request.get_ref().reason,
+        )
+        .map_err(Status::from)?;
+        let req = request.into_inner();
+        let job_id = JobId::new(req.job_id);
+
+        let job = self
+            .job_store
+            .get_job(&job_id)
+            .await
+            .map_err(Status::from)?;
+        if job.status == JobStatus::Completed {
+            return Ok(Response::new(AdminJobResponse {
+                success: false,
+                message: "Job already completed".to_string(),
+            }));
+        }
+        let backend = self.backends.get(&job.backend_id).map_err(Status::from)?;
+
+        self.job_store
+            .update_status(&job_id, JobStatus::Queued)
+            .await
+            .map_err(Status::from)?;
+        Self::spawn_job_execution(
+            self.job_store.clone(),
+            backend,
+            job_id,
+            self.metrics.clone(),
+            None,
+            self.partial_shots,
+            None,
+        );
+
+        Ok(Response::new(AdminJobResponse {
+            success: true,
+            message: format!("Job requeued (was {})", job.status),
+        }))
+    }
+
+    async fn force_fail_job(
+        &self,
+        request: Request<ForceFailJobRequest>,
+    ) -> std::result::Result<Response<AdminJobResponse>, Status> {
+        Self::authorize_admin(
+            &request,
+            "force_fail",
+            &request.get_ref().job_id,
+            &request.get_ref().reason,
+        )
+        .map_err(Status::from)?;
+        let req = request.into_inner();
+        if req.reason.is_empty() {
+            return Err(Status::invalid_argument("A failure reason is required"));
+        }
+        let job_id = JobId::new(req.job_id);
+
+        let job = self
+            .job_store
+            .get_job(&job_id)
+            .await
+            .map_err(Status::from)?;
+        self.job_store
+            .update_status(&job_id, JobStatus::Failed(req.reason))
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(AdminJobResponse {
+            success: true,
+            message: format!("Job marked failed (was {})", job.status),
+        }))
+    }
+
+    async fn purge_queue(
+        &self,
+        request: Request<PurgeQueueRequest>,
+    ) -> std::result::Result<Response<PurgeQueueResponse>, Status> {
+        let detail = format!(
+            "backend: {}; {}",
+            request.get_ref().backend_id,
+            request.get_ref().reason
+        );
+        Self::authorize_admin(&request, "purge", "", &detail).map_err(Status::from)?;
+        let req = request.into_inner();
+
+        let mut filter = JobFilter::new()
+            .with_state(JobStatus::Queued)
+            .with_limit(i64::MAX as usize);
+        if !req.backend_id.is_empty() {
+            filter = filter.with_backend(req.backend_id);
+        }
+        let jobs = self
+            .job_store
+            .list_jobs(filter)
+            .await
+            .map_err(Status::from)?;
+
+        let mut job_ids = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            self.job_store
+                .update_status(&job.id, JobStatus::Cancelled)
+                .await
+                .map_err(Status::from)?;
+            job_ids.push(job.id.0);
+        }
+        info!(jobs = job_ids.len(), "Queue purged");
+
+        Ok(Response::new(PurgeQueueResponse { job_ids }))
+    }
+
+    async fn rebuild_indices(
+        &self,
+        request: Request<RebuildIndicesRequest>,
+    ) -> std::result::Result<Response<RebuildIndicesResponse>, Status> {
+        Self::authorize_admin(&request, "reindex", "", "").map_err(Status::from)?;
+
+        let jobs = self
+            .job_store
+            .rebuild_indices()
+            .await
+            .map_err(Status::from)?;
+
+        Ok(Response::new(RebuildIndicesResponse { jobs: jobs as u64 }))
+    }
+
+    async fn get_workflow_progress(
+        &self,
+        request: Request<GetWorkflowProgressRequest>,
+    ) -> std::result::Result<Response<GetWorkflowProgressResponse>, Status> {
+        let req = request.into_inner();
+        let progress = self
+            .workflow_progress(&req.workflow_id)
+            .await
+            .map_err(Status::from)?;
+        Ok(Response::new(progress))
+    }
+
+    async fn compile(
+        &self,
+        request: Request<CompileRequest>,
+    ) -> std::result::Result<Response<CompileResponse>, Status> {
+        let response = self
+            .compile_circuit(request.into_inner())
+            .await
+            .map_err(Status::from)?;
+        Ok(Response::new(response))
+    }
+
+    async fn save_template(
+        &self,
+        request: Request<SaveTemplateRequest>,
+    ) -> std::result::Result<Response<SaveTemplateResponse>, Status> {
+        let template = request
+            .into_inner()
+            .template
+            .ok_or_else(|| Status::invalid_argument("missing template"))?;
+        ArvakServiceImpl::save_template(self, template)
+            .await
+            .map_err(Status::from)?;
+        Ok(Response::new(SaveTemplateResponse {}))
+    }
+
+    async fn get_template(
+        &self,
+        request: Request<GetTemplateRequest>,
+    ) -> std::result::Result<Response<GetTemplateResponse>, Status> {
+        let template = self
+            .load_template(&request.into_inner().name)
+            .await
+            .map_err(Status::from)?;
+        Ok(Response::new(GetTemplateResponse {
+            template: Some(template_info(template).map_err(Status::from)?),
+        }))
+    }
+
+    async fn list_templates(
+        &self,
+        _request: Request<ListTemplatesRequest>,
+    ) -> std::result::Result<Response<ListTemplatesResponse>, Status> {
+        let templates = self
+            .template_store()
+            .map_err(Status::from)?
+            .list_templates()
+            .await
+            .map_err(|e| Status::from(Error::StorageError(e.to_string())))?;
+        // Templates saved by other clients may reference circuit files;
+        // only those holding QASM source can be returned.
+        let templates = templates
+            .into_iter()
+            .filter_map(|t| template_info(t).ok())
+            .collect();
+        Ok(Response::new(ListTemplatesResponse { templates }))
+    }
+
+    async fn delete_template(
+        &self,
+        request: Request<DeleteTemplateRequest>,
+    ) -> std::result::Result<Response<DeleteTemplateResponse>, Status> {
+        let deleted = self
+            .template_store()
+            .map_err(Status::from)?
+            .delete_template(&request.into_inner().name)
+            .await
+            .map_err(|e| Status::from(Error::StorageError(e.to_string())))?;
+        Ok(Response::new(DeleteTemplateResponse { deleted }))
+    }
+
+    async fn submit_template(
+        &self,
+        request: Request<SubmitTemplateRequest>,
+    ) -> std::result::Result<Response<SubmitJobResponse>, Status> {
+        let (metadata, extensions, req) = request.into_parts();
+        let template = template_info(self.load_template(&req.name).await.map_err(Status::from)?)
+            .map_err(Status::from)?;
+        let shots = if req.shots > 0 {
+            req.shots
+        } else {
+            template.shots
+        };
+        // Jobs run through the regular submission path, with its limits,
+        // idempotency and metrics.
+        let submit = SubmitJobRequest {
+            circuit: template.circuit,
+            backend_id: req.backend_id,
+            shots,
+            deadline: 0,
+        };
+        self.submit_job(Request::from_parts(metadata, extensions, submit))
+            .await
+    }
+
     async fn list_backends(
         &self,
         _request: Request<ListBackendsRequest>,
@@ -871,3 +1507,50 @@ impl arvak_service_server::ArvakService for ArvakServiceImpl {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_ir::StandardGate;
+    use prost::Message;
+
+    #[test]
+    fn test_compile_target() {
+        assert!(compile_target(&arvak_hal::Capabilities::simulator(5)).is_none());
+
+        let (coupling_map, basis) = compile_target(&arvak_hal::Capabilities::iqm("q", 5)).unwrap();
+        assert_eq!(coupling_map.num_qubits(), 5);
+        assert!(basis.contains("prx") && basis.contains("cz") && basis.contains("measure"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn fuzz_circuit_payload_roundtrip(
+            circuit in arvak_ir::strategy::circuit_with_gates(
+                // The QASM parser drops `id` as a no-op.
+                arvak_ir::random::standard_gates()
+                    .into_iter()
+                    .filter(|g| *g != StandardGate::I)
+                    .collect(),
+                5,
+                8,
+            )
+        ) {
+            let request = SubmitJobRequest {
+                circuit: Some(CircuitPayload {
+                    format: Some(circuit_payload::Format::Qasm3(
+                        arvak_qasm3::emit_canonical(&circuit).unwrap(),
+                    )),
+                }),
+                backend_id: "simulator".to_string(),
+                shots: 100,
+                deadline: 0,
+            };
+
+            let decoded = SubmitJobRequest::decode(request.encode_to_vec().as_slice()).unwrap();
+            let parsed = ArvakServiceImpl::parse_circuit_static(decoded.circuit).unwrap();
+            let diff = arvak_qasm3::canonical_diff(&circuit, &parsed).unwrap();
+            proptest::prop_assert!(diff.is_none(), "{}", diff.unwrap_or_default());
+        }
+    }
+}
@@ -1,23 +1,24 @@
 //! In-memory job storage (no persistence).
 //!
 //! This implementation uses `Arc<RwLock<FxHashMap>>` for thread-safe in-memory
-//! storage. Jobs are lost when the server restarts.
+//! storage. Jobs and idempotency keys are lost when the server restarts.
 
 use arvak_hal::job::{JobId, JobStatus};
 use arvak_hal::result::ExecutionResult;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use super::{JobFilter, JobStorage, StoredJob};
+use super::{IdempotencyRecord, JobFilter, JobStorage, StoredJob};
 use crate::error::{Error, Result};
 
 /// In-memory job storage.
 #[derive(Clone)]
 pub struct MemoryStorage {
     jobs: Arc<RwLock<FxHashMap<String, StoredJob>>>,
+    idempotency: Arc<RwLock<FxHashMap<String, IdempotencyRecord>>>,
 }
 
 impl MemoryStorage {
@@ -25,6 +26,7 @@ impl MemoryStorage {
     pub fn new() -> Self {
         Self {
             jobs: Arc::new(RwLock::new(FxHashMap::default())),
+            idempotency: Arc::new(RwLock::new(FxHashMap::default())),
         }
     }
 }
@@ -129,7 +131,7 @@ impl JobStorage for MemoryStorage {
             .collect();
 
         // Sort by submitted_at descending (most recent first)
-        results.sort_by(|a, b| b.submitted_at.cmp(&a.submitted_at));
+        results.sort_by_key(|r| std::cmp::Reverse(r.submitted_at));
 
         // Apply limit
         results.truncate(filter.limit);
@@ -142,6 +144,41 @@ impl JobStorage for MemoryStorage {
         jobs.remove(&job_id.0);
         Ok(())
     }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        claim: &IdempotencyRecord,
+    ) -> Result<Option<IdempotencyRecord>> {
+        let now = Utc::now();
+        let mut records = self.idempotency.write().await;
+        records.retain(|_, record| !record.is_expired(now));
+
+        if let Some(record) = records.get(key) {
+            return Ok(Some(record.clone()));
+        }
+        records.insert(key.to_string(), claim.clone());
+        Ok(None)
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        job_ids: &[JobId],
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut records = self.idempotency.write().await;
+        if let Some(record) = records.get_mut(key) {
+            record.job_ids = Some(job_ids.to_vec());
+            record.expires_at = expires_at;
+        }
+        Ok(())
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> Result<()> {
+        self.idempotency.write().await.remove(key);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
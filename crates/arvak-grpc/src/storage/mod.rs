@@ -85,6 +85,24 @@ impl JobFilter {
     }
 }
 
+/// A submission remembered by its idempotency key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdempotencyRecord {
+    /// Fingerprint of the request first sent with the key.
+    pub fingerprint: u64,
+    /// Jobs the request created, or `None` while it is being handled.
+    pub job_ids: Option<Vec<JobId>>,
+    /// When the key is forgotten.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl IdempotencyRecord {
+    /// Whether the key is forgotten at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
 /// Trait for job storage backends.
 ///
 /// Implementations must be thread-safe (Send + Sync) and support async operations.
@@ -120,6 +138,41 @@ pub trait JobStorage: Send + Sync {
     /// Returns `Ok(())` even if the job doesn't exist (idempotent).
     async fn delete_job(&self, job_id: &JobId) -> Result<()>;
 
+    /// Claim an idempotency key by storing `claim` under it, unless the key
+    /// holds a record that has not expired.
+    ///
+    /// Returns the existing record, or `None` if the claim was stored. Must be
+    /// atomic: of concurrent claims of a key only one may succeed.
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        claim: &IdempotencyRecord,
+    ) -> Result<Option<IdempotencyRecord>>;
+
+    /// Record the jobs created by the request of a claimed idempotency key,
+    /// and when the key expires.
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        job_ids: &[JobId],
+        expires_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// Forget an idempotency key, e.g. because its request failed.
+    async fn release_idempotency_key(&self, key: &str) -> Result<()>;
+
+    /// Rebuild the indices over stored jobs, e.g. after a crash left them
+    /// inconsistent.
+    ///
+    /// Returns the number of jobs in the store. Backends without indices only
+    /// count the jobs.
+    async fn rebuild_indices(&self) -> Result<usize> {
+        let jobs = self
+            .list_jobs(JobFilter::new().with_limit(i64::MAX as usize))
+            .await?;
+        Ok(jobs.len())
+    }
+
     /// Get a job result by ID.
     ///
     /// This is a convenience method that combines get_job and extracting
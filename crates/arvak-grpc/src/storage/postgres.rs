@@ -7,7 +7,7 @@
 //! - Async operations with tokio-postgres
 
 use crate::error::{Error, Result};
-use crate::storage::{JobFilter, JobStorage, StoredJob};
+use crate::storage::{IdempotencyRecord, JobFilter, JobStorage, StoredJob};
 use arvak_hal::job::{JobId, JobStatus};
 use arvak_hal::result::ExecutionResult;
 use async_trait::async_trait;
@@ -114,6 +114,22 @@ impl PostgresStorage {
             .await
             .ok();
 
+        // Idempotency keys of submissions, with the jobs they created
+        client
+            .execute(
+                "CREATE TABLE IF NOT EXISTS idempotency_keys (
+                    key TEXT PRIMARY KEY,
+                    fingerprint BIGINT NOT NULL,
+                    job_ids_json TEXT,
+                    expires_at BIGINT NOT NULL
+                )",
+                &[],
+            )
+            .await
+            .map_err(|e| {
+                Error::StorageError(format!("Failed to create idempotency_keys table: {}", e))
+            })?;
+
         Ok(())
     }
 
@@ -154,6 +170,16 @@ impl PostgresStorage {
             Err(Error::StorageError(format!("Invalid status: {}", s)))
         }
     }
+
+    /// Serialize the jobs of an idempotency key.
+    fn serialize_job_ids(job_ids: &[JobId]) -> Result<String> {
+        Ok(serde_json::to_string(job_ids)?)
+    }
+
+    /// Deserialize the jobs of an idempotency key.
+    fn deserialize_job_ids(json: &str) -> Result<Vec<JobId>> {
+        Ok(serde_json::from_str(json)?)
+    }
 }
 
 #[async_trait]
@@ -467,6 +493,121 @@ impl JobStorage for PostgresStorage {
 
         Ok(())
     }
+
+    async fn rebuild_indices(&self) -> Result<usize> {
+        let client = self.client.lock().await;
+
+        client
+            .batch_execute(
+                "REINDEX TABLE jobs; REINDEX TABLE job_results; REINDEX TABLE idempotency_keys;",
+            )
+            .await
+            .map_err(|e| Error::StorageError(format!("Failed to rebuild indices: {}", e)))?;
+        let row = client
+            .query_one("SELECT COUNT(*) FROM jobs", &[])
+            .await
+            .map_err(|e| Error::StorageError(format!("Failed to count jobs: {}", e)))?;
+
+        Ok(row.get::<_, i64>(0) as usize)
+    }
+
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        claim: &IdempotencyRecord,
+    ) -> Result<Option<IdempotencyRecord>> {
+        let client = self.client.lock().await;
+        let fingerprint = claim.fingerprint as i64;
+        let job_ids_json = claim
+            .job_ids
+            .as_deref()
+            .map(Self::serialize_job_ids)
+            .transpose()?;
+        let expires_at = claim.expires_at.timestamp_millis();
+
+        loop {
+            let now = Utc::now().timestamp_millis();
+            // Takes the key if it is free or expired, atomically across servers
+            let claimed = client
+                .query_opt(
+                    "INSERT INTO idempotency_keys (key, fingerprint, job_ids_json, expires_at)
+                     VALUES ($1, $2, $3, $4)
+                     ON CONFLICT (key) DO UPDATE SET
+                        fingerprint = EXCLUDED.fingerprint,
+                        job_ids_json = EXCLUDED.job_ids_json,
+                        expires_at = EXCLUDED.expires_at
+                     WHERE idempotency_keys.expires_at <= $5
+                     RETURNING key",
+                    &[&key, &fingerprint, &job_ids_json, &expires_at, &now],
+                )
+                .await
+                .map_err(|e| {
+                    Error::StorageError(format!("Failed to claim idempotency key: {}", e))
+                })?;
+            if claimed.is_some() {
+                return Ok(None);
+            }
+
+            let row = client
+                .query_opt(
+                    "SELECT fingerprint, job_ids_json, expires_at
+                     FROM idempotency_keys WHERE key = $1",
+                    &[&key],
+                )
+                .await
+                .map_err(|e| {
+                    Error::StorageError(format!("Failed to get idempotency key: {}", e))
+                })?;
+            // Released since the insert; try again
+            let Some(row) = row else {
+                continue;
+            };
+
+            let job_ids_json: Option<String> = row.get(1);
+            return Ok(Some(IdempotencyRecord {
+                fingerprint: row.get::<_, i64>(0) as u64,
+                job_ids: job_ids_json
+                    .map(|json| Self::deserialize_job_ids(&json))
+                    .transpose()?,
+                expires_at: DateTime::from_timestamp_millis(row.get(2)).unwrap_or_else(Utc::now),
+            }));
+        }
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        job_ids: &[JobId],
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let client = self.client.lock().await;
+        let job_ids_json = Self::serialize_job_ids(job_ids)?;
+
+        client
+            .execute(
+                "UPDATE idempotency_keys SET job_ids_json = $1, expires_at = $2 WHERE key = $3",
+                &[&job_ids_json, &expires_at.timestamp_millis(), &key],
+            )
+            .await
+            .map_err(|e| {
+                Error::StorageError(format!("Failed to complete idempotency key: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> Result<()> {
+        let client = self.client.lock().await;
+
+        client
+            .execute("DELETE FROM idempotency_keys WHERE key = $1", &[&key])
+            .await
+            .map_err(|e| {
+                Error::StorageError(format!("Failed to release idempotency key: {}", e))
+            })?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
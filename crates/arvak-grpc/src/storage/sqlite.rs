@@ -7,7 +7,7 @@
 //! - Automatic schema migrations
 
 use crate::error::{Error, Result};
-use crate::storage::{JobFilter, JobStorage, StoredJob};
+use crate::storage::{IdempotencyRecord, JobFilter, JobStorage, StoredJob};
 use arvak_hal::job::{JobId, JobStatus};
 use arvak_hal::result::ExecutionResult;
 use async_trait::async_trait;
@@ -95,6 +95,17 @@ impl SqliteStorage {
             [],
         )?;
 
+        // Idempotency keys of submissions, with the jobs they created
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS idempotency_keys (
+                key TEXT PRIMARY KEY,
+                fingerprint INTEGER NOT NULL,
+                job_ids_json TEXT,
+                expires_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+
         Ok(())
     }
 
@@ -142,6 +153,15 @@ impl SqliteStorage {
             Err(Error::StorageError(format!("Invalid status: {}", s)))
         }
     }
+    /// Serialize the jobs of an idempotency key.
+    fn serialize_job_ids(job_ids: &[JobId]) -> Result<String> {
+        Ok(serde_json::to_string(job_ids)?)
+    }
+
+    /// Deserialize the jobs of an idempotency key.
+    fn deserialize_job_ids(json: &str) -> Result<Vec<JobId>> {
+        Ok(serde_json::from_str(json)?)
+    }
 }
 
 #[async_trait]
@@ -457,6 +477,126 @@ impl JobStorage for SqliteStorage {
         .await
         .map_err(|e| Error::StorageError(format!("task join error: {}", e)))?
     }
+
+    async fn rebuild_indices(&self) -> Result<usize> {
+        let conn = self.connection.clone();
+
+        task::spawn_blocking(move || {
+            let conn = conn.lock().expect("database lock poisoned");
+
+            conn.execute_batch("REINDEX jobs; REINDEX job_results; REINDEX idempotency_keys;")?;
+            let jobs: i64 = conn.query_row("SELECT COUNT(*) FROM jobs", [], |row| row.get(0))?;
+
+            Ok(jobs as usize)
+        })
+        .await
+        .map_err(|e| Error::StorageError(format!("task join error: {}", e)))?
+    }
+    async fn claim_idempotency_key(
+        &self,
+        key: &str,
+        claim: &IdempotencyRecord,
+    ) -> Result<Option<IdempotencyRecord>> {
+        let key = key.to_string();
+        let claim = claim.clone();
+        let conn = self.connection.clone();
+
+        task::spawn_blocking(move || {
+            let mut conn = conn.lock().expect("database lock poisoned");
+            let tx = conn.transaction()?;
+
+            tx.execute(
+                "DELETE FROM idempotency_keys WHERE expires_at <= ?1",
+                params![Utc::now().timestamp_millis()],
+            )?;
+            let existing = tx
+                .query_row(
+                    "SELECT fingerprint, job_ids_json, expires_at
+                     FROM idempotency_keys WHERE key = ?1",
+                    params![key],
+                    |row| {
+                        let fingerprint: i64 = row.get(0)?;
+                        let job_ids_json: Option<String> = row.get(1)?;
+                        let expires_ms: i64 = row.get(2)?;
+                        Ok((fingerprint, job_ids_json, expires_ms))
+                    },
+                )
+                .optional()?;
+
+            let record = match existing {
+                Some((fingerprint, job_ids_json, expires_ms)) => Some(IdempotencyRecord {
+                    fingerprint: fingerprint as u64,
+                    job_ids: job_ids_json
+                        .map(|json| Self::deserialize_job_ids(&json))
+                        .transpose()?,
+                    expires_at: DateTime::from_timestamp_millis(expires_ms)
+                        .unwrap_or_else(Utc::now),
+                }),
+                None => {
+                    let job_ids_json = claim
+                        .job_ids
+                        .as_deref()
+                        .map(Self::serialize_job_ids)
+                        .transpose()?;
+                    tx.execute(
+                        "INSERT INTO idempotency_keys (key, fingerprint, job_ids_json, expires_at)
+                         VALUES (?1, ?2, ?3, ?4)",
+                        params![
+                            key,
+                            claim.fingerprint as i64,
+                            job_ids_json,
+                            claim.expires_at.timestamp_millis(),
+                        ],
+                    )?;
+                    None
+                }
+            };
+            tx.commit()?;
+
+            Ok(record)
+        })
+        .await
+        .map_err(|e| Error::StorageError(format!("task join error: {}", e)))?
+    }
+
+    async fn complete_idempotency_key(
+        &self,
+        key: &str,
+        job_ids: &[JobId],
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let key = key.to_string();
+        let job_ids_json = Self::serialize_job_ids(job_ids)?;
+        let conn = self.connection.clone();
+
+        task::spawn_blocking(move || {
+            let conn = conn.lock().expect("database lock poisoned");
+
+            conn.execute(
+                "UPDATE idempotency_keys SET job_ids_json = ?1, expires_at = ?2 WHERE key = ?3",
+                params![job_ids_json, expires_at.timestamp_millis(), key],
+            )?;
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| Error::StorageError(format!("task join error: {}", e)))?
+    }
+
+    async fn release_idempotency_key(&self, key: &str) -> Result<()> {
+        let key = key.to_string();
+        let conn = self.connection.clone();
+
+        task::spawn_blocking(move || {
+            let conn = conn.lock().expect("database lock poisoned");
+
+            conn.execute("DELETE FROM idempotency_keys WHERE key = ?1", params![key])?;
+
+            Ok(())
+        })
+        .await
+        .map_err(|e| Error::StorageError(format!("task join error: {}", e)))?
+    }
 }
 
 #[cfg(test)]
@@ -575,4 +715,54 @@ mod tests {
         let jobs = storage.list_jobs(filter).await.unwrap();
         assert_eq!(jobs.len(), 3); // 0, 2, 4
     }
+
+    #[tokio::test]
+    async fn test_sqlite_idempotency_keys() {
+        let storage = SqliteStorage::new(":memory:").unwrap();
+        let claim = IdempotencyRecord {
+            fingerprint: u64::MAX,
+            job_ids: None,
+            expires_at: Utc::now() + chrono::Duration::minutes(10),
+        };
+
+        assert_eq!(
+            storage.claim_idempotency_key("k", &claim).await.unwrap(),
+            None
+        );
+        let pending = storage.claim_idempotency_key("k", &claim).await.unwrap();
+        assert_eq!(pending.unwrap().job_ids, None);
+
+        let job_ids = vec![JobId::new("a".to_string()), JobId::new("b".to_string())];
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        storage
+            .complete_idempotency_key("k", &job_ids, expires_at)
+            .await
+            .unwrap();
+        let record = storage
+            .claim_idempotency_key("k", &claim)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.fingerprint, u64::MAX);
+        assert_eq!(record.job_ids, Some(job_ids));
+        assert_eq!(
+            record.expires_at.timestamp_millis(),
+            expires_at.timestamp_millis()
+        );
+
+        // Released and expired keys can be claimed again
+        storage.release_idempotency_key("k").await.unwrap();
+        assert_eq!(
+            storage.claim_idempotency_key("k", &claim).await.unwrap(),
+            None
+        );
+        storage
+            .complete_idempotency_key("k", &[], Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.claim_idempotency_key("k", &claim).await.unwrap(),
+            None
+        );
+    }
 }
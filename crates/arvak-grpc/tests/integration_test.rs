@@ -14,7 +14,11 @@ cx q[0], q[1];
 
 /// Start a test server on a random port and return the address.
 async fn start_test_server() -> String {
-    let service = ArvakServiceImpl::new();
+    start_server(ArvakServiceImpl::new()).await
+}
+
+/// Start a server for the given service on a random port.
+async fn start_server(service: ArvakServiceImpl) -> String {
     let addr: std::net::SocketAddr = "127.0.0.1:0".parse().unwrap();
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
@@ -88,6 +92,7 @@ async fn test_submit_and_get_status() {
             }),
             backend_id: "simulator".to_string(),
             shots: 1000,
+            deadline: 0,
         }))
         .await
         .unwrap();
@@ -109,6 +114,36 @@ async fn test_submit_and_get_status() {
     assert_eq!(job.shots, 1000);
 }
 
+#[tokio::test]
+async fn test_submit_with_deadline() {
+    let addr = start_test_server().await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+    let submit = |deadline: i64| SubmitJobRequest {
+        circuit: Some(CircuitPayload {
+            format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+        }),
+        backend_id: "simulator".to_string(),
+        shots: 100,
+        deadline,
+    };
+
+    let passed = chrono::Utc::now().timestamp() - 60;
+    let status = client
+        .submit_job(Request::new(submit(passed)))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    let ahead = chrono::Utc::now().timestamp() + 3600;
+    let job_id = client
+        .submit_job(Request::new(submit(ahead)))
+        .await
+        .unwrap()
+        .into_inner()
+        .job_id;
+    assert!(!job_id.is_empty());
+}
+
 #[tokio::test]
 async fn test_full_job_lifecycle() {
     let addr = start_test_server().await;
@@ -122,6 +157,7 @@ async fn test_full_job_lifecycle() {
             }),
             backend_id: "simulator".to_string(),
             shots: 1000,
+            deadline: 0,
         }))
         .await
         .unwrap();
@@ -155,6 +191,7 @@ async fn test_full_job_lifecycle() {
     let response = client
         .get_job_result(Request::new(GetJobResultRequest {
             job_id: job_id.clone(),
+            format: PayloadFormat::Json as i32,
         }))
         .await
         .unwrap();
@@ -163,10 +200,79 @@ async fn test_full_job_lifecycle() {
     assert_eq!(result.job_id, job_id);
     assert_eq!(result.shots, 1000);
     assert!(!result.counts.is_empty());
+    assert!(result.metadata_cbor.is_empty());
 
     // Bell state should produce 00 and 11
     let total: u64 = result.counts.values().sum();
     assert_eq!(total, 1000);
+
+    // Same result with CBOR-encoded metadata
+    let response = client
+        .get_job_result(Request::new(GetJobResultRequest {
+            job_id: job_id.clone(),
+            format: PayloadFormat::Cbor as i32,
+        }))
+        .await
+        .unwrap();
+
+    let cbor_result = response.into_inner().result.unwrap();
+    assert_eq!(cbor_result.counts, result.counts);
+    assert!(cbor_result.metadata_json.is_empty());
+    let metadata: serde_json::Value =
+        ciborium::from_reader(cbor_result.metadata_cbor.as_slice()).unwrap();
+    assert_eq!(
+        metadata,
+        serde_json::from_str::<serde_json::Value>(&result.metadata_json).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_watch_job_partial_results() {
+    let addr = start_server(ArvakServiceImpl::new().with_partial_results(250)).await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+
+    let job_id = client
+        .submit_job(Request::new(SubmitJobRequest {
+            circuit: Some(CircuitPayload {
+                format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+            }),
+            backend_id: "simulator".to_string(),
+            shots: 1000,
+            deadline: 0,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .job_id;
+
+    let mut stream = client
+        .watch_job(Request::new(WatchJobRequest {
+            job_id: job_id.clone(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let mut last = None;
+    while let Some(update) = stream.message().await.unwrap() {
+        if let Some(partial) = &update.partial {
+            // Snapshots of a running job are never final.
+            let running = update.state == JobState::Running as i32;
+            assert_eq!(partial.is_final, !running);
+            assert_eq!(
+                partial.counts.values().sum::<u64>(),
+                partial.shots_completed as u64
+            );
+        }
+        last = Some(update);
+    }
+
+    let last = last.unwrap();
+    assert_eq!(last.state, JobState::Completed as i32);
+    let partial = last.partial.unwrap();
+    assert!(partial.is_final);
+    assert_eq!(partial.shots_completed, 1000);
+    assert_eq!(partial.counts.values().sum::<u64>(), 1000);
 }
 
 #[tokio::test]
@@ -243,6 +349,7 @@ async fn test_invalid_backend() {
             }),
             backend_id: "nonexistent".to_string(),
             shots: 1000,
+            deadline: 0,
         }))
         .await;
 
@@ -263,6 +370,7 @@ async fn test_invalid_circuit() {
             }),
             backend_id: "simulator".to_string(),
             shots: 1000,
+            deadline: 0,
         }))
         .await;
 
@@ -300,6 +408,7 @@ async fn test_cancel_job() {
             }),
             backend_id: "simulator".to_string(),
             shots: 1000,
+            deadline: 0,
         }))
         .await
         .unwrap();
@@ -318,3 +427,482 @@ async fn test_cancel_job() {
     // Either successfully canceled or already in terminal state
     assert!(cancel_result.success || cancel_result.message.contains("terminal state"));
 }
+
+#[tokio::test]
+async fn test_admin_rpcs() {
+    use arvak_config::Secret;
+    use arvak_grpc::server::{AuthInterceptor, JobStore};
+    use arvak_hal::job::JobStatus;
+
+    let store = JobStore::new();
+    let service = ArvakServiceImpl::with_components(
+        store.clone(),
+        arvak_grpc::server::backend_registry::create_default_registry(),
+    );
+    let auth = AuthInterceptor::new()
+        .with_api_keys(vec![Secret::new("user")])
+        .with_admin_keys(vec![Secret::new("ops")]);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        Server::builder()
+            .add_service(
+                arvak_grpc::proto::arvak_service_server::ArvakServiceServer::with_interceptor(
+                    service, auth,
+                ),
+            )
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let mut client = ArvakServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+
+    fn with_key<T>(message: T, key: &str) -> Request<T> {
+        let mut request = Request::new(message);
+        request
+            .metadata_mut()
+            .insert("x-api-key", key.parse().unwrap());
+        request
+    }
+
+    // Jobs created directly in the store stay queued.
+    let circuit = arvak_qasm3::parse(TEST_QASM).unwrap();
+    let stuck = store
+        .create_job(circuit.clone(), "simulator".to_string(), 100)
+        .await
+        .unwrap();
+    let queued = store
+        .create_job(circuit, "simulator".to_string(), 100)
+        .await
+        .unwrap();
+
+    let status = client
+        .force_fail_job(with_key(
+            ForceFailJobRequest {
+                job_id: stuck.0.clone(),
+                reason: "node lost".to_string(),
+            },
+            "user",
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+    let response = client
+        .force_fail_job(with_key(
+            ForceFailJobRequest {
+                job_id: stuck.0.clone(),
+                reason: "node lost".to_string(),
+            },
+            "ops",
+        ))
+        .await
+        .unwrap();
+    assert!(response.into_inner().success);
+    assert_eq!(
+        store.get_job(&stuck).await.unwrap().status,
+        JobStatus::Failed("node lost".to_string())
+    );
+
+    // A requeued job runs again.
+    let response = client
+        .requeue_job(with_key(
+            RequeueJobRequest {
+                job_id: stuck.0.clone(),
+                reason: "node back".to_string(),
+            },
+            "ops",
+        ))
+        .await
+        .unwrap();
+    assert!(response.into_inner().success);
+    for _ in 0..100 {
+        if store.get_job(&stuck).await.unwrap().status == JobStatus::Completed {
+            break;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(
+        store.get_job(&stuck).await.unwrap().status,
+        JobStatus::Completed
+    );
+
+    let response = client
+        .purge_queue(with_key(
+            PurgeQueueRequest {
+                backend_id: "simulator".to_string(),
+                reason: "maintenance".to_string(),
+            },
+            "ops",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.into_inner().job_ids, vec![queued.0.clone()]);
+    assert_eq!(
+        store.get_job(&queued).await.unwrap().status,
+        JobStatus::Cancelled
+    );
+
+    let response = client
+        .rebuild_indices(with_key(RebuildIndicesRequest {}, "ops"))
+        .await
+        .unwrap();
+    assert_eq!(response.into_inner().jobs, 2);
+}
+
+#[tokio::test]
+async fn test_get_workflow_progress() {
+    use arvak_sched::{CircuitSpec, ScheduledJob, SqliteStore, StateStore, WorkflowBuilder};
+    use std::sync::Arc;
+
+    let addr = start_test_server().await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+    let status = client
+        .get_workflow_progress(Request::new(GetWorkflowProgressRequest {
+            workflow_id: uuid::Uuid::new_v4().to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::Unimplemented);
+
+    let store = Arc::new(SqliteStore::in_memory().unwrap());
+    let circuit = CircuitSpec::from_qasm(TEST_QASM);
+    let workflow = WorkflowBuilder::new("pipeline")
+        .add_job(ScheduledJob::new("prepare", circuit.clone()))
+        .then(ScheduledJob::new("measure", circuit))
+        .unwrap()
+        .build();
+    store.save_workflow(&workflow).await.unwrap();
+
+    let addr = start_server(ArvakServiceImpl::new().with_workflow_store(store)).await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+    let progress = client
+        .get_workflow_progress(Request::new(GetWorkflowProgressRequest {
+            workflow_id: workflow.id.to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(progress.name, "pipeline");
+    assert_eq!(progress.status, "Pending");
+    assert_eq!((progress.total, progress.pending), (2, 2));
+    assert!(progress.remaining_seconds > 0.0);
+    assert!(progress.eta > 0);
+
+    let status = client
+        .get_workflow_progress(Request::new(GetWorkflowProgressRequest {
+            workflow_id: uuid::Uuid::new_v4().to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_job_templates() {
+    use arvak_sched::SqliteStore;
+    use std::sync::Arc;
+
+    let store = Arc::new(SqliteStore::in_memory().unwrap());
+    let addr = start_server(ArvakServiceImpl::new().with_workflow_store(store)).await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+
+    client
+        .save_template(Request::new(SaveTemplateRequest {
+            template: Some(TemplateInfo {
+                name: "bell".to_string(),
+                circuit: Some(CircuitPayload {
+                    format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+                }),
+                shots: 100,
+                labels: [("project".to_string(), "demo".to_string())].into(),
+                ..Default::default()
+            }),
+        }))
+        .await
+        .unwrap();
+
+    let templates = client
+        .list_templates(Request::new(ListTemplatesRequest {}))
+        .await
+        .unwrap()
+        .into_inner()
+        .templates;
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].labels["project"], "demo");
+
+    let job_id = client
+        .submit_template(Request::new(SubmitTemplateRequest {
+            name: "bell".to_string(),
+            backend_id: "simulator".to_string(),
+            shots: 0,
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .job_id;
+    let job = client
+        .get_job_status(Request::new(GetJobStatusRequest { job_id }))
+        .await
+        .unwrap()
+        .into_inner()
+        .job
+        .unwrap();
+    assert_eq!(job.shots, 100);
+
+    let deleted = client
+        .delete_template(Request::new(DeleteTemplateRequest {
+            name: "bell".to_string(),
+        }))
+        .await
+        .unwrap()
+        .into_inner()
+        .deleted;
+    assert!(deleted);
+    let status = client
+        .get_template(Request::new(GetTemplateRequest {
+            name: "bell".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_compile() {
+    let addr = start_test_server().await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+    let request = |backend_id: &str, preset: &str| CompileRequest {
+        circuit: Some(CircuitPayload {
+            format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+        }),
+        backend_id: backend_id.to_string(),
+        preset: preset.to_string(),
+        optimization_level: 1,
+        seed: Some(7),
+    };
+
+    let response = client
+        .compile(Request::new(request("simulator", "simulator-fast")))
+        .await
+        .unwrap()
+        .into_inner();
+    let Some(circuit_payload::Format::Qasm3(qasm)) = response.circuit.unwrap().format else {
+        panic!("expected QASM output");
+    };
+    assert_eq!(arvak_qasm3::parse(&qasm).unwrap().num_qubits(), 2);
+    assert_eq!(response.num_ops, 2);
+
+    // Without a preset the optimization level applies.
+    client.compile(Request::new(request("", ""))).await.unwrap();
+
+    let status = client
+        .compile(Request::new(request("simulator", "fastest")))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert!(status.message().contains("superconducting-heavyhex"));
+
+    let status = client
+        .compile(Request::new(request("nonexistent", "simulator-fast")))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_typed_client() {
+    use arvak_compile::PipelinePreset;
+    use arvak_grpc::client::{ArvakClient, ClientConfig, ClientError, CompileOptions};
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    let addr = start_test_server().await;
+    let config = ClientConfig::new(addr)
+        .with_pool_size(3)
+        .with_poll_interval(Duration::from_millis(50));
+    let client = ArvakClient::connect(config).await.unwrap();
+    assert_eq!(client.pool_size(), 3);
+
+    let backends = client.list_backends().await.unwrap();
+    assert!(backends.iter().any(|b| b.backend_id == "simulator"));
+    assert!(client.backend("simulator").await.unwrap().is_available);
+
+    let bell = arvak_qasm3::parse(TEST_QASM).unwrap();
+    let job_id = client.submit(&bell, "simulator", 200).await.unwrap();
+    let result = client.wait(&job_id, Duration::from_secs(10)).await.unwrap();
+    assert_eq!(result.shots, 200);
+    assert_eq!(result.counts.total_shots(), 200);
+
+    // A finished job's watch stream ends with its final state.
+    let updates: Vec<_> = client.watch(&job_id).await.unwrap().collect().await;
+    let last = updates.last().unwrap().as_ref().unwrap();
+    assert_eq!(last.state, JobState::Completed as i32);
+
+    let compiled = client
+        .compile(
+            &bell,
+            &CompileOptions::preset(PipelinePreset::SimulatorFast).with_backend("simulator"),
+        )
+        .await
+        .unwrap();
+    assert_eq!(compiled.circuit.num_qubits(), 2);
+    assert_eq!(compiled.num_ops, 2);
+
+    // Permanent failures surface as the server's status.
+    match client.status("no-such-job").await.unwrap_err() {
+        ClientError::Status(status) => assert_eq!(status.code(), tonic::Code::NotFound),
+        other => panic!("unexpected error {:?}", other),
+    }
+
+    // Deadline helpers share the connection pool.
+    let quick = client.with_timeout(Some(Duration::from_millis(500)));
+    assert_eq!(quick.timeout(), Some(Duration::from_millis(500)));
+    assert_eq!(quick.pool_size(), 3);
+    quick.status(&job_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_idempotent_submission() {
+    use arvak_grpc::server::interceptors::IDEMPOTENCY_KEY_HEADER;
+
+    let addr = start_test_server().await;
+    let mut client = ArvakServiceClient::connect(addr).await.unwrap();
+
+    let submit = |shots: u32, key: &str| {
+        let mut request = Request::new(SubmitJobRequest {
+            circuit: Some(CircuitPayload {
+                format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+            }),
+            backend_id: "simulator".to_string(),
+            shots,
+            deadline: 0,
+        });
+        request
+            .metadata_mut()
+            .insert(IDEMPOTENCY_KEY_HEADER, key.parse().unwrap());
+        request
+    };
+
+    let first = client.submit_job(submit(100, "retry-1")).await.unwrap();
+    let replay = client.submit_job(submit(100, "retry-1")).await.unwrap();
+    assert_eq!(first.into_inner().job_id, replay.get_ref().job_id);
+
+    // Another key is another job.
+    let other = client.submit_job(submit(100, "retry-2")).await.unwrap();
+    assert_ne!(other.into_inner().job_id, replay.into_inner().job_id);
+
+    // The key may not be reused for a different request.
+    let status = client.submit_job(submit(200, "retry-1")).await.unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+    // Batches are deduplicated as a whole.
+    let batch = || {
+        let mut request = Request::new(SubmitBatchRequest {
+            backend_id: "simulator".to_string(),
+            jobs: vec![
+                BatchJobRequest {
+                    circuit: Some(CircuitPayload {
+                        format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+                    }),
+                    shots: 100,
+                };
+                2
+            ],
+        });
+        request
+            .metadata_mut()
+            .insert(IDEMPOTENCY_KEY_HEADER, "batch-1".parse().unwrap());
+        request
+    };
+    let first = client.submit_batch(batch()).await.unwrap().into_inner();
+    let replay = client.submit_batch(batch()).await.unwrap().into_inner();
+    assert_eq!(first.job_ids.len(), 2);
+    assert_eq!(first.job_ids, replay.job_ids);
+}
+
+#[tokio::test]
+async fn test_audit_records() {
+    use arvak_grpc::server::AuditInterceptor;
+    use arvak_sched::InMemoryAuditLog;
+    use std::sync::Arc;
+
+    let log = Arc::new(InMemoryAuditLog::new());
+    let audit = AuditInterceptor::new(log.clone())
+        .with_method_sample_rate("ListBackends", 0.0)
+        .with_max_payload_bytes(32);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        Server::builder()
+            .layer(audit)
+            .add_service(
+                arvak_grpc::proto::arvak_service_server::ArvakServiceServer::new(
+                    ArvakServiceImpl::new(),
+                ),
+            )
+            .serve_with_incoming(tokio_stream::wrappers::TcpListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let mut client = ArvakServiceClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+
+    // Sampled out.
+    client
+        .list_backends(Request::new(ListBackendsRequest {}))
+        .await
+        .unwrap();
+
+    let mut request = Request::new(SubmitJobRequest {
+        circuit: Some(CircuitPayload {
+            format: Some(circuit_payload::Format::Qasm3(TEST_QASM.to_string())),
+        }),
+        backend_id: "simulator".to_string(),
+        shots: 100,
+        deadline: 0,
+    });
+    request
+        .metadata_mut()
+        .insert("x-api-key", "secret-key".parse().unwrap());
+    client.submit_job(request).await.unwrap();
+
+    // Failures are always recorded.
+    client
+        .get_job_status(Request::new(GetJobStatusRequest {
+            job_id: "missing".to_string(),
+        }))
+        .await
+        .unwrap_err();
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let events = log.events();
+    let actions: Vec<_> = events.iter().map(|e| e.action.as_str()).collect();
+    assert_eq!(actions, vec!["SubmitJob", "GetJobStatus"]);
+
+    let submit = &events[0];
+    assert!(submit.principal.starts_with("key:"));
+    assert!(!submit.principal.contains("secret"));
+    assert_eq!(submit.attributes["status"], "Ok");
+    assert_eq!(
+        submit.attributes["method"],
+        "/arvak.v1.ArvakService/SubmitJob"
+    );
+    assert!(submit.attributes["payload"].starts_with("[redacted: "));
+    assert_eq!(submit.attributes["request_digest"].len(), 16);
+    assert!(submit.attributes.contains_key("latency_ms"));
+    assert!(submit.attributes.contains_key("request_id"));
+
+    let status = &events[1];
+    assert_eq!(status.principal, "anonymous");
+    assert_eq!(status.attributes["status"], "NotFound");
+    assert!(!status.attributes["payload"].contains("redacted"));
+    assert!(status.attributes["payload"].contains("missing"));
+}
@@ -8,7 +8,7 @@ use arvak_ir::Circuit;
 use crate::capability::Capabilities;
 use crate::error::HalResult;
 use crate::job::{JobId, JobStatus};
-use crate::result::ExecutionResult;
+use crate::result::{Counts, ExecutionResult, PartialResult};
 
 /// Configuration for a backend.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,6 +112,45 @@ pub trait Backend: Send + Sync {
 
         Err(HalError::Timeout(job_id.0.clone()))
     }
+
+    /// Run a circuit in chunks of at most `chunk_shots` shots.
+    ///
+    /// After every chunk but the last, `on_partial` receives the counts
+    /// accumulated so far. The returned result covers all `shots`.
+    async fn run_chunked(
+        &self,
+        circuit: &Circuit,
+        shots: u32,
+        chunk_shots: u32,
+        on_partial: &mut (dyn FnMut(PartialResult) + Send),
+    ) -> HalResult<ExecutionResult> {
+        let chunk_shots = chunk_shots.max(1);
+        let mut counts = Counts::new();
+        let mut completed = 0;
+        let mut execution_time_ms = None;
+        let mut metadata = serde_json::Value::Null;
+
+        while completed < shots {
+            let chunk = chunk_shots.min(shots - completed);
+            let job_id = self.submit(circuit, chunk).await?;
+            let result = self.wait(&job_id).await?;
+
+            counts.merge(&result.counts);
+            completed += chunk;
+            if let Some(ms) = result.execution_time_ms {
+                execution_time_ms = Some(execution_time_ms.unwrap_or(0) + ms);
+            }
+            metadata = result.metadata;
+
+            if completed < shots {
+                on_partial(PartialResult::new(counts.clone(), completed, shots));
+            }
+        }
+
+        let mut result = ExecutionResult::new(counts, shots).with_metadata(metadata);
+        result.execution_time_ms = execution_time_ms;
+        Ok(result)
+    }
 }
 
 /// Trait for creating backends from configuration.
@@ -1,5 +1,6 @@
 //! Backend capabilities.
 
+use arvak_ir::CouplingMap;
 use serde::{Deserialize, Serialize};
 
 /// Capabilities of a quantum backend.
@@ -73,6 +74,18 @@ impl Capabilities {
             features: vec!["shuttling".into(), "zoned".into()],
         }
     }
+
+    /// Build the coupling map of this backend for layout and routing.
+    pub fn coupling_map(&self) -> CouplingMap {
+        self.topology.coupling_map(self.num_qubits)
+    }
+
+    /// Check whether the backend advertises a feature (case-insensitive).
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features
+            .iter()
+            .any(|f| f.eq_ignore_ascii_case(feature))
+    }
 }
 
 /// Gate set supported by a backend.
@@ -134,6 +147,8 @@ impl GateSet {
                 "ch".into(),
                 "swap".into(),
                 "iswap".into(),
+                "ecr".into(),
+                "csx".into(),
                 "crx".into(),
                 "cry".into(),
                 "crz".into(),
@@ -141,6 +156,7 @@ impl GateSet {
                 "rxx".into(),
                 "ryy".into(),
                 "rzz".into(),
+                "rzx".into(),
             ],
             native: vec![],
         }
@@ -270,6 +286,11 @@ impl Topology {
             .iter()
             .any(|&(a, b)| (a == q1 && b == q2) || (a == q2 && b == q1))
     }
+
+    /// Convert to a [`CouplingMap`] over `num_qubits` physical qubits.
+    pub fn coupling_map(&self, num_qubits: u32) -> CouplingMap {
+        CouplingMap::from_edges(num_qubits, self.edges.iter().copied())
+    }
 }
 
 /// Kind of qubit topology.
@@ -365,6 +386,15 @@ mod tests {
         assert!(!topo.is_connected(0, 5));
     }
 
+    #[test]
+    fn test_capabilities_coupling_map() {
+        let caps = Capabilities::neutral_atom("planqc-atom1", 6, 2);
+        let map = caps.coupling_map();
+        assert_eq!(map.num_qubits(), 6);
+        assert!(map.is_connected(4, 5));
+        assert_eq!(map.connected_components().len(), 2);
+    }
+
     #[test]
     fn test_capabilities_neutral_atom() {
         let caps = Capabilities::neutral_atom("planqc-atom1", 100, 4);
@@ -374,5 +404,7 @@ mod tests {
         assert!(caps.gate_set.contains("rz"));
         assert!(!caps.gate_set.contains("cx"));
         assert!(caps.features.contains(&"shuttling".to_string()));
+        assert!(caps.has_feature("Shuttling"));
+        assert!(!caps.has_feature("dynamic_circuits"));
     }
 }
@@ -10,6 +10,7 @@
 //! - [`Capabilities`] to describe hardware features and constraints
 //! - Authentication support for various providers (API tokens, OIDC)
 //! - Unified result handling via [`ExecutionResult`] and [`Counts`]
+//! - A [`NoiseModel`] format shared by noisy simulation and error mitigation
 //!
 //! # Supported Backends
 //!
@@ -103,6 +104,7 @@ pub mod backend;
 pub mod capability;
 pub mod error;
 pub mod job;
+pub mod noise;
 pub mod plugin;
 pub mod registry;
 pub mod result;
@@ -112,6 +114,10 @@ pub use backend::{Backend, BackendConfig, BackendFactory};
 pub use capability::{Capabilities, GateSet, Topology, TopologyKind};
 pub use error::{HalError, HalResult};
 pub use job::{Job, JobId, JobStatus};
+pub use noise::{
+    GateNoise, IdleNoise, NOISE_MODEL_KEY, NoiseChannel, NoiseModel, ReadoutError,
+    pauli_decomposition,
+};
 pub use plugin::{BackendPlugin, PluginInfo};
 pub use registry::BackendRegistry;
-pub use result::{Counts, ExecutionResult};
+pub use result::{Counts, ExecutionResult, PartialResult};
@@ -0,0 +1,516 @@
+//! Noise model descriptions.
+//!
+//! A [`NoiseModel`] describes the noise of a device in one serializable
+//! format: a channel applied after each gate, the readout confusion of each
+//! qubit and the decoherence of idling qubits. The scheduler builds one from
+//! a backend's calibration, the simulator runs circuits under it, and error
+//! mitigation derives its corrections from it, so that all three agree on
+//! what the device does. Results name the model they were produced or
+//! mitigated with under [`NOISE_MODEL_KEY`] in their metadata.
+//!
+//! ```json
+//! {
+//!   "source": "calibration:garnet@3",
+//!   "gates": [
+//!     {"gate": "cz", "qubits": [0, 1], "channel": {"type": "depolarizing", "probability": 0.01}},
+//!     {"gate": "prx", "channel": {"type": "pauli", "probabilities": {"X": 0.001, "Z": 0.002}}}
+//!   ],
+//!   "readout": [{"qubit": 0, "flip_zero": 0.02, "flip_one": 0.05}],
+//!   "idle": [{"qubit": 0, "t1_us": 48.2, "t2_us": 31.0}]
+//! }
+//! ```
+//!
+//! Pauli operators on `n` qubits are indexed `0..4^n` in base 4, with `I`,
+//! `X`, `Y`, `Z` as digits 0 to 3 and the gate's first operand as the most
+//! significant digit; Pauli strings name the first operand first.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{HalError, HalResult};
+
+/// Key of the noise model's source in a result's metadata.
+pub const NOISE_MODEL_KEY: &str = "noise_model";
+
+/// A noise channel acting on the qubits of one operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NoiseChannel {
+    /// A uniformly random non-identity Pauli error with total probability
+    /// `probability`.
+    Depolarizing { probability: f64 },
+
+    /// Pauli errors with the given probabilities, by Pauli string; the
+    /// identity takes the remaining probability.
+    Pauli {
+        probabilities: BTreeMap<String, f64>,
+    },
+
+    /// Energy relaxation and dephasing of each qubit over `duration_ns`.
+    ThermalRelaxation {
+        t1_us: f64,
+        t2_us: f64,
+        duration_ns: f64,
+    },
+}
+
+impl NoiseChannel {
+    /// The depolarizing channel with the given average gate error on
+    /// `num_qubits` qubits, as reported by randomized benchmarking.
+    pub fn depolarizing_from_error(error: f64, num_qubits: usize) -> Self {
+        let dim = (1u64 << num_qubits) as f64;
+        NoiseChannel::Depolarizing {
+            probability: (error * (dim + 1.0) / dim).clamp(0.0, 1.0),
+        }
+    }
+
+    /// Check that the channel's parameters are physical.
+    pub fn validate(&self) -> HalResult<()> {
+        match self {
+            NoiseChannel::Depolarizing { probability } => probability_in_range(*probability),
+            NoiseChannel::Pauli { probabilities } => {
+                for (pauli, probability) in probabilities {
+                    if pauli.is_empty() || !pauli.chars().all(|c| "IXYZ".contains(c)) {
+                        return Err(HalError::Configuration(format!(
+                            "invalid Pauli string '{}'",
+                            pauli
+                        )));
+                    }
+                    probability_in_range(*probability)?;
+                }
+                probability_in_range(probabilities.values().sum())
+            }
+            NoiseChannel::ThermalRelaxation {
+                t1_us,
+                t2_us,
+                duration_ns,
+            } => {
+                if *t1_us <= 0.0 || *t2_us <= 0.0 || *duration_ns < 0.0 {
+                    return Err(HalError::Configuration(
+                        "T1 and T2 must be positive and durations non-negative".into(),
+                    ));
+                }
+                if *t2_us > 2.0 * t1_us {
+                    return Err(HalError::Configuration(format!(
+                        "T2 ({} us) exceeds 2 T1 ({} us)",
+                        t2_us,
+                        2.0 * t1_us
+                    )));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Pauli fidelities of the channel on `num_qubits` qubits: the factor
+    /// each Pauli operator is scaled by, indexed as described in the
+    /// [module documentation](self).
+    ///
+    /// Thermal relaxation is Pauli-twirled: its transverse components decay
+    /// with T2 and its longitudinal ones with T1.
+    pub fn pauli_fidelities(&self, num_qubits: usize) -> HalResult<Vec<f64>> {
+        let size = 1usize << (2 * num_qubits);
+        match self {
+            NoiseChannel::Depolarizing { probability } => {
+                let f = 1.0 - probability * size as f64 / (size - 1).max(1) as f64;
+                Ok((0..size).map(|q| if q == 0 { 1.0 } else { f }).collect())
+            }
+            NoiseChannel::Pauli { probabilities } => {
+                let mut p = vec![0.0; size];
+                for (pauli, probability) in probabilities {
+                    p[pauli_index(pauli, num_qubits)?] += probability;
+                }
+                p[0] += 1.0 - p.iter().sum::<f64>();
+                Ok((0..size)
+                    .map(|q| {
+                        (0..size)
+                            .map(|e| if commutes(e, q) { p[e] } else { -p[e] })
+                            .sum()
+                    })
+                    .collect())
+            }
+            NoiseChannel::ThermalRelaxation {
+                t1_us,
+                t2_us,
+                duration_ns,
+            } => {
+                let t_us = duration_ns / 1000.0;
+                let transverse = (-t_us / t2_us).exp();
+                let single = [1.0, transverse, transverse, (-t_us / t1_us).exp()];
+                Ok((0..size)
+                    .map(|q| {
+                        (0..num_qubits)
+                            .map(|i| single[(q >> (2 * i)) & 3])
+                            .product()
+                    })
+                    .collect())
+            }
+        }
+    }
+
+    /// Probabilities of each Pauli error of the channel on `num_qubits`
+    /// qubits, indexed as [`pauli_fidelities`](Self::pauli_fidelities).
+    pub fn pauli_probabilities(&self, num_qubits: usize) -> HalResult<Vec<f64>> {
+        Ok(pauli_decomposition(&self.pauli_fidelities(num_qubits)?))
+    }
+}
+
+/// Coefficients of the Pauli channel with the given Pauli fidelities.
+///
+/// Applied to the inverse fidelities, this gives the quasi-probabilities of
+/// the inverse channel, as used by probabilistic error cancellation.
+pub fn pauli_decomposition(fidelities: &[f64]) -> Vec<f64> {
+    let size = fidelities.len();
+    (0..size)
+        .map(|e| {
+            (0..size)
+                .map(|q| {
+                    if commutes(e, q) {
+                        fidelities[q]
+                    } else {
+                        -fidelities[q]
+                    }
+                })
+                .sum::<f64>()
+                / size as f64
+        })
+        .collect()
+}
+
+/// Whether the Paulis with indices `a` and `b` commute.
+fn commutes(a: usize, b: usize) -> bool {
+    let mut anticommuting = 0;
+    let (mut a, mut b) = (a, b);
+    while a != 0 && b != 0 {
+        let (x, y) = (a & 3, b & 3);
+        if x != 0 && y != 0 && x != y {
+            anticommuting += 1;
+        }
+        a >>= 2;
+        b >>= 2;
+    }
+    anticommuting % 2 == 0
+}
+
+/// Index of a Pauli string on `num_qubits` qubits.
+fn pauli_index(pauli: &str, num_qubits: usize) -> HalResult<usize> {
+    if pauli.len() != num_qubits {
+        return Err(HalError::Configuration(format!(
+            "Pauli string '{}' does not act on {} qubit(s)",
+            pauli, num_qubits
+        )));
+    }
+    pauli.chars().try_fold(0, |index, c| {
+        let digit = match c {
+            'I' => 0,
+            'X' => 1,
+            'Y' => 2,
+            'Z' => 3,
+            _ => {
+                return Err(HalError::Configuration(format!(
+                    "invalid Pauli string '{}'",
+                    pauli
+                )));
+            }
+        };
+        Ok(index * 4 + digit)
+    })
+}
+
+fn probability_in_range(probability: f64) -> HalResult<()> {
+    if (0.0..=1.0).contains(&probability) {
+        Ok(())
+    } else {
+        Err(HalError::Configuration(format!(
+            "probability {} is not in [0, 1]",
+            probability
+        )))
+    }
+}
+
+/// Noise following a gate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateNoise {
+    /// Gate name, lowercase.
+    pub gate: String,
+
+    /// Qubits the gate acts on, in operand order; empty for the gate on
+    /// any qubits.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub qubits: Vec<u32>,
+
+    /// Channel applied after the gate.
+    pub channel: NoiseChannel,
+}
+
+/// Readout confusion of a qubit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadoutError {
+    /// Qubit index.
+    pub qubit: u32,
+
+    /// Probability of reading 1 from the qubit in 0.
+    pub flip_zero: f64,
+
+    /// Probability of reading 0 from the qubit in 1.
+    pub flip_one: f64,
+}
+
+/// Decoherence of an idling qubit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdleNoise {
+    /// Qubit index.
+    pub qubit: u32,
+
+    /// Energy relaxation time in microseconds.
+    pub t1_us: f64,
+
+    /// Dephasing time in microseconds.
+    pub t2_us: f64,
+}
+
+impl IdleNoise {
+    /// The channel acting on the qubit while it idles for `duration_ns`.
+    pub fn channel(&self, duration_ns: f64) -> NoiseChannel {
+        NoiseChannel::ThermalRelaxation {
+            t1_us: self.t1_us,
+            t2_us: self.t2_us,
+            duration_ns,
+        }
+    }
+}
+
+/// Noise of a device: gate channels, readout confusion and idle
+/// decoherence.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NoiseModel {
+    /// Where the model came from, e.g. the calibration snapshot it was
+    /// built from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Noise following gates. An entry for the gate on specific qubits
+    /// takes precedence over one for the gate on any qubits.
+    #[serde(default)]
+    pub gates: Vec<GateNoise>,
+
+    /// Readout confusion, by qubit.
+    #[serde(default)]
+    pub readout: Vec<ReadoutError>,
+
+    /// Decoherence of qubits idling in delays, by qubit.
+    #[serde(default)]
+    pub idle: Vec<IdleNoise>,
+}
+
+impl NoiseModel {
+    /// Create a model without noise.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record where the model came from.
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Apply `channel` after `gate` on `qubits`, or on any qubits if empty.
+    pub fn with_gate_noise(
+        mut self,
+        gate: impl Into<String>,
+        qubits: &[u32],
+        channel: NoiseChannel,
+    ) -> Self {
+        self.gates.push(GateNoise {
+            gate: gate.into(),
+            qubits: qubits.to_vec(),
+            channel,
+        });
+        self
+    }
+
+    /// Set the readout confusion of a qubit.
+    pub fn with_readout_error(mut self, qubit: u32, flip_zero: f64, flip_one: f64) -> Self {
+        self.readout.retain(|r| r.qubit != qubit);
+        self.readout.push(ReadoutError {
+            qubit,
+            flip_zero,
+            flip_one,
+        });
+        self
+    }
+
+    /// Set the T1 and T2 times of an idling qubit.
+    pub fn with_idle_noise(mut self, qubit: u32, t1_us: f64, t2_us: f64) -> Self {
+        self.idle.retain(|i| i.qubit != qubit);
+        self.idle.push(IdleNoise {
+            qubit,
+            t1_us,
+            t2_us,
+        });
+        self
+    }
+
+    /// Whether the model describes no noise at all.
+    pub fn is_empty(&self) -> bool {
+        self.gates.is_empty() && self.readout.is_empty() && self.idle.is_empty()
+    }
+
+    /// Name of the model for result metadata: its source, or `custom`.
+    pub fn label(&self) -> &str {
+        self.source.as_deref().unwrap_or("custom")
+    }
+
+    /// Channel following `gate` on `qubits`, if the gate is noisy.
+    pub fn gate_channel(&self, gate: &str, qubits: &[u32]) -> Option<&NoiseChannel> {
+        let mut any = None;
+        for noise in self.gates.iter().filter(|n| n.gate == gate) {
+            if noise.qubits == qubits {
+                return Some(&noise.channel);
+            }
+            if noise.qubits.is_empty() {
+                any = any.or(Some(&noise.channel));
+            }
+        }
+        any
+    }
+
+    /// Readout confusion of a qubit, if it has any.
+    pub fn readout_error(&self, qubit: u32) -> Option<&ReadoutError> {
+        self.readout.iter().find(|r| r.qubit == qubit)
+    }
+
+    /// Idle decoherence of a qubit, if it has any.
+    pub fn idle_noise(&self, qubit: u32) -> Option<&IdleNoise> {
+        self.idle.iter().find(|i| i.qubit == qubit)
+    }
+
+    /// Check that every channel is physical and fits the gate it follows.
+    pub fn validate(&self) -> HalResult<()> {
+        for noise in &self.gates {
+            noise.channel.validate()?;
+            if let NoiseChannel::Pauli { probabilities } = &noise.channel {
+                if let Some(len) = probabilities.keys().map(String::len).next() {
+                    if probabilities.keys().any(|p| p.len() != len)
+                        || (!noise.qubits.is_empty() && len != noise.qubits.len())
+                    {
+                        return Err(HalError::Configuration(format!(
+                            "Pauli strings of gate '{}' do not match its qubits",
+                            noise.gate
+                        )));
+                    }
+                }
+            }
+        }
+        for readout in &self.readout {
+            probability_in_range(readout.flip_zero)?;
+            probability_in_range(readout.flip_one)?;
+        }
+        for idle in &self.idle {
+            idle.channel(0.0).validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx_eq(a: &[f64], b: &[f64]) -> bool {
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12)
+    }
+
+    #[test]
+    fn test_noise_model_roundtrip_and_lookup() {
+        let model = NoiseModel::new()
+            .with_source("calibration:garnet@3")
+            .with_gate_noise("cz", &[], NoiseChannel::depolarizing_from_error(0.02, 2))
+            .with_gate_noise(
+                "cz",
+                &[0, 1],
+                NoiseChannel::Depolarizing { probability: 0.01 },
+            )
+            .with_readout_error(0, 0.02, 0.05)
+            .with_idle_noise(0, 50.0, 30.0);
+        model.validate().unwrap();
+
+        let json = serde_json::to_string(&model).unwrap();
+        let parsed: NoiseModel = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, model);
+
+        assert_eq!(
+            model.gate_channel("cz", &[0, 1]),
+            Some(&NoiseChannel::Depolarizing { probability: 0.01 })
+        );
+        assert_eq!(
+            model.gate_channel("cz", &[1, 2]),
+            Some(&NoiseChannel::Depolarizing { probability: 0.025 })
+        );
+        assert_eq!(model.gate_channel("prx", &[0]), None);
+        assert_eq!(model.readout_error(0).unwrap().flip_one, 0.05);
+        assert!(model.idle_noise(1).is_none());
+        assert_eq!(model.label(), "calibration:garnet@3");
+    }
+
+    #[test]
+    fn test_validate_rejects_unphysical_channels() {
+        let bad = [
+            NoiseChannel::Depolarizing { probability: 1.5 },
+            NoiseChannel::Pauli {
+                probabilities: [("XQ".to_string(), 0.1)].into(),
+            },
+            NoiseChannel::ThermalRelaxation {
+                t1_us: 10.0,
+                t2_us: 30.0,
+                duration_ns: 100.0,
+            },
+        ];
+        for channel in bad {
+            let model = NoiseModel::new().with_gate_noise("x", &[], channel);
+            assert!(model.validate().is_err());
+        }
+        let mismatched = NoiseModel::new().with_gate_noise(
+            "cz",
+            &[0, 1],
+            NoiseChannel::Pauli {
+                probabilities: [("X".to_string(), 0.1)].into(),
+            },
+        );
+        assert!(mismatched.validate().is_err());
+    }
+
+    #[test]
+    fn test_pauli_decomposition() {
+        let channel = NoiseChannel::Pauli {
+            probabilities: [("X".to_string(), 0.1), ("Z".to_string(), 0.05)].into(),
+        };
+        let probabilities = channel.pauli_probabilities(1).unwrap();
+        assert!(approx_eq(&probabilities, &[0.85, 0.1, 0.0, 0.05]));
+
+        // Depolarizing errors are spread evenly over the non-identity Paulis
+        let depolarizing = NoiseChannel::Depolarizing { probability: 0.15 };
+        let probabilities = depolarizing.pauli_probabilities(2).unwrap();
+        assert!((probabilities[0] - 0.85).abs() < 1e-12);
+        assert!(probabilities[1..].iter().all(|p| (p - 0.01).abs() < 1e-12));
+
+        // Twirled relaxation: X and Y flips from T1, extra Z flips from T2
+        let relaxation = NoiseChannel::ThermalRelaxation {
+            t1_us: 50.0,
+            t2_us: 50.0,
+            duration_ns: 1000.0,
+        };
+        let fidelities = relaxation.pauli_fidelities(1).unwrap();
+        let decay = (-0.02f64).exp();
+        assert!(approx_eq(&fidelities, &[1.0, decay, decay, decay]));
+        let probabilities = relaxation.pauli_probabilities(1).unwrap();
+        assert!(approx_eq(&probabilities[1..], &[(1.0 - decay) / 4.0; 3]));
+
+        // Inverting the fidelities inverts the channel
+        let inverse = pauli_decomposition(&fidelities.iter().map(|f| 1.0 / f).collect::<Vec<_>>());
+        assert!((inverse.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(inverse[1..].iter().all(|q| *q < 0.0));
+    }
+}
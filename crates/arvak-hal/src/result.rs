@@ -29,6 +29,13 @@ impl Counts {
         *self.counts.entry(key).or_default() += count;
     }
 
+    /// Add every count from `other` to these counts.
+    pub fn merge(&mut self, other: &Counts) {
+        for (bitstring, count) in other.iter() {
+            self.insert(bitstring.clone(), *count);
+        }
+    }
+
     /// Get the count for a bitstring.
     pub fn get(&self, bitstring: &str) -> u64 {
         self.counts.get(bitstring).copied().unwrap_or(0)
@@ -77,6 +84,90 @@ impl Counts {
         items.sort_by(|a, b| b.1.cmp(a.1));
         items
     }
+
+    /// Counts over a subset of qubits, summing over the others.
+    ///
+    /// Bitstrings follow the little-endian convention: qubit 0 is the
+    /// rightmost bit, and register separators (spaces) are ignored. In the
+    /// marginal bitstrings, `qubits[0]` becomes the rightmost bit. Qubits
+    /// beyond a bitstring's length read as 0.
+    ///
+    /// ```
+    /// use arvak_hal::Counts;
+    ///
+    /// let counts = Counts::from_pairs([("110", 30), ("011", 70)]);
+    /// let marginal = counts.marginal(&[0, 2]);
+    /// assert_eq!(marginal.get("10"), 30);
+    /// assert_eq!(marginal.get("01"), 70);
+    /// ```
+    pub fn marginal(&self, qubits: &[usize]) -> Counts {
+        let mut marginal = Counts::new();
+        for (bitstring, &count) in &self.counts {
+            let bits: Vec<u8> = bitstring.bytes().filter(|b| *b != b' ').collect();
+            let key: String = qubits
+                .iter()
+                .rev()
+                .map(|&q| {
+                    if q < bits.len() && bits[bits.len() - 1 - q] == b'1' {
+                        '1'
+                    } else {
+                        '0'
+                    }
+                })
+                .collect();
+            marginal.insert(key, count);
+        }
+        marginal
+    }
+
+    /// Expectation value of the Z-parity operator over `qubits`, following
+    /// the same bit convention as [`Counts::marginal`].
+    pub fn parity_expectation(&self, qubits: &[usize]) -> f64 {
+        let total = self.total_shots();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let signed: i64 = self
+            .counts
+            .iter()
+            .map(|(bitstring, &count)| {
+                let bits: Vec<u8> = bitstring.bytes().filter(|b| *b != b' ').collect();
+                let ones = qubits
+                    .iter()
+                    .filter(|&&q| q < bits.len() && bits[bits.len() - 1 - q] == b'1')
+                    .count();
+                let count = count as i64;
+                if ones % 2 == 0 { count } else { -count }
+            })
+            .sum();
+        signed as f64 / total as f64
+    }
+
+    /// Total variation distance to another count distribution.
+    pub fn total_variation_distance(&self, other: &Counts) -> f64 {
+        let (p, q) = (self.probabilities(), other.probabilities());
+        let mut sum: f64 = p
+            .iter()
+            .map(|(k, pk)| (pk - q.get(k).copied().unwrap_or(0.0)).abs())
+            .sum();
+        sum += q
+            .iter()
+            .filter(|(k, _)| !p.contains_key(*k))
+            .map(|(_, qk)| qk)
+            .sum::<f64>();
+        0.5 * sum
+    }
+
+    /// Hellinger distance to another count distribution.
+    pub fn hellinger_distance(&self, other: &Counts) -> f64 {
+        let (p, q) = (self.probabilities(), other.probabilities());
+        let overlap: f64 = p
+            .iter()
+            .map(|(k, pk)| (pk * q.get(k).copied().unwrap_or(0.0)).sqrt())
+            .sum();
+        (1.0 - overlap).max(0.0).sqrt()
+    }
 }
 
 impl FromIterator<(String, u64)> for Counts {
@@ -145,6 +236,53 @@ impl Default for ExecutionResult {
     }
 }
 
+/// Counts accumulated so far by a job that is still taking shots.
+///
+/// Partial results are snapshots, not results: only the snapshot built from
+/// a finished job's [`ExecutionResult`] has `is_final` set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PartialResult {
+    /// Counts over the shots completed so far.
+    pub counts: Counts,
+    /// Number of shots the counts cover.
+    pub shots_completed: u32,
+    /// Number of shots requested for the job.
+    pub shots_total: u32,
+    /// Whether these are the job's complete counts.
+    #[serde(default)]
+    pub is_final: bool,
+}
+
+impl PartialResult {
+    /// Create a non-final snapshot.
+    pub fn new(counts: Counts, shots_completed: u32, shots_total: u32) -> Self {
+        Self {
+            counts,
+            shots_completed,
+            shots_total,
+            is_final: false,
+        }
+    }
+
+    /// Create the final snapshot of a finished job.
+    pub fn from_result(result: &ExecutionResult) -> Self {
+        Self {
+            counts: result.counts.clone(),
+            shots_completed: result.shots,
+            shots_total: result.shots,
+            is_final: true,
+        }
+    }
+
+    /// Fraction of the requested shots completed, between 0 and 1.
+    pub fn progress(&self) -> f64 {
+        if self.shots_total == 0 {
+            return 1.0;
+        }
+        (f64::from(self.shots_completed) / f64::from(self.shots_total)).min(1.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,6 +322,24 @@ mod tests {
         assert_eq!(*count, 900);
     }
 
+    #[test]
+    fn test_counts_marginal_and_distances() {
+        let counts = Counts::from_pairs([("01 1", 60), ("10 0", 40)]);
+
+        let marginal = counts.marginal(&[2, 0]);
+        assert_eq!(marginal.get("10"), 60);
+        assert_eq!(marginal.get("01"), 40);
+        assert_eq!(counts.marginal(&[5]).get("0"), 100);
+
+        assert!((counts.parity_expectation(&[0]) - -0.2).abs() < 1e-10);
+        assert!((counts.parity_expectation(&[0, 1]) - 1.0).abs() < 1e-10);
+        assert_eq!(Counts::new().parity_expectation(&[0]), 0.0);
+
+        let other = Counts::from_pairs([("01 1", 100)]);
+        assert!((counts.total_variation_distance(&other) - 0.4).abs() < 1e-10);
+        assert!((counts.hellinger_distance(&counts)).abs() < 1e-7);
+    }
+
     #[test]
     fn test_execution_result() {
         let counts = Counts::from_pairs([("00".to_string(), 500), ("11".to_string(), 500)]);
@@ -196,4 +352,20 @@ mod tests {
         let (_most, prob) = result.most_frequent().unwrap();
         assert!((prob - 0.5).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_partial_result() {
+        let mut counts = Counts::from_pairs([("00", 40)]);
+        counts.merge(&Counts::from_pairs([("00", 10), ("11", 50)]));
+
+        let partial = PartialResult::new(counts.clone(), 100, 400);
+        assert!(!partial.is_final);
+        assert!((partial.progress() - 0.25).abs() < 1e-10);
+
+        let done = PartialResult::from_result(&ExecutionResult::new(counts, 100));
+        assert!(done.is_final);
+        assert_eq!(done.counts.get("00"), 50);
+        assert_eq!(done.counts.get("11"), 50);
+        assert!((done.progress() - 1.0).abs() < 1e-10);
+    }
 }
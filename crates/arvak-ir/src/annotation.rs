@@ -0,0 +1,308 @@
+//! Key/value annotations attached to instructions.
+//!
+//! Passes use annotations to record information about an instruction that
+//! the IR has no field for, such as a calibrated duration, a noise-scaling
+//! factor or the pass that created it. Well-known keys have typed accessors
+//! on [`Annotations`]; any other key can be read and written through the
+//! generic map interface. Annotations are preserved by serde and by the
+//! OpenQASM 3 emitter and parser, which write them as `@arvak.<key> <value>`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The value of an annotation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnnotationValue {
+    /// A boolean flag.
+    Bool(bool),
+    /// An integer.
+    Int(i64),
+    /// A floating-point number.
+    Float(f64),
+    /// A string.
+    String(String),
+}
+
+impl AnnotationValue {
+    /// Parse a value in the format written by its `Display` impl, which
+    /// is JSON for every finite value.
+    pub fn parse(text: &str) -> Option<Self> {
+        serde_json::from_str(text.trim()).ok()
+    }
+
+    /// The value as a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AnnotationValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The value as an integer.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            AnnotationValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The value as a float; integers are converted.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            AnnotationValue::Float(f) => Some(*f),
+            AnnotationValue::Int(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    /// The value as a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AnnotationValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AnnotationValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnnotationValue::Bool(b) => write!(f, "{}", b),
+            AnnotationValue::Int(i) => write!(f, "{}", i),
+            // Debug keeps the decimal point, so the value reads back as a float.
+            AnnotationValue::Float(x) => write!(f, "{:?}", x),
+            AnnotationValue::String(s) => {
+                f.write_str(&serde_json::to_string(s).map_err(|_| fmt::Error)?)
+            }
+        }
+    }
+}
+
+impl From<bool> for AnnotationValue {
+    fn from(b: bool) -> Self {
+        AnnotationValue::Bool(b)
+    }
+}
+
+impl From<i64> for AnnotationValue {
+    fn from(i: i64) -> Self {
+        AnnotationValue::Int(i)
+    }
+}
+
+impl From<f64> for AnnotationValue {
+    fn from(x: f64) -> Self {
+        AnnotationValue::Float(x)
+    }
+}
+
+impl From<String> for AnnotationValue {
+    fn from(s: String) -> Self {
+        AnnotationValue::String(s)
+    }
+}
+
+impl From<&str> for AnnotationValue {
+    fn from(s: &str) -> Self {
+        AnnotationValue::String(s.to_string())
+    }
+}
+
+/// Annotations of an instruction, ordered by key.
+///
+/// # Example
+///
+/// ```rust
+/// use arvak_ir::{Annotations, Instruction, QubitId, StandardGate};
+///
+/// let mut inst = Instruction::single_qubit_gate(StandardGate::X, QubitId(0))
+///     .with_annotation(Annotations::PROVENANCE, "folding");
+/// inst.annotations.set_noise_scale(3.0);
+///
+/// assert_eq!(inst.annotations.noise_scale(), Some(3.0));
+/// assert_eq!(inst.annotations.provenance(), Some("folding"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Annotations(BTreeMap<String, AnnotationValue>);
+
+impl Annotations {
+    /// Duration of the instruction in nanoseconds.
+    pub const DURATION_NS: &'static str = "duration_ns";
+    /// Factor by which the instruction's noise has been amplified, as in
+    /// zero-noise extrapolation.
+    pub const NOISE_SCALE: &'static str = "noise_scale";
+    /// Name of the pass or tool that produced the instruction.
+    pub const PROVENANCE: &'static str = "provenance";
+    /// Label of a barrier at which simulators capture a state snapshot.
+    pub const SNAPSHOT: &'static str = "snapshot";
+
+    /// Create an empty annotation map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether there are no annotations.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Number of annotations.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Get the value of `key`.
+    pub fn get(&self, key: &str) -> Option<&AnnotationValue> {
+        self.0.get(key)
+    }
+
+    /// Set `key`, returning its previous value.
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<AnnotationValue>,
+    ) -> Option<AnnotationValue> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Remove `key`, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<AnnotationValue> {
+        self.0.remove(key)
+    }
+
+    /// Iterate over the annotations in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &AnnotationValue)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// The [`DURATION_NS`](Self::DURATION_NS) annotation.
+    pub fn duration_ns(&self) -> Option<f64> {
+        self.get(Self::DURATION_NS)?.as_f64()
+    }
+
+    /// Set the [`DURATION_NS`](Self::DURATION_NS) annotation.
+    pub fn set_duration_ns(&mut self, duration_ns: f64) {
+        self.insert(Self::DURATION_NS, duration_ns);
+    }
+
+    /// The [`NOISE_SCALE`](Self::NOISE_SCALE) annotation.
+    pub fn noise_scale(&self) -> Option<f64> {
+        self.get(Self::NOISE_SCALE)?.as_f64()
+    }
+
+    /// Set the [`NOISE_SCALE`](Self::NOISE_SCALE) annotation.
+    pub fn set_noise_scale(&mut self, scale: f64) {
+        self.insert(Self::NOISE_SCALE, scale);
+    }
+
+    /// The [`PROVENANCE`](Self::PROVENANCE) annotation.
+    pub fn provenance(&self) -> Option<&str> {
+        self.get(Self::PROVENANCE)?.as_str()
+    }
+
+    /// Set the [`PROVENANCE`](Self::PROVENANCE) annotation.
+    pub fn set_provenance(&mut self, provenance: impl Into<String>) {
+        self.insert(Self::PROVENANCE, provenance.into());
+    }
+
+    /// The [`SNAPSHOT`](Self::SNAPSHOT) annotation.
+    pub fn snapshot(&self) -> Option<&str> {
+        self.get(Self::SNAPSHOT)?.as_str()
+    }
+
+    /// Set the [`SNAPSHOT`](Self::SNAPSHOT) annotation.
+    pub fn set_snapshot(&mut self, label: impl Into<String>) {
+        self.insert(Self::SNAPSHOT, label.into());
+    }
+}
+
+impl<K: Into<String>, V: Into<AnnotationValue>> FromIterator<(K, V)> for Annotations {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+}
+
+impl<K: Into<String>, V: Into<AnnotationValue>> Extend<(K, V)> for Annotations {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.0
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v.into())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_typed_accessors() {
+        let mut annotations = Annotations::new();
+        assert!(annotations.is_empty());
+        assert_eq!(annotations.duration_ns(), None);
+
+        annotations.set_duration_ns(35.0);
+        annotations.set_provenance("basis_translation");
+        annotations.insert(Annotations::NOISE_SCALE, 3i64);
+        annotations.insert("custom.flag", true);
+        annotations.set_snapshot("after_oracle");
+
+        assert_eq!(annotations.len(), 5);
+        assert_eq!(annotations.snapshot(), Some("after_oracle"));
+        assert_eq!(annotations.duration_ns(), Some(35.0));
+        assert_eq!(annotations.noise_scale(), Some(3.0));
+        assert_eq!(annotations.provenance(), Some("basis_translation"));
+        assert_eq!(
+            annotations.get("custom.flag").unwrap().as_bool(),
+            Some(true)
+        );
+
+        // A well-known key holding the wrong type reads as absent.
+        annotations.insert(Annotations::PROVENANCE, 1.5);
+        assert_eq!(annotations.provenance(), None);
+        assert_eq!(
+            annotations.remove(Annotations::PROVENANCE),
+            Some(AnnotationValue::Float(1.5))
+        );
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let annotations: Annotations = [
+            ("a", AnnotationValue::Bool(false)),
+            ("b", AnnotationValue::Int(-2)),
+            ("c", AnnotationValue::Float(2.0)),
+            ("d", AnnotationValue::from("x")),
+        ]
+        .into_iter()
+        .collect();
+
+        let json = serde_json::to_string(&annotations).unwrap();
+        assert_eq!(json, r#"{"a":false,"b":-2,"c":2.0,"d":"x"}"#);
+        let back: Annotations = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, annotations);
+    }
+
+    #[test]
+    fn test_display_and_parse() {
+        assert_eq!(AnnotationValue::Float(3.0).to_string(), "3.0");
+        assert_eq!(AnnotationValue::Int(3).to_string(), "3");
+        assert_eq!(AnnotationValue::from("a b").to_string(), "\"a b\"");
+
+        for value in [
+            AnnotationValue::Bool(true),
+            AnnotationValue::Int(-7),
+            AnnotationValue::Float(1e-7),
+            AnnotationValue::from("say \"hi\""),
+        ] {
+            assert_eq!(AnnotationValue::parse(&value.to_string()), Some(value));
+        }
+        assert_eq!(AnnotationValue::parse("not json"), None);
+    }
+}
@@ -1,11 +1,14 @@
 //! High-level circuit builder API.
 
+use crate::annotation::Annotations;
 use crate::dag::CircuitDag;
-use crate::error::IrResult;
-use crate::gate::{Gate, StandardGate};
+use crate::error::{IrError, IrResult};
+use crate::gate::{CustomGate, Gate, GateKind, StandardGate};
 use crate::instruction::Instruction;
+use crate::instruction::InstructionKind;
 use crate::parameter::ParameterExpression;
 use crate::qubit::{Clbit, ClbitId, Qubit, QubitId};
+use crate::timing::TimeUnit;
 
 /// A quantum circuit.
 ///
@@ -290,6 +293,23 @@ impl Circuit {
         Ok(self)
     }
 
+    /// Apply echoed cross-resonance (ECR) gate.
+    pub fn ecr(&mut self, q1: QubitId, q2: QubitId) -> IrResult<&mut Self> {
+        self.dag
+            .apply(Instruction::two_qubit_gate(StandardGate::ECR, q1, q2))?;
+        Ok(self)
+    }
+
+    /// Apply controlled sqrt(X) gate.
+    pub fn csx(&mut self, control: QubitId, target: QubitId) -> IrResult<&mut Self> {
+        self.dag.apply(Instruction::two_qubit_gate(
+            StandardGate::CSX,
+            control,
+            target,
+        ))?;
+        Ok(self)
+    }
+
     /// Apply controlled-Rz gate.
     pub fn crz(
         &mut self,
@@ -405,6 +425,21 @@ impl Circuit {
         Ok(self)
     }
 
+    /// Apply RZX (ZX rotation) gate, with Z on `q1` and X on `q2`.
+    pub fn rzx(
+        &mut self,
+        theta: impl Into<ParameterExpression>,
+        q1: QubitId,
+        q2: QubitId,
+    ) -> IrResult<&mut Self> {
+        self.dag.apply(Instruction::two_qubit_gate(
+            StandardGate::RZX(theta.into()),
+            q1,
+            q2,
+        ))?;
+        Ok(self)
+    }
+
     // =========================================================================
     // IQM native gates
     // =========================================================================
@@ -434,6 +469,13 @@ impl Circuit {
         Ok(self)
     }
 
+    /// Apply doubly-controlled Z (CCZ) gate.
+    pub fn ccz(&mut self, c1: QubitId, c2: QubitId, target: QubitId) -> IrResult<&mut Self> {
+        self.dag
+            .apply(Instruction::gate(StandardGate::CCZ, [c1, c2, target]))?;
+        Ok(self)
+    }
+
     /// Apply Fredkin (CSWAP) gate.
     pub fn cswap(&mut self, control: QubitId, t1: QubitId, t2: QubitId) -> IrResult<&mut Self> {
         self.dag
@@ -441,6 +483,45 @@ impl Circuit {
         Ok(self)
     }
 
+    // =========================================================================
+    // Multi-controlled gates
+    // =========================================================================
+
+    /// Apply a multi-controlled X gate.
+    ///
+    /// Lowered to basis gates by high-level synthesis during compilation.
+    pub fn mcx(&mut self, controls: &[QubitId], target: QubitId) -> IrResult<&mut Self> {
+        self.apply_multi_controlled(CustomGate::new("mcx", 0), controls, target)
+    }
+
+    /// Apply a multi-controlled Z gate.
+    pub fn mcz(&mut self, controls: &[QubitId], target: QubitId) -> IrResult<&mut Self> {
+        self.apply_multi_controlled(CustomGate::new("mcz", 0), controls, target)
+    }
+
+    /// Apply a multi-controlled phase gate.
+    pub fn mcp(
+        &mut self,
+        lambda: impl Into<ParameterExpression>,
+        controls: &[QubitId],
+        target: QubitId,
+    ) -> IrResult<&mut Self> {
+        let gate = CustomGate::new("mcp", 0).with_params(vec![lambda.into()]);
+        self.apply_multi_controlled(gate, controls, target)
+    }
+
+    fn apply_multi_controlled(
+        &mut self,
+        mut gate: CustomGate,
+        controls: &[QubitId],
+        target: QubitId,
+    ) -> IrResult<&mut Self> {
+        gate.num_qubits = controls.len() as u32 + 1;
+        let qubits = controls.iter().copied().chain([target]);
+        self.dag.apply(Instruction::gate(gate, qubits))?;
+        Ok(self)
+    }
+
     // =========================================================================
     // Other operations
     // =========================================================================
@@ -499,12 +580,36 @@ impl Circuit {
         Ok(self)
     }
 
-    /// Apply a delay to a qubit.
+    /// Apply a barrier to all qubits, marked as a snapshot point.
+    ///
+    /// Simulators tracing the execution capture the full state here under
+    /// `label`; everywhere else it acts as a plain barrier.
+    pub fn snapshot(&mut self, label: impl Into<String>) -> IrResult<&mut Self> {
+        let qubits: Vec<_> = self.qubits.iter().map(|q| q.id).collect();
+        self.dag.apply(
+            Instruction::barrier(qubits).with_annotation(Annotations::SNAPSHOT, label.into()),
+        )?;
+        Ok(self)
+    }
+
+    /// Apply a delay of `duration` backend sample periods (`dt`) to a qubit.
     pub fn delay(&mut self, qubit: QubitId, duration: u64) -> IrResult<&mut Self> {
         self.dag.apply(Instruction::delay(qubit, duration))?;
         Ok(self)
     }
 
+    /// Apply a delay in the given unit to several qubits.
+    pub fn delay_with_unit(
+        &mut self,
+        qubits: impl IntoIterator<Item = QubitId>,
+        duration: u64,
+        unit: TimeUnit,
+    ) -> IrResult<&mut Self> {
+        self.dag
+            .apply(Instruction::delay_with_unit(qubits, duration, unit))?;
+        Ok(self)
+    }
+
     // =========================================================================
     // Accessors
     // =========================================================================
@@ -529,6 +634,61 @@ impl Circuit {
         self.dag.depth()
     }
 
+    /// Upper bound on the bond dimension an exact matrix-product-state
+    /// simulation of the circuit needs, with qubits in index order.
+    ///
+    /// Each multi-qubit gate straddling a cut between qubits multiplies the
+    /// Schmidt rank across it by at most its operator Schmidt rank: `2` for
+    /// gates diagonal in some Pauli basis on all qubits of one side (`cx`,
+    /// `rzz`, ...) and `4` per qubit pair otherwise. The rank is also capped
+    /// by the dimension of the smaller side. Saturates at `u64::MAX`.
+    pub fn bond_dimension_bound(&self) -> u64 {
+        let n = self.num_qubits();
+        let mut bits = vec![0usize; n];
+        for (_, inst) in self.dag.topological_ops() {
+            let InstructionKind::Gate(gate) = &inst.kind else {
+                continue;
+            };
+            if inst.qubits.len() < 2 {
+                continue;
+            }
+            let axes = gate.qubit_axes();
+            let lo = inst.qubits.iter().map(|q| q.0 as usize).min().unwrap_or(0);
+            let hi = inst.qubits.iter().map(|q| q.0 as usize).max().unwrap_or(0);
+            for (cut, cut_bits) in bits.iter_mut().enumerate().take(hi + 1).skip(lo + 1) {
+                let mut left = (0, true);
+                let mut right = (0, true);
+                for (q, axis) in inst.qubits.iter().zip(&axes) {
+                    let side = if (q.0 as usize) < cut {
+                        &mut left
+                    } else {
+                        &mut right
+                    };
+                    side.0 += 1;
+                    side.1 &= axis.is_some();
+                }
+                let mut gate_bits = 2 * left.0.min(right.0);
+                if left.1 {
+                    gate_bits = gate_bits.min(left.0);
+                }
+                if right.1 {
+                    gate_bits = gate_bits.min(right.0);
+                }
+                *cut_bits += gate_bits;
+            }
+        }
+
+        let max_bits = (1..n)
+            .map(|cut| bits[cut].min(cut).min(n - cut))
+            .max()
+            .unwrap_or(0);
+        if max_bits >= 64 {
+            u64::MAX
+        } else {
+            1 << max_bits
+        }
+    }
+
     /// Get a reference to the underlying DAG.
     pub fn dag(&self) -> &CircuitDag {
         &self.dag
@@ -572,6 +732,60 @@ impl Circuit {
         &self.clbits
     }
 
+    // =========================================================================
+    // Parameters
+    // =========================================================================
+
+    /// Get the names of the symbolic parameters, sorted.
+    pub fn parameter_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .dag
+            .topological_ops()
+            .filter_map(|(_, inst)| match &inst.kind {
+                InstructionKind::Gate(gate) => Some(gate_parameters(&gate.kind)),
+                _ => None,
+            })
+            .flatten()
+            .flat_map(ParameterExpression::symbols)
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Bind values to the symbolic parameters, in the order of
+    /// [`parameter_names`](Self::parameter_names).
+    ///
+    /// Returns an error naming the first parameter left without a value.
+    pub fn bind_parameters(&self, values: &[f64]) -> IrResult<Circuit> {
+        let names = self.parameter_names();
+        if let Some(unbound) = names.get(values.len()) {
+            return Err(IrError::UnboundParameter(unbound.clone()));
+        }
+
+        let mut bound = self.clone();
+        let nodes: Vec<_> = bound.dag.topological_ops().map(|(node, _)| node).collect();
+        for node in nodes {
+            let Some(inst) = bound.dag.get_instruction_mut(node) else {
+                continue;
+            };
+            let InstructionKind::Gate(gate) = &mut inst.kind else {
+                continue;
+            };
+            let params: Vec<&mut ParameterExpression> = match &mut gate.kind {
+                GateKind::Standard(g) => g.parameters_mut(),
+                GateKind::Custom(g) => g.params.iter_mut().collect(),
+            };
+            for param in params {
+                for (name, value) in names.iter().zip(values) {
+                    *param = param.bind(name, *value);
+                }
+                *param = param.simplify();
+            }
+        }
+        Ok(bound)
+    }
+
     // =========================================================================
     // Pre-built circuits
     // =========================================================================
@@ -661,6 +875,26 @@ impl Clone for Circuit {
     }
 }
 
+impl std::fmt::Debug for Circuit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ops: Vec<_> = self.dag.topological_ops().map(|(_, inst)| inst).collect();
+        f.debug_struct("Circuit")
+            .field("name", &self.name)
+            .field("num_qubits", &self.qubits.len())
+            .field("num_clbits", &self.clbits.len())
+            .field("ops", &ops)
+            .finish()
+    }
+}
+
+/// Get the parameters of a gate of either kind.
+fn gate_parameters(kind: &GateKind) -> Vec<&ParameterExpression> {
+    match kind {
+        GateKind::Standard(g) => g.parameters(),
+        GateKind::Custom(g) => g.params.iter().collect(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -719,6 +953,44 @@ mod tests {
         assert_eq!(circuit.depth(), 2);
     }
 
+    #[test]
+    fn test_bind_parameters() {
+        let mut circuit = Circuit::with_size("ansatz", 2, 0);
+        circuit
+            .ry(ParameterExpression::symbol("b"), QubitId(0))
+            .unwrap()
+            .rz(ParameterExpression::symbol("a"), QubitId(1))
+            .unwrap()
+            .cx(QubitId(0), QubitId(1))
+            .unwrap();
+        assert_eq!(circuit.parameter_names(), vec!["a", "b"]);
+
+        let bound = circuit.bind_parameters(&[0.5, 1.5]).unwrap();
+        assert!(bound.parameter_names().is_empty());
+        let mut values: Vec<(&str, f64)> = bound
+            .dag()
+            .topological_ops()
+            .filter_map(|(_, inst)| match &inst.kind {
+                InstructionKind::Gate(gate) => match &gate.kind {
+                    GateKind::Standard(g) => g
+                        .parameters()
+                        .first()
+                        .and_then(|p| p.as_f64())
+                        .map(|v| (g.name(), v)),
+                    GateKind::Custom(_) => None,
+                },
+                _ => None,
+            })
+            .collect();
+        values.sort_by_key(|(name, _)| *name);
+        assert_eq!(values, vec![("ry", 1.5), ("rz", 0.5)]);
+
+        assert!(matches!(
+            circuit.bind_parameters(&[0.5]),
+            Err(IrError::UnboundParameter(name)) if name == "b"
+        ));
+    }
+
     #[test]
     fn test_fluent_api() {
         let mut circuit = Circuit::with_size("test", 2, 2);
@@ -734,4 +1006,31 @@ mod tests {
 
         assert_eq!(circuit.depth(), 3); // H, CX, parallel measures
     }
+
+    #[test]
+    fn test_bond_dimension_bound() {
+        // A CX chain crosses every cut once with operator Schmidt rank 2
+        assert_eq!(Circuit::ghz(6).unwrap().bond_dimension_bound(), 2);
+        assert_eq!(Circuit::with_size("empty", 4, 0).bond_dimension_bound(), 1);
+
+        // A ring of ZZ rotations crosses every cut twice
+        let mut ring = Circuit::with_size("ring", 8, 0);
+        for i in 0..8 {
+            ring.rzz(0.3, QubitId(i), QubitId((i + 1) % 8)).unwrap();
+        }
+        assert_eq!(ring.bond_dimension_bound(), 4);
+
+        // Swaps have rank 4, capped by the smaller side of the cut
+        let mut swaps = Circuit::with_size("swaps", 3, 0);
+        swaps.swap(QubitId(0), QubitId(2)).unwrap();
+        assert_eq!(swaps.bond_dimension_bound(), 2);
+        swaps.swap(QubitId(0), QubitId(1)).unwrap();
+        assert_eq!(swaps.bond_dimension_bound(), 2);
+        let mut wide = Circuit::with_size("wide", 6, 0);
+        wide.swap(QubitId(1), QubitId(4)).unwrap();
+        assert_eq!(wide.bond_dimension_bound(), 4);
+
+        // Dense circuits saturate at the half-system dimension
+        assert_eq!(Circuit::qft(6).unwrap().bond_dimension_bound(), 8);
+    }
 }
@@ -0,0 +1,428 @@
+//! Device coupling maps and qubit interaction graphs.
+//!
+//! A [`CouplingMap`] is an undirected graph over physical qubits whose edges
+//! are the pairs that support two-qubit gates. The same type describes the
+//! interaction graph of a circuit (see [`CouplingMap::interaction_graph`]),
+//! so layout, routing and backend matching share one set of graph utilities.
+
+use std::collections::VecDeque;
+
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::dag::CircuitDag;
+
+/// Target device coupling map.
+///
+/// The coupling map defines which pairs of physical qubits can
+/// interact with two-qubit gates. Edges are undirected and stored once.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "CouplingMapData", into = "CouplingMapData")]
+pub struct CouplingMap {
+    /// List of connected qubit pairs (bidirectional).
+    edges: Vec<(u32, u32)>,
+    /// Number of physical qubits.
+    num_qubits: u32,
+    /// Adjacency list for fast lookup.
+    adjacency: FxHashMap<u32, Vec<u32>>,
+}
+
+/// Serialized form of a [`CouplingMap`]; the adjacency list is rebuilt on load.
+#[derive(Serialize, Deserialize)]
+struct CouplingMapData {
+    edges: Vec<(u32, u32)>,
+    num_qubits: u32,
+}
+
+impl From<CouplingMapData> for CouplingMap {
+    fn from(data: CouplingMapData) -> Self {
+        Self::from_edges(data.num_qubits, data.edges)
+    }
+}
+
+impl From<CouplingMap> for CouplingMapData {
+    fn from(map: CouplingMap) -> Self {
+        Self {
+            edges: map.edges,
+            num_qubits: map.num_qubits,
+        }
+    }
+}
+
+impl PartialEq for CouplingMap {
+    fn eq(&self, other: &Self) -> bool {
+        let normalized = |map: &Self| {
+            let mut edges: Vec<_> = map
+                .edges
+                .iter()
+                .map(|&(a, b)| (a.min(b), a.max(b)))
+                .collect();
+            edges.sort_unstable();
+            edges
+        };
+        self.num_qubits == other.num_qubits && normalized(self) == normalized(other)
+    }
+}
+
+impl Eq for CouplingMap {}
+
+impl CouplingMap {
+    /// Create a new coupling map with the given number of qubits.
+    pub fn new(num_qubits: u32) -> Self {
+        Self {
+            edges: vec![],
+            num_qubits,
+            adjacency: FxHashMap::default(),
+        }
+    }
+
+    /// Create a coupling map from a list of edges.
+    pub fn from_edges(num_qubits: u32, edges: impl IntoIterator<Item = (u32, u32)>) -> Self {
+        let mut map = Self::new(num_qubits);
+        for (a, b) in edges {
+            map.add_edge(a, b);
+        }
+        map
+    }
+
+    /// Add an edge between two qubits (bidirectional).
+    ///
+    /// Self-loops and edges already present are ignored.
+    pub fn add_edge(&mut self, q1: u32, q2: u32) {
+        if q1 == q2 || self.is_connected(q1, q2) {
+            return;
+        }
+        self.edges.push((q1, q2));
+        self.adjacency.entry(q1).or_default().push(q2);
+        self.adjacency.entry(q2).or_default().push(q1);
+    }
+
+    /// Check if two qubits are directly connected.
+    pub fn is_connected(&self, q1: u32, q2: u32) -> bool {
+        self.adjacency
+            .get(&q1)
+            .is_some_and(|neighbors| neighbors.contains(&q2))
+    }
+
+    /// Get the number of physical qubits.
+    pub fn num_qubits(&self) -> u32 {
+        self.num_qubits
+    }
+
+    /// Get the coupling edges.
+    pub fn edges(&self) -> &[(u32, u32)] {
+        &self.edges
+    }
+
+    /// Get neighbors of a qubit.
+    pub fn neighbors(&self, qubit: u32) -> impl Iterator<Item = u32> + '_ {
+        self.adjacency
+            .get(&qubit)
+            .map(|v| v.iter().copied())
+            .into_iter()
+            .flatten()
+    }
+
+    /// Get the number of neighbors of a qubit.
+    pub fn degree(&self, qubit: u32) -> usize {
+        self.adjacency.get(&qubit).map_or(0, Vec::len)
+    }
+
+    /// Create a linear coupling map (0-1-2-3-...).
+    pub fn linear(n: u32) -> Self {
+        Self::from_edges(n, (0..n.saturating_sub(1)).map(|i| (i, i + 1)))
+    }
+
+    /// Create a fully connected coupling map.
+    pub fn full(n: u32) -> Self {
+        Self::from_edges(n, (0..n).flat_map(|i| ((i + 1)..n).map(move |j| (i, j))))
+    }
+
+    /// Create a star topology (center qubit connected to all others).
+    pub fn star(n: u32) -> Self {
+        Self::from_edges(n, (1..n).map(|i| (0, i)))
+    }
+
+    /// Create a zoned coupling map for neutral-atom devices.
+    ///
+    /// Qubits within each zone are fully connected; qubits across zones are not
+    /// (they require shuttle operations).
+    pub fn zoned(num_qubits: u32, zones: u32) -> Self {
+        let mut map = Self::new(num_qubits);
+        let qubits_per_zone = num_qubits / zones.max(1);
+
+        for z in 0..zones {
+            let start = z * qubits_per_zone;
+            let end = if z == zones - 1 {
+                num_qubits
+            } else {
+                start + qubits_per_zone
+            };
+            for i in start..end {
+                for j in (i + 1)..end {
+                    map.add_edge(i, j);
+                }
+            }
+        }
+
+        map
+    }
+
+    /// Build the interaction graph of a circuit.
+    ///
+    /// Qubits are connected if the circuit applies a multi-qubit gate to
+    /// them; a gate on more than two qubits connects every pair.
+    pub fn interaction_graph(dag: &CircuitDag) -> Self {
+        let num_qubits = dag.qubits().map(|q| q.0 + 1).max().unwrap_or(0);
+        let mut map = Self::new(num_qubits);
+        for (_, inst) in dag.topological_ops() {
+            if !inst.is_gate() {
+                continue;
+            }
+            for (i, a) in inst.qubits.iter().enumerate() {
+                for b in &inst.qubits[i + 1..] {
+                    map.add_edge(a.0, b.0);
+                }
+            }
+        }
+        map
+    }
+
+    /// BFS distances from `from` to every qubit; `None` where unreachable.
+    pub fn distances_from(&self, from: u32) -> Vec<Option<u32>> {
+        let mut dist = vec![None; self.num_qubits as usize];
+        let Some(slot) = dist.get_mut(from as usize) else {
+            return dist;
+        };
+        *slot = Some(0);
+
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            let d = dist[current as usize].expect("queued qubits have a distance");
+            for neighbor in self.neighbors(current) {
+                if let Some(slot @ None) = dist.get_mut(neighbor as usize) {
+                    *slot = Some(d + 1);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        dist
+    }
+
+    /// All-pairs shortest path distances, indexed `[from][to]`.
+    pub fn distance_matrix(&self) -> Vec<Vec<Option<u32>>> {
+        (0..self.num_qubits)
+            .map(|q| self.distances_from(q))
+            .collect()
+    }
+
+    /// Calculate shortest path distance between two qubits.
+    pub fn distance(&self, from: u32, to: u32) -> Option<u32> {
+        self.shortest_path(from, to)
+            .map(|path| path.len() as u32 - 1)
+    }
+
+    /// Find a shortest path between two qubits, including both endpoints.
+    pub fn shortest_path(&self, from: u32, to: u32) -> Option<Vec<u32>> {
+        if from == to {
+            return Some(vec![from]);
+        }
+
+        let mut previous = FxHashMap::default();
+        previous.insert(from, from);
+        let mut queue = VecDeque::from([from]);
+
+        while let Some(current) = queue.pop_front() {
+            for neighbor in self.neighbors(current) {
+                if previous.contains_key(&neighbor) {
+                    continue;
+                }
+                previous.insert(neighbor, current);
+
+                if neighbor == to {
+                    let mut path = vec![to];
+                    let mut node = to;
+                    while node != from {
+                        node = previous[&node];
+                        path.push(node);
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+
+                queue.push_back(neighbor);
+            }
+        }
+
+        None
+    }
+
+    /// Group qubits into connected components, each sorted, in order of
+    /// their smallest qubit.
+    pub fn connected_components(&self) -> Vec<Vec<u32>> {
+        let mut seen = vec![false; self.num_qubits as usize];
+        let mut components = Vec::new();
+        for start in 0..self.num_qubits {
+            if seen[start as usize] {
+                continue;
+            }
+            let mut component: Vec<u32> = self
+                .distances_from(start)
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.is_some())
+                .map(|(q, _)| q as u32)
+                .collect();
+            for &q in &component {
+                seen[q as usize] = true;
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Check that every qubit can reach every other qubit.
+    pub fn is_connected_graph(&self) -> bool {
+        self.connected_components().len() <= 1
+    }
+
+    /// Check that every pair of qubits is directly connected.
+    pub fn is_fully_connected(&self) -> bool {
+        let n = self.num_qubits as usize;
+        self.edges.len() >= n * n.saturating_sub(1) / 2
+    }
+
+    /// Restrict the map to `qubits`, relabelling `qubits[i]` as qubit `i`.
+    ///
+    /// Edges to qubits outside the subset are dropped.
+    pub fn reduce(&self, qubits: &[u32]) -> Self {
+        let index: FxHashMap<u32, u32> = qubits
+            .iter()
+            .enumerate()
+            .map(|(i, &q)| (q, i as u32))
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .filter_map(|(a, b)| Some((*index.get(a)?, *index.get(b)?)));
+        Self::from_edges(qubits.len() as u32, edges)
+    }
+
+    /// Find `size` qubits forming a connected region.
+    ///
+    /// The region is grown breadth-first from the best-connected qubit that
+    /// has a large enough component, preferring well-connected neighbors, so
+    /// the result is deterministic. Returns `None` if no component is large
+    /// enough.
+    pub fn find_connected_subset(&self, size: u32) -> Option<Vec<u32>> {
+        if size == 0 {
+            return Some(vec![]);
+        }
+        let components = self.connected_components();
+        let start = (0..self.num_qubits)
+            .filter(|&q| {
+                components
+                    .iter()
+                    .any(|c| c.contains(&q) && c.len() >= size as usize)
+            })
+            .max_by_key(|&q| (self.degree(q), std::cmp::Reverse(q)))?;
+
+        let mut chosen = vec![start];
+        let mut frontier: Vec<u32> = Vec::new();
+        while chosen.len() < size as usize {
+            let last = *chosen.last().expect("chosen is not empty");
+            for n in self.neighbors(last) {
+                if !chosen.contains(&n) && !frontier.contains(&n) {
+                    frontier.push(n);
+                }
+            }
+            let best = frontier
+                .iter()
+                .enumerate()
+                .max_by_key(|&(_, &q)| (self.degree(q), std::cmp::Reverse(q)))
+                .map(|(i, _)| i)?;
+            chosen.push(frontier.swap_remove(best));
+        }
+        Some(chosen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Circuit;
+    use crate::qubit::QubitId;
+
+    #[test]
+    fn test_coupling_map_linear() {
+        let map = CouplingMap::linear(5);
+        assert!(map.is_connected(0, 1));
+        assert!(map.is_connected(1, 2));
+        assert!(!map.is_connected(0, 2));
+        assert_eq!(map.distance(0, 4), Some(4));
+    }
+
+    #[test]
+    fn test_coupling_map_star() {
+        let map = CouplingMap::star(5);
+        assert!(map.is_connected(0, 1));
+        assert!(map.is_connected(0, 4));
+        assert!(!map.is_connected(1, 2));
+        assert_eq!(map.distance(1, 2), Some(2));
+    }
+
+    #[test]
+    fn test_paths_and_distances() {
+        let map = CouplingMap::linear(5);
+        assert_eq!(map.shortest_path(0, 4), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(map.shortest_path(2, 2), Some(vec![2]));
+
+        let matrix = map.distance_matrix();
+        assert_eq!(matrix[1][4], Some(3));
+        assert_eq!(matrix[4][1], Some(3));
+
+        let split = CouplingMap::zoned(4, 2);
+        assert_eq!(split.distance(0, 3), None);
+        assert_eq!(split.connected_components(), vec![vec![0, 1], vec![2, 3]]);
+        assert!(!split.is_connected_graph());
+        assert!(CouplingMap::full(4).is_fully_connected());
+    }
+
+    #[test]
+    fn test_reduce_and_subset() {
+        let map = CouplingMap::star(5);
+        let reduced = map.reduce(&[3, 0, 4]);
+        assert_eq!(reduced, CouplingMap::from_edges(3, [(1, 0), (1, 2)]));
+
+        let subset = map.find_connected_subset(3).unwrap();
+        assert_eq!(subset[0], 0);
+        assert!(map.reduce(&subset).is_connected_graph());
+        assert!(map.find_connected_subset(6).is_none());
+        assert!(CouplingMap::zoned(6, 2).find_connected_subset(4).is_none());
+    }
+
+    #[test]
+    fn test_serde_rebuilds_adjacency() {
+        let map = CouplingMap::linear(3);
+        let json = serde_json::to_string(&map).unwrap();
+        let back: CouplingMap = serde_json::from_str(&json).unwrap();
+        assert!(back.is_connected(1, 2));
+        assert_eq!(back, map);
+    }
+
+    #[test]
+    fn test_interaction_graph() {
+        let mut circuit = Circuit::with_size("test", 4, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(2)).unwrap();
+        circuit.ccx(QubitId(1), QubitId(2), QubitId(3)).unwrap();
+
+        let graph = CouplingMap::interaction_graph(circuit.dag());
+        assert_eq!(graph.num_qubits(), 4);
+        assert_eq!(graph.edges().len(), 4);
+        assert!(graph.is_connected(0, 2));
+        assert!(graph.is_connected(1, 3));
+        assert!(!graph.is_connected(0, 1));
+    }
+}
@@ -1,7 +1,8 @@
 //! DAG-based circuit representation.
 
 use petgraph::Direction;
-use petgraph::graph::{DiGraph, NodeIndex as PetNodeIndex};
+use petgraph::graph::NodeIndex as PetNodeIndex;
+use petgraph::stable_graph::StableDiGraph;
 use petgraph::visit::EdgeRef;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -108,7 +109,7 @@ pub enum CircuitLevel {
 /// - Operations are connected to wires in topological order
 pub struct CircuitDag {
     /// The underlying graph.
-    graph: DiGraph<DagNode, DagEdge, u32>,
+    graph: StableDiGraph<DagNode, DagEdge, u32>,
     /// Map from qubit to its input node.
     qubit_inputs: FxHashMap<QubitId, NodeIndex>,
     /// Map from qubit to its current output node.
@@ -127,7 +128,7 @@ impl CircuitDag {
     /// Create a new empty circuit DAG.
     pub fn new() -> Self {
         Self {
-            graph: DiGraph::default(),
+            graph: StableDiGraph::default(),
             qubit_inputs: FxHashMap::default(),
             qubit_outputs: FxHashMap::default(),
             clbit_inputs: FxHashMap::default(),
@@ -349,16 +350,67 @@ impl CircuitDag {
     }
 
     /// Substitute a node with a sequence of instructions.
+    ///
+    /// The replacement is spliced in where the node was, so operations
+    /// before and after it keep their order. Every replacement instruction
+    /// must act only on wires of the substituted node.
     pub fn substitute_node(
         &mut self,
         node: NodeIndex,
         replacement: impl IntoIterator<Item = Instruction>,
     ) -> IrResult<Vec<NodeIndex>> {
-        let _old = self.remove_op(node)?;
-        let mut new_nodes = vec![];
+        if !matches!(self.graph.node_weight(node), Some(DagNode::Op(_))) {
+            return Err(IrError::InvalidNode);
+        }
+        let replacement: Vec<Instruction> = replacement.into_iter().collect();
+
+        // Last node on each wire before the substituted node.
+        let mut prev: FxHashMap<WireId, NodeIndex> = self
+            .graph
+            .edges_directed(node, Direction::Incoming)
+            .map(|e| (e.weight().wire, e.source()))
+            .collect();
+        let next: Vec<_> = self
+            .graph
+            .edges_directed(node, Direction::Outgoing)
+            .map(|e| (e.weight().wire, e.target()))
+            .collect();
+
+        for inst in &replacement {
+            let mut seen = FxHashMap::default();
+            for &qubit in &inst.qubits {
+                if seen.insert(qubit, ()).is_some() {
+                    return Err(IrError::DuplicateQubit {
+                        qubit,
+                        gate_name: Some(inst.name().to_string()),
+                    });
+                }
+            }
+            if instruction_wires(inst).any(|wire| !prev.contains_key(&wire)) {
+                return Err(IrError::InvalidDag(format!(
+                    "replacement '{}' acts on a wire outside the substituted node",
+                    inst.name()
+                )));
+            }
+        }
+
+        self.graph.remove_node(node);
+
+        let mut new_nodes = Vec::with_capacity(replacement.len());
         for inst in replacement {
-            new_nodes.push(self.apply(inst)?);
+            let wires: Vec<_> = instruction_wires(&inst).collect();
+            let op_node = self.graph.add_node(DagNode::Op(inst));
+            for wire in wires {
+                let last = prev.insert(wire, op_node).expect("wire checked above");
+                self.graph.add_edge(last, op_node, DagEdge { wire });
+            }
+            new_nodes.push(op_node);
+        }
+
+        for (wire, succ) in next {
+            self.graph.add_edge(prev[&wire], succ, DagEdge { wire });
         }
+
         Ok(new_nodes)
     }
 
@@ -436,7 +488,7 @@ impl CircuitDag {
     }
 
     /// Get a reference to the underlying graph.
-    pub fn graph(&self) -> &DiGraph<DagNode, DagEdge, u32> {
+    pub fn graph(&self) -> &StableDiGraph<DagNode, DagEdge, u32> {
         &self.graph
     }
 
@@ -590,6 +642,14 @@ impl CircuitDag {
     }
 }
 
+/// Quantum and classical wires an instruction acts on.
+fn instruction_wires(inst: &Instruction) -> impl Iterator<Item = WireId> + '_ {
+    inst.qubits
+        .iter()
+        .map(|&q| WireId::Qubit(q))
+        .chain(inst.clbits.iter().map(|&c| WireId::Clbit(c)))
+}
+
 impl Default for CircuitDag {
     fn default() -> Self {
         Self::new()
@@ -747,6 +807,69 @@ mod tests {
         dag.verify_integrity().unwrap();
     }
 
+    #[test]
+    fn test_substitute_node_in_place() {
+        let mut dag = CircuitDag::new();
+        dag.add_qubit(QubitId(0));
+        dag.add_qubit(QubitId(1));
+        let h = dag
+            .apply(Instruction::single_qubit_gate(StandardGate::H, QubitId(0)))
+            .unwrap();
+        let cx = dag
+            .apply(Instruction::two_qubit_gate(
+                StandardGate::CX,
+                QubitId(0),
+                QubitId(1),
+            ))
+            .unwrap();
+        dag.apply(Instruction::single_qubit_gate(StandardGate::X, QubitId(1)))
+            .unwrap();
+
+        let replacement = vec![
+            Instruction::single_qubit_gate(StandardGate::H, QubitId(1)),
+            Instruction::two_qubit_gate(StandardGate::CZ, QubitId(0), QubitId(1)),
+            Instruction::single_qubit_gate(StandardGate::H, QubitId(1)),
+        ];
+        assert_eq!(dag.substitute_node(cx, replacement).unwrap().len(), 3);
+
+        dag.verify_integrity().unwrap();
+        let names: Vec<_> = dag.topological_ops().map(|(_, i)| i.name()).collect();
+        assert_eq!(names.last(), Some(&"x"));
+        assert_eq!(names.iter().filter(|n| **n == "h").count(), 3);
+        assert_eq!(dag.depth(), 4);
+
+        // A replacement may not reach onto other wires.
+        let outside = Instruction::single_qubit_gate(StandardGate::Z, QubitId(1));
+        assert!(dag.substitute_node(h, vec![outside]).is_err());
+        dag.verify_integrity().unwrap();
+    }
+
+    #[test]
+    fn test_remove_op_keeps_other_indices_valid() {
+        let mut dag = CircuitDag::new();
+        dag.add_qubit(QubitId(0));
+        dag.add_qubit(QubitId(1));
+        let h = dag
+            .apply(Instruction::single_qubit_gate(StandardGate::H, QubitId(0)))
+            .unwrap();
+        let cx = dag
+            .apply(Instruction::two_qubit_gate(
+                StandardGate::CX,
+                QubitId(0),
+                QubitId(1),
+            ))
+            .unwrap();
+
+        dag.remove_op(h).unwrap();
+        assert_eq!(dag.get_instruction(cx).unwrap().name(), "cx");
+        dag.apply(Instruction::single_qubit_gate(StandardGate::X, QubitId(1)))
+            .unwrap();
+
+        dag.verify_integrity().unwrap();
+        let names: Vec<_> = dag.topological_ops().map(|(_, i)| i.name()).collect();
+        assert_eq!(names, vec!["cx", "x"]);
+    }
+
     #[test]
     fn test_verify_integrity_with_measurement() {
         use crate::qubit::ClbitId;
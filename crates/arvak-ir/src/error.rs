@@ -60,6 +60,10 @@ pub enum IrError {
         /// Optional gate name for context.
         gate_name: Option<String>,
     },
+
+    /// Gate set cannot be used for the requested operation.
+    #[error("Invalid gate set: {0}")]
+    InvalidGateSet(String),
 }
 
 /// Helper function to format optional gate context.
@@ -63,6 +63,10 @@ pub enum StandardGate {
     Swap,
     /// iSWAP gate.
     ISwap,
+    /// Echoed cross-resonance gate (IBM native), `RZX(π/4) · X · RZX(-π/4)`.
+    ECR,
+    /// Controlled sqrt(X) gate.
+    CSX,
     /// Controlled rotation around X.
     CRx(ParameterExpression),
     /// Controlled rotation around Y.
@@ -77,12 +81,16 @@ pub enum StandardGate {
     RYY(ParameterExpression),
     /// ZZ rotation gate.
     RZZ(ParameterExpression),
+    /// ZX rotation gate (Z on the first qubit, X on the second).
+    RZX(ParameterExpression),
 
     // Three-qubit gates
     /// Toffoli gate (CCX).
     CCX,
     /// Fredkin gate (CSWAP).
     CSwap,
+    /// Doubly-controlled Z gate.
+    CCZ,
 
     // IQM native gates
     /// Phased RX gate: PRX(θ, φ) = RZ(φ) · RX(θ) · RZ(-φ).
@@ -115,6 +123,8 @@ impl StandardGate {
             StandardGate::CH => "ch",
             StandardGate::Swap => "swap",
             StandardGate::ISwap => "iswap",
+            StandardGate::ECR => "ecr",
+            StandardGate::CSX => "csx",
             StandardGate::CRx(_) => "crx",
             StandardGate::CRy(_) => "cry",
             StandardGate::CRz(_) => "crz",
@@ -122,8 +132,10 @@ impl StandardGate {
             StandardGate::RXX(_) => "rxx",
             StandardGate::RYY(_) => "ryy",
             StandardGate::RZZ(_) => "rzz",
+            StandardGate::RZX(_) => "rzx",
             StandardGate::CCX => "ccx",
             StandardGate::CSwap => "cswap",
+            StandardGate::CCZ => "ccz",
             StandardGate::PRX(_, _) => "prx",
         }
     }
@@ -155,15 +167,18 @@ impl StandardGate {
             | StandardGate::CH
             | StandardGate::Swap
             | StandardGate::ISwap
+            | StandardGate::ECR
+            | StandardGate::CSX
             | StandardGate::CRx(_)
             | StandardGate::CRy(_)
             | StandardGate::CRz(_)
             | StandardGate::CP(_)
             | StandardGate::RXX(_)
             | StandardGate::RYY(_)
-            | StandardGate::RZZ(_) => 2,
+            | StandardGate::RZZ(_)
+            | StandardGate::RZX(_) => 2,
 
-            StandardGate::CCX | StandardGate::CSwap => 3,
+            StandardGate::CCX | StandardGate::CSwap | StandardGate::CCZ => 3,
         }
     }
 
@@ -180,7 +195,8 @@ impl StandardGate {
             | StandardGate::CP(p)
             | StandardGate::RXX(p)
             | StandardGate::RYY(p)
-            | StandardGate::RZZ(p) => p.is_symbolic(),
+            | StandardGate::RZZ(p)
+            | StandardGate::RZX(p) => p.is_symbolic(),
 
             StandardGate::U(a, b, c) => a.is_symbolic() || b.is_symbolic() || c.is_symbolic(),
 
@@ -203,7 +219,8 @@ impl StandardGate {
             | StandardGate::CP(p)
             | StandardGate::RXX(p)
             | StandardGate::RYY(p)
-            | StandardGate::RZZ(p) => vec![p],
+            | StandardGate::RZZ(p)
+            | StandardGate::RZX(p) => vec![p],
 
             StandardGate::U(a, b, c) => vec![a, b, c],
 
@@ -212,6 +229,92 @@ impl StandardGate {
             _ => vec![],
         }
     }
+
+    /// Get mutable references to the parameters of this gate.
+    pub fn parameters_mut(&mut self) -> Vec<&mut ParameterExpression> {
+        match self {
+            StandardGate::Rx(p)
+            | StandardGate::Ry(p)
+            | StandardGate::Rz(p)
+            | StandardGate::P(p)
+            | StandardGate::CRx(p)
+            | StandardGate::CRy(p)
+            | StandardGate::CRz(p)
+            | StandardGate::CP(p)
+            | StandardGate::RXX(p)
+            | StandardGate::RYY(p)
+            | StandardGate::RZZ(p)
+            | StandardGate::RZX(p) => vec![p],
+
+            StandardGate::U(a, b, c) => vec![a, b, c],
+
+            StandardGate::PRX(theta, phi) => vec![theta, phi],
+
+            _ => vec![],
+        }
+    }
+}
+
+/// The Pauli axis a gate acts along on one of its qubits.
+///
+/// A gate has axis `Z` on a qubit if it is diagonal there (e.g. `Rz`, or the
+/// control of `CX`), `X` if it only applies `I`/`X` terms there (e.g. `Rx`,
+/// or the target of `CX`), and likewise for `Y`. Two gates commute if they
+/// share the same axis on every qubit they have in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PauliAxis {
+    /// Acts with `I` and `X` terms only.
+    X,
+    /// Acts with `I` and `Y` terms only.
+    Y,
+    /// Diagonal in the computational basis.
+    Z,
+}
+
+impl StandardGate {
+    /// Get the commutation axis of this gate on each of its qubits.
+    ///
+    /// Entries are `None` where the gate has no single axis on that qubit
+    /// (e.g. `H`, `U`, either qubit of `Swap`), in which case it is treated
+    /// as commuting with nothing that touches the qubit.
+    pub fn qubit_axes(&self) -> Vec<Option<PauliAxis>> {
+        use PauliAxis::{X, Y, Z};
+        match self {
+            StandardGate::I
+            | StandardGate::Z
+            | StandardGate::S
+            | StandardGate::Sdg
+            | StandardGate::T
+            | StandardGate::Tdg
+            | StandardGate::Rz(_)
+            | StandardGate::P(_) => vec![Some(Z)],
+            StandardGate::X | StandardGate::SX | StandardGate::SXdg | StandardGate::Rx(_) => {
+                vec![Some(X)]
+            }
+            StandardGate::Y | StandardGate::Ry(_) => vec![Some(Y)],
+            StandardGate::H | StandardGate::U(_, _, _) | StandardGate::PRX(_, _) => vec![None],
+
+            StandardGate::CX | StandardGate::CRx(_) | StandardGate::CSX | StandardGate::RZX(_) => {
+                vec![Some(Z), Some(X)]
+            }
+            StandardGate::CY | StandardGate::CRy(_) => vec![Some(Z), Some(Y)],
+            StandardGate::CZ
+            | StandardGate::CRz(_)
+            | StandardGate::CP(_)
+            | StandardGate::RZZ(_) => {
+                vec![Some(Z), Some(Z)]
+            }
+            StandardGate::CH => vec![Some(Z), None],
+            StandardGate::RXX(_) => vec![Some(X), Some(X)],
+            StandardGate::RYY(_) => vec![Some(Y), Some(Y)],
+            StandardGate::Swap | StandardGate::ISwap => vec![None, None],
+            StandardGate::ECR => vec![None, Some(X)],
+
+            StandardGate::CCX => vec![Some(Z), Some(Z), Some(X)],
+            StandardGate::CSwap => vec![Some(Z), None, None],
+            StandardGate::CCZ => vec![Some(Z), Some(Z), Some(Z)],
+        }
+    }
 }
 
 /// A quantum gate, either standard or custom.
@@ -250,7 +353,8 @@ pub struct CustomGate {
     pub num_qubits: u32,
     /// Parameters of the gate.
     pub params: Vec<ParameterExpression>,
-    /// Optional unitary matrix (row-major, 2^n × 2^n).
+    /// Optional unitary matrix (row-major, 2^n × 2^n), in the same qubit
+    /// order as [`StandardGate::matrix`].
     #[serde(skip_serializing_if = "Option::is_none")]
     pub matrix: Option<Vec<Complex64>>,
 }
@@ -357,6 +461,18 @@ impl Gate {
     }
 }
 
+impl Gate {
+    /// Get the commutation axis of this gate on each of its qubits.
+    ///
+    /// Custom gates and classically conditioned gates have no axes.
+    pub fn qubit_axes(&self) -> Vec<Option<PauliAxis>> {
+        match &self.kind {
+            GateKind::Standard(g) if self.condition.is_none() => g.qubit_axes(),
+            _ => vec![None; self.num_qubits() as usize],
+        }
+    }
+}
+
 impl From<StandardGate> for Gate {
     fn from(gate: StandardGate) -> Self {
         Gate::standard(gate)
@@ -397,6 +513,26 @@ mod tests {
         assert_eq!(h_labeled.label, Some("my_hadamard".to_string()));
     }
 
+    #[test]
+    fn test_qubit_axes() {
+        assert_eq!(
+            StandardGate::CX.qubit_axes(),
+            vec![Some(PauliAxis::Z), Some(PauliAxis::X)]
+        );
+        assert_eq!(StandardGate::H.qubit_axes(), vec![None]);
+        for gate in [
+            StandardGate::CCX,
+            StandardGate::Swap,
+            StandardGate::RXX(PI.into()),
+        ] {
+            assert_eq!(gate.qubit_axes().len(), gate.num_qubits() as usize);
+        }
+
+        let conditioned =
+            Gate::standard(StandardGate::Z).with_condition(ClassicalCondition::new("c", 1));
+        assert_eq!(conditioned.qubit_axes(), vec![None]);
+    }
+
     #[test]
     fn test_custom_gate() {
         let custom = CustomGate::new("my_gate", 2)
@@ -0,0 +1,83 @@
+//! Stable hashing.
+//!
+//! `DefaultHasher` may change between Rust releases, so hashes that are
+//! stored, sent over the wire or compared across processes use 64-bit
+//! FNV-1a from this module instead.
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hash of `bytes`.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = Fnv1a::new();
+    hash.write(bytes);
+    hash.finish()
+}
+
+/// Incremental 64-bit FNV-1a hasher.
+///
+/// Integers are written little-endian, so the hash of a value does not
+/// depend on the platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv1a(u64);
+
+impl Fnv1a {
+    /// Start a new hash.
+    pub fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+
+    /// Hash raw bytes.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Hash an integer.
+    pub fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    /// Hash a length-prefixed string, so adjacent fields cannot run together.
+    pub fn write_str(&mut self, s: &str) {
+        self.write_u64(s.len() as u64);
+        self.write(s.as_bytes());
+    }
+
+    /// The hash of everything written so far.
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a() {
+        // Reference vectors of the 64-bit FNV-1a specification
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+
+        let mut hash = Fnv1a::new();
+        hash.write(b"foo");
+        hash.write(b"bar");
+        assert_eq!(hash.finish(), fnv1a(b"foobar"));
+
+        let (mut a, mut b) = (Fnv1a::new(), Fnv1a::new());
+        a.write_str("ab");
+        a.write_str("c");
+        b.write_str("a");
+        b.write_str("bc");
+        assert_ne!(a.finish(), b.finish());
+    }
+}
@@ -2,8 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::annotation::{AnnotationValue, Annotations};
 use crate::gate::{Gate, StandardGate};
 use crate::qubit::{ClbitId, QubitId};
+use crate::timing::TimeUnit;
 
 /// The kind of instruction in a circuit.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -16,10 +18,13 @@ pub enum InstructionKind {
     Reset,
     /// Barrier (synchronization point).
     Barrier,
-    /// Delay instruction.
+    /// Idle the qubits for a fixed time.
     Delay {
-        /// Duration in device-specific units.
+        /// Duration, in `unit`.
         duration: u64,
+        /// Unit of `duration`.
+        #[serde(default)]
+        unit: TimeUnit,
     },
     /// Shuttle qubit between zones (neutral-atom architectures).
     Shuttle {
@@ -39,6 +44,9 @@ pub struct Instruction {
     pub qubits: Vec<QubitId>,
     /// Classical bits this instruction operates on (for measure).
     pub clbits: Vec<ClbitId>,
+    /// Metadata attached by passes and front ends.
+    #[serde(default, skip_serializing_if = "Annotations::is_empty")]
+    pub annotations: Annotations,
 }
 
 impl Instruction {
@@ -48,6 +56,7 @@ impl Instruction {
             kind: InstructionKind::Gate(gate.into()),
             qubits: qubits.into_iter().collect(),
             clbits: vec![],
+            annotations: Annotations::new(),
         }
     }
 
@@ -67,6 +76,7 @@ impl Instruction {
             kind: InstructionKind::Measure,
             qubits: vec![qubit],
             clbits: vec![clbit],
+            annotations: Annotations::new(),
         }
     }
 
@@ -79,6 +89,7 @@ impl Instruction {
             kind: InstructionKind::Measure,
             qubits: qubits.into_iter().collect(),
             clbits: clbits.into_iter().collect(),
+            annotations: Annotations::new(),
         }
     }
 
@@ -88,6 +99,7 @@ impl Instruction {
             kind: InstructionKind::Reset,
             qubits: vec![qubit],
             clbits: vec![],
+            annotations: Annotations::new(),
         }
     }
 
@@ -97,15 +109,26 @@ impl Instruction {
             kind: InstructionKind::Barrier,
             qubits: qubits.into_iter().collect(),
             clbits: vec![],
+            annotations: Annotations::new(),
         }
     }
 
-    /// Create a delay instruction.
+    /// Create a delay instruction of `duration` backend sample periods (`dt`).
     pub fn delay(qubit: QubitId, duration: u64) -> Self {
+        Self::delay_with_unit([qubit], duration, TimeUnit::Dt)
+    }
+
+    /// Create a delay instruction on several qubits in the given unit.
+    pub fn delay_with_unit(
+        qubits: impl IntoIterator<Item = QubitId>,
+        duration: u64,
+        unit: TimeUnit,
+    ) -> Self {
         Self {
-            kind: InstructionKind::Delay { duration },
-            qubits: vec![qubit],
+            kind: InstructionKind::Delay { duration, unit },
+            qubits: qubits.into_iter().collect(),
             clbits: vec![],
+            annotations: Annotations::new(),
         }
     }
 
@@ -115,9 +138,20 @@ impl Instruction {
             kind: InstructionKind::Shuttle { from_zone, to_zone },
             qubits: vec![qubit],
             clbits: vec![],
+            annotations: Annotations::new(),
         }
     }
 
+    /// Return this instruction with `key` annotated as `value`.
+    pub fn with_annotation(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<AnnotationValue>,
+    ) -> Self {
+        self.annotations.insert(key, value);
+        self
+    }
+
     /// Check if this is a shuttle instruction.
     pub fn is_shuttle(&self) -> bool {
         matches!(self.kind, InstructionKind::Shuttle { .. })
@@ -143,6 +177,22 @@ impl Instruction {
         matches!(self.kind, InstructionKind::Barrier)
     }
 
+    /// Check if this is a delay.
+    pub fn is_delay(&self) -> bool {
+        matches!(self.kind, InstructionKind::Delay { .. })
+    }
+
+    /// Length of a delay in nanoseconds.
+    ///
+    /// Returns `None` if this is not a delay, or if it is given in `dt` and
+    /// the sample period `dt_ns` is unknown.
+    pub fn delay_ns(&self, dt_ns: Option<f64>) -> Option<f64> {
+        match self.kind {
+            InstructionKind::Delay { duration, unit } => unit.to_ns(duration, dt_ns),
+            _ => None,
+        }
+    }
+
     /// Get the gate if this is a gate instruction.
     pub fn as_gate(&self) -> Option<&Gate> {
         match &self.kind {
@@ -159,6 +209,45 @@ impl Instruction {
         }
     }
 
+    /// Check whether this instruction commutes with another.
+    ///
+    /// This is a conservative check based on [`Gate::qubit_axes`]: two
+    /// instructions commute if they act on disjoint qubits and classical bits,
+    /// or if both are gates with the same axis on every shared qubit. A
+    /// classically conditioned gate never commutes with an instruction that
+    /// writes classical bits. `false` means "not known to commute".
+    pub fn commutes_with(&self, other: &Instruction) -> bool {
+        let conditioned =
+            |inst: &Instruction| inst.as_gate().is_some_and(|g| g.condition.is_some());
+        if (conditioned(self) && !other.clbits.is_empty())
+            || (conditioned(other) && !self.clbits.is_empty())
+        {
+            return false;
+        }
+        if self.clbits.iter().any(|c| other.clbits.contains(c)) {
+            return false;
+        }
+
+        let shared: Vec<_> = self
+            .qubits
+            .iter()
+            .enumerate()
+            .filter_map(|(i, q)| other.qubits.iter().position(|o| o == q).map(|j| (i, j)))
+            .collect();
+        if shared.is_empty() {
+            return true;
+        }
+
+        let (Some(a), Some(b)) = (self.as_gate(), other.as_gate()) else {
+            return false;
+        };
+        let (a, b) = (a.qubit_axes(), b.qubit_axes());
+        shared.iter().all(|&(i, j)| match (a.get(i), b.get(j)) {
+            (Some(Some(x)), Some(Some(y))) => x == y,
+            _ => false,
+        })
+    }
+
     /// Get the name of the instruction.
     pub fn name(&self) -> &str {
         match &self.kind {
@@ -175,6 +264,7 @@ impl Instruction {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parameter::ParameterExpression;
 
     #[test]
     fn test_gate_instruction() {
@@ -199,6 +289,71 @@ mod tests {
         assert_eq!(inst.qubits.len(), 3);
     }
 
+    #[test]
+    fn test_delay_instruction() {
+        let inst = Instruction::delay(QubitId(0), 160);
+        assert!(inst.is_delay());
+        assert_eq!(inst.name(), "delay");
+        assert_eq!(inst.delay_ns(None), None);
+        assert_eq!(inst.delay_ns(Some(0.5)), Some(80.0));
+
+        let inst = Instruction::delay_with_unit([QubitId(0), QubitId(1)], 100, TimeUnit::Ns);
+        assert_eq!(inst.qubits.len(), 2);
+        assert_eq!(inst.delay_ns(None), Some(100.0));
+        assert!(!inst.commutes_with(&Instruction::single_qubit_gate(StandardGate::X, QubitId(1))));
+        assert_eq!(Instruction::barrier([QubitId(0)]).delay_ns(Some(1.0)), None);
+
+        // Delays serialized before units existed are in dt.
+        let old: Instruction =
+            serde_json::from_str(r#"{"kind":{"Delay":{"duration":5}},"qubits":[0],"clbits":[]}"#)
+                .unwrap();
+        assert_eq!(old, Instruction::delay(QubitId(0), 5));
+    }
+
+    #[test]
+    fn test_annotations_serde() {
+        let plain = Instruction::single_qubit_gate(StandardGate::X, QubitId(0));
+        let json = serde_json::to_string(&plain).unwrap();
+        assert!(!json.contains("annotations"));
+
+        let annotated = plain.clone().with_annotation(Annotations::NOISE_SCALE, 3.0);
+        assert_ne!(annotated, plain);
+        let json = serde_json::to_string(&annotated).unwrap();
+        assert!(json.contains(r#""annotations":{"noise_scale":3.0}"#));
+        let back: Instruction = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.annotations.noise_scale(), Some(3.0));
+        assert_eq!(back, annotated);
+    }
+
+    #[test]
+    fn test_commutes_with() {
+        let q = QubitId;
+        let cx = |c, t| Instruction::two_qubit_gate(StandardGate::CX, q(c), q(t));
+        let rz = |i| {
+            Instruction::single_qubit_gate(
+                StandardGate::Rz(ParameterExpression::constant(0.3)),
+                q(i),
+            )
+        };
+
+        // Shared target, shared control, and Z rotation on the control.
+        assert!(cx(0, 2).commutes_with(&cx(1, 2)));
+        assert!(cx(0, 1).commutes_with(&cx(0, 2)));
+        assert!(cx(0, 1).commutes_with(&rz(0)));
+        // Target of one is the control of the other.
+        assert!(!cx(0, 1).commutes_with(&cx(1, 2)));
+        assert!(!cx(0, 1).commutes_with(&rz(1)));
+        // Disjoint operations always commute.
+        assert!(cx(0, 1).commutes_with(&Instruction::measure(q(2), ClbitId(0))));
+        // Non-gate operations on a shared qubit never do.
+        assert!(!rz(0).commutes_with(&Instruction::measure(q(0), ClbitId(0))));
+        assert!(!rz(0).commutes_with(&Instruction::barrier([q(0), q(1)])));
+        assert!(
+            !Instruction::measure(q(0), ClbitId(0))
+                .commutes_with(&Instruction::measure(q(1), ClbitId(0)))
+        );
+    }
+
     #[test]
     fn test_shuttle_instruction() {
         let inst = Instruction::shuttle(QubitId(0), 0, 1);
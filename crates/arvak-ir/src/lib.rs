@@ -13,12 +13,19 @@
 //!
 //! - **Qubits and Classical Bits**: [`QubitId`], [`ClbitId`] for addressing quantum
 //!   and classical registers
-//! - **Gates**: [`StandardGate`] for built-in gates (H, X, CX, etc.) and [`CustomGate`]
-//!   for user-defined operations
+//! - **Gates**: [`StandardGate`] for built-in gates (H, X, CX, etc.) with their unitary
+//!   matrices, and [`CustomGate`] for user-defined operations
 //! - **Parameters**: [`ParameterExpression`] for symbolic parameters in variational circuits
 //! - **Instructions**: [`Instruction`] combining gates with their operands
+//! - **Annotations**: [`Annotations`] for pass-specific key/value metadata on instructions
+//! - **Timing**: [`TimeUnit`] for delay durations in backend samples (`dt`) or nanoseconds
 //! - **DAG**: [`CircuitDag`] for the internal graph representation
+//! - **Coupling maps**: [`CouplingMap`] for device connectivity and qubit interaction graphs
 //! - **Circuit**: [`Circuit`] high-level builder API
+//! - **Stable hashing**: [`hash::fnv1a`] for hashes that are stored or compared
+//!   across processes
+//! - **Random circuits**: [`Circuit::random`] and, with the `proptest` feature,
+//!   the `strategy` module for property tests
 //!
 //! # Example: Building a Bell State
 //!
@@ -73,18 +80,29 @@
 //! | `Swap` | 2 | SWAP gate |
 //! | `CCX` | 3 | Toffoli (CCNOT) gate |
 
+pub mod annotation;
 pub mod circuit;
+pub mod coupling;
 pub mod dag;
 pub mod error;
 pub mod gate;
+pub mod hash;
 pub mod instruction;
+pub mod matrix;
 pub mod parameter;
 pub mod qubit;
+pub mod random;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+pub mod timing;
 
+pub use annotation::{AnnotationValue, Annotations};
 pub use circuit::Circuit;
+pub use coupling::CouplingMap;
 pub use dag::{CircuitDag, CircuitLevel, DagEdge, DagNode, NodeIndex, WireId};
 pub use error::{IrError, IrResult};
-pub use gate::{ClassicalCondition, CustomGate, Gate, GateKind, StandardGate};
+pub use gate::{ClassicalCondition, CustomGate, Gate, GateKind, PauliAxis, StandardGate};
 pub use instruction::{Instruction, InstructionKind};
 pub use parameter::ParameterExpression;
 pub use qubit::{Clbit, ClbitId, Qubit, QubitId};
+pub use timing::TimeUnit;
@@ -0,0 +1,268 @@
+//! Unitary matrices of the standard gates.
+//!
+//! Matrices are row-major `2^n × 2^n` vectors. The first qubit of a gate is
+//! the most significant bit of the basis index, so `CX` has the textbook
+//! matrix with its control first. Simulators indexing amplitudes by
+//! little-endian qubit position must reverse the order when applying them.
+
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use num_complex::Complex64;
+
+use crate::gate::StandardGate;
+
+impl StandardGate {
+    /// The unitary matrix of this gate.
+    ///
+    /// Returns `None` if any parameter is symbolic.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use arvak_ir::StandardGate;
+    ///
+    /// let cx = StandardGate::CX.matrix().unwrap();
+    /// assert_eq!(cx.len(), 16);
+    /// assert_eq!(cx[2 * 4 + 3].re, 1.0); // |10⟩ → |11⟩
+    /// ```
+    pub fn matrix(&self) -> Option<Vec<Complex64>> {
+        let angle = |p: &crate::parameter::ParameterExpression| p.as_f64();
+        let x = [c(0.0), c(1.0), c(1.0), c(0.0)];
+        let y = [c(0.0), -I, I, c(0.0)];
+        let z = [c(1.0), c(0.0), c(0.0), c(-1.0)];
+        let sx = [
+            Complex64::new(0.5, 0.5),
+            Complex64::new(0.5, -0.5),
+            Complex64::new(0.5, -0.5),
+            Complex64::new(0.5, 0.5),
+        ];
+
+        Some(match self {
+            StandardGate::I => identity(1),
+            StandardGate::X => x.to_vec(),
+            StandardGate::Y => y.to_vec(),
+            StandardGate::Z => z.to_vec(),
+            StandardGate::H => [1.0, 1.0, 1.0, -1.0].map(|v| c(v * FRAC_1_SQRT_2)).to_vec(),
+            StandardGate::S => phase(std::f64::consts::FRAC_PI_2),
+            StandardGate::Sdg => phase(-std::f64::consts::FRAC_PI_2),
+            StandardGate::T => phase(std::f64::consts::FRAC_PI_4),
+            StandardGate::Tdg => phase(-std::f64::consts::FRAC_PI_4),
+            StandardGate::SX => sx.to_vec(),
+            StandardGate::SXdg => sx.map(|v| v.conj()).to_vec(),
+            StandardGate::Rx(t) => pauli_rotation(angle(t)?, &x),
+            StandardGate::Ry(t) => pauli_rotation(angle(t)?, &y),
+            StandardGate::Rz(t) => pauli_rotation(angle(t)?, &z),
+            StandardGate::P(l) => phase(angle(l)?),
+            StandardGate::U(t, p, l) => u(angle(t)?, angle(p)?, angle(l)?),
+            StandardGate::PRX(t, p) => {
+                let (t, p) = (angle(t)?, angle(p)?);
+                u(
+                    t,
+                    p - std::f64::consts::FRAC_PI_2,
+                    std::f64::consts::FRAC_PI_2 - p,
+                )
+            }
+
+            StandardGate::CX => controlled(&x),
+            StandardGate::CY => controlled(&y),
+            StandardGate::CZ => controlled(&z),
+            StandardGate::CH => controlled(&StandardGate::H.matrix()?),
+            StandardGate::CSX => controlled(&sx),
+            StandardGate::CRx(t) => controlled(&pauli_rotation(angle(t)?, &x)),
+            StandardGate::CRy(t) => controlled(&pauli_rotation(angle(t)?, &y)),
+            StandardGate::CRz(t) => controlled(&pauli_rotation(angle(t)?, &z)),
+            StandardGate::CP(l) => controlled(&phase(angle(l)?)),
+            StandardGate::Swap => permutation(&[0, 2, 1, 3]),
+            StandardGate::ISwap => {
+                let mut m = permutation(&[0, 2, 1, 3]);
+                m[6] = I;
+                m[9] = I;
+                m
+            }
+            StandardGate::RXX(t) => pauli_rotation(angle(t)?, &kron(&x, &x)),
+            StandardGate::RYY(t) => pauli_rotation(angle(t)?, &kron(&y, &y)),
+            StandardGate::RZZ(t) => pauli_rotation(angle(t)?, &kron(&z, &z)),
+            StandardGate::RZX(t) => pauli_rotation(angle(t)?, &kron(&z, &x)),
+            // (X⊗I − Y⊗X) / √2
+            StandardGate::ECR => {
+                let xi = kron(&x, &identity(1));
+                let yx = kron(&y, &x);
+                xi.iter()
+                    .zip(&yx)
+                    .map(|(a, b)| (a - b) * FRAC_1_SQRT_2)
+                    .collect()
+            }
+
+            StandardGate::CCX => controlled(&controlled(&x)),
+            StandardGate::CCZ => controlled(&controlled(&z)),
+            StandardGate::CSwap => permutation(&[0, 1, 2, 3, 4, 6, 5, 7]),
+        })
+    }
+}
+
+const I: Complex64 = Complex64::new(0.0, 1.0);
+
+fn c(re: f64) -> Complex64 {
+    Complex64::new(re, 0.0)
+}
+
+fn dim(m: &[Complex64]) -> usize {
+    (m.len() as f64).sqrt() as usize
+}
+
+fn identity(num_qubits: u32) -> Vec<Complex64> {
+    permutation(&(0..1 << num_qubits).collect::<Vec<_>>())
+}
+
+/// Matrix mapping basis state `i` to `perm[i]`.
+fn permutation(perm: &[usize]) -> Vec<Complex64> {
+    let n = perm.len();
+    let mut m = vec![c(0.0); n * n];
+    for (col, &row) in perm.iter().enumerate() {
+        m[row * n + col] = c(1.0);
+    }
+    m
+}
+
+fn phase(lambda: f64) -> Vec<Complex64> {
+    vec![c(1.0), c(0.0), c(0.0), Complex64::from_polar(1.0, lambda)]
+}
+
+fn u(theta: f64, phi: f64, lambda: f64) -> Vec<Complex64> {
+    let (s, co) = (theta / 2.0).sin_cos();
+    vec![
+        c(co),
+        -Complex64::from_polar(s, lambda),
+        Complex64::from_polar(s, phi),
+        Complex64::from_polar(co, phi + lambda),
+    ]
+}
+
+/// `exp(-iθ/2 · P)` for a Pauli string `P`.
+fn pauli_rotation(theta: f64, pauli: &[Complex64]) -> Vec<Complex64> {
+    let (s, co) = (theta / 2.0).sin_cos();
+    identity(dim(pauli).trailing_zeros())
+        .iter()
+        .zip(pauli)
+        .map(|(id, p)| id * co - I * s * p)
+        .collect()
+}
+
+fn kron(a: &[Complex64], b: &[Complex64]) -> Vec<Complex64> {
+    let (na, nb) = (dim(a), dim(b));
+    let n = na * nb;
+    let mut m = vec![c(0.0); n * n];
+    for row in 0..n {
+        for col in 0..n {
+            m[row * n + col] = a[(row / nb) * na + col / nb] * b[(row % nb) * nb + col % nb];
+        }
+    }
+    m
+}
+
+/// Add a control qubit in front of `target`.
+fn controlled(target: &[Complex64]) -> Vec<Complex64> {
+    let nt = dim(target);
+    let n = 2 * nt;
+    let mut m = identity(n.trailing_zeros());
+    for row in 0..nt {
+        for col in 0..nt {
+            m[(nt + row) * n + nt + col] = target[row * nt + col];
+        }
+    }
+    m
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::standard_gates;
+
+    fn mul(a: &[Complex64], b: &[Complex64]) -> Vec<Complex64> {
+        let n = dim(a);
+        (0..n * n)
+            .map(|i| (0..n).map(|k| a[i / n * n + k] * b[k * n + i % n]).sum())
+            .collect()
+    }
+
+    fn dagger(a: &[Complex64]) -> Vec<Complex64> {
+        let n = dim(a);
+        (0..n * n).map(|i| a[i % n * n + i / n].conj()).collect()
+    }
+
+    fn assert_close(a: &[Complex64], b: &[Complex64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).norm() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_matrices_are_unitary() {
+        let gates = standard_gates();
+        let random = (0..4).flat_map(|seed| {
+            let circuit = crate::Circuit::random(3, 8, &gates, seed).unwrap();
+            circuit
+                .dag()
+                .topological_ops()
+                .filter_map(|(_, inst)| match &inst.as_gate()?.kind {
+                    crate::GateKind::Standard(g) => Some(g.clone()),
+                    crate::GateKind::Custom(_) => None,
+                })
+                .collect::<Vec<_>>()
+        });
+        for gate in gates.iter().cloned().chain(random) {
+            let m = gate.matrix().unwrap();
+            assert_eq!(m.len(), 1 << (2 * gate.num_qubits()), "{:?}", gate);
+            assert_close(&mul(&m, &dagger(&m)), &identity(gate.num_qubits()));
+        }
+    }
+
+    #[test]
+    fn test_known_relations() {
+        let m = |g: StandardGate| g.matrix().unwrap();
+        // SX² = X, S² = Z, T² = S.
+        assert_close(
+            &mul(&m(StandardGate::SX), &m(StandardGate::SX)),
+            &m(StandardGate::X),
+        );
+        assert_close(
+            &mul(&m(StandardGate::S), &m(StandardGate::S)),
+            &m(StandardGate::Z),
+        );
+        assert_close(
+            &mul(&m(StandardGate::T), &m(StandardGate::T)),
+            &m(StandardGate::S),
+        );
+        // CSX² = CX.
+        assert_close(
+            &mul(&m(StandardGate::CSX), &m(StandardGate::CSX)),
+            &m(StandardGate::CX),
+        );
+
+        // ECR = RZX(-π/4) · (X⊗I) · RZX(π/4).
+        let quarter = std::f64::consts::FRAC_PI_4;
+        let xi = kron(&m(StandardGate::X), &identity(1));
+        let ecr = mul(
+            &m(StandardGate::RZX((-quarter).into())),
+            &mul(&xi, &m(StandardGate::RZX(quarter.into()))),
+        );
+        assert_close(&ecr, &m(StandardGate::ECR));
+
+        // PRX(θ, φ) = RZ(φ) · RX(θ) · RZ(-φ), up to a global phase of 1.
+        let prx = mul(
+            &m(StandardGate::Rz(0.4.into())),
+            &mul(
+                &m(StandardGate::Rx(1.3.into())),
+                &m(StandardGate::Rz((-0.4).into())),
+            ),
+        );
+        assert_close(&prx, &m(StandardGate::PRX(1.3.into(), 0.4.into())));
+
+        assert!(
+            StandardGate::Rz(crate::ParameterExpression::symbol("t"))
+                .matrix()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Random circuit generation for testing and fuzzing.
+//!
+//! [`Circuit::random`] builds a layered circuit from a gate set using a
+//! seeded generator, so a failing property test can be reproduced from its
+//! parameters alone. With the `proptest` feature, the `strategy` module wraps
+//! it in proptest strategies.
+
+use std::f64::consts::PI;
+
+use crate::circuit::Circuit;
+use crate::error::{IrError, IrResult};
+use crate::gate::StandardGate;
+use crate::parameter::ParameterExpression;
+use crate::qubit::QubitId;
+
+impl Circuit {
+    /// Generate a random circuit of `depth` layers.
+    ///
+    /// Each layer shuffles the qubits and applies randomly chosen gates from
+    /// `gate_set` to disjoint qubits until no gate fits the remaining ones,
+    /// so with a single-qubit gate in the set every qubit is used in every
+    /// layer. Parameters of the chosen gates are replaced by random angles
+    /// in `[-π, π)`. The same arguments always produce the same circuit.
+    ///
+    /// Fails with [`IrError::InvalidGateSet`] if no gate in the set fits
+    /// `num_qubits`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use arvak_ir::{Circuit, random};
+    ///
+    /// let circuit = Circuit::random(4, 10, &random::clifford_t_gates(), 42).unwrap();
+    /// assert_eq!(circuit.num_qubits(), 4);
+    /// assert_eq!(circuit.depth(), 10);
+    /// ```
+    pub fn random(
+        num_qubits: u32,
+        depth: u32,
+        gate_set: &[StandardGate],
+        seed: u64,
+    ) -> IrResult<Self> {
+        if !gate_set.iter().any(|g| g.num_qubits() <= num_qubits) {
+            return Err(IrError::InvalidGateSet(format!(
+                "no gate acts on at most {} qubits",
+                num_qubits
+            )));
+        }
+
+        let mut rng = SplitMix64(seed);
+        let mut circuit = Circuit::with_size(format!("random_{}", seed), num_qubits, 0);
+        let mut free: Vec<QubitId> = Vec::with_capacity(num_qubits as usize);
+
+        for _ in 0..depth {
+            free.clear();
+            free.extend((0..num_qubits).map(QubitId));
+            rng.shuffle(&mut free);
+
+            loop {
+                let fitting: Vec<&StandardGate> = gate_set
+                    .iter()
+                    .filter(|g| g.num_qubits() as usize <= free.len())
+                    .collect();
+                if fitting.is_empty() {
+                    break;
+                }
+                let gate = randomize_params(fitting[rng.below(fitting.len())], &mut rng);
+                let qubits = free.split_off(free.len() - gate.num_qubits() as usize);
+                circuit.gate(gate, qubits)?;
+            }
+        }
+
+        Ok(circuit)
+    }
+}
+
+/// Every standard gate, with zero placeholder parameters.
+pub fn standard_gates() -> Vec<StandardGate> {
+    let zero = || ParameterExpression::constant(0.0);
+    vec![
+        StandardGate::I,
+        StandardGate::X,
+        StandardGate::Y,
+        StandardGate::Z,
+        StandardGate::H,
+        StandardGate::S,
+        StandardGate::Sdg,
+        StandardGate::T,
+        StandardGate::Tdg,
+        StandardGate::SX,
+        StandardGate::SXdg,
+        StandardGate::Rx(zero()),
+        StandardGate::Ry(zero()),
+        StandardGate::Rz(zero()),
+        StandardGate::P(zero()),
+        StandardGate::U(zero(), zero(), zero()),
+        StandardGate::CX,
+        StandardGate::CY,
+        StandardGate::CZ,
+        StandardGate::CH,
+        StandardGate::Swap,
+        StandardGate::ISwap,
+        StandardGate::ECR,
+        StandardGate::CSX,
+        StandardGate::CRx(zero()),
+        StandardGate::CRy(zero()),
+        StandardGate::CRz(zero()),
+        StandardGate::CP(zero()),
+        StandardGate::RXX(zero()),
+        StandardGate::RYY(zero()),
+        StandardGate::RZZ(zero()),
+        StandardGate::RZX(zero()),
+        StandardGate::CCX,
+        StandardGate::CSwap,
+        StandardGate::CCZ,
+        StandardGate::PRX(zero(), zero()),
+    ]
+}
+
+/// The Clifford+T gate set: H, S, S†, T, T† and CX.
+pub fn clifford_t_gates() -> Vec<StandardGate> {
+    vec![
+        StandardGate::H,
+        StandardGate::S,
+        StandardGate::Sdg,
+        StandardGate::T,
+        StandardGate::Tdg,
+        StandardGate::CX,
+    ]
+}
+
+/// Copy of `gate` with every parameter replaced by a random angle.
+fn randomize_params(gate: &StandardGate, rng: &mut SplitMix64) -> StandardGate {
+    let mut angle = || ParameterExpression::constant(rng.angle());
+    match gate {
+        StandardGate::Rx(_) => StandardGate::Rx(angle()),
+        StandardGate::Ry(_) => StandardGate::Ry(angle()),
+        StandardGate::Rz(_) => StandardGate::Rz(angle()),
+        StandardGate::P(_) => StandardGate::P(angle()),
+        StandardGate::U(..) => StandardGate::U(angle(), angle(), angle()),
+        StandardGate::CRx(_) => StandardGate::CRx(angle()),
+        StandardGate::CRy(_) => StandardGate::CRy(angle()),
+        StandardGate::CRz(_) => StandardGate::CRz(angle()),
+        StandardGate::CP(_) => StandardGate::CP(angle()),
+        StandardGate::RXX(_) => StandardGate::RXX(angle()),
+        StandardGate::RYY(_) => StandardGate::RYY(angle()),
+        StandardGate::RZZ(_) => StandardGate::RZZ(angle()),
+        StandardGate::RZX(_) => StandardGate::RZX(angle()),
+        StandardGate::PRX(..) => StandardGate::PRX(angle(), angle()),
+        other => other.clone(),
+    }
+}
+
+/// SplitMix64 generator; small, seedable and stable across releases.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index below `n`.
+    fn below(&mut self, n: usize) -> usize {
+        ((u128::from(self.next_u64()) * n as u128) >> 64) as usize
+    }
+
+    /// Uniform angle in `[-π, π)`.
+    fn angle(&mut self) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        (2.0 * unit - 1.0) * PI
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ops(circuit: &Circuit) -> Vec<(String, Vec<u32>, Vec<Option<f64>>)> {
+        circuit
+            .dag()
+            .topological_ops()
+            .map(|(_, inst)| {
+                let params = inst
+                    .as_gate()
+                    .map(|g| match &g.kind {
+                        crate::gate::GateKind::Standard(s) => {
+                            s.parameters().iter().map(|p| p.as_f64()).collect()
+                        }
+                        crate::gate::GateKind::Custom(_) => vec![],
+                    })
+                    .unwrap_or_default();
+                (
+                    inst.name().to_string(),
+                    inst.qubits.iter().map(|q| q.0).collect(),
+                    params,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_random_is_deterministic() {
+        let gates = standard_gates();
+        let a = Circuit::random(5, 8, &gates, 7).unwrap();
+        let b = Circuit::random(5, 8, &gates, 7).unwrap();
+        let c = Circuit::random(5, 8, &gates, 8).unwrap();
+        assert_eq!(ops(&a), ops(&b));
+        assert_ne!(ops(&a), ops(&c));
+        assert!(a.depth() <= 8);
+    }
+
+    #[test]
+    fn test_random_fills_layers() {
+        let circuit = Circuit::random(3, 6, &clifford_t_gates(), 1).unwrap();
+        assert_eq!(circuit.depth(), 6);
+        assert_eq!(
+            circuit.dag().num_ops(),
+            18 - ops(&circuit)
+                .iter()
+                .filter(|(_, q, _)| q.len() == 2)
+                .count()
+        );
+
+        let params = ops(&Circuit::random(
+            2,
+            4,
+            &[StandardGate::Rz(ParameterExpression::constant(0.0))],
+            3,
+        )
+        .unwrap());
+        assert!(
+            params
+                .iter()
+                .all(|(_, _, p)| p[0].is_some_and(|v| (-PI..PI).contains(&v) && v != 0.0))
+        );
+    }
+
+    #[test]
+    fn test_random_rejects_unusable_gate_set() {
+        assert!(matches!(
+            Circuit::random(2, 3, &[StandardGate::CCX], 0),
+            Err(IrError::InvalidGateSet(_))
+        ));
+        assert!(Circuit::random(2, 3, &[], 0).is_err());
+
+        // Gates wider than the circuit are skipped when others fit.
+        let circuit = Circuit::random(2, 3, &[StandardGate::CCX, StandardGate::CZ], 0).unwrap();
+        assert_eq!(circuit.dag().num_ops(), 3);
+    }
+}
@@ -0,0 +1,61 @@
+//! Proptest strategies for circuits.
+//!
+//! Available with the `proptest` feature. Circuits are drawn through
+//! [`Circuit::random`], so a strategy only chooses the size and the seed;
+//! shrinking reduces the qubit count, the depth and the seed.
+//!
+//! ```rust
+//! use arvak_ir::strategy;
+//! use proptest::test_runner::TestRunner;
+//!
+//! TestRunner::default()
+//!     .run(&strategy::circuit(4, 6), |circuit| {
+//!         assert!(circuit.depth() <= 6);
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use proptest::prelude::*;
+
+use crate::circuit::Circuit;
+use crate::gate::StandardGate;
+use crate::random::standard_gates;
+
+/// Random circuits over every standard gate, with 1 to `max_qubits` qubits
+/// and up to `max_depth` layers.
+pub fn circuit(max_qubits: u32, max_depth: u32) -> impl Strategy<Value = Circuit> {
+    circuit_with_gates(standard_gates(), max_qubits, max_depth)
+}
+
+/// Random circuits over `gate_set`.
+///
+/// The qubit count starts at the width of the narrowest gate in the set.
+///
+/// # Panics
+///
+/// Panics if `gate_set` is empty or its narrowest gate is wider than
+/// `max_qubits`.
+pub fn circuit_with_gates(
+    gate_set: Vec<StandardGate>,
+    max_qubits: u32,
+    max_depth: u32,
+) -> impl Strategy<Value = Circuit> {
+    let min_qubits = gate_set
+        .iter()
+        .map(StandardGate::num_qubits)
+        .min()
+        .expect("gate set must not be empty");
+    assert!(
+        min_qubits <= max_qubits,
+        "gate set needs at least {} qubits",
+        min_qubits
+    );
+
+    (min_qubits..=max_qubits, 0..=max_depth, any::<u64>()).prop_map(
+        move |(num_qubits, depth, seed)| {
+            Circuit::random(num_qubits, depth, &gate_set, seed)
+                .expect("gate set fits the qubit count")
+        },
+    )
+}
@@ -0,0 +1,79 @@
+//! Duration units for timed instructions.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Unit of an instruction duration.
+///
+/// `Dt` is the sample period of the target backend's control electronics,
+/// so its length in seconds is only known once a backend is chosen. `Ns` is
+/// absolute time. OpenQASM 3 durations in `us`, `ms` and `s` are stored in
+/// nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeUnit {
+    /// Backend sample periods.
+    #[default]
+    Dt,
+    /// Nanoseconds.
+    Ns,
+}
+
+impl TimeUnit {
+    /// The OpenQASM 3 suffix of this unit.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            TimeUnit::Dt => "dt",
+            TimeUnit::Ns => "ns",
+        }
+    }
+
+    /// Convert `value` in this unit to nanoseconds.
+    ///
+    /// Returns `None` for `Dt` when the sample period `dt_ns` is unknown.
+    pub fn to_ns(self, value: u64, dt_ns: Option<f64>) -> Option<f64> {
+        match self {
+            TimeUnit::Dt => dt_ns.map(|dt| value as f64 * dt),
+            TimeUnit::Ns => Some(value as f64),
+        }
+    }
+
+    /// Convert `value` in this unit to sample periods of `dt_ns`
+    /// nanoseconds, rounding to the nearest whole sample.
+    pub fn to_dt(self, value: u64, dt_ns: f64) -> u64 {
+        match self {
+            TimeUnit::Dt => value,
+            TimeUnit::Ns => (value as f64 / dt_ns).round() as u64,
+        }
+    }
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.suffix())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_unit_conversion() {
+        assert_eq!(TimeUnit::Ns.to_ns(100, None), Some(100.0));
+        assert_eq!(TimeUnit::Dt.to_ns(100, None), None);
+        assert_eq!(TimeUnit::Dt.to_ns(100, Some(0.5)), Some(50.0));
+
+        assert_eq!(TimeUnit::Dt.to_dt(160, 0.222), 160);
+        assert_eq!(TimeUnit::Ns.to_dt(100, 0.222), 450);
+        assert_eq!(TimeUnit::default(), TimeUnit::Dt);
+    }
+
+    #[test]
+    fn test_time_unit_serde() {
+        assert_eq!(serde_json::to_string(&TimeUnit::Ns).unwrap(), "\"ns\"");
+        let unit: TimeUnit = serde_json::from_str("\"dt\"").unwrap();
+        assert_eq!(unit, TimeUnit::Dt);
+        assert_eq!(TimeUnit::Ns.to_string(), "ns");
+    }
+}
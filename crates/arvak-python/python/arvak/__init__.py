@@ -31,11 +31,26 @@ from arvak._native import (
     CouplingMap,
     BasisGates,
     PropertySet,
+    transpile,
+    # Result analysis
+    Counts,
+    # Backends
+    Backend,
+    list_backends,
+    get_backend,
+    register_backend,
+    # Scheduling
+    Scheduler,
+    Session,
+    Batch,
     # QASM I/O
     from_qasm,
     to_qasm,
 )
 
+# Execution traces from the simulator
+from arvak.trace import ExecutionTrace, load_trace
+
 # Import integration registry
 from arvak.integrations import IntegrationRegistry
 
@@ -140,9 +155,24 @@ __all__ = [
     "CouplingMap",
     "BasisGates",
     "PropertySet",
+    "transpile",
+    # Result analysis
+    "Counts",
+    # Backends
+    "Backend",
+    "list_backends",
+    "get_backend",
+    "register_backend",
+    # Scheduling
+    "Scheduler",
+    "Session",
+    "Batch",
     # QASM I/O
     "from_qasm",
     "to_qasm",
+    # Execution traces
+    "ExecutionTrace",
+    "load_trace",
     # Integration API
     "list_integrations",
     "integration_status",
@@ -1,6 +1,6 @@
 """Type stubs for Arvak Python bindings."""
 
-from typing import List, Optional, Union
+from typing import Any, Dict, List, Optional, Tuple, Union
 
 class QubitId:
     """Unique identifier for a qubit within a circuit."""
@@ -92,7 +92,8 @@ class Circuit:
     def measure_all(self) -> Circuit: ...
     def reset(self, qubit: QubitArg) -> Circuit: ...
     def barrier_all(self) -> Circuit: ...
-    def delay(self, qubit: QubitArg, duration: int) -> Circuit: ...
+    def snapshot(self, label: str) -> Circuit: ...
+    def delay(self, qubit: QubitArg, duration: int, unit: str = "dt") -> Circuit: ...
 
     # Pre-built circuits
     @staticmethod
@@ -101,6 +102,18 @@ class Circuit:
     def ghz(n: int) -> Circuit: ...
     @staticmethod
     def qft(n: int) -> Circuit: ...
+
+    # Bulk construction
+    OPCODES: List[str]
+    @staticmethod
+    def from_arrays(
+        name: str,
+        num_qubits: int,
+        opcodes: Any,
+        qubits: Any,
+        params: Optional[Any] = None,
+        num_clbits: int = 0,
+    ) -> Circuit: ...
     def __repr__(self) -> str: ...
     def __str__(self) -> str: ...
 
@@ -164,6 +177,192 @@ class PropertySet:
     ) -> PropertySet: ...
     def __repr__(self) -> str: ...
 
+class Counts:
+    """Measurement counts; qubit 0 is the rightmost bit."""
+
+    @property
+    def shots(self) -> int: ...
+    def __init__(self, counts: Optional[Dict[str, int]] = None) -> None: ...
+    def get(self, bitstring: str) -> int: ...
+    def most_frequent(self) -> Optional[Tuple[str, int]]: ...
+    def to_dict(self) -> Dict[str, int]: ...
+    def probabilities(self) -> Dict[str, float]: ...
+    def marginal(self, qubits: List[int]) -> Counts: ...
+    def tvd(self, other: Counts) -> float: ...
+    def hellinger(self, other: Counts) -> float: ...
+    def expectation(self, pauli: str) -> float: ...
+    def to_dataframe(self) -> Any: ...
+    def __getitem__(self, bitstring: str) -> int: ...
+    def __contains__(self, bitstring: str) -> bool: ...
+    def __len__(self) -> int: ...
+    def __eq__(self, other: Counts) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class Backend:
+    """A quantum backend and the target it compiles for."""
+
+    @property
+    def name(self) -> str: ...
+    @property
+    def num_qubits(self) -> int: ...
+    @property
+    def coupling_map(self) -> List[Tuple[int, int]]: ...
+    @property
+    def basis_gates(self) -> List[str]: ...
+    @property
+    def max_shots(self) -> int: ...
+    @property
+    def is_simulator(self) -> bool: ...
+    @property
+    def features(self) -> List[str]: ...
+    @property
+    def calibration(self) -> Optional[Dict[str, float]]: ...
+    @property
+    def queue_depth(self) -> Optional[int]: ...
+    def __init__(
+        self,
+        name: str,
+        num_qubits: int,
+        coupling_map: Optional[List[Tuple[int, int]]] = None,
+        basis_gates: Optional[List[str]] = None,
+        max_shots: int = 100000,
+        is_simulator: bool = False,
+        features: Optional[List[str]] = None,
+        calibration: Optional[Dict[str, float]] = None,
+        queue_depth: Optional[int] = None,
+    ) -> None: ...
+    @staticmethod
+    def simulator(num_qubits: int = 20) -> Backend: ...
+    @staticmethod
+    def iqm(name: str = "garnet", num_qubits: int = 20) -> Backend: ...
+    @staticmethod
+    def ibm(name: str = "ibm_brisbane", num_qubits: int = 127) -> Backend: ...
+    @staticmethod
+    def neutral_atom(name: str, num_qubits: int, zones: int = 1) -> Backend: ...
+    @staticmethod
+    def from_json(json: str) -> Backend: ...
+    def to_json(self) -> str: ...
+    def to_coupling_map(self) -> CouplingMap: ...
+    def to_basis_gates(self) -> BasisGates: ...
+    def matches(
+        self,
+        circuit: Optional[Circuit] = None,
+        min_qubits: Optional[int] = None,
+        gates: Optional[List[str]] = None,
+        simulator: Optional[bool] = None,
+        features: Optional[List[str]] = None,
+    ) -> bool: ...
+    def __eq__(self, other: Backend) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class Scheduler:
+    """The HPC scheduler, submitting circuits through a batch system."""
+
+    def __init__(
+        self,
+        config: Optional[str] = None,
+        backends: Optional[List[Backend]] = None,
+        state_dir: Optional[str] = None,
+        mock: bool = False,
+    ) -> None: ...
+    def submit(
+        self,
+        circuit: Circuit,
+        shots: int = 1024,
+        name: Optional[str] = None,
+        backend: Optional[str] = None,
+    ) -> str: ...
+    def status(self, job_id: str) -> str: ...
+    def cancel(self, job_id: str) -> None: ...
+    def save_template(
+        self,
+        name: str,
+        circuit: Circuit,
+        shots: int = 1024,
+        labels: Optional[Dict[str, str]] = None,
+        description: Optional[str] = None,
+    ) -> None: ...
+    def templates(self) -> List[str]: ...
+    def delete_template(self, name: str) -> None: ...
+    def submit_template(
+        self,
+        template: str,
+        name: Optional[str] = None,
+        shots: Optional[int] = None,
+        labels: Optional[Dict[str, str]] = None,
+    ) -> str: ...
+    def session(
+        self, backend: str, max_time: Optional[Union[str, int]] = None
+    ) -> Session: ...
+    def batch(
+        self, name: str = "batch", shots: int = 1024, backend: Optional[str] = None
+    ) -> Batch: ...
+    def __repr__(self) -> str: ...
+
+class Session:
+    """A run of jobs pinned to one backend for a bounded time."""
+
+    @property
+    def id(self) -> str: ...
+    @property
+    def backend(self) -> str: ...
+    @property
+    def jobs(self) -> List[str]: ...
+    @property
+    def remaining(self) -> float: ...
+    @property
+    def is_active(self) -> bool: ...
+    def run(
+        self, circuit: Circuit, shots: int = 1024, name: Optional[str] = None
+    ) -> str: ...
+    def close(self) -> None: ...
+    def cancel(self) -> int: ...
+    def __enter__(self) -> Session: ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+    def __repr__(self) -> str: ...
+
+class Batch:
+    """Circuits collected for submission as a single batch job."""
+
+    @property
+    def job_id(self) -> Optional[str]: ...
+    def run(self, circuit: Circuit) -> int: ...
+    def submit(self) -> Optional[str]: ...
+    def __enter__(self) -> Batch: ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+def list_backends(
+    circuit: Optional[Circuit] = None,
+    min_qubits: Optional[int] = None,
+    gates: Optional[List[str]] = None,
+    simulator: Optional[bool] = None,
+    features: Optional[List[str]] = None,
+) -> List[Backend]:
+    """List known backends, optionally only those matching a filter."""
+    ...
+
+def get_backend(name: str) -> Backend:
+    """Get a known backend by name."""
+    ...
+
+def register_backend(backend: Backend) -> None:
+    """Register a backend, replacing any known backend of the same name."""
+    ...
+
+def transpile(
+    circuit: Circuit,
+    coupling_map: Optional[CouplingMap] = None,
+    basis_gates: Optional[BasisGates] = None,
+    optimization_level: int = 1,
+    preset: Optional[str] = None,
+    seed: Optional[int] = None,
+    target: Optional[Backend] = None,
+) -> Circuit:
+    """Compile a circuit for a target, optionally with a named pipeline preset."""
+    ...
+
 def from_qasm(qasm: str) -> Circuit:
     """Parse an OpenQASM 3 string into a Circuit."""
     ...
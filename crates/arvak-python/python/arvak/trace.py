@@ -0,0 +1,118 @@
+"""Reading gate-level execution traces written by the Arvak simulator.
+
+Run a circuit with tracing enabled, e.g.::
+
+    arvak run -i circuit.qasm --trace trace.json --trace-mode snapshots
+
+and inspect the result:
+
+    >>> trace = arvak.load_trace("trace.json")
+    >>> trace.first_deviation() is None
+    True
+    >>> trace.snapshot("superposed")
+    [(0.7071067811865475+0j), (0.7071067811865475+0j), 0j, 0j]
+
+Basis index bit ``k`` is the state of qubit ``k``.
+"""
+
+import json
+from dataclasses import dataclass, field
+from typing import Dict, List, Optional
+
+
+@dataclass
+class TraceStep:
+    """The state after one instruction."""
+
+    index: int
+    instruction: str
+    qubits: List[int]
+    norm: float
+    amplitudes: Dict[int, complex] = field(default_factory=dict)
+
+
+@dataclass
+class StateSnapshot:
+    """The full state at a marked barrier."""
+
+    label: str
+    index: int
+    amplitudes: List[complex]
+
+
+@dataclass
+class ExecutionTrace:
+    """A recorded gate-level trace of one shot."""
+
+    circuit: str
+    num_qubits: int
+    mode: str
+    steps: List[TraceStep]
+    snapshots: List[StateSnapshot]
+
+    def norms(self) -> List[float]:
+        """State norm after every instruction."""
+        return [step.norm for step in self.steps]
+
+    def amplitude_history(self, index: int) -> List[Optional[complex]]:
+        """Amplitude of basis state ``index`` after every instruction.
+
+        Steps that did not record the amplitude yield ``None``.
+        """
+        return [step.amplitudes.get(index) for step in self.steps]
+
+    def snapshot(self, label: str) -> Optional[List[complex]]:
+        """Full state captured at the barrier marked ``label``."""
+        for snap in self.snapshots:
+            if snap.label == label:
+                return snap.amplitudes
+        return None
+
+    def first_deviation(self, tolerance: float = 1e-9) -> Optional[TraceStep]:
+        """First step whose state norm deviates from 1 by more than ``tolerance``."""
+        for step in self.steps:
+            if abs(step.norm - 1.0) > tolerance:
+                return step
+        return None
+
+
+def _mode_name(mode) -> str:
+    # Modes serialize as "norms", "snapshots" or {"amplitudes": [...]}.
+    if isinstance(mode, dict):
+        return next(iter(mode))
+    return mode
+
+
+def parse_trace(data: dict) -> ExecutionTrace:
+    """Build an ExecutionTrace from its decoded JSON."""
+    steps = [
+        TraceStep(
+            index=s["index"],
+            instruction=s["instruction"],
+            qubits=s["qubits"],
+            norm=s["norm"],
+            amplitudes={i: complex(re, im) for i, re, im in s.get("amplitudes", [])},
+        )
+        for s in data["steps"]
+    ]
+    snapshots = [
+        StateSnapshot(
+            label=s["label"],
+            index=s["index"],
+            amplitudes=[complex(re, im) for re, im in s["amplitudes"]],
+        )
+        for s in data.get("snapshots", [])
+    ]
+    return ExecutionTrace(
+        circuit=data["circuit"],
+        num_qubits=data["num_qubits"],
+        mode=_mode_name(data["mode"]),
+        steps=steps,
+        snapshots=snapshots,
+    )
+
+
+def load_trace(path: str) -> ExecutionTrace:
+    """Load an execution trace from a JSON file."""
+    with open(path) as f:
+        return parse_trace(json.load(f))
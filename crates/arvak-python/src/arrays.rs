@@ -0,0 +1,284 @@
+//! Bulk circuit construction from gate arrays.
+//!
+//! Machine-generated circuits can hold millions of gates, where one Python
+//! call per gate dominates the build time. [`build_circuit`] reads opcode,
+//! qubit and parameter arrays through the buffer protocol, so numpy arrays
+//! are read in place, and builds the whole circuit in one call.
+
+use pyo3::buffer::{PyBuffer, ReadOnlyCell};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+
+use arvak_ir::{Circuit, ClbitId, Instruction, QubitId, StandardGate};
+
+use crate::error::ir_to_py_err;
+
+/// Gate names by opcode: opcode `i` is `OPCODES[i]`.
+pub const OPCODES: &[&str] = &[
+    "id", "x", "y", "z", "h", "s", "sdg", "t", "tdg", "sx", "sxdg", "rx", "ry", "rz", "p", "u",
+    "cx", "cy", "cz", "ch", "swap", "iswap", "ecr", "csx", "crx", "cry", "crz", "cp", "rxx", "ryy",
+    "rzz", "rzx", "ccx", "cswap", "ccz", "prx", "measure", "reset",
+];
+
+/// Opcode of `measure`; its second qubit column holds the classical bit.
+const MEASURE: usize = 36;
+/// Opcode of `reset`.
+const RESET: usize = 37;
+
+/// Number of qubit operands of an opcode.
+fn arity(opcode: usize) -> usize {
+    match opcode {
+        0..=15 | RESET => 1,
+        16..=31 | MEASURE => 2,
+        32..=34 => 3,
+        35 => 1,
+        _ => unreachable!("opcode checked against OPCODES"),
+    }
+}
+
+/// Number of parameters of an opcode.
+fn num_params(opcode: usize) -> usize {
+    match opcode {
+        11..=14 | 24..=31 => 1,
+        35 => 2,
+        15 => 3,
+        _ => 0,
+    }
+}
+
+/// Build the standard gate for an opcode from its parameters.
+fn standard_gate(opcode: usize, p: &[f64]) -> StandardGate {
+    match opcode {
+        0 => StandardGate::I,
+        1 => StandardGate::X,
+        2 => StandardGate::Y,
+        3 => StandardGate::Z,
+        4 => StandardGate::H,
+        5 => StandardGate::S,
+        6 => StandardGate::Sdg,
+        7 => StandardGate::T,
+        8 => StandardGate::Tdg,
+        9 => StandardGate::SX,
+        10 => StandardGate::SXdg,
+        11 => StandardGate::Rx(p[0].into()),
+        12 => StandardGate::Ry(p[0].into()),
+        13 => StandardGate::Rz(p[0].into()),
+        14 => StandardGate::P(p[0].into()),
+        15 => StandardGate::U(p[0].into(), p[1].into(), p[2].into()),
+        16 => StandardGate::CX,
+        17 => StandardGate::CY,
+        18 => StandardGate::CZ,
+        19 => StandardGate::CH,
+        20 => StandardGate::Swap,
+        21 => StandardGate::ISwap,
+        22 => StandardGate::ECR,
+        23 => StandardGate::CSX,
+        24 => StandardGate::CRx(p[0].into()),
+        25 => StandardGate::CRy(p[0].into()),
+        26 => StandardGate::CRz(p[0].into()),
+        27 => StandardGate::CP(p[0].into()),
+        28 => StandardGate::RXX(p[0].into()),
+        29 => StandardGate::RYY(p[0].into()),
+        30 => StandardGate::RZZ(p[0].into()),
+        31 => StandardGate::RZX(p[0].into()),
+        32 => StandardGate::CCX,
+        33 => StandardGate::CSwap,
+        34 => StandardGate::CCZ,
+        35 => StandardGate::PRX(p[0].into(), p[1].into()),
+        _ => unreachable!("opcode has no standard gate"),
+    }
+}
+
+/// An integer array in one of the dtypes accepted for opcodes and qubits.
+enum IntArray {
+    I64(PyBuffer<i64>),
+    I32(PyBuffer<i32>),
+    U32(PyBuffer<u32>),
+    U8(PyBuffer<u8>),
+}
+
+/// A C-contiguous view of an [`IntArray`].
+enum IntSlice<'a> {
+    I64(&'a [ReadOnlyCell<i64>]),
+    I32(&'a [ReadOnlyCell<i32>]),
+    U32(&'a [ReadOnlyCell<u32>]),
+    U8(&'a [ReadOnlyCell<u8>]),
+}
+
+impl IntArray {
+    fn extract(name: &str, obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        if let Ok(buf) = PyBuffer::get(obj) {
+            Ok(Self::I64(buf))
+        } else if let Ok(buf) = PyBuffer::get(obj) {
+            Ok(Self::I32(buf))
+        } else if let Ok(buf) = PyBuffer::get(obj) {
+            Ok(Self::U32(buf))
+        } else if let Ok(buf) = PyBuffer::get(obj) {
+            Ok(Self::U8(buf))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "{} must be an int64, int32, uint32 or uint8 array",
+                name
+            )))
+        }
+    }
+
+    fn shape(&self) -> &[usize] {
+        match self {
+            Self::I64(b) => b.shape(),
+            Self::I32(b) => b.shape(),
+            Self::U32(b) => b.shape(),
+            Self::U8(b) => b.shape(),
+        }
+    }
+
+    fn as_slice<'a>(&'a self, py: Python<'a>, name: &str) -> PyResult<IntSlice<'a>> {
+        let slice = match self {
+            Self::I64(b) => b.as_slice(py).map(IntSlice::I64),
+            Self::I32(b) => b.as_slice(py).map(IntSlice::I32),
+            Self::U32(b) => b.as_slice(py).map(IntSlice::U32),
+            Self::U8(b) => b.as_slice(py).map(IntSlice::U8),
+        };
+        slice.ok_or_else(|| not_contiguous(name))
+    }
+}
+
+impl IntSlice<'_> {
+    fn get(&self, i: usize) -> i64 {
+        match self {
+            Self::I64(s) => s[i].get(),
+            Self::I32(s) => i64::from(s[i].get()),
+            Self::U32(s) => i64::from(s[i].get()),
+            Self::U8(s) => i64::from(s[i].get()),
+        }
+    }
+}
+
+fn not_contiguous(name: &str) -> PyErr {
+    PyValueError::new_err(format!(
+        "{} must be C-contiguous (use numpy.ascontiguousarray)",
+        name
+    ))
+}
+
+/// Shape of a 2-D array with `rows` rows, or an error naming the array.
+fn columns(name: &str, shape: &[usize], rows: usize) -> PyResult<usize> {
+    match shape {
+        [r, c] if *r == rows => Ok(*c),
+        _ => Err(PyValueError::new_err(format!(
+            "{} must have shape ({}, k), got {:?}",
+            name, rows, shape
+        ))),
+    }
+}
+
+/// Build a circuit from gate arrays.
+///
+/// `opcodes` is a 1-D array of indices into [`OPCODES`]; row `i` of the 2-D
+/// `qubits` and `params` arrays holds the operands of gate `i`, with unused
+/// trailing columns ignored.
+pub fn build_circuit(
+    py: Python<'_>,
+    name: &str,
+    num_qubits: u32,
+    num_clbits: u32,
+    opcodes: &Bound<'_, PyAny>,
+    qubits: &Bound<'_, PyAny>,
+    params: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Circuit> {
+    let opcodes = IntArray::extract("opcodes", opcodes)?;
+    let num_gates = match opcodes.shape() {
+        [n] => *n,
+        shape => {
+            return Err(PyValueError::new_err(format!(
+                "opcodes must be 1-D, got shape {:?}",
+                shape
+            )));
+        }
+    };
+    let qubits = IntArray::extract("qubits", qubits)?;
+    let qubit_cols = columns("qubits", qubits.shape(), num_gates)?;
+    let params = params
+        .map(|p| {
+            PyBuffer::<f64>::get(p)
+                .map_err(|_| PyTypeError::new_err("params must be a float64 array"))
+        })
+        .transpose()?;
+    let param_cols = match &params {
+        Some(p) => columns("params", p.shape(), num_gates)?,
+        None => 0,
+    };
+
+    let opcode_view = opcodes.as_slice(py, "opcodes")?;
+    let qubit_view = qubits.as_slice(py, "qubits")?;
+    let param_view = match &params {
+        Some(p) => p.as_slice(py).ok_or_else(|| not_contiguous("params"))?,
+        None => &[],
+    };
+
+    let mut circuit = Circuit::with_size(name, num_qubits, num_clbits);
+    let mut operands = Vec::with_capacity(3);
+    let mut values = Vec::with_capacity(3);
+    for i in 0..num_gates {
+        let raw = opcode_view.get(i);
+        let opcode = usize::try_from(raw)
+            .ok()
+            .filter(|&op| op < OPCODES.len())
+            .ok_or_else(|| PyValueError::new_err(format!("gate {}: unknown opcode {}", i, raw)))?;
+
+        let (arity, num_params) = (arity(opcode), num_params(opcode));
+        if arity > qubit_cols {
+            return Err(PyValueError::new_err(format!(
+                "gate {}: '{}' needs {} qubit columns, qubits has {}",
+                i, OPCODES[opcode], arity, qubit_cols
+            )));
+        }
+        if num_params > param_cols {
+            return Err(PyValueError::new_err(format!(
+                "gate {}: '{}' needs {} parameter columns, params has {}",
+                i, OPCODES[opcode], num_params, param_cols
+            )));
+        }
+
+        operands.clear();
+        for col in 0..arity {
+            let raw = qubit_view.get(i * qubit_cols + col);
+            let index = u32::try_from(raw).map_err(|_| {
+                PyValueError::new_err(format!("gate {}: invalid qubit index {}", i, raw))
+            })?;
+            operands.push(index);
+        }
+        values.clear();
+        values.extend((0..num_params).map(|col| param_view[i * param_cols + col].get()));
+
+        let instruction = match opcode {
+            MEASURE => Instruction::measure(QubitId(operands[0]), ClbitId(operands[1])),
+            RESET => Instruction::reset(QubitId(operands[0])),
+            _ => Instruction::gate(
+                standard_gate(opcode, &values),
+                operands.iter().map(|&q| QubitId(q)),
+            ),
+        };
+        circuit.dag_mut().apply(instruction).map_err(ir_to_py_err)?;
+    }
+    Ok(circuit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opcode_table_matches_gate_names() {
+        for (opcode, name) in OPCODES.iter().enumerate() {
+            if opcode == MEASURE || opcode == RESET {
+                continue;
+            }
+            let params = [0.0; 3];
+            let gate = standard_gate(opcode, &params);
+            assert_eq!(gate.name(), *name);
+            assert_eq!(gate.num_qubits() as usize, arity(opcode));
+            assert_eq!(gate.parameters().len(), num_params(opcode));
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! Python wrappers for backend and target introspection.
+
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, Mutex};
+
+use arvak_hal::{Capabilities, GateSet, Topology};
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+
+use crate::circuit::PyCircuit;
+use crate::compile::{PyBasisGates, PyCouplingMap};
+
+/// Gate names that act on two qubits, used to split a basis gate list
+/// into the single- and two-qubit gates of a gate set.
+const TWO_QUBIT_GATES: &[&str] = &[
+    "cx", "cy", "cz", "ch", "swap", "iswap", "ecr", "csx", "crx", "cry", "crz", "cp", "rxx", "ryy",
+    "rzz", "rzx",
+];
+
+/// Backends known to `list_backends` and `get_backend`, seeded with the
+/// profiles of the built-in adapters.
+static REGISTRY: LazyLock<Mutex<Vec<PyBackend>>> = LazyLock::new(|| {
+    Mutex::new(vec![
+        PyBackend::from_capabilities(Capabilities::simulator(20)),
+        PyBackend::from_capabilities(Capabilities::iqm("garnet", 20)),
+        PyBackend::from_capabilities(Capabilities::ibm("ibm_brisbane", 127)),
+    ])
+});
+
+/// A quantum backend and the target it compiles for.
+///
+/// Backends describe a device's qubits, connectivity and native gates,
+/// plus an optional calibration summary and queue depth reported by the
+/// provider. Pass one as `target=` to `transpile`, or as the backend of
+/// a submission.
+#[pyclass(name = "Backend")]
+#[derive(Clone)]
+pub struct PyBackend {
+    pub(crate) capabilities: Capabilities,
+    pub(crate) calibration: Option<BTreeMap<String, f64>>,
+    pub(crate) queue_depth: Option<u32>,
+}
+
+impl PyBackend {
+    pub(crate) fn from_capabilities(capabilities: Capabilities) -> Self {
+        Self {
+            capabilities,
+            calibration: None,
+            queue_depth: None,
+        }
+    }
+
+    /// Coupling map and basis gates to compile for, `None` for simulators,
+    /// which run any circuit as is.
+    pub(crate) fn compile_target(
+        &self,
+    ) -> Option<(arvak_compile::CouplingMap, arvak_compile::BasisGates)> {
+        let caps = &self.capabilities;
+        if caps.is_simulator {
+            return None;
+        }
+        let gates = caps
+            .gate_set
+            .native
+            .iter()
+            .map(String::as_str)
+            .chain(["measure", "barrier"]);
+        Some((caps.coupling_map(), arvak_compile::BasisGates::new(gates)))
+    }
+
+    /// Whether the backend supports a gate, by any of its gate lists.
+    fn supports_gate(&self, gate: &str) -> bool {
+        let gate_set = &self.capabilities.gate_set;
+        gate_set.contains(gate) || gate_set.native.iter().any(|g| g == gate)
+    }
+}
+
+#[pymethods]
+impl PyBackend {
+    /// Describe a backend.
+    ///
+    /// Args:
+    ///     name: Backend name, as used when submitting.
+    ///     num_qubits: Number of physical qubits.
+    ///     coupling_map: Connected qubit pairs; fully connected if omitted.
+    ///     basis_gates: Native gate names; a universal gate set if omitted.
+    ///     max_shots: Maximum number of shots per job.
+    ///     is_simulator: Whether the backend is a simulator.
+    ///     features: Additional features, e.g. "dynamic_circuits".
+    ///     calibration: Calibration summary, e.g. {"t1_us": 45.0}.
+    ///     queue_depth: Number of jobs waiting on the backend.
+    ///
+    /// Raises:
+    ///     ValueError: If a coupling map edge names a qubit out of range.
+    #[new]
+    #[pyo3(signature = (
+        name, num_qubits, coupling_map=None, basis_gates=None, max_shots=100_000,
+        is_simulator=false, features=None, calibration=None, queue_depth=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        name: String,
+        num_qubits: u32,
+        coupling_map: Option<Vec<(u32, u32)>>,
+        basis_gates: Option<Vec<String>>,
+        max_shots: u32,
+        is_simulator: bool,
+        features: Option<Vec<String>>,
+        calibration: Option<BTreeMap<String, f64>>,
+        queue_depth: Option<u32>,
+    ) -> PyResult<Self> {
+        let topology = match coupling_map {
+            Some(edges) => {
+                if let Some(&(a, b)) = edges.iter().find(|&&(a, b)| a.max(b) >= num_qubits) {
+                    return Err(PyValueError::new_err(format!(
+                        "Coupling map edge ({}, {}) is out of range for {} qubits",
+                        a, b, num_qubits
+                    )));
+                }
+                Topology::custom(edges)
+            }
+            None => Topology::full(num_qubits),
+        };
+        let gate_set = match basis_gates {
+            Some(gates) => {
+                let (two_qubit, single_qubit) = gates
+                    .iter()
+                    .cloned()
+                    .partition(|g| TWO_QUBIT_GATES.contains(&g.as_str()));
+                GateSet {
+                    single_qubit,
+                    two_qubit,
+                    native: gates,
+                }
+            }
+            None => GateSet::universal(),
+        };
+
+        Ok(Self {
+            capabilities: Capabilities {
+                name,
+                num_qubits,
+                gate_set,
+                topology,
+                max_shots,
+                is_simulator,
+                features: features.unwrap_or_default(),
+            },
+            calibration,
+            queue_depth,
+        })
+    }
+
+    /// Create a simulator backend.
+    #[staticmethod]
+    #[pyo3(signature = (num_qubits=20))]
+    fn simulator(num_qubits: u32) -> Self {
+        Self::from_capabilities(Capabilities::simulator(num_qubits))
+    }
+
+    /// Create an IQM backend (PRX + CZ, star topology).
+    #[staticmethod]
+    #[pyo3(signature = (name="garnet", num_qubits=20))]
+    fn iqm(name: &str, num_qubits: u32) -> Self {
+        Self::from_capabilities(Capabilities::iqm(name, num_qubits))
+    }
+
+    /// Create an IBM backend (RZ + SX + X + CX).
+    #[staticmethod]
+    #[pyo3(signature = (name="ibm_brisbane", num_qubits=127))]
+    fn ibm(name: &str, num_qubits: u32) -> Self {
+        Self::from_capabilities(Capabilities::ibm(name, num_qubits))
+    }
+
+    /// Create a neutral-atom backend with interaction zones.
+    #[staticmethod]
+    #[pyo3(signature = (name, num_qubits, zones=1))]
+    fn neutral_atom(name: &str, num_qubits: u32, zones: u32) -> Self {
+        Self::from_capabilities(Capabilities::neutral_atom(name, num_qubits, zones))
+    }
+
+    /// Load a backend from its JSON capabilities.
+    ///
+    /// The document is the serialized HAL capabilities, optionally with
+    /// `calibration` and `queue_depth` fields next to them.
+    ///
+    /// Raises:
+    ///     ValueError: If the JSON is not a backend description.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        let description: BackendDescription = serde_json::from_str(json)
+            .map_err(|e| PyValueError::new_err(format!("Invalid backend JSON: {}", e)))?;
+        Ok(Self {
+            capabilities: description.capabilities,
+            calibration: description.calibration,
+            queue_depth: description.queue_depth,
+        })
+    }
+
+    /// Serialize the backend to JSON, the inverse of `from_json`.
+    fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&BackendDescription {
+            capabilities: self.capabilities.clone(),
+            calibration: self.calibration.clone(),
+            queue_depth: self.queue_depth,
+        })
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Backend name.
+    #[getter]
+    fn name(&self) -> String {
+        self.capabilities.name.clone()
+    }
+
+    /// Number of physical qubits.
+    #[getter]
+    fn num_qubits(&self) -> u32 {
+        self.capabilities.num_qubits
+    }
+
+    /// Connected qubit pairs.
+    #[getter]
+    fn coupling_map(&self) -> Vec<(u32, u32)> {
+        self.capabilities.topology.edges.clone()
+    }
+
+    /// Native gate names.
+    #[getter]
+    fn basis_gates(&self) -> Vec<String> {
+        self.capabilities.gate_set.native.clone()
+    }
+
+    /// Maximum number of shots per job.
+    #[getter]
+    fn max_shots(&self) -> u32 {
+        self.capabilities.max_shots
+    }
+
+    /// Whether the backend is a simulator.
+    #[getter]
+    fn is_simulator(&self) -> bool {
+        self.capabilities.is_simulator
+    }
+
+    /// Additional features the backend supports.
+    #[getter]
+    fn features(&self) -> Vec<String> {
+        self.capabilities.features.clone()
+    }
+
+    /// Calibration summary, or None if the provider reports none.
+    #[getter]
+    fn calibration(&self) -> Option<BTreeMap<String, f64>> {
+        self.calibration.clone()
+    }
+
+    /// Number of jobs waiting on the backend, or None if unknown.
+    #[getter]
+    fn queue_depth(&self) -> Option<u32> {
+        self.queue_depth
+    }
+
+    /// Get the coupling map for compilation.
+    fn to_coupling_map(&self) -> PyCouplingMap {
+        PyCouplingMap {
+            inner: self.capabilities.coupling_map(),
+        }
+    }
+
+    /// Get the basis gates for compilation, including measure and barrier.
+    fn to_basis_gates(&self) -> PyBasisGates {
+        let gates = self
+            .capabilities
+            .gate_set
+            .native
+            .iter()
+            .map(String::as_str)
+            .chain(["measure", "barrier"]);
+        PyBasisGates {
+            inner: arvak_compile::BasisGates::new(gates),
+        }
+    }
+
+    /// Check whether the backend can run a circuit and has every feature
+    /// and gate asked for.
+    ///
+    /// Args:
+    ///     circuit: Circuit that must fit on the backend's qubits.
+    ///     min_qubits: Minimum number of qubits.
+    ///     gates: Gate names the backend must support.
+    ///     simulator: Require a simulator (True) or real hardware (False).
+    ///     features: Features the backend must advertise.
+    #[pyo3(signature = (circuit=None, min_qubits=None, gates=None, simulator=None, features=None))]
+    fn matches(
+        &self,
+        circuit: Option<&PyCircuit>,
+        min_qubits: Option<u32>,
+        gates: Option<Vec<String>>,
+        simulator: Option<bool>,
+        features: Option<Vec<String>>,
+    ) -> bool {
+        let caps = &self.capabilities;
+        let required_qubits = circuit
+            .map(|c| c.inner.num_qubits() as u32)
+            .into_iter()
+            .chain(min_qubits)
+            .max()
+            .unwrap_or(0);
+
+        caps.num_qubits >= required_qubits
+            && simulator.is_none_or(|s| s == caps.is_simulator)
+            && gates
+                .unwrap_or_default()
+                .iter()
+                .all(|g| self.supports_gate(g))
+            && features
+                .unwrap_or_default()
+                .iter()
+                .all(|f| caps.has_feature(f))
+    }
+
+    fn __eq__(&self, other: &PyBackend) -> bool {
+        self.capabilities.name == other.capabilities.name
+    }
+
+    fn __repr__(&self) -> String {
+        let mut repr = format!(
+            "Backend(name='{}', num_qubits={}, basis_gates={:?}",
+            self.capabilities.name, self.capabilities.num_qubits, self.capabilities.gate_set.native
+        );
+        if let Some(depth) = self.queue_depth {
+            repr.push_str(&format!(", queue_depth={}", depth));
+        }
+        repr.push(')');
+        repr
+    }
+}
+
+/// Serialized form of a backend: the HAL capabilities plus what the
+/// provider reports about the device's current state.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BackendDescription {
+    #[serde(flatten)]
+    capabilities: Capabilities,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    calibration: Option<BTreeMap<String, f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    queue_depth: Option<u32>,
+}
+
+/// List known backends, optionally only those matching a filter.
+///
+/// Takes the same filters as `Backend.matches`.
+///
+/// Example:
+///     >>> [b.name for b in list_backends(min_qubits=100)]
+///     ['ibm_brisbane']
+#[pyfunction]
+#[pyo3(signature = (circuit=None, min_qubits=None, gates=None, simulator=None, features=None))]
+pub fn list_backends(
+    circuit: Option<&PyCircuit>,
+    min_qubits: Option<u32>,
+    gates: Option<Vec<String>>,
+    simulator: Option<bool>,
+    features: Option<Vec<String>>,
+) -> Vec<PyBackend> {
+    registry()
+        .iter()
+        .filter(|b| {
+            b.matches(
+                circuit,
+                min_qubits,
+                gates.clone(),
+                simulator,
+                features.clone(),
+            )
+        })
+        .cloned()
+        .collect()
+}
+
+/// Get a known backend by name.
+///
+/// Raises:
+///     KeyError: If no backend has that name.
+#[pyfunction]
+pub fn get_backend(name: &str) -> PyResult<PyBackend> {
+    registry()
+        .iter()
+        .find(|b| b.capabilities.name == name)
+        .cloned()
+        .ok_or_else(|| PyKeyError::new_err(format!("Unknown backend: {}", name)))
+}
+
+/// Register a backend, replacing any known backend of the same name.
+#[pyfunction]
+pub fn register_backend(backend: PyBackend) {
+    let mut registry = registry();
+    registry.retain(|b| b.capabilities.name != backend.capabilities.name);
+    registry.push(backend);
+}
+
+/// Snapshot of the known backends.
+pub(crate) fn registered_backends() -> Vec<PyBackend> {
+    registry().clone()
+}
+
+fn registry() -> std::sync::MutexGuard<'static, Vec<PyBackend>> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_from_basis_gates() {
+        let backend = PyBackend::new(
+            "grid".into(),
+            4,
+            Some(vec![(0, 1), (1, 3), (0, 2), (2, 3)]),
+            Some(vec!["rz".into(), "sx".into(), "cz".into()]),
+            10_000,
+            false,
+            None,
+            Some(BTreeMap::from([("readout_error".into(), 0.02)])),
+            Some(3),
+        )
+        .unwrap();
+        assert_eq!(backend.capabilities.gate_set.two_qubit, vec!["cz"]);
+        assert_eq!(backend.capabilities.gate_set.single_qubit, vec!["rz", "sx"]);
+        assert!(backend.matches(None, Some(4), Some(vec!["cz".into()]), Some(false), None));
+        assert!(!backend.matches(None, Some(5), None, None, None));
+
+        let (coupling, basis) = backend.compile_target().unwrap();
+        assert!(coupling.is_connected(1, 3));
+        assert!(basis.contains("measure"));
+
+        let out_of_range = PyBackend::new(
+            "bad".into(),
+            2,
+            Some(vec![(0, 2)]),
+            None,
+            1,
+            false,
+            None,
+            None,
+            None,
+        );
+        assert!(out_of_range.is_err());
+    }
+
+    #[test]
+    fn test_backend_json_roundtrip() {
+        let mut backend = PyBackend::from_capabilities(Capabilities::iqm("garnet", 5));
+        backend.queue_depth = Some(7);
+        let restored = PyBackend::from_json(&backend.to_json().unwrap()).unwrap();
+        assert_eq!(restored.capabilities.name, "garnet");
+        assert_eq!(restored.queue_depth, Some(7));
+        assert_eq!(restored.calibration, None);
+        assert!(PyBackend::simulator(5).compile_target().is_none());
+    }
+}
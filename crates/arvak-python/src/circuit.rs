@@ -2,6 +2,7 @@
 
 use pyo3::prelude::*;
 
+use crate::arrays;
 use crate::error::ir_to_py_err;
 use crate::qubits::{PyClbitId, PyQubitId};
 
@@ -546,17 +547,46 @@ impl PyCircuit {
         Ok(slf)
     }
 
+    /// Apply a barrier to all qubits, marked as a snapshot point.
+    ///
+    /// Simulators recording an execution trace in snapshot mode capture
+    /// the full state here under `label`.
+    fn snapshot(slf: Py<Self>, py: Python<'_>, label: &str) -> PyResult<Py<Self>> {
+        slf.borrow_mut(py)
+            .inner
+            .snapshot(label)
+            .map_err(ir_to_py_err)?;
+        Ok(slf)
+    }
+
     /// Apply a delay to a qubit.
+    ///
+    /// Args:
+    ///     qubit: Qubit to idle
+    ///     duration: Length of the delay
+    ///     unit: "dt" (backend sample periods, default) or "ns"
+    #[pyo3(signature = (qubit, duration, unit="dt"))]
     fn delay(
         slf: Py<Self>,
         py: Python<'_>,
         qubit: &Bound<'_, PyAny>,
         duration: u64,
+        unit: &str,
     ) -> PyResult<Py<Self>> {
         let qid = to_qubit_id(qubit)?;
+        let unit = match unit {
+            "dt" => arvak_ir::TimeUnit::Dt,
+            "ns" => arvak_ir::TimeUnit::Ns,
+            other => {
+                return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                    "Unknown delay unit '{}', expected 'dt' or 'ns'",
+                    other
+                )));
+            }
+        };
         slf.borrow_mut(py)
             .inner
-            .delay(qid, duration)
+            .delay_with_unit([qid], duration, unit)
             .map_err(ir_to_py_err)?;
         Ok(slf)
     }
@@ -601,6 +631,52 @@ impl PyCircuit {
         Ok(Self { inner: circuit })
     }
 
+    /// Gate names by opcode, for use with `from_arrays`.
+    #[classattr]
+    #[allow(non_snake_case)]
+    fn OPCODES() -> Vec<&'static str> {
+        arrays::OPCODES.to_vec()
+    }
+
+    /// Build a circuit from gate arrays in one call.
+    ///
+    /// Arrays are read in place through the buffer protocol, so numpy
+    /// arrays are not copied; they must be C-contiguous.
+    ///
+    /// Args:
+    ///     name: The name of the circuit.
+    ///     num_qubits: Number of qubits.
+    ///     opcodes: 1-D integer array of indices into `Circuit.OPCODES`.
+    ///     qubits: 2-D integer array, row i holding the qubits of gate i.
+    ///         For `measure`, the second column is the classical bit.
+    ///     params: 2-D float64 array, row i holding the angles of gate i.
+    ///         Only needed when the circuit has parameterized gates.
+    ///     num_clbits: Number of classical bits (default: 0).
+    ///
+    /// Returns:
+    ///     A new Circuit instance.
+    ///
+    /// Example:
+    ///     >>> ops = np.array([Circuit.OPCODES.index("h"),
+    ///     ...                 Circuit.OPCODES.index("cx")])
+    ///     >>> qubits = np.array([[0, 0], [0, 1]])
+    ///     >>> qc = Circuit.from_arrays("bell", 2, ops, qubits)
+    #[staticmethod]
+    #[pyo3(signature = (name, num_qubits, opcodes, qubits, params=None, num_clbits=0))]
+    fn from_arrays(
+        py: Python<'_>,
+        name: &str,
+        num_qubits: u32,
+        opcodes: &Bound<'_, PyAny>,
+        qubits: &Bound<'_, PyAny>,
+        params: Option<&Bound<'_, PyAny>>,
+        num_clbits: u32,
+    ) -> PyResult<Self> {
+        let circuit =
+            arrays::build_circuit(py, name, num_qubits, num_clbits, opcodes, qubits, params)?;
+        Ok(Self { inner: circuit })
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "Circuit('{}', num_qubits={}, num_clbits={}, depth={})",
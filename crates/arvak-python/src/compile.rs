@@ -1,7 +1,11 @@
 //! Python wrappers for compilation types.
 
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
+use crate::backend::PyBackend;
+use crate::circuit::PyCircuit;
+use crate::error::compile_to_py_err;
 use crate::qubits::PyQubitId;
 
 /// A mapping from logical qubits to physical qubits.
@@ -312,3 +316,70 @@ impl PyPropertySet {
         )
     }
 }
+
+/// Compile a circuit for a target.
+///
+/// Args:
+///     circuit: The circuit to compile.
+///     coupling_map: The device coupling map; without one, no layout or
+///         routing is done.
+///     basis_gates: The native gate set; without one, gates are not
+///         translated.
+///     optimization_level: Optimization level 0-3, ignored if a preset is given.
+///     preset: Name of a pipeline preset: "superconducting-heavyhex",
+///         "iontrap-alltoall" or "simulator-fast".
+///     seed: Seed for stochastic passes.
+///     target: A Backend to compile for, supplying the coupling map and
+///         basis gates not given explicitly. Simulator targets add none.
+///
+/// Returns:
+///     The compiled circuit.
+///
+/// Raises:
+///     ValueError: If the preset name is unknown.
+///     RuntimeError: If compilation fails.
+///
+/// Example:
+///     >>> qc = Circuit.bell()
+///     >>> compiled = transpile(
+///     ...     qc, CouplingMap.linear(5), BasisGates.ibm(),
+///     ...     preset="superconducting-heavyhex")
+///     >>> compiled = transpile(qc, target=get_backend("garnet"))
+#[pyfunction]
+#[pyo3(signature = (circuit, coupling_map=None, basis_gates=None, optimization_level=1, preset=None, seed=None, target=None))]
+pub fn transpile(
+    circuit: &PyCircuit,
+    coupling_map: Option<PyCouplingMap>,
+    basis_gates: Option<PyBasisGates>,
+    optimization_level: u8,
+    preset: Option<&str>,
+    seed: Option<u64>,
+    target: Option<&PyBackend>,
+) -> PyResult<PyCircuit> {
+    let (target_coupling, target_basis) = target
+        .and_then(PyBackend::compile_target)
+        .map_or((None, None), |(c, b)| (Some(c), Some(b)));
+
+    let mut properties = arvak_compile::PropertySet::new();
+    properties.coupling_map = coupling_map.map(|c| c.inner).or(target_coupling);
+    properties.basis_gates = basis_gates.map(|b| b.inner).or(target_basis);
+
+    let mut builder = arvak_compile::PassManagerBuilder::new().with_properties(properties);
+    builder = match preset {
+        Some(name) => builder.with_preset(
+            name.parse::<arvak_compile::PipelinePreset>()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        ),
+        None => builder.with_optimization_level(optimization_level),
+    };
+    if let Some(seed) = seed {
+        builder = builder.with_seed(seed);
+    }
+
+    let (pm, mut props) = builder.build();
+    let mut dag = circuit.inner.clone().into_dag();
+    pm.run(&mut dag, &mut props).map_err(compile_to_py_err)?;
+    Ok(PyCircuit {
+        inner: arvak_ir::Circuit::from_dag(dag),
+    })
+}
@@ -0,0 +1,218 @@
+//! Python wrapper for measurement counts and their analysis.
+
+use std::collections::HashMap;
+
+use pyo3::exceptions::{PyImportError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Measurement counts from circuit execution.
+///
+/// Bitstrings follow the little-endian convention: qubit 0 is the
+/// rightmost bit. Spaces separating registers are ignored by the
+/// analysis methods.
+#[pyclass(name = "Counts")]
+#[derive(Clone)]
+pub struct PyCounts {
+    pub(crate) inner: arvak_hal::Counts,
+}
+
+#[pymethods]
+impl PyCounts {
+    /// Create counts from a mapping of bitstrings to counts.
+    ///
+    /// Args:
+    ///     counts: Mapping from bitstring to count, e.g. {"00": 510, "11": 490}.
+    #[new]
+    #[pyo3(signature = (counts=None))]
+    fn new(counts: Option<HashMap<String, u64>>) -> Self {
+        Self {
+            inner: arvak_hal::Counts::from_pairs(counts.unwrap_or_default()),
+        }
+    }
+
+    /// Total number of shots.
+    #[getter]
+    fn shots(&self) -> u64 {
+        self.inner.total_shots()
+    }
+
+    /// Get the count for a bitstring (0 if never measured).
+    fn get(&self, bitstring: &str) -> u64 {
+        self.inner.get(bitstring)
+    }
+
+    /// Get the most frequent bitstring and its count.
+    ///
+    /// Returns:
+    ///     A (bitstring, count) tuple, or None if there are no counts.
+    fn most_frequent(&self) -> Option<(String, u64)> {
+        self.inner
+            .most_frequent()
+            .map(|(bits, &count)| (bits.clone(), count))
+    }
+
+    /// Convert to a plain dictionary.
+    fn to_dict(&self) -> HashMap<String, u64> {
+        self.inner.iter().map(|(k, &v)| (k.clone(), v)).collect()
+    }
+
+    /// Normalize the counts to probabilities.
+    ///
+    /// Returns:
+    ///     A dictionary mapping bitstrings to probabilities summing to 1.
+    fn probabilities(&self) -> HashMap<String, f64> {
+        self.inner.probabilities().into_iter().collect()
+    }
+
+    /// Counts over a subset of qubits, summing over the others.
+    ///
+    /// Args:
+    ///     qubits: Qubit indices to keep. qubits[0] becomes the rightmost
+    ///         bit of the marginal bitstrings.
+    ///
+    /// Returns:
+    ///     The marginal Counts.
+    fn marginal(&self, qubits: Vec<usize>) -> Self {
+        Self {
+            inner: self.inner.marginal(&qubits),
+        }
+    }
+
+    /// Total variation distance to other counts.
+    ///
+    /// Returns:
+    ///     A distance between 0 (identical distributions) and 1 (disjoint).
+    fn tvd(&self, other: &PyCounts) -> f64 {
+        self.inner.total_variation_distance(&other.inner)
+    }
+
+    /// Hellinger distance to other counts.
+    ///
+    /// Returns:
+    ///     A distance between 0 (identical distributions) and 1 (disjoint).
+    fn hellinger(&self, other: &PyCounts) -> f64 {
+        self.inner.hellinger_distance(&other.inner)
+    }
+
+    /// Expectation value of a Pauli string.
+    ///
+    /// The string is written like a bitstring: its rightmost character acts
+    /// on qubit 0. Counts are Z-basis measurements, so X and Y factors are
+    /// only meaningful if the circuit rotated those qubits into the Z basis
+    /// before measuring; every non-identity factor contributes its qubit's
+    /// parity.
+    ///
+    /// Args:
+    ///     pauli: Pauli string over I, X, Y and Z, e.g. "ZIZ".
+    ///
+    /// Returns:
+    ///     The expectation value, between -1 and 1.
+    fn expectation(&self, pauli: &str) -> PyResult<f64> {
+        let qubits = pauli_support(pauli).map_err(PyValueError::new_err)?;
+        Ok(self.inner.parity_expectation(&qubits))
+    }
+
+    /// Convert to a pandas DataFrame.
+    ///
+    /// Returns:
+    ///     A DataFrame with `bitstring`, `count` and `probability` columns,
+    ///     most frequent bitstring first.
+    ///
+    /// Raises:
+    ///     ImportError: If pandas is not installed.
+    fn to_dataframe(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let pandas = py.import("pandas").map_err(|_| {
+            PyImportError::new_err("to_dataframe requires pandas: pip install pandas")
+        })?;
+
+        let mut rows: Vec<(&String, &u64)> = self.inner.iter().collect();
+        rows.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let total = self.inner.total_shots();
+
+        let columns = PyDict::new(py);
+        columns.set_item(
+            "bitstring",
+            rows.iter()
+                .map(|(bits, _)| bits.as_str())
+                .collect::<Vec<_>>(),
+        )?;
+        columns.set_item("count", rows.iter().map(|&(_, &c)| c).collect::<Vec<_>>())?;
+        columns.set_item(
+            "probability",
+            rows.iter()
+                .map(|&(_, &c)| {
+                    if total > 0 {
+                        c as f64 / total as f64
+                    } else {
+                        0.0
+                    }
+                })
+                .collect::<Vec<_>>(),
+        )?;
+        Ok(pandas.call_method1("DataFrame", (columns,))?.unbind())
+    }
+
+    fn __getitem__(&self, bitstring: &str) -> u64 {
+        self.inner.get(bitstring)
+    }
+
+    fn __contains__(&self, bitstring: &str) -> bool {
+        self.inner.get(bitstring) > 0
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __eq__(&self, other: &PyCounts) -> bool {
+        self.inner.len() == other.inner.len()
+            && self
+                .inner
+                .iter()
+                .all(|(bits, &count)| other.inner.get(bits) == count)
+    }
+
+    fn __repr__(&self) -> String {
+        let entries: Vec<String> = self
+            .inner
+            .sorted()
+            .into_iter()
+            .map(|(bits, count)| format!("'{}': {}", bits, count))
+            .collect();
+        format!("Counts({{{}}})", entries.join(", "))
+    }
+}
+
+/// Qubits a Pauli string acts on non-trivially.
+fn pauli_support(pauli: &str) -> Result<Vec<usize>, String> {
+    let factors: Vec<char> = pauli.chars().filter(|c| *c != ' ').collect();
+    let mut qubits = Vec::new();
+    for (position, factor) in factors.iter().enumerate() {
+        let qubit = factors.len() - 1 - position;
+        match factor.to_ascii_uppercase() {
+            'I' => {}
+            'X' | 'Y' | 'Z' => qubits.push(qubit),
+            other => {
+                return Err(format!(
+                    "Invalid Pauli factor '{}' in '{}': expected I, X, Y or Z",
+                    other, pauli
+                ));
+            }
+        }
+    }
+    Ok(qubits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauli_support() {
+        assert_eq!(pauli_support("ZIZ").unwrap(), vec![2, 0]);
+        assert_eq!(pauli_support("xy").unwrap(), vec![1, 0]);
+        assert!(pauli_support("III").unwrap().is_empty());
+        assert!(pauli_support("ZA").is_err());
+    }
+}
@@ -8,7 +8,17 @@ pub fn ir_to_py_err(e: arvak_ir::IrError) -> PyErr {
     PyRuntimeError::new_err(format!("IR Error: {}", e))
 }
 
+/// Convert a compilation error to a Python exception.
+pub fn compile_to_py_err(e: arvak_compile::CompileError) -> PyErr {
+    PyRuntimeError::new_err(format!("Compile Error: {}", e))
+}
+
 /// Convert a parse error to a Python exception.
 pub fn parse_to_py_err(e: arvak_qasm3::ParseError) -> PyErr {
     PyRuntimeError::new_err(format!("Parse Error: {}", e))
 }
+
+/// Convert a scheduler error to a Python exception.
+pub fn sched_to_py_err(e: arvak_sched::SchedError) -> PyErr {
+    PyRuntimeError::new_err(format!("Scheduler Error: {}", e))
+}
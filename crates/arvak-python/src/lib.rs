@@ -20,21 +20,30 @@
 //! qc2 = arvak.from_qasm(qasm)
 //! ```
 
+mod arrays;
+mod backend;
 mod circuit;
 mod compile;
+mod counts;
 mod error;
 mod qasm;
 mod qubits;
+mod scheduler;
 
 use pyo3::prelude::*;
 
 /// Arvak: Rust-native quantum compilation platform.
 ///
 /// This module provides:
-/// - Circuit: Quantum circuit builder with fluent API
+/// - Circuit: Quantum circuit builder with fluent API and bulk construction
+///   from numpy gate arrays
 /// - QubitId, ClbitId: Qubit and classical bit identifiers
 /// - from_qasm, to_qasm: QASM3 parsing and emission
 /// - Layout, CouplingMap, BasisGates, PropertySet: Compilation types
+/// - transpile: Compile a circuit for a target, optionally with a preset
+/// - Counts: Measurement counts with marginals, distances and expectations
+/// - Backend, list_backends, get_backend: Target introspection
+/// - Scheduler, Session, Batch: Job submission with sessions and batches
 #[pymodule]
 fn arvak(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Core types
@@ -47,6 +56,21 @@ fn arvak(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<compile::PyCouplingMap>()?;
     m.add_class::<compile::PyBasisGates>()?;
     m.add_class::<compile::PyPropertySet>()?;
+    m.add_function(wrap_pyfunction!(compile::transpile, m)?)?;
+
+    // Result analysis
+    m.add_class::<counts::PyCounts>()?;
+
+    // Backends
+    m.add_class::<backend::PyBackend>()?;
+    m.add_function(wrap_pyfunction!(backend::list_backends, m)?)?;
+    m.add_function(wrap_pyfunction!(backend::get_backend, m)?)?;
+    m.add_function(wrap_pyfunction!(backend::register_backend, m)?)?;
+
+    // Scheduling
+    m.add_class::<scheduler::PyScheduler>()?;
+    m.add_class::<scheduler::PySession>()?;
+    m.add_class::<scheduler::PyBatch>()?;
 
     // QASM I/O functions
     m.add_function(wrap_pyfunction!(qasm::from_qasm, m)?)?;
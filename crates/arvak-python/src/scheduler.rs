@@ -0,0 +1,574 @@
+//! Python wrappers for the HPC scheduler, its sessions and batches.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arvak_hal::{Capabilities, HalError, HalResult};
+use arvak_sched::{
+    BatchSchedulerType, CircuitSpec, HpcScheduler, JobTemplate, JsonStore, ScheduledJob,
+    ScheduledJobId, Scheduler, SchedulerConfig, Session, TemplateOverrides,
+};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use tokio::runtime::Runtime;
+
+use crate::backend::{PyBackend, registered_backends};
+use crate::circuit::PyCircuit;
+use crate::error::sched_to_py_err;
+
+/// A backend known only by its capabilities, for resource matching.
+///
+/// Jobs reach the device through the batch system, so the scheduler never
+/// calls the backend itself.
+struct DescribedBackend {
+    capabilities: Capabilities,
+}
+
+#[async_trait::async_trait]
+impl arvak_hal::Backend for DescribedBackend {
+    fn name(&self) -> &str {
+        &self.capabilities.name
+    }
+
+    async fn capabilities(&self) -> HalResult<Capabilities> {
+        Ok(self.capabilities.clone())
+    }
+
+    async fn is_available(&self) -> HalResult<bool> {
+        Ok(true)
+    }
+
+    async fn submit(
+        &self,
+        _circuit: &arvak_ir::Circuit,
+        _shots: u32,
+    ) -> HalResult<arvak_hal::JobId> {
+        Err(self.unsupported())
+    }
+
+    async fn status(&self, _job_id: &arvak_hal::JobId) -> HalResult<arvak_hal::JobStatus> {
+        Err(self.unsupported())
+    }
+
+    async fn result(&self, _job_id: &arvak_hal::JobId) -> HalResult<arvak_hal::ExecutionResult> {
+        Err(self.unsupported())
+    }
+
+    async fn cancel(&self, _job_id: &arvak_hal::JobId) -> HalResult<()> {
+        Err(self.unsupported())
+    }
+}
+
+impl DescribedBackend {
+    fn unsupported(&self) -> HalError {
+        HalError::Unsupported(format!(
+            "{} is reached through the batch system",
+            self.capabilities.name
+        ))
+    }
+}
+
+/// Convert a circuit to the scheduler's QASM form.
+fn circuit_spec(circuit: &PyCircuit) -> PyResult<CircuitSpec> {
+    CircuitSpec::from_circuit(&circuit.inner).map_err(sched_to_py_err)
+}
+
+fn parse_job_id(job_id: &str) -> PyResult<ScheduledJobId> {
+    ScheduledJobId::parse(job_id)
+        .map_err(|e| PyValueError::new_err(format!("Invalid job ID '{}': {}", job_id, e)))
+}
+
+/// The HPC scheduler, submitting circuits through a batch system.
+///
+/// Example:
+///     >>> scheduler = Scheduler(config="arvak.toml")
+///     >>> with scheduler.session(backend="garnet", max_time="2h") as s:
+///     ...     job = s.run(circuit, shots=1000)
+///     >>> with scheduler.batch(shots=500) as b:
+///     ...     for qc in circuits:
+///     ...         b.run(qc)
+///     >>> scheduler.status(b.job_id)
+///     'Pending'
+#[pyclass(name = "Scheduler")]
+pub struct PyScheduler {
+    runtime: Arc<Runtime>,
+    inner: Arc<HpcScheduler>,
+}
+
+#[pymethods]
+impl PyScheduler {
+    /// Start a scheduler.
+    ///
+    /// Args:
+    ///     config: Path to an `arvak.toml` whose `[scheduler]` table
+    ///         configures the scheduler; defaults apply if omitted.
+    ///     backends: Backends to match jobs against; the known backends
+    ///         of `list_backends` if omitted.
+    ///     state_dir: Directory for job state, overriding the config.
+    ///     mock: Use a mock batch system instead of the real commands.
+    ///
+    /// Raises:
+    ///     RuntimeError: If the config is invalid or the batch system is
+    ///         not available.
+    #[new]
+    #[pyo3(signature = (config=None, backends=None, state_dir=None, mock=false))]
+    fn new(
+        py: Python<'_>,
+        config: Option<PathBuf>,
+        backends: Option<Vec<PyBackend>>,
+        state_dir: Option<PathBuf>,
+        mock: bool,
+    ) -> PyResult<Self> {
+        let mut config = match config {
+            Some(path) => arvak_config::ConfigLoader::new()
+                .with_file(path)
+                .load()
+                .and_then(|file| file.section::<SchedulerConfig>())
+                .map_err(|e| PyValueError::new_err(format!("Invalid scheduler config: {}", e)))?,
+            None => SchedulerConfig::default(),
+        };
+        if let Some(dir) = state_dir {
+            config.state_dir = dir;
+        }
+        let backends: Vec<Arc<dyn arvak_hal::Backend>> = backends
+            .unwrap_or_else(registered_backends)
+            .into_iter()
+            .map(|b| {
+                Arc::new(DescribedBackend {
+                    capabilities: b.capabilities,
+                }) as Arc<dyn arvak_hal::Backend>
+            })
+            .collect();
+
+        let runtime = Runtime::new()
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to start runtime: {}", e)))?;
+        let scheduler = py.allow_threads(|| {
+            runtime.block_on(async {
+                let store = Arc::new(JsonStore::new(config.state_dir.join("jobs")).await?);
+                if !mock {
+                    return HpcScheduler::new(config, backends, store).await;
+                }
+                Ok(match config.scheduler_type {
+                    BatchSchedulerType::Slurm => {
+                        HpcScheduler::with_mock_slurm(config, backends, store)
+                    }
+                    BatchSchedulerType::Pbs => HpcScheduler::with_mock_pbs(config, backends, store),
+                    BatchSchedulerType::Lsf => HpcScheduler::with_mock_lsf(config, backends, store),
+                    BatchSchedulerType::Kubernetes => {
+                        HpcScheduler::with_mock_kubernetes(config, backends, store)
+                    }
+                })
+            })
+        });
+        let inner = Arc::new(scheduler.map_err(sched_to_py_err)?);
+
+        let _guard = runtime.enter();
+        inner.clone().start_background_processor();
+        drop(_guard);
+
+        Ok(Self {
+            runtime: Arc::new(runtime),
+            inner,
+        })
+    }
+
+    /// Submit a circuit as its own job.
+    ///
+    /// Args:
+    ///     circuit: Circuit to run.
+    ///     shots: Number of shots.
+    ///     name: Job name; "job" if omitted.
+    ///     backend: Backend name to pin the job to; matched if omitted.
+    ///
+    /// Returns:
+    ///     The job ID.
+    #[pyo3(signature = (circuit, shots=1024, name=None, backend=None))]
+    fn submit(
+        &self,
+        py: Python<'_>,
+        circuit: &PyCircuit,
+        shots: u32,
+        name: Option<String>,
+        backend: Option<String>,
+    ) -> PyResult<String> {
+        let mut job =
+            ScheduledJob::new(name.unwrap_or_else(|| "job".into()), circuit_spec(circuit)?)
+                .with_shots(shots);
+        job.matched_backend = backend;
+        let job_id = py
+            .allow_threads(|| self.runtime.block_on(self.inner.submit(job)))
+            .map_err(sched_to_py_err)?;
+        Ok(job_id.to_string())
+    }
+
+    /// Get the status of a job, e.g. "Pending" or "Completed".
+    fn status(&self, py: Python<'_>, job_id: &str) -> PyResult<String> {
+        let job_id = parse_job_id(job_id)?;
+        let status = py
+            .allow_threads(|| self.runtime.block_on(self.inner.status(&job_id)))
+            .map_err(sched_to_py_err)?;
+        Ok(status.name().to_string())
+    }
+
+    /// Cancel a job.
+    fn cancel(&self, py: Python<'_>, job_id: &str) -> PyResult<()> {
+        let job_id = parse_job_id(job_id)?;
+        py.allow_threads(|| self.runtime.block_on(self.inner.cancel(&job_id)))
+            .map_err(sched_to_py_err)
+    }
+
+    /// Save a job template, replacing any template of the same name.
+    ///
+    /// Args:
+    ///     name: Template name of letters, digits, "-", "_" and ".".
+    ///     circuit: Circuit the template's jobs run.
+    ///     shots: Number of shots.
+    ///     labels: Labels copied into the metadata of each job.
+    ///     description: What the template is for.
+    ///
+    /// Raises:
+    ///     RuntimeError: If the name is invalid.
+    #[pyo3(signature = (name, circuit, shots=1024, labels=None, description=None))]
+    fn save_template(
+        &self,
+        py: Python<'_>,
+        name: &str,
+        circuit: &PyCircuit,
+        shots: u32,
+        labels: Option<BTreeMap<String, String>>,
+        description: Option<String>,
+    ) -> PyResult<()> {
+        let mut template = JobTemplate::new(name, circuit_spec(circuit)?).with_shots(shots);
+        template.labels = labels.unwrap_or_default();
+        template.description = description;
+        py.allow_threads(|| self.runtime.block_on(self.inner.save_template(template)))
+            .map_err(sched_to_py_err)
+    }
+
+    /// Names of the stored job templates, sorted.
+    fn templates(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        let templates = py
+            .allow_threads(|| self.runtime.block_on(self.inner.templates()))
+            .map_err(sched_to_py_err)?;
+        Ok(templates.into_iter().map(|t| t.name).collect())
+    }
+
+    /// Delete a job template.
+    ///
+    /// Raises:
+    ///     RuntimeError: If no template has this name.
+    fn delete_template(&self, py: Python<'_>, name: &str) -> PyResult<()> {
+        py.allow_threads(|| self.runtime.block_on(self.inner.delete_template(name)))
+            .map_err(sched_to_py_err)
+    }
+
+    /// Submit a job created from a stored template.
+    ///
+    /// Args:
+    ///     template: Template name.
+    ///     name: Job name; the template name if omitted.
+    ///     shots: Number of shots, overriding the template's.
+    ///     labels: Labels added to, or replacing, the template's.
+    ///
+    /// Returns:
+    ///     The job ID.
+    ///
+    /// Raises:
+    ///     RuntimeError: If no template has this name.
+    #[pyo3(signature = (template, name=None, shots=None, labels=None))]
+    fn submit_template(
+        &self,
+        py: Python<'_>,
+        template: &str,
+        name: Option<String>,
+        shots: Option<u32>,
+        labels: Option<BTreeMap<String, String>>,
+    ) -> PyResult<String> {
+        let overrides = TemplateOverrides {
+            name,
+            shots,
+            labels: labels.unwrap_or_default(),
+            ..TemplateOverrides::default()
+        };
+        let job_id = py
+            .allow_threads(|| {
+                self.runtime
+                    .block_on(self.inner.submit_template(template, overrides))
+            })
+            .map_err(sched_to_py_err)?;
+        Ok(job_id.to_string())
+    }
+
+    /// Open a session pinning jobs to one backend for a bounded time.
+    ///
+    /// Use it as a context manager: leaving the block closes the session,
+    /// and an exception inside it also cancels the session's unfinished
+    /// jobs.
+    ///
+    /// Args:
+    ///     backend: Backend name the session's jobs run on.
+    ///     max_time: Time budget such as "30m" or "2h", or seconds; one
+    ///         hour if omitted.
+    ///
+    /// Raises:
+    ///     ValueError: If `max_time` is not a valid duration.
+    #[pyo3(signature = (backend, max_time=None))]
+    fn session(&self, backend: String, max_time: Option<&Bound<'_, PyAny>>) -> PyResult<PySession> {
+        let max_time = match max_time {
+            None => std::time::Duration::from_secs(3_600),
+            Some(t) => match t.extract::<u64>() {
+                Ok(secs) => std::time::Duration::from_secs(secs),
+                Err(_) => arvak_sched::parse_max_time(&t.extract::<String>()?)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            },
+        };
+        Ok(PySession {
+            runtime: self.runtime.clone(),
+            scheduler: self.inner.clone(),
+            session: Session::new(backend, max_time),
+        })
+    }
+
+    /// Collect circuits and submit them as a single batch job.
+    ///
+    /// Use it as a context manager: the batch is submitted when the block
+    /// exits normally and discarded if it raises.
+    ///
+    /// Args:
+    ///     name: Job name.
+    ///     shots: Number of shots per circuit.
+    ///     backend: Backend name to pin the job to; matched if omitted.
+    #[pyo3(signature = (name="batch", shots=1024, backend=None))]
+    fn batch(&self, name: &str, shots: u32, backend: Option<String>) -> PyBatch {
+        PyBatch {
+            runtime: self.runtime.clone(),
+            scheduler: self.inner.clone(),
+            name: name.to_string(),
+            shots,
+            backend,
+            circuits: Vec::new(),
+            job_id: None,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Scheduler(state_dir='{}')",
+            self.inner.config().state_dir.display()
+        )
+    }
+}
+
+/// A run of jobs pinned to one backend for a bounded time.
+///
+/// Created by `Scheduler.session`.
+#[pyclass(name = "Session")]
+pub struct PySession {
+    runtime: Arc<Runtime>,
+    scheduler: Arc<HpcScheduler>,
+    session: Session,
+}
+
+#[pymethods]
+impl PySession {
+    /// Session ID, recorded in the metadata of its jobs.
+    #[getter]
+    fn id(&self) -> String {
+        self.session.id().to_string()
+    }
+
+    /// Backend the session's jobs run on.
+    #[getter]
+    fn backend(&self) -> String {
+        self.session.backend().to_string()
+    }
+
+    /// IDs of the jobs submitted in the session.
+    #[getter]
+    fn jobs(&self) -> Vec<String> {
+        self.session
+            .jobs()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Seconds left before the session expires.
+    #[getter]
+    fn remaining(&self) -> f64 {
+        self.session.remaining().as_secs_f64()
+    }
+
+    /// Whether the session still admits jobs.
+    #[getter]
+    fn is_active(&self) -> bool {
+        self.session.is_active()
+    }
+
+    /// Submit a circuit in the session.
+    ///
+    /// Returns:
+    ///     The job ID.
+    ///
+    /// Raises:
+    ///     RuntimeError: If the session is closed or expired.
+    #[pyo3(signature = (circuit, shots=1024, name=None))]
+    fn run(
+        &mut self,
+        py: Python<'_>,
+        circuit: &PyCircuit,
+        shots: u32,
+        name: Option<String>,
+    ) -> PyResult<String> {
+        let name = name.unwrap_or_else(|| format!("session-{}", self.session.jobs().len()));
+        let job = ScheduledJob::new(name, circuit_spec(circuit)?).with_shots(shots);
+        let Self {
+            runtime,
+            scheduler,
+            session,
+        } = self;
+        let job_id = py
+            .allow_threads(|| runtime.block_on(scheduler.submit_in_session(session, job)))
+            .map_err(sched_to_py_err)?;
+        Ok(job_id.to_string())
+    }
+
+    /// Stop admitting jobs; submitted jobs keep running.
+    fn close(&mut self) {
+        self.session.close();
+    }
+
+    /// Close the session and cancel its unfinished jobs.
+    ///
+    /// Returns:
+    ///     The number of jobs cancelled.
+    fn cancel(&mut self, py: Python<'_>) -> PyResult<usize> {
+        let Self {
+            runtime,
+            scheduler,
+            session,
+        } = self;
+        py.allow_threads(|| runtime.block_on(scheduler.cancel_session(session)))
+            .map_err(sched_to_py_err)
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        if exc_type.is_some_and(|t| !t.is_none()) {
+            self.cancel(py)?;
+        } else {
+            self.close();
+        }
+        Ok(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Session(backend='{}', jobs={}, active={})",
+            self.session.backend(),
+            self.session.jobs().len(),
+            self.session.is_active()
+        )
+    }
+}
+
+/// Circuits collected for submission as a single batch job.
+///
+/// Created by `Scheduler.batch`.
+#[pyclass(name = "Batch")]
+pub struct PyBatch {
+    runtime: Arc<Runtime>,
+    scheduler: Arc<HpcScheduler>,
+    name: String,
+    shots: u32,
+    backend: Option<String>,
+    circuits: Vec<CircuitSpec>,
+    job_id: Option<ScheduledJobId>,
+}
+
+#[pymethods]
+impl PyBatch {
+    /// ID of the submitted batch job, or None before submission.
+    #[getter]
+    fn job_id(&self) -> Option<String> {
+        self.job_id.as_ref().map(ToString::to_string)
+    }
+
+    /// Add a circuit to the batch.
+    ///
+    /// Returns:
+    ///     The index of the circuit's result in the batch job.
+    ///
+    /// Raises:
+    ///     RuntimeError: If the batch was already submitted.
+    fn run(&mut self, circuit: &PyCircuit) -> PyResult<usize> {
+        if self.job_id.is_some() {
+            return Err(PyRuntimeError::new_err("Batch was already submitted"));
+        }
+        self.circuits.push(circuit_spec(circuit)?);
+        Ok(self.circuits.len() - 1)
+    }
+
+    /// Submit the collected circuits as one job.
+    ///
+    /// Returns:
+    ///     The job ID, or None if no circuits were added.
+    fn submit(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        if self.job_id.is_none() && !self.circuits.is_empty() {
+            let mut job =
+                ScheduledJob::batch(&self.name, self.circuits.clone()).with_shots(self.shots);
+            job.matched_backend = self.backend.clone();
+            let job_id = py
+                .allow_threads(|| self.runtime.block_on(self.scheduler.submit(job)))
+                .map_err(sched_to_py_err)?;
+            self.job_id = Some(job_id);
+        }
+        Ok(self.job_id())
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    #[pyo3(signature = (exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        if exc_type.is_some_and(|t| !t.is_none()) {
+            self.circuits.clear();
+        } else {
+            self.submit(py)?;
+        }
+        Ok(false)
+    }
+
+    fn __len__(&self) -> usize {
+        self.circuits.len()
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.job_id {
+            Some(id) => format!("Batch(name='{}', job_id='{}')", self.name, id),
+            None => format!(
+                "Batch(name='{}', circuits={})",
+                self.name,
+                self.circuits.len()
+            ),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+"""Tests for the arvak Backend class and backend registry."""
+
+import json
+
+import pytest
+
+import arvak
+from arvak import Backend, Circuit
+
+
+class TestBackendIntrospection:
+    """Properties of built-in and custom backends."""
+
+    def test_builtin_backends(self):
+        names = [b.name for b in arvak.list_backends()]
+        assert "simulator" in names
+        assert "garnet" in names
+        assert "ibm_brisbane" in names
+
+    def test_get_backend(self):
+        garnet = arvak.get_backend("garnet")
+        assert garnet.num_qubits == 20
+        assert garnet.basis_gates == ["prx", "cz"]
+        assert (0, 1) in garnet.coupling_map
+        assert not garnet.is_simulator
+
+    def test_unknown_backend(self):
+        with pytest.raises(KeyError):
+            arvak.get_backend("no_such_device")
+
+    def test_custom_backend(self):
+        backend = Backend(
+            "grid",
+            4,
+            coupling_map=[(0, 1), (1, 3), (0, 2), (2, 3)],
+            basis_gates=["rz", "sx", "cz"],
+            calibration={"t1_us": 45.0},
+            queue_depth=3,
+        )
+        assert backend.coupling_map == [(0, 1), (1, 3), (0, 2), (2, 3)]
+        assert backend.calibration == {"t1_us": 45.0}
+        assert backend.queue_depth == 3
+        assert backend.to_coupling_map().is_connected(1, 3)
+        assert backend.to_basis_gates().contains("measure")
+
+    def test_edge_out_of_range(self):
+        with pytest.raises(ValueError):
+            Backend("bad", 2, coupling_map=[(0, 2)])
+
+    def test_json_roundtrip(self):
+        backend = Backend.iqm("garnet", 5)
+        data = json.loads(backend.to_json())
+        data["queue_depth"] = 12
+        restored = Backend.from_json(json.dumps(data))
+        assert restored == backend
+        assert restored.queue_depth == 12
+        assert restored.calibration is None
+
+
+class TestBackendMatching:
+    """Filtering backends by requirements."""
+
+    def test_matches(self):
+        ibm = Backend.ibm("ibm_test", 27)
+        assert ibm.matches(min_qubits=27, gates=["cx"], simulator=False)
+        assert ibm.matches(features=["dynamic_circuits"])
+        assert not ibm.matches(min_qubits=28)
+        assert not ibm.matches(gates=["cz"])
+        assert not ibm.matches(simulator=True)
+
+    def test_matches_circuit(self):
+        assert Backend.iqm("small", 3).matches(circuit=Circuit.ghz(3))
+        assert not Backend.iqm("small", 3).matches(circuit=Circuit.ghz(4))
+
+    def test_list_backends_filter(self):
+        names = [b.name for b in arvak.list_backends(min_qubits=100)]
+        assert names == ["ibm_brisbane"]
+        assert all(b.is_simulator for b in arvak.list_backends(simulator=True))
+
+    def test_register_backend(self):
+        arvak.register_backend(Backend.neutral_atom("atoms", 50, zones=2))
+        atoms = arvak.get_backend("atoms")
+        assert "shuttling" in atoms.features
+        assert atoms in arvak.list_backends(features=["zoned"])
+
+
+class TestTranspileTarget:
+    """Compiling for a backend with `target=`."""
+
+    def test_transpile_for_backend(self):
+        compiled = arvak.transpile(
+            Circuit.bell(), target=arvak.get_backend("garnet"), optimization_level=0
+        )
+        gates = {line.split()[0] for line in arvak.to_qasm(compiled).splitlines()}
+        assert "h" not in gates
+        assert "cx" not in gates
+
+    def test_transpile_for_simulator(self):
+        compiled = arvak.transpile(Circuit.bell(), target=Backend.simulator(2))
+        assert compiled.num_qubits == 2
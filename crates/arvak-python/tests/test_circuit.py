@@ -216,6 +216,55 @@ cx q[0], q[1];
         assert qc2.num_qubits == 2
 
 
+class TestFromArrays:
+    """Bulk construction from gate arrays."""
+
+    OP = {name: i for i, name in enumerate(Circuit.OPCODES)}
+
+    def test_matches_gate_calls(self):
+        np = pytest.importorskip("numpy")
+        opcodes = np.array(
+            [self.OP["h"], self.OP["rz"], self.OP["cx"], self.OP["measure"]],
+            dtype=np.uint8,
+        )
+        qubits = np.array([[0, 0], [1, 0], [0, 1], [1, 1]], dtype=np.int32)
+        params = np.array([[0.0], [0.5], [0.0], [0.0]])
+
+        qc = Circuit.from_arrays("gen", 2, opcodes, qubits, params, num_clbits=2)
+        expected = Circuit("gen", num_qubits=2, num_clbits=2)
+        expected.h(0).rz(0.5, 1).cx(0, 1).measure(1, 1)
+        assert arvak.to_qasm(qc) == arvak.to_qasm(expected)
+
+    def test_buffer_protocol_without_numpy(self):
+        import array
+
+        opcodes = array.array("q", [self.OP["h"], self.OP["cx"]])
+        qubits = memoryview(array.array("q", [0, 0, 0, 1])).cast("B").cast("q", [2, 2])
+        qc = Circuit.from_arrays("bell", 2, opcodes, qubits)
+        assert qc.depth() == 2
+
+    def test_missing_params(self):
+        np = pytest.importorskip("numpy")
+        with pytest.raises(ValueError):
+            Circuit.from_arrays("bad", 1, np.array([self.OP["rx"]]), np.array([[0]]))
+
+    def test_unknown_opcode(self):
+        np = pytest.importorskip("numpy")
+        with pytest.raises(ValueError):
+            Circuit.from_arrays("bad", 1, np.array([len(Circuit.OPCODES)]), np.array([[0]]))
+
+    def test_non_contiguous(self):
+        np = pytest.importorskip("numpy")
+        qubits = np.zeros((2, 4), dtype=np.int64)[:, ::2]
+        with pytest.raises(ValueError):
+            Circuit.from_arrays("bad", 1, np.array([self.OP["x"]] * 2), qubits)
+
+    def test_invalid_qubit(self):
+        np = pytest.importorskip("numpy")
+        with pytest.raises(RuntimeError):
+            Circuit.from_arrays("bad", 1, np.array([self.OP["x"]]), np.array([[3]]))
+
+
 class TestErrors:
     """Test error handling."""
 
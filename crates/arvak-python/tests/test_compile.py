@@ -2,7 +2,7 @@
 
 import pytest
 
-from arvak import Layout, CouplingMap, BasisGates, PropertySet, QubitId
+from arvak import Circuit, Layout, CouplingMap, BasisGates, PropertySet, QubitId, transpile
 
 
 class TestLayout:
@@ -183,5 +183,30 @@ class TestPropertySet:
         assert props.get_basis_gates() is not None
 
 
+class TestTranspile:
+    """Test transpile function."""
+
+    def test_transpile_with_preset(self):
+        """Test compiling with a named pipeline preset."""
+        qc = Circuit("test", num_qubits=3)
+        qc.h(0).cx(0, 2)
+        basis = BasisGates.ibm()
+
+        compiled = transpile(
+            qc, CouplingMap.linear(3), basis, preset="superconducting-heavyhex"
+        )
+        assert compiled.num_qubits == 3
+
+    def test_transpile_without_target(self):
+        """Test compiling without a target."""
+        compiled = transpile(Circuit.bell(), preset="simulator-fast")
+        assert compiled.num_qubits == 2
+
+    def test_unknown_preset(self):
+        """Test that an unknown preset name is rejected."""
+        with pytest.raises(ValueError, match="simulator-fast"):
+            transpile(Circuit.bell(), preset="fastest")
+
+
 if __name__ == "__main__":
     pytest.main([__file__, "-v"])
@@ -0,0 +1,69 @@
+"""Tests for the arvak Counts class."""
+
+import pytest
+
+from arvak import Counts
+
+
+class TestCountsBasics:
+    """Construction and lookups."""
+
+    def test_create_counts(self):
+        counts = Counts({"00": 510, "11": 490})
+        assert counts.shots == 1000
+        assert len(counts) == 2
+        assert counts["00"] == 510
+        assert counts.get("01") == 0
+        assert "11" in counts
+        assert "01" not in counts
+        assert counts.most_frequent() == ("00", 510)
+        assert counts.to_dict() == {"00": 510, "11": 490}
+
+    def test_empty_counts(self):
+        counts = Counts()
+        assert counts.shots == 0
+        assert counts.most_frequent() is None
+        assert counts.probabilities() == {}
+
+    def test_equality(self):
+        assert Counts({"0": 1, "1": 2}) == Counts({"1": 2, "0": 1})
+        assert Counts({"0": 1}) != Counts({"0": 2})
+
+
+class TestCountsAnalysis:
+    """Marginals, distances and expectation values."""
+
+    def test_probabilities(self):
+        probs = Counts({"0": 250, "1": 750}).probabilities()
+        assert probs["0"] == pytest.approx(0.25)
+        assert probs["1"] == pytest.approx(0.75)
+
+    def test_marginal(self):
+        counts = Counts({"011": 60, "100": 40})
+        assert counts.marginal([0]) == Counts({"1": 60, "0": 40})
+        # qubits[0] becomes the rightmost bit
+        assert counts.marginal([2, 0]) == Counts({"10": 60, "01": 40})
+
+    def test_distances(self):
+        a = Counts({"00": 500, "11": 500})
+        b = Counts({"00": 1000})
+        assert a.tvd(a) == pytest.approx(0.0)
+        assert a.tvd(b) == pytest.approx(0.5)
+        assert b.tvd(a) == pytest.approx(0.5)
+        assert a.hellinger(b) == pytest.approx((1 - 0.5**0.5) ** 0.5)
+
+    def test_expectation(self):
+        counts = Counts({"011": 60, "100": 40})
+        assert counts.expectation("IIZ") == pytest.approx(-0.2)
+        assert counts.expectation("IZZ") == pytest.approx(1.0)
+        assert counts.expectation("III") == pytest.approx(1.0)
+        with pytest.raises(ValueError):
+            counts.expectation("IQZ")
+
+    def test_to_dataframe(self):
+        pd = pytest.importorskip("pandas")
+        df = Counts({"00": 250, "11": 750}).to_dataframe()
+        assert isinstance(df, pd.DataFrame)
+        assert list(df.columns) == ["bitstring", "count", "probability"]
+        assert df["bitstring"].tolist() == ["11", "00"]
+        assert df["probability"].tolist() == pytest.approx([0.75, 0.25])
@@ -0,0 +1,82 @@
+"""Tests for the arvak Scheduler, Session and Batch classes."""
+
+import pytest
+
+from arvak import Backend, Circuit, Scheduler
+
+
+@pytest.fixture
+def scheduler(tmp_path):
+    backends = [Backend.iqm("garnet", 5), Backend.simulator(10)]
+    return Scheduler(backends=backends, state_dir=str(tmp_path), mock=True)
+
+
+class TestSubmit:
+    """Single jobs."""
+
+    def test_submit_and_cancel(self, scheduler):
+        job_id = scheduler.submit(Circuit.bell(), shots=100)
+        assert scheduler.status(job_id) == "Pending"
+        scheduler.cancel(job_id)
+        assert scheduler.status(job_id) == "Cancelled"
+
+    def test_invalid_job_id(self, scheduler):
+        with pytest.raises(ValueError):
+            scheduler.status("not-a-job")
+
+
+class TestSession:
+    """Sessions pinning jobs to one backend."""
+
+    def test_session_runs_jobs(self, scheduler):
+        with scheduler.session(backend="garnet", max_time="2h") as s:
+            assert s.is_active
+            assert 0 < s.remaining <= 7200
+            first = s.run(Circuit.bell(), shots=100)
+            second = s.run(Circuit.ghz(3))
+        assert s.jobs == [first, second]
+        assert not s.is_active
+        assert scheduler.status(first) == "Pending"
+
+    def test_closed_session_rejects_jobs(self, scheduler):
+        with scheduler.session(backend="garnet", max_time=600) as s:
+            pass
+        with pytest.raises(RuntimeError):
+            s.run(Circuit.bell())
+
+    def test_exception_cancels_jobs(self, scheduler):
+        with pytest.raises(KeyError):
+            with scheduler.session(backend="garnet") as s:
+                job_id = s.run(Circuit.bell())
+                raise KeyError("abort")
+        assert scheduler.status(job_id) == "Cancelled"
+
+    def test_invalid_max_time(self, scheduler):
+        with pytest.raises(ValueError):
+            scheduler.session(backend="garnet", max_time="soon")
+
+
+class TestBatch:
+    """Batches submitted as one job."""
+
+    def test_batch_submits_on_exit(self, scheduler):
+        with scheduler.batch(name="sweep", shots=200) as b:
+            assert b.run(Circuit.bell()) == 0
+            assert b.run(Circuit.ghz(3)) == 1
+            assert b.job_id is None
+        assert len(b) == 2
+        assert scheduler.status(b.job_id) == "Pending"
+        with pytest.raises(RuntimeError):
+            b.run(Circuit.bell())
+
+    def test_exception_discards_batch(self, scheduler):
+        with pytest.raises(KeyError):
+            with scheduler.batch() as b:
+                b.run(Circuit.bell())
+                raise KeyError("abort")
+        assert b.job_id is None
+
+    def test_empty_batch(self, scheduler):
+        with scheduler.batch() as b:
+            pass
+        assert b.job_id is None
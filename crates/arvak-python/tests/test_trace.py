@@ -0,0 +1,68 @@
+"""Tests for reading simulator execution traces."""
+
+import json
+
+import pytest
+
+import arvak
+from arvak.trace import parse_trace
+
+
+TRACE = {
+    "circuit": "bell",
+    "num_qubits": 2,
+    "mode": {"amplitudes": [0, 3]},
+    "steps": [
+        {
+            "index": 0,
+            "instruction": "h",
+            "qubits": [0],
+            "norm": 1.0,
+            "amplitudes": [[0, 0.7071067811865475, 0.0], [3, 0.0, 0.0]],
+        },
+        {
+            "index": 1,
+            "instruction": "cx",
+            "qubits": [0, 1],
+            "norm": 0.5,
+            "amplitudes": [[0, 0.7071067811865475, 0.0], [3, 0.0, 0.0]],
+        },
+    ],
+    "snapshots": [
+        {"label": "end", "index": 1, "amplitudes": [[1.0, 0.0], [0.0, 0.0]]}
+    ],
+}
+
+
+class TestExecutionTrace:
+    """Parsing and inspecting traces."""
+
+    def test_parse(self):
+        trace = parse_trace(TRACE)
+        assert trace.mode == "amplitudes"
+        assert trace.norms() == [1.0, 0.5]
+        assert trace.amplitude_history(3) == [0j, 0j]
+        assert trace.amplitude_history(1) == [None, None]
+        assert trace.snapshot("end") == [1 + 0j, 0j]
+        assert trace.snapshot("missing") is None
+
+    def test_first_deviation(self):
+        step = parse_trace(TRACE).first_deviation()
+        assert step.index == 1
+        assert step.instruction == "cx"
+
+    def test_load_trace(self, tmp_path):
+        path = tmp_path / "trace.json"
+        path.write_text(json.dumps(dict(TRACE, mode="norms")))
+        trace = arvak.load_trace(str(path))
+        assert trace.mode == "norms"
+        assert len(trace.steps) == 2
+
+    def test_snapshot_marks_barrier(self):
+        qc = arvak.Circuit("bell", num_qubits=2)
+        qc.h(0).snapshot("superposed").cx(0, 1)
+        assert '@arvak.snapshot "superposed"' in arvak.to_qasm(qc)
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
@@ -72,6 +72,22 @@ pub enum Statement {
         index: Option<u32>,
         value: Expression,
     },
+
+    /// Statement preceded by `@keyword content` annotation lines.
+    Annotated {
+        annotations: Vec<Annotation>,
+        statement: Box<Statement>,
+    },
+}
+
+/// An annotation: `@keyword content`, where the content runs to the end of
+/// the line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Keyword after the `@`, e.g. `arvak.noise_scale`.
+    pub keyword: String,
+    /// Rest of the line, if not empty.
+    pub content: Option<String>,
 }
 
 /// A gate call.
@@ -218,6 +234,8 @@ pub enum Expression {
     },
     /// Parenthesized expression.
     Paren(Box<Expression>),
+    /// Duration literal: `100ns`, `2.5us`, `160dt`.
+    Duration { value: f64, unit: DurationUnit },
 }
 
 impl Expression {
@@ -259,6 +277,57 @@ impl Expression {
     }
 }
 
+/// Unit of a duration literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DurationUnit {
+    /// Backend sample periods.
+    Dt,
+    /// Nanoseconds.
+    Ns,
+    /// Microseconds (`us` or `µs`).
+    Us,
+    /// Milliseconds.
+    Ms,
+    /// Seconds.
+    S,
+}
+
+impl DurationUnit {
+    /// Parse a duration suffix.
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        Some(match suffix {
+            "dt" => DurationUnit::Dt,
+            "ns" => DurationUnit::Ns,
+            "us" | "µs" => DurationUnit::Us,
+            "ms" => DurationUnit::Ms,
+            "s" => DurationUnit::S,
+            _ => return None,
+        })
+    }
+
+    /// The QASM3 suffix of this unit.
+    pub fn suffix(self) -> &'static str {
+        match self {
+            DurationUnit::Dt => "dt",
+            DurationUnit::Ns => "ns",
+            DurationUnit::Us => "us",
+            DurationUnit::Ms => "ms",
+            DurationUnit::S => "s",
+        }
+    }
+
+    /// Nanoseconds per unit, or `None` for `dt`.
+    pub fn nanoseconds(self) -> Option<f64> {
+        match self {
+            DurationUnit::Dt => None,
+            DurationUnit::Ns => Some(1.0),
+            DurationUnit::Us => Some(1e3),
+            DurationUnit::Ms => Some(1e6),
+            DurationUnit::S => Some(1e9),
+        }
+    }
+}
+
 /// Binary operators.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BinOp {
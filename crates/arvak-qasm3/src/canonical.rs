@@ -0,0 +1,203 @@
+//! Canonical QASM3 form for hashing and diffing circuits.
+//!
+//! QASM text written by hand or by different tools differs in formatting,
+//! operation order and float spelling even when the circuits are the same.
+//! The canonical form (see [`emit_canonical`]) removes these differences, so
+//! it can be hashed to identify a circuit and compared line by line to show
+//! how two circuits differ.
+
+use arvak_ir::Circuit;
+use arvak_ir::hash::fnv1a;
+
+use crate::emitter::emit_canonical;
+use crate::error::ParseResult;
+use crate::parser::parse;
+
+/// Largest diff table computed before falling back to a plain listing.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Parse QASM3 source and re-emit it in canonical form.
+pub fn canonicalize(source: &str) -> ParseResult<String> {
+    emit_canonical(&parse(source)?)
+}
+
+/// Compute a stable hash of a circuit's canonical form.
+///
+/// The hash is FNV-1a over the canonical text, so it is stable across
+/// processes, platforms and releases as long as the canonical form is.
+pub fn canonical_hash(circuit: &Circuit) -> ParseResult<u64> {
+    let text = emit_canonical(circuit)?;
+    Ok(fnv1a(text.as_bytes()))
+}
+
+/// Describe how two circuits differ, as a line diff of their canonical forms.
+///
+/// Returns `None` if the canonical forms are identical. Removed lines are
+/// prefixed with `-`, added lines with `+`, and one line of context is shown
+/// around each change.
+pub fn canonical_diff(expected: &Circuit, actual: &Circuit) -> ParseResult<Option<String>> {
+    let expected = emit_canonical(expected)?;
+    let actual = emit_canonical(actual)?;
+    if expected == actual {
+        return Ok(None);
+    }
+
+    let a: Vec<&str> = expected.lines().collect();
+    let b: Vec<&str> = actual.lines().collect();
+    let edits = line_edits(&a, &b);
+
+    let mut out = String::from("--- expected\n+++ actual\n");
+    let mut last_shown = None;
+    for (i, edit) in edits.iter().enumerate() {
+        let near_change = |j: usize| edits.get(j).is_some_and(|e| !matches!(e, Edit::Keep(_)));
+        let show =
+            !matches!(edit, Edit::Keep(_)) || (i > 0 && near_change(i - 1)) || near_change(i + 1);
+        if !show {
+            continue;
+        }
+        if last_shown.is_some_and(|last| last + 1 < i) {
+            out.push_str("  ...\n");
+        }
+        last_shown = Some(i);
+
+        match edit {
+            Edit::Keep(line) => out.push_str(&format!("  {}\n", line)),
+            Edit::Remove(line) => out.push_str(&format!("- {}\n", line)),
+            Edit::Add(line) => out.push_str(&format!("+ {}\n", line)),
+        }
+    }
+
+    Ok(Some(out))
+}
+
+enum Edit<'a> {
+    Keep(&'a str),
+    Remove(&'a str),
+    Add(&'a str),
+}
+
+/// Compute a line edit script from `a` to `b` via longest common subsequence.
+fn line_edits<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<Edit<'a>> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut edits: Vec<Edit<'a>> = a[..prefix].iter().map(|l| Edit::Keep(l)).collect();
+
+    let (n, m) = (a_mid.len(), b_mid.len());
+    if (n + 1) * (m + 1) > MAX_DIFF_CELLS {
+        edits.extend(a_mid.iter().map(|l| Edit::Remove(l)));
+        edits.extend(b_mid.iter().map(|l| Edit::Add(l)));
+    } else {
+        // lcs[i][j] = LCS length of a_mid[i..] and b_mid[j..]
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if a_mid[i] == b_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if a_mid[i] == b_mid[j] {
+                edits.push(Edit::Keep(a_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+                edits.push(Edit::Remove(a_mid[i]));
+                i += 1;
+            } else {
+                edits.push(Edit::Add(b_mid[j]));
+                j += 1;
+            }
+        }
+        edits.extend(a_mid[i..].iter().map(|l| Edit::Remove(l)));
+        edits.extend(b_mid[j..].iter().map(|l| Edit::Add(l)));
+    }
+
+    edits.extend(a[a.len() - suffix..].iter().map(|l| Edit::Keep(l)));
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formatting_and_order_ignored() {
+        let a = r#"OPENQASM 3.0;
+qubit[3] q;
+h q[0];
+x q[2];
+rz(pi/2) q[1];
+cx q[0], q[1];
+"#;
+        let b = r#"OPENQASM 3.0;
+qubit[3]   q;
+rz(1.5707963267949) q[1];
+x q[2];   // independent of q[0]
+h q[0];
+cx q[0],q[1];
+"#;
+
+        let canonical = canonicalize(a).unwrap();
+        assert_eq!(canonical, canonicalize(b).unwrap());
+        assert!(canonical.contains("h q[0];\nrz(1.5707963268) q[1];\nx q[2];\ncx q[0], q[1];"));
+
+        // The canonical form is itself valid QASM and a fixed point.
+        assert_eq!(canonicalize(&canonical).unwrap(), canonical);
+        assert_eq!(
+            canonical_hash(&parse(a).unwrap()).unwrap(),
+            canonical_hash(&parse(b).unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let expected =
+            parse("OPENQASM 3.0;\nqubit[2] q;\nh q[0];\ncx q[0], q[1];\nrz(0.5) q[1];").unwrap();
+        let actual =
+            parse("OPENQASM 3.0;\nqubit[2] q;\nh q[0];\ncx q[0], q[1];\nrz(0.25) q[1];").unwrap();
+
+        assert!(canonical_diff(&expected, &expected).unwrap().is_none());
+        assert_ne!(
+            canonical_hash(&expected).unwrap(),
+            canonical_hash(&actual).unwrap()
+        );
+
+        let diff = canonical_diff(&expected, &actual).unwrap().unwrap();
+        assert!(diff.contains("  cx q[0], q[1];\n- rz(0.5) q[1];\n+ rz(0.25) q[1];\n"));
+        assert!(!diff.contains("h q[0]"));
+    }
+
+    fn roundtrip_gates() -> Vec<arvak_ir::StandardGate> {
+        // The parser drops `id` as a no-op.
+        arvak_ir::random::standard_gates()
+            .into_iter()
+            .filter(|g| *g != arvak_ir::StandardGate::I)
+            .collect()
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn fuzz_roundtrip(circuit in arvak_ir::strategy::circuit_with_gates(roundtrip_gates(), 5, 8)) {
+            let emitted = crate::emit(&circuit).unwrap();
+            let reparsed = parse(&emitted).unwrap();
+            proptest::prop_assert_eq!(reparsed.num_qubits(), circuit.num_qubits());
+            proptest::prop_assert_eq!(reparsed.dag().num_ops(), circuit.dag().num_ops());
+
+            let canonical = emit_canonical(&circuit).unwrap();
+            let diff = canonical_diff(&circuit, &parse(&canonical).unwrap()).unwrap();
+            proptest::prop_assert!(diff.is_none(), "{}", diff.unwrap_or_default());
+        }
+    }
+}
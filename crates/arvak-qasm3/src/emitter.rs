@@ -1,8 +1,10 @@
 //! QASM3 emitter for serializing circuits.
 
 use arvak_ir::{
-    Circuit, GateKind, Instruction, InstructionKind, ParameterExpression, StandardGate,
+    Circuit, ClbitId, GateKind, Instruction, InstructionKind, ParameterExpression, QubitId,
+    StandardGate,
 };
+use rustc_hash::FxHashMap;
 
 use crate::error::ParseResult;
 
@@ -12,10 +14,26 @@ pub fn emit(circuit: &Circuit) -> ParseResult<String> {
     emitter.emit_circuit(circuit)
 }
 
+/// Emit a circuit as canonical QASM3 source code.
+///
+/// Circuits with the same operations produce identical text regardless of
+/// how they were built: operations are emitted layer by layer and ordered by
+/// qubit within a layer, and constant parameters are folded and printed with
+/// a fixed precision.
+pub fn emit_canonical(circuit: &Circuit) -> ParseResult<String> {
+    let mut emitter = Emitter::new();
+    emitter.canonical = true;
+    emitter.emit_circuit(circuit)
+}
+
+/// Decimal places kept for canonical parameter values.
+const CANONICAL_PRECISION: usize = 10;
+
 /// QASM3 emitter.
 struct Emitter {
     output: String,
     indent: usize,
+    canonical: bool,
 }
 
 impl Emitter {
@@ -23,6 +41,7 @@ impl Emitter {
         Self {
             output: String::new(),
             indent: 0,
+            canonical: false,
         }
     }
 
@@ -48,14 +67,24 @@ impl Emitter {
         }
 
         // Instructions
-        for (_, instruction) in circuit.dag().topological_ops() {
-            self.emit_instruction(instruction)?;
+        if self.canonical {
+            for instruction in canonical_order(circuit) {
+                self.emit_instruction(instruction)?;
+            }
+        } else {
+            for (_, instruction) in circuit.dag().topological_ops() {
+                self.emit_instruction(instruction)?;
+            }
         }
 
         Ok(self.output.clone())
     }
 
     fn emit_instruction(&mut self, instruction: &Instruction) -> ParseResult<()> {
+        if !matches!(instruction.kind, InstructionKind::Shuttle { .. }) {
+            self.emit_annotations(instruction);
+        }
+
         match &instruction.kind {
             InstructionKind::Gate(gate) => {
                 let name = self.emit_gate_name(&gate.kind);
@@ -77,7 +106,15 @@ impl Emitter {
                     self.writeln(&format!("{} = measure {};", clbits, qubits));
                 } else {
                     // Broadcast measurement
-                    for (q, c) in instruction.qubits.iter().zip(instruction.clbits.iter()) {
+                    for (i, (q, c)) in instruction
+                        .qubits
+                        .iter()
+                        .zip(instruction.clbits.iter())
+                        .enumerate()
+                    {
+                        if i > 0 {
+                            self.emit_annotations(instruction);
+                        }
                         self.writeln(&format!("c[{}] = measure q[{}];", c.0, q.0));
                     }
                 }
@@ -97,9 +134,9 @@ impl Emitter {
                 }
             }
 
-            InstructionKind::Delay { duration } => {
+            InstructionKind::Delay { duration, unit } => {
                 let qubits = self.emit_qubits(&instruction.qubits);
-                self.writeln(&format!("delay[{}] {};", duration, qubits));
+                self.writeln(&format!("delay[{}{}] {};", duration, unit, qubits));
             }
 
             InstructionKind::Shuttle { from_zone, to_zone } => {
@@ -115,6 +152,16 @@ impl Emitter {
         Ok(())
     }
 
+    /// Write an `@arvak.<key> <value>` line for each annotation.
+    ///
+    /// Annotations apply to the next statement only, so callers emitting one
+    /// instruction as several statements repeat them.
+    fn emit_annotations(&mut self, instruction: &Instruction) {
+        for (key, value) in instruction.annotations.iter() {
+            self.writeln(&format!("@arvak.{} {}", key, value));
+        }
+    }
+
     fn emit_gate_name(&self, kind: &GateKind) -> String {
         match kind {
             GateKind::Standard(std) => match std {
@@ -140,6 +187,8 @@ impl Emitter {
                 StandardGate::CH => "ch".into(),
                 StandardGate::Swap => "swap".into(),
                 StandardGate::ISwap => "iswap".into(),
+                StandardGate::ECR => "ecr".into(),
+                StandardGate::CSX => "csx".into(),
                 StandardGate::CRx(_) => "crx".into(),
                 StandardGate::CRy(_) => "cry".into(),
                 StandardGate::CRz(_) => "crz".into(),
@@ -147,8 +196,10 @@ impl Emitter {
                 StandardGate::RXX(_) => "rxx".into(),
                 StandardGate::RYY(_) => "ryy".into(),
                 StandardGate::RZZ(_) => "rzz".into(),
+                StandardGate::RZX(_) => "rzx".into(),
                 StandardGate::CCX => "ccx".into(),
                 StandardGate::CSwap => "cswap".into(),
+                StandardGate::CCZ => "ccz".into(),
                 StandardGate::PRX(_, _) => "prx".into(),
             },
             GateKind::Custom(custom) => custom.name.clone(),
@@ -179,6 +230,12 @@ impl Emitter {
     }
 
     fn emit_param(&self, param: &ParameterExpression) -> String {
+        if self.canonical {
+            if let Some(v) = param.as_f64() {
+                return format_canonical_float(v);
+            }
+        }
+
         match param {
             ParameterExpression::Constant(v) => {
                 // Check if close to common fractions of pi
@@ -243,10 +300,74 @@ impl Emitter {
     }
 }
 
+/// Order a circuit's instructions by ASAP layer, then by first qubit.
+///
+/// Instructions in the same layer act on disjoint wires, so any order among
+/// them is equivalent; sorting by qubit makes it deterministic.
+fn canonical_order(circuit: &Circuit) -> Vec<&Instruction> {
+    let mut qubit_layer: FxHashMap<QubitId, usize> = FxHashMap::default();
+    let mut clbit_layer: FxHashMap<ClbitId, usize> = FxHashMap::default();
+    let mut layered = Vec::new();
+
+    for (_, instruction) in circuit.dag().topological_ops() {
+        // A barrier without operands spans every wire.
+        let all_wires =
+            instruction.qubits.is_empty() && matches!(instruction.kind, InstructionKind::Barrier);
+        let layer = if all_wires {
+            qubit_layer
+                .values()
+                .chain(clbit_layer.values())
+                .copied()
+                .max()
+                .unwrap_or(0)
+                + 1
+        } else {
+            instruction
+                .qubits
+                .iter()
+                .filter_map(|q| qubit_layer.get(q))
+                .chain(instruction.clbits.iter().filter_map(|c| clbit_layer.get(c)))
+                .copied()
+                .max()
+                .unwrap_or(0)
+                + 1
+        };
+
+        if all_wires {
+            for q in circuit.dag().qubits() {
+                qubit_layer.insert(q, layer);
+            }
+            for c in circuit.dag().clbits() {
+                clbit_layer.insert(c, layer);
+            }
+        } else {
+            for q in &instruction.qubits {
+                qubit_layer.insert(*q, layer);
+            }
+            for c in &instruction.clbits {
+                clbit_layer.insert(*c, layer);
+            }
+        }
+
+        let first_qubit = instruction.qubits.iter().map(|q| q.0).min();
+        let first_clbit = instruction.clbits.iter().map(|c| c.0).min();
+        layered.push((layer, first_qubit, first_clbit, instruction));
+    }
+
+    layered.sort_by_key(|(layer, qubit, clbit, _)| (*layer, *qubit, *clbit));
+    layered.into_iter().map(|(.., inst)| inst).collect()
+}
+
+/// Format a float with fixed precision, trimming trailing zeros.
+fn format_canonical_float(v: f64) -> String {
+    let s = format!("{:.*}", CANONICAL_PRECISION, v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".into() } else { s.into() }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use arvak_ir::QubitId;
 
     #[test]
     fn test_emit_bell_state() {
@@ -287,6 +408,57 @@ cx q[0], q[1];
         assert_eq!(circuit.depth(), circuit2.depth());
     }
 
+    #[test]
+    fn test_roundtrip_delay() {
+        let source = r#"OPENQASM 3.0;
+qubit[2] q;
+h q[0];
+delay[160dt] q[0];
+delay[1us] q[0], q[1];
+"#;
+
+        let circuit = crate::parse(source).unwrap();
+        let emitted = emit(&circuit).unwrap();
+        assert!(emitted.contains("delay[160dt] q[0];"));
+        assert!(emitted.contains("delay[1000ns] q[0], q[1];"));
+
+        let circuit2 = crate::parse(&emitted).unwrap();
+        assert_eq!(crate::canonical_diff(&circuit, &circuit2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_roundtrip_annotations() {
+        use arvak_ir::{Annotations, ClbitId, Instruction, QubitId, StandardGate};
+
+        let mut circuit = Circuit::with_size("test", 2, 2);
+        let x = Instruction::single_qubit_gate(StandardGate::X, QubitId(0))
+            .with_annotation(Annotations::NOISE_SCALE, 3.0)
+            .with_annotation(Annotations::PROVENANCE, "zne \"fold\"");
+        circuit.dag_mut().apply(x).unwrap();
+        let measure = Instruction::measure_all([QubitId(0), QubitId(1)], [ClbitId(0), ClbitId(1)])
+            .with_annotation("readout_group", 1i64);
+        circuit.dag_mut().apply(measure).unwrap();
+
+        let emitted = emit(&circuit).unwrap();
+        assert!(
+            emitted.contains(
+                "@arvak.noise_scale 3.0\n@arvak.provenance \"zne \\\"fold\\\"\"\nx q[0];"
+            )
+        );
+        assert_eq!(emitted.matches("@arvak.readout_group 1\n").count(), 2);
+
+        let parsed = crate::parse(&emitted).unwrap();
+        for (_, inst) in parsed.dag().topological_ops() {
+            if inst.is_measure() {
+                assert!(inst.annotations.get("readout_group").is_some());
+            } else {
+                assert_eq!(inst.annotations.noise_scale(), Some(3.0));
+                assert_eq!(inst.annotations.provenance(), Some("zne \"fold\""));
+            }
+        }
+        assert_eq!(parsed.dag().num_ops(), 3);
+    }
+
     #[test]
     fn test_roundtrip_missing_gates() {
         // Test all 7 gates that were previously missing from the parser
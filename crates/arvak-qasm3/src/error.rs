@@ -62,6 +62,14 @@ pub enum ParseError {
         size: usize,
     },
 
+    /// Invalid delay duration.
+    #[error("Invalid duration: {0}")]
+    InvalidDuration(String),
+
+    /// Malformed `@arvak.*` annotation.
+    #[error("Invalid annotation: {0}")]
+    InvalidAnnotation(String),
+
     /// IR error during circuit construction.
     #[error("Circuit error: {0}")]
     CircuitError(#[from] arvak_ir::IrError),
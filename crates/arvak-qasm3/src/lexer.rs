@@ -2,6 +2,8 @@
 
 use logos::Logos;
 
+use crate::ast::DurationUnit;
+
 /// Tokens for OpenQASM 3.
 #[derive(Logos, Debug, Clone, PartialEq)]
 #[logos(skip r"[ \t\r\n]+")]
@@ -109,6 +111,9 @@ pub enum Token {
     #[regex(r"[0-9]+", |lex| lex.slice().parse::<u64>().ok())]
     IntLiteral(u64),
 
+    #[regex(r"[0-9]+(\.[0-9]*)?(dt|ns|us|µs|ms|s)", |lex| parse_duration(lex.slice()))]
+    DurationLiteral((f64, DurationUnit)),
+
     #[regex(r#""[^"]*""#, |lex| {
         let s = lex.slice();
         Some(s[1..s.len()-1].to_string())
@@ -201,8 +206,9 @@ pub enum Token {
     #[token("->")]
     Arrow,
 
-    #[token("@")]
-    At,
+    /// An annotation line, without the `@`.
+    #[regex(r"@[^\n]*", |lex| lex.slice()[1..].trim().to_string())]
+    Annotation(String),
 
     #[token("(")]
     LParen,
@@ -270,6 +276,7 @@ impl std::fmt::Display for Token {
             Token::False => write!(f, "false"),
             Token::FloatLiteral(v) => write!(f, "{}", v),
             Token::IntLiteral(v) => write!(f, "{}", v),
+            Token::DurationLiteral((v, unit)) => write!(f, "{}{}", v, unit.suffix()),
             Token::StringLiteral(s) => write!(f, "\"{}\"", s),
             Token::Identifier(s) => write!(f, "{}", s),
             Token::Plus => write!(f, "+"),
@@ -299,7 +306,7 @@ impl std::fmt::Display for Token {
             Token::StarEq => write!(f, "*="),
             Token::SlashEq => write!(f, "/="),
             Token::Arrow => write!(f, "->"),
-            Token::At => write!(f, "@"),
+            Token::Annotation(s) => write!(f, "@{}", s),
             Token::LParen => write!(f, "("),
             Token::RParen => write!(f, ")"),
             Token::LBracket => write!(f, "["),
@@ -314,6 +321,13 @@ impl std::fmt::Display for Token {
     }
 }
 
+/// Split a duration literal such as `2.5us` into its value and unit.
+fn parse_duration(slice: &str) -> Option<(f64, DurationUnit)> {
+    let split = slice.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, suffix) = slice.split_at(split);
+    Some((value.parse().ok()?, DurationUnit::from_suffix(suffix)?))
+}
+
 /// A token with its span information.
 #[derive(Debug, Clone)]
 pub struct SpannedToken {
@@ -407,6 +421,23 @@ mod tests {
         assert_eq!(tokens[5].token, Token::RParen);
     }
 
+    #[test]
+    fn test_duration_literals() {
+        let tokens: Vec<_> = tokenize("delay[100ns] q; delay[2.5us] q; delay[160dt] q;")
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|t| t.token)
+            .collect();
+
+        assert_eq!(tokens[0], Token::Delay);
+        assert_eq!(tokens[2], Token::DurationLiteral((100.0, DurationUnit::Ns)));
+        assert_eq!(tokens[8], Token::DurationLiteral((2.5, DurationUnit::Us)));
+        assert_eq!(
+            tokens[14],
+            Token::DurationLiteral((160.0, DurationUnit::Dt))
+        );
+    }
+
     #[test]
     fn test_comments() {
         let source = r#"
@@ -74,6 +74,17 @@
 //! assert_eq!(circuit.num_qubits(), reparsed.num_qubits());
 //! ```
 //!
+//! # Example: Canonical Form
+//!
+//! ```rust
+//! use arvak_qasm3::canonicalize;
+//!
+//! // Independent gates in a different order, different float spelling
+//! let a = canonicalize("OPENQASM 3.0; qubit[2] q; h q[0]; rz(pi/2) q[1];").unwrap();
+//! let b = canonicalize("OPENQASM 3.0; qubit[2] q; rz(1.5707963268) q[1]; h q[0];").unwrap();
+//! assert_eq!(a, b);
+//! ```
+//!
 //! # Supported Gates
 //!
 //! Single-qubit: `id`, `x`, `y`, `z`, `h`, `s`, `sdg`, `t`, `tdg`, `sx`, `sxdg`
@@ -85,12 +96,14 @@
 //! Three-qubit: `ccx` (Toffoli), `cswap` (Fredkin)
 
 mod ast;
+mod canonical;
 mod emitter;
 mod error;
 mod lexer;
 mod parser;
 
-pub use emitter::emit;
+pub use canonical::{canonical_diff, canonical_hash, canonicalize};
+pub use emitter::{emit, emit_canonical};
 pub use error::{ParseError, ParseResult};
 pub use parser::parse;
 
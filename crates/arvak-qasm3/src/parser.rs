@@ -2,7 +2,9 @@
 
 use std::collections::HashMap;
 
-use arvak_ir::{Circuit, ClbitId, ParameterExpression, QubitId};
+use arvak_ir::{
+    AnnotationValue, Annotations, Circuit, ClbitId, ParameterExpression, QubitId, TimeUnit,
+};
 
 use crate::ast::*;
 use crate::error::{ParseError, ParseResult};
@@ -149,10 +151,12 @@ impl Parser {
             Token::Measure => self.parse_measure(),
             Token::Reset => self.parse_reset(),
             Token::Barrier => self.parse_barrier(),
+            Token::Delay => self.parse_delay(),
             Token::If => self.parse_if(),
             Token::For => self.parse_for(),
             Token::Gate => self.parse_gate_def(),
             Token::Identifier(_) => self.parse_identifier_statement(),
+            Token::Annotation(_) => self.parse_annotated(),
             _ => Err(ParseError::UnexpectedToken {
                 line: self.line,
                 expected: "statement".into(),
@@ -161,6 +165,30 @@ impl Parser {
         }
     }
 
+    /// Parse annotation lines and the statement they annotate.
+    fn parse_annotated(&mut self) -> ParseResult<Statement> {
+        let mut annotations = Vec::new();
+        while let Some(Token::Annotation(text)) = self.peek().cloned() {
+            self.advance();
+            let (keyword, content) = text.split_once(char::is_whitespace).unwrap_or((&text, ""));
+            if keyword.is_empty() {
+                return Err(ParseError::InvalidAnnotation(
+                    "missing keyword after '@'".into(),
+                ));
+            }
+            annotations.push(Annotation {
+                keyword: keyword.to_string(),
+                content: Some(content.trim().to_string()).filter(|c| !c.is_empty()),
+            });
+        }
+
+        let statement = Box::new(self.parse_statement()?);
+        Ok(Statement::Annotated {
+            annotations,
+            statement,
+        })
+    }
+
     /// Parse include statement.
     fn parse_include(&mut self) -> ParseResult<Statement> {
         self.expect(Token::Include)?;
@@ -254,6 +282,21 @@ impl Parser {
         Ok(Statement::Barrier { qubits })
     }
 
+    /// Parse delay statement.
+    fn parse_delay(&mut self) -> ParseResult<Statement> {
+        self.expect(Token::Delay)?;
+        self.expect(Token::LBracket)?;
+        let duration = self.parse_expression()?;
+        self.expect(Token::RBracket)?;
+        let qubits = if !self.check(&Token::Semicolon) {
+            self.parse_qubit_refs()?
+        } else {
+            vec![]
+        };
+        self.expect(Token::Semicolon)?;
+        Ok(Statement::Delay { duration, qubits })
+    }
+
     /// Parse if statement.
     fn parse_if(&mut self) -> ParseResult<Statement> {
         self.expect(Token::If)?;
@@ -532,6 +575,10 @@ impl Parser {
                 self.advance();
                 Ok(Expression::Float(v))
             }
+            Token::DurationLiteral((value, unit)) => {
+                self.advance();
+                Ok(Expression::Duration { value, unit })
+            }
             Token::Pi => {
                 self.advance();
                 Ok(Expression::Pi)
@@ -672,6 +719,30 @@ fn lower_to_circuit(program: &Program) -> ParseResult<Circuit> {
     lowerer.lower(program)
 }
 
+/// Collect the `@arvak.<key> <value>` annotations of a statement.
+///
+/// Annotations with other keywords belong to other tools and are ignored.
+fn arvak_annotations(annotations: &[Annotation]) -> ParseResult<Annotations> {
+    let mut result = Annotations::new();
+    for annotation in annotations {
+        let Some(key) = annotation.keyword.strip_prefix("arvak.") else {
+            continue;
+        };
+        let value = annotation
+            .content
+            .as_deref()
+            .and_then(AnnotationValue::parse)
+            .ok_or_else(|| {
+                ParseError::InvalidAnnotation(format!(
+                    "@{} needs a boolean, number or quoted string value",
+                    annotation.keyword
+                ))
+            })?;
+        result.insert(key, value);
+    }
+    Ok(result)
+}
+
 /// Lowers AST to Circuit.
 struct Lowerer {
     /// Qubit registers: name -> (start_id, size).
@@ -696,7 +767,10 @@ impl Lowerer {
 
     fn lower(&mut self, program: &Program) -> ParseResult<Circuit> {
         // First pass: collect declarations
-        for stmt in &program.statements {
+        for mut stmt in &program.statements {
+            while let Statement::Annotated { statement, .. } = stmt {
+                stmt = statement;
+            }
             match stmt {
                 Statement::QubitDecl { name, size } => {
                     let size = size.unwrap_or(1);
@@ -791,8 +865,40 @@ impl Lowerer {
                 Ok(())
             }
 
-            Statement::Delay { .. } => {
-                // TODO: Implement delays
+            Statement::Annotated {
+                annotations,
+                statement,
+            } => {
+                let annotations = arvak_annotations(annotations)?;
+                if annotations.is_empty() {
+                    return self.lower_statement(circuit, statement);
+                }
+
+                // Lower into a scratch circuit so the annotations reach every
+                // instruction the statement expands to.
+                let mut scratch = Circuit::with_size(
+                    "",
+                    circuit.num_qubits() as u32,
+                    circuit.num_clbits() as u32,
+                );
+                self.lower_statement(&mut scratch, statement)?;
+                for (_, instruction) in scratch.dag().topological_ops() {
+                    let mut instruction = instruction.clone();
+                    instruction
+                        .annotations
+                        .extend(annotations.iter().map(|(k, v)| (k, v.clone())));
+                    circuit.dag_mut().apply(instruction)?;
+                }
+                Ok(())
+            }
+
+            Statement::Delay { duration, qubits } => {
+                let (duration, unit) = expr_to_duration(duration)?;
+                let mut q_ids = self.resolve_qubits(qubits)?;
+                if q_ids.is_empty() {
+                    q_ids = (0..circuit.num_qubits() as u32).map(QubitId).collect();
+                }
+                circuit.delay_with_unit(q_ids, duration, unit)?;
                 Ok(())
             }
         }
@@ -907,6 +1013,25 @@ impl Lowerer {
                 }
                 Ok(())
             }
+            "u1" => {
+                check_param_count("u1", &params, 1)?;
+                for q in qubits {
+                    circuit.p(params[0].clone(), q)?;
+                }
+                Ok(())
+            }
+            "u2" => {
+                check_param_count("u2", &params, 2)?;
+                for q in qubits {
+                    circuit.u(
+                        ParameterExpression::Pi / ParameterExpression::constant(2.0),
+                        params[0].clone(),
+                        params[1].clone(),
+                        q,
+                    )?;
+                }
+                Ok(())
+            }
             "prx" => {
                 check_param_count("prx", &params, 2)?;
                 for q in qubits {
@@ -941,6 +1066,16 @@ impl Lowerer {
                 circuit.iswap(qubits[0], qubits[1])?;
                 Ok(())
             }
+            "ecr" => {
+                check_qubit_count("ecr", &qubits, 2)?;
+                circuit.ecr(qubits[0], qubits[1])?;
+                Ok(())
+            }
+            "csx" => {
+                check_qubit_count("csx", &qubits, 2)?;
+                circuit.csx(qubits[0], qubits[1])?;
+                Ok(())
+            }
             "crz" => {
                 check_param_count("crz", &params, 1)?;
                 check_qubit_count("crz", &qubits, 2)?;
@@ -988,6 +1123,12 @@ impl Lowerer {
                 circuit.rzz(params[0].clone(), qubits[0], qubits[1])?;
                 Ok(())
             }
+            "rzx" => {
+                check_param_count("rzx", &params, 1)?;
+                check_qubit_count("rzx", &qubits, 2)?;
+                circuit.rzx(params[0].clone(), qubits[0], qubits[1])?;
+                Ok(())
+            }
 
             // Three-qubit gates
             "ccx" | "toffoli" => {
@@ -995,6 +1136,11 @@ impl Lowerer {
                 circuit.ccx(qubits[0], qubits[1], qubits[2])?;
                 Ok(())
             }
+            "ccz" => {
+                check_qubit_count("ccz", &qubits, 3)?;
+                circuit.ccz(qubits[0], qubits[1], qubits[2])?;
+                Ok(())
+            }
             "cswap" | "fredkin" => {
                 check_qubit_count("cswap", &qubits, 3)?;
                 circuit.cswap(qubits[0], qubits[1], qubits[2])?;
@@ -1166,6 +1312,31 @@ fn expr_to_param(expr: &Expression) -> ParseResult<ParameterExpression> {
     })
 }
 
+/// Convert a delay duration to a whole number of `dt` or nanoseconds.
+fn expr_to_duration(expr: &Expression) -> ParseResult<(u64, TimeUnit)> {
+    match expr {
+        Expression::Duration { value, unit } => {
+            let (value, time_unit) = match unit.nanoseconds() {
+                Some(scale) => (value * scale, TimeUnit::Ns),
+                None => (*value, TimeUnit::Dt),
+            };
+            let whole = value.round();
+            if (value - whole).abs() > 1e-6 {
+                return Err(ParseError::InvalidDuration(format!(
+                    "{} is not a whole number of {}",
+                    value, time_unit
+                )));
+            }
+            Ok((whole as u64, time_unit))
+        }
+        Expression::Paren(e) => expr_to_duration(e),
+        other => Err(ParseError::InvalidDuration(format!(
+            "expected a duration literal, found {:?}",
+            other
+        ))),
+    }
+}
+
 fn check_param_count(
     gate: &str,
     params: &[ParameterExpression],
@@ -1197,6 +1368,7 @@ fn check_qubit_count(gate: &str, qubits: &[QubitId], expected: usize) -> ParseRe
 #[cfg(test)]
 mod tests {
     use super::*;
+    use arvak_ir::InstructionKind;
 
     #[test]
     fn test_parse_bell_state() {
@@ -1260,6 +1432,96 @@ mod tests {
         assert_eq!(circuit.num_qubits(), 4);
     }
 
+    #[test]
+    fn test_parse_delay() {
+        let source = r#"
+            OPENQASM 3.0;
+            qubit[2] q;
+            delay[160dt] q[0];
+            delay[2.5us] q[0];
+            delay[50ns];
+        "#;
+
+        let circuit = parse(source).unwrap();
+        let delays: Vec<_> = circuit
+            .dag()
+            .topological_ops()
+            .map(|(_, inst)| (inst.kind.clone(), inst.qubits.len()))
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                (
+                    InstructionKind::Delay {
+                        duration: 160,
+                        unit: TimeUnit::Dt
+                    },
+                    1
+                ),
+                (
+                    InstructionKind::Delay {
+                        duration: 2500,
+                        unit: TimeUnit::Ns
+                    },
+                    1
+                ),
+                (
+                    InstructionKind::Delay {
+                        duration: 50,
+                        unit: TimeUnit::Ns
+                    },
+                    2
+                ),
+            ]
+        );
+
+        for bad in [
+            "delay[1.5dt] q[0];",
+            "delay[0.5ns] q[0];",
+            "delay[100] q[0];",
+        ] {
+            let source = format!("OPENQASM 3.0; qubit[1] q; {}", bad);
+            assert!(
+                matches!(parse(&source), Err(ParseError::InvalidDuration(_))),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_annotations() {
+        let source = r#"
+            OPENQASM 3.0;
+            @arvak.provenance "manual"
+            qubit[2] q;
+            @bind calibration(q[0]) -> 1.0
+            @arvak.noise_scale 3
+            @arvak.folded true
+            h q;
+            cx q[0], q[1];
+        "#;
+
+        let circuit = parse(source).unwrap();
+        assert_eq!(circuit.num_qubits(), 2);
+        let ops: Vec<_> = circuit.dag().topological_ops().map(|(_, i)| i).collect();
+        assert_eq!(ops.len(), 3);
+        for h in ops.iter().filter(|i| i.name() == "h") {
+            assert_eq!(h.annotations.noise_scale(), Some(3.0));
+            assert_eq!(h.annotations.get("folded").unwrap().as_bool(), Some(true));
+            // Other tools' annotations are skipped.
+            assert_eq!(h.annotations.len(), 2);
+        }
+        let cx = ops.iter().find(|i| i.name() == "cx").unwrap();
+        assert!(cx.annotations.is_empty());
+
+        let source = "OPENQASM 3.0;\nqubit q;\n@arvak.provenance manual\nx q;";
+        assert!(matches!(
+            parse(source),
+            Err(ParseError::InvalidAnnotation(_))
+        ));
+    }
+
     #[test]
     fn test_parse_error_undefined() {
         let source = r#"
@@ -0,0 +1,116 @@
+//! Benchmarks for stored blob formats (JSON vs CBOR)
+//!
+//! Run with: cargo bench -p arvak-sched --bench blob_bench
+//!
+//! Encoded sizes are printed once per input before timing.
+
+use arvak_hal::{Counts, ExecutionResult};
+use arvak_sched::{BlobFormat, CircuitSpec, ScheduledJob};
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+
+const FORMATS: [(&str, BlobFormat); 2] = [("json", BlobFormat::Json), ("cbor", BlobFormat::Cbor)];
+
+/// A batch job of `n` circuits, each a 20-qubit layered circuit.
+fn make_job(n: usize) -> ScheduledJob {
+    let mut qasm = String::from("OPENQASM 3.0;\nqubit[20] q;\nbit[20] c;\n");
+    for layer in 0..50 {
+        for i in 0..20 {
+            qasm.push_str(&format!("rz({}) q[{}];\n", 0.01 * (layer * i) as f64, i));
+        }
+        for i in (layer % 2..19).step_by(2) {
+            qasm.push_str(&format!("cx q[{}], q[{}];\n", i, i + 1));
+        }
+    }
+    qasm.push_str("c = measure q;\n");
+
+    let circuits = (0..n).map(|_| CircuitSpec::from_qasm(&qasm)).collect();
+    ScheduledJob::batch("bench", circuits)
+}
+
+/// A result with `n` distinct 20-bit outcomes.
+fn make_result(n: usize) -> ExecutionResult {
+    let counts: Counts = (0..n)
+        .map(|i| {
+            (
+                format!("{:020b}", i * 7919 % (1 << 20)),
+                (i % 97 + 1) as u64,
+            )
+        })
+        .collect();
+    ExecutionResult::new(counts, 100_000).with_metadata(serde_json::json!({
+        "expectation_values": (0..256).map(|i| i as f64 * 1e-3).collect::<Vec<_>>(),
+    }))
+}
+
+fn report_sizes<T: serde::Serialize>(label: &str, value: &T) {
+    let json = BlobFormat::Json.encode(value).unwrap().len();
+    let cbor = BlobFormat::Cbor.encode(value).unwrap().len();
+    eprintln!(
+        "{}: json {} B, cbor {} B ({:.0}%)",
+        label,
+        json,
+        cbor,
+        100.0 * cbor as f64 / json as f64
+    );
+}
+
+fn bench_job(c: &mut Criterion) {
+    let mut group = c.benchmark_group("blob_job");
+
+    for n in [1, 16] {
+        let job = make_job(n);
+        report_sizes(&format!("job[{} circuits]", n), &job);
+
+        for (name, format) in FORMATS {
+            let bytes = format.encode(&job).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_encode", name), n),
+                &n,
+                |b, _| {
+                    b.iter(|| black_box(format.encode(&job).unwrap()));
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_decode", name), n),
+                &n,
+                |b, _| {
+                    b.iter(|| black_box(format.decode::<ScheduledJob>(&bytes).unwrap()));
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+fn bench_result(c: &mut Criterion) {
+    let mut group = c.benchmark_group("blob_result");
+
+    for n in [1_000, 100_000] {
+        let result = make_result(n);
+        report_sizes(&format!("result[{} outcomes]", n), &result);
+
+        for (name, format) in FORMATS {
+            let bytes = format.encode(&result).unwrap();
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_encode", name), n),
+                &n,
+                |b, _| {
+                    b.iter(|| black_box(format.encode(&result).unwrap()));
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{}_decode", name), n),
+                &n,
+                |b, _| {
+                    b.iter(|| black_box(format.decode::<ExecutionResult>(&bytes).unwrap()));
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_job, bench_result);
+criterion_main!(benches);
@@ -0,0 +1,101 @@
+//! Benchmarks for the scheduler priority queue
+//!
+//! Run with: cargo bench -p arvak-sched
+
+use arvak_sched::{CircuitSpec, Priority, PriorityQueue, ScheduledJob};
+use criterion::{BatchSize, BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+
+const SIZES: [usize; 4] = [1_000, 10_000, 100_000, 500_000];
+
+fn make_job(i: usize) -> ScheduledJob {
+    let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+    ScheduledJob::new(format!("job{}", i), circuit)
+        .with_priority(Priority::new((i % 7) as u32 * 25))
+}
+
+fn filled_queue(n: usize) -> PriorityQueue {
+    let mut queue = PriorityQueue::with_capacity(n);
+    for i in 0..n {
+        queue.push(make_job(i));
+    }
+    queue
+}
+
+/// Benchmark a steady-state push + pop at different queue depths
+fn bench_push_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("queue_push_pop");
+
+    for &n in SIZES.iter() {
+        let mut queue = filled_queue(n);
+        let mut next = n;
+        group.bench_with_input(BenchmarkId::new("depth", n), &n, |b, _| {
+            b.iter_batched(
+                || {
+                    next += 1;
+                    make_job(next)
+                },
+                |job| {
+                    queue.push(job);
+                    black_box(queue.pop());
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark push followed by removal by ID at different queue depths
+fn bench_remove_by_id(c: &mut Criterion) {
+    let mut group = c.benchmark_group("queue_push_remove_by_id");
+
+    for &n in SIZES.iter() {
+        let mut queue = filled_queue(n);
+        let mut next = n;
+        group.bench_with_input(BenchmarkId::new("depth", n), &n, |b, _| {
+            b.iter_batched(
+                || {
+                    next += 1;
+                    make_job(next)
+                },
+                |job| {
+                    let id = job.id.clone();
+                    queue.push(job);
+                    black_box(queue.remove(&id));
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+/// Benchmark priority updates by job ID
+fn bench_update_priority(c: &mut Criterion) {
+    let mut group = c.benchmark_group("queue_update_priority");
+
+    for &n in SIZES.iter() {
+        let mut queue = filled_queue(n);
+        let ids: Vec<_> = queue.job_ids().step_by(97).cloned().collect();
+        let mut i = 0usize;
+        group.bench_with_input(BenchmarkId::new("depth", n), &n, |b, _| {
+            b.iter(|| {
+                i += 1;
+                let id = &ids[i % ids.len()];
+                black_box(queue.update_priority(id, Priority::new((i % 200) as u32)));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_push_pop,
+    bench_remove_by_id,
+    bench_update_priority
+);
+criterion_main!(benches);
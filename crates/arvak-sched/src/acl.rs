@@ -0,0 +1,401 @@
+//! Job ownership, delegation and audit.
+//!
+//! Jobs may record the principal that owns them. Owners can delegate the
+//! right to cancel or reprioritize their jobs to other principals, e.g. a
+//! team lead managing the jobs of team members. Every authorization decision
+//! and every change to a delegation is recorded in an [`AuditLog`], as are
+//! admin interventions such as force-requeueing or purging jobs.
+//!
+//! Jobs without an owner predate ownership tracking and remain manageable
+//! by anyone.
+
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobId};
+
+/// An action on a job that requires authorization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobAction {
+    /// Cancel the job.
+    Cancel,
+
+    /// Change the job's priority.
+    Reprioritize,
+}
+
+impl std::fmt::Display for JobAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobAction::Cancel => write!(f, "cancel"),
+            JobAction::Reprioritize => write!(f, "reprioritize"),
+        }
+    }
+}
+
+/// Rights granted by a job owner to another principal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Delegation {
+    /// Principal owning the jobs.
+    pub owner: String,
+
+    /// Principal receiving the rights.
+    pub delegate: String,
+
+    /// Actions the delegate may perform on the owner's jobs.
+    pub actions: Vec<JobAction>,
+
+    /// When the delegation was last changed.
+    pub granted_at: DateTime<Utc>,
+}
+
+/// Ownership and delegation rules for job actions.
+#[derive(Default)]
+pub struct AccessPolicy {
+    delegations: RwLock<rustc_hash::FxHashMap<(String, String), Delegation>>,
+    admins: RwLock<rustc_hash::FxHashSet<String>>,
+}
+
+impl AccessPolicy {
+    /// Create a policy with no delegations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow a principal to act on all jobs.
+    pub fn add_admin(&self, principal: impl Into<String>) {
+        self.admins
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(principal.into());
+    }
+
+    /// Check whether a principal is an admin.
+    pub fn is_admin(&self, principal: &str) -> bool {
+        self.admins
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(principal)
+    }
+
+    /// Grant a delegate rights on the owner's jobs, adding to any existing
+    /// grant.
+    pub fn grant(
+        &self,
+        owner: impl Into<String>,
+        delegate: impl Into<String>,
+        actions: &[JobAction],
+    ) -> Delegation {
+        let (owner, delegate) = (owner.into(), delegate.into());
+        let mut delegations = self.delegations.write().unwrap_or_else(|e| e.into_inner());
+        let delegation = delegations
+            .entry((owner.clone(), delegate.clone()))
+            .or_insert_with(|| Delegation {
+                owner,
+                delegate,
+                actions: Vec::new(),
+                granted_at: Utc::now(),
+            });
+        for action in actions {
+            if !delegation.actions.contains(action) {
+                delegation.actions.push(*action);
+            }
+        }
+        delegation.granted_at = Utc::now();
+        delegation.clone()
+    }
+
+    /// Revoke all rights a delegate holds on the owner's jobs.
+    ///
+    /// Returns the removed delegation, if any.
+    pub fn revoke(&self, owner: &str, delegate: &str) -> Option<Delegation> {
+        self.delegations
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(owner.to_string(), delegate.to_string()))
+    }
+
+    /// List the delegations granted by an owner.
+    pub fn delegations_from(&self, owner: &str) -> Vec<Delegation> {
+        let delegations = self.delegations.read().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<_> = delegations
+            .values()
+            .filter(|d| d.owner == owner)
+            .cloned()
+            .collect();
+        out.sort_by(|a, b| a.delegate.cmp(&b.delegate));
+        out
+    }
+
+    /// Check whether a principal may perform an action on a job.
+    pub fn is_allowed(&self, principal: &str, job: &ScheduledJob, action: JobAction) -> bool {
+        let Some(ref owner) = job.owner else {
+            return true;
+        };
+        if owner == principal || self.is_admin(principal) {
+            return true;
+        }
+        self.delegations
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&(owner.clone(), principal.to_string()))
+            .is_some_and(|d| d.actions.contains(&action))
+    }
+
+    /// Authorize an action, returning a permission error if not allowed.
+    pub fn authorize(
+        &self,
+        principal: &str,
+        job: &ScheduledJob,
+        action: JobAction,
+    ) -> SchedResult<()> {
+        if self.is_allowed(principal, job, action) {
+            Ok(())
+        } else {
+            Err(SchedError::PermissionDenied(format!(
+                "{} may not {} job {}",
+                principal, action, job.id
+            )))
+        }
+    }
+}
+
+/// A recorded authorization decision or delegation change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEvent {
+    /// When the event occurred.
+    pub at: DateTime<Utc>,
+
+    /// Principal performing the action.
+    pub principal: String,
+
+    /// Action performed (e.g. `cancel`, `grant`).
+    pub action: String,
+
+    /// Job acted on, if any.
+    pub job_id: Option<ScheduledJobId>,
+
+    /// Whether the action was allowed.
+    pub allowed: bool,
+
+    /// Additional details.
+    pub detail: Option<String>,
+
+    /// Structured details, e.g. the latency and status of an RPC.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl AuditEvent {
+    /// Create an event for an action by a principal.
+    pub fn new(principal: impl Into<String>, action: impl Into<String>, allowed: bool) -> Self {
+        Self {
+            at: Utc::now(),
+            principal: principal.into(),
+            action: action.into(),
+            job_id: None,
+            allowed,
+            detail: None,
+            attributes: BTreeMap::new(),
+        }
+    }
+
+    /// Set the job acted on.
+    pub fn with_job(mut self, job_id: ScheduledJobId) -> Self {
+        self.job_id = Some(job_id);
+        self
+    }
+
+    /// Set additional details.
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Add a structured detail.
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// Sink for audit events.
+pub trait AuditLog: Send + Sync {
+    /// Record an event.
+    fn record(&self, event: AuditEvent);
+}
+
+/// Audit log writing events to the `arvak::audit` tracing target.
+#[derive(Debug, Default)]
+pub struct TracingAuditLog;
+
+impl AuditLog for TracingAuditLog {
+    fn record(&self, event: AuditEvent) {
+        let job_id = event.job_id.map(|id| id.to_string()).unwrap_or_default();
+        let detail = event.detail.unwrap_or_default();
+        tracing::info!(
+            target: "arvak::audit",
+            principal = %event.principal,
+            action = %event.action,
+            job_id = %job_id,
+            allowed = event.allowed,
+            detail = %detail,
+            attributes = ?event.attributes,
+            "audit"
+        );
+    }
+}
+
+/// Audit log keeping events in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryAuditLog {
+    events: Mutex<Vec<AuditEvent>>,
+}
+
+impl InMemoryAuditLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get all recorded events.
+    pub fn events(&self) -> Vec<AuditEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl AuditLog for InMemoryAuditLog {
+    fn record(&self, event: AuditEvent) {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(event);
+    }
+}
+
+/// Audit log appending JSON lines to a file.
+#[derive(Debug)]
+pub struct JsonlAuditLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonlAuditLog {
+    /// Open a log file for appending, creating it if needed.
+    pub fn open(path: impl Into<PathBuf>) -> SchedResult<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AuditLog for JsonlAuditLog {
+    fn record(&self, event: AuditEvent) {
+        // Keep the tracing record too, so the audit target stays complete.
+        TracingAuditLog.record(event.clone());
+        let line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to encode audit event: {}", e);
+                return;
+            }
+        };
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(file, "{}", line) {
+            tracing::warn!(
+                "Failed to write audit event to {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    fn owned_job(owner: &str) -> ScheduledJob {
+        ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;")).with_owner(owner)
+    }
+
+    #[test]
+    fn test_owner_and_delegation() {
+        let policy = AccessPolicy::new();
+        let job = owned_job("alice");
+
+        assert!(policy.is_allowed("alice", &job, JobAction::Cancel));
+        assert!(!policy.is_allowed("lead", &job, JobAction::Cancel));
+
+        policy.grant("alice", "lead", &[JobAction::Reprioritize]);
+        assert!(policy.is_allowed("lead", &job, JobAction::Reprioritize));
+        assert!(!policy.is_allowed("lead", &job, JobAction::Cancel));
+
+        let delegation = policy.grant("alice", "lead", &[JobAction::Cancel]);
+        assert_eq!(
+            delegation.actions,
+            vec![JobAction::Reprioritize, JobAction::Cancel]
+        );
+        assert!(policy.authorize("lead", &job, JobAction::Cancel).is_ok());
+        assert_eq!(policy.delegations_from("alice").len(), 1);
+
+        assert!(policy.revoke("alice", "lead").is_some());
+        assert!(policy.authorize("lead", &job, JobAction::Cancel).is_err());
+    }
+
+    #[test]
+    fn test_admin_and_unowned() {
+        let policy = AccessPolicy::new();
+        policy.add_admin("ops");
+
+        assert!(policy.is_allowed("ops", &owned_job("alice"), JobAction::Cancel));
+
+        let unowned = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        assert!(policy.is_allowed("anyone", &unowned, JobAction::Cancel));
+        assert!(policy.is_admin("ops"));
+        assert!(!policy.is_admin("anyone"));
+    }
+
+    #[test]
+    fn test_jsonl_audit_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let event = AuditEvent::new("ops", "requeue", true)
+            .with_detail("node lost")
+            .with_attribute("request_id", "r-1");
+
+        JsonlAuditLog::open(&path).unwrap().record(event.clone());
+        JsonlAuditLog::open(&path)
+            .unwrap()
+            .record(AuditEvent::new("alice", "purge", false));
+
+        let lines: Vec<AuditEvent> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], event);
+        assert!(!lines[1].allowed);
+    }
+}
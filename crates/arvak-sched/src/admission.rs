@@ -0,0 +1,188 @@
+//! Queue admission info for submitted jobs.
+//!
+//! [`Admission`] tells a submitter how loaded the scheduler is when its job
+//! is queued: the queue depth, the job's position, when it is expected to
+//! start, and an [`AdmissionClass`] summarizing whether to expect a wait.
+//! Clients can use it to back off instead of piling more jobs onto a full
+//! queue.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::{ScheduledJob, ScheduledJobId};
+
+/// Default queue depth at which new jobs are admitted as congested.
+pub const DEFAULT_CONGESTED_QUEUE_DEPTH: usize = 1_000;
+
+/// How a job was admitted to the queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdmissionClass {
+    /// Nothing is ahead of the job; it is dispatched on the next cycle.
+    Immediate,
+
+    /// The job waits behind other ready jobs.
+    Queued,
+
+    /// The job waits on dependencies or a retry backoff.
+    Deferred,
+
+    /// The queue is at or above its congestion depth.
+    Congested,
+}
+
+impl std::fmt::Display for AdmissionClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdmissionClass::Immediate => write!(f, "immediate"),
+            AdmissionClass::Queued => write!(f, "queued"),
+            AdmissionClass::Deferred => write!(f, "deferred"),
+            AdmissionClass::Congested => write!(f, "congested"),
+        }
+    }
+}
+
+/// Load information returned when a job is admitted to the queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Admission {
+    /// Admitted job.
+    pub job_id: ScheduledJobId,
+
+    /// Admission class.
+    pub class: AdmissionClass,
+
+    /// Number of jobs in the scheduler queue, this one included.
+    pub queue_depth: usize,
+
+    /// 1-based position in the scheduler queue, if the job is queued there.
+    pub position: Option<usize>,
+
+    /// Number of ready jobs dispatched before this one.
+    pub jobs_ahead: usize,
+
+    /// Expected start time, estimated from the expected run time of the
+    /// jobs ahead. Unset while the job waits on dependencies.
+    pub estimated_start: Option<DateTime<Utc>>,
+}
+
+impl Admission {
+    /// Assess a job's admission to a queue.
+    ///
+    /// `queued` lists the queued jobs in dispatch order; `is_finished` tells
+    /// whether a dependency has finished. Jobs ahead are assumed to run one
+    /// after another for [`ScheduledJob::expected_runtime_secs`] each, so the
+    /// estimate is pessimistic when several backends serve the queue.
+    pub fn assess(
+        job: &ScheduledJob,
+        queued: &[&ScheduledJob],
+        is_finished: impl Fn(&ScheduledJobId) -> bool,
+        congested_depth: usize,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let position = queued.iter().position(|q| q.id == job.id);
+        let ahead: Vec<&ScheduledJob> = position
+            .map(|pos| {
+                queued[..pos]
+                    .iter()
+                    .filter(|q| q.dependencies.iter().all(&is_finished))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let waiting_on_dependencies = !job.dependencies.iter().all(&is_finished);
+        let backing_off = job.not_before.is_some_and(|t| t > now);
+        let estimated_start = (!waiting_on_dependencies).then(|| {
+            let secs: f64 = ahead.iter().map(|q| q.expected_runtime_secs()).sum();
+            let start = now + chrono::Duration::milliseconds((secs * 1_000.0) as i64);
+            job.not_before.map_or(start, |t| start.max(t))
+        });
+
+        let class = if waiting_on_dependencies || backing_off {
+            AdmissionClass::Deferred
+        } else if queued.len() >= congested_depth {
+            AdmissionClass::Congested
+        } else if ahead.is_empty() {
+            AdmissionClass::Immediate
+        } else {
+            AdmissionClass::Queued
+        };
+
+        Self {
+            job_id: job.id.clone(),
+            class,
+            queue_depth: queued.len(),
+            position: position.map(|pos| pos + 1),
+            jobs_ahead: ahead.len(),
+            estimated_start,
+        }
+    }
+
+    /// Admission of a job that already left the queue of `queue_depth`
+    /// jobs, e.g. because it was dispatched right after submission.
+    pub fn dispatched(job_id: ScheduledJobId, queue_depth: usize, now: DateTime<Utc>) -> Self {
+        Self {
+            job_id,
+            class: AdmissionClass::Immediate,
+            queue_depth,
+            position: None,
+            jobs_ahead: 0,
+            estimated_start: Some(now),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, Priority};
+
+    fn job(name: &str, walltime_secs: u64) -> ScheduledJob {
+        ScheduledJob::new(name, CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .with_walltime(std::time::Duration::from_secs(walltime_secs))
+    }
+
+    #[test]
+    fn test_assess_estimates_start_from_jobs_ahead() {
+        let now = Utc::now();
+        let first = job("first", 600).with_priority(Priority::high());
+        let second = job("second", 300);
+        let third = job("third", 60);
+        let queued = [&first, &second, &third];
+
+        let admission = Admission::assess(&third, &queued, |_| true, 10, now);
+        assert_eq!(admission.class, AdmissionClass::Queued);
+        assert_eq!(admission.queue_depth, 3);
+        assert_eq!(admission.position, Some(3));
+        assert_eq!(admission.jobs_ahead, 2);
+        assert_eq!(
+            admission.estimated_start,
+            Some(now + chrono::Duration::seconds(900))
+        );
+
+        let head = Admission::assess(&first, &queued, |_| true, 10, now);
+        assert_eq!(head.class, AdmissionClass::Immediate);
+        assert_eq!(head.estimated_start, Some(now));
+
+        let congested = Admission::assess(&first, &queued, |_| true, 3, now);
+        assert_eq!(congested.class, AdmissionClass::Congested);
+    }
+
+    #[test]
+    fn test_assess_defers_jobs_waiting_on_dependencies() {
+        let now = Utc::now();
+        let parent = job("parent", 60);
+        let child = job("child", 60).depends_on(parent.id.clone());
+        let queued = [&parent, &child];
+
+        let admission = Admission::assess(&child, &queued, |_| false, 10, now);
+        assert_eq!(admission.class, AdmissionClass::Deferred);
+        assert!(admission.estimated_start.is_none());
+
+        let mut retry = job("retry", 60);
+        retry.not_before = Some(now + chrono::Duration::minutes(5));
+        let admission = Admission::assess(&retry, &[&retry], |_| true, 10, now);
+        assert_eq!(admission.class, AdmissionClass::Deferred);
+        assert_eq!(admission.estimated_start, retry.not_before);
+    }
+}
@@ -0,0 +1,172 @@
+//! Parameter sweeps submitted as batch job arrays.
+//!
+//! A sweep of hundreds of nearly identical circuits, such as one ansatz at
+//! different parameters, should not cost one `sbatch` call per circuit. A
+//! [`JobArraySpec`] groups single-circuit jobs into an array. Members that
+//! are ready together and matched to the same backend are dispatched as
+//! one array job on batch systems that support arrays. Each member records
+//! its array task ID (`<array job ID>_<task>`) as its batch job ID, so
+//! members are polled, cancelled and collected like any other job.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{CircuitSpec, ScheduledJob, ScheduledJobId};
+
+/// A group of single-circuit jobs submitted as one batch job array.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobArraySpec {
+    /// Unique ID of the array, recorded on each member.
+    pub id: String,
+
+    /// Array name, used to name members created from circuits.
+    pub name: String,
+
+    /// Member jobs, in task order.
+    pub jobs: Vec<ScheduledJob>,
+}
+
+impl JobArraySpec {
+    /// Create an empty array.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name: name.into(),
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Create an array with one member per circuit, named `<name>_<task>`.
+    pub fn from_circuits(
+        name: impl Into<String>,
+        circuits: impl IntoIterator<Item = CircuitSpec>,
+    ) -> Self {
+        circuits
+            .into_iter()
+            .fold(Self::new(name), |spec, circuit| spec.add_circuit(circuit))
+    }
+
+    /// Add a member job.
+    pub fn add_job(mut self, job: ScheduledJob) -> Self {
+        self.jobs.push(job);
+        self
+    }
+
+    /// Add a member running `circuit`, named `<name>_<task>`.
+    pub fn add_circuit(self, circuit: CircuitSpec) -> Self {
+        let name = format!("{}_{}", self.name, self.jobs.len());
+        self.add_job(ScheduledJob::new(name, circuit))
+    }
+
+    /// Set the shots of every member.
+    pub fn with_shots(mut self, shots: u32) -> Self {
+        self.jobs = self
+            .jobs
+            .into_iter()
+            .map(|job| job.with_shots(shots))
+            .collect();
+        self
+    }
+
+    /// Number of members.
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Check if the array has no members.
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// IDs of the members, in task order.
+    pub fn job_ids(&self) -> Vec<ScheduledJobId> {
+        self.jobs.iter().map(|job| job.id.clone()).collect()
+    }
+
+    /// Check that the array has members and each runs a single circuit.
+    pub fn validate(&self) -> SchedResult<()> {
+        if self.jobs.is_empty() {
+            return Err(SchedError::InvalidPayload(format!(
+                "job array {} has no members",
+                self.name
+            )));
+        }
+        for job in &self.jobs {
+            if job.is_classical() || job.circuits.len() != 1 {
+                return Err(SchedError::InvalidPayload(format!(
+                    "job array member {} must run exactly one circuit",
+                    job.name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Tag each member with the array ID and return the members.
+    pub fn into_jobs(self) -> Vec<ScheduledJob> {
+        let id = self.id;
+        self.jobs
+            .into_iter()
+            .map(|mut job| {
+                job.array_group = Some(id.clone());
+                job
+            })
+            .collect()
+    }
+}
+
+/// Build the batch job ID of task `task` of array job `array_job_id`.
+pub fn task_id(array_job_id: &str, task: usize) -> String {
+    format!("{}_{}", array_job_id, task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BELL: &str = "OPENQASM 3.0; qubit[2] q; bit[2] c; h q[0]; cx q[0], q[1]; c = measure q;";
+
+    #[test]
+    fn test_spec_tags_members() {
+        let spec =
+            JobArraySpec::from_circuits("sweep", (0..3).map(|_| CircuitSpec::from_qasm(BELL)))
+                .with_shots(500);
+        spec.validate().unwrap();
+        let id = spec.id.clone();
+        let ids = spec.job_ids();
+
+        let jobs = spec.into_jobs();
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[2].name, "sweep_2");
+        assert_eq!(jobs[1].shots, 500);
+        assert!(
+            jobs.iter()
+                .all(|job| job.array_group.as_deref() == Some(id.as_str()))
+        );
+        assert_eq!(
+            jobs.iter().map(|job| job.id.clone()).collect::<Vec<_>>(),
+            ids
+        );
+    }
+
+    #[test]
+    fn test_spec_rejects_multi_circuit_members() {
+        assert!(JobArraySpec::new("empty").validate().is_err());
+        let batch = ScheduledJob::batch(
+            "batch",
+            vec![CircuitSpec::from_qasm(BELL), CircuitSpec::from_qasm(BELL)],
+        );
+        assert!(
+            JobArraySpec::new("sweep")
+                .add_job(batch)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_task_id() {
+        assert_eq!(task_id("4242", 7), "4242_7");
+    }
+}
@@ -0,0 +1,150 @@
+//! Batch systems the scheduler submits jobs to.
+//!
+//! [`HpcScheduler`](crate::HpcScheduler) drives any [`BatchSystem`]; the
+//! crate provides [`SlurmAdapter`](crate::SlurmAdapter),
+//! [`PbsAdapter`](crate::PbsAdapter), [`LsfAdapter`](crate::LsfAdapter) and
+//! [`KubernetesAdapter`](crate::KubernetesAdapter). Jobs record the ID their batch system
+//! assigned in their status, and the system maps its own job states back to
+//! [`ScheduledJobStatus`] when polled.
+//!
+//! Beyond submitting, polling and cancelling quantum jobs, batch systems
+//! may support partial-result snapshots, log files, native dependencies
+//! between batch jobs, job arrays, classical script tasks, status event
+//! files and advance reservations; the defaults report each as unsupported.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobStatus};
+use crate::logs::LogStream;
+use crate::payload::CircuitResult;
+use crate::reservation::Reservation;
+use crate::task::TaskInputs;
+
+/// A batch system quantum jobs and script tasks run on.
+#[async_trait]
+pub trait BatchSystem: Send + Sync {
+    /// Name of the batch system for logs and errors, e.g. `SLURM`.
+    fn name(&self) -> &str;
+
+    /// Submit a quantum job; returns its batch job ID.
+    async fn submit(&self, job: &ScheduledJob) -> SchedResult<String>;
+
+    /// Get the status of the batch job `batch_job_id` running `job`.
+    ///
+    /// States that say nothing about progress, such as a suspended job,
+    /// keep the job's current status.
+    async fn poll(&self, job: &ScheduledJob, batch_job_id: &str)
+    -> SchedResult<ScheduledJobStatus>;
+
+    /// Get the statuses of many batch jobs at once, as
+    /// [`poll`](Self::poll) would, for systems that can query them in one
+    /// call; `None` for jobs to poll one at a time instead, which is all of
+    /// them by default.
+    async fn poll_many(
+        &self,
+        jobs: &[(&ScheduledJob, &str)],
+    ) -> SchedResult<Vec<Option<ScheduledJobStatus>>> {
+        Ok(jobs.iter().map(|_| None).collect())
+    }
+
+    /// Find the batch job `job` was submitted as, for recovering a
+    /// submission whose batch job ID was lost in a crash; `None` if there
+    /// is none or the batch system cannot tell.
+    async fn find_submitted(&self, _job: &ScheduledJob) -> SchedResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Cancel a batch job.
+    async fn cancel(&self, batch_job_id: &str) -> SchedResult<()>;
+
+    /// Requeue a running batch job, so it starts again later under the same
+    /// batch job ID.
+    async fn requeue(&self, _batch_job_id: &str) -> SchedResult<()> {
+        Err(SchedError::ConfigError(format!(
+            "Requeueing jobs is not supported on {}",
+            self.name()
+        )))
+    }
+
+    /// Reserve nodes for `reservation`'s window under its name.
+    async fn create_reservation(&self, _reservation: &Reservation) -> SchedResult<()> {
+        Err(SchedError::ConfigError(format!(
+            "Reservations are not supported on {}",
+            self.name()
+        )))
+    }
+
+    /// Release the reservation `name`.
+    async fn delete_reservation(&self, _name: &str) -> SchedResult<()> {
+        Err(SchedError::ConfigError(format!(
+            "Reservations are not supported on {}",
+            self.name()
+        )))
+    }
+
+    /// Read the results a completed job wrote, one per circuit.
+    async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>>;
+
+    /// Why a queued batch job has not started, if the batch system says.
+    async fn pending_reason(&self, _batch_job_id: &str) -> SchedResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Files a running job writes partial-result snapshots to, one per
+    /// circuit; empty if the batch system does not support them.
+    fn partial_paths(&self, _job: &ScheduledJob) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Files a job's stdout and stderr are written to; empty if the batch
+    /// system keeps no log files or the job has not been submitted.
+    fn log_paths(&self, _job: &ScheduledJob) -> Vec<(LogStream, PathBuf)> {
+        Vec::new()
+    }
+
+    /// Whether submitted jobs honour
+    /// [`ScheduledJob::batch_dependency`], so dependents can be queued
+    /// before their dependencies finish.
+    fn supports_dependencies(&self) -> bool {
+        false
+    }
+
+    /// Whether [`BatchSystem::submit_array`] submits jobs as one array job.
+    fn supports_arrays(&self) -> bool {
+        false
+    }
+
+    /// Directory the jobs write status event files to, see
+    /// [`crate::notify`].
+    fn event_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Submit single-circuit jobs as one array job; returns each job's
+    /// batch job ID, in order.
+    async fn submit_array(&self, _jobs: &[ScheduledJob]) -> SchedResult<Vec<String>> {
+        Err(SchedError::ConfigError(format!(
+            "Job arrays are not supported on {}",
+            self.name()
+        )))
+    }
+
+    /// Submit a classical script task; returns its batch job ID.
+    async fn submit_task(&self, _job: &ScheduledJob, _inputs: &TaskInputs) -> SchedResult<String> {
+        Err(SchedError::ConfigError(format!(
+            "Script tasks are not supported on {}",
+            self.name()
+        )))
+    }
+
+    /// Read the output a completed script task wrote.
+    async fn read_task_output(&self, _job: &ScheduledJob) -> SchedResult<serde_json::Value> {
+        Err(SchedError::ConfigError(format!(
+            "Script tasks are not supported on {}",
+            self.name()
+        )))
+    }
+}
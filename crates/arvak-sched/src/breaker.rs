@@ -0,0 +1,353 @@
+//! Per-backend failure-rate circuit breaker.
+//!
+//! When a backend silently breaks, every job routed to it fails, and
+//! resubmissions only amplify the load. The breaker tracks the outcomes of
+//! recent jobs on each backend and, once the failure rate crosses a
+//! threshold, pauses dispatch to that backend for a cooldown period. After
+//! the cooldown a single probe job is let through; its outcome decides
+//! whether the backend recovers or is paused again.
+//!
+//! State transitions are broadcast as [`BreakerEvent`]s so operators can be
+//! alerted.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Configuration for the failure-rate breaker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BreakerConfig {
+    /// Number of recent job outcomes considered per backend.
+    pub window: usize,
+
+    /// Minimum number of outcomes before the breaker can trip.
+    pub min_samples: usize,
+
+    /// Failure rate (0.0 - 1.0) at or above which the breaker trips.
+    pub failure_threshold: f64,
+
+    /// How long dispatch to a tripped backend is paused (seconds).
+    pub cooldown_secs: u64,
+
+    /// How many times a job failed on a tripped backend may be rerouted.
+    pub max_reroutes: u32,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            window: 20,
+            min_samples: 5,
+            failure_threshold: 0.5,
+            cooldown_secs: 300,
+            max_reroutes: 2,
+        }
+    }
+}
+
+impl BreakerConfig {
+    /// Set the number of outcomes considered.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Set the minimum number of outcomes before tripping.
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    /// Set the failure rate that trips the breaker.
+    pub fn with_failure_threshold(mut self, threshold: f64) -> Self {
+        self.failure_threshold = threshold;
+        self
+    }
+
+    /// Set the cooldown period.
+    pub fn with_cooldown_secs(mut self, secs: u64) -> Self {
+        self.cooldown_secs = secs;
+        self
+    }
+
+    /// Set how many times a job may be rerouted.
+    pub fn with_max_reroutes(mut self, max_reroutes: u32) -> Self {
+        self.max_reroutes = max_reroutes;
+        self
+    }
+}
+
+/// State of the breaker for one backend.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BreakerState {
+    /// Jobs are dispatched normally.
+    Closed,
+
+    /// Dispatch is paused until the given time.
+    Open { until: DateTime<Utc> },
+
+    /// The cooldown has passed; a single probe job decides the next state.
+    HalfOpen { probing: bool },
+}
+
+/// Operator alert emitted on breaker state transitions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BreakerEvent {
+    /// Dispatch to a backend was paused.
+    Tripped {
+        backend: String,
+        failure_rate: f64,
+        samples: usize,
+        paused_until: DateTime<Utc>,
+    },
+
+    /// A probe job succeeded and dispatch to the backend resumed.
+    Recovered { backend: String },
+}
+
+#[derive(Debug)]
+struct BackendHealth {
+    outcomes: VecDeque<bool>,
+    state: BreakerState,
+}
+
+impl BackendHealth {
+    fn new() -> Self {
+        Self {
+            outcomes: VecDeque::new(),
+            state: BreakerState::Closed,
+        }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        let failures = self.outcomes.iter().filter(|ok| !**ok).count();
+        failures as f64 / self.outcomes.len() as f64
+    }
+}
+
+/// Failure-rate breaker shared by all backends of a scheduler.
+pub struct FailureBreaker {
+    config: BreakerConfig,
+    backends: Mutex<rustc_hash::FxHashMap<String, BackendHealth>>,
+    events: broadcast::Sender<BreakerEvent>,
+}
+
+impl FailureBreaker {
+    /// Create a breaker with the given configuration.
+    pub fn new(config: BreakerConfig) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            config,
+            backends: Mutex::new(rustc_hash::FxHashMap::default()),
+            events,
+        }
+    }
+
+    /// Get the breaker configuration.
+    pub fn config(&self) -> &BreakerConfig {
+        &self.config
+    }
+
+    /// Subscribe to breaker events.
+    pub fn subscribe(&self) -> broadcast::Receiver<BreakerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Record the outcome of a job that ran on a backend.
+    ///
+    /// Returns the event emitted if the outcome changed the breaker state.
+    pub fn record(&self, backend: &str, success: bool) -> Option<BreakerEvent> {
+        let now = Utc::now();
+        let mut backends = self.backends.lock().unwrap_or_else(|e| e.into_inner());
+        let health = backends
+            .entry(backend.to_string())
+            .or_insert_with(BackendHealth::new);
+
+        health.outcomes.push_back(success);
+        while health.outcomes.len() > self.config.window {
+            health.outcomes.pop_front();
+        }
+
+        let event = match health.state {
+            BreakerState::HalfOpen { .. } if success => {
+                health.state = BreakerState::Closed;
+                health.outcomes.clear();
+                Some(BreakerEvent::Recovered {
+                    backend: backend.to_string(),
+                })
+            }
+            BreakerState::HalfOpen { .. } => Some(self.trip(backend, health, now)),
+            BreakerState::Closed
+                if health.outcomes.len() >= self.config.min_samples
+                    && health.failure_rate() >= self.config.failure_threshold =>
+            {
+                Some(self.trip(backend, health, now))
+            }
+            _ => None,
+        };
+        drop(backends);
+
+        if let Some(ref event) = event {
+            match event {
+                BreakerEvent::Tripped {
+                    failure_rate,
+                    paused_until,
+                    ..
+                } => tracing::warn!(
+                    "Pausing dispatch to backend {}: failure rate {:.0}%, paused until {}",
+                    backend,
+                    failure_rate * 100.0,
+                    paused_until
+                ),
+                BreakerEvent::Recovered { .. } => {
+                    tracing::info!("Resuming dispatch to backend {}", backend)
+                }
+            }
+            // No subscribers is not an error.
+            let _ = self.events.send(event.clone());
+        }
+        event
+    }
+
+    fn trip(&self, backend: &str, health: &mut BackendHealth, now: DateTime<Utc>) -> BreakerEvent {
+        let paused_until = now + chrono::Duration::seconds(self.config.cooldown_secs as i64);
+        health.state = BreakerState::Open {
+            until: paused_until,
+        };
+        BreakerEvent::Tripped {
+            backend: backend.to_string(),
+            failure_rate: health.failure_rate(),
+            samples: health.outcomes.len(),
+            paused_until,
+        }
+    }
+
+    /// Check whether a job may be dispatched to a backend now.
+    ///
+    /// Once the cooldown of a tripped backend has passed, the first call
+    /// admits a probe job and later calls are refused until its outcome is
+    /// recorded.
+    pub fn allows(&self, backend: &str) -> bool {
+        let mut backends = self.backends.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(health) = backends.get_mut(backend) else {
+            return true;
+        };
+
+        match health.state {
+            BreakerState::Closed => true,
+            BreakerState::Open { until } if Utc::now() < until => false,
+            BreakerState::Open { .. } | BreakerState::HalfOpen { probing: false } => {
+                health.state = BreakerState::HalfOpen { probing: true };
+                true
+            }
+            BreakerState::HalfOpen { probing: true } => false,
+        }
+    }
+
+    /// Check whether dispatch to a backend is currently paused.
+    pub fn is_paused(&self, backend: &str) -> bool {
+        self.state(backend) != BreakerState::Closed
+    }
+
+    /// Get the breaker state of a backend.
+    pub fn state(&self, backend: &str) -> BreakerState {
+        let backends = self.backends.lock().unwrap_or_else(|e| e.into_inner());
+        backends
+            .get(backend)
+            .map(|h| h.state.clone())
+            .unwrap_or(BreakerState::Closed)
+    }
+
+    /// Get the names of backends whose dispatch is paused.
+    pub fn paused_backends(&self) -> Vec<String> {
+        let backends = self.backends.lock().unwrap_or_else(|e| e.into_inner());
+        let mut names: Vec<_> = backends
+            .iter()
+            .filter(|(_, h)| h.state != BreakerState::Closed)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+impl Default for FailureBreaker {
+    fn default() -> Self {
+        Self::new(BreakerConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> BreakerConfig {
+        BreakerConfig::default()
+            .with_window(4)
+            .with_min_samples(4)
+            .with_failure_threshold(0.75)
+    }
+
+    #[test]
+    fn test_trips_on_failure_rate() {
+        let breaker = FailureBreaker::new(config());
+        let mut events = breaker.subscribe();
+
+        assert!(breaker.record("qpu", true).is_none());
+        assert!(breaker.record("qpu", false).is_none());
+        assert!(breaker.record("qpu", false).is_none());
+        // 2 of 3 failed, below min_samples
+        assert!(breaker.allows("qpu"));
+
+        assert!(breaker.record("qpu", false).is_some());
+        assert!(!breaker.allows("qpu"));
+        assert!(breaker.allows("sim"));
+        assert_eq!(breaker.paused_backends(), vec!["qpu".to_string()]);
+
+        match events.try_recv().unwrap() {
+            BreakerEvent::Tripped {
+                backend, samples, ..
+            } => {
+                assert_eq!(backend, "qpu");
+                assert_eq!(samples, 4);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_probe_after_cooldown() {
+        let breaker = FailureBreaker::new(config().with_cooldown_secs(0));
+        for _ in 0..4 {
+            breaker.record("qpu", false);
+        }
+
+        // One probe is admitted after the cooldown.
+        assert!(breaker.allows("qpu"));
+        assert!(!breaker.allows("qpu"));
+
+        // A failed probe pauses the backend again.
+        assert!(matches!(
+            breaker.record("qpu", false),
+            Some(BreakerEvent::Tripped { .. })
+        ));
+
+        assert!(breaker.allows("qpu"));
+        assert_eq!(
+            breaker.record("qpu", true),
+            Some(BreakerEvent::Recovered {
+                backend: "qpu".to_string()
+            })
+        );
+        assert_eq!(breaker.state("qpu"), BreakerState::Closed);
+        assert!(breaker.allows("qpu"));
+        assert!(breaker.allows("qpu"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Content-addressed caching of workflow nodes.
+//!
+//! Re-running a campaign after changing one branch should not execute the
+//! unchanged branches again. A cacheable job gets a [`node_key`] when it is
+//! dispatched: a hash of its circuits, shots, classical task and pinned
+//! backend together with the results of the jobs it depends on. When an
+//! earlier job with the same key completed and its result is still stored,
+//! the scheduler copies that result and marks the job
+//! [`ScheduledJobStatus::CachedComplete`] instead of running it.
+//!
+//! Since upstream results are part of the key, a node whose upstream job
+//! ran again (and so produced fresh counts) runs again too.
+//!
+//! [`ScheduledJobStatus::CachedComplete`]: crate::job::ScheduledJobStatus::CachedComplete
+
+use arvak_hal::ExecutionResult;
+use arvak_ir::hash::Fnv1a;
+
+use crate::error::SchedResult;
+use crate::job::ScheduledJob;
+
+/// Compute the cache key of a job whose dependencies produced `upstream`,
+/// in the order of `job.dependencies` (`None` for jobs without a result).
+///
+/// Circuits are hashed in canonical form, so QASM formatting does not
+/// change the key. Fails if a circuit cannot be resolved.
+pub fn node_key(job: &ScheduledJob, upstream: &[Option<ExecutionResult>]) -> SchedResult<String> {
+    let mut hash = Fnv1a::new();
+    hash.write_u64(job.circuits.len() as u64);
+    for spec in &job.circuits {
+        hash.write_u64(spec.canonical_hash()?);
+        hash.write_str(spec.label().unwrap_or_default());
+        hash.write_u64(spec.shots().map_or(u64::MAX, u64::from));
+    }
+    hash.write_u64(u64::from(job.shots));
+    match &job.task {
+        Some(task) => hash.write_str(&serde_json::to_string(task)?),
+        None => hash.write_str(""),
+    }
+    hash.write_str(job.matched_backend.as_deref().unwrap_or_default());
+
+    hash.write_u64(upstream.len() as u64);
+    for result in upstream {
+        match result {
+            Some(result) => hash.write_u64(result_digest(result)),
+            None => hash.write_u64(0),
+        }
+    }
+    Ok(format!("{:016x}", hash.finish()))
+}
+
+/// Hash a result's counts, shot count and metadata.
+fn result_digest(result: &ExecutionResult) -> u64 {
+    let mut hash = Fnv1a::new();
+    let mut counts: Vec<_> = result.counts.iter().collect();
+    counts.sort_unstable();
+    hash.write_u64(counts.len() as u64);
+    for (bitstring, count) in counts {
+        hash.write_str(bitstring);
+        hash.write_u64(*count);
+    }
+    hash.write_u64(u64::from(result.shots));
+    hash.write_str(&result.metadata.to_string());
+    hash.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+    use arvak_hal::Counts;
+
+    const BELL: &str = "OPENQASM 3.0; qubit[2] q; bit[2] c; h q[0]; cx q[0], q[1]; c = measure q;";
+
+    fn job(qasm: &str) -> ScheduledJob {
+        ScheduledJob::new("bell", CircuitSpec::from_qasm(qasm))
+    }
+
+    #[test]
+    fn test_key_ignores_formatting_and_job_identity() {
+        let reformatted = BELL.replace("; ", ";\n  ");
+        assert_eq!(
+            node_key(&job(BELL), &[]).unwrap(),
+            node_key(&job(&reformatted), &[]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_key_changes_with_inputs() {
+        let base = node_key(&job(BELL), &[]).unwrap();
+        assert_ne!(base, node_key(&job(BELL).with_shots(100), &[]).unwrap());
+        assert_ne!(
+            base,
+            node_key(&job(&BELL.replace("h q[0]", "x q[0]")), &[]).unwrap()
+        );
+
+        let a = ExecutionResult::new(Counts::from_pairs([("00", 60), ("11", 40)]), 100);
+        let b = ExecutionResult::new(Counts::from_pairs([("00", 55), ("11", 45)]), 100);
+        let after_a = node_key(&job(BELL), &[Some(a.clone())]).unwrap();
+        assert_ne!(base, after_a);
+        assert_eq!(after_a, node_key(&job(BELL), &[Some(a)]).unwrap());
+        assert_ne!(after_a, node_key(&job(BELL), &[Some(b)]).unwrap());
+        assert_ne!(after_a, node_key(&job(BELL), &[None]).unwrap());
+    }
+}
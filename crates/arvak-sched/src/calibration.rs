@@ -0,0 +1,671 @@
+//! Backend calibration snapshots.
+//!
+//! Sites publish a device's calibration data every day or so as JSON or
+//! CSV files. [`BackendCalibration::from_file`] parses one into the noise
+//! properties of the device: T1, T2 and readout error per qubit, and the
+//! error of each gate on the qubits it acts on.
+//! [`HpcScheduler::ingest_calibration`](crate::HpcScheduler::ingest_calibration)
+//! stores each snapshot under the next calibration epoch of its backend,
+//! hands it to the [`ResourceMatcher`](crate::ResourceMatcher) and moves
+//! the compile stage to the new epoch so nothing compiled against the old
+//! calibration is reused. [`BackendCalibration::noise_model`] turns a
+//! snapshot into the [`NoiseModel`] noisy simulation and error mitigation
+//! work from.
+//!
+//! # JSON
+//!
+//! ```json
+//! {
+//!   "backend": "garnet",
+//!   "calibrated_at": "2026-10-15T06:00:00Z",
+//!   "qubits": [{"qubit": 0, "t1_us": 48.2, "t2_us": 31.0, "readout_error": 0.021}],
+//!   "gates": [{"gate": "cz", "qubits": [0, 1], "error": 0.008, "duration_ns": 40.0}]
+//! }
+//! ```
+//!
+//! # CSV
+//!
+//! One row per qubit, in the layout of IBM's calibration downloads:
+//!
+//! ```text
+//! Qubit,T1 (us),T2 (us),Readout assignment error,√x (sx) error,CNOT error
+//! 0,120.5,98.1,0.012,0.00021,0_1:0.0071;0_14:0.0093
+//! ```
+//!
+//! Columns are matched by name, case-insensitively, and unknown columns
+//! are ignored. Every other `<gate> error` column holds a gate error: a
+//! number for single-qubit gates, `a_b:error` pairs separated by `;` for
+//! two-qubit gates. The gate is the name in parentheses if there is one,
+//! otherwise the column name without ` error`, lowercased.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use arvak_compile::ErrorRates;
+use arvak_hal::{NoiseChannel, NoiseModel};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+
+/// Calibrated properties of one qubit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QubitProperties {
+    /// Qubit index.
+    pub qubit: u32,
+
+    /// Energy relaxation time in microseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t1_us: Option<f64>,
+
+    /// Dephasing time in microseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t2_us: Option<f64>,
+
+    /// Probability of reading out the wrong state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readout_error: Option<f64>,
+}
+
+impl QubitProperties {
+    /// Create an entry for a qubit without calibrated properties.
+    pub fn new(qubit: u32) -> Self {
+        Self {
+            qubit,
+            t1_us: None,
+            t2_us: None,
+            readout_error: None,
+        }
+    }
+}
+
+/// Calibrated error of a gate on specific qubits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateProperties {
+    /// Gate name, lowercase.
+    pub gate: String,
+
+    /// Qubits the gate acts on, in operand order.
+    pub qubits: Vec<u32>,
+
+    /// Average gate error.
+    pub error: f64,
+
+    /// Gate duration in nanoseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ns: Option<f64>,
+}
+
+/// File formats of calibration snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationFormat {
+    /// JSON in the layout of [`BackendCalibration`].
+    Json,
+
+    /// CSV with one row per qubit.
+    Csv,
+}
+
+impl CalibrationFormat {
+    /// Detect the format from a file extension.
+    pub fn from_path(path: &Path) -> SchedResult<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("json") => Ok(CalibrationFormat::Json),
+            Some("csv") => Ok(CalibrationFormat::Csv),
+            _ => Err(SchedError::ParseError(format!(
+                "unknown calibration format of {}: expected .json or .csv",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// A calibration snapshot of one backend.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendCalibration {
+    /// Name of the calibrated backend.
+    pub backend: String,
+
+    /// Calibration epoch, counting the snapshots ingested for the backend
+    /// from 1; 0 until ingested.
+    #[serde(default)]
+    pub epoch: u64,
+
+    /// When the device was calibrated.
+    pub calibrated_at: DateTime<Utc>,
+
+    /// Per-qubit properties, ordered by qubit.
+    #[serde(default)]
+    pub qubits: Vec<QubitProperties>,
+
+    /// Per-gate errors.
+    #[serde(default)]
+    pub gates: Vec<GateProperties>,
+}
+
+impl BackendCalibration {
+    /// Create an empty snapshot of a backend calibrated now.
+    pub fn new(backend: impl Into<String>) -> Self {
+        Self {
+            backend: backend.into(),
+            epoch: 0,
+            calibrated_at: Utc::now(),
+            qubits: Vec::new(),
+            gates: Vec::new(),
+        }
+    }
+
+    /// Set when the device was calibrated.
+    pub fn with_calibrated_at(mut self, calibrated_at: DateTime<Utc>) -> Self {
+        self.calibrated_at = calibrated_at;
+        self
+    }
+
+    /// Add the properties of a qubit, replacing any earlier entry.
+    pub fn with_qubit(mut self, properties: QubitProperties) -> Self {
+        self.qubits.retain(|q| q.qubit != properties.qubit);
+        self.qubits.push(properties);
+        self.qubits.sort_by_key(|q| q.qubit);
+        self
+    }
+
+    /// Add the error of a gate on some qubits.
+    pub fn with_gate_error(
+        mut self,
+        gate: impl Into<String>,
+        qubits: impl Into<Vec<u32>>,
+        error: f64,
+    ) -> Self {
+        self.gates.push(GateProperties {
+            gate: gate.into().to_lowercase(),
+            qubits: qubits.into(),
+            error,
+            duration_ns: None,
+        });
+        self
+    }
+
+    /// Parse a calibration file, detecting the format from its extension.
+    ///
+    /// CSV files do not name the backend, so `backend` is used for them;
+    /// JSON files must name the same backend if `backend` is given.
+    pub fn from_file(path: &Path, backend: Option<&str>) -> SchedResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        match CalibrationFormat::from_path(path)? {
+            CalibrationFormat::Json => {
+                let calibration = Self::from_json(&content)?;
+                match backend {
+                    Some(name) if name != calibration.backend => {
+                        Err(SchedError::InvalidPayload(format!(
+                            "{} calibrates backend '{}', not '{}'",
+                            path.display(),
+                            calibration.backend,
+                            name
+                        )))
+                    }
+                    _ => Ok(calibration),
+                }
+            }
+            CalibrationFormat::Csv => {
+                let backend = backend.ok_or_else(|| {
+                    SchedError::InvalidPayload(format!(
+                        "{} does not name its backend; pass one",
+                        path.display()
+                    ))
+                })?;
+                let mut calibration = Self::from_csv(backend, &content)?;
+                if let Ok(modified) = std::fs::metadata(path).and_then(|m| m.modified()) {
+                    calibration.calibrated_at = modified.into();
+                }
+                Ok(calibration)
+            }
+        }
+    }
+
+    /// Parse a JSON calibration snapshot.
+    pub fn from_json(content: &str) -> SchedResult<Self> {
+        let mut calibration: Self = serde_json::from_str(content)?;
+        calibration.epoch = 0;
+        calibration.qubits.sort_by_key(|q| q.qubit);
+        for gate in &mut calibration.gates {
+            gate.gate = gate.gate.to_lowercase();
+        }
+        Ok(calibration)
+    }
+
+    /// Parse a CSV calibration snapshot with one row per qubit.
+    pub fn from_csv(backend: impl Into<String>, content: &str) -> SchedResult<Self> {
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let Some((_, header)) = lines.next() else {
+            return Err(SchedError::ParseError(
+                "calibration CSV is empty".to_string(),
+            ));
+        };
+        let columns: Vec<CsvColumn> = split_csv_line(header)
+            .iter()
+            .map(|name| CsvColumn::parse(name))
+            .collect();
+        if !columns.contains(&CsvColumn::Qubit) {
+            return Err(SchedError::ParseError(
+                "calibration CSV has no 'Qubit' column".to_string(),
+            ));
+        }
+
+        let mut calibration = Self::new(backend);
+        for (index, line) in lines {
+            let row = index + 1;
+            let fields = split_csv_line(line);
+            let mut properties = QubitProperties::new(0);
+            let mut gates = Vec::new();
+            for (column, field) in columns.iter().zip(&fields) {
+                let field = field.trim();
+                if field.is_empty() {
+                    continue;
+                }
+                match column {
+                    CsvColumn::Qubit => {
+                        properties.qubit = field
+                            .trim_start_matches(['Q', 'q'])
+                            .parse()
+                            .map_err(|_| csv_error(row, format!("invalid qubit '{}'", field)))?;
+                    }
+                    CsvColumn::T1 => properties.t1_us = Some(parse_number(row, field)?),
+                    CsvColumn::T2 => properties.t2_us = Some(parse_number(row, field)?),
+                    CsvColumn::Readout => {
+                        properties.readout_error = Some(parse_number(row, field)?)
+                    }
+                    CsvColumn::GateError(gate) => gates.push((gate, field)),
+                    CsvColumn::Ignored => {}
+                }
+            }
+
+            for (gate, field) in gates {
+                if !field.contains(':') {
+                    calibration = calibration.with_gate_error(
+                        gate.clone(),
+                        [properties.qubit],
+                        parse_number(row, field)?,
+                    );
+                    continue;
+                }
+                for pair in field.split(';').filter(|p| !p.trim().is_empty()) {
+                    let (qubits, error) = pair
+                        .split_once(':')
+                        .ok_or_else(|| csv_error(row, format!("invalid gate error '{}'", pair)))?;
+                    let qubits = qubits
+                        .trim()
+                        .split('_')
+                        .map(|q| q.parse::<u32>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| csv_error(row, format!("invalid qubits '{}'", qubits)))?;
+                    calibration = calibration.with_gate_error(
+                        gate.clone(),
+                        qubits,
+                        parse_number(row, error.trim())?,
+                    );
+                }
+            }
+            calibration = calibration.with_qubit(properties);
+        }
+        Ok(calibration)
+    }
+
+    /// Check that the values are physical and, if the backend's qubit
+    /// count is known, that every qubit exists.
+    pub fn validate(&self, num_qubits: Option<u32>) -> SchedResult<()> {
+        let invalid = |message: String| {
+            Err(SchedError::InvalidPayload(format!(
+                "calibration of {}: {}",
+                self.backend, message
+            )))
+        };
+        if self.backend.is_empty() {
+            return invalid("no backend name".to_string());
+        }
+        let exists = |qubit: u32| num_qubits.is_none_or(|n| qubit < n);
+        for q in &self.qubits {
+            if !exists(q.qubit) {
+                return invalid(format!("qubit {} does not exist", q.qubit));
+            }
+            for (name, time) in [("T1", q.t1_us), ("T2", q.t2_us)] {
+                if time.is_some_and(|t| !(t.is_finite() && t > 0.0)) {
+                    return invalid(format!("{} of qubit {} must be positive", name, q.qubit));
+                }
+            }
+            if q.readout_error.is_some_and(|e| !is_probability(e)) {
+                return invalid(format!(
+                    "readout error of qubit {} must be in [0, 1]",
+                    q.qubit
+                ));
+            }
+        }
+        for g in &self.gates {
+            if g.qubits.is_empty() {
+                return invalid(format!("gate {} acts on no qubits", g.gate));
+            }
+            if let Some(q) = g.qubits.iter().find(|q| !exists(**q)) {
+                return invalid(format!("gate {} acts on missing qubit {}", g.gate, q));
+            }
+            if !is_probability(g.error) {
+                return invalid(format!(
+                    "error of gate {} on {:?} must be in [0, 1]",
+                    g.gate, g.qubits
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Version string identifying this snapshot, e.g. as the target
+    /// version of compiled circuits.
+    pub fn version(&self) -> String {
+        format!("{}@{}", self.backend, self.epoch)
+    }
+
+    /// Get the properties of a qubit.
+    pub fn qubit(&self, qubit: u32) -> Option<&QubitProperties> {
+        self.qubits.iter().find(|q| q.qubit == qubit)
+    }
+
+    /// Get the readout error of a qubit.
+    pub fn readout_error(&self, qubit: u32) -> Option<f64> {
+        self.qubit(qubit)?.readout_error
+    }
+
+    /// Get the error of a gate on the given qubits.
+    ///
+    /// Two-qubit errors calibrated for one operand order are used for the
+    /// other as well, as for symmetric gates such as CZ.
+    pub fn gate_error(&self, gate: &str, qubits: &[u32]) -> Option<f64> {
+        let find = |qubits: &[u32]| {
+            self.gates
+                .iter()
+                .find(|g| g.gate.eq_ignore_ascii_case(gate) && g.qubits == qubits)
+                .map(|g| g.error)
+        };
+        find(qubits).or_else(|| match qubits {
+            [a, b] => find(&[*b, *a]),
+            _ => None,
+        })
+    }
+
+    /// Mean error over the calibrated gates acting on `arity` qubits.
+    pub fn mean_gate_error(&self, arity: usize) -> Option<f64> {
+        let errors: Vec<f64> = self
+            .gates
+            .iter()
+            .filter(|g| g.qubits.len() == arity)
+            .map(|g| g.error)
+            .collect();
+        if errors.is_empty() {
+            None
+        } else {
+            Some(errors.iter().sum::<f64>() / errors.len() as f64)
+        }
+    }
+
+    /// Mean readout error over the calibrated qubits.
+    pub fn mean_readout_error(&self) -> Option<f64> {
+        let errors: Vec<f64> = self.qubits.iter().filter_map(|q| q.readout_error).collect();
+        if errors.is_empty() {
+            None
+        } else {
+            Some(errors.iter().sum::<f64>() / errors.len() as f64)
+        }
+    }
+
+    /// Error rates of the device for fidelity-aware compilation.
+    ///
+    /// Where several gates are calibrated on the same qubits, the largest
+    /// error is used.
+    pub fn error_rates(&self) -> ErrorRates {
+        let mut two_qubit: BTreeMap<(u32, u32), f64> = BTreeMap::new();
+        let mut single_qubit: BTreeMap<u32, f64> = BTreeMap::new();
+        for g in &self.gates {
+            let error = match g.qubits[..] {
+                [q] => single_qubit.entry(q).or_default(),
+                [a, b] => two_qubit.entry((a.min(b), a.max(b))).or_default(),
+                _ => continue,
+            };
+            *error = error.max(g.error);
+        }
+
+        let mut rates = ErrorRates::new();
+        for ((a, b), error) in two_qubit {
+            rates = rates.with_two_qubit_error(a, b, error);
+        }
+        for (q, error) in single_qubit {
+            rates = rates.with_single_qubit_error(q, error);
+        }
+        for q in &self.qubits {
+            if let Some(error) = q.readout_error {
+                rates = rates.with_readout_error(q.qubit, error);
+            }
+        }
+        rates
+    }
+
+    /// Noise model of the device, named after the snapshot's
+    /// [`version`](Self::version).
+    ///
+    /// Gate errors become depolarizing channels, those of two-qubit gates
+    /// for both operand orders, and each gate's mean error applies on
+    /// qubits it was not calibrated on. Readout errors are taken to be
+    /// symmetric. Qubits with a T1 time decohere while idle, with T2
+    /// capped at 2 T1 and taken to be 2 T1 if missing.
+    pub fn noise_model(&self) -> NoiseModel {
+        let mut model = NoiseModel::new().with_source(format!("calibration:{}", self.version()));
+
+        let mut by_gate: BTreeMap<(&str, usize), Vec<f64>> = BTreeMap::new();
+        for g in &self.gates {
+            let channel = NoiseChannel::depolarizing_from_error(g.error, g.qubits.len());
+            model = model.with_gate_noise(&g.gate, &g.qubits, channel.clone());
+            if let [a, b] = g.qubits[..] {
+                if self.gate_error(&g.gate, &[b, a]) == Some(g.error) {
+                    model = model.with_gate_noise(&g.gate, &[b, a], channel);
+                }
+            }
+            by_gate
+                .entry((&g.gate, g.qubits.len()))
+                .or_default()
+                .push(g.error);
+        }
+        for ((gate, arity), errors) in by_gate {
+            let mean = errors.iter().sum::<f64>() / errors.len() as f64;
+            model = model.with_gate_noise(
+                gate,
+                &[],
+                NoiseChannel::depolarizing_from_error(mean, arity),
+            );
+        }
+
+        for q in &self.qubits {
+            if let Some(error) = q.readout_error {
+                model = model.with_readout_error(q.qubit, error, error);
+            }
+            if let Some(t1) = q.t1_us {
+                let t2 = q.t2_us.unwrap_or(2.0 * t1).min(2.0 * t1);
+                model = model.with_idle_noise(q.qubit, t1, t2);
+            }
+        }
+        model
+    }
+}
+
+/// Meaning of a column of a calibration CSV.
+#[derive(Debug, Clone, PartialEq)]
+enum CsvColumn {
+    Qubit,
+    T1,
+    T2,
+    Readout,
+    GateError(String),
+    Ignored,
+}
+
+impl CsvColumn {
+    fn parse(name: &str) -> Self {
+        let lower = name.trim().to_lowercase();
+        let unit = lower.split_once(" (").map_or(lower.as_str(), |(n, _)| n);
+        match unit {
+            "qubit" => return CsvColumn::Qubit,
+            "t1" => return CsvColumn::T1,
+            "t2" => return CsvColumn::T2,
+            "readout assignment error" | "readout error" => return CsvColumn::Readout,
+            _ => {}
+        }
+        let Some(gate) = lower.strip_suffix(" error") else {
+            return CsvColumn::Ignored;
+        };
+        if gate.contains("readout") || gate.contains("meas") {
+            return CsvColumn::Ignored;
+        }
+        let gate = match (gate.find('('), gate.find(')')) {
+            (Some(open), Some(close)) if open < close => &gate[open + 1..close],
+            _ => gate,
+        };
+        CsvColumn::GateError(gate.trim().to_string())
+    }
+}
+
+/// Split a CSV line into fields, honouring double quotes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn parse_number(row: usize, field: &str) -> SchedResult<f64> {
+    field
+        .parse()
+        .map_err(|_| csv_error(row, format!("invalid number '{}'", field)))
+}
+
+fn csv_error(row: usize, message: String) -> SchedError {
+    SchedError::ParseError(format!("calibration CSV line {}: {}", row, message))
+}
+
+fn is_probability(p: f64) -> bool {
+    (0.0..=1.0).contains(&p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IBM_CSV: &str = "\
+Qubit,T1 (us),T2 (us),Frequency (GHz),Readout assignment error,Prob meas0 prep1,ID error,\u{221a}x (sx) error,CNOT error,Gate time (ns)
+0,120.5,98.1,5.1,0.012,0.015,0.0002,0.00021,0_1:0.0071;0_2:0.0093,\"0_1:300; 0_2:320\"
+1,88.0,,5.0,0.031,0.04,0.0003,0.0003,1_0:0.0071,1_0:300
+";
+
+    #[test]
+    fn test_parse_csv() {
+        let calibration = BackendCalibration::from_csv("eagle", IBM_CSV).unwrap();
+        assert_eq!(calibration.backend, "eagle");
+        assert_eq!(calibration.qubits.len(), 2);
+        assert_eq!(calibration.qubit(0).unwrap().t1_us, Some(120.5));
+        assert_eq!(calibration.qubit(1).unwrap().t2_us, None);
+        assert_eq!(calibration.readout_error(1), Some(0.031));
+        assert_eq!(calibration.gate_error("sx", &[0]), Some(0.00021));
+        assert_eq!(calibration.gate_error("id", &[1]), Some(0.0003));
+        assert_eq!(calibration.gate_error("cnot", &[0, 2]), Some(0.0093));
+        assert_eq!(calibration.gate_error("CNOT", &[2, 0]), Some(0.0093));
+        assert_eq!(calibration.gate_error("cnot", &[1, 2]), None);
+        assert_eq!(
+            calibration.mean_readout_error(),
+            Some((0.012 + 0.031) / 2.0)
+        );
+        let rates = calibration.error_rates();
+        assert_eq!(rates.two_qubit_error(2, 0), Some(0.0093));
+        assert_eq!(rates.single_qubit_error(0), Some(0.00021));
+        assert_eq!(rates.readout_error(1), Some(0.031));
+        calibration.validate(Some(3)).unwrap();
+        assert!(calibration.validate(Some(2)).is_err());
+
+        assert!(BackendCalibration::from_csv("eagle", "T1 (us)\n40").is_err());
+        let bad = "Qubit,T1 (us)\n0,fast\n";
+        let err = BackendCalibration::from_csv("eagle", bad).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_parse_json_and_validate() {
+        let json = r#"{
+            "backend": "garnet",
+            "epoch": 7,
+            "calibrated_at": "2026-10-15T06:00:00Z",
+            "qubits": [
+                {"qubit": 1, "t1_us": 40.0, "readout_error": 0.05},
+                {"qubit": 0, "t1_us": 48.2, "t2_us": 31.0, "readout_error": 0.021}
+            ],
+            "gates": [{"gate": "CZ", "qubits": [0, 1], "error": 0.008, "duration_ns": 40.0}]
+        }"#;
+        let calibration = BackendCalibration::from_json(json).unwrap();
+        assert_eq!(calibration.epoch, 0);
+        assert_eq!(calibration.qubits[0].qubit, 0);
+        assert_eq!(calibration.gate_error("cz", &[1, 0]), Some(0.008));
+        assert_eq!(calibration.mean_gate_error(2), Some(0.008));
+        assert_eq!(calibration.mean_gate_error(1), None);
+        calibration.validate(None).unwrap();
+
+        let bad = calibration.clone().with_gate_error("cz", [0, 2], 1.5);
+        assert!(bad.validate(None).is_err());
+
+        let model = calibration.noise_model();
+        model.validate().unwrap();
+        assert_eq!(model.label(), "calibration:garnet@0");
+        assert_eq!(
+            model.gate_channel("cz", &[1, 0]),
+            Some(&NoiseChannel::depolarizing_from_error(0.008, 2))
+        );
+        assert!(model.gate_channel("cz", &[2, 3]).is_some());
+        assert_eq!(model.readout_error(1).unwrap().flip_one, 0.05);
+        assert_eq!(model.idle_noise(0).unwrap().t2_us, 31.0);
+        assert_eq!(model.idle_noise(1).unwrap().t2_us, 80.0);
+        let mut bad = calibration.clone();
+        bad.qubits[0].t1_us = Some(-1.0);
+        assert!(bad.validate(None).is_err());
+    }
+
+    #[test]
+    fn test_from_file_detects_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv = dir.path().join("eagle.csv");
+        std::fs::write(&csv, IBM_CSV).unwrap();
+        let calibration = BackendCalibration::from_file(&csv, Some("eagle")).unwrap();
+        assert_eq!(calibration.qubits.len(), 2);
+        assert!(BackendCalibration::from_file(&csv, None).is_err());
+
+        let json = dir.path().join("eagle.json");
+        std::fs::write(&json, serde_json::to_string(&calibration).unwrap()).unwrap();
+        let back = BackendCalibration::from_file(&json, None).unwrap();
+        assert_eq!(back.gates, calibration.gates);
+        assert!(BackendCalibration::from_file(&json, Some("garnet")).is_err());
+
+        let txt = dir.path().join("eagle.txt");
+        std::fs::write(&txt, "").unwrap();
+        assert!(BackendCalibration::from_file(&txt, Some("eagle")).is_err());
+    }
+}
@@ -0,0 +1,421 @@
+//! Experiment campaigns.
+//!
+//! A campaign is a [workflow definition](crate::definition) whose nodes
+//! are templates expanded over a matrix of experiment parameters. Writing
+//! `{param}` in a node's fields substitutes the parameter's value; a field
+//! that is exactly `{param}` takes the value with its type, so
+//! `shots: "{shots}"` stays a number.
+//!
+//! ```yaml
+//! name: rb-sweep
+//! matrix:
+//!   depth: [1, 2, 4]
+//!   shots: [1000, 4000]
+//! nodes:
+//!   - name: rb-{depth}-{shots}
+//!     circuit: circuits/rb_{depth}.qasm
+//!     shots: "{shots}"
+//!   - name: fit-{depth}
+//!     task: fit
+//!     depends_on: ["rb-{depth}-{shots}"]
+//!   - name: report
+//!     task: report
+//!     depends_on: ["fit-{depth}"]
+//! ```
+//!
+//! A node is instantiated once per combination of the parameters its name
+//! uses, so above `rb-*` runs six times, `fit-*` three times and `report`
+//! once. A dependency naming parameters the node itself is not expanded
+//! over depends on every value of them: `fit-2` waits for `rb-2-1000` and
+//! `rb-2-4000`. Each instance carries its parameter values as labels.
+//! Placeholders inside flow lists (`[...]`) must be quoted, since YAML
+//! reads braces there as mappings.
+//!
+//! Campaigns always cache their nodes (see [`crate::cache`]), so running
+//! an interrupted or edited campaign again only executes the nodes whose
+//! inputs are new.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde_yaml::{Mapping, Value};
+
+use crate::definition::{WorkflowDefinition, in_file};
+use crate::error::{SchedError, SchedResult};
+use crate::workflow::Workflow;
+
+/// A campaign with its matrix expanded into a workflow definition.
+#[derive(Debug, Clone)]
+pub struct Campaign {
+    /// Parameters of the experiment matrix and their values, in the order
+    /// they were declared.
+    pub matrix: Vec<(String, Vec<String>)>,
+
+    /// The expanded workflow, with caching enabled.
+    pub definition: WorkflowDefinition,
+}
+
+impl Campaign {
+    /// Parse a YAML campaign and expand its matrix.
+    pub fn parse(source: &str) -> SchedResult<Self> {
+        let document: Value = serde_yaml::from_str(source)
+            .map_err(|e| SchedError::ParseError(format!("invalid campaign: {}", e)))?;
+        let Value::Mapping(mut document) = document else {
+            return Err(parse_error("campaign must be a mapping"));
+        };
+
+        let matrix = match document.remove("matrix") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::Mapping(matrix)) => parse_matrix(matrix)?,
+            Some(_) => return Err(parse_error("matrix must map parameters to value lists")),
+        };
+        let templates = match document.remove("nodes") {
+            Some(Value::Sequence(nodes)) => nodes,
+            _ => return Err(parse_error("campaign has no list of nodes")),
+        };
+        if let Some(Value::Bool(false)) = document.get("caching") {
+            return Err(parse_error("campaigns always cache their nodes"));
+        }
+
+        let mut nodes = Vec::new();
+        for template in templates {
+            nodes.extend(expand_node(template, &matrix)?);
+        }
+        document.insert("caching".into(), Value::Bool(true));
+        document.insert("nodes".into(), Value::Sequence(nodes));
+
+        let definition: WorkflowDefinition = serde_yaml::from_value(Value::Mapping(document))
+            .map_err(|e| SchedError::ParseError(format!("invalid campaign: {}", e)))?;
+        definition.validate()?;
+
+        let matrix = matrix
+            .into_iter()
+            .map(|(param, values)| (param, values.iter().map(scalar_text).collect()))
+            .collect();
+        Ok(Self { matrix, definition })
+    }
+
+    /// Load a campaign file.
+    pub fn load(path: &Path) -> SchedResult<Self> {
+        let source = std::fs::read_to_string(path)?;
+        Self::parse(&source).map_err(|e| in_file(path, e))
+    }
+
+    /// Campaign name.
+    pub fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    /// Number of points of the experiment matrix.
+    pub fn points(&self) -> usize {
+        self.matrix.iter().map(|(_, values)| values.len()).product()
+    }
+
+    /// Build the campaign's workflow, resolving circuit files relative to
+    /// `base_dir`.
+    pub fn to_workflow(&self, base_dir: &Path) -> SchedResult<Workflow> {
+        self.definition.to_workflow(base_dir)
+    }
+}
+
+type Matrix = Vec<(String, Vec<Value>)>;
+
+fn parse_error(message: &str) -> SchedError {
+    SchedError::ParseError(format!("invalid campaign: {}", message))
+}
+
+fn parse_matrix(matrix: Mapping) -> SchedResult<Matrix> {
+    matrix
+        .into_iter()
+        .map(|(param, values)| {
+            let Value::String(param) = param else {
+                return Err(parse_error("matrix parameters must be strings"));
+            };
+            let values = match values {
+                Value::Sequence(values) if !values.is_empty() => values,
+                _ => {
+                    return Err(SchedError::ParseError(format!(
+                        "invalid campaign: matrix parameter '{}' needs a non-empty list of values",
+                        param
+                    )));
+                }
+            };
+            if let Some(value) = values.iter().find(|v| !is_scalar(v)) {
+                return Err(SchedError::ParseError(format!(
+                    "invalid campaign: value {:?} of matrix parameter '{}' is not a scalar",
+                    value, param
+                )));
+            }
+            Ok((param, values))
+        })
+        .collect()
+}
+
+fn is_scalar(value: &Value) -> bool {
+    matches!(value, Value::Bool(_) | Value::Number(_) | Value::String(_))
+}
+
+/// Text of a scalar matrix value, as substituted into strings.
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Matrix parameters a string refers to, in matrix order.
+fn params_in(text: &str, matrix: &Matrix) -> Vec<usize> {
+    (0..matrix.len())
+        .filter(|&i| text.contains(&format!("{{{}}}", matrix[i].0)))
+        .collect()
+}
+
+/// Matrix parameters used anywhere in a value.
+fn collect_params(value: &Value, matrix: &Matrix, params: &mut BTreeSet<usize>) {
+    match value {
+        Value::String(text) => params.extend(params_in(text, matrix)),
+        Value::Sequence(items) => {
+            for item in items {
+                collect_params(item, matrix, params);
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (key, item) in mapping {
+                collect_params(key, matrix, params);
+                collect_params(item, matrix, params);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// All combinations of the values of `params`, each as one value index
+/// per parameter.
+fn combinations(params: &[usize], matrix: &Matrix) -> Vec<Vec<(usize, usize)>> {
+    let mut combinations = vec![Vec::new()];
+    for &param in params {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                (0..matrix[param].1.len()).map(move |value| {
+                    let mut combination = combination.clone();
+                    combination.push((param, value));
+                    combination
+                })
+            })
+            .collect();
+    }
+    combinations
+}
+
+/// Substitute bound parameters in a string.
+fn substitute_text(text: &str, bound: &[(usize, usize)], matrix: &Matrix) -> String {
+    let mut text = text.to_string();
+    for &(param, index) in bound {
+        text = text.replace(
+            &format!("{{{}}}", matrix[param].0),
+            &scalar_text(&matrix[param].1[index]),
+        );
+    }
+    text
+}
+
+/// Substitute bound parameters in a value; a string that is exactly one
+/// placeholder becomes the parameter's value.
+fn substitute(value: &Value, bound: &[(usize, usize)], matrix: &Matrix) -> Value {
+    match value {
+        Value::String(text) => {
+            for &(param, index) in bound {
+                if *text == format!("{{{}}}", matrix[param].0) {
+                    return matrix[param].1[index].clone();
+                }
+            }
+            Value::String(substitute_text(text, bound, matrix))
+        }
+        Value::Sequence(items) => Value::Sequence(
+            items
+                .iter()
+                .map(|item| substitute(item, bound, matrix))
+                .collect(),
+        ),
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .iter()
+                .map(|(key, item)| {
+                    (
+                        substitute(key, bound, matrix),
+                        substitute(item, bound, matrix),
+                    )
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Instantiate a node template once per combination of the parameters in
+/// its name.
+fn expand_node(template: Value, matrix: &Matrix) -> SchedResult<Vec<Value>> {
+    let Value::Mapping(mut template) = template else {
+        return Err(parse_error("nodes must be mappings"));
+    };
+    let Some(Value::String(name)) = template.get("name").cloned() else {
+        return Err(parse_error("every node needs a name"));
+    };
+    let depends_on = match template.remove("depends_on") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Sequence(dependencies)) => dependencies
+            .into_iter()
+            .map(|dependency| match dependency {
+                Value::String(dependency) => Ok(dependency),
+                _ => Err(SchedError::ParseError(format!(
+                    "invalid campaign: dependencies of node '{}' must be node names",
+                    name
+                ))),
+            })
+            .collect::<SchedResult<Vec<_>>>()?,
+        Some(_) => {
+            return Err(SchedError::ParseError(format!(
+                "invalid campaign: depends_on of node '{}' must be a list",
+                name
+            )));
+        }
+    };
+
+    let bound = params_in(&name, matrix);
+    let mut used = BTreeSet::new();
+    collect_params(&Value::Mapping(template.clone()), matrix, &mut used);
+    if let Some(&param) = used.iter().find(|param| !bound.contains(param)) {
+        return Err(SchedError::ParseError(format!(
+            "invalid campaign: node '{}' uses matrix parameter '{}' its name does not",
+            name, matrix[param].0
+        )));
+    }
+
+    let mut nodes = Vec::new();
+    for combination in combinations(&bound, matrix) {
+        let Value::Mapping(mut node) =
+            substitute(&Value::Mapping(template.clone()), &combination, matrix)
+        else {
+            unreachable!("substitution keeps mappings");
+        };
+
+        let mut dependencies = Vec::new();
+        for dependency in &depends_on {
+            let dependency = substitute_text(dependency, &combination, matrix);
+            // Parameters the node is not expanded over fan in.
+            let free = params_in(&dependency, matrix);
+            for fan_in in combinations(&free, matrix) {
+                dependencies.push(Value::String(substitute_text(&dependency, &fan_in, matrix)));
+            }
+        }
+        if !dependencies.is_empty() {
+            node.insert("depends_on".into(), Value::Sequence(dependencies));
+        }
+
+        if !combination.is_empty() {
+            let labels = match node
+                .entry("labels".into())
+                .or_insert(Value::Mapping(Mapping::new()))
+            {
+                Value::Mapping(labels) => labels,
+                _ => {
+                    return Err(SchedError::ParseError(format!(
+                        "invalid campaign: labels of node '{}' must be a mapping",
+                        name
+                    )));
+                }
+            };
+            for &(param, index) in &combination {
+                let key = Value::String(matrix[param].0.clone());
+                if !labels.contains_key(&key) {
+                    labels.insert(key, Value::String(scalar_text(&matrix[param].1[index])));
+                }
+            }
+        }
+        nodes.push(Value::Mapping(node));
+    }
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAMPAIGN: &str = "\
+name: rb-sweep
+matrix:
+  depth: [1, 2, 4]
+  shots: [1000, 4000]
+nodes:
+  - name: rb-{depth}-{shots}
+    circuit: rb_{depth}.qasm
+    shots: \"{shots}\"
+  - name: fit-{depth}
+    task: fit
+    depends_on: [\"rb-{depth}-{shots}\"]
+  - name: report
+    task: report
+    depends_on: [\"fit-{depth}\"]
+";
+
+    fn node<'a>(campaign: &'a Campaign, name: &str) -> &'a crate::definition::NodeDefinition {
+        campaign
+            .definition
+            .nodes
+            .iter()
+            .find(|node| node.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_campaign_expansion() {
+        let campaign = Campaign::parse(CAMPAIGN).unwrap();
+        assert_eq!(campaign.name(), "rb-sweep");
+        assert_eq!(campaign.points(), 6);
+        assert!(campaign.definition.caching);
+        assert_eq!(campaign.definition.nodes.len(), 6 + 3 + 1);
+
+        let rb = node(&campaign, "rb-2-4000");
+        assert_eq!(rb.circuit.as_deref(), Some(Path::new("rb_2.qasm")));
+        assert_eq!(rb.shots, Some(4000));
+        assert_eq!(rb.labels["depth"], "2");
+        assert_eq!(rb.labels["shots"], "4000");
+
+        assert_eq!(
+            node(&campaign, "fit-2").depends_on,
+            ["rb-2-1000", "rb-2-4000"]
+        );
+        assert_eq!(node(&campaign, "fit-2").labels.len(), 1);
+        assert_eq!(
+            node(&campaign, "report").depends_on,
+            ["fit-1", "fit-2", "fit-4"]
+        );
+        assert!(node(&campaign, "report").labels.is_empty());
+    }
+
+    #[test]
+    fn test_campaign_errors() {
+        let unnamed = CAMPAIGN.replace("task: report", "task: report-{depth}");
+        let err = Campaign::parse(&unnamed).unwrap_err().to_string();
+        assert!(
+            err.contains("node 'report' uses matrix parameter 'depth'"),
+            "{}",
+            err
+        );
+
+        let empty = CAMPAIGN.replace("[1, 2, 4]", "[]");
+        let err = Campaign::parse(&empty).unwrap_err().to_string();
+        assert!(err.contains("'depth' needs a non-empty list"), "{}", err);
+
+        let uncached = format!("caching: false\n{}", CAMPAIGN);
+        assert!(Campaign::parse(&uncached).is_err());
+
+        // Expanded nodes are validated like any workflow definition
+        let dangling = CAMPAIGN.replace("\"fit-{depth}\"]", "\"fit-{depth}\", missing]");
+        let err = Campaign::parse(&dangling).unwrap_err().to_string();
+        assert!(err.contains("unknown node 'missing'"), "{}", err);
+    }
+}
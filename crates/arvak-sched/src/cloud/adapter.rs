@@ -0,0 +1,135 @@
+//! Adapter submitting jobs straight to cloud QPU providers.
+
+use std::sync::Arc;
+
+use arvak_hal::{Backend, ExecutionResult, JobId, JobStatus};
+use rustc_hash::FxHashMap;
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobStatus};
+use crate::retry::FailureKind;
+
+/// Placeholder batch job ID of jobs submitted directly to a cloud provider.
+pub const CLOUD_JOB_ID: &str = "cloud";
+
+/// Adapter for cloud QPUs reached through their provider's API.
+///
+/// Providers are HAL backends such as the IBM Quantum or IQM adapters, which
+/// talk to the provider's REST API. The scheduler hands a job to this adapter
+/// instead of the batch system when resource matching picks one of its
+/// providers and the job is a single circuit.
+#[derive(Default)]
+pub struct CloudQpuAdapter {
+    providers: FxHashMap<String, Arc<dyn Backend>>,
+}
+
+impl CloudQpuAdapter {
+    /// Create an adapter with no providers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a provider, replacing any provider with the same name.
+    pub fn with_provider(mut self, provider: Arc<dyn Backend>) -> Self {
+        self.providers.insert(provider.name().to_string(), provider);
+        self
+    }
+
+    /// Iterate over the providers.
+    pub fn providers(&self) -> impl Iterator<Item = &Arc<dyn Backend>> {
+        self.providers.values()
+    }
+
+    /// Check whether `backend` is one of the providers.
+    pub fn serves(&self, backend: &str) -> bool {
+        self.providers.contains_key(backend)
+    }
+
+    /// Check whether a job should be submitted through this adapter.
+    pub fn accepts(&self, job: &ScheduledJob) -> bool {
+        !job.is_classical()
+            && job.circuits.len() == 1
+            && job
+                .matched_backend
+                .as_deref()
+                .is_some_and(|b| self.serves(b))
+    }
+
+    /// Submit a job to its matched provider.
+    pub async fn submit(&self, job: &ScheduledJob) -> SchedResult<JobId> {
+        let provider = self.provider(job)?;
+        let [circuit] = job.circuits.as_slice() else {
+            return Err(SchedError::BackendError(format!(
+                "Cloud submission takes a single circuit, job {} has {}",
+                job.id,
+                job.circuits.len()
+            )));
+        };
+        let circuit = circuit.resolve()?;
+        Ok(provider.submit(&circuit, job.shots).await?)
+    }
+
+    /// Poll a submitted job and map the provider status.
+    pub async fn status(&self, job: &ScheduledJob) -> SchedResult<ScheduledJobStatus> {
+        let provider = self.provider(job)?;
+        let quantum_job_id = Self::quantum_job_id(job)?;
+        let slurm_job_id = CLOUD_JOB_ID.to_string();
+
+        Ok(match provider.status(&quantum_job_id).await? {
+            JobStatus::Queued => ScheduledJobStatus::QuantumSubmitted {
+                slurm_job_id,
+                quantum_job_id,
+            },
+            JobStatus::Running => ScheduledJobStatus::QuantumRunning {
+                slurm_job_id,
+                quantum_job_id,
+            },
+            JobStatus::Completed => ScheduledJobStatus::Completed {
+                slurm_job_id,
+                quantum_job_id,
+            },
+            JobStatus::Failed(reason) => ScheduledJobStatus::Failed {
+                reason,
+                kind: FailureKind::Application,
+                slurm_job_id: None,
+                quantum_job_id: Some(quantum_job_id),
+            },
+            JobStatus::Cancelled => ScheduledJobStatus::Cancelled,
+        })
+    }
+
+    /// Fetch the result of a completed job.
+    pub async fn result(&self, job: &ScheduledJob) -> SchedResult<ExecutionResult> {
+        let provider = self.provider(job)?;
+        Ok(provider.result(&Self::quantum_job_id(job)?).await?)
+    }
+
+    /// Cancel a submitted job.
+    pub async fn cancel(&self, job: &ScheduledJob) -> SchedResult<()> {
+        let provider = self.provider(job)?;
+        Ok(provider.cancel(&Self::quantum_job_id(job)?).await?)
+    }
+
+    fn provider(&self, job: &ScheduledJob) -> SchedResult<&Arc<dyn Backend>> {
+        job.matched_backend
+            .as_deref()
+            .and_then(|b| self.providers.get(b))
+            .ok_or_else(|| {
+                SchedError::NoMatchingBackend(format!(
+                    "No cloud provider for job {} (backend: {})",
+                    job.id,
+                    job.matched_backend.as_deref().unwrap_or("none")
+                ))
+            })
+    }
+
+    fn quantum_job_id(job: &ScheduledJob) -> SchedResult<JobId> {
+        job.status
+            .quantum_job_id()
+            .cloned()
+            .ok_or_else(|| SchedError::InvalidJobState {
+                expected: "submitted to a cloud provider".to_string(),
+                found: job.status.name().to_string(),
+            })
+    }
+}
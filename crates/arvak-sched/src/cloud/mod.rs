@@ -0,0 +1,9 @@
+//! Direct submission to cloud QPUs.
+//!
+//! Jobs matched to a cloud backend can skip the batch system: the
+//! [`CloudQpuAdapter`] submits them through the backend's provider API from
+//! the scheduler process and polls them alongside batch jobs.
+
+mod adapter;
+
+pub use adapter::{CLOUD_JOB_ID, CloudQpuAdapter};
@@ -0,0 +1,357 @@
+//! Compile-on-submit stage with a shared compilation cache.
+
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use arvak_compile::passes::{MeasurementSplit, RoutingCost};
+use arvak_compile::{
+    BasisGates, CacheKey, CompileCache, CouplingMap, ErrorRates, PassManagerBuilder, PipelinePreset,
+};
+use arvak_ir::Circuit;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SchedResult;
+use crate::job::{CircuitSpec, ScheduledJob};
+use crate::split::SplitCircuit;
+
+/// Circuits kept in memory by [`CompileStage::new`].
+const DEFAULT_CACHE_CAPACITY: usize = 1024;
+
+/// Settings of the compile-on-submit stage, the `[scheduler.compile]` table
+/// of `arvak.toml`.
+///
+/// ```toml
+/// [scheduler.compile]
+/// preset = "superconducting-heavyhex"
+/// target_version = "2024-06-01"
+/// max_clbits = 64
+/// routing_cost = "gate_error"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompileConfig {
+    /// Pipeline preset to compile with.
+    pub preset: PipelinePreset,
+
+    /// Target calibration version.
+    #[serde(default)]
+    pub target_version: String,
+
+    /// Clbits the backend can measure in one execution; circuits measuring
+    /// more are split, see [`crate::split`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_clbits: Option<usize>,
+
+    /// Cost minimized when routing; `gate_error` routes over the couplers
+    /// with the lowest calibrated error.
+    #[serde(default)]
+    pub routing_cost: RoutingCost,
+}
+
+impl CompileConfig {
+    /// Create a configuration compiling with `preset`.
+    pub fn new(preset: PipelinePreset) -> Self {
+        Self {
+            preset,
+            target_version: String::new(),
+            max_clbits: None,
+            routing_cost: RoutingCost::default(),
+        }
+    }
+
+    /// Set the target calibration version.
+    pub fn with_target_version(mut self, version: impl Into<String>) -> Self {
+        self.target_version = version.into();
+        self
+    }
+
+    /// Split circuits measuring more than `max_clbits` clbits.
+    pub fn with_max_clbits(mut self, max_clbits: usize) -> Self {
+        self.max_clbits = Some(max_clbits);
+        self
+    }
+
+    /// Set the cost minimized when routing.
+    pub fn with_routing_cost(mut self, cost: RoutingCost) -> Self {
+        self.routing_cost = cost;
+        self
+    }
+}
+
+/// Compiles job circuits for the target when they are submitted.
+///
+/// Each circuit is looked up in a [`CompileCache`] keyed by its canonical
+/// hash, the pipeline fingerprint and the target calibration version, so
+/// resubmitting a circuit (e.g. every iteration of a variational batch) only
+/// compiles it once per calibration. The compiled circuit replaces the
+/// original [`CircuitSpec`] in the job.
+///
+/// Update the target version whenever the device is recalibrated so stale
+/// compilations are not reused; the scheduler does so when it ingests a
+/// calibration, see [`crate::calibration`].
+///
+/// With a clbit limit, a compiled circuit measuring more clbits is replaced
+/// by its measurement fragments and the split is recorded in
+/// [`ScheduledJob::measurement_splits`].
+///
+/// Error rates set with [`set_error_rates`](Self::set_error_rates) are
+/// handed to every pipeline, for fidelity-aware routing.
+pub struct CompileStage {
+    builder: Box<dyn Fn() -> PassManagerBuilder + Send + Sync>,
+    target_version: std::sync::RwLock<String>,
+    error_rates: std::sync::RwLock<Option<ErrorRates>>,
+    cache: Arc<CompileCache>,
+    max_clbits: Option<usize>,
+}
+
+impl CompileStage {
+    /// Create a stage compiling with pipelines from `builder` for the target
+    /// at `target_version`, with an in-memory cache.
+    pub fn new(
+        target_version: impl Into<String>,
+        builder: impl Fn() -> PassManagerBuilder + Send + Sync + 'static,
+    ) -> Self {
+        let capacity = NonZeroUsize::new(DEFAULT_CACHE_CAPACITY).expect("capacity is non-zero");
+        Self {
+            builder: Box::new(builder),
+            target_version: std::sync::RwLock::new(target_version.into()),
+            error_rates: std::sync::RwLock::new(None),
+            cache: Arc::new(CompileCache::new(capacity)),
+            max_clbits: None,
+        }
+    }
+
+    /// Create a stage compiling with the configured preset for a target with
+    /// the given coupling map and basis gates, or without a target.
+    pub fn from_config(config: &CompileConfig, target: Option<(CouplingMap, BasisGates)>) -> Self {
+        let preset = config.preset;
+        let routing_cost = config.routing_cost;
+        let stage = Self::new(config.target_version.clone(), move || {
+            let builder = preset.builder().with_routing_cost(routing_cost);
+            match &target {
+                Some((coupling_map, basis_gates)) => {
+                    builder.with_target(coupling_map.clone(), basis_gates.clone())
+                }
+                None => builder,
+            }
+        });
+        match config.max_clbits {
+            Some(max_clbits) => stage.with_max_clbits(max_clbits),
+            None => stage,
+        }
+    }
+
+    /// Use the given cache, e.g. one with an on-disk store or shared with
+    /// other stages.
+    pub fn with_cache(mut self, cache: Arc<CompileCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Split compiled circuits measuring more than `max_clbits` clbits.
+    pub fn with_max_clbits(mut self, max_clbits: usize) -> Self {
+        self.max_clbits = Some(max_clbits);
+        self
+    }
+
+    /// Set the target calibration version.
+    pub fn set_target_version(&self, version: impl Into<String>) {
+        *self
+            .target_version
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = version.into();
+    }
+
+    /// Get the target calibration version.
+    pub fn target_version(&self) -> String {
+        self.target_version
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Set the error rates of the target, e.g. from a new calibration.
+    pub fn set_error_rates(&self, error_rates: ErrorRates) {
+        *self.error_rates.write().unwrap_or_else(|e| e.into_inner()) = Some(error_rates);
+    }
+
+    /// Get the compilation cache.
+    pub fn cache(&self) -> &CompileCache {
+        &self.cache
+    }
+
+    /// Compile one circuit, reusing a cached compilation if there is one.
+    pub fn compile(&self, spec: &CircuitSpec) -> SchedResult<CircuitSpec> {
+        CircuitSpec::from_circuit(&self.compile_circuit(spec)?)
+    }
+
+    fn compile_circuit(&self, spec: &CircuitSpec) -> SchedResult<Circuit> {
+        let circuit = spec.resolve()?;
+        let mut builder = (self.builder)();
+        if let Some(rates) = &*self.error_rates.read().unwrap_or_else(|e| e.into_inner()) {
+            builder = builder.with_error_rates(rates.clone());
+        }
+        let key = CacheKey::new(
+            arvak_qasm3::canonical_hash(&circuit)?,
+            builder.fingerprint(),
+            self.target_version(),
+        );
+        let (pm, mut props) = builder.build();
+        let compiled = self
+            .cache
+            .get_or_compile(&key, &pm, &mut props, circuit.into_dag())?;
+        Ok(Circuit::from_dag(compiled))
+    }
+
+    /// Compile all circuits of a job in place, splitting those that measure
+    /// more clbits than the limit.
+    pub fn compile_job(&self, job: &mut ScheduledJob) -> SchedResult<()> {
+        let mut circuits = Vec::with_capacity(job.circuits.len());
+        let mut splits = Vec::new();
+        for (index, spec) in job.circuits.iter().enumerate() {
+            let compiled = self.compile_circuit(spec)?;
+            let plan = match self.max_clbits {
+                Some(max_clbits) => MeasurementSplit.plan(compiled.dag(), max_clbits)?,
+                None => None,
+            };
+            let Some(plan) = plan else {
+                let mut spec = spec.clone();
+                spec.set_source(CircuitSpec::from_circuit(&compiled)?);
+                circuits.push(spec);
+                continue;
+            };
+
+            let label = job.circuit_label(index);
+            let first = circuits.len();
+            for (position, fragment) in plan.fragment_dags(compiled.dag())?.into_iter().enumerate()
+            {
+                let mut spec = spec
+                    .clone()
+                    .with_label(SplitCircuit::fragment_label(&label, position));
+                spec.set_source(CircuitSpec::from_circuit(&Circuit::from_dag(fragment))?);
+                circuits.push(spec);
+            }
+            splits.push(SplitCircuit {
+                index,
+                label,
+                first,
+                plan,
+            });
+        }
+        job.circuits = circuits;
+        job.measurement_splits.extend(splits);
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for CompileStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompileStage")
+            .field("target_version", &self.target_version())
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_compile::{BasisGates, CouplingMap};
+
+    fn stage() -> CompileStage {
+        CompileStage::new("cal-1", || {
+            PassManagerBuilder::new()
+                .with_optimization_level(0)
+                .with_target(CouplingMap::linear(3), BasisGates::iqm())
+        })
+    }
+
+    #[test]
+    fn test_compiles_once_per_circuit() {
+        let stage = stage();
+        let a = CircuitSpec::from_qasm("OPENQASM 3.0;\nqubit[2] q;\nh q[0];\ncx q[0], q[1];");
+        // Same circuit, different formatting.
+        let b = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;  h q[0]; cx q[0],q[1];");
+
+        let compiled = stage.compile(&a).unwrap();
+        assert_eq!(
+            stage.compile(&b).unwrap().canonical_hash().unwrap(),
+            compiled.canonical_hash().unwrap()
+        );
+
+        let stats = stage.cache().stats();
+        assert_eq!((stats.misses, stats.memory_hits), (1, 1));
+
+        // Native IQM gates only.
+        let circuit = compiled.resolve().unwrap();
+        for (_, inst) in circuit.dag().topological_ops() {
+            assert!(matches!(inst.name(), "prx" | "cz"), "{}", inst.name());
+        }
+    }
+
+    #[test]
+    fn test_stage_from_preset_config() {
+        let config =
+            CompileConfig::new(PipelinePreset::IonTrapAllToAll).with_target_version("cal-7");
+        let stage =
+            CompileStage::from_config(&config, Some((CouplingMap::full(3), BasisGates::ibm())));
+        assert_eq!(stage.target_version(), "cal-7");
+
+        let spec = CircuitSpec::from_qasm("OPENQASM 3.0;\nqubit[3] q;\nh q[0];\ncx q[0], q[2];");
+        let circuit = stage.compile(&spec).unwrap().resolve().unwrap();
+        for (_, inst) in circuit.dag().topological_ops() {
+            assert!(BasisGates::ibm().contains(inst.name()), "{}", inst.name());
+        }
+        // Full connectivity needs no SWAPs.
+        assert_eq!(
+            circuit
+                .dag()
+                .topological_ops()
+                .filter(|(_, i)| i.name() == "cx")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_splits_circuits_over_clbit_limit() {
+        let stage = CompileStage::from_config(
+            &CompileConfig::new(PipelinePreset::SimulatorFast).with_max_clbits(2),
+            None,
+        );
+        let measure_all = |n: usize| {
+            let mut qasm = format!("OPENQASM 3.0;\nqubit[{n}] q;\nbit[{n}] c;\n");
+            for i in 0..n {
+                qasm.push_str(&format!("c[{i}] = measure q[{i}];\n"));
+            }
+            CircuitSpec::from_qasm(qasm)
+        };
+        let mut job = ScheduledJob::batch(
+            "split",
+            vec![measure_all(2), measure_all(5).with_label("wide")],
+        );
+        stage.compile_job(&mut job).unwrap();
+
+        let labels: Vec<_> = (0..job.circuits.len())
+            .map(|i| job.circuit_label(i))
+            .collect();
+        assert_eq!(labels, ["circuit_0", "wide#m0", "wide#m1", "wide#m2"]);
+        for spec in &job.circuits {
+            assert!(spec.resolve().unwrap().num_clbits() <= 2);
+        }
+
+        let split = &job.measurement_splits[0];
+        assert_eq!((split.index, split.label.as_str()), (1, "wide"));
+        assert_eq!(split.fragments(), 1..4);
+    }
+
+    #[test]
+    fn test_new_calibration_recompiles() {
+        let stage = stage();
+        let spec = CircuitSpec::from_qasm("OPENQASM 3.0;\nqubit[1] q;\nh q[0];");
+        stage.compile(&spec).unwrap();
+        stage.set_target_version("cal-2");
+        stage.compile(&spec).unwrap();
+        assert_eq!(stage.cache().stats().misses, 2);
+    }
+}
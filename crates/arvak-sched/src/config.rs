@@ -0,0 +1,532 @@
+//! Loading scheduler settings from `arvak.toml`.
+//!
+//! [`SchedulerConfig`] reads the `[scheduler]` table, with the batch adapter,
+//! breaker, fair-share, preemption, result validation and compile-on-submit
+//! settings in its `slurm`, `pbs`, `lsf`, `kubernetes`, `breaker`,
+//! `fair_share`, `preemption`, `result_validation` and `compile` subtables.
+//! Every key is optional and defaults to the value of the struct's
+//! `Default`, except a `compile` table's `preset`.
+//!
+//! ```toml
+//! [scheduler]
+//! scheduler_type = "slurm"
+//! poll_interval_secs = 10
+//!
+//! [scheduler.slurm]
+//! partition = "q_fiqci"
+//! time_limit = 30
+//!
+//! [scheduler.slurm.priority_qos_mapping]
+//! 200 = "high"
+//!
+//! # Run jobs for this backend on nodes wired to its control hardware
+//! [scheduler.node_features]
+//! iqm_garnet = ["qctrl"]
+//!
+//! # Submit from a workstation through the cluster's login node
+//! [scheduler.slurm.ssh]
+//! host = "login1.example.org"
+//! identity_file = "/home/alice/.ssh/id_cluster"
+//! ```
+//!
+//! ```rust
+//! use arvak_config::LayeredConfig;
+//! use arvak_sched::SchedulerConfig;
+//!
+//! let file = LayeredConfig::parse("[scheduler.slurm]\npartition = \"gpu\"\n", "arvak.toml").unwrap();
+//! let config: SchedulerConfig = file.section().unwrap();
+//! assert_eq!(config.slurm.partition, "gpu");
+//! assert_eq!(config.poll_interval_secs, 30);
+//! ```
+
+use std::collections::BTreeMap;
+
+use arvak_config::{InvalidKey, Section};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Deserializer};
+
+use crate::breaker::BreakerConfig;
+use crate::fairshare::FairShareConfig;
+use crate::k8s::{KubernetesConfig, ResultStore};
+use crate::lsf::LsfConfig;
+use crate::matcher::is_valid_node_feature;
+use crate::pbs::PbsConfig;
+use crate::preempt::PreemptionConfig;
+use crate::scheduler::SchedulerConfig;
+use crate::slurm::SlurmConfig;
+use crate::validate::{ResultValidation, ValidationRule};
+
+impl Section for SchedulerConfig {
+    const NAME: &'static str = "scheduler";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        positive("poll_interval_secs", self.poll_interval_secs)?;
+        positive("max_wait_time_secs", self.max_wait_time_secs)?;
+        positive("max_poll_interval_secs", self.max_poll_interval_secs)?;
+        positive("congested_queue_depth", self.congested_queue_depth as u64)?;
+        if let Some(secs) = self.deadline_horizon_secs {
+            positive("deadline_horizon_secs", secs)?;
+        }
+        self.slurm.validate().map_err(|e| e.within("slurm"))?;
+        self.pbs.validate().map_err(|e| e.within("pbs"))?;
+        self.lsf.validate().map_err(|e| e.within("lsf"))?;
+        self.kubernetes
+            .validate()
+            .map_err(|e| e.within("kubernetes"))?;
+        if let Some(fair_share) = &self.fair_share {
+            fair_share.validate().map_err(|e| e.within("fair_share"))?;
+        }
+        if let Some(preemption) = &self.preemption {
+            preemption.validate().map_err(|e| e.within("preemption"))?;
+        }
+        if let Some(validation) = &self.result_validation {
+            validation
+                .validate()
+                .map_err(|e| e.within("result_validation"))?;
+        }
+        for (backend, features) in &self.node_features {
+            if let Some(feature) = features.iter().find(|f| !is_valid_node_feature(f)) {
+                return Err(InvalidKey::new(
+                    format!("node_features.{}", backend),
+                    format!(
+                        "'{}' is not a node feature: use letters, digits, '-', '_', '.' and ':'",
+                        feature
+                    ),
+                ));
+            }
+        }
+        self.breaker.validate().map_err(|e| e.within("breaker"))
+    }
+}
+
+impl Section for SlurmConfig {
+    const NAME: &'static str = "scheduler.slurm";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        not_empty("partition", &self.partition)?;
+        positive("time_limit", self.time_limit.into())?;
+        positive("memory_mb", self.memory_mb.into())?;
+        positive("cpus_per_task", self.cpus_per_task.into())?;
+        if let Some(shots) = self.partial_shots {
+            positive("partial_shots", shots.into())?;
+        }
+        if let Some(ssh) = &self.ssh {
+            ssh.validate().map_err(|e| e.within("ssh"))?;
+        }
+        Ok(())
+    }
+}
+
+impl Section for PbsConfig {
+    const NAME: &'static str = "scheduler.pbs";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        not_empty("queue", &self.queue)?;
+        let fields: Vec<_> = self.walltime.split(':').collect();
+        if fields.len() != 3 || fields.iter().any(|f| f.parse::<u32>().is_err()) {
+            return Err(InvalidKey::new(
+                "walltime",
+                format!("expected HH:MM:SS, got '{}'", self.walltime),
+            ));
+        }
+        positive("nodes", self.nodes.into())?;
+        positive("ppn", self.ppn.into())
+    }
+}
+
+impl Section for LsfConfig {
+    const NAME: &'static str = "scheduler.lsf";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        not_empty("queue", &self.queue)?;
+        positive("time_limit", self.time_limit.into())?;
+        positive("memory_mb", self.memory_mb.into())?;
+        positive("cores", self.cores.into())?;
+        if self.qubit_resource.as_deref() == Some("") {
+            return Err(InvalidKey::new("qubit_resource", "must not be empty"));
+        }
+        Ok(())
+    }
+}
+
+impl Section for KubernetesConfig {
+    const NAME: &'static str = "scheduler.kubernetes";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        not_empty("namespace", &self.namespace)?;
+        not_empty("image", &self.image)?;
+        not_empty("cpu", &self.cpu)?;
+        positive("memory_mb", self.memory_mb.into())?;
+        positive("time_limit", self.time_limit.into())?;
+        if self.qubit_label.as_deref() == Some("") {
+            return Err(InvalidKey::new("qubit_label", "must not be empty"));
+        }
+        if let Some(label) = self.node_selector.iter().find(|l| !l.contains('=')) {
+            return Err(InvalidKey::new(
+                "node_selector",
+                format!("expected key=value, got '{}'", label),
+            ));
+        }
+        match &self.results {
+            ResultStore::Volume { claim, .. } => {
+                not_empty("results.volume.claim", claim)?;
+            }
+            ResultStore::ObjectStore { url, .. } => {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(InvalidKey::new(
+                        "results.object_store.url",
+                        format!("expected an http(s) URL, got '{}'", url),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Section for BreakerConfig {
+    const NAME: &'static str = "scheduler.breaker";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        positive("window", self.window as u64)?;
+        if !(0.0..=1.0).contains(&self.failure_threshold) {
+            return Err(InvalidKey::new(
+                "failure_threshold",
+                format!("must be between 0 and 1, got {}", self.failure_threshold),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Section for FairShareConfig {
+    const NAME: &'static str = "scheduler.fair_share";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        positive("half_life_secs", self.half_life_secs)?;
+        positive("default_share", self.default_share.into())
+    }
+}
+
+impl Section for PreemptionConfig {
+    const NAME: &'static str = "scheduler.preemption";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        positive("urgent_priority", self.urgent_priority.into())
+    }
+}
+
+impl Section for ResultValidation {
+    const NAME: &'static str = "scheduler.result_validation";
+
+    fn validate(&self) -> Result<(), InvalidKey> {
+        for rule in &self.rules {
+            match rule {
+                ValidationRule::MinEntropy { bits } if !(bits.is_finite() && *bits >= 0.0) => {
+                    return Err(InvalidKey::new(
+                        "rules",
+                        format!("min_entropy must be a non-negative number, got {}", bits),
+                    ));
+                }
+                ValidationRule::RegisterWidth { width: 0 } => {
+                    return Err(InvalidKey::new(
+                        "rules",
+                        "register_width must be greater than 0",
+                    ));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn positive(key: &str, value: u64) -> Result<(), InvalidKey> {
+    if value == 0 {
+        return Err(InvalidKey::new(key, "must be greater than 0"));
+    }
+    Ok(())
+}
+
+fn not_empty(key: &str, value: &str) -> Result<(), InvalidKey> {
+    if value.is_empty() {
+        return Err(InvalidKey::new(key, "must not be empty"));
+    }
+    Ok(())
+}
+
+/// Deserialize a priority mapping whose keys are integers written as TOML
+/// keys, which are always strings.
+pub(crate) fn priority_mapping<'de, D>(
+    deserializer: D,
+) -> Result<Option<FxHashMap<u32, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(raw) = Option::<BTreeMap<String, String>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    raw.into_iter()
+        .map(|(priority, value)| Ok((parse_priority_key::<D::Error>(&priority)?, value)))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Deserialize a map of priority classes keyed by their lowest priority,
+/// written as TOML keys.
+pub(crate) fn priority_classes<'de, D, V>(deserializer: D) -> Result<BTreeMap<u32, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    BTreeMap::<String, V>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(priority, value)| Ok((parse_priority_key::<D::Error>(&priority)?, value)))
+        .collect()
+}
+
+fn parse_priority_key<E: serde::de::Error>(priority: &str) -> Result<u32, E> {
+    priority
+        .parse()
+        .map_err(|_| E::custom(format!("priority '{}' is not an integer", priority)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::CompileConfig;
+    use crate::scheduler::BatchSchedulerType;
+    use arvak_compile::PipelinePreset;
+    use arvak_config::{ConfigLoader, LayeredConfig};
+
+    #[test]
+    fn test_scheduler_section() {
+        let file = LayeredConfig::parse(
+            r#"
+                [scheduler]
+                scheduler_type = "pbs"
+                poll_interval_secs = 5
+                queue_policy = "edf"
+                deadline_horizon_secs = 3600
+
+                [scheduler.pbs]
+                queue = "quantum"
+                walltime = "00:30:00"
+
+                [scheduler.slurm.priority_qos_mapping]
+                200 = "high"
+
+                [scheduler.breaker]
+                failure_threshold = 0.25
+
+                [scheduler.fair_share]
+                group_by = "project"
+
+                [scheduler.fair_share.shares]
+                chemistry = 2
+
+                [scheduler.node_features]
+                qpu = ["qctrl"]
+
+                [scheduler.preemption.policies]
+                0 = "cancel"
+                150 = "never"
+
+                [scheduler.result_validation]
+                action = "flag"
+                rules = [{ type = "total_shots" }, { type = "min_entropy", bits = 0.5 }]
+
+                [scheduler.compile]
+                preset = "iontrap-alltoall"
+                max_clbits = 64
+            "#,
+            "arvak.toml",
+        )
+        .unwrap();
+
+        let config: SchedulerConfig = file.section().unwrap();
+        assert!(matches!(config.scheduler_type, BatchSchedulerType::Pbs));
+        assert_eq!(config.poll_interval_secs, 5);
+        assert_eq!(config.queue_policy, crate::queue::QueuePolicy::Deadline);
+        assert_eq!(config.deadline_horizon_secs, Some(3600));
+        assert_eq!(config.max_wait_time_secs, 86400);
+        assert_eq!(config.pbs.queue, "quantum");
+        assert_eq!(config.pbs.nodes, 1);
+        assert_eq!(
+            config
+                .slurm
+                .priority_qos_mapping
+                .unwrap()
+                .get(&200)
+                .map(String::as_str),
+            Some("high")
+        );
+        assert_eq!(config.breaker.failure_threshold, 0.25);
+        assert_eq!(
+            config.fair_share,
+            Some(
+                FairShareConfig::default()
+                    .with_group_by(crate::fairshare::ShareGroup::Project)
+                    .with_share("chemistry", 2)
+            )
+        );
+        assert_eq!(config.node_features["qpu"], vec!["qctrl"]);
+        let preemption = config.preemption.unwrap();
+        assert_eq!(preemption.urgent_priority, 250);
+        assert_eq!(
+            preemption.policy(crate::job::Priority::DEFAULT),
+            crate::preempt::PreemptionPolicy::Cancel
+        );
+        assert_eq!(
+            config.result_validation,
+            Some(
+                ResultValidation::new()
+                    .with_rule(ValidationRule::TotalShots)
+                    .with_rule(ValidationRule::MinEntropy { bits: 0.5 })
+                    .with_action(crate::validate::ValidationAction::Flag)
+            )
+        );
+        assert_eq!(
+            config.compile,
+            Some(CompileConfig::new(PipelinePreset::IonTrapAllToAll).with_max_clbits(64))
+        );
+
+        // The adapter section can be read on its own.
+        let slurm: SlurmConfig = file.section().unwrap();
+        assert_eq!(slurm.partition, "compute");
+    }
+
+    #[test]
+    fn test_kubernetes_section() {
+        let file = LayeredConfig::parse(
+            r#"
+                [scheduler]
+                scheduler_type = "kubernetes"
+
+                [scheduler.kubernetes]
+                namespace = "quantum"
+                image = "registry.example.org/arvak:0.4"
+                node_selector = ["arvak.io/qpu-access=true"]
+
+                [scheduler.kubernetes.results.object_store]
+                url = "https://objects.example.org/arvak"
+                token_secret = "arvak-results-token"
+                token = { env = "ARVAK_RESULT_TOKEN" }
+
+                [scheduler.kubernetes.priority_class_mapping]
+                200 = "quantum-high"
+            "#,
+            "arvak.toml",
+        )
+        .unwrap();
+
+        let config: SchedulerConfig = file.section().unwrap();
+        assert!(matches!(
+            config.scheduler_type,
+            BatchSchedulerType::Kubernetes
+        ));
+        let k8s = config.kubernetes;
+        assert_eq!(k8s.namespace, "quantum");
+        assert_eq!(k8s.node_selector, ["arvak.io/qpu-access=true"]);
+        assert_eq!(k8s.memory_mb, 4096);
+        assert!(matches!(
+            k8s.results,
+            ResultStore::ObjectStore { ref url, token: Some(_), .. }
+                if url == "https://objects.example.org/arvak"
+        ));
+        assert_eq!(
+            k8s.priority_class_mapping
+                .unwrap()
+                .get(&200)
+                .map(String::as_str),
+            Some("quantum-high")
+        );
+
+        let volume = LayeredConfig::parse(
+            "[scheduler.kubernetes.results.volume]\nclaim = \"results\"\nlocal_path = \"/mnt/results\"\n",
+            "arvak.toml",
+        )
+        .unwrap()
+        .section::<KubernetesConfig>()
+        .unwrap();
+        assert!(matches!(
+            volume.results,
+            ResultStore::Volume { ref mount_path, .. } if mount_path.as_os_str() == "/arvak/results"
+        ));
+    }
+
+    #[test]
+    fn test_invalid_values_name_keys() {
+        let key = |text: &str| {
+            LayeredConfig::parse(text, "arvak.toml")
+                .unwrap()
+                .section::<SchedulerConfig>()
+                .unwrap_err()
+                .key()
+                .map(str::to_string)
+        };
+
+        assert_eq!(
+            key("[scheduler.slurm]\ntime_limit = 0\n").as_deref(),
+            Some("scheduler.slurm.time_limit")
+        );
+        assert_eq!(
+            key("[scheduler.pbs]\nwalltime = \"1h\"\n").as_deref(),
+            Some("scheduler.pbs.walltime")
+        );
+        assert_eq!(
+            key("[scheduler.lsf]\ncores = 0\n").as_deref(),
+            Some("scheduler.lsf.cores")
+        );
+        assert_eq!(
+            key("[scheduler.kubernetes]\nnode_selector = [\"gpu\"]\n").as_deref(),
+            Some("scheduler.kubernetes.node_selector")
+        );
+        assert_eq!(
+            key("[scheduler.kubernetes.results.object_store]\nurl = \"s3://bucket\"\n").as_deref(),
+            Some("scheduler.kubernetes.results.object_store.url")
+        );
+        assert_eq!(
+            key("[scheduler.node_features]\nqpu = [\"qctrl&gpu\"]\n").as_deref(),
+            Some("scheduler.node_features.qpu")
+        );
+        assert_eq!(
+            key("[scheduler.preemption.policies]\nurgent = \"cancel\"\n").as_deref(),
+            Some("scheduler.preemption.policies")
+        );
+        assert_eq!(
+            key("[scheduler.result_validation]\nrules = [{ type = \"register_width\", width = 0 }]\n")
+                .as_deref(),
+            Some("scheduler.result_validation.rules")
+        );
+        assert_eq!(
+            key("[scheduler.breaker]\nfailure_threshold = 2.0\n").as_deref(),
+            Some("scheduler.breaker.failure_threshold")
+        );
+        assert_eq!(
+            key("[scheduler.compile]\npreset = \"fastest\"\n").as_deref(),
+            Some("scheduler.compile.preset")
+        );
+        assert_eq!(
+            key("[scheduler]\nscheduler_type = \"sge\"\n").as_deref(),
+            Some("scheduler.scheduler_type")
+        );
+        assert_eq!(
+            key("[scheduler.slurm.priority_qos_mapping]\nhigh = \"x\"\n").as_deref(),
+            Some("scheduler.slurm.priority_qos_mapping")
+        );
+
+        let err = ConfigLoader::new()
+            .with_env_vars([("ARVAK_SCHEDULER__POLL_INTERVAL_SECS", "0")])
+            .load()
+            .unwrap()
+            .section::<SchedulerConfig>()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("'scheduler.poll_interval_secs' (from environment variable"),
+            "{}",
+            err
+        );
+    }
+}
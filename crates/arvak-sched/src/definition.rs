@@ -0,0 +1,648 @@
+//! Declarative workflow definitions.
+//!
+//! Operators describe a workflow DAG in a YAML or TOML file instead of
+//! building it in Rust. Each node runs a QASM circuit file, resolved
+//! relative to the definition file, or a classical task closure registered
+//! with the scheduler, and lists the nodes it depends on by name.
+//!
+//! ```yaml
+//! name: vqe-campaign
+//! nodes:
+//!   - name: prepare
+//!     circuit: circuits/prepare.qasm
+//!     shots: 2000
+//!     priority: high
+//!     resources:
+//!       min_qubits: 5
+//!       allow_simulator: false
+//!   - name: analyze
+//!     task: analyze
+//!     depends_on: [prepare]
+//!     on_failure: continue_independent
+//! ```
+//!
+//! The same workflow in TOML uses a `[[nodes]]` table per node. Errors in
+//! the definition, from a misspelt field to a dependency cycle, name the
+//! line of the file they were found at; see [`Workflow::from_file`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{CircuitSpec, Priority, ResourceRequirements, ScheduledJob};
+use crate::task::ClassicalTask;
+use crate::workflow::{FailurePolicy, Workflow};
+
+/// File formats of workflow definitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionFormat {
+    /// YAML, with a list of nodes.
+    Yaml,
+
+    /// TOML, with a `[[nodes]]` table per node.
+    Toml,
+}
+
+impl DefinitionFormat {
+    /// Detect the format from a file extension.
+    pub fn from_path(path: &Path) -> SchedResult<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("yaml" | "yml") => Ok(DefinitionFormat::Yaml),
+            Some("toml") => Ok(DefinitionFormat::Toml),
+            _ => Err(SchedError::ParseError(format!(
+                "unknown workflow format of {}: expected .yaml, .yml or .toml",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// A workflow as written in a definition file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkflowDefinition {
+    /// Workflow name.
+    pub name: String,
+
+    /// Whether nodes reuse the results of identical earlier jobs, see
+    /// [`crate::cache`].
+    #[serde(default)]
+    pub caching: bool,
+
+    /// Nodes of the DAG.
+    pub nodes: Vec<NodeDefinition>,
+}
+
+/// A node of a [`WorkflowDefinition`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NodeDefinition {
+    /// Node name, unique within the workflow.
+    pub name: String,
+
+    /// QASM file to run, relative to the definition file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit: Option<PathBuf>,
+
+    /// Name of a classical task closure to run instead of a circuit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+
+    /// Number of shots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shots: Option<u32>,
+
+    /// Priority, by name (`low`, `default`, `high`, `critical`, `urgent`)
+    /// or value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<PriorityDefinition>,
+
+    /// Resource requirements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesDefinition>,
+
+    /// Walltime in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub walltime: Option<u64>,
+
+    /// Labels added to the job metadata.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+
+    /// What a failure of the node does to the workflow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<FailurePolicy>,
+
+    /// Names of the nodes that must succeed before this one runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Line of the definition file the node starts at.
+    #[serde(skip)]
+    pub line: Option<usize>,
+}
+
+/// A priority given by name or value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PriorityDefinition {
+    /// A priority value, see [`Priority`].
+    Value(u32),
+
+    /// A named priority.
+    Named(String),
+}
+
+impl PriorityDefinition {
+    /// Resolve to a priority; unknown names are an error.
+    pub fn resolve(&self) -> Result<Priority, String> {
+        match self {
+            PriorityDefinition::Value(value) => Ok(Priority::new(*value)),
+            PriorityDefinition::Named(name) => match name.to_lowercase().as_str() {
+                "low" => Ok(Priority::low()),
+                "default" | "normal" => Ok(Priority::default()),
+                "high" => Ok(Priority::high()),
+                "critical" => Ok(Priority::critical()),
+                "urgent" => Ok(Priority::urgent()),
+                _ => Err(format!(
+                    "unknown priority '{}': use low, default, high, critical, urgent or a number",
+                    name
+                )),
+            },
+        }
+    }
+}
+
+/// Resource requirements of a node; omitted fields take the defaults of
+/// [`ResourceRequirements`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourcesDefinition {
+    /// Minimum number of qubits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_qubits: Option<u32>,
+
+    /// Whether simulator backends may run the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_simulator: Option<bool>,
+
+    /// Maximum time to wait in the queue, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_queue_time: Option<u64>,
+
+    /// Preferred backend names.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preferred_backends: Vec<String>,
+
+    /// Gates the backend must support.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_gates: Vec<String>,
+
+    /// Batch node features the job needs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub node_features: Vec<String>,
+
+    /// Prefer the backend and layout with the highest estimated success
+    /// probability.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub maximize_esp: bool,
+}
+
+impl ResourcesDefinition {
+    /// Convert to resource requirements.
+    pub fn to_requirements(&self) -> ResourceRequirements {
+        let defaults = ResourceRequirements::default();
+        ResourceRequirements {
+            min_qubits: self.min_qubits.unwrap_or(defaults.min_qubits),
+            allow_simulator: self.allow_simulator.unwrap_or(defaults.allow_simulator),
+            max_queue_time: self.max_queue_time,
+            preferred_backends: self.preferred_backends.clone(),
+            required_gates: self.required_gates.clone(),
+            node_features: self.node_features.clone(),
+            maximize_esp: self.maximize_esp,
+            ..defaults
+        }
+    }
+}
+
+impl WorkflowDefinition {
+    /// Parse and validate a definition.
+    ///
+    /// Errors read `line N: ...` where the line is known.
+    pub fn parse(source: &str, format: DefinitionFormat) -> SchedResult<Self> {
+        let mut definition: Self = match format {
+            DefinitionFormat::Yaml => serde_yaml::from_str(source).map_err(|e| {
+                let mut message = e.to_string();
+                let line = e.location().map(|location| {
+                    // The message ends with the location; keep it in front.
+                    let suffix =
+                        format!(" at line {} column {}", location.line(), location.column());
+                    if message.ends_with(&suffix) {
+                        message.truncate(message.len() - suffix.len());
+                    }
+                    location.line()
+                });
+                located(line, message)
+            })?,
+            DefinitionFormat::Toml => toml::from_str(source).map_err(|e| {
+                let line = e.span().map(|span| line_of(source, span.start));
+                located(line, e.message().to_string())
+            })?,
+        };
+        let lines = node_lines(source, format);
+        if lines.len() == definition.nodes.len() {
+            for (node, line) in definition.nodes.iter_mut().zip(lines) {
+                node.line = Some(line);
+            }
+        }
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Load a definition file, detecting the format from its extension.
+    pub fn load(path: &Path) -> SchedResult<Self> {
+        let format = DefinitionFormat::from_path(path)?;
+        let source = std::fs::read_to_string(path)?;
+        Self::parse(&source, format).map_err(|e| in_file(path, e))
+    }
+
+    /// Check the definition: unique node names, one circuit or task per
+    /// node, known dependencies and no cycles.
+    pub fn validate(&self) -> SchedResult<()> {
+        if self.name.trim().is_empty() {
+            return Err(located(None, "workflow has no name".to_string()));
+        }
+        if self.nodes.is_empty() {
+            return Err(located(None, "workflow has no nodes".to_string()));
+        }
+
+        let mut index = BTreeMap::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let fail = |message: String| Err(located(node.line, message));
+            if node.name.trim().is_empty() {
+                return fail("node has no name".to_string());
+            }
+            if index.insert(node.name.as_str(), i).is_some() {
+                return fail(format!("duplicate node name '{}'", node.name));
+            }
+            match (&node.circuit, &node.task) {
+                (Some(_), Some(_)) => {
+                    return fail(format!(
+                        "node '{}' has both a circuit and a task",
+                        node.name
+                    ));
+                }
+                (None, None) => {
+                    return fail(format!("node '{}' needs a circuit or a task", node.name));
+                }
+                _ => {}
+            }
+            if node.shots == Some(0) {
+                return fail(format!("node '{}' must run at least one shot", node.name));
+            }
+            if let Some(Err(message)) = node.priority.as_ref().map(PriorityDefinition::resolve) {
+                return fail(format!("node '{}': {}", node.name, message));
+            }
+        }
+
+        for node in &self.nodes {
+            for dependency in &node.depends_on {
+                let message = if dependency == &node.name {
+                    format!("node '{}' depends on itself", node.name)
+                } else if !index.contains_key(dependency.as_str()) {
+                    format!(
+                        "node '{}' depends on unknown node '{}'",
+                        node.name, dependency
+                    )
+                } else {
+                    continue;
+                };
+                return Err(located(node.line, message));
+            }
+        }
+
+        if let Some(i) = self.cycle_member(&index) {
+            let node = &self.nodes[i];
+            return Err(located(
+                node.line,
+                format!("node '{}' is part of a dependency cycle", node.name),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Find a node on a dependency cycle: one left over after repeatedly
+    /// removing nodes whose dependencies have all been removed.
+    fn cycle_member(&self, index: &BTreeMap<&str, usize>) -> Option<usize> {
+        let mut remaining: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| node.depends_on.len())
+            .collect();
+        let mut dependents = vec![Vec::new(); self.nodes.len()];
+        for (i, node) in self.nodes.iter().enumerate() {
+            for dependency in &node.depends_on {
+                dependents[index[dependency.as_str()]].push(i);
+            }
+        }
+        let mut ready: Vec<usize> = (0..self.nodes.len())
+            .filter(|i| remaining[*i] == 0)
+            .collect();
+        while let Some(i) = ready.pop() {
+            for &dependent in &dependents[i] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+        remaining.iter().position(|n| *n > 0)
+    }
+
+    /// Build the workflow, resolving circuit files relative to `base_dir`.
+    ///
+    /// Circuit files must exist and parse as QASM.
+    pub fn to_workflow(&self, base_dir: &Path) -> SchedResult<Workflow> {
+        self.validate()?;
+        let mut workflow = Workflow::new(self.name.clone());
+        workflow.caching = self.caching;
+
+        let mut ids = BTreeMap::new();
+        for node in &self.nodes {
+            let mut job = match (&node.circuit, &node.task) {
+                (Some(circuit), _) => {
+                    let path = base_dir.join(circuit);
+                    let spec = CircuitSpec::from_file(&path);
+                    spec.resolve().map_err(|e| {
+                        located(
+                            node.line,
+                            format!("circuit {} of node '{}': {}", path.display(), node.name, e),
+                        )
+                    })?;
+                    ScheduledJob::new(node.name.clone(), spec)
+                }
+                (None, Some(task)) => {
+                    ScheduledJob::classical(node.name.clone(), ClassicalTask::closure(task))
+                }
+                (None, None) => unreachable!("validated above"),
+            };
+            if let Some(shots) = node.shots {
+                job = job.with_shots(shots);
+            }
+            if let Some(priority) = &node.priority {
+                job = job.with_priority(priority.resolve().map_err(SchedError::ParseError)?);
+            }
+            if let Some(resources) = &node.resources {
+                job = job.with_requirements(resources.to_requirements());
+            }
+            if let Some(walltime) = node.walltime {
+                job = job.with_walltime(std::time::Duration::from_secs(walltime));
+            }
+            for (key, value) in &node.labels {
+                job = job.with_metadata(key.clone(), value.clone());
+            }
+            ids.insert(node.name.as_str(), job.id.clone());
+            workflow.add_job(job);
+        }
+
+        for node in &self.nodes {
+            let id = &ids[node.name.as_str()];
+            for dependency in &node.depends_on {
+                workflow.add_dependency(&ids[dependency.as_str()], id)?;
+            }
+            if let Some(policy) = node.on_failure {
+                workflow.set_failure_policy(id, policy)?;
+            }
+        }
+        Ok(workflow)
+    }
+}
+
+impl Workflow {
+    /// Load a workflow from a YAML definition file, see
+    /// [`crate::definition`].
+    pub fn from_yaml(path: impl AsRef<Path>) -> SchedResult<Self> {
+        Self::from_definition(path.as_ref(), DefinitionFormat::Yaml)
+    }
+
+    /// Load a workflow from a TOML definition file, see
+    /// [`crate::definition`].
+    pub fn from_toml(path: impl AsRef<Path>) -> SchedResult<Self> {
+        Self::from_definition(path.as_ref(), DefinitionFormat::Toml)
+    }
+
+    /// Load a workflow from a definition file, detecting the format from
+    /// its extension.
+    ///
+    /// Errors name the file and, where known, the line, e.g.
+    /// `campaign.yaml: line 12: node 'analyze' depends on unknown node 'prep'`.
+    pub fn from_file(path: impl AsRef<Path>) -> SchedResult<Self> {
+        let path = path.as_ref();
+        Self::from_definition(path, DefinitionFormat::from_path(path)?)
+    }
+
+    fn from_definition(path: &Path, format: DefinitionFormat) -> SchedResult<Self> {
+        let source = std::fs::read_to_string(path)?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        WorkflowDefinition::parse(&source, format)
+            .and_then(|definition| definition.to_workflow(base_dir))
+            .map_err(|e| in_file(path, e))
+    }
+}
+
+/// Line (1-based) of a byte offset.
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// Lines (1-based) the nodes start at, in order: each `[[nodes]]` header
+/// in TOML, each item of the block list under `nodes:` in YAML.
+fn node_lines(source: &str, format: DefinitionFormat) -> Vec<usize> {
+    let lines = source.lines().enumerate().map(|(i, line)| (i + 1, line));
+    match format {
+        DefinitionFormat::Toml => lines
+            .filter(|(_, line)| line.trim() == "[[nodes]]")
+            .map(|(n, _)| n)
+            .collect(),
+        DefinitionFormat::Yaml => {
+            let mut starts = Vec::new();
+            let mut in_nodes = false;
+            let mut item_indent = None;
+            for (n, line) in lines {
+                let trimmed = line.trim_start();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    continue;
+                }
+                let indent = line.len() - trimmed.len();
+                if indent == 0 && !trimmed.starts_with('-') {
+                    in_nodes = trimmed.trim_end() == "nodes:";
+                    continue;
+                }
+                if in_nodes
+                    && trimmed.starts_with('-')
+                    && *item_indent.get_or_insert(indent) == indent
+                {
+                    starts.push(n);
+                }
+            }
+            starts
+        }
+    }
+}
+
+fn located(line: Option<usize>, message: String) -> SchedError {
+    match line {
+        Some(line) => SchedError::ParseError(format!("line {}: {}", line, message)),
+        None => SchedError::ParseError(message),
+    }
+}
+
+pub(crate) fn in_file(path: &Path, error: SchedError) -> SchedError {
+    match error {
+        SchedError::ParseError(message) => {
+            SchedError::ParseError(format!("{}: {}", path.display(), message))
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::ScheduledJobId;
+
+    const YAML: &str = "\
+# VQE campaign
+name: vqe-campaign
+caching: true
+nodes:
+  - name: prepare
+    circuit: bell.qasm
+    shots: 2000
+    priority: high
+    resources:
+      min_qubits: 2
+      allow_simulator: false
+  - name: sweep
+    circuit: bell.qasm
+    priority: 120
+    depends_on: [prepare]
+    labels:
+      project: h2
+  - name: analyze
+    task: analyze
+    depends_on:
+      - prepare
+      - sweep
+    on_failure: continue_independent
+";
+
+    const TOML: &str = r#"
+name = "vqe-campaign"
+
+[[nodes]]
+name = "prepare"
+circuit = "bell.qasm"
+walltime = 600
+
+[[nodes]]
+name = "analyze"
+task = "analyze"
+depends_on = ["prepare"]
+"#;
+
+    fn write_circuit(dir: &Path) {
+        std::fs::write(
+            dir.join("bell.qasm"),
+            "OPENQASM 3.0;\nqubit[2] q;\nh q[0];\ncx q[0], q[1];\n",
+        )
+        .unwrap();
+    }
+
+    fn names(workflow: &Workflow, ids: Vec<&ScheduledJobId>) -> Vec<String> {
+        ids.into_iter()
+            .map(|id| workflow.get_job(id).unwrap().name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_workflow_from_yaml() {
+        let dir = tempfile::tempdir().unwrap();
+        write_circuit(dir.path());
+        let path = dir.path().join("campaign.yaml");
+        std::fs::write(&path, YAML).unwrap();
+
+        let workflow = Workflow::from_yaml(&path).unwrap();
+        assert_eq!(workflow.name, "vqe-campaign");
+        assert!(workflow.caching);
+        let order: Vec<_> = workflow
+            .topological_order()
+            .into_iter()
+            .map(|job| job.name.clone())
+            .collect();
+        assert_eq!(order, vec!["prepare", "sweep", "analyze"]);
+
+        let jobs = workflow.all_jobs();
+        let job = |name: &str| *jobs.iter().find(|j| j.name == name).unwrap();
+        let prepare = job("prepare");
+        assert_eq!(prepare.shots, 2000);
+        assert_eq!(prepare.priority, Priority::high());
+        assert_eq!(prepare.requirements.min_qubits, 2);
+        assert!(!prepare.requirements.allow_simulator);
+        assert_eq!(job("sweep").priority, Priority::new(120));
+        assert_eq!(job("sweep").metadata["project"], "h2");
+        assert!(job("analyze").is_classical());
+
+        let analyze = &job("analyze").id;
+        assert_eq!(names(&workflow, workflow.dependencies(analyze)).len(), 2);
+        assert_eq!(
+            workflow.failure_policy(analyze),
+            Some(FailurePolicy::ContinueIndependent)
+        );
+    }
+
+    #[test]
+    fn test_workflow_from_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        write_circuit(dir.path());
+        let path = dir.path().join("campaign.toml");
+        std::fs::write(&path, TOML).unwrap();
+
+        let workflow = Workflow::from_file(&path).unwrap();
+        assert_eq!(workflow.len(), 2);
+        let jobs = workflow.all_jobs();
+        let prepare = jobs.iter().find(|j| j.name == "prepare").unwrap();
+        assert_eq!(prepare.walltime, Some(600));
+        assert!(Workflow::from_file(dir.path().join("campaign.json")).is_err());
+    }
+
+    #[test]
+    fn test_errors_point_at_lines() {
+        let error = |source: &str, format| {
+            WorkflowDefinition::parse(source, format)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let unknown = YAML.replace("depends_on: [prepare]", "depends_on: [prep]");
+        assert!(
+            error(&unknown, DefinitionFormat::Yaml)
+                .contains("line 12: node 'sweep' depends on unknown node 'prep'")
+        );
+        let cycle = YAML.replace("depends_on: [prepare]", "depends_on: [analyze]");
+        assert!(error(&cycle, DefinitionFormat::Yaml).contains("cycle"));
+        let duplicate = YAML.replace("name: analyze", "name: sweep");
+        assert!(
+            error(&duplicate, DefinitionFormat::Yaml)
+                .contains("line 18: duplicate node name 'sweep'")
+        );
+        let typo = YAML.replace("shots: 2000", "shot: 2000");
+        assert!(error(&typo, DefinitionFormat::Yaml).contains("line 7"));
+        let priority = YAML.replace("priority: high", "priority: soon");
+        assert!(error(&priority, DefinitionFormat::Yaml).contains("line 5: node 'prepare'"));
+
+        let both = TOML.replace(
+            "task = \"analyze\"",
+            "task = \"analyze\"\ncircuit = \"x.qasm\"",
+        );
+        assert!(
+            error(&both, DefinitionFormat::Toml)
+                .contains("line 9: node 'analyze' has both a circuit and a task")
+        );
+        let wrong_type = TOML.replace("walltime = 600", "walltime = \"10m\"");
+        assert!(error(&wrong_type, DefinitionFormat::Toml).contains("line 7"));
+
+        // Missing circuit files are reported when building.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("campaign.yaml");
+        std::fs::write(&path, YAML).unwrap();
+        let message = Workflow::from_file(&path).unwrap_err().to_string();
+        assert!(message.contains("campaign.yaml: line 5: circuit"));
+    }
+}
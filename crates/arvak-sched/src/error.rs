@@ -16,6 +16,18 @@ pub enum SchedError {
     #[error("Workflow not found: {0}")]
     WorkflowNotFound(String),
 
+    /// Job template not found in the store.
+    #[error("Template not found: {0}")]
+    TemplateNotFound(String),
+
+    /// Recurring job not found in the store.
+    #[error("Recurring job not found: {0}")]
+    RecurringJobNotFound(String),
+
+    /// Reservation not found in the store.
+    #[error("Reservation not found: {0}")]
+    ReservationNotFound(String),
+
     /// Invalid job state for the requested operation.
     #[error("Invalid job state: expected {expected}, found {found}")]
     InvalidJobState { expected: String, found: String },
@@ -28,6 +40,10 @@ pub enum SchedError {
     #[error("Invalid dependency: job {0} not found")]
     InvalidDependency(String),
 
+    /// Job circuits are inconsistent (e.g. duplicate result labels).
+    #[error("Invalid circuit payload: {0}")]
+    InvalidPayload(String),
+
     /// SLURM submission failed.
     #[error("SLURM submission failed: {0}")]
     SlurmSubmitError(String),
@@ -40,6 +56,15 @@ pub enum SchedError {
     #[error("SLURM job not found: {0}")]
     SlurmJobNotFound(String),
 
+    /// The SSH connection to the cluster failed, so it is unknown whether
+    /// the command ran.
+    #[error("SSH connection to {host} failed running {command}: {message}")]
+    SshConnectionFailed {
+        host: String,
+        command: String,
+        message: String,
+    },
+
     /// PBS submission failed.
     #[error("PBS submission failed: {0}")]
     PbsSubmitError(String),
@@ -52,6 +77,34 @@ pub enum SchedError {
     #[error("PBS job not found: {0}")]
     PbsJobNotFound(String),
 
+    /// LSF submission failed.
+    #[error("LSF submission failed: {0}")]
+    LsfSubmitError(String),
+
+    /// LSF command execution failed.
+    #[error("LSF command failed: {command} - {message}")]
+    LsfCommandError { command: String, message: String },
+
+    /// LSF job not found.
+    #[error("LSF job not found: {0}")]
+    LsfJobNotFound(String),
+
+    /// Kubernetes Job creation failed.
+    #[error("Kubernetes job creation failed: {0}")]
+    KubernetesSubmitError(String),
+
+    /// kubectl command execution failed.
+    #[error("Kubernetes command failed: {command} - {message}")]
+    KubernetesCommandError { command: String, message: String },
+
+    /// Kubernetes Job not found.
+    #[error("Kubernetes job not found: {0}")]
+    KubernetesJobNotFound(String),
+
+    /// Reading from or writing to an object store failed.
+    #[error("Object store request failed: {url} - {message}")]
+    ObjectStoreError { url: String, message: String },
+
     /// No suitable backend found for the job requirements.
     #[error("No matching backend found: {0}")]
     NoMatchingBackend(String),
@@ -80,6 +133,10 @@ pub enum SchedError {
     #[error("Parse error: {0}")]
     ParseError(String),
 
+    /// Compiling a circuit for the target failed.
+    #[error("Compilation error: {0}")]
+    CompilationError(String),
+
     /// Timeout waiting for job completion.
     #[error("Job timeout: {0}")]
     Timeout(String),
@@ -88,10 +145,34 @@ pub enum SchedError {
     #[error("Job cancelled: {0}")]
     Cancelled(String),
 
+    /// A site hook failed under the abort policy.
+    #[error("{point} hook {hook} failed: {message}")]
+    HookFailed {
+        hook: String,
+        point: crate::hooks::HookPoint,
+        message: String,
+    },
+
+    /// Principal is not allowed to perform an action.
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    /// Result verification failed.
+    #[error("Result verification failed: {0}")]
+    VerificationFailed(String),
+
+    /// This instance lost its leader lease, e.g. to a takeover.
+    #[error("Lost lease {0} to another instance")]
+    LeaseLost(String),
+
     /// Configuration error.
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
+    /// The state store does not support an operation.
+    #[error("{0} is not supported by this state store")]
+    Unsupported(String),
+
     /// Internal scheduler error.
     #[error("Internal error: {0}")]
     Internal(String),
@@ -103,12 +184,24 @@ impl From<arvak_hal::HalError> for SchedError {
     }
 }
 
+impl From<arvak_config::ConfigError> for SchedError {
+    fn from(e: arvak_config::ConfigError) -> Self {
+        SchedError::ConfigError(e.to_string())
+    }
+}
+
 impl From<rusqlite::Error> for SchedError {
     fn from(e: rusqlite::Error) -> Self {
         SchedError::DatabaseError(e.to_string())
     }
 }
 
+impl From<arvak_compile::CompileError> for SchedError {
+    fn from(e: arvak_compile::CompileError) -> Self {
+        SchedError::CompilationError(e.to_string())
+    }
+}
+
 impl From<arvak_qasm3::ParseError> for SchedError {
     fn from(e: arvak_qasm3::ParseError) -> Self {
         SchedError::ParseError(e.to_string())
@@ -0,0 +1,302 @@
+//! Scheduler decision log.
+//!
+//! The scheduler records every decision it takes about a job, from
+//! submission through resource matching and batch dispatch to the final
+//! status, as a [`SchedulerEvent`]. Events go to an [`EventLog`]; the
+//! [`JsonlEventLog`] appends them to a file, one JSON object per line, which
+//! [`crate::replay`] can later turn back into a decision trace.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use arvak_hal::JobId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::cloud::CLOUD_JOB_ID;
+use crate::error::{SchedError, SchedResult};
+use crate::job::{Priority, ScheduledJobId, ScheduledJobStatus};
+use crate::negotiate::Negotiation;
+use crate::preempt::PreemptionPolicy;
+use crate::retry::FailureKind;
+
+/// A decision the scheduler took about a job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerEvent {
+    /// When the decision was taken.
+    pub at: DateTime<Utc>,
+
+    /// Job the decision concerns.
+    pub job_id: ScheduledJobId,
+
+    /// What was decided.
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+impl SchedulerEvent {
+    /// Create an event for a job, timestamped now.
+    pub fn new(job_id: ScheduledJobId, kind: EventKind) -> Self {
+        Self {
+            at: Utc::now(),
+            job_id,
+            kind,
+        }
+    }
+}
+
+/// Kinds of scheduler decisions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    /// The job was accepted and queued.
+    Submitted {
+        name: String,
+        priority: Priority,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        dependencies: Vec<ScheduledJobId>,
+        status: ScheduledJobStatus,
+    },
+
+    /// Resource matching picked a backend.
+    Matched { backend: String },
+
+    /// Pre-flight capability negotiation with the matched backend ran.
+    Negotiated { outcome: Negotiation },
+
+    /// The job was kept in the queue.
+    Held { reason: String },
+
+    /// The job was handed to the batch system.
+    Dispatched { batch_job_id: String },
+
+    /// The job was submitted directly to a cloud provider.
+    CloudSubmitted {
+        backend: String,
+        remote_job_id: String,
+    },
+
+    /// The job failed on a paused backend and was requeued.
+    Rerouted { from_backend: String, attempt: u32 },
+
+    /// The job failed in a way its retry policy covers and was requeued.
+    Retried {
+        attempt: u32,
+        kind: FailureKind,
+        reason: String,
+        delay_secs: u64,
+    },
+
+    /// The running job was preempted for an urgent job.
+    Preempted {
+        by: ScheduledJobId,
+        policy: PreemptionPolicy,
+        status: ScheduledJobStatus,
+    },
+
+    /// The job's status changed.
+    StatusChanged {
+        status: ScheduledJobStatus,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+}
+
+impl EventKind {
+    /// Status the job is in after the decision, if it changed it.
+    pub fn status(&self) -> Option<ScheduledJobStatus> {
+        match self {
+            EventKind::Submitted { status, .. }
+            | EventKind::Preempted { status, .. }
+            | EventKind::StatusChanged { status, .. } => Some(status.clone()),
+            EventKind::Dispatched { batch_job_id } => Some(ScheduledJobStatus::SlurmQueued {
+                slurm_job_id: batch_job_id.clone(),
+            }),
+            EventKind::CloudSubmitted { remote_job_id, .. } => {
+                Some(ScheduledJobStatus::QuantumSubmitted {
+                    slurm_job_id: CLOUD_JOB_ID.to_string(),
+                    quantum_job_id: JobId(remote_job_id.clone()),
+                })
+            }
+            EventKind::Rerouted { .. } | EventKind::Retried { .. } => {
+                Some(ScheduledJobStatus::Pending)
+            }
+            EventKind::Matched { .. } | EventKind::Negotiated { .. } | EventKind::Held { .. } => {
+                None
+            }
+        }
+    }
+}
+
+/// Sink for scheduler events.
+pub trait EventLog: Send + Sync {
+    /// Record an event.
+    fn record(&self, event: SchedulerEvent);
+}
+
+/// Event log discarding all events.
+#[derive(Debug, Default)]
+pub struct NullEventLog;
+
+impl EventLog for NullEventLog {
+    fn record(&self, _event: SchedulerEvent) {}
+}
+
+/// Event log keeping events in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryEventLog {
+    events: Mutex<Vec<SchedulerEvent>>,
+}
+
+impl InMemoryEventLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get all recorded events.
+    pub fn events(&self) -> Vec<SchedulerEvent> {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl EventLog for InMemoryEventLog {
+    fn record(&self, event: SchedulerEvent) {
+        self.events
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(event);
+    }
+}
+
+/// Event log appending JSON lines to a file.
+#[derive(Debug)]
+pub struct JsonlEventLog {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonlEventLog {
+    /// Open a log file for appending, creating it if needed.
+    pub fn open(path: impl Into<PathBuf>) -> SchedResult<Self> {
+        let path = path.into();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read all events from a log file.
+    ///
+    /// Blank lines are skipped; malformed lines are reported with their
+    /// line number.
+    pub fn read(path: impl AsRef<Path>) -> SchedResult<Vec<SchedulerEvent>> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = serde_json::from_str(&line).map_err(|e| {
+                SchedError::ParseError(format!("{}:{}: {}", path.display(), i + 1, e))
+            })?;
+            events.push(event);
+        }
+        Ok(events)
+    }
+}
+
+impl EventLog for JsonlEventLog {
+    fn record(&self, event: SchedulerEvent) {
+        let line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to encode scheduler event: {}", e);
+                return;
+            }
+        };
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = writeln!(file, "{}", line) {
+            tracing::warn!(
+                "Failed to write scheduler event to {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("events.jsonl");
+        let job_id = ScheduledJobId::new();
+
+        let events = vec![
+            SchedulerEvent::new(
+                job_id.clone(),
+                EventKind::Submitted {
+                    name: "bell".into(),
+                    priority: Priority::high(),
+                    dependencies: vec![],
+                    status: ScheduledJobStatus::Pending,
+                },
+            ),
+            SchedulerEvent::new(
+                job_id.clone(),
+                EventKind::Dispatched {
+                    batch_job_id: "42".into(),
+                },
+            ),
+        ];
+
+        let log = JsonlEventLog::open(&path).unwrap();
+        for event in &events {
+            log.record(event.clone());
+        }
+        drop(log);
+
+        // Reopening appends rather than truncating.
+        JsonlEventLog::open(&path)
+            .unwrap()
+            .record(SchedulerEvent::new(
+                job_id,
+                EventKind::Held {
+                    reason: "paused".into(),
+                },
+            ));
+
+        let read = JsonlEventLog::read(&path).unwrap();
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[..2], events[..]);
+        let first = std::fs::read_to_string(&path).unwrap();
+        assert!(
+            first
+                .lines()
+                .next()
+                .unwrap()
+                .contains(r#""event":"submitted""#)
+        );
+
+        std::fs::write(&path, "{}\n").unwrap();
+        let err = JsonlEventLog::read(&path).unwrap_err();
+        assert!(err.to_string().contains("events.jsonl:1"), "{}", err);
+    }
+}
@@ -0,0 +1,382 @@
+//! Explanations of why a job has not started yet.
+//!
+//! A [`QueueExplanation`] collects what stands between a job and execution:
+//! its position in the scheduler queue and the jobs ahead of it, the state of
+//! the backends it may run on, and any holds, such as unfinished
+//! dependencies, paused backends or the batch system's own pending reason.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::breaker::BreakerState;
+use crate::error::{SchedError, SchedResult};
+use crate::job::{JobFilter, Priority, ScheduledJob, ScheduledJobId, ScheduledJobStatus};
+use crate::persistence::StateStore;
+
+/// Why a job is queued ahead of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AheadReason {
+    /// The job has a higher priority.
+    HigherPriority,
+
+    /// The job has the same priority and was queued earlier.
+    QueuedEarlier,
+}
+
+/// A job queued ahead of the explained one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobAhead {
+    /// Job ID.
+    pub job_id: ScheduledJobId,
+
+    /// Job name.
+    pub name: String,
+
+    /// Submitting principal, if known.
+    pub owner: Option<String>,
+
+    /// Job priority.
+    pub priority: Priority,
+
+    /// Why it is ahead.
+    pub reason: AheadReason,
+}
+
+/// Availability of a backend the job may run on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackendAvailability {
+    /// Backend name.
+    pub backend: String,
+
+    /// Failure breaker state of the backend.
+    pub breaker: BreakerState,
+
+    /// Whether dispatch to the backend is paused.
+    pub paused: bool,
+}
+
+/// Category of a batch system pending reason.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchHoldKind {
+    /// Waiting behind jobs with a higher fair-share priority.
+    FairShare,
+
+    /// Waiting for free nodes.
+    Resources,
+
+    /// Held back by a QOS, association or partition limit.
+    Throttle,
+
+    /// Waiting for a batch dependency.
+    Dependency,
+
+    /// Held by a user or administrator.
+    Held,
+
+    /// Any other reason.
+    Other,
+}
+
+impl BatchHoldKind {
+    /// Classify a SLURM pending reason as reported by `squeue`.
+    pub fn classify(reason: &str) -> Self {
+        match reason {
+            "Priority" => BatchHoldKind::FairShare,
+            "Resources" | "ReqNodeNotAvail" | "NodeDown" => BatchHoldKind::Resources,
+            "Dependency" => BatchHoldKind::Dependency,
+            r if r.starts_with("JobHeld") => BatchHoldKind::Held,
+            r if r.starts_with("QOS") || r.starts_with("Assoc") || r.contains("Limit") => {
+                BatchHoldKind::Throttle
+            }
+            _ => BatchHoldKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for BatchHoldKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchHoldKind::FairShare => write!(f, "fair-share"),
+            BatchHoldKind::Resources => write!(f, "resources"),
+            BatchHoldKind::Throttle => write!(f, "throttle"),
+            BatchHoldKind::Dependency => write!(f, "dependency"),
+            BatchHoldKind::Held => write!(f, "held"),
+            BatchHoldKind::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Something keeping a job from starting.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "hold", rename_all = "snake_case")]
+pub enum Hold {
+    /// Dependencies have not finished yet.
+    Dependencies { waiting_on: Vec<ScheduledJobId> },
+
+    /// Every backend the job may run on is paused by the failure breaker.
+    BackendsPaused,
+
+    /// No backend satisfies the job's requirements.
+    NoMatchingBackend { message: String },
+
+    /// The batch system has not started the job.
+    BatchSystem { reason: String, kind: BatchHoldKind },
+
+    /// The job failed and is backing off before its next attempt.
+    RetryBackoff { attempt: u32, until: DateTime<Utc> },
+
+    /// The window of the job's reservation has not opened yet.
+    Reservation { name: String, opens: DateTime<Utc> },
+}
+
+impl std::fmt::Display for Hold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hold::Dependencies { waiting_on } => {
+                let ids: Vec<String> = waiting_on.iter().map(ToString::to_string).collect();
+                write!(f, "waiting on dependencies: {}", ids.join(", "))
+            }
+            Hold::BackendsPaused => write!(f, "all matching backends are paused"),
+            Hold::NoMatchingBackend { message } => write!(f, "no matching backend: {}", message),
+            Hold::BatchSystem { reason, kind } => {
+                write!(f, "batch system pending reason {} ({})", reason, kind)
+            }
+            Hold::RetryBackoff { attempt, until } => {
+                write!(f, "attempt {} backing off until {}", attempt, until)
+            }
+            Hold::Reservation { name, opens } => {
+                write!(f, "reservation {} opens at {}", name, opens)
+            }
+        }
+    }
+}
+
+/// Why a job has not started yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueExplanation {
+    /// Explained job.
+    pub job_id: ScheduledJobId,
+
+    /// Current status.
+    pub status: ScheduledJobStatus,
+
+    /// 1-based position in the scheduler queue, if the job is queued there.
+    pub position: Option<usize>,
+
+    /// Number of jobs in the scheduler queue.
+    pub queue_length: usize,
+
+    /// Jobs dispatched before this one.
+    pub ahead: Vec<JobAhead>,
+
+    /// Backends the job may run on.
+    pub backends: Vec<BackendAvailability>,
+
+    /// What keeps the job from starting.
+    pub holds: Vec<Hold>,
+}
+
+impl QueueExplanation {
+    /// Explain a job's place in a queue.
+    ///
+    /// `queued` lists the queued jobs in dispatch order; `is_finished` tells
+    /// whether a dependency has finished. Jobs ahead that still wait on
+    /// their own dependencies do not hold the job back and are left out.
+    pub fn from_queue(
+        job: &ScheduledJob,
+        queued: &[&ScheduledJob],
+        is_finished: impl Fn(&ScheduledJobId) -> bool,
+    ) -> Self {
+        let position = queued.iter().position(|q| q.id == job.id);
+        let ahead = position
+            .map(|pos| {
+                queued[..pos]
+                    .iter()
+                    .filter(|q| q.dependencies.iter().all(&is_finished))
+                    .map(|q| JobAhead {
+                        job_id: q.id.clone(),
+                        name: q.name.clone(),
+                        owner: q.owner.clone(),
+                        priority: q.priority,
+                        reason: if q.priority > job.priority {
+                            AheadReason::HigherPriority
+                        } else {
+                            AheadReason::QueuedEarlier
+                        },
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let waiting_on: Vec<ScheduledJobId> = job
+            .dependencies
+            .iter()
+            .filter(|dep| !is_finished(dep))
+            .cloned()
+            .collect();
+        let mut holds = Vec::new();
+        if !job.status.is_terminal() && !waiting_on.is_empty() {
+            holds.push(Hold::Dependencies { waiting_on });
+        }
+
+        Self {
+            job_id: job.id.clone(),
+            status: job.status.clone(),
+            position: position.map(|pos| pos + 1),
+            queue_length: queued.len(),
+            ahead,
+            backends: Vec::new(),
+            holds,
+        }
+    }
+
+    /// Explain a job from a store alone.
+    ///
+    /// The queue order is rebuilt from the stored pending jobs, by priority
+    /// and then submission time. Backend availability and batch system
+    /// holds are only known to a running scheduler and are left empty.
+    pub async fn load(store: &dyn StateStore, job_id: &ScheduledJobId) -> SchedResult<Self> {
+        let job = store
+            .load_job(job_id)
+            .await?
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+
+        let mut pending = store.list_jobs(&JobFilter::pending()).await?;
+        pending.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| a.created_at.cmp(&b.created_at))
+        });
+
+        let queued_ids: HashSet<&ScheduledJobId> = pending.iter().map(|q| &q.id).collect();
+        let mut finished = HashSet::new();
+        for dep in pending
+            .iter()
+            .chain(std::iter::once(&job))
+            .flat_map(|q| &q.dependencies)
+        {
+            if queued_ids.contains(dep) || finished.contains(dep) {
+                continue;
+            }
+            if let Some(dep_job) = store.load_job(dep).await? {
+                if dep_job.status.is_terminal() {
+                    finished.insert(dep.clone());
+                }
+            }
+        }
+
+        let queued: Vec<&ScheduledJob> = pending.iter().collect();
+        Ok(Self::from_queue(&job, &queued, |dep| {
+            finished.contains(dep)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, DependencyKind};
+    use crate::persistence::SqliteStore;
+
+    fn make_job(name: &str, priority: u32) -> ScheduledJob {
+        ScheduledJob::new(name, CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q;"))
+            .with_priority(Priority(priority))
+    }
+
+    #[test]
+    fn test_from_queue() {
+        let urgent = make_job("urgent", 200);
+        let parent = make_job("parent", 150);
+        let mut blocked = make_job("blocked", 150);
+        blocked.add_dependency(parent.id.clone(), DependencyKind::AfterOk);
+        let early = make_job("early", 100);
+        let mut job = make_job("mine", 100);
+        job.add_dependency(urgent.id.clone(), DependencyKind::AfterOk);
+        let late = make_job("late", 100);
+
+        let queued = [&urgent, &parent, &blocked, &early, &job, &late];
+        let explanation = QueueExplanation::from_queue(&job, &queued, |_| false);
+
+        assert_eq!(explanation.position, Some(5));
+        assert_eq!(explanation.queue_length, 6);
+        let ahead: Vec<_> = explanation
+            .ahead
+            .iter()
+            .map(|a| (a.name.as_str(), a.reason))
+            .collect();
+        assert_eq!(
+            ahead,
+            vec![
+                ("urgent", AheadReason::HigherPriority),
+                ("parent", AheadReason::HigherPriority),
+                ("early", AheadReason::QueuedEarlier),
+            ]
+        );
+        assert_eq!(
+            explanation.holds,
+            vec![Hold::Dependencies {
+                waiting_on: vec![urgent.id.clone()]
+            }]
+        );
+
+        let unqueued = QueueExplanation::from_queue(&make_job("other", 1), &queued, |_| true);
+        assert_eq!(unqueued.position, None);
+        assert!(unqueued.ahead.is_empty() && unqueued.holds.is_empty());
+    }
+
+    #[test]
+    fn test_classify_batch_reason() {
+        assert_eq!(
+            BatchHoldKind::classify("Priority"),
+            BatchHoldKind::FairShare
+        );
+        assert_eq!(
+            BatchHoldKind::classify("Resources"),
+            BatchHoldKind::Resources
+        );
+        assert_eq!(
+            BatchHoldKind::classify("QOSMaxJobsPerUserLimit"),
+            BatchHoldKind::Throttle
+        );
+        assert_eq!(
+            BatchHoldKind::classify("AssocGrpCPUMinutesLimit"),
+            BatchHoldKind::Throttle
+        );
+        assert_eq!(BatchHoldKind::classify("JobHeldAdmin"), BatchHoldKind::Held);
+        assert_eq!(BatchHoldKind::classify("BeginTime"), BatchHoldKind::Other);
+    }
+
+    #[tokio::test]
+    async fn test_load_from_store() {
+        let store = SqliteStore::in_memory().unwrap();
+        let mut done = make_job("done", 100);
+        done.status = ScheduledJobStatus::Cancelled;
+        let mut first = make_job("first", 100);
+        first.add_dependency(done.id.clone(), DependencyKind::AfterAny);
+        let urgent = make_job("urgent", 200);
+        let mut job = make_job("mine", 100);
+        job.add_dependency(urgent.id.clone(), DependencyKind::AfterOk);
+        for j in [&done, &first, &urgent, &job] {
+            store.save_job(j).await.unwrap();
+        }
+
+        let explanation = QueueExplanation::load(&store, &job.id).await.unwrap();
+        assert_eq!(explanation.position, Some(3));
+        assert_eq!(explanation.queue_length, 3);
+        let ahead: Vec<_> = explanation.ahead.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(ahead, vec!["urgent", "first"]);
+        assert_eq!(
+            explanation.holds[0].to_string(),
+            format!("waiting on dependencies: {}", urgent.id)
+        );
+
+        assert!(matches!(
+            QueueExplanation::load(&store, &ScheduledJobId::new()).await,
+            Err(SchedError::JobNotFound(_))
+        ));
+    }
+}
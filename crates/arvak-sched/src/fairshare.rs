@@ -0,0 +1,347 @@
+//! Fair-share reweighting of job priorities.
+//!
+//! When several users or projects share one scheduler, a [`FairSharePolicy`]
+//! keeps each account's recent usage, decayed with a configurable half-life,
+//! and compares it with the account's configured share. Queued jobs of
+//! accounts below their share are moved up and jobs of accounts above it are
+//! moved down, by at most [`FairShareConfig::weight`] priority points, so
+//! heavy users cannot starve everyone else while explicit priorities still
+//! dominate.
+//!
+//! Usage is the wall time from dispatch to completion of finished jobs. It
+//! is loaded from the state store on first use and updated as jobs finish.
+//!
+//! ```toml
+//! [scheduler.fair_share]
+//! group_by = "project"
+//! half_life_secs = 604800
+//!
+//! [scheduler.fair_share.shares]
+//! chemistry = 2
+//! materials = 1
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::{Priority, ScheduledJob};
+
+/// Metadata key naming a job's project.
+pub const PROJECT_METADATA_KEY: &str = "project";
+
+/// Account of jobs without an owner or project.
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// What jobs are grouped by when sharing the scheduler.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareGroup {
+    /// The job's owner.
+    #[default]
+    User,
+
+    /// The job's `project` metadata.
+    Project,
+}
+
+/// Configuration for fair-share scheduling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FairShareConfig {
+    /// What jobs are grouped by.
+    pub group_by: ShareGroup,
+
+    /// Relative share of each account.
+    pub shares: BTreeMap<String, u32>,
+
+    /// Share of accounts not listed in `shares`.
+    pub default_share: u32,
+
+    /// Time after which recorded usage counts half (seconds).
+    pub half_life_secs: u64,
+
+    /// Largest priority adjustment in either direction.
+    pub weight: u32,
+}
+
+impl Default for FairShareConfig {
+    fn default() -> Self {
+        Self {
+            group_by: ShareGroup::default(),
+            shares: BTreeMap::new(),
+            default_share: 1,
+            half_life_secs: 7 * 24 * 3600,
+            weight: 50,
+        }
+    }
+}
+
+impl FairShareConfig {
+    /// Set what jobs are grouped by.
+    pub fn with_group_by(mut self, group_by: ShareGroup) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// Set the share of an account.
+    pub fn with_share(mut self, account: impl Into<String>, share: u32) -> Self {
+        self.shares.insert(account.into(), share);
+        self
+    }
+
+    /// Set the usage half-life.
+    pub fn with_half_life(mut self, half_life: std::time::Duration) -> Self {
+        self.half_life_secs = half_life.as_secs().max(1);
+        self
+    }
+
+    /// Set the largest priority adjustment.
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Decayed usage of one account.
+#[derive(Debug, Clone, Copy)]
+struct Usage {
+    seconds: f64,
+    at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct UsageTable {
+    loaded: bool,
+    accounts: rustc_hash::FxHashMap<String, Usage>,
+}
+
+/// Tracks usage per account and reweights priorities by fair share.
+#[derive(Debug)]
+pub struct FairSharePolicy {
+    config: FairShareConfig,
+    usage: Mutex<UsageTable>,
+}
+
+impl FairSharePolicy {
+    /// Create a policy with no recorded usage.
+    pub fn new(config: FairShareConfig) -> Self {
+        Self {
+            config,
+            usage: Mutex::new(UsageTable::default()),
+        }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &FairShareConfig {
+        &self.config
+    }
+
+    /// Account a job is charged to.
+    pub fn account(&self, job: &ScheduledJob) -> String {
+        let account = match self.config.group_by {
+            ShareGroup::User => job.owner.as_deref(),
+            ShareGroup::Project => job.metadata.get(PROJECT_METADATA_KEY).map(String::as_str),
+        };
+        account.unwrap_or(DEFAULT_ACCOUNT).to_string()
+    }
+
+    /// Configured share of an account.
+    pub fn share(&self, account: &str) -> u32 {
+        self.config
+            .shares
+            .get(account)
+            .copied()
+            .unwrap_or(self.config.default_share)
+    }
+
+    /// How far back finished jobs still carry noticeable usage.
+    pub fn horizon(&self) -> chrono::Duration {
+        chrono::Duration::seconds((self.config.half_life_secs * 8) as i64)
+    }
+
+    /// Check if usage history has been loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.lock().loaded
+    }
+
+    /// Load usage history from finished jobs, adding to usage recorded so
+    /// far.
+    pub fn load<'a>(&self, jobs: impl IntoIterator<Item = &'a ScheduledJob>, now: DateTime<Utc>) {
+        for job in jobs {
+            self.record_job(job, now);
+        }
+        self.lock().loaded = true;
+    }
+
+    /// Charge a finished job's wall time from dispatch to completion to its
+    /// account.
+    pub fn record_job(&self, job: &ScheduledJob, now: DateTime<Utc>) {
+        let Some(submitted) = job.submitted_at else {
+            return;
+        };
+        let completed = job.completed_at.unwrap_or(now);
+        let seconds = (completed - submitted).num_milliseconds().max(0) as f64 / 1_000.0;
+        self.record(&self.account(job), seconds, completed);
+    }
+
+    /// Charge `seconds` of usage at time `at` to an account.
+    pub fn record(&self, account: &str, seconds: f64, at: DateTime<Utc>) {
+        let mut table = self.lock();
+        let usage = table
+            .accounts
+            .entry(account.to_string())
+            .or_insert(Usage { seconds: 0.0, at });
+        // Keep the later of both times and decay the other amount to it.
+        if at >= usage.at {
+            usage.seconds = usage.seconds * self.decay(at - usage.at) + seconds;
+            usage.at = at;
+        } else {
+            usage.seconds += seconds * self.decay(usage.at - at);
+        }
+    }
+
+    /// Decayed usage of an account at `now`, in seconds.
+    pub fn usage(&self, account: &str, now: DateTime<Utc>) -> f64 {
+        self.lock()
+            .accounts
+            .get(account)
+            .map_or(0.0, |usage| self.decayed(usage, now))
+    }
+
+    /// Fair-share factor of each account, between 0 and 1.
+    ///
+    /// An account without usage has factor 1, one whose share of the usage
+    /// equals its share of the scheduler has factor 0.5, and the factor
+    /// halves for each further multiple. Shares are normalized over the
+    /// `active` accounts and those with recorded usage.
+    pub fn factors(
+        &self,
+        active: impl IntoIterator<Item = String>,
+        now: DateTime<Utc>,
+    ) -> BTreeMap<String, f64> {
+        let table = self.lock();
+        let accounts: BTreeSet<String> = active
+            .into_iter()
+            .chain(table.accounts.keys().cloned())
+            .collect();
+        let usage: BTreeMap<&str, f64> = accounts
+            .iter()
+            .map(|account| {
+                let used = table
+                    .accounts
+                    .get(account)
+                    .map_or(0.0, |usage| self.decayed(usage, now));
+                (account.as_str(), used)
+            })
+            .collect();
+        let total_usage: f64 = usage.values().sum();
+        let total_share: f64 = accounts.iter().map(|a| f64::from(self.share(a))).sum();
+
+        accounts
+            .iter()
+            .map(|account| {
+                let share = f64::from(self.share(account));
+                let factor = if total_usage <= 0.0 {
+                    1.0
+                } else if share <= 0.0 {
+                    0.0
+                } else {
+                    let used = usage[account.as_str()] / total_usage;
+                    2f64.powf(-used / (share / total_share))
+                };
+                (account.clone(), factor)
+            })
+            .collect()
+    }
+
+    /// Adjust a priority by a fair-share factor: factor 1 adds the weight,
+    /// 0.5 leaves it unchanged and 0 subtracts the weight.
+    pub fn adjust(&self, priority: Priority, factor: f64) -> Priority {
+        let delta = (f64::from(self.config.weight) * (2.0 * factor - 1.0)).round() as i64;
+        let value = (i64::from(priority.value()) + delta).clamp(0, i64::from(u32::MAX));
+        Priority::new(value as u32)
+    }
+
+    fn decay(&self, elapsed: chrono::Duration) -> f64 {
+        let secs = elapsed.num_milliseconds().max(0) as f64 / 1_000.0;
+        0.5f64.powf(secs / self.config.half_life_secs.max(1) as f64)
+    }
+
+    fn decayed(&self, usage: &Usage, now: DateTime<Utc>) -> f64 {
+        usage.seconds * self.decay(now - usage.at)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, UsageTable> {
+        self.usage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    fn finished(owner: &str, secs: i64, now: DateTime<Utc>) -> ScheduledJob {
+        let mut job =
+            ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;")).with_owner(owner);
+        job.submitted_at = Some(now - chrono::Duration::seconds(secs));
+        job.completed_at = Some(now);
+        job
+    }
+
+    #[test]
+    fn test_usage_decays_with_half_life() {
+        let policy = FairSharePolicy::new(
+            FairShareConfig::default().with_half_life(std::time::Duration::from_secs(3_600)),
+        );
+        let now = Utc::now();
+        policy.record("alice", 100.0, now - chrono::Duration::hours(1));
+        assert!((policy.usage("alice", now) - 50.0).abs() < 1e-6);
+
+        // Older usage recorded later decays the same way.
+        policy.record("alice", 100.0, now - chrono::Duration::hours(2));
+        assert!((policy.usage("alice", now) - 75.0).abs() < 1e-6);
+        assert_eq!(policy.usage("bob", now), 0.0);
+    }
+
+    #[test]
+    fn test_factors_favor_light_accounts() {
+        let policy = FairSharePolicy::new(FairShareConfig::default().with_share("carol", 3));
+        let now = Utc::now();
+        policy.load(
+            [&finished("alice", 900, now), &finished("carol", 300, now)],
+            now,
+        );
+        assert!(policy.is_loaded());
+
+        let factors = policy.factors(["bob".to_string()], now);
+        assert_eq!(factors["bob"], 1.0);
+        assert!(factors["alice"] < 0.5);
+        assert!(factors["carol"] > 0.5);
+
+        assert_eq!(
+            policy.adjust(Priority::DEFAULT, factors["bob"]),
+            Priority::new(150)
+        );
+        assert_eq!(policy.adjust(Priority::DEFAULT, 0.5), Priority::DEFAULT);
+        assert_eq!(policy.adjust(Priority::new(10), 0.0), Priority::new(0));
+    }
+
+    #[test]
+    fn test_account_grouping() {
+        let job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .with_owner("alice")
+            .with_metadata(PROJECT_METADATA_KEY, "chemistry");
+        let by_user = FairSharePolicy::new(FairShareConfig::default());
+        let by_project =
+            FairSharePolicy::new(FairShareConfig::default().with_group_by(ShareGroup::Project));
+        assert_eq!(by_user.account(&job), "alice");
+        assert_eq!(by_project.account(&job), "chemistry");
+
+        let anonymous = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        assert_eq!(by_project.account(&anonymous), DEFAULT_ACCOUNT);
+    }
+}
@@ -0,0 +1,432 @@
+//! Lifecycle management for batch job artifacts.
+//!
+//! Adapters write scripts, circuits, results and logs into their work
+//! directory, named after the scheduler job ID or the batch job ID. This
+//! module ties those files back to job records so they can be removed once a
+//! job expires under a [`RetentionPolicy`], or when its record is archived.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::error::SchedResult;
+use crate::id::ULID_LEN;
+use crate::job::{JobFilter, ScheduledJob, ScheduledJobId, ScheduledJobStatus};
+use crate::pbs::PbsConfig;
+use crate::persistence::StateStore;
+use crate::slurm::SlurmConfig;
+
+/// Work directory subdirectories holding per-job files named by job ID.
+pub const ARTIFACT_DIRS: [&str; 4] = ["scripts", "circuits", "results", "tasks"];
+
+/// Layout of the files a batch adapter generates for its jobs.
+pub trait JobArtifacts: Send + Sync {
+    /// Work directory holding job artifacts.
+    fn work_dir(&self) -> &Path;
+
+    /// Extract the batch job ID from a log file name, if it is a job log.
+    fn log_job_id<'a>(&self, file_name: &'a str) -> Option<&'a str>;
+}
+
+impl JobArtifacts for SlurmConfig {
+    fn work_dir(&self) -> &Path {
+        &self.work_dir
+    }
+
+    fn log_job_id<'a>(&self, file_name: &'a str) -> Option<&'a str> {
+        log_id(file_name, "slurm-")
+    }
+}
+
+impl JobArtifacts for PbsConfig {
+    fn work_dir(&self) -> &Path {
+        &self.work_dir
+    }
+
+    fn log_job_id<'a>(&self, file_name: &'a str) -> Option<&'a str> {
+        log_id(file_name, "pbs-")
+    }
+}
+
+fn log_id<'a>(file_name: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = file_name.strip_prefix(prefix)?;
+    rest.strip_suffix(".out")
+        .or_else(|| rest.strip_suffix(".err"))
+}
+
+/// Retention policy for job artifacts.
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// Keep artifacts of successful jobs for this many days.
+    pub keep_completed_days: u32,
+
+    /// Keep artifacts of failed or cancelled jobs for this many days.
+    pub keep_failed_days: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_completed_days: 7,
+            keep_failed_days: 30,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Keep all artifacts for the given number of days.
+    pub fn days(days: u32) -> Self {
+        Self {
+            keep_completed_days: days,
+            keep_failed_days: days,
+        }
+    }
+
+    /// Set the retention for failed or cancelled jobs.
+    pub fn with_failed_days(mut self, days: u32) -> Self {
+        self.keep_failed_days = days;
+        self
+    }
+
+    /// Check whether a job's artifacts have expired at the given time.
+    pub fn is_expired(&self, job: &ScheduledJob, now: DateTime<Utc>) -> bool {
+        if !job.status.is_terminal() {
+            return false;
+        }
+        let days = if job.status.is_success() {
+            self.keep_completed_days
+        } else {
+            self.keep_failed_days
+        };
+        let finished = job
+            .completed_at
+            .or(job.submitted_at)
+            .unwrap_or(job.created_at);
+        finished < now - chrono::Duration::days(i64::from(days))
+    }
+}
+
+/// Summary of a garbage collection run.
+#[derive(Debug, Clone, Default)]
+pub struct GcReport {
+    /// Number of expired jobs whose artifacts were removed.
+    pub jobs_cleaned: usize,
+
+    /// Number of files not belonging to any known job that were removed.
+    pub orphans_removed: usize,
+
+    /// Total number of files removed.
+    pub files_removed: usize,
+
+    /// Total bytes freed.
+    pub bytes_freed: u64,
+
+    /// Whether this was a dry run (nothing actually removed).
+    pub dry_run: bool,
+}
+
+/// Index of the artifacts in a work directory.
+#[derive(Debug, Default)]
+pub struct ArtifactIndex {
+    by_job: rustc_hash::FxHashMap<ScheduledJobId, Vec<PathBuf>>,
+    by_batch_id: rustc_hash::FxHashMap<String, Vec<PathBuf>>,
+}
+
+impl ArtifactIndex {
+    /// Scan a work directory.
+    pub async fn scan(artifacts: &dyn JobArtifacts) -> SchedResult<Self> {
+        let mut index = Self::default();
+        let work_dir = artifacts.work_dir();
+
+        for dir in ARTIFACT_DIRS {
+            for (name, path) in list_dir(&work_dir.join(dir)).await? {
+                // Files are named `{job_id}.ext`, `{job_id}_{n}.ext` or `{job_id}`,
+                // with a ULID or, for older jobs, a UUID as the ID.
+                let Some(id) = [ULID_LEN, 36]
+                    .into_iter()
+                    .find_map(|len| ScheduledJobId::parse(name.get(..len)?).ok())
+                else {
+                    continue;
+                };
+                index.by_job.entry(id).or_default().push(path);
+            }
+        }
+
+        for (name, path) in list_dir(work_dir).await? {
+            if let Some(batch_id) = artifacts.log_job_id(&name) {
+                index
+                    .by_batch_id
+                    .entry(batch_id.to_string())
+                    .or_default()
+                    .push(path);
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Remove and return the artifacts belonging to a job.
+    pub fn take_job(&mut self, job: &ScheduledJob) -> Vec<PathBuf> {
+        let mut paths = self.by_job.remove(&job.id).unwrap_or_default();
+        if let Some(batch_id) = batch_job_id(&job.status) {
+            paths.extend(self.by_batch_id.remove(batch_id).unwrap_or_default());
+        }
+        paths
+    }
+
+    /// Remove and return all artifacts not claimed by the given jobs.
+    pub fn take_unclaimed(&mut self, jobs: &[ScheduledJob]) -> Vec<PathBuf> {
+        let job_ids: rustc_hash::FxHashSet<_> = jobs.iter().map(|j| &j.id).collect();
+        let batch_ids: rustc_hash::FxHashSet<_> = jobs
+            .iter()
+            .filter_map(|j| batch_job_id(&j.status))
+            .collect();
+
+        let mut paths = Vec::new();
+        self.by_job.retain(|id, files| {
+            let claimed = job_ids.contains(id);
+            if !claimed {
+                paths.append(files);
+            }
+            claimed
+        });
+        self.by_batch_id.retain(|id, files| {
+            let claimed = batch_ids.contains(id.as_str());
+            if !claimed {
+                paths.append(files);
+            }
+            claimed
+        });
+        paths
+    }
+}
+
+fn batch_job_id(status: &ScheduledJobStatus) -> Option<&str> {
+    status
+        .slurm_job_id()
+        .filter(|id| *id != crate::task::LOCAL_TASK_ID && *id != crate::task::SUB_WORKFLOW_JOB_ID)
+}
+
+async fn list_dir(dir: &Path) -> SchedResult<Vec<(String, PathBuf)>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut out = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if let Some(name) = entry.file_name().to_str() {
+            out.push((name.to_string(), entry.path()));
+        }
+    }
+    Ok(out)
+}
+
+/// Remove files or directories, returning the number of files and bytes.
+///
+/// Missing paths are ignored. With `dry_run`, only sizes are counted.
+pub async fn remove_artifacts(paths: &[PathBuf], dry_run: bool) -> SchedResult<(usize, u64)> {
+    let mut files = 0;
+    let mut bytes = 0;
+
+    let mut stack: Vec<PathBuf> = paths.to_vec();
+    let mut dirs = Vec::new();
+    while let Some(path) = stack.pop() {
+        let meta = match tokio::fs::symlink_metadata(&path).await {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if meta.is_dir() {
+            stack.extend(list_dir(&path).await?.into_iter().map(|(_, p)| p));
+            dirs.push(path);
+        } else {
+            files += 1;
+            bytes += meta.len();
+            if !dry_run {
+                tokio::fs::remove_file(&path).await?;
+            }
+        }
+    }
+
+    if !dry_run {
+        // Children were pushed after their parents, so remove in reverse.
+        for dir in dirs.into_iter().rev() {
+            tokio::fs::remove_dir(&dir).await?;
+        }
+    }
+
+    Ok((files, bytes))
+}
+
+/// Remove artifacts of expired jobs and orphaned files.
+///
+/// Orphans (files whose job is unknown to the store) are removed once older
+/// than the failed-job retention, the longer of the two windows by default.
+pub async fn collect_garbage(
+    store: &dyn StateStore,
+    artifacts: &dyn JobArtifacts,
+    policy: &RetentionPolicy,
+    dry_run: bool,
+) -> SchedResult<GcReport> {
+    let now = Utc::now();
+    let mut index = ArtifactIndex::scan(artifacts).await?;
+    let jobs = store.list_jobs(&JobFilter::default()).await?;
+    let mut report = GcReport {
+        dry_run,
+        ..Default::default()
+    };
+
+    for job in &jobs {
+        if !policy.is_expired(job, now) {
+            continue;
+        }
+        let paths = index.take_job(job);
+        if paths.is_empty() {
+            continue;
+        }
+        let (files, bytes) = remove_artifacts(&paths, dry_run).await?;
+        report.jobs_cleaned += 1;
+        report.files_removed += files;
+        report.bytes_freed += bytes;
+    }
+
+    let orphan_cutoff = std::time::SystemTime::now()
+        - std::time::Duration::from_secs(u64::from(policy.keep_failed_days) * 86_400);
+    for path in index.take_unclaimed(&jobs) {
+        let modified = tokio::fs::symlink_metadata(&path)
+            .await
+            .ok()
+            .and_then(|m| m.modified().ok());
+        if modified.is_none_or(|t| t >= orphan_cutoff) {
+            continue;
+        }
+        let (files, bytes) = remove_artifacts(std::slice::from_ref(&path), dry_run).await?;
+        report.orphans_removed += 1;
+        report.files_removed += files;
+        report.bytes_freed += bytes;
+    }
+
+    tracing::info!(
+        "Artifact GC{}: {} job(s), {} orphan(s), {} file(s), {} bytes",
+        if dry_run { " (dry run)" } else { "" },
+        report.jobs_cleaned,
+        report.orphans_removed,
+        report.files_removed,
+        report.bytes_freed
+    );
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+    use crate::persistence::SqliteStore;
+    use crate::retry::FailureKind;
+
+    fn finished_job(days_ago: i64, batch_id: &str) -> ScheduledJob {
+        let mut job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q;"));
+        job.status = ScheduledJobStatus::Completed {
+            slurm_job_id: batch_id.to_string(),
+            quantum_job_id: arvak_hal::JobId("q".to_string()),
+        };
+        job.completed_at = Some(Utc::now() - chrono::Duration::days(days_ago));
+        job
+    }
+
+    async fn touch(path: PathBuf) {
+        tokio::fs::create_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+        tokio::fs::write(&path, b"data").await.unwrap();
+    }
+
+    #[test]
+    fn test_retention_policy() {
+        let policy = RetentionPolicy::days(7).with_failed_days(30);
+        let now = Utc::now();
+
+        assert!(policy.is_expired(&finished_job(10, "1"), now));
+        assert!(!policy.is_expired(&finished_job(3, "1"), now));
+
+        let mut failed = finished_job(10, "1");
+        failed.status = ScheduledJobStatus::Failed {
+            reason: "boom".to_string(),
+            kind: FailureKind::Application,
+            slurm_job_id: Some("1".to_string()),
+            quantum_job_id: None,
+        };
+        assert!(!policy.is_expired(&failed, now));
+    }
+
+    #[test]
+    fn test_log_job_id() {
+        let slurm = SlurmConfig::default();
+        assert_eq!(slurm.log_job_id("slurm-1234.out"), Some("1234"));
+        assert_eq!(slurm.log_job_id("slurm-1234.err"), Some("1234"));
+        assert_eq!(slurm.log_job_id("pbs-1234.out"), None);
+        assert_eq!(PbsConfig::default().log_job_id("pbs-9.err"), Some("9"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SlurmConfig {
+            work_dir: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        let store = SqliteStore::in_memory().unwrap();
+
+        let old = finished_job(10, "100");
+        let recent = finished_job(1, "200");
+        store.save_job(&old).await.unwrap();
+        store.save_job(&recent).await.unwrap();
+
+        let work = dir.path();
+        touch(work.join("scripts").join(format!("{}.sh", old.id))).await;
+        touch(work.join("circuits").join(format!("{}_0.qasm", old.id))).await;
+        touch(
+            work.join("results")
+                .join(old.id.to_string())
+                .join("result_0.json"),
+        )
+        .await;
+        touch(work.join("slurm-100.out")).await;
+        touch(work.join("scripts").join(format!("{}.sh", recent.id))).await;
+        touch(work.join("slurm-200.out")).await;
+        // Unknown job, but too recent to count as an orphan
+        touch(
+            work.join("scripts")
+                .join(format!("{}.sh", ScheduledJobId::new())),
+        )
+        .await;
+
+        let policy = RetentionPolicy::days(7);
+        let dry = collect_garbage(&store, &config, &policy, true)
+            .await
+            .unwrap();
+        assert_eq!(dry.jobs_cleaned, 1);
+        assert_eq!(dry.files_removed, 4);
+        assert!(work.join("slurm-100.out").exists());
+
+        let report = collect_garbage(&store, &config, &policy, false)
+            .await
+            .unwrap();
+        assert_eq!(report.jobs_cleaned, 1);
+        assert_eq!(report.orphans_removed, 0);
+        assert_eq!(report.files_removed, 4);
+        assert_eq!(report.bytes_freed, 16);
+
+        assert!(!work.join("slurm-100.out").exists());
+        assert!(!work.join("results").join(old.id.to_string()).exists());
+        assert!(work.join("slurm-200.out").exists());
+        assert!(
+            work.join("scripts")
+                .join(format!("{}.sh", recent.id))
+                .exists()
+        );
+    }
+}
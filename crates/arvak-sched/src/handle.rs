@@ -0,0 +1,356 @@
+//! Cloneable handle to a scheduler running as an actor.
+//!
+//! [`SchedulerHandle::spawn`] starts a task that owns the changes to an
+//! [`HpcScheduler`]: submissions, cancellations, workflows and
+//! reservations are sent to it over a channel and applied one at a time.
+//! Handles are cheap to clone and `Send + Sync`, so request handlers,
+//! such as the dashboard's, can each hold one without wrapping the
+//! scheduler in a lock.
+//!
+//! Submissions that arrive together are checked and saved one by one and
+//! then enter the queue under a single write lock, so concurrent
+//! submitters do not contend for it. Queries and waits do not change the
+//! scheduler and go to it directly, so a long wait never holds up the
+//! actor.
+
+use std::sync::Arc;
+
+use arvak_hal::ExecutionResult;
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{
+    CircuitSpec, JobFilter, Priority, ResourceRequirements, ScheduledJob, ScheduledJobId,
+    ScheduledJobStatus,
+};
+use crate::logs::LogTail;
+use crate::reservation::{Reservation, TimeWindow};
+use crate::scheduler::{HpcScheduler, Scheduler};
+use crate::workflow::{Workflow, WorkflowBuilder, WorkflowId, WorkflowStatus};
+
+/// Default number of requests waiting for the actor before senders wait.
+pub const DEFAULT_COMMAND_BUFFER: usize = 1024;
+
+/// Most submissions the actor takes from its channel at once.
+const MAX_SUBMIT_BATCH: usize = 64;
+
+type Reply<T> = oneshot::Sender<SchedResult<T>>;
+
+/// A change to the scheduler, applied by the actor.
+enum Command {
+    Submit {
+        job: Box<ScheduledJob>,
+        reply: Reply<ScheduledJobId>,
+    },
+    Cancel {
+        job_id: ScheduledJobId,
+        reply: Reply<()>,
+    },
+    SubmitWorkflow {
+        workflow: Box<Workflow>,
+        reply: Reply<WorkflowId>,
+    },
+    CancelWorkflow {
+        workflow_id: WorkflowId,
+        reply: Reply<Vec<ScheduledJobId>>,
+    },
+    ResumeWorkflow {
+        workflow_id: WorkflowId,
+        reply: Reply<Vec<ScheduledJobId>>,
+    },
+    Reserve {
+        backend: String,
+        window: TimeWindow,
+        capacity: u32,
+        reply: Reply<Reservation>,
+    },
+    ReleaseReservation {
+        name: String,
+        reply: Reply<Vec<ScheduledJobId>>,
+    },
+}
+
+/// Cloneable handle to an [`HpcScheduler`] driven by an actor task.
+///
+/// The actor stops once every handle is dropped.
+#[derive(Clone)]
+pub struct SchedulerHandle {
+    commands: mpsc::Sender<Command>,
+    scheduler: Arc<HpcScheduler>,
+}
+
+impl SchedulerHandle {
+    /// Start an actor applying changes to `scheduler` and return a handle
+    /// to it.
+    ///
+    /// Must be called within a Tokio runtime. Once spawned, the scheduler
+    /// should only be reached through handles; its background processor is
+    /// started with [`start_background_processor`](Self::start_background_processor).
+    pub fn spawn(scheduler: Arc<HpcScheduler>) -> Self {
+        Self::spawn_with_buffer(scheduler, DEFAULT_COMMAND_BUFFER)
+    }
+
+    /// Like [`SchedulerHandle::spawn`], with room for `buffer` requests
+    /// waiting for the actor.
+    pub fn spawn_with_buffer(scheduler: Arc<HpcScheduler>, buffer: usize) -> Self {
+        let (commands, receiver) = mpsc::channel(buffer.max(1));
+        tokio::spawn(run(scheduler.clone(), receiver));
+        Self {
+            commands,
+            scheduler,
+        }
+    }
+
+    /// Start the scheduler's background processor, see
+    /// [`HpcScheduler::start_background_processor`].
+    pub fn start_background_processor(&self) -> JoinHandle<()> {
+        self.scheduler.clone().start_background_processor()
+    }
+
+    /// Tail the log files of a job; like other queries, this goes to the
+    /// scheduler directly.
+    pub async fn job_logs(&self, job_id: &ScheduledJobId) -> SchedResult<LogTail> {
+        self.scheduler.job_logs(job_id).await
+    }
+
+    /// Send a command built around a reply channel and wait for the reply.
+    async fn request<T>(&self, command: impl FnOnce(Reply<T>) -> Command) -> SchedResult<T> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(command(reply))
+            .await
+            .map_err(|_| stopped())?;
+        response.await.map_err(|_| stopped())?
+    }
+}
+
+fn stopped() -> SchedError {
+    SchedError::Internal("scheduler actor has stopped".to_string())
+}
+
+/// Apply commands until every handle is dropped.
+async fn run(scheduler: Arc<HpcScheduler>, mut receiver: mpsc::Receiver<Command>) {
+    let mut next = None;
+    loop {
+        let command = match next.take() {
+            Some(command) => command,
+            None => match receiver.recv().await {
+                Some(command) => command,
+                None => break,
+            },
+        };
+
+        let Command::Submit { job, reply } = command else {
+            apply(&scheduler, command).await;
+            continue;
+        };
+
+        // Take the submissions already waiting along with this one
+        let mut jobs = vec![*job];
+        let mut replies = vec![reply];
+        while jobs.len() < MAX_SUBMIT_BATCH {
+            match receiver.try_recv() {
+                Ok(Command::Submit { job, reply }) => {
+                    jobs.push(*job);
+                    replies.push(reply);
+                }
+                Ok(command) => {
+                    next = Some(command);
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+        for (reply, result) in replies.into_iter().zip(scheduler.submit_all(jobs).await) {
+            let _ = reply.send(result);
+        }
+    }
+    tracing::debug!("Scheduler actor stopped");
+}
+
+/// Apply one command on its own.
+async fn apply(scheduler: &HpcScheduler, command: Command) {
+    // Requesters that gave up waiting no longer need the reply
+    match command {
+        Command::Submit { job, reply } => {
+            let _ = reply.send(scheduler.submit(*job).await);
+        }
+        Command::Cancel { job_id, reply } => {
+            let _ = reply.send(scheduler.cancel(&job_id).await);
+        }
+        Command::SubmitWorkflow { workflow, reply } => {
+            let _ = reply.send(scheduler.submit_workflow(*workflow).await);
+        }
+        Command::CancelWorkflow { workflow_id, reply } => {
+            let _ = reply.send(scheduler.cancel_workflow(&workflow_id).await);
+        }
+        Command::ResumeWorkflow { workflow_id, reply } => {
+            let _ = reply.send(scheduler.resume_workflow(&workflow_id).await);
+        }
+        Command::Reserve {
+            backend,
+            window,
+            capacity,
+            reply,
+        } => {
+            let _ = reply.send(scheduler.reserve(&backend, window, capacity).await);
+        }
+        Command::ReleaseReservation { name, reply } => {
+            let _ = reply.send(scheduler.release_reservation(&name).await);
+        }
+    }
+}
+
+#[async_trait]
+impl Scheduler for SchedulerHandle {
+    async fn submit(&self, job: ScheduledJob) -> SchedResult<ScheduledJobId> {
+        self.request(|reply| Command::Submit {
+            job: Box::new(job),
+            reply,
+        })
+        .await
+    }
+
+    async fn submit_batch(
+        &self,
+        name: &str,
+        circuits: Vec<CircuitSpec>,
+        shots: u32,
+        priority: Priority,
+        requirements: ResourceRequirements,
+    ) -> SchedResult<ScheduledJobId> {
+        let job = ScheduledJob::batch(name, circuits)
+            .with_shots(shots)
+            .with_priority(priority)
+            .with_requirements(requirements);
+
+        self.submit(job).await
+    }
+
+    async fn status(&self, job_id: &ScheduledJobId) -> SchedResult<ScheduledJobStatus> {
+        self.scheduler.status(job_id).await
+    }
+
+    async fn cancel(&self, job_id: &ScheduledJobId) -> SchedResult<()> {
+        let job_id = job_id.clone();
+        self.request(|reply| Command::Cancel { job_id, reply })
+            .await
+    }
+
+    async fn wait(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
+        self.scheduler.wait(job_id).await
+    }
+
+    async fn result(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
+        self.scheduler.result(job_id).await
+    }
+
+    async fn list_jobs(&self, filter: JobFilter) -> SchedResult<Vec<ScheduledJob>> {
+        self.scheduler.list_jobs(filter).await
+    }
+
+    fn create_workflow(&self, name: &str) -> WorkflowBuilder {
+        self.scheduler.create_workflow(name)
+    }
+
+    async fn submit_workflow(&self, workflow: Workflow) -> SchedResult<WorkflowId> {
+        self.request(|reply| Command::SubmitWorkflow {
+            workflow: Box::new(workflow),
+            reply,
+        })
+        .await
+    }
+
+    async fn workflow_status(&self, workflow_id: &WorkflowId) -> SchedResult<WorkflowStatus> {
+        self.scheduler.workflow_status(workflow_id).await
+    }
+
+    async fn wait_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<()> {
+        self.scheduler.wait_workflow(workflow_id).await
+    }
+
+    async fn cancel_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Vec<ScheduledJobId>> {
+        let workflow_id = workflow_id.clone();
+        self.request(|reply| Command::CancelWorkflow { workflow_id, reply })
+            .await
+    }
+
+    async fn resume_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Vec<ScheduledJobId>> {
+        let workflow_id = workflow_id.clone();
+        self.request(|reply| Command::ResumeWorkflow { workflow_id, reply })
+            .await
+    }
+
+    async fn reserve(
+        &self,
+        backend: &str,
+        window: TimeWindow,
+        capacity: u32,
+    ) -> SchedResult<Reservation> {
+        let backend = backend.to_string();
+        self.request(|reply| Command::Reserve {
+            backend,
+            window,
+            capacity,
+            reply,
+        })
+        .await
+    }
+
+    async fn release_reservation(&self, name: &str) -> SchedResult<Vec<ScheduledJobId>> {
+        let name = name.to_string();
+        self.request(|reply| Command::ReleaseReservation { name, reply })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::SqliteStore;
+    use crate::scheduler::SchedulerConfig;
+
+    #[tokio::test]
+    async fn test_handles_submit_concurrently() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store);
+        let handle = SchedulerHandle::spawn(Arc::new(scheduler));
+
+        let tasks: Vec<_> = (0..32)
+            .map(|i| {
+                let handle = handle.clone();
+                tokio::spawn(async move {
+                    let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+                    handle
+                        .submit(ScheduledJob::new(format!("job-{}", i), circuit))
+                        .await
+                })
+            })
+            .collect();
+        let mut ids = Vec::new();
+        for task in tasks {
+            ids.push(task.await.unwrap().unwrap());
+        }
+        ids.sort_by_key(ToString::to_string);
+        ids.dedup();
+        assert_eq!(ids.len(), 32);
+        assert_eq!(
+            handle.list_jobs(JobFilter::pending()).await.unwrap().len(),
+            32
+        );
+
+        // Rejected jobs fail alone
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let invalid = ScheduledJob::new("no-shots", circuit).with_shots(0);
+        assert!(handle.submit(invalid).await.is_err());
+
+        handle.cancel(&ids[0]).await.unwrap();
+        assert!(matches!(
+            handle.status(&ids[0]).await.unwrap(),
+            ScheduledJobStatus::Cancelled
+        ));
+    }
+}
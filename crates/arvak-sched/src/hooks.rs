@@ -0,0 +1,256 @@
+//! Site hooks run at job lifecycle points.
+//!
+//! A [`SchedulerHook`] lets a site inject its own logic into the scheduler,
+//! e.g. stamping accounting codes onto jobs before they are stored or
+//! scanning payloads and results. Hooks are registered on the scheduler with
+//! a [`HookErrorPolicy`] deciding what a failing hook does:
+//!
+//! | Point | [`HookErrorPolicy::Abort`] | [`HookErrorPolicy::Warn`] |
+//! |-------|----------------------------|---------------------------|
+//! | pre-submit | submission is rejected | logged, submission continues |
+//! | post-complete | job is marked failed | logged |
+//! | on-failure | remaining hooks are skipped | logged |
+//!
+//! Hooks run in registration order.
+
+use std::sync::{Arc, RwLock};
+
+use arvak_hal::ExecutionResult;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::ScheduledJob;
+
+/// Lifecycle point at which hooks run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookPoint {
+    /// Before a job is stored and queued.
+    PreSubmit,
+
+    /// After a job completed successfully.
+    PostComplete,
+
+    /// After a job failed.
+    OnFailure,
+}
+
+impl std::fmt::Display for HookPoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HookPoint::PreSubmit => write!(f, "pre-submit"),
+            HookPoint::PostComplete => write!(f, "post-complete"),
+            HookPoint::OnFailure => write!(f, "on-failure"),
+        }
+    }
+}
+
+/// What a failing hook does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookErrorPolicy {
+    /// Reject the job at this point.
+    #[default]
+    Abort,
+
+    /// Log the error and carry on.
+    Warn,
+}
+
+/// Site logic run at job lifecycle points.
+///
+/// Every method defaults to doing nothing, so a hook implements only the
+/// points it cares about.
+#[async_trait]
+pub trait SchedulerHook: Send + Sync {
+    /// Name used in logs and errors.
+    fn name(&self) -> &str;
+
+    /// Inspect or amend a job before it is stored and queued.
+    async fn pre_submit(&self, _job: &mut ScheduledJob) -> SchedResult<()> {
+        Ok(())
+    }
+
+    /// Inspect a job that completed successfully, with its stored result.
+    async fn post_complete(
+        &self,
+        _job: &ScheduledJob,
+        _result: Option<&ExecutionResult>,
+    ) -> SchedResult<()> {
+        Ok(())
+    }
+
+    /// React to a job that failed.
+    async fn on_failure(&self, _job: &ScheduledJob, _reason: &str) -> SchedResult<()> {
+        Ok(())
+    }
+}
+
+/// Ordered list of hooks with their error policies.
+#[derive(Default)]
+pub struct HookRegistry {
+    hooks: RwLock<Vec<(Arc<dyn SchedulerHook>, HookErrorPolicy)>>,
+}
+
+impl HookRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a hook to run after the already registered ones.
+    pub fn register(&self, hook: Arc<dyn SchedulerHook>, policy: HookErrorPolicy) {
+        self.hooks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((hook, policy));
+    }
+
+    /// Names of the registered hooks, in run order.
+    pub fn names(&self) -> Vec<String> {
+        self.snapshot()
+            .iter()
+            .map(|(hook, _)| hook.name().to_string())
+            .collect()
+    }
+
+    /// Check whether no hooks are registered.
+    pub fn is_empty(&self) -> bool {
+        self.hooks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    fn snapshot(&self) -> Vec<(Arc<dyn SchedulerHook>, HookErrorPolicy)> {
+        self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Apply a hook's error policy; returns the error if it aborts.
+    fn handle(
+        hook: &dyn SchedulerHook,
+        policy: HookErrorPolicy,
+        point: HookPoint,
+        job: &ScheduledJob,
+        result: SchedResult<()>,
+    ) -> SchedResult<()> {
+        let Err(e) = result else {
+            return Ok(());
+        };
+        match policy {
+            HookErrorPolicy::Abort => Err(SchedError::HookFailed {
+                hook: hook.name().to_string(),
+                point,
+                message: e.to_string(),
+            }),
+            HookErrorPolicy::Warn => {
+                tracing::warn!(
+                    "{} hook {} failed for job {}: {}",
+                    point,
+                    hook.name(),
+                    job.id,
+                    e
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Run the pre-submit hooks; an aborting hook rejects the job.
+    pub async fn run_pre_submit(&self, job: &mut ScheduledJob) -> SchedResult<()> {
+        for (hook, policy) in self.snapshot() {
+            let result = hook.pre_submit(job).await;
+            Self::handle(hook.as_ref(), policy, HookPoint::PreSubmit, job, result)?;
+        }
+        Ok(())
+    }
+
+    /// Run the post-complete hooks; an aborting hook stops the rest and its
+    /// error is returned.
+    pub async fn run_post_complete(
+        &self,
+        job: &ScheduledJob,
+        result: Option<&ExecutionResult>,
+    ) -> SchedResult<()> {
+        for (hook, policy) in self.snapshot() {
+            let outcome = hook.post_complete(job, result).await;
+            Self::handle(hook.as_ref(), policy, HookPoint::PostComplete, job, outcome)?;
+        }
+        Ok(())
+    }
+
+    /// Run the on-failure hooks; an aborting hook stops the rest and its
+    /// error is returned.
+    pub async fn run_on_failure(&self, job: &ScheduledJob, reason: &str) -> SchedResult<()> {
+        for (hook, policy) in self.snapshot() {
+            let result = hook.on_failure(job, reason).await;
+            Self::handle(hook.as_ref(), policy, HookPoint::OnFailure, job, result)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    struct Stamp;
+
+    #[async_trait]
+    impl SchedulerHook for Stamp {
+        fn name(&self) -> &str {
+            "stamp"
+        }
+
+        async fn pre_submit(&self, job: &mut ScheduledJob) -> SchedResult<()> {
+            job.metadata
+                .insert("account".to_string(), "proj-1".to_string());
+            Ok(())
+        }
+    }
+
+    struct Reject;
+
+    #[async_trait]
+    impl SchedulerHook for Reject {
+        fn name(&self) -> &str {
+            "reject"
+        }
+
+        async fn pre_submit(&self, _job: &mut ScheduledJob) -> SchedResult<()> {
+            Err(SchedError::Internal("payload flagged".to_string()))
+        }
+    }
+
+    fn make_job() -> ScheduledJob {
+        ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q;"))
+    }
+
+    #[tokio::test]
+    async fn test_hook_policies() {
+        let registry = HookRegistry::new();
+        assert!(registry.is_empty());
+        registry.register(Arc::new(Reject), HookErrorPolicy::Warn);
+        registry.register(Arc::new(Stamp), HookErrorPolicy::Abort);
+        assert_eq!(registry.names(), vec!["reject", "stamp"]);
+
+        // A warning hook does not stop later hooks.
+        let mut job = make_job();
+        registry.run_pre_submit(&mut job).await.unwrap();
+        assert_eq!(job.metadata.get("account").unwrap(), "proj-1");
+
+        registry.register(Arc::new(Reject), HookErrorPolicy::Abort);
+        let err = registry.run_pre_submit(&mut make_job()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            SchedError::HookFailed { ref hook, point: HookPoint::PreSubmit, .. } if hook == "reject"
+        ));
+        assert!(err.to_string().contains("payload flagged"), "{}", err);
+
+        // Hooks not implementing a point pass through.
+        registry.run_post_complete(&job, None).await.unwrap();
+        registry.run_on_failure(&job, "lost").await.unwrap();
+    }
+}
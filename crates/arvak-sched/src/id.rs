@@ -0,0 +1,198 @@
+//! Time-ordered identifiers.
+//!
+//! Job and workflow IDs are [ULIDs](https://github.com/ulid/spec): a 48-bit
+//! millisecond timestamp followed by 80 random bits, written as 26
+//! Crockford base32 characters. They sort by creation time, and the time a
+//! record was created can be read off its ID.
+//!
+//! IDs from before ULIDs were UUIDs. Those still parse and deserialize, to
+//! the same 128 bits, so old records load; their timestamps are
+//! meaningless.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+use crate::job::ScheduledJobId;
+use crate::workflow::WorkflowId;
+
+/// What an ID resolved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedId {
+    /// A job, by its own ID or an external reference.
+    Job(ScheduledJobId),
+
+    /// A workflow.
+    Workflow(WorkflowId),
+}
+
+/// Crockford base32 alphabet.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Length of an encoded ULID.
+pub const ULID_LEN: usize = 26;
+
+/// Bits of randomness after the timestamp.
+const RANDOM_BITS: u32 = 80;
+
+/// Error returned when a string is not a ULID or UUID.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid ID '{0}': expected a ULID or UUID")]
+pub struct ParseIdError(String);
+
+/// A 128-bit ULID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ulid(u128);
+
+impl Ulid {
+    /// Create a ULID for the current time.
+    pub fn new() -> Self {
+        Self::from_parts(Utc::now(), Uuid::new_v4().as_u128())
+    }
+
+    /// Create a ULID from a timestamp and random bits; only the low 80
+    /// random bits are used.
+    pub fn from_parts(timestamp: DateTime<Utc>, random: u128) -> Self {
+        let millis = u128::from(timestamp.timestamp_millis().max(0) as u64) & ((1 << 48) - 1);
+        Self((millis << RANDOM_BITS) | (random & ((1 << RANDOM_BITS) - 1)))
+    }
+
+    /// Time the ULID was created, to the millisecond.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        let millis = (self.0 >> RANDOM_BITS) as i64;
+        Utc.timestamp_millis_opt(millis)
+            .single()
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+
+    /// Get the ULID as a 128-bit integer.
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Parse a ULID, or a UUID from before ULIDs.
+    pub fn parse(s: &str) -> Result<Self, ParseIdError> {
+        if s.len() == ULID_LEN {
+            return decode(s).ok_or_else(|| ParseIdError(s.to_string()));
+        }
+        Uuid::parse_str(s)
+            .map(Self::from)
+            .map_err(|_| ParseIdError(s.to_string()))
+    }
+}
+
+impl Default for Ulid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<Uuid> for Ulid {
+    fn from(uuid: Uuid) -> Self {
+        Self(uuid.as_u128())
+    }
+}
+
+impl std::str::FromStr for Ulid {
+    type Err = ParseIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for Ulid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = [0u8; ULID_LEN];
+        for (i, c) in out.iter_mut().enumerate() {
+            let shift = 5 * (ULID_LEN - 1 - i);
+            *c = ALPHABET[((self.0 >> shift) & 0x1f) as usize];
+        }
+        f.write_str(std::str::from_utf8(&out).expect("alphabet is ASCII"))
+    }
+}
+
+/// Decode 26 Crockford base32 characters, case-insensitively.
+fn decode(s: &str) -> Option<Ulid> {
+    let mut value: u128 = 0;
+    for (i, b) in s.bytes().enumerate() {
+        let digit = ALPHABET.iter().position(|&a| a == b.to_ascii_uppercase())? as u128;
+        // The first character carries only the top 3 of 128 bits.
+        if i == 0 && digit > 7 {
+            return None;
+        }
+        value = (value << 5) | digit;
+    }
+    Some(Ulid(value))
+}
+
+impl Serialize for Ulid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ulid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UlidVisitor;
+
+        impl Visitor<'_> for UlidVisitor {
+            type Value = Ulid;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a ULID or UUID")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Ulid, E> {
+                Ulid::parse(v).map_err(E::custom)
+            }
+
+            // Binary formats stored UUIDs as their 16 bytes.
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Ulid, E> {
+                Uuid::from_slice(v).map(Ulid::from).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(UlidVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulid_round_trip_and_order() {
+        let earlier = Ulid::from_parts(Utc.timestamp_millis_opt(1_700_000_000_000).unwrap(), 42);
+        let later = Ulid::from_parts(Utc.timestamp_millis_opt(1_700_000_000_001).unwrap(), 0);
+        assert!(earlier < later);
+        assert!(earlier.to_string() < later.to_string());
+        assert_eq!(earlier.timestamp().timestamp_millis(), 1_700_000_000_000);
+
+        let text = earlier.to_string();
+        assert_eq!(text.len(), ULID_LEN);
+        assert_eq!(Ulid::parse(&text).unwrap(), earlier);
+        assert_eq!(Ulid::parse(&text.to_lowercase()).unwrap(), earlier);
+        assert!(Ulid::parse("8ZZZZZZZZZZZZZZZZZZZZZZZZZ").is_err());
+        assert!(Ulid::parse("not-an-id").is_err());
+    }
+
+    #[test]
+    fn test_legacy_uuids_parse() {
+        let uuid = Uuid::new_v4();
+        let ulid = Ulid::parse(&uuid.to_string()).unwrap();
+        assert_eq!(ulid.as_u128(), uuid.as_u128());
+
+        let from_json: Ulid = serde_json::from_str(&format!("\"{}\"", uuid)).unwrap();
+        assert_eq!(from_json, ulid);
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&uuid, &mut cbor).unwrap();
+        let from_cbor: Ulid = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(from_cbor, ulid);
+        assert_eq!(
+            serde_json::to_string(&ulid).unwrap(),
+            format!("\"{}\"", ulid)
+        );
+    }
+}
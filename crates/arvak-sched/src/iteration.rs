@@ -0,0 +1,105 @@
+//! Per-iteration records of variational runs.
+//!
+//! VQE and QAOA loops evaluate many parameter sets, usually through jobs
+//! submitted to the scheduler. Recording every iteration in the state store,
+//! keyed by the run's workflow or campaign id, keeps the convergence history
+//! available to the dashboard while the run progresses and for postmortems
+//! afterwards.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::ScheduledJobId;
+
+/// One iteration of a variational run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IterationRecord {
+    /// Workflow or campaign id the iteration belongs to.
+    pub run_id: String,
+
+    /// Iteration number within the run, starting at 0.
+    pub iteration: usize,
+
+    /// Parameters evaluated.
+    pub params: Vec<f64>,
+
+    /// Objective value, e.g. energy for VQE or expected cut for QAOA.
+    pub value: f64,
+
+    /// Variance of the objective estimate, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variance: Option<f64>,
+
+    /// Jobs that evaluated the iteration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub job_ids: Vec<ScheduledJobId>,
+
+    /// When the iteration was recorded.
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl IterationRecord {
+    /// Create a record for an iteration of a run.
+    pub fn new(run_id: impl Into<String>, iteration: usize, params: Vec<f64>, value: f64) -> Self {
+        Self {
+            run_id: run_id.into(),
+            iteration,
+            params,
+            value,
+            variance: None,
+            job_ids: Vec::new(),
+            recorded_at: Utc::now(),
+        }
+    }
+
+    /// Set the variance of the objective estimate.
+    pub fn with_variance(mut self, variance: f64) -> Self {
+        self.variance = Some(variance);
+        self
+    }
+
+    /// Set the jobs that evaluated the iteration.
+    pub fn with_job_ids(mut self, job_ids: Vec<ScheduledJobId>) -> Self {
+        self.job_ids = job_ids;
+        self
+    }
+
+    /// Standard error of the objective estimate, if the variance is known.
+    pub fn std_error(&self) -> Option<f64> {
+        self.variance.map(f64::sqrt)
+    }
+}
+
+/// Sort records by iteration, keeping only the latest record of each.
+pub(crate) fn latest_per_iteration(mut records: Vec<IterationRecord>) -> Vec<IterationRecord> {
+    records.sort_by_key(|r| r.iteration);
+    let mut latest: Vec<IterationRecord> = Vec::with_capacity(records.len());
+    for record in records {
+        match latest.last_mut() {
+            Some(last) if last.iteration == record.iteration => *last = record,
+            _ => latest.push(record),
+        }
+    }
+    latest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_per_iteration() {
+        let records = vec![
+            IterationRecord::new("run", 1, vec![0.1], -0.5),
+            IterationRecord::new("run", 0, vec![0.0], -0.2).with_variance(0.04),
+            IterationRecord::new("run", 1, vec![0.2], -0.7),
+        ];
+
+        let latest = latest_per_iteration(records);
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].iteration, 0);
+        assert_eq!(latest[0].std_error(), Some(0.2));
+        assert_eq!(latest[1].params, vec![0.2]);
+        assert_eq!(latest[1].std_error(), None);
+    }
+}
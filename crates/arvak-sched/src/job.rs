@@ -1,29 +1,46 @@
 //! Job types for the HPC scheduler.
 
+use std::collections::BTreeMap;
+
+use arvak_eval::scheduler_context::{SchedulerConstraints, SchedulerContext};
 use arvak_hal::JobId;
 use arvak_ir::Circuit;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-/// Unique identifier for a scheduled job.
+use crate::id::{ParseIdError, Ulid};
+use crate::negotiate::{CapabilityRequest, Negotiation};
+use crate::retry::{Attempt, FailureKind, RetryPolicy};
+use crate::split::SplitCircuit;
+use crate::task::ClassicalTask;
+use crate::validate::ResultValidation;
+use crate::verify::ResultVerification;
+use crate::workflow::WorkflowId;
+
+/// Unique identifier for a scheduled job, a [`Ulid`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct ScheduledJobId(pub Uuid);
+pub struct ScheduledJobId(pub Ulid);
 
 impl ScheduledJobId {
-    /// Create a new random job ID.
+    /// Create a new job ID for the current time.
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(Ulid::new())
     }
 
     /// Create a job ID from a UUID.
     pub fn from_uuid(uuid: Uuid) -> Self {
-        Self(uuid)
+        Self(uuid.into())
+    }
+
+    /// Parse a job ID from a ULID or UUID string.
+    pub fn parse(s: &str) -> Result<Self, ParseIdError> {
+        Ok(Self(Ulid::parse(s)?))
     }
 
-    /// Parse a job ID from a string.
-    pub fn parse(s: &str) -> Result<Self, uuid::Error> {
-        Ok(Self(Uuid::parse_str(s)?))
+    /// Time the ID was created.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.0.timestamp()
     }
 }
 
@@ -56,6 +73,10 @@ impl Priority {
     /// Critical priority (200).
     pub const CRITICAL: Priority = Priority(200);
 
+    /// Urgent priority (250); with preemption configured, urgent jobs that
+    /// cannot start may preempt running lower-priority jobs.
+    pub const URGENT: Priority = Priority(250);
+
     /// Create a new priority with the given value.
     pub fn new(value: u32) -> Self {
         Self(value)
@@ -81,6 +102,11 @@ impl Priority {
         Self::CRITICAL
     }
 
+    /// Create an urgent priority.
+    pub fn urgent() -> Self {
+        Self::URGENT
+    }
+
     /// Get the numeric value.
     pub fn value(&self) -> u32 {
         self.0
@@ -126,15 +152,26 @@ pub enum ScheduledJobStatus {
         quantum_job_id: JobId,
     },
 
+    /// Job was not run because an identical earlier job completed; its
+    /// result was copied from that job.
+    CachedComplete { source: ScheduledJobId },
+
     /// Job failed.
     Failed {
         reason: String,
+        /// How the job failed, which decides whether it is retried.
+        #[serde(default)]
+        kind: FailureKind,
         slurm_job_id: Option<String>,
         quantum_job_id: Option<JobId>,
     },
 
     /// Job was cancelled.
     Cancelled,
+
+    /// Job could not finish before its deadline, so it was not dispatched
+    /// or was cancelled when the deadline passed.
+    DeadlineExceeded { deadline: DateTime<Utc> },
 }
 
 impl ScheduledJobStatus {
@@ -143,8 +180,10 @@ impl ScheduledJobStatus {
         matches!(
             self,
             ScheduledJobStatus::Completed { .. }
+                | ScheduledJobStatus::CachedComplete { .. }
                 | ScheduledJobStatus::Failed { .. }
                 | ScheduledJobStatus::Cancelled
+                | ScheduledJobStatus::DeadlineExceeded { .. }
         )
     }
 
@@ -166,7 +205,10 @@ impl ScheduledJobStatus {
 
     /// Check if the job completed successfully.
     pub fn is_success(&self) -> bool {
-        matches!(self, ScheduledJobStatus::Completed { .. })
+        matches!(
+            self,
+            ScheduledJobStatus::Completed { .. } | ScheduledJobStatus::CachedComplete { .. }
+        )
     }
 
     /// Get a human-readable status name.
@@ -179,8 +221,10 @@ impl ScheduledJobStatus {
             ScheduledJobStatus::QuantumSubmitted { .. } => "QuantumSubmitted",
             ScheduledJobStatus::QuantumRunning { .. } => "QuantumRunning",
             ScheduledJobStatus::Completed { .. } => "Completed",
+            ScheduledJobStatus::CachedComplete { .. } => "CachedComplete",
             ScheduledJobStatus::Failed { .. } => "Failed",
             ScheduledJobStatus::Cancelled => "Cancelled",
+            ScheduledJobStatus::DeadlineExceeded { .. } => "DeadlineExceeded",
         }
     }
 
@@ -250,8 +294,14 @@ impl std::fmt::Display for ScheduledJobStatus {
                     slurm_job_id, quantum_job_id.0
                 )
             }
+            ScheduledJobStatus::CachedComplete { source } => {
+                write!(f, "Completed from cache (job {})", source)
+            }
             ScheduledJobStatus::Failed { reason, .. } => write!(f, "Failed: {}", reason),
             ScheduledJobStatus::Cancelled => write!(f, "Cancelled"),
+            ScheduledJobStatus::DeadlineExceeded { deadline } => {
+                write!(f, "Deadline exceeded ({})", deadline)
+            }
         }
     }
 }
@@ -289,6 +339,16 @@ pub struct ResourceRequirements {
 
     /// Required gate set (gate names that must be supported).
     pub required_gates: Vec<String>,
+
+    /// Batch node features the job needs, e.g. `qctrl` for nodes wired to
+    /// the FPGA control stack, in addition to those of the matched backend.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub node_features: Vec<String>,
+
+    /// Prefer the calibrated backend and initial layout with the highest
+    /// estimated success probability, compiling the circuits for them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub maximize_esp: bool,
 }
 
 impl Default for ResourceRequirements {
@@ -300,6 +360,8 @@ impl Default for ResourceRequirements {
             max_queue_time: None,
             preferred_backends: Vec::new(),
             required_gates: Vec::new(),
+            node_features: Vec::new(),
+            maximize_esp: false,
         }
     }
 }
@@ -342,6 +404,61 @@ impl ResourceRequirements {
         self.required_gates.push(gate.into());
         self
     }
+
+    /// Add required batch node feature.
+    pub fn require_node_feature(mut self, feature: impl Into<String>) -> Self {
+        self.node_features.push(feature.into());
+        self
+    }
+
+    /// Prefer the backend and layout with the highest estimated success
+    /// probability.
+    pub fn prefer_high_esp(mut self) -> Self {
+        self.maximize_esp = true;
+        self
+    }
+}
+
+/// Batch-system resources of one array task that differ from the job's.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskOverride {
+    /// Memory limit in MB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u32>,
+
+    /// Time limit in minutes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_limit: Option<u32>,
+}
+
+impl TaskOverride {
+    /// Override the memory limit, in MB.
+    pub fn memory_mb(memory_mb: u32) -> Self {
+        Self {
+            memory_mb: Some(memory_mb),
+            ..Default::default()
+        }
+    }
+
+    /// Override the time limit, in minutes.
+    pub fn time_limit(minutes: u32) -> Self {
+        Self {
+            time_limit: Some(minutes),
+            ..Default::default()
+        }
+    }
+
+    /// Also override the memory limit, in MB.
+    pub fn with_memory_mb(mut self, memory_mb: u32) -> Self {
+        self.memory_mb = Some(memory_mb);
+        self
+    }
+
+    /// Also override the time limit, in minutes.
+    pub fn with_time_limit(mut self, minutes: u32) -> Self {
+        self.time_limit = Some(minutes);
+        self
+    }
 }
 
 /// Specification for a circuit to be executed.
@@ -354,6 +471,26 @@ pub enum CircuitSpec {
 
     /// Path to QASM file.
     QasmFile(std::path::PathBuf),
+
+    /// Content hash of a QASM3 source kept once in the state store, see
+    /// [`crate::persistence::circuit_hash`]. Only stored job records hold
+    /// these; stores replace them with the source when loading jobs.
+    Stored(String),
+
+    /// A circuit with its own result label and shot count, for jobs that
+    /// carry several circuits (e.g. an experiment and its calibration).
+    Annotated {
+        /// The circuit itself.
+        circuit: Box<CircuitSpec>,
+
+        /// Label identifying the circuit's result.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+
+        /// Shots for this circuit, overriding the job's shot count.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        shots: Option<u32>,
+    },
 }
 
 impl CircuitSpec {
@@ -373,6 +510,67 @@ impl CircuitSpec {
         CircuitSpec::QasmFile(path.into())
     }
 
+    /// Label the circuit's result.
+    pub fn with_label(self, label: impl Into<String>) -> Self {
+        let mut spec = self.annotated();
+        if let CircuitSpec::Annotated { label: l, .. } = &mut spec {
+            *l = Some(label.into());
+        }
+        spec
+    }
+
+    /// Run the circuit with its own shot count instead of the job's.
+    pub fn with_shots(self, shots: u32) -> Self {
+        let mut spec = self.annotated();
+        if let CircuitSpec::Annotated { shots: s, .. } = &mut spec {
+            *s = Some(shots);
+        }
+        spec
+    }
+
+    fn annotated(self) -> Self {
+        match self {
+            CircuitSpec::Annotated { .. } => self,
+            circuit => CircuitSpec::Annotated {
+                circuit: Box::new(circuit),
+                label: None,
+                shots: None,
+            },
+        }
+    }
+
+    /// Get the result label, if one was set.
+    pub fn label(&self) -> Option<&str> {
+        match self {
+            CircuitSpec::Annotated { label, .. } => label.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Get the circuit's own shot count, if one was set.
+    pub fn shots(&self) -> Option<u32> {
+        match self {
+            CircuitSpec::Annotated { shots, .. } => *shots,
+            _ => None,
+        }
+    }
+
+    /// Get the circuit source without its annotations.
+    pub fn source(&self) -> &CircuitSpec {
+        match self {
+            CircuitSpec::Annotated { circuit, .. } => circuit.source(),
+            _ => self,
+        }
+    }
+
+    /// Replace the circuit source, keeping the annotations.
+    pub fn set_source(&mut self, source: CircuitSpec) {
+        match self {
+            CircuitSpec::Annotated { circuit, .. } => circuit.set_source(source),
+            _ => *self = source,
+        }
+    }
+
     /// Resolve the circuit spec to a circuit.
     pub fn resolve(&self) -> crate::SchedResult<Circuit> {
         match self {
@@ -381,6 +579,11 @@ impl CircuitSpec {
                 let qasm = std::fs::read_to_string(path)?;
                 Ok(arvak_qasm3::parse(&qasm)?)
             }
+            CircuitSpec::Stored(hash) => Err(crate::SchedError::PersistenceError(format!(
+                "circuit {} was not loaded from the store",
+                hash
+            ))),
+            CircuitSpec::Annotated { circuit, .. } => circuit.resolve(),
         }
     }
 
@@ -389,6 +592,70 @@ impl CircuitSpec {
         let circuit = self.resolve()?;
         Ok(circuit.num_qubits() as u32)
     }
+
+    /// Get the bond dimension an exact MPS simulation of the circuit needs
+    /// at most; see [`arvak_ir::Circuit::bond_dimension_bound`].
+    pub fn bond_dimension_bound(&self) -> crate::SchedResult<u64> {
+        Ok(self.resolve()?.bond_dimension_bound())
+    }
+
+    /// Get a stable hash of the circuit, independent of QASM formatting.
+    ///
+    /// Specs describing the same circuit hash equally, so the hash can key
+    /// caches of compiled circuits or results.
+    pub fn canonical_hash(&self) -> crate::SchedResult<u64> {
+        let circuit = self.resolve()?;
+        Ok(arvak_qasm3::canonical_hash(&circuit)?)
+    }
+}
+
+/// Condition an upstream job must meet before a dependent job may start.
+///
+/// Mirrors SLURM's `--dependency` types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum DependencyKind {
+    /// Upstream job completed successfully (`afterok`).
+    #[default]
+    AfterOk,
+
+    /// Upstream job reached any terminal state (`afterany`).
+    AfterAny,
+
+    /// Upstream job failed or was cancelled (`afternotok`).
+    AfterNotOk,
+}
+
+impl DependencyKind {
+    /// SLURM dependency type name.
+    pub fn slurm_type(&self) -> &'static str {
+        match self {
+            DependencyKind::AfterOk => "afterok",
+            DependencyKind::AfterAny => "afterany",
+            DependencyKind::AfterNotOk => "afternotok",
+        }
+    }
+
+    /// Check whether a finished upstream job satisfies this condition.
+    pub fn is_satisfied_by(&self, succeeded: bool) -> bool {
+        match self {
+            DependencyKind::AfterOk => succeeded,
+            DependencyKind::AfterAny => true,
+            DependencyKind::AfterNotOk => !succeeded,
+        }
+    }
+}
+
+/// Readiness of a job with respect to its dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyState {
+    /// Some dependencies are still unfinished.
+    Waiting,
+
+    /// Enough dependencies are satisfied for the job to run.
+    Ready,
+
+    /// The dependency condition can no longer be met.
+    Unsatisfiable,
 }
 
 /// A scheduled job in the HPC scheduler.
@@ -412,15 +679,134 @@ pub struct ScheduledJob {
     /// Circuits to execute (supports batch).
     pub circuits: Vec<CircuitSpec>,
 
-    /// Number of shots per circuit.
+    /// Circuits replaced by several fragments in `circuits` because they
+    /// measure more clbits than the backend holds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub measurement_splits: Vec<SplitCircuit>,
+
+    /// Number of shots per circuit, unless a circuit sets its own.
     pub shots: u32,
 
     /// Job dependencies (must complete before this job can run).
     pub dependencies: Vec<ScheduledJobId>,
 
+    /// Dependency kinds for entries in `dependencies` (default: `AfterOk`).
+    #[serde(default, skip_serializing_if = "rustc_hash::FxHashMap::is_empty")]
+    pub dependency_kinds: rustc_hash::FxHashMap<ScheduledJobId, DependencyKind>,
+
+    /// Number of dependencies that must be satisfied (default: all).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_dependencies: Option<usize>,
+
+    /// Native batch-system dependency expression, set at dispatch when the
+    /// upstream jobs are already queued on the same cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_dependency: Option<String>,
+
+    /// Classical task to run instead of circuits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<ClassicalTask>,
+
+    /// Capabilities the job needs from its backend, checked before dispatch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preflight: Option<CapabilityRequest>,
+
+    /// Outcome of the pre-flight negotiation, once it ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub negotiation: Option<Negotiation>,
+
+    /// Principal owning the job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+
+    /// Job this job was derived from (e.g. by mitigation or ZNE).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<ScheduledJobId>,
+
+    /// How this job was derived from its parent (e.g. `zne:scale=3`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation: Option<String>,
+
+    /// Workflow the job is a node of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow_id: Option<WorkflowId>,
+
+    /// ID of the API request that submitted the job, e.g. a gRPC request ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+
+    /// Batch job ID of the latest submission, kept after the status moves
+    /// on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_job_id: Option<String>,
+
     /// Matched backend name (set after resource matching).
     pub matched_backend: Option<String>,
 
+    /// Batch node features the job is constrained to, resolved from its
+    /// requirements and matched backend when it is dispatched.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub node_features: Vec<String>,
+
+    /// Number of times the job was rerouted away from a failing backend.
+    #[serde(default)]
+    pub reroutes: u32,
+
+    /// Policy for requeueing the job when it fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+
+    /// Earlier attempts at the job that failed and were retried.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<Attempt>,
+
+    /// Checks on the job's results, overriding the scheduler's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ResultValidation>,
+
+    /// The job is not dispatched before this time, e.g. while backing off
+    /// before a retry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<DateTime<Utc>>,
+
+    /// Reuse the result of an identical earlier job instead of running,
+    /// see [`crate::cache`].
+    #[serde(default)]
+    pub cacheable: bool,
+
+    /// Cache key computed when the job was dispatched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_key: Option<String>,
+
+    /// Wall-clock time limit for the job (seconds), passed to the batch
+    /// system instead of its configured default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub walltime: Option<u64>,
+
+    /// Time by which the job must have finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<DateTime<Utc>>,
+
+    /// Name of the [`Reservation`](crate::reservation::Reservation) the
+    /// job runs in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reservation: Option<String>,
+
+    /// Run each circuit as a task of a batch-system job array instead of
+    /// one after another in a single job.
+    #[serde(default)]
+    pub array: bool,
+
+    /// ID of the [`JobArraySpec`](crate::array::JobArraySpec) the job is a
+    /// member of; members dispatched together share one array job.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub array_group: Option<String>,
+
+    /// Resources of array tasks that differ from the job's, by task
+    /// (circuit) index.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub task_overrides: BTreeMap<usize, TaskOverride>,
+
     /// Job creation timestamp.
     pub created_at: DateTime<Utc>,
 
@@ -444,9 +830,36 @@ impl ScheduledJob {
             priority: Priority::default(),
             requirements: ResourceRequirements::default(),
             circuits: vec![circuit],
+            measurement_splits: Vec::new(),
             shots: 1024,
             dependencies: Vec::new(),
+            dependency_kinds: rustc_hash::FxHashMap::default(),
+            min_dependencies: None,
+            batch_dependency: None,
+            task: None,
+            preflight: None,
+            negotiation: None,
+            owner: None,
+            parent: None,
+            derivation: None,
+            workflow_id: None,
+            request_id: None,
+            batch_job_id: None,
             matched_backend: None,
+            node_features: Vec::new(),
+            reroutes: 0,
+            retry: None,
+            validation: None,
+            attempts: Vec::new(),
+            not_before: None,
+            cacheable: false,
+            cache_key: None,
+            walltime: None,
+            deadline: None,
+            reservation: None,
+            array: false,
+            array_group: None,
+            task_overrides: BTreeMap::new(),
             created_at: Utc::now(),
             submitted_at: None,
             completed_at: None,
@@ -463,9 +876,36 @@ impl ScheduledJob {
             priority: Priority::default(),
             requirements: ResourceRequirements::default(),
             circuits,
+            measurement_splits: Vec::new(),
             shots: 1024,
             dependencies: Vec::new(),
+            dependency_kinds: rustc_hash::FxHashMap::default(),
+            min_dependencies: None,
+            batch_dependency: None,
+            task: None,
+            preflight: None,
+            negotiation: None,
+            owner: None,
+            parent: None,
+            derivation: None,
+            workflow_id: None,
+            request_id: None,
+            batch_job_id: None,
             matched_backend: None,
+            node_features: Vec::new(),
+            reroutes: 0,
+            retry: None,
+            validation: None,
+            attempts: Vec::new(),
+            not_before: None,
+            cacheable: false,
+            cache_key: None,
+            walltime: None,
+            deadline: None,
+            reservation: None,
+            array: false,
+            array_group: None,
+            task_overrides: BTreeMap::new(),
             created_at: Utc::now(),
             submitted_at: None,
             completed_at: None,
@@ -473,6 +913,109 @@ impl ScheduledJob {
         }
     }
 
+    /// Create a classical task job.
+    pub fn classical(name: impl Into<String>, task: ClassicalTask) -> Self {
+        let mut job = Self::batch(name, Vec::new()).with_shots(0);
+        job.task = Some(task);
+        job
+    }
+
+    /// Create a node that verifies the result of one job against another.
+    ///
+    /// The node depends on both compared jobs.
+    pub fn verification(name: impl Into<String>, verification: ResultVerification) -> Self {
+        let deps = [
+            verification.reference.clone(),
+            verification.candidate.clone(),
+        ];
+        Self::classical(name, ClassicalTask::verify(verification)).depends_on_all(deps)
+    }
+
+    /// Negotiate capabilities with the matched backend before dispatch.
+    pub fn with_preflight(mut self, request: CapabilityRequest) -> Self {
+        self.preflight = Some(request);
+        self
+    }
+
+    /// Requeue the job according to `policy` when it fails.
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Check the job's results with `validation` when it finishes.
+    pub fn with_validation(mut self, validation: ResultValidation) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+
+    /// Complete the job from the result of an identical earlier job, if
+    /// one is stored, instead of running it.
+    pub fn with_caching(mut self) -> Self {
+        self.cacheable = true;
+        self
+    }
+
+    /// Limit the job's wall-clock time.
+    pub fn with_walltime(mut self, walltime: std::time::Duration) -> Self {
+        self.walltime = Some(walltime.as_secs().max(1));
+        self
+    }
+
+    /// Require the job to finish by `deadline`.
+    ///
+    /// Jobs that cannot finish in time are not dispatched, and jobs still
+    /// running when it passes are cancelled; both end as
+    /// [`ScheduledJobStatus::DeadlineExceeded`].
+    pub fn with_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Run the job in the reservation `name`.
+    ///
+    /// On submission the job is rejected unless it can finish inside the
+    /// reservation's window and fits its remaining capacity; it then runs
+    /// on the reservation's backend once the window opens.
+    pub fn with_reservation(mut self, name: impl Into<String>) -> Self {
+        self.reservation = Some(name.into());
+        self
+    }
+
+    /// Run each circuit as a task of a job array; jobs with a single
+    /// circuit run as a plain job.
+    pub fn as_array(mut self) -> Self {
+        self.array = true;
+        self
+    }
+
+    /// Give one array task its own resources, e.g. more memory for the
+    /// larger circuits at the end of a sweep. Makes the job an array.
+    pub fn with_task_override(mut self, index: usize, task: TaskOverride) -> Self {
+        self.array = true;
+        self.task_overrides.insert(index, task);
+        self
+    }
+
+    /// Set the principal owning the job.
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
+    }
+
+    /// Mark this job as derived from a parent job.
+    pub fn derived_from(mut self, parent: ScheduledJobId, derivation: impl Into<String>) -> Self {
+        self.parent = Some(parent);
+        self.derivation = Some(derivation.into());
+        self
+    }
+
+    /// Record the ID of the API request submitting the job.
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
     /// Set the job priority.
     pub fn with_priority(mut self, priority: Priority) -> Self {
         self.priority = priority;
@@ -503,18 +1046,122 @@ impl ScheduledJob {
         self
     }
 
+    /// Add a dependency with a specific kind.
+    pub fn depends_on_with(mut self, job_id: ScheduledJobId, kind: DependencyKind) -> Self {
+        self.add_dependency(job_id, kind);
+        self
+    }
+
+    /// Require only `n` of the dependencies to be satisfied.
+    pub fn with_min_dependencies(mut self, n: usize) -> Self {
+        self.min_dependencies = Some(n);
+        self
+    }
+
+    /// Record a dependency, replacing the kind if already present.
+    pub fn add_dependency(&mut self, job_id: ScheduledJobId, kind: DependencyKind) {
+        if !self.dependencies.contains(&job_id) {
+            self.dependencies.push(job_id.clone());
+        }
+        if kind == DependencyKind::AfterOk {
+            self.dependency_kinds.remove(&job_id);
+        } else {
+            self.dependency_kinds.insert(job_id, kind);
+        }
+    }
+
+    /// Get the kind of a dependency.
+    pub fn dependency_kind(&self, job_id: &ScheduledJobId) -> DependencyKind {
+        self.dependency_kinds
+            .get(job_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Number of dependencies that must be satisfied.
+    pub fn required_dependencies(&self) -> usize {
+        self.min_dependencies
+            .map_or(self.dependencies.len(), |n| n.min(self.dependencies.len()))
+    }
+
     /// Add metadata.
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.metadata.insert(key.into(), value.into());
         self
     }
 
-    /// Check if all dependencies are satisfied (given a set of completed job IDs).
+    /// Check if dependencies are satisfied.
+    ///
+    /// `finished` maps finished job IDs to whether they succeeded.
     pub fn dependencies_satisfied(
         &self,
-        completed: &rustc_hash::FxHashSet<ScheduledJobId>,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
     ) -> bool {
-        self.dependencies.iter().all(|dep| completed.contains(dep))
+        self.dependency_state(finished) == DependencyState::Ready
+    }
+
+    /// Evaluate dependency readiness.
+    ///
+    /// `finished` maps finished job IDs to whether they succeeded.
+    pub fn dependency_state(
+        &self,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
+    ) -> DependencyState {
+        let required = self.required_dependencies();
+        let mut satisfied = 0;
+        let mut unsatisfied = 0;
+        for dep in &self.dependencies {
+            match finished.get(dep) {
+                Some(&succeeded) if self.dependency_kind(dep).is_satisfied_by(succeeded) => {
+                    satisfied += 1;
+                }
+                Some(_) => unsatisfied += 1,
+                None => {}
+            }
+        }
+
+        if satisfied >= required {
+            DependencyState::Ready
+        } else if self.dependencies.len() - unsatisfied < required {
+            DependencyState::Unsatisfiable
+        } else {
+            DependencyState::Waiting
+        }
+    }
+
+    /// Build a SLURM `--dependency` expression for a waiting job.
+    ///
+    /// `batch_id` resolves an unfinished upstream job to its batch job ID.
+    /// Returns `None` when an unfinished upstream job has no batch ID yet or
+    /// the condition has no native equivalent (N-of-M with 1 < N < M).
+    pub fn slurm_dependency(
+        &self,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
+        batch_id: impl Fn(&ScheduledJobId) -> Option<String>,
+    ) -> Option<String> {
+        // Finished dependencies are either already satisfied (all-of) or
+        // can no longer help (any-of), so only pending ones are rendered.
+        let pending: Vec<&ScheduledJobId> = self
+            .dependencies
+            .iter()
+            .filter(|dep| !finished.contains_key(*dep))
+            .collect();
+        if pending.is_empty() {
+            return None;
+        }
+
+        let separator = match self.required_dependencies() {
+            n if n == self.dependencies.len() => ",",
+            1 => "?",
+            _ => return None,
+        };
+
+        let mut terms = Vec::with_capacity(pending.len());
+        for dep in pending {
+            let id = batch_id(dep)?;
+            terms.push(format!("{}:{}", self.dependency_kind(dep).slurm_type(), id));
+        }
+        Some(terms.join(separator))
     }
 
     /// Get the maximum qubit count across all circuits.
@@ -526,10 +1173,154 @@ impl ScheduledJob {
         Ok(max)
     }
 
+    /// Get the largest bond-dimension bound across all circuits.
+    pub fn max_bond_dimension_bound(&self) -> crate::SchedResult<u64> {
+        let mut max = 1;
+        for circuit in &self.circuits {
+            max = max.max(circuit.bond_dimension_bound()?);
+        }
+        Ok(max)
+    }
+
+    /// Get the number of shots for a circuit.
+    pub fn circuit_shots(&self, index: usize) -> u32 {
+        self.circuits
+            .get(index)
+            .and_then(CircuitSpec::shots)
+            .unwrap_or(self.shots)
+    }
+
+    /// Get the result label of a circuit, defaulting to `circuit_<index>`.
+    pub fn circuit_label(&self, index: usize) -> String {
+        self.circuits
+            .get(index)
+            .and_then(CircuitSpec::label)
+            .map_or_else(|| format!("circuit_{}", index), str::to_string)
+    }
+
+    /// Get the total number of shots across all circuits.
+    pub fn total_shots(&self) -> u64 {
+        (0..self.circuits.len())
+            .map(|i| u64::from(self.circuit_shots(i)))
+            .sum()
+    }
+
+    /// Estimate the job's walltime in seconds with the evaluator's walltime
+    /// model, summed over its circuits.
+    ///
+    /// Circuits that cannot be resolved count with the model's fixed
+    /// overhead only; classical tasks are estimated at zero.
+    pub fn estimated_walltime_secs(&self) -> f64 {
+        let constraints = SchedulerConstraints::simulator();
+        self.circuits
+            .iter()
+            .map(|spec| {
+                let (depth, ops) = spec
+                    .resolve()
+                    .map(|c| (c.depth(), c.dag().num_ops()))
+                    .unwrap_or((0, 0));
+                SchedulerContext::estimate_walltime(depth, ops, &constraints).total_seconds
+            })
+            .sum()
+    }
+
+    /// Expected run time (seconds): the walltime if one was set, otherwise
+    /// [`ScheduledJob::estimated_walltime_secs`].
+    pub fn expected_runtime_secs(&self) -> f64 {
+        self.walltime
+            .map_or_else(|| self.estimated_walltime_secs(), |secs| secs as f64)
+    }
+
+    /// Walltime rounded up to whole minutes, the unit batch systems take.
+    pub fn walltime_minutes(&self) -> Option<u32> {
+        self.walltime
+            .map(|secs| u32::try_from(secs.div_ceil(60)).unwrap_or(u32::MAX))
+    }
+
+    /// Check whether a job dispatched at `now` can no longer finish by its
+    /// deadline.
+    pub fn misses_deadline(&self, now: DateTime<Utc>) -> bool {
+        self.deadline.is_some_and(|deadline| {
+            let runtime =
+                chrono::Duration::milliseconds((self.expected_runtime_secs() * 1000.0) as i64);
+            now + runtime > deadline
+        })
+    }
+
+    /// Return the job to `Pending` so it is matched and dispatched afresh,
+    /// keeping its earlier attempts.
+    pub fn reset(&mut self) {
+        self.status = ScheduledJobStatus::Pending;
+        self.matched_backend = None;
+        self.node_features.clear();
+        self.negotiation = None;
+        self.batch_dependency = None;
+        self.cache_key = None;
+        self.submitted_at = None;
+        self.completed_at = None;
+    }
+
+    /// External IDs the job can be looked up by: its batch job IDs, from
+    /// every attempt, and the request that submitted it.
+    pub fn references(&self) -> Vec<&str> {
+        let mut refs: Vec<&str> = self
+            .attempts
+            .iter()
+            .filter_map(|attempt| attempt.batch_job_id.as_deref())
+            .chain(self.batch_job_id.as_deref())
+            .chain(self.status.slurm_job_id())
+            .chain(self.request_id.as_deref())
+            .filter(|id| {
+                *id != crate::cloud::CLOUD_JOB_ID && *id != crate::task::SUB_WORKFLOW_JOB_ID
+            })
+            .collect();
+        refs.sort_unstable();
+        refs.dedup();
+        refs
+    }
+
+    /// Check that result labels are unique, every circuit has shots and
+    /// every array task override names a circuit.
+    pub fn validate_circuits(&self) -> crate::SchedResult<()> {
+        let mut labels = rustc_hash::FxHashSet::default();
+        for i in 0..self.circuits.len() {
+            let label = self.circuit_label(i);
+            if !labels.insert(label.clone()) {
+                return Err(crate::SchedError::InvalidPayload(format!(
+                    "duplicate result label '{}'",
+                    label
+                )));
+            }
+            if self.circuit_shots(i) == 0 && !self.is_classical() {
+                return Err(crate::SchedError::InvalidPayload(format!(
+                    "circuit '{}' has no shots",
+                    label
+                )));
+            }
+        }
+        if let Some(index) = self
+            .task_overrides
+            .keys()
+            .find(|&&i| i >= self.circuits.len())
+        {
+            return Err(crate::SchedError::InvalidPayload(format!(
+                "resource override for array task {} of a job with {} circuit(s)",
+                index,
+                self.circuits.len()
+            )));
+        }
+        Ok(())
+    }
+
     /// Check if this is a batch job.
     pub fn is_batch(&self) -> bool {
         self.circuits.len() > 1
     }
+
+    /// Check if this is a classical task job.
+    pub fn is_classical(&self) -> bool {
+        self.task.is_some()
+    }
 }
 
 /// Filter for listing jobs.
@@ -555,6 +1346,9 @@ pub struct JobFilter {
     /// Include only running jobs.
     pub running_only: bool,
 
+    /// Include only jobs derived from this parent.
+    pub parent: Option<ScheduledJobId>,
+
     /// Maximum number of results.
     pub limit: Option<usize>,
 }
@@ -576,6 +1370,14 @@ impl JobFilter {
         }
     }
 
+    /// Create a filter for the jobs derived from a parent.
+    pub fn children_of(parent: ScheduledJobId) -> Self {
+        Self {
+            parent: Some(parent),
+            ..Default::default()
+        }
+    }
+
     /// Filter by status.
     pub fn with_status(mut self, status: impl IntoIterator<Item = impl Into<String>>) -> Self {
         self.status = Some(status.into_iter().map(Into::into).collect());
@@ -638,6 +1440,11 @@ impl JobFilter {
             }
         }
 
+        // Check parent
+        if self.parent.is_some() && job.parent != self.parent {
+            return false;
+        }
+
         true
     }
 }
@@ -662,6 +1469,7 @@ mod tests {
         assert!(Priority::LOW < Priority::DEFAULT);
         assert!(Priority::DEFAULT < Priority::HIGH);
         assert!(Priority::HIGH < Priority::CRITICAL);
+        assert!(Priority::CRITICAL < Priority::URGENT);
     }
 
     #[test]
@@ -701,6 +1509,60 @@ mod tests {
         assert_eq!(job.metadata.get("user"), Some(&"alice".to_string()));
     }
 
+    #[test]
+    fn test_multi_circuit_payload() {
+        let qasm = "OPENQASM 3.0; qubit[1] q; x q[0];";
+        let mut job = ScheduledJob::batch(
+            "calibrated",
+            vec![
+                CircuitSpec::from_qasm(qasm).with_label("experiment"),
+                CircuitSpec::from_qasm(qasm)
+                    .with_shots(100)
+                    .with_label("readout"),
+                CircuitSpec::from_qasm(qasm),
+            ],
+        )
+        .with_shots(1000);
+
+        assert_eq!(job.circuit_label(0), "experiment");
+        assert_eq!(job.circuit_label(1), "readout");
+        assert_eq!(job.circuit_label(2), "circuit_2");
+        assert_eq!(job.circuit_shots(0), 1000);
+        assert_eq!(job.circuit_shots(1), 100);
+        assert_eq!(job.total_shots(), 2100);
+        assert_eq!(job.max_qubits().unwrap(), 1);
+        job.validate_circuits().unwrap();
+
+        // Annotations survive replacing the source and a serde roundtrip.
+        job.circuits[1].set_source(CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"));
+        let json = serde_json::to_string(&job).unwrap();
+        let job: ScheduledJob = serde_json::from_str(&json).unwrap();
+        assert_eq!(job.circuits[1].label(), Some("readout"));
+        assert_eq!(job.circuits[1].shots(), Some(100));
+        assert_eq!(job.circuits[1].resolve().unwrap().num_qubits(), 2);
+        assert!(matches!(job.circuits[2].source(), CircuitSpec::Qasm3(_)));
+
+        let mut duplicate = job.clone();
+        duplicate.circuits[2] = CircuitSpec::from_qasm(qasm).with_label("readout");
+        assert!(matches!(
+            duplicate.validate_circuits(),
+            Err(crate::SchedError::InvalidPayload(_))
+        ));
+
+        let mut no_shots = job.clone();
+        no_shots.circuits[0] = CircuitSpec::from_qasm(qasm).with_shots(0);
+        assert!(no_shots.validate_circuits().is_err());
+
+        let array = job.with_task_override(2, TaskOverride::memory_mb(8192));
+        assert!(array.array);
+        array.validate_circuits().unwrap();
+        let array = array.with_task_override(3, TaskOverride::time_limit(30));
+        assert!(matches!(
+            array.validate_circuits(),
+            Err(crate::SchedError::InvalidPayload(_))
+        ));
+    }
+
     #[test]
     fn test_resource_requirements_builder() {
         let req = ResourceRequirements::new(5)
@@ -727,4 +1589,100 @@ mod tests {
         let filter = JobFilter::running();
         assert!(!filter.matches(&job));
     }
+
+    fn dep_job(name: &str) -> ScheduledJob {
+        ScheduledJob::new(name, CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q;"))
+    }
+
+    #[test]
+    fn test_dependency_kinds() {
+        let a = ScheduledJobId::new();
+        let b = ScheduledJobId::new();
+        let job = dep_job("job")
+            .depends_on(a.clone())
+            .depends_on_with(b.clone(), DependencyKind::AfterNotOk);
+
+        let mut finished = rustc_hash::FxHashMap::default();
+        assert_eq!(job.dependency_state(&finished), DependencyState::Waiting);
+
+        finished.insert(a.clone(), true);
+        assert_eq!(job.dependency_state(&finished), DependencyState::Waiting);
+
+        finished.insert(b.clone(), false);
+        assert_eq!(job.dependency_state(&finished), DependencyState::Ready);
+
+        finished.insert(b, true);
+        assert_eq!(
+            job.dependency_state(&finished),
+            DependencyState::Unsatisfiable
+        );
+
+        let any = dep_job("any").depends_on_with(a.clone(), DependencyKind::AfterAny);
+        finished.insert(a, false);
+        assert!(any.dependencies_satisfied(&finished));
+    }
+
+    #[test]
+    fn test_dependency_quorum() {
+        let ids: Vec<_> = (0..3).map(|_| ScheduledJobId::new()).collect();
+        let job = dep_job("quorum")
+            .depends_on_all(ids.clone())
+            .with_min_dependencies(2);
+
+        let mut finished = rustc_hash::FxHashMap::default();
+        finished.insert(ids[0].clone(), true);
+        assert_eq!(job.dependency_state(&finished), DependencyState::Waiting);
+
+        finished.insert(ids[1].clone(), false);
+        assert_eq!(job.dependency_state(&finished), DependencyState::Waiting);
+
+        finished.insert(ids[2].clone(), true);
+        assert_eq!(job.dependency_state(&finished), DependencyState::Ready);
+
+        finished.insert(ids[2].clone(), false);
+        assert_eq!(
+            job.dependency_state(&finished),
+            DependencyState::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn test_slurm_dependency() {
+        let a = ScheduledJobId::new();
+        let b = ScheduledJobId::new();
+        let batch_id = |id: &ScheduledJobId| {
+            if *id == a {
+                Some("101".to_string())
+            } else if *id == b {
+                Some("102".to_string())
+            } else {
+                None
+            }
+        };
+        let finished = rustc_hash::FxHashMap::default();
+
+        let all = dep_job("all")
+            .depends_on(a.clone())
+            .depends_on_with(b.clone(), DependencyKind::AfterAny);
+        assert_eq!(
+            all.slurm_dependency(&finished, batch_id).as_deref(),
+            Some("afterok:101,afterany:102")
+        );
+
+        let any = all.clone().with_min_dependencies(1);
+        assert_eq!(
+            any.slurm_dependency(&finished, batch_id).as_deref(),
+            Some("afterok:101?afterany:102")
+        );
+
+        // Unknown upstream batch ID: not chainable.
+        let unknown = dep_job("unknown").depends_on(ScheduledJobId::new());
+        assert!(unknown.slurm_dependency(&finished, batch_id).is_none());
+
+        // 2-of-3 has no native equivalent.
+        let quorum = dep_job("quorum")
+            .depends_on_all([a.clone(), b.clone(), ScheduledJobId::new()])
+            .with_min_dependencies(2);
+        assert!(quorum.slurm_dependency(&finished, batch_id).is_none());
+    }
 }
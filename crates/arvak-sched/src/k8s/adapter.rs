@@ -0,0 +1,613 @@
+//! Kubernetes adapter for job submission and tracking.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use arvak_config::{Secret, SecretSource};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::batch::BatchSystem;
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobStatus};
+use crate::k8s::manifest::{self, JOB_ID_LABEL};
+use crate::k8s::parser;
+use crate::payload::{self, CircuitResult};
+use crate::retry::FailureKind;
+
+/// Kubernetes job state, derived from the Job's conditions and its pods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KubernetesState {
+    /// No pod of the job is running yet.
+    Pending,
+    /// A pod of the job is running.
+    Running,
+    /// The Job is suspended, e.g. by a queueing controller such as Kueue
+    /// that has not admitted it yet.
+    Suspended,
+    /// The Job completed.
+    Succeeded,
+    /// The Job failed, e.g. because a pod exited nonzero or it ran past
+    /// its deadline.
+    Failed,
+}
+
+impl KubernetesState {
+    /// Check if this is a terminal state.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, KubernetesState::Succeeded | KubernetesState::Failed)
+    }
+
+    /// Check if this represents a successful completion.
+    pub fn is_success(&self) -> bool {
+        matches!(self, KubernetesState::Succeeded)
+    }
+}
+
+/// Information about a Kubernetes job.
+#[derive(Debug, Clone)]
+pub struct KubernetesJobInfo {
+    /// Name of the Kubernetes Job.
+    pub name: String,
+
+    /// Current state.
+    pub state: KubernetesState,
+
+    /// Why a pending job has not started (e.g., "Unschedulable: 0/4 nodes
+    /// are available" or "ImagePullBackOff").
+    pub pending_reason: Option<String>,
+
+    /// Why a failed job failed (e.g., "DeadlineExceeded").
+    pub failure_reason: Option<String>,
+
+    /// Exit code of the failed container (for finished jobs).
+    pub exit_code: Option<i32>,
+}
+
+/// Where pods leave their results for the scheduler.
+///
+/// In TOML this is a single-key table, e.g.
+/// `{ volume = { claim = "arvak-results", local_path = "/mnt/results" } }`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ResultStore {
+    /// A PersistentVolumeClaim that is also mounted on the scheduler host.
+    Volume {
+        /// Name of the PersistentVolumeClaim.
+        claim: String,
+        /// Where the pod mounts the volume.
+        #[serde(default = "default_mount_path")]
+        mount_path: PathBuf,
+        /// Where the volume is mounted on the scheduler host.
+        local_path: PathBuf,
+    },
+    /// An HTTP object store accepting `PUT` and `GET` below a base URL,
+    /// e.g. a bucket behind an S3 gateway. Pods upload with `curl`.
+    ObjectStore {
+        /// Base URL results are stored below.
+        url: String,
+        /// Kubernetes Secret whose `token` key pods send as a bearer token.
+        token_secret: Option<String>,
+        /// Source of the bearer token the scheduler reads results with.
+        token: Option<SecretSource>,
+    },
+}
+
+fn default_mount_path() -> PathBuf {
+    PathBuf::from("/arvak/results")
+}
+
+impl Default for ResultStore {
+    fn default() -> Self {
+        ResultStore::Volume {
+            claim: "arvak-results".to_string(),
+            mount_path: default_mount_path(),
+            local_path: PathBuf::from("/tmp/arvak-jobs/results"),
+        }
+    }
+}
+
+/// Configuration for Kubernetes adapter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KubernetesConfig {
+    /// Namespace Jobs are created in.
+    pub namespace: String,
+
+    /// kubeconfig file, if not the default.
+    pub kubeconfig: Option<PathBuf>,
+
+    /// kubeconfig context, if not the current one.
+    pub context: Option<String>,
+
+    /// Path to the kubectl binary.
+    pub kubectl: PathBuf,
+
+    /// Container image with the Arvak binary (and `curl` for object
+    /// stores).
+    pub image: String,
+
+    /// Image pull policy (`Always`, `IfNotPresent` or `Never`).
+    pub image_pull_policy: Option<String>,
+
+    /// Secrets for pulling the image from a private registry.
+    pub image_pull_secrets: Vec<String>,
+
+    /// Service account pods run as.
+    pub service_account: Option<String>,
+
+    /// CPU request, as a Kubernetes quantity (e.g., "500m").
+    pub cpu: String,
+
+    /// Memory request and limit in MiB.
+    pub memory_mb: u32,
+
+    /// Run limit in minutes (`activeDeadlineSeconds`).
+    pub time_limit: u32,
+
+    /// Pod retries before the Job fails.
+    pub backoff_limit: u32,
+
+    /// Delete finished Jobs after this many seconds. Keep it well above
+    /// the poll interval, or jobs vanish before their outcome is seen.
+    pub ttl_seconds_after_finished: Option<u32>,
+
+    /// Node labels pods must match, as `key=value`.
+    pub node_selector: Vec<String>,
+
+    /// Numeric node label advertising the qubits reachable from a node.
+    /// When set, pods require nodes with at least the qubits they need.
+    pub qubit_label: Option<String>,
+
+    /// Path to the Arvak binary inside the image.
+    pub arvak_binary: PathBuf,
+
+    /// Where pods leave results.
+    pub results: ResultStore,
+
+    /// Mapping from priority value to PriorityClass names.
+    #[serde(deserialize_with = "crate::config::priority_mapping")]
+    pub priority_class_mapping: Option<rustc_hash::FxHashMap<u32, String>>,
+}
+
+impl Default for KubernetesConfig {
+    fn default() -> Self {
+        Self {
+            namespace: "default".to_string(),
+            kubeconfig: None,
+            context: None,
+            kubectl: PathBuf::from("kubectl"),
+            image: "arvak:latest".to_string(),
+            image_pull_policy: None,
+            image_pull_secrets: Vec::new(),
+            service_account: None,
+            cpu: "1".to_string(),
+            memory_mb: 4096,
+            time_limit: 60,
+            backoff_limit: 0,
+            ttl_seconds_after_finished: None,
+            node_selector: Vec::new(),
+            qubit_label: None,
+            arvak_binary: PathBuf::from("arvak"),
+            results: ResultStore::default(),
+            priority_class_mapping: None,
+        }
+    }
+}
+
+/// Adapter running jobs as Kubernetes Jobs.
+pub struct KubernetesAdapter {
+    config: KubernetesConfig,
+    /// Object store token resolved from `config.results`.
+    token: Option<Secret>,
+    /// Client for reading results from an object store.
+    http: reqwest::Client,
+    /// Whether to use mock mode (for testing).
+    mock_mode: bool,
+    /// Mock job counter for generating fake job IDs.
+    mock_counter: std::sync::atomic::AtomicU64,
+}
+
+impl KubernetesAdapter {
+    /// Create a new Kubernetes adapter with the given configuration.
+    pub async fn new(config: KubernetesConfig) -> SchedResult<Self> {
+        let token = match &config.results {
+            ResultStore::Volume { local_path, .. } => {
+                // Ensure the result directory exists
+                tokio::fs::create_dir_all(local_path).await?;
+                None
+            }
+            ResultStore::ObjectStore { token, .. } => {
+                token.as_ref().map(SecretSource::resolve).transpose()?
+            }
+        };
+
+        Ok(Self {
+            config,
+            token,
+            http: http_client()?,
+            mock_mode: false,
+            mock_counter: std::sync::atomic::AtomicU64::new(1000),
+        })
+    }
+
+    /// Create a new Kubernetes adapter in mock mode (for testing).
+    pub fn mock(config: KubernetesConfig) -> Self {
+        Self {
+            config,
+            token: None,
+            http: reqwest::Client::new(),
+            mock_mode: true,
+            mock_counter: std::sync::atomic::AtomicU64::new(1000),
+        }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &KubernetesConfig {
+        &self.config
+    }
+
+    /// Submit a job as a Kubernetes Job, returning the Job's name.
+    pub async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+        if self.mock_mode {
+            let job_id = self
+                .mock_counter
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            return Ok(format!("arvak-{}", job_id));
+        }
+
+        let mut circuits = Vec::with_capacity(job.circuits.len());
+        for spec in &job.circuits {
+            circuits.push(arvak_qasm3::emit(&spec.resolve()?)?);
+        }
+        let manifest = manifest::generate_job_manifest(job, &self.config, &circuits);
+
+        let output = self
+            .kubectl(&["create", "-f", "-"], Some(serde_json::to_vec(&manifest)?))
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(SchedError::KubernetesSubmitError(stderr.trim().to_string()));
+        }
+
+        Ok(manifest::job_name(job))
+    }
+
+    /// Get the status of a Kubernetes Job.
+    pub async fn status(&self, name: &str) -> SchedResult<KubernetesJobInfo> {
+        if self.mock_mode {
+            return Ok(KubernetesJobInfo {
+                name: name.to_string(),
+                state: KubernetesState::Succeeded,
+                pending_reason: None,
+                failure_reason: None,
+                exit_code: Some(0),
+            });
+        }
+
+        let job = self
+            .kubectl_get(&["get", "job", name, "-o", "json"])
+            .await?;
+        let job_id = name.strip_prefix("arvak-").unwrap_or(name);
+        let selector = format!("{}={}", JOB_ID_LABEL, job_id);
+        let pods = self
+            .kubectl_get(&["get", "pods", "-l", &selector, "-o", "json"])
+            .await?;
+
+        parser::parse_job_status(&job, &pods)
+    }
+
+    /// Cancel a Kubernetes Job, deleting it and its pods.
+    pub async fn cancel(&self, name: &str) -> SchedResult<()> {
+        if self.mock_mode {
+            return Ok(());
+        }
+
+        let output = self
+            .kubectl(
+                &[
+                    "delete",
+                    "job",
+                    name,
+                    "--cascade=background",
+                    "--wait=false",
+                ],
+                None,
+            )
+            .await?;
+        if output.status.success() {
+            return Ok(());
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if parser::is_not_found(&stderr) {
+            return Err(SchedError::KubernetesJobNotFound(name.to_string()));
+        }
+        Err(SchedError::KubernetesCommandError {
+            command: "kubectl delete".to_string(),
+            message: stderr.trim().to_string(),
+        })
+    }
+
+    /// Read the per-circuit results written by a completed job.
+    pub async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        if self.mock_mode {
+            return Ok(Vec::new());
+        }
+
+        match &self.config.results {
+            ResultStore::Volume { local_path, .. } => {
+                let result_path = if job.is_batch() {
+                    local_path.join(job.id.to_string())
+                } else {
+                    local_path.join(format!("{}.json", job.id))
+                };
+                payload::read_results(job, &result_path).await
+            }
+            ResultStore::ObjectStore { url, .. } => {
+                let mut results = Vec::new();
+                for index in 0..job.circuits.len() {
+                    let url = format!(
+                        "{}/{}",
+                        url.trim_end_matches('/'),
+                        manifest::result_key(job, index)
+                    );
+                    if let Some(data) = self.fetch(&url).await? {
+                        results.push(payload::parse_result(job, index, &url, &data)?);
+                    }
+                }
+                Ok(results)
+            }
+        }
+    }
+
+    /// Fetch an object, or `None` if it does not exist.
+    async fn fetch(&self, url: &str) -> SchedResult<Option<Vec<u8>>> {
+        let mut request = self.http.get(url);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token.expose());
+        }
+
+        let error = |message: String| SchedError::ObjectStoreError {
+            url: url.to_string(),
+            message,
+        };
+        let response = request.send().await.map_err(|e| error(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(error(format!("HTTP {}", response.status())));
+        }
+        let data = response.bytes().await.map_err(|e| error(e.to_string()))?;
+        Ok(Some(data.to_vec()))
+    }
+
+    /// Run a `kubectl get`, failing if the object does not exist.
+    async fn kubectl_get(&self, args: &[&str]) -> SchedResult<String> {
+        let output = self.kubectl(args, None).await?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if parser::is_not_found(&stderr) {
+            return Err(SchedError::KubernetesJobNotFound(args[2].to_string()));
+        }
+        if !output.status.success() {
+            return Err(SchedError::KubernetesCommandError {
+                command: format!("kubectl {}", args[..2].join(" ")),
+                message: stderr.trim().to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Run kubectl against the configured cluster and namespace.
+    async fn kubectl(
+        &self,
+        args: &[&str],
+        stdin: Option<Vec<u8>>,
+    ) -> SchedResult<std::process::Output> {
+        let mut command = Command::new(&self.config.kubectl);
+        if let Some(ref kubeconfig) = self.config.kubeconfig {
+            command.arg("--kubeconfig").arg(kubeconfig);
+        }
+        if let Some(ref context) = self.config.context {
+            command.args(["--context", context]);
+        }
+        command
+            .args(["--namespace", &self.config.namespace])
+            .args(args)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let command_error = |e: std::io::Error| SchedError::KubernetesCommandError {
+            command: format!("kubectl {}", args[0]),
+            message: e.to_string(),
+        };
+        let mut child = command.spawn().map_err(command_error)?;
+        if let (Some(data), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(&data).await.map_err(command_error)?;
+        }
+        child.wait_with_output().await.map_err(command_error)
+    }
+}
+
+fn http_client() -> SchedResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()
+        .map_err(|e| SchedError::ObjectStoreError {
+            url: String::new(),
+            message: format!("Failed to create HTTP client: {}", e),
+        })
+}
+
+#[async_trait]
+impl BatchSystem for KubernetesAdapter {
+    fn name(&self) -> &str {
+        "Kubernetes"
+    }
+
+    async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+        KubernetesAdapter::submit(self, job).await
+    }
+
+    async fn poll(
+        &self,
+        job: &ScheduledJob,
+        batch_job_id: &str,
+    ) -> SchedResult<ScheduledJobStatus> {
+        let info = self.status(batch_job_id).await?;
+        Ok(map_state(job, info))
+    }
+
+    async fn cancel(&self, batch_job_id: &str) -> SchedResult<()> {
+        KubernetesAdapter::cancel(self, batch_job_id).await
+    }
+
+    async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        KubernetesAdapter::read_results(self, job).await
+    }
+
+    async fn pending_reason(&self, batch_job_id: &str) -> SchedResult<Option<String>> {
+        let info = self.status(batch_job_id).await?;
+        Ok(match info.state {
+            // Named like SLURM's hold reasons so explanations classify it.
+            KubernetesState::Suspended => Some("JobHeld".to_string()),
+            KubernetesState::Pending => info.pending_reason,
+            _ => None,
+        })
+    }
+}
+
+/// Map a Kubernetes job state to the status of the job it runs.
+fn map_state(job: &ScheduledJob, info: KubernetesJobInfo) -> ScheduledJobStatus {
+    let name = info.name;
+
+    match info.state {
+        KubernetesState::Pending | KubernetesState::Suspended => {
+            ScheduledJobStatus::SlurmQueued { slurm_job_id: name }
+        }
+        KubernetesState::Running => ScheduledJobStatus::SlurmRunning { slurm_job_id: name },
+        KubernetesState::Succeeded => ScheduledJobStatus::Completed {
+            slurm_job_id: name,
+            quantum_job_id: arvak_hal::JobId("completed".to_string()),
+        },
+        KubernetesState::Failed => ScheduledJobStatus::Failed {
+            kind: failure_kind(info.failure_reason.as_deref()),
+            reason: match (info.failure_reason, info.exit_code) {
+                (Some(reason), _) => format!("Kubernetes job failed: {}", reason),
+                (None, Some(code)) => {
+                    format!("Kubernetes job failed with exit code {}", code)
+                }
+                (None, None) => "Kubernetes job failed".to_string(),
+            },
+            slurm_job_id: Some(name),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+    }
+}
+
+/// Classify a failed Kubernetes job by its condition and container reasons.
+fn failure_kind(reason: Option<&str>) -> FailureKind {
+    match reason {
+        Some(r) if r.contains("DeadlineExceeded") => FailureKind::Timeout,
+        Some(r) if r.contains("OOMKilled") => FailureKind::OutOfMemory,
+        _ => FailureKind::Application,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, Priority};
+    use arvak_hal::{Counts, ExecutionResult};
+
+    #[tokio::test]
+    async fn test_mock_kubernetes_adapter() {
+        let adapter = KubernetesAdapter::mock(KubernetesConfig::default());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job = ScheduledJob::new("test_job", circuit).with_priority(Priority::default());
+
+        let name = adapter.submit(&job).await.unwrap();
+        assert_eq!(name, "arvak-1000");
+
+        let info = adapter.status(&name).await.unwrap();
+        assert_eq!(info.name, name);
+        assert!(info.state.is_success());
+
+        adapter.cancel(&name).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_results_from_volume() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = KubernetesConfig {
+            results: ResultStore::Volume {
+                claim: "arvak-results".to_string(),
+                mount_path: default_mount_path(),
+                local_path: dir.path().to_path_buf(),
+            },
+            ..Default::default()
+        };
+        let adapter = KubernetesAdapter::new(config).await.unwrap();
+
+        let job = ScheduledJob::new("test_job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let result = ExecutionResult::new(Counts::from_pairs([("0", 1000)]), 1000);
+        std::fs::write(
+            dir.path().join(manifest::result_key(&job, 0)),
+            serde_json::to_vec(&result).unwrap(),
+        )
+        .unwrap();
+
+        let results = adapter.read_results(&job).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].result.counts.get("0"), 1000);
+    }
+
+    #[test]
+    fn test_kubernetes_state() {
+        assert!(KubernetesState::Succeeded.is_terminal());
+        assert!(KubernetesState::Failed.is_terminal());
+        assert!(!KubernetesState::Suspended.is_terminal());
+        assert!(KubernetesState::Succeeded.is_success());
+        assert!(!KubernetesState::Failed.is_success());
+    }
+
+    #[test]
+    fn test_map_kubernetes_state() {
+        let job = ScheduledJob::new("test_job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let info = |state, failure_reason: Option<&str>| KubernetesJobInfo {
+            name: "arvak-42".to_string(),
+            state,
+            pending_reason: None,
+            failure_reason: failure_reason.map(str::to_string),
+            exit_code: None,
+        };
+
+        assert!(matches!(
+            map_state(&job, info(KubernetesState::Suspended, None)),
+            ScheduledJobStatus::SlurmQueued { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(KubernetesState::Running, None)),
+            ScheduledJobStatus::SlurmRunning { ref slurm_job_id } if slurm_job_id == "arvak-42"
+        ));
+        assert!(matches!(
+            map_state(&job, info(KubernetesState::Succeeded, None)),
+            ScheduledJobStatus::Completed { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(KubernetesState::Failed, Some("DeadlineExceeded"))),
+            ScheduledJobStatus::Failed { ref reason, kind: FailureKind::Timeout, .. }
+                if reason.contains("DeadlineExceeded")
+        ));
+    }
+}
@@ -0,0 +1,416 @@
+//! Kubernetes Job manifests.
+
+use serde_json::{Value, json};
+
+use crate::job::ScheduledJob;
+use crate::k8s::adapter::{KubernetesConfig, ResultStore};
+
+/// Label carrying the Arvak job ID on Jobs and their pods.
+pub const JOB_ID_LABEL: &str = "arvak.io/job-id";
+
+/// Environment variable the pod reads the object store token from.
+const TOKEN_ENV: &str = "ARVAK_RESULT_TOKEN";
+
+/// Directory in the pod for circuits and, with an object store, results.
+const SCRATCH_DIR: &str = "/tmp/arvak";
+
+/// Name of the Kubernetes Job running a scheduled job.
+///
+/// ```
+/// use arvak_sched::{CircuitSpec, ScheduledJob};
+/// use arvak_sched::k8s::job_name;
+///
+/// let job = ScheduledJob::new("bell", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+/// assert_eq!(job_name(&job), format!("arvak-{}", job.id));
+/// ```
+pub fn job_name(job: &ScheduledJob) -> String {
+    format!("arvak-{}", job.id)
+}
+
+/// Where the result of circuit `index` goes, relative to the volume mount
+/// or object store URL: `<job id>.json` for a single circuit, otherwise
+/// `<job id>/result_<i>.json`.
+pub fn result_key(job: &ScheduledJob, index: usize) -> String {
+    if job.is_batch() {
+        format!("{}/result_{}.json", job.id, index)
+    } else {
+        format!("{}.json", job.id)
+    }
+}
+
+/// Generate the Kubernetes Job manifest for a quantum job.
+///
+/// Each circuit is passed as QASM in an `ARVAK_CIRCUIT_<i>` environment
+/// variable, so the Job needs no other objects and deleting it cleans up
+/// everything.
+pub fn generate_job_manifest(
+    job: &ScheduledJob,
+    config: &KubernetesConfig,
+    circuits: &[String],
+) -> Value {
+    let name = job_name(job);
+    let labels = json!({
+        "app.kubernetes.io/name": "arvak",
+        "app.kubernetes.io/managed-by": "arvak-sched",
+        JOB_ID_LABEL: job.id.to_string(),
+    });
+
+    let mut env: Vec<Value> = circuits
+        .iter()
+        .enumerate()
+        .map(|(i, qasm)| json!({ "name": format!("ARVAK_CIRCUIT_{}", i), "value": qasm }))
+        .collect();
+
+    let mut volume_mounts = Vec::new();
+    let mut volumes = Vec::new();
+    match &config.results {
+        ResultStore::Volume {
+            claim, mount_path, ..
+        } => {
+            volume_mounts.push(json!({ "name": "results", "mountPath": mount_path }));
+            volumes.push(json!({
+                "name": "results",
+                "persistentVolumeClaim": { "claimName": claim },
+            }));
+        }
+        ResultStore::ObjectStore {
+            token_secret: Some(secret),
+            ..
+        } => {
+            env.push(json!({
+                "name": TOKEN_ENV,
+                "valueFrom": { "secretKeyRef": { "name": secret, "key": "token" } },
+            }));
+        }
+        ResultStore::ObjectStore { .. } => {}
+    }
+
+    let mut container = json!({
+        "name": "arvak",
+        "image": config.image,
+        "command": ["/bin/sh", "-c", pod_script(job, config)],
+        "env": env,
+        "resources": {
+            "requests": { "cpu": config.cpu, "memory": format!("{}Mi", config.memory_mb) },
+            "limits": { "memory": format!("{}Mi", config.memory_mb) },
+        },
+    });
+    if let Some(ref policy) = config.image_pull_policy {
+        container["imagePullPolicy"] = json!(policy);
+    }
+    if !volume_mounts.is_empty() {
+        container["volumeMounts"] = json!(volume_mounts);
+    }
+
+    let mut pod_spec = json!({
+        "restartPolicy": "Never",
+        "containers": [container],
+    });
+    if !volumes.is_empty() {
+        pod_spec["volumes"] = json!(volumes);
+    }
+    if let Some(ref account) = config.service_account {
+        pod_spec["serviceAccountName"] = json!(account);
+    }
+    if !config.image_pull_secrets.is_empty() {
+        pod_spec["imagePullSecrets"] = config
+            .image_pull_secrets
+            .iter()
+            .map(|name| json!({ "name": name }))
+            .collect();
+    }
+    if !config.node_selector.is_empty() {
+        pod_spec["nodeSelector"] = config
+            .node_selector
+            .iter()
+            .filter_map(|label| label.split_once('='))
+            .map(|(key, value)| (key.to_string(), json!(value)))
+            .collect::<serde_json::Map<_, _>>()
+            .into();
+    }
+    if let Some(affinity) = qubit_affinity(job, config) {
+        pod_spec["affinity"] = affinity;
+    }
+    if let Some(class) = config
+        .priority_class_mapping
+        .as_ref()
+        .and_then(|mapping| mapping.get(&job.priority.value()))
+    {
+        pod_spec["priorityClassName"] = json!(class);
+    }
+
+    // Scale the deadline based on number of circuits
+    let deadline_secs = u64::from(config.time_limit) * 60 * circuits.len().max(1) as u64;
+    let mut spec = json!({
+        "backoffLimit": config.backoff_limit,
+        "activeDeadlineSeconds": deadline_secs,
+        "template": {
+            "metadata": { "labels": labels },
+            "spec": pod_spec,
+        },
+    });
+    if let Some(ttl) = config.ttl_seconds_after_finished {
+        spec["ttlSecondsAfterFinished"] = json!(ttl);
+    }
+
+    json!({
+        "apiVersion": "batch/v1",
+        "kind": "Job",
+        "metadata": {
+            "name": name,
+            "namespace": config.namespace,
+            "labels": labels,
+            "annotations": { "arvak.io/job-name": job.name },
+        },
+        "spec": spec,
+    })
+}
+
+/// Node affinity requiring a node label of at least the job's `min_qubits`.
+fn qubit_affinity(job: &ScheduledJob, config: &KubernetesConfig) -> Option<Value> {
+    let label = config.qubit_label.as_ref()?;
+    if job.requirements.min_qubits == 0 {
+        return None;
+    }
+    Some(json!({
+        "nodeAffinity": {
+            "requiredDuringSchedulingIgnoredDuringExecution": {
+                "nodeSelectorTerms": [{
+                    "matchExpressions": [{
+                        "key": label,
+                        "operator": "Gt",
+                        "values": [(job.requirements.min_qubits - 1).to_string()],
+                    }],
+                }],
+            },
+        },
+    }))
+}
+
+/// Shell script run by the pod's container.
+fn pod_script(job: &ScheduledJob, config: &KubernetesConfig) -> String {
+    let num_circuits = job.circuits.len();
+    let mut script = String::new();
+
+    script.push_str(&format!("mkdir -p {}\n", SCRATCH_DIR));
+    script.push_str("echo \"Pod: $HOSTNAME\"\n");
+    script.push_str("echo \"Start Time: $(date)\"\n");
+    if let ResultStore::Volume { mount_path, .. } = &config.results {
+        if job.is_batch() {
+            script.push_str(&format!("mkdir -p {}/{}\n", mount_path.display(), job.id));
+        }
+    }
+    script.push_str("FAILED=0\n\n");
+
+    let backend_flag = match job.matched_backend {
+        Some(ref backend) => format!(" --backend {}", backend),
+        None => String::new(),
+    };
+    for i in 0..num_circuits {
+        let circuit_file = format!("{}/circuit_{}.qasm", SCRATCH_DIR, i);
+        let result_file = match &config.results {
+            ResultStore::Volume { mount_path, .. } => {
+                format!("{}/{}", mount_path.display(), result_key(job, i))
+            }
+            ResultStore::ObjectStore { .. } => format!("{}/result_{}.json", SCRATCH_DIR, i),
+        };
+
+        script.push_str(&format!(
+            "printf '%s' \"$ARVAK_CIRCUIT_{}\" > {}\n",
+            i, circuit_file
+        ));
+        script.push_str(&format!(
+            "echo \"Running circuit {} of {} ({})\"\n",
+            i + 1,
+            num_circuits,
+            sanitize_name(&job.circuit_label(i))
+        ));
+        script.push_str(&format!(
+            "if ! {} run {} --shots {}{} --output {}; then\n",
+            config.arvak_binary.display(),
+            circuit_file,
+            job.circuit_shots(i),
+            backend_flag,
+            result_file,
+        ));
+        script.push_str("    echo \"Circuit failed\"\n");
+        script.push_str("    FAILED=$((FAILED + 1))\n");
+        if let ResultStore::ObjectStore {
+            url, token_secret, ..
+        } = &config.results
+        {
+            let auth = if token_secret.is_some() {
+                format!(" -H \"Authorization: Bearer ${}\"", TOKEN_ENV)
+            } else {
+                String::new()
+            };
+            script.push_str(&format!(
+                "elif ! curl -fsS{} --upload-file {} {}/{}; then\n",
+                auth,
+                result_file,
+                url.trim_end_matches('/'),
+                result_key(job, i),
+            ));
+            script.push_str("    echo \"Result upload failed\"\n");
+            script.push_str("    FAILED=$((FAILED + 1))\n");
+        }
+        script.push_str("fi\n\n");
+    }
+
+    script.push_str("echo \"Job completed at: $(date)\"\n");
+    script.push_str("echo \"Failed circuits: $FAILED\"\n");
+    script.push_str("exit $FAILED\n");
+    script
+}
+
+/// Sanitize a label for echoing in the pod script.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, Priority, ResourceRequirements};
+    use std::path::PathBuf;
+
+    fn test_config() -> KubernetesConfig {
+        KubernetesConfig {
+            namespace: "quantum".to_string(),
+            image: "registry.example.org/arvak:0.4".to_string(),
+            image_pull_policy: Some("IfNotPresent".to_string()),
+            image_pull_secrets: vec!["registry".to_string()],
+            service_account: Some("arvak-runner".to_string()),
+            node_selector: vec!["arvak.io/qpu-access=true".to_string()],
+            qubit_label: Some("arvak.io/qubits".to_string()),
+            ttl_seconds_after_finished: Some(3600),
+            priority_class_mapping: Some([(200, "quantum-high".to_string())].into_iter().collect()),
+            results: ResultStore::Volume {
+                claim: "arvak-results".to_string(),
+                mount_path: PathBuf::from("/results"),
+                local_path: PathBuf::from("/mnt/arvak-results"),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_generate_job_manifest() {
+        let config = test_config();
+        let job = ScheduledJob::new(
+            "test job",
+            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"),
+        )
+        .with_shots(1000)
+        .with_priority(Priority::new(200))
+        .with_requirements(ResourceRequirements::new(5));
+        let qasm = vec!["OPENQASM 3.0;\nqubit[2] q;\n".to_string()];
+
+        let manifest = generate_job_manifest(&job, &config, &qasm);
+        assert_eq!(manifest["kind"], "Job");
+        assert_eq!(manifest["metadata"]["name"], job_name(&job));
+        assert_eq!(manifest["metadata"]["namespace"], "quantum");
+        assert_eq!(
+            manifest["metadata"]["labels"][JOB_ID_LABEL],
+            job.id.to_string()
+        );
+        assert_eq!(
+            manifest["metadata"]["annotations"]["arvak.io/job-name"],
+            "test job"
+        );
+
+        let spec = &manifest["spec"];
+        assert_eq!(spec["backoffLimit"], 0);
+        assert_eq!(spec["activeDeadlineSeconds"], 3600);
+        assert_eq!(spec["ttlSecondsAfterFinished"], 3600);
+
+        let pod = &spec["template"]["spec"];
+        assert_eq!(pod["restartPolicy"], "Never");
+        assert_eq!(pod["serviceAccountName"], "arvak-runner");
+        assert_eq!(pod["priorityClassName"], "quantum-high");
+        assert_eq!(pod["imagePullSecrets"][0]["name"], "registry");
+        assert_eq!(pod["nodeSelector"]["arvak.io/qpu-access"], "true");
+        let expr = &pod["affinity"]["nodeAffinity"]["requiredDuringSchedulingIgnoredDuringExecution"]
+            ["nodeSelectorTerms"][0]["matchExpressions"][0];
+        assert_eq!(expr["key"], "arvak.io/qubits");
+        assert_eq!(expr["operator"], "Gt");
+        assert_eq!(expr["values"][0], "4");
+        assert_eq!(
+            pod["volumes"][0]["persistentVolumeClaim"]["claimName"],
+            "arvak-results"
+        );
+
+        let container = &pod["containers"][0];
+        assert_eq!(container["image"], "registry.example.org/arvak:0.4");
+        assert_eq!(container["imagePullPolicy"], "IfNotPresent");
+        assert_eq!(container["resources"]["requests"]["memory"], "4096Mi");
+        assert_eq!(container["env"][0]["name"], "ARVAK_CIRCUIT_0");
+        assert_eq!(container["env"][0]["value"], qasm[0]);
+        assert_eq!(container["volumeMounts"][0]["mountPath"], "/results");
+
+        let script = container["command"][2].as_str().unwrap();
+        assert!(script.contains("printf '%s' \"$ARVAK_CIRCUIT_0\" > /tmp/arvak/circuit_0.qasm"));
+        assert!(script.contains(&format!(
+            "arvak run /tmp/arvak/circuit_0.qasm --shots 1000 --output /results/{}.json",
+            job.id
+        )));
+        assert!(!script.contains("curl"));
+    }
+
+    #[test]
+    fn test_generate_job_manifest_object_store() {
+        let config = KubernetesConfig {
+            results: ResultStore::ObjectStore {
+                url: "https://objects.example.org/arvak/".to_string(),
+                token_secret: Some("arvak-results-token".to_string()),
+                token: None,
+            },
+            ..Default::default()
+        };
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::batch("batch_job", vec![circuit.clone(), circuit.clone(), circuit])
+            .with_requirements(ResourceRequirements::new(0));
+        let qasm = vec![String::new(); 3];
+
+        let manifest = generate_job_manifest(&job, &config, &qasm);
+        let pod = &manifest["spec"]["template"]["spec"];
+        assert_eq!(manifest["spec"]["activeDeadlineSeconds"], 3 * 3600);
+        assert!(pod.get("volumes").is_none());
+        assert!(pod.get("affinity").is_none());
+        assert!(pod.get("priorityClassName").is_none());
+
+        let env = &pod["containers"][0]["env"];
+        assert_eq!(env[3]["name"], "ARVAK_RESULT_TOKEN");
+        assert_eq!(
+            env[3]["valueFrom"]["secretKeyRef"]["name"],
+            "arvak-results-token"
+        );
+
+        let script = pod["containers"][0]["command"][2].as_str().unwrap();
+        assert!(script.contains("Running circuit 3 of 3"));
+        assert!(script.contains(&format!(
+            "elif ! curl -fsS -H \"Authorization: Bearer $ARVAK_RESULT_TOKEN\" --upload-file /tmp/arvak/result_2.json https://objects.example.org/arvak/{}/result_2.json; then",
+            job.id
+        )));
+        assert!(script.ends_with("exit $FAILED\n"));
+    }
+
+    #[test]
+    fn test_result_key() {
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0;");
+        let single = ScheduledJob::new("single", circuit.clone());
+        assert_eq!(result_key(&single, 0), format!("{}.json", single.id));
+
+        let batch = ScheduledJob::batch("batch", vec![circuit.clone(), circuit]);
+        assert_eq!(result_key(&batch, 1), format!("{}/result_1.json", batch.id));
+    }
+}
@@ -0,0 +1,16 @@
+//! Kubernetes integration for cloud and hybrid deployments.
+//!
+//! This module runs each scheduled job as a Kubernetes `Job`, created,
+//! watched and deleted with `kubectl`. Circuits travel inside the Job
+//! manifest; results come back through a shared volume or an HTTP object
+//! store. It mirrors the SLURM, PBS and LSF adapters so the scheduler
+//! treats all of them alike.
+
+mod adapter;
+mod manifest;
+mod parser;
+
+pub use adapter::{
+    KubernetesAdapter, KubernetesConfig, KubernetesJobInfo, KubernetesState, ResultStore,
+};
+pub use manifest::{generate_job_manifest, job_name};
@@ -0,0 +1,260 @@
+//! Parsers for `kubectl` output.
+
+use serde_json::Value;
+
+use crate::error::{SchedError, SchedResult};
+use crate::k8s::adapter::{KubernetesJobInfo, KubernetesState};
+
+/// Container waiting reasons that are part of a normal pod start.
+const STARTING_REASONS: &[&str] = &["ContainerCreating", "PodInitializing"];
+
+/// Parse a Job and its pods, as printed by `kubectl get job -o json` and
+/// `kubectl get pods -o json`.
+///
+/// The Job's `Complete`, `Failed` and `Suspended` conditions decide
+/// terminal and suspended states; otherwise the job runs once one of its
+/// pods does. Pending pods explain why the job has not started yet.
+pub fn parse_job_status(job_json: &str, pods_json: &str) -> SchedResult<KubernetesJobInfo> {
+    let job = parse_json("kubectl get job", job_json)?;
+    let pods = parse_json("kubectl get pods", pods_json)?;
+    let pods = pods["items"].as_array().map(Vec::as_slice).unwrap_or(&[]);
+
+    let name = job["metadata"]["name"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    let condition = |kind: &str| {
+        job["status"]["conditions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|c| c["type"] == kind && c["status"] == "True")
+    };
+
+    let mut info = KubernetesJobInfo {
+        name,
+        state: KubernetesState::Pending,
+        pending_reason: None,
+        failure_reason: None,
+        exit_code: None,
+    };
+
+    if condition("Complete").is_some() {
+        info.state = KubernetesState::Succeeded;
+        info.exit_code = Some(0);
+    } else if let Some(failed) = condition("Failed") {
+        info.state = KubernetesState::Failed;
+        info.exit_code = pods.iter().find_map(terminated_exit_code);
+        info.failure_reason = Some(match pods.iter().find_map(pod_failure) {
+            Some(detail) => format!("{} ({})", describe(failed), detail),
+            None => describe(failed),
+        });
+    } else if condition("Suspended").is_some() || job["spec"]["suspend"] == true {
+        info.state = KubernetesState::Suspended;
+    } else if pods.iter().any(|pod| pod["status"]["phase"] == "Running") {
+        info.state = KubernetesState::Running;
+    } else {
+        info.pending_reason = pods.iter().find_map(pending_reason);
+    }
+
+    Ok(info)
+}
+
+/// Why a pending pod has not started: a container waiting reason such as
+/// `ImagePullBackOff`, or the scheduler's `Unschedulable` message.
+fn pending_reason(pod: &Value) -> Option<String> {
+    if pod["status"]["phase"] != "Pending" {
+        return None;
+    }
+
+    let waiting = pod["status"]["containerStatuses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|status| status["state"]["waiting"].as_object())
+        .find(|waiting| {
+            waiting
+                .get("reason")
+                .and_then(Value::as_str)
+                .is_some_and(|reason| !STARTING_REASONS.contains(&reason))
+        });
+    if let Some(waiting) = waiting {
+        return Some(describe(&Value::Object(waiting.clone())));
+    }
+
+    pod["status"]["conditions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|c| c["type"] == "PodScheduled" && c["status"] == "False")
+        .map(describe)
+}
+
+/// Why a pod's container terminated unsuccessfully, e.g. `OOMKilled`.
+fn pod_failure(pod: &Value) -> Option<String> {
+    pod["status"]["containerStatuses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|status| &status["state"]["terminated"])
+        .find(|terminated| {
+            terminated["exitCode"]
+                .as_i64()
+                .is_some_and(|code| code != 0)
+        })
+        .and_then(|terminated| terminated["reason"].as_str())
+        .map(str::to_string)
+}
+
+fn terminated_exit_code(pod: &Value) -> Option<i32> {
+    pod["status"]["containerStatuses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|status| status["state"]["terminated"]["exitCode"].as_i64())
+        .find(|&code| code != 0)
+        .and_then(|code| i32::try_from(code).ok())
+}
+
+/// `Reason: message` of a condition or container state.
+fn describe(value: &Value) -> String {
+    let reason = value["reason"].as_str().unwrap_or("Unknown");
+    match value["message"].as_str().filter(|m| !m.is_empty()) {
+        Some(message) => format!("{}: {}", reason, message),
+        None => reason.to_string(),
+    }
+}
+
+fn parse_json(command: &str, output: &str) -> SchedResult<Value> {
+    serde_json::from_str(output).map_err(|e| SchedError::KubernetesCommandError {
+        command: command.to_string(),
+        message: format!("Unexpected output: {}", e),
+    })
+}
+
+/// Check `kubectl` stderr for a missing object.
+pub fn is_not_found(stderr: &str) -> bool {
+    stderr.contains("(NotFound)")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn job(status: Value) -> String {
+        json!({ "metadata": { "name": "arvak-1" }, "spec": {}, "status": status }).to_string()
+    }
+
+    fn pods(pods: Vec<Value>) -> String {
+        json!({ "items": pods }).to_string()
+    }
+
+    #[test]
+    fn test_parse_pending_job() {
+        let unschedulable = json!({ "status": {
+            "phase": "Pending",
+            "conditions": [{
+                "type": "PodScheduled",
+                "status": "False",
+                "reason": "Unschedulable",
+                "message": "0/4 nodes are available: 4 Insufficient memory.",
+            }],
+        }});
+        let info =
+            parse_job_status(&job(json!({ "active": 1 })), &pods(vec![unschedulable])).unwrap();
+        assert_eq!(info.name, "arvak-1");
+        assert_eq!(info.state, KubernetesState::Pending);
+        assert_eq!(
+            info.pending_reason.as_deref(),
+            Some("Unschedulable: 0/4 nodes are available: 4 Insufficient memory.")
+        );
+
+        let pulling = json!({ "status": {
+            "phase": "Pending",
+            "containerStatuses": [{ "state": { "waiting": {
+                "reason": "ImagePullBackOff",
+                "message": "Back-off pulling image \"arvak:latest\"",
+            }}}],
+        }});
+        let info = parse_job_status(&job(json!({ "active": 1 })), &pods(vec![pulling])).unwrap();
+        assert_eq!(
+            info.pending_reason.as_deref(),
+            Some("ImagePullBackOff: Back-off pulling image \"arvak:latest\"")
+        );
+
+        let creating = json!({ "status": {
+            "phase": "Pending",
+            "containerStatuses": [{ "state": { "waiting": { "reason": "ContainerCreating" }}}],
+        }});
+        let info = parse_job_status(&job(json!({})), &pods(vec![creating])).unwrap();
+        assert_eq!(info.pending_reason, None);
+
+        // No pods yet
+        let info = parse_job_status(&job(json!({})), &pods(vec![])).unwrap();
+        assert_eq!(info.state, KubernetesState::Pending);
+    }
+
+    #[test]
+    fn test_parse_running_and_finished_job() {
+        let running = json!({ "status": { "phase": "Running" }});
+        let info = parse_job_status(&job(json!({ "active": 1 })), &pods(vec![running])).unwrap();
+        assert_eq!(info.state, KubernetesState::Running);
+
+        let complete = job(json!({
+            "succeeded": 1,
+            "conditions": [{ "type": "Complete", "status": "True" }],
+        }));
+        let info = parse_job_status(&complete, &pods(vec![])).unwrap();
+        assert_eq!(
+            (info.state, info.exit_code),
+            (KubernetesState::Succeeded, Some(0))
+        );
+
+        let failed = job(json!({
+            "failed": 1,
+            "conditions": [{
+                "type": "Failed",
+                "status": "True",
+                "reason": "BackoffLimitExceeded",
+                "message": "Job has reached the specified backoff limit",
+            }],
+        }));
+        let oom = json!({ "status": {
+            "phase": "Failed",
+            "containerStatuses": [{ "state": { "terminated": {
+                "reason": "OOMKilled",
+                "exitCode": 137,
+            }}}],
+        }});
+        let info = parse_job_status(&failed, &pods(vec![oom])).unwrap();
+        assert_eq!(info.state, KubernetesState::Failed);
+        assert_eq!(info.exit_code, Some(137));
+        assert_eq!(
+            info.failure_reason.as_deref(),
+            Some("BackoffLimitExceeded: Job has reached the specified backoff limit (OOMKilled)")
+        );
+    }
+
+    #[test]
+    fn test_parse_suspended_job() {
+        let suspended = json!({
+            "metadata": { "name": "arvak-1" },
+            "spec": { "suspend": true },
+            "status": {},
+        });
+        let info = parse_job_status(&suspended.to_string(), &pods(vec![])).unwrap();
+        assert_eq!(info.state, KubernetesState::Suspended);
+
+        assert!(parse_job_status("error: unknown", &pods(vec![])).is_err());
+    }
+
+    #[test]
+    fn test_is_not_found() {
+        assert!(is_not_found(
+            "Error from server (NotFound): jobs.batch \"arvak-1\" not found"
+        ));
+        assert!(!is_not_found("error: You must be logged in to the server"));
+    }
+}
@@ -1,7 +1,8 @@
-//! Arvak HPC Scheduler for SLURM and PBS Clusters
+//! Arvak HPC Scheduler for SLURM, PBS, LSF and Kubernetes Clusters
 //!
 //! This crate provides enterprise-grade job scheduling for quantum circuits on HPC clusters,
-//! supporting both SLURM and PBS/Torque schedulers with workflow orchestration.
+//! supporting SLURM, PBS/Torque and IBM Spectrum LSF schedulers with workflow orchestration.
+//! Kubernetes clusters can run the same workflows in cloud and hybrid deployments.
 //!
 //! # Overview
 //!
@@ -17,14 +18,53 @@
 //! |-----------|----------|-----------|
 //! | SLURM | sbatch, squeue, sacct, scancel | LUMI (CSC), many others |
 //! | PBS/Torque | qsub, qstat, qdel, qhold | Various |
+//! | LSF | bsub, bjobs, bacct, bkill | Various |
+//! | Kubernetes | kubectl create, get, delete | Cloud clusters |
 //!
 //! # Key Features
 //!
-//! - **Multi-Scheduler**: Unified API for SLURM and PBS
-//! - **Workflows**: DAG-based job dependencies for complex pipelines
-//! - **Persistence**: JSON or SQLite storage for job state
+//! - **Multi-Scheduler**: Unified API for SLURM, PBS, LSF and Kubernetes, or any custom [`BatchSystem`]
+//! - **Workflows**: DAG-based job dependencies for complex pipelines, with progress and ETA
+//! - **Workflow Files**: Workflows defined in YAML or TOML, with errors pointing at the offending line
+//! - **Campaigns**: Workflow node templates expanded over a matrix of experiment parameters
+//! - **Map/Reduce**: Fan a workflow out over a list of inputs and collect the results in one reduce node
+//! - **Loop Nodes**: Part of a workflow re-runs until a convergence check passes, resuming after restarts
+//! - **Sub-Workflows**: A workflow node runs a whole workflow, following its status and cancellation
+//! - **Node Caching**: Re-runs of a workflow reuse the results of nodes whose inputs are unchanged
+//! - **Failure Policies**: Per-node choice to fail the workflow, skip dependents or carry on; failed workflows resume from where they stopped
+//! - **Persistence**: JSON or SQLite storage for job state, with circuit sources stored once by content hash, or Redis with expiring finished state for ephemeral deployments
 //! - **Batch Jobs**: Submit multiple circuits as array jobs
-//! - **Resource Matching**: Automatic backend selection based on circuit requirements
+//! - **Parameter Sweeps**: Many single-circuit jobs submitted as one job array and tracked one by one
+//! - **Job Templates**: Named job definitions kept in the state store and instantiated with overrides
+//! - **Recurring Jobs**: Jobs resubmitted on a cron schedule, with pausing and a bounded run history
+//! - **Reservations**: Backend time windows booked as batch-system reservations, with jobs admitted only if they fit
+//! - **Scheduler Handles**: Cloneable handles to a scheduler run as an actor, shared across tasks without locking
+//! - **Sessions**: Pin a run of jobs to one backend for a bounded time
+//! - **Primitives**: Sampler and Estimator calls batched into jobs, with readout mitigation and shots topped up until a target standard error is met
+//! - **Error Cancellation**: Probabilistic error cancellation from a noise model shared with the simulator, built from a backend's calibration
+//! - **Multi-Circuit Payloads**: Per-circuit shots and result labels, with provenance on each result
+//! - **Batch Packing**: Priority- and deadline-aware choice of which jobs share a batch
+//! - **Fair Share**: Queued jobs reweighted by each user's or project's recent usage against its share
+//! - **Preemption**: Urgent jobs waiting for resources requeue or cancel running lower-priority jobs
+//! - **Backfill**: Optional queue policies that hold or backfill jobs behind one waiting for its backend
+//! - **Deadline Ordering**: Optional earliest-deadline-first queue order for jobs racing a hardware window
+//! - **Resource Matching**: Automatic backend selection based on circuit requirements,
+//!   constraining batch jobs to nodes wired to the matched backend's control hardware
+//! - **Capability Negotiation**: Jobs check their matched backend before dispatch and split or clamp shots to fit
+//! - **High Availability**: Lease-based leader election across scheduler instances
+//! - **Failure Breaker**: Pauses dispatch to backends with a high failure rate
+//! - **Automatic Retries**: Jobs killed by timeouts or node failures are requeued with backoff
+//! - **Result Validation**: Sanity checks on counts that flag suspicious results or fail and re-run the job
+//! - **Compile Cache**: Optional compile-on-submit stage reusing earlier compilations
+//! - **Calibration Ingestion**: Daily JSON or CSV calibration files stored as versioned epochs per backend, feeding matching and compilation
+//! - **Cloud QPUs**: Jobs matched to a cloud backend can bypass the batch system
+//! - **Decision Replay**: Scheduling decisions logged as events and replayed for postmortems
+//! - **Status Events**: Per-job status streams, fed by job event files instead of polling every job
+//! - **Partial Results**: Long jobs report count snapshots that subscribers receive as they land
+//! - **Log Tailing**: Follow a running job's stdout and stderr line by line
+//! - **Admission Info**: Submissions report queue depth, estimated start and admission class
+//! - **Queue Explanations**: Why a job is still queued, from queue position to batch system holds
+//! - **Site Hooks**: Custom logic before submission and after completion or failure
 //!
 //! # Example: Single Job Submission
 //!
@@ -77,6 +117,23 @@
 //! let workflow_id = scheduler.submit_workflow(workflow).await?;
 //! ```
 //!
+//! # Example: Hybrid Workflow with Classical Tasks
+//!
+//! ```ignore
+//! use arvak_sched::{ClassicalTask, ScheduledJob, WorkflowBuilder};
+//!
+//! scheduler.register_task("postprocess", |inputs| async move {
+//!     let counts = &inputs.by_name("sample").unwrap().counts;
+//!     Ok(serde_json::json!({ "distinct": counts.len() }))
+//! });
+//!
+//! let workflow = WorkflowBuilder::new("hybrid")
+//!     .add_job(ScheduledJob::new("sample", circuit))
+//!     .then(ScheduledJob::classical("postprocess", ClassicalTask::closure("postprocess")))?
+//!     .then(ScheduledJob::classical("report", ClassicalTask::script("/opt/report.py")))?
+//!     .build();
+//! ```
+//!
 //! # Example: PBS Configuration
 //!
 //! ```ignore
@@ -95,39 +152,160 @@
 //! Job state can be persisted for recovery and auditing:
 //!
 //! ```ignore
-//! use arvak_sched::{JsonStore, SqliteStore, StateStore};
+//! use arvak_sched::{JsonStore, RedisConfig, RedisStore, SqliteStore, StateStore};
 //!
 //! // JSON file storage (simple, portable)
 //! let store = JsonStore::new("./jobs.json")?;
 //!
 //! // SQLite database (queryable, efficient)
 //! let store = SqliteStore::new("./jobs.db").await?;
+//!
+//! // Redis with finished state expiring (ephemeral CI and demo deployments)
+//! let config = RedisConfig::new("redis://cache:6379").with_ttl(Some(Duration::from_secs(3600)));
+//! let store = RedisStore::connect(config).await?;
+//!
+//! // Archive jobs older than 30 days to compressed bundles, rehydrating on access
+//! let archive = FilesystemArchive::new("/archive/arvak").await?;
+//! // or: HttpArchive::new("https://archive.example.org/arvak")?.with_token(token)
+//! let store = ArchivingStore::new(Arc::new(store), Arc::new(archive), ArchivePolicy::older_than_days(30));
+//! store.archive_old_jobs().await?;
 //! ```
+//!
+//! With `write_ahead_log` set in the config, the scheduler also logs every
+//! dispatch to `<state_dir>/scheduler.wal` before taking it, and on startup
+//! [`HpcScheduler::recover`] adopts jobs that reached the batch system just
+//! before a crash instead of submitting them twice.
 
+pub mod acl;
+pub mod admission;
+pub mod array;
+pub mod batch;
+pub mod breaker;
 pub mod broker;
+pub mod cache;
+pub mod calibration;
+pub mod campaign;
+pub mod cloud;
+pub mod compile;
+pub mod config;
+pub mod definition;
 pub mod error;
+pub mod events;
+pub mod explain;
+pub mod fairshare;
+pub mod gc;
+pub mod handle;
+pub mod hooks;
+pub mod id;
+pub mod iteration;
 pub mod job;
+pub mod k8s;
+pub mod leader;
+pub mod lineage;
+pub mod logs;
+pub mod lsf;
 pub mod matcher;
+pub mod negotiate;
+pub mod notify;
+pub mod packing;
+pub mod partial;
+pub mod payload;
 pub mod pbs;
+pub mod pec;
 pub mod persistence;
+pub mod preempt;
+pub mod primitives;
 pub mod queue;
+pub mod recurring;
+pub mod reload;
+pub mod replay;
+pub mod reservation;
+pub mod retry;
 pub mod router;
 pub mod scheduler;
+pub mod session;
 pub mod slurm;
+pub mod split;
+pub mod task;
+pub mod template;
+pub mod validate;
+pub mod verify;
 pub mod workflow;
 
 // Re-exports
+pub use acl::{
+    AccessPolicy, AuditEvent, AuditLog, Delegation, InMemoryAuditLog, JobAction, JsonlAuditLog,
+    TracingAuditLog,
+};
+pub use admission::{Admission, AdmissionClass};
+pub use array::JobArraySpec;
+pub use batch::BatchSystem;
+pub use breaker::{BreakerConfig, BreakerEvent, BreakerState, FailureBreaker};
 pub use broker::{InMemoryBroker, JobMessage, MessageBroker, MessageSubscription};
+pub use cache::node_key;
+pub use calibration::{BackendCalibration, CalibrationFormat, GateProperties, QubitProperties};
+pub use campaign::Campaign;
+pub use cloud::{CLOUD_JOB_ID, CloudQpuAdapter};
+pub use compile::{CompileConfig, CompileStage};
+pub use definition::{
+    DefinitionFormat, NodeDefinition, PriorityDefinition, ResourcesDefinition, WorkflowDefinition,
+};
 pub use error::{SchedError, SchedResult};
+pub use events::{
+    EventKind, EventLog, InMemoryEventLog, JsonlEventLog, NullEventLog, SchedulerEvent,
+};
+pub use explain::{
+    AheadReason, BackendAvailability, BatchHoldKind, Hold, JobAhead, QueueExplanation,
+};
+pub use fairshare::{FairShareConfig, FairSharePolicy, PROJECT_METADATA_KEY, ShareGroup};
+pub use gc::{GcReport, JobArtifacts, RetentionPolicy, collect_garbage};
+pub use handle::{DEFAULT_COMMAND_BUFFER, SchedulerHandle};
+pub use hooks::{HookErrorPolicy, HookPoint, HookRegistry, SchedulerHook};
+pub use id::{ParseIdError, ResolvedId, Ulid};
+pub use iteration::IterationRecord;
 pub use job::{
-    CircuitSpec, JobFilter, Priority, ResourceRequirements, ScheduledJob, ScheduledJobId,
-    ScheduledJobStatus, TopologyPreference,
+    CircuitSpec, DependencyKind, DependencyState, JobFilter, Priority, ResourceRequirements,
+    ScheduledJob, ScheduledJobId, ScheduledJobStatus, TaskOverride, TopologyPreference,
 };
+pub use k8s::{KubernetesAdapter, KubernetesConfig};
+pub use leader::{InMemoryLeaseStore, LeaderElector, LeaseInfo, LeaseStore};
+pub use lineage::JobLineage;
+pub use logs::{LogLine, LogStream, LogTail};
+pub use lsf::{LsfAdapter, LsfConfig};
 pub use matcher::{MatchResult, ResourceMatcher};
+pub use negotiate::{CapabilityReport, CapabilityRequest, Negotiation, ShotPolicy};
+pub use notify::{AdaptivePoll, CompletionWatcher, StatusUpdate};
+pub use packing::{BatchPacker, BatchPlan, PackItem, PackingConfig, PlannedBatch};
+pub use partial::ResultUpdate;
+pub use payload::{CircuitProvenance, CircuitResult};
 pub use pbs::{PbsAdapter, PbsConfig};
-pub use persistence::{JsonStore, SqliteStore, StateStore};
-pub use queue::PriorityQueue;
+pub use pec::{PecSample, PecSampler};
+pub use persistence::{
+    ArchiveBackend, ArchivePolicy, ArchivingStore, BlobFormat, FilesystemArchive, HttpArchive,
+    JsonStore, RecoveryReport, RedisConfig, RedisStore, SqliteStore, StateStore, WalOp, WalRecord,
+    WriteAheadLog,
+};
+pub use preempt::{PreemptionConfig, PreemptionPolicy};
+pub use primitives::{
+    Estimator, EstimatorJob, EstimatorResult, Mitigation, Observable, PauliTerm, PrimitiveOptions,
+    QuasiDistribution, ReadoutCalibration, Sampler, SamplerJob, SamplerResult,
+};
+pub use queue::{DispatchGate, PriorityQueue, QueuePolicy};
+pub use recurring::{CronSchedule, RECURRING_METADATA_KEY, RecurringJob, RecurringRun};
+pub use reload::{ConfigChange, SchedulerConfigUpdate};
+pub use replay::{DecisionStep, DecisionTrace, JobReplay, ReplayFilter, replay};
+pub use reservation::{Reservation, TimeWindow};
+pub use retry::{Attempt, Backoff, FailureKind, RetryPolicy};
 pub use router::{JobRouter, RouteTarget, RoutingRules};
 pub use scheduler::{BatchSchedulerType, HpcScheduler, Scheduler, SchedulerConfig};
-pub use slurm::{SlurmAdapter, SlurmConfig};
-pub use workflow::{Workflow, WorkflowBuilder, WorkflowId, WorkflowStatus};
+pub use session::{SESSION_METADATA_KEY, Session, parse_max_time};
+pub use slurm::{SlurmAdapter, SlurmConfig, SshConfig, SshTransport};
+pub use split::SplitCircuit;
+pub use task::{ClassicalTask, TaskInput, TaskInputs, TaskRegistry};
+pub use template::{JobTemplate, TEMPLATE_METADATA_KEY, TemplateOverrides};
+pub use validate::{ResultValidation, ValidationAction, ValidationRule, Violation};
+pub use verify::{ResultMetric, ResultVerification, VerificationReport};
+pub use workflow::{
+    FailurePolicy, LoopDecision, LoopNode, SubWorkflow, Workflow, WorkflowBuilder, WorkflowId,
+    WorkflowProgress, WorkflowStatus,
+};
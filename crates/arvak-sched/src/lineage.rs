@@ -0,0 +1,119 @@
+//! Experiment lineage between parent and derived jobs.
+//!
+//! Error mitigation and zero-noise extrapolation spawn derived jobs from an
+//! original experiment. Each derived job records its parent and how it was
+//! derived, so the full family of an experiment can be reconstructed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::ScheduledJobId;
+use crate::persistence::StateStore;
+
+/// Lineage of a job: where it came from and what was derived from it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobLineage {
+    /// The job described.
+    pub job_id: ScheduledJobId,
+
+    /// How the job was derived from its parent, if it was.
+    pub derivation: Option<String>,
+
+    /// Ancestors, nearest first; the last entry is the root experiment.
+    pub ancestors: Vec<ScheduledJobId>,
+
+    /// Jobs directly derived from this job.
+    pub children: Vec<ScheduledJobId>,
+}
+
+impl JobLineage {
+    /// Load the lineage of a job from a store.
+    pub async fn load(store: &dyn StateStore, job_id: &ScheduledJobId) -> SchedResult<Self> {
+        let job = store
+            .load_job(job_id)
+            .await?
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+
+        let mut ancestors = Vec::new();
+        let mut next = job.parent.clone();
+        while let Some(parent_id) = next {
+            // Guard against corrupted records forming a cycle.
+            if parent_id == *job_id || ancestors.contains(&parent_id) {
+                break;
+            }
+            next = store.load_job(&parent_id).await?.and_then(|p| p.parent);
+            ancestors.push(parent_id);
+        }
+
+        let children = store
+            .list_children(job_id)
+            .await?
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+
+        Ok(Self {
+            job_id: job_id.clone(),
+            derivation: job.derivation,
+            ancestors,
+            children,
+        })
+    }
+
+    /// Get the direct parent, if any.
+    pub fn parent(&self) -> Option<&ScheduledJobId> {
+        self.ancestors.first()
+    }
+
+    /// Get the root experiment of the family (the job itself if underived).
+    pub fn root(&self) -> &ScheduledJobId {
+        self.ancestors.last().unwrap_or(&self.job_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, ScheduledJob};
+    use crate::persistence::{JsonStore, SqliteStore};
+
+    async fn check_lineage(store: &dyn StateStore) {
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0;");
+        let root = ScheduledJob::new("vqe", circuit.clone());
+        let scaled = ScheduledJob::new("vqe_zne_3", circuit.clone())
+            .derived_from(root.id.clone(), "zne:scale=3");
+        let twirled = ScheduledJob::new("vqe_zne_3_twirl", circuit.clone())
+            .derived_from(scaled.id.clone(), "twirl:0");
+        let unrelated = ScheduledJob::new("other", circuit);
+        for job in [&root, &scaled, &twirled, &unrelated] {
+            store.save_job(job).await.unwrap();
+        }
+
+        let children = store.list_children(&root.id).await.unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, scaled.id);
+
+        let lineage = JobLineage::load(store, &twirled.id).await.unwrap();
+        assert_eq!(lineage.derivation.as_deref(), Some("twirl:0"));
+        assert_eq!(lineage.parent(), Some(&scaled.id));
+        assert_eq!(lineage.root(), &root.id);
+        assert!(lineage.children.is_empty());
+
+        let lineage = JobLineage::load(store, &root.id).await.unwrap();
+        assert!(lineage.ancestors.is_empty());
+        assert_eq!(lineage.children, vec![scaled.id.clone()]);
+    }
+
+    #[tokio::test]
+    async fn test_lineage_sqlite() {
+        let store = SqliteStore::in_memory().unwrap();
+        check_lineage(&store).await;
+    }
+
+    #[tokio::test]
+    async fn test_lineage_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JsonStore::new(dir.path()).await.unwrap();
+        check_lineage(&store).await;
+    }
+}
@@ -0,0 +1,224 @@
+//! Tailing the output of batch jobs.
+//!
+//! Batch systems write each job's stdout and stderr to files in their work
+//! directory, named by [`BatchSystem::log_paths`](crate::BatchSystem::log_paths).
+//! A [`LogTail`] reads the lines appended to them since its last read, so
+//! callers such as the dashboard can follow a job's output while it runs.
+//! Files that do not exist yet, e.g. while the job is queued, read as empty.
+
+use std::io::SeekFrom;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::error::SchedResult;
+
+/// Most bytes read from one file per [`LogTail::read`].
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+
+/// Which output of a job a log file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    /// Standard output.
+    Stdout,
+
+    /// Standard error.
+    Stderr,
+}
+
+impl std::fmt::Display for LogStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogStream::Stdout => write!(f, "stdout"),
+            LogStream::Stderr => write!(f, "stderr"),
+        }
+    }
+}
+
+/// One line of a job's output, without its line terminator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLine {
+    /// Output the line was written to.
+    pub stream: LogStream,
+
+    /// Text of the line; invalid UTF-8 is replaced.
+    pub text: String,
+}
+
+/// A log file and how far it has been read.
+#[derive(Debug)]
+struct TailedFile {
+    stream: LogStream,
+    path: PathBuf,
+    offset: u64,
+    started: bool,
+    pending: Vec<u8>,
+}
+
+/// Follows the log files of one job.
+#[derive(Debug)]
+pub struct LogTail {
+    files: Vec<TailedFile>,
+    backlog: Option<u64>,
+}
+
+impl LogTail {
+    /// Create a tail reading each file from its start.
+    pub fn new(files: Vec<(LogStream, PathBuf)>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|(stream, path)| TailedFile {
+                    stream,
+                    path,
+                    offset: 0,
+                    started: false,
+                    pending: Vec::new(),
+                })
+                .collect(),
+            backlog: None,
+        }
+    }
+
+    /// Start each file at most `max_bytes` before its end as first read,
+    /// skipping the line cut in half.
+    pub fn with_backlog(mut self, max_bytes: u64) -> Self {
+        self.backlog = Some(max_bytes);
+        self
+    }
+
+    /// Check if the job has no log files to follow.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Paths of the followed files.
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().map(|file| &file.path)
+    }
+
+    /// Read the complete lines appended since the last read.
+    ///
+    /// A file that shrank, e.g. because it was truncated when the job was
+    /// requeued, is read again from its start.
+    pub async fn read(&mut self) -> SchedResult<Vec<LogLine>> {
+        let mut lines = Vec::new();
+        for file in &mut self.files {
+            let mut handle = match tokio::fs::File::open(&file.path).await {
+                Ok(handle) => handle,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let len = handle.metadata().await?.len();
+            let mut skip_partial = false;
+            if !file.started {
+                file.started = true;
+                if let Some(backlog) = self.backlog.filter(|b| len > *b) {
+                    file.offset = len - backlog;
+                    skip_partial = true;
+                }
+            } else if len < file.offset {
+                file.offset = 0;
+                file.pending.clear();
+            }
+            if len == file.offset {
+                continue;
+            }
+
+            handle.seek(SeekFrom::Start(file.offset)).await?;
+            let mut buf = Vec::new();
+            handle.take(MAX_READ_BYTES).read_to_end(&mut buf).await?;
+            file.offset += buf.len() as u64;
+            if skip_partial {
+                match buf.iter().position(|b| *b == b'\n') {
+                    Some(end) => {
+                        buf.drain(..=end);
+                    }
+                    None => buf.clear(),
+                }
+            }
+            file.pending.extend_from_slice(&buf);
+
+            let Some(end) = file.pending.iter().rposition(|b| *b == b'\n') else {
+                continue;
+            };
+            let complete: Vec<u8> = file.pending.drain(..=end).collect();
+            lines.extend(
+                complete[..complete.len() - 1]
+                    .split(|b| *b == b'\n')
+                    .map(|line| LogLine {
+                        stream: file.stream,
+                        text: String::from_utf8_lossy(line)
+                            .trim_end_matches('\r')
+                            .to_string(),
+                    }),
+            );
+        }
+        Ok(lines)
+    }
+
+    /// Take the unterminated last line of each file, e.g. once the job has
+    /// finished.
+    pub fn flush(&mut self) -> Vec<LogLine> {
+        self.files
+            .iter_mut()
+            .filter(|file| !file.pending.is_empty())
+            .map(|file| LogLine {
+                stream: file.stream,
+                text: String::from_utf8_lossy(&std::mem::take(&mut file.pending))
+                    .trim_end_matches('\r')
+                    .to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    fn texts(lines: &[LogLine]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_tail_follows_appended_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("slurm-1.out");
+        let mut tail = LogTail::new(vec![
+            (LogStream::Stdout, out.clone()),
+            (LogStream::Stderr, dir.path().join("slurm-1.err")),
+        ]);
+        assert!(tail.read().await.unwrap().is_empty());
+
+        let mut file = tokio::fs::File::create(&out).await.unwrap();
+        file.write_all(b"first\r\nsec").await.unwrap();
+        assert_eq!(texts(&tail.read().await.unwrap()), ["first"]);
+
+        file.write_all(b"ond\nthird").await.unwrap();
+        let lines = tail.read().await.unwrap();
+        assert_eq!(texts(&lines), ["second"]);
+        assert_eq!(lines[0].stream, LogStream::Stdout);
+        assert_eq!(texts(&tail.flush()), ["third"]);
+        assert!(tail.flush().is_empty());
+
+        // A truncated file is read again from its start.
+        tokio::fs::write(&out, "requeued\n").await.unwrap();
+        assert_eq!(texts(&tail.read().await.unwrap()), ["requeued"]);
+    }
+
+    #[tokio::test]
+    async fn test_tail_backlog_skips_cut_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("slurm-1.out");
+        tokio::fs::write(&out, "line one\nline two\nline three\n")
+            .await
+            .unwrap();
+
+        let mut tail = LogTail::new(vec![(LogStream::Stdout, out)]).with_backlog(15);
+        assert_eq!(texts(&tail.read().await.unwrap()), ["line three"]);
+    }
+}
@@ -0,0 +1,553 @@
+//! LSF adapter for job submission and tracking.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use tokio::fs;
+use tokio::process::Command;
+
+use crate::batch::BatchSystem;
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobStatus};
+use crate::lsf::parser;
+use crate::lsf::templates;
+use crate::payload::{self, CircuitResult};
+use crate::retry::FailureKind;
+
+/// LSF job state, as reported in the `STAT` column of `bjobs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LsfState {
+    /// Job is pending in the queue.
+    Pending,
+    /// Job was suspended by its owner or an administrator while pending.
+    PendingSuspended,
+    /// Job is running.
+    Running,
+    /// Job was suspended by its owner or an administrator while running.
+    UserSuspended,
+    /// Job was suspended by LSF, e.g. because its host is overloaded.
+    SystemSuspended,
+    /// Job finished with exit code 0.
+    Done,
+    /// Job finished with a nonzero exit code or was killed.
+    Exit,
+    /// Chunk job member waiting for its chunk to run.
+    Waiting,
+    /// Job was killed but LSF could not confirm it is gone.
+    Zombie,
+    /// The LSF daemon lost contact with the job's host.
+    Unknown(String),
+}
+
+impl LsfState {
+    /// Check if this is a terminal state.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, LsfState::Done | LsfState::Exit | LsfState::Zombie)
+    }
+
+    /// Check if this represents a successful completion.
+    pub fn is_success(&self) -> bool {
+        matches!(self, LsfState::Done)
+    }
+
+    /// Convert to the `bjobs` state name.
+    pub fn to_code(&self) -> &'static str {
+        match self {
+            LsfState::Pending => "PEND",
+            LsfState::PendingSuspended => "PSUSP",
+            LsfState::Running => "RUN",
+            LsfState::UserSuspended => "USUSP",
+            LsfState::SystemSuspended => "SSUSP",
+            LsfState::Done => "DONE",
+            LsfState::Exit => "EXIT",
+            LsfState::Waiting => "WAIT",
+            LsfState::Zombie => "ZOMBI",
+            LsfState::Unknown(_) => "UNKWN",
+        }
+    }
+}
+
+/// Information about an LSF job.
+#[derive(Debug, Clone)]
+pub struct LsfJobInfo {
+    /// LSF job ID (e.g., "12345").
+    pub job_id: String,
+
+    /// Job name.
+    pub name: String,
+
+    /// Current state.
+    pub state: LsfState,
+
+    /// Queue the job is in.
+    pub queue: Option<String>,
+
+    /// Why a pending job has not started (e.g., "Job's requirements for
+    /// resource reservation not satisfied").
+    pub pending_reason: Option<String>,
+
+    /// Exit code (for finished jobs).
+    pub exit_code: Option<i32>,
+}
+
+/// Configuration for LSF adapter.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LsfConfig {
+    /// LSF queue to submit to.
+    pub queue: String,
+
+    /// LSF project for accounting (`-P`).
+    pub project: Option<String>,
+
+    /// Run limit in minutes (`-W`).
+    pub time_limit: u32,
+
+    /// Memory to reserve in MB (`rusage[mem=...]`).
+    pub memory_mb: u32,
+
+    /// Number of cores (`-n`).
+    pub cores: u32,
+
+    /// Keep all cores on one host (`span[hosts=1]`).
+    pub single_host: bool,
+
+    /// Numeric host resource advertising the qubits reachable from a host.
+    /// When set, jobs select hosts with at least the qubits they need.
+    pub qubit_resource: Option<String>,
+
+    /// Extra host selection expression ANDed into `select[...]`, e.g.
+    /// `"qpu_access"`.
+    pub select: Option<String>,
+
+    /// Working directory for job files.
+    pub work_dir: PathBuf,
+
+    /// Path to the Arvak binary.
+    pub arvak_binary: PathBuf,
+
+    /// Modules to load before running.
+    pub modules: Vec<String>,
+
+    /// Python virtual environment path.
+    pub python_venv: Option<PathBuf>,
+
+    /// Additional `#BSUB` directives.
+    pub extra_directives: Vec<String>,
+
+    /// Mapping from priority value to LSF queue names.
+    #[serde(deserialize_with = "crate::config::priority_mapping")]
+    pub priority_queue_mapping: Option<rustc_hash::FxHashMap<u32, String>>,
+}
+
+impl Default for LsfConfig {
+    fn default() -> Self {
+        Self {
+            queue: "normal".to_string(),
+            project: None,
+            time_limit: 60,
+            memory_mb: 4096,
+            cores: 1,
+            single_host: true,
+            qubit_resource: None,
+            select: None,
+            work_dir: PathBuf::from("/tmp/arvak-jobs"),
+            arvak_binary: PathBuf::from("arvak"),
+            modules: Vec::new(),
+            python_venv: None,
+            extra_directives: Vec::new(),
+            priority_queue_mapping: None,
+        }
+    }
+}
+
+/// Adapter for the LSF HPC scheduler.
+pub struct LsfAdapter {
+    config: LsfConfig,
+    /// Whether to use mock mode (for testing).
+    mock_mode: bool,
+    /// Mock job counter for generating fake job IDs.
+    mock_counter: std::sync::atomic::AtomicU64,
+}
+
+impl LsfAdapter {
+    /// Create a new LSF adapter with the given configuration.
+    pub async fn new(config: LsfConfig) -> SchedResult<Self> {
+        // Ensure work directory exists
+        fs::create_dir_all(&config.work_dir).await?;
+        fs::create_dir_all(config.work_dir.join("scripts")).await?;
+        fs::create_dir_all(config.work_dir.join("circuits")).await?;
+        fs::create_dir_all(config.work_dir.join("results")).await?;
+
+        Ok(Self {
+            config,
+            mock_mode: false,
+            mock_counter: std::sync::atomic::AtomicU64::new(1000),
+        })
+    }
+
+    /// Create a new LSF adapter in mock mode (for testing).
+    pub fn mock(config: LsfConfig) -> Self {
+        Self {
+            config,
+            mock_mode: true,
+            mock_counter: std::sync::atomic::AtomicU64::new(1000),
+        }
+    }
+
+    /// Get the configuration.
+    pub fn config(&self) -> &LsfConfig {
+        &self.config
+    }
+
+    /// Submit a job to LSF.
+    pub async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+        // In mock mode, skip file I/O
+        if self.mock_mode {
+            let job_id = self
+                .mock_counter
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            return Ok(job_id.to_string());
+        }
+
+        // Write circuit(s) to file
+        let circuit_files = self.write_circuits(job).await?;
+
+        // Generate batch script
+        let script = if circuit_files.len() == 1 {
+            let result_file = self
+                .config
+                .work_dir
+                .join("results")
+                .join(format!("{}.json", job.id));
+            templates::generate_lsf_script(job, &self.config, &circuit_files[0], &result_file)
+        } else {
+            let result_dir = self
+                .config
+                .work_dir
+                .join("results")
+                .join(job.id.to_string());
+            let circuit_refs: Vec<&Path> = circuit_files.iter().map(|p| p.as_path()).collect();
+            templates::generate_lsf_script_multi(job, &self.config, &circuit_refs, &result_dir)
+        };
+
+        // Write batch script
+        let script_path = self
+            .config
+            .work_dir
+            .join("scripts")
+            .join(format!("{}.lsf", job.id));
+        fs::write(&script_path, &script).await?;
+
+        // Submit via bsub
+        self.run_bsub(&script_path).await
+    }
+
+    /// Get the status of an LSF job.
+    pub async fn status(&self, lsf_job_id: &str) -> SchedResult<LsfJobInfo> {
+        if self.mock_mode {
+            return Ok(LsfJobInfo {
+                job_id: lsf_job_id.to_string(),
+                name: "mock_job".to_string(),
+                state: LsfState::Done,
+                queue: Some(self.config.queue.clone()),
+                pending_reason: None,
+                exit_code: Some(0),
+            });
+        }
+
+        // bjobs knows active jobs and recently finished ones
+        if let Some(info) = self.run_bjobs(lsf_job_id).await? {
+            return Ok(info);
+        }
+
+        // Older jobs are only in the accounting log
+        if let Some(info) = self.run_bacct(lsf_job_id).await? {
+            return Ok(info);
+        }
+
+        Err(SchedError::LsfJobNotFound(lsf_job_id.to_string()))
+    }
+
+    /// Cancel an LSF job.
+    pub async fn cancel(&self, lsf_job_id: &str) -> SchedResult<()> {
+        if self.mock_mode {
+            return Ok(());
+        }
+
+        let output = Command::new("bkill")
+            .arg(lsf_job_id)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| SchedError::LsfCommandError {
+                command: "bkill".to_string(),
+                message: e.to_string(),
+            })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        parser::parse_bkill_output(&stdout, &stderr)
+    }
+
+    /// Get the result file path for a job.
+    pub fn result_path(&self, job: &ScheduledJob) -> PathBuf {
+        if job.is_batch() {
+            self.config
+                .work_dir
+                .join("results")
+                .join(job.id.to_string())
+        } else {
+            self.config
+                .work_dir
+                .join("results")
+                .join(format!("{}.json", job.id))
+        }
+    }
+
+    /// Read the per-circuit results written by a completed job.
+    pub async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        if self.mock_mode {
+            return Ok(Vec::new());
+        }
+        payload::read_results(job, &self.result_path(job)).await
+    }
+
+    /// Write circuit files for a job.
+    async fn write_circuits(&self, job: &ScheduledJob) -> SchedResult<Vec<PathBuf>> {
+        let mut paths = Vec::with_capacity(job.circuits.len());
+
+        for (i, spec) in job.circuits.iter().enumerate() {
+            let circuit = spec.resolve()?;
+            let qasm = arvak_qasm3::emit(&circuit)?;
+
+            let filename = if job.circuits.len() == 1 {
+                format!("{}.qasm", job.id)
+            } else {
+                format!("{}_{}.qasm", job.id, i)
+            };
+
+            let path = self.config.work_dir.join("circuits").join(filename);
+            fs::write(&path, qasm).await?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+
+    /// Run bsub with the script on stdin, so LSF reads its `#BSUB` lines.
+    async fn run_bsub(&self, script_path: &Path) -> SchedResult<String> {
+        let script = std::fs::File::open(script_path)?;
+        let output = Command::new("bsub")
+            .stdin(Stdio::from(script))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| SchedError::LsfCommandError {
+                command: "bsub".to_string(),
+                message: e.to_string(),
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(SchedError::LsfSubmitError(stderr.to_string()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parser::parse_bsub_output(&stdout)
+    }
+
+    /// Run bjobs to get the status of an active or recently finished job.
+    async fn run_bjobs(&self, lsf_job_id: &str) -> SchedResult<Option<LsfJobInfo>> {
+        let output = Command::new("bjobs")
+            .args(["-a", "-noheader", "-o", parser::BJOBS_FORMAT, lsf_job_id])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| SchedError::LsfCommandError {
+                command: "bjobs".to_string(),
+                message: e.to_string(),
+            })?;
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("is not found") {
+            return Ok(None);
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parser::parse_bjobs_output(&stdout)
+    }
+
+    /// Run bacct to get the outcome of a job bjobs no longer reports.
+    async fn run_bacct(&self, lsf_job_id: &str) -> SchedResult<Option<LsfJobInfo>> {
+        let output = Command::new("bacct")
+            .args(["-l", lsf_job_id])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .output()
+            .await
+            .map_err(|e| SchedError::LsfCommandError {
+                command: "bacct".to_string(),
+                message: e.to_string(),
+            })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parser::parse_bacct_output(&stdout)
+    }
+}
+
+#[async_trait]
+impl BatchSystem for LsfAdapter {
+    fn name(&self) -> &str {
+        "LSF"
+    }
+
+    async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+        LsfAdapter::submit(self, job).await
+    }
+
+    async fn poll(
+        &self,
+        job: &ScheduledJob,
+        batch_job_id: &str,
+    ) -> SchedResult<ScheduledJobStatus> {
+        let info = self.status(batch_job_id).await?;
+        Ok(map_state(job, info))
+    }
+
+    async fn cancel(&self, batch_job_id: &str) -> SchedResult<()> {
+        LsfAdapter::cancel(self, batch_job_id).await
+    }
+
+    async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        LsfAdapter::read_results(self, job).await
+    }
+
+    async fn pending_reason(&self, batch_job_id: &str) -> SchedResult<Option<String>> {
+        let info = self.status(batch_job_id).await?;
+        Ok(match info.state {
+            // Named like SLURM's hold reasons so explanations classify it.
+            LsfState::PendingSuspended => Some("JobHeld".to_string()),
+            LsfState::Pending | LsfState::Waiting => info.pending_reason,
+            _ => None,
+        })
+    }
+}
+
+/// Map an LSF job state to the status of the job it runs.
+fn map_state(job: &ScheduledJob, info: LsfJobInfo) -> ScheduledJobStatus {
+    let lsf_job_id = info.job_id;
+
+    match info.state {
+        LsfState::Pending | LsfState::PendingSuspended | LsfState::Waiting => {
+            ScheduledJobStatus::SlurmQueued {
+                slurm_job_id: lsf_job_id,
+            }
+        }
+        LsfState::Running => ScheduledJobStatus::SlurmRunning {
+            slurm_job_id: lsf_job_id,
+        },
+        LsfState::Done => ScheduledJobStatus::Completed {
+            slurm_job_id: lsf_job_id,
+            quantum_job_id: arvak_hal::JobId("completed".to_string()),
+        },
+        LsfState::Exit => ScheduledJobStatus::Failed {
+            reason: match info.exit_code {
+                Some(code) => format!("LSF job failed with exit code {}", code),
+                None => "LSF job was killed".to_string(),
+            },
+            kind: FailureKind::Application,
+            slurm_job_id: Some(lsf_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        LsfState::Zombie => ScheduledJobStatus::Failed {
+            reason: "LSF job was killed".to_string(),
+            kind: FailureKind::Application,
+            slurm_job_id: Some(lsf_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        LsfState::UserSuspended | LsfState::SystemSuspended => {
+            // Keep current status for suspended jobs
+            job.status.clone()
+        }
+        LsfState::Unknown(state) => {
+            tracing::warn!("Unknown LSF state: {}", state);
+            job.status.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, Priority};
+
+    #[tokio::test]
+    async fn test_mock_lsf_adapter() {
+        let adapter = LsfAdapter::mock(LsfConfig::default());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job = ScheduledJob::new("test_job", circuit).with_priority(Priority::default());
+
+        let lsf_job_id = adapter.submit(&job).await.unwrap();
+        assert_eq!(lsf_job_id, "1000");
+
+        let info = adapter.status(&lsf_job_id).await.unwrap();
+        assert_eq!(info.job_id, lsf_job_id);
+        assert!(info.state.is_success());
+
+        adapter.cancel(&lsf_job_id).await.unwrap();
+    }
+
+    #[test]
+    fn test_lsf_state() {
+        assert!(LsfState::Done.is_terminal());
+        assert!(LsfState::Exit.is_terminal());
+        assert!(!LsfState::Pending.is_terminal());
+        assert!(!LsfState::SystemSuspended.is_terminal());
+
+        assert!(LsfState::Done.is_success());
+        assert!(!LsfState::Exit.is_success());
+        assert_eq!(LsfState::PendingSuspended.to_code(), "PSUSP");
+    }
+
+    #[test]
+    fn test_map_lsf_state() {
+        let job = ScheduledJob::new("test_job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let info = |state, exit_code| LsfJobInfo {
+            job_id: "42".to_string(),
+            name: "test_job".to_string(),
+            state,
+            queue: None,
+            pending_reason: None,
+            exit_code,
+        };
+
+        assert!(matches!(
+            map_state(&job, info(LsfState::PendingSuspended, None)),
+            ScheduledJobStatus::SlurmQueued { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(LsfState::Running, None)),
+            ScheduledJobStatus::SlurmRunning { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(LsfState::Done, Some(0))),
+            ScheduledJobStatus::Completed { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(LsfState::Exit, Some(2))),
+            ScheduledJobStatus::Failed { ref reason, .. } if reason.contains("exit code 2")
+        ));
+        assert_eq!(
+            map_state(&job, info(LsfState::UserSuspended, None)),
+            job.status
+        );
+    }
+}
@@ -0,0 +1,12 @@
+//! IBM Spectrum LSF integration for HPC job submission.
+//!
+//! This module submits jobs with `bsub`, tracks them with `bjobs` and,
+//! once LSF has forgotten them, `bacct`. It mirrors the SLURM and PBS
+//! adapters so the scheduler treats all three alike.
+
+mod adapter;
+mod parser;
+mod templates;
+
+pub use adapter::{LsfAdapter, LsfConfig, LsfJobInfo, LsfState};
+pub use templates::resource_string;
@@ -0,0 +1,262 @@
+//! Parsers for LSF command output.
+
+use crate::error::{SchedError, SchedResult};
+use crate::lsf::adapter::{LsfJobInfo, LsfState};
+
+/// Output format requested from `bjobs -o`.
+///
+/// The pending reason is last since it is free text.
+pub const BJOBS_FORMAT: &str = "jobid stat exit_code queue job_name pend_reason delimiter='|'";
+
+/// Parse bsub output to extract job ID.
+///
+/// bsub output format:
+/// ```text
+/// Job <12345> is submitted to queue <normal>.
+/// ```
+pub fn parse_bsub_output(output: &str) -> SchedResult<String> {
+    output
+        .lines()
+        .find_map(|line| {
+            let id = bracketed(line.trim().strip_prefix("Job ")?)?;
+            id.chars()
+                .all(|c| c.is_ascii_digit())
+                .then(|| id.to_string())
+        })
+        .ok_or_else(|| SchedError::LsfCommandError {
+            command: "bsub".to_string(),
+            message: format!("Unexpected output format: {}", output.trim()),
+        })
+}
+
+/// Parse `bjobs -noheader -o` output in [`BJOBS_FORMAT`].
+///
+/// ```text
+/// 12345|PEND|-|normal|my_job|Job's requirements for reservation not satisfied
+/// ```
+pub fn parse_bjobs_output(output: &str) -> SchedResult<Option<LsfJobInfo>> {
+    let Some(line) = output.lines().map(str::trim).find(|l| !l.is_empty()) else {
+        return Ok(None);
+    };
+
+    let fields: Vec<&str> = line.splitn(6, '|').collect();
+    if fields.len() < 5 {
+        return Err(SchedError::LsfCommandError {
+            command: "bjobs".to_string(),
+            message: format!("Unexpected output format: {}", line),
+        });
+    }
+
+    let present = |value: &str| {
+        let value = value.trim();
+        (!value.is_empty() && value != "-").then(|| value.to_string())
+    };
+
+    Ok(Some(LsfJobInfo {
+        job_id: fields[0].trim().to_string(),
+        state: parse_lsf_state(fields[1].trim()),
+        exit_code: present(fields[2]).and_then(|code| code.parse().ok()),
+        queue: present(fields[3]),
+        name: fields[4].trim().to_string(),
+        pending_reason: fields.get(5).and_then(|reason| present(reason)),
+    }))
+}
+
+/// Parse `bacct -l` output for a finished job.
+///
+/// bacct wraps long lines, continuing them indented:
+/// ```text
+/// Job <12345>, Job Name <my_job>, User <alice>, Project <default>, Status <EX
+///                      IT>, Queue <normal>, Command <#!/bin/bash>
+/// Mon Oct  5 10:05:00: Completed <exit>; exit code <1>.
+/// ```
+pub fn parse_bacct_output(output: &str) -> SchedResult<Option<LsfJobInfo>> {
+    // Undo line wrapping
+    let mut lines: Vec<String> = Vec::new();
+    for line in output.lines() {
+        match lines.last_mut() {
+            Some(last) if line.starts_with("                     ") => {
+                last.push_str(line.trim_start());
+            }
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    let Some(header) = lines.iter().find(|l| l.starts_with("Job <")) else {
+        return Ok(None);
+    };
+    let Some(job_id) = bracketed(header) else {
+        return Ok(None);
+    };
+
+    let mut state = field(header, "Status")
+        .map(parse_lsf_state)
+        .unwrap_or(LsfState::Unknown(String::new()));
+    let mut exit_code = None;
+    if let Some(completed) = lines.iter().find_map(|l| l.split_once("Completed <")) {
+        let rest = completed.1;
+        if rest.starts_with("done>") {
+            state = LsfState::Done;
+            exit_code = Some(0);
+        } else if rest.starts_with("exit>") {
+            state = LsfState::Exit;
+            exit_code = field(rest, "exit code").and_then(|code| code.parse().ok());
+        }
+    }
+
+    Ok(Some(LsfJobInfo {
+        job_id: job_id.to_string(),
+        name: field(header, "Job Name").unwrap_or_default().to_string(),
+        state,
+        queue: field(header, "Queue").map(str::to_string),
+        pending_reason: None,
+        exit_code,
+    }))
+}
+
+/// Parse LSF state string to LsfState enum.
+pub fn parse_lsf_state(state: &str) -> LsfState {
+    match state.to_uppercase().as_str() {
+        "PEND" => LsfState::Pending,
+        "PSUSP" => LsfState::PendingSuspended,
+        "RUN" => LsfState::Running,
+        "USUSP" => LsfState::UserSuspended,
+        "SSUSP" => LsfState::SystemSuspended,
+        "DONE" => LsfState::Done,
+        "EXIT" => LsfState::Exit,
+        "WAIT" => LsfState::Waiting,
+        "ZOMBI" => LsfState::Zombie,
+        _ => LsfState::Unknown(state.to_string()),
+    }
+}
+
+/// Parse bkill output to verify the job is being killed.
+pub fn parse_bkill_output(output: &str, stderr: &str) -> SchedResult<()> {
+    if stderr.contains("No matching job found") || stderr.contains("is not found") {
+        return Err(SchedError::LsfJobNotFound(
+            "Job not found or already cleaned up".to_string(),
+        ));
+    }
+
+    if stderr.contains("permission denied") || stderr.contains("Not the job owner") {
+        return Err(SchedError::LsfCommandError {
+            command: "bkill".to_string(),
+            message: "Permission denied".to_string(),
+        });
+    }
+
+    if !stderr.trim().is_empty() && !stderr.contains("Job has already finished") {
+        return Err(SchedError::LsfCommandError {
+            command: "bkill".to_string(),
+            message: stderr.to_string(),
+        });
+    }
+
+    // "Job <12345> is being terminated" or no output are OK
+    let _ = output;
+    Ok(())
+}
+
+/// The text between the first `<` and the following `>`.
+fn bracketed(text: &str) -> Option<&str> {
+    let start = text.find('<')? + 1;
+    let end = start + text[start..].find('>')?;
+    Some(&text[start..end])
+}
+
+/// The bracketed value following `name` in text like `Name <value>`.
+fn field<'a>(text: &'a str, name: &str) -> Option<&'a str> {
+    let at = text.find(&format!("{} <", name))?;
+    bracketed(&text[at..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bsub_output() {
+        let output = "Job <12345> is submitted to queue <normal>.\n";
+        assert_eq!(parse_bsub_output(output).unwrap(), "12345");
+
+        // Some sites print a notice before the job line
+        let output =
+            "Memory reservation is (MB): 4096\nJob <777> is submitted to default queue <short>.\n";
+        assert_eq!(parse_bsub_output(output).unwrap(), "777");
+
+        assert!(parse_bsub_output("Request aborted by esub.").is_err());
+    }
+
+    #[test]
+    fn test_parse_bjobs_output() {
+        let output = "12345|PEND|-|quantum|arvak_job|New job is waiting for scheduling;\n";
+        let info = parse_bjobs_output(output).unwrap().unwrap();
+        assert_eq!(info.job_id, "12345");
+        assert_eq!(info.state, LsfState::Pending);
+        assert_eq!(info.exit_code, None);
+        assert_eq!(info.queue.as_deref(), Some("quantum"));
+        assert_eq!(info.name, "arvak_job");
+        assert_eq!(
+            info.pending_reason.as_deref(),
+            Some("New job is waiting for scheduling;")
+        );
+
+        let info = parse_bjobs_output("12345|EXIT|3|quantum|arvak_job|-")
+            .unwrap()
+            .unwrap();
+        assert_eq!((info.state, info.exit_code), (LsfState::Exit, Some(3)));
+
+        assert!(parse_bjobs_output("").unwrap().is_none());
+        assert!(parse_bjobs_output("garbage").is_err());
+    }
+
+    #[test]
+    fn test_parse_bacct_output() {
+        let output = r#"
+Accounting information about jobs that are:
+  - submitted by all users.
+------------------------------------------------------------------------------
+
+Job <12345>, Job Name <arvak_job>, User <alice>, Project <quantum>, Status <EX
+                     IT>, Queue <normal>, Command <#!/bin/bash>
+Mon Oct  5 10:00:00: Submitted from host <login1>, CWD <$HOME>;
+Mon Oct  5 10:05:00: Completed <exit>; exit code <1>.
+"#;
+        let info = parse_bacct_output(output).unwrap().unwrap();
+        assert_eq!(info.job_id, "12345");
+        assert_eq!(info.name, "arvak_job");
+        assert_eq!(info.state, LsfState::Exit);
+        assert_eq!(info.exit_code, Some(1));
+        assert_eq!(info.queue.as_deref(), Some("normal"));
+
+        let done = "Job <9>, Job Name <j>, Status <DONE>, Queue <normal>\nMon Oct  5 10:05:00: Completed <done>.\n";
+        let info = parse_bacct_output(done).unwrap().unwrap();
+        assert_eq!((info.state, info.exit_code), (LsfState::Done, Some(0)));
+
+        assert!(
+            parse_bacct_output("No matching job found")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_lsf_state() {
+        assert_eq!(parse_lsf_state("PEND"), LsfState::Pending);
+        assert_eq!(parse_lsf_state("run"), LsfState::Running);
+        assert_eq!(parse_lsf_state("SSUSP"), LsfState::SystemSuspended);
+        assert_eq!(parse_lsf_state("ZOMBI"), LsfState::Zombie);
+        assert!(matches!(parse_lsf_state("UNKWN"), LsfState::Unknown(_)));
+    }
+
+    #[test]
+    fn test_parse_bkill_output() {
+        assert!(parse_bkill_output("Job <12345> is being terminated\n", "").is_ok());
+        assert!(parse_bkill_output("", "Job <12345>: Job has already finished").is_ok());
+        assert!(matches!(
+            parse_bkill_output("", "Job <12345>: No matching job found"),
+            Err(SchedError::LsfJobNotFound(_))
+        ));
+        assert!(parse_bkill_output("", "Job <12345>: User permission denied").is_err());
+    }
+}
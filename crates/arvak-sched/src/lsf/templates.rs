@@ -0,0 +1,346 @@
+//! LSF batch script templates.
+
+use std::path::Path;
+
+use crate::job::{ResourceRequirements, ScheduledJob};
+use crate::lsf::adapter::LsfConfig;
+
+/// Build the `-R` resource requirement string for a job.
+///
+/// Hosts are selected by the configured qubit resource (at least the
+/// job's `min_qubits`) and extra selection expression; memory is reserved
+/// with `rusage`, and `span` keeps the job on one host if configured.
+///
+/// ```
+/// use arvak_sched::{LsfConfig, ResourceRequirements};
+/// use arvak_sched::lsf::resource_string;
+///
+/// let config = LsfConfig {
+///     qubit_resource: Some("qpu_qubits".to_string()),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     resource_string(&ResourceRequirements::new(5), &config),
+///     "select[qpu_qubits>=5] rusage[mem=4096] span[hosts=1]"
+/// );
+/// ```
+pub fn resource_string(requirements: &ResourceRequirements, config: &LsfConfig) -> String {
+    let mut select = Vec::new();
+    if let Some(ref resource) = config.qubit_resource {
+        if requirements.min_qubits > 0 {
+            select.push(format!("{}>={}", resource, requirements.min_qubits));
+        }
+    }
+    if let Some(ref expr) = config.select {
+        select.push(expr.clone());
+    }
+
+    let mut sections = Vec::new();
+    if !select.is_empty() {
+        sections.push(format!("select[{}]", select.join(" && ")));
+    }
+    sections.push(format!("rusage[mem={}]", config.memory_mb));
+    if config.single_host {
+        sections.push("span[hosts=1]".to_string());
+    }
+    sections.join(" ")
+}
+
+/// Generate an LSF batch script for a quantum job.
+pub fn generate_lsf_script(
+    job: &ScheduledJob,
+    config: &LsfConfig,
+    circuit_file: &Path,
+    result_file: &Path,
+) -> String {
+    let mut script = header(job, config, config.time_limit);
+
+    // Job information
+    script.push_str("# Job information\n");
+    script.push_str("echo \"Job ID: $LSB_JOBID\"\n");
+    script.push_str("echo \"Job Name: $LSB_JOBNAME\"\n");
+    script.push_str("echo \"Hosts: $LSB_HOSTS\"\n");
+    script.push_str("echo \"Queue: $LSB_QUEUE\"\n");
+    script.push_str("echo \"Start Time: $(date)\"\n\n");
+
+    // Execute Arvak command
+    script.push_str("# Execute quantum job\n");
+    script.push_str(&format!(
+        "{} run {} --shots {} {} --output {}\n",
+        config.arvak_binary.display(),
+        circuit_file.display(),
+        job.circuit_shots(0),
+        backend_flag(job),
+        result_file.display(),
+    ));
+
+    // Completion message
+    script.push_str("\necho \"Job completed at: $(date)\"\n");
+    script.push_str("echo \"Exit code: $?\"\n");
+
+    script
+}
+
+/// Generate an LSF batch script for multiple circuits (batch job).
+pub fn generate_lsf_script_multi(
+    job: &ScheduledJob,
+    config: &LsfConfig,
+    circuit_files: &[&Path],
+    result_dir: &Path,
+) -> String {
+    // Scale run limit based on number of circuits
+    let time_limit = config.time_limit.saturating_mul(circuit_files.len() as u32);
+    let mut script = header(job, config, time_limit);
+
+    // Job information
+    script.push_str("# Job information\n");
+    script.push_str("echo \"Job ID: $LSB_JOBID\"\n");
+    script.push_str(&format!("echo \"Batch size: {}\"\n", circuit_files.len()));
+    script.push_str("echo \"Start Time: $(date)\"\n\n");
+
+    // Create result directory
+    script.push_str(&format!("mkdir -p {}\n\n", result_dir.display()));
+
+    // Execute each circuit
+    script.push_str("# Execute quantum jobs\n");
+    script.push_str("FAILED=0\n\n");
+
+    let backend_flag = backend_flag(job);
+    for (i, circuit_file) in circuit_files.iter().enumerate() {
+        let result_file = result_dir.join(format!("result_{}.json", i));
+        script.push_str(&format!(
+            "echo \"Running circuit {} of {} ({})\"\n",
+            i + 1,
+            circuit_files.len(),
+            sanitize_name(&job.circuit_label(i))
+        ));
+        script.push_str(&format!(
+            "if ! {} run {} --shots {} {} --output {}; then\n",
+            config.arvak_binary.display(),
+            circuit_file.display(),
+            job.circuit_shots(i),
+            backend_flag,
+            result_file.display(),
+        ));
+        script.push_str("    echo \"Circuit failed\"\n");
+        script.push_str("    FAILED=$((FAILED + 1))\n");
+        script.push_str("fi\n\n");
+    }
+
+    // Summary
+    script.push_str("echo \"Job completed at: $(date)\"\n");
+    script.push_str(&format!(
+        "echo \"Total circuits: {}\"\n",
+        circuit_files.len()
+    ));
+    script.push_str("echo \"Failed circuits: $FAILED\"\n");
+    script.push_str("exit $FAILED\n");
+
+    script
+}
+
+/// Shebang, `#BSUB` directives and environment setup.
+fn header(job: &ScheduledJob, config: &LsfConfig, time_limit: u32) -> String {
+    let mut script = String::new();
+
+    // Shebang
+    script.push_str("#!/bin/bash\n");
+
+    // LSF directives
+    script.push_str(&format!("#BSUB -J {}\n", sanitize_name(&job.name)));
+    script.push_str(&format!(
+        "#BSUB -o {}/lsf-%J.out\n",
+        config.work_dir.display()
+    ));
+    script.push_str(&format!(
+        "#BSUB -e {}/lsf-%J.err\n",
+        config.work_dir.display()
+    ));
+
+    // Queue selection (can be overridden by priority mapping)
+    let queue = config
+        .priority_queue_mapping
+        .as_ref()
+        .and_then(|mapping| mapping.get(&job.priority.value()))
+        .unwrap_or(&config.queue);
+    script.push_str(&format!("#BSUB -q {}\n", queue));
+
+    // Project if specified
+    if let Some(ref project) = config.project {
+        script.push_str(&format!("#BSUB -P {}\n", project));
+    }
+
+    // Resource requests
+    script.push_str(&format!("#BSUB -W {}\n", format_minutes(time_limit)));
+    script.push_str(&format!("#BSUB -n {}\n", config.cores));
+    script.push_str(&format!(
+        "#BSUB -R \"{}\"\n",
+        resource_string(&job.requirements, config)
+    ));
+
+    // Give up on jobs pending longer than they may wait
+    if let Some(secs) = job.requirements.max_queue_time {
+        script.push_str(&format!(
+            "#BSUB -ptl {}\n",
+            format_minutes(secs.div_ceil(60).try_into().unwrap_or(u32::MAX))
+        ));
+    }
+
+    // Additional directives
+    for directive in &config.extra_directives {
+        script.push_str(&format!("#BSUB {}\n", directive));
+    }
+
+    // Environment setup
+    script.push_str("\n# Environment setup\n");
+    script.push_str("set -e\n");
+    script.push_str("set -o pipefail\n\n");
+
+    // Change to submission directory (LSF specific)
+    script.push_str("# Change to submission directory\n");
+    script.push_str("cd $LS_SUBCWD\n\n");
+
+    // Load modules if configured
+    if !config.modules.is_empty() {
+        script.push_str("# Load required modules\n");
+        for module in &config.modules {
+            script.push_str(&format!("module load {}\n", module));
+        }
+        script.push('\n');
+    }
+
+    // Activate virtual environment if configured
+    if let Some(ref venv) = config.python_venv {
+        script.push_str("# Activate Python environment\n");
+        script.push_str(&format!("source {}/bin/activate\n\n", venv.display()));
+    }
+
+    script
+}
+
+fn backend_flag(job: &ScheduledJob) -> String {
+    if let Some(ref backend) = job.matched_backend {
+        format!("--backend {}", backend)
+    } else {
+        String::new()
+    }
+}
+
+/// Format minutes as LSF's `hour:minute`.
+fn format_minutes(minutes: u32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+/// Sanitize a job name for LSF.
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, Priority};
+    use std::path::PathBuf;
+
+    fn test_config() -> LsfConfig {
+        LsfConfig {
+            queue: "quantum".to_string(),
+            project: Some("project123".to_string()),
+            time_limit: 90,
+            memory_mb: 8192,
+            cores: 2,
+            single_host: true,
+            qubit_resource: Some("qpu_qubits".to_string()),
+            select: Some("qpu_access".to_string()),
+            work_dir: PathBuf::from("/scratch/jobs"),
+            arvak_binary: PathBuf::from("/opt/arvak/bin/arvak"),
+            modules: vec!["python/3.11".to_string()],
+            python_venv: Some(PathBuf::from("/opt/arvak/venv")),
+            extra_directives: vec!["-G quantum_users".to_string()],
+            priority_queue_mapping: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_lsf_script() {
+        let config = test_config();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::new("test job", circuit)
+            .with_priority(Priority::high())
+            .with_requirements(ResourceRequirements::new(5).with_max_queue_time(5400));
+
+        let script = generate_lsf_script(
+            &job,
+            &config,
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+
+        assert!(script.starts_with("#!/bin/bash\n"));
+        assert!(script.contains("#BSUB -J test_job"));
+        assert!(script.contains("#BSUB -o /scratch/jobs/lsf-%J.out"));
+        assert!(script.contains("#BSUB -q quantum"));
+        assert!(script.contains("#BSUB -P project123"));
+        assert!(script.contains("#BSUB -W 1:30"));
+        assert!(script.contains("#BSUB -n 2"));
+        assert!(script.contains(
+            "#BSUB -R \"select[qpu_qubits>=5 && qpu_access] rusage[mem=8192] span[hosts=1]\""
+        ));
+        assert!(script.contains("#BSUB -ptl 1:30"));
+        assert!(script.contains("#BSUB -G quantum_users"));
+        assert!(script.contains("module load python/3.11"));
+        assert!(script.contains("source /opt/arvak/venv/bin/activate"));
+        assert!(script.contains("/opt/arvak/bin/arvak run /scratch/circuit.qasm"));
+        assert!(script.contains("cd $LS_SUBCWD"));
+    }
+
+    #[test]
+    fn test_generate_lsf_script_multi() {
+        let mut config = test_config();
+        config.priority_queue_mapping = Some([(200, "priority".to_string())].into_iter().collect());
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::new("batch_job", circuit).with_priority(Priority::new(200));
+
+        let circuits = vec![
+            Path::new("/scratch/c1.qasm"),
+            Path::new("/scratch/c2.qasm"),
+            Path::new("/scratch/c3.qasm"),
+        ];
+
+        let script =
+            generate_lsf_script_multi(&job, &config, &circuits, Path::new("/scratch/results"));
+
+        assert!(script.contains("#BSUB -q priority"));
+        assert!(script.contains("#BSUB -W 4:30")); // Scaled 3x
+        assert!(!script.contains("-ptl"));
+        assert!(script.contains("mkdir -p /scratch/results"));
+        assert!(script.contains("Running circuit 3 of 3"));
+        assert!(script.contains("exit $FAILED"));
+    }
+
+    #[test]
+    fn test_resource_string() {
+        let mut config = LsfConfig::default();
+        let requirements = ResourceRequirements::new(20);
+        assert_eq!(
+            resource_string(&requirements, &config),
+            "rusage[mem=4096] span[hosts=1]"
+        );
+
+        config.qubit_resource = Some("qubits".to_string());
+        config.single_host = false;
+        assert_eq!(
+            resource_string(&requirements, &config),
+            "select[qubits>=20] rusage[mem=4096]"
+        );
+    }
+}
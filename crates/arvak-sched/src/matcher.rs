@@ -1,10 +1,15 @@
 //! Resource matcher for matching circuits to backends.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+use arvak_compile::passes::RoutingCost;
+use arvak_compile::{BasisGates, ErrorRates, Layout, PassManagerBuilder};
 use arvak_hal::{Backend, Capabilities};
+use arvak_ir::{Circuit, CouplingMap, QubitId};
 use async_trait::async_trait;
 
+use crate::calibration::BackendCalibration;
 use crate::error::{SchedError, SchedResult};
 use crate::job::{ResourceRequirements, TopologyPreference};
 
@@ -22,6 +27,23 @@ pub struct MatchResult {
 
     /// Reasons for the match score.
     pub score_breakdown: Vec<(String, f64)>,
+
+    /// Calibration epoch of the matched backend, if it has been calibrated.
+    pub calibration_epoch: Option<u64>,
+
+    /// Estimated success probability of the job's circuits on the backend,
+    /// from [`ResourceMatcher::find_matches_by_esp`].
+    pub esp: Option<f64>,
+
+    /// Initial layout reaching [`esp`](Self::esp).
+    pub layout: Option<Layout>,
+}
+
+impl MatchResult {
+    /// Get the capabilities of the matched backend.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
 }
 
 /// Trait for matching circuits to backends.
@@ -37,11 +59,29 @@ pub trait Matcher: Send + Sync {
     ) -> SchedResult<Vec<MatchResult>>;
 }
 
+/// Check that a batch node feature is a non-empty name of ASCII letters,
+/// digits, `-`, `_`, `.` and `:`, so it cannot alter a constraint
+/// expression.
+pub fn is_valid_node_feature(feature: &str) -> bool {
+    !feature.is_empty()
+        && feature
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+/// Couplers with the lowest error that [`ResourceMatcher::find_matches_by_esp`]
+/// grows candidate layouts from.
+const ESP_SEED_COUPLERS: usize = 4;
+
 /// Resource matcher that finds suitable backends for circuit execution.
 pub struct ResourceMatcher {
     backends: Vec<Arc<dyn Backend>>,
     /// Cache of backend capabilities.
     capabilities_cache: tokio::sync::RwLock<rustc_hash::FxHashMap<String, Capabilities>>,
+    /// Batch node features each backend's control hardware is attached to.
+    node_features: BTreeMap<String, Vec<String>>,
+    /// Latest calibration of each backend.
+    calibrations: tokio::sync::RwLock<rustc_hash::FxHashMap<String, BackendCalibration>>,
 }
 
 impl ResourceMatcher {
@@ -50,9 +90,42 @@ impl ResourceMatcher {
         Self {
             backends,
             capabilities_cache: tokio::sync::RwLock::new(rustc_hash::FxHashMap::default()),
+            node_features: BTreeMap::new(),
+            calibrations: tokio::sync::RwLock::new(rustc_hash::FxHashMap::default()),
         }
     }
 
+    /// Set the batch node features required to reach each backend.
+    pub fn with_node_features(mut self, node_features: BTreeMap<String, Vec<String>>) -> Self {
+        self.node_features = node_features;
+        self
+    }
+
+    /// Batch node features a job with `requirements` needs on `backend`:
+    /// the backend's own followed by the requested ones, without
+    /// duplicates. Features that are not valid names are dropped.
+    pub fn node_features(
+        &self,
+        backend: Option<&str>,
+        requirements: &ResourceRequirements,
+    ) -> Vec<String> {
+        let mut features: Vec<String> = Vec::new();
+        let backend_features = backend
+            .and_then(|name| self.node_features.get(name))
+            .into_iter()
+            .flatten();
+        for feature in backend_features.chain(&requirements.node_features) {
+            if !is_valid_node_feature(feature) {
+                tracing::warn!("Ignoring invalid node feature '{}'", feature);
+                continue;
+            }
+            if !features.contains(feature) {
+                features.push(feature.clone());
+            }
+        }
+        features
+    }
+
     /// Add a backend to the matcher.
     pub fn add_backend(&mut self, backend: Arc<dyn Backend>) {
         self.backends.push(backend);
@@ -72,6 +145,77 @@ impl ResourceMatcher {
         Ok(())
     }
 
+    /// Use a new calibration of a backend.
+    ///
+    /// The backend's cached capabilities are dropped, since recalibration
+    /// may change them too. Older epochs than the current one are ignored.
+    pub async fn set_calibration(&self, calibration: BackendCalibration) {
+        let mut calibrations = self.calibrations.write().await;
+        if calibrations
+            .get(&calibration.backend)
+            .is_some_and(|current| current.epoch > calibration.epoch)
+        {
+            return;
+        }
+        self.capabilities_cache
+            .write()
+            .await
+            .remove(&calibration.backend);
+        calibrations.insert(calibration.backend.clone(), calibration);
+    }
+
+    /// Get the latest calibration of a backend, if it has one.
+    pub async fn calibration_of(&self, name: &str) -> Option<BackendCalibration> {
+        self.calibrations.read().await.get(name).cloned()
+    }
+
+    /// Get the names of the matched backends.
+    pub fn backend_names(&self) -> Vec<&str> {
+        self.backends.iter().map(|b| b.name()).collect()
+    }
+
+    /// Get the capabilities of a backend by name, if it is known.
+    pub async fn capabilities_of(&self, name: &str) -> Option<Capabilities> {
+        let backend = self.backends.iter().find(|b| b.name() == name)?;
+        self.get_capabilities(backend.as_ref()).await.ok()
+    }
+
+    /// Find all matching backends for the given circuits, best estimated
+    /// success probability first.
+    ///
+    /// Each calibrated match compiles the circuits with gate-error routing
+    /// from the trivial layout and from regions grown around its best
+    /// couplers, and keeps the layout whose circuits have the highest
+    /// product of ESPs. Matches without calibration follow in score order.
+    pub async fn find_matches_by_esp(
+        &self,
+        requirements: &ResourceRequirements,
+        circuits: &[Circuit],
+    ) -> SchedResult<Vec<MatchResult>> {
+        let mut matches = self.find_all_matches(requirements).await?;
+        let calibrations = self.calibrations.read().await;
+        for m in &mut matches {
+            let Some(calibration) = calibrations.get(&m.backend_name) else {
+                continue;
+            };
+            let rates = calibration.error_rates();
+            if let Some((esp, layout)) = best_layout(&m.capabilities, &rates, circuits) {
+                m.score_breakdown
+                    .push(("Estimated success probability".to_string(), esp));
+                m.esp = Some(esp);
+                m.layout = Some(layout);
+            }
+        }
+
+        matches.sort_by(|a, b| match (a.esp, b.esp) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        Ok(matches)
+    }
+
     /// Get cached capabilities for a backend.
     async fn get_capabilities(&self, backend: &dyn Backend) -> SchedResult<Capabilities> {
         // Check cache first
@@ -133,7 +277,7 @@ impl ResourceMatcher {
 
         // Topology matching
         if let Some(ref pref) = requirements.topology_preference {
-            let topology_score = self.score_topology(pref, capabilities);
+            let topology_score = self.score_topology(pref, requirements.min_qubits, capabilities);
             score += topology_score;
             breakdown.push(("Topology match".to_string(), topology_score));
         }
@@ -147,12 +291,17 @@ impl ResourceMatcher {
     }
 
     /// Score topology match.
-    fn score_topology(&self, preference: &TopologyPreference, capabilities: &Capabilities) -> f64 {
+    fn score_topology(
+        &self,
+        preference: &TopologyPreference,
+        min_qubits: u32,
+        capabilities: &Capabilities,
+    ) -> f64 {
+        let map = capabilities.coupling_map();
         match preference {
             TopologyPreference::Linear => {
-                // Check if topology is linear-compatible
-                // For now, just check if it's not too sparse
-                if capabilities.topology.edges.len() >= capabilities.num_qubits as usize - 1 {
+                // The job fits on a connected region of the device
+                if map.find_connected_subset(min_qubits).is_some() {
                     10.0
                 } else {
                     5.0
@@ -160,15 +309,11 @@ impl ResourceMatcher {
             }
             TopologyPreference::Grid => {
                 // Prefer topologies with higher connectivity
-                let avg_degree =
-                    capabilities.topology.edges.len() as f64 / capabilities.num_qubits as f64;
+                let avg_degree = 2.0 * map.edges().len() as f64 / map.num_qubits().max(1) as f64;
                 if avg_degree >= 2.0 { 10.0 } else { 5.0 }
             }
             TopologyPreference::AllToAll => {
-                // Check if fully connected
-                let max_edges =
-                    capabilities.num_qubits as usize * (capabilities.num_qubits as usize - 1) / 2;
-                if capabilities.topology.edges.len() >= max_edges {
+                if map.is_fully_connected() {
                     15.0
                 } else {
                     5.0
@@ -265,6 +410,14 @@ impl Matcher for ResourceMatcher {
                 score,
                 capabilities,
                 score_breakdown: breakdown,
+                calibration_epoch: self
+                    .calibrations
+                    .read()
+                    .await
+                    .get(backend.name())
+                    .map(|c| c.epoch),
+                esp: None,
+                layout: None,
             });
         }
 
@@ -279,6 +432,110 @@ impl Matcher for ResourceMatcher {
     }
 }
 
+/// Pipeline compiling for a backend with gate-error routing, as
+/// [`ResourceMatcher::find_matches_by_esp`] scores it.
+pub(crate) fn esp_builder(capabilities: &Capabilities, rates: &ErrorRates) -> PassManagerBuilder {
+    let gate_set = &capabilities.gate_set;
+    let gates: Vec<&str> = if gate_set.native.is_empty() {
+        gate_set
+            .single_qubit
+            .iter()
+            .chain(&gate_set.two_qubit)
+            .map(String::as_str)
+            .collect()
+    } else {
+        gate_set.native.iter().map(String::as_str).collect()
+    };
+    let basis_gates = BasisGates::new(gates.into_iter().chain(["measure", "barrier"]));
+    PassManagerBuilder::new()
+        .with_target(capabilities.coupling_map(), basis_gates)
+        .with_error_rates(rates.clone())
+        .with_routing_cost(RoutingCost::GateError)
+}
+
+/// The layout with the highest product of ESPs over the circuits, with that
+/// product, or `None` if no candidate compiles.
+fn best_layout(
+    capabilities: &Capabilities,
+    rates: &ErrorRates,
+    circuits: &[Circuit],
+) -> Option<(f64, Layout)> {
+    let num_qubits = circuits.iter().map(Circuit::num_qubits).max()? as u32;
+    let map = capabilities.coupling_map();
+    let mut best: Option<(f64, Layout)> = None;
+    for region in candidate_regions(&map, rates, num_qubits) {
+        let mut layout = Layout::new();
+        for (logical, physical) in region.into_iter().enumerate() {
+            layout.add(QubitId(logical as u32), physical);
+        }
+        let esp: Option<f64> = circuits
+            .iter()
+            .map(|circuit| {
+                let (pm, mut props) = esp_builder(capabilities, rates)
+                    .with_layout(layout.clone())
+                    .build();
+                let mut dag = circuit.clone().into_dag();
+                pm.run(&mut dag, &mut props).ok()?;
+                Some(rates.estimated_success_probability(&dag))
+            })
+            .product();
+        if let Some(esp) = esp.filter(|&esp| best.as_ref().is_none_or(|(best, _)| esp > *best)) {
+            best = Some((esp, layout));
+        }
+    }
+    best
+}
+
+/// Physical qubits to place `n` logical qubits on: the first `n` qubits,
+/// then connected regions grown from the lowest-error couplers.
+fn candidate_regions(map: &CouplingMap, rates: &ErrorRates, n: u32) -> Vec<Vec<u32>> {
+    if n == 0 || n > map.num_qubits() {
+        return Vec::new();
+    }
+    let mut regions = vec![(0..n).collect::<Vec<_>>()];
+
+    let mut couplers = map.edges().to_vec();
+    couplers.sort_by(|&(a, b), &(c, d)| {
+        coupler_error(rates, a, b).total_cmp(&coupler_error(rates, c, d))
+    });
+    for &(a, b) in couplers.iter().take(ESP_SEED_COUPLERS) {
+        if let Some(region) = grow_region(map, rates, a, b, n) {
+            if !regions.contains(&region) {
+                regions.push(region);
+            }
+        }
+    }
+    regions
+}
+
+/// Grow a connected region of `n` qubits from the coupler `(a, b)`, adding
+/// the neighbour with the lowest coupler and readout error each step.
+fn grow_region(map: &CouplingMap, rates: &ErrorRates, a: u32, b: u32, n: u32) -> Option<Vec<u32>> {
+    let mut region = vec![a, b];
+    region.truncate(n as usize);
+    while region.len() < n as usize {
+        let next = region
+            .iter()
+            .flat_map(|&q| map.neighbors(q).map(move |p| (q, p)))
+            .filter(|(_, p)| !region.contains(p))
+            .map(|(q, p)| {
+                let cost = coupler_error(rates, q, p) + rates.readout_error(p).unwrap_or(0.0);
+                (cost, p)
+            })
+            .min_by(|x, y| x.0.total_cmp(&y.0))?;
+        region.push(next.1);
+    }
+    Some(region)
+}
+
+/// Two-qubit error of a coupler, the mean if it is not calibrated.
+fn coupler_error(rates: &ErrorRates, a: u32, b: u32) -> f64 {
+    rates
+        .two_qubit_error(a, b)
+        .or_else(|| rates.mean_two_qubit_error())
+        .unwrap_or(0.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -435,4 +692,73 @@ mod tests {
         let result = matcher.find_match(&requirements).await;
         assert!(matches!(result, Err(SchedError::NoMatchingBackend(_))));
     }
+
+    #[tokio::test]
+    async fn test_find_matches_by_esp() {
+        let matcher = ResourceMatcher::new(vec![
+            make_backend("noisy", 5, false),
+            make_backend("clean", 5, false),
+        ]);
+        let calibrate = |name: &str, errors: [f64; 4]| {
+            errors
+                .into_iter()
+                .enumerate()
+                .fold(BackendCalibration::new(name), |c, (i, error)| {
+                    c.with_gate_error("cx", [i as u32, i as u32 + 1], error)
+                })
+        };
+        matcher.set_calibration(calibrate("noisy", [0.1; 4])).await;
+        matcher
+            .set_calibration(calibrate("clean", [0.2, 0.05, 0.05, 0.001]))
+            .await;
+
+        let mut circuit = Circuit::with_size("bell", 2, 0);
+        circuit.h(QubitId(0)).unwrap();
+        circuit.cx(QubitId(0), QubitId(1)).unwrap();
+
+        // The preferred backend wins on score alone
+        let requirements = ResourceRequirements::new(2).prefer_backend("noisy");
+        let matches = matcher.find_all_matches(&requirements).await.unwrap();
+        assert_eq!(matches[0].backend_name, "noisy");
+
+        // but the other one has a far better coupler at the end of the chain
+        let matches = matcher
+            .find_matches_by_esp(&requirements, &[circuit])
+            .await
+            .unwrap();
+        assert_eq!(matches[0].backend_name, "clean");
+        assert!((matches[0].esp.unwrap() - 0.999).abs() < 1e-9);
+        let layout = matches[0].layout.as_ref().unwrap();
+        assert_eq!(layout.get_physical(QubitId(0)), Some(3));
+        assert_eq!(layout.get_physical(QubitId(1)), Some(4));
+        assert!((matches[1].esp.unwrap() - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_node_features() {
+        let matcher = ResourceMatcher::new(vec![]).with_node_features(BTreeMap::from([(
+            "qpu".to_string(),
+            vec!["qctrl".to_string()],
+        )]));
+        let requirements = ResourceRequirements::new(2)
+            .require_node_feature("ib")
+            .require_node_feature("qctrl")
+            .require_node_feature("a&b");
+
+        assert_eq!(
+            matcher.node_features(Some("qpu"), &requirements),
+            vec!["qctrl", "ib"]
+        );
+        assert_eq!(
+            matcher.node_features(Some("sim"), &requirements),
+            vec!["ib", "qctrl"]
+        );
+        assert!(
+            matcher
+                .node_features(None, &ResourceRequirements::new(2))
+                .is_empty()
+        );
+        assert!(!is_valid_node_feature(""));
+        assert!(is_valid_node_feature("gpu:a100"));
+    }
 }
@@ -0,0 +1,335 @@
+//! Pre-flight capability negotiation between a job and its backend.
+//!
+//! A job can state what it needs from the backend it is matched to, such as
+//! features like `dynamic_circuits` and a policy for shot counts above the
+//! backend's limit. Before dispatch the scheduler checks the request against
+//! the backend's [`Capabilities`], lets the job adapt (clamping or splitting
+//! its shots) or fails it, and records the outcome as a [`Negotiation`].
+
+use arvak_hal::Capabilities;
+use serde::{Deserialize, Serialize};
+
+use crate::job::{CircuitSpec, ScheduledJob};
+use crate::matcher::MatchResult;
+
+/// What a job needs from its backend.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityRequest {
+    /// Backend features the job relies on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+
+    /// What to do when a circuit asks for more shots than the backend runs.
+    #[serde(default)]
+    pub excess_shots: ShotPolicy,
+}
+
+impl CapabilityRequest {
+    /// Create a request with no required features that rejects excess shots.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a backend feature.
+    pub fn require_feature(mut self, feature: impl Into<String>) -> Self {
+        self.features.push(feature.into());
+        self
+    }
+
+    /// Set the policy for shot counts above the backend's limit.
+    pub fn with_excess_shots(mut self, policy: ShotPolicy) -> Self {
+        self.excess_shots = policy;
+        self
+    }
+}
+
+/// How a job adapts to a backend's shot limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShotPolicy {
+    /// Fail the job.
+    #[default]
+    Reject,
+
+    /// Run the backend's maximum instead.
+    Clamp,
+
+    /// Split the circuit into several circuits within the limit, labelled
+    /// `<label>#<k>`.
+    Split,
+}
+
+/// How well a backend meets a job's request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapabilityReport {
+    /// Backend the report is about.
+    pub backend: String,
+
+    /// Most shots the backend runs per circuit.
+    pub max_shots: u32,
+
+    /// Most shots any circuit of the job asks for.
+    pub requested_shots: u32,
+
+    /// Required features the backend does not advertise.
+    pub missing_features: Vec<String>,
+}
+
+impl CapabilityReport {
+    /// Check a job's request against a backend.
+    pub fn new(
+        backend: &str,
+        capabilities: &Capabilities,
+        job: &ScheduledJob,
+        request: &CapabilityRequest,
+    ) -> Self {
+        Self {
+            backend: backend.to_string(),
+            max_shots: capabilities.max_shots,
+            requested_shots: (0..job.circuits.len())
+                .map(|i| job.circuit_shots(i))
+                .max()
+                .unwrap_or(0),
+            missing_features: request
+                .features
+                .iter()
+                .filter(|f| !capabilities.has_feature(f))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Check a job's request against a matched backend.
+    pub fn for_match(
+        matched: &MatchResult,
+        job: &ScheduledJob,
+        request: &CapabilityRequest,
+    ) -> Self {
+        Self::new(&matched.backend_name, matched.capabilities(), job, request)
+    }
+
+    /// Whether every circuit fits within the backend's shot limit.
+    pub fn shots_supported(&self) -> bool {
+        self.requested_shots <= self.max_shots
+    }
+
+    /// Whether the backend meets the request without adaptation.
+    pub fn is_satisfied(&self) -> bool {
+        self.shots_supported() && self.missing_features.is_empty()
+    }
+}
+
+/// Outcome of a pre-flight negotiation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum Negotiation {
+    /// The backend meets the request as is.
+    Accepted { backend: String },
+
+    /// Circuit shots were lowered to the backend's maximum.
+    Clamped { backend: String, max_shots: u32 },
+
+    /// Circuits were split so each run fits the backend's maximum.
+    Split {
+        backend: String,
+        max_shots: u32,
+        circuits: usize,
+    },
+
+    /// The backend cannot run the job.
+    Rejected { backend: String, reason: String },
+}
+
+impl Negotiation {
+    /// Whether the job may be dispatched.
+    pub fn is_accepted(&self) -> bool {
+        !matches!(self, Negotiation::Rejected { .. })
+    }
+}
+
+impl std::fmt::Display for Negotiation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Negotiation::Accepted { backend } => write!(f, "accepted by {}", backend),
+            Negotiation::Clamped { backend, max_shots } => {
+                write!(f, "shots clamped to {} for {}", max_shots, backend)
+            }
+            Negotiation::Split {
+                backend,
+                max_shots,
+                circuits,
+            } => write!(
+                f,
+                "split into {} circuits of at most {} shots for {}",
+                circuits, max_shots, backend
+            ),
+            Negotiation::Rejected { backend, reason } => {
+                write!(f, "rejected by {}: {}", backend, reason)
+            }
+        }
+    }
+}
+
+/// Negotiate a job's request with a backend, adapting the job's circuits
+/// as the request's shot policy allows.
+pub fn negotiate(
+    job: &mut ScheduledJob,
+    request: &CapabilityRequest,
+    backend: &str,
+    capabilities: &Capabilities,
+) -> Negotiation {
+    let report = CapabilityReport::new(backend, capabilities, job, request);
+    let backend = backend.to_string();
+
+    if !report.missing_features.is_empty() {
+        return Negotiation::Rejected {
+            backend,
+            reason: format!("missing features: {}", report.missing_features.join(", ")),
+        };
+    }
+    if report.shots_supported() {
+        return Negotiation::Accepted { backend };
+    }
+
+    let max_shots = report.max_shots;
+    if max_shots == 0 {
+        return Negotiation::Rejected {
+            backend,
+            reason: "backend reports no shot capacity".to_string(),
+        };
+    }
+    match request.excess_shots {
+        ShotPolicy::Reject => Negotiation::Rejected {
+            backend,
+            reason: format!(
+                "{} shots requested, backend runs at most {}",
+                report.requested_shots, max_shots
+            ),
+        },
+        ShotPolicy::Clamp => {
+            for i in 0..job.circuits.len() {
+                if job.circuit_shots(i) > max_shots {
+                    job.circuits[i] = job.circuits[i].clone().with_shots(max_shots);
+                }
+            }
+            Negotiation::Clamped { backend, max_shots }
+        }
+        ShotPolicy::Split => {
+            let mut circuits = Vec::with_capacity(job.circuits.len());
+            for (i, spec) in job.circuits.iter().enumerate() {
+                let shots = job.circuit_shots(i);
+                if shots <= max_shots {
+                    circuits.push(spec.clone());
+                    continue;
+                }
+                let label = job.circuit_label(i);
+                let parts = shots.div_ceil(max_shots);
+                for k in 0..parts {
+                    // Spread the remainder so chunk sizes differ by at most one.
+                    let chunk = shots / parts + u32::from(k < shots % parts);
+                    circuits.push(
+                        CircuitSpec::clone(spec.source())
+                            .with_label(format!("{}#{}", label, k))
+                            .with_shots(chunk),
+                    );
+                }
+            }
+            job.circuits = circuits;
+            Negotiation::Split {
+                backend,
+                max_shots,
+                circuits: job.circuits.len(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(shots: u32) -> ScheduledJob {
+        let qasm = "OPENQASM 3.0; qubit[2] q; h q[0];";
+        ScheduledJob::batch(
+            "experiment",
+            vec![
+                CircuitSpec::from_qasm(qasm).with_label("main"),
+                CircuitSpec::from_qasm(qasm)
+                    .with_label("cal")
+                    .with_shots(500),
+            ],
+        )
+        .with_shots(shots)
+    }
+
+    #[test]
+    fn test_report() {
+        let caps = Capabilities::iqm("garnet", 20);
+        let request = CapabilityRequest::new().require_feature("dynamic_circuits");
+
+        let report = CapabilityReport::new("garnet", &caps, &job(50_000), &request);
+        assert_eq!(report.backend, "garnet");
+        assert_eq!(report.requested_shots, 50_000);
+        assert!(!report.shots_supported());
+        assert_eq!(report.missing_features, vec!["dynamic_circuits"]);
+
+        let caps = Capabilities::ibm("eagle", 127);
+        let report = CapabilityReport::new("eagle", &caps, &job(10_000), &request);
+        assert!(report.is_satisfied());
+    }
+
+    #[test]
+    fn test_negotiate_policies() {
+        let caps = Capabilities::iqm("garnet", 20);
+
+        let mut accepted = job(10_000);
+        assert_eq!(
+            negotiate(&mut accepted, &CapabilityRequest::new(), "garnet", &caps),
+            Negotiation::Accepted {
+                backend: "garnet".into()
+            }
+        );
+
+        let mut rejected = job(50_000);
+        let outcome = negotiate(&mut rejected, &CapabilityRequest::new(), "garnet", &caps);
+        assert!(!outcome.is_accepted());
+        assert_eq!(rejected.circuits.len(), 2);
+
+        let mut missing = job(10);
+        let request = CapabilityRequest::new().require_feature("dynamic_circuits");
+        assert!(matches!(
+            negotiate(&mut missing, &request, "garnet", &caps),
+            Negotiation::Rejected { reason, .. } if reason.contains("dynamic_circuits")
+        ));
+
+        let mut clamped = job(50_000);
+        let request = CapabilityRequest::new().with_excess_shots(ShotPolicy::Clamp);
+        assert!(negotiate(&mut clamped, &request, "garnet", &caps).is_accepted());
+        assert_eq!(clamped.circuit_shots(0), 20_000);
+        assert_eq!(clamped.circuit_label(0), "main");
+        assert_eq!(clamped.circuit_shots(1), 500);
+    }
+
+    #[test]
+    fn test_negotiate_split() {
+        let caps = Capabilities::iqm("garnet", 20);
+        let request = CapabilityRequest::new().with_excess_shots(ShotPolicy::Split);
+        let mut job = job(50_001);
+
+        let outcome = negotiate(&mut job, &request, "garnet", &caps);
+        assert_eq!(
+            outcome,
+            Negotiation::Split {
+                backend: "garnet".into(),
+                max_shots: 20_000,
+                circuits: 4,
+            }
+        );
+        let labels: Vec<_> = (0..4).map(|i| job.circuit_label(i)).collect();
+        assert_eq!(labels, ["main#0", "main#1", "main#2", "cal"]);
+        let shots: Vec<_> = (0..4).map(|i| job.circuit_shots(i)).collect();
+        assert_eq!(shots, [16_667, 16_667, 16_667, 500]);
+        assert_eq!(job.total_shots(), 50_501);
+        job.validate_circuits().unwrap();
+    }
+}
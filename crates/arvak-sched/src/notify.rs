@@ -0,0 +1,184 @@
+//! Push-based job status updates.
+//!
+//! Polling `squeue` for every active job on every tick loads the batch
+//! controller of a large cluster. Instead, batch scripts can touch a
+//! `<batch job ID>.event` file in a shared event directory when they start
+//! and when they exit. A [`CompletionWatcher`] drains those files, and the
+//! scheduler polls just the jobs they name. Full polls of all active jobs
+//! remain as a fallback, backed off by an [`AdaptivePoll`] while events keep
+//! arriving.
+//!
+//! Status changes are published as [`StatusUpdate`]s, which
+//! `HpcScheduler::subscribe` turns into a stream per job.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::SchedResult;
+use crate::job::{ScheduledJobId, ScheduledJobStatus};
+
+/// Suffix of event files.
+pub const EVENT_SUFFIX: &str = ".event";
+
+/// A job's new status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusUpdate {
+    /// Job whose status changed.
+    pub job_id: ScheduledJobId,
+
+    /// New status.
+    pub status: ScheduledJobStatus,
+}
+
+/// Watches a directory for event files written by batch jobs.
+#[derive(Debug, Clone)]
+pub struct CompletionWatcher {
+    dir: PathBuf,
+}
+
+impl CompletionWatcher {
+    /// Watch `dir` for event files.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Watched directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Take the batch job IDs with new events, removing their event files.
+    ///
+    /// A missing directory has no events.
+    pub async fn drain(&self) -> SchedResult<Vec<String>> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut ids = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let Some(id) = name.to_str().and_then(|n| n.strip_suffix(EVENT_SUFFIX)) else {
+                continue;
+            };
+            // A job may touch the file again while it is removed; that
+            // event is then seen on the next drain.
+            match tokio::fs::remove_file(entry.path()).await {
+                Ok(()) => ids.push(id.to_string()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+    }
+}
+
+/// Interval of fallback polls, doubling while polls find nothing new.
+///
+/// Events postpone the next poll, but never beyond the maximum interval,
+/// since jobs killed before they could write an event are only seen by
+/// polls.
+#[derive(Debug, Clone)]
+pub struct AdaptivePoll {
+    min: Duration,
+    max: Duration,
+    current: Duration,
+    last_poll: Option<Instant>,
+    last_event: Option<Instant>,
+}
+
+impl AdaptivePoll {
+    /// Poll at least every `max` and at most every `min`.
+    pub fn new(min: Duration, max: Duration) -> Self {
+        Self {
+            min,
+            max: max.max(min),
+            current: min,
+            last_poll: None,
+            last_event: None,
+        }
+    }
+
+    /// Current interval.
+    pub fn interval(&self) -> Duration {
+        self.current
+    }
+
+    /// Check if a poll is due.
+    pub fn is_due(&self, now: Instant) -> bool {
+        let Some(last_poll) = self.last_poll else {
+            return true;
+        };
+        let last = self
+            .last_event
+            .map_or(last_poll, |event| event.max(last_poll));
+        now.saturating_duration_since(last_poll) >= self.max
+            || now.saturating_duration_since(last) >= self.current
+    }
+
+    /// Record a poll; one that found changes the events missed resets the
+    /// interval, one that found nothing doubles it.
+    pub fn polled(&mut self, now: Instant, changed: bool) {
+        self.last_poll = Some(now);
+        self.current = if changed {
+            self.min
+        } else {
+            (self.current * 2).min(self.max)
+        };
+    }
+
+    /// Record that events arrived, postponing the next poll.
+    pub fn event(&mut self, now: Instant) {
+        self.last_event = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_watcher_drains_event_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let watcher = CompletionWatcher::new(dir.path().join("events"));
+        assert!(watcher.drain().await.unwrap().is_empty());
+
+        std::fs::create_dir(watcher.dir()).unwrap();
+        for name in ["4242.event", "4243_1.event", "notes.txt"] {
+            std::fs::write(watcher.dir().join(name), "").unwrap();
+        }
+        assert_eq!(watcher.drain().await.unwrap(), vec!["4242", "4243_1"]);
+        assert!(watcher.drain().await.unwrap().is_empty());
+        assert!(watcher.dir().join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_adaptive_poll_backs_off() {
+        let (min, max) = (Duration::from_secs(10), Duration::from_secs(35));
+        let mut poll = AdaptivePoll::new(min, max);
+        let start = Instant::now();
+        assert!(poll.is_due(start));
+
+        poll.polled(start, false);
+        assert_eq!(poll.interval(), Duration::from_secs(20));
+        poll.polled(start, false);
+        assert_eq!(poll.interval(), max);
+        assert!(!poll.is_due(start + Duration::from_secs(30)));
+        assert!(poll.is_due(start + max));
+
+        poll.polled(start, true);
+        assert_eq!(poll.interval(), min);
+        poll.event(start + Duration::from_secs(8));
+        assert!(!poll.is_due(start + Duration::from_secs(12)));
+        assert!(poll.is_due(start + Duration::from_secs(18)));
+        for secs in [16, 24, 32] {
+            poll.event(start + Duration::from_secs(secs));
+        }
+        assert!(poll.is_due(start + max));
+    }
+}
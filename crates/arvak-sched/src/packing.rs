@@ -0,0 +1,552 @@
+//! Priority- and deadline-aware batch packing.
+//!
+//! Coalescing queued jobs into one batch job saves the batch system's
+//! per-submission overhead, but every job in a batch completes only when the
+//! whole batch does. [`BatchPacker`] decides which jobs share a batch and in
+//! which order the batches run, minimizing the priority-weighted sum of
+//! completion times without starting a job after its deadline where that
+//! can be avoided.
+//!
+//! Batches run one after another. A batch takes `batch_overhead_secs` plus
+//! the run time of its jobs. A job's weight is its priority and its deadline
+//! is its `max_queue_time`: the latest time its batch may start.
+//!
+//! The packer first fixes a job order, then splits it into consecutive
+//! batches by dynamic programming, which is optimal for that order. Two
+//! orders are tried, highest weight per second first (Smith's rule) and
+//! earliest deadline first, and the better one is improved by swapping
+//! neighbouring jobs.
+
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::{ScheduledJob, ScheduledJobId};
+
+/// Tolerance for comparing times and costs.
+const EPSILON: f64 = 1e-9;
+
+/// Largest number of jobs for which neighbour swaps are tried.
+const LOCAL_SEARCH_LIMIT: usize = 64;
+
+/// Configuration for batch packing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PackingConfig {
+    /// Maximum number of circuits in one batch.
+    pub max_circuits: usize,
+
+    /// Maximum run time of one batch, excluding overhead (seconds).
+    pub max_batch_secs: f64,
+
+    /// Fixed cost of submitting a batch (seconds).
+    pub batch_overhead_secs: f64,
+
+    /// Estimated run time per shot (seconds).
+    pub secs_per_shot: f64,
+}
+
+impl Default for PackingConfig {
+    fn default() -> Self {
+        Self {
+            max_circuits: 100,
+            max_batch_secs: 3600.0,
+            batch_overhead_secs: 60.0,
+            secs_per_shot: 0.001,
+        }
+    }
+}
+
+impl PackingConfig {
+    /// Set the maximum number of circuits per batch.
+    pub fn with_max_circuits(mut self, max_circuits: usize) -> Self {
+        self.max_circuits = max_circuits.max(1);
+        self
+    }
+
+    /// Set the maximum run time of a batch.
+    pub fn with_max_batch_secs(mut self, secs: f64) -> Self {
+        self.max_batch_secs = secs;
+        self
+    }
+
+    /// Set the per-batch overhead.
+    pub fn with_batch_overhead_secs(mut self, secs: f64) -> Self {
+        self.batch_overhead_secs = secs;
+        self
+    }
+
+    /// Set the estimated run time per shot.
+    pub fn with_secs_per_shot(mut self, secs: f64) -> Self {
+        self.secs_per_shot = secs;
+        self
+    }
+}
+
+/// A job as seen by the packer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackItem {
+    /// Job identifier.
+    pub job_id: ScheduledJobId,
+
+    /// Weight of the job's completion time.
+    pub weight: f64,
+
+    /// Number of circuits the job adds to a batch.
+    pub circuits: usize,
+
+    /// Estimated run time (seconds).
+    pub run_secs: f64,
+
+    /// Latest start, in seconds from now.
+    pub deadline_secs: Option<f64>,
+}
+
+impl PackItem {
+    /// Create an item without a deadline.
+    pub fn new(job_id: ScheduledJobId, weight: f64, circuits: usize, run_secs: f64) -> Self {
+        Self {
+            job_id,
+            weight,
+            circuits,
+            run_secs,
+            deadline_secs: None,
+        }
+    }
+
+    /// Set the latest start, in seconds from now.
+    pub fn with_deadline(mut self, secs: f64) -> Self {
+        self.deadline_secs = Some(secs);
+        self
+    }
+
+    /// Describe a queued job, estimating its run time from its shots.
+    pub fn from_job(job: &ScheduledJob, config: &PackingConfig, now: DateTime<Utc>) -> Self {
+        let circuits = job.circuits.len().max(1);
+        let run_secs = job.total_shots() as f64 * config.secs_per_shot;
+        let item = Self::new(
+            job.id.clone(),
+            f64::from(job.priority.value().max(1)),
+            circuits,
+            run_secs,
+        );
+        match job.requirements.max_queue_time {
+            Some(secs) => {
+                let deadline = job.created_at + chrono::Duration::seconds(secs as i64);
+                item.with_deadline((deadline - now).num_milliseconds() as f64 / 1000.0)
+            }
+            None => item,
+        }
+    }
+}
+
+/// One batch of a plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedBatch {
+    /// Jobs in the batch, in run order.
+    pub jobs: Vec<ScheduledJobId>,
+
+    /// Start of the batch, in seconds from now.
+    pub start_secs: f64,
+
+    /// End of the batch, in seconds from now.
+    pub end_secs: f64,
+}
+
+/// Batches chosen by the packer, in run order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchPlan {
+    /// The batches.
+    pub batches: Vec<PlannedBatch>,
+
+    /// Sum of each job's weight times its completion time.
+    pub weighted_completion: f64,
+
+    /// Jobs whose batch starts after their deadline.
+    pub late: Vec<ScheduledJobId>,
+}
+
+impl BatchPlan {
+    /// Number of jobs in the plan.
+    pub fn num_jobs(&self) -> usize {
+        self.batches.iter().map(|b| b.jobs.len()).sum()
+    }
+}
+
+/// Cost of a plan: weighted lateness first, then weighted completion time.
+#[derive(Debug, Clone, Copy, Default)]
+struct Cost {
+    tardiness: f64,
+    completion: f64,
+}
+
+impl Cost {
+    fn add(self, other: Cost) -> Cost {
+        Cost {
+            tardiness: self.tardiness + other.tardiness,
+            completion: self.completion + other.completion,
+        }
+    }
+
+    fn is_better(&self, other: &Cost) -> bool {
+        if (self.tardiness - other.tardiness).abs() > EPSILON {
+            return self.tardiness < other.tardiness;
+        }
+        self.completion < other.completion - EPSILON
+    }
+}
+
+/// Packs queued jobs into batches.
+#[derive(Debug, Clone, Default)]
+pub struct BatchPacker {
+    config: PackingConfig,
+}
+
+impl BatchPacker {
+    /// Create a packer.
+    pub fn new(config: PackingConfig) -> Self {
+        Self { config }
+    }
+
+    /// Get the packing configuration.
+    pub fn config(&self) -> &PackingConfig {
+        &self.config
+    }
+
+    /// Decide batch composition and order for the given jobs.
+    pub fn pack(&self, items: &[PackItem]) -> BatchPlan {
+        if items.is_empty() {
+            return BatchPlan::default();
+        }
+
+        let mut best = smith_order(items);
+        let mut best_cost = self.split(items, &best).0;
+        let edf = edf_order(items);
+        let edf_cost = self.split(items, &edf).0;
+        if edf_cost.is_better(&best_cost) {
+            best = edf;
+            best_cost = edf_cost;
+        }
+
+        if items.len() <= LOCAL_SEARCH_LIMIT {
+            let mut improved = true;
+            while improved {
+                improved = false;
+                for i in 0..best.len() - 1 {
+                    best.swap(i, i + 1);
+                    let cost = self.split(items, &best).0;
+                    if cost.is_better(&best_cost) {
+                        best_cost = cost;
+                        improved = true;
+                    } else {
+                        best.swap(i, i + 1);
+                    }
+                }
+            }
+        }
+
+        let (_, segments) = self.split(items, &best);
+        self.plan(items, &best, &segments)
+    }
+
+    /// Optimally split a fixed job order into consecutive batches.
+    ///
+    /// Returns the cost and the `(start, end)` index ranges of the batches.
+    fn split(&self, items: &[PackItem], order: &[usize]) -> (Cost, Vec<(usize, usize)>) {
+        let n = order.len();
+        let overhead = self.config.batch_overhead_secs;
+        let mut prefix = vec![0.0; n + 1];
+        for (i, &idx) in order.iter().enumerate() {
+            prefix[i + 1] = prefix[i] + items[idx].run_secs;
+        }
+
+        // best[i][k]: cheapest way to run the first i jobs in k batches,
+        // with the start index of the last batch.
+        let mut best: Vec<Vec<Option<(Cost, usize)>>> = vec![vec![None; n + 1]; n + 1];
+        best[0][0] = Some((Cost::default(), 0));
+
+        for i in 1..=n {
+            let mut circuits = 0;
+            let mut weight = 0.0;
+            let mut deadlines: Vec<(f64, f64)> = Vec::new();
+            for j in (0..i).rev() {
+                let item = &items[order[j]];
+                circuits += item.circuits;
+                weight += item.weight;
+                if let Some(deadline) = item.deadline_secs {
+                    deadlines.push((deadline, item.weight));
+                }
+                let run = prefix[i] - prefix[j];
+                if i - j > 1
+                    && (circuits > self.config.max_circuits
+                        || run > self.config.max_batch_secs + EPSILON)
+                {
+                    break;
+                }
+
+                let min_batches = usize::from(j > 0);
+                for k in (min_batches + 1)..=(j + 1) {
+                    let Some((prev, _)) = best[j][k - 1] else {
+                        continue;
+                    };
+                    let start = (k - 1) as f64 * overhead + prefix[j];
+                    let end = start + overhead + run;
+                    let tardiness = deadlines
+                        .iter()
+                        .map(|&(deadline, w)| w * (start - deadline).max(0.0))
+                        .sum();
+                    let cost = prev.add(Cost {
+                        tardiness,
+                        completion: weight * end,
+                    });
+                    if best[i][k].is_none_or(|(current, _)| cost.is_better(&current)) {
+                        best[i][k] = Some((cost, j));
+                    }
+                }
+            }
+        }
+
+        let (mut k, cost) = (1..=n)
+            .filter_map(|k| best[n][k].map(|(cost, _)| (k, cost)))
+            .reduce(|a, b| if b.1.is_better(&a.1) { b } else { a })
+            .expect("a single-job batch is always feasible");
+
+        let mut segments = Vec::with_capacity(k);
+        let mut i = n;
+        while i > 0 {
+            let (_, j) = best[i][k].expect("backtracking follows stored states");
+            segments.push((j, i));
+            i = j;
+            k -= 1;
+        }
+        segments.reverse();
+        (cost, segments)
+    }
+
+    /// Turn a split order into a plan.
+    fn plan(&self, items: &[PackItem], order: &[usize], segments: &[(usize, usize)]) -> BatchPlan {
+        let mut plan = BatchPlan::default();
+        let mut clock = 0.0;
+        for &(from, to) in segments {
+            let batch: Vec<&PackItem> = order[from..to].iter().map(|&i| &items[i]).collect();
+            let start = clock;
+            let end = start
+                + self.config.batch_overhead_secs
+                + batch.iter().map(|item| item.run_secs).sum::<f64>();
+            for item in &batch {
+                plan.weighted_completion += item.weight * end;
+                if item.deadline_secs.is_some_and(|d| start > d + EPSILON) {
+                    plan.late.push(item.job_id.clone());
+                }
+            }
+            plan.batches.push(PlannedBatch {
+                jobs: batch.iter().map(|item| item.job_id.clone()).collect(),
+                start_secs: start,
+                end_secs: end,
+            });
+            clock = end;
+        }
+        plan
+    }
+}
+
+/// Highest weight per second of run time first.
+fn smith_order(items: &[PackItem]) -> Vec<usize> {
+    let ratio = |item: &PackItem| item.weight / item.run_secs.max(EPSILON);
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| {
+        ratio(&items[b])
+            .partial_cmp(&ratio(&items[a]))
+            .unwrap_or(Ordering::Equal)
+    });
+    order
+}
+
+/// Earliest deadline first, then jobs without a deadline by Smith's rule.
+fn edf_order(items: &[PackItem]) -> Vec<usize> {
+    let mut order = smith_order(items);
+    order.sort_by(
+        |&a, &b| match (items[a].deadline_secs, items[b].deadline_secs) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    );
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, Priority, ResourceRequirements};
+
+    fn item(weight: f64, run_secs: f64) -> PackItem {
+        PackItem::new(ScheduledJobId::new(), weight, 1, run_secs)
+    }
+
+    /// Deterministic pseudo-random workload.
+    fn workload(n: usize, seed: u64) -> Vec<PackItem> {
+        let mut state = seed;
+        let mut next = move |bound: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (state >> 33) % bound
+        };
+        (0..n)
+            .map(|_| {
+                let weight = [50.0, 100.0, 200.0][next(3) as usize];
+                let run_secs = 10.0 + next(300) as f64;
+                let circuits = 1 + next(4) as usize;
+                let mut item = PackItem::new(ScheduledJobId::new(), weight, circuits, run_secs);
+                if next(4) == 0 {
+                    item = item.with_deadline(next(2000) as f64);
+                }
+                item
+            })
+            .collect()
+    }
+
+    /// Cost of running jobs in input order, filling each batch greedily.
+    fn first_fit_cost(config: &PackingConfig, items: &[PackItem]) -> f64 {
+        let mut cost = 0.0;
+        let mut clock = 0.0;
+        let mut batch: Vec<&PackItem> = Vec::new();
+        let mut flush = |batch: &mut Vec<&PackItem>, clock: &mut f64| {
+            let end =
+                *clock + config.batch_overhead_secs + batch.iter().map(|i| i.run_secs).sum::<f64>();
+            cost += batch.iter().map(|i| i.weight * end).sum::<f64>();
+            *clock = end;
+            batch.clear();
+        };
+        for item in items {
+            let circuits: usize = batch.iter().map(|i| i.circuits).sum();
+            let run: f64 = batch.iter().map(|i| i.run_secs).sum();
+            if !batch.is_empty()
+                && (circuits + item.circuits > config.max_circuits
+                    || run + item.run_secs > config.max_batch_secs)
+            {
+                flush(&mut batch, &mut clock);
+            }
+            batch.push(item);
+        }
+        flush(&mut batch, &mut clock);
+        cost
+    }
+
+    #[test]
+    fn test_high_priority_runs_first() {
+        let packer = BatchPacker::new(
+            PackingConfig::default()
+                .with_max_circuits(3)
+                .with_batch_overhead_secs(30.0),
+        );
+        let mut items: Vec<PackItem> = (0..6).map(|_| item(50.0, 100.0)).collect();
+        let urgent = item(500.0, 100.0);
+        items.push(urgent.clone());
+
+        let plan = packer.pack(&items);
+        assert_eq!(plan.num_jobs(), 7);
+        assert_eq!(plan.batches[0].jobs[0], urgent.job_id);
+        assert!(plan.batches.iter().all(|b| b.jobs.len() <= 3));
+        assert!(plan.late.is_empty());
+    }
+
+    #[test]
+    fn test_overhead_trades_off_batch_size() {
+        let items: Vec<PackItem> = (0..8).map(|_| item(100.0, 10.0)).collect();
+
+        // Expensive submissions: one batch amortizes the overhead.
+        let packer = BatchPacker::new(PackingConfig::default().with_batch_overhead_secs(600.0));
+        assert_eq!(packer.pack(&items).batches.len(), 1);
+
+        // Free submissions: nobody should wait for anyone else.
+        let packer = BatchPacker::new(PackingConfig::default().with_batch_overhead_secs(0.0));
+        assert_eq!(packer.pack(&items).batches.len(), 8);
+
+        // Capacity still caps batch size.
+        let packer = BatchPacker::new(
+            PackingConfig::default()
+                .with_batch_overhead_secs(600.0)
+                .with_max_batch_secs(35.0),
+        );
+        let plan = packer.pack(&items);
+        assert_eq!(plan.batches.len(), 3);
+        assert!(
+            plan.batches
+                .windows(2)
+                .all(|w| (w[0].end_secs - w[1].start_secs).abs() < EPSILON)
+        );
+    }
+
+    #[test]
+    fn test_deadline_overrides_priority() {
+        let packer = BatchPacker::new(
+            PackingConfig::default()
+                .with_max_circuits(2)
+                .with_batch_overhead_secs(10.0),
+        );
+        let mut items: Vec<PackItem> = (0..6).map(|_| item(200.0, 50.0)).collect();
+        let deadline = item(10.0, 50.0).with_deadline(0.0);
+        items.push(deadline.clone());
+
+        let plan = packer.pack(&items);
+        assert!(plan.batches[0].jobs.contains(&deadline.job_id));
+        assert!(plan.late.is_empty());
+
+        // Two jobs that must both start now cannot share a batch of one.
+        let packer = BatchPacker::new(PackingConfig::default().with_max_circuits(1));
+        let items = vec![
+            item(100.0, 50.0).with_deadline(0.0),
+            item(100.0, 50.0).with_deadline(0.0),
+        ];
+        assert_eq!(packer.pack(&items).late.len(), 1);
+    }
+
+    #[test]
+    fn test_synthetic_workloads_beat_first_fit() {
+        let config = PackingConfig::default()
+            .with_max_circuits(8)
+            .with_max_batch_secs(900.0)
+            .with_batch_overhead_secs(120.0);
+        let packer = BatchPacker::new(config.clone());
+
+        for seed in 1..=10 {
+            let items = workload(40, seed);
+            let plan = packer.pack(&items);
+
+            assert_eq!(plan.num_jobs(), items.len());
+            for batch in &plan.batches {
+                let batch_items: Vec<&PackItem> = batch
+                    .jobs
+                    .iter()
+                    .map(|id| items.iter().find(|i| &i.job_id == id).unwrap())
+                    .collect();
+                let circuits: usize = batch_items.iter().map(|i| i.circuits).sum();
+                let run: f64 = batch_items.iter().map(|i| i.run_secs).sum();
+                assert!(batch_items.len() == 1 || circuits <= config.max_circuits);
+                assert!(batch_items.len() == 1 || run <= config.max_batch_secs + EPSILON);
+            }
+            assert!(plan.weighted_completion <= first_fit_cost(&config, &items) + EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_item_from_job() {
+        let now = Utc::now();
+        let mut job =
+            ScheduledJob::batch("sweep", vec![CircuitSpec::from_qasm("OPENQASM 3.0;"); 4])
+                .with_priority(Priority::high())
+                .with_shots(1000)
+                .with_requirements(ResourceRequirements::default().with_max_queue_time(600));
+        job.created_at = now - chrono::Duration::seconds(100);
+
+        let item = PackItem::from_job(&job, &PackingConfig::default(), now);
+        assert_eq!(item.circuits, 4);
+        assert!((item.run_secs - 4.0).abs() < EPSILON);
+        assert_eq!(item.weight, f64::from(Priority::high().value()));
+        assert!((item.deadline_secs.unwrap() - 500.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,119 @@
+//! Partial results for long-running jobs.
+//!
+//! A batch job asked to report progress runs its shots in chunks and, after
+//! each chunk, overwrites a snapshot file with the counts accumulated so
+//! far. The job script tells `arvak run` where to write through
+//! [`PARTIAL_OUTPUT_ENV`] and how often through [`PARTIAL_SHOTS_ENV`].
+//!
+//! The scheduler reads the snapshots while the job runs and publishes every
+//! new one as a [`ResultUpdate`]. Snapshots are never final; the complete
+//! counts still arrive as the job's result.
+
+use std::path::{Path, PathBuf};
+
+use arvak_hal::PartialResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::ScheduledJobId;
+
+/// Environment variable naming the snapshot file a job writes to.
+pub const PARTIAL_OUTPUT_ENV: &str = "ARVAK_PARTIAL_OUTPUT";
+
+/// Environment variable holding the number of shots between snapshots.
+pub const PARTIAL_SHOTS_ENV: &str = "ARVAK_PARTIAL_SHOTS";
+
+/// Snapshot file belonging to a result file: `result.json` becomes
+/// `result.partial.json`.
+pub fn snapshot_path(result_file: &Path) -> PathBuf {
+    result_file.with_extension("partial.json")
+}
+
+/// Write a snapshot file.
+///
+/// The snapshot is written next to `path` and renamed over it, so a reader
+/// never sees a half-written file.
+pub fn write_snapshot(path: impl AsRef<Path>, partial: &PartialResult) -> SchedResult<()> {
+    let path = path.as_ref();
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, serde_json::to_vec(partial)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Read a snapshot file, or `None` if the job has not written one yet.
+pub fn read_snapshot(path: impl AsRef<Path>) -> SchedResult<Option<PartialResult>> {
+    let path = path.as_ref();
+    match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| SchedError::ParseError(format!("{}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// A new snapshot of a job's counts, as published by the scheduler.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultUpdate {
+    /// When the scheduler ingested the snapshot.
+    pub at: DateTime<Utc>,
+
+    /// Job the snapshot belongs to.
+    pub job_id: ScheduledJobId,
+
+    /// Index of the circuit within the job.
+    pub circuit_index: usize,
+
+    /// Counts so far.
+    pub partial: PartialResult,
+}
+
+impl ResultUpdate {
+    /// Create an update for one circuit of a job, timestamped now.
+    pub fn new(job_id: ScheduledJobId, circuit_index: usize, partial: PartialResult) -> Self {
+        Self {
+            at: Utc::now(),
+            job_id,
+            circuit_index,
+            partial,
+        }
+    }
+
+    /// Whether this update carries the job's complete counts.
+    pub fn is_final(&self) -> bool {
+        self.partial.is_final
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arvak_hal::Counts;
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("partial.json");
+        assert!(read_snapshot(&path).unwrap().is_none());
+
+        let partial = PartialResult::new(Counts::from_pairs([("00", 60), ("11", 40)]), 100, 1000);
+        write_snapshot(&path, &partial).unwrap();
+
+        let read = read_snapshot(&path).unwrap().unwrap();
+        assert_eq!(read.shots_completed, 100);
+        assert_eq!(read.shots_total, 1000);
+        assert_eq!(read.counts.get("00"), 60);
+        assert!(!read.is_final);
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(read_snapshot(&path).is_err());
+
+        assert_eq!(
+            snapshot_path(Path::new("/work/results/job.json")),
+            Path::new("/work/results/job.partial.json")
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Per-circuit results of multi-circuit jobs.
+//!
+//! A job may carry several circuits, each with its own shot count and result
+//! label (e.g. an experiment and its readout calibration). The batch script
+//! writes one result file per circuit; [`read_results`] collects them in
+//! circuit order and records in each result where it came from.
+
+use std::path::{Path, PathBuf};
+
+use arvak_hal::{ExecutionResult, NOISE_MODEL_KEY};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobId};
+
+/// Key of the provenance record in a result's metadata.
+pub const PROVENANCE_KEY: &str = "provenance";
+
+/// Where a circuit result came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitProvenance {
+    /// Job the circuit belonged to.
+    pub job_id: ScheduledJobId,
+
+    /// Index of the circuit within the job.
+    pub circuit_index: usize,
+
+    /// Result label of the circuit.
+    pub label: String,
+
+    /// Shots requested for the circuit.
+    pub shots: u32,
+
+    /// Canonical hash of the circuit, if it could be resolved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_hash: Option<u64>,
+
+    /// Backend the job was matched to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+
+    /// Noise model the result was simulated or mitigated with, e.g.
+    /// `calibration:garnet@3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_model: Option<String>,
+}
+
+impl CircuitProvenance {
+    /// Describe one circuit of a job.
+    pub fn new(job: &ScheduledJob, index: usize) -> Self {
+        Self {
+            job_id: job.id.clone(),
+            circuit_index: index,
+            label: job.circuit_label(index),
+            shots: job.circuit_shots(index),
+            circuit_hash: job
+                .circuits
+                .get(index)
+                .and_then(|spec| spec.canonical_hash().ok()),
+            backend: job.matched_backend.clone(),
+            noise_model: None,
+        }
+    }
+
+    /// Record the provenance in a result's metadata, keeping other entries.
+    ///
+    /// Unless set, the noise model is taken from the result's own metadata
+    /// (see [`NOISE_MODEL_KEY`]).
+    pub fn attach(&self, result: &mut ExecutionResult) -> SchedResult<()> {
+        let mut provenance = self.clone();
+        if provenance.noise_model.is_none() {
+            provenance.noise_model = result
+                .metadata
+                .get(NOISE_MODEL_KEY)
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+        }
+        let value = serde_json::to_value(&provenance)?;
+        match &mut result.metadata {
+            serde_json::Value::Object(map) => {
+                map.insert(PROVENANCE_KEY.to_string(), value);
+            }
+            metadata => *metadata = serde_json::json!({ PROVENANCE_KEY: value }),
+        }
+        Ok(())
+    }
+
+    /// Read the provenance recorded in a result, if any.
+    pub fn from_result(result: &ExecutionResult) -> Option<Self> {
+        let value = result.metadata.get(PROVENANCE_KEY)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+}
+
+/// The result of one circuit of a job.
+#[derive(Debug, Clone)]
+pub struct CircuitResult {
+    /// Index of the circuit within the job.
+    pub index: usize,
+
+    /// Result label of the circuit.
+    pub label: String,
+
+    /// The result, with its provenance in the metadata.
+    pub result: ExecutionResult,
+}
+
+/// Result files written by a job's batch script, one per circuit.
+///
+/// `result_path` is the file of a single-circuit job, or the directory
+/// holding `result_<i>.json` for a batch job.
+pub fn result_files(job: &ScheduledJob, result_path: &Path) -> Vec<PathBuf> {
+    if job.is_batch() {
+        (0..job.circuits.len())
+            .map(|i| result_path.join(format!("result_{}.json", i)))
+            .collect()
+    } else {
+        vec![result_path.to_path_buf()]
+    }
+}
+
+/// Read the per-circuit results of a job in circuit order.
+///
+/// Circuits that wrote no result file (e.g. because they failed) are left
+/// out; check [`CircuitResult::index`] to see which ones are present.
+pub async fn read_results(
+    job: &ScheduledJob,
+    result_path: &Path,
+) -> SchedResult<Vec<CircuitResult>> {
+    let mut results = Vec::new();
+    for (index, path) in result_files(job, result_path).into_iter().enumerate() {
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        results.push(parse_result(
+            job,
+            index,
+            &path.display().to_string(),
+            &data,
+        )?);
+    }
+    Ok(results)
+}
+
+/// Parse the result of one circuit, read from `source`, and attach its
+/// provenance.
+pub fn parse_result(
+    job: &ScheduledJob,
+    index: usize,
+    source: &str,
+    data: &[u8],
+) -> SchedResult<CircuitResult> {
+    let mut result: ExecutionResult = serde_json::from_slice(data)
+        .map_err(|e| SchedError::ParseError(format!("{}: {}", source, e)))?;
+    CircuitProvenance::new(job, index).attach(&mut result)?;
+    Ok(CircuitResult {
+        index,
+        label: job.circuit_label(index),
+        result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+    use arvak_hal::Counts;
+
+    #[tokio::test]
+    async fn test_read_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let qasm = "OPENQASM 3.0; qubit[1] q; x q[0];";
+        let job = ScheduledJob::batch(
+            "calibrated",
+            vec![
+                CircuitSpec::from_qasm(qasm).with_label("experiment"),
+                CircuitSpec::from_qasm(qasm).with_shots(100),
+                CircuitSpec::from_qasm(qasm).with_label("missing"),
+            ],
+        )
+        .with_shots(1000);
+
+        let files = result_files(&job, dir.path());
+        let mut first = ExecutionResult::new(Counts::from_pairs([("1", 1000)]), 1000);
+        first.metadata = serde_json::json!({ "device": "sim", NOISE_MODEL_KEY: "custom" });
+        std::fs::write(&files[0], serde_json::to_vec(&first).unwrap()).unwrap();
+        let second = ExecutionResult::new(Counts::from_pairs([("1", 100)]), 100);
+        std::fs::write(&files[1], serde_json::to_vec(&second).unwrap()).unwrap();
+
+        let results = read_results(&job, dir.path()).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].label, "experiment");
+        assert_eq!(results[1].label, "circuit_1");
+        assert_eq!(results[0].result.metadata["device"], "sim");
+        let provenance = CircuitProvenance::from_result(&results[0].result).unwrap();
+        assert_eq!(provenance.noise_model.as_deref(), Some("custom"));
+
+        let provenance = CircuitProvenance::from_result(&results[1].result).unwrap();
+        assert_eq!(provenance.job_id, job.id);
+        assert_eq!(provenance.circuit_index, 1);
+        assert_eq!(provenance.shots, 100);
+        assert_eq!(
+            provenance.circuit_hash,
+            Some(CircuitSpec::from_qasm(qasm).canonical_hash().unwrap())
+        );
+
+        std::fs::write(&files[2], "not json").unwrap();
+        assert!(matches!(
+            read_results(&job, dir.path()).await,
+            Err(SchedError::ParseError(_))
+        ));
+    }
+}
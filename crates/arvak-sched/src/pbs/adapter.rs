@@ -1,15 +1,20 @@
 //! PBS adapter for job submission and tracking.
 
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
 use tokio::fs;
 use tokio::process::Command;
 
+use crate::batch::BatchSystem;
 use crate::error::{SchedError, SchedResult};
-use crate::job::ScheduledJob;
+use crate::job::{ScheduledJob, ScheduledJobStatus};
+use crate::payload::{self, CircuitResult};
 use crate::pbs::parser;
 use crate::pbs::templates;
+use crate::retry::FailureKind;
 
 /// PBS job state.
 ///
@@ -117,7 +122,8 @@ pub struct PbsResourcesUsed {
 }
 
 /// Configuration for PBS adapter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct PbsConfig {
     /// PBS queue to submit to.
     pub queue: String,
@@ -156,6 +162,7 @@ pub struct PbsConfig {
     pub extra_directives: Vec<String>,
 
     /// Mapping from priority value to PBS queue names.
+    #[serde(deserialize_with = "crate::config::priority_mapping")]
     pub priority_queue_mapping: Option<rustc_hash::FxHashMap<u32, String>>,
 }
 
@@ -328,6 +335,14 @@ impl PbsAdapter {
         }
     }
 
+    /// Read the per-circuit results written by a completed job.
+    pub async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        if self.mock_mode {
+            return Ok(Vec::new());
+        }
+        payload::read_results(job, &self.result_path(job)).await
+    }
+
     /// Write circuit files for a job.
     async fn write_circuits(&self, job: &ScheduledJob) -> SchedResult<Vec<PathBuf>> {
         let mut paths = Vec::with_capacity(job.circuits.len());
@@ -476,6 +491,110 @@ impl PbsAdapter {
     }
 }
 
+#[async_trait]
+impl BatchSystem for PbsAdapter {
+    fn name(&self) -> &str {
+        "PBS"
+    }
+
+    async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+        PbsAdapter::submit(self, job).await
+    }
+
+    async fn poll(
+        &self,
+        job: &ScheduledJob,
+        batch_job_id: &str,
+    ) -> SchedResult<ScheduledJobStatus> {
+        let info = self.status(batch_job_id).await?;
+        Ok(map_state(job, info))
+    }
+
+    async fn cancel(&self, batch_job_id: &str) -> SchedResult<()> {
+        PbsAdapter::cancel(self, batch_job_id).await
+    }
+
+    async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        PbsAdapter::read_results(self, job).await
+    }
+
+    async fn pending_reason(&self, batch_job_id: &str) -> SchedResult<Option<String>> {
+        let info = self.status(batch_job_id).await?;
+        Ok(match info.state {
+            // Named like SLURM's hold reasons so explanations classify it.
+            PbsState::Held => Some("JobHeld".to_string()),
+            PbsState::Waiting => Some("Waiting for start time".to_string()),
+            _ => None,
+        })
+    }
+}
+
+/// Map a PBS job state to the status of the job it runs.
+fn map_state(job: &ScheduledJob, info: PbsJobInfo) -> ScheduledJobStatus {
+    let pbs_job_id = info.job_id;
+
+    match info.state {
+        PbsState::Queued | PbsState::Waiting | PbsState::Held => ScheduledJobStatus::SlurmQueued {
+            slurm_job_id: pbs_job_id,
+        },
+        PbsState::Running | PbsState::Exiting | PbsState::ArrayRunning => {
+            ScheduledJobStatus::SlurmRunning {
+                slurm_job_id: pbs_job_id,
+            }
+        }
+        PbsState::Completed => {
+            // Check exit status to determine if it was a success
+            if info.exit_status == Some(0) || info.exit_status.is_none() {
+                ScheduledJobStatus::Completed {
+                    slurm_job_id: pbs_job_id,
+                    quantum_job_id: arvak_hal::JobId("completed".to_string()),
+                }
+            } else {
+                ScheduledJobStatus::Failed {
+                    reason: format!("PBS job failed with exit status {:?}", info.exit_status),
+                    kind: info
+                        .exit_status
+                        .map_or(FailureKind::Application, failure_kind),
+                    slurm_job_id: Some(pbs_job_id),
+                    quantum_job_id: job.status.quantum_job_id().cloned(),
+                }
+            }
+        }
+        PbsState::Failed => ScheduledJobStatus::Failed {
+            reason: "PBS job failed".to_string(),
+            kind: FailureKind::Application,
+            slurm_job_id: Some(pbs_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        PbsState::Suspended | PbsState::Transit => {
+            // Keep current status for suspended/transit jobs
+            job.status.clone()
+        }
+        PbsState::Unknown(state) => {
+            tracing::warn!("Unknown PBS state: {}", state);
+            job.status.clone()
+        }
+    }
+}
+
+/// PBS exit statuses of jobs killed for exceeding their memory limits.
+const JOB_EXEC_KILL_MEM: i32 = -25;
+const JOB_EXEC_KILL_VMEM: i32 = -26;
+
+/// PBS exit status of a job killed for exceeding its walltime.
+const JOB_EXEC_KILL_WALLTIME: i32 = -29;
+
+/// Classify a PBS exit status: negative statuses are set by PBS itself when
+/// it could not run or had to kill the job, not by the job's program.
+fn failure_kind(exit_status: i32) -> FailureKind {
+    match exit_status {
+        JOB_EXEC_KILL_WALLTIME => FailureKind::Timeout,
+        JOB_EXEC_KILL_MEM | JOB_EXEC_KILL_VMEM => FailureKind::OutOfMemory,
+        status if status < 0 => FailureKind::NodeFailure,
+        _ => FailureKind::Application,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -532,4 +651,44 @@ mod tests {
         assert_eq!(config.nodes, 1);
         assert_eq!(config.ppn, 1);
     }
+
+    #[test]
+    fn test_map_pbs_state() {
+        let job = ScheduledJob::new("test_job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let info = |state, exit_status| PbsJobInfo {
+            job_id: "42.pbs-server".to_string(),
+            name: "test_job".to_string(),
+            state,
+            queue: None,
+            exit_status,
+            walltime_used: None,
+            resources_used: None,
+        };
+
+        assert!(matches!(
+            map_state(&job, info(PbsState::Held, None)),
+            ScheduledJobStatus::SlurmQueued { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(PbsState::Exiting, None)),
+            ScheduledJobStatus::SlurmRunning { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(PbsState::Completed, Some(0))),
+            ScheduledJobStatus::Completed { .. }
+        ));
+        assert!(matches!(
+            map_state(&job, info(PbsState::Completed, Some(1))),
+            ScheduledJobStatus::Failed { ref reason, kind: FailureKind::Application, .. }
+                if reason.contains("exit status")
+        ));
+        assert!(matches!(
+            map_state(&job, info(PbsState::Completed, Some(-29))),
+            ScheduledJobStatus::Failed {
+                kind: FailureKind::Timeout,
+                ..
+            }
+        ));
+        assert_eq!(map_state(&job, info(PbsState::Suspended, None)), job.status);
+    }
 }
@@ -108,7 +108,7 @@ pub fn generate_pbs_script(
         "{} run {} --shots {} {} --output {}\n",
         config.arvak_binary.display(),
         circuit_file.display(),
-        job.shots,
+        job.circuit_shots(0),
         backend_flag,
         result_file.display(),
     ));
@@ -219,15 +219,16 @@ pub fn generate_pbs_script_multi(
     for (i, circuit_file) in circuit_files.iter().enumerate() {
         let result_file = result_dir.join(format!("result_{}.json", i));
         script.push_str(&format!(
-            "echo \"Running circuit {} of {}\"\n",
+            "echo \"Running circuit {} of {} ({})\"\n",
             i + 1,
-            circuit_files.len()
+            circuit_files.len(),
+            sanitize_name(&job.circuit_label(i))
         ));
         script.push_str(&format!(
             "if ! {} run {} --shots {} {} --output {}; then\n",
             config.arvak_binary.display(),
             circuit_file.display(),
-            job.shots,
+            job.circuit_shots(i),
             backend_flag,
             result_file.display(),
         ));
@@ -351,7 +352,11 @@ pub fn generate_pbs_array_script(
     for circuit_file in circuit_files {
         script.push_str(&format!("    \"{}\"\n", circuit_file.display()));
     }
-    script.push_str(")\n\n");
+    script.push_str(")\n");
+    let shots: Vec<String> = (0..circuit_files.len())
+        .map(|i| job.circuit_shots(i).to_string())
+        .collect();
+    script.push_str(&format!("SHOTS=({})\n\n", shots.join(" ")));
 
     // Select circuit based on array index
     script.push_str("CIRCUIT=${CIRCUITS[$PBS_ARRAYID]}\n");
@@ -370,9 +375,8 @@ pub fn generate_pbs_array_script(
     };
 
     script.push_str(&format!(
-        "{} run $CIRCUIT --shots {} {} --output $RESULT\n",
+        "{} run $CIRCUIT --shots ${{SHOTS[$PBS_ARRAYID]}} {} --output $RESULT\n",
         config.arvak_binary.display(),
-        job.shots,
         backend_flag,
     ));
 
@@ -489,5 +493,6 @@ mod tests {
         assert!(script.contains("#PBS -t 0-1")); // Array indices
         assert!(script.contains("CIRCUITS=("));
         assert!(script.contains("$PBS_ARRAYID"));
+        assert!(script.contains("SHOTS=(1024 1024)"));
     }
 }
@@ -0,0 +1,923 @@
+//! Cold-storage archival of old jobs.
+//!
+//! Terminal jobs older than the configured retention window are moved out of
+//! the hot store into gzip-compressed JSON bundles on an [`ArchiveBackend`]:
+//! a local directory ([`FilesystemArchive`]) or a generic HTTP store
+//! ([`HttpArchive`]). Each archived job leaves a lightweight stub record
+//! behind that points at its bundle, and [`ArchivingStore`] rehydrates the
+//! full record transparently when the job or its result is accessed. Saving
+//! or deleting an archived job through the store takes it out of its bundle.
+//!
+//! Bundles are JSON only; there is no Parquet format. [`HttpArchive`] is not
+//! an S3 client: it authenticates with a bearer token and does not sign
+//! requests with AWS SigV4, so S3 and other stores requiring signed requests
+//! need a gateway in front or their own [`ArchiveBackend`].
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use arvak_config::Secret;
+use arvak_hal::ExecutionResult;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use crate::calibration::BackendCalibration;
+use crate::error::{SchedError, SchedResult};
+use crate::gc::{ArtifactIndex, JobArtifacts, remove_artifacts};
+use crate::iteration::IterationRecord;
+use crate::job::{JobFilter, ScheduledJob, ScheduledJobId, ScheduledJobStatus};
+use crate::persistence::StateStore;
+use crate::recurring::RecurringJob;
+use crate::reservation::Reservation;
+use crate::template::JobTemplate;
+use crate::workflow::{Workflow, WorkflowId};
+
+/// Metadata key on stub records holding the archive bundle key.
+pub const ARCHIVE_BUNDLE_KEY: &str = "arvak.archive.bundle";
+
+/// Current archive bundle format version.
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Object storage for archive bundles.
+///
+/// Implemented for directories by [`FilesystemArchive`] and for plain HTTP
+/// stores by [`HttpArchive`].
+#[async_trait]
+pub trait ArchiveBackend: Send + Sync {
+    /// Store a bundle under the given key, replacing any existing object.
+    async fn put(&self, key: &str, data: Vec<u8>) -> SchedResult<()>;
+
+    /// Fetch a bundle by key.
+    async fn get(&self, key: &str) -> SchedResult<Option<Vec<u8>>>;
+
+    /// Delete a bundle by key.
+    async fn delete(&self, key: &str) -> SchedResult<bool>;
+}
+
+/// Archive backend storing bundles as files below a root directory.
+pub struct FilesystemArchive {
+    root: PathBuf,
+}
+
+impl FilesystemArchive {
+    /// Create a filesystem archive rooted at the given directory.
+    pub async fn new(root: impl AsRef<Path>) -> SchedResult<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root).await?;
+        Ok(Self { root })
+    }
+
+    fn object_path(&self, key: &str) -> SchedResult<PathBuf> {
+        check_key(key)?;
+        Ok(self.root.join(key))
+    }
+}
+
+/// Reject keys that are empty or climb out of the archive root.
+fn check_key(key: &str) -> SchedResult<()> {
+    if key.split('/').any(|part| part.is_empty() || part == "..") {
+        return Err(SchedError::PersistenceError(format!(
+            "Invalid archive key: {}",
+            key
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ArchiveBackend for FilesystemArchive {
+    async fn put(&self, key: &str, data: Vec<u8>) -> SchedResult<()> {
+        let path = self.object_path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&path, data).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> SchedResult<Option<Vec<u8>>> {
+        let path = self.object_path(key)?;
+        match fs::read(&path).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> SchedResult<bool> {
+        let path = self.object_path(key)?;
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+}
+
+/// Archive backend storing bundles in a generic HTTP store that accepts
+/// `PUT`, `GET` and `DELETE` below a base URL, e.g. a WebDAV share or an
+/// object-store gateway. Requests carry an optional bearer token and are not
+/// SigV4-signed.
+pub struct HttpArchive {
+    base_url: String,
+    token: Option<Secret>,
+    http: reqwest::Client,
+}
+
+impl HttpArchive {
+    /// Create an archive storing bundles below the given base URL.
+    pub fn new(base_url: impl Into<String>) -> SchedResult<Self> {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .map_err(|e| SchedError::ObjectStoreError {
+                url: base_url.clone(),
+                message: format!("Failed to create HTTP client: {}", e),
+            })?;
+        Ok(Self {
+            base_url,
+            token: None,
+            http,
+        })
+    }
+
+    /// Send a bearer token with every request.
+    pub fn with_token(mut self, token: Secret) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Send a request for an object, returning `None` if it does not exist.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        body: Option<Vec<u8>>,
+    ) -> SchedResult<Option<reqwest::Response>> {
+        check_key(key)?;
+        let url = format!("{}/{}", self.base_url, key);
+        let mut request = self.http.request(method, &url);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token.expose());
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+
+        let error = |message: String| SchedError::ObjectStoreError {
+            url: url.clone(),
+            message,
+        };
+        let response = request.send().await.map_err(|e| error(e.to_string()))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(error(format!("HTTP {}", response.status())));
+        }
+        Ok(Some(response))
+    }
+}
+
+#[async_trait]
+impl ArchiveBackend for HttpArchive {
+    async fn put(&self, key: &str, data: Vec<u8>) -> SchedResult<()> {
+        match self.send(reqwest::Method::PUT, key, Some(data)).await? {
+            Some(_) => Ok(()),
+            None => Err(SchedError::ObjectStoreError {
+                url: format!("{}/{}", self.base_url, key),
+                message: "HTTP 404 Not Found".to_string(),
+            }),
+        }
+    }
+
+    async fn get(&self, key: &str) -> SchedResult<Option<Vec<u8>>> {
+        let Some(response) = self.send(reqwest::Method::GET, key, None).await? else {
+            return Ok(None);
+        };
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| SchedError::ObjectStoreError {
+                url: format!("{}/{}", self.base_url, key),
+                message: e.to_string(),
+            })?;
+        Ok(Some(data.to_vec()))
+    }
+
+    async fn delete(&self, key: &str) -> SchedResult<bool> {
+        Ok(self
+            .send(reqwest::Method::DELETE, key, None)
+            .await?
+            .is_some())
+    }
+}
+
+/// Policy controlling which jobs are archived.
+#[derive(Debug, Clone)]
+pub struct ArchivePolicy {
+    /// Archive terminal jobs completed more than this many days ago.
+    pub max_age_days: u32,
+
+    /// Maximum number of jobs written into a single bundle.
+    pub max_jobs_per_bundle: usize,
+}
+
+impl Default for ArchivePolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: 30,
+            max_jobs_per_bundle: 1000,
+        }
+    }
+}
+
+impl ArchivePolicy {
+    /// Create a policy archiving jobs older than the given number of days.
+    pub fn older_than_days(days: u32) -> Self {
+        Self {
+            max_age_days: days,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum number of jobs per bundle.
+    pub fn with_max_jobs_per_bundle(mut self, max: usize) -> Self {
+        self.max_jobs_per_bundle = max.max(1);
+        self
+    }
+
+    /// Check whether a job is eligible for archival at the given time.
+    pub fn is_eligible(&self, job: &ScheduledJob, now: DateTime<Utc>) -> bool {
+        let cutoff = now - chrono::Duration::days(i64::from(self.max_age_days));
+        job.status.is_terminal()
+            && !is_archived(job)
+            && job.completed_at.is_some_and(|t| t < cutoff)
+    }
+}
+
+/// A single job and its result inside an archive bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedJob {
+    /// The full job record.
+    pub job: ScheduledJob,
+
+    /// The execution result, if one was stored.
+    pub result: Option<ExecutionResult>,
+}
+
+/// A compressed bundle of archived jobs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveBundle {
+    /// Bundle format version.
+    pub version: u32,
+
+    /// When the bundle was written.
+    pub created_at: DateTime<Utc>,
+
+    /// Archived jobs.
+    pub jobs: Vec<ArchivedJob>,
+}
+
+impl ArchiveBundle {
+    /// Encode the bundle as gzip-compressed JSON.
+    pub fn encode(&self) -> SchedResult<Vec<u8>> {
+        let json = serde_json::to_vec(self)?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json)?;
+        Ok(encoder.finish()?)
+    }
+
+    /// Decode a bundle from gzip-compressed JSON.
+    pub fn decode(data: &[u8]) -> SchedResult<Self> {
+        let mut json = Vec::new();
+        GzDecoder::new(data).read_to_end(&mut json)?;
+        let bundle: ArchiveBundle = serde_json::from_slice(&json)?;
+        if bundle.version > BUNDLE_FORMAT_VERSION {
+            return Err(SchedError::PersistenceError(format!(
+                "Unsupported archive bundle version: {}",
+                bundle.version
+            )));
+        }
+        Ok(bundle)
+    }
+
+    /// Find an archived job by ID.
+    pub fn find(&self, job_id: &ScheduledJobId) -> Option<&ArchivedJob> {
+        self.jobs.iter().find(|a| &a.job.id == job_id)
+    }
+}
+
+/// Summary of an archival run.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveReport {
+    /// Number of jobs moved to cold storage.
+    pub archived_jobs: usize,
+
+    /// Keys of the bundles written.
+    pub bundles: Vec<String>,
+
+    /// Number of artifact files removed for archived jobs.
+    pub artifacts_removed: usize,
+}
+
+/// Check whether a job record is an archive stub.
+pub fn is_archived(job: &ScheduledJob) -> bool {
+    job.metadata.contains_key(ARCHIVE_BUNDLE_KEY)
+}
+
+/// Build the stub record left in the hot store for an archived job.
+fn make_stub(job: &ScheduledJob, bundle_key: &str) -> ScheduledJob {
+    let mut stub = job.clone();
+    stub.circuits.clear();
+    stub.metadata
+        .insert(ARCHIVE_BUNDLE_KEY.to_string(), bundle_key.to_string());
+    stub
+}
+
+/// State store decorator that archives old jobs to cold storage and
+/// rehydrates them transparently on access.
+pub struct ArchivingStore {
+    inner: Arc<dyn StateStore>,
+    archive: Arc<dyn ArchiveBackend>,
+    policy: ArchivePolicy,
+    artifacts: Option<Arc<dyn JobArtifacts>>,
+    /// Serializes read-modify-write cycles on bundles.
+    bundle_lock: tokio::sync::Mutex<()>,
+}
+
+impl ArchivingStore {
+    /// Wrap a store with the given archive backend and policy.
+    pub fn new(
+        inner: Arc<dyn StateStore>,
+        archive: Arc<dyn ArchiveBackend>,
+        policy: ArchivePolicy,
+    ) -> Self {
+        Self {
+            inner,
+            archive,
+            policy,
+            artifacts: None,
+            bundle_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Remove each job's batch artifacts (scripts, circuits, logs) once it
+    /// has been archived.
+    pub fn with_artifacts(mut self, artifacts: Arc<dyn JobArtifacts>) -> Self {
+        self.artifacts = Some(artifacts);
+        self
+    }
+
+    /// Get the archival policy.
+    pub fn policy(&self) -> &ArchivePolicy {
+        &self.policy
+    }
+
+    /// Move all eligible jobs into archive bundles, leaving stub records.
+    pub async fn archive_old_jobs(&self) -> SchedResult<ArchiveReport> {
+        let now = Utc::now();
+        let eligible: Vec<_> = self
+            .inner
+            .list_jobs(&JobFilter::default())
+            .await?
+            .into_iter()
+            .filter(|job| self.policy.is_eligible(job, now))
+            .collect();
+
+        let mut index = match &self.artifacts {
+            Some(artifacts) if !eligible.is_empty() => {
+                Some(ArtifactIndex::scan(artifacts.as_ref()).await?)
+            }
+            _ => None,
+        };
+
+        let mut report = ArchiveReport::default();
+        for chunk in eligible.chunks(self.policy.max_jobs_per_bundle) {
+            let mut jobs = Vec::with_capacity(chunk.len());
+            for job in chunk {
+                let result = self.inner.load_result(&job.id).await?;
+                jobs.push(ArchivedJob {
+                    job: job.clone(),
+                    result,
+                });
+            }
+
+            let bundle = ArchiveBundle {
+                version: BUNDLE_FORMAT_VERSION,
+                created_at: now,
+                jobs,
+            };
+            let key = format!(
+                "jobs/{}/{}.json.gz",
+                now.format("%Y-%m-%d"),
+                uuid::Uuid::new_v4()
+            );
+
+            // Write the bundle before touching the hot store so a failure
+            // never loses data.
+            self.archive.put(&key, bundle.encode()?).await?;
+
+            for job in chunk {
+                self.inner.save_job(&make_stub(job, &key)).await?;
+                self.inner.delete_result(&job.id).await?;
+                if let Some(index) = index.as_mut() {
+                    let (files, _) = remove_artifacts(&index.take_job(job), false).await?;
+                    report.artifacts_removed += files;
+                }
+            }
+
+            tracing::info!("Archived {} job(s) to bundle {}", chunk.len(), key);
+            report.archived_jobs += chunk.len();
+            report.bundles.push(key);
+        }
+
+        Ok(report)
+    }
+
+    /// Load an archived job and its result from cold storage.
+    pub async fn rehydrate(&self, stub: &ScheduledJob) -> SchedResult<ArchivedJob> {
+        let (key, bundle) = self.load_bundle(stub).await?;
+        bundle.find(&stub.id).cloned().ok_or_else(|| {
+            SchedError::PersistenceError(format!(
+                "Job {} missing from archive bundle {}",
+                stub.id, key
+            ))
+        })
+    }
+
+    /// Fetch and decode the bundle a stub points at.
+    async fn load_bundle(&self, stub: &ScheduledJob) -> SchedResult<(String, ArchiveBundle)> {
+        let key = stub.metadata.get(ARCHIVE_BUNDLE_KEY).ok_or_else(|| {
+            SchedError::PersistenceError(format!("Job {} is not archived", stub.id))
+        })?;
+        let data = self.archive.get(key).await?.ok_or_else(|| {
+            SchedError::PersistenceError(format!("Archive bundle {} not found", key))
+        })?;
+        Ok((key.clone(), ArchiveBundle::decode(&data)?))
+    }
+
+    /// Remove an archived job from its bundle, rewriting the bundle or
+    /// deleting it once it is empty. Returns the removed entry, if present.
+    async fn evict(&self, stub: &ScheduledJob) -> SchedResult<Option<ArchivedJob>> {
+        let _guard = self.bundle_lock.lock().await;
+        let (key, mut bundle) = self.load_bundle(stub).await?;
+        let Some(pos) = bundle.jobs.iter().position(|a| a.job.id == stub.id) else {
+            return Ok(None);
+        };
+        let evicted = bundle.jobs.remove(pos);
+        if bundle.jobs.is_empty() {
+            self.archive.delete(&key).await?;
+        } else {
+            self.archive.put(&key, bundle.encode()?).await?;
+        }
+        Ok(Some(evicted))
+    }
+
+    /// Load the hot-store record for a job if it is an archive stub.
+    async fn load_stub(&self, job_id: &ScheduledJobId) -> SchedResult<Option<ScheduledJob>> {
+        Ok(self.inner.load_job(job_id).await?.filter(is_archived))
+    }
+}
+
+#[async_trait]
+impl StateStore for ArchivingStore {
+    async fn save_job(&self, job: &ScheduledJob) -> SchedResult<()> {
+        // Saving a stub keeps the job archived. Saving a full record over a
+        // stub brings the job back into the hot store with its result, and
+        // only then drops it from the bundle so a failure never loses data.
+        if is_archived(job) {
+            return self.inner.save_job(job).await;
+        }
+        let Some(stub) = self.load_stub(&job.id).await? else {
+            return self.inner.save_job(job).await;
+        };
+        let archived = self.rehydrate(&stub).await?;
+        self.inner.save_job(job).await?;
+        if let Some(result) = &archived.result {
+            if self.inner.load_result(&job.id).await?.is_none() {
+                self.inner.save_result(&job.id, result).await?;
+            }
+        }
+        self.evict(&stub).await?;
+        Ok(())
+    }
+
+    async fn load_job(&self, job_id: &ScheduledJobId) -> SchedResult<Option<ScheduledJob>> {
+        match self.inner.load_job(job_id).await? {
+            Some(job) if is_archived(&job) => Ok(Some(self.rehydrate(&job).await?.job)),
+            other => Ok(other),
+        }
+    }
+
+    async fn update_status(
+        &self,
+        job_id: &ScheduledJobId,
+        status: ScheduledJobStatus,
+    ) -> SchedResult<()> {
+        self.inner.update_status(job_id, status).await
+    }
+
+    async fn delete_job(&self, job_id: &ScheduledJobId) -> SchedResult<bool> {
+        let stub = self.load_stub(job_id).await?;
+        let deleted = self.inner.delete_job(job_id).await?;
+        if let Some(stub) = stub {
+            self.evict(&stub).await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn list_jobs(&self, filter: &JobFilter) -> SchedResult<Vec<ScheduledJob>> {
+        // Listing returns stubs; callers needing circuits use load_job().
+        self.inner.list_jobs(filter).await
+    }
+
+    async fn save_result(
+        &self,
+        job_id: &ScheduledJobId,
+        result: &ExecutionResult,
+    ) -> SchedResult<()> {
+        self.inner.save_result(job_id, result).await
+    }
+
+    async fn load_result(&self, job_id: &ScheduledJobId) -> SchedResult<Option<ExecutionResult>> {
+        if let Some(result) = self.inner.load_result(job_id).await? {
+            return Ok(Some(result));
+        }
+        match self.inner.load_job(job_id).await? {
+            Some(job) if is_archived(&job) => Ok(self.rehydrate(&job).await?.result),
+            _ => Ok(None),
+        }
+    }
+
+    async fn delete_result(&self, job_id: &ScheduledJobId) -> SchedResult<bool> {
+        self.inner.delete_result(job_id).await
+    }
+
+    async fn save_workflow(&self, workflow: &Workflow) -> SchedResult<()> {
+        self.inner.save_workflow(workflow).await
+    }
+
+    async fn load_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Option<Workflow>> {
+        self.inner.load_workflow(workflow_id).await
+    }
+
+    async fn delete_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<bool> {
+        self.inner.delete_workflow(workflow_id).await
+    }
+
+    async fn list_workflows(&self) -> SchedResult<Vec<WorkflowId>> {
+        self.inner.list_workflows().await
+    }
+
+    async fn save_iteration(&self, record: &IterationRecord) -> SchedResult<()> {
+        self.inner.save_iteration(record).await
+    }
+
+    async fn load_iterations(&self, run_id: &str) -> SchedResult<Vec<IterationRecord>> {
+        self.inner.load_iterations(run_id).await
+    }
+
+    async fn save_cache_entry(&self, key: &str, job_id: &ScheduledJobId) -> SchedResult<()> {
+        self.inner.save_cache_entry(key, job_id).await
+    }
+
+    async fn load_cache_entry(&self, key: &str) -> SchedResult<Option<ScheduledJobId>> {
+        self.inner.load_cache_entry(key).await
+    }
+
+    async fn find_job_by_ref(&self, reference: &str) -> SchedResult<Option<ScheduledJobId>> {
+        self.inner.find_job_by_ref(reference).await
+    }
+
+    async fn save_template(&self, template: &JobTemplate) -> SchedResult<()> {
+        self.inner.save_template(template).await
+    }
+
+    async fn load_template(&self, name: &str) -> SchedResult<Option<JobTemplate>> {
+        self.inner.load_template(name).await
+    }
+
+    async fn delete_template(&self, name: &str) -> SchedResult<bool> {
+        self.inner.delete_template(name).await
+    }
+
+    async fn list_templates(&self) -> SchedResult<Vec<JobTemplate>> {
+        self.inner.list_templates().await
+    }
+
+    async fn save_recurring(&self, recurring: &RecurringJob) -> SchedResult<()> {
+        self.inner.save_recurring(recurring).await
+    }
+
+    async fn load_recurring(&self, name: &str) -> SchedResult<Option<RecurringJob>> {
+        self.inner.load_recurring(name).await
+    }
+
+    async fn delete_recurring(&self, name: &str) -> SchedResult<bool> {
+        self.inner.delete_recurring(name).await
+    }
+
+    async fn list_recurring(&self) -> SchedResult<Vec<RecurringJob>> {
+        self.inner.list_recurring().await
+    }
+
+    async fn save_reservation(&self, reservation: &Reservation) -> SchedResult<()> {
+        self.inner.save_reservation(reservation).await
+    }
+
+    async fn load_reservation(&self, name: &str) -> SchedResult<Option<Reservation>> {
+        self.inner.load_reservation(name).await
+    }
+
+    async fn delete_reservation(&self, name: &str) -> SchedResult<bool> {
+        self.inner.delete_reservation(name).await
+    }
+
+    async fn list_reservations(&self) -> SchedResult<Vec<Reservation>> {
+        self.inner.list_reservations().await
+    }
+
+    async fn save_calibration(&self, calibration: &BackendCalibration) -> SchedResult<()> {
+        self.inner.save_calibration(calibration).await
+    }
+
+    async fn load_calibration(
+        &self,
+        backend: &str,
+        epoch: Option<u64>,
+    ) -> SchedResult<Option<BackendCalibration>> {
+        self.inner.load_calibration(backend, epoch).await
+    }
+
+    async fn cleanup_old_jobs(&self, max_age_seconds: u64) -> SchedResult<usize> {
+        self.inner.cleanup_old_jobs(max_age_seconds).await
+    }
+
+    async fn rebuild_indices(&self) -> SchedResult<usize> {
+        self.inner.rebuild_indices().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+    use crate::persistence::SqliteStore;
+    use arvak_hal::{Counts, JobId};
+
+    fn old_completed_job(name: &str, days_ago: i64) -> ScheduledJob {
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let mut job = ScheduledJob::new(name, circuit);
+        job.status = ScheduledJobStatus::Completed {
+            slurm_job_id: "1".to_string(),
+            quantum_job_id: JobId("q".to_string()),
+        };
+        job.completed_at = Some(Utc::now() - chrono::Duration::days(days_ago));
+        job
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let bundle = ArchiveBundle {
+            version: BUNDLE_FORMAT_VERSION,
+            created_at: Utc::now(),
+            jobs: vec![ArchivedJob {
+                job: old_completed_job("a", 40),
+                result: None,
+            }],
+        };
+        let decoded = ArchiveBundle::decode(&bundle.encode().unwrap()).unwrap();
+        assert_eq!(decoded.jobs.len(), 1);
+        assert_eq!(decoded.jobs[0].job.name, "a");
+    }
+
+    /// Serve an in-memory HTTP/1.1 object store, returning its base URL.
+    async fn http_store(token: &'static str) -> String {
+        use std::collections::HashMap;
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/bucket", listener.local_addr().unwrap());
+        let objects = Arc::new(tokio::sync::Mutex::new(HashMap::<String, Vec<u8>>::new()));
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let objects = objects.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    loop {
+                        let mut line = String::new();
+                        if stream.read_line(&mut line).await.unwrap_or(0) == 0 {
+                            return;
+                        }
+                        let mut parts = line.split_whitespace();
+                        let (method, path) = (parts.next().unwrap(), parts.next().unwrap());
+                        let (method, path) = (method.to_string(), path.to_string());
+                        let (mut length, mut authorized) = (0, false);
+                        loop {
+                            line.clear();
+                            stream.read_line(&mut line).await.unwrap();
+                            let header = line.trim_end().to_ascii_lowercase();
+                            if header.is_empty() {
+                                break;
+                            }
+                            if let Some(value) = header.strip_prefix("content-length:") {
+                                length = value.trim().parse().unwrap();
+                            }
+                            authorized |= header == format!("authorization: bearer {}", token);
+                        }
+                        let mut body = vec![0; length];
+                        stream.read_exact(&mut body).await.unwrap();
+
+                        let mut objects = objects.lock().await;
+                        let (status, data) = match method.as_str() {
+                            _ if !authorized => ("401 Unauthorized", Vec::new()),
+                            "PUT" => {
+                                objects.insert(path, body);
+                                ("200 OK", Vec::new())
+                            }
+                            "GET" => match objects.get(&path) {
+                                Some(data) => ("200 OK", data.clone()),
+                                None => ("404 Not Found", Vec::new()),
+                            },
+                            _ => match objects.remove(&path) {
+                                Some(_) => ("204 No Content", Vec::new()),
+                                None => ("404 Not Found", Vec::new()),
+                            },
+                        };
+                        let head = format!(
+                            "HTTP/1.1 {}\r\ncontent-length: {}\r\n\r\n",
+                            status,
+                            data.len()
+                        );
+                        let stream = stream.get_mut();
+                        stream.write_all(head.as_bytes()).await.unwrap();
+                        stream.write_all(&data).await.unwrap();
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_http_archive() {
+        let url = http_store("secret").await;
+        let archive = HttpArchive::new(format!("{}/", url))
+            .unwrap()
+            .with_token(Secret::new("secret"));
+
+        assert_eq!(archive.get("2026/a.json.gz").await.unwrap(), None);
+        archive
+            .put("2026/a.json.gz", b"bundle".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            archive.get("2026/a.json.gz").await.unwrap().as_deref(),
+            Some(&b"bundle"[..])
+        );
+        assert!(archive.delete("2026/a.json.gz").await.unwrap());
+        assert!(!archive.delete("2026/a.json.gz").await.unwrap());
+        assert!(archive.put("../escape", Vec::new()).await.is_err());
+
+        let anonymous = HttpArchive::new(url).unwrap();
+        let err = anonymous.get("2026/a.json.gz").await.unwrap_err();
+        assert!(err.to_string().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_rehydrate() {
+        let inner = Arc::new(SqliteStore::in_memory().unwrap());
+        let dir = std::env::temp_dir().join(format!("arvak-archive-{}", uuid::Uuid::new_v4()));
+        let archive = Arc::new(FilesystemArchive::new(&dir).await.unwrap());
+        let store = ArchivingStore::new(inner.clone(), archive, ArchivePolicy::older_than_days(30));
+
+        let old = old_completed_job("old", 45);
+        let recent = old_completed_job("recent", 1);
+        let old_id = old.id.clone();
+        store.save_job(&old).await.unwrap();
+        store.save_job(&recent).await.unwrap();
+
+        let counts = Counts::from_pairs([("00", 10u64)]);
+        store
+            .save_result(&old_id, &ExecutionResult::new(counts, 10))
+            .await
+            .unwrap();
+
+        let report = store.archive_old_jobs().await.unwrap();
+        assert_eq!(report.archived_jobs, 1);
+        assert_eq!(report.bundles.len(), 1);
+
+        // Hot store only holds a stub without the result.
+        let stub = inner.load_job(&old_id).await.unwrap().unwrap();
+        assert!(is_archived(&stub));
+        assert!(stub.circuits.is_empty());
+        assert!(inner.load_result(&old_id).await.unwrap().is_none());
+
+        // Access through the archiving store rehydrates transparently.
+        let job = store.load_job(&old_id).await.unwrap().unwrap();
+        assert!(!is_archived(&job));
+        assert_eq!(job.circuits.len(), 1);
+        let result = store.load_result(&old_id).await.unwrap().unwrap();
+        assert_eq!(result.counts.get("00"), 10);
+
+        // A second pass does not re-archive stubs.
+        let report = store.archive_old_jobs().await.unwrap();
+        assert_eq!(report.archived_jobs, 0);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_save_and_delete_archived_jobs() {
+        let inner = Arc::new(SqliteStore::in_memory().unwrap());
+        let dir = tempfile::tempdir().unwrap();
+        let archive = Arc::new(FilesystemArchive::new(dir.path()).await.unwrap());
+        let store = ArchivingStore::new(
+            inner.clone(),
+            archive.clone(),
+            ArchivePolicy::older_than_days(30),
+        );
+
+        let (a, b) = (old_completed_job("a", 45), old_completed_job("b", 45));
+        store.save_job(&a).await.unwrap();
+        store.save_job(&b).await.unwrap();
+        let counts = Counts::from_pairs([("11", 5u64)]);
+        store
+            .save_result(&a.id, &ExecutionResult::new(counts, 5))
+            .await
+            .unwrap();
+        let report = store.archive_old_jobs().await.unwrap();
+        let key = &report.bundles[0];
+
+        // Saving a rehydrated job moves it back into the hot store with its
+        // result and out of the bundle.
+        let mut job = store.load_job(&a.id).await.unwrap().unwrap();
+        job.metadata.insert("note".into(), "restored".into());
+        store.save_job(&job).await.unwrap();
+        let hot = inner.load_job(&a.id).await.unwrap().unwrap();
+        assert!(!is_archived(&hot));
+        assert_eq!(hot.circuits.len(), 1);
+        assert_eq!(
+            inner
+                .load_result(&a.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .counts
+                .get("11"),
+            5
+        );
+        let bundle = ArchiveBundle::decode(&archive.get(key).await.unwrap().unwrap()).unwrap();
+        assert!(bundle.find(&a.id).is_none());
+        assert!(bundle.find(&b.id).is_some());
+
+        // Deleting the last archived job deletes the bundle.
+        assert!(store.delete_job(&b.id).await.unwrap());
+        assert!(inner.load_job(&b.id).await.unwrap().is_none());
+        assert!(archive.get(key).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_archive_removes_artifacts() {
+        let inner = Arc::new(SqliteStore::in_memory().unwrap());
+        let dir = tempfile::tempdir().unwrap();
+        let archive = Arc::new(
+            FilesystemArchive::new(dir.path().join("cold"))
+                .await
+                .unwrap(),
+        );
+        let slurm = crate::slurm::SlurmConfig {
+            work_dir: dir.path().join("work"),
+            ..Default::default()
+        };
+        let store = ArchivingStore::new(inner, archive, ArchivePolicy::older_than_days(30))
+            .with_artifacts(Arc::new(slurm));
+
+        let old = old_completed_job("old", 45);
+        store.save_job(&old).await.unwrap();
+
+        let script = dir
+            .path()
+            .join("work/scripts")
+            .join(format!("{}.sh", old.id));
+        let log = dir.path().join("work/slurm-1.out");
+        std::fs::create_dir_all(script.parent().unwrap()).unwrap();
+        std::fs::write(&script, "#!/bin/bash").unwrap();
+        std::fs::write(&log, "done").unwrap();
+
+        let report = store.archive_old_jobs().await.unwrap();
+        assert_eq!(report.archived_jobs, 1);
+        assert_eq!(report.artifacts_removed, 2);
+        assert!(!script.exists());
+        assert!(!log.exists());
+    }
+}
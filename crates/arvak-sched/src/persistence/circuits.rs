@@ -0,0 +1,124 @@
+//! Content-addressed storage of circuit sources.
+//!
+//! Parameter sweeps store the same ansatz in thousands of job records. The
+//! stores keep each distinct circuit once, keyed by its canonical hash, and
+//! write job records with [`CircuitSpec::Stored`] references in place of
+//! the sources. A source is reference-counted by the jobs holding it and
+//! removed with the last of them. Loaded jobs always carry their sources.
+//!
+//! The key is the same canonical hash that keys compile caches and result
+//! provenance, so sources that differ only in formatting share one entry
+//! and a job may load back an equivalent source formatted differently.
+//! Sources that do not parse stay inline in the job record.
+//!
+//! Records written before circuits were stored this way keep their inline
+//! sources and are converted when next saved.
+
+use std::collections::BTreeSet;
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{CircuitSpec, ScheduledJob};
+
+/// Content hash of a circuit source: its [`arvak_qasm3::canonical_hash`]
+/// in hex. Fails if the source does not parse.
+pub fn circuit_hash(source: &str) -> SchedResult<String> {
+    let circuit = arvak_qasm3::parse(source)?;
+    Ok(format!("{:016x}", arvak_qasm3::canonical_hash(&circuit)?))
+}
+
+/// Split `job` into the record to store, with its QASM3 sources replaced
+/// by their hashes, and the sources by hash.
+pub(crate) fn dehydrate(job: &ScheduledJob) -> (ScheduledJob, Vec<(String, String)>) {
+    let mut record = job.clone();
+    let mut sources = Vec::new();
+    for spec in &mut record.circuits {
+        if let CircuitSpec::Qasm3(source) = spec.source() {
+            let Ok(hash) = circuit_hash(source) else {
+                continue;
+            };
+            sources.push((hash.clone(), source.clone()));
+            spec.set_source(CircuitSpec::Stored(hash));
+        }
+    }
+    (record, sources)
+}
+
+/// Hashes of the circuit sources a stored job record references.
+pub(crate) fn stored_hashes(record: &ScheduledJob) -> BTreeSet<String> {
+    record
+        .circuits
+        .iter()
+        .filter_map(|spec| match spec.source() {
+            CircuitSpec::Stored(hash) => Some(hash.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Replace the circuit references of a stored job record with the sources
+/// `source` looks up by hash.
+pub(crate) fn hydrate(
+    job: &mut ScheduledJob,
+    mut source: impl FnMut(&str) -> SchedResult<Option<String>>,
+) -> SchedResult<()> {
+    for spec in &mut job.circuits {
+        if let CircuitSpec::Stored(hash) = spec.source() {
+            let qasm = source(hash)?.ok_or_else(|| {
+                SchedError::PersistenceError(format!(
+                    "circuit {} of job {} is missing from the store",
+                    hash, job.id
+                ))
+            })?;
+            spec.set_source(CircuitSpec::Qasm3(qasm));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dehydrate_roundtrip() {
+        let ansatz = "OPENQASM 3.0; qubit[2] q; h q[0];";
+        let job = ScheduledJob::batch(
+            "sweep",
+            vec![
+                CircuitSpec::from_qasm(ansatz),
+                CircuitSpec::from_qasm(ansatz).with_label("again"),
+                CircuitSpec::from_file("/scratch/c.qasm"),
+                CircuitSpec::from_qasm("not qasm"),
+            ],
+        );
+
+        let hash = circuit_hash(ansatz).unwrap();
+        assert_eq!(
+            hash,
+            format!("{:016x}", job.circuits[0].canonical_hash().unwrap())
+        );
+        // Formatting does not change the key
+        assert_eq!(
+            circuit_hash("OPENQASM 3.0;\nqubit[2] q;\nh q[0];\n").unwrap(),
+            hash
+        );
+
+        let (record, sources) = dehydrate(&job);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0], (hash.clone(), ansatz.to_string()));
+        assert!(matches!(record.circuits[0], CircuitSpec::Stored(_)));
+        assert_eq!(record.circuits[1].label(), Some("again"));
+        assert!(matches!(record.circuits[2], CircuitSpec::QasmFile(_)));
+        assert!(matches!(record.circuits[3], CircuitSpec::Qasm3(_)));
+        assert_eq!(stored_hashes(&record), BTreeSet::from([hash]));
+        assert!(stored_hashes(&job).is_empty());
+
+        let mut loaded = record.clone();
+        hydrate(&mut loaded, |_| Ok(Some(ansatz.to_string()))).unwrap();
+        assert!(matches!(&loaded.circuits[0], CircuitSpec::Qasm3(s) if s == ansatz));
+        assert!(matches!(loaded.circuits[1].source(), CircuitSpec::Qasm3(_)));
+
+        let mut broken = record;
+        assert!(hydrate(&mut broken, |_| Ok(None)).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Serialization formats for stored job, result and workflow blobs.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::error::{SchedError, SchedResult};
+
+/// Encoding used for blobs written by a store.
+///
+/// JSON is human-readable and the default. CBOR is a compact binary
+/// encoding that is smaller and faster to encode and decode for large
+/// circuits and results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlobFormat {
+    /// JSON text.
+    #[default]
+    Json,
+
+    /// CBOR (RFC 8949) binary.
+    Cbor,
+}
+
+impl BlobFormat {
+    /// Encode a value.
+    pub fn encode<T: Serialize>(self, value: &T) -> SchedResult<Vec<u8>> {
+        match self {
+            BlobFormat::Json => Ok(serde_json::to_vec(value)?),
+            BlobFormat::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf)
+                    .map_err(|e| SchedError::PersistenceError(format!("CBOR encode: {}", e)))?;
+                Ok(buf)
+            }
+        }
+    }
+
+    /// Decode a value.
+    pub fn decode<T: DeserializeOwned>(self, bytes: &[u8]) -> SchedResult<T> {
+        match self {
+            BlobFormat::Json => Ok(serde_json::from_slice(bytes)?),
+            BlobFormat::Cbor => ciborium::from_reader(bytes)
+                .map_err(|e| SchedError::PersistenceError(format!("CBOR decode: {}", e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::{CircuitSpec, DependencyKind, ScheduledJob, ScheduledJobId};
+    use arvak_hal::{Counts, ExecutionResult};
+
+    #[test]
+    fn test_roundtrip() {
+        let job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .depends_on_with(ScheduledJobId::new(), DependencyKind::AfterAny)
+            .with_metadata("k", "v");
+        let result = ExecutionResult::new(Counts::from_pairs([("01", 3u64)]), 3)
+            .with_metadata(serde_json::json!({"energy": -1.5, "tags": ["a"]}));
+
+        for format in [BlobFormat::Json, BlobFormat::Cbor] {
+            let bytes = format.encode(&job).unwrap();
+            let back: ScheduledJob = format.decode(&bytes).unwrap();
+            assert_eq!(back.id, job.id);
+            assert_eq!(back.dependency_kinds, job.dependency_kinds);
+
+            let bytes = format.encode(&result).unwrap();
+            let back: ExecutionResult = format.decode(&bytes).unwrap();
+            assert_eq!(back.counts.get("01"), 3);
+            assert_eq!(back.metadata, result.metadata);
+        }
+    }
+}
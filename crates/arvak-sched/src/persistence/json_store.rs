@@ -1,27 +1,49 @@
 //! JSON file-based persistence for development and testing.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
 use arvak_hal::ExecutionResult;
 use async_trait::async_trait;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
 use tokio::sync::RwLock;
 
+use crate::calibration::BackendCalibration;
 use crate::error::{SchedError, SchedResult};
+use crate::iteration::{IterationRecord, latest_per_iteration};
 use crate::job::{JobFilter, ScheduledJob, ScheduledJobId, ScheduledJobStatus};
 use crate::persistence::StateStore;
+use crate::persistence::circuits::{dehydrate, hydrate, stored_hashes};
+use crate::recurring::RecurringJob;
+use crate::reservation::Reservation;
+use crate::template::JobTemplate;
 use crate::workflow::{Workflow, WorkflowId};
 
 /// JSON file-based state store.
 ///
-/// Stores each job as a separate JSON file. Suitable for development
-/// and testing, not recommended for production use.
+/// Stores each job as a separate JSON file and the iterations of each run
+/// as JSON lines appended to one file. Suitable for development and
+/// testing, not recommended for production use.
 pub struct JsonStore {
     /// Base directory for storage.
     base_dir: PathBuf,
 
     /// In-memory cache of jobs.
     cache: RwLock<rustc_hash::FxHashMap<ScheduledJobId, ScheduledJob>>,
+
+    /// Stored circuit sources each job record references.
+    circuits: RwLock<CircuitRefs>,
+}
+
+/// Reference counts of the circuit sources in `circuits/`.
+#[derive(Default)]
+struct CircuitRefs {
+    /// Hashes each job record references.
+    jobs: rustc_hash::FxHashMap<ScheduledJobId, BTreeSet<String>>,
+
+    /// Number of job records referencing each hash.
+    counts: rustc_hash::FxHashMap<String, usize>,
 }
 
 impl JsonStore {
@@ -33,10 +55,18 @@ impl JsonStore {
         fs::create_dir_all(base_dir.join("jobs")).await?;
         fs::create_dir_all(base_dir.join("results")).await?;
         fs::create_dir_all(base_dir.join("workflows")).await?;
+        fs::create_dir_all(base_dir.join("iterations")).await?;
+        fs::create_dir_all(base_dir.join("cache")).await?;
+        fs::create_dir_all(base_dir.join("templates")).await?;
+        fs::create_dir_all(base_dir.join("recurring")).await?;
+        fs::create_dir_all(base_dir.join("reservations")).await?;
+        fs::create_dir_all(base_dir.join("calibrations")).await?;
+        fs::create_dir_all(base_dir.join("circuits")).await?;
 
         let store = Self {
             base_dir,
             cache: RwLock::new(rustc_hash::FxHashMap::default()),
+            circuits: RwLock::new(CircuitRefs::default()),
         };
 
         // Load existing jobs into cache
@@ -55,6 +85,90 @@ impl JsonStore {
         self.base_dir.join("jobs").join(format!("{}.json", job_id))
     }
 
+    fn circuit_path(&self, hash: &str) -> PathBuf {
+        self.base_dir
+            .join("circuits")
+            .join(format!("{}.qasm", hash))
+    }
+
+    /// Write a job record with its circuit sources stored by hash, keeping
+    /// the reference counts of the sources in step.
+    async fn write_job(&self, job: &ScheduledJob) -> SchedResult<()> {
+        let (record, sources) = dehydrate(job);
+        let sources: BTreeMap<String, String> = sources.into_iter().collect();
+        let mut refs = self.circuits.write().await;
+        let held = refs.jobs.remove(&job.id).unwrap_or_default();
+
+        for (hash, source) in &sources {
+            if held.contains(hash) {
+                continue;
+            }
+            let count = refs.counts.entry(hash.clone()).or_insert(0);
+            if *count == 0 {
+                fs::write(self.circuit_path(hash), source).await?;
+            }
+            *count += 1;
+        }
+        let json = serde_json::to_string_pretty(&record)?;
+        fs::write(self.job_path(&job.id), json).await?;
+
+        for hash in held.iter().filter(|hash| !sources.contains_key(*hash)) {
+            self.release_circuit(&mut refs, hash).await?;
+        }
+        if !sources.is_empty() {
+            refs.jobs
+                .insert(job.id.clone(), sources.into_keys().collect());
+        }
+        Ok(())
+    }
+
+    /// Drop a job record's reference to a circuit source, removing the
+    /// source with its last reference.
+    async fn release_circuit(&self, refs: &mut CircuitRefs, hash: &str) -> SchedResult<()> {
+        let Some(count) = refs.counts.get_mut(hash) else {
+            return Ok(());
+        };
+        *count -= 1;
+        if *count == 0 {
+            refs.counts.remove(hash);
+            match fs::remove_file(self.circuit_path(hash)).await {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(SchedError::IoError(e)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse a job record, loading its circuit sources and counting its
+    /// references to them.
+    async fn read_job(&self, content: &str) -> SchedResult<ScheduledJob> {
+        let mut job: ScheduledJob = serde_json::from_str(content)?;
+        let hashes = stored_hashes(&job);
+        let mut sources = rustc_hash::FxHashMap::default();
+        for hash in &hashes {
+            match fs::read_to_string(self.circuit_path(hash)).await {
+                Ok(source) => {
+                    sources.insert(hash.clone(), source);
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(SchedError::IoError(e)),
+            }
+        }
+        hydrate(&mut job, |hash| Ok(sources.remove(hash)))?;
+
+        let mut refs = self.circuits.write().await;
+        if !refs.jobs.contains_key(&job.id) {
+            for hash in &hashes {
+                *refs.counts.entry(hash.clone()).or_insert(0) += 1;
+            }
+            if !hashes.is_empty() {
+                refs.jobs.insert(job.id.clone(), hashes);
+            }
+        }
+        Ok(job)
+    }
+
     fn result_path(&self, job_id: &ScheduledJobId) -> PathBuf {
         self.base_dir
             .join("results")
@@ -67,6 +181,70 @@ impl JsonStore {
             .join(format!("{}.json", workflow_id))
     }
 
+    fn iterations_path(&self, run_id: &str) -> PathBuf {
+        // Run ids are free-form; records carry the exact id, so collisions
+        // after sanitizing are filtered out on load.
+        self.base_dir
+            .join("iterations")
+            .join(format!("{}.jsonl", file_name(run_id)))
+    }
+
+    fn calibrations_dir(&self, backend: &str) -> SchedResult<PathBuf> {
+        // Snapshots are replaced by epoch, so names must not collide after
+        // sanitizing.
+        if backend.is_empty() || file_name(backend) != backend {
+            return Err(SchedError::InvalidPayload(format!(
+                "backend name '{}' cannot be stored: use letters, digits, '-' and '_'",
+                backend
+            )));
+        }
+        Ok(self.base_dir.join("calibrations").join(backend))
+    }
+
+    fn cache_entry_path(&self, key: &str) -> PathBuf {
+        self.base_dir.join("cache").join(format!("{}.json", key))
+    }
+
+    fn template_path(&self, name: &str) -> SchedResult<PathBuf> {
+        crate::template::validate_name(name)?;
+        Ok(self
+            .base_dir
+            .join("templates")
+            .join(format!("{}.json", name)))
+    }
+
+    fn recurring_path(&self, name: &str) -> SchedResult<PathBuf> {
+        crate::template::validate_name(name)?;
+        Ok(self
+            .base_dir
+            .join("recurring")
+            .join(format!("{}.json", name)))
+    }
+
+    fn reservation_path(&self, name: &str) -> SchedResult<PathBuf> {
+        crate::template::validate_name(name)?;
+        Ok(self
+            .base_dir
+            .join("reservations")
+            .join(format!("{}.json", name)))
+    }
+
+    async fn read_calibration(
+        &self,
+        backend: &str,
+        epoch: u64,
+    ) -> SchedResult<Option<BackendCalibration>> {
+        // Names that cannot be stored cannot be found either.
+        let Ok(dir) = self.calibrations_dir(backend) else {
+            return Ok(None);
+        };
+        match fs::read_to_string(dir.join(format!("{}.json", epoch))).await {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
     async fn load_all_jobs(&self) -> SchedResult<()> {
         let jobs_dir = self.base_dir.join("jobs");
         let mut cache = self.cache.write().await;
@@ -76,7 +254,7 @@ impl JsonStore {
             let path = entry.path();
             if path.extension().is_some_and(|ext| ext == "json") {
                 match fs::read_to_string(&path).await {
-                    Ok(content) => match serde_json::from_str::<ScheduledJob>(&content) {
+                    Ok(content) => match self.read_job(&content).await {
                         Ok(job) => {
                             cache.insert(job.id.clone(), job);
                         }
@@ -98,12 +276,10 @@ impl JsonStore {
 #[async_trait]
 impl StateStore for JsonStore {
     async fn save_job(&self, job: &ScheduledJob) -> SchedResult<()> {
-        let path = self.job_path(&job.id);
-        let json = serde_json::to_string_pretty(job)?;
-        fs::write(&path, json).await?;
+        let mut cache = self.cache.write().await;
+        self.write_job(job).await?;
 
         // Update cache
-        let mut cache = self.cache.write().await;
         cache.insert(job.id.clone(), job.clone());
 
         Ok(())
@@ -121,7 +297,7 @@ impl StateStore for JsonStore {
         let path = self.job_path(job_id);
         match fs::read_to_string(&path).await {
             Ok(content) => {
-                let job: ScheduledJob = serde_json::from_str(&content)?;
+                let job = self.read_job(&content).await?;
                 // Update cache
                 let mut cache = self.cache.write().await;
                 cache.insert(job.id.clone(), job.clone());
@@ -148,9 +324,7 @@ impl StateStore for JsonStore {
             }
 
             // Write to file
-            let path = self.job_path(job_id);
-            let json = serde_json::to_string_pretty(&*job)?;
-            fs::write(&path, json).await?;
+            self.write_job(job).await?;
 
             Ok(())
         } else {
@@ -165,6 +339,11 @@ impl StateStore for JsonStore {
         let mut cache = self.cache.write().await;
         let was_present = cache.remove(job_id).is_some();
 
+        let mut refs = self.circuits.write().await;
+        for hash in refs.jobs.remove(job_id).unwrap_or_default() {
+            self.release_circuit(&mut refs, &hash).await?;
+        }
+
         // Remove file
         match fs::remove_file(&path).await {
             Ok(()) => Ok(true),
@@ -219,6 +398,15 @@ impl StateStore for JsonStore {
         }
     }
 
+    async fn delete_result(&self, job_id: &ScheduledJobId) -> SchedResult<bool> {
+        let path = self.result_path(job_id);
+        match fs::remove_file(&path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
     async fn save_workflow(&self, workflow: &Workflow) -> SchedResult<()> {
         let path = self.workflow_path(&workflow.id);
         let json = serde_json::to_string_pretty(workflow)?;
@@ -268,6 +456,218 @@ impl StateStore for JsonStore {
         Ok(workflow_ids)
     }
 
+    async fn save_iteration(&self, record: &IterationRecord) -> SchedResult<()> {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.iterations_path(&record.run_id))
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn load_iterations(&self, run_id: &str) -> SchedResult<Vec<IterationRecord>> {
+        let content = match fs::read_to_string(self.iterations_path(run_id)).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(SchedError::IoError(e)),
+        };
+        let mut records = Vec::new();
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let record: IterationRecord = serde_json::from_str(line)?;
+            if record.run_id == run_id {
+                records.push(record);
+            }
+        }
+        Ok(latest_per_iteration(records))
+    }
+
+    async fn save_cache_entry(&self, key: &str, job_id: &ScheduledJobId) -> SchedResult<()> {
+        let json = serde_json::to_string(job_id)?;
+        fs::write(self.cache_entry_path(key), json).await?;
+        Ok(())
+    }
+
+    async fn load_cache_entry(&self, key: &str) -> SchedResult<Option<ScheduledJobId>> {
+        match fs::read_to_string(self.cache_entry_path(key)).await {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn save_template(&self, template: &JobTemplate) -> SchedResult<()> {
+        let json = serde_json::to_string_pretty(template)?;
+        fs::write(self.template_path(&template.name)?, json).await?;
+        Ok(())
+    }
+
+    async fn load_template(&self, name: &str) -> SchedResult<Option<JobTemplate>> {
+        // Names that cannot be stored cannot be found either.
+        let Ok(path) = self.template_path(name) else {
+            return Ok(None);
+        };
+        match fs::read_to_string(path).await {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn delete_template(&self, name: &str) -> SchedResult<bool> {
+        let Ok(path) = self.template_path(name) else {
+            return Ok(false);
+        };
+        match fs::remove_file(path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn list_templates(&self) -> SchedResult<Vec<JobTemplate>> {
+        let mut templates = Vec::new();
+        let mut entries = fs::read_dir(self.base_dir.join("templates")).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = fs::read_to_string(&path).await?;
+                templates.push(serde_json::from_str::<JobTemplate>(&content)?);
+            }
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(templates)
+    }
+
+    async fn save_recurring(&self, recurring: &RecurringJob) -> SchedResult<()> {
+        let json = serde_json::to_string_pretty(recurring)?;
+        fs::write(self.recurring_path(&recurring.name)?, json).await?;
+        Ok(())
+    }
+
+    async fn load_recurring(&self, name: &str) -> SchedResult<Option<RecurringJob>> {
+        let Ok(path) = self.recurring_path(name) else {
+            return Ok(None);
+        };
+        match fs::read_to_string(path).await {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn delete_recurring(&self, name: &str) -> SchedResult<bool> {
+        let Ok(path) = self.recurring_path(name) else {
+            return Ok(false);
+        };
+        match fs::remove_file(path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn list_recurring(&self) -> SchedResult<Vec<RecurringJob>> {
+        let mut recurring = Vec::new();
+        let mut entries = fs::read_dir(self.base_dir.join("recurring")).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = fs::read_to_string(&path).await?;
+                recurring.push(serde_json::from_str::<RecurringJob>(&content)?);
+            }
+        }
+        recurring.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(recurring)
+    }
+
+    async fn save_reservation(&self, reservation: &Reservation) -> SchedResult<()> {
+        let json = serde_json::to_string_pretty(reservation)?;
+        fs::write(self.reservation_path(&reservation.name)?, json).await?;
+        Ok(())
+    }
+
+    async fn load_reservation(&self, name: &str) -> SchedResult<Option<Reservation>> {
+        let Ok(path) = self.reservation_path(name) else {
+            return Ok(None);
+        };
+        match fs::read_to_string(path).await {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn delete_reservation(&self, name: &str) -> SchedResult<bool> {
+        let Ok(path) = self.reservation_path(name) else {
+            return Ok(false);
+        };
+        match fs::remove_file(path).await {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(SchedError::IoError(e)),
+        }
+    }
+
+    async fn list_reservations(&self) -> SchedResult<Vec<Reservation>> {
+        let mut reservations = Vec::new();
+        let mut entries = fs::read_dir(self.base_dir.join("reservations")).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = fs::read_to_string(&path).await?;
+                reservations.push(serde_json::from_str::<Reservation>(&content)?);
+            }
+        }
+        reservations.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(reservations)
+    }
+
+    async fn save_calibration(&self, calibration: &BackendCalibration) -> SchedResult<()> {
+        let dir = self.calibrations_dir(&calibration.backend)?;
+        fs::create_dir_all(&dir).await?;
+        let json = serde_json::to_string_pretty(calibration)?;
+        fs::write(dir.join(format!("{}.json", calibration.epoch)), json).await?;
+        Ok(())
+    }
+
+    async fn load_calibration(
+        &self,
+        backend: &str,
+        epoch: Option<u64>,
+    ) -> SchedResult<Option<BackendCalibration>> {
+        if let Some(epoch) = epoch {
+            return self.read_calibration(backend, epoch).await;
+        }
+        let Ok(dir) = self.calibrations_dir(backend) else {
+            return Ok(None);
+        };
+        let mut entries = match fs::read_dir(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(SchedError::IoError(e)),
+        };
+        let mut epochs = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(epoch) = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse::<u64>().ok())
+                {
+                    epochs.push(epoch);
+                }
+            }
+        }
+        match epochs.into_iter().max() {
+            Some(epoch) => self.read_calibration(backend, epoch).await,
+            None => Ok(None),
+        }
+    }
+
     async fn cleanup_old_jobs(&self, max_age_seconds: u64) -> SchedResult<usize> {
         let cutoff = chrono::Utc::now() - chrono::Duration::seconds(max_age_seconds as i64);
         let mut removed = 0;
@@ -291,6 +691,28 @@ impl StateStore for JsonStore {
 
         Ok(removed)
     }
+
+    async fn rebuild_indices(&self) -> SchedResult<usize> {
+        // The cache is the only index; reload it from the job files.
+        self.cache.write().await.clear();
+        *self.circuits.write().await = CircuitRefs::default();
+        self.load_all_jobs().await?;
+        Ok(self.cache.read().await.len())
+    }
+}
+
+/// Turn a free-form key into a file name of ASCII letters, digits, `-` and
+/// `_`.
+fn file_name(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -355,4 +777,146 @@ mod tests {
         assert_eq!(jobs[0].name, "job2");
         assert_eq!(jobs[1].name, "job1");
     }
+
+    #[tokio::test]
+    async fn test_json_store_iterations() {
+        let store = JsonStore::temp().await.unwrap();
+
+        for (iteration, energy) in [(0, -0.5), (1, -0.8), (1, -0.9)] {
+            let record = IterationRecord::new("vqe/h2", iteration, vec![0.1], energy);
+            store.save_iteration(&record).await.unwrap();
+        }
+        let other = IterationRecord::new("vqe_h2", 0, vec![0.2], -0.1);
+        store.save_iteration(&other).await.unwrap();
+
+        // "vqe/h2" and "vqe_h2" share a file but not their records.
+        let records = store.load_iterations("vqe/h2").await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].value, -0.9);
+        assert_eq!(store.load_iterations("vqe_h2").await.unwrap().len(), 1);
+        assert!(store.load_iterations("missing").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_json_store_calibrations() {
+        let store = JsonStore::temp().await.unwrap();
+        assert!(
+            store
+                .load_calibration("garnet", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        for epoch in [1, 2, 10] {
+            let mut calibration = BackendCalibration::new("garnet");
+            calibration.epoch = epoch;
+            store.save_calibration(&calibration).await.unwrap();
+        }
+        let latest = store
+            .load_calibration("garnet", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.epoch, 10);
+        assert!(
+            store
+                .load_calibration("garnet", Some(1))
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            store
+                .load_calibration("garnet", Some(3))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let escaping = BackendCalibration::new("../jobs");
+        assert!(store.save_calibration(&escaping).await.is_err());
+        assert!(
+            store
+                .load_calibration("../jobs", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_store_cache_entries() {
+        let store = JsonStore::temp().await.unwrap();
+        let (first, second) = (ScheduledJobId::new(), ScheduledJobId::new());
+
+        assert!(store.load_cache_entry("00ff").await.unwrap().is_none());
+        store.save_cache_entry("00ff", &first).await.unwrap();
+        store.save_cache_entry("00ff", &second).await.unwrap();
+        assert_eq!(store.load_cache_entry("00ff").await.unwrap(), Some(second));
+    }
+
+    #[tokio::test]
+    async fn test_json_store_templates() {
+        let store = JsonStore::temp().await.unwrap();
+        let template = JobTemplate::new("bell", CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .with_label("project", "demo");
+
+        store.save_template(&template).await.unwrap();
+        let loaded = store.load_template("bell").await.unwrap().unwrap();
+        assert_eq!(loaded.labels, template.labels);
+        assert_eq!(store.list_templates().await.unwrap().len(), 1);
+        assert!(store.load_template("../jobs/x").await.unwrap().is_none());
+        assert!(store.delete_template("bell").await.unwrap());
+        assert!(store.list_templates().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_json_store_dedups_circuits() {
+        let store = JsonStore::temp().await.unwrap();
+        let ansatz = "OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];";
+        let jobs: Vec<_> = (0..3)
+            .map(|i| ScheduledJob::new(format!("point-{}", i), CircuitSpec::from_qasm(ansatz)))
+            .collect();
+        for job in &jobs {
+            store.save_job(job).await.unwrap();
+        }
+        store
+            .update_status(&jobs[0].id, ScheduledJobStatus::Cancelled)
+            .await
+            .unwrap();
+
+        let circuits_dir = store.base_dir.join("circuits");
+        let count = || std::fs::read_dir(&circuits_dir).unwrap().count();
+        assert_eq!(count(), 1);
+        let record = std::fs::read_to_string(store.job_path(&jobs[0].id)).unwrap();
+        assert!(!record.contains("OPENQASM"));
+
+        // A reopened store loads the sources and their reference counts
+        let store = JsonStore::new(&store.base_dir).await.unwrap();
+        let loaded = store.load_job(&jobs[1].id).await.unwrap().unwrap();
+        assert!(matches!(&loaded.circuits[0], CircuitSpec::Qasm3(s) if s == ansatz));
+        store.delete_job(&jobs[0].id).await.unwrap();
+        store.delete_job(&jobs[1].id).await.unwrap();
+        assert_eq!(count(), 1);
+        store.delete_job(&jobs[2].id).await.unwrap();
+        assert_eq!(count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_json_store_recurring() {
+        let store = JsonStore::temp().await.unwrap();
+        let job = ScheduledJob::new("suite", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let schedule = crate::recurring::CronSchedule::parse("@daily").unwrap();
+        let recurring = RecurringJob::new("suite", schedule, job);
+
+        store.save_recurring(&recurring).await.unwrap();
+        let loaded = store.load_recurring("suite").await.unwrap().unwrap();
+        assert_eq!(loaded.schedule, recurring.schedule);
+        assert_eq!(loaded.job.id, recurring.job.id);
+        assert_eq!(store.list_recurring().await.unwrap().len(), 1);
+        assert!(store.load_recurring("../jobs/x").await.unwrap().is_none());
+        assert!(store.delete_recurring("suite").await.unwrap());
+        assert!(store.list_recurring().await.unwrap().is_empty());
+    }
 }
@@ -1,19 +1,42 @@
 //! Persistence layer for job state.
 
+pub mod archive;
+mod circuits;
+mod codec;
 mod json_store;
+mod redis_store;
 mod sqlite_store;
-
+mod wal;
+
+pub use archive::{
+    ArchiveBackend, ArchiveBundle, ArchivePolicy, ArchiveReport, ArchivingStore, FilesystemArchive,
+    HttpArchive,
+};
+pub use circuits::circuit_hash;
+pub use codec::BlobFormat;
 pub use json_store::JsonStore;
+pub use redis_store::{DEFAULT_REDIS_TTL, RedisConfig, RedisStore};
 pub use sqlite_store::SqliteStore;
+pub use wal::{RecoveryReport, WalOp, WalRecord, WriteAheadLog};
 
 use arvak_hal::ExecutionResult;
 use async_trait::async_trait;
 
-use crate::error::SchedResult;
+use crate::calibration::BackendCalibration;
+use crate::error::{SchedError, SchedResult};
+use crate::iteration::IterationRecord;
 use crate::job::{JobFilter, ScheduledJob, ScheduledJobId, ScheduledJobStatus};
+use crate::recurring::RecurringJob;
+use crate::reservation::Reservation;
+use crate::template::JobTemplate;
 use crate::workflow::{Workflow, WorkflowId};
 
 /// Trait for persistent state storage.
+///
+/// Besides jobs, results and workflows, stores may keep iterations, cache
+/// entries, templates, recurring jobs, reservations and calibration
+/// snapshots. Stores that do not keep these can leave the defaults, which
+/// fail writes with [`SchedError::Unsupported`] and find nothing on reads.
 #[async_trait]
 pub trait StateStore: Send + Sync {
     /// Save a job to the store.
@@ -35,6 +58,36 @@ pub trait StateStore: Send + Sync {
     /// List jobs matching a filter.
     async fn list_jobs(&self, filter: &JobFilter) -> SchedResult<Vec<ScheduledJob>>;
 
+    /// Find the job an external ID refers to: a batch job ID it ran as or
+    /// the ID of the request that submitted it (see
+    /// [`ScheduledJob::references`]).
+    ///
+    /// The default scans every job.
+    async fn find_job_by_ref(&self, reference: &str) -> SchedResult<Option<ScheduledJobId>> {
+        let jobs = self.list_jobs(&JobFilter::default()).await?;
+        Ok(jobs
+            .into_iter()
+            .find(|job| job.references().contains(&reference))
+            .map(|job| job.id))
+    }
+
+    /// Resolve any ID naming a stored job: the job's own ID or one of its
+    /// external references.
+    async fn resolve_job_id(&self, id: &str) -> SchedResult<Option<ScheduledJobId>> {
+        if let Ok(job_id) = ScheduledJobId::parse(id) {
+            if self.load_job(&job_id).await?.is_some() {
+                return Ok(Some(job_id));
+            }
+        }
+        self.find_job_by_ref(id).await
+    }
+
+    /// List the jobs derived from a parent job.
+    async fn list_children(&self, job_id: &ScheduledJobId) -> SchedResult<Vec<ScheduledJob>> {
+        self.list_jobs(&JobFilter::children_of(job_id.clone()))
+            .await
+    }
+
     /// Save execution result for a job.
     async fn save_result(
         &self,
@@ -45,6 +98,11 @@ pub trait StateStore: Send + Sync {
     /// Load execution result for a job.
     async fn load_result(&self, job_id: &ScheduledJobId) -> SchedResult<Option<ExecutionResult>>;
 
+    /// Delete execution result for a job.
+    async fn delete_result(&self, _job_id: &ScheduledJobId) -> SchedResult<bool> {
+        Err(SchedError::Unsupported("Deleting results".to_string()))
+    }
+
     /// Save a workflow to the store.
     async fn save_workflow(&self, workflow: &Workflow) -> SchedResult<()>;
 
@@ -57,6 +115,122 @@ pub trait StateStore: Send + Sync {
     /// List all workflow IDs.
     async fn list_workflows(&self) -> SchedResult<Vec<WorkflowId>>;
 
+    /// Save an iteration of a variational run, replacing any earlier
+    /// record of the same iteration.
+    async fn save_iteration(&self, _record: &IterationRecord) -> SchedResult<()> {
+        Err(SchedError::Unsupported("Recording iterations".to_string()))
+    }
+
+    /// Load the iterations recorded for a run, ordered by iteration.
+    async fn load_iterations(&self, _run_id: &str) -> SchedResult<Vec<IterationRecord>> {
+        Ok(Vec::new())
+    }
+
+    /// Record that the result of `job_id` is the cached output for a
+    /// workflow node cache key, replacing any earlier entry.
+    async fn save_cache_entry(&self, _key: &str, _job_id: &ScheduledJobId) -> SchedResult<()> {
+        Err(SchedError::Unsupported("Caching results".to_string()))
+    }
+
+    /// Look up the job whose result is cached under a key.
+    async fn load_cache_entry(&self, _key: &str) -> SchedResult<Option<ScheduledJobId>> {
+        Ok(None)
+    }
+
+    /// Save a job template, replacing any template of the same name.
+    async fn save_template(&self, _template: &JobTemplate) -> SchedResult<()> {
+        Err(SchedError::Unsupported("Storing job templates".to_string()))
+    }
+
+    /// Load a job template by name.
+    async fn load_template(&self, _name: &str) -> SchedResult<Option<JobTemplate>> {
+        Ok(None)
+    }
+
+    /// Delete a job template.
+    async fn delete_template(&self, _name: &str) -> SchedResult<bool> {
+        Err(SchedError::Unsupported("Storing job templates".to_string()))
+    }
+
+    /// List all job templates, ordered by name.
+    async fn list_templates(&self) -> SchedResult<Vec<JobTemplate>> {
+        Ok(Vec::new())
+    }
+
+    /// Save a recurring job, replacing any recurring job of the same name.
+    async fn save_recurring(&self, _recurring: &RecurringJob) -> SchedResult<()> {
+        Err(SchedError::Unsupported(
+            "Storing recurring jobs".to_string(),
+        ))
+    }
+
+    /// Load a recurring job by name.
+    async fn load_recurring(&self, _name: &str) -> SchedResult<Option<RecurringJob>> {
+        Ok(None)
+    }
+
+    /// Delete a recurring job.
+    async fn delete_recurring(&self, _name: &str) -> SchedResult<bool> {
+        Err(SchedError::Unsupported(
+            "Storing recurring jobs".to_string(),
+        ))
+    }
+
+    /// List all recurring jobs, ordered by name.
+    async fn list_recurring(&self) -> SchedResult<Vec<RecurringJob>> {
+        Ok(Vec::new())
+    }
+
+    /// Save a reservation, replacing any reservation of the same name.
+    async fn save_reservation(&self, _reservation: &Reservation) -> SchedResult<()> {
+        Err(SchedError::Unsupported("Storing reservations".to_string()))
+    }
+
+    /// Load a reservation by name.
+    async fn load_reservation(&self, _name: &str) -> SchedResult<Option<Reservation>> {
+        Ok(None)
+    }
+
+    /// Delete a reservation.
+    async fn delete_reservation(&self, _name: &str) -> SchedResult<bool> {
+        Err(SchedError::Unsupported("Storing reservations".to_string()))
+    }
+
+    /// List all reservations, ordered by name.
+    async fn list_reservations(&self) -> SchedResult<Vec<Reservation>> {
+        Ok(Vec::new())
+    }
+
+    /// Save a calibration snapshot under its backend and epoch, replacing
+    /// any snapshot of the same epoch.
+    async fn save_calibration(&self, _calibration: &BackendCalibration) -> SchedResult<()> {
+        Err(SchedError::Unsupported(
+            "Storing calibration snapshots".to_string(),
+        ))
+    }
+
+    /// Load a backend's calibration snapshot of an epoch, or its latest one
+    /// if `epoch` is `None`.
+    async fn load_calibration(
+        &self,
+        _backend: &str,
+        _epoch: Option<u64>,
+    ) -> SchedResult<Option<BackendCalibration>> {
+        Ok(None)
+    }
+
     /// Clean up old completed/failed jobs.
     async fn cleanup_old_jobs(&self, max_age_seconds: u64) -> SchedResult<usize>;
+
+    /// Rebuild the indices derived from stored jobs (status, priority,
+    /// lineage, ...) from the jobs themselves.
+    ///
+    /// Returns the number of jobs reindexed. The default re-saves every job.
+    async fn rebuild_indices(&self) -> SchedResult<usize> {
+        let jobs = self.list_jobs(&JobFilter::default()).await?;
+        for job in &jobs {
+            self.save_job(job).await?;
+        }
+        Ok(jobs.len())
+    }
 }
@@ -1,16 +1,26 @@
 //! SQLite-based persistence for production use.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::Path;
 use std::sync::Arc;
 
 use arvak_hal::ExecutionResult;
 use async_trait::async_trait;
-use rusqlite::Connection;
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{Connection, OptionalExtension};
 use std::sync::Mutex;
 
+use crate::calibration::BackendCalibration;
 use crate::error::{SchedError, SchedResult};
+use crate::id::Ulid;
+use crate::iteration::IterationRecord;
 use crate::job::{JobFilter, ScheduledJob, ScheduledJobId, ScheduledJobStatus};
-use crate::persistence::StateStore;
+use crate::leader::{LeaseInfo, LeaseStore};
+use crate::persistence::circuits::{dehydrate, hydrate};
+use crate::persistence::{BlobFormat, StateStore};
+use crate::recurring::RecurringJob;
+use crate::reservation::Reservation;
+use crate::template::JobTemplate;
 use crate::workflow::{Workflow, WorkflowId};
 
 /// SQLite-based state store.
@@ -19,6 +29,7 @@ use crate::workflow::{Workflow, WorkflowId};
 /// production use.
 pub struct SqliteStore {
     conn: Arc<Mutex<Connection>>,
+    format: BlobFormat,
 }
 
 impl SqliteStore {
@@ -27,6 +38,7 @@ impl SqliteStore {
         let conn = Connection::open(path)?;
         let store = Self {
             conn: Arc::new(Mutex::new(conn)),
+            format: BlobFormat::default(),
         };
         store.init_schema_sync()?;
         Ok(store)
@@ -37,16 +49,37 @@ impl SqliteStore {
         let conn = Connection::open_in_memory()?;
         let store = Self {
             conn: Arc::new(Mutex::new(conn)),
+            format: BlobFormat::default(),
         };
         store.init_schema_sync()?;
         Ok(store)
     }
 
+    /// Set the encoding for newly written job, result and workflow blobs.
+    ///
+    /// Existing rows keep their encoding and remain readable, so the format
+    /// can be switched on an existing database.
+    pub fn with_blob_format(mut self, format: BlobFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Encode a blob; JSON is stored as TEXT and CBOR as BLOB.
+    fn encode<T: serde::Serialize>(&self, value: &T) -> SchedResult<Value> {
+        Ok(match self.format {
+            BlobFormat::Json => Value::Text(serde_json::to_string(value)?),
+            BlobFormat::Cbor => Value::Blob(BlobFormat::Cbor.encode(value)?),
+        })
+    }
+
     fn init_schema_sync(&self) -> SchedResult<()> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let has_refs: bool = conn
+            .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'job_refs'")?
+            .exists([])?;
         conn.execute_batch(
             r#"
             CREATE TABLE IF NOT EXISTS jobs (
@@ -57,7 +90,8 @@ impl SqliteStore {
                 data TEXT NOT NULL,
                 created_at TEXT NOT NULL,
                 submitted_at TEXT,
-                completed_at TEXT
+                completed_at TEXT,
+                parent_id TEXT
             );
 
             CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);
@@ -76,12 +110,253 @@ impl SqliteStore {
                 data TEXT NOT NULL,
                 created_at TEXT NOT NULL
             );
+
+            CREATE TABLE IF NOT EXISTS iterations (
+                run_id TEXT NOT NULL,
+                iteration INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                recorded_at TEXT NOT NULL,
+                PRIMARY KEY (run_id, iteration)
+            );
+
+            CREATE TABLE IF NOT EXISTS node_cache (
+                key TEXT PRIMARY KEY,
+                job_id TEXT NOT NULL,
+                recorded_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS leases (
+                name TEXT PRIMARY KEY,
+                holder TEXT NOT NULL,
+                expires_at INTEGER NOT NULL,
+                generation INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE TABLE IF NOT EXISTS job_refs (
+                reference TEXT NOT NULL,
+                job_id TEXT NOT NULL,
+                PRIMARY KEY (reference, job_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS circuits (
+                hash TEXT PRIMARY KEY,
+                source TEXT NOT NULL,
+                refs INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS job_circuits (
+                job_id TEXT NOT NULL,
+                hash TEXT NOT NULL,
+                PRIMARY KEY (job_id, hash)
+            );
+
+            CREATE TABLE IF NOT EXISTS templates (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS recurring_jobs (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS reservations (
+                name TEXT PRIMARY KEY,
+                data TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS calibrations (
+                backend TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                calibrated_at TEXT NOT NULL,
+                PRIMARY KEY (backend, epoch)
+            );
             "#,
         )?;
+
+        // Databases created before lineage tracking lack the parent column.
+        let has_parent: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('jobs') WHERE name = 'parent_id'")?
+            .exists([])?;
+        if !has_parent {
+            conn.execute_batch(
+                r#"
+                ALTER TABLE jobs ADD COLUMN parent_id TEXT;
+                UPDATE jobs SET parent_id = json_extract(data, '$.parent');
+                "#,
+            )?;
+        }
+        conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_jobs_parent_id ON jobs(parent_id);")?;
+
+        // Databases created before fencing tokens lack the generation column.
+        let has_generation: bool = conn
+            .prepare("SELECT 1 FROM pragma_table_info('leases') WHERE name = 'generation'")?
+            .exists([])?;
+        if !has_generation {
+            conn.execute_batch(
+                "ALTER TABLE leases ADD COLUMN generation INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+
+        migrate_legacy_ids(&conn)?;
+
+        // Databases created before cross-references lack them for old jobs.
+        if !has_refs {
+            let mut stmt = conn.prepare("SELECT data FROM jobs")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                save_refs(&conn, &decode::<ScheduledJob>(row.get_ref(0)?)?)?;
+            }
+        }
         Ok(())
     }
 }
 
+/// Rewrite keys written as UUIDs, before IDs were ULIDs, to the ULID form
+/// the same IDs are written as now.
+fn migrate_legacy_ids(conn: &Connection) -> SchedResult<()> {
+    const KEYS: [(&str, &str); 6] = [
+        ("jobs", "id"),
+        ("jobs", "parent_id"),
+        ("results", "job_id"),
+        ("workflows", "id"),
+        ("node_cache", "job_id"),
+        ("job_refs", "job_id"),
+    ];
+    for (table, column) in KEYS {
+        let legacy: Vec<String> = conn
+            .prepare(&format!(
+                "SELECT DISTINCT {column} FROM {table} WHERE length({column}) = 36"
+            ))?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+        for old in legacy {
+            if let Ok(id) = Ulid::parse(&old) {
+                conn.execute(
+                    &format!("UPDATE {table} SET {column} = ?1 WHERE {column} = ?2"),
+                    rusqlite::params![id.to_string(), old],
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Index a job's external references.
+fn save_refs(conn: &Connection, job: &ScheduledJob) -> SchedResult<()> {
+    let job_id = job.id.to_string();
+    for reference in job.references() {
+        conn.execute(
+            "INSERT OR IGNORE INTO job_refs (reference, job_id) VALUES (?1, ?2)",
+            rusqlite::params![reference, job_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Record the circuit sources a job holds, by hash. Each source counts a
+/// reference per job holding it and is dropped with its last reference.
+fn save_circuits(conn: &Connection, job_id: &str, sources: &[(String, String)]) -> SchedResult<()> {
+    let held: BTreeSet<String> = conn
+        .prepare("SELECT hash FROM job_circuits WHERE job_id = ?1")?
+        .query_map(rusqlite::params![job_id], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    let sources: BTreeMap<&str, &str> = sources
+        .iter()
+        .map(|(hash, source)| (hash.as_str(), source.as_str()))
+        .collect();
+
+    for (hash, source) in &sources {
+        if held.contains(*hash) {
+            continue;
+        }
+        conn.execute(
+            r#"
+            INSERT INTO circuits (hash, source, refs) VALUES (?1, ?2, 1)
+            ON CONFLICT (hash) DO UPDATE SET refs = refs + 1
+            "#,
+            rusqlite::params![hash, source],
+        )?;
+        conn.execute(
+            "INSERT INTO job_circuits (job_id, hash) VALUES (?1, ?2)",
+            rusqlite::params![job_id, hash],
+        )?;
+    }
+    for hash in held
+        .iter()
+        .filter(|hash| !sources.contains_key(hash.as_str()))
+    {
+        conn.execute(
+            "DELETE FROM job_circuits WHERE job_id = ?1 AND hash = ?2",
+            rusqlite::params![job_id, hash],
+        )?;
+        conn.execute(
+            "UPDATE circuits SET refs = refs - 1 WHERE hash = ?1",
+            rusqlite::params![hash],
+        )?;
+    }
+    conn.execute("DELETE FROM circuits WHERE refs <= 0", [])?;
+    Ok(())
+}
+
+/// Recount the references to circuit sources after jobs were deleted in
+/// bulk, dropping the sources no job holds any more.
+fn recount_circuits(conn: &Connection) -> SchedResult<()> {
+    conn.execute_batch(
+        r#"
+        DELETE FROM job_circuits WHERE job_id NOT IN (SELECT id FROM jobs);
+        UPDATE circuits SET refs = (
+            SELECT COUNT(*) FROM job_circuits WHERE job_circuits.hash = circuits.hash
+        );
+        DELETE FROM circuits WHERE refs <= 0;
+        "#,
+    )?;
+    Ok(())
+}
+
+/// Decode a stored job record, loading its circuit sources; `sources`
+/// memoizes sources shared by several records.
+fn decode_job(
+    conn: &Connection,
+    value: ValueRef<'_>,
+    sources: &mut rustc_hash::FxHashMap<String, String>,
+) -> SchedResult<ScheduledJob> {
+    let mut job: ScheduledJob = decode(value)?;
+    hydrate(&mut job, |hash| {
+        if let Some(source) = sources.get(hash) {
+            return Ok(Some(source.clone()));
+        }
+        let source: Option<String> = conn
+            .query_row(
+                "SELECT source FROM circuits WHERE hash = ?1",
+                rusqlite::params![hash],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(source) = &source {
+            sources.insert(hash.to_string(), source.clone());
+        }
+        Ok(source)
+    })?;
+    Ok(job)
+}
+
+/// Decode a blob written in either format.
+fn decode<T: serde::de::DeserializeOwned>(value: ValueRef<'_>) -> SchedResult<T> {
+    match value {
+        ValueRef::Text(text) => BlobFormat::Json.decode(text),
+        ValueRef::Blob(bytes) => BlobFormat::Cbor.decode(bytes),
+        other => Err(SchedError::PersistenceError(format!(
+            "Unexpected blob type: {:?}",
+            other.data_type()
+        ))),
+    }
+}
+
 #[async_trait]
 impl StateStore for SqliteStore {
     async fn save_job(&self, job: &ScheduledJob) -> SchedResult<()> {
@@ -89,12 +364,14 @@ impl StateStore for SqliteStore {
             .conn
             .lock()
             .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
-        let data = serde_json::to_string(job)?;
+        let (record, sources) = dehydrate(job);
+        let data = self.encode(&record)?;
 
-        conn.execute(
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
             r#"
-            INSERT OR REPLACE INTO jobs (id, name, status, priority, data, created_at, submitted_at, completed_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+            INSERT OR REPLACE INTO jobs (id, name, status, priority, data, created_at, submitted_at, completed_at, parent_id)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
             "#,
             rusqlite::params![
                 job.id.to_string(),
@@ -105,8 +382,12 @@ impl StateStore for SqliteStore {
                 job.created_at.to_rfc3339(),
                 job.submitted_at.map(|t| t.to_rfc3339()),
                 job.completed_at.map(|t| t.to_rfc3339()),
+                job.parent.as_ref().map(|p| p.to_string()),
             ],
         )?;
+        save_refs(&tx, job)?;
+        save_circuits(&tx, &job.id.to_string(), &sources)?;
+        tx.commit()?;
 
         Ok(())
     }
@@ -121,8 +402,7 @@ impl StateStore for SqliteStore {
         let mut rows = stmt.query(rusqlite::params![job_id.to_string()])?;
 
         if let Some(row) = rows.next()? {
-            let data: String = row.get(0)?;
-            let job: ScheduledJob = serde_json::from_str(&data)?;
+            let job = decode_job(&conn, row.get_ref(0)?, &mut Default::default())?;
             Ok(Some(job))
         } else {
             Ok(None)
@@ -153,10 +433,17 @@ impl StateStore for SqliteStore {
             .conn
             .lock()
             .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
-        let deleted = conn.execute(
+        let tx = conn.unchecked_transaction()?;
+        let deleted = tx.execute(
             "DELETE FROM jobs WHERE id = ?1",
             rusqlite::params![job_id.to_string()],
         )?;
+        tx.execute(
+            "DELETE FROM job_refs WHERE job_id = ?1",
+            rusqlite::params![job_id.to_string()],
+        )?;
+        save_circuits(&tx, &job_id.to_string(), &[])?;
+        tx.commit()?;
         Ok(deleted > 0)
     }
 
@@ -202,6 +489,12 @@ impl StateStore for SqliteStore {
             params.push(Box::new(max_priority.value() as i64));
         }
 
+        if let Some(ref parent) = filter.parent {
+            let idx = params.len() + 1;
+            sql.push_str(&format!(" AND parent_id = ?{}", idx));
+            params.push(Box::new(parent.to_string()));
+        }
+
         sql.push_str(" ORDER BY priority DESC, created_at ASC");
 
         if let Some(limit) = filter.limit {
@@ -216,10 +509,10 @@ impl StateStore for SqliteStore {
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|b| b.as_ref()).collect();
         let mut rows = stmt.query(params_refs.as_slice())?;
 
+        let mut sources = rustc_hash::FxHashMap::default();
         let mut jobs = Vec::new();
         while let Some(row) = rows.next()? {
-            let data: String = row.get(0)?;
-            let job: ScheduledJob = serde_json::from_str(&data)?;
+            let job = decode_job(&conn, row.get_ref(0)?, &mut sources)?;
 
             // Apply additional filters that can't be done in SQL
             if let Some(ref pattern) = filter.name_pattern {
@@ -255,7 +548,7 @@ impl StateStore for SqliteStore {
             .conn
             .lock()
             .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
-        let data = serde_json::to_string(result)?;
+        let data = self.encode(result)?;
 
         conn.execute(
             "INSERT OR REPLACE INTO results (job_id, data) VALUES (?1, ?2)",
@@ -275,20 +568,31 @@ impl StateStore for SqliteStore {
         let mut rows = stmt.query(rusqlite::params![job_id.to_string()])?;
 
         if let Some(row) = rows.next()? {
-            let data: String = row.get(0)?;
-            let result: ExecutionResult = serde_json::from_str(&data)?;
+            let result: ExecutionResult = decode(row.get_ref(0)?)?;
             Ok(Some(result))
         } else {
             Ok(None)
         }
     }
 
+    async fn delete_result(&self, job_id: &ScheduledJobId) -> SchedResult<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let deleted = conn.execute(
+            "DELETE FROM results WHERE job_id = ?1",
+            rusqlite::params![job_id.to_string()],
+        )?;
+        Ok(deleted > 0)
+    }
+
     async fn save_workflow(&self, workflow: &Workflow) -> SchedResult<()> {
         let conn = self
             .conn
             .lock()
             .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
-        let data = serde_json::to_string(workflow)?;
+        let data = self.encode(workflow)?;
 
         conn.execute(
             r#"
@@ -316,8 +620,7 @@ impl StateStore for SqliteStore {
         let mut rows = stmt.query(rusqlite::params![workflow_id.to_string()])?;
 
         if let Some(row) = rows.next()? {
-            let data: String = row.get(0)?;
-            let workflow: Workflow = serde_json::from_str(&data)?;
+            let workflow: Workflow = decode(row.get_ref(0)?)?;
             Ok(Some(workflow))
         } else {
             Ok(None)
@@ -356,6 +659,342 @@ impl StateStore for SqliteStore {
         Ok(ids)
     }
 
+    async fn save_iteration(&self, record: &IterationRecord) -> SchedResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let data = self.encode(record)?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO iterations (run_id, iteration, data, recorded_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            rusqlite::params![
+                record.run_id,
+                record.iteration as i64,
+                data,
+                record.recorded_at.to_rfc3339(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn load_iterations(&self, run_id: &str) -> SchedResult<Vec<IterationRecord>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt =
+            conn.prepare("SELECT data FROM iterations WHERE run_id = ?1 ORDER BY iteration")?;
+        let mut rows = stmt.query(rusqlite::params![run_id])?;
+
+        let mut records = Vec::new();
+        while let Some(row) = rows.next()? {
+            records.push(decode(row.get_ref(0)?)?);
+        }
+
+        Ok(records)
+    }
+
+    async fn save_cache_entry(&self, key: &str, job_id: &ScheduledJobId) -> SchedResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO node_cache (key, job_id, recorded_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+            rusqlite::params![key, job_id.to_string(), chrono::Utc::now().to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    async fn load_cache_entry(&self, key: &str) -> SchedResult<Option<ScheduledJobId>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT job_id FROM node_cache WHERE key = ?1")?;
+        let mut rows = stmt.query(rusqlite::params![key])?;
+
+        match rows.next()? {
+            Some(row) => {
+                let id: String = row.get(0)?;
+                Ok(ScheduledJobId::parse(&id).ok())
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn find_job_by_ref(&self, reference: &str) -> SchedResult<Option<ScheduledJobId>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let mut stmt = conn.prepare(
+            "SELECT job_id FROM job_refs WHERE reference = ?1 ORDER BY rowid DESC LIMIT 1",
+        )?;
+        let mut rows = stmt.query(rusqlite::params![reference])?;
+        match rows.next()? {
+            Some(row) => Ok(ScheduledJobId::parse(&row.get::<_, String>(0)?).ok()),
+            None => Ok(None),
+        }
+    }
+
+    async fn save_template(&self, template: &JobTemplate) -> SchedResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let data = self.encode(template)?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO templates (name, data, updated_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+            rusqlite::params![template.name, data, template.updated_at.to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    async fn load_template(&self, name: &str) -> SchedResult<Option<JobTemplate>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT data FROM templates WHERE name = ?1")?;
+        let mut rows = stmt.query(rusqlite::params![name])?;
+
+        match rows.next()? {
+            Some(row) => Ok(Some(decode(row.get_ref(0)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_template(&self, name: &str) -> SchedResult<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let deleted = conn.execute(
+            "DELETE FROM templates WHERE name = ?1",
+            rusqlite::params![name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    async fn list_templates(&self) -> SchedResult<Vec<JobTemplate>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT data FROM templates ORDER BY name")?;
+        let mut rows = stmt.query([])?;
+
+        let mut templates = Vec::new();
+        while let Some(row) = rows.next()? {
+            templates.push(decode(row.get_ref(0)?)?);
+        }
+
+        Ok(templates)
+    }
+
+    async fn save_recurring(&self, recurring: &RecurringJob) -> SchedResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let data = self.encode(recurring)?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO recurring_jobs (name, data, updated_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+            rusqlite::params![recurring.name, data, recurring.updated_at.to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    async fn load_recurring(&self, name: &str) -> SchedResult<Option<RecurringJob>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT data FROM recurring_jobs WHERE name = ?1")?;
+        let mut rows = stmt.query(rusqlite::params![name])?;
+
+        match rows.next()? {
+            Some(row) => Ok(Some(decode(row.get_ref(0)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_recurring(&self, name: &str) -> SchedResult<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let deleted = conn.execute(
+            "DELETE FROM recurring_jobs WHERE name = ?1",
+            rusqlite::params![name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    async fn list_recurring(&self) -> SchedResult<Vec<RecurringJob>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT data FROM recurring_jobs ORDER BY name")?;
+        let mut rows = stmt.query([])?;
+
+        let mut recurring = Vec::new();
+        while let Some(row) = rows.next()? {
+            recurring.push(decode(row.get_ref(0)?)?);
+        }
+
+        Ok(recurring)
+    }
+
+    async fn save_reservation(&self, reservation: &Reservation) -> SchedResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let data = self.encode(reservation)?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO reservations (name, data, created_at)
+            VALUES (?1, ?2, ?3)
+            "#,
+            rusqlite::params![reservation.name, data, reservation.created_at.to_rfc3339()],
+        )?;
+
+        Ok(())
+    }
+
+    async fn load_reservation(&self, name: &str) -> SchedResult<Option<Reservation>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT data FROM reservations WHERE name = ?1")?;
+        let mut rows = stmt.query(rusqlite::params![name])?;
+
+        match rows.next()? {
+            Some(row) => Ok(Some(decode(row.get_ref(0)?)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn delete_reservation(&self, name: &str) -> SchedResult<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let deleted = conn.execute(
+            "DELETE FROM reservations WHERE name = ?1",
+            rusqlite::params![name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    async fn list_reservations(&self) -> SchedResult<Vec<Reservation>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare("SELECT data FROM reservations ORDER BY name")?;
+        let mut rows = stmt.query([])?;
+
+        let mut reservations = Vec::new();
+        while let Some(row) = rows.next()? {
+            reservations.push(decode(row.get_ref(0)?)?);
+        }
+
+        Ok(reservations)
+    }
+
+    async fn save_calibration(&self, calibration: &BackendCalibration) -> SchedResult<()> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let data = self.encode(calibration)?;
+        let epoch = i64::try_from(calibration.epoch)
+            .map_err(|_| SchedError::PersistenceError("calibration epoch overflow".into()))?;
+
+        conn.execute(
+            r#"
+            INSERT OR REPLACE INTO calibrations (backend, epoch, data, calibrated_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+            rusqlite::params![
+                calibration.backend,
+                epoch,
+                data,
+                calibration.calibrated_at.to_rfc3339()
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn load_calibration(
+        &self,
+        backend: &str,
+        epoch: Option<u64>,
+    ) -> SchedResult<Option<BackendCalibration>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let epoch = match epoch.map(i64::try_from) {
+            Some(Ok(epoch)) => Some(epoch),
+            Some(Err(_)) => return Ok(None),
+            None => None,
+        };
+        let mut stmt = match epoch {
+            Some(_) => {
+                conn.prepare("SELECT data FROM calibrations WHERE backend = ?1 AND epoch = ?2")?
+            }
+            None => conn.prepare(
+                "SELECT data FROM calibrations WHERE backend = ?1 ORDER BY epoch DESC LIMIT 1",
+            )?,
+        };
+        let mut rows = match epoch {
+            Some(epoch) => stmt.query(rusqlite::params![backend, epoch])?,
+            None => stmt.query(rusqlite::params![backend])?,
+        };
+
+        match rows.next()? {
+            Some(row) => Ok(Some(decode(row.get_ref(0)?)?)),
+            None => Ok(None),
+        }
+    }
+
     async fn cleanup_old_jobs(&self, max_age_seconds: u64) -> SchedResult<usize> {
         let cutoff = chrono::Utc::now() - chrono::Duration::seconds(max_age_seconds as i64);
         let cutoff_str = cutoff.to_rfc3339();
@@ -372,7 +1011,7 @@ impl StateStore for SqliteStore {
                 SELECT id FROM jobs
                 WHERE completed_at IS NOT NULL
                 AND completed_at < ?1
-                AND status IN ('Completed', 'Failed', 'Cancelled')
+                AND status IN ('Completed', 'CachedComplete', 'Failed', 'Cancelled', 'DeadlineExceeded')
             )
             "#,
             rusqlite::params![cutoff_str],
@@ -384,13 +1023,149 @@ impl StateStore for SqliteStore {
             DELETE FROM jobs
             WHERE completed_at IS NOT NULL
             AND completed_at < ?1
-            AND status IN ('Completed', 'Failed', 'Cancelled')
+            AND status IN ('Completed', 'CachedComplete', 'Failed', 'Cancelled', 'DeadlineExceeded')
             "#,
             rusqlite::params![cutoff_str],
         )?;
+        conn.execute(
+            "DELETE FROM job_refs WHERE job_id NOT IN (SELECT id FROM jobs)",
+            [],
+        )?;
+        recount_circuits(&conn)?;
 
         Ok(deleted)
     }
+
+    async fn rebuild_indices(&self) -> SchedResult<usize> {
+        let mut conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        let tx = conn.transaction()?;
+
+        // Re-derive the columns from the job blobs, which are authoritative.
+        let jobs = {
+            let mut stmt = tx.prepare("SELECT data FROM jobs")?;
+            let mut rows = stmt.query([])?;
+            let mut jobs = Vec::new();
+            while let Some(row) = rows.next()? {
+                jobs.push(decode::<ScheduledJob>(row.get_ref(0)?)?);
+            }
+            jobs
+        };
+        for job in &jobs {
+            tx.execute(
+                r#"
+                UPDATE jobs SET name = ?2, status = ?3, priority = ?4, created_at = ?5,
+                    submitted_at = ?6, completed_at = ?7, parent_id = ?8
+                WHERE id = ?1
+                "#,
+                rusqlite::params![
+                    job.id.to_string(),
+                    job.name,
+                    job.status.name(),
+                    job.priority.value(),
+                    job.created_at.to_rfc3339(),
+                    job.submitted_at.map(|t| t.to_rfc3339()),
+                    job.completed_at.map(|t| t.to_rfc3339()),
+                    job.parent.as_ref().map(|p| p.to_string()),
+                ],
+            )?;
+        }
+        tx.execute("DELETE FROM job_refs", [])?;
+        for job in &jobs {
+            save_refs(&tx, job)?;
+        }
+        tx.execute_batch("REINDEX jobs;")?;
+        tx.commit()?;
+
+        Ok(jobs.len())
+    }
+}
+
+#[async_trait]
+impl LeaseStore for SqliteStore {
+    async fn try_acquire_lease(
+        &self,
+        name: &str,
+        holder: &str,
+        ttl: std::time::Duration,
+    ) -> SchedResult<Option<u64>> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let expires_at = now.saturating_add(ttl.as_millis() as i64);
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        // Upsert only when the lease is free, expired, or already ours,
+        // moving the fencing token on unless we renew in time.
+        let generation: Option<i64> = conn
+            .query_row(
+                r#"
+                INSERT INTO leases (name, holder, expires_at, generation)
+                VALUES (?1, ?2, ?3, 1)
+                ON CONFLICT(name) DO UPDATE SET
+                    holder = excluded.holder,
+                    expires_at = excluded.expires_at,
+                    generation = CASE
+                        WHEN leases.holder = excluded.holder AND leases.expires_at > ?4
+                        THEN leases.generation
+                        ELSE leases.generation + 1
+                    END
+                WHERE leases.holder = excluded.holder OR leases.expires_at <= ?4
+                RETURNING generation
+                "#,
+                rusqlite::params![name, holder, expires_at, now],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(generation.map(|g| g as u64))
+    }
+
+    async fn release_lease(&self, name: &str, holder: &str) -> SchedResult<bool> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+        // Expire rather than delete the lease to keep its fencing token.
+        let released = conn.execute(
+            "UPDATE leases SET expires_at = 0 WHERE name = ?1 AND holder = ?2 AND expires_at > 0",
+            rusqlite::params![name, holder],
+        )?;
+        Ok(released > 0)
+    }
+
+    async fn lease_holder(&self, name: &str) -> SchedResult<Option<LeaseInfo>> {
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| SchedError::DatabaseError(e.to_string()))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT holder, expires_at, generation FROM leases WHERE name = ?1 AND expires_at > ?2",
+        )?;
+        let mut rows = stmt.query(rusqlite::params![
+            name,
+            chrono::Utc::now().timestamp_millis()
+        ])?;
+
+        if let Some(row) = rows.next()? {
+            let holder: String = row.get(0)?;
+            let expires_ms: i64 = row.get(1)?;
+            let generation: i64 = row.get(2)?;
+            Ok(Some(LeaseInfo {
+                name: name.to_string(),
+                holder,
+                expires_at: chrono::DateTime::from_timestamp_millis(expires_ms).unwrap_or_default(),
+                generation: generation as u64,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -486,4 +1261,291 @@ mod tests {
         assert_eq!(loaded.shots, 1000);
         assert_eq!(loaded.counts.get("00"), 500);
     }
+
+    #[tokio::test]
+    async fn test_sqlite_store_leases() {
+        use std::time::Duration;
+
+        let store = SqliteStore::in_memory().unwrap();
+        let ttl = Duration::from_secs(30);
+
+        assert_eq!(
+            store.try_acquire_lease("dispatch", "a", ttl).await.unwrap(),
+            Some(1)
+        );
+        assert_eq!(
+            store.try_acquire_lease("dispatch", "b", ttl).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            store.try_acquire_lease("dispatch", "a", ttl).await.unwrap(),
+            Some(1)
+        );
+
+        let info = store.lease_holder("dispatch").await.unwrap().unwrap();
+        assert_eq!(info.holder, "a");
+        assert!(store.holds_lease("dispatch", "a", 1).await.unwrap());
+
+        assert!(store.release_lease("dispatch", "a").await.unwrap());
+        assert!(!store.holds_lease("dispatch", "a", 1).await.unwrap());
+        assert_eq!(
+            store.try_acquire_lease("dispatch", "b", ttl).await.unwrap(),
+            Some(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_migrates_parent_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.db");
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0;");
+        let parent = ScheduledJob::new("parent", circuit.clone());
+        let child = ScheduledJob::new("child", circuit).derived_from(parent.id.clone(), "zne");
+        {
+            // Schema as written before lineage tracking.
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE jobs (id TEXT PRIMARY KEY, name TEXT NOT NULL, \
+                 status TEXT NOT NULL, priority INTEGER NOT NULL, data TEXT NOT NULL, \
+                 created_at TEXT NOT NULL, submitted_at TEXT, completed_at TEXT);",
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO jobs (id, name, status, priority, data, created_at) \
+                 VALUES (?1, 'child', 'Pending', 100, ?2, ?3)",
+                rusqlite::params![
+                    child.id.to_string(),
+                    serde_json::to_string(&child).unwrap(),
+                    child.created_at.to_rfc3339(),
+                ],
+            )
+            .unwrap();
+        }
+
+        let store = SqliteStore::new(&path).unwrap();
+        let children = store.list_children(&parent.id).await.unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].id, child.id);
+    }
+    #[tokio::test]
+    async fn test_sqlite_store_rebuild_indices() {
+        let store = SqliteStore::in_memory().unwrap();
+        let job = ScheduledJob::new("stale", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        store.save_job(&job).await.unwrap();
+
+        // Columns drifted from the blob, e.g. after a manual edit.
+        store
+            .conn
+            .lock()
+            .unwrap()
+            .execute("UPDATE jobs SET status = 'Completed', priority = 0", [])
+            .unwrap();
+        assert!(
+            store
+                .list_jobs(&JobFilter::pending())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(store.rebuild_indices().await.unwrap(), 1);
+        let pending = store.list_jobs(&JobFilter::pending()).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].id, job.id);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_dedups_circuits() {
+        let store = SqliteStore::in_memory().unwrap();
+        let ansatz = "OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];";
+        let jobs: Vec<_> = (0..3)
+            .map(|i| ScheduledJob::new(format!("point-{}", i), CircuitSpec::from_qasm(ansatz)))
+            .collect();
+        for job in &jobs {
+            store.save_job(job).await.unwrap();
+        }
+        // Saving again does not count another reference
+        store.save_job(&jobs[0]).await.unwrap();
+
+        let circuits = |store: &SqliteStore| -> Vec<(String, i64)> {
+            let conn = store.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT hash, refs FROM circuits").unwrap();
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        assert_eq!(
+            circuits(&store),
+            [(crate::persistence::circuit_hash(ansatz).unwrap(), 3)]
+        );
+        let data: String = store
+            .conn
+            .lock()
+            .unwrap()
+            .query_row("SELECT data FROM jobs LIMIT 1", [], |row| row.get(0))
+            .unwrap();
+        assert!(!data.contains("OPENQASM"));
+
+        let loaded = store.load_job(&jobs[1].id).await.unwrap().unwrap();
+        assert!(matches!(&loaded.circuits[0], CircuitSpec::Qasm3(s) if s == ansatz));
+        let listed = store.list_jobs(&JobFilter::default()).await.unwrap();
+        assert_eq!(listed.len(), 3);
+        assert!(listed.iter().all(|job| job.circuits[0].resolve().is_ok()));
+
+        // Changing a job's circuit moves its reference
+        let mut changed = jobs[2].clone();
+        changed.circuits = vec![CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q;")];
+        store.save_job(&changed).await.unwrap();
+        assert_eq!(circuits(&store).len(), 2);
+
+        store.delete_job(&jobs[0].id).await.unwrap();
+        store.delete_job(&jobs[1].id).await.unwrap();
+        assert_eq!(circuits(&store).len(), 1);
+        store.delete_job(&changed.id).await.unwrap();
+        assert!(circuits(&store).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_iterations() {
+        let store = SqliteStore::in_memory().unwrap();
+        let job_id = ScheduledJobId::new();
+
+        for (iteration, energy) in [(1, -0.8), (0, -0.5), (1, -0.9)] {
+            let record = IterationRecord::new("campaign", iteration, vec![0.1, 0.2], energy)
+                .with_variance(1e-4)
+                .with_job_ids(vec![job_id.clone()]);
+            store.save_iteration(&record).await.unwrap();
+        }
+
+        let records = store.load_iterations("campaign").await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].iteration, 0);
+        assert_eq!(records[1].value, -0.9);
+        assert_eq!(records[1].job_ids, vec![job_id]);
+        assert!(store.load_iterations("other").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_cache_entries() {
+        let store = SqliteStore::in_memory().unwrap();
+        let (first, second) = (ScheduledJobId::new(), ScheduledJobId::new());
+
+        assert!(store.load_cache_entry("00ff").await.unwrap().is_none());
+        store.save_cache_entry("00ff", &first).await.unwrap();
+        store.save_cache_entry("00ff", &second).await.unwrap();
+        assert_eq!(store.load_cache_entry("00ff").await.unwrap(), Some(second));
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_templates() {
+        let store = SqliteStore::in_memory().unwrap();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0;");
+        let bell = JobTemplate::new("bell", circuit.clone()).with_shots(100);
+        let ghz = JobTemplate::new("ghz", circuit).with_label("project", "demo");
+
+        store.save_template(&ghz).await.unwrap();
+        store.save_template(&bell).await.unwrap();
+        store
+            .save_template(&bell.clone().with_shots(200))
+            .await
+            .unwrap();
+
+        let names: Vec<_> = store
+            .list_templates()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["bell", "ghz"]);
+        assert_eq!(
+            store.load_template("bell").await.unwrap().unwrap().shots,
+            200
+        );
+        assert!(store.delete_template("bell").await.unwrap());
+        assert!(!store.delete_template("bell").await.unwrap());
+        assert!(store.load_template("bell").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_calibrations() {
+        let store = SqliteStore::in_memory().unwrap();
+        assert!(
+            store
+                .load_calibration("garnet", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        for epoch in [1, 2] {
+            let mut calibration = BackendCalibration::new("garnet").with_gate_error(
+                "cz",
+                [0, 1],
+                0.01 * epoch as f64,
+            );
+            calibration.epoch = epoch;
+            store.save_calibration(&calibration).await.unwrap();
+        }
+        let latest = store
+            .load_calibration("garnet", None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.epoch, 2);
+        let first = store
+            .load_calibration("garnet", Some(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.gate_error("cz", &[0, 1]), Some(0.01));
+        assert!(
+            store
+                .load_calibration("eagle", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_store_resolves_references_and_legacy_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.db");
+
+        let uuid = uuid::Uuid::new_v4();
+        let mut job = ScheduledJob::new("legacy", CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .with_request_id("req-7");
+        job.id = ScheduledJobId::from_uuid(uuid);
+        job.batch_job_id = Some("4242".to_string());
+        {
+            let store = SqliteStore::new(&path).unwrap();
+            store.save_job(&job).await.unwrap();
+            // Key the row the way it was written before ULIDs.
+            store
+                .conn
+                .lock()
+                .unwrap()
+                .execute("UPDATE jobs SET id = ?1", [uuid.to_string()])
+                .unwrap();
+        }
+
+        let store = SqliteStore::new(&path).unwrap();
+        let loaded = store.load_job(&job.id).await.unwrap().unwrap();
+        assert_eq!(loaded.name, "legacy");
+
+        assert_eq!(
+            store.find_job_by_ref("4242").await.unwrap(),
+            Some(job.id.clone())
+        );
+        for id in ["req-7", &uuid.to_string(), &job.id.to_string()] {
+            assert_eq!(
+                store.resolve_job_id(id).await.unwrap(),
+                Some(job.id.clone())
+            );
+        }
+        assert!(store.resolve_job_id("unknown").await.unwrap().is_none());
+    }
 }
@@ -0,0 +1,416 @@
+//! Write-ahead log of job dispatches.
+//!
+//! A job is taken off the queue, handed to the batch system and only then
+//! saved with its batch job ID. A crash in between leaves a job the store
+//! still sees as pending although it may already be running on the
+//! cluster. The [`WriteAheadLog`] records each step before it is taken,
+//! one JSON object per line synced to disk, so that
+//! [`crate::HpcScheduler::recover`] can tell which jobs were in flight.
+//!
+//! Writes, syncs and compaction run on a dedicated writer thread, so
+//! scheduler tasks only wait for their record to be durable. Records that
+//! arrive while a sync is in progress are synced together.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, mpsc};
+use std::thread::JoinHandle;
+
+use chrono::{DateTime, Utc};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::ScheduledJobId;
+
+/// Lines after which the log is rewritten to hold only open jobs.
+const COMPACT_AFTER_LINES: usize = 10_000;
+
+/// A step in a job's dispatch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalRecord {
+    /// When the step was logged.
+    pub at: DateTime<Utc>,
+
+    /// Job the step concerns.
+    pub job_id: ScheduledJobId,
+
+    /// The step.
+    #[serde(flatten)]
+    pub op: WalOp,
+}
+
+/// Steps of a job's dispatch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum WalOp {
+    /// The job was accepted and is about to be queued.
+    Enqueue,
+
+    /// The job is about to be handed to the batch system.
+    Dispatch,
+
+    /// The batch system accepted the job under this ID.
+    SlurmIdAssigned { slurm_job_id: String },
+
+    /// The job reached a terminal state; nothing is left to recover.
+    Complete,
+}
+
+/// Outcome of [`crate::HpcScheduler::recover`].
+#[derive(Debug, Clone, Default)]
+pub struct RecoveryReport {
+    /// Jobs found on the batch system, with the batch job ID they were
+    /// adopted under.
+    pub adopted: Vec<(ScheduledJobId, String)>,
+
+    /// Jobs that never reached the batch system and were queued again.
+    pub requeued: Vec<ScheduledJobId>,
+}
+
+/// Last record of every job not yet completed.
+type OpenRecords = Arc<Mutex<FxHashMap<ScheduledJobId, WalRecord>>>;
+
+/// Requests to the writer thread, each answered once it is on disk.
+enum WalCommand {
+    Append(WalRecord, oneshot::Sender<SchedResult<()>>),
+    Checkpoint(oneshot::Sender<SchedResult<()>>),
+}
+
+/// Append-only, fsynced log of job dispatches.
+#[derive(Debug)]
+pub struct WriteAheadLog {
+    path: PathBuf,
+    open: OpenRecords,
+    commands: Option<mpsc::Sender<WalCommand>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl WriteAheadLog {
+    /// Open a log file, creating it if needed, and replay its records.
+    ///
+    /// A malformed last line is taken to be a write torn by a crash and
+    /// ignored; malformed lines elsewhere are reported with their line
+    /// number. The file is then rewritten to hold only open jobs, so a torn
+    /// line is never followed by new records.
+    pub fn open(path: impl Into<PathBuf>) -> SchedResult<Self> {
+        let path = path.into();
+        let records = if path.exists() {
+            Self::read(&path)?
+        } else {
+            Vec::new()
+        };
+        let mut open = FxHashMap::default();
+        for record in records {
+            match record.op {
+                WalOp::Complete => {
+                    open.remove(&record.job_id);
+                }
+                _ => {
+                    open.insert(record.job_id.clone(), record);
+                }
+            }
+        }
+        let open = Arc::new(Mutex::new(open));
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut writer = WalWriter {
+            path: path.clone(),
+            file,
+            lines: 0,
+            open: open.clone(),
+        };
+        writer.compact()?;
+
+        let (commands, received) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("arvak-wal".to_string())
+            .spawn(move || writer.run(received))?;
+        Ok(Self {
+            path,
+            open,
+            commands: Some(commands),
+            writer: Some(writer),
+        })
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Log a step, returning once it is synced to disk.
+    pub async fn append(&self, job_id: &ScheduledJobId, op: WalOp) -> SchedResult<()> {
+        let record = WalRecord {
+            at: Utc::now(),
+            job_id: job_id.clone(),
+            op,
+        };
+        self.send(|done| WalCommand::Append(record, done)).await
+    }
+
+    /// Last record of every job not yet completed, oldest first.
+    pub fn open_records(&self) -> Vec<WalRecord> {
+        let open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        let mut records: Vec<_> = open.values().cloned().collect();
+        records.sort_by_key(|record| record.at);
+        records
+    }
+
+    /// Discard every record, once recovery has made the store consistent.
+    pub async fn checkpoint(&self) -> SchedResult<()> {
+        self.send(WalCommand::Checkpoint).await
+    }
+
+    async fn send(
+        &self,
+        command: impl FnOnce(oneshot::Sender<SchedResult<()>>) -> WalCommand,
+    ) -> SchedResult<()> {
+        let stopped = || SchedError::PersistenceError("Write-ahead log writer stopped".into());
+        let (done, written) = oneshot::channel();
+        self.commands
+            .as_ref()
+            .ok_or_else(stopped)?
+            .send(command(done))
+            .map_err(|_| stopped())?;
+        written.await.map_err(|_| stopped())?
+    }
+
+    /// Read all records from a log file, ignoring a torn last line.
+    pub fn read(path: impl AsRef<Path>) -> SchedResult<Vec<WalRecord>> {
+        let path = path.as_ref();
+        let reader = BufReader::new(File::open(path)?);
+        let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+        let last = lines.iter().rposition(|line| !line.trim().is_empty());
+        let mut records = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                Err(_) if Some(i) == last => {
+                    tracing::warn!("Ignoring torn last record in {}:{}", path.display(), i + 1);
+                }
+                Err(e) => {
+                    return Err(SchedError::ParseError(format!(
+                        "{}:{}: {}",
+                        path.display(),
+                        i + 1,
+                        e
+                    )));
+                }
+            }
+        }
+        Ok(records)
+    }
+}
+
+impl Drop for WriteAheadLog {
+    fn drop(&mut self) {
+        // Closing the channel stops the writer once queued records are on disk
+        self.commands.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Owner of the log file, running on the writer thread.
+struct WalWriter {
+    path: PathBuf,
+    file: File,
+    lines: usize,
+    open: OpenRecords,
+}
+
+impl WalWriter {
+    fn run(mut self, commands: mpsc::Receiver<WalCommand>) {
+        while let Ok(command) = commands.recv() {
+            let mut appended = Vec::new();
+            for command in std::iter::once(command).chain(commands.try_iter()) {
+                match command {
+                    WalCommand::Append(record, done) => {
+                        let written = self.write(&record);
+                        appended.push((record, done, written));
+                    }
+                    WalCommand::Checkpoint(done) => {
+                        self.sync(std::mem::take(&mut appended));
+                        let _ = done.send(self.checkpoint());
+                    }
+                }
+            }
+            self.sync(appended);
+
+            // Callers have their acknowledgements; compact before the next batch
+            if self.lines > COMPACT_AFTER_LINES {
+                if let Err(e) = self.compact() {
+                    tracing::warn!("Failed to compact {}: {}", self.path.display(), e);
+                }
+            }
+        }
+    }
+
+    fn write(&mut self, record: &WalRecord) -> SchedResult<()> {
+        let line = serde_json::to_string(record)?;
+        writeln!(self.file, "{}", line)?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// Sync written records with one `fsync` and acknowledge them.
+    fn sync(
+        &mut self,
+        appended: Vec<(WalRecord, oneshot::Sender<SchedResult<()>>, SchedResult<()>)>,
+    ) {
+        if appended.is_empty() {
+            return;
+        }
+        let synced = self.file.sync_data().map_err(|e| e.to_string());
+        let mut open = self.open.lock().unwrap_or_else(|e| e.into_inner());
+        for (record, done, written) in appended {
+            let result = written.and_then(|()| {
+                synced.clone().map_err(|e| {
+                    SchedError::PersistenceError(format!("Failed to sync write-ahead log: {}", e))
+                })
+            });
+            if result.is_ok() {
+                match record.op {
+                    WalOp::Complete => {
+                        open.remove(&record.job_id);
+                    }
+                    _ => {
+                        open.insert(record.job_id.clone(), record);
+                    }
+                }
+            }
+            let _ = done.send(result);
+        }
+    }
+
+    fn checkpoint(&mut self) -> SchedResult<()> {
+        self.file.set_len(0)?;
+        self.file.sync_data()?;
+        self.open.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        self.lines = 0;
+        Ok(())
+    }
+
+    /// Rewrite the log to hold only the records of open jobs.
+    fn compact(&mut self) -> SchedResult<()> {
+        let tmp = self.path.with_extension("wal.tmp");
+        let mut records: Vec<_> = self
+            .open
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        records.sort_by_key(|record| record.at);
+        {
+            let mut file = File::create(&tmp)?;
+            for record in &records {
+                writeln!(file, "{}", serde_json::to_string(record)?)?;
+            }
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.lines = records.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wal_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.wal");
+        let done = ScheduledJobId::new();
+        let in_flight = ScheduledJobId::new();
+
+        let wal = WriteAheadLog::open(&path).unwrap();
+        wal.append(&done, WalOp::Enqueue).await.unwrap();
+        wal.append(&done, WalOp::Dispatch).await.unwrap();
+        wal.append(
+            &done,
+            WalOp::SlurmIdAssigned {
+                slurm_job_id: "42".into(),
+            },
+        )
+        .await
+        .unwrap();
+        wal.append(&in_flight, WalOp::Enqueue).await.unwrap();
+        wal.append(&done, WalOp::Complete).await.unwrap();
+        wal.append(&in_flight, WalOp::Dispatch).await.unwrap();
+        drop(wal);
+
+        // A crash mid-write leaves a torn last line.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"at\":\"2026-").unwrap();
+        drop(file);
+
+        let wal = WriteAheadLog::open(&path).unwrap();
+        let open = wal.open_records();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].job_id, in_flight);
+        assert_eq!(open[0].op, WalOp::Dispatch);
+
+        // Reopening dropped the torn line, so new records stay readable.
+        wal.append(&done, WalOp::Enqueue).await.unwrap();
+        assert_eq!(WriteAheadLog::read(&path).unwrap().len(), 2);
+
+        wal.checkpoint().await.unwrap();
+        assert!(wal.open_records().is_empty());
+        assert!(WriteAheadLog::read(&path).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wal_concurrent_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.wal");
+        let wal = WriteAheadLog::open(&path).unwrap();
+        let jobs: Vec<_> = (0..50).map(|_| ScheduledJobId::new()).collect();
+
+        // Appends queued behind a sync are synced together
+        futures::future::try_join_all(jobs.iter().map(|job| wal.append(job, WalOp::Enqueue)))
+            .await
+            .unwrap();
+        futures::future::try_join_all(
+            jobs.iter()
+                .step_by(2)
+                .map(|job| wal.append(job, WalOp::Complete)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(wal.open_records().len(), 25);
+        assert_eq!(WriteAheadLog::read(&path).unwrap().len(), 75);
+        drop(wal);
+        let wal = WriteAheadLog::open(&path).unwrap();
+        assert_eq!(wal.open_records().len(), 25);
+        assert_eq!(WriteAheadLog::read(&path).unwrap().len(), 25);
+    }
+
+    #[tokio::test]
+    async fn test_wal_rejects_corruption_before_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.wal");
+        let wal = WriteAheadLog::open(&path).unwrap();
+        wal.append(&ScheduledJobId::new(), WalOp::Enqueue)
+            .await
+            .unwrap();
+        drop(wal);
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("garbage\n{}", contents)).unwrap();
+
+        let err = WriteAheadLog::open(&path).unwrap_err();
+        assert!(err.to_string().contains(":1:"));
+    }
+}
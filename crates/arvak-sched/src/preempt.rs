@@ -0,0 +1,220 @@
+//! Preemption of running jobs for urgent ones.
+//!
+//! An urgent job, at or above [`PreemptionConfig::urgent_priority`], that
+//! waits in the batch queue for resources may take them from a running job
+//! of lower priority. How a job is preempted depends on its priority class:
+//!
+//! - [`PreemptionPolicy::Never`]: the job keeps running.
+//! - [`PreemptionPolicy::Requeue`]: the batch system requeues the job
+//!   (`scontrol requeue` on SLURM), which starts it again once resources
+//!   free up.
+//! - [`PreemptionPolicy::Cancel`]: the batch job is cancelled and the
+//!   scheduler queues the job again, to be matched and dispatched afresh.
+//!
+//! ```toml
+//! [scheduler.preemption]
+//! urgent_priority = 250
+//!
+//! # Policy by the lowest priority of each class
+//! [scheduler.preemption.policies]
+//! 0 = "cancel"
+//! 100 = "requeue"
+//! 200 = "never"
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cloud::CLOUD_JOB_ID;
+use crate::job::{Priority, ScheduledJob, ScheduledJobStatus};
+use crate::task::SUB_WORKFLOW_JOB_ID;
+
+/// Pending reasons of a batch job that preempting a running job can
+/// resolve.
+pub const RESOURCE_REASONS: &[&str] = &["Resources", "Priority", "ReqNodeNotAvail"];
+
+/// How a running job is preempted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PreemptionPolicy {
+    /// The job is never preempted.
+    #[default]
+    Never,
+
+    /// The batch system requeues the job under the same batch job ID.
+    Requeue,
+
+    /// The batch job is cancelled and the job queued in the scheduler again.
+    Cancel,
+}
+
+impl std::fmt::Display for PreemptionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreemptionPolicy::Never => write!(f, "never"),
+            PreemptionPolicy::Requeue => write!(f, "requeue"),
+            PreemptionPolicy::Cancel => write!(f, "cancel"),
+        }
+    }
+}
+
+/// Configuration for preempting running jobs for urgent ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreemptionConfig {
+    /// Priority from which jobs may preempt others.
+    pub urgent_priority: u32,
+
+    /// Policy of each priority class, keyed by the lowest priority of the
+    /// class. Jobs below every class are never preempted.
+    #[serde(deserialize_with = "crate::config::priority_classes")]
+    pub policies: BTreeMap<u32, PreemptionPolicy>,
+
+    /// Seconds an urgent job waits after preempting a job before it may
+    /// preempt another.
+    pub grace_secs: u64,
+}
+
+impl Default for PreemptionConfig {
+    fn default() -> Self {
+        Self {
+            urgent_priority: Priority::URGENT.value(),
+            policies: BTreeMap::from([
+                (0, PreemptionPolicy::Requeue),
+                (Priority::CRITICAL.value(), PreemptionPolicy::Never),
+            ]),
+            grace_secs: 120,
+        }
+    }
+}
+
+impl PreemptionConfig {
+    /// Set the priority from which jobs may preempt others.
+    pub fn with_urgent_priority(mut self, priority: Priority) -> Self {
+        self.urgent_priority = priority.value();
+        self
+    }
+
+    /// Set the policy of jobs at or above `priority`, up to the next class.
+    pub fn with_policy(mut self, priority: Priority, policy: PreemptionPolicy) -> Self {
+        self.policies.insert(priority.value(), policy);
+        self
+    }
+
+    /// Set the wait between preemptions for the same urgent job.
+    pub fn with_grace(mut self, grace: std::time::Duration) -> Self {
+        self.grace_secs = grace.as_secs();
+        self
+    }
+
+    /// Check if a job of this priority may preempt others.
+    pub fn is_urgent(&self, priority: Priority) -> bool {
+        priority.value() >= self.urgent_priority
+    }
+
+    /// Policy for preempting a job of this priority.
+    pub fn policy(&self, priority: Priority) -> PreemptionPolicy {
+        self.policies
+            .range(..=priority.value())
+            .next_back()
+            .map_or(PreemptionPolicy::Never, |(_, policy)| *policy)
+    }
+
+    /// Pick the running job to preempt for `urgent`, with its policy.
+    ///
+    /// Candidates are running batch jobs of lower priority that are not
+    /// urgent themselves and whose policy allows preemption. The lowest
+    /// priority goes first, then the job that started last, which loses
+    /// the least work.
+    pub fn select_victim<'a>(
+        &self,
+        urgent: &ScheduledJob,
+        running: &'a [ScheduledJob],
+    ) -> Option<(&'a ScheduledJob, PreemptionPolicy)> {
+        running
+            .iter()
+            .filter(|job| job.id != urgent.id && is_running_batch_job(job))
+            .filter(|job| job.priority < urgent.priority && !self.is_urgent(job.priority))
+            .filter_map(|job| match self.policy(job.priority) {
+                PreemptionPolicy::Never => None,
+                policy => Some((job, policy)),
+            })
+            .min_by(|(a, _), (b, _)| {
+                a.priority
+                    .cmp(&b.priority)
+                    .then(b.submitted_at.cmp(&a.submitted_at))
+            })
+    }
+}
+
+/// Check if a job holds a running batch job, as opposed to one queued in
+/// the batch system, submitted to a cloud provider or run as a
+/// sub-workflow.
+fn is_running_batch_job(job: &ScheduledJob) -> bool {
+    match &job.status {
+        ScheduledJobStatus::SlurmRunning { slurm_job_id } => slurm_job_id != SUB_WORKFLOW_JOB_ID,
+        ScheduledJobStatus::QuantumSubmitted { slurm_job_id, .. }
+        | ScheduledJobStatus::QuantumRunning { slurm_job_id, .. } => slurm_job_id != CLOUD_JOB_ID,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    fn running(name: &str, priority: Priority, started_mins_ago: i64) -> ScheduledJob {
+        let mut job = ScheduledJob::new(name, CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .with_priority(priority);
+        job.status = ScheduledJobStatus::SlurmRunning {
+            slurm_job_id: format!("{}-1", name),
+        };
+        job.submitted_at = Some(chrono::Utc::now() - chrono::Duration::minutes(started_mins_ago));
+        job
+    }
+
+    #[test]
+    fn test_policy_by_priority_class() {
+        let config = PreemptionConfig::default()
+            .with_policy(Priority::LOW, PreemptionPolicy::Cancel)
+            .with_policy(Priority::DEFAULT, PreemptionPolicy::Requeue);
+        assert_eq!(config.policy(Priority::new(10)), PreemptionPolicy::Requeue);
+        assert_eq!(config.policy(Priority::LOW), PreemptionPolicy::Cancel);
+        assert_eq!(config.policy(Priority::HIGH), PreemptionPolicy::Requeue);
+        assert_eq!(config.policy(Priority::CRITICAL), PreemptionPolicy::Never);
+        assert!(config.is_urgent(Priority::urgent()));
+        assert!(!config.is_urgent(Priority::CRITICAL));
+
+        let none = PreemptionConfig {
+            policies: BTreeMap::from([(100, PreemptionPolicy::Cancel)]),
+            ..Default::default()
+        };
+        assert_eq!(none.policy(Priority::LOW), PreemptionPolicy::Never);
+    }
+
+    #[test]
+    fn test_select_victim() {
+        let config = PreemptionConfig::default();
+        let urgent = ScheduledJob::new("urgent", CircuitSpec::from_qasm("OPENQASM 3.0;"))
+            .with_priority(Priority::urgent());
+        let mut queued = running("queued", Priority::LOW, 0);
+        queued.status = ScheduledJobStatus::SlurmQueued {
+            slurm_job_id: "q-1".to_string(),
+        };
+        let jobs = vec![
+            running("critical", Priority::CRITICAL, 1),
+            running("old", Priority::DEFAULT, 60),
+            running("new", Priority::DEFAULT, 5),
+            queued,
+        ];
+
+        let (victim, policy) = config.select_victim(&urgent, &jobs).unwrap();
+        assert_eq!(victim.name, "new");
+        assert_eq!(policy, PreemptionPolicy::Requeue);
+
+        let normal = urgent.clone().with_priority(Priority::DEFAULT);
+        assert!(config.select_victim(&normal, &jobs).is_none());
+    }
+}
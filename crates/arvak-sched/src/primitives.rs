@@ -0,0 +1,1346 @@
+//! Sampler and Estimator primitives over the scheduler.
+//!
+//! Most programs want one of two things from a backend: the output
+//! distribution of some circuits, or expectation values of observables on
+//! the states they prepare. A [`Sampler`] turns circuits into
+//! [`QuasiDistribution`]s and an [`Estimator`] turns circuits paired with
+//! [`Observable`]s into [`EstimatorResult`]s. Each call runs as one
+//! multi-circuit job, so a call's circuits share one trip through the
+//! queue.
+//!
+//! An estimator groups the Pauli terms of each observable into qubit-wise
+//! commuting sets and measures each set with one circuit, rotated into the
+//! set's basis. With a [`PrimitiveOptions::precision`] target, a sampler
+//! runs enough shots to estimate every probability to the target. An
+//! estimator first runs a pilot round, giving each group shots in
+//! proportion to the weight of its coefficients, then tops up the groups
+//! in further jobs, in proportion to their estimated standard deviations,
+//! until the standard error meets the target or the
+//! [`PrimitiveOptions::shot_budget`] is spent.
+//!
+//! With [`Mitigation::Readout`], the first job also runs readout
+//! calibration circuits and the results are corrected by inverting a
+//! per-qubit confusion matrix; corrected probabilities may be negative.
+//!
+//! Bitstrings are read with qubit 0 rightmost. Circuits passed to an
+//! estimator must not measure; circuits passed to a sampler measure qubit
+//! `i` into clbit `i` when readout mitigation is on.
+//!
+//! ```ignore
+//! let estimator = Estimator::new(scheduler.clone())
+//!     .with_options(PrimitiveOptions::default().with_precision(0.01));
+//! let hamiltonian = Observable::from_pairs([(0.5, "ZZ"), (-0.2, "XX")]);
+//! let results = estimator.run(&[(ansatz, hamiltonian)]).await?;
+//! println!("E = {} ± {}", results[0].value, results[0].std_error);
+//! ```
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use arvak_hal::{Counts, ExecutionResult, NoiseModel};
+use arvak_ir::{Circuit, QubitId};
+use futures::StreamExt;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{CircuitSpec, Priority, ScheduledJob, ScheduledJobId};
+use crate::scheduler::{HpcScheduler, Scheduler};
+
+/// Result label of the readout calibration circuit preparing all zeros.
+const CALIBRATION_ZEROS: &str = "readout_cal_0";
+
+/// Result label of the readout calibration circuit preparing all ones.
+const CALIBRATION_ONES: &str = "readout_cal_1";
+
+/// Fewest shots a group runs in an estimator's pilot round, so its
+/// variance can be estimated.
+const MIN_PILOT_SHOTS: u32 = 32;
+
+/// Most rounds an estimator runs to reach its precision target.
+const MAX_ROUNDS: u32 = 10;
+
+/// Error mitigation applied to primitive results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mitigation {
+    /// Raw results.
+    #[default]
+    None,
+
+    /// Correct readout errors using calibration circuits run in the same
+    /// job.
+    Readout,
+}
+
+/// Options shared by [`Sampler`] and [`Estimator`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrimitiveOptions {
+    /// Shots per circuit when no precision target is set.
+    pub shots: u32,
+
+    /// Target standard error; shots are then chosen per circuit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<f64>,
+
+    /// Most shots an estimator spends on one observable over all rounds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shot_budget: Option<u64>,
+
+    /// Most shots any one circuit may run.
+    pub max_shots: u32,
+
+    /// Error mitigation to apply.
+    #[serde(default)]
+    pub mitigation: Mitigation,
+
+    /// Priority of the submitted jobs.
+    #[serde(default)]
+    pub priority: Priority,
+}
+
+impl Default for PrimitiveOptions {
+    fn default() -> Self {
+        Self {
+            shots: 1024,
+            precision: None,
+            shot_budget: None,
+            max_shots: 100_000,
+            mitigation: Mitigation::None,
+            priority: Priority::default(),
+        }
+    }
+}
+
+impl PrimitiveOptions {
+    /// Run every circuit with `shots` shots.
+    pub fn with_shots(mut self, shots: u32) -> Self {
+        self.shots = shots;
+        self
+    }
+
+    /// Choose shots to reach a standard error of at most `precision`.
+    pub fn with_precision(mut self, precision: f64) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    /// Cap the shots an estimator spends on one observable.
+    pub fn with_shot_budget(mut self, shot_budget: u64) -> Self {
+        self.shot_budget = Some(shot_budget);
+        self
+    }
+
+    /// Cap the shots of any one circuit.
+    pub fn with_max_shots(mut self, max_shots: u32) -> Self {
+        self.max_shots = max_shots;
+        self
+    }
+
+    /// Apply error mitigation.
+    pub fn with_mitigation(mut self, mitigation: Mitigation) -> Self {
+        self.mitigation = mitigation;
+        self
+    }
+
+    /// Submit jobs with the given priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    fn validate(&self) -> SchedResult<()> {
+        if self.shots == 0 || self.max_shots == 0 || self.shot_budget == Some(0) {
+            return Err(SchedError::ConfigError(
+                "primitive shots must be positive".to_string(),
+            ));
+        }
+        if let Some(precision) = self.precision.filter(|p| !(p.is_finite() && *p > 0.0)) {
+            return Err(SchedError::ConfigError(format!(
+                "primitive precision must be positive, got {}",
+                precision
+            )));
+        }
+        Ok(())
+    }
+
+    /// Shots for a circuit needing `variance / precision²` shots, capped.
+    fn shots_for(&self, variance: f64) -> u32 {
+        match self.precision {
+            Some(precision) => {
+                let shots = (variance / (precision * precision)).ceil();
+                shots.clamp(1.0, f64::from(self.max_shots)) as u32
+            }
+            None => self.shots.min(self.max_shots),
+        }
+    }
+
+    /// Pilot shots of a group holding `fraction` of the weight of the
+    /// heaviest group of its observable.
+    fn pilot_shots(&self, fraction: f64) -> u32 {
+        if self.precision.is_none() {
+            return self.shots.min(self.max_shots);
+        }
+        let shots = (f64::from(self.shots) * fraction).ceil() as u32;
+        shots
+            .max(MIN_PILOT_SHOTS.min(self.shots))
+            .min(self.max_shots)
+    }
+}
+
+/// A weighted tensor product of Paulis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PauliTerm {
+    /// Coefficient of the term.
+    pub coeff: f64,
+
+    /// One of `I`, `X`, `Y`, `Z` per qubit, qubit 0 rightmost.
+    pub paulis: String,
+}
+
+impl PauliTerm {
+    /// Create a term.
+    pub fn new(coeff: f64, paulis: impl Into<String>) -> Self {
+        Self {
+            coeff,
+            paulis: paulis.into(),
+        }
+    }
+
+    /// The Pauli acting on a qubit.
+    fn pauli(&self, qubit: usize) -> u8 {
+        let paulis = self.paulis.as_bytes();
+        paulis
+            .len()
+            .checked_sub(qubit + 1)
+            .map_or(b'I', |i| paulis[i])
+    }
+
+    /// Qubits the term acts on non-trivially.
+    fn support(&self) -> Vec<usize> {
+        (0..self.paulis.len())
+            .filter(|q| self.pauli(*q) != b'I')
+            .collect()
+    }
+}
+
+/// A Hermitian observable as a sum of Pauli terms.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Observable {
+    terms: Vec<PauliTerm>,
+}
+
+impl Observable {
+    /// Create an observable from its terms.
+    pub fn new(terms: Vec<PauliTerm>) -> Self {
+        Self { terms }
+    }
+
+    /// Create an observable from `(coefficient, paulis)` pairs.
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (f64, &'a str)>) -> Self {
+        Self::new(
+            pairs
+                .into_iter()
+                .map(|(coeff, paulis)| PauliTerm::new(coeff, paulis))
+                .collect(),
+        )
+    }
+
+    /// Add a term.
+    pub fn with_term(mut self, coeff: f64, paulis: impl Into<String>) -> Self {
+        self.terms.push(PauliTerm::new(coeff, paulis));
+        self
+    }
+
+    /// The terms of the observable.
+    pub fn terms(&self) -> &[PauliTerm] {
+        &self.terms
+    }
+
+    fn validate(&self, num_qubits: usize) -> SchedResult<()> {
+        for term in &self.terms {
+            if let Some(c) = term.paulis.chars().find(|c| !"IXYZ".contains(*c)) {
+                return Err(SchedError::InvalidPayload(format!(
+                    "invalid Pauli '{}' in term {}",
+                    c, term.paulis
+                )));
+            }
+            if term.support().iter().any(|q| *q >= num_qubits) {
+                return Err(SchedError::InvalidPayload(format!(
+                    "term {} acts on more than the circuit's {} qubits",
+                    term.paulis, num_qubits
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Split the terms into an identity constant and qubit-wise commuting
+    /// groups, each measurable in one basis.
+    fn group(&self) -> (f64, Vec<MeasurementGroup>) {
+        let mut constant = 0.0;
+        let mut groups: Vec<MeasurementGroup> = Vec::new();
+        for term in &self.terms {
+            let support = term.support();
+            if support.is_empty() {
+                constant += term.coeff;
+                continue;
+            }
+            let paulis: Vec<(usize, u8)> = support.iter().map(|q| (*q, term.pauli(*q))).collect();
+            let group = match groups.iter_mut().find(|g| g.accepts(&paulis)) {
+                Some(group) => group,
+                None => {
+                    groups.push(MeasurementGroup::default());
+                    groups.last_mut().expect("group was just pushed")
+                }
+            };
+            for (qubit, pauli) in paulis {
+                group.basis.insert(qubit, pauli);
+            }
+            group.terms.push((term.coeff, support));
+        }
+        (constant, groups)
+    }
+}
+
+/// Terms measured together in one basis.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct MeasurementGroup {
+    /// Pauli measured on each qubit in the group's support.
+    basis: FxHashMap<usize, u8>,
+
+    /// Coefficient and support of each term.
+    terms: Vec<(f64, Vec<usize>)>,
+}
+
+impl MeasurementGroup {
+    fn accepts(&self, paulis: &[(usize, u8)]) -> bool {
+        paulis
+            .iter()
+            .all(|(qubit, pauli)| self.basis.get(qubit).is_none_or(|p| p == pauli))
+    }
+
+    /// Sum of the absolute coefficients, bounding each shot's value.
+    fn weight(&self) -> f64 {
+        self.terms.iter().map(|(coeff, _)| coeff.abs()).sum()
+    }
+
+    /// Estimate the group's value from its counts, corrected for readout
+    /// errors by `calibration`.
+    fn estimate(&self, counts: &Counts, calibration: &ReadoutCalibration) -> GroupEstimate {
+        let total = counts.total_shots();
+        if total == 0 {
+            return GroupEstimate {
+                mean: 0.0,
+                variance: 0.0,
+                shots: 0,
+            };
+        }
+        let (mut sum, mut sum_sq) = (0.0, 0.0);
+        for (bitstring, count) in counts.iter() {
+            let bits = clean_bits(bitstring);
+            let value: f64 = self
+                .terms
+                .iter()
+                .map(|(coeff, support)| {
+                    coeff
+                        * support
+                            .iter()
+                            .map(|q| calibration.z_values(*q)[bit(&bits, *q)])
+                            .product::<f64>()
+                })
+                .sum();
+            let weight = *count as f64 / total as f64;
+            sum += weight * value;
+            sum_sq += weight * value * value;
+        }
+        GroupEstimate {
+            mean: sum,
+            variance: (sum_sq - sum * sum).max(0.0),
+            shots: total,
+        }
+    }
+
+    /// The circuit followed by the basis change and a measurement of every
+    /// qubit.
+    fn measurement_circuit(&self, circuit: &Circuit) -> SchedResult<Circuit> {
+        let mut measured = circuit.clone();
+        let mut qubits: Vec<_> = self.basis.iter().collect();
+        qubits.sort();
+        for (qubit, pauli) in qubits {
+            let q = QubitId(*qubit as u32);
+            match pauli {
+                b'X' => {
+                    measured.h(q).map_err(ir_error)?;
+                }
+                b'Y' => {
+                    measured.sdg(q).map_err(ir_error)?.h(q).map_err(ir_error)?;
+                }
+                _ => {}
+            }
+        }
+        measured.measure_all().map_err(ir_error)?;
+        Ok(measured)
+    }
+}
+
+fn ir_error(e: arvak_ir::IrError) -> SchedError {
+    SchedError::InvalidPayload(e.to_string())
+}
+
+/// Value of bit `qubit` of a bitstring, qubit 0 rightmost.
+fn bit(bits: &[u8], qubit: usize) -> usize {
+    bits.len()
+        .checked_sub(qubit + 1)
+        .map_or(0, |i| usize::from(bits[i] == b'1'))
+}
+
+/// A bitstring without register separators.
+fn clean_bits(bitstring: &str) -> Vec<u8> {
+    bitstring.bytes().filter(|b| *b != b' ').collect()
+}
+
+/// Per-qubit readout error rates measured by calibration circuits.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadoutCalibration {
+    /// Probability of reading 1 from each qubit prepared in 0.
+    pub flip_zero: Vec<f64>,
+
+    /// Probability of reading 0 from each qubit prepared in 1.
+    pub flip_one: Vec<f64>,
+}
+
+impl ReadoutCalibration {
+    /// Estimate the error rates of `num_qubits` qubits from the counts of
+    /// circuits preparing all zeros and all ones.
+    pub fn from_counts(zeros: &Counts, ones: &Counts, num_qubits: usize) -> Self {
+        let rate = |counts: &Counts, wrong: usize, qubit: usize| {
+            let total = counts.total_shots();
+            if total == 0 {
+                return 0.0;
+            }
+            let flipped: u64 = counts
+                .iter()
+                .filter(|(bitstring, _)| bit(&clean_bits(bitstring), qubit) == wrong)
+                .map(|(_, count)| *count)
+                .sum();
+            flipped as f64 / total as f64
+        };
+        Self {
+            flip_zero: (0..num_qubits).map(|q| rate(zeros, 1, q)).collect(),
+            flip_one: (0..num_qubits).map(|q| rate(ones, 0, q)).collect(),
+        }
+    }
+
+    /// The readout confusion of `num_qubits` qubits in a noise model, e.g.
+    /// one built from a backend's calibration, for correcting results
+    /// without running calibration circuits.
+    pub fn from_noise_model(model: &NoiseModel, num_qubits: usize) -> Self {
+        let (flip_zero, flip_one) = (0..num_qubits as u32)
+            .map(|q| {
+                model
+                    .readout_error(q)
+                    .map_or((0.0, 0.0), |r| (r.flip_zero, r.flip_one))
+            })
+            .unzip();
+        Self {
+            flip_zero,
+            flip_one,
+        }
+    }
+
+    /// Inverse confusion matrix of a qubit, indexed `[prepared][measured]`,
+    /// or `None` for a qubit without errors or one too noisy to invert.
+    fn inverse(&self, qubit: usize) -> Option<[[f64; 2]; 2]> {
+        let e0 = self.flip_zero.get(qubit).copied().unwrap_or(0.0);
+        let e1 = self.flip_one.get(qubit).copied().unwrap_or(0.0);
+        let det = 1.0 - e0 - e1;
+        if (e0 == 0.0 && e1 == 0.0) || det <= f64::EPSILON {
+            return None;
+        }
+        Some([[(1.0 - e1) / det, -e1 / det], [-e0 / det, (1.0 - e0) / det]])
+    }
+
+    /// Value of Z on a qubit given each measured outcome, corrected so its
+    /// mean over the measured distribution is the error-free expectation.
+    fn z_values(&self, qubit: usize) -> [f64; 2] {
+        match self.inverse(qubit) {
+            Some(inv) => [inv[0][0] - inv[1][0], inv[0][1] - inv[1][1]],
+            None => [1.0, -1.0],
+        }
+    }
+
+    /// Correct measured counts into a quasi-distribution.
+    ///
+    /// The correction is applied one qubit at a time, so the result may
+    /// hold up to `2^n` bitstrings for `n` noisy qubits.
+    pub fn apply(&self, counts: &Counts) -> QuasiDistribution {
+        let mut dist = QuasiDistribution::from_counts(counts);
+        let width = dist
+            .probabilities
+            .keys()
+            .map(String::len)
+            .max()
+            .unwrap_or(0);
+        for qubit in 0..width.min(self.flip_zero.len()) {
+            let Some(inv) = self.inverse(qubit) else {
+                continue;
+            };
+            let mut corrected: FxHashMap<String, f64> = FxHashMap::default();
+            for (bitstring, p) in dist.probabilities {
+                let mut bits = bitstring.into_bytes();
+                let Some(i) = bits.len().checked_sub(qubit + 1) else {
+                    continue;
+                };
+                let measured = usize::from(bits[i] == b'1');
+                for (prepared, row) in inv.iter().enumerate() {
+                    bits[i] = if prepared == 1 { b'1' } else { b'0' };
+                    let key = String::from_utf8(bits.clone()).expect("bitstrings are ASCII");
+                    *corrected.entry(key).or_default() += row[measured] * p;
+                }
+            }
+            corrected.retain(|_, p| *p != 0.0);
+            dist.probabilities = corrected;
+        }
+        dist
+    }
+}
+
+/// Probabilities of bitstrings, possibly negative after error mitigation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuasiDistribution {
+    probabilities: FxHashMap<String, f64>,
+}
+
+impl QuasiDistribution {
+    /// The relative frequencies of counts, without register separators.
+    pub fn from_counts(counts: &Counts) -> Self {
+        let total = counts.total_shots() as f64;
+        let mut probabilities: FxHashMap<String, f64> = FxHashMap::default();
+        if total > 0.0 {
+            for (bitstring, count) in counts.iter() {
+                let bits = String::from_utf8(clean_bits(bitstring)).expect("bitstrings are ASCII");
+                *probabilities.entry(bits).or_default() += *count as f64 / total;
+            }
+        }
+        Self { probabilities }
+    }
+
+    /// Quasi-probability of a bitstring.
+    pub fn get(&self, bitstring: &str) -> f64 {
+        self.probabilities.get(bitstring).copied().unwrap_or(0.0)
+    }
+
+    /// Iterate over bitstrings and their quasi-probabilities.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &f64)> {
+        self.probabilities.iter()
+    }
+
+    /// Number of bitstrings.
+    pub fn len(&self) -> usize {
+        self.probabilities.len()
+    }
+
+    /// Check if the distribution is empty.
+    pub fn is_empty(&self) -> bool {
+        self.probabilities.is_empty()
+    }
+}
+
+/// The output distribution of one sampled circuit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplerResult {
+    /// Index of the circuit in the call.
+    pub index: usize,
+
+    /// Quasi-distribution of the circuit's outcomes.
+    pub quasi_dist: QuasiDistribution,
+
+    /// Shots the circuit ran.
+    pub shots: u32,
+}
+
+/// The expectation value of one observable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EstimatorResult {
+    /// Index of the circuit and observable in the call.
+    pub index: usize,
+
+    /// Estimated expectation value.
+    pub value: f64,
+
+    /// Standard error of the estimate.
+    pub std_error: f64,
+
+    /// Shots run over all measurement circuits of the observable.
+    pub shots: u64,
+}
+
+/// A submitted sampler call, to collect once its job finishes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SamplerJob {
+    job_id: ScheduledJobId,
+    labels: Vec<String>,
+    calibration: Option<usize>,
+}
+
+impl SamplerJob {
+    /// The job running the circuits.
+    pub fn job_id(&self) -> &ScheduledJobId {
+        &self.job_id
+    }
+}
+
+/// One circuit and observable of a submitted estimator call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EstimatorPlan {
+    constant: f64,
+    groups: Vec<PlannedGroup>,
+}
+
+impl EstimatorPlan {
+    /// Choose top-up shots for each group to bring the standard error to
+    /// `precision`, within the budget. Returns false if none are needed or
+    /// the budget is spent.
+    fn top_up(
+        &mut self,
+        calibration: &ReadoutCalibration,
+        precision: f64,
+        options: &PrimitiveOptions,
+    ) -> bool {
+        let stats: Vec<GroupEstimate> = self
+            .groups
+            .iter()
+            .map(|g| g.group.estimate(&g.counts, calibration))
+            .collect();
+        let variance: f64 = stats.iter().map(GroupEstimate::variance_of_mean).sum();
+        if variance.sqrt() <= precision {
+            return false;
+        }
+
+        // Neyman allocation: the fewest total shots reaching the target
+        // give each group shots in proportion to its standard deviation.
+        let total_sd: f64 = stats.iter().map(|e| e.variance.sqrt()).sum();
+        let mut wanted: Vec<u64> = stats
+            .iter()
+            .map(|e| {
+                let needed = (e.variance.sqrt() * total_sd / (precision * precision)).ceil();
+                (needed as u64).saturating_sub(e.shots)
+            })
+            .collect();
+        let requested: u64 = wanted.iter().sum();
+        if let Some(budget) = options.shot_budget {
+            let spent: u64 = stats.iter().map(|e| e.shots).sum();
+            let remaining = budget.saturating_sub(spent);
+            if requested > remaining {
+                for shots in &mut wanted {
+                    *shots = *shots * remaining / requested;
+                }
+            }
+        }
+
+        for (group, shots) in self.groups.iter_mut().zip(wanted) {
+            group.pending = shots.min(u64::from(options.max_shots)) as u32;
+        }
+        self.groups.iter().any(|g| g.pending > 0)
+    }
+}
+
+/// A measurement group of a submitted estimator call and its counts so far.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PlannedGroup {
+    /// Result label of the group's measurement circuit.
+    label: String,
+
+    group: MeasurementGroup,
+
+    /// Measurement circuit, without label or shots.
+    circuit: CircuitSpec,
+
+    /// Shots the group runs in the pending job.
+    pending: u32,
+
+    /// Counts of the finished rounds.
+    counts: Counts,
+}
+
+/// A submitted estimator call, to collect once its job finishes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EstimatorJob {
+    job_id: Option<ScheduledJobId>,
+    plans: Vec<EstimatorPlan>,
+    calibration: Option<usize>,
+}
+
+impl EstimatorJob {
+    /// The job running the measurement circuits, if any observable had a
+    /// term to measure.
+    pub fn job_id(&self) -> Option<&ScheduledJobId> {
+        self.job_id.as_ref()
+    }
+}
+
+/// Samples output distributions of circuits.
+pub struct Sampler {
+    scheduler: Arc<HpcScheduler>,
+    options: PrimitiveOptions,
+}
+
+impl Sampler {
+    /// Create a sampler with default options.
+    pub fn new(scheduler: Arc<HpcScheduler>) -> Self {
+        Self {
+            scheduler,
+            options: PrimitiveOptions::default(),
+        }
+    }
+
+    /// Set the options of later calls.
+    pub fn with_options(mut self, options: PrimitiveOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Submit the circuits as one job.
+    ///
+    /// With a precision target, each circuit runs `1 / (4 precision²)`
+    /// shots, enough for the standard error of any probability.
+    pub async fn submit(&self, circuits: &[Circuit]) -> SchedResult<SamplerJob> {
+        self.options.validate()?;
+        let shots = self.options.shots_for(0.25);
+        let mut specs = Vec::with_capacity(circuits.len() + 2);
+        let mut labels = Vec::with_capacity(circuits.len());
+        for (i, circuit) in circuits.iter().enumerate() {
+            let label = format!("sample_{}", i);
+            specs.push(
+                CircuitSpec::from_circuit(circuit)?
+                    .with_label(&label)
+                    .with_shots(shots),
+            );
+            labels.push(label);
+        }
+        let width = circuits.iter().map(Circuit::num_qubits).max().unwrap_or(0);
+        let calibration = self.options.mitigation == Mitigation::Readout;
+        if calibration {
+            specs.extend(calibration_circuits(width, shots)?);
+        }
+
+        let job_id = submit_job(&self.scheduler, &self.options, "sampler", specs).await?;
+        Ok(SamplerJob {
+            job_id,
+            labels,
+            calibration: calibration.then_some(width),
+        })
+    }
+
+    /// Wait for a submitted call's job and read its distributions.
+    pub async fn collect(&self, job: &SamplerJob) -> SchedResult<Vec<SamplerResult>> {
+        let results = finished_results(&self.scheduler, &job.job_id).await?;
+        sampler_results(job, results)
+    }
+
+    /// Sample the circuits and wait for their distributions.
+    pub async fn run(&self, circuits: &[Circuit]) -> SchedResult<Vec<SamplerResult>> {
+        let job = self.submit(circuits).await?;
+        self.collect(&job).await
+    }
+}
+
+/// Estimates expectation values of observables.
+pub struct Estimator {
+    scheduler: Arc<HpcScheduler>,
+    options: PrimitiveOptions,
+}
+
+impl Estimator {
+    /// Create an estimator with default options.
+    pub fn new(scheduler: Arc<HpcScheduler>) -> Self {
+        Self {
+            scheduler,
+            options: PrimitiveOptions::default(),
+        }
+    }
+
+    /// Set the options of later calls.
+    pub fn with_options(mut self, options: PrimitiveOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Submit the measurement circuits of every observable as one job.
+    ///
+    /// Without a precision target, every group runs the configured shots.
+    /// With one, this is the pilot round: the heaviest group of each
+    /// observable runs the configured shots, the others fewer in
+    /// proportion to the weights of their coefficients.
+    pub async fn submit(&self, pubs: &[(Circuit, Observable)]) -> SchedResult<EstimatorJob> {
+        self.options.validate()?;
+        let mut plans = Vec::with_capacity(pubs.len());
+        for (i, (circuit, observable)) in pubs.iter().enumerate() {
+            observable.validate(circuit.num_qubits())?;
+            let (constant, groups) = observable.group();
+            let heaviest = groups
+                .iter()
+                .map(MeasurementGroup::weight)
+                .fold(0.0, f64::max);
+            let mut pilot: Vec<u32> = groups
+                .iter()
+                .map(|g| self.options.pilot_shots(g.weight() / heaviest))
+                .collect();
+            let total: u64 = pilot.iter().map(|s| u64::from(*s)).sum();
+            if let Some(budget) = self.options.shot_budget.filter(|b| *b < total) {
+                for shots in &mut pilot {
+                    *shots = (u64::from(*shots) * budget / total).max(1) as u32;
+                }
+            }
+
+            let mut planned = Vec::with_capacity(groups.len());
+            for (j, (group, shots)) in groups.into_iter().zip(pilot).enumerate() {
+                planned.push(PlannedGroup {
+                    label: format!("pub_{}_group_{}", i, j),
+                    circuit: CircuitSpec::from_circuit(&group.measurement_circuit(circuit)?)?,
+                    group,
+                    pending: shots,
+                    counts: Counts::new(),
+                });
+            }
+            plans.push(EstimatorPlan {
+                constant,
+                groups: planned,
+            });
+        }
+
+        let width = pubs.iter().map(|(c, _)| c.num_qubits()).max().unwrap_or(0);
+        let mut job = EstimatorJob {
+            job_id: None,
+            plans,
+            calibration: (self.options.mitigation == Mitigation::Readout).then_some(width),
+        };
+        job.job_id = self.submit_round(&job).await?;
+        if job.job_id.is_none() {
+            job.calibration = None;
+        }
+        Ok(job)
+    }
+
+    /// Submit the pending shots of every group, and the calibration
+    /// circuits if not yet run, as one job.
+    ///
+    /// Returns `None` when there is nothing to run, e.g. for observables
+    /// of only identity terms.
+    async fn submit_round(&self, job: &EstimatorJob) -> SchedResult<Option<ScheduledJobId>> {
+        let pending: Vec<&PlannedGroup> = job
+            .plans
+            .iter()
+            .flat_map(|plan| &plan.groups)
+            .filter(|g| g.pending > 0)
+            .collect();
+        if pending.is_empty() {
+            return Ok(None);
+        }
+        let mut specs: Vec<CircuitSpec> = pending
+            .iter()
+            .map(|g| g.circuit.clone().with_label(&g.label).with_shots(g.pending))
+            .collect();
+        if let Some(width) = job.calibration {
+            let shots = pending.iter().map(|g| g.pending).max().unwrap_or_default();
+            specs.extend(calibration_circuits(width, shots)?);
+        }
+        submit_job(&self.scheduler, &self.options, "estimator", specs)
+            .await
+            .map(Some)
+    }
+
+    /// Wait for a submitted call's job and compute its expectation values.
+    ///
+    /// With a precision target, further jobs top up the shots of the
+    /// observables whose standard error misses the target, until it is met,
+    /// the shot budget is spent or [`MAX_ROUNDS`] rounds have run.
+    pub async fn collect(&self, job: &EstimatorJob) -> SchedResult<Vec<EstimatorResult>> {
+        let mut job = job.clone();
+        let mut calibration = ReadoutCalibration::default();
+        let mut round = 1;
+        while let Some(job_id) = job.job_id.take() {
+            let mut results = finished_results(&self.scheduler, &job_id).await?;
+            if let Some(measured) = take_calibration(&mut results, job.calibration.take())? {
+                calibration = measured;
+            }
+            for group in job.plans.iter_mut().flat_map(|plan| &mut plan.groups) {
+                if std::mem::take(&mut group.pending) > 0 {
+                    let counts = take_result(&mut results, &group.label)?.counts;
+                    for (bitstring, count) in counts.iter() {
+                        group.counts.insert(bitstring.clone(), *count);
+                    }
+                }
+            }
+
+            let Some(precision) = self.options.precision else {
+                break;
+            };
+            if round == MAX_ROUNDS {
+                break;
+            }
+            let mut topped_up = false;
+            for plan in &mut job.plans {
+                topped_up |= plan.top_up(&calibration, precision, &self.options);
+            }
+            if topped_up {
+                job.job_id = self.submit_round(&job).await?;
+                round += 1;
+            }
+        }
+        Ok(estimator_results(&job, &calibration))
+    }
+
+    /// Estimate the observables and wait for their values.
+    pub async fn run(&self, pubs: &[(Circuit, Observable)]) -> SchedResult<Vec<EstimatorResult>> {
+        let job = self.submit(pubs).await?;
+        self.collect(&job).await
+    }
+}
+
+/// Circuits preparing all zeros and all ones on `width` qubits.
+fn calibration_circuits(width: usize, shots: u32) -> SchedResult<Vec<CircuitSpec>> {
+    let mut zeros = Circuit::with_size(CALIBRATION_ZEROS, width as u32, width as u32);
+    zeros.measure_all().map_err(ir_error)?;
+    let mut ones = Circuit::with_size(CALIBRATION_ONES, width as u32, width as u32);
+    for qubit in 0..width {
+        ones.x(QubitId(qubit as u32)).map_err(ir_error)?;
+    }
+    ones.measure_all().map_err(ir_error)?;
+    Ok(vec![
+        CircuitSpec::from_circuit(&zeros)?
+            .with_label(CALIBRATION_ZEROS)
+            .with_shots(shots),
+        CircuitSpec::from_circuit(&ones)?
+            .with_label(CALIBRATION_ONES)
+            .with_shots(shots),
+    ])
+}
+
+async fn submit_job(
+    scheduler: &HpcScheduler,
+    options: &PrimitiveOptions,
+    name: &str,
+    specs: Vec<CircuitSpec>,
+) -> SchedResult<ScheduledJobId> {
+    let job = ScheduledJob::batch(name, specs)
+        .with_shots(options.shots)
+        .with_priority(options.priority);
+    scheduler.submit(job).await
+}
+
+/// Wait for a job to finish and read its circuit results by label.
+async fn finished_results(
+    scheduler: &HpcScheduler,
+    job_id: &ScheduledJobId,
+) -> SchedResult<FxHashMap<String, ExecutionResult>> {
+    let max_wait = Duration::from_secs(scheduler.config().max_wait_time_secs);
+    let mut updates = std::pin::pin!(scheduler.subscribe(job_id).await?);
+    let status = tokio::time::timeout(max_wait, async {
+        let mut last = None;
+        while let Some(status) = updates.next().await {
+            last = Some(status);
+        }
+        last
+    })
+    .await
+    .map_err(|_| SchedError::Timeout(format!("Timeout waiting for job {}", job_id)))?;
+
+    match status {
+        Some(status) if status.is_success() => {}
+        status => {
+            return Err(SchedError::JobNotFound(format!(
+                "Job {} failed or was cancelled: {:?}",
+                job_id, status
+            )));
+        }
+    }
+    Ok(scheduler
+        .circuit_results(job_id)
+        .await?
+        .into_iter()
+        .map(|r| (r.label, r.result))
+        .collect())
+}
+
+fn take_result(
+    results: &mut FxHashMap<String, ExecutionResult>,
+    label: &str,
+) -> SchedResult<ExecutionResult> {
+    results
+        .remove(label)
+        .ok_or_else(|| SchedError::Internal(format!("No result for circuit {}", label)))
+}
+
+fn take_calibration(
+    results: &mut FxHashMap<String, ExecutionResult>,
+    width: Option<usize>,
+) -> SchedResult<Option<ReadoutCalibration>> {
+    let Some(width) = width else {
+        return Ok(None);
+    };
+    let zeros = take_result(results, CALIBRATION_ZEROS)?;
+    let ones = take_result(results, CALIBRATION_ONES)?;
+    Ok(Some(ReadoutCalibration::from_counts(
+        &zeros.counts,
+        &ones.counts,
+        width,
+    )))
+}
+
+fn sampler_results(
+    job: &SamplerJob,
+    mut results: FxHashMap<String, ExecutionResult>,
+) -> SchedResult<Vec<SamplerResult>> {
+    let calibration = take_calibration(&mut results, job.calibration)?;
+    job.labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            let result = take_result(&mut results, label)?;
+            let quasi_dist = match &calibration {
+                Some(calibration) => calibration.apply(&result.counts),
+                None => QuasiDistribution::from_counts(&result.counts),
+            };
+            Ok(SamplerResult {
+                index,
+                quasi_dist,
+                shots: result.shots,
+            })
+        })
+        .collect()
+}
+
+fn estimator_results(job: &EstimatorJob, calibration: &ReadoutCalibration) -> Vec<EstimatorResult> {
+    job.plans
+        .iter()
+        .enumerate()
+        .map(|(index, plan)| {
+            let mut value = plan.constant;
+            let mut variance = 0.0;
+            let mut shots = 0;
+            for planned in &plan.groups {
+                let estimate = planned.group.estimate(&planned.counts, calibration);
+                value += estimate.mean;
+                variance += estimate.variance_of_mean();
+                shots += estimate.shots;
+            }
+            EstimatorResult {
+                index,
+                value,
+                std_error: variance.sqrt(),
+                shots,
+            }
+        })
+        .collect()
+}
+
+/// Statistics of one measurement group's per-shot values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GroupEstimate {
+    mean: f64,
+
+    /// Variance of a single shot's value.
+    variance: f64,
+
+    shots: u64,
+}
+
+impl GroupEstimate {
+    fn variance_of_mean(&self) -> f64 {
+        if self.shots == 0 {
+            0.0
+        } else {
+            self.variance / self.shots as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::BatchSystem;
+    use crate::job::{JobFilter, ScheduledJobStatus};
+    use crate::payload::CircuitResult;
+    use crate::persistence::{SqliteStore, StateStore};
+    use crate::scheduler::SchedulerConfig;
+    use arvak_hal::{Backend, Capabilities};
+    use async_trait::async_trait;
+
+    #[test]
+    fn test_observable_grouping() {
+        let observable = Observable::from_pairs([
+            (1.0, "ZZ"),
+            (0.5, "IZ"),
+            (0.25, "XX"),
+            (2.0, "II"),
+            (-0.5, "XI"),
+        ]);
+        let (constant, groups) = observable.group();
+        assert_eq!(constant, 2.0);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].terms, vec![(1.0, vec![0, 1]), (0.5, vec![0])]);
+        assert_eq!(groups[1].terms, vec![(0.25, vec![0, 1]), (-0.5, vec![1])]);
+        assert_eq!(groups[1].weight(), 0.75);
+
+        assert!(observable.validate(2).is_ok());
+        assert!(observable.validate(1).is_err());
+        assert!(Observable::from_pairs([(1.0, "ZA")]).validate(2).is_err());
+    }
+
+    #[test]
+    fn test_precision_shot_selection() {
+        let options = PrimitiveOptions::default().with_precision(0.1);
+        // Sampler: 1 / (4 · 0.01)
+        assert_eq!(options.shots_for(0.25), 25);
+        assert_eq!(options.clone().with_max_shots(20).shots_for(0.25), 20);
+        assert_eq!(PrimitiveOptions::default().shots_for(0.25), 1024);
+
+        // Estimator pilots: in proportion to the heaviest group, at least
+        // enough shots to estimate a variance.
+        let options = options.with_shots(1000);
+        assert_eq!(options.pilot_shots(1.0), 1000);
+        assert_eq!(options.pilot_shots(0.25), 250);
+        assert_eq!(options.pilot_shots(0.001), MIN_PILOT_SHOTS);
+        assert_eq!(PrimitiveOptions::default().pilot_shots(0.25), 1024);
+
+        assert!(
+            PrimitiveOptions::default()
+                .with_precision(0.0)
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_top_up_allocation() {
+        // ZZ + ZI on a Bell state: each shot's value is 2 or 0.
+        let observable = Observable::from_pairs([(1.0, "ZZ"), (1.0, "ZI")]);
+        let (_, mut groups) = observable.group();
+        let plan = EstimatorPlan {
+            constant: 0.0,
+            groups: vec![PlannedGroup {
+                label: "pub_0_group_0".to_string(),
+                group: groups.remove(0),
+                circuit: CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"),
+                pending: 0,
+                counts: Counts::from_pairs([("00", 50u64), ("11", 50)]),
+            }],
+        };
+        let calibration = ReadoutCalibration::default();
+        let top_up = |options: PrimitiveOptions| {
+            let mut plan = plan.clone();
+            let precision = options.precision.unwrap();
+            plan.top_up(&calibration, precision, &options)
+                .then(|| plan.groups[0].pending)
+        };
+
+        // A standard deviation of 1 needs 1 / 0.05² = 400 shots in all.
+        let options = PrimitiveOptions::default().with_precision(0.05);
+        assert_eq!(top_up(options.clone()), Some(300));
+        assert_eq!(top_up(options.clone().with_shot_budget(250)), Some(150));
+        assert_eq!(top_up(options.clone().with_shot_budget(100)), None);
+        assert_eq!(top_up(options.with_max_shots(200)), Some(200));
+        assert_eq!(
+            top_up(PrimitiveOptions::default().with_precision(0.1)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_readout_mitigation() {
+        // Qubit 0 reads 1 for 10% of zeros and 0 for 20% of ones.
+        let zeros = Counts::from_pairs([("00", 90u64), ("01", 10)]);
+        let ones = Counts::from_pairs([("11", 80u64), ("10", 20)]);
+        let calibration = ReadoutCalibration::from_counts(&zeros, &ones, 2);
+        assert_eq!(calibration.flip_zero, vec![0.1, 0.0]);
+        assert_eq!(calibration.flip_one, vec![0.2, 0.0]);
+        let model = NoiseModel::new().with_readout_error(0, 0.1, 0.2);
+        assert_eq!(ReadoutCalibration::from_noise_model(&model, 2), calibration);
+
+        let dist = calibration.apply(&zeros);
+        assert!((dist.get("00") - 1.0).abs() < 1e-9);
+        assert!(dist.get("01").abs() < 1e-9);
+
+        let group = MeasurementGroup {
+            basis: [(0, b'Z')].into_iter().collect(),
+            terms: vec![(1.0, vec![0])],
+        };
+        let raw = group.estimate(&zeros, &ReadoutCalibration::default());
+        assert!((raw.mean - 0.8).abs() < 1e-9);
+        let mitigated = group.estimate(&zeros, &calibration);
+        assert!((mitigated.mean - 1.0).abs() < 1e-9);
+        assert_eq!(mitigated.shots, 100);
+    }
+
+    struct MockBackend;
+
+    #[async_trait]
+    impl Backend for MockBackend {
+        fn name(&self) -> &str {
+            "qpu"
+        }
+
+        async fn capabilities(&self) -> arvak_hal::HalResult<Capabilities> {
+            Ok(Capabilities::simulator(4))
+        }
+
+        async fn is_available(&self) -> arvak_hal::HalResult<bool> {
+            Ok(true)
+        }
+
+        async fn submit(
+            &self,
+            _circuit: &Circuit,
+            _shots: u32,
+        ) -> arvak_hal::HalResult<arvak_hal::JobId> {
+            Ok(arvak_hal::JobId("mock".to_string()))
+        }
+
+        async fn status(
+            &self,
+            _job_id: &arvak_hal::JobId,
+        ) -> arvak_hal::HalResult<arvak_hal::JobStatus> {
+            Ok(arvak_hal::JobStatus::Completed)
+        }
+
+        async fn result(
+            &self,
+            _job_id: &arvak_hal::JobId,
+        ) -> arvak_hal::HalResult<ExecutionResult> {
+            Err(arvak_hal::HalError::JobNotFound("not used".to_string()))
+        }
+
+        async fn cancel(&self, _job_id: &arvak_hal::JobId) -> arvak_hal::HalResult<()> {
+            Ok(())
+        }
+
+        async fn wait(&self, job_id: &arvak_hal::JobId) -> arvak_hal::HalResult<ExecutionResult> {
+            self.result(job_id).await
+        }
+    }
+
+    /// Completes jobs at once with a Bell state's counts for every circuit
+    /// but the error-free readout calibrations.
+    struct BellBatch;
+
+    #[async_trait]
+    impl BatchSystem for BellBatch {
+        fn name(&self) -> &str {
+            "bell"
+        }
+
+        async fn submit(&self, _job: &ScheduledJob) -> SchedResult<String> {
+            Ok("bell-1".to_string())
+        }
+
+        async fn poll(
+            &self,
+            _job: &ScheduledJob,
+            batch_job_id: &str,
+        ) -> SchedResult<ScheduledJobStatus> {
+            Ok(ScheduledJobStatus::Completed {
+                slurm_job_id: batch_job_id.to_string(),
+                quantum_job_id: arvak_hal::JobId("q-1".to_string()),
+            })
+        }
+
+        async fn cancel(&self, _batch_job_id: &str) -> SchedResult<()> {
+            Ok(())
+        }
+
+        async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+            Ok((0..job.circuits.len())
+                .map(|index| {
+                    let label = job.circuit_label(index);
+                    let shots = job.circuit_shots(index);
+                    let counts = if label == CALIBRATION_ZEROS {
+                        Counts::from_pairs([("00", u64::from(shots))])
+                    } else if label == CALIBRATION_ONES {
+                        Counts::from_pairs([("11", u64::from(shots))])
+                    } else {
+                        let half = u64::from(shots / 2);
+                        Counts::from_pairs([("00", half), ("11", u64::from(shots) - half)])
+                    };
+                    CircuitResult {
+                        index,
+                        label,
+                        result: ExecutionResult::new(counts, shots),
+                    }
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_estimator_over_scheduler() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = Arc::new(HpcScheduler::with_batch_system(
+            SchedulerConfig::default(),
+            Arc::new(BellBatch),
+            vec![Arc::new(MockBackend)],
+            store.clone(),
+        ));
+        let processor = scheduler.clone().start_background_processor();
+
+        let mut bell = Circuit::with_size("bell", 2, 0);
+        bell.h(QubitId(0))
+            .unwrap()
+            .cx(QubitId(0), QubitId(1))
+            .unwrap();
+        let observable =
+            Observable::from_pairs([(1.0, "ZZ"), (1.0, "ZI"), (0.5, "XX"), (-0.25, "II")]);
+
+        let estimator = Estimator::new(scheduler.clone()).with_options(
+            PrimitiveOptions::default()
+                .with_shots(100)
+                .with_precision(0.04)
+                .with_mitigation(Mitigation::Readout),
+        );
+        let job = estimator
+            .submit(&[(bell.clone(), observable.clone())])
+            .await
+            .unwrap();
+
+        // The pilot job carries both groups and the calibration circuits.
+        let pilot = store
+            .load_job(job.job_id().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        let labels: Vec<_> = (0..pilot.circuits.len())
+            .map(|i| (pilot.circuit_label(i), pilot.circuit_shots(i)))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                ("pub_0_group_0".to_string(), 100),
+                ("pub_0_group_1".to_string(), MIN_PILOT_SHOTS),
+                (CALIBRATION_ZEROS.to_string(), 100),
+                (CALIBRATION_ONES.to_string(), 100),
+            ]
+        );
+
+        // ZZ + ZI has a standard deviation of 1 per shot, so its group is
+        // topped up to about 1 / 0.04² shots; XX has none.
+        let results = estimator.collect(&job).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert!((results[0].value - 1.25).abs() < 0.01);
+        assert!(results[0].std_error <= 0.04);
+        assert!((625..=640).contains(&(results[0].shots - u64::from(MIN_PILOT_SHOTS))));
+        let jobs = store.list_jobs(&JobFilter::default()).await.unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        let sampler = Sampler::new(scheduler.clone())
+            .with_options(PrimitiveOptions::default().with_shots(100));
+        let mut measured = bell;
+        measured.measure_all().unwrap();
+        let results = sampler.run(&[measured]).await.unwrap();
+        assert_eq!(results[0].shots, 100);
+        assert_eq!(results[0].quasi_dist.get("00"), 0.5);
+        assert_eq!(results[0].quasi_dist.get("11"), 0.5);
+
+        processor.abort();
+    }
+}
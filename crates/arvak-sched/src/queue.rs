@@ -1,60 +1,170 @@
 //! Priority queue for job scheduling.
+//!
+//! Jobs are ordered by a `(priority, sequence)` key held in a `BTreeMap`, so
+//! push, pop, remove and priority updates are all O(log n), and jobs with the
+//! same priority keep strict FIFO order.
+//!
+//! Priorities can be reweighted in place with [`PriorityQueue::reweight`],
+//! which the scheduler uses to apply a
+//! [`FairSharePolicy`](crate::FairSharePolicy).
+//!
+//! The queue's [`QueuePolicy`] decides what happens to jobs behind a ready
+//! job that cannot be placed on a backend: they are dispatched regardless,
+//! held, or backfilled only where they cannot delay it. A [`DispatchGate`]
+//! applies the policy during one dispatch pass. Under
+//! [`QueuePolicy::Deadline`] the key is prefixed with the job's deadline, so
+//! jobs are ordered earliest deadline first, and by priority among jobs with
+//! the same or no deadline. With a deadline horizon, see
+//! [`PriorityQueue::with_deadline_horizon`], jobs without a deadline are
+//! given one derived from their priority, so deadline and priority jobs
+//! interleave instead of priority jobs waiting behind every deadline.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::job::{DependencyState, Priority, ScheduledJob, ScheduledJobId};
+
+/// How jobs behind a ready job that cannot be placed are dispatched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueuePolicy {
+    /// Every ready job is dispatched as soon as it can be placed.
+    #[default]
+    Priority,
+
+    /// Nothing is dispatched past a ready job that cannot be placed.
+    Strict,
+
+    /// Jobs behind a job that cannot be placed are dispatched only if they
+    /// cannot delay its estimated start: they run on a backend it does not
+    /// wait for, or finish before it is expected to start.
+    Backfill,
+
+    /// Jobs are ordered earliest deadline first, then by priority; jobs
+    /// without a deadline come after those with one, unless the queue
+    /// derives deadlines from priorities. Every ready job is dispatched as
+    /// soon as it can be placed.
+    #[serde(alias = "edf")]
+    Deadline,
+}
 
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-
-use crate::job::{Priority, ScheduledJob, ScheduledJobId};
-
-/// Entry in the priority queue.
-#[derive(Debug)]
-struct QueueEntry {
-    /// Job ID.
-    job_id: ScheduledJobId,
+/// The first ready job of a dispatch pass that could not be placed.
+#[derive(Debug, Clone)]
+struct BlockedHead {
+    /// Backends the job waits for.
+    backends: Vec<String>,
 
-    /// Job priority.
-    priority: Priority,
+    /// When the job is expected to be placeable, if known.
+    estimated_start: Option<DateTime<Utc>>,
+}
 
-    /// Insertion order (for FIFO ordering of same-priority jobs).
-    insertion_order: u64,
+/// Applies a [`QueuePolicy`] to the jobs of one dispatch pass, taken in
+/// priority order.
+#[derive(Debug, Clone)]
+pub struct DispatchGate {
+    policy: QueuePolicy,
+    now: DateTime<Utc>,
+    head: Option<BlockedHead>,
 }
 
-impl PartialEq for QueueEntry {
-    fn eq(&self, other: &Self) -> bool {
-        self.job_id == other.job_id
+impl DispatchGate {
+    /// Create a gate for a pass starting at `now`.
+    pub fn new(policy: QueuePolicy, now: DateTime<Utc>) -> Self {
+        Self {
+            policy,
+            now,
+            head: None,
+        }
     }
-}
 
-impl Eq for QueueEntry {}
+    /// Check if a blocked job would become the head jobs must not pass.
+    pub fn needs_head(&self) -> bool {
+        !matches!(self.policy, QueuePolicy::Priority | QueuePolicy::Deadline) && self.head.is_none()
+    }
 
-impl PartialOrd for QueueEntry {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Record a ready job that could not be placed, waiting for `backends`
+    /// until `estimated_start`. Only the first one counts.
+    pub fn block(&mut self, backends: Vec<String>, estimated_start: Option<DateTime<Utc>>) {
+        if self.head.is_none() {
+            self.head = Some(BlockedHead {
+                backends,
+                estimated_start,
+            });
+        }
     }
-}
 
-impl Ord for QueueEntry {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Higher priority first
-        match self.priority.cmp(&other.priority) {
-            Ordering::Equal => {
-                // For same priority, earlier insertion first (FIFO)
-                // Note: BinaryHeap is a max-heap, so we reverse the comparison
-                other.insertion_order.cmp(&self.insertion_order)
+    /// Check if a placed job may be dispatched past the blocked head.
+    pub fn admits(&self, job: &ScheduledJob) -> bool {
+        let Some(head) = &self.head else {
+            return true;
+        };
+        match self.policy {
+            QueuePolicy::Priority | QueuePolicy::Deadline => true,
+            QueuePolicy::Strict => false,
+            QueuePolicy::Backfill => {
+                let competes = job
+                    .matched_backend
+                    .as_ref()
+                    .is_none_or(|backend| head.backends.contains(backend));
+                let runtime =
+                    chrono::Duration::milliseconds((job.expected_runtime_secs() * 1_000.0) as i64);
+                !competes
+                    || head
+                        .estimated_start
+                        .is_some_and(|start| self.now + runtime <= start)
             }
-            other_cmp => other_cmp,
         }
     }
 }
 
+/// Deadline part of a queue key.
+///
+/// Sorts deadlines earliest first, then jobs without one. Every job has
+/// [`DeadlineKey::None`] unless the queue orders by deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum DeadlineKey {
+    At(DateTime<Utc>),
+    None,
+}
+
+/// Ordering key in the priority queue.
+///
+/// Sorts earlier deadlines first under [`QueuePolicy::Deadline`], then
+/// higher priorities first, then earlier insertions first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct QueueKey {
+    /// Job deadline, if the queue orders by deadline.
+    deadline: DeadlineKey,
+
+    /// Job priority (reversed so the highest sorts first).
+    priority: Reverse<Priority>,
+
+    /// Insertion sequence number (for FIFO ordering of same-priority jobs).
+    seq: u64,
+}
+
+/// A queued job together with its current ordering key.
+#[derive(Debug)]
+struct QueueEntry {
+    key: QueueKey,
+    job: ScheduledJob,
+}
+
 /// A priority queue for scheduled jobs.
 ///
-/// Jobs with higher priority are dequeued first. Among jobs with the same
-/// priority, jobs are dequeued in FIFO order.
+/// Jobs with higher priority are dequeued first, unless the queue orders
+/// by deadline. Among jobs with the same priority, jobs are dequeued in FIFO
+/// order.
 #[derive(Debug)]
 pub struct PriorityQueue {
-    heap: BinaryHeap<QueueEntry>,
-    jobs: rustc_hash::FxHashMap<ScheduledJobId, ScheduledJob>,
-    insertion_counter: u64,
+    order: BTreeMap<QueueKey, ScheduledJobId>,
+    jobs: rustc_hash::FxHashMap<ScheduledJobId, QueueEntry>,
+    next_seq: u64,
+    policy: QueuePolicy,
+    deadline_horizon: Option<chrono::Duration>,
 }
 
 impl Default for PriorityQueue {
@@ -67,76 +177,120 @@ impl PriorityQueue {
     /// Create a new empty priority queue.
     pub fn new() -> Self {
         Self {
-            heap: BinaryHeap::new(),
+            order: BTreeMap::new(),
             jobs: rustc_hash::FxHashMap::default(),
-            insertion_counter: 0,
+            next_seq: 0,
+            policy: QueuePolicy::default(),
+            deadline_horizon: None,
         }
     }
 
     /// Create a priority queue with the given capacity.
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            heap: BinaryHeap::with_capacity(capacity),
+            order: BTreeMap::new(),
             jobs: rustc_hash::FxHashMap::with_capacity_and_hasher(
                 capacity,
                 rustc_hash::FxBuildHasher,
             ),
-            insertion_counter: 0,
+            next_seq: 0,
+            policy: QueuePolicy::default(),
+            deadline_horizon: None,
         }
     }
 
+    /// Set the dispatch policy, reordering queued jobs if it changes
+    /// whether deadlines come first.
+    pub fn with_policy(mut self, policy: QueuePolicy) -> Self {
+        self.policy = policy;
+        self.rekey_deadlines();
+        self
+    }
+
+    /// Under [`QueuePolicy::Deadline`], treat a job without a deadline as
+    /// due `horizon` after it was created at the default priority, and
+    /// proportionally sooner or later at higher or lower priorities: a
+    /// high-priority (150) job is due after two thirds of the horizon, a
+    /// low-priority (50) one after twice the horizon. Jobs of priority 0
+    /// still come after every job with a deadline.
+    pub fn with_deadline_horizon(mut self, horizon: chrono::Duration) -> Self {
+        self.deadline_horizon = Some(horizon);
+        self.rekey_deadlines();
+        self
+    }
+
+    /// Recompute the deadline part of every key, e.g. after the policy
+    /// changed.
+    fn rekey_deadlines(&mut self) {
+        let (policy, horizon) = (self.policy, self.deadline_horizon);
+        self.order.clear();
+        for (job_id, entry) in &mut self.jobs {
+            entry.key.deadline = deadline_key(policy, horizon, &entry.job, entry.key.priority.0);
+            self.order.insert(entry.key, job_id.clone());
+        }
+    }
+
+    /// Get the dispatch policy.
+    pub fn policy(&self) -> QueuePolicy {
+        self.policy
+    }
+
+    /// Create a gate applying the queue's policy to a dispatch pass.
+    pub fn dispatch_gate(&self, now: DateTime<Utc>) -> DispatchGate {
+        DispatchGate::new(self.policy, now)
+    }
+
     /// Push a job onto the queue.
+    ///
+    /// Pushing a job that is already queued replaces it and moves it to the
+    /// back of its priority class.
     pub fn push(&mut self, job: ScheduledJob) {
-        let entry = QueueEntry {
-            job_id: job.id.clone(),
-            priority: job.priority,
-            insertion_order: self.insertion_counter,
+        let key = QueueKey {
+            deadline: deadline_key(self.policy, self.deadline_horizon, &job, job.priority),
+            priority: Reverse(job.priority),
+            seq: self.next_seq,
         };
-        self.insertion_counter += 1;
-        self.jobs.insert(job.id.clone(), job);
-        self.heap.push(entry);
+        self.next_seq += 1;
+
+        if let Some(old) = self.jobs.remove(&job.id) {
+            self.order.remove(&old.key);
+        }
+        self.order.insert(key, job.id.clone());
+        self.jobs.insert(job.id.clone(), QueueEntry { key, job });
     }
 
     /// Pop the highest priority job from the queue.
     pub fn pop(&mut self) -> Option<ScheduledJob> {
-        // Skip entries for jobs that have been removed
-        while let Some(entry) = self.heap.pop() {
-            if let Some(job) = self.jobs.remove(&entry.job_id) {
-                return Some(job);
-            }
-            // Job was removed via remove(), skip this entry
-        }
-        None
+        let (_, job_id) = self.order.pop_first()?;
+        self.jobs.remove(&job_id).map(|entry| entry.job)
     }
 
     /// Peek at the highest priority job without removing it.
     pub fn peek(&self) -> Option<&ScheduledJob> {
-        // Find the first entry that still has a corresponding job
-        for entry in self.heap.iter() {
-            if let Some(job) = self.jobs.get(&entry.job_id) {
-                return Some(job);
-            }
-        }
-        None
+        let (_, job_id) = self.order.first_key_value()?;
+        self.jobs.get(job_id).map(|entry| &entry.job)
     }
 
     /// Get a job by ID.
     pub fn get(&self, job_id: &ScheduledJobId) -> Option<&ScheduledJob> {
-        self.jobs.get(job_id)
+        self.jobs.get(job_id).map(|entry| &entry.job)
     }
 
     /// Get a mutable reference to a job by ID.
+    ///
+    /// Use [`update_priority`](Self::update_priority) to change the priority;
+    /// editing `priority` through this reference does not reorder the queue.
     pub fn get_mut(&mut self, job_id: &ScheduledJobId) -> Option<&mut ScheduledJob> {
-        self.jobs.get_mut(job_id)
+        self.jobs.get_mut(job_id).map(|entry| &mut entry.job)
     }
 
     /// Remove a job from the queue by ID.
     ///
     /// Returns the removed job if found.
     pub fn remove(&mut self, job_id: &ScheduledJobId) -> Option<ScheduledJob> {
-        // We don't remove from the heap directly (O(n) operation).
-        // Instead, we just remove from the jobs map and let pop() skip invalid entries.
-        self.jobs.remove(job_id)
+        let entry = self.jobs.remove(job_id)?;
+        self.order.remove(&entry.key);
+        Some(entry.job)
     }
 
     /// Check if a job is in the queue.
@@ -156,79 +310,125 @@ impl PriorityQueue {
 
     /// Clear all jobs from the queue.
     pub fn clear(&mut self) {
-        self.heap.clear();
+        self.order.clear();
         self.jobs.clear();
     }
 
-    /// Iterate over all jobs in the queue (not in priority order).
+    /// Iterate over all jobs in the queue in dequeue order.
     pub fn iter(&self) -> impl Iterator<Item = &ScheduledJob> {
-        self.jobs.values()
+        self.order
+            .values()
+            .filter_map(|job_id| self.jobs.get(job_id).map(|entry| &entry.job))
     }
 
-    /// Get all job IDs in the queue.
+    /// Get all job IDs in the queue in dequeue order.
     pub fn job_ids(&self) -> impl Iterator<Item = &ScheduledJobId> {
-        self.jobs.keys()
+        self.order.values()
     }
 
     /// Update a job's priority.
     ///
-    /// This re-inserts the job with the new priority. The old entry in the heap
-    /// will be skipped on pop().
+    /// The job keeps its original insertion position relative to other jobs
+    /// of the new priority.
     pub fn update_priority(&mut self, job_id: &ScheduledJobId, new_priority: Priority) -> bool {
-        if let Some(job) = self.jobs.get_mut(job_id) {
-            job.priority = new_priority;
-            // Add a new entry with the updated priority
-            let entry = QueueEntry {
-                job_id: job_id.clone(),
-                priority: new_priority,
-                insertion_order: self.insertion_counter,
-            };
-            self.insertion_counter += 1;
-            self.heap.push(entry);
-            true
-        } else {
-            false
+        let Some(entry) = self.jobs.get_mut(job_id) else {
+            return false;
+        };
+
+        self.order.remove(&entry.key);
+        entry.key.deadline =
+            deadline_key(self.policy, self.deadline_horizon, &entry.job, new_priority);
+        entry.key.priority = Reverse(new_priority);
+        entry.job.priority = new_priority;
+        self.order.insert(entry.key, job_id.clone());
+        true
+    }
+
+    /// Reorder the queue by an effective priority per job, e.g. one
+    /// reweighted by fair share.
+    ///
+    /// Jobs keep their own `priority` and their insertion order among jobs
+    /// of the same effective priority. The order holds until the next
+    /// reweight; pushed jobs are ordered by their own priority.
+    pub fn reweight(&mut self, effective: impl Fn(&ScheduledJob) -> Priority) {
+        let (policy, horizon) = (self.policy, self.deadline_horizon);
+        self.order.clear();
+        for (job_id, entry) in &mut self.jobs {
+            let priority = effective(&entry.job);
+            entry.key.deadline = deadline_key(policy, horizon, &entry.job, priority);
+            entry.key.priority = Reverse(priority);
+            self.order.insert(entry.key, job_id.clone());
         }
     }
 
     /// Drain all jobs whose dependencies are satisfied.
     ///
-    /// Returns jobs in priority order.
+    /// `finished` maps finished job IDs to whether they succeeded. Returns
+    /// jobs in priority order.
     pub fn drain_ready(
         &mut self,
-        completed: &rustc_hash::FxHashSet<ScheduledJobId>,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
     ) -> Vec<ScheduledJob> {
-        let mut ready = Vec::new();
-        let mut to_remove = Vec::new();
+        self.drain_by_state(finished, DependencyState::Ready)
+    }
 
-        // Collect jobs that are ready
-        for (job_id, job) in &self.jobs {
-            if job.dependencies_satisfied(completed) {
-                to_remove.push(job_id.clone());
-            }
-        }
+    /// Drain all jobs whose dependency condition can no longer be met.
+    pub fn drain_unsatisfiable(
+        &mut self,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
+    ) -> Vec<ScheduledJob> {
+        self.drain_by_state(finished, DependencyState::Unsatisfiable)
+    }
 
-        // Remove ready jobs and collect them
-        for job_id in to_remove {
-            if let Some(job) = self.jobs.remove(&job_id) {
-                ready.push(job);
-            }
-        }
+    fn drain_by_state(
+        &mut self,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
+        state: DependencyState,
+    ) -> Vec<ScheduledJob> {
+        let ids: Vec<ScheduledJobId> = self
+            .iter()
+            .filter(|job| job.dependency_state(finished) == state)
+            .map(|job| job.id.clone())
+            .collect();
+
+        ids.iter()
+            .filter_map(|job_id| self.remove(job_id))
+            .collect()
+    }
+}
 
-        // Sort by priority (highest first), then by insertion order
-        ready.sort_by(|a, b| match b.priority.cmp(&a.priority) {
-            Ordering::Equal => a.created_at.cmp(&b.created_at),
-            other => other,
+/// Deadline part of the queue key of a job with `priority` under `policy`,
+/// deriving a deadline from the priority if the job has none and a
+/// `horizon` is set.
+fn deadline_key(
+    policy: QueuePolicy,
+    horizon: Option<chrono::Duration>,
+    job: &ScheduledJob,
+    priority: Priority,
+) -> DeadlineKey {
+    if policy != QueuePolicy::Deadline {
+        return DeadlineKey::None;
+    }
+    let implied = horizon
+        .filter(|_| priority.value() > 0)
+        .and_then(|horizon| {
+            let millis = horizon
+                .num_milliseconds()
+                .saturating_mul(i64::from(Priority::DEFAULT.value()))
+                / i64::from(priority.value());
+            job.created_at
+                .checked_add_signed(chrono::Duration::try_milliseconds(millis)?)
         });
-
-        ready
+    match job.deadline.or(implied) {
+        Some(deadline) => DeadlineKey::At(deadline),
+        None => DeadlineKey::None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::job::CircuitSpec;
+    use crate::job::{CircuitSpec, DependencyKind};
 
     fn make_job(name: &str, priority: Priority) -> ScheduledJob {
         let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
@@ -316,6 +516,68 @@ mod tests {
         assert_eq!(queue.pop().unwrap().name, "high");
     }
 
+    #[test]
+    fn test_reweight_keeps_job_priority() {
+        let mut queue = PriorityQueue::new();
+        let heavy = make_job("heavy", Priority::high());
+        let light = make_job("light", Priority::default());
+        let light_id = light.id.clone();
+        queue.push(heavy);
+        queue.push(light);
+
+        queue.reweight(|job| {
+            if job.name == "light" {
+                Priority::critical()
+            } else {
+                job.priority
+            }
+        });
+        assert_eq!(queue.peek().unwrap().name, "light");
+        assert_eq!(queue.get(&light_id).unwrap().priority, Priority::default());
+
+        queue.reweight(|job| job.priority);
+        assert_eq!(queue.pop().unwrap().name, "heavy");
+    }
+
+    #[test]
+    fn test_update_priority_keeps_age() {
+        let mut queue = PriorityQueue::new();
+
+        let old = make_job("old", Priority::low());
+        let old_id = old.id.clone();
+        queue.push(old);
+        queue.push(make_job("new", Priority::high()));
+
+        // Promoted job was inserted earlier, so it stays ahead of its new peers.
+        queue.update_priority(&old_id, Priority::high());
+        assert_eq!(queue.pop().unwrap().name, "old");
+        assert_eq!(queue.pop().unwrap().name, "new");
+    }
+
+    #[test]
+    fn test_push_existing_replaces() {
+        let mut queue = PriorityQueue::new();
+
+        let job = make_job("job", Priority::default());
+        queue.push(job.clone());
+        queue.push(job);
+
+        assert_eq!(queue.len(), 1);
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_none());
+    }
+
+    #[test]
+    fn test_iter_in_priority_order() {
+        let mut queue = PriorityQueue::new();
+        queue.push(make_job("low", Priority::low()));
+        queue.push(make_job("high", Priority::high()));
+        queue.push(make_job("default", Priority::default()));
+
+        let names: Vec<_> = queue.iter().map(|j| j.name.as_str()).collect();
+        assert_eq!(names, ["high", "default", "low"]);
+    }
+
     #[test]
     fn test_drain_ready() {
         let mut queue = PriorityQueue::new();
@@ -333,7 +595,7 @@ mod tests {
         queue.push(job3);
 
         // With no completions, only jobs without dependencies should be ready
-        let mut completed = rustc_hash::FxHashSet::default();
+        let mut completed = rustc_hash::FxHashMap::default();
         let ready = queue.drain_ready(&completed);
 
         assert_eq!(ready.len(), 2);
@@ -345,7 +607,7 @@ mod tests {
         assert!(queue.contains(&job2_id));
 
         // Mark job1 as completed
-        completed.insert(job1_id);
+        completed.insert(job1_id, true);
 
         // Now job2 should be ready
         let ready = queue.drain_ready(&completed);
@@ -353,6 +615,100 @@ mod tests {
         assert_eq!(ready[0].name, "job2");
     }
 
+    #[test]
+    fn test_dispatch_gate_policies() {
+        let now = Utc::now();
+        let job = |name: &str, backend: &str, secs: u64| {
+            let mut job =
+                make_job(name, Priority::low()).with_walltime(std::time::Duration::from_secs(secs));
+            job.matched_backend = Some(backend.to_string());
+            job
+        };
+        let short = job("short", "qpu_a", 600);
+        let long = job("long", "qpu_a", 7_200);
+        let elsewhere = job("elsewhere", "qpu_b", 7_200);
+        let blocked = |policy| {
+            let mut gate = PriorityQueue::new().with_policy(policy).dispatch_gate(now);
+            assert!(gate.admits(&long));
+            assert!(gate.needs_head());
+            gate.block(
+                vec!["qpu_a".to_string()],
+                Some(now + chrono::Duration::hours(1)),
+            );
+            gate
+        };
+
+        let gate = blocked(QueuePolicy::Backfill);
+        assert!(gate.admits(&short));
+        assert!(!gate.admits(&long));
+        assert!(gate.admits(&elsewhere));
+
+        let gate = blocked(QueuePolicy::Strict);
+        assert!(!gate.admits(&short) && !gate.admits(&elsewhere));
+
+        let mut gate = PriorityQueue::new().dispatch_gate(now);
+        assert!(!gate.needs_head());
+        gate.block(vec!["qpu_a".to_string()], None);
+        assert!(gate.admits(&long));
+    }
+
+    #[test]
+    fn test_deadline_ordering() {
+        let now = Utc::now();
+        let jobs = || {
+            [
+                make_job("critical", Priority::critical()),
+                make_job("late", Priority::low()).with_deadline(now + chrono::Duration::hours(2)),
+                make_job("soon", Priority::low()).with_deadline(now + chrono::Duration::hours(1)),
+                make_job("high", Priority::high()).with_deadline(now + chrono::Duration::hours(1)),
+            ]
+        };
+
+        let mut queue = PriorityQueue::new().with_policy(QueuePolicy::Deadline);
+        jobs().into_iter().for_each(|job| queue.push(job));
+        let names: Vec<_> = queue.iter().map(|job| job.name.as_str()).collect();
+        assert_eq!(names, ["high", "soon", "late", "critical"]);
+        assert!(!queue.dispatch_gate(now).needs_head());
+
+        // Switching policy reorders queued jobs.
+        let mut queue = PriorityQueue::new();
+        jobs().into_iter().for_each(|job| queue.push(job));
+        assert_eq!(queue.peek().unwrap().name, "critical");
+        let queue = queue.with_policy(QueuePolicy::Deadline);
+        assert_eq!(queue.peek().unwrap().name, "high");
+
+        // With a horizon, the critical job is due after half an hour.
+        let queue = queue.with_deadline_horizon(chrono::Duration::hours(1));
+        let names: Vec<_> = queue.iter().map(|job| job.name.as_str()).collect();
+        assert_eq!(names, ["critical", "high", "soon", "late"]);
+    }
+
+    #[test]
+    fn test_drain_unsatisfiable() {
+        let mut queue = PriorityQueue::new();
+
+        let upstream = make_job("upstream", Priority::default());
+        let upstream_id = upstream.id.clone();
+
+        let on_ok = make_job("on_ok", Priority::default()).depends_on(upstream_id.clone());
+        let on_fail = make_job("on_fail", Priority::default())
+            .depends_on_with(upstream_id.clone(), DependencyKind::AfterNotOk);
+        queue.push(on_ok);
+        queue.push(on_fail);
+
+        let mut finished = rustc_hash::FxHashMap::default();
+        finished.insert(upstream_id, false);
+
+        let blocked = queue.drain_unsatisfiable(&finished);
+        assert_eq!(blocked.len(), 1);
+        assert_eq!(blocked[0].name, "on_ok");
+
+        let ready = queue.drain_ready(&finished);
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].name, "on_fail");
+        assert!(queue.is_empty());
+    }
+
     #[test]
     fn test_len_and_is_empty() {
         let mut queue = PriorityQueue::new();
@@ -0,0 +1,521 @@
+//! Recurring jobs submitted on a cron schedule.
+//!
+//! A [`RecurringJob`] pairs a prototype [`ScheduledJob`] with a
+//! [`CronSchedule`]. While the background processor runs, each due
+//! schedule submits a fresh copy of the prototype, recorded under
+//! [`RECURRING_METADATA_KEY`], and remembers the run in a bounded history
+//! kept with the schedule in the [`StateStore`](crate::StateStore).
+//! Schedules can be paused and resumed; runs missed while paused or while
+//! no scheduler was running are not made up, except for a single catch-up
+//! run when a due schedule is first seen again.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobId, ScheduledJobStatus};
+
+/// Metadata key recording the recurring job a run was submitted by.
+pub const RECURRING_METADATA_KEY: &str = "recurring";
+
+/// Runs kept in a recurring job's history by default.
+pub const DEFAULT_MAX_HISTORY: usize = 30;
+
+/// Years searched for the next matching time before giving up, e.g. for
+/// `0 0 30 2 *`.
+const MAX_SEARCH_YEARS: i32 = 5;
+
+/// A standard five-field cron expression, evaluated in UTC.
+///
+/// The fields are minute (0-59), hour (0-23), day of month (1-31), month
+/// (1-12 or `jan`-`dec`) and day of week (0-7 or `sun`-`sat`, 0 and 7
+/// both being Sunday). Each field is `*` or a comma-separated list of
+/// values and ranges `a-b`, optionally stepped with `/n`. As in cron, a
+/// time matches if its day matches either the day of month or the day of
+/// week when both are restricted. `@hourly`, `@daily` (`@midnight`),
+/// `@weekly`, `@monthly` and `@yearly` (`@annually`) are accepted too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+/// Range and value names of one cron field.
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+const DAY_OF_MONTH: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ],
+};
+const DAY_OF_WEEK: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+impl Field {
+    fn value(&self, token: &str) -> Result<u32, String> {
+        if let Some(index) = self
+            .names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(token))
+        {
+            // Month names start at 1, day names at 0
+            return Ok(index as u32 + self.min);
+        }
+        let value: u32 = token
+            .parse()
+            .map_err(|_| format!("invalid {} '{}'", self.name, token))?;
+        if value < self.min || value > self.max {
+            return Err(format!(
+                "{} {} is outside {}-{}",
+                self.name, value, self.min, self.max
+            ));
+        }
+        Ok(value)
+    }
+
+    /// Parse a field into a bit set of its values, and whether it is `*`.
+    fn parse(&self, field: &str) -> Result<(u64, bool), String> {
+        let mut bits = 0u64;
+        for item in field.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .ok()
+                        .filter(|&step| step > 0)
+                        .ok_or_else(|| format!("invalid step '{}' in {}", step, self.name))?;
+                    (range, step)
+                }
+                None => (item, 1),
+            };
+            let (start, end) = match range {
+                "*" => (self.min, self.max),
+                _ => match range.split_once('-') {
+                    Some((start, end)) => (self.value(start)?, self.value(end)?),
+                    // `n/step` runs from n to the end of the range
+                    None if step > 1 => (self.value(range)?, self.max),
+                    None => {
+                        let value = self.value(range)?;
+                        (value, value)
+                    }
+                },
+            };
+            if start > end {
+                return Err(format!("empty {} range '{}'", self.name, range));
+            }
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+        Ok((bits, field == "*"))
+    }
+}
+
+impl CronSchedule {
+    /// Parse a cron expression.
+    pub fn parse(expression: &str) -> SchedResult<Self> {
+        let invalid = |reason: String| {
+            SchedError::ConfigError(format!(
+                "invalid cron expression '{}': {}",
+                expression, reason
+            ))
+        };
+        let expanded = match expression.trim() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields.as_slice() else {
+            return Err(invalid(format!(
+                "expected 5 fields, found {}",
+                fields.len()
+            )));
+        };
+
+        let (minutes, _) = MINUTE.parse(minute).map_err(invalid)?;
+        let (hours, _) = HOUR.parse(hour).map_err(invalid)?;
+        let (days_of_month, any_day_of_month) =
+            DAY_OF_MONTH.parse(day_of_month).map_err(invalid)?;
+        let (months, _) = MONTH.parse(month).map_err(invalid)?;
+        let (mut days_of_week, any_day_of_week) =
+            DAY_OF_WEEK.parse(day_of_week).map_err(invalid)?;
+        // 7 is Sunday too
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            any_day_of_month,
+            any_day_of_week,
+        })
+    }
+
+    /// The expression the schedule was parsed from.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Check whether the schedule fires at the minute of `time`.
+    pub fn matches(&self, time: DateTime<Utc>) -> bool {
+        self.matches_day(time.date_naive())
+            && self.hours & (1 << time.hour()) != 0
+            && self.minutes & (1 << time.minute()) != 0
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let by_month = self.days_of_month & (1 << date.day()) != 0;
+        let by_week = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => by_week,
+            (false, true) => by_month,
+            (false, false) => by_month || by_week,
+        }
+    }
+
+    /// The first time strictly after `after` at which the schedule fires,
+    /// or `None` if it never does, e.g. on February 30th.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = after.date_naive();
+        let limit = start.year() + MAX_SEARCH_YEARS;
+        let mut date = start;
+        while date.year() <= limit {
+            if self.matches_day(date) {
+                for hour in 0..24 {
+                    if self.hours & (1 << hour) == 0 {
+                        continue;
+                    }
+                    for minute in 0..60 {
+                        if self.minutes & (1 << minute) == 0 {
+                            continue;
+                        }
+                        let time = Utc.from_utc_datetime(&date.and_hms_opt(hour, minute, 0)?);
+                        if time > after {
+                            return Some(time);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = SchedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for CronSchedule {
+    type Error = SchedError;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        Self::parse(&expression)
+    }
+}
+
+impl From<CronSchedule> for String {
+    fn from(schedule: CronSchedule) -> Self {
+        schedule.expression
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// One submission of a recurring job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecurringRun {
+    /// ID of the submitted job.
+    pub job_id: ScheduledJobId,
+
+    /// Time the run was due.
+    pub scheduled_for: DateTime<Utc>,
+
+    /// Time the job was submitted.
+    pub submitted_at: DateTime<Utc>,
+
+    /// Why the submission failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A job submitted again and again on a cron schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringJob {
+    /// Unique name, with the rules of template names.
+    pub name: String,
+
+    /// When to submit the job.
+    pub schedule: CronSchedule,
+
+    /// The job each run submits a copy of.
+    pub job: ScheduledJob,
+
+    /// Whether the schedule is paused.
+    #[serde(default)]
+    pub paused: bool,
+
+    /// When the next run is due; `None` while paused or if the schedule
+    /// never fires again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<DateTime<Utc>>,
+
+    /// Past runs, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<RecurringRun>,
+
+    /// Runs kept in the history.
+    #[serde(default = "default_max_history")]
+    pub max_history: usize,
+
+    /// When the recurring job was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the recurring job was last saved.
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_max_history() -> usize {
+    DEFAULT_MAX_HISTORY
+}
+
+impl RecurringJob {
+    /// Submit copies of `job` on `schedule`.
+    pub fn new(name: impl Into<String>, schedule: CronSchedule, job: ScheduledJob) -> Self {
+        let now = Utc::now();
+        Self {
+            name: name.into(),
+            schedule,
+            job,
+            paused: false,
+            next_run: None,
+            history: Vec::new(),
+            max_history: DEFAULT_MAX_HISTORY,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Keep at most `max_history` runs in the history.
+    pub fn with_max_history(mut self, max_history: usize) -> Self {
+        self.max_history = max_history;
+        self
+    }
+
+    /// Check the name and that the prototype job can be submitted again:
+    /// it must be pending and not depend on other jobs.
+    pub fn validate(&self) -> SchedResult<()> {
+        crate::template::validate_name(&self.name)?;
+        if !matches!(self.job.status, ScheduledJobStatus::Pending) {
+            return Err(SchedError::InvalidPayload(format!(
+                "recurring job {} must repeat a pending job",
+                self.name
+            )));
+        }
+        if !self.job.dependencies.is_empty() || self.job.workflow_id.is_some() {
+            return Err(SchedError::InvalidPayload(format!(
+                "recurring job {} cannot repeat a job with dependencies",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check whether a run is due at `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        !self.paused && self.next_run.is_some_and(|next| next <= now)
+    }
+
+    /// A fresh copy of the prototype job for the next run.
+    pub fn instantiate(&self) -> ScheduledJob {
+        let mut job = self.job.clone();
+        job.id = ScheduledJobId::new();
+        job.created_at = Utc::now();
+        job.metadata
+            .insert(RECURRING_METADATA_KEY.to_string(), self.name.clone());
+        job
+    }
+
+    /// Record a run, dropping the oldest beyond `max_history`.
+    pub fn record_run(&mut self, run: RecurringRun) {
+        self.history.push(run);
+        let excess = self.history.len().saturating_sub(self.max_history);
+        self.history.drain(..excess);
+    }
+
+    /// Schedule the next run after `now`, skipping any missed runs.
+    pub fn advance(&mut self, now: DateTime<Utc>) {
+        self.next_run = if self.paused {
+            None
+        } else {
+            self.schedule.next_after(now)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+    use chrono::Duration;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_cron_next_after() {
+        let daily = CronSchedule::parse("30 6 * * *").unwrap();
+        assert_eq!(
+            daily.next_after(at("2026-03-01T06:30:00Z")),
+            Some(at("2026-03-02T06:30:00Z"))
+        );
+        assert_eq!(
+            daily.next_after(at("2026-03-01T05:00:00Z")),
+            Some(at("2026-03-01T06:30:00Z"))
+        );
+
+        // Every 15 minutes during working hours on weekdays
+        let working = CronSchedule::parse("*/15 9-17 * * mon-fri").unwrap();
+        assert_eq!(
+            working.next_after(at("2026-03-06T17:50:00Z")), // a Friday
+            Some(at("2026-03-09T09:00:00Z"))
+        );
+
+        // Day of month or day of week, as in cron
+        let either = CronSchedule::parse("0 0 13 * 5").unwrap();
+        assert_eq!(
+            either.next_after(at("2026-03-01T00:00:00Z")),
+            Some(at("2026-03-06T00:00:00Z"))
+        );
+
+        assert_eq!(
+            CronSchedule::parse("@weekly")
+                .unwrap()
+                .next_after(at("2026-03-04T12:00:00Z")),
+            Some(at("2026-03-08T00:00:00Z"))
+        );
+        assert!(
+            CronSchedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(at("2026-01-01T00:00:00Z"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cron_parse_errors() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* * 0 * *",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "* * * foo *",
+        ] {
+            assert!(CronSchedule::parse(expression).is_err(), "{}", expression);
+        }
+        let sunday = CronSchedule::parse("0 12 * * 7").unwrap();
+        assert!(sunday.matches(at("2026-03-08T12:00:00Z")));
+
+        let json = serde_json::to_string(&sunday).unwrap();
+        assert_eq!(json, "\"0 12 * * 7\"");
+        assert_eq!(serde_json::from_str::<CronSchedule>(&json).unwrap(), sunday);
+        assert!(serde_json::from_str::<CronSchedule>("\"nope\"").is_err());
+    }
+
+    #[test]
+    fn test_recurring_history_is_bounded() {
+        let job = ScheduledJob::new("suite", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let mut recurring =
+            RecurringJob::new("suite", CronSchedule::parse("@hourly").unwrap(), job)
+                .with_max_history(2);
+        recurring.validate().unwrap();
+
+        let now = at("2026-03-01T10:20:00Z");
+        recurring.advance(now);
+        assert_eq!(recurring.next_run, Some(at("2026-03-01T11:00:00Z")));
+        assert!(!recurring.is_due(now));
+        assert!(recurring.is_due(at("2026-03-01T11:00:00Z")));
+
+        for hour in 0..3 {
+            let run = recurring.instantiate();
+            assert_ne!(run.id, recurring.job.id);
+            assert_eq!(run.metadata[RECURRING_METADATA_KEY], "suite");
+            recurring.record_run(RecurringRun {
+                job_id: run.id,
+                scheduled_for: now + Duration::hours(hour),
+                submitted_at: now,
+                error: None,
+            });
+        }
+        assert_eq!(recurring.history.len(), 2);
+        assert_eq!(recurring.history[0].scheduled_for, now + Duration::hours(1));
+
+        recurring.paused = true;
+        recurring.advance(now);
+        assert!(recurring.next_run.is_none());
+    }
+}
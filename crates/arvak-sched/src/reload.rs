@@ -0,0 +1,478 @@
+//! Live reconfiguration of a running scheduler.
+//!
+//! Only settings that can change without losing queued state are applied at
+//! runtime. Restart-only settings are accepted in updates so that attempts to
+//! change them can be rejected with a diagnostic instead of silently ignored;
+//! every [`SchedulerConfig`] setting is one or the other.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::breaker::BreakerConfig;
+use crate::compile::CompileConfig;
+use crate::error::{SchedError, SchedResult};
+use crate::fairshare::FairShareConfig;
+use crate::preempt::PreemptionConfig;
+use crate::queue::QueuePolicy;
+use crate::scheduler::{BatchSchedulerType, SchedulerConfig};
+use crate::validate::ResultValidation;
+
+/// A partial scheduler configuration update.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchedulerConfigUpdate {
+    /// Status polling interval in seconds.
+    pub poll_interval_secs: Option<u64>,
+
+    /// Maximum time to wait for a job (seconds).
+    pub max_wait_time_secs: Option<u64>,
+
+    /// Whether to automatically match resources on submit.
+    pub auto_match_resources: Option<bool>,
+
+    /// Longest interval in seconds between polls of all active jobs.
+    pub max_poll_interval_secs: Option<u64>,
+
+    /// Queue depth at which new jobs are admitted as congested.
+    pub congested_queue_depth: Option<usize>,
+
+    /// Preemption of running jobs for urgent ones; `null` disables it.
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub preemption: Option<Option<PreemptionConfig>>,
+
+    /// Checks on the results of quantum jobs that set none of their own;
+    /// `null` disables them.
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub result_validation: Option<Option<ResultValidation>>,
+
+    /// Queue ordering policy. Restart only.
+    pub queue_policy: Option<QueuePolicy>,
+
+    /// Deadline horizon of the deadline queue policy. Restart only.
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub deadline_horizon_secs: Option<Option<u64>>,
+
+    /// Fair-share settings. Restart only.
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub fair_share: Option<Option<FairShareConfig>>,
+
+    /// Batch node features by backend name. Restart only.
+    pub node_features: Option<BTreeMap<String, Vec<String>>>,
+
+    /// Failure-rate breaker settings. Restart only.
+    pub breaker: Option<BreakerConfig>,
+
+    /// Compile-on-submit settings. Restart only.
+    #[serde(deserialize_with = "nullable", skip_serializing_if = "Option::is_none")]
+    pub compile: Option<Option<CompileConfig>>,
+
+    /// Whether dispatches are logged ahead. Restart only.
+    pub write_ahead_log: Option<bool>,
+
+    /// Batch scheduler type (`slurm`, `pbs`, `lsf` or `kubernetes`). Restart only.
+    pub scheduler_type: Option<String>,
+
+    /// Working directory for scheduler state. Restart only.
+    pub state_dir: Option<PathBuf>,
+
+    /// SLURM adapter settings. Restart only.
+    pub slurm: Option<serde_json::Value>,
+
+    /// PBS adapter settings. Restart only.
+    pub pbs: Option<serde_json::Value>,
+
+    /// LSF adapter settings. Restart only.
+    pub lsf: Option<serde_json::Value>,
+
+    /// Kubernetes adapter settings. Restart only.
+    pub kubernetes: Option<serde_json::Value>,
+}
+
+/// Deserialize a setting that may be `null`, keeping `null` apart from a
+/// missing setting.
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// A setting's value as shown in diagnostics and change records.
+fn describe<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "?".to_string())
+}
+
+/// A configuration change applied by an update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// Name of the changed setting.
+    pub field: &'static str,
+
+    /// Previous value.
+    pub old: String,
+
+    /// New value.
+    pub new: String,
+}
+
+impl std::fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.old, self.new)
+    }
+}
+
+impl SchedulerConfigUpdate {
+    /// Create an empty update.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the polling interval.
+    pub fn with_poll_interval_secs(mut self, secs: u64) -> Self {
+        self.poll_interval_secs = Some(secs);
+        self
+    }
+
+    /// Set the maximum wait time.
+    pub fn with_max_wait_time_secs(mut self, secs: u64) -> Self {
+        self.max_wait_time_secs = Some(secs);
+        self
+    }
+
+    /// Enable or disable automatic resource matching.
+    pub fn with_auto_match_resources(mut self, enabled: bool) -> Self {
+        self.auto_match_resources = Some(enabled);
+        self
+    }
+
+    /// Parse an update from JSON.
+    pub fn from_json(json: &str) -> SchedResult<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| SchedError::ConfigError(format!("Invalid config update: {}", e)))
+    }
+
+    /// Read an update from a JSON file.
+    pub async fn from_file(path: &Path) -> SchedResult<Self> {
+        let content = tokio::fs::read_to_string(path).await?;
+        Self::from_json(&content)
+    }
+
+    /// Validate the update and apply it to `config`.
+    ///
+    /// The update is applied atomically: if any setting is invalid or can
+    /// only change on restart, `config` is left untouched and the error lists
+    /// every problem found.
+    pub fn apply_to(&self, config: &mut SchedulerConfig) -> SchedResult<Vec<ConfigChange>> {
+        let mut problems = Vec::new();
+
+        if self.poll_interval_secs == Some(0) {
+            problems.push("poll_interval_secs must be greater than 0".to_string());
+        }
+        if self.max_wait_time_secs == Some(0) {
+            problems.push("max_wait_time_secs must be greater than 0".to_string());
+        }
+        if self.max_poll_interval_secs == Some(0) {
+            problems.push("max_poll_interval_secs must be greater than 0".to_string());
+        }
+
+        if let Some(ref scheduler_type) = self.scheduler_type {
+            let current = match config.scheduler_type {
+                BatchSchedulerType::Slurm => "slurm",
+                BatchSchedulerType::Pbs => "pbs",
+                BatchSchedulerType::Lsf => "lsf",
+                BatchSchedulerType::Kubernetes => "kubernetes",
+            };
+            if !scheduler_type.eq_ignore_ascii_case(current) {
+                problems.push(format!(
+                    "scheduler_type cannot change at runtime ({} -> {}); restart required",
+                    current, scheduler_type
+                ));
+            }
+        }
+        if let Some(ref state_dir) = self.state_dir {
+            if *state_dir != config.state_dir {
+                problems.push(format!(
+                    "state_dir cannot change at runtime ({} -> {}); restart required",
+                    config.state_dir.display(),
+                    state_dir.display()
+                ));
+            }
+        }
+        if self.slurm.is_some() {
+            problems
+                .push("slurm adapter settings cannot change at runtime; restart required".into());
+        }
+        if self.pbs.is_some() {
+            problems.push("pbs adapter settings cannot change at runtime; restart required".into());
+        }
+        if self.lsf.is_some() {
+            problems.push("lsf adapter settings cannot change at runtime; restart required".into());
+        }
+        if self.kubernetes.is_some() {
+            problems.push(
+                "kubernetes adapter settings cannot change at runtime; restart required".into(),
+            );
+        }
+        restart_only(
+            &mut problems,
+            "queue_policy",
+            &self.queue_policy,
+            &config.queue_policy,
+        );
+        restart_only(
+            &mut problems,
+            "deadline_horizon_secs",
+            &self.deadline_horizon_secs,
+            &config.deadline_horizon_secs,
+        );
+        restart_only(
+            &mut problems,
+            "fair_share",
+            &self.fair_share,
+            &config.fair_share,
+        );
+        restart_only(
+            &mut problems,
+            "node_features",
+            &self.node_features,
+            &config.node_features,
+        );
+        restart_only(&mut problems, "breaker", &self.breaker, &config.breaker);
+        restart_only(&mut problems, "compile", &self.compile, &config.compile);
+        restart_only(
+            &mut problems,
+            "write_ahead_log",
+            &self.write_ahead_log,
+            &config.write_ahead_log,
+        );
+
+        if !problems.is_empty() {
+            return Err(SchedError::ConfigError(format!(
+                "Rejected config update: {}",
+                problems.join("; ")
+            )));
+        }
+
+        let mut changes = Vec::new();
+        if let Some(secs) = self.poll_interval_secs {
+            if secs != config.poll_interval_secs {
+                changes.push(ConfigChange {
+                    field: "poll_interval_secs",
+                    old: config.poll_interval_secs.to_string(),
+                    new: secs.to_string(),
+                });
+                config.poll_interval_secs = secs;
+            }
+        }
+        if let Some(secs) = self.max_wait_time_secs {
+            if secs != config.max_wait_time_secs {
+                changes.push(ConfigChange {
+                    field: "max_wait_time_secs",
+                    old: config.max_wait_time_secs.to_string(),
+                    new: secs.to_string(),
+                });
+                config.max_wait_time_secs = secs;
+            }
+        }
+        if let Some(enabled) = self.auto_match_resources {
+            if enabled != config.auto_match_resources {
+                changes.push(ConfigChange {
+                    field: "auto_match_resources",
+                    old: config.auto_match_resources.to_string(),
+                    new: enabled.to_string(),
+                });
+                config.auto_match_resources = enabled;
+            }
+        }
+        live(
+            &mut changes,
+            "max_poll_interval_secs",
+            &self.max_poll_interval_secs,
+            &mut config.max_poll_interval_secs,
+        );
+        live(
+            &mut changes,
+            "congested_queue_depth",
+            &self.congested_queue_depth,
+            &mut config.congested_queue_depth,
+        );
+        live(
+            &mut changes,
+            "preemption",
+            &self.preemption,
+            &mut config.preemption,
+        );
+        live(
+            &mut changes,
+            "result_validation",
+            &self.result_validation,
+            &mut config.result_validation,
+        );
+
+        Ok(changes)
+    }
+}
+
+/// Reject a change of a restart-only setting; an update repeating the
+/// current value is accepted.
+fn restart_only<T: PartialEq + Serialize>(
+    problems: &mut Vec<String>,
+    field: &str,
+    update: &Option<T>,
+    current: &T,
+) {
+    if let Some(value) = update {
+        if value != current {
+            problems.push(format!(
+                "{} cannot change at runtime ({} -> {}); restart required",
+                field,
+                describe(current),
+                describe(value)
+            ));
+        }
+    }
+}
+
+/// Apply a change of a live setting.
+fn live<T: Clone + PartialEq + Serialize>(
+    changes: &mut Vec<ConfigChange>,
+    field: &'static str,
+    update: &Option<T>,
+    current: &mut T,
+) {
+    if let Some(value) = update {
+        if value != current {
+            changes.push(ConfigChange {
+                field,
+                old: describe(current),
+                new: describe(value),
+            });
+            *current = value.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_safe_changes() {
+        let mut config = SchedulerConfig::default();
+        let update = SchedulerConfigUpdate::new()
+            .with_poll_interval_secs(5)
+            .with_auto_match_resources(true);
+
+        let changes = update.apply_to(&mut config).unwrap();
+        assert_eq!(config.poll_interval_secs, 5);
+        // auto_match_resources was already true
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].to_string(), "poll_interval_secs: 30 -> 5");
+    }
+
+    #[test]
+    fn test_reject_is_atomic() {
+        let mut config = SchedulerConfig::default();
+        let update = SchedulerConfigUpdate::from_json(
+            r#"{"poll_interval_secs": 5, "scheduler_type": "pbs", "max_wait_time_secs": 0}"#,
+        )
+        .unwrap();
+
+        let err = update.apply_to(&mut config).unwrap_err().to_string();
+        assert!(err.contains("scheduler_type"));
+        assert!(err.contains("max_wait_time_secs"));
+        assert_eq!(config.poll_interval_secs, 30);
+    }
+
+    #[test]
+    fn test_unchanged_restart_settings_accepted() {
+        let mut config = SchedulerConfig::default();
+        let update = SchedulerConfigUpdate {
+            scheduler_type: Some("SLURM".to_string()),
+            state_dir: Some(config.state_dir.clone()),
+            ..Default::default()
+        };
+        assert!(update.apply_to(&mut config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unknown_field_rejected() {
+        assert!(SchedulerConfigUpdate::from_json(r#"{"poll_interval": 5}"#).is_err());
+    }
+
+    #[test]
+    fn test_every_config_field_known() {
+        let config = SchedulerConfig {
+            fair_share: Some(FairShareConfig::default()),
+            preemption: Some(serde_json::from_str(r#"{"urgent_priority": 250}"#).unwrap()),
+            result_validation: Some(ResultValidation::new()),
+            compile: Some(CompileConfig::new(
+                arvak_compile::PipelinePreset::IonTrapAllToAll,
+            )),
+            deadline_horizon_secs: Some(3600),
+            node_features: BTreeMap::from([("qpu".to_string(), vec!["qctrl".to_string()])]),
+            ..Default::default()
+        };
+        let serde_json::Value::Object(fields) = serde_json::to_value(&config).unwrap() else {
+            panic!("config is not an object");
+        };
+        for (field, value) in fields {
+            let json = serde_json::json!({ field.clone(): value }).to_string();
+            assert!(
+                SchedulerConfigUpdate::from_json(&json).is_ok(),
+                "{} missing from SchedulerConfigUpdate",
+                field
+            );
+        }
+    }
+
+    #[test]
+    fn test_later_settings() {
+        let mut config = SchedulerConfig::default();
+        let update = SchedulerConfigUpdate::from_json(
+            r#"{
+                "max_poll_interval_secs": 120,
+                "congested_queue_depth": 10,
+                "result_validation": {"rules": [{"type": "total_shots"}]}
+            }"#,
+        )
+        .unwrap();
+        let changes = update.apply_to(&mut config).unwrap();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(config.max_poll_interval_secs, 120);
+        assert_eq!(config.congested_queue_depth, 10);
+        assert!(config.result_validation.is_some());
+
+        // null switches an optional live setting off
+        let update = SchedulerConfigUpdate::from_json(r#"{"result_validation": null}"#).unwrap();
+        let changes = update.apply_to(&mut config).unwrap();
+        assert_eq!(changes[0].new, "null");
+        assert!(config.result_validation.is_none());
+
+        for json in [
+            r#"{"queue_policy": "deadline"}"#,
+            r#"{"deadline_horizon_secs": 60}"#,
+            r#"{"fair_share": {}}"#,
+            r#"{"node_features": {"qpu": ["qctrl"]}}"#,
+            r#"{"breaker": {"failure_threshold": 0.9}}"#,
+            r#"{"compile": {"preset": "iontrap-alltoall"}}"#,
+            r#"{"write_ahead_log": true}"#,
+        ] {
+            let err = SchedulerConfigUpdate::from_json(json)
+                .unwrap()
+                .apply_to(&mut config)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("restart required"), "{}: {}", json, err);
+        }
+
+        // Restating the current value is accepted
+        let update =
+            SchedulerConfigUpdate::from_json(r#"{"write_ahead_log": false, "compile": null}"#)
+                .unwrap();
+        assert!(update.apply_to(&mut config).unwrap().is_empty());
+    }
+}
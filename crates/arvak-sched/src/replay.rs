@@ -0,0 +1,449 @@
+//! Replaying the scheduler decision log.
+//!
+//! [`replay`] folds a sequence of [`SchedulerEvent`]s into the state of each
+//! job, step by step, and returns the [`DecisionTrace`] for the jobs and time
+//! window selected by a [`ReplayFilter`]. Events before the window are still
+//! applied so that the first step of the trace starts from the job's actual
+//! state at that time.
+//!
+//! ```rust
+//! use arvak_sched::events::{EventKind, SchedulerEvent};
+//! use arvak_sched::replay::{ReplayFilter, replay};
+//! use arvak_sched::{Priority, ScheduledJobId, ScheduledJobStatus};
+//!
+//! let job = ScheduledJobId::new();
+//! let events = vec![
+//!     SchedulerEvent::new(job.clone(), EventKind::Submitted {
+//!         name: "bell".into(),
+//!         priority: Priority::default(),
+//!         dependencies: vec![],
+//!         status: ScheduledJobStatus::Pending,
+//!     }),
+//!     SchedulerEvent::new(job.clone(), EventKind::Dispatched { batch_job_id: "42".into() }),
+//! ];
+//!
+//! let trace = replay(events, &ReplayFilter::for_job(job));
+//! assert_eq!(trace.steps.len(), 2);
+//! assert_eq!(trace.jobs[0].batch_job_id.as_deref(), Some("42"));
+//! ```
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use rustc_hash::FxHashMap;
+use serde::Serialize;
+
+use crate::events::{EventKind, SchedulerEvent};
+use crate::job::{Priority, ScheduledJobId, ScheduledJobStatus};
+
+/// Selects the part of an event log to trace.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayFilter {
+    /// Only trace this job.
+    pub job_id: Option<ScheduledJobId>,
+
+    /// Only trace events at or after this time.
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only trace events at or before this time.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ReplayFilter {
+    /// Trace every job over the whole log.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Trace a single job.
+    pub fn for_job(job_id: ScheduledJobId) -> Self {
+        Self {
+            job_id: Some(job_id),
+            ..Self::default()
+        }
+    }
+
+    /// Start the window at `since`.
+    pub fn with_since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// End the window at `until`.
+    pub fn with_until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    fn selects_job(&self, job_id: &ScheduledJobId) -> bool {
+        self.job_id.as_ref().is_none_or(|id| id == job_id)
+    }
+}
+
+/// State of a job reconstructed from the log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobReplay {
+    /// Job ID.
+    pub job_id: ScheduledJobId,
+
+    /// Job name, if its submission is in the log.
+    pub name: Option<String>,
+
+    /// Job priority, if its submission is in the log.
+    pub priority: Option<Priority>,
+
+    /// Last known status.
+    pub status: Option<ScheduledJobStatus>,
+
+    /// Backend the job is matched to.
+    pub backend: Option<String>,
+
+    /// Batch system job ID of the current dispatch.
+    pub batch_job_id: Option<String>,
+
+    /// Number of times the job was rerouted.
+    pub reroutes: u32,
+}
+
+impl JobReplay {
+    fn new(job_id: ScheduledJobId) -> Self {
+        Self {
+            job_id,
+            name: None,
+            priority: None,
+            status: None,
+            backend: None,
+            batch_job_id: None,
+            reroutes: 0,
+        }
+    }
+
+    /// Apply an event, returning a description of the decision.
+    fn apply(&mut self, kind: &EventKind) -> String {
+        match kind {
+            EventKind::Submitted {
+                name,
+                priority,
+                dependencies,
+                status,
+            } => {
+                self.name = Some(name.clone());
+                self.priority = Some(*priority);
+                self.status = Some(status.clone());
+                let mut decision =
+                    format!("submitted '{}' with priority {}", name, priority.value());
+                if matches!(status, ScheduledJobStatus::WaitingOnDependencies) {
+                    decision.push_str(&format!(", waiting on {} dependencies", dependencies.len()));
+                }
+                decision
+            }
+            EventKind::Matched { backend } => {
+                self.backend = Some(backend.clone());
+                format!("matched to backend {}", backend)
+            }
+            EventKind::Negotiated { outcome } => format!("negotiated capabilities: {}", outcome),
+            EventKind::Held { reason } => format!("held in queue: {}", reason),
+            EventKind::Dispatched { batch_job_id } => {
+                self.batch_job_id = Some(batch_job_id.clone());
+                self.status = Some(ScheduledJobStatus::SlurmQueued {
+                    slurm_job_id: batch_job_id.clone(),
+                });
+                format!("dispatched to batch system as {}", batch_job_id)
+            }
+            EventKind::CloudSubmitted {
+                backend,
+                remote_job_id,
+            } => {
+                self.backend = Some(backend.clone());
+                self.batch_job_id = None;
+                self.status = Some(ScheduledJobStatus::QuantumSubmitted {
+                    slurm_job_id: crate::cloud::CLOUD_JOB_ID.to_string(),
+                    quantum_job_id: arvak_hal::JobId(remote_job_id.clone()),
+                });
+                format!(
+                    "submitted directly to cloud backend {} as {}",
+                    backend, remote_job_id
+                )
+            }
+            EventKind::Rerouted {
+                from_backend,
+                attempt,
+            } => {
+                self.reroutes = *attempt;
+                self.backend = None;
+                self.batch_job_id = None;
+                self.status = Some(ScheduledJobStatus::Pending);
+                format!(
+                    "rerouted away from paused backend {} (attempt {})",
+                    from_backend, attempt
+                )
+            }
+            EventKind::Retried {
+                attempt,
+                kind,
+                reason,
+                delay_secs,
+            } => {
+                self.batch_job_id = None;
+                self.status = Some(ScheduledJobStatus::Pending);
+                format!(
+                    "attempt {} failed ({}: {}), retrying in {}s",
+                    attempt, kind, reason, delay_secs
+                )
+            }
+            EventKind::Preempted { by, policy, status } => {
+                if matches!(status, ScheduledJobStatus::Pending) {
+                    self.backend = None;
+                    self.batch_job_id = None;
+                }
+                self.status = Some(status.clone());
+                format!("preempted for urgent job {} ({})", by, policy)
+            }
+            EventKind::StatusChanged { status, reason } => {
+                let before = self
+                    .status
+                    .replace(status.clone())
+                    .map_or("unknown", |s| s.name());
+                let mut decision = format!("status {} -> {}", before, status.name());
+                if let ScheduledJobStatus::Failed { reason, .. } = status {
+                    decision.push_str(&format!(": {}", reason));
+                }
+                if let Some(reason) = reason {
+                    decision.push_str(&format!(" ({})", reason));
+                }
+                decision
+            }
+        }
+    }
+}
+
+/// One decision in a trace.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionStep {
+    /// When the decision was taken.
+    pub at: DateTime<Utc>,
+
+    /// Job the decision concerns.
+    pub job_id: ScheduledJobId,
+
+    /// Human-readable description.
+    pub decision: String,
+
+    /// The recorded event.
+    pub event: EventKind,
+
+    /// Job status after the decision.
+    pub status: Option<ScheduledJobStatus>,
+}
+
+/// Decisions taken in a replayed window, in time order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DecisionTrace {
+    /// Decisions in time order.
+    pub steps: Vec<DecisionStep>,
+
+    /// State of each traced job at the end of the window, in order of
+    /// first decision.
+    pub jobs: Vec<JobReplay>,
+}
+
+/// Reconstruct the decisions selected by `filter` from an event log.
+pub fn replay(
+    events: impl IntoIterator<Item = SchedulerEvent>,
+    filter: &ReplayFilter,
+) -> DecisionTrace {
+    let mut events: Vec<_> = events
+        .into_iter()
+        .filter(|e| filter.selects_job(&e.job_id))
+        .filter(|e| filter.until.is_none_or(|until| e.at <= until))
+        .collect();
+    // Concurrent writers may append slightly out of order.
+    events.sort_by_key(|e| e.at);
+
+    let mut states: FxHashMap<ScheduledJobId, JobReplay> = FxHashMap::default();
+    let mut order = Vec::new();
+    let mut steps = Vec::new();
+    for event in events {
+        let state = states
+            .entry(event.job_id.clone())
+            .or_insert_with(|| JobReplay::new(event.job_id.clone()));
+        let decision = state.apply(&event.kind);
+        if filter.since.is_some_and(|since| event.at < since) {
+            continue;
+        }
+        if !order.contains(&event.job_id) {
+            order.push(event.job_id.clone());
+        }
+        steps.push(DecisionStep {
+            at: event.at,
+            job_id: event.job_id,
+            decision,
+            event: event.kind,
+            status: state.status.clone(),
+        });
+    }
+
+    let jobs = order.iter().filter_map(|id| states.remove(id)).collect();
+    DecisionTrace { steps, jobs }
+}
+
+impl fmt::Display for DecisionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{}  {}  {}",
+                step.at.format("%Y-%m-%d %H:%M:%S%.3f"),
+                step.job_id,
+                step.decision
+            )?;
+        }
+        if !self.jobs.is_empty() {
+            writeln!(f)?;
+        }
+        for job in &self.jobs {
+            write!(f, "{}", job.job_id)?;
+            if let Some(name) = &job.name {
+                write!(f, " '{}'", name)?;
+            }
+            match &job.status {
+                Some(status) => write!(f, ": {}", status)?,
+                None => write!(f, ": unknown")?,
+            }
+            if let Some(backend) = &job.backend {
+                write!(f, " on {}", backend)?;
+            }
+            if job.reroutes > 0 {
+                write!(f, ", rerouted {} time(s)", job.reroutes)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    use crate::retry::FailureKind;
+
+    fn at(event: SchedulerEvent, offset_secs: i64, start: DateTime<Utc>) -> SchedulerEvent {
+        SchedulerEvent {
+            at: start + Duration::seconds(offset_secs),
+            ..event
+        }
+    }
+
+    #[test]
+    fn test_replay_reconstructs_reroute() {
+        let start = Utc::now();
+        let a = ScheduledJobId::new();
+        let b = ScheduledJobId::new();
+        let submitted = |name: &str| EventKind::Submitted {
+            name: name.into(),
+            priority: Priority::default(),
+            dependencies: vec![],
+            status: ScheduledJobStatus::Pending,
+        };
+        let failed = ScheduledJobStatus::Failed {
+            reason: "calibration drift".into(),
+            kind: FailureKind::Application,
+            slurm_job_id: Some("1".into()),
+            quantum_job_id: None,
+        };
+
+        // Written out of order, as concurrent writers may.
+        let events = vec![
+            at(SchedulerEvent::new(a.clone(), submitted("a")), 0, start),
+            at(
+                SchedulerEvent::new(
+                    a.clone(),
+                    EventKind::Matched {
+                        backend: "qpu1".into(),
+                    },
+                ),
+                1,
+                start,
+            ),
+            at(
+                SchedulerEvent::new(
+                    a.clone(),
+                    EventKind::StatusChanged {
+                        status: failed,
+                        reason: None,
+                    },
+                ),
+                10,
+                start,
+            ),
+            at(
+                SchedulerEvent::new(
+                    a.clone(),
+                    EventKind::Dispatched {
+                        batch_job_id: "1".into(),
+                    },
+                ),
+                2,
+                start,
+            ),
+            at(SchedulerEvent::new(b.clone(), submitted("b")), 3, start),
+            at(
+                SchedulerEvent::new(
+                    a.clone(),
+                    EventKind::Rerouted {
+                        from_backend: "qpu1".into(),
+                        attempt: 1,
+                    },
+                ),
+                11,
+                start,
+            ),
+            at(
+                SchedulerEvent::new(
+                    a.clone(),
+                    EventKind::Matched {
+                        backend: "qpu2".into(),
+                    },
+                ),
+                12,
+                start,
+            ),
+        ];
+
+        let trace = replay(events.clone(), &ReplayFilter::for_job(a.clone()));
+        let decisions: Vec<_> = trace.steps.iter().map(|s| s.decision.as_str()).collect();
+        assert_eq!(
+            decisions,
+            [
+                "submitted 'a' with priority 100",
+                "matched to backend qpu1",
+                "dispatched to batch system as 1",
+                "status SlurmQueued -> Failed: calibration drift",
+                "rerouted away from paused backend qpu1 (attempt 1)",
+                "matched to backend qpu2",
+            ]
+        );
+        assert_eq!(trace.jobs.len(), 1);
+        assert_eq!(trace.jobs[0].backend.as_deref(), Some("qpu2"));
+        assert_eq!(trace.jobs[0].status, Some(ScheduledJobStatus::Pending));
+        assert_eq!(trace.jobs[0].reroutes, 1);
+
+        // A window keeps the state built up before it.
+        let window = ReplayFilter::all()
+            .with_since(start + Duration::seconds(3))
+            .with_until(start + Duration::seconds(10));
+        let trace = replay(events, &window);
+        assert_eq!(trace.steps.len(), 2);
+        assert_eq!(trace.steps[0].job_id, b);
+        assert_eq!(
+            trace.steps[1].decision,
+            "status SlurmQueued -> Failed: calibration drift"
+        );
+        assert_eq!(trace.jobs[1].backend.as_deref(), Some("qpu1"));
+
+        let text = trace.to_string();
+        assert!(text.contains(&format!("{} 'b': Pending", b)), "{}", text);
+    }
+}
@@ -0,0 +1,202 @@
+//! Advance reservations of hardware time windows.
+//!
+//! Partners often grant QPU access only in fixed windows. A
+//! [`Reservation`] books batch nodes of one backend for such a window
+//! (a SLURM reservation on SLURM) and is kept in the
+//! [`StateStore`](crate::StateStore). Jobs target it with
+//! [`ScheduledJob::with_reservation`]; on submission they are admitted only
+//! if they can finish inside the window and the reservation still has
+//! room for them, and are then held until the window opens.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::ScheduledJob;
+
+/// A span of time, start inclusive and end exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// When the window opens.
+    pub start: DateTime<Utc>,
+
+    /// When the window closes.
+    pub end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    /// Create a window; fails unless it ends after it starts.
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> SchedResult<Self> {
+        if end <= start {
+            return Err(SchedError::ConfigError(format!(
+                "time window ends at {} before it starts at {}",
+                end, start
+            )));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Create a window of `duration` from `start`.
+    pub fn starting_at(start: DateTime<Utc>, duration: std::time::Duration) -> SchedResult<Self> {
+        let duration = Duration::from_std(duration)
+            .map_err(|e| SchedError::ConfigError(format!("invalid window length: {}", e)))?;
+        Self::new(start, start + duration)
+    }
+
+    /// Length of the window.
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Check whether two windows share any time.
+    pub fn overlaps(&self, other: &TimeWindow) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Part of the window still ahead at `now`, `None` once it has closed.
+    pub fn remaining(&self, now: DateTime<Utc>) -> Option<TimeWindow> {
+        let start = self.start.max(now);
+        (start < self.end).then_some(TimeWindow {
+            start,
+            end: self.end,
+        })
+    }
+}
+
+/// Batch nodes of a backend booked for a time window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reservation {
+    /// Unique name, also the name of the batch system's reservation.
+    pub name: String,
+
+    /// Backend the reserved jobs run on.
+    pub backend: String,
+
+    /// When the nodes are reserved.
+    pub window: TimeWindow,
+
+    /// Number of nodes reserved; as many jobs run at once.
+    pub capacity: u32,
+
+    /// When the reservation was made.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Reservation {
+    /// Describe a reservation of `capacity` nodes for `backend`, named
+    /// after the backend and the start of the window.
+    pub fn new(backend: impl Into<String>, window: TimeWindow, capacity: u32) -> SchedResult<Self> {
+        let backend = backend.into();
+        if capacity == 0 {
+            return Err(SchedError::ConfigError(
+                "a reservation needs a capacity of at least one node".to_string(),
+            ));
+        }
+        let name = format!(
+            "arvak-{}-{}",
+            backend
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect::<String>(),
+            window.start.format("%Y%m%dT%H%M")
+        );
+        Ok(Self {
+            name,
+            backend,
+            window,
+            capacity,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Node time the reservation offers from `now` until it closes.
+    pub fn remaining_capacity(&self, now: DateTime<Utc>) -> Duration {
+        self.window
+            .remaining(now)
+            .map_or_else(Duration::zero, |window| {
+                window.duration() * self.capacity as i32
+            })
+    }
+
+    /// Check that `job` fits into the reservation at `now`, given the
+    /// expected run time in seconds of the unfinished jobs already in it.
+    ///
+    /// The job must be able to finish before the window closes, and the
+    /// run times of all jobs in the reservation must fit into its
+    /// remaining node time.
+    pub fn admit(
+        &self,
+        job: &ScheduledJob,
+        committed_secs: f64,
+        now: DateTime<Utc>,
+    ) -> SchedResult<()> {
+        let reject = |reason: String| {
+            Err(SchedError::InvalidPayload(format!(
+                "job {} does not fit reservation {}: {}",
+                job.id, self.name, reason
+            )))
+        };
+        if let Some(backend) = job.matched_backend.as_ref().filter(|b| **b != self.backend) {
+            return reject(format!("it is pinned to backend {}", backend));
+        }
+        let Some(remaining) = self.window.remaining(now) else {
+            return reject(format!("the window closed at {}", self.window.end));
+        };
+        let runtime = job.expected_runtime_secs();
+        let available = remaining.duration().num_seconds() as f64;
+        if runtime > available {
+            return reject(format!(
+                "its expected run time of {:.0}s exceeds the {:.0}s left in the window",
+                runtime, available
+            ));
+        }
+        let capacity = self.remaining_capacity(now).num_seconds() as f64;
+        if committed_secs + runtime > capacity {
+            return reject(format!(
+                "{:.0}s of the {:.0}s of node time left are taken",
+                committed_secs, capacity
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    #[test]
+    fn test_reservation_admits_jobs_that_fit() {
+        let start = Utc::now() + Duration::hours(1);
+        let window = TimeWindow::starting_at(start, std::time::Duration::from_secs(3_600)).unwrap();
+        let reservation = Reservation::new("iqm_garnet", window, 2).unwrap();
+        assert!(reservation.name.starts_with("arvak-iqm-garnet-"));
+        assert!(Reservation::new("iqm", window, 0).is_err());
+        assert!(TimeWindow::new(start, start).is_err());
+
+        let job = |secs| {
+            ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"))
+                .with_walltime(std::time::Duration::from_secs(secs))
+        };
+        let now = Utc::now();
+        reservation.admit(&job(1_800), 0.0, now).unwrap();
+        // Longer than the window
+        assert!(reservation.admit(&job(7_200), 0.0, now).is_err());
+        // Two nodes for an hour hold 7200s of jobs
+        reservation.admit(&job(1_800), 5_400.0, now).unwrap();
+        assert!(reservation.admit(&job(1_800), 6_000.0, now).is_err());
+        // Only half an hour left
+        let late = start + Duration::minutes(30);
+        assert!(reservation.admit(&job(2_400), 0.0, late).is_err());
+        assert!(
+            reservation
+                .admit(&job(60), 0.0, start + Duration::hours(2))
+                .is_err()
+        );
+
+        let mut pinned = job(60);
+        pinned.matched_backend = Some("other".to_string());
+        assert!(reservation.admit(&pinned, 0.0, now).is_err());
+    }
+}
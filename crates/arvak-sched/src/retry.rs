@@ -0,0 +1,246 @@
+//! Automatic retries of failed jobs.
+//!
+//! Jobs die for reasons that have nothing to do with the job: a node fails
+//! under them or they hit a time limit on a busy system. A job carrying a
+//! [`RetryPolicy`] is requeued when it fails in one of the ways the policy
+//! retries on, after an exponentially growing delay, until it runs out of
+//! attempts. Batch systems classify each failure as a [`FailureKind`], so
+//! application errors, which would fail again, are not retried by default.
+//! Every failed attempt is kept on the job as an [`Attempt`].
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why a job failed, as far as the batch system can tell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The job itself failed, e.g. its program exited with an error.
+    #[default]
+    Application,
+
+    /// The job hit its time limit.
+    Timeout,
+
+    /// The node running the job failed.
+    NodeFailure,
+
+    /// The job ran out of memory.
+    OutOfMemory,
+
+    /// The job could not be handed to the batch system or backend.
+    Submission,
+
+    /// The job's results failed validation, see [`crate::validate`].
+    InvalidResult,
+}
+
+impl FailureKind {
+    /// Get the name of this kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FailureKind::Application => "application",
+            FailureKind::Timeout => "timeout",
+            FailureKind::NodeFailure => "node_failure",
+            FailureKind::OutOfMemory => "out_of_memory",
+            FailureKind::Submission => "submission",
+            FailureKind::InvalidResult => "invalid_result",
+        }
+    }
+}
+
+impl std::fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Exponential delay between attempts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Backoff {
+    /// Delay before the first retry (seconds).
+    pub initial_secs: u64,
+
+    /// Factor the delay grows by with each retry.
+    pub multiplier: f64,
+
+    /// Upper bound on the delay (seconds).
+    pub max_secs: u64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_secs: 30,
+            multiplier: 2.0,
+            max_secs: 3_600,
+        }
+    }
+}
+
+impl Backoff {
+    /// Create a backoff starting at `initial_secs` and doubling each retry.
+    pub fn exponential(initial_secs: u64) -> Self {
+        Self {
+            initial_secs,
+            ..Default::default()
+        }
+    }
+
+    /// Set the factor the delay grows by.
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Set the upper bound on the delay.
+    pub fn with_max_secs(mut self, max_secs: u64) -> Self {
+        self.max_secs = max_secs;
+        self
+    }
+
+    /// Delay before retry number `retry`, counting from 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponent = i32::try_from(retry.saturating_sub(1)).unwrap_or(i32::MAX);
+        let secs = self.initial_secs as f64 * self.multiplier.powi(exponent);
+        Duration::from_secs_f64(secs.min(self.max_secs as f64))
+    }
+}
+
+/// When and how often a failed job is retried.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first.
+    pub max_attempts: u32,
+
+    /// Delay between attempts.
+    pub backoff: Backoff,
+
+    /// Failure kinds that are retried.
+    pub retry_on: Vec<FailureKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: Backoff::default(),
+            retry_on: vec![
+                FailureKind::Timeout,
+                FailureKind::NodeFailure,
+                FailureKind::InvalidResult,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy allowing `max_attempts` attempts on timeouts, node
+    /// failures and invalid results.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// Set the delay between attempts.
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the failure kinds that are retried.
+    pub fn retry_on(mut self, kinds: impl IntoIterator<Item = FailureKind>) -> Self {
+        self.retry_on = kinds.into_iter().collect();
+        self
+    }
+
+    /// Delay before the next attempt, or `None` if a job that failed with
+    /// `kind` after `attempts` attempts is not retried.
+    pub fn next_delay(&self, kind: FailureKind, attempts: u32) -> Option<Duration> {
+        (attempts < self.max_attempts && self.retry_on.contains(&kind))
+            .then(|| self.backoff.delay(attempts))
+    }
+}
+
+/// A failed attempt at running a job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    /// Attempt number, counting from 1.
+    pub number: u32,
+
+    /// Batch job that ran the attempt, if it got that far.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch_job_id: Option<String>,
+
+    /// Backend the attempt was matched to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+
+    /// How the attempt failed.
+    pub kind: FailureKind,
+
+    /// Failure reason reported for the attempt.
+    pub reason: String,
+
+    /// When the attempt was handed to the batch system.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<DateTime<Utc>>,
+
+    /// When the attempt was seen to fail.
+    pub failed_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_to_cap() {
+        let backoff = Backoff::exponential(10).with_max_secs(60);
+        assert_eq!(backoff.delay(1), Duration::from_secs(10));
+        assert_eq!(backoff.delay(2), Duration::from_secs(20));
+        assert_eq!(backoff.delay(3), Duration::from_secs(40));
+        assert_eq!(backoff.delay(4), Duration::from_secs(60));
+        assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_policy_retries_listed_kinds_only() {
+        let policy = RetryPolicy::new(3).with_backoff(Backoff::exponential(5));
+        assert_eq!(
+            policy.next_delay(FailureKind::NodeFailure, 1),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            policy.next_delay(FailureKind::Timeout, 2),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(policy.next_delay(FailureKind::Timeout, 3), None);
+        assert_eq!(policy.next_delay(FailureKind::Application, 1), None);
+
+        let policy = policy.retry_on([FailureKind::Application]);
+        assert!(policy.next_delay(FailureKind::Application, 1).is_some());
+        assert!(policy.next_delay(FailureKind::NodeFailure, 1).is_none());
+    }
+
+    #[test]
+    fn test_policy_defaults_when_deserialized() {
+        let policy: RetryPolicy = serde_json::from_str(
+            r#"{"max_attempts": 5, "retry_on": ["node_failure", "out_of_memory"],
+                "backoff": {"initial_secs": 60}}"#,
+        )
+        .unwrap();
+        assert_eq!(policy.max_attempts, 5);
+        assert_eq!(policy.backoff.multiplier, 2.0);
+        assert_eq!(
+            policy.retry_on,
+            vec![FailureKind::NodeFailure, FailureKind::OutOfMemory]
+        );
+    }
+}
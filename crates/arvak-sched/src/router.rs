@@ -3,6 +3,11 @@
 //! The router examines job properties (qubit count, shots, priority, topology
 //! preference) and routes them to the best execution target: cloud backend,
 //! HPC scheduler, or local simulator.
+//!
+//! Jobs too wide for local statevector simulation still take the local
+//! express lane when their entanglement is bounded: if every circuit's
+//! bond-dimension bound fits the local MPS engine, it simulates them
+//! exactly without a round trip to the cloud.
 
 use serde::{Deserialize, Serialize};
 use tracing::debug;
@@ -40,6 +45,11 @@ impl RouteTarget {
 pub struct RoutingRules {
     /// Maximum qubits for local simulation.
     pub local_qubit_limit: u32,
+    /// Maximum qubits for local MPS simulation of low-entanglement jobs.
+    pub local_mps_qubit_limit: u32,
+    /// Largest bond-dimension bound the local MPS engine accepts; `0`
+    /// disables the MPS express lane.
+    pub local_max_bond_dimension: u64,
     /// Maximum qubits for cloud execution.
     pub cloud_qubit_limit: u32,
     /// Preferred cloud backend.
@@ -52,6 +62,8 @@ impl Default for RoutingRules {
     fn default() -> Self {
         Self {
             local_qubit_limit: 25,
+            local_mps_qubit_limit: 128,
+            local_max_bond_dimension: 64,
             cloud_qubit_limit: 100,
             default_cloud_backend: "iqm".into(),
             prefer_hpc_for_large_jobs: true,
@@ -106,6 +118,18 @@ impl JobRouter {
             return RouteTarget::Local;
         }
 
+        // Wide but weakly entangled circuits: local MPS express lane
+        if num_qubits <= self.rules.local_mps_qubit_limit {
+            let bound = job.max_bond_dimension_bound().unwrap_or(u64::MAX);
+            if bound <= self.rules.local_max_bond_dimension {
+                debug!(
+                    "Routing to local MPS (qubits={}, bond dimension bound={} <= {})",
+                    num_qubits, bound, self.rules.local_max_bond_dimension
+                );
+                return RouteTarget::Local;
+            }
+        }
+
         // Large circuits: prefer HPC if enabled
         if self.rules.prefer_hpc_for_large_jobs && num_qubits > self.rules.cloud_qubit_limit {
             debug!(
@@ -142,6 +166,23 @@ mod tests {
         for i in 0..n.saturating_sub(1) {
             qasm.push_str(&format!(" cx q[{}], q[{}];", i, i + 1));
         }
+        // All-to-all layer, too entangled for MPS
+        for i in 0..n {
+            qasm.push_str(&format!(" swap q[{}], q[{}];", i, n - 1 - i));
+        }
+        CircuitSpec::from_qasm(qasm)
+    }
+
+    fn ring_qasm(n: usize) -> CircuitSpec {
+        let mut qasm = format!("OPENQASM 3.0; qubit[{}] q;", n);
+        for i in 0..n {
+            qasm.push_str(&format!(
+                " h q[{}]; rzz(0.8) q[{}], q[{}];",
+                i,
+                i,
+                (i + 1) % n
+            ));
+        }
         CircuitSpec::from_qasm(qasm)
     }
 
@@ -166,6 +207,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_route_low_entanglement_to_local_mps() {
+        let router = JobRouter::new();
+        let job = ScheduledJob::new("qaoa", ring_qasm(40)).with_shots(1000);
+        assert_eq!(router.route(&job), RouteTarget::Local);
+
+        // Disabled, or beyond the MPS qubit limit, it goes to the cloud
+        let rules = RoutingRules {
+            local_max_bond_dimension: 0,
+            ..RoutingRules::default()
+        };
+        assert!(matches!(
+            JobRouter::with_rules(rules).route(&job),
+            RouteTarget::Cloud { .. }
+        ));
+        let rules = RoutingRules {
+            local_mps_qubit_limit: 32,
+            ..RoutingRules::default()
+        };
+        assert!(matches!(
+            JobRouter::with_rules(rules).route(&job),
+            RouteTarget::Cloud { .. }
+        ));
+    }
+
     #[test]
     fn test_route_large_to_hpc() {
         let router = JobRouter::new();
@@ -178,6 +244,8 @@ mod tests {
     fn test_custom_rules() {
         let rules = RoutingRules {
             local_qubit_limit: 5,
+            local_mps_qubit_limit: 5,
+            local_max_bond_dimension: 64,
             cloud_qubit_limit: 50,
             default_cloud_backend: "cudaq".into(),
             prefer_hpc_for_large_jobs: true,
@@ -1,44 +1,111 @@
 //! HPC Scheduler implementation.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use arvak_hal::{Backend, ExecutionResult};
+use arvak_hal::{Backend, ExecutionResult, NoiseModel, PartialResult};
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 use tokio::time::interval;
 
+use crate::acl::{AccessPolicy, AuditEvent, AuditLog, Delegation, JobAction, TracingAuditLog};
+use crate::admission::{Admission, DEFAULT_CONGESTED_QUEUE_DEPTH};
+use crate::array::JobArraySpec;
+use crate::batch::BatchSystem;
+use crate::breaker::{BreakerConfig, BreakerEvent, BreakerState, FailureBreaker};
+use crate::cache;
+use crate::calibration::BackendCalibration;
+use crate::cloud::{CLOUD_JOB_ID, CloudQpuAdapter};
+use crate::compile::{CompileConfig, CompileStage};
 use crate::error::{SchedError, SchedResult};
+use crate::events::{EventKind, EventLog, NullEventLog, SchedulerEvent};
+use crate::explain::{BackendAvailability, BatchHoldKind, Hold, QueueExplanation};
+use crate::fairshare::{FairShareConfig, FairSharePolicy};
+use crate::hooks::{HookErrorPolicy, HookRegistry, SchedulerHook};
+use crate::id::ResolvedId;
+use crate::iteration::IterationRecord;
 use crate::job::{
     CircuitSpec, JobFilter, Priority, ResourceRequirements, ScheduledJob, ScheduledJobId,
     ScheduledJobStatus,
 };
-use crate::matcher::{Matcher, ResourceMatcher};
-use crate::pbs::{PbsAdapter, PbsConfig, PbsState};
-use crate::persistence::StateStore;
-use crate::queue::PriorityQueue;
-use crate::slurm::{SlurmAdapter, SlurmConfig, SlurmState};
-use crate::workflow::{Workflow, WorkflowBuilder, WorkflowId, WorkflowStatus};
+use crate::k8s::{KubernetesAdapter, KubernetesConfig};
+use crate::leader::LeaderElector;
+use crate::lineage::JobLineage;
+use crate::logs::LogTail;
+use crate::lsf::{LsfAdapter, LsfConfig};
+use crate::matcher::{Matcher, ResourceMatcher, esp_builder};
+use crate::negotiate::{CapabilityReport, CapabilityRequest, Negotiation, negotiate};
+use crate::notify::{AdaptivePoll, CompletionWatcher, StatusUpdate};
+use crate::partial::{ResultUpdate, read_snapshot};
+use crate::payload::{CircuitProvenance, CircuitResult};
+use crate::pbs::{PbsAdapter, PbsConfig};
+use crate::persistence::{RecoveryReport, StateStore, WalOp, WriteAheadLog};
+use crate::preempt::{PreemptionConfig, PreemptionPolicy, RESOURCE_REASONS};
+use crate::queue::{DispatchGate, PriorityQueue, QueuePolicy};
+use crate::recurring::{RecurringJob, RecurringRun};
+use crate::reload::{ConfigChange, SchedulerConfigUpdate};
+use crate::reservation::{Reservation, TimeWindow};
+use crate::retry::{Attempt, FailureKind};
+use crate::session::Session;
+use crate::slurm::{SlurmAdapter, SlurmConfig};
+use crate::split::merge_results;
+use crate::task::{
+    ClassicalTask, LOCAL_TASK_ID, SUB_WORKFLOW_JOB_ID, TaskInputs, TaskRegistry, task_result,
+};
+use crate::template::{JobTemplate, TemplateOverrides};
+use crate::validate::{ResultValidation, ValidationAction};
+use crate::workflow::{
+    LoopDecision, SubWorkflow, Workflow, WorkflowBuilder, WorkflowId, WorkflowProgress,
+    WorkflowStatus,
+};
+
+/// Polls of a job after a status event before the event is dropped
+/// without having changed the job's status.
+const EVENT_POLLS: u32 = 5;
+
+/// How often the leader rescans all pending jobs in the store instead of
+/// only those created since its last sync, to pick up jobs requeued by
+/// other instances.
+const FULL_QUEUE_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How far incremental queue syncs look back before the previous sync, to
+/// tolerate clock skew between instances sharing the store.
+const QUEUE_SYNC_OVERLAP: Duration = Duration::from_secs(30);
+
+/// Progress of syncing the leader's queue from the store.
+#[derive(Debug, Default)]
+struct QueueSync {
+    /// When the previous sync started.
+    last: Option<chrono::DateTime<chrono::Utc>>,
+
+    /// When all pending jobs were last scanned.
+    last_full: Option<std::time::Instant>,
+}
 
 /// The type of HPC batch scheduler to use.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BatchSchedulerType {
     /// SLURM (Simple Linux Utility for Resource Management).
     #[default]
     Slurm,
     /// PBS (Portable Batch System) / Torque / PBS Pro.
     Pbs,
-}
-
-/// Enum to hold either SLURM or PBS adapter.
-enum BatchAdapter {
-    Slurm(SlurmAdapter),
-    Pbs(PbsAdapter),
+    /// IBM Spectrum LSF (Load Sharing Facility).
+    Lsf,
+    /// Kubernetes Jobs, for cloud and hybrid deployments.
+    Kubernetes,
 }
 
 /// Configuration for the HPC scheduler.
-#[derive(Debug, Clone)]
+///
+/// Can be loaded from the `[scheduler]` table of `arvak.toml`, see
+/// [`crate::config`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SchedulerConfig {
     /// Type of batch scheduler to use.
     pub scheduler_type: BatchSchedulerType,
@@ -49,17 +116,73 @@ pub struct SchedulerConfig {
     /// PBS configuration (used when scheduler_type is Pbs).
     pub pbs: PbsConfig,
 
-    /// Status polling interval in seconds.
+    /// LSF configuration (used when scheduler_type is Lsf).
+    pub lsf: LsfConfig,
+
+    /// Kubernetes configuration (used when scheduler_type is Kubernetes).
+    pub kubernetes: KubernetesConfig,
+
+    /// Status polling interval in seconds; while the batch system writes
+    /// status events, how often they are checked.
     pub poll_interval_secs: u64,
 
+    /// Longest interval in seconds between polls of all active jobs while
+    /// the batch system writes status events; see [`crate::notify`].
+    pub max_poll_interval_secs: u64,
+
     /// Maximum time to wait for a job (seconds).
     pub max_wait_time_secs: u64,
 
     /// Whether to automatically match resources on submit.
     pub auto_match_resources: bool,
 
+    /// How queued jobs are ordered, and how jobs behind a job that cannot
+    /// be placed are dispatched.
+    pub queue_policy: QueuePolicy,
+
+    /// Under the deadline queue policy, seconds after creation by which a
+    /// default-priority job without a deadline is treated as due, scaled
+    /// by priority; unset, such jobs come after every job with a deadline.
+    /// See [`PriorityQueue::with_deadline_horizon`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline_horizon_secs: Option<u64>,
+
+    /// Fair-share reweighting of queued jobs across users or projects;
+    /// disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fair_share: Option<FairShareConfig>,
+
+    /// Preemption of running jobs for urgent ones; disabled if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preemption: Option<PreemptionConfig>,
+
+    /// Checks on the results of quantum jobs that set none of their own;
+    /// results are not checked if unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result_validation: Option<ResultValidation>,
+
+    /// Batch node features each backend's control hardware is attached to,
+    /// e.g. `qctrl`, by backend name. Jobs matched to a backend are
+    /// constrained to nodes with all of them.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub node_features: BTreeMap<String, Vec<String>>,
+
     /// Working directory for scheduler state.
     pub state_dir: PathBuf,
+
+    /// Per-backend failure-rate breaker settings.
+    pub breaker: BreakerConfig,
+
+    /// Compile-on-submit pipeline; see [`CompileStage::from_config`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compile: Option<CompileConfig>,
+
+    /// Queue depth at which new jobs are admitted as congested.
+    pub congested_queue_depth: usize,
+
+    /// Log dispatches to `scheduler.wal` in `state_dir` and recover jobs
+    /// in flight on startup; see [`HpcScheduler::recover`].
+    pub write_ahead_log: bool,
 }
 
 impl Default for SchedulerConfig {
@@ -68,10 +191,23 @@ impl Default for SchedulerConfig {
             scheduler_type: BatchSchedulerType::default(),
             slurm: SlurmConfig::default(),
             pbs: PbsConfig::default(),
+            lsf: LsfConfig::default(),
+            kubernetes: KubernetesConfig::default(),
             poll_interval_secs: 30,
+            max_poll_interval_secs: 600,
             max_wait_time_secs: 86400, // 24 hours
             auto_match_resources: true,
+            queue_policy: QueuePolicy::default(),
+            deadline_horizon_secs: None,
+            fair_share: None,
+            preemption: None,
+            result_validation: None,
+            node_features: BTreeMap::new(),
             state_dir: PathBuf::from("/tmp/arvak-scheduler"),
+            breaker: BreakerConfig::default(),
+            compile: None,
+            congested_queue_depth: DEFAULT_CONGESTED_QUEUE_DEPTH,
+            write_ahead_log: false,
         }
     }
 }
@@ -94,6 +230,24 @@ impl SchedulerConfig {
             ..Default::default()
         }
     }
+
+    /// Create a configuration for LSF.
+    pub fn with_lsf(lsf: LsfConfig) -> Self {
+        Self {
+            scheduler_type: BatchSchedulerType::Lsf,
+            lsf,
+            ..Default::default()
+        }
+    }
+
+    /// Create a configuration for Kubernetes.
+    pub fn with_kubernetes(kubernetes: KubernetesConfig) -> Self {
+        Self {
+            scheduler_type: BatchSchedulerType::Kubernetes,
+            kubernetes,
+            ..Default::default()
+        }
+    }
 }
 
 /// Trait for scheduler implementations.
@@ -116,6 +270,11 @@ pub trait Scheduler: Send + Sync {
     async fn status(&self, job_id: &ScheduledJobId) -> SchedResult<ScheduledJobStatus>;
 
     /// Cancel a job.
+    ///
+    /// Queued jobs leave the queue, jobs handed to the batch system are
+    /// cancelled there, and queued jobs whose dependency condition can no
+    /// longer be met are cancelled with it. Fails if the job already
+    /// finished.
     async fn cancel(&self, job_id: &ScheduledJobId) -> SchedResult<()>;
 
     /// Wait for a job to complete and return the result.
@@ -138,17 +297,88 @@ pub trait Scheduler: Send + Sync {
 
     /// Wait for a workflow to complete.
     async fn wait_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<()>;
+
+    /// Cancel every unfinished job of a workflow and mark it cancelled;
+    /// returns the jobs that were cancelled.
+    ///
+    /// The default cancels each unfinished member job with
+    /// [`cancel`](Self::cancel) and leaves the workflow's status to its
+    /// jobs.
+    async fn cancel_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Vec<ScheduledJobId>> {
+        let mut cancelled = Vec::new();
+        for job in self.list_jobs(JobFilter::default()).await? {
+            if job.workflow_id.as_ref() != Some(workflow_id) || job.status.is_terminal() {
+                continue;
+            }
+            match self.cancel(&job.id).await {
+                Ok(()) => cancelled.push(job.id),
+                // Finished since it was listed, or cancelled with a
+                // dependency cancelled above
+                Err(SchedError::InvalidJobState { .. }) => {
+                    if matches!(self.status(&job.id).await?, ScheduledJobStatus::Cancelled) {
+                        cancelled.push(job.id);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(cancelled)
+    }
+
+    /// Re-run the jobs of a workflow that failed, were skipped or were
+    /// cancelled; completed jobs keep their stored results. Returns the
+    /// re-run jobs in topological order.
+    async fn resume_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Vec<ScheduledJobId>>;
+
+    /// Reserve `capacity` batch nodes for jobs on `backend` during
+    /// `window`; jobs run in it with
+    /// [`ScheduledJob::with_reservation`]. Fails if the window overlaps
+    /// another reservation of the backend.
+    async fn reserve(
+        &self,
+        backend: &str,
+        window: TimeWindow,
+        capacity: u32,
+    ) -> SchedResult<Reservation>;
+
+    /// Release a reservation and cancel its unfinished jobs; returns the
+    /// jobs that were cancelled.
+    async fn release_reservation(&self, name: &str) -> SchedResult<Vec<ScheduledJobId>>;
 }
 
-/// HPC Scheduler with SLURM and PBS integration.
+/// HPC Scheduler with SLURM, PBS, LSF and Kubernetes integration.
 pub struct HpcScheduler {
-    config: SchedulerConfig,
-    adapter: BatchAdapter,
+    config: std::sync::RwLock<SchedulerConfig>,
+    batch: Arc<dyn BatchSystem>,
+    cloud: CloudQpuAdapter,
     matcher: ResourceMatcher,
     store: Arc<dyn StateStore>,
     queue: RwLock<PriorityQueue>,
     workflows: RwLock<rustc_hash::FxHashMap<WorkflowId, Workflow>>,
-    completed_jobs: RwLock<rustc_hash::FxHashSet<ScheduledJobId>>,
+    /// Finished jobs and whether they succeeded.
+    completed_jobs: RwLock<rustc_hash::FxHashMap<ScheduledJobId, bool>>,
+    leader: Option<Arc<LeaderElector>>,
+    queue_sync: std::sync::Mutex<QueueSync>,
+    tasks: TaskRegistry,
+    hooks: HookRegistry,
+    breaker: FailureBreaker,
+    fair_share: Option<FairSharePolicy>,
+    /// When each urgent job last preempted a running job.
+    preemptions: std::sync::Mutex<rustc_hash::FxHashMap<ScheduledJobId, std::time::Instant>>,
+    access: AccessPolicy,
+    audit: Arc<dyn AuditLog>,
+    events: Arc<dyn EventLog>,
+    compile_stage: Option<CompileStage>,
+    /// Latest partial result of each circuit of running jobs.
+    partials: RwLock<rustc_hash::FxHashMap<ScheduledJobId, Vec<ResultUpdate>>>,
+    results: tokio::sync::broadcast::Sender<ResultUpdate>,
+    statuses: tokio::sync::broadcast::Sender<StatusUpdate>,
+    /// Watcher of the batch system's status event files, if it writes any.
+    watcher: Option<CompletionWatcher>,
+    /// Held while reserving or admitting a job to a reservation, so
+    /// concurrent calls cannot overbook one.
+    reserving: tokio::sync::Mutex<()>,
+    wal: Option<Arc<WriteAheadLog>>,
 }
 
 impl HpcScheduler {
@@ -158,657 +388,6648 @@ impl HpcScheduler {
         backends: Vec<Arc<dyn Backend>>,
         store: Arc<dyn StateStore>,
     ) -> SchedResult<Self> {
-        let adapter = match config.scheduler_type {
-            BatchSchedulerType::Slurm => {
-                BatchAdapter::Slurm(SlurmAdapter::new(config.slurm.clone()).await?)
-            }
-            BatchSchedulerType::Pbs => {
-                BatchAdapter::Pbs(PbsAdapter::new(config.pbs.clone()).await?)
+        let batch: Arc<dyn BatchSystem> = match config.scheduler_type {
+            BatchSchedulerType::Slurm => Arc::new(SlurmAdapter::new(config.slurm.clone()).await?),
+            BatchSchedulerType::Pbs => Arc::new(PbsAdapter::new(config.pbs.clone()).await?),
+            BatchSchedulerType::Lsf => Arc::new(LsfAdapter::new(config.lsf.clone()).await?),
+            BatchSchedulerType::Kubernetes => {
+                Arc::new(KubernetesAdapter::new(config.kubernetes.clone()).await?)
             }
         };
-        let matcher = ResourceMatcher::new(backends);
-
-        Ok(Self {
-            config,
-            adapter,
-            matcher,
-            store,
-            queue: RwLock::new(PriorityQueue::new()),
-            workflows: RwLock::new(rustc_hash::FxHashMap::default()),
-            completed_jobs: RwLock::new(rustc_hash::FxHashSet::default()),
-        })
+        let wal_path = config
+            .write_ahead_log
+            .then(|| config.state_dir.join("scheduler.wal"));
+        let mut scheduler = Self::with_batch_system(config, batch, backends, store);
+        if let Some(path) = wal_path {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            scheduler = scheduler.with_write_ahead_log(Arc::new(WriteAheadLog::open(path)?));
+            let report = scheduler.recover().await?;
+            if !report.adopted.is_empty() || !report.requeued.is_empty() {
+                tracing::info!(
+                    "Recovered {} dispatched job(s), requeued {}",
+                    report.adopted.len(),
+                    report.requeued.len()
+                );
+            }
+        }
+        Ok(scheduler)
     }
 
-    /// Create a scheduler with a mock SLURM adapter (for testing).
-    pub fn with_mock_slurm(
+    /// Create a scheduler submitting to the given batch system, e.g. one
+    /// the crate does not provide. `config.scheduler_type` and the adapter
+    /// settings are ignored.
+    pub fn with_batch_system(
         config: SchedulerConfig,
+        batch: Arc<dyn BatchSystem>,
         backends: Vec<Arc<dyn Backend>>,
         store: Arc<dyn StateStore>,
     ) -> Self {
-        let adapter = BatchAdapter::Slurm(SlurmAdapter::mock(config.slurm.clone()));
-        let matcher = ResourceMatcher::new(backends);
+        let matcher =
+            ResourceMatcher::new(backends).with_node_features(config.node_features.clone());
+        let breaker = FailureBreaker::new(config.breaker.clone());
+        let watcher = batch.event_dir().map(CompletionWatcher::new);
+        let mut queue = PriorityQueue::new().with_policy(config.queue_policy);
+        if let Some(horizon) = config
+            .deadline_horizon_secs
+            .and_then(|secs| chrono::Duration::try_seconds(i64::try_from(secs).ok()?))
+        {
+            queue = queue.with_deadline_horizon(horizon);
+        }
+        let fair_share = config.fair_share.clone().map(FairSharePolicy::new);
 
         Self {
-            config,
-            adapter,
+            config: std::sync::RwLock::new(config),
+            batch,
+            cloud: CloudQpuAdapter::new(),
             matcher,
             store,
-            queue: RwLock::new(PriorityQueue::new()),
+            queue: RwLock::new(queue),
             workflows: RwLock::new(rustc_hash::FxHashMap::default()),
-            completed_jobs: RwLock::new(rustc_hash::FxHashSet::default()),
+            completed_jobs: RwLock::new(rustc_hash::FxHashMap::default()),
+            leader: None,
+            queue_sync: std::sync::Mutex::new(QueueSync::default()),
+            tasks: TaskRegistry::new(),
+            hooks: HookRegistry::new(),
+            breaker,
+            fair_share,
+            preemptions: std::sync::Mutex::new(rustc_hash::FxHashMap::default()),
+            access: AccessPolicy::new(),
+            audit: Arc::new(TracingAuditLog),
+            events: Arc::new(NullEventLog),
+            compile_stage: None,
+            partials: RwLock::new(rustc_hash::FxHashMap::default()),
+            results: tokio::sync::broadcast::channel(256).0,
+            statuses: tokio::sync::broadcast::channel(256).0,
+            watcher,
+            reserving: tokio::sync::Mutex::new(()),
+            wal: None,
         }
     }
 
+    /// Create a scheduler with a mock SLURM adapter (for testing).
+    pub fn with_mock_slurm(
+        config: SchedulerConfig,
+        backends: Vec<Arc<dyn Backend>>,
+        store: Arc<dyn StateStore>,
+    ) -> Self {
+        let batch = Arc::new(SlurmAdapter::mock(config.slurm.clone()));
+        Self::with_batch_system(config, batch, backends, store)
+    }
+
     /// Create a scheduler with a mock PBS adapter (for testing).
     pub fn with_mock_pbs(
         config: SchedulerConfig,
         backends: Vec<Arc<dyn Backend>>,
         store: Arc<dyn StateStore>,
     ) -> Self {
-        let adapter = BatchAdapter::Pbs(PbsAdapter::mock(config.pbs.clone()));
-        let matcher = ResourceMatcher::new(backends);
-
-        Self {
-            config,
-            adapter,
-            matcher,
-            store,
-            queue: RwLock::new(PriorityQueue::new()),
-            workflows: RwLock::new(rustc_hash::FxHashMap::default()),
-            completed_jobs: RwLock::new(rustc_hash::FxHashSet::default()),
-        }
+        let batch = Arc::new(PbsAdapter::mock(config.pbs.clone()));
+        Self::with_batch_system(config, batch, backends, store)
     }
 
-    /// Start the background job processing loop.
-    pub fn start_background_processor(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
-        let scheduler = self.clone();
-        let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
-
-        tokio::spawn(async move {
-            let mut ticker = interval(poll_interval);
-            loop {
-                ticker.tick().await;
-                if let Err(e) = scheduler.process_pending_jobs().await {
-                    tracing::error!("Error processing jobs: {}", e);
-                }
-                if let Err(e) = scheduler.update_job_statuses().await {
-                    tracing::error!("Error updating job statuses: {}", e);
-                }
-            }
-        })
+    /// Create a scheduler with a mock LSF adapter (for testing).
+    pub fn with_mock_lsf(
+        config: SchedulerConfig,
+        backends: Vec<Arc<dyn Backend>>,
+        store: Arc<dyn StateStore>,
+    ) -> Self {
+        let batch = Arc::new(LsfAdapter::mock(config.lsf.clone()));
+        Self::with_batch_system(config, batch, backends, store)
     }
 
-    /// Process pending jobs from the queue.
-    async fn process_pending_jobs(&self) -> SchedResult<()> {
-        let completed = self.completed_jobs.read().await;
-        let ready_jobs = {
-            let mut queue = self.queue.write().await;
-            queue.drain_ready(&completed)
-        };
+    /// Create a scheduler with a mock Kubernetes adapter (for testing).
+    pub fn with_mock_kubernetes(
+        config: SchedulerConfig,
+        backends: Vec<Arc<dyn Backend>>,
+        store: Arc<dyn StateStore>,
+    ) -> Self {
+        let batch = Arc::new(KubernetesAdapter::mock(config.kubernetes.clone()));
+        Self::with_batch_system(config, batch, backends, store)
+    }
 
-        for mut job in ready_jobs {
-            // Match resources if enabled
-            if self.config.auto_match_resources && job.matched_backend.is_none() {
-                match self.matcher.find_match(&job.requirements).await {
-                    Ok(match_result) => {
-                        job.matched_backend = Some(match_result.backend_name);
-                    }
-                    Err(e) => {
-                        tracing::warn!("Resource matching failed for job {}: {}", job.id, e);
-                        job.status = ScheduledJobStatus::Failed {
-                            reason: e.to_string(),
-                            slurm_job_id: None,
-                            quantum_job_id: None,
-                        };
-                        self.store.save_job(&job).await?;
-                        continue;
-                    }
-                }
-            }
+    /// Enable leader election for HA deployments sharing one store.
+    ///
+    /// Only the instance holding the dispatch lease submits jobs and polls
+    /// the batch system; other instances keep serving reads. The lease TTL
+    /// should span several poll intervals. Each submission first checks the
+    /// lease's fencing token, so a deposed leader cannot dispatch.
+    pub fn with_leader_election(mut self, elector: Arc<LeaderElector>) -> Self {
+        self.leader = Some(elector);
+        self
+    }
 
-            // Submit to batch scheduler (SLURM or PBS)
-            let submit_result = match &self.adapter {
-                BatchAdapter::Slurm(slurm) => slurm.submit(&job).await,
-                BatchAdapter::Pbs(pbs) => pbs.submit(&job).await,
-            };
+    /// Record ownership decisions to the given audit log instead of tracing.
+    pub fn with_audit_log(mut self, audit: Arc<dyn AuditLog>) -> Self {
+        self.audit = audit;
+        self
+    }
 
-            match submit_result {
-                Ok(batch_job_id) => {
-                    job.status = ScheduledJobStatus::SlurmQueued {
-                        slurm_job_id: batch_job_id,
-                    };
-                    job.submitted_at = Some(chrono::Utc::now());
-                    self.store.save_job(&job).await?;
-                    tracing::info!("Submitted job {} to batch scheduler", job.id);
-                }
-                Err(e) => {
-                    tracing::error!("Batch submission failed for job {}: {}", job.id, e);
-                    job.status = ScheduledJobStatus::Failed {
-                        reason: e.to_string(),
-                        slurm_job_id: None,
-                        quantum_job_id: None,
-                    };
-                    self.store.save_job(&job).await?;
-                }
-            }
+    /// Submit jobs matched to the adapter's cloud providers directly to
+    /// the provider instead of through the batch system.
+    ///
+    /// The providers are added to resource matching, so jobs are routed
+    /// across HPC and cloud backends by the same rules.
+    pub fn with_cloud_adapter(mut self, cloud: CloudQpuAdapter) -> Self {
+        for provider in cloud.providers() {
+            self.matcher.add_backend(provider.clone());
         }
+        self.cloud = cloud;
+        self
+    }
 
-        Ok(())
+    /// Record scheduling decisions to the given event log for later replay.
+    pub fn with_event_log(mut self, events: Arc<dyn EventLog>) -> Self {
+        self.events = events;
+        self
     }
 
-    /// Update statuses of running jobs.
-    async fn update_job_statuses(&self) -> SchedResult<()> {
-        let jobs = self.store.list_jobs(&JobFilter::running()).await?;
+    /// Log every dispatch to the given write-ahead log before taking it,
+    /// so [`HpcScheduler::recover`] can tell which jobs were in flight.
+    pub fn with_write_ahead_log(mut self, wal: Arc<WriteAheadLog>) -> Self {
+        self.wal = Some(wal);
+        self
+    }
 
-        for job in jobs {
-            if let Some(batch_job_id) = job.status.slurm_job_id() {
-                let new_status = match &self.adapter {
-                    BatchAdapter::Slurm(slurm) => match slurm.status(batch_job_id).await {
-                        Ok(info) => Some(self.map_slurm_status(&job, &info)),
-                        Err(e) => {
-                            tracing::warn!(
-                                "Failed to get status for SLURM job {}: {}",
-                                batch_job_id,
-                                e
-                            );
-                            None
-                        }
-                    },
-                    BatchAdapter::Pbs(pbs) => match pbs.status(batch_job_id).await {
-                        Ok(info) => Some(self.map_pbs_status(&job, &info)),
-                        Err(e) => {
-                            tracing::warn!(
-                                "Failed to get status for PBS job {}: {}",
-                                batch_job_id,
-                                e
-                            );
-                            None
-                        }
-                    },
-                };
+    /// Compile job circuits for the target on submission.
+    ///
+    /// Jobs are stored and dispatched with their compiled circuits; repeated
+    /// circuits are served from the stage's cache.
+    pub fn with_compile_stage(mut self, stage: CompileStage) -> Self {
+        self.compile_stage = Some(stage);
+        self
+    }
 
-                if let Some(new_status) = new_status {
-                    if new_status != job.status {
-                        self.store
-                            .update_status(&job.id, new_status.clone())
-                            .await?;
+    /// Get the compile-on-submit stage, if configured.
+    pub fn compile_stage(&self) -> Option<&CompileStage> {
+        self.compile_stage.as_ref()
+    }
 
-                        if new_status.is_terminal() {
-                            let mut completed = self.completed_jobs.write().await;
-                            completed.insert(job.id.clone());
-                        }
-                    }
-                }
-            }
+    /// Log a dispatch step to the write-ahead log, if configured.
+    async fn log_transition(&self, job_id: &ScheduledJobId, op: WalOp) -> SchedResult<()> {
+        match &self.wal {
+            Some(wal) => wal.append(job_id, op).await,
+            None => Ok(()),
         }
+    }
 
-        // Update workflow statuses
-        let mut workflows = self.workflows.write().await;
-        for workflow in workflows.values_mut() {
-            if !workflow.status.is_terminal() {
-                workflow.update_status();
-                self.store.save_workflow(workflow).await?;
-            }
+    /// Record a scheduling decision about a job.
+    fn record(&self, job_id: &ScheduledJobId, kind: EventKind) {
+        if let Some(status) = kind.status() {
+            // Nobody may be subscribed.
+            let _ = self.statuses.send(StatusUpdate {
+                job_id: job_id.clone(),
+                status,
+            });
         }
+        self.events
+            .record(SchedulerEvent::new(job_id.clone(), kind));
+    }
 
-        Ok(())
+    /// Record a status change of a job.
+    fn record_status(&self, job_id: &ScheduledJobId, status: &ScheduledJobStatus) {
+        self.record(
+            job_id,
+            EventKind::StatusChanged {
+                status: status.clone(),
+                reason: None,
+            },
+        );
     }
 
-    /// Map SLURM job state to scheduler job status.
-    fn map_slurm_status(
-        &self,
-        job: &ScheduledJob,
-        info: &crate::slurm::SlurmJobInfo,
-    ) -> ScheduledJobStatus {
-        let slurm_job_id = info.job_id.clone();
+    /// Get the ownership and delegation policy.
+    pub fn access_policy(&self) -> &AccessPolicy {
+        &self.access
+    }
 
-        match &info.state {
-            SlurmState::Pending => ScheduledJobStatus::SlurmQueued { slurm_job_id },
-            SlurmState::Running | SlurmState::Completing => {
-                ScheduledJobStatus::SlurmRunning { slurm_job_id }
-            }
-            SlurmState::Completed => {
-                // Job completed - in a real scenario, we'd read the result file
-                // and get the quantum job ID from it
-                ScheduledJobStatus::Completed {
-                    slurm_job_id,
-                    quantum_job_id: arvak_hal::JobId("completed".to_string()),
-                }
-            }
-            SlurmState::Failed | SlurmState::NodeFail | SlurmState::OutOfMemory => {
-                ScheduledJobStatus::Failed {
-                    reason: format!("SLURM job failed: {:?}", info.state),
-                    slurm_job_id: Some(slurm_job_id),
-                    quantum_job_id: job.status.quantum_job_id().cloned(),
-                }
-            }
-            SlurmState::Timeout => ScheduledJobStatus::Failed {
-                reason: "SLURM job timed out".to_string(),
-                slurm_job_id: Some(slurm_job_id),
-                quantum_job_id: job.status.quantum_job_id().cloned(),
-            },
-            SlurmState::Cancelled | SlurmState::Preempted => ScheduledJobStatus::Cancelled,
-            SlurmState::Unknown(state) => {
-                tracing::warn!("Unknown SLURM state: {}", state);
-                job.status.clone()
-            }
-        }
+    /// Grant a delegate rights on the owner's jobs.
+    pub fn grant(&self, owner: &str, delegate: &str, actions: &[JobAction]) -> Delegation {
+        let delegation = self.access.grant(owner, delegate, actions);
+        let actions: Vec<_> = delegation.actions.iter().map(|a| a.to_string()).collect();
+        self.audit
+            .record(AuditEvent::new(owner, "grant", true).with_detail(format!(
+                "{}: {}",
+                delegate,
+                actions.join(",")
+            )));
+        delegation
     }
 
-    /// Map PBS job state to scheduler job status.
-    fn map_pbs_status(
-        &self,
-        job: &ScheduledJob,
-        info: &crate::pbs::PbsJobInfo,
-    ) -> ScheduledJobStatus {
-        let pbs_job_id = info.job_id.clone();
+    /// Revoke all rights a delegate holds on the owner's jobs.
+    pub fn revoke(&self, owner: &str, delegate: &str) -> bool {
+        let revoked = self.access.revoke(owner, delegate).is_some();
+        self.audit
+            .record(AuditEvent::new(owner, "revoke", true).with_detail(delegate));
+        revoked
+    }
 
-        match &info.state {
-            PbsState::Queued | PbsState::Waiting | PbsState::Held => {
-                ScheduledJobStatus::SlurmQueued {
-                    slurm_job_id: pbs_job_id,
-                }
-            }
-            PbsState::Running | PbsState::Exiting | PbsState::ArrayRunning => {
-                ScheduledJobStatus::SlurmRunning {
-                    slurm_job_id: pbs_job_id,
-                }
-            }
-            PbsState::Completed => {
-                // Check exit status to determine if it was a success
-                if info.exit_status == Some(0) || info.exit_status.is_none() {
-                    ScheduledJobStatus::Completed {
-                        slurm_job_id: pbs_job_id,
-                        quantum_job_id: arvak_hal::JobId("completed".to_string()),
-                    }
-                } else {
-                    ScheduledJobStatus::Failed {
-                        reason: format!("PBS job failed with exit status {:?}", info.exit_status),
-                        slurm_job_id: Some(pbs_job_id),
-                        quantum_job_id: job.status.quantum_job_id().cloned(),
-                    }
-                }
-            }
-            PbsState::Failed => ScheduledJobStatus::Failed {
-                reason: "PBS job failed".to_string(),
-                slurm_job_id: Some(pbs_job_id),
-                quantum_job_id: job.status.quantum_job_id().cloned(),
-            },
-            PbsState::Suspended | PbsState::Transit => {
-                // Keep current status for suspended/transit jobs
-                job.status.clone()
-            }
-            PbsState::Unknown(state) => {
-                tracing::warn!("Unknown PBS state: {}", state);
-                job.status.clone()
+    /// Resolve any ID: a job or workflow ID, or a batch job or request ID
+    /// a job is known by.
+    pub async fn resolve_id(&self, id: &str) -> SchedResult<ResolvedId> {
+        if let Some(job_id) = self.store.resolve_job_id(id).await? {
+            return Ok(ResolvedId::Job(job_id));
+        }
+        if let Ok(workflow_id) = WorkflowId::parse(id) {
+            let known = self.workflows.read().await.contains_key(&workflow_id)
+                || self.store.load_workflow(&workflow_id).await?.is_some();
+            if known {
+                return Ok(ResolvedId::Workflow(workflow_id));
             }
         }
+        Err(SchedError::JobNotFound(id.to_string()))
     }
-}
 
-#[async_trait]
-impl Scheduler for HpcScheduler {
-    async fn submit(&self, mut job: ScheduledJob) -> SchedResult<ScheduledJobId> {
-        let job_id = job.id.clone();
-
-        // Check if job has unsatisfied dependencies
-        if !job.dependencies.is_empty() {
-            let completed = self.completed_jobs.read().await;
-            if !job.dependencies_satisfied(&completed) {
-                job.status = ScheduledJobStatus::WaitingOnDependencies;
+    /// Get the status of the job any of its IDs refers to, see
+    /// [`HpcScheduler::resolve_id`].
+    pub async fn status_by_any_id(
+        &self,
+        id: &str,
+    ) -> SchedResult<(ScheduledJobId, ScheduledJobStatus)> {
+        match self.resolve_id(id).await? {
+            ResolvedId::Job(job_id) => {
+                let status = self.status(&job_id).await?;
+                Ok((job_id, status))
             }
+            ResolvedId::Workflow(_) => Err(SchedError::InvalidJobState {
+                expected: "job ID".to_string(),
+                found: format!("workflow {}", id),
+            }),
         }
+    }
 
-        // Save to store
-        self.store.save_job(&job).await?;
-
-        // Add to queue
-        let mut queue = self.queue.write().await;
-        queue.push(job);
-
-        tracing::info!("Job {} submitted to scheduler", job_id);
-        Ok(job_id)
+    /// Submit the members of a job array; returns their IDs in task order.
+    ///
+    /// Members are queued like other jobs. Those dispatched in the same
+    /// pass to the same backend are submitted as one array job when the
+    /// batch system supports arrays, and one by one otherwise.
+    pub async fn submit_array(&self, spec: JobArraySpec) -> SchedResult<Vec<ScheduledJobId>> {
+        spec.validate()?;
+        let mut job_ids = Vec::with_capacity(spec.len());
+        for job in spec.into_jobs() {
+            job_ids.push(self.submit(job).await?);
+        }
+        Ok(job_ids)
     }
 
-    async fn submit_batch(
+    /// Submit a job in a session, pinned to the session's backend.
+    ///
+    /// Fails without submitting if the session is closed or expired.
+    pub async fn submit_in_session(
         &self,
-        name: &str,
-        circuits: Vec<CircuitSpec>,
-        shots: u32,
-        priority: Priority,
-        requirements: ResourceRequirements,
+        session: &mut Session,
+        job: ScheduledJob,
     ) -> SchedResult<ScheduledJobId> {
-        let job = ScheduledJob::batch(name, circuits)
-            .with_shots(shots)
-            .with_priority(priority)
-            .with_requirements(requirements);
-
-        self.submit(job).await
+        let job = session.admit(job)?;
+        let job_id = self.submit(job).await?;
+        session.record(job_id.clone());
+        Ok(job_id)
     }
 
-    async fn status(&self, job_id: &ScheduledJobId) -> SchedResult<ScheduledJobStatus> {
-        // Check queue first
-        {
-            let queue = self.queue.read().await;
-            if let Some(job) = queue.get(job_id) {
-                return Ok(job.status.clone());
+    /// Close a session and cancel its unfinished jobs; returns how many
+    /// were cancelled.
+    pub async fn cancel_session(&self, session: &mut Session) -> SchedResult<usize> {
+        session.close();
+        let mut cancelled = 0;
+        for job_id in session.jobs() {
+            if !self.status(job_id).await?.is_terminal() {
+                self.cancel(job_id).await?;
+                cancelled += 1;
             }
         }
+        Ok(cancelled)
+    }
 
-        // Check store
-        let job = self
-            .store
+    /// List the jobs derived from a job.
+    pub async fn list_children(&self, job_id: &ScheduledJobId) -> SchedResult<Vec<ScheduledJob>> {
+        self.store.list_children(job_id).await
+    }
+
+    /// Get the lineage of a job.
+    pub async fn lineage(&self, job_id: &ScheduledJobId) -> SchedResult<JobLineage> {
+        JobLineage::load(self.store.as_ref(), job_id).await
+    }
+
+    /// Load a job from the queue or the store.
+    async fn load_job(&self, job_id: &ScheduledJobId) -> SchedResult<ScheduledJob> {
+        if let Some(job) = self.queue.read().await.get(job_id) {
+            return Ok(job.clone());
+        }
+        self.store
             .load_job(job_id)
             .await?
-            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))
+    }
 
-        Ok(job.status)
+    /// Check that a principal may act on a job, recording the decision.
+    async fn authorize(
+        &self,
+        principal: &str,
+        job_id: &ScheduledJobId,
+        action: JobAction,
+    ) -> SchedResult<()> {
+        let job = self.load_job(job_id).await?;
+        let result = self.access.authorize(principal, &job, action);
+        let mut event =
+            AuditEvent::new(principal, action.to_string(), result.is_ok()).with_job(job_id.clone());
+        if let Some(owner) = job.owner {
+            event = event.with_detail(format!("owner: {}", owner));
+        }
+        self.audit.record(event);
+        result
     }
 
-    async fn cancel(&self, job_id: &ScheduledJobId) -> SchedResult<()> {
-        // Remove from queue if present
-        {
+    /// Cancel a job on behalf of a principal.
+    ///
+    /// The principal must own the job, hold a delegated cancel right from
+    /// its owner, or be an admin.
+    pub async fn cancel_as(&self, principal: &str, job_id: &ScheduledJobId) -> SchedResult<()> {
+        self.authorize(principal, job_id, JobAction::Cancel).await?;
+        self.cancel(job_id).await
+    }
+
+    /// Change the priority of a queued job.
+    pub async fn reprioritize(
+        &self,
+        job_id: &ScheduledJobId,
+        priority: Priority,
+    ) -> SchedResult<()> {
+        let updated = {
             let mut queue = self.queue.write().await;
-            if queue.remove(job_id).is_some() {
-                self.store
-                    .update_status(job_id, ScheduledJobStatus::Cancelled)
-                    .await?;
-                return Ok(());
+            if queue.update_priority(job_id, priority) {
+                queue.get(job_id).cloned()
+            } else {
+                None
             }
-        }
-
-        // Check if job is running on batch scheduler
-        let job = self
-            .store
-            .load_job(job_id)
-            .await?
-            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+        };
 
-        if let Some(batch_job_id) = job.status.slurm_job_id() {
-            match &self.adapter {
-                BatchAdapter::Slurm(slurm) => slurm.cancel(batch_job_id).await?,
-                BatchAdapter::Pbs(pbs) => pbs.cancel(batch_job_id).await?,
+        match updated {
+            Some(job) => self.store.save_job(&job).await,
+            None => {
+                let job = self.load_job(job_id).await?;
+                Err(SchedError::InvalidJobState {
+                    expected: "Pending".to_string(),
+                    found: job.status.to_string(),
+                })
             }
         }
+    }
 
-        self.store
-            .update_status(job_id, ScheduledJobStatus::Cancelled)
+    /// Change the priority of a queued job on behalf of a principal.
+    pub async fn reprioritize_as(
+        &self,
+        principal: &str,
+        job_id: &ScheduledJobId,
+        priority: Priority,
+    ) -> SchedResult<()> {
+        self.authorize(principal, job_id, JobAction::Reprioritize)
             .await?;
-
-        Ok(())
+        self.reprioritize(job_id, priority).await
     }
 
-    async fn wait(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
-        let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
-        let max_wait = Duration::from_secs(self.config.max_wait_time_secs);
-        let start = std::time::Instant::now();
+    /// Check that a principal is an admin, recording the decision.
+    fn authorize_admin(
+        &self,
+        principal: &str,
+        action: &str,
+        job_id: Option<&ScheduledJobId>,
+        detail: String,
+    ) -> SchedResult<()> {
+        let allowed = self.access.is_admin(principal);
+        let mut event = AuditEvent::new(principal, action, allowed).with_detail(detail);
+        if let Some(job_id) = job_id {
+            event = event.with_job(job_id.clone());
+        }
+        self.audit.record(event);
+        if allowed {
+            Ok(())
+        } else {
+            Err(SchedError::PermissionDenied(format!(
+                "{} may not {}: admin only",
+                principal, action
+            )))
+        }
+    }
+
+    /// Cancel a job's batch or cloud job or its running sub-workflow, if it
+    /// has one.
+    async fn cancel_remote(&self, job: &ScheduledJob) -> SchedResult<()> {
+        if let Some(batch_job_id) = job.status.slurm_job_id() {
+            if batch_job_id == SUB_WORKFLOW_JOB_ID {
+                if let Some(ClassicalTask::Workflow(sub)) = &job.task {
+                    let workflow_id = &sub.workflow.id;
+                    if self
+                        .workflow_status(workflow_id)
+                        .await
+                        .is_ok_and(|status| !status.is_terminal())
+                    {
+                        self.cancel_workflow_jobs(workflow_id).await?;
+                    }
+                }
+            } else if batch_job_id == CLOUD_JOB_ID {
+                self.cloud.cancel(job).await?;
+            } else {
+                self.batch.cancel(batch_job_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark a job cancelled in the store and among the finished jobs.
+    async fn mark_cancelled(
+        &self,
+        job_id: &ScheduledJobId,
+        reason: Option<String>,
+    ) -> SchedResult<()> {
+        self.store
+            .update_status(job_id, ScheduledJobStatus::Cancelled)
+            .await?;
+        self.record(
+            job_id,
+            EventKind::StatusChanged {
+                status: ScheduledJobStatus::Cancelled,
+                reason,
+            },
+        );
+        let mut completed = self.completed_jobs.write().await;
+        completed.insert(job_id.clone(), false);
+        Ok(())
+    }
+
+    /// Mark a job as having missed its deadline in the store and among the
+    /// finished jobs.
+    async fn mark_deadline_exceeded(
+        &self,
+        job_id: &ScheduledJobId,
+        deadline: chrono::DateTime<chrono::Utc>,
+        reason: String,
+    ) -> SchedResult<()> {
+        let status = ScheduledJobStatus::DeadlineExceeded { deadline };
+        self.store.update_status(job_id, status.clone()).await?;
+        self.record(
+            job_id,
+            EventKind::StatusChanged {
+                status,
+                reason: Some(reason),
+            },
+        );
+        let mut completed = self.completed_jobs.write().await;
+        completed.insert(job_id.clone(), false);
+        Ok(())
+    }
 
+    /// Cancel queued jobs whose dependency condition can no longer be met,
+    /// cascading to their own dependents; returns the cancelled jobs.
+    async fn cancel_unsatisfiable(&self) -> SchedResult<Vec<ScheduledJobId>> {
+        let mut cancelled = Vec::new();
         loop {
-            let status = self.status(job_id).await?;
+            let blocked = {
+                let completed = self.completed_jobs.read().await;
+                self.queue.write().await.drain_unsatisfiable(&completed)
+            };
+            if blocked.is_empty() {
+                return Ok(cancelled);
+            }
+            for job in blocked {
+                self.mark_cancelled(
+                    &job.id,
+                    Some("dependency condition can no longer be met".to_string()),
+                )
+                .await?;
+                cancelled.push(job.id);
+            }
+        }
+    }
 
-            if status.is_terminal() {
-                if status.is_success() {
-                    return self.result(job_id).await;
-                } else {
-                    return Err(SchedError::JobNotFound(format!(
-                        "Job {} failed or was cancelled: {:?}",
-                        job_id, status
-                    )));
+    /// Mark finished jobs in their workflows and update the workflows'
+    /// statuses.
+    ///
+    /// Sub-workflow nodes follow the status of their sub-workflows; a
+    /// finished sub-workflow finishes its node, so the parent workflow is
+    /// updated again.
+    async fn update_workflows(&self) -> SchedResult<()> {
+        // Jobs left unfinished by workflows that failed early, see
+        // `FailurePolicy::FailWorkflow`
+        let mut abandoned = Vec::new();
+        loop {
+            // Sub-workflows whose status changed, with their nodes
+            let mut changed = Vec::new();
+            {
+                let completed = self.completed_jobs.read().await;
+                let mut workflows = self.workflows.write().await;
+                for workflow in workflows.values_mut() {
+                    if !workflow.status.is_terminal() {
+                        let before = workflow.status.clone();
+                        let job_ids: Vec<ScheduledJobId> =
+                            workflow.job_ids().into_iter().cloned().collect();
+                        for job_id in job_ids {
+                            match completed.get(&job_id) {
+                                Some(true) => workflow.mark_completed(&job_id)?,
+                                Some(false) => workflow.mark_failed(&job_id)?,
+                                None => {}
+                            }
+                        }
+                        workflow.update_status();
+                        if workflow.status.is_terminal() {
+                            abandoned.extend(workflow.unfinished_jobs().into_iter().cloned());
+                        }
+                        if let Some(node_id) = &workflow.parent {
+                            if workflow.status != before {
+                                changed.push((
+                                    node_id.clone(),
+                                    workflow.id.clone(),
+                                    workflow.status.clone(),
+                                ));
+                            }
+                        }
+                        self.store.save_workflow(workflow).await?;
+                    }
                 }
             }
+            if changed.is_empty() {
+                break;
+            }
+            for (node_id, workflow_id, status) in changed {
+                self.follow_sub_workflow(&node_id, &workflow_id, status)
+                    .await?;
+            }
+        }
 
-            if start.elapsed() > max_wait {
-                return Err(SchedError::Timeout(format!(
-                    "Timeout waiting for job {}",
-                    job_id
-                )));
+        // Cancelling a job cancels its queued dependents, so check each time
+        for job_id in &abandoned {
+            if !self.status(job_id).await?.is_terminal() {
+                self.cancel(job_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Cancel a workflow's unfinished jobs, including the sub-workflows of
+    /// its nodes, and mark it cancelled. Returns the cancelled jobs.
+    async fn cancel_workflow_jobs(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> SchedResult<Vec<ScheduledJobId>> {
+        let job_ids: Vec<ScheduledJobId> = {
+            let mut workflows = self.workflows.write().await;
+            let workflow = workflows
+                .get_mut(workflow_id)
+                .ok_or_else(|| SchedError::WorkflowNotFound(workflow_id.to_string()))?;
+            // Cancelled before its jobs, so that cancelling them does not
+            // fail it
+            workflow.status = WorkflowStatus::Cancelled;
+            workflow.completed_at = Some(chrono::Utc::now());
+            workflow.job_ids().into_iter().cloned().collect()
+        };
+
+        let mut active = Vec::new();
+        for job_id in job_ids {
+            if !self.status(&job_id).await?.is_terminal() {
+                active.push(job_id);
             }
+        }
+        // Cancelling a job cancels its queued dependents, so check again
+        for job_id in &active {
+            if !self.status(job_id).await?.is_terminal() {
+                self.cancel(job_id).await?;
+            }
+        }
 
-            tokio::time::sleep(poll_interval).await;
+        let mut workflows = self.workflows.write().await;
+        if let Some(workflow) = workflows.get_mut(workflow_id) {
+            workflow.refresh_from(self.store.as_ref()).await?;
+            self.store.save_workflow(workflow).await?;
         }
+        Ok(active)
     }
 
-    async fn result(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
-        self.store
-            .load_result(job_id)
+    /// Start the sub-workflow of a node, or run it again from scratch if
+    /// the node ran before.
+    async fn start_sub_workflow(&self, node: &ScheduledJob, sub: SubWorkflow) -> SchedResult<()> {
+        let mut workflow = *sub.workflow;
+        let previous_run = match self.tracked_workflow(&workflow.id).await {
+            Ok(run) => Some(run),
+            Err(SchedError::WorkflowNotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        let Some(mut run) = previous_run else {
+            workflow.parent = Some(node.id.clone());
+            self.submit_workflow(workflow).await?;
+            return Ok(());
+        };
+
+        run.parent = Some(node.id.clone());
+        run.refresh_from(self.store.as_ref()).await?;
+        let reset: Vec<ScheduledJobId> = run.job_ids().into_iter().cloned().collect();
+        let previous: Vec<ScheduledJob> = reset
+            .iter()
+            .filter_map(|id| run.get_job(id).cloned())
+            .collect();
+        run.reset_jobs(&reset)?;
+        self.requeue_reset(
+            run,
+            previous,
+            &format!("sub-workflow {} runs again", node.name),
+        )
+        .await
+    }
+
+    /// Bring a sub-workflow node up to date with the status of its
+    /// sub-workflow.
+    async fn follow_sub_workflow(
+        &self,
+        job_id: &ScheduledJobId,
+        workflow_id: &WorkflowId,
+        status: WorkflowStatus,
+    ) -> SchedResult<()> {
+        let job = self.load_job(job_id).await?;
+        if job.status.is_terminal() {
+            return Ok(());
+        }
+        match status {
+            WorkflowStatus::Pending => Ok(()),
+            WorkflowStatus::Running => {
+                let running = ScheduledJobStatus::SlurmRunning {
+                    slurm_job_id: SUB_WORKFLOW_JOB_ID.to_string(),
+                };
+                if job.status != running {
+                    self.store.update_status(job_id, running.clone()).await?;
+                    self.record_status(job_id, &running);
+                }
+                Ok(())
+            }
+            WorkflowStatus::Completed => {
+                let output = self.sub_workflow_output(workflow_id).await;
+                self.finish_local_task(job, output).await
+            }
+            WorkflowStatus::Failed { reason } => {
+                let error =
+                    SchedError::Internal(format!("sub-workflow {} failed: {}", job.name, reason));
+                self.finish_local_task(job, Err(error)).await
+            }
+            WorkflowStatus::Cancelled => {
+                self.mark_cancelled(job_id, Some("sub-workflow cancelled".to_string()))
+                    .await
+            }
+        }
+    }
+
+    /// Output of a completed sub-workflow node: the results of the
+    /// sub-workflow's last jobs by name.
+    async fn sub_workflow_output(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> SchedResult<serde_json::Value> {
+        let workflow = self.tracked_workflow(workflow_id).await?;
+        let mut outputs = serde_json::Map::new();
+        for job in workflow.all_jobs() {
+            if !workflow.dependents(&job.id).is_empty() {
+                continue;
+            }
+            let result = match self.store.load_result(&job.id).await? {
+                Some(result) => Some(result),
+                None => match self.store.load_job(&job.id).await? {
+                    Some(stored) => self.batch_result(&stored).await,
+                    None => None,
+                },
+            };
+            if let Some(result) = result {
+                outputs.insert(job.name.clone(), serde_json::to_value(result)?);
+            }
+        }
+        Ok(serde_json::json!({
+            "workflow_id": workflow_id.to_string(),
+            "jobs": workflow.len(),
+            "outputs": outputs,
+        }))
+    }
+
+    /// Put a job back into the queue, e.g. when it is stuck on a node.
+    ///
+    /// Admin only. Any batch job it still has is cancelled (failures are
+    /// logged, since the batch system may have lost the job already), and
+    /// the job is matched and dispatched afresh. Completed jobs cannot be
+    /// requeued.
+    pub async fn force_requeue(
+        &self,
+        principal: &str,
+        job_id: &ScheduledJobId,
+        reason: &str,
+    ) -> SchedResult<()> {
+        self.authorize_admin(principal, "requeue", Some(job_id), reason.to_string())?;
+        let mut job = self.load_job(job_id).await?;
+        if matches!(job.status, ScheduledJobStatus::Completed { .. }) {
+            return Err(SchedError::InvalidJobState {
+                expected: "not Completed".to_string(),
+                found: job.status.to_string(),
+            });
+        }
+        if let Err(e) = self.cancel_remote(&job).await {
+            tracing::warn!("Could not cancel batch job of {}: {}", job_id, e);
+        }
+
+        job.reset();
+        self.store.save_job(&job).await?;
+        self.partials.write().await.remove(job_id);
+        self.completed_jobs.write().await.remove(job_id);
+        {
+            let mut queue = self.queue.write().await;
+            queue.remove(job_id);
+            queue.push(job);
+        }
+        self.record(
+            job_id,
+            EventKind::StatusChanged {
+                status: ScheduledJobStatus::Pending,
+                reason: Some(format!("requeued by {}: {}", principal, reason)),
+            },
+        );
+        Ok(())
+    }
+
+    /// Mark a job failed with a reason, e.g. when its result is known to be
+    /// bad or it can never finish.
+    ///
+    /// Admin only. Any batch job it still has is cancelled (failures are
+    /// logged), and jobs depending on it see it as failed.
+    pub async fn force_fail(
+        &self,
+        principal: &str,
+        job_id: &ScheduledJobId,
+        reason: &str,
+    ) -> SchedResult<()> {
+        self.authorize_admin(principal, "force_fail", Some(job_id), reason.to_string())?;
+        let mut job = self.load_job(job_id).await?;
+        if !job.status.is_terminal() {
+            if let Err(e) = self.cancel_remote(&job).await {
+                tracing::warn!("Could not cancel batch job of {}: {}", job_id, e);
+            }
+        }
+
+        job.status = ScheduledJobStatus::Failed {
+            reason: reason.to_string(),
+            kind: FailureKind::Application,
+            slurm_job_id: job.status.slurm_job_id().map(str::to_string),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        };
+        job.completed_at = Some(chrono::Utc::now());
+        self.run_finish_hooks(&mut job).await;
+        self.store.save_job(&job).await?;
+        self.queue.write().await.remove(job_id);
+        self.partials.write().await.remove(job_id);
+        self.completed_jobs
+            .write()
+            .await
+            .insert(job_id.clone(), false);
+        self.record(
+            job_id,
+            EventKind::StatusChanged {
+                status: job.status,
+                reason: Some(format!("failed by {}", principal)),
+            },
+        );
+        Ok(())
+    }
+
+    /// Cancel every job of an owner that has not started running: queued,
+    /// waiting on dependencies, or queued in the batch system.
+    ///
+    /// Admin only. Returns the cancelled jobs.
+    pub async fn purge_owner(
+        &self,
+        principal: &str,
+        owner: &str,
+    ) -> SchedResult<Vec<ScheduledJobId>> {
+        self.authorize_admin(principal, "purge", None, format!("owner: {}", owner))?;
+        let filter = JobFilter {
+            status: Some(
+                ["Pending", "WaitingOnDependencies", "SlurmQueued"]
+                    .map(String::from)
+                    .to_vec(),
+            ),
+            ..Default::default()
+        };
+        let purged: Vec<_> = self
+            .store
+            .list_jobs(&filter)
             .await?
-            .ok_or_else(|| SchedError::JobNotFound(format!("No result for job {}", job_id)))
+            .into_iter()
+            .filter(|job| job.owner.as_deref() == Some(owner))
+            .map(|job| job.id)
+            .collect();
+        for job_id in &purged {
+            self.cancel(job_id).await?;
+        }
+        Ok(purged)
     }
 
-    async fn list_jobs(&self, filter: JobFilter) -> SchedResult<Vec<ScheduledJob>> {
-        self.store.list_jobs(&filter).await
+    /// Rebuild the store's derived indices from the stored jobs.
+    ///
+    /// Admin only. Returns the number of jobs reindexed.
+    pub async fn rebuild_indices(&self, principal: &str) -> SchedResult<usize> {
+        self.authorize_admin(principal, "reindex", None, String::new())?;
+        self.store.rebuild_indices().await
     }
 
-    fn create_workflow(&self, name: &str) -> WorkflowBuilder {
-        WorkflowBuilder::new(name)
+    /// Summarize a workflow's progress with a critical-path ETA.
+    ///
+    /// Job states are refreshed from the store first, so running jobs are
+    /// reported as such. Workflows not tracked by this instance are loaded
+    /// from the store.
+    pub async fn workflow_progress(
+        &self,
+        workflow_id: &WorkflowId,
+    ) -> SchedResult<WorkflowProgress> {
+        let mut workflow = self.tracked_workflow(workflow_id).await?;
+        workflow.refresh_from(self.store.as_ref()).await?;
+        Ok(workflow.progress())
     }
 
-    async fn submit_workflow(&self, workflow: Workflow) -> SchedResult<WorkflowId> {
-        let workflow_id = workflow.id.clone();
+    /// Re-run part of a workflow, e.g. after fixing the bug that made a
+    /// job fail: the job and every job downstream of it are reset and
+    /// queued again, while upstream jobs keep their results.
+    ///
+    /// Batch jobs still held by reset jobs are cancelled (failures are
+    /// logged) and their old results dropped. Reset jobs bypass the result
+    /// cache, since they are meant to be computed afresh. Returns the reset
+    /// jobs in topological order.
+    pub async fn rerun_from(
+        &self,
+        workflow_id: &WorkflowId,
+        job_id: &ScheduledJobId,
+    ) -> SchedResult<Vec<ScheduledJobId>> {
+        let mut workflow = self.tracked_workflow(workflow_id).await?;
+        workflow.refresh_from(self.store.as_ref()).await?;
+        let previous: Vec<ScheduledJob> = workflow
+            .descendants(job_id)
+            .into_iter()
+            .filter_map(|id| workflow.get_job(id).cloned())
+            .collect();
+        let reset = workflow.reset_from(job_id)?;
+        self.requeue_reset(workflow, previous, &format!("re-run from job {}", job_id))
+            .await?;
+        tracing::info!(
+            "Re-running {} job(s) of workflow {} from job {}",
+            reset.len(),
+            workflow_id,
+            job_id
+        );
+        Ok(reset)
+    }
+
+    /// Get the tracked copy of a workflow, or load it from the store.
+    async fn tracked_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Workflow> {
+        let tracked = self.workflows.read().await.get(workflow_id).cloned();
+        match tracked {
+            Some(workflow) => Ok(workflow),
+            None => self
+                .store
+                .load_workflow(workflow_id)
+                .await?
+                .ok_or_else(|| SchedError::WorkflowNotFound(workflow_id.to_string())),
+        }
+    }
+
+    /// Queue jobs that were just reset in their workflow again, given their
+    /// copies from before the reset, and track the workflow.
+    ///
+    /// Batch jobs still held by the jobs are cancelled (failures are
+    /// logged) and their old results dropped.
+    async fn requeue_reset(
+        &self,
+        mut workflow: Workflow,
+        previous: Vec<ScheduledJob>,
+        reason: &str,
+    ) -> SchedResult<()> {
+        // Upstream jobs finished before this instance started tracking the
+        // workflow must still count as finished.
+        let finished = {
+            let mut completed = self.completed_jobs.write().await;
+            for job in workflow.all_jobs() {
+                if job.status.is_terminal() {
+                    completed.insert(job.id.clone(), job.status.is_success());
+                }
+            }
+            for job in &previous {
+                completed.remove(&job.id);
+            }
+            completed.clone()
+        };
+
+        for mut job in previous {
+            if !job.status.is_terminal() {
+                if let Err(e) = self.cancel_remote(&job).await {
+                    tracing::warn!("Could not cancel batch job of {}: {}", job.id, e);
+                }
+            }
+            self.queue.write().await.remove(&job.id);
+            self.partials.write().await.remove(&job.id);
+            self.store.delete_result(&job.id).await?;
+
+            job.reset();
+            job.cacheable = false;
+            if !job.dependencies_satisfied(&finished) {
+                job.status = ScheduledJobStatus::WaitingOnDependencies;
+            }
+            self.store.save_job(&job).await?;
+            self.record(
+                &job.id,
+                EventKind::StatusChanged {
+                    status: job.status.clone(),
+                    reason: Some(reason.to_string()),
+                },
+            );
+            workflow.refresh_job(job.clone());
+            self.queue.write().await.push(job);
+        }
 
-        // Save workflow
         self.store.save_workflow(&workflow).await?;
+        self.workflows
+            .write()
+            .await
+            .insert(workflow.id.clone(), workflow);
+        Ok(())
+    }
 
-        // Submit all jobs
-        for job in workflow.all_jobs() {
-            self.store.save_job(job).await?;
-            let mut queue = self.queue.write().await;
-            queue.push(job.clone());
+    /// Start the next iteration of a loop node: its body and the loop node
+    /// itself, carrying the updated loop state, are reset and queued again.
+    async fn restart_loop(&self, workflow_id: &WorkflowId, job: ScheduledJob) -> SchedResult<()> {
+        let Some(ClassicalTask::Loop(node)) = &job.task else {
+            return Err(SchedError::Internal(format!(
+                "Job {} is not a loop",
+                job.id
+            )));
+        };
+        let iteration = node.iteration + 1;
+        let mut reset = node.body.clone();
+        reset.push(job.id.clone());
+
+        let mut workflow = self.tracked_workflow(workflow_id).await?;
+        workflow.refresh_from(self.store.as_ref()).await?;
+        let previous: Vec<ScheduledJob> = reset
+            .iter()
+            .filter_map(|id| {
+                if id == &job.id {
+                    Some(job.clone())
+                } else {
+                    workflow.get_job(id).cloned()
+                }
+            })
+            .collect();
+        workflow.reset_jobs(&reset)?;
+        self.requeue_reset(
+            workflow,
+            previous,
+            &format!("iteration {} of loop {}", iteration, job.name),
+        )
+        .await?;
+        tracing::info!("Starting iteration {} of loop {}", iteration, job.id);
+        Ok(())
+    }
+
+    /// Loop nodes whose state a job reads: the job itself if it is a loop
+    /// node, or else the loop nodes of its workflow it is part of the body
+    /// of, as stored.
+    async fn enclosing_loops(&self, job: &ScheduledJob) -> SchedResult<Vec<ScheduledJob>> {
+        if matches!(job.task, Some(ClassicalTask::Loop(_))) {
+            return Ok(vec![job.clone()]);
         }
+        let Some(workflow_id) = &job.workflow_id else {
+            return Ok(Vec::new());
+        };
+        let enclosing = |workflow: &Workflow| -> Vec<ScheduledJobId> {
+            workflow
+                .loops()
+                .into_iter()
+                .filter(|(_, node)| node.body.contains(&job.id))
+                .map(|(loop_job, _)| loop_job.id.clone())
+                .collect()
+        };
+        let tracked = self.workflows.read().await.get(workflow_id).map(enclosing);
+        let loop_ids = match tracked {
+            Some(ids) => ids,
+            None => self
+                .store
+                .load_workflow(workflow_id)
+                .await?
+                .map(|workflow| enclosing(&workflow))
+                .unwrap_or_default(),
+        };
 
-        // Store workflow for tracking
-        {
-            let mut workflows = self.workflows.write().await;
-            workflows.insert(workflow_id.clone(), workflow);
+        let mut loops = Vec::with_capacity(loop_ids.len());
+        for loop_id in &loop_ids {
+            loops.extend(self.store.load_job(loop_id).await?);
         }
+        Ok(loops)
+    }
 
-        tracing::info!("Workflow {} submitted", workflow_id);
-        Ok(workflow_id)
+    /// Persist an iteration of a variational run under its workflow or
+    /// campaign id.
+    pub async fn record_iteration(&self, record: &IterationRecord) -> SchedResult<()> {
+        self.store.save_iteration(record).await
     }
 
-    async fn workflow_status(&self, workflow_id: &WorkflowId) -> SchedResult<WorkflowStatus> {
-        let workflows = self.workflows.read().await;
-        workflows
-            .get(workflow_id)
-            .map(|w| w.status.clone())
-            .ok_or_else(|| SchedError::WorkflowNotFound(workflow_id.to_string()))
+    /// Load the recorded iterations of a run, ordered by iteration.
+    pub async fn iterations(&self, run_id: &str) -> SchedResult<Vec<IterationRecord>> {
+        self.store.load_iterations(run_id).await
     }
 
-    async fn wait_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<()> {
-        let poll_interval = Duration::from_secs(self.config.poll_interval_secs);
-        let max_wait = Duration::from_secs(self.config.max_wait_time_secs);
-        let start = std::time::Instant::now();
+    /// Save a job template, replacing any template of the same name.
+    ///
+    /// A replaced template keeps its creation time.
+    pub async fn save_template(&self, mut template: JobTemplate) -> SchedResult<()> {
+        template.validate()?;
+        if let Some(existing) = self.store.load_template(&template.name).await? {
+            template.created_at = existing.created_at;
+        }
+        template.updated_at = chrono::Utc::now();
+        self.store.save_template(&template).await
+    }
+
+    /// Load a job template by name.
+    pub async fn template(&self, name: &str) -> SchedResult<JobTemplate> {
+        self.store
+            .load_template(name)
+            .await?
+            .ok_or_else(|| SchedError::TemplateNotFound(name.to_string()))
+    }
+
+    /// List the stored job templates, ordered by name.
+    pub async fn templates(&self) -> SchedResult<Vec<JobTemplate>> {
+        self.store.list_templates().await
+    }
+
+    /// Delete a job template; jobs created from it are unaffected.
+    pub async fn delete_template(&self, name: &str) -> SchedResult<()> {
+        if self.store.delete_template(name).await? {
+            Ok(())
+        } else {
+            Err(SchedError::TemplateNotFound(name.to_string()))
+        }
+    }
+
+    /// Submit a job created from a stored template.
+    ///
+    /// See [`ScheduledJob::from_template`].
+    pub async fn submit_template(
+        &self,
+        name: &str,
+        overrides: TemplateOverrides,
+    ) -> SchedResult<ScheduledJobId> {
+        let template = self.template(name).await?;
+        self.submit(ScheduledJob::from_template(&template, overrides))
+            .await
+    }
+
+    /// Save a recurring job, replacing any recurring job of the same name.
+    ///
+    /// A replaced recurring job keeps its creation time and run history.
+    /// Its next run is scheduled from now.
+    pub async fn save_recurring(&self, mut recurring: RecurringJob) -> SchedResult<()> {
+        recurring.validate()?;
+        if let Some(existing) = self.store.load_recurring(&recurring.name).await? {
+            recurring.created_at = existing.created_at;
+            recurring.history = existing.history;
+        }
+        let now = chrono::Utc::now();
+        recurring.advance(now);
+        recurring.updated_at = now;
+        self.store.save_recurring(&recurring).await
+    }
+
+    /// Load a recurring job by name.
+    pub async fn recurring_job(&self, name: &str) -> SchedResult<RecurringJob> {
+        self.store
+            .load_recurring(name)
+            .await?
+            .ok_or_else(|| SchedError::RecurringJobNotFound(name.to_string()))
+    }
+
+    /// List the stored recurring jobs, ordered by name.
+    pub async fn recurring_jobs(&self) -> SchedResult<Vec<RecurringJob>> {
+        self.store.list_recurring().await
+    }
+
+    /// Pause a recurring job; no runs are submitted until it is resumed.
+    pub async fn pause_recurring(&self, name: &str) -> SchedResult<()> {
+        self.set_recurring_paused(name, true).await
+    }
+
+    /// Resume a paused recurring job from its next scheduled time; runs
+    /// missed while paused are skipped.
+    pub async fn resume_recurring(&self, name: &str) -> SchedResult<()> {
+        self.set_recurring_paused(name, false).await
+    }
+
+    async fn set_recurring_paused(&self, name: &str, paused: bool) -> SchedResult<()> {
+        let mut recurring = self.recurring_job(name).await?;
+        if recurring.paused == paused {
+            return Ok(());
+        }
+        let now = chrono::Utc::now();
+        recurring.paused = paused;
+        recurring.advance(now);
+        recurring.updated_at = now;
+        self.store.save_recurring(&recurring).await
+    }
+
+    /// Delete a recurring job; jobs it submitted are unaffected.
+    pub async fn delete_recurring(&self, name: &str) -> SchedResult<()> {
+        if self.store.delete_recurring(name).await? {
+            Ok(())
+        } else {
+            Err(SchedError::RecurringJobNotFound(name.to_string()))
+        }
+    }
+
+    /// Submit a run of every recurring job that is due, returning the IDs
+    /// of the submitted jobs.
+    ///
+    /// The background processor calls this on each tick. A schedule that
+    /// fell behind, e.g. while no scheduler was running, submits one run
+    /// and continues from its next time after now.
+    pub async fn submit_due_recurring(&self) -> SchedResult<Vec<ScheduledJobId>> {
+        self.submit_recurring_due_at(chrono::Utc::now()).await
+    }
+
+    async fn submit_recurring_due_at(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> SchedResult<Vec<ScheduledJobId>> {
+        let mut submitted = Vec::new();
+        for mut recurring in self.store.list_recurring().await? {
+            let Some(scheduled_for) = recurring.next_run.filter(|_| recurring.is_due(now)) else {
+                continue;
+            };
+            let job = recurring.instantiate();
+            let job_id = job.id.clone();
+            let error = match self.submit(job).await {
+                Ok(_) => {
+                    submitted.push(job_id.clone());
+                    None
+                }
+                Err(e) => {
+                    tracing::warn!("Recurring job {} failed to submit: {}", recurring.name, e);
+                    Some(e.to_string())
+                }
+            };
+            recurring.record_run(RecurringRun {
+                job_id,
+                scheduled_for,
+                submitted_at: now,
+                error,
+            });
+            recurring.advance(now);
+            self.store.save_recurring(&recurring).await?;
+        }
+        Ok(submitted)
+    }
+
+    /// Load a reservation by name.
+    pub async fn reservation(&self, name: &str) -> SchedResult<Reservation> {
+        self.store
+            .load_reservation(name)
+            .await?
+            .ok_or_else(|| SchedError::ReservationNotFound(name.to_string()))
+    }
+
+    /// List the stored reservations, ordered by name.
+    pub async fn reservations(&self) -> SchedResult<Vec<Reservation>> {
+        self.store.list_reservations().await
+    }
+
+    /// Submit jobs that arrived together, one result per job in order.
+    ///
+    /// Each job is checked and saved on its own, then the accepted jobs
+    /// enter the queue under a single write lock.
+    pub(crate) async fn submit_all(
+        &self,
+        jobs: Vec<ScheduledJob>,
+    ) -> Vec<SchedResult<ScheduledJobId>> {
+        let mut results = Vec::with_capacity(jobs.len());
+        let mut accepted = Vec::with_capacity(jobs.len());
+        for job in jobs {
+            match self.prepare_submission(job).await {
+                Ok(job) => {
+                    results.push(Ok(job.id.clone()));
+                    accepted.push(job);
+                }
+                Err(e) => results.push(Err(e)),
+            }
+        }
+
+        let mut queue = self.queue.write().await;
+        for job in accepted {
+            tracing::info!("Job {} submitted to scheduler", job.id);
+            queue.push(job);
+        }
+        results
+    }
+
+    /// Check, compile and save a submitted job, returning it ready to
+    /// enter the queue.
+    async fn prepare_submission(&self, mut job: ScheduledJob) -> SchedResult<ScheduledJob> {
+        let job_id = job.id.clone();
+        job.validate_circuits()?;
+        self.hooks.run_pre_submit(&mut job).await?;
+
+        // Reserved jobs are checked against the window and held until it
+        // opens; the guard lives until the job is saved
+        let _admission = match job.reservation.clone() {
+            Some(name) => Some(self.admit_to_reservation(&mut job, &name).await?),
+            None => None,
+        };
+
+        if let Some(stage) = &self.compile_stage {
+            stage.compile_job(&mut job)?;
+        }
+
+        // Check if job has unsatisfied dependencies
+        if !job.dependencies.is_empty() {
+            let completed = self.completed_jobs.read().await;
+            if !job.dependencies_satisfied(&completed) {
+                job.status = ScheduledJobStatus::WaitingOnDependencies;
+            }
+        }
+
+        // Save to store
+        self.log_transition(&job_id, WalOp::Enqueue).await?;
+        self.store.save_job(&job).await?;
+        self.record(
+            &job_id,
+            EventKind::Submitted {
+                name: job.name.clone(),
+                priority: job.priority,
+                dependencies: job.dependencies.clone(),
+                status: job.status.clone(),
+            },
+        );
+        Ok(job)
+    }
+
+    /// Admit `job` to the reservation `name`: check that it fits, pin it
+    /// to the reservation's backend and hold it until the window opens,
+    /// with the end of the window as its deadline.
+    ///
+    /// The returned guard keeps other admissions out until the job is
+    /// saved.
+    async fn admit_to_reservation(
+        &self,
+        job: &mut ScheduledJob,
+        name: &str,
+    ) -> SchedResult<tokio::sync::MutexGuard<'_, ()>> {
+        let guard = self.reserving.lock().await;
+        let reservation = self.reservation(name).await?;
+        let committed: f64 = self
+            .store
+            .list_jobs(&JobFilter::default())
+            .await?
+            .iter()
+            .filter(|other| {
+                other.reservation.as_deref() == Some(name)
+                    && other.id != job.id
+                    && !other.status.is_terminal()
+            })
+            .map(ScheduledJob::expected_runtime_secs)
+            .sum();
+        reservation.admit(job, committed, chrono::Utc::now())?;
+
+        job.matched_backend = Some(reservation.backend.clone());
+        job.not_before = job.not_before.max(Some(reservation.window.start));
+        job.deadline = Some(
+            job.deadline
+                .map_or(reservation.window.end, |d| d.min(reservation.window.end)),
+        );
+        Ok(guard)
+    }
+
+    /// Parse a calibration file and apply it, see
+    /// [`BackendCalibration::from_file`] and
+    /// [`HpcScheduler::apply_calibration`].
+    pub async fn ingest_calibration(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        backend: Option<&str>,
+    ) -> SchedResult<BackendCalibration> {
+        let calibration = BackendCalibration::from_file(path.as_ref(), backend)?;
+        self.apply_calibration(calibration).await
+    }
+
+    /// Store a calibration snapshot of a backend under its next epoch and
+    /// start using it: the matcher sees the new calibration and the compile
+    /// stage compiles for the new epoch. Returns the snapshot with its
+    /// epoch.
+    ///
+    /// Fails if the backend is unknown or the snapshot does not fit it.
+    pub async fn apply_calibration(
+        &self,
+        mut calibration: BackendCalibration,
+    ) -> SchedResult<BackendCalibration> {
+        if !self
+            .matcher
+            .backend_names()
+            .contains(&calibration.backend.as_str())
+        {
+            return Err(SchedError::NoMatchingBackend(format!(
+                "cannot calibrate unknown backend {}",
+                calibration.backend
+            )));
+        }
+        let num_qubits = self
+            .matcher
+            .capabilities_of(&calibration.backend)
+            .await
+            .map(|caps| caps.num_qubits);
+        calibration.validate(num_qubits)?;
+
+        let latest = self
+            .store
+            .load_calibration(&calibration.backend, None)
+            .await?;
+        calibration.epoch = latest.map_or(0, |c| c.epoch) + 1;
+        self.store.save_calibration(&calibration).await?;
+        self.use_calibration(calibration.clone()).await;
+        tracing::info!(
+            "Backend {} calibration epoch {} ({} qubit(s), {} gate(s))",
+            calibration.backend,
+            calibration.epoch,
+            calibration.qubits.len(),
+            calibration.gates.len()
+        );
+        Ok(calibration)
+    }
+
+    /// Get the latest calibration of a backend, if it has one.
+    pub async fn calibration(&self, backend: &str) -> SchedResult<Option<BackendCalibration>> {
+        if let Some(calibration) = self.matcher.calibration_of(backend).await {
+            return Ok(Some(calibration));
+        }
+        self.store.load_calibration(backend, None).await
+    }
+
+    /// Get the noise model of a backend from its latest calibration, see
+    /// [`BackendCalibration::noise_model`].
+    pub async fn noise_model(&self, backend: &str) -> SchedResult<Option<NoiseModel>> {
+        Ok(self
+            .calibration(backend)
+            .await?
+            .map(|calibration| calibration.noise_model()))
+    }
+
+    /// Load the latest stored calibration of every backend into the matcher
+    /// and compile stage, e.g. after a restart. Returns the number of
+    /// backends with a calibration.
+    pub async fn restore_calibrations(&self) -> SchedResult<usize> {
+        let mut restored = 0;
+        let backends: Vec<String> = self
+            .matcher
+            .backend_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        for backend in backends {
+            if let Some(calibration) = self.store.load_calibration(&backend, None).await? {
+                self.use_calibration(calibration).await;
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    }
+
+    /// Hand a calibration to the matcher and the compile stage.
+    async fn use_calibration(&self, calibration: BackendCalibration) {
+        if let Some(stage) = &self.compile_stage {
+            stage.set_target_version(calibration.version());
+            stage.set_error_rates(calibration.error_rates());
+        }
+        self.matcher.set_calibration(calibration).await;
+    }
+
+    /// Explain why a job has not started yet.
+    ///
+    /// Reports the job's position in the scheduler queue with the jobs
+    /// ahead of it, the breaker state of the backends it may run on, and
+    /// its holds: unfinished dependencies, a retry backoff, paused or
+    /// missing backends, and the batch system's pending reason once it has
+    /// been dispatched.
+    pub async fn explain(&self, job_id: &ScheduledJobId) -> SchedResult<QueueExplanation> {
+        let job = self.load_job(job_id).await?;
+        let mut explanation = {
+            let completed = self.completed_jobs.read().await;
+            let queue = self.queue.read().await;
+            let queued: Vec<&ScheduledJob> = queue.iter().collect();
+            QueueExplanation::from_queue(&job, &queued, |dep| completed.contains_key(dep))
+        };
+
+        if let Some(until) = job.not_before.filter(|t| *t > chrono::Utc::now()) {
+            explanation.holds.push(match &job.reservation {
+                Some(name) if job.attempts.is_empty() => Hold::Reservation {
+                    name: name.clone(),
+                    opens: until,
+                },
+                _ => Hold::RetryBackoff {
+                    attempt: job.attempts.len() as u32 + 1,
+                    until,
+                },
+            });
+        }
+
+        if job.status.is_pending() && !job.is_classical() {
+            let auto_match = self.config().auto_match_resources;
+            let candidates = match &job.matched_backend {
+                Some(backend) => Ok(vec![backend.clone()]),
+                None if auto_match => self
+                    .matcher
+                    .find_all_matches(&job.requirements)
+                    .await
+                    .map(|matches| matches.into_iter().map(|m| m.backend_name).collect()),
+                None => Ok(Vec::new()),
+            };
+            match candidates {
+                Ok(names) if names.is_empty() && auto_match => {
+                    explanation.holds.push(Hold::NoMatchingBackend {
+                        message: format!(
+                            "No backend found with {} qubits",
+                            job.requirements.min_qubits
+                        ),
+                    });
+                }
+                Ok(names) => {
+                    explanation.backends = names
+                        .into_iter()
+                        .map(|backend| BackendAvailability {
+                            breaker: self.breaker.state(&backend),
+                            paused: self.breaker.is_paused(&backend),
+                            backend,
+                        })
+                        .collect();
+                    if !explanation.backends.is_empty()
+                        && explanation.backends.iter().all(|b| b.paused)
+                    {
+                        explanation.holds.push(Hold::BackendsPaused);
+                    }
+                }
+                Err(e) => explanation.holds.push(Hold::NoMatchingBackend {
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        if let ScheduledJobStatus::SlurmQueued { slurm_job_id } = &job.status {
+            match self.batch.pending_reason(slurm_job_id).await {
+                Ok(Some(reason)) => explanation.holds.push(Hold::BatchSystem {
+                    kind: BatchHoldKind::classify(&reason),
+                    reason,
+                }),
+                Ok(None) => {}
+                Err(e) => tracing::warn!(
+                    "Failed to get status for {} job {}: {}",
+                    self.batch.name(),
+                    slurm_job_id,
+                    e
+                ),
+            }
+        }
+
+        Ok(explanation)
+    }
+
+    /// Submit a job and report how it was admitted to the queue.
+    ///
+    /// Like [`Scheduler::submit`], but also returns the queue depth, the
+    /// job's position and estimated start, and its admission class, so
+    /// callers can back off while the queue is congested.
+    pub async fn submit_with_admission(&self, job: ScheduledJob) -> SchedResult<Admission> {
+        let job_id = self.submit(job).await?;
+        self.admission(&job_id).await
+    }
+
+    /// Submit a job and wait until it is dispatched, returning its
+    /// admission and its status once it left the queue.
+    ///
+    /// See [`HpcScheduler::wait_dispatched`].
+    pub async fn submit_and_wait_dispatched(
+        &self,
+        job: ScheduledJob,
+    ) -> SchedResult<(Admission, ScheduledJobStatus)> {
+        let admission = self.submit_with_admission(job).await?;
+        let status = self.wait_dispatched(&admission.job_id).await?;
+        Ok((admission, status))
+    }
+
+    /// Assess a job's admission against the current queue.
+    pub async fn admission(&self, job_id: &ScheduledJobId) -> SchedResult<Admission> {
+        let congested_depth = self.config().congested_queue_depth;
+        let now = chrono::Utc::now();
+        let completed = self.completed_jobs.read().await;
+        let queue = self.queue.read().await;
+        let Some(job) = queue.get(job_id) else {
+            // Already dispatched; fail only for unknown jobs.
+            if self.store.load_job(job_id).await?.is_none() {
+                return Err(SchedError::JobNotFound(job_id.to_string()));
+            }
+            return Ok(Admission::dispatched(job_id.clone(), queue.len(), now));
+        };
+        let queued: Vec<&ScheduledJob> = queue.iter().collect();
+        Ok(Admission::assess(
+            job,
+            &queued,
+            |dep| completed.contains_key(dep),
+            congested_depth,
+            now,
+        ))
+    }
+
+    /// Wait until a job leaves the scheduler queue and return its status.
+    ///
+    /// Returns once the job was handed to the batch system or a cloud
+    /// provider rather than when it was merely enqueued, or when it
+    /// finished without being dispatched, e.g. because it was cancelled.
+    pub async fn wait_dispatched(
+        &self,
+        job_id: &ScheduledJobId,
+    ) -> SchedResult<ScheduledJobStatus> {
+        let max_wait = Duration::from_secs(self.config().max_wait_time_secs);
+        let start = std::time::Instant::now();
+
+        loop {
+            let status = self.status(job_id).await?;
+            if !status.is_pending() {
+                return Ok(status);
+            }
+
+            if start.elapsed() > max_wait {
+                return Err(SchedError::Timeout(format!(
+                    "Timeout waiting for job {} to be dispatched",
+                    job_id
+                )));
+            }
+
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Get a snapshot of the current configuration.
+    pub fn config(&self) -> SchedulerConfig {
+        self.config
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.config().poll_interval_secs)
+    }
+
+    /// Apply a configuration update without restarting.
+    ///
+    /// Safe settings (polling intervals, wait timeout, resource matching,
+    /// congestion threshold, preemption, result validation) take effect on
+    /// the next loop iteration. Updates touching restart-only
+    /// settings are rejected as a whole with a diagnostic.
+    pub fn update_config(&self, update: &SchedulerConfigUpdate) -> SchedResult<Vec<ConfigChange>> {
+        let mut config = self.config.write().unwrap_or_else(|e| e.into_inner());
+        let changes = update.apply_to(&mut config)?;
+        for change in &changes {
+            tracing::info!("Scheduler config updated: {}", change);
+        }
+        Ok(changes)
+    }
+
+    /// Watch a JSON config update file and apply it whenever it changes.
+    ///
+    /// The file is checked every `check_every`. Rejected or unreadable updates
+    /// are logged and leave the running configuration unchanged.
+    pub fn watch_config(
+        self: Arc<Self>,
+        path: impl Into<PathBuf>,
+        check_every: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let path = path.into();
+        tokio::spawn(async move {
+            let mut last_modified = modified_time(&path).await;
+            let mut ticker = interval(check_every);
+            loop {
+                ticker.tick().await;
+                let modified = modified_time(&path).await;
+                if modified.is_none() || modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+
+                let result = match SchedulerConfigUpdate::from_file(&path).await {
+                    Ok(update) => self.update_config(&update),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Ignoring config file {}: {}", path.display(), e);
+                }
+            }
+        })
+    }
+
+    /// Register a closure for classical task nodes.
+    ///
+    /// Closures run on the scheduler's processing loop and receive the
+    /// results of the job's dependencies; their output is stored as the
+    /// job's result.
+    pub fn register_task<F, Fut>(&self, name: impl Into<String>, f: F)
+    where
+        F: Fn(TaskInputs) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = SchedResult<serde_json::Value>> + Send + 'static,
+    {
+        self.tasks.register(name, f);
+    }
+
+    /// Register a site hook to run at job lifecycle points.
+    ///
+    /// Hooks run in registration order; `policy` decides whether a failing
+    /// hook rejects the job or is only logged.
+    pub fn register_hook(&self, hook: Arc<dyn SchedulerHook>, policy: HookErrorPolicy) {
+        self.hooks.register(hook, policy);
+    }
+
+    /// Run the post-complete or on-failure hooks of a job that just finished.
+    ///
+    /// An aborting post-complete hook turns the job into a failure, after
+    /// which the on-failure hooks run.
+    async fn run_finish_hooks(&self, job: &mut ScheduledJob) {
+        if self.hooks.is_empty() {
+            return;
+        }
+        if job.status.is_success() {
+            let result = self.store.load_result(&job.id).await.ok().flatten();
+            if let Err(e) = self.hooks.run_post_complete(job, result.as_ref()).await {
+                tracing::warn!("Failing job {}: {}", job.id, e);
+                job.status = ScheduledJobStatus::Failed {
+                    reason: e.to_string(),
+                    kind: FailureKind::Application,
+                    slurm_job_id: job.status.slurm_job_id().map(str::to_string),
+                    quantum_job_id: job.status.quantum_job_id().cloned(),
+                };
+            }
+        }
+        if let ScheduledJobStatus::Failed { reason, .. } = &job.status {
+            if let Err(e) = self.hooks.run_on_failure(job, reason).await {
+                tracing::error!("Job {}: {}", job.id, e);
+            }
+        }
+    }
+
+    /// Subscribe to operator alerts from the per-backend failure breaker.
+    pub fn breaker_events(&self) -> tokio::sync::broadcast::Receiver<BreakerEvent> {
+        self.breaker.subscribe()
+    }
+
+    /// Subscribe to result updates.
+    ///
+    /// Running jobs report the counts accumulated so far as non-final
+    /// updates; a final update follows once a job's complete result is
+    /// stored.
+    pub fn subscribe_results(&self) -> tokio::sync::broadcast::Receiver<ResultUpdate> {
+        self.results.subscribe()
+    }
+
+    /// Stream a job's status: its current status, then every change until
+    /// it reaches a terminal state.
+    ///
+    /// Changes are pushed as the scheduler applies them, so subscribers do
+    /// not poll. A subscriber that falls behind skips to the job's stored
+    /// status.
+    pub async fn subscribe(
+        &self,
+        job_id: &ScheduledJobId,
+    ) -> SchedResult<impl futures::Stream<Item = ScheduledJobStatus> + Send + 'static> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        // Subscribe first so no change after the current status is missed.
+        let updates = self.statuses.subscribe();
+        let current = self.status(job_id).await?;
+        let store = self.store.clone();
+        let job_id = job_id.clone();
+
+        let state = (updates, Some(current), None::<ScheduledJobStatus>);
+        Ok(futures::stream::unfold(Some(state), move |state| {
+            let store = store.clone();
+            let job_id = job_id.clone();
+            async move {
+                let (mut updates, mut next, last) = state?;
+                let status = loop {
+                    let status = match next.take() {
+                        Some(status) => status,
+                        None => match updates.recv().await {
+                            Ok(update) if update.job_id == job_id => update.status,
+                            Ok(_) => continue,
+                            Err(RecvError::Lagged(_)) => {
+                                store.load_job(&job_id).await.ok()??.status
+                            }
+                            Err(RecvError::Closed) => return None,
+                        },
+                    };
+                    if last.as_ref() != Some(&status) {
+                        break status;
+                    }
+                };
+                let state = (!status.is_terminal()).then(|| (updates, None, Some(status.clone())));
+                Some((status, state))
+            }
+        }))
+    }
+
+    /// Get the latest partial result of each circuit of a running job.
+    pub async fn partial_results(&self, job_id: &ScheduledJobId) -> Vec<ResultUpdate> {
+        self.partials
+            .read()
+            .await
+            .get(job_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Get the per-circuit results of a finished job, labelled and with
+    /// provenance in each result's metadata.
+    ///
+    /// Circuits split over several executions are merged back into one
+    /// result each; see [`crate::split`].
+    pub async fn circuit_results(
+        &self,
+        job_id: &ScheduledJobId,
+    ) -> SchedResult<Vec<CircuitResult>> {
+        let job = self
+            .store
+            .load_job(job_id)
+            .await?
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+
+        if job.status.slurm_job_id() == Some(CLOUD_JOB_ID) {
+            let Some(mut result) = self.store.load_result(job_id).await? else {
+                return Ok(Vec::new());
+            };
+            CircuitProvenance::new(&job, 0).attach(&mut result)?;
+            return Ok(vec![CircuitResult {
+                index: 0,
+                label: job.circuit_label(0),
+                result,
+            }]);
+        }
+
+        let results = self.batch.read_results(&job).await?;
+        merge_results(&job, results)
+    }
+
+    /// Follow the stdout and stderr of a job.
+    ///
+    /// The tail is empty for jobs not yet handed to the batch system, jobs
+    /// submitted to a cloud provider and batch systems without log files.
+    pub async fn job_logs(&self, job_id: &ScheduledJobId) -> SchedResult<LogTail> {
+        let job = self
+            .store
+            .load_job(job_id)
+            .await?
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+        if job.status.slurm_job_id() == Some(CLOUD_JOB_ID) {
+            return Ok(LogTail::new(Vec::new()));
+        }
+        Ok(LogTail::new(self.batch.log_paths(&job)))
+    }
+
+    /// Publish the snapshots a running job has written since the last poll.
+    async fn ingest_partial_results(&self, job: &ScheduledJob) {
+        let paths = self.batch.partial_paths(job);
+        if paths.is_empty() {
+            return;
+        }
+
+        let mut partials = self.partials.write().await;
+        let latest = partials.entry(job.id.clone()).or_default();
+        for (index, path) in paths.iter().enumerate() {
+            let mut partial = match read_snapshot(path) {
+                Ok(Some(partial)) => partial,
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("Failed to read partial result of job {}: {}", job.id, e);
+                    continue;
+                }
+            };
+            let seen = latest.iter().find(|u| u.circuit_index == index);
+            if seen.is_some_and(|u| u.partial.shots_completed >= partial.shots_completed) {
+                continue;
+            }
+            // Only the stored result is authoritative.
+            partial.is_final = false;
+            let update = ResultUpdate::new(job.id.clone(), index, partial);
+            latest.retain(|u| u.circuit_index != index);
+            latest.push(update.clone());
+            let _ = self.results.send(update);
+        }
+    }
+
+    /// Drop a finished job's partial results, publishing its final counts
+    /// if it produced a result.
+    async fn finish_partial_results(
+        &self,
+        job_id: &ScheduledJobId,
+        result: Option<&ExecutionResult>,
+    ) {
+        self.partials.write().await.remove(job_id);
+        if let Some(result) = result {
+            let update = ResultUpdate::new(job_id.clone(), 0, PartialResult::from_result(result));
+            let _ = self.results.send(update);
+        }
+    }
+
+    /// Get the fair-share policy, if fair-share scheduling is enabled.
+    pub fn fair_share(&self) -> Option<&FairSharePolicy> {
+        self.fair_share.as_ref()
+    }
+
+    /// Get the names of backends whose dispatch is paused by the breaker.
+    pub fn paused_backends(&self) -> Vec<String> {
+        self.breaker.paused_backends()
+    }
+
+    /// Check whether this instance may dispatch jobs.
+    ///
+    /// Always true when leader election is disabled.
+    pub fn is_leader(&self) -> bool {
+        self.leader.as_ref().is_none_or(|l| l.is_leader())
+    }
+
+    /// Check, before a write dispatching work, that this instance still
+    /// holds the dispatch lease under its fencing token, so a leader that
+    /// stalled past its lease expiry cannot dispatch after a takeover.
+    ///
+    /// Does nothing when leader election is disabled.
+    async fn check_fence(&self) -> SchedResult<()> {
+        match &self.leader {
+            Some(leader) => leader.check_fence().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Renew leadership and, on takeover, rebuild the queue from the store.
+    ///
+    /// Returns whether this instance should dispatch on this tick.
+    async fn refresh_leadership(&self) -> SchedResult<bool> {
+        let Some(leader) = &self.leader else {
+            return Ok(true);
+        };
+
+        let was_leader = leader.is_leader();
+        if !leader.tick().await? {
+            return Ok(false);
+        }
+
+        if !was_leader {
+            // Jobs queued locally while following may have been dispatched
+            // by the previous leader; the store is the source of truth.
+            self.queue.write().await.clear();
+            let finished = self
+                .store
+                .list_jobs(&JobFilter::default().with_status([
+                    "Completed",
+                    "CachedComplete",
+                    "Failed",
+                    "Cancelled",
+                    "DeadlineExceeded",
+                ]))
+                .await?;
+            let mut completed_jobs = self.completed_jobs.write().await;
+            completed_jobs.extend(
+                finished
+                    .into_iter()
+                    .map(|job| (job.id, job.status.is_success())),
+            );
+            drop(completed_jobs);
+            self.sync_queue_from_store().await?;
+        } else {
+            self.sync_new_jobs_from_store().await?;
+        }
+        Ok(true)
+    }
+
+    /// Enqueue all pending jobs from the store that are missing from the
+    /// queue.
+    async fn sync_queue_from_store(&self) -> SchedResult<()> {
+        self.sync_pending(JobFilter::pending(), true).await
+    }
+
+    /// Enqueue pending jobs created since the previous sync, falling back
+    /// to a full rescan every [`FULL_QUEUE_SYNC_INTERVAL`].
+    async fn sync_new_jobs_from_store(&self) -> SchedResult<()> {
+        let since = {
+            let sync = self.queue_sync.lock().unwrap_or_else(|e| e.into_inner());
+            sync.last
+                .filter(|_| {
+                    sync.last_full
+                        .is_some_and(|t| t.elapsed() < FULL_QUEUE_SYNC_INTERVAL)
+                })
+                .map(|last| {
+                    last - chrono::Duration::from_std(QUEUE_SYNC_OVERLAP).unwrap_or_default()
+                })
+        };
+        match since {
+            Some(since) => {
+                let filter = JobFilter {
+                    created_after: Some(since),
+                    ..JobFilter::pending()
+                };
+                self.sync_pending(filter, false).await
+            }
+            None => self.sync_queue_from_store().await,
+        }
+    }
+
+    async fn sync_pending(&self, filter: JobFilter, full: bool) -> SchedResult<()> {
+        let started = chrono::Utc::now();
+        let pending = self.store.list_jobs(&filter).await?;
+        let mut queue = self.queue.write().await;
+        for job in pending {
+            if !queue.contains(&job.id) {
+                queue.push(job);
+            }
+        }
+        drop(queue);
+
+        let mut sync = self.queue_sync.lock().unwrap_or_else(|e| e.into_inner());
+        sync.last = Some(started);
+        if full {
+            sync.last_full = Some(std::time::Instant::now());
+        }
+        Ok(())
+    }
+
+    /// Reconcile the store with the write-ahead log after a restart.
+    ///
+    /// Jobs logged as dispatched whose batch job ID the store lacks are
+    /// looked up on the batch system (see [`BatchSystem::find_submitted`]):
+    /// jobs found there are adopted under their batch job ID and polled,
+    /// the rest are queued again. The log is then cleared. Does nothing
+    /// without a write-ahead log.
+    ///
+    /// Only the SLURM adapter can find lost submissions, and not those of
+    /// job array members; other jobs are requeued and may run twice.
+    pub async fn recover(&self) -> SchedResult<RecoveryReport> {
+        let Some(wal) = &self.wal else {
+            return Ok(RecoveryReport::default());
+        };
+
+        let mut report = RecoveryReport::default();
+        for record in wal.open_records() {
+            let Some(mut job) = self.store.load_job(&record.job_id).await? else {
+                continue;
+            };
+            if job.status.is_terminal() || job.batch_job_id.is_some() {
+                continue;
+            }
+            let batch_job_id = match record.op {
+                WalOp::Dispatch => self.batch.find_submitted(&job).await?,
+                WalOp::SlurmIdAssigned { slurm_job_id } => Some(slurm_job_id),
+                WalOp::Enqueue | WalOp::Complete => continue,
+            };
+            let Some(batch_job_id) = batch_job_id else {
+                tracing::warn!(
+                    "Job {} was not found on {}; requeueing",
+                    job.id,
+                    self.batch.name()
+                );
+                report.requeued.push(job.id);
+                continue;
+            };
+
+            tracing::info!(
+                "Adopting job {} as {} job {}",
+                job.id,
+                self.batch.name(),
+                batch_job_id
+            );
+            self.record(
+                &job.id,
+                EventKind::Dispatched {
+                    batch_job_id: batch_job_id.clone(),
+                },
+            );
+            job.batch_job_id = Some(batch_job_id.clone());
+            job.status = ScheduledJobStatus::SlurmQueued {
+                slurm_job_id: batch_job_id.clone(),
+            };
+            job.submitted_at.get_or_insert_with(chrono::Utc::now);
+            self.store.save_job(&job).await?;
+            report.adopted.push((job.id.clone(), batch_job_id));
+            self.update_job_status(job).await?;
+        }
+
+        self.sync_queue_from_store().await?;
+        wal.checkpoint().await?;
+        Ok(report)
+    }
+
+    /// Start the background job processing loop.
+    pub fn start_background_processor(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let scheduler = self.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = scheduler.restore_calibrations().await {
+                tracing::error!("Error restoring calibrations: {}", e);
+            }
+            let mut poll_interval = scheduler.poll_interval();
+            let mut ticker = interval(poll_interval);
+            let mut fallback = scheduler.fallback_poll();
+            let mut events = BTreeMap::new();
+            loop {
+                ticker.tick().await;
+                let current = scheduler.poll_interval();
+                if current != poll_interval {
+                    poll_interval = current;
+                    ticker = interval(poll_interval);
+                    ticker.tick().await;
+                    fallback = scheduler.fallback_poll();
+                }
+                match scheduler.refresh_leadership().await {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        tracing::error!("Leader election failed: {}", e);
+                        continue;
+                    }
+                }
+                if let Err(e) = scheduler.submit_due_recurring().await {
+                    tracing::error!("Error submitting recurring jobs: {}", e);
+                }
+                if let Err(e) = scheduler.process_pending_jobs().await {
+                    tracing::error!("Error processing jobs: {}", e);
+                }
+                if let Err(e) = scheduler.preempt_for_urgent().await {
+                    tracing::error!("Error preempting jobs: {}", e);
+                }
+                // With status events, poll all jobs only as a fallback.
+                if scheduler.watcher.is_some() {
+                    match scheduler.process_status_events(&mut events).await {
+                        Ok(true) => fallback.event(std::time::Instant::now()),
+                        Ok(false) => {}
+                        Err(e) => tracing::error!("Error processing status events: {}", e),
+                    }
+                    if !fallback.is_due(std::time::Instant::now()) {
+                        continue;
+                    }
+                }
+                match scheduler.update_job_statuses().await {
+                    Ok(changed) => fallback.polled(std::time::Instant::now(), changed),
+                    Err(e) => tracing::error!("Error updating job statuses: {}", e),
+                }
+            }
+        })
+    }
+
+    /// Interval of fallback polls while status events arrive.
+    fn fallback_poll(&self) -> AdaptivePoll {
+        let max = Duration::from_secs(self.config().max_poll_interval_secs);
+        AdaptivePoll::new(self.poll_interval(), max)
+    }
+
+    /// Reorder the queue by fair share, loading usage history from the
+    /// store on first use.
+    async fn apply_fair_share(&self) -> SchedResult<()> {
+        let Some(policy) = &self.fair_share else {
+            return Ok(());
+        };
+        let now = chrono::Utc::now();
+        if !policy.is_loaded() {
+            let filter = JobFilter {
+                status: Some(
+                    ["Completed", "Failed", "Cancelled", "DeadlineExceeded"]
+                        .map(String::from)
+                        .to_vec(),
+                ),
+                created_after: Some(now - policy.horizon()),
+                ..Default::default()
+            };
+            let jobs = self.store.list_jobs(&filter).await?;
+            policy.load(&jobs, now);
+        }
+
+        let mut queue = self.queue.write().await;
+        let factors = policy.factors(queue.iter().map(|job| policy.account(job)), now);
+        queue.reweight(|job| {
+            let factor = factors.get(&policy.account(job)).copied().unwrap_or(1.0);
+            policy.adjust(job.priority, factor)
+        });
+        Ok(())
+    }
+
+    /// Process pending jobs from the queue.
+    async fn process_pending_jobs(&self) -> SchedResult<()> {
+        self.apply_fair_share().await?;
+        let mut completed = self.completed_jobs.write().await;
+        let (ready_jobs, blocked_jobs) = {
+            let mut queue = self.queue.write().await;
+            (
+                queue.drain_ready(&completed),
+                queue.drain_unsatisfiable(&completed),
+            )
+        };
+
+        for job in blocked_jobs {
+            tracing::info!(
+                "Cancelling job {}: dependency condition can no longer be met",
+                job.id
+            );
+            self.store
+                .update_status(&job.id, ScheduledJobStatus::Cancelled)
+                .await?;
+            self.record(
+                &job.id,
+                EventKind::StatusChanged {
+                    status: ScheduledJobStatus::Cancelled,
+                    reason: Some("dependency condition can no longer be met".to_string()),
+                },
+            );
+            completed.insert(job.id, false);
+        }
+
+        let mut dispatch = ready_jobs;
+        if self.batch.supports_dependencies() {
+            dispatch.extend(self.take_natively_chainable(&completed).await?);
+        }
+        drop(completed);
+
+        let now = chrono::Utc::now();
+        let mut gate = self.queue.read().await.dispatch_gate(now);
+        let mut held = Vec::new();
+        let mut arrays: BTreeMap<(String, Option<String>), Vec<ScheduledJob>> = BTreeMap::new();
+        for mut job in dispatch {
+            // Jobs backing off before a retry or waiting for their
+            // reservation to open wait in the queue
+            if job.not_before.is_some_and(|t| t > now) {
+                held.push(job);
+                continue;
+            }
+
+            if let Some(deadline) = job.deadline.filter(|_| job.misses_deadline(now)) {
+                tracing::info!(
+                    "Not dispatching job {}: it cannot finish by {}",
+                    job.id,
+                    deadline
+                );
+                self.mark_deadline_exceeded(
+                    &job.id,
+                    deadline,
+                    format!(
+                        "expected run time of {:.0}s exceeds the deadline",
+                        job.expected_runtime_secs()
+                    ),
+                )
+                .await?;
+                continue;
+            }
+
+            if job.cacheable && self.complete_from_cache(&mut job).await? {
+                continue;
+            }
+
+            if let Some(task) = job.task.clone() {
+                self.dispatch_classical(job, task).await?;
+                continue;
+            }
+
+            // Match resources if enabled, skipping backends paused by the breaker
+            if self.config().auto_match_resources && job.matched_backend.is_none() {
+                match self.select_backend(&mut job).await {
+                    Ok(Some(backend)) => {
+                        self.record(
+                            &job.id,
+                            EventKind::Matched {
+                                backend: backend.clone(),
+                            },
+                        );
+                        job.matched_backend = Some(backend);
+                    }
+                    Ok(None) => {
+                        self.record(
+                            &job.id,
+                            EventKind::Held {
+                                reason: "all matching backends are paused".to_string(),
+                            },
+                        );
+                        self.block_gate(&mut gate, &job).await;
+                        held.push(job);
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Resource matching failed for job {}: {}", job.id, e);
+                        job.status = ScheduledJobStatus::Failed {
+                            reason: e.to_string(),
+                            kind: FailureKind::Application,
+                            slurm_job_id: None,
+                            quantum_job_id: None,
+                        };
+                        self.run_finish_hooks(&mut job).await;
+                        self.store.save_job(&job).await?;
+                        self.record_status(&job.id, &job.status);
+                        continue;
+                    }
+                }
+            } else if let Some(ref backend) = job.matched_backend {
+                if !self.breaker.allows(backend) {
+                    self.record(
+                        &job.id,
+                        EventKind::Held {
+                            reason: format!("backend {} is paused", backend),
+                        },
+                    );
+                    self.block_gate(&mut gate, &job).await;
+                    held.push(job);
+                    continue;
+                }
+            }
+
+            if !gate.admits(&job) {
+                self.record(
+                    &job.id,
+                    EventKind::Held {
+                        reason: "would delay a higher-priority job".to_string(),
+                    },
+                );
+                held.push(job);
+                continue;
+            }
+
+            if let Some(request) = job.preflight.clone() {
+                if !self.run_preflight(&mut job, &request).await? {
+                    continue;
+                }
+            }
+
+            if self.cloud.accepts(&job) {
+                self.dispatch_cloud(job).await?;
+                continue;
+            }
+
+            job.node_features = self
+                .matcher
+                .node_features(job.matched_backend.as_deref(), &job.requirements);
+
+            // Members of a job array on the same backend are submitted
+            // together once the pass is over
+            if let Some(group) = job.array_group.clone() {
+                if job.batch_dependency.is_none() && self.batch.supports_arrays() {
+                    arrays
+                        .entry((group, job.matched_backend.clone()))
+                        .or_default()
+                        .push(job);
+                    continue;
+                }
+            }
+
+            // Submit to the batch system
+            self.dispatch_batch(job).await?;
+        }
+
+        for jobs in arrays.into_values() {
+            if jobs.len() == 1 {
+                let job = jobs.into_iter().next().expect("one job");
+                self.dispatch_batch(job).await?;
+                continue;
+            }
+            self.check_fence().await?;
+            for job in &jobs {
+                self.log_transition(&job.id, WalOp::Dispatch).await?;
+            }
+            match self.batch.submit_array(&jobs).await {
+                Ok(batch_job_ids) => {
+                    tracing::info!("Submitted {} jobs as one array job", jobs.len());
+                    for (job, batch_job_id) in jobs.into_iter().zip(batch_job_ids) {
+                        self.log_transition(
+                            &job.id,
+                            WalOp::SlurmIdAssigned {
+                                slurm_job_id: batch_job_id.clone(),
+                            },
+                        )
+                        .await?;
+                        self.record_submission(job, Ok(batch_job_id)).await?;
+                    }
+                }
+                Err(e) => {
+                    let reason = e.to_string();
+                    for job in jobs {
+                        self.record_submission(job, Err(reason.clone())).await?;
+                    }
+                }
+            }
+        }
+
+        if !held.is_empty() {
+            tracing::debug!("Holding {} job(s) in the queue", held.len());
+            let mut queue = self.queue.write().await;
+            for job in held {
+                queue.push(job);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Preempt running jobs for urgent jobs waiting in the batch queue for
+    /// resources, see [`crate::preempt`]. Returns the preempted jobs.
+    async fn preempt_for_urgent(&self) -> SchedResult<Vec<ScheduledJobId>> {
+        let Some(config) = self.config().preemption else {
+            return Ok(Vec::new());
+        };
+        let filter = JobFilter::default().with_status([
+            "SlurmQueued",
+            "SlurmRunning",
+            "QuantumSubmitted",
+            "QuantumRunning",
+        ]);
+        let mut active = self.store.list_jobs(&filter).await?;
+        let mut urgent: Vec<ScheduledJob> = active
+            .iter()
+            .filter(|job| {
+                config.is_urgent(job.priority)
+                    && matches!(job.status, ScheduledJobStatus::SlurmQueued { .. })
+            })
+            .cloned()
+            .collect();
+        urgent.sort_by_key(|job| std::cmp::Reverse(job.priority));
+
+        let grace = Duration::from_secs(config.grace_secs);
+        let mut preempted = Vec::new();
+        for job in urgent {
+            let recent = self
+                .preemptions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(&job.id)
+                .is_some_and(|at| at.elapsed() < grace);
+            let Some(batch_job_id) = job.status.slurm_job_id().filter(|_| !recent) else {
+                continue;
+            };
+            match self.batch.pending_reason(batch_job_id).await {
+                Ok(Some(reason)) if RESOURCE_REASONS.contains(&reason.as_str()) => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::warn!("Could not get pending reason of job {}: {}", job.id, e);
+                    continue;
+                }
+            }
+            let Some((victim, policy)) = config.select_victim(&job, &active) else {
+                continue;
+            };
+            let victim = victim.clone();
+            match self.preempt(victim, policy, &job.id).await {
+                Ok(victim_id) => {
+                    active.retain(|j| j.id != victim_id);
+                    preempted.push(victim_id);
+                    self.preemptions
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(job.id.clone(), std::time::Instant::now());
+                }
+                Err(e) => tracing::warn!("Could not preempt a job for {}: {}", job.id, e),
+            }
+        }
+
+        let mut preemptions = self.preemptions.lock().unwrap_or_else(|e| e.into_inner());
+        preemptions.retain(|_, at| at.elapsed() < grace);
+        Ok(preempted)
+    }
+
+    /// Requeue or cancel a running job for the urgent job `by`.
+    async fn preempt(
+        &self,
+        mut victim: ScheduledJob,
+        policy: PreemptionPolicy,
+        by: &ScheduledJobId,
+    ) -> SchedResult<ScheduledJobId> {
+        let Some(batch_job_id) = victim.status.slurm_job_id().map(str::to_string) else {
+            return Err(SchedError::InvalidJobState {
+                expected: "running batch job".to_string(),
+                found: victim.status.to_string(),
+            });
+        };
+        match policy {
+            PreemptionPolicy::Never => {
+                return Err(SchedError::InvalidJobState {
+                    expected: "preemptible job".to_string(),
+                    found: format!("job {} with policy never", victim.id),
+                });
+            }
+            PreemptionPolicy::Requeue => {
+                self.batch.requeue(&batch_job_id).await?;
+                victim.status = ScheduledJobStatus::SlurmQueued {
+                    slurm_job_id: batch_job_id,
+                };
+            }
+            PreemptionPolicy::Cancel => {
+                self.batch.cancel(&batch_job_id).await?;
+                victim.reset();
+            }
+        }
+        self.store.save_job(&victim).await?;
+        self.partials.write().await.remove(&victim.id);
+        tracing::info!(
+            "Preempted job {} for urgent job {} ({})",
+            victim.id,
+            by,
+            policy
+        );
+        self.record(
+            &victim.id,
+            EventKind::Preempted {
+                by: by.clone(),
+                policy,
+                status: victim.status.clone(),
+            },
+        );
+        let victim_id = victim.id.clone();
+        if victim.status.is_pending() {
+            self.queue.write().await.push(victim);
+        }
+        Ok(victim_id)
+    }
+
+    /// Make a job that cannot be placed the head of a dispatch pass, waiting
+    /// for its candidate backends until the earliest ends its pause.
+    async fn block_gate(&self, gate: &mut DispatchGate, job: &ScheduledJob) {
+        if !gate.needs_head() {
+            return;
+        }
+        let backends = match &job.matched_backend {
+            Some(backend) => vec![backend.clone()],
+            None => self
+                .matcher
+                .find_all_matches(&job.requirements)
+                .await
+                .map(|matches| matches.into_iter().map(|m| m.backend_name).collect())
+                .unwrap_or_default(),
+        };
+        let now = chrono::Utc::now();
+        let estimated_start = backends
+            .iter()
+            .map(|backend| match self.breaker.state(backend) {
+                BreakerState::Open { until } => until.max(now),
+                _ => now,
+            })
+            .min();
+        gate.block(backends, estimated_start);
+    }
+
+    /// Hand a job to the batch system and record the outcome, logging the
+    /// dispatch ahead of it.
+    async fn dispatch_batch(&self, job: ScheduledJob) -> SchedResult<()> {
+        self.check_fence().await?;
+        self.log_transition(&job.id, WalOp::Dispatch).await?;
+        let submit_result = self.batch.submit(&job).await.map_err(|e| e.to_string());
+        if let Ok(batch_job_id) = &submit_result {
+            self.log_transition(
+                &job.id,
+                WalOp::SlurmIdAssigned {
+                    slurm_job_id: batch_job_id.clone(),
+                },
+            )
+            .await?;
+        }
+        self.record_submission(job, submit_result).await
+    }
+
+    /// Record the outcome of handing a job to the batch system, retrying
+    /// or failing the job if it was refused.
+    async fn record_submission(
+        &self,
+        mut job: ScheduledJob,
+        submit_result: Result<String, String>,
+    ) -> SchedResult<()> {
+        match submit_result {
+            Ok(batch_job_id) => {
+                self.record(
+                    &job.id,
+                    EventKind::Dispatched {
+                        batch_job_id: batch_job_id.clone(),
+                    },
+                );
+                job.batch_job_id = Some(batch_job_id.clone());
+                job.status = ScheduledJobStatus::SlurmQueued {
+                    slurm_job_id: batch_job_id,
+                };
+                job.submitted_at = Some(chrono::Utc::now());
+                self.store.save_job(&job).await?;
+                tracing::info!("Submitted job {} to batch scheduler", job.id);
+            }
+            Err(reason) => {
+                tracing::error!("Batch submission failed for job {}: {}", job.id, reason);
+                job.status = ScheduledJobStatus::Failed {
+                    reason,
+                    kind: FailureKind::Submission,
+                    slurm_job_id: None,
+                    quantum_job_id: None,
+                };
+                if self.retry_if_allowed(&job, &job.status).await? {
+                    return Ok(());
+                }
+                self.run_finish_hooks(&mut job).await;
+                self.store.save_job(&job).await?;
+                self.log_transition(&job.id, WalOp::Complete).await?;
+                self.record_status(&job.id, &job.status);
+                let mut completed = self.completed_jobs.write().await;
+                completed.insert(job.id, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// Negotiate a job's capability request with its matched backend.
+    ///
+    /// Returns false if the backend cannot run the job, which is then
+    /// failed. Jobs without a matched backend are left as they are.
+    async fn run_preflight(
+        &self,
+        job: &mut ScheduledJob,
+        request: &CapabilityRequest,
+    ) -> SchedResult<bool> {
+        let Some(backend) = job.matched_backend.clone() else {
+            return Ok(true);
+        };
+        let outcome = match self.matcher.capabilities_of(&backend).await {
+            Some(capabilities) => negotiate(job, request, &backend, &capabilities),
+            None => Negotiation::Rejected {
+                backend,
+                reason: "capabilities unavailable".to_string(),
+            },
+        };
+
+        self.record(
+            &job.id,
+            EventKind::Negotiated {
+                outcome: outcome.clone(),
+            },
+        );
+        let accepted = outcome.is_accepted();
+        if !accepted {
+            tracing::warn!("Capability negotiation for job {} {}", job.id, outcome);
+            job.status = ScheduledJobStatus::Failed {
+                reason: format!("capability negotiation {}", outcome),
+                kind: FailureKind::Application,
+                slurm_job_id: None,
+                quantum_job_id: None,
+            };
+        }
+        job.negotiation = Some(outcome);
+        if !accepted {
+            self.run_finish_hooks(job).await;
+            self.store.save_job(job).await?;
+            self.record_status(&job.id, &job.status);
+        }
+        Ok(accepted)
+    }
+
+    /// Check a job's capability request against the backend it is, or
+    /// would be, matched to, without submitting it.
+    pub async fn capability_report(&self, job: &ScheduledJob) -> SchedResult<CapabilityReport> {
+        let request = job.preflight.clone().unwrap_or_default();
+        match &job.matched_backend {
+            Some(backend) => {
+                let capabilities =
+                    self.matcher.capabilities_of(backend).await.ok_or_else(|| {
+                        SchedError::NoMatchingBackend(format!("unknown backend {}", backend))
+                    })?;
+                Ok(CapabilityReport::new(backend, &capabilities, job, &request))
+            }
+            None => {
+                let matched = self.matcher.find_match(&job.requirements).await?;
+                Ok(CapabilityReport::for_match(&matched, job, &request))
+            }
+        }
+    }
+
+    /// Submit a job straight to its cloud provider.
+    async fn dispatch_cloud(&self, mut job: ScheduledJob) -> SchedResult<()> {
+        self.check_fence().await?;
+        match self.cloud.submit(&job).await {
+            Ok(quantum_job_id) => {
+                self.record(
+                    &job.id,
+                    EventKind::CloudSubmitted {
+                        backend: job.matched_backend.clone().unwrap_or_default(),
+                        remote_job_id: quantum_job_id.0.clone(),
+                    },
+                );
+                job.status = ScheduledJobStatus::QuantumSubmitted {
+                    slurm_job_id: CLOUD_JOB_ID.to_string(),
+                    quantum_job_id,
+                };
+                job.submitted_at = Some(chrono::Utc::now());
+                self.store.save_job(&job).await?;
+                tracing::info!("Submitted job {} to cloud provider", job.id);
+            }
+            Err(e) => {
+                tracing::error!("Cloud submission failed for job {}: {}", job.id, e);
+                job.status = ScheduledJobStatus::Failed {
+                    reason: e.to_string(),
+                    kind: FailureKind::Submission,
+                    slurm_job_id: None,
+                    quantum_job_id: None,
+                };
+                if self.retry_if_allowed(&job, &job.status).await? {
+                    return Ok(());
+                }
+                self.run_finish_hooks(&mut job).await;
+                self.store.save_job(&job).await?;
+                self.record_status(&job.id, &job.status);
+                let mut completed = self.completed_jobs.write().await;
+                completed.insert(job.id, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// Pick the best matching backend whose dispatch is not paused.
+    ///
+    /// Returns `None` if every matching backend is paused.
+    async fn select_backend(&self, job: &mut ScheduledJob) -> SchedResult<Option<String>> {
+        // Jobs in a reservation run on its backend
+        if let Some(name) = &job.reservation {
+            let backend = self.reservation(name).await?.backend;
+            return Ok(Some(backend).filter(|b| self.breaker.allows(b)));
+        }
+        if job.requirements.maximize_esp {
+            return self.select_backend_by_esp(job).await;
+        }
+        let matches = self.matcher.find_all_matches(&job.requirements).await?;
+        if matches.is_empty() {
+            return Err(SchedError::NoMatchingBackend(format!(
+                "No backend found with {} qubits",
+                job.requirements.min_qubits
+            )));
+        }
+        Ok(matches
+            .into_iter()
+            .map(|m| m.backend_name)
+            .find(|name| self.breaker.allows(name)))
+    }
+
+    /// Select the allowed backend and layout with the highest estimated
+    /// success probability, and compile the job's circuits for them.
+    async fn select_backend_by_esp(&self, job: &mut ScheduledJob) -> SchedResult<Option<String>> {
+        let circuits = job
+            .circuits
+            .iter()
+            .map(CircuitSpec::resolve)
+            .collect::<SchedResult<Vec<_>>>()?;
+        let matches = self
+            .matcher
+            .find_matches_by_esp(&job.requirements, &circuits)
+            .await?;
+        if matches.is_empty() {
+            return Err(SchedError::NoMatchingBackend(format!(
+                "No backend found with {} qubits",
+                job.requirements.min_qubits
+            )));
+        }
+        let Some(matched) = matches
+            .into_iter()
+            .find(|m| self.breaker.allows(&m.backend_name))
+        else {
+            return Ok(None);
+        };
+
+        let calibration = self.matcher.calibration_of(&matched.backend_name).await;
+        if let (Some(esp), Some(layout), Some(calibration)) =
+            (matched.esp, matched.layout, calibration)
+        {
+            let rates = calibration.error_rates();
+            for (spec, circuit) in job.circuits.iter_mut().zip(circuits) {
+                let (pm, mut props) = esp_builder(&matched.capabilities, &rates)
+                    .with_layout(layout.clone())
+                    .build();
+                let mut dag = circuit.into_dag();
+                pm.run(&mut dag, &mut props)?;
+                spec.set_source(CircuitSpec::from_circuit(&arvak_ir::Circuit::from_dag(
+                    dag,
+                ))?);
+            }
+            tracing::debug!(
+                "Job {} compiled for {} with estimated success probability {:.4}",
+                job.id,
+                matched.backend_name,
+                esp
+            );
+        }
+        Ok(Some(matched.backend_name))
+    }
+
+    /// Record a finished job with the breaker and reroute it if it failed
+    /// on a backend that is now paused.
+    ///
+    /// Returns true if the job was requeued.
+    async fn reroute_if_tripped(
+        &self,
+        job: &ScheduledJob,
+        status: &ScheduledJobStatus,
+    ) -> SchedResult<bool> {
+        let Some(ref backend) = job.matched_backend else {
+            return Ok(false);
+        };
+        if job.is_classical() || matches!(status, ScheduledJobStatus::Cancelled) {
+            return Ok(false);
+        }
+
+        self.breaker.record(backend, status.is_success());
+        if status.is_success()
+            || !self.breaker.is_paused(backend)
+            || !self.config().auto_match_resources
+            || job.reroutes >= self.breaker.config().max_reroutes
+        {
+            return Ok(false);
+        }
+
+        tracing::info!(
+            "Rerouting job {} away from paused backend {}",
+            job.id,
+            backend
+        );
+        let mut job = job.clone();
+        job.reroutes += 1;
+        self.record(
+            &job.id,
+            EventKind::Rerouted {
+                from_backend: backend.clone(),
+                attempt: job.reroutes,
+            },
+        );
+        job.matched_backend = None;
+        job.status = ScheduledJobStatus::Pending;
+        job.submitted_at = None;
+        self.store.save_job(&job).await?;
+        self.queue.write().await.push(job);
+        Ok(true)
+    }
+
+    /// Requeue a failed job if its retry policy covers the failure,
+    /// recording the failed attempt on the job.
+    ///
+    /// Returns true if the job was requeued.
+    async fn retry_if_allowed(
+        &self,
+        job: &ScheduledJob,
+        status: &ScheduledJobStatus,
+    ) -> SchedResult<bool> {
+        let (
+            Some(policy),
+            ScheduledJobStatus::Failed {
+                reason,
+                kind,
+                slurm_job_id,
+                ..
+            },
+        ) = (&job.retry, status)
+        else {
+            return Ok(false);
+        };
+        let attempt = job.attempts.len() as u32 + 1;
+        let Some(delay) = policy.next_delay(*kind, attempt) else {
+            return Ok(false);
+        };
+
+        tracing::info!(
+            "Retrying job {} after {} failure (attempt {} of {}) in {:?}",
+            job.id,
+            kind,
+            attempt,
+            policy.max_attempts,
+            delay
+        );
+        let now = chrono::Utc::now();
+        let mut job = job.clone();
+        job.attempts.push(Attempt {
+            number: attempt,
+            batch_job_id: slurm_job_id.clone(),
+            backend: job.matched_backend.clone(),
+            kind: *kind,
+            reason: reason.clone(),
+            submitted_at: job.submitted_at,
+            failed_at: now,
+        });
+        self.record(
+            &job.id,
+            EventKind::Retried {
+                attempt,
+                kind: *kind,
+                reason: reason.clone(),
+                delay_secs: delay.as_secs(),
+            },
+        );
+        job.not_before = chrono::Duration::from_std(delay)
+            .ok()
+            .and_then(|d| now.checked_add_signed(d));
+        job.status = ScheduledJobStatus::Pending;
+        job.submitted_at = None;
+        job.batch_dependency = None;
+        self.store.save_job(&job).await?;
+        self.queue.write().await.push(job);
+        Ok(true)
+    }
+
+    /// Collect the results of a job's dependencies.
+    async fn task_inputs(&self, job: &ScheduledJob) -> SchedResult<TaskInputs> {
+        let mut inputs = TaskInputs::new();
+        for dep in &job.dependencies {
+            let dep_job = self.store.load_job(dep).await?;
+            let result = match self.store.load_result(dep).await? {
+                Some(result) => result,
+                None => match &dep_job {
+                    Some(dep_job) => match self.batch_result(dep_job).await {
+                        Some(result) => result,
+                        None => continue,
+                    },
+                    None => continue,
+                },
+            };
+            let name = dep_job.map(|j| j.name).unwrap_or_default();
+            inputs.push(dep.clone(), name, result);
+        }
+        for loop_job in self.enclosing_loops(job).await? {
+            if let Some(ClassicalTask::Loop(node)) = loop_job.task {
+                inputs.push(loop_job.id, loop_job.name, task_result(node.state));
+            }
+        }
+        Ok(inputs)
+    }
+
+    /// Read the first circuit result of a finished quantum job, which batch
+    /// jobs leave in the batch system's work directory instead of the store.
+    async fn batch_result(&self, job: &ScheduledJob) -> Option<ExecutionResult> {
+        if job.is_classical() || !job.status.is_success() {
+            return None;
+        }
+        match self.circuit_results(&job.id).await {
+            Ok(results) => results.into_iter().next().map(|r| r.result),
+            Err(e) => {
+                tracing::warn!("Failed to read results of job {}: {}", job.id, e);
+                None
+            }
+        }
+    }
+
+    /// Run a closure or verification task in-process, start a sub-workflow
+    /// or submit a script task.
+    async fn dispatch_classical(
+        &self,
+        mut job: ScheduledJob,
+        task: ClassicalTask,
+    ) -> SchedResult<()> {
+        let inputs = self.task_inputs(&job).await?;
+
+        match task {
+            ClassicalTask::Closure { name } => {
+                let output = match self.tasks.get(&name) {
+                    Some(f) => f(inputs).await,
+                    None => Err(SchedError::ConfigError(format!(
+                        "No classical task registered as '{}'",
+                        name
+                    ))),
+                };
+                self.finish_local_task(job, output).await?;
+            }
+            ClassicalTask::Verify(verification) => {
+                let output = match verification.verify(&inputs) {
+                    Ok(report) if report.passed => Ok(serde_json::to_value(&report)?),
+                    Ok(report) => {
+                        // Keep the report of a failed check for diagnosis.
+                        let value = serde_json::to_value(&report)?;
+                        self.store.save_result(&job.id, &task_result(value)).await?;
+                        Err(SchedError::VerificationFailed(report.failures()))
+                    }
+                    Err(e) => Err(e),
+                };
+                self.finish_local_task(job, output).await?;
+            }
+            ClassicalTask::Loop(mut node) => {
+                let Some(workflow_id) = job.workflow_id.clone() else {
+                    let error = SchedError::ConfigError(format!(
+                        "Loop {} is not part of a workflow",
+                        job.name
+                    ));
+                    return self.finish_local_task(job, Err(error)).await;
+                };
+                let decision = match &node.condition {
+                    Some(name) => match self.tasks.get(name) {
+                        Some(f) => f(inputs).await.and_then(|value| {
+                            serde_json::from_value::<LoopDecision>(value).map_err(|e| {
+                                SchedError::ConfigError(format!(
+                                    "Loop condition '{}' returned an invalid decision: {}",
+                                    name, e
+                                ))
+                            })
+                        }),
+                        None => Err(SchedError::ConfigError(format!(
+                            "No classical task registered as '{}'",
+                            name
+                        ))),
+                    },
+                    None => Ok(LoopDecision::default()),
+                };
+                let decision = match decision {
+                    Ok(decision) => decision,
+                    Err(e) => return self.finish_local_task(job, Err(e)).await,
+                };
+
+                node.iteration += 1;
+                if let Some(state) = decision.state {
+                    node.state = state;
+                }
+                let output = serde_json::json!({
+                    "iterations": node.iteration,
+                    "converged": decision.converged,
+                    "state": node.state,
+                });
+                let done = decision.converged || node.iteration >= node.max_iterations;
+                job.task = Some(ClassicalTask::Loop(node));
+                if done {
+                    self.finish_local_task(job, Ok(output)).await?;
+                } else {
+                    self.restart_loop(&workflow_id, job).await?;
+                }
+            }
+            ClassicalTask::Workflow(sub) => {
+                job.status = ScheduledJobStatus::SlurmQueued {
+                    slurm_job_id: SUB_WORKFLOW_JOB_ID.to_string(),
+                };
+                job.submitted_at = Some(chrono::Utc::now());
+                self.store.save_job(&job).await?;
+                self.record_status(&job.id, &job.status);
+                if let Err(e) = self.start_sub_workflow(&job, sub).await {
+                    return self.finish_local_task(job, Err(e)).await;
+                }
+                tracing::info!("Started sub-workflow of node {}", job.id);
+            }
+            ClassicalTask::Script { .. } => {
+                self.check_fence().await?;
+                let submit_result = self.batch.submit_task(&job, &inputs).await;
+
+                match submit_result {
+                    Ok(batch_job_id) => {
+                        self.record(
+                            &job.id,
+                            EventKind::Dispatched {
+                                batch_job_id: batch_job_id.clone(),
+                            },
+                        );
+                        job.batch_job_id = Some(batch_job_id.clone());
+                        job.status = ScheduledJobStatus::SlurmQueued {
+                            slurm_job_id: batch_job_id,
+                        };
+                        job.submitted_at = Some(chrono::Utc::now());
+                        self.store.save_job(&job).await?;
+                        tracing::info!("Submitted classical task {} to SLURM", job.id);
+                    }
+                    Err(e) => {
+                        tracing::error!("Submission failed for classical task {}: {}", job.id, e);
+                        job.status = ScheduledJobStatus::Failed {
+                            reason: e.to_string(),
+                            kind: FailureKind::Submission,
+                            slurm_job_id: None,
+                            quantum_job_id: None,
+                        };
+                        if self.retry_if_allowed(&job, &job.status).await? {
+                            return Ok(());
+                        }
+                        self.run_finish_hooks(&mut job).await;
+                        self.store.save_job(&job).await?;
+                        self.record_status(&job.id, &job.status);
+                        let mut completed = self.completed_jobs.write().await;
+                        completed.insert(job.id, false);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record the outcome of a task executed in-process.
+    async fn finish_local_task(
+        &self,
+        mut job: ScheduledJob,
+        output: SchedResult<serde_json::Value>,
+    ) -> SchedResult<()> {
+        match output {
+            Ok(value) => {
+                self.store.save_result(&job.id, &task_result(value)).await?;
+                job.status = ScheduledJobStatus::Completed {
+                    slurm_job_id: LOCAL_TASK_ID.to_string(),
+                    quantum_job_id: arvak_hal::JobId(LOCAL_TASK_ID.to_string()),
+                };
+                tracing::info!("Classical task {} completed", job.id);
+            }
+            Err(e) => {
+                tracing::error!("Classical task {} failed: {}", job.id, e);
+                job.status = ScheduledJobStatus::Failed {
+                    reason: e.to_string(),
+                    kind: FailureKind::Application,
+                    slurm_job_id: None,
+                    quantum_job_id: None,
+                };
+            }
+        }
+        let now = chrono::Utc::now();
+        job.submitted_at.get_or_insert(now);
+        job.completed_at = Some(now);
+        self.run_finish_hooks(&mut job).await;
+        self.store.save_job(&job).await?;
+        self.record_status(&job.id, &job.status);
+
+        let succeeded = job.status.is_success();
+        if succeeded {
+            self.remember_cached(&job).await?;
+        }
+        let mut completed = self.completed_jobs.write().await;
+        completed.insert(job.id, succeeded);
+        Ok(())
+    }
+
+    /// Complete a cacheable job from the stored result of an identical
+    /// earlier job, if there is one; otherwise record its cache key so its
+    /// own result is cached once it completes.
+    ///
+    /// Returns true if the job was completed from the cache.
+    async fn complete_from_cache(&self, job: &mut ScheduledJob) -> SchedResult<bool> {
+        let mut upstream = Vec::with_capacity(job.dependencies.len());
+        for dep in &job.dependencies {
+            upstream.push(self.store.load_result(dep).await?);
+        }
+        let key = match cache::node_key(job, &upstream) {
+            Ok(key) => key,
+            Err(e) => {
+                tracing::warn!("Cannot compute cache key of job {}: {}", job.id, e);
+                return Ok(false);
+            }
+        };
+        job.cache_key = Some(key.clone());
+
+        let Some(source) = self.store.load_cache_entry(&key).await? else {
+            return Ok(false);
+        };
+        let Some(result) = self.store.load_result(&source).await? else {
+            // The cached job's result was cleaned up since
+            return Ok(false);
+        };
+
+        tracing::info!(
+            "Completing job {} from the result of job {}",
+            job.id,
+            source
+        );
+        self.store.save_result(&job.id, &result).await?;
+        job.status = ScheduledJobStatus::CachedComplete { source };
+        job.submitted_at = Some(chrono::Utc::now());
+        job.completed_at = job.submitted_at;
+        self.store.save_job(job).await?;
+        self.record_status(&job.id, &job.status);
+        self.completed_jobs
+            .write()
+            .await
+            .insert(job.id.clone(), true);
+        for workflow in self.workflows.write().await.values_mut() {
+            workflow.refresh_job(job.clone());
+        }
+        Ok(true)
+    }
+
+    /// Cache the result of a job that completed, under the key it was
+    /// dispatched with.
+    async fn remember_cached(&self, job: &ScheduledJob) -> SchedResult<()> {
+        match &job.cache_key {
+            Some(key) => self.store.save_cache_entry(key, &job.id).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Take waiting jobs whose unfinished dependencies are all queued on
+    /// SLURM, attaching a native `--dependency` expression so SLURM can
+    /// start them without waiting for the next poll.
+    async fn take_natively_chainable(
+        &self,
+        finished: &rustc_hash::FxHashMap<ScheduledJobId, bool>,
+    ) -> SchedResult<Vec<ScheduledJob>> {
+        let waiting: Vec<ScheduledJob> = {
+            let queue = self.queue.read().await;
+            // Classical tasks need their inputs at submit time.
+            queue
+                .iter()
+                .filter(|job| !job.dependencies.is_empty() && !job.is_classical())
+                .cloned()
+                .collect()
+        };
+
+        let mut batch_ids = rustc_hash::FxHashMap::default();
+        let mut chainable = Vec::new();
+        for mut job in waiting {
+            for dep in &job.dependencies {
+                if finished.contains_key(dep) || batch_ids.contains_key(dep) {
+                    continue;
+                }
+                if let Some(upstream) = self.store.load_job(dep).await? {
+                    if let Some(id) = upstream.status.slurm_job_id() {
+                        batch_ids.insert(dep.clone(), id.to_string());
+                    }
+                }
+            }
+
+            if let Some(expr) = job.slurm_dependency(finished, |dep| batch_ids.get(dep).cloned()) {
+                job.batch_dependency = Some(expr);
+                chainable.push(job);
+            }
+        }
+
+        let mut queue = self.queue.write().await;
+        chainable.retain(|job| queue.remove(&job.id).is_some());
+        Ok(chainable)
+    }
+
+    /// Update statuses of running jobs; returns whether any changed.
+    async fn update_job_statuses(&self) -> SchedResult<bool> {
+        // Queued jobs must be polled too, or they never reach a terminal state.
+        let active = JobFilter::default().with_status([
+            "SlurmQueued",
+            "SlurmRunning",
+            "QuantumSubmitted",
+            "QuantumRunning",
+        ]);
+        let jobs = self.store.list_jobs(&active).await?;
+
+        // Query the batch system for all its jobs at once where it can.
+        let batch_jobs: Vec<(&ScheduledJob, &str)> = jobs
+            .iter()
+            .filter_map(|job| Some((job, job.status.slurm_job_id()?)))
+            .filter(|(_, id)| *id != SUB_WORKFLOW_JOB_ID && *id != CLOUD_JOB_ID)
+            .collect();
+        let mut polled: rustc_hash::FxHashMap<ScheduledJobId, ScheduledJobStatus> =
+            rustc_hash::FxHashMap::default();
+        match self.batch.poll_many(&batch_jobs).await {
+            Ok(statuses) => {
+                for ((job, _), status) in batch_jobs.iter().zip(statuses) {
+                    if let Some(status) = status {
+                        polled.insert(job.id.clone(), status);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(
+                "Failed to get statuses of {} jobs: {}",
+                self.batch.name(),
+                e
+            ),
+        }
+
+        let mut changed = false;
+        for job in jobs {
+            let status = polled.remove(&job.id);
+            changed |= self.update_job_status_polled(job, status).await?;
+        }
+
+        self.update_workflows().await?;
+        Ok(changed)
+    }
+
+    /// Poll the jobs named by status events; returns whether any events
+    /// arrived.
+    ///
+    /// `pending` holds batch job IDs whose events did not change the job's
+    /// status yet, e.g. because the batch system still reports a job as
+    /// running just after its script exited, with the number of polls left.
+    async fn process_status_events(
+        &self,
+        pending: &mut BTreeMap<String, u32>,
+    ) -> SchedResult<bool> {
+        let Some(watcher) = &self.watcher else {
+            return Ok(false);
+        };
+        let arrived = watcher.drain().await?;
+        let any_arrived = !arrived.is_empty();
+        for batch_job_id in arrived {
+            pending.insert(batch_job_id, EVENT_POLLS);
+        }
+
+        let mut changed = false;
+        for (batch_job_id, polls_left) in std::mem::take(pending) {
+            let Some(job) = self.find_active_by_batch_id(&batch_job_id).await? else {
+                continue;
+            };
+            if self.update_job_status(job).await? {
+                changed = true;
+            } else if polls_left > 1 {
+                pending.insert(batch_job_id, polls_left - 1);
+            }
+        }
+
+        if changed {
+            self.update_workflows().await?;
+        }
+        Ok(any_arrived)
+    }
+
+    /// Find the dispatched, unfinished job a batch job ID belongs to.
+    ///
+    /// Tasks of an array job that runs one job's circuits report
+    /// `<array job ID>_<task>`; they belong to the job of the array.
+    async fn find_active_by_batch_id(
+        &self,
+        batch_job_id: &str,
+    ) -> SchedResult<Option<ScheduledJob>> {
+        let mut job_id = self.store.find_job_by_ref(batch_job_id).await?;
+        if job_id.is_none() {
+            if let Some((array_job_id, _)) = batch_job_id.split_once('_') {
+                job_id = self.store.find_job_by_ref(array_job_id).await?;
+            }
+        }
+        let Some(job_id) = job_id else {
+            return Ok(None);
+        };
+        Ok(self
+            .store
+            .load_job(&job_id)
+            .await?
+            .filter(|job| !job.status.is_pending() && !job.status.is_terminal()))
+    }
+
+    /// Check the results of a successful quantum job against its
+    /// validation rules, or the scheduler's.
+    ///
+    /// Returns the status to apply, failed if a rule was violated and the
+    /// validation fails jobs, and a note listing violations it only flags.
+    /// `result` is the result of a cloud job; batch jobs' results are read
+    /// from the batch system.
+    async fn validate_results(
+        &self,
+        job: &ScheduledJob,
+        status: ScheduledJobStatus,
+        result: Option<&ExecutionResult>,
+    ) -> (ScheduledJobStatus, Option<String>) {
+        let Some(validation) = job
+            .validation
+            .clone()
+            .or_else(|| self.config().result_validation)
+            .filter(|v| !v.rules.is_empty())
+        else {
+            return (status, None);
+        };
+
+        let results = match result {
+            Some(result) => vec![CircuitResult {
+                index: 0,
+                label: job.circuit_label(0),
+                result: result.clone(),
+            }],
+            None => match self
+                .batch
+                .read_results(job)
+                .await
+                .and_then(|results| merge_results(job, results))
+            {
+                Ok(results) => results,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to read results of job {} to validate: {}",
+                        job.id,
+                        e
+                    );
+                    return (status, None);
+                }
+            },
+        };
+
+        let violations = validation.check(job, &results);
+        if violations.is_empty() {
+            return (status, None);
+        }
+        let summary = violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        tracing::warn!("Results of job {} failed validation: {}", job.id, summary);
+        match validation.action {
+            ValidationAction::Flag => (status, Some(format!("results flagged: {}", summary))),
+            ValidationAction::Fail => (
+                ScheduledJobStatus::Failed {
+                    reason: format!("result validation failed: {}", summary),
+                    kind: FailureKind::InvalidResult,
+                    slurm_job_id: status.slurm_job_id().map(str::to_string),
+                    quantum_job_id: status.quantum_job_id().cloned(),
+                },
+                None,
+            ),
+        }
+    }
+
+    /// Poll one dispatched job and apply its new status; returns whether
+    /// the status changed.
+    async fn update_job_status(&self, job: ScheduledJob) -> SchedResult<bool> {
+        self.update_job_status_polled(job, None).await
+    }
+
+    /// Like [`update_job_status`](Self::update_job_status), with the batch
+    /// job's status already polled if `polled` is given.
+    async fn update_job_status_polled(
+        &self,
+        job: ScheduledJob,
+        polled: Option<ScheduledJobStatus>,
+    ) -> SchedResult<bool> {
+        let now = chrono::Utc::now();
+        if let Some(deadline) = job.deadline.filter(|d| *d <= now) {
+            tracing::info!("Cancelling job {}: deadline {} passed", job.id, deadline);
+            if let Err(e) = self.cancel_remote(&job).await {
+                tracing::warn!("Failed to cancel job {}: {}", job.id, e);
+            }
+            self.mark_deadline_exceeded(&job.id, deadline, "deadline passed".to_string())
+                .await?;
+            return Ok(true);
+        }
+
+        // Sub-workflow nodes follow their sub-workflow, see `update_workflows`
+        let Some(batch_job_id) = job
+            .status
+            .slurm_job_id()
+            .filter(|id| *id != SUB_WORKFLOW_JOB_ID)
+        else {
+            return Ok(false);
+        };
+        let is_cloud = batch_job_id == CLOUD_JOB_ID;
+        let new_status = if is_cloud {
+            match self.cloud.status(&job).await {
+                Ok(status) => status,
+                Err(e) => {
+                    tracing::warn!("Failed to get status for cloud job {}: {}", job.id, e);
+                    return Ok(false);
+                }
+            }
+        } else if let Some(status) = polled {
+            status
+        } else {
+            match self.batch.poll(&job, batch_job_id).await {
+                Ok(status) => status,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to get status for {} job {}: {}",
+                        self.batch.name(),
+                        batch_job_id,
+                        e
+                    );
+                    return Ok(false);
+                }
+            }
+        };
+
+        if let ScheduledJobStatus::SlurmRunning { .. } = &new_status {
+            self.ingest_partial_results(&job).await;
+        }
+
+        if new_status == job.status {
+            return Ok(false);
+        }
+
+        let mut result = None;
+        if new_status.is_success() && is_cloud {
+            match self.cloud.result(&job).await {
+                Ok(cloud_result) => {
+                    self.store.save_result(&job.id, &cloud_result).await?;
+                    result = Some(cloud_result);
+                }
+                Err(e) => {
+                    // Leave the job running and fetch again next poll.
+                    tracing::warn!("Failed to fetch result of cloud job {}: {}", job.id, e);
+                    return Ok(false);
+                }
+            }
+        }
+        if new_status.is_success() && job.is_classical() {
+            let output = self.batch.read_task_output(&job).await?;
+            self.store
+                .save_result(&job.id, &task_result(output))
+                .await?;
+        }
+        let (new_status, note) = if new_status.is_success() && !job.is_classical() {
+            self.validate_results(&job, new_status, result.as_ref())
+                .await
+        } else {
+            (new_status, None)
+        };
+        if new_status.is_terminal()
+            && (self.reroute_if_tripped(&job, &new_status).await?
+                || self.retry_if_allowed(&job, &new_status).await?)
+        {
+            return Ok(true);
+        }
+        let new_status = if new_status.is_terminal() {
+            let mut finished = job.clone();
+            finished.status = new_status;
+            self.run_finish_hooks(&mut finished).await;
+            finished.status
+        } else {
+            new_status
+        };
+        self.store
+            .update_status(&job.id, new_status.clone())
+            .await?;
+        if new_status.is_terminal() {
+            self.log_transition(&job.id, WalOp::Complete).await?;
+        }
+        self.record(
+            &job.id,
+            EventKind::StatusChanged {
+                status: new_status.clone(),
+                reason: note,
+            },
+        );
+
+        if new_status.is_terminal() {
+            // Until history is loaded, the store is the one record of usage.
+            if let Some(policy) = self.fair_share.as_ref().filter(|p| p.is_loaded()) {
+                policy.record_job(&job, now);
+            }
+            self.finish_partial_results(&job.id, result.as_ref()).await;
+            if new_status.is_success() {
+                self.remember_cached(&job).await?;
+            }
+            let mut completed = self.completed_jobs.write().await;
+            completed.insert(job.id.clone(), new_status.is_success());
+        }
+        Ok(true)
+    }
+}
+
+/// Get a file's modification time, if it exists.
+async fn modified_time(path: &std::path::Path) -> Option<std::time::SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}
+
+#[async_trait]
+impl Scheduler for HpcScheduler {
+    async fn submit(&self, job: ScheduledJob) -> SchedResult<ScheduledJobId> {
+        let job = self.prepare_submission(job).await?;
+        let job_id = job.id.clone();
+
+        // Add to queue
+        let mut queue = self.queue.write().await;
+        queue.push(job);
+
+        tracing::info!("Job {} submitted to scheduler", job_id);
+        Ok(job_id)
+    }
+
+    async fn submit_batch(
+        &self,
+        name: &str,
+        circuits: Vec<CircuitSpec>,
+        shots: u32,
+        priority: Priority,
+        requirements: ResourceRequirements,
+    ) -> SchedResult<ScheduledJobId> {
+        let job = ScheduledJob::batch(name, circuits)
+            .with_shots(shots)
+            .with_priority(priority)
+            .with_requirements(requirements);
+
+        self.submit(job).await
+    }
+
+    async fn status(&self, job_id: &ScheduledJobId) -> SchedResult<ScheduledJobStatus> {
+        // Check queue first
+        {
+            let queue = self.queue.read().await;
+            if let Some(job) = queue.get(job_id) {
+                return Ok(job.status.clone());
+            }
+        }
+
+        // Check store
+        let job = self
+            .store
+            .load_job(job_id)
+            .await?
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+
+        Ok(job.status)
+    }
+
+    async fn cancel(&self, job_id: &ScheduledJobId) -> SchedResult<()> {
+        // Remove from queue if present, otherwise cancel on the batch system
+        let queued = self.queue.write().await.remove(job_id);
+        if queued.is_none() {
+            let job = self
+                .store
+                .load_job(job_id)
+                .await?
+                .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+            if job.status.is_terminal() {
+                return Err(SchedError::InvalidJobState {
+                    expected: "unfinished job".to_string(),
+                    found: job.status.name().to_string(),
+                });
+            }
+            self.cancel_remote(&job).await?;
+        }
+
+        self.mark_cancelled(job_id, None).await?;
+        let dependents = self.cancel_unsatisfiable().await?;
+        if !dependents.is_empty() {
+            tracing::info!(
+                "Cancelled {} job(s) depending on cancelled job {}",
+                dependents.len(),
+                job_id
+            );
+        }
+        self.update_workflows().await
+    }
+
+    async fn wait(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
+        let max_wait = Duration::from_secs(self.config().max_wait_time_secs);
+        let start = std::time::Instant::now();
+
+        loop {
+            let status = self.status(job_id).await?;
+
+            if status.is_terminal() {
+                if status.is_success() {
+                    return self.result(job_id).await;
+                } else {
+                    return Err(SchedError::JobNotFound(format!(
+                        "Job {} failed or was cancelled: {:?}",
+                        job_id, status
+                    )));
+                }
+            }
+
+            if start.elapsed() > max_wait {
+                return Err(SchedError::Timeout(format!(
+                    "Timeout waiting for job {}",
+                    job_id
+                )));
+            }
+
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    async fn result(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
+        self.store
+            .load_result(job_id)
+            .await?
+            .ok_or_else(|| SchedError::JobNotFound(format!("No result for job {}", job_id)))
+    }
+
+    async fn list_jobs(&self, filter: JobFilter) -> SchedResult<Vec<ScheduledJob>> {
+        self.store.list_jobs(&filter).await
+    }
+
+    fn create_workflow(&self, name: &str) -> WorkflowBuilder {
+        WorkflowBuilder::new(name)
+    }
+
+    async fn submit_workflow(&self, mut workflow: Workflow) -> SchedResult<WorkflowId> {
+        let workflow_id = workflow.id.clone();
+
+        // Any job rejected by a hook rejects the whole workflow
+        let job_ids: Vec<ScheduledJobId> = workflow.job_ids().into_iter().cloned().collect();
+        let caching = workflow.caching;
+        // Loop iterations depend on loop state the cache key does not cover,
+        // and sub-workflows cache through their own jobs.
+        let looped: rustc_hash::FxHashSet<ScheduledJobId> = workflow
+            .loops()
+            .into_iter()
+            .flat_map(|(job, node)| node.body.iter().chain([&job.id]))
+            .chain(
+                workflow
+                    .all_jobs()
+                    .into_iter()
+                    .filter(|job| matches!(job.task, Some(ClassicalTask::Workflow(_))))
+                    .map(|job| &job.id),
+            )
+            .cloned()
+            .collect();
+        for job_id in job_ids {
+            if let Some(job) = workflow.get_job_mut(&job_id) {
+                job.cacheable |= caching && !looped.contains(&job_id);
+                job.workflow_id = Some(workflow_id.clone());
+                self.hooks.run_pre_submit(job).await?;
+            }
+        }
+
+        // Save workflow
+        self.store.save_workflow(&workflow).await?;
+
+        // Submit all jobs
+        for job in workflow.all_jobs() {
+            self.store.save_job(job).await?;
+            let mut queue = self.queue.write().await;
+            queue.push(job.clone());
+        }
+
+        // Store workflow for tracking
+        {
+            let mut workflows = self.workflows.write().await;
+            workflows.insert(workflow_id.clone(), workflow);
+        }
+
+        tracing::info!("Workflow {} submitted", workflow_id);
+        Ok(workflow_id)
+    }
+
+    async fn workflow_status(&self, workflow_id: &WorkflowId) -> SchedResult<WorkflowStatus> {
+        let workflows = self.workflows.read().await;
+        workflows
+            .get(workflow_id)
+            .map(|w| w.status.clone())
+            .ok_or_else(|| SchedError::WorkflowNotFound(workflow_id.to_string()))
+    }
+
+    async fn cancel_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Vec<ScheduledJobId>> {
+        let active = self.cancel_workflow_jobs(workflow_id).await?;
+
+        // A cancelled sub-workflow cancels its node
+        let parent = self
+            .workflows
+            .read()
+            .await
+            .get(workflow_id)
+            .and_then(|w| w.parent.clone());
+        if let Some(node_id) = parent {
+            if !self.status(&node_id).await?.is_terminal() {
+                self.cancel(&node_id).await?;
+            }
+        }
+        tracing::info!(
+            "Workflow {} cancelled ({} job(s))",
+            workflow_id,
+            active.len()
+        );
+        Ok(active)
+    }
+
+    async fn resume_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<Vec<ScheduledJobId>> {
+        let mut workflow = self.tracked_workflow(workflow_id).await?;
+        workflow.refresh_from(self.store.as_ref()).await?;
+        let reset: Vec<ScheduledJobId> =
+            workflow.unsuccessful_jobs().into_iter().cloned().collect();
+        if reset.is_empty() {
+            return Ok(reset);
+        }
+
+        let previous: Vec<ScheduledJob> = reset
+            .iter()
+            .filter_map(|id| workflow.get_job(id).cloned())
+            .collect();
+        workflow.reset_jobs(&reset)?;
+        self.requeue_reset(workflow, previous, "workflow resumed")
+            .await?;
+        tracing::info!("Resuming workflow {} ({} job(s))", workflow_id, reset.len());
+        Ok(reset)
+    }
+
+    async fn wait_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<()> {
+        let max_wait = Duration::from_secs(self.config().max_wait_time_secs);
+        let start = std::time::Instant::now();
+
+        loop {
+            let status = self.workflow_status(workflow_id).await?;
+
+            if status.is_terminal() {
+                return match status {
+                    WorkflowStatus::Completed => Ok(()),
+                    WorkflowStatus::Failed { reason } => {
+                        Err(SchedError::Internal(format!("Workflow failed: {}", reason)))
+                    }
+                    WorkflowStatus::Cancelled => {
+                        Err(SchedError::Cancelled(workflow_id.to_string()))
+                    }
+                    _ => unreachable!(),
+                };
+            }
+
+            if start.elapsed() > max_wait {
+                return Err(SchedError::Timeout(format!(
+                    "Timeout waiting for workflow {}",
+                    workflow_id
+                )));
+            }
+
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    async fn reserve(
+        &self,
+        backend: &str,
+        window: TimeWindow,
+        capacity: u32,
+    ) -> SchedResult<Reservation> {
+        if !self.matcher.backend_names().contains(&backend) {
+            return Err(SchedError::NoMatchingBackend(format!(
+                "cannot reserve unknown backend {}",
+                backend
+            )));
+        }
+        if window.remaining(chrono::Utc::now()).is_none() {
+            return Err(SchedError::ConfigError(format!(
+                "cannot reserve a window that closed at {}",
+                window.end
+            )));
+        }
+        let reservation = Reservation::new(backend, window, capacity)?;
+
+        let _guard = self.reserving.lock().await;
+        if let Some(other) = self
+            .store
+            .list_reservations()
+            .await?
+            .into_iter()
+            .find(|other| other.backend == backend && other.window.overlaps(&window))
+        {
+            return Err(SchedError::ConfigError(format!(
+                "window overlaps reservation {} of backend {}",
+                other.name, backend
+            )));
+        }
+        self.batch.create_reservation(&reservation).await?;
+        self.store.save_reservation(&reservation).await?;
+
+        tracing::info!(
+            "Reserved {} node(s) for {} from {} to {} as {}",
+            capacity,
+            backend,
+            window.start,
+            window.end,
+            reservation.name
+        );
+        Ok(reservation)
+    }
+
+    async fn release_reservation(&self, name: &str) -> SchedResult<Vec<ScheduledJobId>> {
+        let reservation = self.reservation(name).await?;
+        let unfinished: Vec<ScheduledJobId> = self
+            .store
+            .list_jobs(&JobFilter::default())
+            .await?
+            .into_iter()
+            .filter(|job| job.reservation.as_deref() == Some(name) && !job.status.is_terminal())
+            .map(|job| job.id)
+            .collect();
+        let mut cancelled = Vec::new();
+        for job_id in unfinished {
+            match self.cancel(&job_id).await {
+                Ok(()) => cancelled.push(job_id),
+                Err(e) => tracing::warn!("Failed to cancel job {}: {}", job_id, e),
+            }
+        }
+
+        // The batch system drops reservations once their window closes
+        if reservation.window.remaining(chrono::Utc::now()).is_some() {
+            self.batch.delete_reservation(name).await?;
+        }
+        self.store.delete_reservation(name).await?;
+        Ok(cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::acl::InMemoryAuditLog;
+    use crate::admission::AdmissionClass;
+    use crate::events::InMemoryEventLog;
+    use crate::explain::AheadReason;
+    use crate::job::DependencyKind;
+    use crate::negotiate::ShotPolicy;
+    use crate::persistence::SqliteStore;
+    use crate::recurring::RECURRING_METADATA_KEY;
+    use crate::retry::{Backoff, RetryPolicy};
+    use crate::task::ClassicalTask;
+    use crate::validate::ValidationRule;
+    use crate::verify::ResultVerification;
+    use crate::workflow::{FailurePolicy, LoopNode};
+    use arvak_hal::{Capabilities, Counts};
+
+    /// Mock backend for testing.
+    struct MockBackend {
+        name: String,
+        num_qubits: u32,
+    }
+
+    #[async_trait]
+    impl Backend for MockBackend {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn capabilities(&self) -> arvak_hal::HalResult<Capabilities> {
+            Ok(Capabilities::simulator(self.num_qubits))
+        }
+
+        async fn is_available(&self) -> arvak_hal::HalResult<bool> {
+            Ok(true)
+        }
+
+        async fn submit(
+            &self,
+            _circuit: &arvak_ir::Circuit,
+            _shots: u32,
+        ) -> arvak_hal::HalResult<arvak_hal::JobId> {
+            Ok(arvak_hal::JobId("mock".to_string()))
+        }
+
+        async fn status(
+            &self,
+            _job_id: &arvak_hal::JobId,
+        ) -> arvak_hal::HalResult<arvak_hal::JobStatus> {
+            Ok(arvak_hal::JobStatus::Completed)
+        }
+
+        async fn result(
+            &self,
+            _job_id: &arvak_hal::JobId,
+        ) -> arvak_hal::HalResult<arvak_hal::ExecutionResult> {
+            let counts = Counts::from_pairs([("00", 500u64), ("11", 500u64)]);
+            Ok(arvak_hal::ExecutionResult::new(counts, 1000))
+        }
+
+        async fn cancel(&self, _job_id: &arvak_hal::JobId) -> arvak_hal::HalResult<()> {
+            Ok(())
+        }
+
+        async fn wait(
+            &self,
+            job_id: &arvak_hal::JobId,
+        ) -> arvak_hal::HalResult<arvak_hal::ExecutionResult> {
+            self.result(job_id).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_compiles_through_cache() {
+        use arvak_compile::{BasisGates, CouplingMap, PassManagerBuilder};
+
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let stage = CompileStage::new("cal-1", || {
+            PassManagerBuilder::new()
+                .with_optimization_level(0)
+                .with_target(CouplingMap::linear(3), BasisGates::iqm())
+        });
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store)
+            .with_compile_stage(stage);
+
+        let qasm = "OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];";
+        let first = scheduler
+            .submit(ScheduledJob::new("a", CircuitSpec::from_qasm(qasm)))
+            .await
+            .unwrap();
+        scheduler
+            .submit(ScheduledJob::batch(
+                "b",
+                vec![CircuitSpec::from_qasm(qasm), CircuitSpec::from_qasm(qasm)],
+            ))
+            .await
+            .unwrap();
+
+        let stats = scheduler.compile_stage().unwrap().cache().stats();
+        assert_eq!((stats.misses, stats.memory_hits), (1, 2));
+
+        let job = scheduler.load_job(&first).await.unwrap();
+        let circuit = job.circuits[0].resolve().unwrap();
+        assert!(
+            circuit
+                .dag()
+                .topological_ops()
+                .all(|(_, i)| matches!(i.name(), "prx" | "cz"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ingest_calibration() {
+        use arvak_compile::{BasisGates, CouplingMap, PassManagerBuilder};
+
+        let backends = || -> Vec<Arc<dyn Backend>> {
+            vec![Arc::new(MockBackend {
+                name: "test_backend".to_string(),
+                num_qubits: 3,
+            })]
+        };
+        let stage = || {
+            CompileStage::new("cal-0", || {
+                PassManagerBuilder::new()
+                    .with_optimization_level(0)
+                    .with_target(CouplingMap::linear(3), BasisGates::iqm())
+            })
+        };
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends(), store.clone())
+                .with_compile_stage(stage());
+        let qasm = "OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];";
+        let submit = || scheduler.submit(ScheduledJob::new("a", CircuitSpec::from_qasm(qasm)));
+        submit().await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("2026-10-15.csv");
+        std::fs::write(
+            &path,
+            "Qubit,T1 (us),Readout assignment error,CZ error\n0,50,0.02,0_1:0.01\n1,45,0.03,\n",
+        )
+        .unwrap();
+        let first = scheduler
+            .ingest_calibration(&path, Some("test_backend"))
+            .await
+            .unwrap();
+        assert_eq!(first.epoch, 1);
+        let second = scheduler
+            .ingest_calibration(&path, Some("test_backend"))
+            .await
+            .unwrap();
+        assert_eq!(second.epoch, 2);
+        assert_eq!(
+            scheduler.compile_stage().unwrap().target_version(),
+            "test_backend@2"
+        );
+
+        // The new epoch invalidates earlier compilations.
+        submit().await.unwrap();
+        let stats = scheduler.compile_stage().unwrap().cache().stats();
+        assert_eq!(stats.misses, 2);
+
+        let matched = scheduler
+            .matcher
+            .find_match(&ResourceRequirements::new(2))
+            .await
+            .unwrap();
+        assert_eq!(matched.calibration_epoch, Some(2));
+        let calibration = scheduler
+            .calibration("test_backend")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(calibration.gate_error("cz", &[1, 0]), Some(0.01));
+
+        // Unknown backends and qubits the backend lacks are rejected.
+        assert!(
+            scheduler
+                .ingest_calibration(&path, Some("other"))
+                .await
+                .is_err()
+        );
+        let wide = BackendCalibration::new("test_backend").with_gate_error("cz", [2, 3], 0.01);
+        assert!(scheduler.apply_calibration(wide).await.is_err());
+
+        // A restarted scheduler picks up the latest epoch.
+        let restarted =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends(), store)
+                .with_compile_stage(stage());
+        assert_eq!(restarted.restore_calibrations().await.unwrap(), 1);
+        assert_eq!(
+            restarted.compile_stage().unwrap().target_version(),
+            "test_backend@2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_submit() {
+        let config = SchedulerConfig::default();
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job = ScheduledJob::new("test_job", circuit);
+        let job_id = job.id.clone();
+
+        let submitted_id = scheduler.submit(job).await.unwrap();
+        assert_eq!(submitted_id, job_id);
+
+        let status = scheduler.status(&job_id).await.unwrap();
+        assert!(status.is_pending());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_submit_batch() {
+        let config = SchedulerConfig::default();
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+
+        let circuits = vec![
+            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];"),
+            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; x q[0];"),
+        ];
+
+        let job_id = scheduler
+            .submit_batch(
+                "batch_test",
+                circuits,
+                1000,
+                Priority::default(),
+                ResourceRequirements::default(),
+            )
+            .await
+            .unwrap();
+
+        let status = scheduler.status(&job_id).await.unwrap();
+        assert!(status.is_pending());
+    }
+
+    #[tokio::test]
+    async fn test_session_pins_and_cancels_jobs() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+
+        let mut session = Session::new("test_backend", Duration::from_secs(3_600));
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];");
+        let first = scheduler
+            .submit_in_session(&mut session, ScheduledJob::new("first", circuit.clone()))
+            .await
+            .unwrap();
+        scheduler
+            .submit_in_session(&mut session, ScheduledJob::new("second", circuit.clone()))
+            .await
+            .unwrap();
+        assert_eq!(session.jobs().len(), 2);
+
+        let job = scheduler.load_job(&first).await.unwrap();
+        assert_eq!(job.matched_backend.as_deref(), Some("test_backend"));
+
+        assert_eq!(scheduler.cancel_session(&mut session).await.unwrap(), 2);
+        assert!(matches!(
+            scheduler.status(&first).await.unwrap(),
+            ScheduledJobStatus::Cancelled
+        ));
+        assert!(
+            scheduler
+                .submit_in_session(&mut session, ScheduledJob::new("late", circuit))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explain_queued_job() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let urgent = ScheduledJob::new("urgent", circuit.clone()).with_priority(Priority::high());
+        let early = ScheduledJob::new("early", circuit.clone());
+        let mine = ScheduledJob::new("mine", circuit.clone());
+        let huge =
+            ScheduledJob::new("huge", circuit).with_requirements(ResourceRequirements::new(50));
+        let (urgent_id, early_id) = (urgent.id.clone(), early.id.clone());
+        let mine_id = scheduler.submit(mine).await.unwrap();
+        let huge_id = scheduler.submit(huge).await.unwrap();
+        scheduler.submit(early).await.unwrap();
+        scheduler.submit(urgent).await.unwrap();
+
+        let explanation = scheduler.explain(&mine_id).await.unwrap();
+        assert_eq!(explanation.position, Some(2));
+        assert_eq!(explanation.queue_length, 4);
+        assert_eq!(explanation.ahead.len(), 1);
+        assert_eq!(explanation.ahead[0].job_id, urgent_id);
+        assert_eq!(explanation.ahead[0].reason, AheadReason::HigherPriority);
+        assert_eq!(explanation.backends.len(), 1);
+        assert!(!explanation.backends[0].paused);
+        assert!(explanation.holds.is_empty());
+
+        let explanation = scheduler.explain(&huge_id).await.unwrap();
+        assert_eq!(explanation.ahead.len(), 2);
+        assert_eq!(explanation.ahead[1].job_id, mine_id);
+        assert_eq!(explanation.ahead[1].reason, AheadReason::QueuedEarlier);
+        assert!(matches!(
+            explanation.holds[..],
+            [Hold::NoMatchingBackend { .. }]
+        ));
+
+        let explanation = scheduler.explain(&early_id).await.unwrap();
+        assert_eq!(explanation.position, Some(4));
+        assert!(scheduler.explain(&ScheduledJobId::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_workflow() {
+        let config = SchedulerConfig::default();
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler =
+            HpcScheduler::with_mock_slurm(config.clone(), backends.clone(), store.clone());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job1 = ScheduledJob::new("job1", circuit.clone());
+        let job2 = ScheduledJob::new("job2", circuit);
+
+        let workflow = scheduler
+            .create_workflow("test_workflow")
+            .add_job(job1)
+            .then(job2)
+            .unwrap()
+            .build();
+
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        let status = scheduler.workflow_status(&workflow_id).await.unwrap();
+        assert!(matches!(status, WorkflowStatus::Pending));
+
+        let progress = scheduler.workflow_progress(&workflow_id).await.unwrap();
+        assert_eq!((progress.total, progress.pending), (2, 2));
+        assert_eq!(progress.percent_complete, 0.0);
+        assert!(progress.remaining_secs > 0.0);
+
+        // Another instance sharing the store sees the same workflow.
+        let other = HpcScheduler::with_mock_slurm(config, backends, store);
+        let loaded = other.workflow_progress(&workflow_id).await.unwrap();
+        assert_eq!(loaded.total, 2);
+        assert_eq!(loaded.remaining_secs, progress.remaining_secs);
+        assert!(other.workflow_progress(&WorkflowId::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_propagates_to_dependents() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let jobs: Vec<ScheduledJob> = (0..3)
+            .map(|i| ScheduledJob::new(format!("job{i}"), circuit.clone()))
+            .collect();
+        let ids: Vec<ScheduledJobId> = jobs.iter().map(|j| j.id.clone()).collect();
+        let mut jobs = jobs.into_iter();
+        let workflow = scheduler
+            .create_workflow("chain")
+            .add_job(jobs.next().unwrap())
+            .then(jobs.next().unwrap())
+            .unwrap()
+            .then(jobs.next().unwrap())
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        scheduler.cancel(&ids[0]).await.unwrap();
+        for id in &ids {
+            assert!(matches!(
+                scheduler.status(id).await.unwrap(),
+                ScheduledJobStatus::Cancelled
+            ));
+        }
+        assert!(
+            scheduler
+                .workflow_status(&workflow_id)
+                .await
+                .unwrap()
+                .is_terminal()
+        );
+        assert!(matches!(
+            scheduler.cancel(&ids[1]).await,
+            Err(SchedError::InvalidJobState { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_workflow() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let workflow = scheduler
+            .create_workflow("pair")
+            .add_job(ScheduledJob::new("a", circuit.clone()))
+            .then(ScheduledJob::new("b", circuit))
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        let cancelled = scheduler.cancel_workflow(&workflow_id).await.unwrap();
+        assert_eq!(cancelled.len(), 2);
+        assert!(matches!(
+            scheduler.workflow_status(&workflow_id).await.unwrap(),
+            WorkflowStatus::Cancelled
+        ));
+        assert!(scheduler.cancel_workflow(&WorkflowId::new()).await.is_err());
+    }
+
+    /// A scheduler relying on the trait's default `cancel_workflow`.
+    struct Delegating(HpcScheduler);
+
+    #[async_trait]
+    impl Scheduler for Delegating {
+        async fn submit(&self, job: ScheduledJob) -> SchedResult<ScheduledJobId> {
+            self.0.submit(job).await
+        }
+
+        async fn submit_batch(
+            &self,
+            name: &str,
+            circuits: Vec<CircuitSpec>,
+            shots: u32,
+            priority: Priority,
+            requirements: ResourceRequirements,
+        ) -> SchedResult<ScheduledJobId> {
+            self.0
+                .submit_batch(name, circuits, shots, priority, requirements)
+                .await
+        }
+
+        async fn status(&self, job_id: &ScheduledJobId) -> SchedResult<ScheduledJobStatus> {
+            self.0.status(job_id).await
+        }
+
+        async fn cancel(&self, job_id: &ScheduledJobId) -> SchedResult<()> {
+            self.0.cancel(job_id).await
+        }
+
+        async fn wait(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
+            self.0.wait(job_id).await
+        }
+
+        async fn result(&self, job_id: &ScheduledJobId) -> SchedResult<ExecutionResult> {
+            self.0.result(job_id).await
+        }
+
+        async fn list_jobs(&self, filter: JobFilter) -> SchedResult<Vec<ScheduledJob>> {
+            self.0.list_jobs(filter).await
+        }
+
+        fn create_workflow(&self, name: &str) -> WorkflowBuilder {
+            self.0.create_workflow(name)
+        }
+
+        async fn submit_workflow(&self, workflow: Workflow) -> SchedResult<WorkflowId> {
+            self.0.submit_workflow(workflow).await
+        }
+
+        async fn workflow_status(&self, workflow_id: &WorkflowId) -> SchedResult<WorkflowStatus> {
+            self.0.workflow_status(workflow_id).await
+        }
+
+        async fn wait_workflow(&self, workflow_id: &WorkflowId) -> SchedResult<()> {
+            self.0.wait_workflow(workflow_id).await
+        }
+
+        async fn resume_workflow(
+            &self,
+            workflow_id: &WorkflowId,
+        ) -> SchedResult<Vec<ScheduledJobId>> {
+            self.0.resume_workflow(workflow_id).await
+        }
+
+        async fn reserve(
+            &self,
+            backend: &str,
+            window: TimeWindow,
+            capacity: u32,
+        ) -> SchedResult<Reservation> {
+            self.0.reserve(backend, window, capacity).await
+        }
+
+        async fn release_reservation(&self, name: &str) -> SchedResult<Vec<ScheduledJobId>> {
+            self.0.release_reservation(name).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_default_cancel_workflow() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = Delegating(HpcScheduler::with_mock_slurm(
+            SchedulerConfig::default(),
+            Vec::new(),
+            store,
+        ));
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let other = scheduler
+            .submit(ScheduledJob::new("other", circuit.clone()))
+            .await
+            .unwrap();
+        let workflow = scheduler
+            .create_workflow("pair")
+            .add_job(ScheduledJob::new("a", circuit.clone()))
+            .then(ScheduledJob::new("b", circuit))
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        // Cancelling the first job also cancels the second, its dependent
+        let cancelled = scheduler.cancel_workflow(&workflow_id).await.unwrap();
+        assert_eq!(cancelled.len(), 2);
+        for job_id in &cancelled {
+            assert!(matches!(
+                scheduler.status(job_id).await.unwrap(),
+                ScheduledJobStatus::Cancelled
+            ));
+        }
+        assert!(!scheduler.status(&other).await.unwrap().is_terminal());
+        assert!(
+            scheduler
+                .cancel_workflow(&workflow_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workflow_classical_tasks() {
+        let config = SchedulerConfig::default();
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, Vec::new(), store.clone());
+
+        scheduler.register_task("source", |_| async { Ok(serde_json::json!(21)) });
+        scheduler.register_task("double", |inputs: TaskInputs| async move {
+            let x = inputs
+                .output("source")
+                .and_then(|v| v.as_i64())
+                .unwrap_or(0);
+            Ok(serde_json::json!(x * 2))
+        });
+
+        let source = ScheduledJob::classical("source", ClassicalTask::closure("source"));
+        let double = ScheduledJob::classical("double", ClassicalTask::closure("double"));
+        let report = ScheduledJob::classical("report", ClassicalTask::script("/opt/report.sh"));
+        let double_id = double.id.clone();
+        let report_id = report.id.clone();
+
+        let workflow = scheduler
+            .create_workflow("hybrid")
+            .add_job(source)
+            .then(double)
+            .unwrap()
+            .then(report)
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.process_pending_jobs().await.unwrap();
+        let result = scheduler.result(&double_id).await.unwrap();
+        assert_eq!(result.metadata, serde_json::json!(42));
+
+        // The script task is submitted as a SLURM job and its output
+        // ingested once it completes.
+        scheduler.process_pending_jobs().await.unwrap();
+        let report = store.load_job(&report_id).await.unwrap().unwrap();
+        assert!(report.status.slurm_job_id().is_some());
+
+        scheduler.update_job_statuses().await.unwrap();
+        assert!(store.load_result(&report_id).await.unwrap().is_some());
+        assert_eq!(
+            scheduler.workflow_status(&workflow_id).await.unwrap(),
+            WorkflowStatus::Completed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rerun_from_failed_node() {
+        let config = SchedulerConfig::default();
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, Vec::new(), store.clone());
+
+        let prepared = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let fixed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        {
+            let prepared = prepared.clone();
+            scheduler.register_task("prepare", move |_| {
+                prepared.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Ok(serde_json::json!(1)) }
+            });
+        }
+        {
+            let fixed = fixed.clone();
+            scheduler.register_task("analyze", move |_| {
+                let fixed = fixed.load(std::sync::atomic::Ordering::SeqCst);
+                async move {
+                    if fixed {
+                        Ok(serde_json::json!("ok"))
+                    } else {
+                        Err(SchedError::Internal("analysis bug".to_string()))
+                    }
+                }
+            });
+        }
+        scheduler.register_task("report", |_| async { Ok(serde_json::json!("done")) });
+
+        let prepare = ScheduledJob::classical("prepare", ClassicalTask::closure("prepare"));
+        let analyze = ScheduledJob::classical("analyze", ClassicalTask::closure("analyze"));
+        let report = ScheduledJob::classical("report", ClassicalTask::closure("report"));
+        let (prepare_id, analyze_id, report_id) =
+            (prepare.id.clone(), analyze.id.clone(), report.id.clone());
+        let workflow = WorkflowBuilder::new("campaign")
+            .with_caching()
+            .add_job(prepare)
+            .then(analyze)
+            .unwrap()
+            .then(report)
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+        let run = || async {
+            for _ in 0..3 {
+                scheduler.process_pending_jobs().await.unwrap();
+            }
+            scheduler.update_workflows().await.unwrap();
+            scheduler.workflow_status(&workflow_id).await.unwrap()
+        };
+
+        assert!(matches!(run().await, WorkflowStatus::Failed { .. }));
+        assert_eq!(
+            scheduler.status(&report_id).await.unwrap(),
+            ScheduledJobStatus::Cancelled
+        );
+
+        fixed.store(true, std::sync::atomic::Ordering::SeqCst);
+        let reset = scheduler
+            .rerun_from(&workflow_id, &analyze_id)
+            .await
+            .unwrap();
+        assert_eq!(reset, vec![analyze_id.clone(), report_id.clone()]);
+        assert_eq!(
+            scheduler.status(&report_id).await.unwrap(),
+            ScheduledJobStatus::WaitingOnDependencies
+        );
+
+        assert_eq!(run().await, WorkflowStatus::Completed);
+        assert_eq!(prepared.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(scheduler.status(&prepare_id).await.unwrap().is_success());
+        assert_eq!(
+            scheduler.result(&report_id).await.unwrap().metadata,
+            serde_json::json!("done")
+        );
+        assert!(
+            scheduler
+                .rerun_from(&workflow_id, &ScheduledJobId::new())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fail_workflow_policy_and_resume() {
+        let config = SchedulerConfig::default();
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, Vec::new(), store.clone());
+
+        let prepared = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let fixed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        {
+            let prepared = prepared.clone();
+            scheduler.register_task("prepare", move |_| {
+                prepared.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Ok(serde_json::json!(1)) }
+            });
+        }
+        {
+            let fixed = fixed.clone();
+            scheduler.register_task("calibrate", move |_| {
+                let fixed = fixed.load(std::sync::atomic::Ordering::SeqCst);
+                async move {
+                    if fixed {
+                        Ok(serde_json::json!("ok"))
+                    } else {
+                        Err(SchedError::Internal("calibration drifted".to_string()))
+                    }
+                }
+            });
+        }
+        scheduler.register_task("report", |_| async { Ok(serde_json::json!("done")) });
+
+        let calibrate = ScheduledJob::classical("calibrate", ClassicalTask::closure("calibrate"));
+        let prepare = ScheduledJob::classical("prepare", ClassicalTask::closure("prepare"));
+        let report = ScheduledJob::classical("report", ClassicalTask::closure("report"));
+        let (calibrate_id, prepare_id, report_id) =
+            (calibrate.id.clone(), prepare.id.clone(), report.id.clone());
+        let workflow = WorkflowBuilder::new("campaign")
+            .add_job(calibrate)
+            .on_failure(FailurePolicy::FailWorkflow)
+            .unwrap()
+            .add_job(prepare)
+            .then_with(report, DependencyKind::AfterOk)
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        // The failure fails the workflow before the independent chain is done
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.update_workflows().await.unwrap();
+        assert!(matches!(
+            scheduler.workflow_status(&workflow_id).await.unwrap(),
+            WorkflowStatus::Failed { .. }
+        ));
+        assert!(!scheduler.status(&report_id).await.unwrap().is_success());
+        assert!(scheduler.status(&report_id).await.unwrap().is_terminal());
+
+        fixed.store(true, std::sync::atomic::Ordering::SeqCst);
+        let resumed = scheduler.resume_workflow(&workflow_id).await.unwrap();
+        assert!(resumed.contains(&calibrate_id));
+        assert!(resumed.contains(&report_id));
+        assert!(!resumed.contains(&prepare_id));
+
+        for _ in 0..3 {
+            scheduler.process_pending_jobs().await.unwrap();
+        }
+        scheduler.update_workflows().await.unwrap();
+        assert_eq!(
+            scheduler.workflow_status(&workflow_id).await.unwrap(),
+            WorkflowStatus::Completed
+        );
+        assert_eq!(prepared.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(
+            scheduler.result(&report_id).await.unwrap().metadata,
+            serde_json::json!("done")
+        );
+        assert!(
+            scheduler
+                .resume_workflow(&workflow_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loop_node_resumes_after_restart() {
+        fn register(scheduler: &HpcScheduler) {
+            // Each iteration halves the state until it drops below one.
+            scheduler.register_task("step", |inputs: TaskInputs| async move {
+                let x = inputs.output("minimize").and_then(|v| v.as_f64());
+                Ok(serde_json::json!(x.unwrap_or_default() / 2.0))
+            });
+            scheduler.register_task("converged", |inputs: TaskInputs| async move {
+                let x = inputs.output("step").and_then(|v| v.as_f64()).unwrap();
+                let decision = if x < 1.0 {
+                    LoopDecision::converged().with_state(serde_json::json!(x))
+                } else {
+                    LoopDecision::next(serde_json::json!(x))
+                };
+                Ok(decision.into())
+            });
+        }
+        async fn run_until_finished(scheduler: &HpcScheduler, job_id: &ScheduledJobId) {
+            for _ in 0..10 {
+                scheduler.process_pending_jobs().await.unwrap();
+                if scheduler.status(job_id).await.unwrap().is_terminal() {
+                    return;
+                }
+            }
+        }
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone());
+        register(&scheduler);
+
+        let step = ScheduledJob::classical("step", ClassicalTask::closure("step"));
+        let body = vec![step.id.clone()];
+        let workflow = WorkflowBuilder::new("minimize")
+            .with_caching()
+            .add_job(step)
+            .loop_over(
+                "minimize",
+                LoopNode::new(body, 10)
+                    .with_condition("converged")
+                    .with_state(serde_json::json!(8.0)),
+            )
+            .unwrap()
+            .build();
+        let (loop_job, _) = workflow.loops()[0];
+        let loop_id = loop_job.id.clone();
+        scheduler.submit_workflow(workflow).await.unwrap();
+
+        // Run the first iteration, then continue with a new instance.
+        for _ in 0..2 {
+            scheduler.process_pending_jobs().await.unwrap();
+        }
+        let stored = store.load_job(&loop_id).await.unwrap().unwrap();
+        let Some(ClassicalTask::Loop(node)) = stored.task else {
+            panic!("loop node lost its task");
+        };
+        assert_eq!(node.iteration, 1);
+        assert_eq!(node.state, serde_json::json!(4.0));
+        assert_eq!(stored.status, ScheduledJobStatus::WaitingOnDependencies);
+        drop(scheduler);
+
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone());
+        register(&scheduler);
+        scheduler.sync_queue_from_store().await.unwrap();
+        run_until_finished(&scheduler, &loop_id).await;
+
+        assert!(scheduler.status(&loop_id).await.unwrap().is_success());
+        assert_eq!(
+            scheduler.result(&loop_id).await.unwrap().metadata,
+            serde_json::json!({"iterations": 4, "converged": true, "state": 0.5})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sub_workflow_follows_and_cancels() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone());
+        scheduler.register_task("run", |_| async { Ok(serde_json::json!(0.75)) });
+        scheduler.register_task("mitigate", |inputs: TaskInputs| async move {
+            let x = inputs.output("run").and_then(|v| v.as_f64()).unwrap();
+            Ok(serde_json::json!(x + 0.125))
+        });
+        scheduler.register_task("report", |inputs: TaskInputs| async move {
+            Ok(inputs.output("pipeline").unwrap()["outputs"]["mitigate"]["metadata"].clone())
+        });
+
+        let pipeline = WorkflowBuilder::new("pipeline")
+            .add_job(ScheduledJob::classical(
+                "run",
+                ClassicalTask::closure("run"),
+            ))
+            .then(ScheduledJob::classical(
+                "mitigate",
+                ClassicalTask::closure("mitigate"),
+            ))
+            .unwrap()
+            .build();
+        let pipeline_id = pipeline.id.clone();
+        let report = ScheduledJob::classical("report", ClassicalTask::closure("report"));
+        let report_id = report.id.clone();
+        let sweep = WorkflowBuilder::new("sweep")
+            .then_workflow(pipeline)
+            .unwrap()
+            .then(report)
+            .unwrap()
+            .build();
+        let node_id = sweep.dependencies(&report_id)[0].clone();
+        let sweep_id = scheduler.submit_workflow(sweep).await.unwrap();
+
+        // The node starts its sub-workflow and waits for it
+        scheduler.process_pending_jobs().await.unwrap();
+        assert_eq!(
+            scheduler.status(&node_id).await.unwrap(),
+            ScheduledJobStatus::SlurmQueued {
+                slurm_job_id: SUB_WORKFLOW_JOB_ID.to_string()
+            }
+        );
+        let child = store.load_workflow(&pipeline_id).await.unwrap().unwrap();
+        assert_eq!(child.parent.as_ref(), Some(&node_id));
+
+        for _ in 0..4 {
+            scheduler.process_pending_jobs().await.unwrap();
+            scheduler.update_workflows().await.unwrap();
+        }
+        assert_eq!(
+            scheduler.workflow_status(&pipeline_id).await.unwrap(),
+            WorkflowStatus::Completed
+        );
+        assert!(scheduler.status(&node_id).await.unwrap().is_success());
+        assert_eq!(
+            scheduler.result(&report_id).await.unwrap().metadata,
+            serde_json::json!(0.875)
+        );
+        assert_eq!(
+            scheduler.workflow_status(&sweep_id).await.unwrap(),
+            WorkflowStatus::Completed
+        );
+
+        // Cancelling the parent cascades into the sub-workflow, and
+        // cancelling a sub-workflow cancels its node.
+        for cancel_parent in [true, false] {
+            let script = ScheduledJob::classical("fit", ClassicalTask::script("/opt/fit.sh"));
+            let script_id = script.id.clone();
+            let pipeline = WorkflowBuilder::new("pipeline").add_job(script).build();
+            let pipeline_id = pipeline.id.clone();
+            let sweep = WorkflowBuilder::new("sweep")
+                .then_workflow(pipeline)
+                .unwrap()
+                .build();
+            let node_id = sweep.job_ids()[0].clone();
+            let sweep_id = scheduler.submit_workflow(sweep).await.unwrap();
+            scheduler.process_pending_jobs().await.unwrap();
+            scheduler.process_pending_jobs().await.unwrap();
+            assert!(
+                scheduler
+                    .status(&script_id)
+                    .await
+                    .unwrap()
+                    .slurm_job_id()
+                    .is_some()
+            );
+
+            if cancel_parent {
+                scheduler.cancel_workflow(&sweep_id).await.unwrap();
+            } else {
+                scheduler.cancel_workflow(&pipeline_id).await.unwrap();
+            }
+            for job_id in [&script_id, &node_id] {
+                assert_eq!(
+                    scheduler.status(job_id).await.unwrap(),
+                    ScheduledJobStatus::Cancelled
+                );
+            }
+            assert_eq!(
+                scheduler.workflow_status(&pipeline_id).await.unwrap(),
+                WorkflowStatus::Cancelled
+            );
+            assert!(
+                scheduler
+                    .workflow_status(&sweep_id)
+                    .await
+                    .unwrap()
+                    .is_terminal()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_map_reduce_collects_results() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_batch_system(
+            SchedulerConfig::default(),
+            Arc::new(InstantBatch::default()),
+            vec![Arc::new(MockBackend {
+                name: "qpu".to_string(),
+                num_qubits: 10,
+            })],
+            store.clone(),
+        );
+        scheduler.register_task("sum", |inputs: TaskInputs| async move {
+            let names: Vec<_> = inputs.iter().map(|i| i.name.clone()).collect();
+            let zeros: u64 = inputs.iter().map(|i| i.result.counts.get("00")).sum();
+            Ok(serde_json::json!({ "names": names, "zeros": zeros }))
+        });
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let sum = ScheduledJob::classical("sum", ClassicalTask::closure("sum"));
+        let sum_id = sum.id.clone();
+        let workflow = WorkflowBuilder::new("estimate")
+            .map(["zz", "xx", "yy"], |group| {
+                ScheduledJob::new(format!("measure_{}", group), circuit.clone())
+            })
+            .unwrap()
+            .reduce(sum)
+            .unwrap()
+            .build();
+        scheduler.submit_workflow(workflow).await.unwrap();
+
+        for _ in 0..3 {
+            scheduler.process_pending_jobs().await.unwrap();
+            scheduler.update_job_statuses().await.unwrap();
+            scheduler.update_workflows().await.unwrap();
+        }
+        assert_eq!(
+            scheduler.result(&sum_id).await.unwrap().metadata,
+            serde_json::json!({
+                "names": ["measure_zz", "measure_xx", "measure_yy"],
+                "zeros": 30,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_workflow_node_caching() {
+        let config = SchedulerConfig::default();
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, Vec::new(), store.clone());
+
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        for name in ["prepare", "analyze", "analyze_v2"] {
+            let runs = runs.clone();
+            scheduler.register_task(name, move |_| {
+                runs.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async move { Ok(serde_json::json!(name)) }
+            });
+        }
+        let run_campaign = |analysis: &'static str| {
+            let prepare = ScheduledJob::classical("prepare", ClassicalTask::closure("prepare"));
+            let analyze = ScheduledJob::classical("analyze", ClassicalTask::closure(analysis));
+            let ids = (prepare.id.clone(), analyze.id.clone());
+            let workflow = WorkflowBuilder::new("campaign")
+                .with_caching()
+                .add_job(prepare)
+                .then(analyze)
+                .unwrap()
+                .build();
+            let scheduler = &scheduler;
+            async move {
+                let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+                scheduler.process_pending_jobs().await.unwrap();
+                scheduler.process_pending_jobs().await.unwrap();
+                scheduler.update_workflows().await.unwrap();
+                assert_eq!(
+                    scheduler.workflow_status(&workflow_id).await.unwrap(),
+                    WorkflowStatus::Completed
+                );
+                (workflow_id, ids)
+            }
+        };
+        let count = || runs.load(std::sync::atomic::Ordering::SeqCst);
+
+        let (_, (first_prepare, _)) = run_campaign("analyze").await;
+        assert_eq!(count(), 2);
+
+        // An identical re-run executes nothing
+        let (workflow_id, (prepare, analyze)) = run_campaign("analyze").await;
+        assert_eq!(count(), 2);
+        assert_eq!(
+            scheduler.status(&prepare).await.unwrap(),
+            ScheduledJobStatus::CachedComplete {
+                source: first_prepare
+            }
+        );
+        assert!(matches!(
+            scheduler.status(&analyze).await.unwrap(),
+            ScheduledJobStatus::CachedComplete { .. }
+        ));
+        assert_eq!(
+            scheduler.result(&analyze).await.unwrap().metadata,
+            serde_json::json!("analyze")
+        );
+        let progress = scheduler.workflow_progress(&workflow_id).await.unwrap();
+        assert_eq!((progress.completed, progress.cached), (2, 2));
+
+        // Changing one node runs only that node
+        let (_, (prepare, analyze)) = run_campaign("analyze_v2").await;
+        assert_eq!(count(), 3);
+        assert!(matches!(
+            scheduler.status(&prepare).await.unwrap(),
+            ScheduledJobStatus::CachedComplete { .. }
+        ));
+        assert_eq!(
+            scheduler.result(&analyze).await.unwrap().metadata,
+            serde_json::json!("analyze_v2")
+        );
+
+        // Jobs of workflows without caching always run
+        let workflow = WorkflowBuilder::new("uncached")
+            .add_job(ScheduledJob::classical(
+                "prepare",
+                ClassicalTask::closure("prepare"),
+            ))
+            .build();
+        scheduler.submit_workflow(workflow).await.unwrap();
+        scheduler.process_pending_jobs().await.unwrap();
+        assert_eq!(count(), 4);
+    }
+
+    /// Site hook stamping an account, refusing jobs named "forbidden" and
+    /// rejecting results of 13.
+    #[derive(Default)]
+    struct SiteHook {
+        failures: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SchedulerHook for SiteHook {
+        fn name(&self) -> &str {
+            "site"
+        }
+
+        async fn pre_submit(&self, job: &mut ScheduledJob) -> SchedResult<()> {
+            if job.name == "forbidden" {
+                return Err(SchedError::Internal("payload flagged".to_string()));
+            }
+            job.metadata
+                .insert("account".to_string(), "proj-1".to_string());
+            Ok(())
+        }
+
+        async fn post_complete(
+            &self,
+            _job: &ScheduledJob,
+            result: Option<&ExecutionResult>,
+        ) -> SchedResult<()> {
+            match result {
+                Some(r) if r.metadata == serde_json::json!(13) => {
+                    Err(SchedError::Internal("unlucky result".to_string()))
+                }
+                _ => Ok(()),
+            }
+        }
+
+        async fn on_failure(&self, _job: &ScheduledJob, reason: &str) -> SchedResult<()> {
+            self.failures.lock().unwrap().push(reason.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_hooks() {
+        let config = SchedulerConfig::default();
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, Vec::new(), store.clone());
+        let hook = Arc::new(SiteHook::default());
+        scheduler.register_hook(hook.clone(), HookErrorPolicy::Abort);
+        scheduler.register_task("lucky", |_| async { Ok(serde_json::json!(7)) });
+        scheduler.register_task("unlucky", |_| async { Ok(serde_json::json!(13)) });
+
+        let lucky = scheduler
+            .submit(ScheduledJob::classical(
+                "lucky",
+                ClassicalTask::closure("lucky"),
+            ))
+            .await
+            .unwrap();
+        let unlucky = scheduler
+            .submit(ScheduledJob::classical(
+                "unlucky",
+                ClassicalTask::closure("unlucky"),
+            ))
+            .await
+            .unwrap();
+        let stored = store.load_job(&lucky).await.unwrap().unwrap();
+        assert_eq!(stored.metadata.get("account").unwrap(), "proj-1");
+
+        let forbidden = ScheduledJob::classical("forbidden", ClassicalTask::closure("lucky"));
+        let forbidden_id = forbidden.id.clone();
+        let err = scheduler.submit(forbidden).await.unwrap_err();
+        assert!(matches!(err, SchedError::HookFailed { .. }), "{}", err);
+        assert!(store.load_job(&forbidden_id).await.unwrap().is_none());
+
+        scheduler.process_pending_jobs().await.unwrap();
+        assert!(scheduler.status(&lucky).await.unwrap().is_success());
+        assert!(matches!(
+            scheduler.status(&unlucky).await.unwrap(),
+            ScheduledJobStatus::Failed { ref reason, .. } if reason.contains("unlucky result")
+        ));
+        let failures = hook.failures.lock().unwrap().clone();
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].contains("post-complete hook site"));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_verification_node() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone());
+        scheduler.register_task("run", |_| async { Ok(serde_json::Value::Null) });
+
+        let sim = ScheduledJob::classical("sim", ClassicalTask::closure("run"));
+        let hw = ScheduledJob::classical("hw", ClassicalTask::closure("run"));
+        let (sim_id, hw_id) = (sim.id.clone(), hw.id.clone());
+        let verification = ResultVerification::new(sim_id.clone(), hw_id.clone()).with_tvd(0.1);
+
+        let workflow = scheduler
+            .create_workflow("validation")
+            .add_job(sim)
+            .add_job(hw)
+            .verify("check", verification)
+            .unwrap()
+            .build();
+        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+
+        // Stand in for circuit results that diverge by a TVD of 0.2.
+        scheduler.process_pending_jobs().await.unwrap();
+        let bell = Counts::from_pairs([("00", 500), ("11", 500)]);
+        let noisy = Counts::from_pairs([("00", 400), ("11", 400), ("01", 200)]);
+        store
+            .save_result(&sim_id, &ExecutionResult::new(bell, 1000))
+            .await
+            .unwrap();
+        store
+            .save_result(&hw_id, &ExecutionResult::new(noisy, 1000))
+            .await
+            .unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.update_job_statuses().await.unwrap();
+        assert!(matches!(
+            scheduler.workflow_status(&workflow_id).await.unwrap(),
+            WorkflowStatus::Failed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_breaker_reroutes_failed_job() {
+        let config = SchedulerConfig {
+            breaker: BreakerConfig::default().with_window(2).with_min_samples(2),
+            ..Default::default()
+        };
+        let backends: Vec<Arc<dyn Backend>> = vec![
+            Arc::new(MockBackend {
+                name: "qpu_a".to_string(),
+                num_qubits: 10,
+            }),
+            Arc::new(MockBackend {
+                name: "qpu_b".to_string(),
+                num_qubits: 10,
+            }),
+        ];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let log = Arc::new(InMemoryEventLog::new());
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store.clone())
+            .with_event_log(log.clone());
+        let mut events = scheduler.breaker_events();
+
+        let mut job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        job.matched_backend = Some("qpu_a".to_string());
+        store.save_job(&job).await.unwrap();
+
+        scheduler.breaker.record("qpu_a", false);
+        let failed = ScheduledJobStatus::Failed {
+            reason: "backend error".to_string(),
+            kind: FailureKind::Application,
+            slurm_job_id: Some("1000".to_string()),
+            quantum_job_id: None,
+        };
+        assert!(scheduler.reroute_if_tripped(&job, &failed).await.unwrap());
+        assert_eq!(scheduler.paused_backends(), vec!["qpu_a".to_string()]);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            BreakerEvent::Tripped { .. }
+        ));
+
+        scheduler.process_pending_jobs().await.unwrap();
+        let job = store.load_job(&job.id).await.unwrap().unwrap();
+        assert_eq!(job.matched_backend.as_deref(), Some("qpu_b"));
+        assert_eq!(job.reroutes, 1);
+        assert!(job.status.slurm_job_id().is_some());
+
+        let trace = crate::replay::replay(log.events(), &Default::default());
+        let decisions: Vec<_> = trace.steps.iter().map(|s| s.decision.as_str()).collect();
+        assert_eq!(
+            decisions[0],
+            "rerouted away from paused backend qpu_a (attempt 1)"
+        );
+        assert_eq!(decisions[1], "matched to backend qpu_b");
+        assert!(decisions[2].starts_with("dispatched to batch system as "));
+    }
+
+    #[tokio::test]
+    async fn test_delegated_job_management() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let audit = Arc::new(InMemoryAuditLog::new());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone())
+                .with_audit_log(audit.clone());
+
+        let job =
+            ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;")).with_owner("alice");
+        let job_id = scheduler.submit(job).await.unwrap();
+
+        let err = scheduler.cancel_as("lead", &job_id).await.unwrap_err();
+        assert!(matches!(err, SchedError::PermissionDenied(_)));
+
+        scheduler.grant(
+            "alice",
+            "lead",
+            &[JobAction::Reprioritize, JobAction::Cancel],
+        );
+        scheduler
+            .reprioritize_as("lead", &job_id, Priority::critical())
+            .await
+            .unwrap();
+        let stored = store.load_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(stored.priority, Priority::critical());
+
+        scheduler.cancel_as("lead", &job_id).await.unwrap();
+        assert_eq!(
+            scheduler.status(&job_id).await.unwrap(),
+            ScheduledJobStatus::Cancelled
+        );
+
+        // Only queued jobs can be reprioritized.
+        assert!(
+            scheduler
+                .reprioritize(&job_id, Priority::low())
+                .await
+                .is_err()
+        );
+
+        let events = audit.events();
+        let decisions: Vec<_> = events
+            .iter()
+            .map(|e| (e.principal.as_str(), e.action.as_str(), e.allowed))
+            .collect();
+        assert_eq!(
+            decisions,
+            vec![
+                ("lead", "cancel", false),
+                ("alice", "grant", true),
+                ("lead", "reprioritize", true),
+                ("lead", "cancel", true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_admin_interventions() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let audit = Arc::new(InMemoryAuditLog::new());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone())
+                .with_audit_log(audit.clone());
+        scheduler.access_policy().add_admin("ops");
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0;");
+        let stuck = scheduler
+            .submit(ScheduledJob::new("stuck", circuit.clone()).with_owner("alice"))
+            .await
+            .unwrap();
+        let queued = scheduler
+            .submit(ScheduledJob::new("queued", circuit.clone()).with_owner("alice"))
+            .await
+            .unwrap();
+        let other = scheduler
+            .submit(ScheduledJob::new("other", circuit).with_owner("bob"))
+            .await
+            .unwrap();
+
+        let err = scheduler
+            .force_fail("alice", &stuck, "node lost")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SchedError::PermissionDenied(_)));
+
+        scheduler
+            .force_fail("ops", &stuck, "node lost")
+            .await
+            .unwrap();
+        assert!(matches!(
+            scheduler.status(&stuck).await.unwrap(),
+            ScheduledJobStatus::Failed { reason, .. } if reason == "node lost"
+        ));
+
+        scheduler
+            .force_requeue("ops", &stuck, "node back")
+            .await
+            .unwrap();
+        assert_eq!(
+            scheduler.status(&stuck).await.unwrap(),
+            ScheduledJobStatus::Pending
+        );
+        assert!(scheduler.queue.read().await.contains(&stuck));
+
+        let mut purged = scheduler.purge_owner("ops", "alice").await.unwrap();
+        purged.sort_by_key(|id| id.to_string());
+        let mut expected = vec![stuck.clone(), queued];
+        expected.sort_by_key(|id| id.to_string());
+        assert_eq!(purged, expected);
+        assert_eq!(
+            scheduler.status(&stuck).await.unwrap(),
+            ScheduledJobStatus::Cancelled
+        );
+        assert_eq!(
+            scheduler.status(&other).await.unwrap(),
+            ScheduledJobStatus::Pending
+        );
+
+        assert_eq!(scheduler.rebuild_indices("ops").await.unwrap(), 3);
+
+        let actions: Vec<_> = audit
+            .events()
+            .into_iter()
+            .map(|e| (e.principal, e.action, e.allowed))
+            .collect();
+        let expected: Vec<_> = [
+            ("alice", "force_fail", false),
+            ("ops", "force_fail", true),
+            ("ops", "requeue", true),
+            ("ops", "purge", true),
+            ("ops", "reindex", true),
+        ]
+        .into_iter()
+        .map(|(p, a, allowed)| (p.to_string(), a.to_string(), allowed))
+        .collect();
+        assert_eq!(actions, expected);
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_task_fails() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone());
+
+        let job = ScheduledJob::classical("missing", ClassicalTask::closure("missing"));
+        let job_id = scheduler.submit(job).await.unwrap();
+        scheduler.process_pending_jobs().await.unwrap();
+
+        let status = scheduler.status(&job_id).await.unwrap();
+        assert!(matches!(status, ScheduledJobStatus::Failed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_native_slurm_dependency() {
+        let config = SchedulerConfig::default();
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store.clone());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job1 = ScheduledJob::new("job1", circuit.clone());
+        let job2 = ScheduledJob::new("job2", circuit);
+        let job1_id = job1.id.clone();
+        let job2_id = job2.id.clone();
+
+        let workflow = scheduler
+            .create_workflow("chained")
+            .add_job(job1)
+            .then_with(job2, DependencyKind::AfterAny)
+            .unwrap()
+            .build();
+        scheduler.submit_workflow(workflow).await.unwrap();
+
+        // First tick dispatches job1 only; job2 waits in the queue.
+        scheduler.process_pending_jobs().await.unwrap();
+        let job1 = store.load_job(&job1_id).await.unwrap().unwrap();
+        let batch_id = job1.status.slurm_job_id().unwrap().to_string();
+        assert!(scheduler.queue.read().await.contains(&job2_id));
+
+        // Second tick chains job2 on SLURM behind job1.
+        scheduler.process_pending_jobs().await.unwrap();
+        let job2 = store.load_job(&job2_id).await.unwrap().unwrap();
+        assert!(job2.status.slurm_job_id().is_some());
+        assert_eq!(
+            job2.batch_dependency,
+            Some(format!("afterany:{}", batch_id))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cloud_adapter_bypasses_batch_system() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let cloud = CloudQpuAdapter::new().with_provider(Arc::new(MockBackend {
+            name: "cloud_qpu".to_string(),
+            num_qubits: 10,
+        }));
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store.clone())
+                .with_cloud_adapter(cloud);
+
+        let circuit = || CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let single = scheduler
+            .submit(ScheduledJob::new("single", circuit()))
+            .await
+            .unwrap();
+        let batch = scheduler
+            .submit(ScheduledJob::batch("batch", vec![circuit(), circuit()]))
+            .await
+            .unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+
+        // The matcher picked the cloud provider for both; only the single
+        // circuit goes to it directly.
+        let job = store.load_job(&single).await.unwrap().unwrap();
+        assert_eq!(job.matched_backend.as_deref(), Some("cloud_qpu"));
+        assert_eq!(
+            job.status,
+            ScheduledJobStatus::QuantumSubmitted {
+                slurm_job_id: CLOUD_JOB_ID.to_string(),
+                quantum_job_id: arvak_hal::JobId("mock".to_string()),
+            }
+        );
+        let job = store.load_job(&batch).await.unwrap().unwrap();
+        assert!(matches!(job.status, ScheduledJobStatus::SlurmQueued { .. }));
+
+        scheduler.update_job_statuses().await.unwrap();
+        assert!(scheduler.status(&single).await.unwrap().is_success());
+        assert_eq!(scheduler.result(&single).await.unwrap().shots, 1000);
+    }
+
+    #[tokio::test]
+    async fn test_partial_results_published() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("results")).unwrap();
+        let mut config = SchedulerConfig::default();
+        config.slurm.work_dir = dir.path().to_path_buf();
+        config.slurm.partial_shots = Some(100);
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, Vec::new(), store);
+        let mut updates = scheduler.subscribe_results();
+
+        let job = ScheduledJob::new(
+            "long",
+            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];"),
+        )
+        .with_shots(400);
+        let snapshot = dir
+            .path()
+            .join("results")
+            .join(format!("{}.partial.json", job.id));
+
+        // Nothing written yet.
+        scheduler.ingest_partial_results(&job).await;
+        assert!(updates.try_recv().is_err());
+
+        let counts = arvak_hal::Counts::from_pairs([("00", 60), ("01", 40)]);
+        crate::partial::write_snapshot(&snapshot, &PartialResult::new(counts, 100, 400)).unwrap();
+        scheduler.ingest_partial_results(&job).await;
+        let update = updates.try_recv().unwrap();
+        assert_eq!(update.job_id, job.id);
+        assert_eq!(update.partial.shots_completed, 100);
+        assert!(!update.is_final());
+
+        // An unchanged snapshot is not republished, and a snapshot claiming
+        // to be final is still published as partial.
+        scheduler.ingest_partial_results(&job).await;
+        assert!(updates.try_recv().is_err());
+        let counts = arvak_hal::Counts::from_pairs([("00", 110), ("01", 90)]);
+        let mut partial = PartialResult::new(counts, 200, 400);
+        partial.is_final = true;
+        crate::partial::write_snapshot(&snapshot, &partial).unwrap();
+        scheduler.ingest_partial_results(&job).await;
+        assert!(!updates.try_recv().unwrap().is_final());
+        assert_eq!(scheduler.partial_results(&job.id).await.len(), 1);
+
+        let counts = arvak_hal::Counts::from_pairs([("00", 220), ("01", 180)]);
+        let result = ExecutionResult::new(counts, 400);
+        scheduler
+            .finish_partial_results(&job.id, Some(&result))
+            .await;
+        let update = updates.try_recv().unwrap();
+        assert!(update.is_final());
+        assert_eq!(update.partial.counts.get("00"), 220);
+        assert!(scheduler.partial_results(&job.id).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_negotiation() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "sim".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let log = Arc::new(InMemoryEventLog::new());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store.clone())
+                .with_event_log(log.clone());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];");
+        let split = ScheduledJob::new("long", circuit.clone())
+            .with_shots(250_000)
+            .with_preflight(CapabilityRequest::new().with_excess_shots(ShotPolicy::Split));
+        let report = scheduler.capability_report(&split).await.unwrap();
+        assert_eq!(report.backend, "sim");
+        assert_eq!(report.max_shots, 100_000);
+        assert!(!report.shots_supported());
+
+        let needs_reset = ScheduledJob::new("reset", circuit)
+            .with_preflight(CapabilityRequest::new().require_feature("dynamic_circuits"));
+        let split_id = scheduler.submit(split).await.unwrap();
+        let reset_id = scheduler.submit(needs_reset).await.unwrap();
+        scheduler.process_pending_jobs().await.unwrap();
+
+        let split = store.load_job(&split_id).await.unwrap().unwrap();
+        assert!(matches!(
+            split.status,
+            ScheduledJobStatus::SlurmQueued { .. }
+        ));
+        assert_eq!(split.circuits.len(), 3);
+        assert_eq!(split.total_shots(), 250_000);
+        assert!(matches!(
+            split.negotiation,
+            Some(Negotiation::Split { circuits: 3, .. })
+        ));
+
+        let reset = store.load_job(&reset_id).await.unwrap().unwrap();
+        assert!(
+            matches!(reset.status, ScheduledJobStatus::Failed { ref reason, .. }
+            if reason.contains("dynamic_circuits"))
+        );
+        assert!(log.events().iter().any(|e| e.job_id == reset_id
+            && matches!(&e.kind, EventKind::Negotiated { outcome } if !outcome.is_accepted())));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_submit_with_pbs() {
+        let config = SchedulerConfig::with_pbs(PbsConfig::default());
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler = HpcScheduler::with_mock_pbs(config, backends, store);
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job = ScheduledJob::new("pbs_test_job", circuit);
+        let job_id = job.id.clone();
+
+        let submitted_id = scheduler.submit(job).await.unwrap();
+        assert_eq!(submitted_id, job_id);
+
+        let status = scheduler.status(&job_id).await.unwrap();
+        assert!(status.is_pending());
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_dispatch_with_lsf() {
+        let config = SchedulerConfig::with_lsf(LsfConfig::default());
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler = HpcScheduler::with_mock_lsf(config, backends, store.clone());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job_id = scheduler
+            .submit(ScheduledJob::new("lsf_test_job", circuit))
+            .await
+            .unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        let job = store.load_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(job.status.slurm_job_id(), Some("1000"));
+
+        // The mock reports every job DONE.
+        scheduler.update_job_statuses().await.unwrap();
+        let status = scheduler.status(&job_id).await.unwrap();
+        assert!(matches!(status, ScheduledJobStatus::Completed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_dispatch_with_kubernetes() {
+        let config = SchedulerConfig::with_kubernetes(KubernetesConfig::default());
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+
+        let scheduler = HpcScheduler::with_mock_kubernetes(config, backends, store.clone());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job_id = scheduler
+            .submit(ScheduledJob::new("k8s_test_job", circuit))
+            .await
+            .unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        let job = store.load_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(job.status.slurm_job_id(), Some("arvak-1000"));
+
+        // The mock reports every Job complete.
+        scheduler.update_job_statuses().await.unwrap();
+        let status = scheduler.status(&job_id).await.unwrap();
+        assert!(matches!(status, ScheduledJobStatus::Completed { .. }));
+    }
+
+    /// Batch system finishing every job as soon as it is polled.
+    #[derive(Default)]
+    struct InstantBatch {
+        submitted: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl BatchSystem for InstantBatch {
+        fn name(&self) -> &str {
+            "instant"
+        }
+
+        async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+            let mut submitted = self.submitted.lock().unwrap();
+            submitted.push(job.name.clone());
+            Ok(format!("instant-{}", submitted.len()))
+        }
+
+        async fn poll(
+            &self,
+            _job: &ScheduledJob,
+            batch_job_id: &str,
+        ) -> SchedResult<ScheduledJobStatus> {
+            Ok(ScheduledJobStatus::Completed {
+                slurm_job_id: batch_job_id.to_string(),
+                quantum_job_id: arvak_hal::JobId("q-1".to_string()),
+            })
+        }
+
+        async fn cancel(&self, _batch_job_id: &str) -> SchedResult<()> {
+            Ok(())
+        }
+
+        async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+            let counts = Counts::from_pairs([("00", 10u64)]);
+            Ok(vec![CircuitResult {
+                index: 0,
+                label: job.name.clone(),
+                result: ExecutionResult::new(counts, 10),
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_with_custom_batch_system() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let batch = Arc::new(InstantBatch::default());
+        let scheduler = HpcScheduler::with_batch_system(
+            SchedulerConfig::default(),
+            batch.clone(),
+            backends,
+            store.clone(),
+        );
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job_id = scheduler
+            .submit(ScheduledJob::new("custom", circuit))
+            .await
+            .unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        assert_eq!(*batch.submitted.lock().unwrap(), vec!["custom".to_string()]);
+
+        scheduler.update_job_statuses().await.unwrap();
+        let job = store.load_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(job.status.slurm_job_id(), Some("instant-1"));
+        assert!(matches!(job.status, ScheduledJobStatus::Completed { .. }));
+        let results = scheduler.circuit_results(&job_id).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].result.counts.get("00"), 10);
+    }
+
+    /// Batch system answering every poll in one batch call, failing single
+    /// polls.
+    #[derive(Default)]
+    struct BulkBatch {
+        batches: std::sync::Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl BatchSystem for BulkBatch {
+        fn name(&self) -> &str {
+            "bulk"
+        }
+
+        async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+            Ok(format!("bulk-{}", job.name))
+        }
+
+        async fn poll(
+            &self,
+            _job: &ScheduledJob,
+            batch_job_id: &str,
+        ) -> SchedResult<ScheduledJobStatus> {
+            Err(SchedError::SlurmJobNotFound(batch_job_id.to_string()))
+        }
+
+        async fn poll_many(
+            &self,
+            jobs: &[(&ScheduledJob, &str)],
+        ) -> SchedResult<Vec<Option<ScheduledJobStatus>>> {
+            self.batches.lock().unwrap().push(jobs.len());
+            Ok(jobs
+                .iter()
+                .map(|(_, batch_job_id)| {
+                    Some(ScheduledJobStatus::SlurmRunning {
+                        slurm_job_id: batch_job_id.to_string(),
+                    })
+                })
+                .collect())
+        }
+
+        async fn cancel(&self, _batch_job_id: &str) -> SchedResult<()> {
+            Ok(())
+        }
+
+        async fn read_results(&self, _job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_statuses_are_polled_in_one_batch() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let batch = Arc::new(BulkBatch::default());
+        let scheduler = HpcScheduler::with_batch_system(
+            SchedulerConfig::default(),
+            batch.clone(),
+            vec![Arc::new(MockBackend {
+                name: "test_backend".to_string(),
+                num_qubits: 10,
+            })],
+            store.clone(),
+        );
+
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c"] {
+            let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q; h q[0];");
+            ids.push(
+                scheduler
+                    .submit(ScheduledJob::new(name, circuit))
+                    .await
+                    .unwrap(),
+            );
+        }
+        scheduler.process_pending_jobs().await.unwrap();
+
+        assert!(scheduler.update_job_statuses().await.unwrap());
+        assert_eq!(*batch.batches.lock().unwrap(), [3]);
+        for id in &ids {
+            let job = store.load_job(id).await.unwrap().unwrap();
+            assert!(matches!(
+                job.status,
+                ScheduledJobStatus::SlurmRunning { .. }
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_result_validation_fails_and_retries() {
+        let config = SchedulerConfig {
+            result_validation: Some(
+                ResultValidation::new().with_rule(ValidationRule::MinEntropy { bits: 0.5 }),
+            ),
+            ..Default::default()
+        };
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_batch_system(
+            config,
+            Arc::new(InstantBatch::default()),
+            vec![Arc::new(MockBackend {
+                name: "qpu".to_string(),
+                num_qubits: 10,
+            })],
+            store.clone(),
+        );
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let retried = scheduler
+            .submit(
+                ScheduledJob::new("retried", circuit.clone())
+                    .with_shots(10)
+                    .with_retry(RetryPolicy::new(2).with_backoff(Backoff::exponential(0))),
+            )
+            .await
+            .unwrap();
+        let flagged = scheduler
+            .submit(
+                ScheduledJob::new("flagged", circuit).with_validation(
+                    ResultValidation::new()
+                        .with_rule(ValidationRule::TotalShots)
+                        .with_action(ValidationAction::Flag),
+                ),
+            )
+            .await
+            .unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.update_job_statuses().await.unwrap();
+
+        // All-zero counts fail the entropy check and the job runs again.
+        let job = store.load_job(&retried).await.unwrap().unwrap();
+        assert_eq!(job.status, ScheduledJobStatus::Pending);
+        assert_eq!(job.attempts[0].kind, FailureKind::InvalidResult);
+
+        // Flagged results still complete the job.
+        let status = scheduler.status(&flagged).await.unwrap();
+        assert!(matches!(status, ScheduledJobStatus::Completed { .. }));
+
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.update_job_statuses().await.unwrap();
+        match scheduler.status(&retried).await.unwrap() {
+            ScheduledJobStatus::Failed { kind, reason, .. } => {
+                assert_eq!(kind, FailureKind::InvalidResult);
+                assert!(reason.contains("entropy"), "{}", reason);
+            }
+            status => panic!("unexpected status {:?}", status),
+        }
+    }
+
+    /// Batch system whose queued jobs all wait for resources, recording
+    /// requeued and cancelled jobs.
+    #[derive(Default)]
+    struct FullBatch {
+        requeued: std::sync::Mutex<Vec<String>>,
+        cancelled: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl BatchSystem for FullBatch {
+        fn name(&self) -> &str {
+            "full"
+        }
+
+        async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+            Ok(format!("full-{}", job.name))
+        }
+
+        async fn poll(
+            &self,
+            job: &ScheduledJob,
+            _batch_job_id: &str,
+        ) -> SchedResult<ScheduledJobStatus> {
+            Ok(job.status.clone())
+        }
+
+        async fn cancel(&self, batch_job_id: &str) -> SchedResult<()> {
+            self.cancelled
+                .lock()
+                .unwrap()
+                .push(batch_job_id.to_string());
+            Ok(())
+        }
+
+        async fn requeue(&self, batch_job_id: &str) -> SchedResult<()> {
+            self.requeued.lock().unwrap().push(batch_job_id.to_string());
+            Ok(())
+        }
+
+        async fn pending_reason(&self, _batch_job_id: &str) -> SchedResult<Option<String>> {
+            Ok(Some("Resources".to_string()))
+        }
+
+        async fn read_results(&self, _job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_urgent_job_preempts_running_jobs() {
+        let config = SchedulerConfig {
+            preemption: Some(
+                PreemptionConfig::default()
+                    .with_policy(Priority::LOW, PreemptionPolicy::Cancel)
+                    .with_policy(Priority::DEFAULT, PreemptionPolicy::Requeue),
+            ),
+            ..Default::default()
+        };
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let batch = Arc::new(FullBatch::default());
+        let scheduler =
+            HpcScheduler::with_batch_system(config, batch.clone(), Vec::new(), store.clone());
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let mut ids = Vec::new();
+        for (name, priority, queued) in [
+            ("low", Priority::LOW, false),
+            ("normal", Priority::DEFAULT, false),
+            ("urgent", Priority::urgent(), true),
+            ("urgent2", Priority::urgent(), true),
+        ] {
+            let mut job = ScheduledJob::new(name, circuit.clone()).with_priority(priority);
+            let slurm_job_id = format!("full-{}", name);
+            job.status = if queued {
+                ScheduledJobStatus::SlurmQueued { slurm_job_id }
+            } else {
+                ScheduledJobStatus::SlurmRunning { slurm_job_id }
+            };
+            job.submitted_at = Some(chrono::Utc::now());
+            store.save_job(&job).await.unwrap();
+            ids.push(job.id);
+        }
+
+        // Each urgent job preempts one job, the lowest priority first.
+        let preempted = scheduler.preempt_for_urgent().await.unwrap();
+        assert_eq!(preempted, vec![ids[0].clone(), ids[1].clone()]);
+        assert_eq!(*batch.cancelled.lock().unwrap(), vec!["full-low"]);
+        assert_eq!(*batch.requeued.lock().unwrap(), vec!["full-normal"]);
+
+        // The cancelled job is queued again; the requeued one waits in the
+        // batch queue.
+        assert_eq!(
+            scheduler.status(&ids[0]).await.unwrap(),
+            ScheduledJobStatus::Pending
+        );
+        assert!(scheduler.queue.read().await.contains(&ids[0]));
+        assert_eq!(
+            scheduler.status(&ids[1]).await.unwrap(),
+            ScheduledJobStatus::SlurmQueued {
+                slurm_job_id: "full-normal".to_string()
+            }
+        );
+
+        // Nothing is left to preempt, and urgent jobs wait out their grace.
+        assert!(scheduler.preempt_for_urgent().await.unwrap().is_empty());
+    }
+
+    /// Batch system failing the first polled jobs with a given kind.
+    struct FlakyBatch {
+        failures: std::sync::Mutex<Vec<FailureKind>>,
+        submitted: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl BatchSystem for FlakyBatch {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn submit(&self, _job: &ScheduledJob) -> SchedResult<String> {
+            let n = self
+                .submitted
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(format!("flaky-{}", n + 1))
+        }
+
+        async fn poll(
+            &self,
+            _job: &ScheduledJob,
+            batch_job_id: &str,
+        ) -> SchedResult<ScheduledJobStatus> {
+            let failure = self.failures.lock().unwrap().pop();
+            Ok(match failure {
+                Some(kind) => ScheduledJobStatus::Failed {
+                    reason: format!("{} on {}", kind, batch_job_id),
+                    kind,
+                    slurm_job_id: Some(batch_job_id.to_string()),
+                    quantum_job_id: None,
+                },
+                None => ScheduledJobStatus::Completed {
+                    slurm_job_id: batch_job_id.to_string(),
+                    quantum_job_id: arvak_hal::JobId("q-1".to_string()),
+                },
+            })
+        }
+
+        async fn cancel(&self, _batch_job_id: &str) -> SchedResult<()> {
+            Ok(())
+        }
+
+        async fn read_results(&self, _job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn flaky_scheduler(failures: Vec<FailureKind>) -> (HpcScheduler, Arc<FlakyBatch>) {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let batch = Arc::new(FlakyBatch {
+            failures: std::sync::Mutex::new(failures),
+            submitted: Default::default(),
+        });
+        let scheduler = HpcScheduler::with_batch_system(
+            SchedulerConfig::default(),
+            batch.clone(),
+            backends,
+            Arc::new(SqliteStore::in_memory().unwrap()),
+        );
+        (scheduler, batch)
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_node_failure() {
+        let (scheduler, batch) =
+            flaky_scheduler(vec![FailureKind::Timeout, FailureKind::NodeFailure]);
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::new("flaky", circuit)
+            .with_retry(RetryPolicy::new(3).with_backoff(Backoff::exponential(0)));
+        let job_id = scheduler.submit(job).await.unwrap();
+
+        for _ in 0..3 {
+            scheduler.process_pending_jobs().await.unwrap();
+            scheduler.update_job_statuses().await.unwrap();
+        }
+        assert_eq!(batch.submitted.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert!(scheduler.status(&job_id).await.unwrap().is_success());
+
+        let job = scheduler.load_job(&job_id).await.unwrap();
+        let kinds: Vec<FailureKind> = job.attempts.iter().map(|a| a.kind).collect();
+        assert_eq!(kinds, vec![FailureKind::NodeFailure, FailureKind::Timeout]);
+        assert_eq!(job.attempts[0].batch_job_id.as_deref(), Some("flaky-1"));
+        assert_eq!(job.attempts[1].number, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_application_errors_and_backs_off() {
+        let (scheduler, batch) =
+            flaky_scheduler(vec![FailureKind::Application, FailureKind::NodeFailure]);
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::new("flaky", circuit)
+            .with_retry(RetryPolicy::new(3).with_backoff(Backoff::exponential(3_600)));
+        let job_id = scheduler.submit(job).await.unwrap();
+
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.update_job_statuses().await.unwrap();
+        let explanation = scheduler.explain(&job_id).await.unwrap();
+        assert!(matches!(
+            explanation.holds[..],
+            [Hold::RetryBackoff { attempt: 2, .. }]
+        ));
+
+        // Still backing off, so not resubmitted.
+        scheduler.process_pending_jobs().await.unwrap();
+        assert_eq!(batch.submitted.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // An application error is final even with attempts left.
+        scheduler
+            .queue
+            .write()
+            .await
+            .get_mut(&job_id)
+            .unwrap()
+            .not_before = None;
+        scheduler.process_pending_jobs().await.unwrap();
+        scheduler.update_job_statuses().await.unwrap();
+        assert!(matches!(
+            scheduler.status(&job_id).await.unwrap(),
+            ScheduledJobStatus::Failed {
+                kind: FailureKind::Application,
+                ..
+            }
+        ));
+        assert_eq!(scheduler.load_job(&job_id).await.unwrap().attempts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_submit_array() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+        let circuits = (0..3).map(|_| CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];"));
+        let job_ids = scheduler
+            .submit_array(JobArraySpec::from_circuits("sweep", circuits))
+            .await
+            .unwrap();
+        assert_eq!(job_ids.len(), 3);
+
+        scheduler.process_pending_jobs().await.unwrap();
+        let mut batch_job_ids = Vec::new();
+        for job_id in &job_ids {
+            let status = scheduler.status(job_id).await.unwrap();
+            batch_job_ids.push(status.slurm_job_id().unwrap().to_string());
+        }
+        // One array job, one task per member
+        let (array_job_id, _) = batch_job_ids[0].split_once('_').unwrap();
+        let mut tasks: Vec<String> = batch_job_ids
+            .iter()
+            .map(|id| id.strip_prefix(array_job_id).unwrap().to_string())
+            .collect();
+        tasks.sort();
+        assert_eq!(tasks, ["_0", "_1", "_2"]);
+
+        // Members are tracked by their own task
+        scheduler.update_job_statuses().await.unwrap();
+        for (job_id, batch_job_id) in job_ids.iter().zip(&batch_job_ids) {
+            let status = scheduler.status(job_id).await.unwrap();
+            assert!(status.is_success());
+            assert_eq!(status.slurm_job_id(), Some(batch_job_id.as_str()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recover_from_write_ahead_log() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.wal");
+        let adapter = Arc::new(SlurmAdapter::mock(SlurmConfig::default()));
+        let store: Arc<dyn StateStore> = Arc::new(SqliteStore::in_memory().unwrap());
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];");
+
+        let wal = Arc::new(WriteAheadLog::open(&path).unwrap());
+        let scheduler = HpcScheduler::with_batch_system(
+            SchedulerConfig::default(),
+            adapter.clone(),
+            backends.clone(),
+            store.clone(),
+        )
+        .with_write_ahead_log(wal.clone());
+        let submitted = ScheduledJob::new("submitted", circuit.clone());
+        let lost = ScheduledJob::new("lost", circuit);
+        let (submitted_id, lost_id) = (submitted.id.clone(), lost.id.clone());
+        scheduler.submit(submitted).await.unwrap();
+        scheduler.submit(lost).await.unwrap();
+
+        // Crash after sbatch accepted one job but before its ID was saved,
+        // and before the other was handed over at all
+        wal.append(&submitted_id, WalOp::Dispatch).await.unwrap();
+        let job = store.load_job(&submitted_id).await.unwrap().unwrap();
+        let batch_job_id = adapter.submit(&job).await.unwrap();
+        wal.append(&lost_id, WalOp::Dispatch).await.unwrap();
+        drop((scheduler, wal));
+
+        let scheduler =
+            HpcScheduler::with_batch_system(SchedulerConfig::default(), adapter, backends, store)
+                .with_write_ahead_log(Arc::new(WriteAheadLog::open(&path).unwrap()));
+        let report = scheduler.recover().await.unwrap();
+        assert_eq!(
+            report.adopted,
+            vec![(submitted_id.clone(), batch_job_id.clone())]
+        );
+        assert_eq!(report.requeued, vec![lost_id.clone()]);
+
+        let status = scheduler.status(&submitted_id).await.unwrap();
+        assert_eq!(status.slurm_job_id(), Some(batch_job_id.as_str()));
+        assert!(scheduler.queue.read().await.contains(&lost_id));
+        assert!(!scheduler.queue.read().await.contains(&submitted_id));
+        assert!(WriteAheadLog::read(&path).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_status_events_push_updates() {
+        use futures::StreamExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = SchedulerConfig::default();
+        config.slurm.event_dir = Some(dir.path().to_path_buf());
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
 
-        loop {
-            let status = self.workflow_status(workflow_id).await?;
+        let job_id = scheduler
+            .submit(ScheduledJob::new("evented", circuit.clone()))
+            .await
+            .unwrap();
+        let quiet_id = scheduler
+            .submit(ScheduledJob::new("quiet", circuit))
+            .await
+            .unwrap();
+        let updates = scheduler.subscribe(&job_id).await.unwrap();
+        scheduler.process_pending_jobs().await.unwrap();
 
-            if status.is_terminal() {
-                return match status {
-                    WorkflowStatus::Completed => Ok(()),
-                    WorkflowStatus::Failed { reason } => {
-                        Err(SchedError::Internal(format!("Workflow failed: {}", reason)))
-                    }
-                    WorkflowStatus::Cancelled => {
-                        Err(SchedError::Cancelled(workflow_id.to_string()))
-                    }
-                    _ => unreachable!(),
-                };
-            }
+        let status = scheduler.status(&job_id).await.unwrap();
+        let batch_job_id = status.slurm_job_id().unwrap();
+        std::fs::write(dir.path().join(format!("{}.event", batch_job_id)), "").unwrap();
+        let mut pending = BTreeMap::new();
+        assert!(scheduler.process_status_events(&mut pending).await.unwrap());
+        assert!(pending.is_empty());
 
-            if start.elapsed() > max_wait {
-                return Err(SchedError::Timeout(format!(
-                    "Timeout waiting for workflow {}",
-                    workflow_id
-                )));
-            }
+        // The stream ends with the terminal status.
+        let names: Vec<&str> = updates
+            .collect::<Vec<_>>()
+            .await
+            .iter()
+            .map(|s| s.name())
+            .collect();
+        assert_eq!(names, ["Pending", "SlurmQueued", "Completed"]);
 
-            tokio::time::sleep(poll_interval).await;
-        }
+        // Jobs without events wait for the fallback poll.
+        assert!(matches!(
+            scheduler.status(&quiet_id).await.unwrap(),
+            ScheduledJobStatus::SlurmQueued { .. }
+        ));
+        assert!(!scheduler.process_status_events(&mut pending).await.unwrap());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::persistence::SqliteStore;
-    use arvak_hal::{Capabilities, Counts};
+    #[tokio::test]
+    async fn test_fair_share_reorders_queue() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let config = SchedulerConfig {
+            fair_share: Some(FairShareConfig::default()),
+            ..Default::default()
+        };
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
 
-    /// Mock backend for testing.
-    struct MockBackend {
-        name: String,
-        num_qubits: u32,
-    }
+        // Alice ran for an hour recently.
+        let now = chrono::Utc::now();
+        let mut past = ScheduledJob::new("past", circuit.clone()).with_owner("alice");
+        past.status = ScheduledJobStatus::Completed {
+            slurm_job_id: "1".to_string(),
+            quantum_job_id: arvak_hal::JobId("q-1".to_string()),
+        };
+        past.submitted_at = Some(now - chrono::Duration::hours(1));
+        past.completed_at = Some(now);
+        store.save_job(&past).await.unwrap();
 
-    #[async_trait]
-    impl Backend for MockBackend {
-        fn name(&self) -> &str {
-            &self.name
-        }
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+        let alice = ScheduledJob::new("alice", circuit.clone()).with_owner("alice");
+        let bob = ScheduledJob::new("bob", circuit).with_owner("bob");
+        scheduler.submit(alice).await.unwrap();
+        let bob_id = scheduler.submit(bob).await.unwrap();
+        assert_eq!(scheduler.queue.read().await.peek().unwrap().name, "alice");
 
-        async fn capabilities(&self) -> arvak_hal::HalResult<Capabilities> {
-            Ok(Capabilities::simulator(self.num_qubits))
-        }
+        scheduler.apply_fair_share().await.unwrap();
+        let policy = scheduler.fair_share().unwrap();
+        assert!(policy.usage("alice", chrono::Utc::now()) > 3_500.0);
+        let queue = scheduler.queue.read().await;
+        assert_eq!(queue.peek().unwrap().name, "bob");
+        assert_eq!(queue.get(&bob_id).unwrap().priority, Priority::default());
+    }
 
-        async fn is_available(&self) -> arvak_hal::HalResult<bool> {
-            Ok(true)
-        }
+    #[tokio::test]
+    async fn test_node_features_follow_matched_backend() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let config = SchedulerConfig {
+            node_features: BTreeMap::from([(
+                "test_backend".to_string(),
+                vec!["qctrl".to_string()],
+            )]),
+            ..Default::default()
+        };
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store.clone());
 
-        async fn submit(
-            &self,
-            _circuit: &arvak_ir::Circuit,
-            _shots: u32,
-        ) -> arvak_hal::HalResult<arvak_hal::JobId> {
-            Ok(arvak_hal::JobId("mock".to_string()))
-        }
+        let job = ScheduledJob::new(
+            "controlled",
+            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"),
+        )
+        .with_requirements(ResourceRequirements::new(2).require_node_feature("ib"));
+        let job_id = scheduler.submit(job).await.unwrap();
+        scheduler.process_pending_jobs().await.unwrap();
 
-        async fn status(
-            &self,
-            _job_id: &arvak_hal::JobId,
-        ) -> arvak_hal::HalResult<arvak_hal::JobStatus> {
-            Ok(arvak_hal::JobStatus::Completed)
-        }
+        let job = store.load_job(&job_id).await.unwrap().unwrap();
+        assert_eq!(job.matched_backend.as_deref(), Some("test_backend"));
+        assert_eq!(job.node_features, vec!["qctrl", "ib"]);
+    }
 
-        async fn result(
-            &self,
-            _job_id: &arvak_hal::JobId,
-        ) -> arvak_hal::HalResult<arvak_hal::ExecutionResult> {
-            let counts = Counts::from_pairs([("00", 500u64), ("11", 500u64)]);
-            Ok(arvak_hal::ExecutionResult::new(counts, 1000))
-        }
+    #[tokio::test]
+    async fn test_queue_policy_backfill() {
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        for (policy, dispatched) in [(QueuePolicy::Strict, false), (QueuePolicy::Backfill, true)] {
+            let config = SchedulerConfig {
+                queue_policy: policy,
+                breaker: BreakerConfig::default().with_window(2).with_min_samples(2),
+                ..Default::default()
+            };
+            let backends: Vec<Arc<dyn Backend>> = ["qpu_a", "qpu_b"]
+                .into_iter()
+                .map(|name| {
+                    Arc::new(MockBackend {
+                        name: name.to_string(),
+                        num_qubits: 10,
+                    }) as Arc<dyn Backend>
+                })
+                .collect();
+            let store = Arc::new(SqliteStore::in_memory().unwrap());
+            let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+            scheduler.breaker.record("qpu_a", false);
+            scheduler.breaker.record("qpu_a", false);
+            assert!(scheduler.breaker.is_paused("qpu_a"));
 
-        async fn cancel(&self, _job_id: &arvak_hal::JobId) -> arvak_hal::HalResult<()> {
-            Ok(())
-        }
+            // The head waits for its paused backend.
+            let mut head =
+                ScheduledJob::new("head", circuit.clone()).with_priority(Priority::high());
+            head.matched_backend = Some("qpu_a".to_string());
+            let head_id = scheduler.submit(head).await.unwrap();
+            let small_id = scheduler
+                .submit(ScheduledJob::new("small", circuit.clone()))
+                .await
+                .unwrap();
 
-        async fn wait(
-            &self,
-            job_id: &arvak_hal::JobId,
-        ) -> arvak_hal::HalResult<arvak_hal::ExecutionResult> {
-            self.result(job_id).await
+            scheduler.process_pending_jobs().await.unwrap();
+            assert!(scheduler.status(&head_id).await.unwrap().is_pending());
+            let small = scheduler.status(&small_id).await.unwrap();
+            assert_eq!(small.slurm_job_id().is_some(), dispatched, "{:?}", policy);
         }
     }
 
     #[tokio::test]
-    async fn test_scheduler_submit() {
-        let config = SchedulerConfig::default();
+    async fn test_reservations() {
         let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
             name: "test_backend".to_string(),
             num_qubits: 10,
         })];
         let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+        let start = chrono::Utc::now() + chrono::Duration::hours(1);
+        let window = TimeWindow::starting_at(start, Duration::from_secs(3_600)).unwrap();
 
-        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+        assert!(matches!(
+            scheduler.reserve("unknown", window, 1).await,
+            Err(SchedError::NoMatchingBackend(_))
+        ));
+        let reservation = scheduler.reserve("test_backend", window, 1).await.unwrap();
+        let overlapping = TimeWindow::starting_at(
+            start + chrono::Duration::minutes(30),
+            Duration::from_secs(3_600),
+        )
+        .unwrap();
+        assert!(
+            scheduler
+                .reserve("test_backend", overlapping, 1)
+                .await
+                .is_err()
+        );
+        assert_eq!(scheduler.reservations().await.unwrap().len(), 1);
 
-        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
-        let job = ScheduledJob::new("test_job", circuit);
-        let job_id = job.id.clone();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = |secs| {
+            ScheduledJob::new("windowed", circuit.clone())
+                .with_walltime(Duration::from_secs(secs))
+                .with_reservation(&reservation.name)
+        };
+        let job_id = scheduler.submit(job(2_400)).await.unwrap();
+        let admitted = scheduler.load_job(&job_id).await.unwrap();
+        assert_eq!(admitted.matched_backend.as_deref(), Some("test_backend"));
+        assert_eq!(admitted.not_before, Some(start));
+        assert_eq!(admitted.deadline, Some(window.end));
 
-        let submitted_id = scheduler.submit(job).await.unwrap();
-        assert_eq!(submitted_id, job_id);
+        // Held until the window opens
+        scheduler.process_pending_jobs().await.unwrap();
+        assert!(scheduler.status(&job_id).await.unwrap().is_pending());
+        let explanation = scheduler.explain(&job_id).await.unwrap();
+        assert!(matches!(
+            explanation.holds.as_slice(),
+            [Hold::Reservation { opens, .. }] if *opens == start
+        ));
 
-        let status = scheduler.status(&job_id).await.unwrap();
-        assert!(status.is_pending());
+        // Too long for the window, or for the capacity left
+        for secs in [7_200, 2_400] {
+            assert!(matches!(
+                scheduler.submit(job(secs)).await,
+                Err(SchedError::InvalidPayload(_))
+            ));
+        }
+        assert!(matches!(
+            scheduler
+                .submit(ScheduledJob::new("lost", circuit.clone()).with_reservation("missing"))
+                .await,
+            Err(SchedError::ReservationNotFound(_))
+        ));
+
+        let cancelled = scheduler
+            .release_reservation(&reservation.name)
+            .await
+            .unwrap();
+        assert_eq!(cancelled, [job_id]);
+        assert!(scheduler.reservations().await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_scheduler_submit_batch() {
-        let config = SchedulerConfig::default();
+    async fn test_submit_template() {
         let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
             name: "test_backend".to_string(),
             num_qubits: 10,
         })];
         let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+        let template =
+            JobTemplate::new("bell", CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"))
+                .with_shots(300)
+                .with_label("project", "demo");
 
-        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
-
-        let circuits = vec![
-            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0];"),
-            CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; x q[0];"),
-        ];
+        assert!(matches!(
+            scheduler
+                .submit_template("bell", TemplateOverrides::new())
+                .await,
+            Err(SchedError::TemplateNotFound(_))
+        ));
+        assert!(
+            scheduler
+                .save_template(JobTemplate::new("a/b", template.circuit.clone()))
+                .await
+                .is_err()
+        );
+        scheduler.save_template(template.clone()).await.unwrap();
+        let created = scheduler.template("bell").await.unwrap().created_at;
+        scheduler
+            .save_template(template.with_shots(400))
+            .await
+            .unwrap();
+        assert_eq!(
+            scheduler.template("bell").await.unwrap().created_at,
+            created
+        );
 
         let job_id = scheduler
-            .submit_batch(
-                "batch_test",
-                circuits,
-                1000,
-                Priority::default(),
-                ResourceRequirements::default(),
-            )
+            .submit_template("bell", TemplateOverrides::new().with_name("bell-1"))
             .await
             .unwrap();
+        let job = scheduler.load_job(&job_id).await.unwrap();
+        assert_eq!((job.name.as_str(), job.shots), ("bell-1", 400));
+        assert_eq!(job.metadata["project"], "demo");
 
-        let status = scheduler.status(&job_id).await.unwrap();
-        assert!(status.is_pending());
+        scheduler.delete_template("bell").await.unwrap();
+        assert!(scheduler.templates().await.unwrap().is_empty());
+        assert!(scheduler.delete_template("bell").await.is_err());
     }
 
     #[tokio::test]
-    async fn test_scheduler_workflow() {
-        let config = SchedulerConfig::default();
+    async fn test_recurring_jobs() {
         let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
             name: "test_backend".to_string(),
             num_qubits: 10,
         })];
         let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store);
+        let job = ScheduledJob::new("suite", CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"));
+        let schedule = crate::recurring::CronSchedule::parse("0 6 * * *").unwrap();
+        scheduler
+            .save_recurring(RecurringJob::new("suite", schedule, job).with_max_history(2))
+            .await
+            .unwrap();
 
-        let scheduler = HpcScheduler::with_mock_slurm(config, backends, store);
+        let next = scheduler
+            .recurring_job("suite")
+            .await
+            .unwrap()
+            .next_run
+            .unwrap();
+        assert!(
+            scheduler
+                .submit_recurring_due_at(next - chrono::Duration::minutes(1))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Three days late: one catch-up run, then the next 06:00 after now
+        let late = next + chrono::Duration::days(3);
+        let first = scheduler.submit_recurring_due_at(late).await.unwrap();
+        assert_eq!(first.len(), 1);
+        let run = scheduler.load_job(&first[0]).await.unwrap();
+        assert_eq!(run.metadata[RECURRING_METADATA_KEY], "suite");
+        let recurring = scheduler.recurring_job("suite").await.unwrap();
+        assert_eq!(recurring.history[0].scheduled_for, next);
+        assert_eq!(recurring.next_run, Some(next + chrono::Duration::days(4)));
+
+        // Paused schedules submit nothing; resuming skips the missed runs
+        scheduler.pause_recurring("suite").await.unwrap();
+        let later = late + chrono::Duration::days(2);
+        assert!(
+            scheduler
+                .submit_recurring_due_at(later)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        scheduler.resume_recurring("suite").await.unwrap();
+        let recurring = scheduler.recurring_job("suite").await.unwrap();
+        assert!(!recurring.paused);
+        assert!(recurring.next_run.unwrap() > chrono::Utc::now());
+
+        for day in 1..=2 {
+            let due = recurring.next_run.unwrap() + chrono::Duration::days(day);
+            assert_eq!(
+                scheduler.submit_recurring_due_at(due).await.unwrap().len(),
+                1
+            );
+        }
+        let history = scheduler.recurring_job("suite").await.unwrap().history;
+        assert_eq!(history.len(), 2);
+        assert!(!history.iter().any(|run| run.job_id == first[0]));
 
+        scheduler.delete_recurring("suite").await.unwrap();
+        assert!(matches!(
+            scheduler.recurring_job("suite").await,
+            Err(SchedError::RecurringJobNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_submit_with_admission() {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+            name: "test_backend".to_string(),
+            num_qubits: 10,
+        })];
+        let config = SchedulerConfig {
+            poll_interval_secs: 1,
+            congested_queue_depth: 3,
+            ..Default::default()
+        };
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = Arc::new(HpcScheduler::with_mock_slurm(config, backends, store));
         let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
-        let job1 = ScheduledJob::new("job1", circuit.clone());
-        let job2 = ScheduledJob::new("job2", circuit);
+        let job = |name: &str| {
+            ScheduledJob::new(name, circuit.clone())
+                .with_walltime(std::time::Duration::from_secs(600))
+        };
 
-        let workflow = scheduler
-            .create_workflow("test_workflow")
-            .add_job(job1)
-            .then(job2)
-            .unwrap()
-            .build();
+        let first = scheduler.submit_with_admission(job("first")).await.unwrap();
+        assert_eq!(first.class, AdmissionClass::Immediate);
+        let second = scheduler
+            .submit_with_admission(job("second"))
+            .await
+            .unwrap();
+        assert_eq!(second.class, AdmissionClass::Queued);
+        assert_eq!((second.position, second.jobs_ahead), (Some(2), 1));
+        assert!(
+            second.estimated_start.unwrap() >= chrono::Utc::now() + chrono::Duration::minutes(9)
+        );
+        let third = scheduler.submit_with_admission(job("third")).await.unwrap();
+        assert_eq!(third.class, AdmissionClass::Congested);
 
-        let workflow_id = scheduler.submit_workflow(workflow).await.unwrap();
+        // Waiting returns once the job reached the batch system.
+        let waiter = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move { scheduler.wait_dispatched(&first.job_id).await })
+        };
+        scheduler.process_pending_jobs().await.unwrap();
+        let status = waiter.await.unwrap().unwrap();
+        assert!(status.slurm_job_id().is_some());
 
-        let status = scheduler.workflow_status(&workflow_id).await.unwrap();
-        assert!(matches!(status, WorkflowStatus::Pending));
+        let dispatched = scheduler.admission(&second.job_id).await.unwrap();
+        assert_eq!(dispatched.position, None);
     }
 
     #[tokio::test]
-    async fn test_scheduler_submit_with_pbs() {
-        let config = SchedulerConfig::with_pbs(PbsConfig::default());
+    async fn test_deadline_exceeded() {
         let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
             name: "test_backend".to_string(),
             num_qubits: 10,
         })];
         let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store.clone());
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let now = chrono::Utc::now();
 
-        let scheduler = HpcScheduler::with_mock_pbs(config, backends, store);
+        // Two hours of walltime cannot fit into the next minute.
+        let late = ScheduledJob::new("late", circuit.clone())
+            .with_walltime(std::time::Duration::from_secs(2 * 3_600))
+            .with_deadline(now + chrono::Duration::minutes(1));
+        let on_time = ScheduledJob::new("on_time", circuit)
+            .with_walltime(std::time::Duration::from_secs(600))
+            .with_deadline(now + chrono::Duration::hours(1));
+        let late_id = scheduler.submit(late).await.unwrap();
+        let on_time_id = scheduler.submit(on_time).await.unwrap();
 
-        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
-        let job = ScheduledJob::new("pbs_test_job", circuit);
-        let job_id = job.id.clone();
+        scheduler.process_pending_jobs().await.unwrap();
+        assert!(matches!(
+            scheduler.status(&late_id).await.unwrap(),
+            ScheduledJobStatus::DeadlineExceeded { .. }
+        ));
+        assert!(
+            scheduler
+                .status(&on_time_id)
+                .await
+                .unwrap()
+                .slurm_job_id()
+                .is_some()
+        );
 
-        let submitted_id = scheduler.submit(job).await.unwrap();
-        assert_eq!(submitted_id, job_id);
+        // A dispatched job is stopped once its deadline passes.
+        let mut job = scheduler.load_job(&on_time_id).await.unwrap();
+        job.deadline = Some(now - chrono::Duration::seconds(1));
+        store.save_job(&job).await.unwrap();
+        scheduler.update_job_statuses().await.unwrap();
+        let status = scheduler.status(&on_time_id).await.unwrap();
+        assert!(matches!(
+            status,
+            ScheduledJobStatus::DeadlineExceeded { .. }
+        ));
+        assert!(status.is_terminal() && !status.is_success());
+    }
 
-        let status = scheduler.status(&job_id).await.unwrap();
-        assert!(status.is_pending());
+    #[tokio::test]
+    async fn test_leader_election_single_dispatcher() {
+        use crate::leader::LeaderElector;
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let make = |id: &str| {
+            let elector =
+                LeaderElector::new(store.clone(), Duration::from_secs(60)).with_instance_id(id);
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), vec![], store.clone())
+                .with_leader_election(Arc::new(elector))
+        };
+        let a = make("a");
+        let b = make("b");
+
+        // Job submitted through the follower is picked up by the leader.
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job_id = b.submit(ScheduledJob::new("ha", circuit)).await.unwrap();
+
+        assert!(a.refresh_leadership().await.unwrap());
+        assert!(!b.refresh_leadership().await.unwrap());
+        assert!(a.is_leader());
+        assert!(!b.is_leader());
+        assert!(a.queue.read().await.contains(&job_id));
+    }
+
+    #[tokio::test]
+    async fn test_stalled_leader_is_fenced_out() {
+        use crate::leader::LeaderElector;
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let make = |id: &str| {
+            let elector =
+                LeaderElector::new(store.clone(), Duration::from_millis(50)).with_instance_id(id);
+            let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(MockBackend {
+                name: "test_backend".to_string(),
+                num_qubits: 10,
+            })];
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), backends, store.clone())
+                .with_leader_election(Arc::new(elector))
+        };
+        let a = make("a");
+        let b = make("b");
+
+        assert!(a.refresh_leadership().await.unwrap());
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job_id = a.submit(ScheduledJob::new("ha", circuit)).await.unwrap();
+
+        // A stalls past its lease expiry and B takes over. A still believes
+        // it leads, but its dispatch is refused by the fencing token.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(b.refresh_leadership().await.unwrap());
+        assert!(a.is_leader());
+        let err = a.process_pending_jobs().await.unwrap_err();
+        assert!(matches!(err, SchedError::LeaseLost(_)));
+        assert!(!a.is_leader());
+        let job = store.load_job(&job_id).await.unwrap().unwrap();
+        assert!(job.batch_job_id.is_none());
+
+        b.process_pending_jobs().await.unwrap();
+        let job = store.load_job(&job_id).await.unwrap().unwrap();
+        assert!(job.batch_job_id.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_queue_sync_is_incremental() {
+        use crate::leader::LeaderElector;
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let elector = LeaderElector::new(store.clone(), Duration::from_secs(60));
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), vec![], store.clone())
+                .with_leader_election(Arc::new(elector));
+        assert!(scheduler.refresh_leadership().await.unwrap());
+
+        // A job created through another instance is picked up on the next
+        // tick; an old job turning pending again waits for a full rescan.
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let new = ScheduledJob::new("new", circuit.clone());
+        let mut requeued = ScheduledJob::new("requeued", circuit);
+        requeued.created_at = chrono::Utc::now() - chrono::Duration::hours(1);
+        store.save_job(&new).await.unwrap();
+        store.save_job(&requeued).await.unwrap();
+
+        assert!(scheduler.refresh_leadership().await.unwrap());
+        assert!(scheduler.queue.read().await.contains(&new.id));
+        assert!(!scheduler.queue.read().await.contains(&requeued.id));
+
+        scheduler.queue_sync.lock().unwrap().last_full = None;
+        assert!(scheduler.refresh_leadership().await.unwrap());
+        assert!(scheduler.queue.read().await.contains(&requeued.id));
+    }
+
+    #[tokio::test]
+    async fn test_update_config() {
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler =
+            HpcScheduler::with_mock_slurm(SchedulerConfig::default(), Vec::new(), store);
+
+        let changes = scheduler
+            .update_config(&SchedulerConfigUpdate::new().with_poll_interval_secs(2))
+            .unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(scheduler.config().poll_interval_secs, 2);
+
+        let rejected = SchedulerConfigUpdate {
+            poll_interval_secs: Some(1),
+            state_dir: Some(PathBuf::from("/elsewhere")),
+            ..Default::default()
+        };
+        assert!(scheduler.update_config(&rejected).is_err());
+        assert_eq!(scheduler.config().poll_interval_secs, 2);
+    }
+
+    #[tokio::test]
+    async fn test_watch_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = Arc::new(HpcScheduler::with_mock_slurm(
+            SchedulerConfig::default(),
+            Vec::new(),
+            store,
+        ));
+        let handle = scheduler
+            .clone()
+            .watch_config(&path, Duration::from_millis(10));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        std::fs::write(&path, r#"{"max_wait_time_secs": 60}"#).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.abort();
+
+        assert_eq!(scheduler.config().max_wait_time_secs, 60);
     }
 
     #[tokio::test]
@@ -827,5 +7048,20 @@ mod tests {
             ..Default::default()
         });
         assert!(matches!(pbs_config.scheduler_type, BatchSchedulerType::Pbs));
+
+        let lsf_config = SchedulerConfig::with_lsf(LsfConfig {
+            queue: "quantum".to_string(),
+            ..Default::default()
+        });
+        assert!(matches!(lsf_config.scheduler_type, BatchSchedulerType::Lsf));
+
+        let k8s_config = SchedulerConfig::with_kubernetes(KubernetesConfig {
+            namespace: "quantum".to_string(),
+            ..Default::default()
+        });
+        assert!(matches!(
+            k8s_config.scheduler_type,
+            BatchSchedulerType::Kubernetes
+        ));
     }
 }
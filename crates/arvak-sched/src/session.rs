@@ -0,0 +1,209 @@
+//! Sessions: a run of jobs pinned to one backend for a bounded time.
+//!
+//! Iterative algorithms submit many short jobs whose results feed the next
+//! submission. A [`Session`] keeps those jobs on the same backend, so
+//! calibration drifts and queue changes between backends do not mix into
+//! one run, and stops admitting jobs once its time budget is spent. Jobs
+//! are submitted with [`HpcScheduler::submit_in_session`] and the ones
+//! still unfinished when a run is abandoned are cancelled with
+//! [`HpcScheduler::cancel_session`].
+//!
+//! [`HpcScheduler::submit_in_session`]: crate::HpcScheduler::submit_in_session
+//! [`HpcScheduler::cancel_session`]: crate::HpcScheduler::cancel_session
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{ScheduledJob, ScheduledJobId};
+
+/// Job metadata key holding the ID of the session a job ran in.
+pub const SESSION_METADATA_KEY: &str = "session";
+
+/// A time-bounded run of jobs on one backend.
+#[derive(Debug, Clone)]
+pub struct Session {
+    id: String,
+    backend: String,
+    started_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    closed: bool,
+    jobs: Vec<ScheduledJobId>,
+}
+
+impl Session {
+    /// Open a session on a backend that admits jobs for `max_time`.
+    pub fn new(backend: impl Into<String>, max_time: Duration) -> Self {
+        let started_at = Utc::now();
+        let max_time = chrono::Duration::from_std(max_time).unwrap_or(chrono::Duration::MAX);
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            backend: backend.into(),
+            started_at,
+            expires_at: started_at
+                .checked_add_signed(max_time)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            closed: false,
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Session ID, recorded in the metadata of every job it admits.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Backend the session's jobs run on.
+    pub fn backend(&self) -> &str {
+        &self.backend
+    }
+
+    /// When the session was opened.
+    pub fn started_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+
+    /// When the session stops admitting jobs.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Time left before the session expires.
+    pub fn remaining(&self) -> Duration {
+        (self.expires_at - Utc::now()).to_std().unwrap_or_default()
+    }
+
+    /// Whether the session still admits jobs.
+    pub fn is_active(&self) -> bool {
+        !self.closed && Utc::now() < self.expires_at
+    }
+
+    /// Stop admitting jobs; jobs already submitted keep running.
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    /// Jobs submitted in the session, in submission order.
+    pub fn jobs(&self) -> &[ScheduledJobId] {
+        &self.jobs
+    }
+
+    /// Pin a job to the session's backend and tag it with the session ID.
+    ///
+    /// The job's maximum queue time is capped at the time the session has
+    /// left. Fails if the session is closed or expired.
+    pub fn admit(&self, mut job: ScheduledJob) -> SchedResult<ScheduledJob> {
+        if self.closed {
+            return Err(SchedError::InvalidJobState {
+                expected: "open session".into(),
+                found: format!("session {} closed", self.id),
+            });
+        }
+        let remaining = self.remaining().as_secs();
+        if remaining == 0 {
+            return Err(SchedError::Timeout(format!(
+                "session {} expired at {}",
+                self.id, self.expires_at
+            )));
+        }
+
+        job.matched_backend = Some(self.backend.clone());
+        job.requirements.max_queue_time = Some(
+            job.requirements
+                .max_queue_time
+                .map_or(remaining, |t| t.min(remaining)),
+        );
+        job.metadata
+            .insert(SESSION_METADATA_KEY.to_string(), self.id.clone());
+        Ok(job)
+    }
+
+    /// Record a job submitted in the session.
+    pub(crate) fn record(&mut self, job_id: ScheduledJobId) {
+        self.jobs.push(job_id);
+    }
+}
+
+/// Parse a session time budget such as `90s`, `30m`, `2h` or `1h30m`.
+///
+/// A bare number is a count of seconds.
+pub fn parse_max_time(s: &str) -> SchedResult<Duration> {
+    let invalid = || SchedError::ConfigError(format!("Invalid session time '{}'", s));
+    let s = s.trim();
+    if let Ok(secs) = s.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c {
+            'd' => 86_400,
+            'h' => 3_600,
+            'm' => 60,
+            's' => 1,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = digits.parse().map_err(|_| invalid())?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(invalid)?;
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    #[test]
+    fn test_parse_max_time() {
+        assert_eq!(parse_max_time("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_max_time("30m").unwrap(), Duration::from_secs(1_800));
+        assert_eq!(parse_max_time("2h").unwrap(), Duration::from_secs(7_200));
+        assert_eq!(parse_max_time("1h30m").unwrap(), Duration::from_secs(5_400));
+        assert!(parse_max_time("2x").is_err());
+        assert!(parse_max_time("h").is_err());
+        assert!(parse_max_time("2h30").is_err());
+        assert!(parse_max_time("0m").is_err());
+    }
+
+    #[test]
+    fn test_admit_pins_backend() {
+        let session = Session::new("qpu1", Duration::from_secs(600));
+        let job = ScheduledJob::new("a", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let job = session.admit(job).unwrap();
+
+        assert_eq!(job.matched_backend.as_deref(), Some("qpu1"));
+        assert!(job.requirements.max_queue_time.unwrap() <= 600);
+        assert_eq!(
+            job.metadata.get(SESSION_METADATA_KEY).map(String::as_str),
+            Some(session.id())
+        );
+    }
+
+    #[test]
+    fn test_closed_or_expired_session_rejects_jobs() {
+        let job = ScheduledJob::new("a", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+
+        let mut session = Session::new("qpu1", Duration::from_secs(600));
+        session.close();
+        assert!(!session.is_active());
+        assert!(session.admit(job.clone()).is_err());
+
+        let expired = Session::new("qpu1", Duration::ZERO);
+        assert!(!expired.is_active());
+        assert!(matches!(expired.admit(job), Err(SchedError::Timeout(_))));
+    }
+}
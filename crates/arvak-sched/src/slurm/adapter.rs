@@ -1,15 +1,30 @@
 //! SLURM adapter for job submission and tracking.
 
+use arvak_config::{Secret, SecretSource};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
+use std::process::{Output, Stdio};
 
 use tokio::fs;
-use tokio::process::Command;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
 
+use crate::array;
+use crate::batch::BatchSystem;
 use crate::error::{SchedError, SchedResult};
-use crate::job::ScheduledJob;
+use crate::job::{ScheduledJob, ScheduledJobStatus};
+use crate::logs::LogStream;
+use crate::partial::snapshot_path;
+use crate::payload::{self, CircuitResult};
+use crate::reservation::Reservation;
+use crate::retry::FailureKind;
 use crate::slurm::parser;
 use crate::slurm::templates;
+use crate::slurm::transport::{SshConfig, SshTransport, connection_failed};
+use crate::task::{ClassicalTask, TaskInputs};
 
 /// SLURM job state.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -67,7 +82,8 @@ pub struct SlurmJobInfo {
 }
 
 /// Configuration for SLURM adapter.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct SlurmConfig {
     /// SLURM partition to submit to.
     pub partition: String,
@@ -97,7 +113,33 @@ pub struct SlurmConfig {
     pub python_venv: Option<PathBuf>,
 
     /// Mapping from priority value to SLURM QOS.
+    #[serde(deserialize_with = "crate::config::priority_mapping")]
     pub priority_qos_mapping: Option<rustc_hash::FxHashMap<u32, String>>,
+
+    /// Source of a JWT for Slurm's `auth/jwt` plugin, passed to the Slurm
+    /// commands as `SLURM_JWT`.
+    pub jwt: Option<SecretSource>,
+
+    /// When set, jobs write a partial-results snapshot every this many
+    /// shots.
+    pub partial_shots: Option<u32>,
+
+    /// When set, jobs touch a `<batch job ID>.event` file in this directory
+    /// when they start and exit, and the scheduler polls the jobs named
+    /// there instead of every active job. Must be shared with the compute
+    /// nodes.
+    pub event_dir: Option<PathBuf>,
+
+    /// Command the Slurm client commands are run through, e.g.
+    /// `["docker", "exec", "slurm"]`. The work directory must be visible
+    /// at the same path on the other side.
+    pub command_prefix: Vec<String>,
+
+    /// Run the Slurm commands on a login node over SSH, copying job and
+    /// result files to and from the same path there. Takes precedence
+    /// over `command_prefix`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssh: Option<SshConfig>,
 }
 
 impl Default for SlurmConfig {
@@ -113,6 +155,11 @@ impl Default for SlurmConfig {
             modules: Vec::new(),
             python_venv: None,
             priority_qos_mapping: None,
+            jwt: None,
+            partial_shots: None,
+            event_dir: None,
+            command_prefix: Vec::new(),
+            ssh: None,
         }
     }
 }
@@ -120,10 +167,16 @@ impl Default for SlurmConfig {
 /// Adapter for SLURM HPC scheduler.
 pub struct SlurmAdapter {
     config: SlurmConfig,
+    /// JWT resolved from `config.jwt`.
+    jwt: Option<Secret>,
+    /// Transport built from `config.ssh`.
+    ssh: Option<SshTransport>,
     /// Whether to use mock mode (for testing).
     mock_mode: bool,
     /// Mock job counter for generating fake job IDs.
     mock_counter: std::sync::atomic::AtomicU64,
+    /// Batch job IDs of jobs submitted in mock mode, by job ID.
+    mock_submitted: std::sync::Mutex<rustc_hash::FxHashMap<String, String>>,
 }
 
 impl SlurmAdapter {
@@ -134,11 +187,24 @@ impl SlurmAdapter {
         fs::create_dir_all(config.work_dir.join("scripts")).await?;
         fs::create_dir_all(config.work_dir.join("circuits")).await?;
         fs::create_dir_all(config.work_dir.join("results")).await?;
+        fs::create_dir_all(config.work_dir.join("tasks")).await?;
+
+        let jwt = config.jwt.as_ref().map(SecretSource::resolve).transpose()?;
+
+        let ssh = config.ssh.clone().map(SshTransport::new).transpose()?;
+        if let Some(ssh) = &ssh {
+            let dirs = ["scripts", "circuits", "results", "tasks"].map(|d| config.work_dir.join(d));
+            let dirs: Vec<&Path> = dirs.iter().map(PathBuf::as_path).collect();
+            ssh.create_dirs(&dirs).await?;
+        }
 
         Ok(Self {
             config,
+            jwt,
+            ssh,
             mock_mode: false,
             mock_counter: std::sync::atomic::AtomicU64::new(1000),
+            mock_submitted: std::sync::Mutex::default(),
         })
     }
 
@@ -146,8 +212,11 @@ impl SlurmAdapter {
     pub fn mock(config: SlurmConfig) -> Self {
         Self {
             config,
+            jwt: None,
+            ssh: None,
             mock_mode: true,
             mock_counter: std::sync::atomic::AtomicU64::new(1000),
+            mock_submitted: std::sync::Mutex::default(),
         }
     }
 
@@ -155,10 +224,15 @@ impl SlurmAdapter {
     pub async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
         // In mock mode, skip file I/O
         if self.mock_mode {
-            let job_id = self
+            let batch_job_id = self
                 .mock_counter
-                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-            return Ok(job_id.to_string());
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                .to_string();
+            self.mock_submitted
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(job.id.to_string(), batch_job_id.clone());
+            return Ok(batch_job_id);
         }
 
         // Write circuit(s) to file
@@ -172,6 +246,10 @@ impl SlurmAdapter {
                 .join("results")
                 .join(format!("{}.json", job.id));
             templates::generate_batch_script(job, &self.config, &circuit_files[0], &result_file)
+        } else if job.array {
+            let result_dir = self.result_path(job);
+            let circuit_refs: Vec<&Path> = circuit_files.iter().map(|p| p.as_path()).collect();
+            templates::generate_array_script(job, &self.config, &circuit_refs, &result_dir)
         } else {
             let result_dir = self
                 .config
@@ -189,9 +267,131 @@ impl SlurmAdapter {
             .join("scripts")
             .join(format!("{}.sh", job.id));
         fs::write(&script_path, &script).await?;
+        for path in circuit_files.iter().chain([&script_path]) {
+            self.upload(path).await?;
+        }
 
         // Submit via sbatch
-        self.run_sbatch(&script_path).await
+        self.run_sbatch(&script_path, job).await
+    }
+
+    /// Submit single-circuit jobs as the tasks of one job array.
+    ///
+    /// Returns each job's array task ID (`<array job ID>_<task>`), in the
+    /// order of `jobs`.
+    pub async fn submit_array(&self, jobs: &[ScheduledJob]) -> SchedResult<Vec<String>> {
+        let Some(head) = jobs.first() else {
+            return Ok(Vec::new());
+        };
+        if let Some(job) = jobs.iter().find(|job| job.circuits.len() != 1) {
+            return Err(SchedError::InvalidPayload(format!(
+                "job array member {} must run exactly one circuit",
+                job.name
+            )));
+        }
+
+        let array_job_id = if self.mock_mode {
+            self.mock_counter
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+                .to_string()
+        } else {
+            let mut circuit_files = Vec::with_capacity(jobs.len());
+            for job in jobs {
+                circuit_files.extend(self.write_circuits(job).await?);
+            }
+            let result_files: Vec<PathBuf> = jobs.iter().map(|job| self.result_path(job)).collect();
+            let circuit_refs: Vec<&Path> = circuit_files.iter().map(|p| p.as_path()).collect();
+            let script = templates::generate_sweep_script(
+                jobs,
+                &self.config,
+                &circuit_refs,
+                &result_files,
+                &self.config.work_dir.join("results"),
+            );
+
+            let script_path = self
+                .config
+                .work_dir
+                .join("scripts")
+                .join(format!("{}_array.sh", head.id));
+            fs::write(&script_path, &script).await?;
+            for path in circuit_files.iter().chain([&script_path]) {
+                self.upload(path).await?;
+            }
+            self.run_sbatch(&script_path, head).await?
+        };
+
+        Ok((0..jobs.len())
+            .map(|task| array::task_id(&array_job_id, task))
+            .collect())
+    }
+
+    /// Submit a classical script task to SLURM.
+    ///
+    /// Upstream results are written to a JSON file passed to the script via
+    /// `ARVAK_TASK_INPUTS`.
+    pub async fn submit_task(
+        &self,
+        job: &ScheduledJob,
+        inputs: &TaskInputs,
+    ) -> SchedResult<String> {
+        let Some(ClassicalTask::Script { path, args }) = &job.task else {
+            return Err(SchedError::InvalidJobState {
+                expected: "script task".to_string(),
+                found: job.name.clone(),
+            });
+        };
+
+        if self.mock_mode {
+            let job_id = self
+                .mock_counter
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            return Ok(job_id.to_string());
+        }
+
+        let inputs_file = self
+            .config
+            .work_dir
+            .join("tasks")
+            .join(format!("{}.inputs.json", job.id));
+        fs::write(&inputs_file, serde_json::to_vec(inputs)?).await?;
+
+        let script = templates::generate_task_script(
+            job,
+            &self.config,
+            path,
+            args,
+            &inputs_file,
+            &self.result_path(job),
+        );
+
+        let script_path = self
+            .config
+            .work_dir
+            .join("scripts")
+            .join(format!("{}.sh", job.id));
+        fs::write(&script_path, &script).await?;
+        self.upload(&inputs_file).await?;
+        self.upload(&script_path).await?;
+
+        self.run_sbatch(&script_path, job).await
+    }
+
+    /// Read the output written by a completed script task.
+    ///
+    /// Returns `Null` if the script wrote no output.
+    pub async fn read_task_output(&self, job: &ScheduledJob) -> SchedResult<serde_json::Value> {
+        if self.mock_mode {
+            return Ok(serde_json::Value::Null);
+        }
+
+        let path = self.result_path(job);
+        self.download(&path).await?;
+        match fs::read(path).await {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Value::Null),
+            Err(e) => Err(e.into()),
+        }
     }
 
     /// Get the status of a SLURM job.
@@ -219,29 +419,142 @@ impl SlurmAdapter {
         Err(SchedError::SlurmJobNotFound(slurm_job_id.to_string()))
     }
 
+    /// Find the SLURM job `job` was submitted as from the comment its batch
+    /// script tags it with, in `squeue` and then in `sacct` since the job
+    /// was created.
+    ///
+    /// Finding finished jobs needs comments in the accounting database
+    /// (`AccountingStoreFlags=job_comment`).
+    pub async fn find_submitted(&self, job: &ScheduledJob) -> SchedResult<Option<String>> {
+        if self.mock_mode {
+            let submitted = self
+                .mock_submitted
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            return Ok(submitted.get(&job.id.to_string()).cloned());
+        }
+
+        let comment = templates::submission_comment(job);
+        let name = format!("--name={}", templates::sanitize_name(&job.name));
+        let output = self.output("squeue", ["-h", &name, "-o", "%i|%k"]).await?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if let Some(batch_job_id) = parser::find_commented_job(&stdout, &comment) {
+            return Ok(Some(batch_job_id));
+        }
+
+        let since = job.created_at.format("%Y-%m-%dT%H:%M:%S").to_string();
+        let output = self
+            .output(
+                "sacct",
+                ["-X", "-n", "-P", &name, "-S", &since, "-o", "JobID,Comment"],
+            )
+            .await?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(parser::find_commented_job(&stdout, &comment))
+    }
+
     /// Cancel a SLURM job.
     pub async fn cancel(&self, slurm_job_id: &str) -> SchedResult<()> {
         if self.mock_mode {
             return Ok(());
         }
 
-        let output = Command::new("scancel")
-            .arg(slurm_job_id)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-            .map_err(|e| SchedError::SlurmCommandError {
-                command: "scancel".to_string(),
-                message: e.to_string(),
-            })?;
+        let output = self.output("scancel", [slurm_job_id]).await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stderr = self.redact(&String::from_utf8_lossy(&output.stderr));
 
         parser::parse_scancel_output(&stdout, &stderr)
     }
 
+    /// Requeue a running SLURM job with `scontrol requeue`, so it starts
+    /// again from the beginning under the same job ID.
+    pub async fn requeue(&self, slurm_job_id: &str) -> SchedResult<()> {
+        if self.mock_mode {
+            return Ok(());
+        }
+
+        let output = self.output("scontrol", ["requeue", slurm_job_id]).await?;
+        let stderr = self.redact(&String::from_utf8_lossy(&output.stderr));
+        parser::parse_requeue_output(&stderr)
+    }
+
+    /// Reserve nodes of the partition for `reservation` with `scontrol
+    /// create reservation`.
+    pub async fn create_reservation(&self, reservation: &Reservation) -> SchedResult<()> {
+        if self.mock_mode {
+            return Ok(());
+        }
+
+        let args = self.reservation_args(reservation, Utc::now())?;
+        let output = self.output("scontrol", &args).await?;
+        let stderr = self.redact(&String::from_utf8_lossy(&output.stderr));
+        parser::parse_reservation_output("scontrol create reservation", &stderr)
+    }
+
+    /// Release the SLURM reservation `name`.
+    pub async fn delete_reservation(&self, name: &str) -> SchedResult<()> {
+        if self.mock_mode {
+            return Ok(());
+        }
+
+        let output = self
+            .output("scontrol", ["delete", &format!("ReservationName={}", name)])
+            .await?;
+        let stderr = self.redact(&String::from_utf8_lossy(&output.stderr));
+        parser::parse_reservation_output("scontrol delete reservation", &stderr)
+    }
+
+    /// Arguments of `scontrol` creating `reservation` at `now`.
+    ///
+    /// The start time is relative to `now`, so it does not depend on the
+    /// time zone of the SLURM controller. The nodes are reserved for the
+    /// configured account or, without one, for the submitting user.
+    fn reservation_args(
+        &self,
+        reservation: &Reservation,
+        now: DateTime<Utc>,
+    ) -> SchedResult<Vec<String>> {
+        let window = reservation.window.remaining(now).ok_or_else(|| {
+            SchedError::ConfigError(format!(
+                "the window of reservation {} has closed",
+                reservation.name
+            ))
+        })?;
+        let start = match (window.start - now).num_seconds() {
+            0 => "now".to_string(),
+            secs => format!("now+{}seconds", secs),
+        };
+        let minutes = (window.duration().num_seconds() + 59) / 60;
+        let owner = match &self.config.account {
+            Some(account) => format!("Accounts={}", account),
+            None => {
+                let user = self
+                    .config
+                    .ssh
+                    .as_ref()
+                    .and_then(|ssh| ssh.user.clone())
+                    .or_else(|| std::env::var("USER").ok())
+                    .ok_or_else(|| {
+                        SchedError::ConfigError(
+                            "reservations need a SLURM account or user".to_string(),
+                        )
+                    })?;
+                format!("Users={}", user)
+            }
+        };
+        Ok(vec![
+            "create".to_string(),
+            "reservation".to_string(),
+            format!("ReservationName={}", reservation.name),
+            format!("StartTime={}", start),
+            format!("Duration={}", minutes),
+            format!("NodeCnt={}", reservation.capacity),
+            format!("PartitionName={}", self.config.partition),
+            owner,
+        ])
+    }
+
     /// Get the result file path for a job.
     pub fn result_path(&self, job: &ScheduledJob) -> PathBuf {
         if job.is_batch() {
@@ -257,6 +570,47 @@ impl SlurmAdapter {
         }
     }
 
+    /// Partial-results snapshot files for a job, one per circuit.
+    ///
+    /// Empty unless `partial_shots` is configured.
+    pub fn partial_paths(&self, job: &ScheduledJob) -> Vec<PathBuf> {
+        if self.config.partial_shots.is_none() {
+            return Vec::new();
+        }
+        payload::result_files(job, &self.result_path(job))
+            .iter()
+            .map(|path| snapshot_path(path))
+            .collect()
+    }
+
+    /// Files a submitted job's stdout and stderr are written to.
+    pub fn log_paths(&self, job: &ScheduledJob) -> Vec<(LogStream, PathBuf)> {
+        let Some(slurm_job_id) = job.status.slurm_job_id() else {
+            return Vec::new();
+        };
+        let log = |ext: &str| {
+            self.config
+                .work_dir
+                .join(format!("slurm-{}.{}", slurm_job_id, ext))
+        };
+        vec![
+            (LogStream::Stdout, log("out")),
+            (LogStream::Stderr, log("err")),
+        ]
+    }
+
+    /// Read the per-circuit results written by a completed job.
+    pub async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        if self.mock_mode {
+            return Ok(Vec::new());
+        }
+        let result_path = self.result_path(job);
+        for path in payload::result_files(job, &result_path) {
+            self.download(&path).await?;
+        }
+        payload::read_results(job, &result_path).await
+    }
+
     /// Write circuit files for a job.
     async fn write_circuits(&self, job: &ScheduledJob) -> SchedResult<Vec<PathBuf>> {
         let mut paths = Vec::with_capacity(job.circuits.len());
@@ -279,22 +633,143 @@ impl SlurmAdapter {
         Ok(paths)
     }
 
-    /// Run sbatch command.
-    async fn run_sbatch(&self, script_path: &Path) -> SchedResult<String> {
-        let output = Command::new("sbatch")
-            .arg(script_path)
+    /// Build a Slurm command, authenticated with the configured JWT.
+    fn command(&self, program: &str) -> Command {
+        let mut command = match self.config.command_prefix.split_first() {
+            Some((wrapper, args)) => {
+                let mut command = Command::new(wrapper);
+                command.args(args).arg(program);
+                command
+            }
+            None => Command::new(program),
+        };
+        if let Some(jwt) = &self.jwt {
+            command.env("SLURM_JWT", jwt.expose());
+        }
+        command
+    }
+
+    /// Run a Slurm command locally or over SSH and collect its output.
+    async fn output<I, S>(&self, program: &str, args: I) -> SchedResult<Output>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let args: Vec<OsString> = args.into_iter().map(|a| a.as_ref().to_owned()).collect();
+        if let Some(ssh) = &self.ssh {
+            let jwt = self.jwt.as_ref().map(|jwt| ("SLURM_JWT", jwt.expose()));
+            return ssh
+                .run(program, &args, None, jwt.as_slice())
+                .await
+                .map_err(|e| self.redact_error(e));
+        }
+        self.command(program)
+            .args(&args)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .output()
             .await
             .map_err(|e| SchedError::SlurmCommandError {
-                command: "sbatch".to_string(),
+                command: program.to_string(),
                 message: e.to_string(),
-            })?;
+            })
+    }
+
+    /// Start a Slurm command locally or over SSH with its standard output
+    /// piped, for reading the output as it arrives.
+    async fn spawn_piped(&self, program: &str, args: &[OsString]) -> SchedResult<Child> {
+        if let Some(ssh) = &self.ssh {
+            let jwt = self.jwt.as_ref().map(|jwt| ("SLURM_JWT", jwt.expose()));
+            return ssh.spawn_piped(program, args, jwt.as_slice()).await;
+        }
+        self.command(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| SchedError::SlurmCommandError {
+                command: program.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    /// Copy a job file to the login node when running over SSH.
+    async fn upload(&self, path: &Path) -> SchedResult<()> {
+        match &self.ssh {
+            Some(ssh) => ssh.upload(path).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Copy a result file back from the login node when running over SSH.
+    async fn download(&self, path: &Path) -> SchedResult<()> {
+        if let Some(ssh) = &self.ssh {
+            ssh.download(path).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the JWT from command output before it is reported.
+    fn redact(&self, text: &str) -> String {
+        match &self.jwt {
+            Some(jwt) => jwt.redact(text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Remove the JWT from the message of a command error.
+    fn redact_error(&self, error: SchedError) -> SchedError {
+        match error {
+            SchedError::SlurmCommandError { command, message } => SchedError::SlurmCommandError {
+                command,
+                message: self.redact(&message),
+            },
+            SchedError::SshConnectionFailed {
+                host,
+                command,
+                message,
+            } => SchedError::SshConnectionFailed {
+                host,
+                command,
+                message: self.redact(&message),
+            },
+            e => e,
+        }
+    }
+
+    /// Run sbatch on the batch script of `job`.
+    ///
+    /// Submitting is not safe to repeat: when the SSH connection drops,
+    /// sbatch may have submitted the job anyway. The job is then looked up
+    /// by the comment its script tags it with, as in
+    /// [`find_submitted`](Self::find_submitted), and only submitted again
+    /// if it is not found.
+    async fn run_sbatch(&self, script_path: &Path, job: &ScheduledJob) -> SchedResult<String> {
+        let mut attempt = 0;
+        let output = loop {
+            let error = match self.output("sbatch", [script_path]).await {
+                Err(e @ SchedError::SshConnectionFailed { .. }) => e,
+                result => break result?,
+            };
+            let Some(ssh) = self.ssh.as_ref().filter(|ssh| attempt < ssh.retries()) else {
+                return Err(error);
+            };
+            ssh.backoff("sbatch", attempt).await;
+            attempt += 1;
+            if let Some(slurm_job_id) = self.find_submitted(job).await? {
+                tracing::info!(
+                    "Found job {} submitted as {} before the SSH connection dropped",
+                    job.id,
+                    slurm_job_id
+                );
+                return Ok(slurm_job_id);
+            }
+        };
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(SchedError::SlurmSubmitError(stderr.to_string()));
+            return Err(SchedError::SlurmSubmitError(self.redact(&stderr)));
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -303,16 +778,9 @@ impl SlurmAdapter {
 
     /// Run squeue command to get job status.
     async fn run_squeue(&self, slurm_job_id: &str) -> SchedResult<Option<SlurmJobInfo>> {
-        let output = Command::new("squeue")
-            .args(["-j", slurm_job_id, "-o", "%i|%j|%T|%r|%S"])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
-            .await
-            .map_err(|e| SchedError::SlurmCommandError {
-                command: "squeue".to_string(),
-                message: e.to_string(),
-            })?;
+        let output = self
+            .output("squeue", ["-j", slurm_job_id, "-o", "%i|%j|%T|%r|%S"])
+            .await?;
 
         let stdout = String::from_utf8_lossy(&output.stdout);
         parser::parse_squeue_output(&stdout)
@@ -320,25 +788,271 @@ impl SlurmAdapter {
 
     /// Run sacct command to get completed job status.
     async fn run_sacct(&self, slurm_job_id: &str) -> SchedResult<Option<SlurmJobInfo>> {
-        let output = Command::new("sacct")
-            .args([
-                "-j",
-                slurm_job_id,
-                "-o",
-                "JobID,JobName,State,ExitCode",
-                "-P",
-            ])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()
+        let output = self
+            .output(
+                "sacct",
+                [
+                    "-j",
+                    slurm_job_id,
+                    "-o",
+                    "JobID,JobName,State,ExitCode",
+                    "-P",
+                ],
+            )
+            .await?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parser::parse_sacct_output(&stdout)
+    }
+
+    /// Get accounting information for many SLURM jobs with a single sacct
+    /// call; one entry per ID, `None` for jobs sacct does not know yet.
+    ///
+    /// The output is parsed line by line as it is read from sacct, so
+    /// memory use does not grow with the size of the output. The records
+    /// of an array job's tasks are combined with
+    /// [`combine_array_tasks`](parser::combine_array_tasks).
+    pub async fn status_many(
+        &self,
+        slurm_job_ids: &[&str],
+    ) -> SchedResult<Vec<Option<SlurmJobInfo>>> {
+        if slurm_job_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if self.mock_mode {
+            let mut infos = Vec::with_capacity(slurm_job_ids.len());
+            for id in slurm_job_ids {
+                infos.push(Some(self.status(id).await?));
+            }
+            return Ok(infos);
+        }
+
+        let index: rustc_hash::FxHashMap<&str, usize> = slurm_job_ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+        let args: Vec<OsString> = [
+            "-j",
+            &slurm_job_ids.join(","),
+            "-o",
+            "JobID,JobName,State,ExitCode",
+            "--parsable2",
+        ]
+        .into_iter()
+        .map(OsString::from)
+        .collect();
+
+        let mut attempt = 0;
+        loop {
+            let mut stream = parser::SacctStream::default();
+            let mut tasks = vec![Vec::new(); slurm_job_ids.len()];
+            let child = self.spawn_piped("sacct", &args).await?;
+            let output = read_lines(child, |line| {
+                let Some(info) = stream.push(line) else {
+                    return;
+                };
+                // Tasks of an array job report `<array job ID>_<task>`.
+                let slot = index.get(info.job_id.as_str()).or_else(|| {
+                    let (array_job_id, _) = info.job_id.split_once('_')?;
+                    index.get(array_job_id)
+                });
+                if let Some(&i) = slot {
+                    tasks[i].push(info);
+                }
+            })
             .await
             .map_err(|e| SchedError::SlurmCommandError {
                 command: "sacct".to_string(),
                 message: e.to_string(),
             })?;
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        parser::parse_sacct_output(&stdout)
+            if let Some(ssh) = self.ssh.as_ref().filter(|_| connection_failed(&output)) {
+                if attempt >= ssh.retries() {
+                    return Err(self.redact_error(ssh.connection_error("sacct", &output)));
+                }
+                ssh.backoff("sacct", attempt).await;
+                attempt += 1;
+                continue;
+            }
+            if !output.status.success() {
+                return Err(SchedError::SlurmCommandError {
+                    command: "sacct".to_string(),
+                    message: self.redact(String::from_utf8_lossy(&output.stderr).trim()),
+                });
+            }
+            return Ok(tasks.into_iter().map(parser::combine_array_tasks).collect());
+        }
+    }
+}
+
+/// Pass each line of a child's standard output to `on_line` as it is
+/// read, then wait for the child to exit; the returned output holds its
+/// exit status and standard error.
+async fn read_lines(mut child: Child, mut on_line: impl FnMut(&str)) -> std::io::Result<Output> {
+    if let Some(stdout) = child.stdout.take() {
+        let mut reader = BufReader::new(stdout);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line).await? > 0 {
+            on_line(&String::from_utf8_lossy(&line));
+            line.clear();
+        }
+    }
+    child.wait_with_output().await
+}
+
+#[async_trait]
+impl BatchSystem for SlurmAdapter {
+    fn name(&self) -> &str {
+        "SLURM"
+    }
+
+    async fn submit(&self, job: &ScheduledJob) -> SchedResult<String> {
+        SlurmAdapter::submit(self, job).await
+    }
+
+    async fn poll(
+        &self,
+        job: &ScheduledJob,
+        batch_job_id: &str,
+    ) -> SchedResult<ScheduledJobStatus> {
+        let mut info = self.status(batch_job_id).await?;
+        // Keep the task of an array task ID, which the parser folds into
+        // its array job.
+        info.job_id = batch_job_id.to_string();
+        Ok(map_state(job, info))
+    }
+
+    async fn poll_many(
+        &self,
+        jobs: &[(&ScheduledJob, &str)],
+    ) -> SchedResult<Vec<Option<ScheduledJobStatus>>> {
+        let ids: Vec<&str> = jobs.iter().map(|(_, id)| *id).collect();
+        let infos = self.status_many(&ids).await?;
+        Ok(jobs
+            .iter()
+            .zip(infos)
+            .map(|((job, batch_job_id), info)| {
+                info.map(|mut info| {
+                    info.job_id = batch_job_id.to_string();
+                    map_state(job, info)
+                })
+            })
+            .collect())
+    }
+
+    async fn find_submitted(&self, job: &ScheduledJob) -> SchedResult<Option<String>> {
+        SlurmAdapter::find_submitted(self, job).await
+    }
+
+    async fn cancel(&self, batch_job_id: &str) -> SchedResult<()> {
+        SlurmAdapter::cancel(self, batch_job_id).await
+    }
+
+    async fn requeue(&self, batch_job_id: &str) -> SchedResult<()> {
+        SlurmAdapter::requeue(self, batch_job_id).await
+    }
+
+    async fn create_reservation(&self, reservation: &Reservation) -> SchedResult<()> {
+        SlurmAdapter::create_reservation(self, reservation).await
+    }
+
+    async fn delete_reservation(&self, name: &str) -> SchedResult<()> {
+        SlurmAdapter::delete_reservation(self, name).await
+    }
+
+    async fn read_results(&self, job: &ScheduledJob) -> SchedResult<Vec<CircuitResult>> {
+        SlurmAdapter::read_results(self, job).await
+    }
+
+    async fn pending_reason(&self, batch_job_id: &str) -> SchedResult<Option<String>> {
+        let info = self.status(batch_job_id).await?;
+        Ok(info.reason.filter(|r| r != "None"))
+    }
+
+    fn partial_paths(&self, job: &ScheduledJob) -> Vec<PathBuf> {
+        SlurmAdapter::partial_paths(self, job)
+    }
+
+    fn log_paths(&self, job: &ScheduledJob) -> Vec<(LogStream, PathBuf)> {
+        SlurmAdapter::log_paths(self, job)
+    }
+
+    fn supports_dependencies(&self) -> bool {
+        true
+    }
+
+    fn supports_arrays(&self) -> bool {
+        true
+    }
+
+    fn event_dir(&self) -> Option<PathBuf> {
+        self.config.event_dir.clone()
+    }
+
+    async fn submit_array(&self, jobs: &[ScheduledJob]) -> SchedResult<Vec<String>> {
+        SlurmAdapter::submit_array(self, jobs).await
+    }
+
+    async fn submit_task(&self, job: &ScheduledJob, inputs: &TaskInputs) -> SchedResult<String> {
+        SlurmAdapter::submit_task(self, job, inputs).await
+    }
+
+    async fn read_task_output(&self, job: &ScheduledJob) -> SchedResult<serde_json::Value> {
+        SlurmAdapter::read_task_output(self, job).await
+    }
+}
+
+/// Map a SLURM job state to the status of the job it runs.
+fn map_state(job: &ScheduledJob, info: SlurmJobInfo) -> ScheduledJobStatus {
+    let slurm_job_id = info.job_id;
+
+    match info.state {
+        SlurmState::Pending => ScheduledJobStatus::SlurmQueued { slurm_job_id },
+        SlurmState::Running | SlurmState::Completing => {
+            ScheduledJobStatus::SlurmRunning { slurm_job_id }
+        }
+        SlurmState::Completed => {
+            // Job completed - in a real scenario, we'd read the result file
+            // and get the quantum job ID from it
+            ScheduledJobStatus::Completed {
+                slurm_job_id,
+                quantum_job_id: arvak_hal::JobId("completed".to_string()),
+            }
+        }
+        SlurmState::Failed => ScheduledJobStatus::Failed {
+            reason: match info.exit_code {
+                Some(code) if code != 0 => format!("SLURM job failed with exit code {}", code),
+                _ => "SLURM job failed".to_string(),
+            },
+            kind: FailureKind::Application,
+            slurm_job_id: Some(slurm_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        SlurmState::NodeFail => ScheduledJobStatus::Failed {
+            reason: "SLURM node failure".to_string(),
+            kind: FailureKind::NodeFailure,
+            slurm_job_id: Some(slurm_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        SlurmState::OutOfMemory => ScheduledJobStatus::Failed {
+            reason: "SLURM job ran out of memory".to_string(),
+            kind: FailureKind::OutOfMemory,
+            slurm_job_id: Some(slurm_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        SlurmState::Timeout => ScheduledJobStatus::Failed {
+            reason: "SLURM job timed out".to_string(),
+            kind: FailureKind::Timeout,
+            slurm_job_id: Some(slurm_job_id),
+            quantum_job_id: job.status.quantum_job_id().cloned(),
+        },
+        SlurmState::Cancelled | SlurmState::Preempted => ScheduledJobStatus::Cancelled,
+        SlurmState::Unknown(state) => {
+            tracing::warn!("Unknown SLURM state: {}", state);
+            job.status.clone()
+        }
     }
 }
 
@@ -347,6 +1061,76 @@ mod tests {
     use super::*;
     use crate::job::{CircuitSpec, Priority};
 
+    #[test]
+    fn test_map_state_classifies_failures() {
+        let job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q;"));
+        let info = |state, exit_code| SlurmJobInfo {
+            job_id: "42".to_string(),
+            name: "job".to_string(),
+            state,
+            reason: None,
+            exit_code,
+        };
+        let kind = |info| match map_state(&job, info) {
+            ScheduledJobStatus::Failed { kind, .. } => kind,
+            status => panic!("unexpected status {:?}", status),
+        };
+
+        assert_eq!(kind(info(SlurmState::Timeout, None)), FailureKind::Timeout);
+        assert_eq!(
+            kind(info(SlurmState::NodeFail, Some(0))),
+            FailureKind::NodeFailure
+        );
+        assert_eq!(
+            kind(info(SlurmState::OutOfMemory, Some(0))),
+            FailureKind::OutOfMemory
+        );
+        assert_eq!(
+            kind(info(SlurmState::Failed, Some(3))),
+            FailureKind::Application
+        );
+    }
+
+    #[test]
+    fn test_reservation_args() {
+        use crate::reservation::TimeWindow;
+
+        let config = SlurmConfig {
+            partition: "quantum".to_string(),
+            account: Some("project123".to_string()),
+            ..Default::default()
+        };
+        let adapter = SlurmAdapter::mock(config);
+        let now = Utc::now();
+        let window = TimeWindow::new(
+            now + chrono::Duration::hours(2),
+            now + chrono::Duration::hours(3) + chrono::Duration::seconds(10),
+        )
+        .unwrap();
+        let reservation = Reservation::new("iqm", window, 2).unwrap();
+
+        let args = adapter.reservation_args(&reservation, now).unwrap();
+        assert_eq!(
+            args[2..],
+            [
+                format!("ReservationName={}", reservation.name),
+                "StartTime=now+7200seconds".to_string(),
+                "Duration=61".to_string(),
+                "NodeCnt=2".to_string(),
+                "PartitionName=quantum".to_string(),
+                "Accounts=project123".to_string(),
+            ]
+        );
+
+        // Once the window is open only its rest is reserved
+        let args = adapter
+            .reservation_args(&reservation, window.start + chrono::Duration::minutes(30))
+            .unwrap();
+        assert_eq!(args[3], "StartTime=now");
+        assert_eq!(args[4], "Duration=31");
+        assert!(adapter.reservation_args(&reservation, window.end).is_err());
+    }
+
     #[tokio::test]
     async fn test_mock_slurm_adapter() {
         let config = SlurmConfig::default();
@@ -366,6 +1150,17 @@ mod tests {
 
         // Cancel
         adapter.cancel(&slurm_job_id).await.unwrap();
+
+        // Logs are named after the batch job ID
+        assert!(adapter.log_paths(&job).is_empty());
+        let mut submitted = job.clone();
+        submitted.status = ScheduledJobStatus::SlurmRunning { slurm_job_id };
+        let logs = adapter.log_paths(&submitted);
+        assert_eq!(logs[1].0, LogStream::Stderr);
+        assert!(logs[1].1.ends_with(format!(
+            "slurm-{}.err",
+            submitted.status.slurm_job_id().unwrap()
+        )));
     }
 
     #[test]
@@ -379,4 +1174,235 @@ mod tests {
         assert!(SlurmState::Completed.is_success());
         assert!(!SlurmState::Failed.is_success());
     }
+
+    #[tokio::test]
+    async fn test_jwt_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("jwt"), "eyJ.token.sig\n").unwrap();
+        let config = SlurmConfig {
+            work_dir: dir.path().join("work"),
+            jwt: Some(SecretSource::File(dir.path().join("jwt"))),
+            ..Default::default()
+        };
+        let adapter = SlurmAdapter::new(config).await.unwrap();
+
+        let command = adapter.command("squeue");
+        let env: Vec<_> = command.as_std().get_envs().collect();
+        assert_eq!(
+            env,
+            vec![(
+                std::ffi::OsStr::new("SLURM_JWT"),
+                Some(std::ffi::OsStr::new("eyJ.token.sig"))
+            )]
+        );
+        assert_eq!(
+            adapter.redact("Invalid token eyJ.token.sig"),
+            "Invalid token [REDACTED]"
+        );
+
+        let missing = SlurmConfig {
+            work_dir: dir.path().join("work"),
+            jwt: Some(SecretSource::File(dir.path().join("missing"))),
+            ..Default::default()
+        };
+        assert!(matches!(
+            SlurmAdapter::new(missing).await,
+            Err(SchedError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_command_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = SlurmConfig {
+            work_dir: dir.path().to_path_buf(),
+            command_prefix: vec!["docker".into(), "exec".into(), "arvak-slurm".into()],
+            ..Default::default()
+        };
+        let adapter = SlurmAdapter::new(config).await.unwrap();
+
+        let command = adapter.command("sbatch");
+        let command = command.as_std();
+        assert_eq!(command.get_program(), "docker");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, ["exec", "arvak-slurm", "sbatch"]);
+    }
+
+    #[tokio::test]
+    async fn test_submit_over_ssh() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A stand-in ssh that runs the remote command locally, with a fake
+        // sbatch on its PATH.
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+        let log = dir.path().join("remote.log");
+        let scripts = [
+            (
+                dir.path().join("ssh"),
+                format!(
+                    "#!/bin/sh\nwhile [ \"$1\" != -- ]; do shift; done\nshift\n\
+                     echo \"$1\" >> {}\nPATH={}:$PATH exec sh -c \"$1\"\n",
+                    log.display(),
+                    bin.display()
+                ),
+            ),
+            (
+                bin.join("sbatch"),
+                "#!/bin/sh\necho 'Submitted batch job 4242'\n".to_string(),
+            ),
+        ];
+        for (path, script) in &scripts {
+            std::fs::write(path, script).unwrap();
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let config = SlurmConfig {
+            work_dir: dir.path().join("work"),
+            ssh: Some(SshConfig {
+                ssh_binary: dir.path().join("ssh"),
+                control_persist_secs: 0,
+                ..SshConfig::new("login1")
+            }),
+            ..Default::default()
+        };
+        let adapter = SlurmAdapter::new(config).await.unwrap();
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job = ScheduledJob::new("remote", circuit);
+        assert_eq!(adapter.submit(&job).await.unwrap(), "4242");
+        assert!(adapter.read_results(&job).await.unwrap().is_empty());
+
+        let remote = std::fs::read_to_string(&log).unwrap();
+        let commands: Vec<_> = remote
+            .lines()
+            .map(|l| l.split(' ').next().unwrap())
+            .collect();
+        assert_eq!(commands, ["mkdir", "sh", "sh", "sbatch", "sh"]);
+    }
+
+    #[tokio::test]
+    async fn test_status_many_over_ssh() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A stand-in ssh whose first connection drops, with a fake sacct
+        // printing an array job's tasks, a job step and an unrequested job.
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+        let dropped = dir.path().join("dropped");
+        let scripts = [
+            (
+                dir.path().join("ssh"),
+                format!(
+                    "#!/bin/sh\n[ -e {0} ] || {{ touch {0}; exit 255; }}\n\
+                     while [ \"$1\" != -- ]; do shift; done\nshift\n\
+                     PATH={1}:$PATH exec sh -c \"$1\"\n",
+                    dropped.display(),
+                    bin.display()
+                ),
+            ),
+            (
+                bin.join("sacct"),
+                "#!/bin/sh\nprintf 'JobID|JobName|State|ExitCode\\n\
+                 7_0|sweep|COMPLETED|0:0\\n7_1|sweep|RUNNING|0:0\\n\
+                 8|single|FAILED|3:0\\n8.batch|batch|FAILED|3:0\\n9|other|COMPLETED|0:0\\n'\n"
+                    .to_string(),
+            ),
+        ];
+        for (path, script) in &scripts {
+            std::fs::write(path, script).unwrap();
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let config = SlurmConfig {
+            work_dir: dir.path().join("work"),
+            ssh: Some(SshConfig {
+                ssh_binary: dir.path().join("ssh"),
+                control_persist_secs: 0,
+                retry_backoff_ms: 1,
+                ..SshConfig::new("login1")
+            }),
+            ..Default::default()
+        };
+        let adapter = SlurmAdapter::new(config).await.unwrap();
+
+        let infos = adapter.status_many(&["7", "8", "10"]).await.unwrap();
+        assert_eq!(infos.len(), 3);
+        let array = infos[0].as_ref().unwrap();
+        assert_eq!(array.job_id, "7");
+        assert!(matches!(array.state, SlurmState::Running));
+        let single = infos[1].as_ref().unwrap();
+        assert!(matches!(single.state, SlurmState::Failed));
+        assert_eq!(single.exit_code, Some(3));
+        assert!(infos[2].is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sbatch_not_repeated_after_dropped_connection() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A stand-in ssh that runs sbatch and then reports a dropped
+        // connection, with a fake squeue listing what sbatch submitted.
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin");
+        std::fs::create_dir(&bin).unwrap();
+        let dropped = dir.path().join("dropped");
+        let submitted = dir.path().join("submitted");
+        let scripts = [
+            (
+                dir.path().join("ssh"),
+                format!(
+                    "#!/bin/sh\nwhile [ \"$1\" != -- ]; do shift; done\nshift\n\
+                     PATH={1}:$PATH sh -c \"$1\"\nstatus=$?\n\
+                     case \"$1\" in sbatch*) [ -e {0} ] || {{ touch {0}; exit 255; }};; esac\n\
+                     exit $status\n",
+                    dropped.display(),
+                    bin.display()
+                ),
+            ),
+            (
+                bin.join("sbatch"),
+                format!(
+                    "#!/bin/sh\ngrep -o 'arvak:[^ ]*' \"$1\" >> {}\n\
+                     echo 'Submitted batch job 4242'\n",
+                    submitted.display()
+                ),
+            ),
+            (
+                bin.join("squeue"),
+                format!(
+                    "#!/bin/sh\n[ -e {0} ] && echo \"4242|$(head -n 1 {0})\"\nexit 0\n",
+                    submitted.display()
+                ),
+            ),
+        ];
+        for (path, script) in &scripts {
+            std::fs::write(path, script).unwrap();
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let config = SlurmConfig {
+            work_dir: dir.path().join("work"),
+            ssh: Some(SshConfig {
+                ssh_binary: dir.path().join("ssh"),
+                control_persist_secs: 0,
+                retry_backoff_ms: 1,
+                ..SshConfig::new("login1")
+            }),
+            ..Default::default()
+        };
+        let adapter = SlurmAdapter::new(config).await.unwrap();
+
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];");
+        let job = ScheduledJob::new("dropped", circuit);
+        assert_eq!(adapter.submit(&job).await.unwrap(), "4242");
+
+        let submissions = std::fs::read_to_string(&submitted).unwrap();
+        assert_eq!(
+            submissions.lines().collect::<Vec<_>>(),
+            [templates::submission_comment(&job)]
+        );
+    }
 }
@@ -3,5 +3,10 @@
 mod adapter;
 mod parser;
 mod templates;
+mod transport;
 
 pub use adapter::{SlurmAdapter, SlurmConfig, SlurmJobInfo, SlurmState};
+pub use parser::{
+    ParsableReader, ParsableRecord, SacctStream, parse_sacct_records, parse_squeue_records,
+};
+pub use transport::{SshConfig, SshTransport};
@@ -23,48 +23,171 @@ pub fn parse_sbatch_output(output: &str) -> SchedResult<String> {
     })
 }
 
-/// Parse squeue output to extract job information.
+/// Find the job tagged with `comment` in `<job ID>|<comment>` lines, as
+/// printed by `squeue -h -o "%i|%k"` and `sacct -X -n -P -o JobID,Comment`.
 ///
-/// Expected format (from `squeue -j <id> -o "%i|%j|%T|%r|%S"`):
-/// JOBID|NAME|STATE|REASON|START_TIME
-/// 12345|job_name|RUNNING|None|2024-01-15T10:30:00
-pub fn parse_squeue_output(output: &str) -> SchedResult<Option<SlurmJobInfo>> {
-    let lines: Vec<&str> = output.lines().collect();
+/// The tasks of a job array, listed as `<array job ID>_<task>`, are
+/// reported as the array job's ID.
+pub fn find_commented_job(output: &str, comment: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (job_id, found) = line.trim().split_once('|')?;
+        let job_id = job_id.split('_').next().unwrap_or(job_id);
+        (found.trim() == comment && !job_id.is_empty()).then(|| job_id.to_string())
+    })
+}
+
+/// Header-driven reader over pipe-delimited SLURM output.
+///
+/// Handles `--parsable2` output from `sacct` as well as `squeue -o` formats
+/// using `|` as separator. Records borrow from the input and fields are
+/// located by header name, so no per-line allocation is needed.
+pub struct ParsableReader<'a> {
+    header: &'a str,
+    lines: std::str::Lines<'a>,
+}
 
-    // Skip header line
-    if lines.len() < 2 {
-        return Ok(None);
+impl<'a> ParsableReader<'a> {
+    /// Create a reader, consuming the first non-empty line as header.
+    pub fn new(output: &'a str) -> Option<Self> {
+        let mut lines = output.lines();
+        let header = lines.by_ref().map(str::trim).find(|l| !l.is_empty())?;
+        Some(Self { header, lines })
     }
 
-    let data_line = lines[1].trim();
-    if data_line.is_empty() {
-        return Ok(None);
+    /// Get the index of a column by (case-insensitive) header name.
+    pub fn column(&self, name: &str) -> Option<usize> {
+        header_column(self.header, name)
     }
 
-    let parts: Vec<&str> = data_line.split('|').collect();
-    if parts.len() < 4 {
-        return Err(SchedError::SlurmCommandError {
-            command: "squeue".to_string(),
-            message: format!("Unexpected output format: {}", data_line),
-        });
+    /// The header line.
+    pub fn header(&self) -> &'a str {
+        self.header
     }
+}
 
-    let job_id = parts[0].trim().to_string();
-    let name = parts[1].trim().to_string();
-    let state = parse_slurm_state(parts[2].trim());
-    let reason = if parts[3].trim() == "None" {
-        None
-    } else {
-        Some(parts[3].trim().to_string())
-    };
+impl<'a> Iterator for ParsableReader<'a> {
+    type Item = ParsableRecord<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.by_ref().map(str::trim).find_map(|line| {
+            if line.is_empty() {
+                None
+            } else {
+                Some(ParsableRecord { line })
+            }
+        })
+    }
+}
 
-    Ok(Some(SlurmJobInfo {
-        job_id,
-        name,
-        state,
-        reason,
-        exit_code: None,
-    }))
+/// A single borrowed record of pipe-delimited SLURM output.
+#[derive(Debug, Clone, Copy)]
+pub struct ParsableRecord<'a> {
+    line: &'a str,
+}
+
+impl<'a> ParsableRecord<'a> {
+    /// Wrap a record line.
+    pub fn new(line: &'a str) -> Self {
+        Self { line }
+    }
+
+    /// Get the fields at the given column indices, splitting the line once;
+    /// `None` if the record has too few fields.
+    pub fn select<const N: usize>(&self, columns: [usize; N]) -> Option<[&'a str; N]> {
+        let last = columns.iter().copied().max()?;
+        let mut fields = [""; N];
+        let mut found = 0;
+        for (i, field) in self.line.split('|').take(last + 1).enumerate() {
+            for (slot, _) in fields.iter_mut().zip(columns).filter(|(_, c)| *c == i) {
+                *slot = field.trim();
+            }
+            found = i + 1;
+        }
+        (found > last).then_some(fields)
+    }
+
+    /// Whether the record has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.line.is_empty()
+    }
+
+    /// The raw record line.
+    pub fn as_str(&self) -> &'a str {
+        self.line
+    }
+}
+
+/// Get the index of a column by (case-insensitive) header name.
+fn header_column(header: &str, name: &str) -> Option<usize> {
+    header
+        .split('|')
+        .position(|col| col.trim().eq_ignore_ascii_case(name))
+}
+
+/// Column indices resolved from a header, with positional fallbacks.
+struct Columns {
+    job_id: usize,
+    name: usize,
+    state: usize,
+    extra: usize,
+}
+
+impl Columns {
+    fn resolve(header: &str, extra: &str) -> Self {
+        Self {
+            job_id: header_column(header, "JobID").unwrap_or(0),
+            name: header_column(header, "JobName")
+                .or_else(|| header_column(header, "NAME"))
+                .unwrap_or(1),
+            state: header_column(header, "STATE").unwrap_or(2),
+            extra: header_column(header, extra).unwrap_or(3),
+        }
+    }
+
+    /// Indices of the job ID, name, state and extra columns.
+    fn indices(&self) -> [usize; 4] {
+        [self.job_id, self.name, self.state, self.extra]
+    }
+}
+
+/// Parse squeue output to extract job information.
+///
+/// Expected format (from `squeue -j <id> -o "%i|%j|%T|%r|%S"`):
+/// JOBID|NAME|STATE|REASON|START_TIME
+/// 12345|job_name|RUNNING|None|2024-01-15T10:30:00
+///
+/// The rows of an array job's tasks are combined with
+/// [`combine_array_tasks`].
+pub fn parse_squeue_output(output: &str) -> SchedResult<Option<SlurmJobInfo>> {
+    let tasks = parse_squeue_records(output).collect::<SchedResult<Vec<_>>>()?;
+    Ok(combine_array_tasks(tasks))
+}
+
+/// Stream all job records from squeue output.
+pub fn parse_squeue_records(output: &str) -> impl Iterator<Item = SchedResult<SlurmJobInfo>> + '_ {
+    ParsableReader::new(output).into_iter().flat_map(|reader| {
+        let cols = Columns::resolve(reader.header(), "REASON");
+        reader.map(move |record| {
+            let Some([job_id, name, state, reason]) = record.select(cols.indices()) else {
+                return Err(SchedError::SlurmCommandError {
+                    command: "squeue".to_string(),
+                    message: format!("Unexpected output format: {}", record.as_str()),
+                });
+            };
+
+            Ok(SlurmJobInfo {
+                job_id: job_id.to_string(),
+                name: name.to_string(),
+                state: parse_slurm_state(state),
+                reason: if reason == "None" || reason.is_empty() {
+                    None
+                } else {
+                    Some(reason.to_string())
+                },
+                exit_code: None,
+            })
+        })
+    })
 }
 
 /// Parse sacct output for completed job information.
@@ -73,41 +196,91 @@ pub fn parse_squeue_output(output: &str) -> SchedResult<Option<SlurmJobInfo>> {
 /// JobID|JobName|State|ExitCode
 /// 12345|job_name|COMPLETED|0:0
 /// 12345.batch|batch|COMPLETED|0:0
+///
+/// The records of an array job's tasks are combined with
+/// [`combine_array_tasks`].
 pub fn parse_sacct_output(output: &str) -> SchedResult<Option<SlurmJobInfo>> {
-    let lines: Vec<&str> = output.lines().collect();
+    Ok(combine_array_tasks(parse_sacct_records(output).collect()))
+}
 
-    // Skip header line
-    if lines.len() < 2 {
-        return Ok(None);
-    }
+/// Stream the main job records from sacct output, skipping job steps
+/// (e.g., `12345.batch`, `12345.extern`) and malformed lines.
+pub fn parse_sacct_records(output: &str) -> impl Iterator<Item = SlurmJobInfo> + '_ {
+    ParsableReader::new(output).into_iter().flat_map(|reader| {
+        let cols = Columns::resolve(reader.header(), "ExitCode");
+        reader.filter_map(move |record| sacct_record(&record, &cols))
+    })
+}
 
-    // Find the main job line (not .batch or .extern)
-    for line in &lines[1..] {
-        let parts: Vec<&str> = line.split('|').collect();
-        if parts.len() < 4 {
-            continue;
-        }
+/// Convert a sacct record into job information, if it is a main job line.
+fn sacct_record(record: &ParsableRecord<'_>, cols: &Columns) -> Option<SlurmJobInfo> {
+    let [job_id, name, state, exit_code] = record.select(cols.indices())?;
+    if job_id.is_empty() || job_id.contains('.') {
+        return None;
+    }
 
-        let job_id = parts[0].trim();
-        // Skip sub-jobs (e.g., "12345.batch", "12345.extern")
-        if job_id.contains('.') {
-            continue;
-        }
+    Some(SlurmJobInfo {
+        job_id: job_id.to_string(),
+        name: name.to_string(),
+        state: parse_slurm_state(state),
+        reason: None,
+        exit_code: parse_exit_code(exit_code),
+    })
+}
 
-        let name = parts[1].trim().to_string();
-        let state = parse_slurm_state(parts[2].trim());
-        let exit_code = parse_exit_code(parts[3].trim());
+/// Combine the records of a job's array tasks into one record for the job.
+///
+/// The job is active while any task is, and failed if any task failed;
+/// task IDs such as `12345_3` are reported as the array's ID `12345`. A
+/// single record, as for jobs that are not arrays, is returned unchanged.
+pub fn combine_array_tasks(mut tasks: Vec<SlurmJobInfo>) -> Option<SlurmJobInfo> {
+    if tasks.len() <= 1 {
+        return tasks.pop();
+    }
 
-        return Ok(Some(SlurmJobInfo {
-            job_id: job_id.to_string(),
-            name,
-            state,
-            reason: None,
-            exit_code,
-        }));
+    let pick = |tasks: &mut Vec<SlurmJobInfo>, f: fn(&SlurmState) -> bool| {
+        tasks
+            .iter()
+            .position(|t| f(&t.state))
+            .map(|i| tasks.swap_remove(i))
+    };
+    let mut info = pick(&mut tasks, |s| {
+        matches!(s, SlurmState::Running | SlurmState::Completing)
+    })
+    .or_else(|| pick(&mut tasks, |s| !s.is_terminal()))
+    .or_else(|| pick(&mut tasks, |s| !s.is_success()))
+    .or_else(|| tasks.pop())?;
+    if let Some((array_id, _)) = info.job_id.split_once('_') {
+        info.job_id = array_id.to_string();
     }
+    Some(info)
+}
+
+/// Incremental sacct parser, fed one line of output at a time.
+///
+/// The first non-empty line is taken as the header and every later line is
+/// parsed on its own, so memory use is bounded by the longest line rather
+/// than the full `sacct` output.
+#[derive(Default)]
+pub struct SacctStream {
+    cols: Option<Columns>,
+}
 
-    Ok(None)
+impl SacctStream {
+    /// Parse the next line; returns the job of a main job record.
+    pub fn push(&mut self, line: &str) -> Option<SlurmJobInfo> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+        match &self.cols {
+            Some(cols) => sacct_record(&ParsableRecord::new(line), cols),
+            None => {
+                self.cols = Some(Columns::resolve(line, "ExitCode"));
+                None
+            }
+        }
+    }
 }
 
 /// Parse SLURM state string.
@@ -155,10 +328,68 @@ pub fn parse_scancel_output(output: &str, stderr: &str) -> SchedResult<()> {
     Ok(())
 }
 
+/// Parse `scontrol requeue` output to verify the job was requeued.
+pub fn parse_requeue_output(stderr: &str) -> SchedResult<()> {
+    if stderr.contains("Invalid job id") || stderr.contains("does not exist") {
+        return Err(SchedError::SlurmJobNotFound(
+            "Job not found or already completed".to_string(),
+        ));
+    }
+
+    if !stderr.trim().is_empty() {
+        return Err(SchedError::SlurmCommandError {
+            command: "scontrol requeue".to_string(),
+            message: stderr.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Parse the output of `scontrol create reservation` or `scontrol delete
+/// ReservationName=`; `command` names the command for errors.
+pub fn parse_reservation_output(command: &str, stderr: &str) -> SchedResult<()> {
+    if !stderr.trim().is_empty() {
+        return Err(SchedError::SlurmCommandError {
+            command: command.to_string(),
+            message: stderr.trim().to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_requeue_output() {
+        assert!(parse_requeue_output("").is_ok());
+        assert!(matches!(
+            parse_requeue_output("scontrol: error: Invalid job id specified"),
+            Err(SchedError::SlurmJobNotFound(_))
+        ));
+        assert!(matches!(
+            parse_requeue_output("Requested operation is presently disabled for job 12345"),
+            Err(SchedError::SlurmCommandError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_reservation_output() {
+        assert!(parse_reservation_output("scontrol create reservation", "").is_ok());
+        assert!(matches!(
+            parse_reservation_output(
+                "scontrol create reservation",
+                "Error creating the reservation: Requested nodes are busy\n"
+            ),
+            Err(SchedError::SlurmCommandError { command, message })
+                if command == "scontrol create reservation"
+                    && message == "Error creating the reservation: Requested nodes are busy"
+        ));
+    }
+
     #[test]
     fn test_parse_sbatch_output() {
         let output = "Submitted batch job 12345\n";
@@ -176,6 +407,23 @@ mod tests {
         assert!(parse_sbatch_output(output).is_err());
     }
 
+    #[test]
+    fn test_find_commented_job() {
+        let output = "100|arvak:01A\n101|\n102|arvak:01B\n";
+        assert_eq!(
+            find_commented_job(output, "arvak:01B").as_deref(),
+            Some("102")
+        );
+        assert_eq!(find_commented_job(output, "arvak:01C"), None);
+        assert_eq!(find_commented_job("", "arvak:01A"), None);
+
+        let output = "200_[2-4]|arvak:01D\n200_1|arvak:01D\n";
+        assert_eq!(
+            find_commented_job(output, "arvak:01D").as_deref(),
+            Some("200")
+        );
+    }
+
     #[test]
     fn test_parse_squeue_output() {
         let output =
@@ -217,6 +465,109 @@ mod tests {
         assert_eq!(info.exit_code, Some(1));
     }
 
+    #[test]
+    fn test_combine_array_tasks() {
+        let output = "JOBID|NAME|STATE|REASON|START_TIME\n\
+                      12345_0|sweep|COMPLETED|None|N/A\n\
+                      12345_[2-3]|sweep|PENDING|Resources|N/A\n\
+                      12345_1|sweep|RUNNING|None|N/A\n";
+        let info = parse_squeue_output(output).unwrap().unwrap();
+        assert_eq!(info.job_id, "12345");
+        assert!(matches!(info.state, SlurmState::Running));
+
+        let output = "JobID|JobName|State|ExitCode\n\
+                      12345_0|sweep|COMPLETED|0:0\n\
+                      12345_0.batch|batch|COMPLETED|0:0\n\
+                      12345_1|sweep|OUT_OF_MEMORY|0:125\n\
+                      12345_2|sweep|COMPLETED|0:0\n";
+        let info = parse_sacct_output(output).unwrap().unwrap();
+        assert_eq!(info.job_id, "12345");
+        assert!(matches!(info.state, SlurmState::OutOfMemory));
+
+        let output = "JobID|JobName|State|ExitCode\n\
+                      12345_0|sweep|COMPLETED|0:0\n\
+                      12345_1|sweep|COMPLETED|0:0\n";
+        let info = parse_sacct_output(output).unwrap().unwrap();
+        assert!(matches!(info.state, SlurmState::Completed));
+    }
+
+    #[test]
+    fn test_parse_sacct_records_many() {
+        let output = "JobID|JobName|State|ExitCode\n\
+                      1|a|COMPLETED|0:0\n\
+                      1.batch|batch|COMPLETED|0:0\n\
+                      2|b|FAILED|2:0\n\
+                      \n\
+                      3|c|TIMEOUT|0:1\n";
+        let jobs: Vec<_> = parse_sacct_records(output).collect();
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[1].job_id, "2");
+        assert_eq!(jobs[1].exit_code, Some(2));
+        assert!(matches!(jobs[2].state, SlurmState::Timeout));
+    }
+
+    #[test]
+    fn test_parse_sacct_reordered_columns() {
+        let output = "State|ExitCode|JobID|JobName\nCOMPLETED|0:0|77|reordered\n";
+        let info = parse_sacct_output(output).unwrap().unwrap();
+        assert_eq!(info.job_id, "77");
+        assert_eq!(info.name, "reordered");
+        assert!(matches!(info.state, SlurmState::Completed));
+    }
+
+    #[test]
+    fn test_sacct_stream() {
+        let output = "\nJobID|JobName|State|ExitCode\n10|x|COMPLETED|0:0\n10.extern|extern|COMPLETED|0:0\n11|y|RUNNING|0:0\n";
+        let mut stream = SacctStream::default();
+        let seen: Vec<_> = output
+            .lines()
+            .filter_map(|line| stream.push(line))
+            .map(|info| info.job_id)
+            .collect();
+        assert_eq!(seen, ["10", "11"]);
+    }
+
+    #[test]
+    fn test_select_fields() {
+        let record = ParsableRecord::new("a| b |c");
+        assert_eq!(record.select([2, 0]), Some(["c", "a"]));
+        assert_eq!(record.select([1, 1]), Some(["b", "b"]));
+        assert_eq!(record.select([3]), None);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn fuzz_squeue_never_panics(output in "[A-Za-z0-9|.:_ \n-]{0,256}") {
+            for record in parse_squeue_records(&output) {
+                let _ = record;
+            }
+        }
+
+        #[test]
+        fn fuzz_sacct_never_panics(output in "[A-Za-z0-9|.:_ \n-]{0,256}") {
+            let _ = parse_sacct_records(&output).count();
+            let mut stream = SacctStream::default();
+            let _ = output.lines().filter_map(|line| stream.push(line)).count();
+        }
+
+        #[test]
+        fn fuzz_sacct_roundtrip(
+            rows in proptest::collection::vec(("[0-9]{1,7}", "[a-z_]{1,12}", 0i32..256), 0..50)
+        ) {
+            let mut output = String::from("JobID|JobName|State|ExitCode\n");
+            for (id, name, code) in &rows {
+                output.push_str(&format!("{}|{}|COMPLETED|{}:0\n{}.batch|batch|COMPLETED|0:0\n", id, name, code, id));
+            }
+            let jobs: Vec<_> = parse_sacct_records(&output).collect();
+            proptest::prop_assert_eq!(jobs.len(), rows.len());
+            for (job, (id, name, code)) in jobs.iter().zip(&rows) {
+                proptest::prop_assert_eq!(&job.job_id, id);
+                proptest::prop_assert_eq!(&job.name, name);
+                proptest::prop_assert_eq!(job.exit_code, Some(*code));
+            }
+        }
+    }
+
     #[test]
     fn test_parse_slurm_state() {
         assert!(matches!(parse_slurm_state("PENDING"), SlurmState::Pending));
@@ -1,8 +1,11 @@
 //! SLURM batch script templates.
 
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-use crate::job::ScheduledJob;
+use crate::job::{ScheduledJob, TaskOverride};
+use crate::notify::EVENT_SUFFIX;
+use crate::partial::{PARTIAL_OUTPUT_ENV, PARTIAL_SHOTS_ENV, snapshot_path};
 use crate::slurm::adapter::SlurmConfig;
 
 /// Generate a SLURM batch script for a quantum job.
@@ -38,7 +41,7 @@ pub fn generate_batch_script(
 
     script.push_str(&format!(
         "#SBATCH --time={}\n",
-        format_time(config.time_limit)
+        format_time(time_limit(job, config))
     ));
     script.push_str(&format!("#SBATCH --mem={}M\n", config.memory_mb));
     script.push_str(&format!(
@@ -46,6 +49,11 @@ pub fn generate_batch_script(
         config.cpus_per_task
     ));
 
+    push_dependency(&mut script, job);
+    push_constraint(&mut script, job);
+    push_reservation(&mut script, job);
+    push_comment(&mut script, job);
+
     // Optional QOS based on priority
     if let Some(ref qos_mapping) = config.priority_qos_mapping {
         if let Some(qos) = qos_mapping.get(&job.priority.value()) {
@@ -57,6 +65,7 @@ pub fn generate_batch_script(
     script.push_str("\n# Environment setup\n");
     script.push_str("set -e\n");
     script.push_str("set -o pipefail\n\n");
+    push_status_events(&mut script, config);
 
     // Load modules if configured
     if !config.modules.is_empty() {
@@ -82,6 +91,7 @@ pub fn generate_batch_script(
 
     // Execute Arvak command
     script.push_str("# Execute quantum job\n");
+    push_partial_output(&mut script, config, result_file);
 
     let backend_flag = if let Some(ref backend) = job.matched_backend {
         format!("--backend {}", backend)
@@ -93,7 +103,7 @@ pub fn generate_batch_script(
         "{} run {} --shots {} {} --output {}\n",
         config.arvak_binary.display(),
         circuit_file.display(),
-        job.shots,
+        job.circuit_shots(0),
         backend_flag,
         result_file.display(),
     ));
@@ -136,8 +146,10 @@ pub fn generate_batch_script_multi(
         script.push_str(&format!("#SBATCH --account={}\n", account));
     }
 
-    // Scale time based on number of circuits
-    let scaled_time = config.time_limit * circuit_files.len() as u32;
+    // Scale time based on number of circuits unless the job set its own
+    let scaled_time = job
+        .walltime_minutes()
+        .unwrap_or(config.time_limit * circuit_files.len() as u32);
     script.push_str(&format!("#SBATCH --time={}\n", format_time(scaled_time)));
     script.push_str(&format!("#SBATCH --mem={}M\n", config.memory_mb));
     script.push_str(&format!(
@@ -145,10 +157,16 @@ pub fn generate_batch_script_multi(
         config.cpus_per_task
     ));
 
+    push_dependency(&mut script, job);
+    push_constraint(&mut script, job);
+    push_reservation(&mut script, job);
+    push_comment(&mut script, job);
+
     // Environment setup
     script.push_str("\n# Environment setup\n");
     script.push_str("set -e\n");
     script.push_str("set -o pipefail\n\n");
+    push_status_events(&mut script, config);
 
     // Load modules if configured
     if !config.modules.is_empty() {
@@ -187,15 +205,17 @@ pub fn generate_batch_script_multi(
     for (i, circuit_file) in circuit_files.iter().enumerate() {
         let result_file = result_dir.join(format!("result_{}.json", i));
         script.push_str(&format!(
-            "echo \"Running circuit {} of {}\"\n",
+            "echo \"Running circuit {} of {} ({})\"\n",
             i + 1,
-            circuit_files.len()
+            circuit_files.len(),
+            sanitize_name(&job.circuit_label(i))
         ));
+        push_partial_output(&mut script, config, &result_file);
         script.push_str(&format!(
             "if ! {} run {} --shots {} {} --output {}; then\n",
             config.arvak_binary.display(),
             circuit_file.display(),
-            job.shots,
+            job.circuit_shots(i),
             backend_flag,
             result_file.display(),
         ));
@@ -216,8 +236,408 @@ pub fn generate_batch_script_multi(
     script
 }
 
+/// Generate a job array script running each circuit as its own task.
+///
+/// Tasks with a [`TaskOverride`](crate::job::TaskOverride) get their own
+/// memory and time limits through a `case` on `SLURM_ARRAY_TASK_ID`. The
+/// array is allocated for the largest limits, and each task's job step is
+/// held to its own with `srun --mem --time`.
+pub fn generate_array_script(
+    job: &ScheduledJob,
+    config: &SlurmConfig,
+    circuit_files: &[&Path],
+    result_dir: &Path,
+) -> String {
+    let shots: Vec<u32> = (0..circuit_files.len())
+        .map(|i| job.circuit_shots(i))
+        .collect();
+    let result_files: Vec<PathBuf> = (0..circuit_files.len())
+        .map(|i| result_dir.join(format!("result_{}.json", i)))
+        .collect();
+    array_script(
+        job,
+        config,
+        circuit_files,
+        &shots,
+        &result_files,
+        &job.task_overrides,
+        result_dir,
+    )
+}
+
+/// Generate a job array script running one single-circuit job per task.
+///
+/// The first job names the array and supplies its priority, dependency,
+/// walltime and backend; each job's own override of circuit 0 becomes the
+/// override of its task.
+pub fn generate_sweep_script(
+    jobs: &[ScheduledJob],
+    config: &SlurmConfig,
+    circuit_files: &[&Path],
+    result_files: &[PathBuf],
+    result_dir: &Path,
+) -> String {
+    let shots: Vec<u32> = jobs.iter().map(|job| job.circuit_shots(0)).collect();
+    let overrides: BTreeMap<usize, TaskOverride> = jobs
+        .iter()
+        .enumerate()
+        .filter_map(|(task, job)| Some((task, job.task_overrides.get(&0)?.clone())))
+        .collect();
+    array_script(
+        &jobs[0],
+        config,
+        circuit_files,
+        &shots,
+        result_files,
+        &overrides,
+        result_dir,
+    )
+}
+
+/// Generate a job array script from each task's circuit, shots and
+/// result file.
+fn array_script(
+    job: &ScheduledJob,
+    config: &SlurmConfig,
+    circuit_files: &[&Path],
+    shots: &[u32],
+    result_files: &[PathBuf],
+    overrides: &BTreeMap<usize, TaskOverride>,
+    result_dir: &Path,
+) -> String {
+    let mut script = String::new();
+
+    // Shebang
+    script.push_str("#!/bin/bash\n");
+
+    // SLURM directives
+    script.push_str(&format!(
+        "#SBATCH --job-name={}\n",
+        sanitize_name(&job.name)
+    ));
+    script.push_str(&format!(
+        "#SBATCH --output={}/slurm-%A_%a.out\n",
+        config.work_dir.display()
+    ));
+    script.push_str(&format!(
+        "#SBATCH --error={}/slurm-%A_%a.err\n",
+        config.work_dir.display()
+    ));
+    script.push_str(&format!("#SBATCH --partition={}\n", config.partition));
+
+    if let Some(ref account) = config.account {
+        script.push_str(&format!("#SBATCH --account={}\n", account));
+    }
+
+    script.push_str(&format!(
+        "#SBATCH --array=0-{}\n",
+        circuit_files.len().saturating_sub(1)
+    ));
+    let max_time = overrides
+        .values()
+        .filter_map(|o| o.time_limit)
+        .fold(time_limit(job, config), u32::max);
+    let max_memory = overrides
+        .values()
+        .filter_map(|o| o.memory_mb)
+        .fold(config.memory_mb, u32::max);
+    script.push_str(&format!("#SBATCH --time={}\n", format_time(max_time)));
+    script.push_str(&format!("#SBATCH --mem={}M\n", max_memory));
+    script.push_str(&format!(
+        "#SBATCH --cpus-per-task={}\n",
+        config.cpus_per_task
+    ));
+
+    push_dependency(&mut script, job);
+    push_constraint(&mut script, job);
+    push_reservation(&mut script, job);
+    push_comment(&mut script, job);
+
+    if let Some(ref qos_mapping) = config.priority_qos_mapping {
+        if let Some(qos) = qos_mapping.get(&job.priority.value()) {
+            script.push_str(&format!("#SBATCH --qos={}\n", qos));
+        }
+    }
+
+    // Environment setup
+    script.push_str("\n# Environment setup\n");
+    script.push_str("set -e\n");
+    script.push_str("set -o pipefail\n\n");
+    push_status_events(&mut script, config);
+
+    // Load modules if configured
+    if !config.modules.is_empty() {
+        script.push_str("# Load required modules\n");
+        for module in &config.modules {
+            script.push_str(&format!("module load {}\n", module));
+        }
+        script.push('\n');
+    }
+
+    // Activate virtual environment if configured
+    if let Some(ref venv) = config.python_venv {
+        script.push_str("# Activate Python environment\n");
+        script.push_str(&format!("source {}/bin/activate\n\n", venv.display()));
+    }
+
+    // Task inputs, indexed by array task ID
+    script.push_str("# Circuit files, shots and result files by task\n");
+    script.push_str("CIRCUITS=(\n");
+    for circuit_file in circuit_files {
+        script.push_str(&format!(
+            "    {}\n",
+            shell_quote(&circuit_file.display().to_string())
+        ));
+    }
+    script.push_str(")\n");
+    let shots: Vec<String> = shots.iter().map(u32::to_string).collect();
+    script.push_str(&format!("SHOTS=({})\n", shots.join(" ")));
+    script.push_str("RESULTS=(\n");
+    for result_file in result_files {
+        script.push_str(&format!(
+            "    {}\n",
+            shell_quote(&result_file.display().to_string())
+        ));
+    }
+    script.push_str(")\n\n");
+
+    script.push_str("TASK=$SLURM_ARRAY_TASK_ID\n");
+    script.push_str("CIRCUIT=${CIRCUITS[$TASK]}\n");
+    script.push_str("RESULT=${RESULTS[$TASK]}\n\n");
+
+    let launcher = if overrides.is_empty() {
+        String::new()
+    } else {
+        script.push_str("# Per-task resources\n");
+        script.push_str(&format!("TASK_MEM={}M\n", config.memory_mb));
+        script.push_str(&format!(
+            "TASK_TIME={}\n",
+            format_time(time_limit(job, config))
+        ));
+        script.push_str("case $TASK in\n");
+        for (index, task) in overrides {
+            let mut settings = Vec::new();
+            if let Some(memory_mb) = task.memory_mb {
+                settings.push(format!("TASK_MEM={}M", memory_mb));
+            }
+            if let Some(time_limit) = task.time_limit {
+                settings.push(format!("TASK_TIME={}", format_time(time_limit)));
+            }
+            if !settings.is_empty() {
+                script.push_str(&format!("    {}) {} ;;\n", index, settings.join("; ")));
+            }
+        }
+        script.push_str("esac\n\n");
+        "srun --mem=$TASK_MEM --time=$TASK_TIME ".to_string()
+    };
+
+    // Job information
+    script.push_str("# Job information\n");
+    script.push_str("echo \"Job ID: ${SLURM_ARRAY_JOB_ID}_${TASK}\"\n");
+    script.push_str("echo \"Node: $SLURM_NODELIST\"\n");
+    script.push_str("echo \"Start Time: $(date)\"\n\n");
+
+    script.push_str(&format!("mkdir -p {}\n\n", result_dir.display()));
+
+    // Execute the task's circuit
+    script.push_str("# Execute quantum job\n");
+    if let Some(shots) = config.partial_shots {
+        script.push_str(&format!(
+            "export {}=\"${{RESULT%.json}}.partial.json\"\n",
+            PARTIAL_OUTPUT_ENV
+        ));
+        script.push_str(&format!("export {}={}\n", PARTIAL_SHOTS_ENV, shots));
+    }
+
+    let backend_flag = if let Some(ref backend) = job.matched_backend {
+        format!("--backend {}", backend)
+    } else {
+        String::new()
+    };
+
+    script.push_str(&format!(
+        "{}{} run \"$CIRCUIT\" --shots ${{SHOTS[$TASK]}} {} --output \"$RESULT\"\n",
+        launcher,
+        config.arvak_binary.display(),
+        backend_flag,
+    ));
+
+    script.push_str("\necho \"Task $TASK completed at: $(date)\"\n");
+
+    script
+}
+
+/// Generate a batch script running a user script as a classical task.
+pub fn generate_task_script(
+    job: &ScheduledJob,
+    config: &SlurmConfig,
+    task_script: &Path,
+    args: &[String],
+    inputs_file: &Path,
+    output_file: &Path,
+) -> String {
+    let mut script = String::new();
+
+    // Shebang
+    script.push_str("#!/bin/bash\n");
+
+    // SLURM directives
+    script.push_str(&format!(
+        "#SBATCH --job-name={}\n",
+        sanitize_name(&job.name)
+    ));
+    script.push_str(&format!(
+        "#SBATCH --output={}/slurm-%j.out\n",
+        config.work_dir.display()
+    ));
+    script.push_str(&format!(
+        "#SBATCH --error={}/slurm-%j.err\n",
+        config.work_dir.display()
+    ));
+    script.push_str(&format!("#SBATCH --partition={}\n", config.partition));
+
+    if let Some(ref account) = config.account {
+        script.push_str(&format!("#SBATCH --account={}\n", account));
+    }
+
+    script.push_str(&format!(
+        "#SBATCH --time={}\n",
+        format_time(time_limit(job, config))
+    ));
+    script.push_str(&format!("#SBATCH --mem={}M\n", config.memory_mb));
+    script.push_str(&format!(
+        "#SBATCH --cpus-per-task={}\n",
+        config.cpus_per_task
+    ));
+
+    push_dependency(&mut script, job);
+    push_comment(&mut script, job);
+
+    // Environment setup
+    script.push_str("\n# Environment setup\n");
+    script.push_str("set -e\n");
+    script.push_str("set -o pipefail\n\n");
+    push_status_events(&mut script, config);
+
+    if !config.modules.is_empty() {
+        script.push_str("# Load required modules\n");
+        for module in &config.modules {
+            script.push_str(&format!("module load {}\n", module));
+        }
+        script.push('\n');
+    }
+
+    if let Some(ref venv) = config.python_venv {
+        script.push_str("# Activate Python environment\n");
+        script.push_str(&format!("source {}/bin/activate\n\n", venv.display()));
+    }
+
+    // Task I/O
+    script.push_str(&format!(
+        "export ARVAK_TASK_INPUTS={}\n",
+        shell_quote(&inputs_file.display().to_string())
+    ));
+    script.push_str(&format!(
+        "export ARVAK_TASK_OUTPUT={}\n\n",
+        shell_quote(&output_file.display().to_string())
+    ));
+
+    // Execute classical task
+    script.push_str("# Execute classical task\n");
+    let mut command = shell_quote(&task_script.display().to_string());
+    for arg in args {
+        command.push(' ');
+        command.push_str(&shell_quote(arg));
+    }
+    script.push_str(&command);
+    script.push('\n');
+
+    script.push_str("\necho \"Job completed at: $(date)\"\n");
+
+    script
+}
+
+/// Quote a string for safe use as a single shell word.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Add native dependency directives when the job is chained on SLURM.
+fn push_dependency(script: &mut String, job: &ScheduledJob) {
+    if let Some(ref dependency) = job.batch_dependency {
+        script.push_str(&format!("#SBATCH --dependency={}\n", dependency));
+        // Let SLURM drop the job instead of holding it forever with
+        // reason DependencyNeverSatisfied.
+        script.push_str("#SBATCH --kill-on-invalid-dep=yes\n");
+    }
+}
+
+/// Restrict the job to nodes with all of its node features, e.g. those
+/// wired to the control hardware of its backend.
+fn push_constraint(script: &mut String, job: &ScheduledJob) {
+    if !job.node_features.is_empty() {
+        script.push_str(&format!(
+            "#SBATCH --constraint={}\n",
+            job.node_features.join("&")
+        ));
+    }
+}
+
+/// Run the job in its advance reservation.
+fn push_reservation(script: &mut String, job: &ScheduledJob) {
+    if let Some(reservation) = &job.reservation {
+        script.push_str(&format!("#SBATCH --reservation={}\n", reservation));
+    }
+}
+
+/// Tag the batch job with the ID of the job it runs, so a submission whose
+/// batch job ID was lost can be found again, see
+/// [`SlurmAdapter::find_submitted`](crate::SlurmAdapter::find_submitted).
+fn push_comment(script: &mut String, job: &ScheduledJob) {
+    script.push_str(&format!("#SBATCH --comment={}\n", submission_comment(job)));
+}
+
+/// Comment a job's batch jobs are tagged with.
+pub(crate) fn submission_comment(job: &ScheduledJob) -> String {
+    format!("arvak:{}", job.id)
+}
+
+/// Tell the next `arvak run` where to write partial-results snapshots.
+fn push_partial_output(script: &mut String, config: &SlurmConfig, result_file: &Path) {
+    if let Some(shots) = config.partial_shots {
+        script.push_str(&format!(
+            "export {}={}\n",
+            PARTIAL_OUTPUT_ENV,
+            shell_quote(&snapshot_path(result_file).display().to_string())
+        ));
+        script.push_str(&format!("export {}={}\n", PARTIAL_SHOTS_ENV, shots));
+    }
+}
+
+/// Touch the job's event file when it starts and when it exits, so the
+/// scheduler polls it right away.
+fn push_status_events(script: &mut String, config: &SlurmConfig) {
+    if let Some(ref dir) = config.event_dir {
+        script.push_str(
+            "# Status events
+",
+        );
+        // Array tasks are known by <array job ID>_<task>.
+        script.push_str(
+            "EVENT_ID=${SLURM_ARRAY_TASK_ID:+${SLURM_ARRAY_JOB_ID}_${SLURM_ARRAY_TASK_ID}}\n",
+        );
+        script.push_str(&format!(
+            "EVENT_FILE={}/\"${{EVENT_ID:-$SLURM_JOB_ID}}{}\"\n",
+            shell_quote(&dir.display().to_string()),
+            EVENT_SUFFIX
+        ));
+        script.push_str("touch \"$EVENT_FILE\"\n");
+        script.push_str("trap 'touch \"$EVENT_FILE\"' EXIT\n\n");
+    }
+}
+
 /// Sanitize a job name for SLURM.
-fn sanitize_name(name: &str) -> String {
+pub(crate) fn sanitize_name(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '_' || c == '-' {
@@ -230,6 +650,11 @@ fn sanitize_name(name: &str) -> String {
         .collect()
 }
 
+/// Time limit (minutes) of a job: its own walltime, else the configured one.
+fn time_limit(job: &ScheduledJob, config: &SlurmConfig) -> u32 {
+    job.walltime_minutes().unwrap_or(config.time_limit)
+}
+
 /// Format time in minutes to SLURM time format (D-HH:MM:SS or HH:MM:SS).
 fn format_time(minutes: u32) -> String {
     let hours = minutes / 60;
@@ -247,7 +672,8 @@ fn format_time(minutes: u32) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::job::{CircuitSpec, Priority};
+    use crate::job::{CircuitSpec, Priority, TaskOverride};
+    use crate::task::ClassicalTask;
     use std::path::PathBuf;
 
     fn test_config() -> SlurmConfig {
@@ -262,6 +688,11 @@ mod tests {
             modules: vec!["python/3.11".to_string()],
             python_venv: Some(PathBuf::from("/opt/arvak/venv")),
             priority_qos_mapping: None,
+            jwt: None,
+            partial_shots: None,
+            event_dir: None,
+            command_prefix: Vec::new(),
+            ssh: None,
         }
     }
 
@@ -280,12 +711,211 @@ mod tests {
 
         assert!(script.contains("#!/bin/bash"));
         assert!(script.contains("#SBATCH --job-name=test_job"));
+        assert!(script.contains(&format!("#SBATCH --comment=arvak:{}\n", job.id)));
         assert!(script.contains("#SBATCH --partition=quantum"));
         assert!(script.contains("#SBATCH --account=project123"));
         assert!(script.contains("#SBATCH --time=01:00:00"));
         assert!(script.contains("module load python/3.11"));
         assert!(script.contains("source /opt/arvak/venv/bin/activate"));
         assert!(script.contains("/opt/arvak/bin/arvak run"));
+        assert!(!script.contains("ARVAK_PARTIAL_OUTPUT"));
+        assert!(!script.contains("--constraint"));
+        assert!(!script.contains("--reservation"));
+    }
+
+    #[test]
+    fn test_batch_script_node_constraint() {
+        let config = test_config();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let mut job = ScheduledJob::new("controlled", circuit);
+        job.node_features = vec!["qctrl".to_string(), "ib".to_string()];
+
+        let script = generate_batch_script(
+            &job,
+            &config,
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+
+        assert!(script.contains("#SBATCH --constraint=qctrl&ib\n"));
+    }
+
+    #[test]
+    fn test_batch_script_reservation() {
+        let config = test_config();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job =
+            ScheduledJob::new("windowed", circuit).with_reservation("arvak-iqm-20261016T0800");
+
+        let script = generate_batch_script(
+            &job,
+            &config,
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+
+        assert!(script.contains("#SBATCH --reservation=arvak-iqm-20261016T0800\n"));
+    }
+
+    #[test]
+    fn test_batch_script_partial_output() {
+        let config = SlurmConfig {
+            partial_shots: Some(500),
+            ..test_config()
+        };
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::new("long_run", circuit);
+
+        let script = generate_batch_script(
+            &job,
+            &config,
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+
+        assert!(script.contains("export ARVAK_PARTIAL_OUTPUT='/scratch/result.partial.json'"));
+        assert!(script.contains("export ARVAK_PARTIAL_SHOTS=500"));
+    }
+
+    #[test]
+    fn test_batch_script_per_circuit_shots() {
+        let config = test_config();
+        let job = ScheduledJob::batch(
+            "calibrated",
+            vec![
+                CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;").with_label("experiment"),
+                CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;")
+                    .with_label("readout cal")
+                    .with_shots(200),
+            ],
+        )
+        .with_shots(4000);
+
+        let script = generate_batch_script_multi(
+            &job,
+            &config,
+            &[Path::new("/scratch/c0.qasm"), Path::new("/scratch/c1.qasm")],
+            Path::new("/scratch/results"),
+        );
+
+        assert!(script.contains("Running circuit 1 of 2 (experiment)"));
+        assert!(script.contains("Running circuit 2 of 2 (readout_cal)"));
+        assert!(script.contains("/scratch/c0.qasm --shots 4000"));
+        assert!(script.contains("/scratch/c1.qasm --shots 200"));
+    }
+
+    #[test]
+    fn test_array_script_task_overrides() {
+        let config = test_config();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let job = ScheduledJob::batch("sweep", vec![circuit.clone(), circuit.clone(), circuit])
+            .with_task_override(1, TaskOverride::memory_mb(8192))
+            .with_task_override(2, TaskOverride::memory_mb(16384).with_time_limit(150));
+
+        let script = generate_array_script(
+            &job,
+            &config,
+            &[
+                Path::new("/scratch/c0.qasm"),
+                Path::new("/scratch/c1.qasm"),
+                Path::new("/scratch/c2.qasm"),
+            ],
+            Path::new("/scratch/results"),
+        );
+
+        assert!(script.contains("#SBATCH --array=0-2"));
+        assert!(script.contains("#SBATCH --output=/scratch/jobs/slurm-%A_%a.out"));
+        // The allocation fits the largest task
+        assert!(script.contains("#SBATCH --mem=16384M"));
+        assert!(script.contains("#SBATCH --time=02:30:00"));
+        assert!(script.contains("TASK_MEM=4096M\nTASK_TIME=01:00:00\n"));
+        assert!(script.contains("    1) TASK_MEM=8192M ;;"));
+        assert!(script.contains("    2) TASK_MEM=16384M; TASK_TIME=02:30:00 ;;"));
+        assert!(script.contains("'/scratch/results/result_2.json'"));
+        assert!(script.contains(
+            "srun --mem=$TASK_MEM --time=$TASK_TIME /opt/arvak/bin/arvak run \"$CIRCUIT\""
+        ));
+
+        // Without overrides every task runs with the job's resources
+        let plain = ScheduledJob::batch("sweep", job.circuits.clone()).as_array();
+        let script = generate_array_script(
+            &plain,
+            &config,
+            &[Path::new("/scratch/c0.qasm"), Path::new("/scratch/c1.qasm")],
+            Path::new("/scratch/results"),
+        );
+        assert!(script.contains("#SBATCH --mem=4096M"));
+        assert!(!script.contains("case $TASK"));
+        assert!(script.contains("\n/opt/arvak/bin/arvak run \"$CIRCUIT\" --shots ${SHOTS[$TASK]}"));
+    }
+
+    #[test]
+    fn test_sweep_script() {
+        let config = test_config();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let jobs = vec![
+            ScheduledJob::new("theta_0", circuit.clone()).with_shots(100),
+            ScheduledJob::new("theta_1", circuit)
+                .with_shots(200)
+                .with_task_override(0, TaskOverride::memory_mb(8192)),
+        ];
+
+        let script = generate_sweep_script(
+            &jobs,
+            &config,
+            &[Path::new("/scratch/a.qasm"), Path::new("/scratch/b.qasm")],
+            &[
+                PathBuf::from("/scratch/results/a.json"),
+                PathBuf::from("/scratch/results/b.json"),
+            ],
+            Path::new("/scratch/results"),
+        );
+
+        assert!(script.contains("#SBATCH --job-name=theta_0"));
+        assert!(script.contains("#SBATCH --array=0-1"));
+        assert!(script.contains("SHOTS=(100 200)"));
+        assert!(script.contains("'/scratch/results/b.json'"));
+        assert!(script.contains("    1) TASK_MEM=8192M ;;"));
+        assert!(script.contains("#SBATCH --mem=8192M"));
+    }
+
+    #[test]
+    fn test_batch_script_dependency() {
+        let config = test_config();
+        let circuit = CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;");
+        let mut job = ScheduledJob::new("chained", circuit);
+        job.batch_dependency = Some("afterok:123,afternotok:456".to_string());
+
+        let script = generate_batch_script(
+            &job,
+            &config,
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+
+        assert!(script.contains("#SBATCH --dependency=afterok:123,afternotok:456"));
+        assert!(script.contains("#SBATCH --kill-on-invalid-dep=yes"));
+    }
+
+    #[test]
+    fn test_generate_task_script() {
+        let config = test_config();
+        let job = ScheduledJob::classical("post", ClassicalTask::script("/opt/post.py"));
+
+        let script = generate_task_script(
+            &job,
+            &config,
+            Path::new("/opt/post.py"),
+            &["--label".to_string(), "it's".to_string()],
+            Path::new("/scratch/tasks/in.json"),
+            Path::new("/scratch/results/out.json"),
+        );
+
+        assert!(script.contains("#SBATCH --job-name=post"));
+        assert!(script.contains(&format!("#SBATCH --comment=arvak:{}", job.id)));
+        assert!(script.contains("export ARVAK_TASK_INPUTS='/scratch/tasks/in.json'"));
+        assert!(script.contains("export ARVAK_TASK_OUTPUT='/scratch/results/out.json'"));
+        assert!(script.contains("'/opt/post.py' '--label' 'it'\\''s'"));
     }
 
     #[test]
@@ -299,6 +929,44 @@ mod tests {
         assert_eq!(sanitize_name(&long_name).len(), 64);
     }
 
+    #[test]
+    fn test_walltime_sets_time_limit() {
+        let config = test_config();
+        let job = ScheduledJob::new("short", CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q;"))
+            .with_walltime(std::time::Duration::from_secs(90 * 60 + 1));
+
+        let script = generate_batch_script(
+            &job,
+            &config,
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+        assert!(script.contains("#SBATCH --time=01:31:00"));
+    }
+
+    #[test]
+    fn test_event_dir_touches_event_file() {
+        let job = ScheduledJob::new("evented", CircuitSpec::from_qasm("OPENQASM 3.0;"));
+        let paths = (
+            Path::new("/scratch/circuit.qasm"),
+            Path::new("/scratch/result.json"),
+        );
+        let script = generate_batch_script(&job, &test_config(), paths.0, paths.1);
+        assert!(!script.contains("EVENT_FILE"));
+
+        let config = SlurmConfig {
+            event_dir: Some(PathBuf::from("/scratch/events")),
+            ..test_config()
+        };
+        let script = generate_batch_script(&job, &config, paths.0, paths.1);
+        assert!(
+            script.contains("EVENT_FILE='/scratch/events'/\"${EVENT_ID:-$SLURM_JOB_ID}.event\"")
+        );
+        assert!(script.contains("trap 'touch \"$EVENT_FILE\"' EXIT"));
+        // The start event follows the shell options.
+        assert!(script.find("set -o pipefail") < script.find("touch \"$EVENT_FILE\""));
+    }
+
     #[test]
     fn test_format_time() {
         assert_eq!(format_time(30), "00:30:00");
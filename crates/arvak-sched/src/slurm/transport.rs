@@ -0,0 +1,533 @@
+//! SSH transport for running Slurm commands on a remote login node.
+//!
+//! With [`SlurmConfig::ssh`](super::SlurmConfig::ssh) set, the adapter runs
+//! `sbatch`, `squeue`, `sacct`, `scancel` and `scontrol` on the cluster
+//! through `ssh` instead of locally. Job files are still written to the
+//! local work directory and copied to the same path on the cluster before
+//! submission; result files are copied back before they are read.
+//!
+//! Commands share one OpenSSH master connection per host (`ControlMaster`),
+//! kept open for [`SshConfig::control_persist_secs`] after the last command,
+//! so polling does not pay for a handshake each time. Authentication is by
+//! key only: `ssh` runs in batch mode and never prompts. Commands that are
+//! safe to repeat are retried with exponential backoff when the connection
+//! drops; for others, such as `sbatch`, the connection may have dropped
+//! after the command ran, so callers must check before running them again.
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::{Output, Stdio};
+use std::time::Duration;
+
+use arvak_config::InvalidKey;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+
+use crate::error::{SchedError, SchedResult};
+
+/// Exit status `ssh` reports when the connection itself failed, as opposed
+/// to the remote command.
+const SSH_CONNECTION_ERROR: i32 = 255;
+
+/// Commands that only read state or converge to the same state when run
+/// twice, and so are retried when the connection drops.
+const IDEMPOTENT_COMMANDS: &[&str] = &["squeue", "sacct", "scontrol", "mkdir"];
+
+/// Settings for reaching the cluster over SSH.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SshConfig {
+    /// Login node to connect to.
+    pub host: String,
+
+    /// Remote user name; the local SSH configuration decides if omitted.
+    pub user: Option<String>,
+
+    /// SSH port; the local SSH configuration decides if omitted.
+    pub port: Option<u16>,
+
+    /// Private key to authenticate with.
+    pub identity_file: Option<PathBuf>,
+
+    /// Seconds the shared connection stays open after the last command;
+    /// 0 opens a new connection per command.
+    pub control_persist_secs: u64,
+
+    /// Directory holding the shared connection sockets.
+    pub control_dir: PathBuf,
+
+    /// Seconds to wait for a connection to be established.
+    pub connect_timeout_secs: u64,
+
+    /// Retries of a command whose connection dropped.
+    pub retries: u32,
+
+    /// Delay before the first retry in milliseconds, doubled for each
+    /// further retry.
+    pub retry_backoff_ms: u64,
+
+    /// Path of the `ssh` binary.
+    pub ssh_binary: PathBuf,
+}
+
+impl Default for SshConfig {
+    fn default() -> Self {
+        Self {
+            host: String::new(),
+            user: None,
+            port: None,
+            identity_file: None,
+            control_persist_secs: 600,
+            control_dir: std::env::temp_dir().join("arvak-ssh"),
+            connect_timeout_secs: 10,
+            retries: 3,
+            retry_backoff_ms: 500,
+            ssh_binary: PathBuf::from("ssh"),
+        }
+    }
+}
+
+impl SshConfig {
+    /// Connect to a login node with default settings.
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Check the settings, naming the first invalid key.
+    pub fn validate(&self) -> Result<(), InvalidKey> {
+        if self.host.is_empty() {
+            return Err(InvalidKey::new("host", "must not be empty"));
+        }
+        if self.connect_timeout_secs == 0 {
+            return Err(InvalidKey::new(
+                "connect_timeout_secs",
+                "must be greater than 0",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Runs commands and copies files over SSH.
+#[derive(Debug, Clone)]
+pub struct SshTransport {
+    config: SshConfig,
+}
+
+impl SshTransport {
+    /// Create a transport; connections are opened lazily by the first
+    /// command.
+    pub fn new(config: SshConfig) -> SchedResult<Self> {
+        config
+            .validate()
+            .map_err(|e| SchedError::ConfigError(e.within("ssh").to_string()))?;
+        Ok(Self { config })
+    }
+
+    /// Destination argument, `user@host` or `host`.
+    fn destination(&self) -> String {
+        match &self.config.user {
+            Some(user) => format!("{}@{}", user, self.config.host),
+            None => self.config.host.clone(),
+        }
+    }
+
+    /// Build an `ssh` invocation running `program` with `args` remotely.
+    ///
+    /// The remote side runs the command through a shell, so every word is
+    /// quoted.
+    pub fn command(&self, program: &str, args: &[OsString]) -> Command {
+        self.command_with_env(program, args, &[])
+    }
+
+    /// Like [`command`](Self::command), forwarding environment variables
+    /// with `SendEnv`; the server must accept them with `AcceptEnv`.
+    fn command_with_env(&self, program: &str, args: &[OsString], env: &[(&str, &str)]) -> Command {
+        let mut command = Command::new(&self.config.ssh_binary);
+        command.args(self.ssh_options());
+        for (key, value) in env {
+            command
+                .env(key, value)
+                .arg("-o")
+                .arg(format!("SendEnv={}", key));
+        }
+        command.arg(self.destination());
+        command.arg("--");
+        command.arg(
+            std::iter::once(shell_quote(program))
+                .chain(args.iter().map(|a| shell_quote(&a.to_string_lossy())))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        command
+    }
+
+    /// Options shared by every invocation.
+    fn ssh_options(&self) -> Vec<String> {
+        let config = &self.config;
+        let mut options = vec![
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            format!("ConnectTimeout={}", config.connect_timeout_secs),
+        ];
+        if config.control_persist_secs > 0 {
+            options.extend([
+                "-o".to_string(),
+                "ControlMaster=auto".to_string(),
+                "-o".to_string(),
+                format!("ControlPath={}", config.control_dir.join("%C").display()),
+                "-o".to_string(),
+                format!("ControlPersist={}", config.control_persist_secs),
+            ]);
+        }
+        if let Some(identity) = &config.identity_file {
+            options.extend([
+                "-i".to_string(),
+                identity.display().to_string(),
+                "-o".to_string(),
+                "IdentitiesOnly=yes".to_string(),
+            ]);
+        }
+        if let Some(port) = config.port {
+            options.extend(["-p".to_string(), port.to_string()]);
+        }
+        options
+    }
+
+    /// Run a remote command.
+    ///
+    /// Commands safe to repeat (`squeue`, `sacct`, `scontrol`, `mkdir`) are
+    /// retried while the connection fails; any other command fails with
+    /// [`SchedError::SshConnectionFailed`] on the first dropped connection.
+    /// `stdin` is written to the command's standard input. Returns the
+    /// output of the last attempt; a nonzero remote exit status is not an
+    /// error here.
+    pub async fn run(
+        &self,
+        program: &str,
+        args: &[OsString],
+        stdin: Option<&[u8]>,
+        env: &[(&str, &str)],
+    ) -> SchedResult<Output> {
+        let retries = if IDEMPOTENT_COMMANDS.contains(&program) {
+            self.config.retries
+        } else {
+            0
+        };
+        self.run_with_retries(program, args, stdin, env, retries)
+            .await
+    }
+
+    /// Run a remote command, retrying up to `retries` times while the
+    /// connection fails.
+    async fn run_with_retries(
+        &self,
+        program: &str,
+        args: &[OsString],
+        stdin: Option<&[u8]>,
+        env: &[(&str, &str)],
+        retries: u32,
+    ) -> SchedResult<Output> {
+        if self.config.control_persist_secs > 0 {
+            tokio::fs::create_dir_all(&self.config.control_dir).await?;
+        }
+
+        let mut attempt = 0;
+        loop {
+            let command = self.command_with_env(program, args, env);
+            let output =
+                spawn(command, stdin)
+                    .await
+                    .map_err(|e| SchedError::SlurmCommandError {
+                        command: program.to_string(),
+                        message: e.to_string(),
+                    })?;
+            if !connection_failed(&output) {
+                return Ok(output);
+            }
+            if attempt >= retries {
+                return Err(self.connection_error(program, &output));
+            }
+            self.backoff(program, attempt).await;
+            attempt += 1;
+        }
+    }
+
+    /// Start a remote command with its standard output piped, for callers
+    /// that read the output as it arrives.
+    ///
+    /// Unlike [`run`](Self::run), this does not retry; callers check the
+    /// exit status for a dropped connection and retry up to
+    /// [`retries`](Self::retries) times themselves.
+    pub async fn spawn_piped(
+        &self,
+        program: &str,
+        args: &[OsString],
+        env: &[(&str, &str)],
+    ) -> SchedResult<Child> {
+        if self.config.control_persist_secs > 0 {
+            tokio::fs::create_dir_all(&self.config.control_dir).await?;
+        }
+        self.command_with_env(program, args, env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| SchedError::SlurmCommandError {
+                command: program.to_string(),
+                message: e.to_string(),
+            })
+    }
+
+    /// Retries of a command whose connection dropped.
+    pub fn retries(&self) -> u32 {
+        self.config.retries
+    }
+
+    /// Error for `program` failing because the connection did.
+    pub(crate) fn connection_error(&self, program: &str, output: &Output) -> SchedError {
+        SchedError::SshConnectionFailed {
+            host: self.config.host.clone(),
+            command: program.to_string(),
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+    }
+
+    /// Wait before retry `attempt` of `program`.
+    pub(crate) async fn backoff(&self, program: &str, attempt: u32) {
+        let delay = self.retry_delay(attempt);
+        tracing::warn!(
+            "SSH connection to {} dropped running {}, retrying in {:?}",
+            self.config.host,
+            program,
+            delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    /// Backoff before retry `attempt`, counting from 0.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        Duration::from_millis(
+            self.config
+                .retry_backoff_ms
+                .saturating_mul(1 << attempt.min(16)),
+        )
+    }
+
+    /// Create directories on the remote side.
+    pub async fn create_dirs(&self, dirs: &[&Path]) -> SchedResult<()> {
+        let mut args = vec![OsString::from("-p")];
+        args.extend(dirs.iter().map(|d| d.as_os_str().to_owned()));
+        let output = self.run("mkdir", &args, None, &[]).await?;
+        check("mkdir", &output)
+    }
+
+    /// Copy a local file to the same path on the remote side.
+    pub async fn upload(&self, path: &Path) -> SchedResult<()> {
+        let data = tokio::fs::read(path).await?;
+        let args = [OsString::from("-c"), upload_script(path).into()];
+        // Overwriting the file again is safe, so dropped connections are
+        // retried.
+        let output = self
+            .run_with_retries("sh", &args, Some(&data), &[], self.config.retries)
+            .await?;
+        check("upload", &output)
+    }
+
+    /// Copy a remote file to the same local path; returns false if the
+    /// remote file does not exist.
+    pub async fn download(&self, path: &Path) -> SchedResult<bool> {
+        let args = [
+            OsString::from("-c"),
+            format!(
+                "test ! -e {0} && exit 3; cat {0}",
+                shell_quote(&path.to_string_lossy())
+            )
+            .into(),
+        ];
+        let output = self
+            .run_with_retries("sh", &args, None, &[], self.config.retries)
+            .await?;
+        if output.status.code() == Some(3) {
+            return Ok(false);
+        }
+        check("download", &output)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, &output.stdout).await?;
+        Ok(true)
+    }
+}
+
+/// Whether `ssh` failed to connect rather than the remote command failing.
+pub(crate) fn connection_failed(output: &Output) -> bool {
+    output.status.code() == Some(SSH_CONNECTION_ERROR)
+}
+
+/// Remote script writing its standard input to `path`.
+fn upload_script(path: &Path) -> String {
+    let quoted = shell_quote(&path.to_string_lossy());
+    match path.parent() {
+        Some(parent) => format!(
+            "mkdir -p {} && cat > {}",
+            shell_quote(&parent.to_string_lossy()),
+            quoted
+        ),
+        None => format!("cat > {}", quoted),
+    }
+}
+
+/// Spawn a command, feeding it `stdin` if given, and collect its output.
+async fn spawn(mut command: Command, stdin: Option<&[u8]>) -> std::io::Result<Output> {
+    command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command.spawn()?;
+    if let (Some(data), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(data).await?;
+    }
+    child.wait_with_output().await
+}
+
+/// Fail with the remote stderr if a command did not succeed.
+fn check(command: &str, output: &Output) -> SchedResult<()> {
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(SchedError::SlurmCommandError {
+        command: command.to_string(),
+        message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    })
+}
+
+/// Quote a word for a POSIX shell.
+fn shell_quote(word: &str) -> String {
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c))
+    {
+        return word.to_string();
+    }
+    format!("'{}'", word.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("squeue"), "squeue");
+        assert_eq!(shell_quote("/tmp/a b.sh"), "'/tmp/a b.sh'");
+        assert_eq!(shell_quote("%i|%j"), "'%i|%j'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_ssh_command() {
+        let transport = SshTransport::new(SshConfig {
+            user: Some("alice".into()),
+            port: Some(2222),
+            identity_file: Some("/keys/cluster".into()),
+            control_dir: "/run/arvak-ssh".into(),
+            ..SshConfig::new("login1.example.org")
+        })
+        .unwrap();
+
+        let command = transport.command("squeue", &["-o".into(), "%i|%T".into()]);
+        let command = command.as_std();
+        assert_eq!(command.get_program(), "ssh");
+        let args: Vec<_> = command
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert!(args.contains(&"BatchMode=yes".to_string()));
+        assert!(args.contains(&"ControlPath=/run/arvak-ssh/%C".to_string()));
+        assert!(args.contains(&"ControlPersist=600".to_string()));
+        assert!(args.windows(2).any(|w| w == ["-i", "/keys/cluster"]));
+        assert!(args.windows(2).any(|w| w == ["-p", "2222"]));
+        assert_eq!(
+            &args[args.len() - 3..],
+            ["alice@login1.example.org", "--", "squeue -o '%i|%T'"]
+        );
+    }
+
+    #[test]
+    fn test_no_pooling_and_retry_delay() {
+        let transport = SshTransport::new(SshConfig {
+            control_persist_secs: 0,
+            retry_backoff_ms: 100,
+            ..SshConfig::new("login1")
+        })
+        .unwrap();
+        let command = transport.command("sbatch", &[]);
+        assert!(
+            !command
+                .as_std()
+                .get_args()
+                .any(|a| a.to_string_lossy().starts_with("ControlMaster"))
+        );
+        assert_eq!(transport.retry_delay(0), Duration::from_millis(100));
+        assert_eq!(transport.retry_delay(2), Duration::from_millis(400));
+
+        assert!(SshTransport::new(SshConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_upload_script() {
+        assert_eq!(
+            upload_script(Path::new("/work/scripts/a b.sh")),
+            "mkdir -p /work/scripts && cat > '/work/scripts/a b.sh'"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retries_dropped_connection() {
+        // A stand-in ssh binary that always reports a connection failure.
+        let dir = tempfile::tempdir().unwrap();
+        let fake_ssh = dir.path().join("ssh");
+        let log = dir.path().join("attempts");
+        std::fs::write(
+            &fake_ssh,
+            format!(
+                "#!/bin/sh\necho attempt >> {}\necho dropped >&2\nexit 255\n",
+                log.display()
+            ),
+        )
+        .unwrap();
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&fake_ssh, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let transport = SshTransport::new(SshConfig {
+            ssh_binary: fake_ssh,
+            control_dir: dir.path().join("control"),
+            retries: 2,
+            retry_backoff_ms: 1,
+            ..SshConfig::new("login1")
+        })
+        .unwrap();
+        let attempts = || std::fs::read_to_string(&log).unwrap().lines().count();
+
+        let err = transport.run("squeue", &[], None, &[]).await.unwrap_err();
+        assert!(err.to_string().contains("dropped"));
+        assert_eq!(attempts(), 3);
+
+        // sbatch may have run before the connection dropped.
+        let err = transport.run("sbatch", &[], None, &[]).await.unwrap_err();
+        assert!(matches!(err, SchedError::SshConnectionFailed { .. }));
+        assert_eq!(attempts(), 4);
+    }
+}
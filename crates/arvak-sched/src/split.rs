@@ -0,0 +1,254 @@
+//! Circuits split over several executions for limited classical memory.
+//!
+//! When a circuit measures more clbits than the backend can hold, the
+//! compile stage replaces it in the job by one fragment per measurement
+//! group (see [`MeasurementSplitPlan`]) and records the split on the job.
+//! [`merge_results`] turns the fragment results back into one result per
+//! submitted circuit.
+//!
+//! Fragments run in separate executions, so their shots are not
+//! correlated. The merged counts pair the fragments' shots at random: every
+//! clbit group keeps its exact distribution, while outcomes across groups
+//! are a sample of the product of the group distributions.
+
+use arvak_compile::passes::MeasurementSplitPlan;
+use arvak_hal::{Counts, ExecutionResult};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SchedResult;
+use crate::job::ScheduledJob;
+use crate::payload::{CircuitProvenance, CircuitResult};
+
+/// Key of the split record in a merged result's metadata.
+pub const MEASUREMENT_SPLIT_KEY: &str = "measurement_split";
+
+/// A submitted circuit that runs as several fragments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SplitCircuit {
+    /// Index of the circuit as submitted.
+    pub index: usize,
+
+    /// Result label of the circuit as submitted.
+    pub label: String,
+
+    /// Index of the first fragment in the job's circuits; the others follow.
+    pub first: usize,
+
+    /// How the circuit's clbits are divided among the fragments.
+    pub plan: MeasurementSplitPlan,
+}
+
+impl SplitCircuit {
+    /// Job circuit indices of the fragments.
+    pub fn fragments(&self) -> std::ops::Range<usize> {
+        self.first..self.first + self.plan.len()
+    }
+
+    /// Result label of the fragment at `position` in the plan.
+    pub fn fragment_label(label: &str, position: usize) -> String {
+        format!("{}#m{}", label, position)
+    }
+}
+
+/// Merge the results of split circuits, returning one result per submitted
+/// circuit with its submitted index and label.
+///
+/// A split circuit with a missing fragment result is left out, like a
+/// circuit that wrote no result.
+pub fn merge_results(
+    job: &ScheduledJob,
+    results: Vec<CircuitResult>,
+) -> SchedResult<Vec<CircuitResult>> {
+    if job.measurement_splits.is_empty() {
+        return Ok(results);
+    }
+
+    let mut fragments: FxHashMap<usize, ExecutionResult> = FxHashMap::default();
+    let mut merged = Vec::with_capacity(results.len());
+    for mut result in results {
+        let split = job
+            .measurement_splits
+            .iter()
+            .any(|s| s.fragments().contains(&result.index));
+        if split {
+            fragments.insert(result.index, result.result);
+            continue;
+        }
+
+        result.index = submitted_index(job, result.index);
+        if let Some(mut provenance) = CircuitProvenance::from_result(&result.result) {
+            provenance.circuit_index = result.index;
+            provenance.attach(&mut result.result)?;
+        }
+        merged.push(result);
+    }
+
+    for split in &job.measurement_splits {
+        let parts: Option<Vec<ExecutionResult>> =
+            split.fragments().map(|i| fragments.remove(&i)).collect();
+        if let Some(parts) = parts {
+            merged.push(CircuitResult {
+                index: split.index,
+                label: split.label.clone(),
+                result: merge_split(job, split, parts)?,
+            });
+        }
+    }
+
+    merged.sort_by_key(|r| r.index);
+    Ok(merged)
+}
+
+/// Index as submitted of a job circuit that was not split.
+fn submitted_index(job: &ScheduledJob, index: usize) -> usize {
+    let extra: usize = job
+        .measurement_splits
+        .iter()
+        .filter(|s| s.fragments().end <= index)
+        .map(|s| s.plan.len() - 1)
+        .sum();
+    index - extra
+}
+
+fn merge_split(
+    job: &ScheduledJob,
+    split: &SplitCircuit,
+    parts: Vec<ExecutionResult>,
+) -> SchedResult<ExecutionResult> {
+    let shots: Vec<Vec<&str>> = parts
+        .iter()
+        .enumerate()
+        .map(|(position, part)| shuffled_shots(&part.counts, position as u64))
+        .collect();
+    let num_shots = shots.iter().map(Vec::len).min().unwrap_or(0);
+
+    let mut counts = Counts::new();
+    for shot in 0..num_shots {
+        let outcomes: Vec<&str> = shots.iter().map(|s| s[shot]).collect();
+        counts.insert(split.plan.merge_outcomes(&outcomes), 1);
+    }
+
+    let execution_time_ms = parts
+        .iter()
+        .map(|p| p.execution_time_ms)
+        .sum::<Option<u64>>();
+    let mut result = ExecutionResult::new(counts, num_shots as u32);
+    result.execution_time_ms = execution_time_ms;
+    result.metadata = serde_json::json!({
+        MEASUREMENT_SPLIT_KEY: {
+            "fragments": split.plan.fragments,
+            "shots": parts.iter().map(|p| p.counts.total_shots()).collect::<Vec<_>>(),
+        }
+    });
+
+    let mut provenance = CircuitProvenance::new(job, split.first);
+    provenance.circuit_index = split.index;
+    provenance.label = split.label.clone();
+    provenance.circuit_hash = None;
+    provenance.attach(&mut result)?;
+    Ok(result)
+}
+
+/// The outcomes of every shot in `counts`, in a random order fixed by `seed`.
+fn shuffled_shots(counts: &Counts, seed: u64) -> Vec<&str> {
+    let mut shots: Vec<&str> = counts
+        .sorted()
+        .into_iter()
+        .flat_map(|(outcome, &n)| std::iter::repeat_n(outcome.as_str(), n as usize))
+        .collect();
+
+    // Fisher-Yates with splitmix64
+    let mut state = seed;
+    for i in (1..shots.len()).rev() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        shots.swap(i, (z % (i as u64 + 1)) as usize);
+    }
+    shots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+
+    fn result(pairs: &[(&str, u64)]) -> ExecutionResult {
+        let counts = Counts::from_pairs(pairs.iter().map(|&(s, n)| (s, n)));
+        let shots = counts.total_shots() as u32;
+        ExecutionResult::new(counts, shots)
+    }
+
+    #[test]
+    fn test_merge_results() {
+        let spec = || CircuitSpec::from_qasm("OPENQASM 3.0;");
+        // Submitted as [a, b, c] with b split into three fragments.
+        let mut job = ScheduledJob::batch("split", vec![spec(); 5]);
+        job.measurement_splits.push(SplitCircuit {
+            index: 1,
+            label: "b".into(),
+            first: 1,
+            plan: MeasurementSplitPlan {
+                num_clbits: 5,
+                fragments: vec![vec![0, 1], vec![2, 3], vec![4]],
+            },
+        });
+
+        let results = vec![
+            (0, result(&[("0", 100)])),
+            (1, result(&[("00", 50), ("11", 50)])),
+            (2, result(&[("10", 100)])),
+            (3, result(&[("1", 100)])),
+            (4, result(&[("1", 100)])),
+        ];
+        let results = results
+            .into_iter()
+            .map(|(index, result)| CircuitResult {
+                index,
+                label: job.circuit_label(index),
+                result,
+            })
+            .collect();
+
+        let merged = merge_results(&job, results).unwrap();
+        let indices: Vec<_> = merged.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+
+        let b = &merged[1];
+        assert_eq!(b.label, "b");
+        assert_eq!(b.result.shots, 100);
+        // The first group keeps its 50/50 split; the others are fixed.
+        assert_eq!(b.result.counts.get("11000"), 50);
+        assert_eq!(b.result.counts.get("11011"), 50);
+
+        let provenance = CircuitProvenance::from_result(&b.result).unwrap();
+        assert_eq!(
+            (provenance.circuit_index, provenance.label.as_str()),
+            (1, "b")
+        );
+        assert!(b.result.metadata.get(MEASUREMENT_SPLIT_KEY).is_some());
+    }
+
+    #[test]
+    fn test_missing_fragment_drops_circuit() {
+        let mut job = ScheduledJob::batch("split", vec![CircuitSpec::from_qasm(""); 2]);
+        job.measurement_splits.push(SplitCircuit {
+            index: 0,
+            label: "a".into(),
+            first: 0,
+            plan: MeasurementSplitPlan {
+                num_clbits: 2,
+                fragments: vec![vec![0], vec![1]],
+            },
+        });
+        let results = vec![CircuitResult {
+            index: 0,
+            label: "a#m0".into(),
+            result: result(&[("1", 10)]),
+        }];
+        assert!(merge_results(&job, results).unwrap().is_empty());
+    }
+}
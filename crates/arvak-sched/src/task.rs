@@ -0,0 +1,271 @@
+//! Classical task nodes for hybrid workflows.
+//!
+//! A classical task runs either a named async closure registered with the
+//! scheduler, a user script submitted as a batch job, a built-in check
+//! such as a [`ResultVerification`], the convergence check of a
+//! [`LoopNode`], or a whole [`SubWorkflow`]. Its output is stored
+//! as an [`ExecutionResult`] (empty counts, output in `metadata`), so
+//! downstream nodes consume it exactly like a circuit result.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+use arvak_hal::{Counts, ExecutionResult};
+use serde::{Deserialize, Serialize};
+
+use crate::error::SchedResult;
+use crate::job::ScheduledJobId;
+use crate::verify::ResultVerification;
+use crate::workflow::{LoopNode, SubWorkflow};
+
+/// Placeholder batch/quantum job ID for tasks executed in-process.
+pub const LOCAL_TASK_ID: &str = "local";
+
+/// Placeholder batch job ID for sub-workflow nodes, whose work runs as the
+/// jobs of their sub-workflow.
+pub const SUB_WORKFLOW_JOB_ID: &str = "workflow";
+
+/// A classical (non-quantum) workflow task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ClassicalTask {
+    /// Run a closure registered with the scheduler under this name.
+    ///
+    /// Closures are referenced by name so that persisted jobs survive a
+    /// restart; re-register them on startup.
+    Closure { name: String },
+
+    /// Run a user script as a batch job.
+    ///
+    /// The script receives `ARVAK_TASK_INPUTS` (path to a JSON file with the
+    /// upstream results) and `ARVAK_TASK_OUTPUT` (path where it may write a
+    /// JSON output value).
+    Script { path: PathBuf, args: Vec<String> },
+
+    /// Compare the results of two upstream jobs, failing if they diverge.
+    Verify(ResultVerification),
+
+    /// Decide whether to run part of a workflow again, see [`LoopNode`].
+    Loop(LoopNode),
+
+    /// Run a workflow of its own, see [`SubWorkflow`].
+    Workflow(SubWorkflow),
+}
+
+impl ClassicalTask {
+    /// Create a closure task.
+    pub fn closure(name: impl Into<String>) -> Self {
+        ClassicalTask::Closure { name: name.into() }
+    }
+
+    /// Create a script task.
+    pub fn script(path: impl Into<PathBuf>) -> Self {
+        ClassicalTask::Script {
+            path: path.into(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Create a result verification task.
+    pub fn verify(verification: ResultVerification) -> Self {
+        ClassicalTask::Verify(verification)
+    }
+
+    /// Add an argument (script tasks only).
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        if let ClassicalTask::Script { ref mut args, .. } = self {
+            args.push(arg.into());
+        }
+        self
+    }
+
+    /// Check whether this task runs in-process.
+    pub fn is_local(&self) -> bool {
+        matches!(
+            self,
+            ClassicalTask::Closure { .. }
+                | ClassicalTask::Verify(_)
+                | ClassicalTask::Loop(_)
+                | ClassicalTask::Workflow(_)
+        )
+    }
+}
+
+/// Result of an upstream job passed to a classical task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskInput {
+    /// Upstream job ID.
+    pub job_id: ScheduledJobId,
+
+    /// Upstream job name.
+    pub name: String,
+
+    /// Upstream result.
+    pub result: ExecutionResult,
+}
+
+/// Results of the upstream jobs of a classical task.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaskInputs {
+    inputs: Vec<TaskInput>,
+}
+
+impl TaskInputs {
+    /// Create an empty input set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an upstream result.
+    pub fn push(
+        &mut self,
+        job_id: ScheduledJobId,
+        name: impl Into<String>,
+        result: ExecutionResult,
+    ) {
+        self.inputs.push(TaskInput {
+            job_id,
+            name: name.into(),
+            result,
+        });
+    }
+
+    /// Get an upstream result by job ID.
+    pub fn get(&self, job_id: &ScheduledJobId) -> Option<&ExecutionResult> {
+        self.inputs
+            .iter()
+            .find(|i| &i.job_id == job_id)
+            .map(|i| &i.result)
+    }
+
+    /// Get an upstream result by job name.
+    pub fn by_name(&self, name: &str) -> Option<&ExecutionResult> {
+        self.inputs
+            .iter()
+            .find(|i| i.name == name)
+            .map(|i| &i.result)
+    }
+
+    /// Get the output of an upstream classical task by job name.
+    pub fn output(&self, name: &str) -> Option<&serde_json::Value> {
+        self.by_name(name).map(|r| &r.metadata)
+    }
+
+    /// Iterate over upstream results.
+    pub fn iter(&self) -> impl Iterator<Item = &TaskInput> {
+        self.inputs.iter()
+    }
+
+    /// Get the number of upstream results.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Check if there are no upstream results.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+/// Future returned by a classical task closure.
+pub type TaskFuture = Pin<Box<dyn Future<Output = SchedResult<serde_json::Value>> + Send>>;
+
+/// A registered classical task closure.
+pub type TaskFn = Arc<dyn Fn(TaskInputs) -> TaskFuture + Send + Sync>;
+
+/// Registry of named classical task closures.
+#[derive(Default)]
+pub struct TaskRegistry {
+    tasks: RwLock<rustc_hash::FxHashMap<String, TaskFn>>,
+}
+
+impl TaskRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a closure under a name, replacing any existing one.
+    pub fn register<F, Fut>(&self, name: impl Into<String>, f: F)
+    where
+        F: Fn(TaskInputs) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = SchedResult<serde_json::Value>> + Send + 'static,
+    {
+        let task: TaskFn = Arc::new(move |inputs| Box::pin(f(inputs)));
+        self.tasks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.into(), task);
+    }
+
+    /// Look up a closure by name.
+    pub fn get(&self, name: &str) -> Option<TaskFn> {
+        self.tasks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned()
+    }
+
+    /// Check whether a closure is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.tasks
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(name)
+    }
+}
+
+/// Wrap a task output as an execution result.
+pub fn task_result(output: serde_json::Value) -> ExecutionResult {
+    ExecutionResult::new(Counts::new(), 0).with_metadata(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_registry() {
+        let registry = TaskRegistry::new();
+        registry.register("double", |inputs: TaskInputs| async move {
+            let x = inputs
+                .output("source")
+                .and_then(|v| v.as_i64())
+                .unwrap_or_default();
+            Ok(serde_json::json!(x * 2))
+        });
+
+        assert!(registry.contains("double"));
+        assert!(registry.get("missing").is_none());
+
+        let mut inputs = TaskInputs::new();
+        inputs.push(
+            ScheduledJobId::new(),
+            "source",
+            task_result(serde_json::json!(21)),
+        );
+
+        let f = registry.get("double").unwrap();
+        assert_eq!(f(inputs).await.unwrap(), serde_json::json!(42));
+    }
+
+    #[test]
+    fn test_task_spec() {
+        let task = ClassicalTask::script("/opt/post.py").arg("--fast");
+        assert_eq!(
+            task,
+            ClassicalTask::Script {
+                path: PathBuf::from("/opt/post.py"),
+                args: vec!["--fast".to_string()],
+            }
+        );
+        assert!(!task.is_local());
+        assert!(ClassicalTask::closure("f").is_local());
+
+        let json = serde_json::to_string(&task).unwrap();
+        let back: ClassicalTask = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, task);
+    }
+}
@@ -0,0 +1,301 @@
+//! Named job templates.
+//!
+//! A [`JobTemplate`] captures what near-identical submissions share: the
+//! circuit, shots, priority, resource requirements and labels. Templates
+//! are stored by name in the [`StateStore`](crate::StateStore), and
+//! [`ScheduledJob::from_template`] turns one into a job, applying
+//! [`TemplateOverrides`] for whatever differs in this submission.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::{CircuitSpec, Priority, ResourceRequirements, ScheduledJob};
+
+/// Metadata key recording the template a job was created from.
+pub const TEMPLATE_METADATA_KEY: &str = "template";
+
+/// A named, reusable job definition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobTemplate {
+    /// Unique template name.
+    pub name: String,
+
+    /// What the template is for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Circuit, as QASM source or a file reference.
+    pub circuit: CircuitSpec,
+
+    /// Number of shots.
+    pub shots: u32,
+
+    /// Job priority.
+    pub priority: Priority,
+
+    /// Resource requirements.
+    pub requirements: ResourceRequirements,
+
+    /// Labels copied into the metadata of each job.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+
+    /// Walltime in seconds, if bounded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub walltime: Option<u64>,
+
+    /// When the template was created.
+    pub created_at: DateTime<Utc>,
+
+    /// When the template was last saved.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobTemplate {
+    /// Create a template running `circuit` with the defaults of
+    /// [`ScheduledJob::new`].
+    pub fn new(name: impl Into<String>, circuit: CircuitSpec) -> Self {
+        let defaults = ScheduledJob::new("", circuit.clone());
+        let now = Utc::now();
+        Self {
+            name: name.into(),
+            description: None,
+            circuit,
+            shots: defaults.shots,
+            priority: defaults.priority,
+            requirements: defaults.requirements,
+            labels: BTreeMap::new(),
+            walltime: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Create a template from an existing single-circuit job.
+    pub fn from_job(name: impl Into<String>, job: &ScheduledJob) -> SchedResult<Self> {
+        let [circuit] = job.circuits.as_slice() else {
+            return Err(SchedError::InvalidPayload(format!(
+                "job {} must run exactly one circuit to become a template",
+                job.id
+            )));
+        };
+        let mut template = Self::new(name, circuit.clone())
+            .with_shots(job.shots)
+            .with_priority(job.priority)
+            .with_requirements(job.requirements.clone());
+        template.labels = job
+            .metadata
+            .iter()
+            .filter(|(key, _)| key.as_str() != TEMPLATE_METADATA_KEY)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        template.walltime = job.walltime;
+        Ok(template)
+    }
+
+    /// Set the description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the number of shots.
+    pub fn with_shots(mut self, shots: u32) -> Self {
+        self.shots = shots;
+        self
+    }
+
+    /// Set the priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set the resource requirements.
+    pub fn with_requirements(mut self, requirements: ResourceRequirements) -> Self {
+        self.requirements = requirements;
+        self
+    }
+
+    /// Add a label.
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the walltime.
+    pub fn with_walltime(mut self, walltime: std::time::Duration) -> Self {
+        self.walltime = Some(walltime.as_secs().max(1));
+        self
+    }
+
+    /// Check that the template name is usable as a key and file name.
+    pub fn validate(&self) -> SchedResult<()> {
+        validate_name(&self.name)
+    }
+}
+
+/// Check that a template name is non-empty and made of ASCII letters,
+/// digits, `-`, `_` and `.`, without leading dots.
+pub fn validate_name(name: &str) -> SchedResult<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SchedError::InvalidPayload(format!(
+            "invalid template name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        )))
+    }
+}
+
+/// Differences of one job from its template.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateOverrides {
+    /// Job name; defaults to the template name.
+    pub name: Option<String>,
+
+    /// Circuit replacing the template's.
+    pub circuit: Option<CircuitSpec>,
+
+    /// Number of shots.
+    pub shots: Option<u32>,
+
+    /// Priority.
+    pub priority: Option<Priority>,
+
+    /// Resource requirements.
+    pub requirements: Option<ResourceRequirements>,
+
+    /// Labels added to, or replacing, the template's.
+    pub labels: BTreeMap<String, String>,
+
+    /// Walltime in seconds.
+    pub walltime: Option<u64>,
+}
+
+impl TemplateOverrides {
+    /// Create empty overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the job name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Replace the circuit.
+    pub fn with_circuit(mut self, circuit: CircuitSpec) -> Self {
+        self.circuit = Some(circuit);
+        self
+    }
+
+    /// Set the number of shots.
+    pub fn with_shots(mut self, shots: u32) -> Self {
+        self.shots = Some(shots);
+        self
+    }
+
+    /// Set the priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Set the resource requirements.
+    pub fn with_requirements(mut self, requirements: ResourceRequirements) -> Self {
+        self.requirements = Some(requirements);
+        self
+    }
+
+    /// Add or replace a label.
+    pub fn with_label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set the walltime.
+    pub fn with_walltime(mut self, walltime: std::time::Duration) -> Self {
+        self.walltime = Some(walltime.as_secs().max(1));
+        self
+    }
+}
+
+impl ScheduledJob {
+    /// Create a job from a template, applying `overrides`.
+    ///
+    /// The template's labels become job metadata, and the template name is
+    /// recorded under [`TEMPLATE_METADATA_KEY`].
+    pub fn from_template(template: &JobTemplate, overrides: TemplateOverrides) -> Self {
+        let name = overrides.name.unwrap_or_else(|| template.name.clone());
+        let circuit = overrides
+            .circuit
+            .unwrap_or_else(|| template.circuit.clone());
+        let mut job = ScheduledJob::new(name, circuit)
+            .with_shots(overrides.shots.unwrap_or(template.shots))
+            .with_priority(overrides.priority.unwrap_or(template.priority))
+            .with_requirements(
+                overrides
+                    .requirements
+                    .unwrap_or_else(|| template.requirements.clone()),
+            );
+        job.walltime = overrides.walltime.or(template.walltime);
+        for (key, value) in template.labels.iter().chain(&overrides.labels) {
+            job.metadata.insert(key.clone(), value.clone());
+        }
+        job.metadata
+            .insert(TEMPLATE_METADATA_KEY.to_string(), template.name.clone());
+        job
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BELL: &str = "OPENQASM 3.0; qubit[2] q; bit[2] c; h q[0]; cx q[0], q[1]; c = measure q;";
+
+    #[test]
+    fn test_from_template_applies_overrides() {
+        let template = JobTemplate::new("bell", CircuitSpec::from_qasm(BELL))
+            .with_shots(2_000)
+            .with_priority(Priority::high())
+            .with_label("project", "calibration")
+            .with_label("site", "lumi");
+
+        let job = ScheduledJob::from_template(
+            &template,
+            TemplateOverrides::new()
+                .with_shots(500)
+                .with_label("site", "local"),
+        );
+        assert_eq!(job.name, "bell");
+        assert_eq!(job.shots, 500);
+        assert_eq!(job.priority, Priority::HIGH);
+        assert_eq!(job.metadata["project"], "calibration");
+        assert_eq!(job.metadata["site"], "local");
+        assert_eq!(job.metadata[TEMPLATE_METADATA_KEY], "bell");
+
+        let again = JobTemplate::from_job("bell2", &job).unwrap();
+        assert_eq!(again.shots, 500);
+        assert!(!again.labels.contains_key(TEMPLATE_METADATA_KEY));
+    }
+
+    #[test]
+    fn test_template_names() {
+        assert!(validate_name("vqe-h2_v1.0").is_ok());
+        for name in ["", ".hidden", "a/b", "a b"] {
+            assert!(validate_name(name).is_err(), "{}", name);
+        }
+    }
+}
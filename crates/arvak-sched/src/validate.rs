@@ -0,0 +1,236 @@
+//! Sanity checks on job results.
+//!
+//! Hardware glitches occasionally return results the batch system reports
+//! as successful: all-zero counts, the wrong number of shots or bitstrings
+//! of the wrong width. A [`ResultValidation`] checks each circuit result of
+//! a finished job against its [`ValidationRule`]s. On a violation, its
+//! [`ValidationAction`] either flags the job, which still completes, or
+//! fails it with [`FailureKind::InvalidResult`], which a job's
+//! [`RetryPolicy`](crate::retry::RetryPolicy) retries by default.
+//!
+//! ```toml
+//! [scheduler.result_validation]
+//! action = "fail"
+//! rules = [
+//!     { type = "total_shots" },
+//!     { type = "min_entropy", bits = 0.5 },
+//!     { type = "register_width", width = 5 },
+//! ]
+//! ```
+//!
+//! [`FailureKind::InvalidResult`]: crate::retry::FailureKind::InvalidResult
+
+use arvak_hal::Counts;
+use serde::{Deserialize, Serialize};
+
+use crate::job::ScheduledJob;
+use crate::payload::CircuitResult;
+
+/// A check a circuit result must pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ValidationRule {
+    /// The counts add up to the shots requested for the circuit.
+    TotalShots,
+
+    /// The Shannon entropy of the count distribution is at least `bits`.
+    MinEntropy { bits: f64 },
+
+    /// Every bitstring has `width` bits, not counting register separators.
+    RegisterWidth { width: usize },
+}
+
+impl ValidationRule {
+    /// Check the counts of a circuit run with `shots` shots, describing the
+    /// violation if they fail.
+    pub fn check(&self, counts: &Counts, shots: u32) -> Option<String> {
+        match self {
+            ValidationRule::TotalShots => {
+                let total = counts.total_shots();
+                (total != u64::from(shots))
+                    .then(|| format!("counts total {} shots, expected {}", total, shots))
+            }
+            ValidationRule::MinEntropy { bits } => {
+                let entropy = entropy(counts);
+                (entropy < *bits).then(|| format!("entropy {:.3} bits is below {}", entropy, bits))
+            }
+            ValidationRule::RegisterWidth { width } => counts
+                .iter()
+                .map(|(bitstring, _)| (bitstring, bit_width(bitstring)))
+                .find(|(_, bits)| bits != width)
+                .map(|(bitstring, bits)| {
+                    format!(
+                        "bitstring '{}' has {} bits, expected {}",
+                        bitstring, bits, width
+                    )
+                }),
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationRule::TotalShots => write!(f, "total_shots"),
+            ValidationRule::MinEntropy { bits } => write!(f, "min_entropy({})", bits),
+            ValidationRule::RegisterWidth { width } => write!(f, "register_width({})", width),
+        }
+    }
+}
+
+/// What happens to a job whose results violate a rule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationAction {
+    /// The job completes, with the violations noted on its status change.
+    Flag,
+
+    /// The job fails, and is re-executed if its retry policy allows.
+    #[default]
+    Fail,
+}
+
+/// A rule violated by one circuit result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// Index of the circuit within the job.
+    pub index: usize,
+
+    /// Result label of the circuit.
+    pub label: String,
+
+    /// The violated rule.
+    pub rule: ValidationRule,
+
+    /// What was wrong.
+    pub detail: String,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} ({})", self.label, self.detail, self.rule)
+    }
+}
+
+/// Rules checked on the results of finished quantum jobs.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResultValidation {
+    /// Rules every circuit result must pass.
+    pub rules: Vec<ValidationRule>,
+
+    /// What happens on a violation.
+    pub action: ValidationAction,
+}
+
+impl ResultValidation {
+    /// Create a validation without rules that fails violating jobs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule.
+    pub fn with_rule(mut self, rule: ValidationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set what happens on a violation.
+    pub fn with_action(mut self, action: ValidationAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Check the circuit results of a job, returning every violation.
+    pub fn check(&self, job: &ScheduledJob, results: &[CircuitResult]) -> Vec<Violation> {
+        results
+            .iter()
+            .flat_map(|circuit| {
+                let shots = job.circuit_shots(circuit.index);
+                self.rules.iter().filter_map(move |rule| {
+                    rule.check(&circuit.result.counts, shots)
+                        .map(|detail| Violation {
+                            index: circuit.index,
+                            label: circuit.label.clone(),
+                            rule: rule.clone(),
+                            detail,
+                        })
+                })
+            })
+            .collect()
+    }
+}
+
+/// Shannon entropy of a count distribution, in bits.
+pub fn entropy(counts: &Counts) -> f64 {
+    counts
+        .probabilities()
+        .values()
+        .filter(|p| **p > 0.0)
+        .map(|p| -p * p.log2())
+        .sum()
+}
+
+/// Number of bits in a bitstring, skipping separators between registers.
+fn bit_width(bitstring: &str) -> usize {
+    bitstring.chars().filter(|c| !c.is_whitespace()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::job::CircuitSpec;
+    use arvak_hal::ExecutionResult;
+
+    fn result(index: usize, pairs: &[(&str, u64)]) -> CircuitResult {
+        let counts = Counts::from_pairs(pairs.iter().copied());
+        CircuitResult {
+            index,
+            label: format!("c{}", index),
+            result: ExecutionResult::new(counts, 100),
+        }
+    }
+
+    #[test]
+    fn test_rules() {
+        let bell = Counts::from_pairs([("00", 50), ("11", 50)]);
+        let zeros = Counts::from_pairs([("00", 100)]);
+
+        assert_eq!(ValidationRule::TotalShots.check(&bell, 100), None);
+        assert!(
+            ValidationRule::TotalShots
+                .check(&Counts::new(), 100)
+                .is_some()
+        );
+
+        let min_entropy = ValidationRule::MinEntropy { bits: 0.5 };
+        assert!((entropy(&bell) - 1.0).abs() < 1e-9);
+        assert_eq!(min_entropy.check(&bell, 100), None);
+        assert!(min_entropy.check(&zeros, 100).is_some());
+
+        let width = ValidationRule::RegisterWidth { width: 3 };
+        assert!(width.check(&bell, 100).is_some());
+        let registers = Counts::from_pairs([("0 01", 100)]);
+        assert_eq!(width.check(&registers, 100), None);
+    }
+
+    #[test]
+    fn test_validate_job_results() {
+        let job = ScheduledJob::new("job", CircuitSpec::from_qasm("OPENQASM 3.0;")).with_shots(100);
+        let validation = ResultValidation::new()
+            .with_rule(ValidationRule::TotalShots)
+            .with_rule(ValidationRule::MinEntropy { bits: 0.5 });
+
+        let good = [result(0, &[("00", 50), ("11", 50)])];
+        assert!(validation.check(&job, &good).is_empty());
+
+        let glitched = [result(0, &[("00", 0)])];
+        let violations = validation.check(&job, &glitched);
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].rule, ValidationRule::TotalShots);
+        assert_eq!(
+            violations[0].to_string(),
+            "c0: counts total 0 shots, expected 100 (total_shots)"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Cross-backend result verification.
+//!
+//! A verification node compares the results of two upstream jobs, typically
+//! the same circuit run on a simulator and on hardware, and fails if they
+//! diverge beyond the configured thresholds. Since a failed node fails its
+//! workflow, this turns a validation study into a pass/fail pipeline.
+
+use arvak_hal::{Counts, ExecutionResult};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{SchedError, SchedResult};
+use crate::job::ScheduledJobId;
+use crate::task::TaskInputs;
+
+/// A measure of divergence between two results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResultMetric {
+    /// Total variation distance between the count distributions.
+    Tvd,
+
+    /// Hellinger distance between the count distributions.
+    Hellinger,
+
+    /// Absolute difference of the Z-parity expectation value over the given
+    /// qubits (qubit 0 is the rightmost bit).
+    ExpectationDelta { qubits: Vec<usize> },
+}
+
+impl ResultMetric {
+    /// Compute the metric between a reference and a candidate result.
+    pub fn compute(&self, reference: &Counts, candidate: &Counts) -> f64 {
+        match self {
+            ResultMetric::Tvd => total_variation_distance(reference, candidate),
+            ResultMetric::Hellinger => hellinger_distance(reference, candidate),
+            ResultMetric::ExpectationDelta { qubits } => {
+                (z_expectation(reference, qubits) - z_expectation(candidate, qubits)).abs()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ResultMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResultMetric::Tvd => write!(f, "tvd"),
+            ResultMetric::Hellinger => write!(f, "hellinger"),
+            ResultMetric::ExpectationDelta { qubits } => {
+                write!(f, "expectation_delta{:?}", qubits)
+            }
+        }
+    }
+}
+
+/// A metric with the maximum value it may take.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationCheck {
+    /// Metric to compute.
+    pub metric: ResultMetric,
+
+    /// Maximum allowed value (inclusive).
+    pub threshold: f64,
+}
+
+/// Specification of a verification node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultVerification {
+    /// Job whose result is the reference (e.g. a simulator run).
+    pub reference: ScheduledJobId,
+
+    /// Job whose result is checked against the reference.
+    pub candidate: ScheduledJobId,
+
+    /// Checks that must all pass.
+    pub checks: Vec<VerificationCheck>,
+}
+
+impl ResultVerification {
+    /// Compare the results of two jobs.
+    pub fn new(reference: ScheduledJobId, candidate: ScheduledJobId) -> Self {
+        Self {
+            reference,
+            candidate,
+            checks: Vec::new(),
+        }
+    }
+
+    /// Require the total variation distance to be at most `threshold`.
+    pub fn with_tvd(self, threshold: f64) -> Self {
+        self.with_check(ResultMetric::Tvd, threshold)
+    }
+
+    /// Require the Hellinger distance to be at most `threshold`.
+    pub fn with_hellinger(self, threshold: f64) -> Self {
+        self.with_check(ResultMetric::Hellinger, threshold)
+    }
+
+    /// Require the Z-parity expectation over `qubits` to differ by at most
+    /// `threshold`.
+    pub fn with_expectation_delta(self, qubits: Vec<usize>, threshold: f64) -> Self {
+        self.with_check(ResultMetric::ExpectationDelta { qubits }, threshold)
+    }
+
+    /// Add a check.
+    pub fn with_check(mut self, metric: ResultMetric, threshold: f64) -> Self {
+        self.checks.push(VerificationCheck { metric, threshold });
+        self
+    }
+
+    /// Run the checks against the upstream results.
+    ///
+    /// Returns an error if either result is missing or has no counts. A
+    /// report is returned even if checks fail; see [`VerificationReport::passed`].
+    pub fn verify(&self, inputs: &TaskInputs) -> SchedResult<VerificationReport> {
+        let reference = counts_of(inputs.get(&self.reference), &self.reference)?;
+        let candidate = counts_of(inputs.get(&self.candidate), &self.candidate)?;
+
+        let checks: Vec<_> = self
+            .checks
+            .iter()
+            .map(|check| {
+                let value = check.metric.compute(reference, candidate);
+                CheckOutcome {
+                    metric: check.metric.clone(),
+                    value,
+                    threshold: check.threshold,
+                    passed: value <= check.threshold,
+                }
+            })
+            .collect();
+
+        Ok(VerificationReport {
+            reference: self.reference.clone(),
+            candidate: self.candidate.clone(),
+            passed: checks.iter().all(|c| c.passed),
+            checks,
+        })
+    }
+}
+
+fn counts_of<'a>(
+    result: Option<&'a ExecutionResult>,
+    job_id: &ScheduledJobId,
+) -> SchedResult<&'a Counts> {
+    match result {
+        Some(result) if !result.counts.is_empty() => Ok(&result.counts),
+        Some(_) => Err(SchedError::VerificationFailed(format!(
+            "result of job {} has no counts",
+            job_id
+        ))),
+        None => Err(SchedError::VerificationFailed(format!(
+            "no result for job {}",
+            job_id
+        ))),
+    }
+}
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckOutcome {
+    /// Metric computed.
+    pub metric: ResultMetric,
+
+    /// Computed value.
+    pub value: f64,
+
+    /// Maximum allowed value.
+    pub threshold: f64,
+
+    /// Whether the value is within the threshold.
+    pub passed: bool,
+}
+
+/// Outcome of a verification node, stored as its result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Reference job.
+    pub reference: ScheduledJobId,
+
+    /// Candidate job.
+    pub candidate: ScheduledJobId,
+
+    /// Per-check outcomes.
+    pub checks: Vec<CheckOutcome>,
+
+    /// Whether all checks passed.
+    pub passed: bool,
+}
+
+impl VerificationReport {
+    /// Describe the failed checks.
+    pub fn failures(&self) -> String {
+        self.checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| format!("{} = {:.4} exceeds {:.4}", c.metric, c.value, c.threshold))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Total variation distance between two count distributions.
+pub fn total_variation_distance(p: &Counts, q: &Counts) -> f64 {
+    p.total_variation_distance(q)
+}
+
+/// Hellinger distance between two count distributions.
+pub fn hellinger_distance(p: &Counts, q: &Counts) -> f64 {
+    p.hellinger_distance(q)
+}
+
+/// Expectation value of the Z-parity operator over `qubits`.
+///
+/// Bitstrings follow the little-endian convention: qubit 0 is the rightmost
+/// bit. Register separators (spaces) are ignored.
+pub fn z_expectation(counts: &Counts, qubits: &[usize]) -> f64 {
+    counts.parity_expectation(qubits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::task_result;
+
+    fn counts(pairs: &[(&str, u64)]) -> Counts {
+        Counts::from_pairs(pairs.iter().copied())
+    }
+
+    #[test]
+    fn test_distances() {
+        let bell = counts(&[("00", 500), ("11", 500)]);
+        let noisy = counts(&[("00", 450), ("11", 450), ("01", 100)]);
+
+        assert_eq!(total_variation_distance(&bell, &bell), 0.0);
+        assert!((total_variation_distance(&bell, &noisy) - 0.1).abs() < 1e-9);
+        assert!((total_variation_distance(&noisy, &bell) - 0.1).abs() < 1e-9);
+
+        let disjoint = counts(&[("01", 10)]);
+        assert!((total_variation_distance(&bell, &disjoint) - 1.0).abs() < 1e-9);
+        assert!((hellinger_distance(&bell, &disjoint) - 1.0).abs() < 1e-9);
+        assert!(hellinger_distance(&bell, &bell).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_z_expectation() {
+        let c = counts(&[("01", 75), ("00", 25)]);
+        assert!((z_expectation(&c, &[0]) + 0.5).abs() < 1e-9);
+        assert!((z_expectation(&c, &[1]) - 1.0).abs() < 1e-9);
+        assert!((z_expectation(&c, &[0, 1]) + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_verify() {
+        let sim = ScheduledJobId::new();
+        let hw = ScheduledJobId::new();
+
+        let mut inputs = TaskInputs::new();
+        inputs.push(
+            sim.clone(),
+            "sim",
+            ExecutionResult::new(counts(&[("00", 500), ("11", 500)]), 1000),
+        );
+        inputs.push(
+            hw.clone(),
+            "hw",
+            ExecutionResult::new(counts(&[("00", 400), ("11", 400), ("10", 200)]), 1000),
+        );
+
+        let report = ResultVerification::new(sim.clone(), hw.clone())
+            .with_tvd(0.05)
+            .with_expectation_delta(vec![0, 1], 0.5)
+            .verify(&inputs)
+            .unwrap();
+        assert!(!report.passed);
+        assert!(!report.checks[0].passed);
+        assert!(report.checks[1].passed);
+        assert!(report.failures().starts_with("tvd = 0.2000"));
+
+        let lenient = ResultVerification::new(sim.clone(), hw.clone()).with_tvd(0.25);
+        assert!(lenient.verify(&inputs).unwrap().passed);
+
+        let mut missing = TaskInputs::new();
+        missing.push(hw.clone(), "hw", task_result(serde_json::json!(1)));
+        assert!(lenient.verify(&missing).is_err());
+    }
+}
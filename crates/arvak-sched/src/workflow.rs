@@ -5,24 +5,34 @@ use petgraph::Direction;
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
 
 use crate::error::{SchedError, SchedResult};
-use crate::job::{ScheduledJob, ScheduledJobId};
-
-/// Unique identifier for a workflow.
+use crate::id::{ParseIdError, Ulid};
+use crate::job::{
+    DependencyKind, DependencyState, ScheduledJob, ScheduledJobId, ScheduledJobStatus,
+};
+use crate::persistence::StateStore;
+use crate::task::ClassicalTask;
+use crate::verify::ResultVerification;
+
+/// Unique identifier for a workflow, a [`Ulid`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct WorkflowId(pub Uuid);
+pub struct WorkflowId(pub Ulid);
 
 impl WorkflowId {
-    /// Create a new random workflow ID.
+    /// Create a new workflow ID for the current time.
     pub fn new() -> Self {
-        Self(Uuid::new_v4())
+        Self(Ulid::new())
+    }
+
+    /// Parse a workflow ID from a ULID or UUID string.
+    pub fn parse(s: &str) -> Result<Self, ParseIdError> {
+        Ok(Self(Ulid::parse(s)?))
     }
 
-    /// Parse a workflow ID from a string.
-    pub fn parse(s: &str) -> Result<Self, uuid::Error> {
-        Ok(Self(Uuid::parse_str(s)?))
+    /// Time the ID was created.
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.0.timestamp()
     }
 }
 
@@ -67,6 +77,23 @@ impl WorkflowStatus {
     }
 }
 
+/// What a failing node does to the rest of its workflow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailurePolicy {
+    /// Fail the workflow at once, cancelling its unfinished jobs.
+    FailWorkflow,
+
+    /// Skip the jobs downstream of the node; the other jobs run on and the
+    /// workflow fails once they have finished.
+    #[default]
+    SkipDescendants,
+
+    /// Skip the jobs downstream of the node; the other jobs run on and the
+    /// failure does not fail the workflow, e.g. for optional analyses.
+    ContinueIndependent,
+}
+
 /// A node in the workflow DAG.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkflowNode {
@@ -78,10 +105,212 @@ pub struct WorkflowNode {
 
     /// Whether this node failed.
     pub failed: bool,
+
+    /// Whether this node was skipped because its dependencies can no
+    /// longer be satisfied.
+    #[serde(default)]
+    pub skipped: bool,
+
+    /// Whether this node completed from the cached result of an identical
+    /// earlier job instead of running.
+    #[serde(default)]
+    pub cached: bool,
+
+    /// What a failure of this node does to the workflow.
+    #[serde(default)]
+    pub failure_policy: FailurePolicy,
+}
+
+/// A node that re-runs part of a workflow until it converges, e.g. the
+/// circuit evaluations and optimizer step of a variational algorithm.
+///
+/// Once every job of the body has finished, the scheduler calls the
+/// `condition` closure from its [`TaskRegistry`](crate::task::TaskRegistry)
+/// with the results of the body's last jobs and the current loop state,
+/// as an input named after the loop node. The closure returns a
+/// [`LoopDecision`]. Unless it converged or `max_iterations` is reached,
+/// the body is reset and runs again with the new state; body tasks read
+/// the state as an input named after the loop node as well. Without a
+/// condition, the body runs `max_iterations` times.
+///
+/// The iteration count and state are stored with the loop node's job, so a
+/// restarted scheduler continues the loop where it stopped. Once finished,
+/// the loop node's output is `{"iterations", "converged", "state"}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoopNode {
+    /// Jobs run on every iteration.
+    pub body: Vec<ScheduledJobId>,
+
+    /// Name of the registered closure deciding whether the loop converged.
+    pub condition: Option<String>,
+
+    /// Most iterations run, even if the loop does not converge.
+    pub max_iterations: u32,
+
+    /// Number of finished iterations.
+    #[serde(default)]
+    pub iteration: u32,
+
+    /// State carried from one iteration to the next.
+    #[serde(default)]
+    pub state: serde_json::Value,
+}
+
+impl LoopNode {
+    /// Create a loop over the given jobs, running at most `max_iterations`
+    /// times.
+    pub fn new(body: Vec<ScheduledJobId>, max_iterations: u32) -> Self {
+        Self {
+            body,
+            condition: None,
+            max_iterations,
+            iteration: 0,
+            state: serde_json::Value::Null,
+        }
+    }
+
+    /// Decide after each iteration with the closure registered under this
+    /// name.
+    pub fn with_condition(mut self, name: impl Into<String>) -> Self {
+        self.condition = Some(name.into());
+        self
+    }
+
+    /// Set the state of the first iteration.
+    pub fn with_state(mut self, state: serde_json::Value) -> Self {
+        self.state = state;
+        self
+    }
+}
+
+/// Output of a loop condition closure.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoopDecision {
+    /// Whether the loop is done.
+    #[serde(default)]
+    pub converged: bool,
+
+    /// State of the next iteration; unset keeps the current state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+}
+
+impl LoopDecision {
+    /// Finish the loop.
+    pub fn converged() -> Self {
+        Self {
+            converged: true,
+            state: None,
+        }
+    }
+
+    /// Run another iteration with a new state.
+    pub fn next(state: serde_json::Value) -> Self {
+        Self {
+            converged: false,
+            state: Some(state),
+        }
+    }
+
+    /// Set the state, e.g. the final parameters of a converged loop.
+    pub fn with_state(mut self, state: serde_json::Value) -> Self {
+        self.state = Some(state);
+        self
+    }
+}
+
+impl From<LoopDecision> for serde_json::Value {
+    fn from(decision: LoopDecision) -> Self {
+        serde_json::json!(decision)
+    }
+}
+
+/// A node that runs a whole workflow of its own, e.g. a reusable
+/// "transpile, run, mitigate" pipeline embedded in a larger sweep.
+///
+/// When the node becomes ready, the scheduler submits the sub-workflow
+/// with [`Workflow::parent`] pointing at the node. The node is queued
+/// while the sub-workflow is pending, running while it runs, and finishes
+/// with it: completed, failed with its failure reason, or cancelled.
+/// Cancelling the node cancels the sub-workflow, and cancelling the
+/// sub-workflow cancels the node. Once completed, the node's output is
+/// `{"workflow_id", "jobs", "outputs"}`, where `outputs` maps the names of
+/// the sub-workflow's last jobs to their results.
+///
+/// A node that runs again, e.g. in a later loop iteration or after
+/// [`HpcScheduler::rerun_from`](crate::HpcScheduler::rerun_from), runs
+/// its sub-workflow again from scratch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubWorkflow {
+    /// The workflow to run.
+    pub workflow: Box<Workflow>,
+}
+
+impl SubWorkflow {
+    /// Create a node running `workflow`.
+    pub fn new(workflow: Workflow) -> SchedResult<Self> {
+        if workflow.is_empty() {
+            return Err(SchedError::InvalidDependency(format!(
+                "sub-workflow {} has no jobs",
+                workflow.name
+            )));
+        }
+        Ok(Self {
+            workflow: Box::new(workflow),
+        })
+    }
+
+    /// Create the job of a node running `workflow`, named after it.
+    fn into_job(self) -> ScheduledJob {
+        let name = self.workflow.name.clone();
+        ScheduledJob::classical(name, ClassicalTask::Workflow(self))
+    }
+}
+
+// Workflow IDs are unique, so nodes running the same workflow are equal.
+impl PartialEq for SubWorkflow {
+    fn eq(&self, other: &Self) -> bool {
+        self.workflow.id == other.workflow.id
+    }
+}
+
+/// Progress summary of a workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkflowProgress {
+    /// Number of jobs in the workflow.
+    pub total: usize,
+
+    /// Jobs that completed successfully.
+    pub completed: usize,
+
+    /// Completed jobs whose result came from the node cache.
+    pub cached: usize,
+
+    /// Jobs handed to the batch system or a QPU and not yet finished.
+    pub running: usize,
+
+    /// Jobs not yet dispatched.
+    pub pending: usize,
+
+    /// Jobs that failed or were cancelled.
+    pub failed: usize,
+
+    /// Jobs skipped because their dependencies can no longer be satisfied.
+    pub skipped: usize,
+
+    /// Share of finished jobs, from 0 to 100.
+    pub percent_complete: f64,
+
+    /// Estimated seconds left along the critical path of unfinished jobs.
+    pub remaining_secs: f64,
+
+    /// Estimated completion time, unset once the workflow is terminal.
+    pub eta: Option<DateTime<Utc>>,
 }
 
 /// A workflow consisting of jobs with dependencies.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "WorkflowRecord", into = "WorkflowRecord")]
 pub struct Workflow {
     /// Unique workflow identifier.
     pub id: WorkflowId,
@@ -98,15 +327,90 @@ pub struct Workflow {
     /// Completion timestamp.
     pub completed_at: Option<DateTime<Utc>>,
 
+    /// Whether jobs reuse the results of identical earlier jobs, see
+    /// [`crate::cache`].
+    pub caching: bool,
+
+    /// The node running this workflow, if it is a [`SubWorkflow`].
+    pub parent: Option<ScheduledJobId>,
+
     /// The DAG of jobs.
-    #[serde(skip)]
-    dag: DiGraph<WorkflowNode, ()>,
+    dag: DiGraph<WorkflowNode, DependencyKind>,
 
     /// Mapping from job ID to node index.
-    #[serde(skip)]
     job_index: rustc_hash::FxHashMap<ScheduledJobId, NodeIndex>,
 }
 
+/// Serialized form of a workflow, with the DAG flattened to node and edge lists.
+///
+/// Workflows stored before the DAG was persisted load with no jobs.
+#[derive(Serialize, Deserialize)]
+struct WorkflowRecord {
+    id: WorkflowId,
+    name: String,
+    status: WorkflowStatus,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    caching: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<ScheduledJobId>,
+    #[serde(default)]
+    nodes: Vec<WorkflowNode>,
+    #[serde(default)]
+    edges: Vec<(usize, usize, DependencyKind)>,
+}
+
+impl From<Workflow> for WorkflowRecord {
+    fn from(workflow: Workflow) -> Self {
+        let edges = workflow
+            .dag
+            .edge_references()
+            .map(|e| (e.source().index(), e.target().index(), *e.weight()))
+            .collect();
+        let (nodes, _) = workflow.dag.into_nodes_edges();
+        Self {
+            id: workflow.id,
+            name: workflow.name,
+            status: workflow.status,
+            created_at: workflow.created_at,
+            completed_at: workflow.completed_at,
+            caching: workflow.caching,
+            parent: workflow.parent,
+            nodes: nodes.into_iter().map(|n| n.weight).collect(),
+            edges,
+        }
+    }
+}
+
+impl From<WorkflowRecord> for Workflow {
+    fn from(record: WorkflowRecord) -> Self {
+        let mut dag = DiGraph::with_capacity(record.nodes.len(), record.edges.len());
+        let mut job_index = rustc_hash::FxHashMap::default();
+        for node in record.nodes {
+            let job_id = node.job.id.clone();
+            job_index.insert(job_id, dag.add_node(node));
+        }
+        let count = dag.node_count();
+        for (from, to, kind) in record.edges {
+            if from < count && to < count {
+                dag.update_edge(NodeIndex::new(from), NodeIndex::new(to), kind);
+            }
+        }
+        Self {
+            id: record.id,
+            name: record.name,
+            status: record.status,
+            created_at: record.created_at,
+            completed_at: record.completed_at,
+            caching: record.caching,
+            parent: record.parent,
+            dag,
+            job_index,
+        }
+    }
+}
+
 impl Workflow {
     /// Create a new empty workflow.
     pub fn new(name: impl Into<String>) -> Self {
@@ -116,6 +420,8 @@ impl Workflow {
             status: WorkflowStatus::Pending,
             created_at: Utc::now(),
             completed_at: None,
+            caching: false,
+            parent: None,
             dag: DiGraph::new(),
             job_index: rustc_hash::FxHashMap::default(),
         }
@@ -128,6 +434,9 @@ impl Workflow {
             job,
             completed: false,
             failed: false,
+            skipped: false,
+            cached: false,
+            failure_policy: FailurePolicy::default(),
         };
         let idx = self.dag.add_node(node);
         self.job_index.insert(job_id, idx);
@@ -141,6 +450,19 @@ impl Workflow {
         &mut self,
         from: &ScheduledJobId,
         to: &ScheduledJobId,
+    ) -> SchedResult<()> {
+        self.add_dependency_with(from, to, DependencyKind::AfterOk)
+    }
+
+    /// Add a dependency edge of a specific kind between two jobs.
+    ///
+    /// The dependency is also recorded on the `to` job so the scheduler
+    /// queue enforces it.
+    pub fn add_dependency_with(
+        &mut self,
+        from: &ScheduledJobId,
+        to: &ScheduledJobId,
+        kind: DependencyKind,
     ) -> SchedResult<()> {
         let from_idx = self
             .job_index
@@ -156,10 +478,35 @@ impl Workflow {
             return Err(SchedError::DependencyCycle);
         }
 
-        self.dag.add_edge(*from_idx, *to_idx, ());
+        let (from_idx, to_idx) = (*from_idx, *to_idx);
+        self.dag.update_edge(from_idx, to_idx, kind);
+        if let Some(node) = self.dag.node_weight_mut(to_idx) {
+            node.job.add_dependency(from.clone(), kind);
+        }
+        Ok(())
+    }
+
+    /// Require only `n` of a job's dependencies to be satisfied.
+    pub fn set_min_dependencies(&mut self, job_id: &ScheduledJobId, n: usize) -> SchedResult<()> {
+        let job = self
+            .get_job_mut(job_id)
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+        job.min_dependencies = Some(n);
         Ok(())
     }
 
+    /// Get the kind of the dependency edge between two jobs.
+    pub fn dependency_kind(
+        &self,
+        from: &ScheduledJobId,
+        to: &ScheduledJobId,
+    ) -> Option<DependencyKind> {
+        let from_idx = self.job_index.get(from)?;
+        let to_idx = self.job_index.get(to)?;
+        let edge = self.dag.find_edge(*from_idx, *to_idx)?;
+        self.dag.edge_weight(edge).copied()
+    }
+
     /// Get a job by ID.
     pub fn get_job(&self, job_id: &ScheduledJobId) -> Option<&ScheduledJob> {
         self.job_index
@@ -177,6 +524,29 @@ impl Workflow {
             .map(|node| &mut node.job)
     }
 
+    /// Set what a failure of a job does to the workflow.
+    pub fn set_failure_policy(
+        &mut self,
+        job_id: &ScheduledJobId,
+        policy: FailurePolicy,
+    ) -> SchedResult<()> {
+        let node = self
+            .job_index
+            .get(job_id)
+            .and_then(|idx| self.dag.node_weight_mut(*idx))
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+        node.failure_policy = policy;
+        Ok(())
+    }
+
+    /// Get what a failure of a job does to the workflow.
+    pub fn failure_policy(&self, job_id: &ScheduledJobId) -> Option<FailurePolicy> {
+        self.job_index
+            .get(job_id)
+            .and_then(|idx| self.dag.node_weight(*idx))
+            .map(|n| n.failure_policy)
+    }
+
     /// Mark a job as completed.
     pub fn mark_completed(&mut self, job_id: &ScheduledJobId) -> SchedResult<()> {
         let idx = self
@@ -207,15 +577,68 @@ impl Workflow {
         }
     }
 
+    /// Mark a job as skipped.
+    pub fn mark_skipped(&mut self, job_id: &ScheduledJobId) -> SchedResult<()> {
+        let idx = self
+            .job_index
+            .get(job_id)
+            .ok_or_else(|| SchedError::JobNotFound(job_id.to_string()))?;
+
+        if let Some(node) = self.dag.node_weight_mut(*idx) {
+            node.skipped = true;
+            Ok(())
+        } else {
+            Err(SchedError::JobNotFound(job_id.to_string()))
+        }
+    }
+
+    /// Map of finished jobs to whether they succeeded.
+    ///
+    /// Skipped jobs count as not succeeded, matching SLURM's treatment of
+    /// cancelled dependencies.
+    fn finished_jobs(&self) -> rustc_hash::FxHashMap<ScheduledJobId, bool> {
+        self.dag
+            .node_weights()
+            .filter(|n| n.completed || n.failed || n.skipped)
+            .map(|n| (n.job.id.clone(), n.completed))
+            .collect()
+    }
+
+    /// Skip jobs whose dependencies can no longer be satisfied.
+    ///
+    /// Skips cascade to downstream jobs. Returns the IDs of newly skipped jobs.
+    pub fn skip_unsatisfiable(&mut self) -> Vec<ScheduledJobId> {
+        let order = petgraph::algo::toposort(&self.dag, None).unwrap_or_default();
+        let mut finished = self.finished_jobs();
+        let mut skipped = Vec::new();
+
+        for idx in order {
+            let Some(node) = self.dag.node_weight_mut(idx) else {
+                continue;
+            };
+            if node.completed || node.failed || node.skipped {
+                continue;
+            }
+            if node.job.dependency_state(&finished) == DependencyState::Unsatisfiable {
+                node.skipped = true;
+                finished.insert(node.job.id.clone(), false);
+                skipped.push(node.job.id.clone());
+            }
+        }
+
+        skipped
+    }
+
     /// Get jobs that are ready to run (all dependencies satisfied).
     pub fn ready_jobs(&self) -> Vec<&ScheduledJob> {
+        let finished = self.finished_jobs();
         self.dag
             .node_indices()
             .filter_map(|idx| {
                 let node = self.dag.node_weight(idx)?;
 
-                // Skip completed or failed jobs
-                if node.completed || node.failed {
+                // Skip finished jobs
+                if node.completed || node.failed || node.skipped {
                     return None;
                 }
 
@@ -224,18 +647,7 @@ impl Workflow {
                     return None;
                 }
 
-                // Check all dependencies are completed
-                let deps_satisfied =
-                    self.dag
-                        .edges_directed(idx, Direction::Incoming)
-                        .all(|edge| {
-                            self.dag
-                                .node_weight(edge.source())
-                                .map(|n| n.completed)
-                                .unwrap_or(false)
-                        });
-
-                if deps_satisfied {
+                if node.job.dependencies_satisfied(&finished) {
                     Some(&node.job)
                 } else {
                     None
@@ -307,12 +719,22 @@ impl Workflow {
             .count()
     }
 
+    /// Get the number of skipped jobs.
+    pub fn skipped_count(&self) -> usize {
+        self.dag.node_weights().filter(|n| n.skipped).count()
+    }
+
+    /// Get the number of jobs completed from the node cache.
+    pub fn cached_count(&self) -> usize {
+        self.dag.node_weights().filter(|n| n.cached).count()
+    }
+
     /// Check if the workflow is complete.
     pub fn is_complete(&self) -> bool {
         self.dag.node_indices().all(|idx| {
             self.dag
                 .node_weight(idx)
-                .map(|n| n.completed || n.failed)
+                .map(|n| n.completed || n.failed || n.skipped)
                 .unwrap_or(true)
         })
     }
@@ -322,6 +744,36 @@ impl Workflow {
         self.failed_count() > 0
     }
 
+    /// Failed jobs whose failure policy fails the workflow. Jobs cancelled
+    /// because they were skipped do not count.
+    fn failing_nodes(&self) -> impl Iterator<Item = &WorkflowNode> {
+        self.dag.node_weights().filter(|n| {
+            n.failed && !n.skipped && n.failure_policy != FailurePolicy::ContinueIndependent
+        })
+    }
+
+    /// Get jobs that have not finished, e.g. to cancel them.
+    pub fn unfinished_jobs(&self) -> Vec<&ScheduledJobId> {
+        self.dag
+            .node_weights()
+            .filter(|n| !(n.completed || n.failed || n.skipped))
+            .map(|n| &n.job.id)
+            .collect()
+    }
+
+    /// Get jobs that finished without succeeding: failed, cancelled or
+    /// skipped.
+    pub fn unsuccessful_jobs(&self) -> Vec<&ScheduledJobId> {
+        self.topological_order()
+            .into_iter()
+            .filter(|job| {
+                let node = &self.dag[self.job_index[&job.id]];
+                !node.completed && (node.failed || node.skipped)
+            })
+            .map(|job| &job.id)
+            .collect()
+    }
+
     /// Get dependencies of a job.
     pub fn dependencies(&self, job_id: &ScheduledJobId) -> Vec<&ScheduledJobId> {
         let Some(idx) = self.job_index.get(job_id) else {
@@ -346,12 +798,317 @@ impl Workflow {
             .collect()
     }
 
+    /// Get a job and every job downstream of it, in topological order.
+    pub fn descendants(&self, job_id: &ScheduledJobId) -> Vec<&ScheduledJobId> {
+        let Some(&start) = self.job_index.get(job_id) else {
+            return Vec::new();
+        };
+        let mut reachable = rustc_hash::FxHashSet::default();
+        let mut bfs = petgraph::visit::Bfs::new(&self.dag, start);
+        while let Some(idx) = bfs.next(&self.dag) {
+            reachable.insert(idx);
+        }
+        petgraph::algo::toposort(&self.dag, None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|idx| reachable.contains(idx))
+            .filter_map(|idx| self.dag.node_weight(idx).map(|n| &n.job.id))
+            .collect()
+    }
+
+    /// Reset a job and everything downstream of it to pending, so that part
+    /// of the workflow runs again, and reopen the workflow.
+    ///
+    /// Upstream jobs keep their state. Returns the reset jobs in
+    /// topological order.
+    pub fn reset_from(&mut self, job_id: &ScheduledJobId) -> SchedResult<Vec<ScheduledJobId>> {
+        let reset: Vec<ScheduledJobId> = self.descendants(job_id).into_iter().cloned().collect();
+        if reset.is_empty() {
+            return Err(SchedError::JobNotFound(job_id.to_string()));
+        }
+        self.reset_jobs(&reset)?;
+        Ok(reset)
+    }
+
+    /// Reset the given jobs to pending and reopen the workflow.
+    pub fn reset_jobs(&mut self, job_ids: &[ScheduledJobId]) -> SchedResult<()> {
+        if let Some(missing) = job_ids.iter().find(|id| !self.job_index.contains_key(*id)) {
+            return Err(SchedError::JobNotFound(missing.to_string()));
+        }
+        for id in job_ids {
+            if let Some(node) = self.dag.node_weight_mut(self.job_index[id]) {
+                node.job.reset();
+                node.completed = false;
+                node.failed = false;
+                node.skipped = false;
+                node.cached = false;
+            }
+        }
+        self.status = WorkflowStatus::Pending;
+        self.completed_at = None;
+        Ok(())
+    }
+
+    /// Add a loop node re-running `body` until it converges, see
+    /// [`LoopNode`].
+    ///
+    /// The loop node depends on the jobs of the body no other body job
+    /// depends on. Jobs that consume the outcome of the loop should depend
+    /// on the loop node, not on body jobs, whose results are replaced on
+    /// every iteration. Returns the ID of the loop node.
+    pub fn add_loop(
+        &mut self,
+        name: impl Into<String>,
+        node: LoopNode,
+    ) -> SchedResult<ScheduledJobId> {
+        if node.body.is_empty() {
+            return Err(SchedError::InvalidDependency(
+                "loop body has no jobs".to_string(),
+            ));
+        }
+        if node.max_iterations == 0 {
+            return Err(SchedError::InvalidDependency(
+                "loop must allow at least one iteration".to_string(),
+            ));
+        }
+        if let Some(missing) = node
+            .body
+            .iter()
+            .find(|id| !self.job_index.contains_key(*id))
+        {
+            return Err(SchedError::InvalidDependency(missing.to_string()));
+        }
+
+        let sinks: Vec<ScheduledJobId> = node
+            .body
+            .iter()
+            .filter(|id| {
+                !self
+                    .dependents(id)
+                    .into_iter()
+                    .any(|dependent| node.body.contains(dependent))
+            })
+            .cloned()
+            .collect();
+        let job = ScheduledJob::classical(name, ClassicalTask::Loop(node));
+        let loop_id = job.id.clone();
+        self.add_job(job);
+        for sink in &sinks {
+            self.add_dependency(sink, &loop_id)?;
+        }
+        Ok(loop_id)
+    }
+
+    /// Add a node running `workflow` as a sub-workflow, see
+    /// [`SubWorkflow`]. Returns the ID of the node.
+    pub fn add_sub_workflow(&mut self, workflow: Workflow) -> SchedResult<ScheduledJobId> {
+        let job = SubWorkflow::new(workflow)?.into_job();
+        let job_id = job.id.clone();
+        self.add_job(job);
+        Ok(job_id)
+    }
+
+    /// Get the loop nodes of the workflow.
+    pub fn loops(&self) -> Vec<(&ScheduledJob, &LoopNode)> {
+        self.dag
+            .node_weights()
+            .filter_map(|n| match &n.job.task {
+                Some(ClassicalTask::Loop(node)) => Some((&n.job, node)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replace a job's copy with a fresher one, e.g. loaded from the store.
+    ///
+    /// The node's completed, failed and cached flags follow the job's
+    /// status; cancelled jobs count as failed. Returns false if the job is
+    /// not part of the workflow.
+    pub fn refresh_job(&mut self, job: ScheduledJob) -> bool {
+        let Some(node) = self
+            .job_index
+            .get(&job.id)
+            .and_then(|idx| self.dag.node_weight_mut(*idx))
+        else {
+            return false;
+        };
+        node.completed = job.status.is_success();
+        node.failed = job.status.is_terminal() && !job.status.is_success();
+        node.cached = matches!(job.status, ScheduledJobStatus::CachedComplete { .. });
+        node.job = job;
+        true
+    }
+
+    /// Refresh every job from a store, see [`Workflow::refresh_job`].
+    ///
+    /// Jobs missing from the store keep their current copy.
+    pub async fn refresh_from(&mut self, store: &dyn StateStore) -> SchedResult<()> {
+        let job_ids: Vec<ScheduledJobId> = self.job_index.keys().cloned().collect();
+        for job_id in job_ids {
+            if let Some(job) = store.load_job(&job_id).await? {
+                self.refresh_job(job);
+            }
+        }
+        Ok(())
+    }
+
+    /// Summarize progress, estimating each job's walltime with
+    /// [`ScheduledJob::estimated_walltime_secs`].
+    pub fn progress(&self) -> WorkflowProgress {
+        self.progress_with(|job| job.estimated_walltime_secs())
+    }
+
+    /// Summarize progress with a custom walltime estimate per job, in seconds.
+    ///
+    /// Finished jobs contribute nothing to the remaining time; running jobs
+    /// contribute their estimate minus the time since submission. The
+    /// remaining time is the longest such path through the DAG.
+    pub fn progress_with(&self, estimate: impl Fn(&ScheduledJob) -> f64) -> WorkflowProgress {
+        let now = Utc::now();
+        let mut progress = WorkflowProgress {
+            total: self.len(),
+            completed: 0,
+            cached: 0,
+            running: 0,
+            pending: 0,
+            failed: 0,
+            skipped: 0,
+            percent_complete: 100.0,
+            remaining_secs: 0.0,
+            eta: None,
+        };
+
+        let order = petgraph::algo::toposort(&self.dag, None).unwrap_or_default();
+        let mut finish = vec![0.0_f64; self.dag.node_count()];
+        for idx in order {
+            let node = &self.dag[idx];
+            let remaining = if node.completed {
+                progress.completed += 1;
+                progress.cached += usize::from(node.cached);
+                0.0
+            } else if node.failed {
+                progress.failed += 1;
+                0.0
+            } else if node.skipped {
+                progress.skipped += 1;
+                0.0
+            } else if node.job.status.is_pending() {
+                progress.pending += 1;
+                estimate(&node.job)
+            } else {
+                progress.running += 1;
+                let elapsed = node
+                    .job
+                    .submitted_at
+                    .map(|at| (now - at).num_milliseconds() as f64 / 1000.0)
+                    .unwrap_or(0.0);
+                (estimate(&node.job) - elapsed).max(0.0)
+            };
+            let start = self
+                .dag
+                .edges_directed(idx, Direction::Incoming)
+                .map(|e| finish[e.source().index()])
+                .fold(0.0, f64::max);
+            finish[idx.index()] = start + remaining;
+        }
+
+        progress.remaining_secs = finish.into_iter().fold(0.0, f64::max);
+        if progress.total > 0 {
+            let finished = progress.completed + progress.failed + progress.skipped;
+            progress.percent_complete = finished as f64 * 100.0 / progress.total as f64;
+        }
+        if !self.status.is_terminal() {
+            progress.eta = Some(
+                now + chrono::Duration::milliseconds((progress.remaining_secs * 1000.0) as i64),
+            );
+        }
+        progress
+    }
+
+    /// Render the DAG in Graphviz DOT.
+    ///
+    /// Nodes are filled by job status, with skipped nodes dashed and cached
+    /// ones marked; hovering a failed node shows the failure reason. Edges
+    /// are drawn by dependency kind: solid for `afterok`, dashed for
+    /// `afterany`, dotted red for `afternotok`. Call
+    /// [`Workflow::refresh_from`] first for live job states.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n", dot_quote(&self.name));
+        dot.push_str("  rankdir=LR;\n");
+        dot.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\"];\n");
+        dot.push_str("  edge [fontname=\"Helvetica\", fontsize=10];\n");
+
+        for idx in self.dag.node_indices() {
+            let node = &self.dag[idx];
+            let (state, fill) = if node.skipped {
+                ("Skipped", "#f5f5f5")
+            } else if node.cached {
+                ("Cached", "#b7e4c7")
+            } else {
+                (node.job.status.name(), dot_color(&node.job.status))
+            };
+            let style = if node.skipped {
+                "rounded,filled,dashed"
+            } else {
+                "rounded,filled"
+            };
+            let tooltip = match &node.job.status {
+                ScheduledJobStatus::Failed { reason, .. } => reason.clone(),
+                status => status
+                    .slurm_job_id()
+                    .map(|id| format!("batch job {}", id))
+                    .unwrap_or_else(|| node.job.id.to_string()),
+            };
+            dot.push_str(&format!(
+                "  {} [label={}, fillcolor=\"{}\", style=\"{}\", tooltip={}];\n",
+                dot_quote(&node.job.id.to_string()),
+                dot_quote(&format!("{}\n{}", node.job.name, state)),
+                fill,
+                style,
+                dot_quote(&tooltip)
+            ));
+        }
+
+        for edge in self.dag.edge_references() {
+            let attrs = match edge.weight() {
+                DependencyKind::AfterOk => String::new(),
+                DependencyKind::AfterAny => " [style=dashed, label=\"afterany\"]".to_string(),
+                DependencyKind::AfterNotOk => {
+                    " [style=dotted, color=\"#c0392b\", label=\"afternotok\"]".to_string()
+                }
+            };
+            dot.push_str(&format!(
+                "  {} -> {}{};\n",
+                dot_quote(&self.dag[edge.source()].job.id.to_string()),
+                dot_quote(&self.dag[edge.target()].job.id.to_string()),
+                attrs
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
     /// Update workflow status based on job states.
+    ///
+    /// A failed job with [`FailurePolicy::FailWorkflow`] fails the workflow
+    /// even if other jobs have not finished; see
+    /// [`Workflow::unfinished_jobs`].
     pub fn update_status(&mut self) {
-        if self.is_complete() {
-            if self.has_failures() {
+        self.skip_unsatisfiable();
+        let fatal = self
+            .failing_nodes()
+            .find(|n| n.failure_policy == FailurePolicy::FailWorkflow)
+            .map(|n| n.job.name.clone());
+        if let Some(name) = fatal {
+            self.status = WorkflowStatus::Failed {
+                reason: format!("job {} failed", name),
+            };
+            self.completed_at = Some(Utc::now());
+        } else if self.is_complete() {
+            let failed = self.failing_nodes().count();
+            if failed > 0 {
                 self.status = WorkflowStatus::Failed {
-                    reason: format!("{} job(s) failed", self.failed_count()),
+                    reason: format!("{} job(s) failed", failed),
                 };
             } else {
                 self.status = WorkflowStatus::Completed;
@@ -372,6 +1129,8 @@ impl Workflow {
 pub struct WorkflowBuilder {
     workflow: Workflow,
     last_job_id: Option<ScheduledJobId>,
+    /// Jobs added by the last [`WorkflowBuilder::map`], until reduced.
+    mapped: Vec<ScheduledJobId>,
 }
 
 impl WorkflowBuilder {
@@ -380,6 +1139,7 @@ impl WorkflowBuilder {
         Self {
             workflow: Workflow::new(name),
             last_job_id: None,
+            mapped: Vec::new(),
         }
     }
 
@@ -392,6 +1152,7 @@ impl WorkflowBuilder {
 
     /// Add a job that depends on the previously added job.
     pub fn then(mut self, job: ScheduledJob) -> SchedResult<Self> {
+        self.check_reduced()?;
         let Some(ref prev_id) = self.last_job_id else {
             // No previous job, just add it
             self.last_job_id = Some(job.id.clone());
@@ -406,6 +1167,21 @@ impl WorkflowBuilder {
         Ok(self)
     }
 
+    /// Add a job that depends on the previously added job with a specific kind.
+    pub fn then_with(mut self, job: ScheduledJob, kind: DependencyKind) -> SchedResult<Self> {
+        self.check_reduced()?;
+        let Some(prev_id) = self.last_job_id.clone() else {
+            return Ok(self.add_job(job));
+        };
+
+        let current_id = job.id.clone();
+        self.workflow.add_job(job);
+        self.workflow
+            .add_dependency_with(&prev_id, &current_id, kind)?;
+        self.last_job_id = Some(current_id);
+        Ok(self)
+    }
+
     /// Add a job that depends on a specific job.
     pub fn add_job_after(
         mut self,
@@ -436,12 +1212,196 @@ impl WorkflowBuilder {
         Ok(self)
     }
 
+    /// Add a job that depends on a specific job with a specific kind.
+    pub fn add_job_after_with(
+        mut self,
+        job: ScheduledJob,
+        depends_on: &ScheduledJobId,
+        kind: DependencyKind,
+    ) -> SchedResult<Self> {
+        let current_id = job.id.clone();
+        self.workflow.add_job(job);
+        self.workflow
+            .add_dependency_with(depends_on, &current_id, kind)?;
+        self.last_job_id = Some(current_id);
+        Ok(self)
+    }
+
+    /// Add a job that runs once `n` of the given jobs have succeeded.
+    pub fn add_job_after_n_of(
+        mut self,
+        job: ScheduledJob,
+        depends_on: &[ScheduledJobId],
+        n: usize,
+    ) -> SchedResult<Self> {
+        if n == 0 || n > depends_on.len() {
+            return Err(SchedError::InvalidDependency(format!(
+                "cannot require {} of {} dependencies",
+                n,
+                depends_on.len()
+            )));
+        }
+
+        let current_id = job.id.clone();
+        self.workflow.add_job(job.with_min_dependencies(n));
+        for dep_id in depends_on {
+            self.workflow.add_dependency(dep_id, &current_id)?;
+        }
+
+        self.last_job_id = Some(current_id);
+        Ok(self)
+    }
+
+    /// Fan out: add one job per input, made by `job_factory`.
+    ///
+    /// The jobs depend on the previously added job, if any, and run in
+    /// parallel. Follow with [`WorkflowBuilder::reduce`] to collect their
+    /// results.
+    pub fn map<T>(
+        mut self,
+        inputs: impl IntoIterator<Item = T>,
+        mut job_factory: impl FnMut(T) -> ScheduledJob,
+    ) -> SchedResult<Self> {
+        self.check_reduced()?;
+        let mut mapped = Vec::new();
+        for input in inputs {
+            let job = job_factory(input);
+            let job_id = job.id.clone();
+            self.workflow.add_job(job);
+            if let Some(prev_id) = &self.last_job_id {
+                self.workflow.add_dependency(prev_id, &job_id)?;
+            }
+            mapped.push(job_id);
+        }
+        if mapped.is_empty() {
+            return Err(SchedError::InvalidDependency(
+                "map has no inputs".to_string(),
+            ));
+        }
+        self.mapped = mapped;
+        Ok(self)
+    }
+
+    /// Fan in: add a job that runs once every job of the preceding
+    /// [`WorkflowBuilder::map`] has succeeded.
+    ///
+    /// A classical reduce job receives the mapped jobs' results as its
+    /// [`TaskInputs`](crate::task::TaskInputs), in the order of the map's
+    /// inputs.
+    pub fn reduce(mut self, job: ScheduledJob) -> SchedResult<Self> {
+        if self.mapped.is_empty() {
+            return Err(SchedError::InvalidDependency(
+                "reduce must follow a map".to_string(),
+            ));
+        }
+        let mapped = std::mem::take(&mut self.mapped);
+        self.add_job_after_all(job, &mapped)
+    }
+
+    /// Fail if mapped jobs are still waiting for their reduce job.
+    fn check_reduced(&self) -> SchedResult<()> {
+        if self.mapped.is_empty() {
+            Ok(())
+        } else {
+            Err(SchedError::InvalidDependency(
+                "mapped jobs must be reduced first".to_string(),
+            ))
+        }
+    }
+
+    /// Add a loop node re-running `body` until it converges, see
+    /// [`Workflow::add_loop`].
+    ///
+    /// The body jobs must have been added already; jobs added with
+    /// [`WorkflowBuilder::then`] afterwards run once the loop has finished.
+    pub fn loop_over(mut self, name: impl Into<String>, node: LoopNode) -> SchedResult<Self> {
+        let loop_id = self.workflow.add_loop(name, node)?;
+        self.last_job_id = Some(loop_id);
+        Ok(self)
+    }
+
+    /// Add a node running `workflow` as a sub-workflow after the previously
+    /// added job, see [`SubWorkflow`].
+    pub fn then_workflow(self, workflow: Workflow) -> SchedResult<Self> {
+        self.then(SubWorkflow::new(workflow)?.into_job())
+    }
+
+    /// Set what a failure of the previously added job does to the workflow.
+    pub fn on_failure(mut self, policy: FailurePolicy) -> SchedResult<Self> {
+        let Some(job_id) = &self.last_job_id else {
+            return Err(SchedError::InvalidDependency(
+                "no job to set a failure policy for".to_string(),
+            ));
+        };
+        self.workflow.set_failure_policy(job_id, policy)?;
+        Ok(self)
+    }
+
+    /// Reuse the results of identical earlier jobs for unchanged nodes, see
+    /// [`crate::cache`].
+    pub fn with_caching(mut self) -> Self {
+        self.workflow.caching = true;
+        self
+    }
+
+    /// Add a node that verifies the result of one job against another.
+    ///
+    /// The workflow fails if the results diverge beyond the configured
+    /// thresholds.
+    pub fn verify(
+        self,
+        name: impl Into<String>,
+        verification: ResultVerification,
+    ) -> SchedResult<Self> {
+        let deps = [
+            verification.reference.clone(),
+            verification.candidate.clone(),
+        ];
+        let job = ScheduledJob::classical(name, ClassicalTask::verify(verification));
+        self.add_job_after_all(job, &deps)
+    }
+
     /// Build the workflow.
     pub fn build(self) -> Workflow {
         self.workflow
     }
 }
 
+/// Quote a DOT identifier or label.
+fn dot_quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Fill color of a job status in DOT output.
+fn dot_color(status: &ScheduledJobStatus) -> &'static str {
+    match status {
+        ScheduledJobStatus::Pending | ScheduledJobStatus::WaitingOnDependencies => "#e0e0e0",
+        ScheduledJobStatus::SlurmQueued { .. } | ScheduledJobStatus::QuantumSubmitted { .. } => {
+            "#fff3b0"
+        }
+        ScheduledJobStatus::SlurmRunning { .. } | ScheduledJobStatus::QuantumRunning { .. } => {
+            "#a9d6f5"
+        }
+        ScheduledJobStatus::Completed { .. } | ScheduledJobStatus::CachedComplete { .. } => {
+            "#74c69d"
+        }
+        ScheduledJobStatus::Failed { .. } => "#f4978e",
+        ScheduledJobStatus::Cancelled => "#bdbdbd",
+        ScheduledJobStatus::DeadlineExceeded { .. } => "#f8ad6d",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -614,4 +1574,468 @@ mod tests {
         assert!(workflow.has_failures());
         assert_eq!(workflow.failed_count(), 1);
     }
+
+    #[test]
+    fn test_workflow_reset_from() {
+        let job1 = make_job("job1");
+        let job2 = make_job("job2");
+        let job3 = make_job("job3");
+        let side = make_job("side");
+        let (id1, id2, id3, side_id) = (
+            job1.id.clone(),
+            job2.id.clone(),
+            job3.id.clone(),
+            side.id.clone(),
+        );
+        let mut workflow = WorkflowBuilder::new("rerun")
+            .add_job(job1)
+            .then(job2)
+            .unwrap()
+            .then(job3)
+            .unwrap()
+            .add_job_after(side, &id1)
+            .unwrap()
+            .build();
+
+        workflow.mark_completed(&id1).unwrap();
+        workflow.mark_completed(&side_id).unwrap();
+        workflow.mark_failed(&id2).unwrap();
+        workflow.update_status();
+        assert!(workflow.status.is_terminal());
+        assert_eq!(workflow.skipped_count(), 1);
+
+        assert_eq!(workflow.reset_from(&id2).unwrap(), vec![id2.clone(), id3]);
+        assert_eq!(workflow.status, WorkflowStatus::Pending);
+        assert_eq!(workflow.completed_count(), 2);
+        assert_eq!(workflow.failed_count(), 0);
+        assert_eq!(workflow.skipped_count(), 0);
+        assert_eq!(workflow.ready_jobs()[0].id, id2);
+        assert!(workflow.reset_from(&ScheduledJobId::new()).is_err());
+    }
+
+    #[test]
+    fn test_workflow_failure_policies() {
+        let calibrate = make_job("calibrate");
+        let sweep = make_job("sweep");
+        let plot = make_job("plot");
+        let side = make_job("side");
+        let (calibrate_id, sweep_id, plot_id, side_id) = (
+            calibrate.id.clone(),
+            sweep.id.clone(),
+            plot.id.clone(),
+            side.id.clone(),
+        );
+        let build = |policy| {
+            WorkflowBuilder::new("policies")
+                .add_job(calibrate.clone())
+                .then(sweep.clone())
+                .unwrap()
+                .then(plot.clone())
+                .unwrap()
+                .on_failure(policy)
+                .unwrap()
+                .add_job(side.clone())
+                .build()
+        };
+
+        // Optional analyses may fail without failing the workflow
+        let mut workflow = build(FailurePolicy::ContinueIndependent);
+        assert_eq!(
+            workflow.failure_policy(&plot_id),
+            Some(FailurePolicy::ContinueIndependent)
+        );
+        assert_eq!(
+            workflow.failure_policy(&sweep_id),
+            Some(FailurePolicy::SkipDescendants)
+        );
+        for id in [&calibrate_id, &sweep_id, &side_id] {
+            workflow.mark_completed(id).unwrap();
+        }
+        workflow.mark_failed(&plot_id).unwrap();
+        workflow.update_status();
+        assert_eq!(workflow.status, WorkflowStatus::Completed);
+        assert_eq!(workflow.unsuccessful_jobs(), vec![&plot_id]);
+
+        // A fatal failure ends the workflow with jobs still unfinished
+        let mut workflow = build(FailurePolicy::SkipDescendants);
+        workflow
+            .set_failure_policy(&calibrate_id, FailurePolicy::FailWorkflow)
+            .unwrap();
+        workflow.mark_failed(&calibrate_id).unwrap();
+        workflow.update_status();
+        assert!(matches!(workflow.status, WorkflowStatus::Failed { .. }));
+        assert_eq!(workflow.unfinished_jobs(), vec![&side_id]);
+        assert_eq!(
+            workflow.unsuccessful_jobs(),
+            vec![&calibrate_id, &sweep_id, &plot_id]
+        );
+
+        let json = serde_json::to_string(&workflow).unwrap();
+        let back: Workflow = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            back.failure_policy(&calibrate_id),
+            Some(FailurePolicy::FailWorkflow)
+        );
+        assert!(
+            workflow
+                .set_failure_policy(&ScheduledJobId::new(), FailurePolicy::FailWorkflow)
+                .is_err()
+        );
+        assert!(
+            WorkflowBuilder::new("empty")
+                .on_failure(FailurePolicy::FailWorkflow)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_workflow_loop_node() {
+        let prepare = make_job("prepare");
+        let evaluate = make_job("evaluate");
+        let update = make_job("update");
+        let after = make_job("after");
+        let (prepare_id, evaluate_id, update_id) =
+            (prepare.id.clone(), evaluate.id.clone(), update.id.clone());
+        let body = vec![evaluate_id.clone(), update_id.clone()];
+
+        let workflow = WorkflowBuilder::new("vqe")
+            .add_job(prepare)
+            .then(evaluate)
+            .unwrap()
+            .then(update)
+            .unwrap()
+            .loop_over("optimize", LoopNode::new(body, 5).with_condition("done"))
+            .unwrap()
+            .then(after)
+            .unwrap()
+            .build();
+
+        let loops = workflow.loops();
+        assert_eq!(loops.len(), 1);
+        let (loop_job, node) = loops[0];
+        assert_eq!(node.condition.as_deref(), Some("done"));
+        // The loop node waits on the last job of the body only.
+        assert_eq!(workflow.dependencies(&loop_job.id), vec![&update_id]);
+        assert_eq!(workflow.dependents(&loop_job.id).len(), 1);
+
+        let restored: Workflow =
+            serde_json::from_str(&serde_json::to_string(&workflow).unwrap()).unwrap();
+        assert_eq!(restored.loops()[0].1, node);
+
+        let mut workflow = Workflow::new("invalid");
+        workflow.add_job(make_job("job"));
+        assert!(
+            workflow
+                .add_loop("empty", LoopNode::new(Vec::new(), 5))
+                .is_err()
+        );
+        assert!(
+            workflow
+                .add_loop("never", LoopNode::new(vec![prepare_id], 0))
+                .is_err()
+        );
+        assert!(
+            workflow
+                .add_loop("unknown", LoopNode::new(vec![evaluate_id], 5))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_workflow_map_reduce() {
+        let groups = ["zz", "xx", "yy"];
+
+        let builder = WorkflowBuilder::new("estimate")
+            .add_job(make_job("prepare"))
+            .map(groups, |group| make_job(&format!("measure_{}", group)))
+            .unwrap();
+        let Err(SchedError::InvalidDependency(_)) = builder.then(make_job("early")) else {
+            panic!("then must not follow an unreduced map");
+        };
+
+        let sum = make_job("sum");
+        let sum_id = sum.id.clone();
+        let workflow = WorkflowBuilder::new("estimate")
+            .add_job(make_job("prepare"))
+            .map(groups, |group| make_job(&format!("measure_{}", group)))
+            .unwrap()
+            .reduce(sum)
+            .unwrap()
+            .then(make_job("report"))
+            .unwrap()
+            .build();
+        assert_eq!(workflow.len(), 6);
+
+        // The reduce job waits on every mapped job, in input order.
+        let sum = workflow.get_job(&sum_id).unwrap();
+        let names: Vec<_> = sum
+            .dependencies
+            .iter()
+            .map(|id| workflow.get_job(id).unwrap().name.as_str())
+            .collect();
+        assert_eq!(names, ["measure_zz", "measure_xx", "measure_yy"]);
+        for id in &sum.dependencies {
+            assert_eq!(workflow.dependencies(id).len(), 1);
+        }
+        assert_eq!(workflow.dependents(&sum_id).len(), 1);
+
+        assert!(
+            WorkflowBuilder::new("empty")
+                .map(Vec::<u32>::new(), |_| make_job("never"))
+                .is_err()
+        );
+        assert!(
+            WorkflowBuilder::new("lone")
+                .reduce(make_job("sum"))
+                .is_err()
+        );
+        assert!(
+            WorkflowBuilder::new("once")
+                .add_job(make_job("prepare"))
+                .map([1], |_| make_job("measure"))
+                .unwrap()
+                .reduce(make_job("sum"))
+                .unwrap()
+                .reduce(make_job("again"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_workflow_dependency_kinds() {
+        let main = make_job("main");
+        let on_success = make_job("on_success");
+        let on_failure = make_job("on_failure");
+        let cleanup = make_job("cleanup");
+
+        let main_id = main.id.clone();
+        let on_success_id = on_success.id.clone();
+        let on_failure_id = on_failure.id.clone();
+        let cleanup_id = cleanup.id.clone();
+
+        let mut workflow = WorkflowBuilder::new("kinds")
+            .add_job(main)
+            .add_job_after(on_success, &main_id)
+            .unwrap()
+            .add_job_after_with(on_failure, &main_id, DependencyKind::AfterNotOk)
+            .unwrap()
+            .add_job_after_with(cleanup, &main_id, DependencyKind::AfterAny)
+            .unwrap()
+            .build();
+
+        assert_eq!(
+            workflow.dependency_kind(&main_id, &on_failure_id),
+            Some(DependencyKind::AfterNotOk)
+        );
+        assert_eq!(
+            workflow.get_job(&on_failure_id).unwrap().dependencies,
+            vec![main_id.clone()]
+        );
+
+        workflow.mark_failed(&main_id).unwrap();
+        let ready: Vec<_> = workflow.ready_jobs().iter().map(|j| j.id.clone()).collect();
+        assert_eq!(ready.len(), 2);
+        assert!(ready.contains(&on_failure_id));
+        assert!(ready.contains(&cleanup_id));
+
+        assert_eq!(workflow.skip_unsatisfiable(), vec![on_success_id]);
+        assert_eq!(workflow.skipped_count(), 1);
+    }
+
+    #[test]
+    fn test_workflow_error_handler_skipped_on_success() {
+        let main = make_job("main");
+        let handler = make_job("handler");
+        let main_id = main.id.clone();
+
+        let mut workflow = WorkflowBuilder::new("handler")
+            .add_job(main)
+            .then_with(handler, DependencyKind::AfterNotOk)
+            .unwrap()
+            .build();
+
+        workflow.mark_completed(&main_id).unwrap();
+        workflow.update_status();
+
+        assert!(workflow.is_complete());
+        assert_eq!(workflow.status, WorkflowStatus::Completed);
+        assert_eq!(workflow.skipped_count(), 1);
+    }
+
+    #[test]
+    fn test_workflow_n_of_m() {
+        let shards: Vec<_> = (0..3).map(|i| make_job(&format!("shard{}", i))).collect();
+        let shard_ids: Vec<_> = shards.iter().map(|j| j.id.clone()).collect();
+        let reduce = make_job("reduce");
+        let reduce_id = reduce.id.clone();
+
+        let mut builder = WorkflowBuilder::new("quorum");
+        for shard in shards {
+            builder = builder.add_job(shard);
+        }
+        let mut workflow = builder
+            .add_job_after_n_of(reduce, &shard_ids, 2)
+            .unwrap()
+            .build();
+
+        workflow.mark_completed(&shard_ids[0]).unwrap();
+        workflow.mark_failed(&shard_ids[1]).unwrap();
+        assert!(!workflow.ready_jobs().iter().any(|j| j.id == reduce_id));
+
+        workflow.mark_completed(&shard_ids[2]).unwrap();
+        assert!(workflow.ready_jobs().iter().any(|j| j.id == reduce_id));
+
+        assert!(
+            WorkflowBuilder::new("bad")
+                .add_job_after_n_of(make_job("x"), &shard_ids, 4)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_workflow_progress() {
+        let a = make_job("a");
+        let b = make_job("b");
+        let c = make_job("c");
+        let d = make_job("d");
+        let (a_id, b_id, c_id, d_id) = (a.id.clone(), b.id.clone(), c.id.clone(), d.id.clone());
+        let mut workflow = WorkflowBuilder::new("diamond")
+            .add_job(a)
+            .add_job_after(b, &a_id)
+            .unwrap()
+            .add_job_after(c, &a_id)
+            .unwrap()
+            .add_job_after(d, &b_id)
+            .unwrap()
+            .build();
+        workflow.add_dependency(&c_id, &d_id).unwrap();
+
+        let estimate = |job: &ScheduledJob| match job.name.as_str() {
+            "a" => 5.0,
+            "b" => 10.0,
+            "c" => 30.0,
+            _ => 20.0,
+        };
+
+        let progress = workflow.progress_with(estimate);
+        assert_eq!(progress.total, 4);
+        assert_eq!(progress.pending, 4);
+        assert_eq!(progress.percent_complete, 0.0);
+        assert_eq!(progress.remaining_secs, 55.0);
+        assert!(progress.eta.is_some());
+
+        let mut done = workflow.get_job(&a_id).unwrap().clone();
+        done.status = ScheduledJobStatus::Completed {
+            slurm_job_id: "1".into(),
+            quantum_job_id: arvak_hal::JobId::new("q1"),
+        };
+        assert!(workflow.refresh_job(done));
+        let mut running = workflow.get_job(&c_id).unwrap().clone();
+        running.status = ScheduledJobStatus::SlurmRunning {
+            slurm_job_id: "2".into(),
+        };
+        assert!(workflow.refresh_job(running));
+        assert!(!workflow.refresh_job(make_job("stranger")));
+
+        let progress = workflow.progress_with(estimate);
+        assert_eq!(
+            (progress.completed, progress.running, progress.pending),
+            (1, 1, 2)
+        );
+        assert_eq!(progress.percent_complete, 25.0);
+        assert_eq!(progress.remaining_secs, 50.0);
+
+        // The DAG survives a round trip through the store's JSON encoding.
+        let json = serde_json::to_string(&workflow).unwrap();
+        let restored: Workflow = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.len(), 4);
+        assert_eq!(restored.dependencies(&d_id).len(), 2);
+        assert_eq!(restored.progress_with(estimate).remaining_secs, 50.0);
+    }
+
+    #[test]
+    fn test_workflow_to_dot() {
+        let mut workflow = Workflow::new("debug \"stuck\"");
+        let prepare = make_job("prepare");
+        let measure = make_job("measure");
+        let cleanup = make_job("cleanup");
+        let (prepare_id, measure_id, cleanup_id) =
+            (prepare.id.clone(), measure.id.clone(), cleanup.id.clone());
+        workflow.add_job(prepare.clone());
+        workflow.add_job(measure);
+        workflow.add_job(cleanup);
+        workflow.add_dependency(&prepare_id, &measure_id).unwrap();
+        workflow
+            .add_dependency_with(&prepare_id, &cleanup_id, DependencyKind::AfterNotOk)
+            .unwrap();
+
+        let mut failed = prepare;
+        failed.status = ScheduledJobStatus::Failed {
+            reason: "sbatch: invalid partition".to_string(),
+            kind: crate::retry::FailureKind::Submission,
+            slurm_job_id: None,
+            quantum_job_id: None,
+        };
+        workflow.refresh_job(failed);
+        workflow.update_status();
+
+        let dot = workflow.to_dot();
+        assert!(
+            dot.starts_with("digraph \"debug \\\"stuck\\\"\" {"),
+            "{}",
+            dot
+        );
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"prepare\\nFailed\", fillcolor=\"#f4978e\", style=\"rounded,filled\", \
+             tooltip=\"sbatch: invalid partition\"];",
+            prepare_id
+        )));
+        assert!(dot.contains("\"measure\\nSkipped\""));
+        assert!(dot.contains("rounded,filled,dashed"));
+        assert!(dot.contains(&format!("\"{}\" -> \"{}\";", prepare_id, measure_id)));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [style=dotted, color=\"#c0392b\", label=\"afternotok\"];",
+            prepare_id, cleanup_id
+        )));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn test_sub_workflow_node() {
+        assert!(SubWorkflow::new(Workflow::new("empty")).is_err());
+
+        let inner = WorkflowBuilder::new("mitigate")
+            .add_job(make_job("run"))
+            .then(make_job("mitigate"))
+            .unwrap()
+            .build();
+        let inner_id = inner.id.clone();
+        let outer = WorkflowBuilder::new("sweep")
+            .add_job(make_job("prepare"))
+            .then_workflow(inner)
+            .unwrap()
+            .build();
+        assert_eq!(outer.len(), 2);
+        assert!(outer.parent.is_none());
+
+        let node = outer
+            .all_jobs()
+            .into_iter()
+            .find(|job| job.name == "mitigate")
+            .unwrap();
+        assert_eq!(outer.dependencies(&node.id).len(), 1);
+        let Some(ClassicalTask::Workflow(sub)) = &node.task else {
+            panic!("node does not run a sub-workflow");
+        };
+        assert_eq!(sub.workflow.id, inner_id);
+        assert_eq!(sub.workflow.len(), 2);
+
+        // The sub-workflow is stored with its node
+        let json = serde_json::to_string(&outer).unwrap();
+        let back: Workflow = serde_json::from_str(&json).unwrap();
+        let Some(ClassicalTask::Workflow(sub)) = &back.get_job(&node.id).unwrap().task else {
+            panic!("sub-workflow lost");
+        };
+        assert_eq!(sub.workflow.topological_order()[0].name, "run");
+    }
 }
@@ -80,23 +80,19 @@ fn lumi_slurm_config() -> SlurmConfig {
         memory_mb: 4096,
         cpus_per_task: 1,
         work_dir: PathBuf::from("/tmp/arvak-lumi-test"),
-        arvak_binary: PathBuf::from("arvak"),
         modules: vec!["iqm-client".to_string()],
-        python_venv: None,
-        priority_qos_mapping: None,
+        ..Default::default()
     }
 }
 
 /// Create LUMI scheduler configuration.
 fn lumi_scheduler_config() -> SchedulerConfig {
     SchedulerConfig {
-        scheduler_type: BatchSchedulerType::Slurm,
-        slurm: lumi_slurm_config(),
-        pbs: PbsConfig::default(),
         poll_interval_secs: 5,
         max_wait_time_secs: 1800, // 30 minutes
         auto_match_resources: true,
         state_dir: PathBuf::from("/tmp/arvak-lumi-test/state"),
+        ..SchedulerConfig::with_slurm(lumi_slurm_config())
     }
 }
 
@@ -267,10 +263,8 @@ async fn test_pbs_scheduler_creation() {
             work_dir: PathBuf::from("/tmp/arvak-pbs-test"),
             arvak_binary: PathBuf::from("arvak"),
             modules: vec!["quantum-toolkit".to_string()],
-            python_venv: None,
             server: Some("pbs-server.local".to_string()),
-            extra_directives: Vec::new(),
-            priority_queue_mapping: None,
+            ..Default::default()
         },
         ..Default::default()
     };
@@ -0,0 +1,141 @@
+//! Redis Store Integration Tests
+//!
+//! These tests run `RedisStore` against a live Redis server. They are
+//! ignored by default; start a server and run:
+//!
+//! ```text
+//! docker run --rm -p 6379:6379 redis:7
+//! cargo test -p arvak-sched --test redis_store -- --ignored
+//! ```
+//!
+//! The server is taken from `ARVAK_TEST_REDIS_URL`, defaulting to
+//! `redis://127.0.0.1:6379`. Each test writes under its own key prefix.
+
+use std::time::Duration;
+
+use arvak_hal::{Counts, ExecutionResult};
+use arvak_sched::{
+    CircuitSpec, JobFilter, LeaseStore, RedisConfig, RedisStore, ScheduledJob, ScheduledJobStatus,
+    StateStore,
+};
+
+/// Connect with keys under a prefix no other test run uses.
+async fn connect(test: &str, ttl: Option<Duration>) -> RedisStore {
+    let url = std::env::var("ARVAK_TEST_REDIS_URL")
+        .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
+    let prefix = format!(
+        "arvak-it:{}:{}:{}",
+        test,
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis()
+    );
+    RedisStore::connect(RedisConfig::new(url).with_key_prefix(prefix).with_ttl(ttl))
+        .await
+        .expect("Redis server at ARVAK_TEST_REDIS_URL")
+}
+
+fn job(name: &str) -> ScheduledJob {
+    ScheduledJob::new(
+        name,
+        CircuitSpec::from_qasm("OPENQASM 3.0; qubit[1] q; h q[0];"),
+    )
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server (ARVAK_TEST_REDIS_URL)"]
+async fn test_job_lifecycle() {
+    let store = connect("lifecycle", None).await;
+
+    let submitted = job("lifecycle").with_request_id("req-1");
+    store.save_job(&submitted).await.unwrap();
+    let loaded = store.load_job(&submitted.id).await.unwrap().unwrap();
+    assert_eq!(loaded.name, "lifecycle");
+    assert_eq!(
+        store.find_job_by_ref("req-1").await.unwrap(),
+        Some(submitted.id.clone())
+    );
+
+    store
+        .update_status(&submitted.id, ScheduledJobStatus::Cancelled)
+        .await
+        .unwrap();
+    let loaded = store.load_job(&submitted.id).await.unwrap().unwrap();
+    assert_eq!(loaded.status, ScheduledJobStatus::Cancelled);
+    assert!(loaded.completed_at.is_some());
+
+    let result = ExecutionResult::new(Counts::from_pairs([("0", 5u64), ("1", 5)]), 10);
+    store.save_result(&submitted.id, &result).await.unwrap();
+    assert!(store.load_result(&submitted.id).await.unwrap().is_some());
+
+    let listed = store.list_jobs(&JobFilter::default()).await.unwrap();
+    assert_eq!(listed.len(), 1);
+
+    assert!(store.delete_job(&submitted.id).await.unwrap());
+    assert!(store.delete_result(&submitted.id).await.unwrap());
+    assert!(store.load_job(&submitted.id).await.unwrap().is_none());
+    assert_eq!(store.find_job_by_ref("req-1").await.unwrap(), None);
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server (ARVAK_TEST_REDIS_URL)"]
+async fn test_listing_many_jobs() {
+    let store = connect("many", None).await;
+
+    // More jobs than one MGET fetches, so listing pipelines several
+    for i in 0..1200 {
+        store.save_job(&job(&format!("job-{}", i))).await.unwrap();
+    }
+    let listed = store.list_jobs(&JobFilter::default()).await.unwrap();
+    assert_eq!(listed.len(), 1200);
+    assert_eq!(store.rebuild_indices().await.unwrap(), 1200);
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server (ARVAK_TEST_REDIS_URL)"]
+async fn test_finished_jobs_expire() {
+    let store = connect("expiry", Some(Duration::from_millis(200))).await;
+
+    let running = job("running");
+    let mut finished = job("finished");
+    finished.status = ScheduledJobStatus::Cancelled;
+    store.save_job(&running).await.unwrap();
+    store.save_job(&finished).await.unwrap();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(store.load_job(&running.id).await.unwrap().is_some());
+    assert!(store.load_job(&finished.id).await.unwrap().is_none());
+    let listed = store.list_jobs(&JobFilter::default()).await.unwrap();
+    assert_eq!(listed.len(), 1);
+}
+
+#[tokio::test]
+#[ignore = "requires a Redis server (ARVAK_TEST_REDIS_URL)"]
+async fn test_lease_fencing() {
+    let store = connect("lease", None).await;
+    let ttl = Duration::from_millis(300);
+
+    let first = store
+        .try_acquire_lease("leader", "a", ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        store.try_acquire_lease("leader", "b", ttl).await.unwrap(),
+        None
+    );
+    // Renewing keeps the fencing token
+    assert_eq!(
+        store.try_acquire_lease("leader", "a", ttl).await.unwrap(),
+        Some(first)
+    );
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let second = store
+        .try_acquire_lease("leader", "b", ttl)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(second > first);
+    assert!(!store.holds_lease("leader", "a", first).await.unwrap());
+    assert!(store.holds_lease("leader", "b", second).await.unwrap());
+}
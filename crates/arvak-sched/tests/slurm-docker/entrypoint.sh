@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Start munge, MariaDB, slurmdbd, slurmctld and slurmd in one container.
+set -euo pipefail
+
+mkdir -p /run/munge /var/spool/slurmctld /var/spool/slurmd /var/log/slurm
+chown munge:munge /run/munge
+[ -f /etc/munge/munge.key ] || mungekey --create
+runuser -u munge -- munged
+
+mysqld_safe >/dev/null 2>&1 &
+until mysqladmin ping --silent; do sleep 1; done
+mysql <<'SQL'
+CREATE DATABASE IF NOT EXISTS slurm_acct_db;
+CREATE USER IF NOT EXISTS 'slurm'@'localhost' IDENTIFIED BY 'slurm';
+GRANT ALL ON slurm_acct_db.* TO 'slurm'@'localhost';
+SQL
+
+slurmdbd
+until sacctmgr -n list cluster >/dev/null 2>&1; do sleep 1; done
+sacctmgr -i add cluster arvak >/dev/null || true
+
+slurmctld
+slurmd
+
+exec tail -F /var/log/slurm/slurmctld.log /var/log/slurm/slurmd.log /var/log/slurm/slurmdbd.log
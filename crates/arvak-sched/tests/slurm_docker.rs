@@ -0,0 +1,366 @@
+//! Slurm-in-Docker Integration Tests
+//!
+//! These tests submit real jobs through `HpcScheduler` to the single-node
+//! Slurm cluster defined in `tests/slurm-docker`, covering completion,
+//! failure accounting, cancellation, native dependencies and recovery after
+//! a scheduler restart.
+//!
+//! They are ignored by default. Run them with `scripts/slurm-it.sh`, or start
+//! the cluster yourself and run:
+//!
+//! ```text
+//! cargo test -p arvak-sched --test slurm_docker -- --ignored --test-threads=1
+//! ```
+//!
+//! Slurm commands run through `docker exec`, and the work directory is
+//! bind-mounted at the same path inside the container. Jobs run a stub
+//! `arvak` binary whose behaviour is picked by the job name.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arvak_hal::{Backend, Capabilities, Counts, ExecutionResult, HalResult, JobId, JobStatus};
+use arvak_ir::Circuit;
+use arvak_sched::{
+    CircuitSpec, HpcScheduler, ScheduledJob, ScheduledJobId, ScheduledJobStatus, Scheduler,
+    SchedulerConfig, SlurmAdapter, SlurmConfig, SqliteStore, StateStore, WorkflowBuilder,
+};
+use async_trait::async_trait;
+use tempfile::TempDir;
+
+/// Stub `arvak` binary run by the batch scripts.
+const STUB_ARVAK: &str = r#"#!/bin/sh
+case "$SLURM_JOB_NAME" in
+  *fail*) sleep 3; exit 3 ;;
+  *sleep*) sleep 120 ;;
+  *slow*) sleep 5 ;;
+esac
+exit 0
+"#;
+
+/// How long a job may take to reach the expected state.
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Simulator the matcher can pick; it is never asked to run anything.
+struct StubBackend;
+
+#[async_trait]
+impl Backend for StubBackend {
+    fn name(&self) -> &str {
+        "sim"
+    }
+
+    async fn capabilities(&self) -> HalResult<Capabilities> {
+        Ok(Capabilities::simulator(8))
+    }
+
+    async fn is_available(&self) -> HalResult<bool> {
+        Ok(true)
+    }
+
+    async fn submit(&self, _circuit: &Circuit, _shots: u32) -> HalResult<JobId> {
+        Ok(JobId::new("stub"))
+    }
+
+    async fn status(&self, _job_id: &JobId) -> HalResult<JobStatus> {
+        Ok(JobStatus::Completed)
+    }
+
+    async fn result(&self, _job_id: &JobId) -> HalResult<ExecutionResult> {
+        Ok(ExecutionResult::new(Counts::from_pairs([("00", 1u64)]), 1))
+    }
+
+    async fn cancel(&self, _job_id: &JobId) -> HalResult<()> {
+        Ok(())
+    }
+}
+
+/// A scratch area on the shared work directory plus matching configuration.
+struct Cluster {
+    dir: TempDir,
+    config: SchedulerConfig,
+}
+
+impl Cluster {
+    /// Check the cluster is up and prepare a fresh work directory.
+    fn connect() -> Self {
+        let container =
+            std::env::var("ARVAK_SLURM_CONTAINER").unwrap_or_else(|_| "arvak-slurm".to_string());
+        let ready = std::process::Command::new("docker")
+            .args(["exec", &container, "sinfo", "-h", "-o", "%t"])
+            .output()
+            .is_ok_and(|out| {
+                out.status.success() && String::from_utf8_lossy(&out.stdout).contains("idle")
+            });
+        assert!(
+            ready,
+            "Slurm container `{container}` is not ready; start it with scripts/slurm-it.sh"
+        );
+
+        let root = std::env::var("ARVAK_SLURM_WORK_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/tmp/arvak-slurm-it"));
+        std::fs::create_dir_all(&root).unwrap();
+        let dir = tempfile::tempdir_in(&root).unwrap();
+
+        let bin = dir.path().join("bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let arvak = bin.join("arvak");
+        std::fs::write(&arvak, STUB_ARVAK).unwrap();
+        std::fs::set_permissions(&arvak, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let slurm = SlurmConfig {
+            partition: "debug".to_string(),
+            time_limit: 5,
+            memory_mb: 100,
+            cpus_per_task: 1,
+            work_dir: dir.path().join("work"),
+            arvak_binary: arvak,
+            command_prefix: vec!["docker".to_string(), "exec".to_string(), container],
+            ..Default::default()
+        };
+        let config = SchedulerConfig {
+            poll_interval_secs: 1,
+            state_dir: dir.path().join("state"),
+            ..SchedulerConfig::with_slurm(slurm)
+        };
+
+        Self { dir, config }
+    }
+
+    /// Persistent store shared by schedulers in the same test.
+    fn store(&self) -> Arc<dyn StateStore> {
+        Arc::new(SqliteStore::new(self.dir.path().join("state.db")).unwrap())
+    }
+
+    /// Create a scheduler and start its background loop.
+    async fn scheduler(
+        &self,
+        store: Arc<dyn StateStore>,
+    ) -> (Arc<HpcScheduler>, tokio::task::JoinHandle<()>) {
+        let backends: Vec<Arc<dyn Backend>> = vec![Arc::new(StubBackend)];
+        let scheduler = Arc::new(
+            HpcScheduler::new(self.config.clone(), backends, store)
+                .await
+                .unwrap(),
+        );
+        let handle = scheduler.clone().start_background_processor();
+        (scheduler, handle)
+    }
+
+    /// Adapter for querying Slurm directly.
+    async fn adapter(&self) -> SlurmAdapter {
+        SlurmAdapter::new(self.config.slurm.clone()).await.unwrap()
+    }
+}
+
+fn job(name: &str) -> ScheduledJob {
+    ScheduledJob::new(
+        name,
+        CircuitSpec::from_qasm("OPENQASM 3.0; qubit[2] q; h q[0]; cx q[0], q[1];"),
+    )
+    .with_shots(100)
+}
+
+/// Poll the scheduler until the job's status satisfies `done`.
+async fn wait_for(
+    scheduler: &HpcScheduler,
+    job_id: &ScheduledJobId,
+    done: impl Fn(&ScheduledJobStatus) -> bool,
+) -> ScheduledJobStatus {
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        let status = scheduler.status(job_id).await.unwrap();
+        if done(&status) {
+            return status;
+        }
+        assert!(
+            Instant::now() < deadline,
+            "job {job_id} stuck in {status:?}"
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+fn is_terminal(status: &ScheduledJobStatus) -> bool {
+    status.is_terminal()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires the dockerized Slurm cluster (scripts/slurm-it.sh)"]
+async fn test_submit_completes_with_accounting() {
+    let cluster = Cluster::connect();
+    let (scheduler, handle) = cluster.scheduler(cluster.store()).await;
+
+    let job_id = scheduler.submit(job("ok")).await.unwrap();
+    let status = wait_for(&scheduler, &job_id, is_terminal).await;
+    let ScheduledJobStatus::Completed { slurm_job_id, .. } = status else {
+        panic!("expected completion, got {status:?}");
+    };
+
+    // Finished jobs leave squeue after MinJobAge; sacct must still know them.
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    let info = cluster.adapter().await.status(&slurm_job_id).await.unwrap();
+    assert!(info.state.is_success());
+    assert_eq!(info.exit_code, Some(0));
+
+    let script = cluster
+        .config
+        .slurm
+        .work_dir
+        .join("scripts")
+        .join(format!("{job_id}.sh"));
+    assert!(script.exists());
+
+    handle.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires the dockerized Slurm cluster (scripts/slurm-it.sh)"]
+async fn test_failed_job_reports_exit_code() {
+    let cluster = Cluster::connect();
+    let (scheduler, handle) = cluster.scheduler(cluster.store()).await;
+
+    let job_id = scheduler.submit(job("fail")).await.unwrap();
+    let status = wait_for(&scheduler, &job_id, is_terminal).await;
+    let ScheduledJobStatus::Failed {
+        slurm_job_id: Some(slurm_job_id),
+        ..
+    } = status
+    else {
+        panic!("expected failure, got {status:?}");
+    };
+
+    let info = cluster.adapter().await.status(&slurm_job_id).await.unwrap();
+    assert!(!info.state.is_success());
+    assert_eq!(info.exit_code, Some(3));
+
+    handle.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires the dockerized Slurm cluster (scripts/slurm-it.sh)"]
+async fn test_cancel_running_job() {
+    let cluster = Cluster::connect();
+    let (scheduler, handle) = cluster.scheduler(cluster.store()).await;
+
+    let job_id = scheduler.submit(job("sleep")).await.unwrap();
+    let status = wait_for(&scheduler, &job_id, |s| {
+        matches!(s, ScheduledJobStatus::SlurmRunning { .. }) || s.is_terminal()
+    })
+    .await;
+    let ScheduledJobStatus::SlurmRunning { slurm_job_id } = status else {
+        panic!("expected a running job, got {status:?}");
+    };
+
+    scheduler.cancel(&job_id).await.unwrap();
+    assert_eq!(
+        scheduler.status(&job_id).await.unwrap(),
+        ScheduledJobStatus::Cancelled
+    );
+
+    let adapter = cluster.adapter().await;
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        let info = adapter.status(&slurm_job_id).await.unwrap();
+        if info.state.is_terminal() {
+            assert!(!info.state.is_success(), "cancelled job ran to completion");
+            break;
+        }
+        assert!(Instant::now() < deadline, "scancel had no effect");
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    handle.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires the dockerized Slurm cluster (scripts/slurm-it.sh)"]
+async fn test_native_dependency_chain() {
+    let cluster = Cluster::connect();
+    let store = cluster.store();
+    let (scheduler, handle) = cluster.scheduler(store.clone()).await;
+
+    let parent = job("slow-parent");
+    let child = job("child");
+    let (parent_id, child_id) = (parent.id.clone(), child.id.clone());
+    let workflow = WorkflowBuilder::new("chain")
+        .add_job(parent)
+        .then(child)
+        .unwrap()
+        .build();
+    scheduler.submit_workflow(workflow).await.unwrap();
+
+    // The child is handed to Slurm while the parent still runs.
+    wait_for(&scheduler, &child_id, |s| s.slurm_job_id().is_some()).await;
+    assert!(!scheduler.status(&parent_id).await.unwrap().is_terminal());
+    let stored = store.load_job(&child_id).await.unwrap().unwrap();
+    let dependency = stored.batch_dependency.expect("child not chained natively");
+    assert!(dependency.contains("afterok:"), "{dependency}");
+
+    assert!(
+        wait_for(&scheduler, &child_id, is_terminal)
+            .await
+            .is_success()
+    );
+    assert!(scheduler.status(&parent_id).await.unwrap().is_success());
+
+    handle.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires the dockerized Slurm cluster (scripts/slurm-it.sh)"]
+async fn test_failed_dependency_cancels_child() {
+    let cluster = Cluster::connect();
+    let (scheduler, handle) = cluster.scheduler(cluster.store()).await;
+
+    let parent = job("fail-parent");
+    let child = job("child");
+    let (parent_id, child_id) = (parent.id.clone(), child.id.clone());
+    let workflow = WorkflowBuilder::new("broken-chain")
+        .add_job(parent)
+        .then(child)
+        .unwrap()
+        .build();
+    scheduler.submit_workflow(workflow).await.unwrap();
+
+    let parent_status = wait_for(&scheduler, &parent_id, is_terminal).await;
+    assert!(matches!(parent_status, ScheduledJobStatus::Failed { .. }));
+    assert_eq!(
+        wait_for(&scheduler, &child_id, is_terminal).await,
+        ScheduledJobStatus::Cancelled
+    );
+
+    handle.abort();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires the dockerized Slurm cluster (scripts/slurm-it.sh)"]
+async fn test_recovery_after_restart() {
+    let cluster = Cluster::connect();
+
+    let job_id = {
+        let (scheduler, handle) = cluster.scheduler(cluster.store()).await;
+        let job_id = scheduler.submit(job("slow")).await.unwrap();
+        wait_for(&scheduler, &job_id, |s| s.slurm_job_id().is_some()).await;
+        handle.abort();
+        job_id
+    };
+
+    // A new scheduler on the same store picks the job up where Slurm left it.
+    let (scheduler, handle) = cluster.scheduler(cluster.store()).await;
+    let status = wait_for(&scheduler, &job_id, is_terminal).await;
+    assert!(status.is_success(), "{status:?}");
+
+    // Jobs submitted after the restart still go through.
+    let job_id = scheduler.submit(job("after-restart")).await.unwrap();
+    assert!(
+        wait_for(&scheduler, &job_id, is_terminal)
+            .await
+            .is_success()
+    );
+
+    handle.abort();
+}
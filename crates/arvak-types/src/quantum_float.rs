@@ -233,7 +233,7 @@ impl<const M: usize, const E: usize> QuantumFloat<M, E> {
         }
 
         // Scale mantissa to our bit width
-        let mantissa_bits = ieee_mantissa >> (52 - M).max(0);
+        let mantissa_bits = ieee_mantissa >> (52 - M);
 
         Ok((mantissa_bits, our_exp as u64))
     }
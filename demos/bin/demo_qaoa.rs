@@ -25,6 +25,11 @@ struct Args {
     /// Maximum optimization iterations
     #[arg(short, long, default_value = "100")]
     iterations: usize,
+
+    /// Stop when improvement over moving averages of this many iterations
+    /// is within the shot noise
+    #[arg(long)]
+    convergence_window: Option<usize>,
 }
 
 fn main() {
@@ -65,6 +70,14 @@ fn main() {
         format!("{:?} | {:?}", exact_s, exact_t),
     );
 
+    print_section("Classical Baselines");
+    for baseline in [
+        graph.hyperplane_rounding_cut(100, 42),
+        graph.annealing_cut(1000, 42),
+    ] {
+        print_result(&baseline.solver.to_string(), baseline.value);
+    }
+
     print_section("Max-Cut Problem");
     println!("  The Max-Cut problem: Partition graph nodes into two sets");
     println!("  to maximize the number of edges between the sets.");
@@ -82,9 +95,12 @@ fn main() {
     println!("  2. Mixer unitary: exp(-iβB) exploring solutions");
     println!();
 
-    let runner = QaoaRunner::new(graph.clone())
+    let mut runner = QaoaRunner::new(graph.clone())
         .with_layers(args.layers)
         .with_maxiter(args.iterations);
+    if let Some(window) = args.convergence_window {
+        runner = runner.with_shot_noise_convergence(window);
+    }
 
     let pb = create_progress_bar(args.iterations as u64, "Optimizing...");
 
@@ -97,6 +113,13 @@ fn main() {
 
     print_result("Best cut found", result.best_cut);
     print_result("Best partition", format!("{:?} | {:?}", found_s, found_t));
+    print_result(
+        "Expected cut",
+        format!(
+            "{:.3} ± {:.3}",
+            result.expected_cut, result.expected_cut_std_error
+        ),
+    );
     print_result(
         "Approximation ratio",
         format!("{:.1}%", result.approximation_ratio * 100.0),
@@ -31,6 +31,15 @@ struct Args {
     /// Number of shots per energy evaluation
     #[arg(short, long, default_value = "1024")]
     shots: u32,
+
+    /// Stop when improvement over moving averages of this many iterations
+    /// is within the shot noise
+    #[arg(long)]
+    convergence_window: Option<usize>,
+
+    /// Drop Hamiltonian terms with coefficient magnitude below this value
+    #[arg(long)]
+    truncate: Option<f64>,
 }
 
 fn main() {
@@ -96,10 +105,17 @@ fn main() {
     println!();
 
     // Create and run VQE
-    let runner = VqeRunner::new(hamiltonian)
+    let mut runner = VqeRunner::new(hamiltonian)
         .with_reps(args.reps)
         .with_shots(args.shots)
         .with_maxiter(args.iterations);
+    if let Some(window) = args.convergence_window {
+        runner = runner.with_shot_noise_convergence(window);
+    }
+    if let Some(epsilon) = args.truncate {
+        runner = runner.with_truncation(epsilon);
+        print_result("Terms after truncation", runner.hamiltonian.num_terms());
+    }
 
     let num_params = runner.num_parameters();
     print_result("Parameters", num_params);
@@ -114,10 +130,14 @@ fn main() {
     print_section("Results");
     print_result(
         "Optimal energy",
-        format!("{:.6} Hartree", result.optimal_energy),
+        format!(
+            "{:.6} ± {:.6} Hartree",
+            result.optimal_energy, result.optimal_std_error
+        ),
     );
     print_result("Iterations", result.iterations);
     print_result("Circuit evaluations", result.circuit_evaluations);
+    print_result("Circuits per evaluation", result.measurement_bases);
     print_result("Converged", if result.converged { "Yes" } else { "No" });
 
     if let Some(exact) = exact_energy {
@@ -6,6 +6,8 @@
 use arvak_ir::Circuit;
 use arvak_ir::qubit::QubitId;
 
+use crate::problems::{MeasurementBasis, Pauli};
+
 /// Generate a TwoLocal ansatz circuit.
 ///
 /// The TwoLocal ansatz alternates between:
@@ -186,6 +188,29 @@ pub fn uccsd_like_ansatz(n_qubits: usize, params: &[f64]) -> Circuit {
     circuit
 }
 
+/// Append basis rotations and measurements to an ansatz so that every term
+/// of `basis` can be read off the Z-basis outcomes.
+///
+/// X is rotated with H and Y with S†·H; Z and unlisted qubits are measured
+/// directly.
+pub fn measurement_circuit(ansatz: &Circuit, basis: &MeasurementBasis) -> Circuit {
+    let mut circuit = ansatz.clone();
+    for &(q, pauli) in &basis.paulis {
+        let qubit = QubitId(q as u32);
+        match pauli {
+            Pauli::X => {
+                circuit.h(qubit).unwrap();
+            }
+            Pauli::Y => {
+                circuit.sdg(qubit).unwrap().h(qubit).unwrap();
+            }
+            Pauli::I | Pauli::Z => {}
+        }
+    }
+    circuit.measure_all().unwrap();
+    circuit
+}
+
 /// Calculate the number of parameters needed for a given ansatz.
 pub fn num_parameters(ansatz: &str, n_qubits: usize, reps: usize) -> usize {
     match ansatz {
@@ -234,4 +259,18 @@ mod tests {
         assert_eq!(num_parameters("hardware_efficient", 4, 2), 24);
         assert_eq!(num_parameters("ry", 4, 0), 4);
     }
+
+    #[test]
+    fn test_measurement_circuit() {
+        let ansatz = ry_ansatz(2, &[0.1, 0.2]);
+        let basis = MeasurementBasis {
+            paulis: vec![(0, Pauli::X), (1, Pauli::Y)],
+            terms: vec![0],
+        };
+        let circuit = measurement_circuit(&ansatz, &basis);
+
+        // RY on each qubit, H on q0, S†·H on q1, then a joint measurement.
+        assert_eq!(circuit.dag().num_ops(), 2 + 3 + 1);
+        assert_eq!(ansatz.dag().num_ops(), 2);
+    }
 }
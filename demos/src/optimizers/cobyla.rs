@@ -3,7 +3,7 @@
 //! This is a derivative-free optimization algorithm suitable for
 //! variational quantum algorithms where gradients are expensive.
 
-use super::Optimizer;
+use super::{ConvergenceCriterion, Optimizer};
 
 /// Result of an optimization run.
 #[derive(Debug, Clone)]
@@ -73,7 +73,12 @@ impl Cobyla {
 }
 
 impl Optimizer for Cobyla {
-    fn minimize<F>(&self, mut objective: F, initial_params: Vec<f64>) -> OptimizationResult
+    fn minimize_until<F>(
+        &self,
+        mut objective: F,
+        initial_params: Vec<f64>,
+        criterion: &mut dyn ConvergenceCriterion,
+    ) -> OptimizationResult
     where
         F: FnMut(&[f64]) -> f64,
     {
@@ -229,6 +234,11 @@ impl Optimizer for Cobyla {
                 f_x = f_simplex[min_idx];
                 history.push(f_x);
             }
+
+            if criterion.observe(f_simplex[min_idx]) {
+                converged = true;
+                break;
+            }
         }
 
         // Find best point
@@ -294,7 +304,12 @@ impl Spsa {
 }
 
 impl Optimizer for Spsa {
-    fn minimize<F>(&self, mut objective: F, initial_params: Vec<f64>) -> OptimizationResult
+    fn minimize_until<F>(
+        &self,
+        mut objective: F,
+        initial_params: Vec<f64>,
+        criterion: &mut dyn ConvergenceCriterion,
+    ) -> OptimizationResult
     where
         F: FnMut(&[f64]) -> f64,
     {
@@ -303,6 +318,7 @@ impl Optimizer for Spsa {
         let mut f_x = objective(&x);
         let mut history = vec![f_x];
         let mut num_evaluations = 1;
+        let mut num_iterations = 0;
 
         // Simple LCG for reproducible randomness
         let mut rand_state: u64 = 42;
@@ -344,14 +360,19 @@ impl Optimizer for Spsa {
 
             f_x = objective(&x);
             num_evaluations += 1;
+            num_iterations += 1;
             history.push(f_x);
+
+            if criterion.observe(f_x) {
+                break;
+            }
         }
 
         OptimizationResult {
             optimal_params: x,
             optimal_value: f_x,
             num_evaluations,
-            num_iterations: self.maxiter,
+            num_iterations,
             history,
             converged: true,
         }
@@ -411,4 +432,31 @@ mod tests {
 
         assert!(result.optimal_value < 0.5);
     }
+
+    #[test]
+    fn test_cobyla_stops_on_shot_noise() {
+        use crate::optimizers::ShotNoiseCriterion;
+
+        // Quadratic bowl plus deterministic "shot noise" of amplitude 0.03,
+        // which keeps the simplex spread above the raw tolerance.
+        let noisy = || {
+            let mut calls = 0u64;
+            move |params: &[f64]| {
+                calls += 1;
+                let noise = 0.03 * ((calls as f64) * 12.9898).sin();
+                (params[0] - 1.0).powi(2) + (params[1] - 2.0).powi(2) + noise
+            }
+        };
+
+        let cobyla = Cobyla::new().with_maxiter(500);
+        let raw = cobyla.minimize(noisy(), vec![0.0, 0.0]);
+        assert!(!raw.converged);
+
+        let mut criterion = ShotNoiseCriterion::from_shots(1000, 1.0);
+        let result = cobyla.minimize_until(noisy(), vec![0.0, 0.0], &mut criterion);
+        assert!(result.converged);
+        assert!(result.num_evaluations < raw.num_evaluations / 10);
+        assert!((result.optimal_params[0] - 1.0).abs() < 0.2);
+        assert!((result.optimal_params[1] - 2.0).abs() < 0.2);
+    }
 }
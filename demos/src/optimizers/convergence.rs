@@ -0,0 +1,130 @@
+//! Convergence criteria for optimizers fed by noisy objective values.
+//!
+//! An expectation value estimated from `shots` measurements fluctuates with
+//! a standard error of about `σ / √shots`, where `σ` is the single-shot
+//! standard deviation. Raw tolerance checks mistake these fluctuations for
+//! progress (or lack of it). [`ShotNoiseCriterion`] instead compares moving
+//! averages of consecutive windows and declares convergence once their
+//! difference is within the shot noise.
+
+use std::collections::VecDeque;
+
+/// Decides when an optimization has converged.
+pub trait ConvergenceCriterion {
+    /// Record the objective value reached in an iteration and report
+    /// whether the optimization has converged.
+    fn observe(&mut self, value: f64) -> bool;
+}
+
+/// Closures act as criteria, e.g. `|_| false` never converges.
+impl<F: FnMut(f64) -> bool> ConvergenceCriterion for F {
+    fn observe(&mut self, value: f64) -> bool {
+        self(value)
+    }
+}
+
+/// Stops once improvement is statistically insignificant under shot noise.
+///
+/// The last `2 * window` values are split into an older and a newer window.
+/// With `se` the standard error of one value, the difference of the window
+/// means has standard error `se * √(2 / window)`; the optimization has
+/// converged when the improvement is below `confidence` times that.
+#[derive(Debug, Clone)]
+pub struct ShotNoiseCriterion {
+    /// Values per moving-average window.
+    pub window: usize,
+    /// Standard error of a single objective value.
+    pub std_error: f64,
+    /// Significance threshold in standard errors.
+    pub confidence: f64,
+    values: VecDeque<f64>,
+}
+
+impl ShotNoiseCriterion {
+    /// Create a criterion for values estimated from `shots` measurements
+    /// with single-shot standard deviation `shot_std`.
+    pub fn from_shots(shots: u32, shot_std: f64) -> Self {
+        Self::with_std_error(shot_std / f64::from(shots.max(1)).sqrt())
+    }
+
+    /// Create a criterion from the standard error of one value.
+    pub fn with_std_error(std_error: f64) -> Self {
+        Self {
+            window: 5,
+            std_error,
+            confidence: 2.0,
+            values: VecDeque::new(),
+        }
+    }
+
+    /// Set the number of values per window.
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Set the significance threshold in standard errors.
+    pub fn with_confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Improvement of the newer window mean over the older one, once both
+    /// windows are full.
+    pub fn improvement(&self) -> Option<f64> {
+        if self.values.len() < 2 * self.window {
+            return None;
+        }
+        let older: f64 = self.values.iter().take(self.window).sum();
+        let newer: f64 = self.values.iter().skip(self.window).sum();
+        Some((older - newer) / self.window as f64)
+    }
+
+    /// Smallest improvement that counts as significant.
+    pub fn threshold(&self) -> f64 {
+        self.confidence * self.std_error * (2.0 / self.window as f64).sqrt()
+    }
+}
+
+impl ConvergenceCriterion for ShotNoiseCriterion {
+    fn observe(&mut self, value: f64) -> bool {
+        self.values.push_back(value);
+        while self.values.len() > 2 * self.window {
+            self.values.pop_front();
+        }
+        self.improvement()
+            .is_some_and(|improvement| improvement < self.threshold())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shot_noise_criterion() {
+        let mut criterion = ShotNoiseCriterion::from_shots(100, 1.0).with_window(3);
+        assert!((criterion.std_error - 0.1).abs() < 1e-12);
+
+        // Steady progress well above the noise keeps going.
+        for i in 0..10 {
+            assert!(!criterion.observe(-(i as f64)));
+        }
+
+        // Fluctuations within the noise converge once both windows fill.
+        let noise = [0.05, -0.05, 0.08, -0.02, 0.0, 0.03];
+        let converged: Vec<bool> = noise
+            .iter()
+            .map(|&n| criterion.observe(-10.0 + n))
+            .collect();
+        assert_eq!(converged, vec![false, false, false, false, false, true]);
+        assert!(criterion.improvement().unwrap().abs() < criterion.threshold());
+    }
+
+    #[test]
+    fn test_closure_criterion() {
+        let mut below = |value: f64| value < 1.0;
+        assert!(!below.observe(2.0));
+        assert!(below.observe(0.5));
+    }
+}
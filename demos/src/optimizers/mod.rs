@@ -1,8 +1,10 @@
 //! Classical optimizers for variational algorithms.
 
 pub mod cobyla;
+pub mod convergence;
 
 pub use cobyla::{Cobyla, OptimizationResult};
+pub use convergence::{ConvergenceCriterion, ShotNoiseCriterion};
 
 /// Trait for classical optimizers.
 pub trait Optimizer {
@@ -15,6 +17,24 @@ pub trait Optimizer {
     /// # Returns
     /// Optimization result with optimal parameters and value.
     fn minimize<F>(&self, objective: F, initial_params: Vec<f64>) -> OptimizationResult
+    where
+        F: FnMut(&[f64]) -> f64,
+    {
+        self.minimize_until(objective, initial_params, &mut |_| false)
+    }
+
+    /// Minimize the objective function, also stopping once `criterion`
+    /// reports convergence.
+    ///
+    /// The criterion observes the best objective value after every
+    /// iteration, in addition to the optimizer's own stopping rule. Use a
+    /// [`ShotNoiseCriterion`] when the objective is estimated from shots.
+    fn minimize_until<F>(
+        &self,
+        objective: F,
+        initial_params: Vec<f64>,
+        criterion: &mut dyn ConvergenceCriterion,
+    ) -> OptimizationResult
     where
         F: FnMut(&[f64]) -> f64;
 }
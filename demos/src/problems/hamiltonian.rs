@@ -3,6 +3,11 @@
 //! A Hamiltonian is represented as a sum of Pauli strings:
 //! H = Σᵢ cᵢ Pᵢ
 //! where each Pᵢ is a tensor product of Pauli operators.
+//!
+//! Molecular Hamiltonians carry many terms with tiny coefficients. They can
+//! be truncated, and the remaining terms grouped into qubit-wise commuting
+//! sets that share a [`MeasurementBasis`], so one circuit per group suffices
+//! to estimate the energy.
 
 use serde::{Deserialize, Serialize};
 
@@ -108,6 +113,43 @@ impl PauliTerm {
     pub fn max_qubit(&self) -> Option<usize> {
         self.operators.iter().map(|(q, _)| *q).max()
     }
+
+    /// Get the Pauli acting on a qubit.
+    pub fn pauli_on(&self, qubit: usize) -> Pauli {
+        self.operators
+            .iter()
+            .find(|(q, _)| *q == qubit)
+            .map_or(Pauli::I, |(_, p)| *p)
+    }
+
+    /// Check whether two terms commute qubit-wise, i.e. act with the same
+    /// Pauli on every qubit both act on non-trivially.
+    pub fn commutes_qubitwise(&self, other: &PauliTerm) -> bool {
+        self.operators.iter().all(|&(q, p)| {
+            let o = other.pauli_on(q);
+            o == Pauli::I || o == p || p == Pauli::I
+        })
+    }
+}
+
+/// A product basis in which every term of a group can be measured at once.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasurementBasis {
+    /// The Pauli measured on each qubit, sorted by qubit; qubits not listed
+    /// are measured in Z.
+    pub paulis: Vec<(usize, Pauli)>,
+    /// Indices of the Hamiltonian terms measured in this basis.
+    pub terms: Vec<usize>,
+}
+
+impl MeasurementBasis {
+    /// Get the Pauli measured on a qubit.
+    pub fn pauli_on(&self, qubit: usize) -> Pauli {
+        self.paulis
+            .iter()
+            .find(|(q, _)| *q == qubit)
+            .map_or(Pauli::Z, |(_, p)| *p)
+    }
 }
 
 impl std::fmt::Display for PauliTerm {
@@ -183,6 +225,80 @@ impl PauliHamiltonian {
     pub fn non_identity_terms(&self) -> impl Iterator<Item = &PauliTerm> {
         self.terms.iter().filter(|t| !t.is_identity())
     }
+
+    /// Drop terms whose coefficient magnitude is below `epsilon`, returning
+    /// how many were dropped. The identity term is always kept.
+    pub fn truncate(&mut self, epsilon: f64) -> usize {
+        let before = self.terms.len();
+        self.terms
+            .retain(|t| t.is_identity() || t.coefficient.abs() >= epsilon);
+        before - self.terms.len()
+    }
+
+    /// Sort terms by decreasing coefficient magnitude.
+    pub fn sort_terms(&mut self) {
+        self.terms
+            .sort_by(|a, b| b.coefficient.abs().total_cmp(&a.coefficient.abs()));
+    }
+
+    /// Partition the non-identity terms into qubit-wise commuting groups.
+    ///
+    /// Terms are placed greedily, largest coefficient first, into the first
+    /// group they commute with. Returns indices into `terms`.
+    pub fn group_qubitwise_commuting(&self) -> Vec<Vec<usize>> {
+        let mut order: Vec<usize> = (0..self.terms.len())
+            .filter(|&i| !self.terms[i].is_identity())
+            .collect();
+        order.sort_by(|&a, &b| {
+            self.terms[b]
+                .coefficient
+                .abs()
+                .total_cmp(&self.terms[a].coefficient.abs())
+        });
+
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for i in order {
+            let term = &self.terms[i];
+            match groups
+                .iter_mut()
+                .find(|g| g.iter().all(|&j| term.commutes_qubitwise(&self.terms[j])))
+            {
+                Some(group) => group.push(i),
+                None => groups.push(vec![i]),
+            }
+        }
+        groups
+    }
+
+    /// Measurement bases covering all non-identity terms, one per qubit-wise
+    /// commuting group.
+    pub fn measurement_bases(&self) -> Vec<MeasurementBasis> {
+        self.group_qubitwise_commuting()
+            .into_iter()
+            .map(|terms| {
+                let mut paulis: Vec<(usize, Pauli)> = terms
+                    .iter()
+                    .flat_map(|&i| self.terms[i].operators.iter().copied())
+                    .filter(|&(_, p)| p != Pauli::I)
+                    .collect();
+                paulis.sort_by_key(|&(q, _)| q);
+                paulis.dedup_by_key(|&mut (q, _)| q);
+                MeasurementBasis { paulis, terms }
+            })
+            .collect()
+    }
+
+    /// Upper bound on the single-shot standard deviation of an energy
+    /// estimate that measures every term separately.
+    ///
+    /// A Pauli term has single-shot variance `c² (1 - ⟨P⟩²) ≤ c²`, so the
+    /// bound is the root of the summed squared coefficients.
+    pub fn shot_std(&self) -> f64 {
+        self.non_identity_terms()
+            .map(|t| t.coefficient.powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
 }
 
 impl std::fmt::Display for PauliHamiltonian {
@@ -232,4 +348,50 @@ mod tests {
         assert_eq!(h.num_qubits(), 2);
         assert_eq!(h.identity_coefficient(), -1.0);
     }
+
+    #[test]
+    fn test_truncate_and_sort() {
+        let mut h = PauliHamiltonian::new(vec![
+            PauliTerm::identity(1e-9),
+            PauliTerm::z(1e-6, 0),
+            PauliTerm::x(-0.5, 1),
+            PauliTerm::zz(0.8, 0, 1),
+        ]);
+
+        assert_eq!(h.truncate(1e-4), 1);
+        assert_eq!(h.num_terms(), 3);
+
+        h.sort_terms();
+        let coefficients: Vec<f64> = h.terms.iter().map(|t| t.coefficient).collect();
+        assert_eq!(coefficients, vec![0.8, -0.5, 1e-9]);
+    }
+
+    #[test]
+    fn test_measurement_bases() {
+        let h = PauliHamiltonian::new(vec![
+            PauliTerm::identity(-1.0),
+            PauliTerm::z(0.5, 0),
+            PauliTerm::zz(0.3, 0, 1),
+            PauliTerm::xx(0.2, 0, 1),
+            PauliTerm::x(0.1, 1),
+            PauliTerm::yy(0.05, 0, 1),
+        ]);
+
+        assert!(h.terms[1].commutes_qubitwise(&h.terms[2]));
+        assert!(!h.terms[2].commutes_qubitwise(&h.terms[3]));
+        assert!(h.terms[3].commutes_qubitwise(&h.terms[4]));
+
+        let bases = h.measurement_bases();
+        assert_eq!(bases.len(), 3);
+        assert_eq!(bases[0].terms, vec![1, 2]);
+        assert_eq!(bases[0].paulis, vec![(0, Pauli::Z), (1, Pauli::Z)]);
+        assert_eq!(bases[1].terms, vec![3, 4]);
+        assert_eq!(bases[1].pauli_on(1), Pauli::X);
+        assert_eq!(bases[2].terms, vec![5]);
+
+        // Every non-identity term is measured exactly once.
+        let mut measured: Vec<usize> = bases.iter().flat_map(|b| b.terms.clone()).collect();
+        measured.sort_unstable();
+        assert_eq!(measured, vec![1, 2, 3, 4, 5]);
+    }
 }
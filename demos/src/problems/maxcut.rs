@@ -5,9 +5,80 @@
 //!
 //! This is an NP-hard combinatorial optimization problem with applications
 //! in circuit layout, statistical physics, and network design.
+//!
+//! Classical baselines for judging QAOA results:
+//! - [`Graph::exact_max_cut`]: brute-force enumeration, up to
+//!   [`EXACT_MAX_NODES`] nodes.
+//! - [`Graph::hyperplane_rounding_cut`]: Goemans–Williamson-style random
+//!   hyperplane rounding of a low-rank vector relaxation, which is solved by
+//!   coordinate ascent instead of an SDP.
+//! - [`Graph::annealing_cut`]: simulated annealing over single-node flips.
 
 use serde::{Deserialize, Serialize};
 
+/// Largest graph [`Graph::exact_max_cut`] will enumerate.
+pub const EXACT_MAX_NODES: usize = 24;
+
+/// Dimension of the vectors in the rounding relaxation.
+const RELAXATION_RANK: usize = 3;
+
+/// Coordinate-ascent sweeps over the rounding relaxation.
+const RELAXATION_SWEEPS: usize = 100;
+
+/// A classical Max-Cut solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CutSolver {
+    /// Brute-force enumeration.
+    Exact,
+    /// Random hyperplane rounding followed by local search.
+    HyperplaneRounding,
+    /// Simulated annealing followed by local search.
+    Annealing,
+}
+
+impl std::fmt::Display for CutSolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CutSolver::Exact => write!(f, "exact"),
+            CutSolver::HyperplaneRounding => write!(f, "hyperplane rounding"),
+            CutSolver::Annealing => write!(f, "simulated annealing"),
+        }
+    }
+}
+
+/// A cut found by a classical solver.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CutSolution {
+    /// Solver that found the cut.
+    pub solver: CutSolver,
+    /// Node assignment, `true` for set S.
+    pub assignment: Vec<bool>,
+    /// Cut value.
+    pub value: f64,
+}
+
+impl CutSolution {
+    /// Get the assignment as a bitstring (nodes beyond 64 are dropped).
+    pub fn bitstring(&self) -> usize {
+        self.assignment
+            .iter()
+            .take(usize::BITS as usize)
+            .enumerate()
+            .filter(|(_, s)| **s)
+            .map(|(i, _)| 1 << i)
+            .sum()
+    }
+}
+
+/// Uniform sample in [0, 1) from a 64-bit LCG, for reproducible baselines.
+fn next_uniform(state: &mut u64) -> f64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    (*state >> 11) as f64 / (1u64 << 53) as f64
+}
+
 /// A graph for the Max-Cut problem.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Graph {
@@ -101,6 +172,11 @@ impl Graph {
         Self::new(n_nodes, edges.into_iter().collect())
     }
 
+    /// Sum of all edge weights, the largest possible cut.
+    pub fn total_weight(&self) -> f64 {
+        self.edges.iter().map(|(_, _, w)| w).sum()
+    }
+
     /// Get the number of edges.
     pub fn num_edges(&self) -> usize {
         self.edges.len()
@@ -173,6 +249,186 @@ impl Graph {
             .collect();
         (offset, zz_terms)
     }
+
+    /// Find the maximum cut by enumeration, or `None` if the graph has more
+    /// than [`EXACT_MAX_NODES`] nodes.
+    pub fn exact_max_cut(&self) -> Option<CutSolution> {
+        if self.n_nodes > EXACT_MAX_NODES {
+            return None;
+        }
+        let (bitstring, value) = self.max_cut_brute_force();
+        Some(CutSolution {
+            solver: CutSolver::Exact,
+            assignment: (0..self.n_nodes)
+                .map(|i| (bitstring >> i) & 1 == 1)
+                .collect(),
+            value,
+        })
+    }
+
+    /// Approximate the maximum cut by random hyperplane rounding.
+    ///
+    /// Each node gets a unit vector; coordinate ascent pushes the vectors of
+    /// adjacent nodes apart, then each of `rounds` random hyperplanes splits
+    /// them into a cut that is improved by local search. The best cut wins.
+    pub fn hyperplane_rounding_cut(&self, rounds: usize, seed: u64) -> CutSolution {
+        let adjacency = self.adjacency();
+        let mut state = seed;
+        let random_vector = |state: &mut u64| {
+            let mut v = [0.0; RELAXATION_RANK];
+            for x in &mut v {
+                *x = 2.0 * next_uniform(state) - 1.0;
+            }
+            v
+        };
+
+        let mut vectors: Vec<[f64; RELAXATION_RANK]> = (0..self.n_nodes)
+            .map(|_| normalized(random_vector(&mut state)))
+            .collect();
+        for _ in 0..RELAXATION_SWEEPS {
+            for i in 0..self.n_nodes {
+                let mut field = [0.0; RELAXATION_RANK];
+                for &(j, w) in &adjacency[i] {
+                    for (f, x) in field.iter_mut().zip(vectors[j]) {
+                        *f -= w * x;
+                    }
+                }
+                if field.iter().any(|f| f.abs() > 1e-12) {
+                    vectors[i] = normalized(field);
+                }
+            }
+        }
+
+        let mut best = vec![false; self.n_nodes];
+        let mut best_value = self.cut_value(&best);
+        for _ in 0..rounds.max(1) {
+            let normal = random_vector(&mut state);
+            let mut assignment: Vec<bool> = vectors
+                .iter()
+                .map(|v| v.iter().zip(normal).map(|(a, b)| a * b).sum::<f64>() >= 0.0)
+                .collect();
+            self.local_search(&adjacency, &mut assignment);
+            let value = self.cut_value(&assignment);
+            if value > best_value {
+                best_value = value;
+                best = assignment;
+            }
+        }
+
+        CutSolution {
+            solver: CutSolver::HyperplaneRounding,
+            assignment: best,
+            value: best_value,
+        }
+    }
+
+    /// Approximate the maximum cut by simulated annealing.
+    ///
+    /// Each of `sweeps` sweeps proposes one flip per node, of randomly chosen
+    /// nodes, with the temperature decaying geometrically from the largest
+    /// weighted degree to a thousandth of it. The best cut seen is improved
+    /// by local search.
+    pub fn annealing_cut(&self, sweeps: usize, seed: u64) -> CutSolution {
+        let adjacency = self.adjacency();
+        let mut state = seed;
+        let mut assignment: Vec<bool> = (0..self.n_nodes)
+            .map(|_| next_uniform(&mut state) < 0.5)
+            .collect();
+        let mut value = self.cut_value(&assignment);
+        let mut best = assignment.clone();
+        let mut best_value = value;
+
+        let t_start = adjacency
+            .iter()
+            .map(|edges| edges.iter().map(|(_, w)| w.abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+            .max(1e-9);
+        let sweeps = sweeps.max(1);
+        let cooling = 1e-3_f64.powf(1.0 / sweeps as f64);
+
+        let mut temperature = t_start;
+        for _ in 0..sweeps {
+            for _ in 0..self.n_nodes {
+                let i = ((next_uniform(&mut state) * self.n_nodes as f64) as usize)
+                    .min(self.n_nodes - 1);
+                let gain = flip_gain(&adjacency, &assignment, i);
+                if gain >= 0.0 || next_uniform(&mut state) < (gain / temperature).exp() {
+                    assignment[i] = !assignment[i];
+                    value += gain;
+                    if value > best_value {
+                        best_value = value;
+                        best.clone_from(&assignment);
+                    }
+                }
+            }
+            temperature *= cooling;
+        }
+
+        self.local_search(&adjacency, &mut best);
+        CutSolution {
+            solver: CutSolver::Annealing,
+            value: self.cut_value(&best),
+            assignment: best,
+        }
+    }
+
+    /// The best available classical cut: exact when the graph is small
+    /// enough, otherwise the better of the two heuristics.
+    pub fn best_classical_cut(&self, seed: u64) -> CutSolution {
+        if let Some(exact) = self.exact_max_cut() {
+            return exact;
+        }
+        let rounding = self.hyperplane_rounding_cut(100, seed);
+        let annealing = self.annealing_cut(1000, seed);
+        if annealing.value > rounding.value {
+            annealing
+        } else {
+            rounding
+        }
+    }
+
+    /// Neighbours and edge weights of every node.
+    fn adjacency(&self) -> Vec<Vec<(usize, f64)>> {
+        let mut adjacency = vec![Vec::new(); self.n_nodes];
+        for &(a, b, w) in &self.edges {
+            adjacency[a].push((b, w));
+            adjacency[b].push((a, w));
+        }
+        adjacency
+    }
+
+    /// Flip single nodes while that increases the cut.
+    fn local_search(&self, adjacency: &[Vec<(usize, f64)>], assignment: &mut [bool]) {
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 0..self.n_nodes {
+                if flip_gain(adjacency, assignment, i) > 1e-12 {
+                    assignment[i] = !assignment[i];
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+/// Change in cut value from moving `node` to the other set.
+fn flip_gain(adjacency: &[Vec<(usize, f64)>], assignment: &[bool], node: usize) -> f64 {
+    adjacency[node]
+        .iter()
+        .map(|&(j, w)| {
+            if assignment[j] == assignment[node] {
+                w
+            } else {
+                -w
+            }
+        })
+        .sum()
+}
+
+fn normalized(v: [f64; RELAXATION_RANK]) -> [f64; RELAXATION_RANK] {
+    let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 { v.map(|x| x / norm) } else { v }
 }
 
 impl std::fmt::Display for Graph {
@@ -245,4 +501,48 @@ mod tests {
         assert_eq!(offset, 2.0); // 4 edges * 0.5
         assert_eq!(terms.len(), 4);
     }
+
+    #[test]
+    fn test_exact_max_cut() {
+        let g = Graph::ring_6();
+        let exact = g.exact_max_cut().unwrap();
+        assert_eq!(exact.solver, CutSolver::Exact);
+        assert_eq!(exact.value, 6.0);
+        assert_eq!(g.cut_value_from_bitstring(exact.bitstring()), 6.0);
+
+        let large = Graph::new(EXACT_MAX_NODES + 1, vec![(0, 1)]);
+        assert!(large.exact_max_cut().is_none());
+    }
+
+    #[test]
+    fn test_heuristics_match_exact_on_small_graphs() {
+        for g in [
+            Graph::square_4(),
+            Graph::complete_4(),
+            Graph::ring_6(),
+            Graph::grid_6(),
+            Graph::random(10, 0.5, 7),
+        ] {
+            let exact = g.exact_max_cut().unwrap().value;
+            let rounding = g.hyperplane_rounding_cut(20, 1);
+            let annealing = g.annealing_cut(200, 1);
+
+            assert_eq!(rounding.value, g.cut_value(&rounding.assignment));
+            assert_eq!(annealing.value, g.cut_value(&annealing.assignment));
+            // Goemans–Williamson guarantees 0.878 in expectation.
+            assert!(rounding.value >= 0.878 * exact);
+            assert!((annealing.value - exact).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_best_classical_cut_on_large_graph() {
+        let g = Graph::random(40, 0.2, 3);
+        let best = g.best_classical_cut(5);
+
+        assert_ne!(best.solver, CutSolver::Exact);
+        assert_eq!(best.assignment.len(), 40);
+        // A random cut cuts half the weight; local search does better.
+        assert!(best.value > g.total_weight() / 2.0);
+    }
 }
@@ -4,8 +4,8 @@ pub mod hamiltonian;
 pub mod maxcut;
 pub mod molecules;
 
-pub use hamiltonian::{Pauli, PauliHamiltonian, PauliTerm};
-pub use maxcut::Graph;
+pub use hamiltonian::{MeasurementBasis, Pauli, PauliHamiltonian, PauliTerm};
+pub use maxcut::{CutSolution, CutSolver, EXACT_MAX_NODES, Graph};
 pub use molecules::{
     beh2_hamiltonian, exact_ground_state_energy, h2_hamiltonian, h2_hamiltonian_4q,
     h2o_hamiltonian, lih_hamiltonian,
@@ -0,0 +1,19 @@
+//! Live per-evaluation updates from variational runners.
+
+use tokio::sync::mpsc::UnboundedSender;
+
+/// One objective evaluation of a VQE or QAOA run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterationUpdate {
+    /// Evaluation number, starting at 0.
+    pub iteration: usize,
+    /// Parameters evaluated.
+    pub params: Vec<f64>,
+    /// Objective value: energy for VQE, expected cut for QAOA.
+    pub value: f64,
+    /// Variance of the value estimated from the configured shots.
+    pub variance: f64,
+}
+
+/// Channel end runners send their updates to.
+pub type IterationSender = UnboundedSender<IterationUpdate>;
@@ -9,6 +9,8 @@
 
 use std::collections::HashMap;
 
+use arvak_hal::NoiseModel;
+
 /// Error mitigation configuration.
 #[derive(Debug, Clone)]
 pub struct MitigationConfig {
@@ -224,6 +226,48 @@ impl MeasurementMitigator {
         }
     }
 
+    /// Create a mitigator from the per-qubit readout errors of a noise
+    /// model, e.g. one built from a backend's calibration.
+    ///
+    /// # Arguments
+    /// * `n_qubits` - Number of qubits
+    /// * `model` - Noise model; qubits without a readout error read out perfectly
+    pub fn from_noise_model(n_qubits: usize, model: &NoiseModel) -> Self {
+        let dim = 1 << n_qubits;
+        let mut calibration_matrix = vec![vec![0.0; dim]; dim];
+
+        // Build calibration matrix assuming independent errors per qubit
+        for (i, row) in calibration_matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..n_qubits)
+                    .map(|q| {
+                        let (flip_zero, flip_one) = model
+                            .readout_error(q as u32)
+                            .map_or((0.0, 0.0), |r| (r.flip_zero, r.flip_one));
+                        let flip = if (i >> q) & 1 == 0 {
+                            flip_zero
+                        } else {
+                            flip_one
+                        };
+                        if ((i ^ j) >> q) & 1 == 1 {
+                            flip
+                        } else {
+                            1.0 - flip
+                        }
+                    })
+                    .product();
+            }
+        }
+
+        let inverse_matrix = Self::pseudo_inverse(&calibration_matrix, dim);
+
+        Self {
+            n_qubits,
+            calibration_matrix,
+            inverse_matrix,
+        }
+    }
+
     /// Compute pseudo-inverse using iterative method.
     fn pseudo_inverse(matrix: &[Vec<f64>], dim: usize) -> Vec<Vec<f64>> {
         // Simplified pseudo-inverse: transpose for near-identity matrices
@@ -364,6 +408,20 @@ mod tests {
 
         // Mitigated should be closer to pure state
         assert!(mitigated[0] > noisy_probs[0]);
+
+        // The same errors described by a noise model
+        let model = NoiseModel::new()
+            .with_readout_error(0, 0.05, 0.05)
+            .with_readout_error(1, 0.05, 0.05);
+        let from_model = MeasurementMitigator::from_noise_model(2, &model);
+        for (a, b) in from_model
+            .calibration_matrix
+            .iter()
+            .flatten()
+            .zip(mitigator.calibration_matrix.iter().flatten())
+        {
+            assert!((a - b).abs() < 1e-12);
+        }
     }
 
     #[test]
@@ -1,6 +1,7 @@
 //! Demo runners for executing quantum algorithms.
 
 pub mod benchmark;
+pub mod iteration;
 pub mod mitigation;
 pub mod orchestrator;
 pub mod qaoa;
@@ -11,6 +12,7 @@ pub use benchmark::{
     BackendComparison, BenchmarkConfig, BenchmarkResult, BenchmarkTimer, benchmark_qaoa,
     benchmark_vqe, qaoa_scaling_benchmark, vqe_scaling_benchmark,
 };
+pub use iteration::{IterationSender, IterationUpdate};
 pub use mitigation::{MeasurementMitigator, MitigationConfig, ZneResult, zero_noise_extrapolation};
 pub use orchestrator::run_multi_demo;
 pub use qaoa::{QaoaResult, QaoaRunner};
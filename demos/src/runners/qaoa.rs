@@ -6,8 +6,9 @@ use crate::circuits::qaoa::{
     InitStrategy, ParameterBounds, graph_aware_initial_parameters,
     initial_parameters_with_strategy, num_parameters, qaoa_circuit_no_measure,
 };
-use crate::optimizers::{Cobyla, Optimizer};
-use crate::problems::Graph;
+use crate::optimizers::{Cobyla, Optimizer, ShotNoiseCriterion};
+use crate::problems::{CutSolver, Graph};
+use crate::runners::iteration::{IterationSender, IterationUpdate};
 
 /// Result of a QAOA run.
 #[derive(Debug, Clone)]
@@ -24,12 +25,32 @@ pub struct QaoaResult {
     pub iterations: usize,
     /// Number of circuit evaluations.
     pub circuit_evaluations: usize,
-    /// Approximation ratio (best_cut / max_cut).
+    /// Expected cut value of the optimized state.
+    pub expected_cut: f64,
+    /// Standard error of the expected cut estimated from `shots` shots.
+    pub expected_cut_std_error: f64,
+    /// Approximation ratio (best_cut / reference_cut).
     pub approximation_ratio: f64,
+    /// Cut value of the classical baseline the ratio is computed against.
+    pub reference_cut: f64,
+    /// Solver that found the reference cut; exact for graphs of at most
+    /// [`EXACT_MAX_NODES`](crate::problems::EXACT_MAX_NODES) nodes.
+    pub reference_solver: CutSolver,
     /// Energy history during optimization.
     pub energy_history: Vec<f64>,
 }
 
+impl QaoaResult {
+    /// Approximation ratio of the best cut against another baseline value.
+    pub fn approximation_ratio_to(&self, baseline: f64) -> f64 {
+        approximation_ratio(self.best_cut, baseline)
+    }
+}
+
+fn approximation_ratio(cut: f64, baseline: f64) -> f64 {
+    if baseline > 0.0 { cut / baseline } else { 1.0 }
+}
+
 /// QAOA runner configuration.
 pub struct QaoaRunner {
     /// The graph to optimize.
@@ -46,6 +67,11 @@ pub struct QaoaRunner {
     pub use_graph_aware_init: bool,
     /// Parameter bounds for optimization.
     pub bounds: Option<ParameterBounds>,
+    /// Moving-average window for shot-noise-aware convergence; `None`
+    /// stops on the optimizer's raw tolerance only.
+    pub convergence_window: Option<usize>,
+    /// Receives every expected-cut evaluation while the run progresses.
+    pub iteration_log: Option<IterationSender>,
 }
 
 impl QaoaRunner {
@@ -59,6 +85,8 @@ impl QaoaRunner {
             init_strategy: InitStrategy::TrotterizedAdiabatic,
             use_graph_aware_init: true,
             bounds: Some(ParameterBounds::tight()),
+            convergence_window: None,
+            iteration_log: None,
         }
     }
 
@@ -104,6 +132,19 @@ impl QaoaRunner {
         self
     }
 
+    /// Stop once cut improvements over moving averages of `window`
+    /// iterations are insignificant given the shot noise.
+    pub fn with_shot_noise_convergence(mut self, window: usize) -> Self {
+        self.convergence_window = Some(window);
+        self
+    }
+
+    /// Send every expected-cut evaluation to `sender` as it happens.
+    pub fn with_iteration_log(mut self, sender: IterationSender) -> Self {
+        self.iteration_log = Some(sender);
+        self
+    }
+
     /// Run QAOA with automatic initial parameters.
     pub fn run(&self) -> QaoaResult {
         let (gamma, beta) = if self.use_graph_aware_init {
@@ -160,29 +201,52 @@ impl QaoaRunner {
         // Create optimizer
         let optimizer = Cobyla::new().with_maxiter(self.maxiter).with_tol(1e-4);
 
+        let log = self.iteration_log.as_ref();
+        let shots = f64::from(self.shots.max(1));
+        let mut evaluations = 0;
+
         // Objective function: minimize negative expected cut (maximize cut)
         let objective = |params: &[f64]| -> f64 {
             let gamma = &params[..p];
             let beta = &params[p..];
-            -evaluate_expected_cut(graph, gamma, beta)
+            let (expected_cut, cut_variance) = cut_statistics(graph, gamma, beta);
+            if let Some(log) = log {
+                // A closed receiver only means nobody is listening anymore.
+                let _ = log.send(IterationUpdate {
+                    iteration: evaluations,
+                    params: params.to_vec(),
+                    value: expected_cut,
+                    variance: cut_variance / shots,
+                });
+            }
+            evaluations += 1;
+            -expected_cut
         };
 
-        let result = optimizer.minimize(objective, initial_params);
+        // A single-shot cut lies in [0, total weight], so its standard
+        // deviation is at most half the total weight.
+        let result = match self.convergence_window {
+            Some(window) => {
+                let mut criterion =
+                    ShotNoiseCriterion::from_shots(self.shots, graph.total_weight() / 2.0)
+                        .with_window(window);
+                optimizer.minimize_until(objective, initial_params, &mut criterion)
+            }
+            None => optimizer.minimize(objective, initial_params),
+        };
 
         // Extract optimal parameters
         let optimal_gamma = result.optimal_params[..p].to_vec();
         let optimal_beta = result.optimal_params[p..].to_vec();
 
+        let (expected_cut, cut_variance) = cut_statistics(graph, &optimal_gamma, &optimal_beta);
+
         // Sample the final distribution to find best bitstring
         let (best_bitstring, best_cut) = sample_best_solution(graph, &optimal_gamma, &optimal_beta);
 
-        // Compute exact max cut for approximation ratio
-        let (_, max_cut) = graph.max_cut_brute_force();
-        let approximation_ratio = if max_cut > 0.0 {
-            best_cut / max_cut
-        } else {
-            1.0
-        };
+        // Compare against the best classical cut
+        let reference = graph.best_classical_cut(42);
+        let approximation_ratio = approximation_ratio(best_cut, reference.value);
 
         QaoaResult {
             best_cut,
@@ -191,7 +255,11 @@ impl QaoaRunner {
             optimal_beta,
             iterations: result.num_iterations,
             circuit_evaluations: result.num_evaluations,
+            expected_cut,
+            expected_cut_std_error: (cut_variance / shots).sqrt(),
             approximation_ratio,
+            reference_cut: reference.value,
+            reference_solver: reference.solver,
             energy_history: result.history.iter().map(|x| -x).collect(),
         }
     }
@@ -204,18 +272,28 @@ impl QaoaRunner {
 
 /// Evaluate the expected cut value for given parameters.
 fn evaluate_expected_cut(graph: &Graph, gamma: &[f64], beta: &[f64]) -> f64 {
+    cut_statistics(graph, gamma, beta).0
+}
+
+/// Evaluate the mean and single-shot variance of the cut value.
+fn cut_statistics(graph: &Graph, gamma: &[f64], beta: &[f64]) -> (f64, f64) {
     let circuit = qaoa_circuit_no_measure(graph, gamma, beta);
     let statevector = simulate_qaoa_statevector(&circuit, graph.n_nodes);
 
-    // Calculate expected cut value
+    // Calculate the first two moments of the cut value
     let mut expected_cut = 0.0;
+    let mut expected_square = 0.0;
     for (i, &amplitude) in statevector.iter().enumerate() {
         let prob = amplitude.norm_sqr();
         let cut = graph.cut_value_from_bitstring(i);
         expected_cut += prob * cut;
+        expected_square += prob * cut * cut;
     }
 
-    expected_cut
+    (
+        expected_cut,
+        (expected_square - expected_cut * expected_cut).max(0.0),
+    )
 }
 
 /// Sample the best solution from the final QAOA state.
@@ -391,6 +469,27 @@ mod tests {
         // For a 4-node square, max cut is 4
         assert!(result.best_cut >= 2.0);
         assert!(result.approximation_ratio >= 0.5);
+        assert_eq!(result.reference_solver, CutSolver::Exact);
+        assert_eq!(result.reference_cut, 4.0);
+        assert_eq!(result.approximation_ratio_to(8.0), result.best_cut / 8.0);
+
+        // A single-shot cut in [0, 4] has standard deviation at most 2.
+        assert!(result.expected_cut > 0.0 && result.expected_cut <= result.reference_cut);
+        assert!(result.expected_cut_std_error > 0.0);
+        assert!(result.expected_cut_std_error <= 2.0 / f64::from(runner.shots).sqrt());
+    }
+
+    #[test]
+    fn test_qaoa_shot_noise_convergence() {
+        let runner = QaoaRunner::new(Graph::square_4())
+            .with_maxiter(100)
+            .with_shots(256)
+            .with_shot_noise_convergence(5);
+        let raw = QaoaRunner::new(Graph::square_4()).with_maxiter(100).run();
+        let result = runner.run();
+
+        assert!(result.circuit_evaluations < raw.circuit_evaluations);
+        assert!(result.approximation_ratio >= 0.5);
     }
 
     #[test]
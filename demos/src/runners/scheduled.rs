@@ -8,14 +8,16 @@ use std::time::Duration;
 
 use arvak_qasm3::emit;
 use arvak_sched::{
-    CircuitSpec, HpcScheduler, Priority, ResourceRequirements, SchedResult, ScheduledJob,
-    ScheduledJobId, ScheduledJobStatus, Scheduler, WorkflowBuilder,
+    CircuitSpec, HpcScheduler, IterationRecord, Priority, ResourceRequirements, SchedError,
+    SchedResult, ScheduledJob, ScheduledJobId, ScheduledJobStatus, Scheduler, WorkflowBuilder,
 };
+use tokio::sync::mpsc;
 
 use crate::circuits::grover::{grover_circuit, optimal_iterations};
 use crate::circuits::qaoa::qaoa_circuit;
 use crate::circuits::vqe::two_local_ansatz;
 use crate::problems::Graph;
+use crate::runners::{QaoaResult, QaoaRunner, VqeResult, VqeRunner};
 
 /// Result from a scheduled demo job.
 #[derive(Debug, Clone)]
@@ -120,6 +122,69 @@ impl ScheduledRunner {
         self.scheduler.submit(job).await
     }
 
+    /// Run VQE, recording every energy evaluation under `run_id`.
+    ///
+    /// Each evaluation is submitted to the scheduler as a job and persisted
+    /// with its parameters, energy, variance and job id while the run
+    /// progresses, so the dashboard can chart it live.
+    pub async fn run_vqe_logged(
+        &self,
+        runner: VqeRunner,
+        run_id: &str,
+        priority: Priority,
+    ) -> SchedResult<VqeResult> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (n_qubits, reps) = (runner.n_qubits, runner.reps);
+        let run = tokio::task::spawn_blocking(move || runner.with_iteration_log(tx).run());
+
+        while let Some(update) = rx.recv().await {
+            let job_id = self
+                .submit_vqe_evaluation(&update.params, n_qubits, reps, priority)
+                .await?;
+            let record =
+                IterationRecord::new(run_id, update.iteration, update.params, update.value)
+                    .with_variance(update.variance)
+                    .with_job_ids(vec![job_id]);
+            self.scheduler.record_iteration(&record).await?;
+        }
+
+        run.await
+            .map_err(|e| SchedError::Internal(format!("VQE run failed: {}", e)))
+    }
+
+    /// Run QAOA, recording every expected-cut evaluation under `run_id`.
+    ///
+    /// Like [`run_vqe_logged`](Self::run_vqe_logged), each evaluation is
+    /// submitted as a job and persisted while the run progresses.
+    pub async fn run_qaoa_logged(
+        &self,
+        runner: QaoaRunner,
+        run_id: &str,
+        priority: Priority,
+    ) -> SchedResult<QaoaResult> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (graph, p) = (runner.graph.clone(), runner.p);
+        let run = tokio::task::spawn_blocking(move || runner.with_iteration_log(tx).run());
+
+        while let Some(update) = rx.recv().await {
+            let (gamma, beta) = update.params.split_at(p);
+            let job_id = self.submit_qaoa(&graph, gamma, beta, priority).await?;
+            let record =
+                IterationRecord::new(run_id, update.iteration, update.params, update.value)
+                    .with_variance(update.variance)
+                    .with_job_ids(vec![job_id]);
+            self.scheduler.record_iteration(&record).await?;
+        }
+
+        run.await
+            .map_err(|e| SchedError::Internal(format!("QAOA run failed: {}", e)))
+    }
+
+    /// Load the iterations recorded for a run.
+    pub async fn iterations(&self, run_id: &str) -> SchedResult<Vec<IterationRecord>> {
+        self.scheduler.iterations(run_id).await
+    }
+
     /// Submit a batch of simple circuits.
     pub async fn submit_batch(
         &self,
@@ -235,4 +300,36 @@ mod tests {
         assert!(config.mock_slurm);
         assert_eq!(config.poll_interval, Duration::from_millis(100));
     }
+
+    #[tokio::test]
+    async fn test_run_qaoa_logged() {
+        use arvak_sched::{SchedulerConfig, SqliteStore};
+
+        let store = Arc::new(SqliteStore::in_memory().unwrap());
+        let scheduler = Arc::new(HpcScheduler::with_mock_slurm(
+            SchedulerConfig::default(),
+            Vec::new(),
+            store,
+        ));
+        let runner = ScheduledRunner::new(scheduler.clone());
+
+        let qaoa = QaoaRunner::new(Graph::square_4()).with_maxiter(10);
+        let result = runner
+            .run_qaoa_logged(qaoa, "qaoa-square", Priority::default())
+            .await
+            .unwrap();
+
+        let records = runner.iterations("qaoa-square").await.unwrap();
+        assert_eq!(records.len(), result.circuit_evaluations);
+        assert!(records.iter().enumerate().all(|(i, r)| r.iteration == i));
+        assert!(
+            records
+                .iter()
+                .all(|r| r.params.len() == 2 && r.job_ids.len() == 1)
+        );
+        assert!(records.iter().all(|r| r.variance.is_some_and(|v| v >= 0.0)));
+
+        let job = scheduler.status(&records[0].job_ids[0]).await;
+        assert!(job.is_ok());
+    }
 }
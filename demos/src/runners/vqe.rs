@@ -3,21 +3,31 @@
 //! VQE is a hybrid classical-quantum algorithm for finding ground state
 //! energies of quantum systems.
 
-use crate::circuits::vqe::{num_parameters, two_local_ansatz};
-use crate::optimizers::{Cobyla, Optimizer};
-use crate::problems::{Pauli, PauliHamiltonian};
+use crate::circuits::vqe::{measurement_circuit, num_parameters, two_local_ansatz};
+use crate::optimizers::{Cobyla, Optimizer, ShotNoiseCriterion};
+use crate::problems::{MeasurementBasis, Pauli, PauliHamiltonian};
+use crate::runners::iteration::{IterationSender, IterationUpdate};
 
 /// Result of a VQE run.
 #[derive(Debug, Clone)]
 pub struct VqeResult {
     /// Optimal energy found.
     pub optimal_energy: f64,
+    /// Standard error of the optimal energy estimated from `shots` shots
+    /// per measurement basis.
+    pub optimal_std_error: f64,
+    /// Standard error of each Hamiltonian term's contribution to the
+    /// optimal energy, in term order.
+    pub term_std_errors: Vec<f64>,
     /// Optimal parameters.
     pub optimal_params: Vec<f64>,
     /// Number of iterations.
     pub iterations: usize,
     /// Number of circuit evaluations.
     pub circuit_evaluations: usize,
+    /// Circuits measured per energy evaluation, one per qubit-wise
+    /// commuting group of Hamiltonian terms.
+    pub measurement_bases: usize,
     /// Energy history during optimization.
     pub energy_history: Vec<f64>,
     /// Whether optimization converged.
@@ -36,6 +46,11 @@ pub struct VqeRunner {
     pub shots: u32,
     /// Maximum optimization iterations.
     pub maxiter: usize,
+    /// Moving-average window for shot-noise-aware convergence; `None`
+    /// stops on the optimizer's raw tolerance only.
+    pub convergence_window: Option<usize>,
+    /// Receives every energy evaluation while the run progresses.
+    pub iteration_log: Option<IterationSender>,
 }
 
 impl VqeRunner {
@@ -48,6 +63,8 @@ impl VqeRunner {
             reps: 2,
             shots: 1024,
             maxiter: 100,
+            convergence_window: None,
+            iteration_log: None,
         }
     }
 
@@ -69,6 +86,27 @@ impl VqeRunner {
         self
     }
 
+    /// Drop Hamiltonian terms with coefficient magnitude below `epsilon`
+    /// and sort the rest by magnitude.
+    pub fn with_truncation(mut self, epsilon: f64) -> Self {
+        self.hamiltonian.truncate(epsilon);
+        self.hamiltonian.sort_terms();
+        self
+    }
+
+    /// Stop once energy improvements over moving averages of `window`
+    /// iterations are insignificant given the shot noise.
+    pub fn with_shot_noise_convergence(mut self, window: usize) -> Self {
+        self.convergence_window = Some(window);
+        self
+    }
+
+    /// Send every energy evaluation to `sender` as it happens.
+    pub fn with_iteration_log(mut self, sender: IterationSender) -> Self {
+        self.iteration_log = Some(sender);
+        self
+    }
+
     /// Run VQE with random initial parameters.
     pub fn run(&self) -> VqeResult {
         let num_params = num_parameters("two_local", self.n_qubits, self.reps);
@@ -96,20 +134,51 @@ impl VqeRunner {
         let hamiltonian = &self.hamiltonian;
         let n_qubits = self.n_qubits;
         let reps = self.reps;
-        let shots = self.shots;
+
+        let bases = hamiltonian.measurement_bases();
+        let shots = f64::from(self.shots.max(1));
+
+        let log = self.iteration_log.as_ref();
 
         let objective = |params: &[f64]| -> f64 {
+            let stats = evaluate_energy(hamiltonian, &bases, n_qubits, reps, params);
+            if let Some(log) = log {
+                // A closed receiver only means nobody is listening anymore.
+                let _ = log.send(IterationUpdate {
+                    iteration: circuit_evaluations,
+                    params: params.to_vec(),
+                    value: stats.energy,
+                    variance: stats.variance / shots,
+                });
+            }
             circuit_evaluations += 1;
-            evaluate_energy(hamiltonian, n_qubits, reps, params, shots)
+            stats.energy
+        };
+
+        let result = match self.convergence_window {
+            Some(window) => {
+                let mut criterion =
+                    ShotNoiseCriterion::from_shots(self.shots, self.hamiltonian.shot_std())
+                        .with_window(window);
+                optimizer.minimize_until(objective, initial_params, &mut criterion)
+            }
+            None => optimizer.minimize(objective, initial_params),
         };
 
-        let result = optimizer.minimize(objective, initial_params);
+        let optimum = evaluate_energy(hamiltonian, &bases, n_qubits, reps, &result.optimal_params);
 
         VqeResult {
             optimal_energy: result.optimal_value,
+            optimal_std_error: (optimum.variance / shots).sqrt(),
+            term_std_errors: optimum
+                .term_variances
+                .iter()
+                .map(|v| (v / shots).sqrt())
+                .collect(),
             optimal_params: result.optimal_params,
             iterations: result.num_iterations,
             circuit_evaluations: result.num_evaluations,
+            measurement_bases: bases.len(),
             energy_history: result.history,
             converged: result.converged,
         }
@@ -119,19 +188,42 @@ impl VqeRunner {
     pub fn num_parameters(&self) -> usize {
         num_parameters("two_local", self.n_qubits, self.reps)
     }
+
+    /// Build the circuits measured for one energy evaluation, one per
+    /// measurement basis of the Hamiltonian.
+    pub fn measurement_circuits(&self, params: &[f64]) -> Vec<arvak_ir::Circuit> {
+        let ansatz = two_local_ansatz(self.n_qubits, self.reps, params);
+        self.hamiltonian
+            .measurement_bases()
+            .iter()
+            .map(|basis| measurement_circuit(&ansatz, basis))
+            .collect()
+    }
 }
 
-/// Evaluate the energy expectation value for given parameters.
+/// Energy of a state with the single-shot variances of its estimate.
+struct EnergyStatistics {
+    /// Energy expectation value.
+    energy: f64,
+    /// Single-shot variance `c² (1 - ⟨P⟩²)` of each term.
+    term_variances: Vec<f64>,
+    /// Single-shot variance of the energy when each measurement basis is
+    /// measured once, including correlations between terms sharing a basis.
+    variance: f64,
+}
+
+/// Evaluate the energy expectation value for given parameters, together
+/// with the variances of its estimate when measured in `bases`.
 ///
 /// This simulates the quantum circuit execution and measurement.
 /// In a real system, this would submit a job to a quantum backend.
 fn evaluate_energy(
     hamiltonian: &PauliHamiltonian,
+    bases: &[MeasurementBasis],
     n_qubits: usize,
     reps: usize,
     params: &[f64],
-    _shots: u32,
-) -> f64 {
+) -> EnergyStatistics {
     // Build the ansatz circuit
     let circuit = two_local_ansatz(n_qubits, reps, params);
 
@@ -139,7 +231,49 @@ fn evaluate_energy(
     let statevector = simulate_statevector(&circuit, n_qubits);
 
     // Calculate expectation value
-    expectation_value(hamiltonian, &statevector)
+    let energy = expectation_value(hamiltonian, &statevector);
+
+    let term_variances = hamiltonian
+        .terms
+        .iter()
+        .map(|term| {
+            if term.is_identity() {
+                return 0.0;
+            }
+            let single = PauliHamiltonian::new(vec![term.clone()]);
+            energy_variance(
+                &single,
+                &statevector,
+                expectation_value(&single, &statevector),
+            )
+        })
+        .collect();
+
+    // Terms measured in the same basis come from the same shots, so each
+    // basis contributes the variance of its summed terms.
+    let variance = bases
+        .iter()
+        .map(|basis| {
+            let group = PauliHamiltonian::new(
+                basis
+                    .terms
+                    .iter()
+                    .map(|&i| hamiltonian.terms[i].clone())
+                    .collect(),
+            );
+            energy_variance(
+                &group,
+                &statevector,
+                expectation_value(&group, &statevector),
+            )
+        })
+        .sum();
+
+    EnergyStatistics {
+        energy,
+        term_variances,
+        variance,
+    }
 }
 
 /// Simplified statevector simulation.
@@ -322,6 +456,28 @@ fn expectation_value(
     energy
 }
 
+/// Calculate the single-shot variance ⟨H²⟩ - ⟨H⟩² of a Hamiltonian.
+fn energy_variance(
+    hamiltonian: &PauliHamiltonian,
+    statevector: &[num_complex::Complex64],
+    energy: f64,
+) -> f64 {
+    use num_complex::Complex64;
+
+    let n = (statevector.len() as f64).log2() as usize;
+    let mut h_psi = vec![Complex64::new(0.0, 0.0); statevector.len()];
+
+    for term in &hamiltonian.terms {
+        for (i, &amplitude) in statevector.iter().enumerate() {
+            let (j, phase) = apply_pauli_string(i, &term.operators, n);
+            h_psi[j] += term.coefficient * phase * amplitude;
+        }
+    }
+
+    let h_squared: f64 = h_psi.iter().map(|a| a.norm_sqr()).sum();
+    (h_squared - energy * energy).max(0.0)
+}
+
 /// Apply a Pauli string to a basis state index.
 /// Returns the new index and accumulated phase.
 fn apply_pauli_string(
@@ -366,6 +522,86 @@ mod tests {
     use super::*;
     use crate::problems::{PauliTerm, h2_hamiltonian};
 
+    #[test]
+    fn test_vqe_truncation_and_bases() {
+        let mut h = h2_hamiltonian();
+        h.add_term(PauliTerm::z(1e-8, 1));
+        let terms = h.num_terms();
+        let runner = VqeRunner::new(h).with_truncation(1e-6).with_maxiter(5);
+        assert_eq!(runner.hamiltonian.num_terms(), terms - 1);
+
+        let bases = runner.hamiltonian.measurement_bases();
+        assert!(bases.len() < runner.hamiltonian.non_identity_terms().count());
+
+        let params = vec![0.1; runner.num_parameters()];
+        assert_eq!(runner.measurement_circuits(&params).len(), bases.len());
+        assert_eq!(
+            runner.run_with_params(params).measurement_bases,
+            bases.len()
+        );
+    }
+
+    #[test]
+    fn test_energy_variance() {
+        use num_complex::Complex64;
+
+        let zero = vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)];
+        let z = PauliHamiltonian::new(vec![PauliTerm::z(1.0, 0)]);
+        let x = PauliHamiltonian::new(vec![PauliTerm::x(1.0, 0)]);
+
+        // |0⟩ is an eigenstate of Z but an equal superposition in X.
+        assert!(energy_variance(&z, &zero, expectation_value(&z, &zero)).abs() < 1e-12);
+        assert!((energy_variance(&x, &zero, expectation_value(&x, &zero)) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_vqe_iteration_log() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let result = VqeRunner::new(h2_hamiltonian())
+            .with_maxiter(5)
+            .with_iteration_log(tx)
+            .run();
+
+        let mut updates = Vec::new();
+        while let Ok(update) = rx.try_recv() {
+            updates.push(update);
+        }
+        assert_eq!(updates.len(), result.circuit_evaluations);
+        assert_eq!(updates[0].iteration, 0);
+        assert!(updates.iter().all(|u| u.variance >= 0.0));
+    }
+
+    #[test]
+    fn test_energy_statistics() {
+        use crate::problems::PauliTerm;
+
+        // RY(π/2) on qubit 0 leaves |+0⟩: X0 and Z1 are exactly 1, while Z0
+        // is a fair coin.
+        let h = PauliHamiltonian::new(vec![
+            PauliTerm::identity(-1.0),
+            PauliTerm::z(2.0, 0),
+            PauliTerm::x(0.5, 0),
+            PauliTerm::z(1.0, 1),
+        ]);
+        let bases = h.measurement_bases();
+        let mut params = vec![0.0; num_parameters("two_local", 2, 0)];
+        params[0] = std::f64::consts::FRAC_PI_2;
+
+        let stats = evaluate_energy(&h, &bases, 2, 0, &params);
+        assert!((stats.energy - 0.5).abs() < 1e-10);
+        assert_eq!(stats.term_variances.len(), 4);
+        assert!((stats.term_variances[1] - 4.0).abs() < 1e-10);
+        assert!(stats.term_variances[2].abs() < 1e-10);
+        assert!((stats.variance - 4.0).abs() < 1e-10);
+
+        let result = VqeRunner::new(h2_hamiltonian())
+            .with_shots(400)
+            .with_maxiter(5)
+            .run();
+        assert_eq!(result.term_std_errors.len(), h2_hamiltonian().num_terms());
+        assert!(result.optimal_std_error >= 0.0);
+    }
+
     #[test]
     fn test_vqe_runner_creation() {
         let h = h2_hamiltonian();
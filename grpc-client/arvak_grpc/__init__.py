@@ -60,7 +60,14 @@ from .result_analysis import (
     batch_compare,
     group_by_similarity,
 )
-from .types import Job, JobResult, JobState, BackendInfo
+from .types import (
+    Job,
+    JobResult,
+    JobState,
+    BackendInfo,
+    WorkflowProgress,
+    CompiledCircuit,
+)
 from .exceptions import (
     ArvakError,
     ArvakJobNotFoundError,
@@ -119,6 +126,8 @@ __all__ = [
     "JobResult",
     "JobState",
     "BackendInfo",
+    "WorkflowProgress",
+    "CompiledCircuit",
     "ArvakError",
     "ArvakJobNotFoundError",
     "ArvakBackendNotFoundError",
@@ -73,6 +73,51 @@ class ArvakServiceStub(object):
                 request_serializer=arvak__pb2.GetBackendInfoRequest.SerializeToString,
                 response_deserializer=arvak__pb2.GetBackendInfoResponse.FromString,
                 _registered_method=True)
+        self.WatchJob = channel.unary_stream(
+                '/arvak.v1.ArvakService/WatchJob',
+                request_serializer=arvak__pb2.WatchJobRequest.SerializeToString,
+                response_deserializer=arvak__pb2.JobStatusUpdate.FromString,
+                _registered_method=True)
+        self.StreamResults = channel.unary_stream(
+                '/arvak.v1.ArvakService/StreamResults',
+                request_serializer=arvak__pb2.StreamResultsRequest.SerializeToString,
+                response_deserializer=arvak__pb2.ResultChunk.FromString,
+                _registered_method=True)
+        self.SubmitBatchStream = channel.stream_stream(
+                '/arvak.v1.ArvakService/SubmitBatchStream',
+                request_serializer=arvak__pb2.BatchJobSubmission.SerializeToString,
+                response_deserializer=arvak__pb2.BatchJobResult.FromString,
+                _registered_method=True)
+        self.RequeueJob = channel.unary_unary(
+                '/arvak.v1.ArvakService/RequeueJob',
+                request_serializer=arvak__pb2.RequeueJobRequest.SerializeToString,
+                response_deserializer=arvak__pb2.AdminJobResponse.FromString,
+                _registered_method=True)
+        self.ForceFailJob = channel.unary_unary(
+                '/arvak.v1.ArvakService/ForceFailJob',
+                request_serializer=arvak__pb2.ForceFailJobRequest.SerializeToString,
+                response_deserializer=arvak__pb2.AdminJobResponse.FromString,
+                _registered_method=True)
+        self.PurgeQueue = channel.unary_unary(
+                '/arvak.v1.ArvakService/PurgeQueue',
+                request_serializer=arvak__pb2.PurgeQueueRequest.SerializeToString,
+                response_deserializer=arvak__pb2.PurgeQueueResponse.FromString,
+                _registered_method=True)
+        self.RebuildIndices = channel.unary_unary(
+                '/arvak.v1.ArvakService/RebuildIndices',
+                request_serializer=arvak__pb2.RebuildIndicesRequest.SerializeToString,
+                response_deserializer=arvak__pb2.RebuildIndicesResponse.FromString,
+                _registered_method=True)
+        self.GetWorkflowProgress = channel.unary_unary(
+                '/arvak.v1.ArvakService/GetWorkflowProgress',
+                request_serializer=arvak__pb2.GetWorkflowProgressRequest.SerializeToString,
+                response_deserializer=arvak__pb2.GetWorkflowProgressResponse.FromString,
+                _registered_method=True)
+        self.Compile = channel.unary_unary(
+                '/arvak.v1.ArvakService/Compile',
+                request_serializer=arvak__pb2.CompileRequest.SerializeToString,
+                response_deserializer=arvak__pb2.CompileResponse.FromString,
+                _registered_method=True)
 
 
 class ArvakServiceServicer(object):
@@ -131,6 +176,69 @@ class ArvakServiceServicer(object):
         context.set_details('Method not implemented!')
         raise NotImplementedError('Method not implemented!')
 
+    def WatchJob(self, request, context):
+        """/ Watch job status updates in real-time (server streaming).
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def StreamResults(self, request, context):
+        """/ Stream large result sets in chunks (server streaming).
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def SubmitBatchStream(self, request_iterator, context):
+        """/ Submit batch jobs with streaming feedback (bidirectional streaming).
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def RequeueJob(self, request, context):
+        """/ Admin: run a job again, e.g. when it is stuck.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def ForceFailJob(self, request, context):
+        """/ Admin: mark a job failed with a reason.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def PurgeQueue(self, request, context):
+        """/ Admin: cancel all queued jobs, optionally only those of one backend.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def RebuildIndices(self, request, context):
+        """/ Admin: rebuild the job store's indices.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def GetWorkflowProgress(self, request, context):
+        """/ Get progress and ETA of an HPC scheduler workflow.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
+    def Compile(self, request, context):
+        """/ Compile a circuit with a named pipeline preset.
+        """
+        context.set_code(grpc.StatusCode.UNIMPLEMENTED)
+        context.set_details('Method not implemented!')
+        raise NotImplementedError('Method not implemented!')
+
 
 def add_ArvakServiceServicer_to_server(servicer, server):
     rpc_method_handlers = {
@@ -169,6 +277,51 @@ def add_ArvakServiceServicer_to_server(servicer, server):
                     request_deserializer=arvak__pb2.GetBackendInfoRequest.FromString,
                     response_serializer=arvak__pb2.GetBackendInfoResponse.SerializeToString,
             ),
+            'WatchJob': grpc.unary_stream_rpc_method_handler(
+                    servicer.WatchJob,
+                    request_deserializer=arvak__pb2.WatchJobRequest.FromString,
+                    response_serializer=arvak__pb2.JobStatusUpdate.SerializeToString,
+            ),
+            'StreamResults': grpc.unary_stream_rpc_method_handler(
+                    servicer.StreamResults,
+                    request_deserializer=arvak__pb2.StreamResultsRequest.FromString,
+                    response_serializer=arvak__pb2.ResultChunk.SerializeToString,
+            ),
+            'SubmitBatchStream': grpc.stream_stream_rpc_method_handler(
+                    servicer.SubmitBatchStream,
+                    request_deserializer=arvak__pb2.BatchJobSubmission.FromString,
+                    response_serializer=arvak__pb2.BatchJobResult.SerializeToString,
+            ),
+            'RequeueJob': grpc.unary_unary_rpc_method_handler(
+                    servicer.RequeueJob,
+                    request_deserializer=arvak__pb2.RequeueJobRequest.FromString,
+                    response_serializer=arvak__pb2.AdminJobResponse.SerializeToString,
+            ),
+            'ForceFailJob': grpc.unary_unary_rpc_method_handler(
+                    servicer.ForceFailJob,
+                    request_deserializer=arvak__pb2.ForceFailJobRequest.FromString,
+                    response_serializer=arvak__pb2.AdminJobResponse.SerializeToString,
+            ),
+            'PurgeQueue': grpc.unary_unary_rpc_method_handler(
+                    servicer.PurgeQueue,
+                    request_deserializer=arvak__pb2.PurgeQueueRequest.FromString,
+                    response_serializer=arvak__pb2.PurgeQueueResponse.SerializeToString,
+            ),
+            'RebuildIndices': grpc.unary_unary_rpc_method_handler(
+                    servicer.RebuildIndices,
+                    request_deserializer=arvak__pb2.RebuildIndicesRequest.FromString,
+                    response_serializer=arvak__pb2.RebuildIndicesResponse.SerializeToString,
+            ),
+            'GetWorkflowProgress': grpc.unary_unary_rpc_method_handler(
+                    servicer.GetWorkflowProgress,
+                    request_deserializer=arvak__pb2.GetWorkflowProgressRequest.FromString,
+                    response_serializer=arvak__pb2.GetWorkflowProgressResponse.SerializeToString,
+            ),
+            'Compile': grpc.unary_unary_rpc_method_handler(
+                    servicer.Compile,
+                    request_deserializer=arvak__pb2.CompileRequest.FromString,
+                    response_serializer=arvak__pb2.CompileResponse.SerializeToString,
+            ),
     }
     generic_handler = grpc.method_handlers_generic_handler(
             'arvak.v1.ArvakService', rpc_method_handlers)
@@ -372,3 +525,246 @@ class ArvakService(object):
             timeout,
             metadata,
             _registered_method=True)
+
+    @staticmethod
+    def WatchJob(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_stream(
+            request,
+            target,
+            '/arvak.v1.ArvakService/WatchJob',
+            arvak__pb2.WatchJobRequest.SerializeToString,
+            arvak__pb2.JobStatusUpdate.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def StreamResults(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_stream(
+            request,
+            target,
+            '/arvak.v1.ArvakService/StreamResults',
+            arvak__pb2.StreamResultsRequest.SerializeToString,
+            arvak__pb2.ResultChunk.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def SubmitBatchStream(request_iterator,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.stream_stream(
+            request_iterator,
+            target,
+            '/arvak.v1.ArvakService/SubmitBatchStream',
+            arvak__pb2.BatchJobSubmission.SerializeToString,
+            arvak__pb2.BatchJobResult.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def RequeueJob(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/arvak.v1.ArvakService/RequeueJob',
+            arvak__pb2.RequeueJobRequest.SerializeToString,
+            arvak__pb2.AdminJobResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def ForceFailJob(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/arvak.v1.ArvakService/ForceFailJob',
+            arvak__pb2.ForceFailJobRequest.SerializeToString,
+            arvak__pb2.AdminJobResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def PurgeQueue(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/arvak.v1.ArvakService/PurgeQueue',
+            arvak__pb2.PurgeQueueRequest.SerializeToString,
+            arvak__pb2.PurgeQueueResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def RebuildIndices(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/arvak.v1.ArvakService/RebuildIndices',
+            arvak__pb2.RebuildIndicesRequest.SerializeToString,
+            arvak__pb2.RebuildIndicesResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def GetWorkflowProgress(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/arvak.v1.ArvakService/GetWorkflowProgress',
+            arvak__pb2.GetWorkflowProgressRequest.SerializeToString,
+            arvak__pb2.GetWorkflowProgressResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
+
+    @staticmethod
+    def Compile(request,
+            target,
+            options=(),
+            channel_credentials=None,
+            call_credentials=None,
+            insecure=False,
+            compression=None,
+            wait_for_ready=None,
+            timeout=None,
+            metadata=None):
+        return grpc.experimental.unary_unary(
+            request,
+            target,
+            '/arvak.v1.ArvakService/Compile',
+            arvak__pb2.CompileRequest.SerializeToString,
+            arvak__pb2.CompileResponse.FromString,
+            options,
+            channel_credentials,
+            insecure,
+            call_credentials,
+            compression,
+            wait_for_ready,
+            timeout,
+            metadata,
+            _registered_method=True)
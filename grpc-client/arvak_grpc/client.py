@@ -15,7 +15,14 @@ from .exceptions import (
     ArvakJobNotCompletedError,
     ArvakJobNotFoundError,
 )
-from .types import BackendInfo, Job, JobResult, JobState
+from .types import (
+    BackendInfo,
+    CompiledCircuit,
+    Job,
+    JobResult,
+    JobState,
+    WorkflowProgress,
+)
 from .job_future import JobFuture
 
 
@@ -62,7 +69,11 @@ class ArvakClient:
         self.close()
 
     def submit_qasm(
-        self, qasm_code: str, backend_id: str, shots: int = 1024
+        self,
+        qasm_code: str,
+        backend_id: str,
+        shots: int = 1024,
+        idempotency_key: Optional[str] = None,
     ) -> str:
         """Submit an OpenQASM 3 circuit for execution.
 
@@ -70,6 +81,8 @@ class ArvakClient:
             qasm_code: OpenQASM 3 source code
             backend_id: ID of the backend to execute on
             shots: Number of shots to execute (default: 1024)
+            idempotency_key: Optional key making the submission safe to
+                retry; a repeat with the same key returns the original job
 
         Returns:
             Job ID string
@@ -85,13 +98,21 @@ class ArvakClient:
                 backend_id=backend_id,
                 shots=shots,
             )
-            response = self.stub.SubmitJob(request, timeout=self.timeout)
+            response = self.stub.SubmitJob(
+                request,
+                timeout=self.timeout,
+                metadata=self._idempotency_metadata(idempotency_key),
+            )
             return response.job_id
         except grpc.RpcError as e:
             self._handle_grpc_error(e)
 
     def submit_circuit_json(
-        self, circuit_json: str, backend_id: str, shots: int = 1024
+        self,
+        circuit_json: str,
+        backend_id: str,
+        shots: int = 1024,
+        idempotency_key: Optional[str] = None,
     ) -> str:
         """Submit an Arvak IR JSON circuit for execution.
 
@@ -99,6 +120,8 @@ class ArvakClient:
             circuit_json: Arvak IR JSON representation
             backend_id: ID of the backend to execute on
             shots: Number of shots to execute (default: 1024)
+            idempotency_key: Optional key making the submission safe to
+                retry; a repeat with the same key returns the original job
 
         Returns:
             Job ID string
@@ -114,7 +137,11 @@ class ArvakClient:
                 backend_id=backend_id,
                 shots=shots,
             )
-            response = self.stub.SubmitJob(request, timeout=self.timeout)
+            response = self.stub.SubmitJob(
+                request,
+                timeout=self.timeout,
+                metadata=self._idempotency_metadata(idempotency_key),
+            )
             return response.job_id
         except grpc.RpcError as e:
             self._handle_grpc_error(e)
@@ -124,6 +151,7 @@ class ArvakClient:
         circuits: List[tuple[str, int]],
         backend_id: str,
         format: str = "qasm3",
+        idempotency_key: Optional[str] = None,
     ) -> List[str]:
         """Submit multiple circuits as a batch.
 
@@ -131,6 +159,8 @@ class ArvakClient:
             circuits: List of (circuit_code, shots) tuples
             backend_id: ID of the backend to execute on
             format: Circuit format ("qasm3" or "json")
+            idempotency_key: Optional key making the submission safe to
+                retry; a repeat with the same key returns the original jobs
 
         Returns:
             List of job ID strings
@@ -153,7 +183,11 @@ class ArvakClient:
                 batch_jobs.append(arvak_pb2.BatchJobRequest(circuit=payload, shots=shots))
 
             request = arvak_pb2.SubmitBatchRequest(backend_id=backend_id, jobs=batch_jobs)
-            response = self.stub.SubmitBatch(request, timeout=self.timeout)
+            response = self.stub.SubmitBatch(
+                request,
+                timeout=self.timeout,
+                metadata=self._idempotency_metadata(idempotency_key),
+            )
             return list(response.job_ids)
         except grpc.RpcError as e:
             self._handle_grpc_error(e)
@@ -344,6 +378,81 @@ class ArvakClient:
         except grpc.RpcError as e:
             self._handle_grpc_error(e)
 
+    def get_workflow_progress(self, workflow_id: str) -> WorkflowProgress:
+        """Get progress and estimated completion time of a workflow.
+
+        The server must be configured with the HPC scheduler's state
+        database (``storage.scheduler_db``).
+
+        Args:
+            workflow_id: Workflow ID
+
+        Returns:
+            WorkflowProgress object with job counts and ETA
+
+        Raises:
+            ArvakError: If the workflow does not exist or the server does not
+                track workflows
+        """
+        try:
+            request = arvak_pb2.GetWorkflowProgressRequest(workflow_id=workflow_id)
+            response = self.stub.GetWorkflowProgress(request, timeout=self.timeout)
+            return self._proto_to_workflow_progress(response)
+        except grpc.RpcError as e:
+            self._handle_grpc_error(e)
+
+    def compile(
+        self,
+        qasm_code: str,
+        backend_id: Optional[str] = None,
+        preset: Optional[str] = None,
+        optimization_level: int = 1,
+        seed: Optional[int] = None,
+    ) -> CompiledCircuit:
+        """Compile an OpenQASM 3 circuit on the server.
+
+        Args:
+            qasm_code: OpenQASM 3 source code
+            backend_id: Compile for this backend's topology and native gates
+                (default: no target)
+            preset: Named pipeline preset, e.g. "superconducting-heavyhex",
+                "iontrap-alltoall" or "simulator-fast"
+            optimization_level: Optimization level 0-3, used without a preset
+            seed: Seed for stochastic passes
+
+        Returns:
+            CompiledCircuit with the compiled OpenQASM 3 source
+
+        Raises:
+            ArvakInvalidCircuitError: If the circuit or preset is invalid
+            ArvakBackendNotFoundError: If the backend does not exist
+            ArvakError: For other errors
+        """
+        try:
+            request = arvak_pb2.CompileRequest(
+                circuit=arvak_pb2.CircuitPayload(qasm3=qasm_code),
+                backend_id=backend_id or "",
+                preset=preset or "",
+                optimization_level=optimization_level,
+            )
+            if seed is not None:
+                request.seed = seed
+            response = self.stub.Compile(request, timeout=self.timeout)
+            return CompiledCircuit(
+                qasm=response.circuit.qasm3,
+                depth=response.depth,
+                num_ops=response.num_ops,
+            )
+        except grpc.RpcError as e:
+            self._handle_grpc_error(e)
+
+    @staticmethod
+    def _idempotency_metadata(idempotency_key: Optional[str]):
+        """Request metadata carrying an idempotency key, if given."""
+        if idempotency_key is None:
+            return None
+        return (("idempotency-key", idempotency_key),)
+
     def _proto_to_job(self, proto_job) -> Job:
         """Convert protobuf Job to Job dataclass."""
         submitted_at = datetime.fromtimestamp(proto_job.submitted_at)
@@ -406,6 +515,23 @@ class ArvakClient:
             topology=topology,
         )
 
+    def _proto_to_workflow_progress(self, proto_progress) -> WorkflowProgress:
+        """Convert protobuf GetWorkflowProgressResponse to WorkflowProgress dataclass."""
+        return WorkflowProgress(
+            workflow_id=proto_progress.workflow_id,
+            name=proto_progress.name,
+            status=proto_progress.status,
+            total=proto_progress.total,
+            completed=proto_progress.completed,
+            running=proto_progress.running,
+            pending=proto_progress.pending,
+            failed=proto_progress.failed,
+            skipped=proto_progress.skipped,
+            percent_complete=proto_progress.percent_complete,
+            remaining_seconds=proto_progress.remaining_seconds,
+            eta=datetime.fromtimestamp(proto_progress.eta) if proto_progress.eta > 0 else None,
+        )
+
     def watch_job(self, job_id: str):
         """Watch job status updates in real-time via server streaming.
 
@@ -82,3 +82,33 @@ class BackendInfo:
     description: str
     supported_gates: list[str]
     topology: Optional[Dict] = None
+
+
+@dataclass
+class WorkflowProgress:
+    """Progress of an HPC scheduler workflow."""
+    workflow_id: str
+    name: str
+    status: str
+    total: int
+    completed: int
+    running: int
+    pending: int
+    failed: int
+    skipped: int
+    percent_complete: float
+    remaining_seconds: float
+    eta: Optional[datetime] = None
+
+    @property
+    def is_finished(self) -> bool:
+        """Check if every job in the workflow has finished."""
+        return self.completed + self.failed + self.skipped == self.total
+
+
+@dataclass
+class CompiledCircuit:
+    """A circuit compiled by the server."""
+    qasm: str
+    depth: int
+    num_ops: int
@@ -1,9 +1,12 @@
 """Tests for the Arvak gRPC Python client."""
 
+import uuid
+
 import pytest
 import grpc
 from arvak_grpc import ArvakClient, JobState
 from arvak_grpc.exceptions import (
+    ArvakError,
     ArvakJobNotFoundError,
     ArvakBackendNotFoundError,
     ArvakInvalidCircuitError,
@@ -87,6 +90,17 @@ def test_submit_and_wait(client):
     assert abs(sum(probs.values()) - 1.0) < 0.001
 
 
+def test_submit_idempotent(client):
+    """Test that a submission repeated with its key returns the same job."""
+    key = f"test-{uuid.uuid4()}"
+    first = client.submit_qasm(BELL_STATE_QASM, "simulator", shots=100, idempotency_key=key)
+    replay = client.submit_qasm(BELL_STATE_QASM, "simulator", shots=100, idempotency_key=key)
+    assert first == replay
+
+    other = client.submit_qasm(BELL_STATE_QASM, "simulator", shots=100)
+    assert other != first
+
+
 def test_get_job_status(client):
     """Test getting job status."""
     job_id = client.submit_qasm(BELL_STATE_QASM, "simulator", shots=500)
@@ -185,5 +199,21 @@ def test_job_terminal_states(client):
     assert not job.is_pending
 
 
+def test_get_workflow_progress_unconfigured(client):
+    """Test workflow progress on a server without a scheduler state database."""
+    with pytest.raises(ArvakError):
+        client.get_workflow_progress("00000000-0000-0000-0000-000000000000")
+
+
+def test_compile_with_preset(client):
+    """Test compiling a circuit with a named pipeline preset."""
+    compiled = client.compile(BELL_STATE_QASM, "simulator", preset="simulator-fast")
+    assert "OPENQASM 3" in compiled.qasm
+    assert compiled.num_ops > 0
+
+    with pytest.raises(ArvakError):
+        client.compile(BELL_STATE_QASM, preset="fastest")
+
+
 if __name__ == "__main__":
     pytest.main([__file__, "-v"])
@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+# Run the Slurm integration tests against a dockerized cluster.
+# Usage: ./scripts/slurm-it.sh [extra cargo test args]
+#
+# Set KEEP_CLUSTER=1 to leave the cluster running afterwards.
+
+set -euo pipefail
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+COMPOSE_FILE="$ROOT/crates/arvak-sched/tests/slurm-docker/docker-compose.yml"
+export ARVAK_SLURM_WORK_DIR="${ARVAK_SLURM_WORK_DIR:-/tmp/arvak-slurm-it}"
+
+mkdir -p "$ARVAK_SLURM_WORK_DIR"
+
+if [ "${KEEP_CLUSTER:-0}" != "1" ]; then
+  trap 'docker compose -f "$COMPOSE_FILE" down -v' EXIT
+fi
+
+docker compose -f "$COMPOSE_FILE" up -d --build --wait
+
+cd "$ROOT"
+cargo test -p arvak-sched --test slurm_docker -- --ignored --test-threads=1 "$@"